@@ -1,8 +1,21 @@
 #![deny(clippy::all)]
-use laythe_vm::vm::{default_native_vm, ExecuteResult};
+use laythe_vm::{
+  dap,
+  debugger::{Breakpoint, CliDebugger},
+  dump_op_codes, lsp, test_runner,
+  tracer::{TraceConfig, TraceEvents},
+  vm::{Interrupt, LintLevel, Vm, VmBuilder, VmConfig, VmError},
+  MANIFEST,
+};
 use std::env;
 use std::fs::read_to_string;
-use std::{path::PathBuf, process};
+use std::io;
+use std::iter;
+use std::{cell::RefCell, rc::Rc};
+use std::{
+  path::{Path, PathBuf},
+  process,
+};
 
 #[cfg(feature = "jemalloc")]
 use jemallocator::Jemalloc;
@@ -11,35 +24,383 @@ use jemallocator::Jemalloc;
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
+#[cfg(unix)]
+mod sigint {
+  use laythe_vm::vm::Interrupt;
+  use std::sync::OnceLock;
+
+  static INTERRUPT: OnceLock<Interrupt> = OnceLock::new();
+
+  extern "C" fn on_sigint(_: libc::c_int) {
+    if let Some(interrupt) = INTERRUPT.get() {
+      // a second ctrl-c before the vm observed the first exits immediately
+      if interrupt.is_interrupted() {
+        unsafe { libc::_exit(130) };
+      }
+
+      interrupt.interrupt();
+    }
+  }
+
+  /// Interrupt the vm on ctrl-c
+  pub fn install(interrupt: Interrupt) {
+    if INTERRUPT.set(interrupt).is_ok() {
+      let handler = on_sigint as extern "C" fn(libc::c_int);
+      unsafe { libc::signal(libc::SIGINT, handler as libc::sighandler_t) };
+    }
+  }
+}
+
+#[cfg(unix)]
+fn handle_sigint(interrupt: Interrupt) {
+  sigint::install(interrupt);
+}
+
+#[cfg(not(unix))]
+fn handle_sigint(_interrupt: Interrupt) {}
+
+const USAGE: &str = "Usage: laythe [options] [command] [file] [args...]
+
+Commands:
+  run <file>        Run a script. The default when only a file is provided.
+                    Arguments after the file are passed to the script
+  repl              Start the interactive repl. The default with no arguments
+  check <file>      Compile a script reporting diagnostics without running it
+  dis <file>        Print the disassembled bytecode of a script
+  debug <file>      Run a script in the step debugger
+  dap               Serve the debug adapter protocol over stdio
+  lsp               Serve the language server protocol over stdio
+  test [paths...]   Run the *_test.lay files in paths, the current directory by default
+
+Options:
+  --deny-warnings   Promote lint warnings to compile errors
+  --type-check      Check scripts against their type annotations before running
+  --gc-stress       Collect garbage on every allocation
+  --trace           Print the stack and each instruction as it executes
+  --trace=<events>  Print the comma separated events as they occur. Events are
+                    instructions, calls, gc, channels or all
+  --trace-module=<path>
+                    Only trace functions in modules ending with path. Repeatable
+  --trace-fun=<name>
+                    Only trace functions named name. Repeatable
+  --budget <count>  Stop after executing count instructions
+  --break <m:line>  Set a debugger breakpoint at line of module m
+  --plugin <path>   Load the native modules of a plugin library, importable
+                    under the library's name. Repeatable
+  --profile=<path>  Write the time spent in each call stack to path as collapsed
+                    stacks for flamegraph tools
+  --stats           Print the instructions executed per op code and call site
+  --cache           Cache compiled scripts in .laythe-cache next to each script
+  --emit=<kind>     Print a script's ast or bytecode as json instead of running it.
+                    kind is one of ast or bytecode
+  --dump-opcodes    Print the vm's op codes and exit
+  -h, --help        Print this message";
+
+/// The commands the cli accepts in place of a script
+const COMMANDS: [&str; 8] = ["repl", "run", "check", "dis", "debug", "dap", "lsp", "test"];
+
+/// The exit code for a malformed command line
+const USAGE_ERROR: i32 = 64;
+
+/// What the cli was asked to do
+enum Command {
+  Repl,
+  Run(PathBuf),
+  Check(PathBuf),
+  Dis(PathBuf),
+  Debug(PathBuf),
+  Dap,
+  Lsp,
+  Test(Vec<PathBuf>),
+  Emit(Emit, PathBuf),
+  DumpOpCodes,
+  Help,
+}
+
+/// What representation of a script to print as json
+enum Emit {
+  Ast,
+  Bytecode,
+}
+
+/// Parse the command line into a command and the vm it should be run with
+fn parse_args(args: &[String]) -> Result<(Command, VmBuilder), String> {
+  let mut builder = VmBuilder::default();
+  let mut config = VmConfig::default();
+  let mut positional: Vec<&str> = vec![];
+  let mut dump_op_codes = false;
+  let mut emit = None;
+  let mut trace = TraceConfig::default();
+
+  let mut iter = args.iter();
+  while let Some(arg) = iter.next() {
+    match arg.as_str() {
+      "-h" | "--help" => return Ok((Command::Help, builder)),
+      "--deny-warnings" => builder = builder.lint_level(LintLevel::Deny),
+      "--type-check" => builder = builder.type_check(true),
+      "--gc-stress" => builder = builder.gc_stress(true),
+      "--trace" => trace.events.instructions = true,
+      "--stats" => builder = builder.stats(true),
+      "--cache" => builder = builder.chunk_cache(true),
+      "--dump-opcodes" => dump_op_codes = true,
+      "--budget" => {
+        let budget = iter
+          .next()
+          .and_then(|budget| budget.parse::<u64>().ok())
+          .ok_or_else(|| "--budget expects an instruction count".to_string())?;
+        config.instruction_budget = Some(budget);
+      },
+      "--break" => {
+        let breakpoint = iter
+          .next()
+          .and_then(|breakpoint| Breakpoint::parse(breakpoint))
+          .ok_or_else(|| "--break expects a breakpoint as module:line".to_string())?;
+        builder = builder.breakpoint(breakpoint);
+      },
+      "--plugin" => {
+        let path = iter
+          .next()
+          .ok_or_else(|| "--plugin expects a path to a plugin library".to_string())?;
+        builder = builder.plugin(PathBuf::from(path));
+      },
+      flag if flag.starts_with("--profile=") => {
+        let path = &flag["--profile=".len()..];
+        if path.is_empty() {
+          return Err("--profile expects a path as --profile=<path>".to_string());
+        }
+        builder = builder.profile(PathBuf::from(path));
+      },
+      flag if flag.starts_with("--trace=") => {
+        let events = TraceEvents::parse(&flag["--trace=".len()..]).ok_or_else(|| {
+          "--trace expects events from instructions, calls, gc, channels or all".to_string()
+        })?;
+        trace.events = trace.events.union(events);
+      },
+      flag if flag.starts_with("--trace-module=") => {
+        trace = trace.module(&flag["--trace-module=".len()..]);
+      },
+      flag if flag.starts_with("--trace-fun=") => {
+        trace = trace.function(&flag["--trace-fun=".len()..]);
+      },
+      "--emit=ast" => emit = Some(Emit::Ast),
+      "--emit=bytecode" => emit = Some(Emit::Bytecode),
+      flag if flag.starts_with("--emit") => {
+        return Err(format!("{} expects --emit=ast or --emit=bytecode", flag))
+      },
+      flag if flag.starts_with('-') => return Err(format!("Unknown option {}", flag)),
+      arg => {
+        positional.push(arg);
+
+        // everything after the script to run belongs to the script
+        if let Some(script) = script_to_run(&positional) {
+          let script_args = iter::once(script.to_string())
+            .chain(iter.by_ref().cloned())
+            .collect();
+          builder = builder.args(script_args);
+          break;
+        }
+      },
+    }
+  }
+
+  // filters alone trace the instructions of the selected functions
+  let filtered = !trace.modules.is_empty() || !trace.functions.is_empty();
+  if trace.events.is_empty() && filtered {
+    trace.events.instructions = true;
+  }
+  if !trace.events.is_empty() {
+    builder = builder.tracer(trace);
+  }
+
+  let builder = builder.config(config);
+  if dump_op_codes {
+    return Ok((Command::DumpOpCodes, builder));
+  }
+
+  let command = match positional.as_slice() {
+    [] | ["repl"] => Command::Repl,
+    ["run", file] => Command::Run(PathBuf::from(file)),
+    ["check", file] => Command::Check(PathBuf::from(file)),
+    ["dis", file] => Command::Dis(PathBuf::from(file)),
+    ["debug", file] => Command::Debug(PathBuf::from(file)),
+    ["dap"] => Command::Dap,
+    ["lsp"] => Command::Lsp,
+    ["test"] => Command::Test(vec![PathBuf::from(".")]),
+    ["test", paths @ ..] => Command::Test(paths.iter().map(PathBuf::from).collect()),
+    [command] if ["run", "check", "dis", "debug"].contains(command) => {
+      return Err(format!("{} expects a file", command))
+    },
+    [file] => Command::Run(PathBuf::from(file)),
+    _ => return Err("Too many arguments".to_string()),
+  };
+
+  let command = match (emit, command) {
+    (None, command) => command,
+    (Some(emit), Command::Run(path)) => Command::Emit(emit, path),
+    (Some(_), _) => return Err("--emit expects a file to run".to_string()),
+  };
+
+  Ok((command, builder))
+}
+
+/// The script the positional arguments run once they name one
+fn script_to_run<'a>(positional: &[&'a str]) -> Option<&'a str> {
+  match positional {
+    ["run", file] => Some(file),
+    [file] if !COMMANDS.contains(file) => Some(file),
+    _ => None,
+  }
+}
+
+/// Exit the process with the exit code of the script or of the error that stopped it
+fn exit(result: Result<u16, u16>) -> ! {
+  match result {
+    Ok(code) | Err(code) => process::exit(code.into()),
+  }
+}
+
+/// Build the configured vm interruptible with ctrl-c, exiting if
+/// the vm could not be created
+fn build(builder: VmBuilder) -> Vm {
+  match builder.build() {
+    Ok(vm) => {
+      handle_sigint(vm.interrupt_handle());
+      vm
+    },
+    Err(error) => {
+      eprintln!("{}", error);
+      process::exit(error.exit_code().into())
+    },
+  }
+}
+
+/// Run a script in the package of the nearest manifest in its
+/// directory or any of its parent directories
+fn with_manifest(builder: VmBuilder, path: &Path) -> VmBuilder {
+  let manifest = path.canonicalize().ok().and_then(|path| {
+    path
+      .ancestors()
+      .skip(1)
+      .map(|dir| dir.join(MANIFEST))
+      .find(|manifest| manifest.is_file())
+  });
+
+  match manifest {
+    Some(manifest) => builder.manifest(manifest),
+    None => builder,
+  }
+}
+
+/// Read the provided file and hand it to the vm, reporting any error
+/// the vm returns
+fn with_file<T>(
+  mut vm: Vm,
+  path: PathBuf,
+  f: impl FnOnce(&mut Vm, PathBuf, &str) -> Result<T, VmError>,
+) -> Result<T, u16> {
+  match read_to_string(&path) {
+    Ok(source) => {
+      let result = f(&mut vm, path, &source);
+      reported(&vm, result)
+    },
+    Err(e) => {
+      eprintln!("{}", e);
+      process::exit(4)
+    },
+  }
+}
+
+/// Report an error the vm returned mapping it to its exit code
+fn reported<T>(vm: &Vm, result: Result<T, VmError>) -> Result<T, u16> {
+  result.map_err(|error| {
+    vm.report(&error);
+    error.exit_code()
+  })
+}
+
 fn main() {
-  let mut vm = default_native_vm();
-  let args: Vec<String> = env::args().collect();
-
-  match args.as_slice() {
-    [_] => match vm.repl() {
-      ExecuteResult::Ok(code) => process::exit(code.into()),
-      ExecuteResult::FunResult(_) => panic!("Fun result should only be returned internally"),
-      ExecuteResult::CompileError => process::exit(2),
-      ExecuteResult::RuntimeError => process::exit(3),
-      ExecuteResult::InternalError => process::exit(4),
-    },
-    _ => {
-      let file_path = &args.as_slice()[1];
-      let path = PathBuf::from(file_path);
-
-      match read_to_string(&path) {
-        Ok(source) => match vm.run(path, &source) {
-          ExecuteResult::Ok(code) => process::exit(code.into()),
-          ExecuteResult::FunResult(_) => panic!("Fun result should only be returned internally"),
-          ExecuteResult::CompileError => process::exit(2),
-          ExecuteResult::RuntimeError => process::exit(3),
-          ExecuteResult::InternalError => process::exit(4),
+  let args: Vec<String> = env::args().skip(1).collect();
+
+  let (command, builder) = match parse_args(&args) {
+    Ok(parsed) => parsed,
+    Err(message) => {
+      eprintln!("{}\n\n{}", message, USAGE);
+      process::exit(USAGE_ERROR)
+    },
+  };
+
+  let result = match command {
+    Command::Help => {
+      println!("{}", USAGE);
+      Ok(0)
+    },
+    Command::DumpOpCodes => match dump_op_codes(&mut io::stdout()) {
+      Ok(()) => Ok(0),
+      Err(e) => {
+        eprintln!("{}", e);
+        Err(4)
+      },
+    },
+    Command::Repl => {
+      let mut vm = build(builder);
+      let result = vm.repl();
+      reported(&vm, result)
+    },
+    Command::Run(path) => with_file(build(with_manifest(builder, &path)), path, Vm::run),
+    Command::Check(path) => {
+      with_file(build(with_manifest(builder, &path)), path, Vm::check).map(|()| 0)
+    },
+    Command::Dis(path) => {
+      with_file(build(with_manifest(builder, &path)), path, Vm::disassemble).map(|()| 0)
+    },
+    Command::Debug(path) => {
+      let debugger = Rc::new(RefCell::new(CliDebugger::stdio()));
+      let builder = with_manifest(builder, &path).debugger(debugger);
+      with_file(build(builder), path, Vm::run)
+    },
+    Command::Dap => {
+      let input = Box::new(io::BufReader::new(io::stdin()));
+      match dap::serve(builder, input, Box::new(io::stdout())) {
+        Ok(()) => Ok(0),
+        Err(e) => {
+          eprintln!("{}", e);
+          Err(4)
+        },
+      }
+    },
+    Command::Lsp => {
+      let input = Box::new(io::BufReader::new(io::stdin()));
+      match lsp::serve(builder, input, Box::new(io::stdout())) {
+        Ok(()) => Ok(0),
+        Err(e) => {
+          eprintln!("{}", e);
+          Err(4)
         },
+      }
+    },
+    Command::Test(paths) => {
+      let files = match test_runner::discover(&paths) {
+        Ok(files) => files,
         Err(e) => {
           eprintln!("{}", e);
           process::exit(4)
         },
+      };
+
+      match test_runner::run(&builder, &files, &mut io::stdout()) {
+        Ok(summary) if summary.is_success() => Ok(0),
+        Ok(_) => Ok(1),
+        Err(e) => {
+          eprintln!("{}", e);
+          Err(4)
+        },
       }
     },
-  }
+    Command::Emit(Emit::Ast, path) => with_file(build(builder), path, Vm::ast_json).map(|()| 0),
+    Command::Emit(Emit::Bytecode, path) => {
+      with_file(build(builder), path, Vm::bytecode_json).map(|()| 0)
+    },
+  };
+
+  exit(result)
 }
@@ -0,0 +1,17 @@
+use std::{env, process::Command};
+
+/// Record the version of the compiler building this crate. Plugins built
+/// by another compiler may lay out the types they share with laythe
+/// differently, so a host only loads plugins with a matching version
+fn main() {
+  let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+  let version = Command::new(rustc)
+    .arg("--version")
+    .output()
+    .ok()
+    .and_then(|output| String::from_utf8(output.stdout).ok())
+    .unwrap_or_default();
+
+  println!("cargo:rerun-if-env-changed=RUSTC");
+  println!("cargo:rustc-env=LAYTHE_RUSTC_VERSION={}", version.trim());
+}
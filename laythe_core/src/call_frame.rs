@@ -1,4 +1,5 @@
 use crate::{
+  chunk::SourceSpan,
   managed::{DebugHeap, DebugWrap, GcObj},
   object::Closure,
   value::Value,
@@ -28,6 +29,18 @@ impl CallFrame {
       stack_start,
     }
   }
+
+  /// The source line of this frame's current instruction
+  pub fn line(&self) -> u32 {
+    self.span().line
+  }
+
+  /// The source span of this frame's current instruction
+  pub fn span(&self) -> SourceSpan {
+    let fun = self.closure.fun();
+    let offset = unsafe { self.ip.offset_from(fun.chunk().instructions().as_ptr()) } as usize;
+    fun.chunk().get_span(offset)
+  }
 }
 
 impl DebugHeap for CallFrame {
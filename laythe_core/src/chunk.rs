@@ -8,20 +8,44 @@ pub trait Encode {
   fn encode(self, buf: &mut Vec<u8>) -> u32;
 }
 
-/// Represent tokens on a line
-#[derive(Debug, Clone, PartialEq)]
-struct Line {
+/// The region of source an instruction was emitted from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SourceSpan {
   /// Line number
   pub line: u32,
 
-  /// Count of tokens on the line
+  /// Column the span starts at
+  pub column: u32,
+
+  /// Length of the span in bytes
+  pub length: u32,
+}
+
+impl SourceSpan {
+  /// Create a new source span
+  pub fn new(line: u32, column: u32, length: u32) -> SourceSpan {
+    SourceSpan {
+      line,
+      column,
+      length,
+    }
+  }
+}
+
+/// Represent a run of instructions emitted from the same source span
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+  /// The source span of this run
+  pub span: SourceSpan,
+
+  /// The offset of the end of this run
   pub offset: u32,
 }
 
 impl Line {
   /// Create a new line
-  fn new(line: u32, offset: u32) -> Line {
-    Line { line, offset }
+  pub fn new(span: SourceSpan, offset: u32) -> Line {
+    Line { span, offset }
   }
 }
 
@@ -46,21 +70,27 @@ impl ChunkBuilder {
     &self.instructions
   }
 
+  /// constants in this code chunk
+  #[inline]
+  pub fn constants(&self) -> &[Value] {
+    &self.constants
+  }
+
   /// Write an instruction to this chunk
   #[inline]
-  pub fn write_instruction<T: Encode>(&mut self, item: T, line: u32) {
+  pub fn write_instruction<T: Encode>(&mut self, item: T, span: SourceSpan) {
     let delta = item.encode(&mut self.instructions);
     let len = self.instructions.len() as u32;
 
     match self.lines.last_mut() {
       Some(last_line) => {
-        if last_line.line == line {
+        if last_line.span == span {
           last_line.offset += delta;
         } else {
-          self.lines.push(Line::new(line, len));
+          self.lines.push(Line::new(span, len));
         }
       },
-      None => self.lines.push(Line::new(line, len)),
+      None => self.lines.push(Line::new(span, len)),
     }
   }
 
@@ -71,6 +101,23 @@ impl ChunkBuilder {
     self.instructions[index] = byte
   }
 
+  /// Clear the instructions and line information in this chunk
+  /// so it can be rewritten. Constants are retained
+  pub fn clear_instructions(&mut self) {
+    self.instructions.clear();
+    self.lines.clear();
+  }
+
+  /// Get the line number at a token offset
+  pub fn get_line(&self, offset: usize) -> u32 {
+    span_at(&self.lines, offset).line
+  }
+
+  /// Get the source span at a token offset
+  pub fn get_span(&self, offset: usize) -> SourceSpan {
+    span_at(&self.lines, offset)
+  }
+
   /// Add a constant to this chunk
   ///
   /// # Examples
@@ -121,6 +168,13 @@ impl Trace for ChunkBuilder {
     self.constants.iter().for_each(|constant| constant.trace());
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self
+      .constants
+      .iter()
+      .for_each(|constant| constant.visit_refs(visitor));
+  }
+
   fn trace_debug(&self, log: &mut dyn std::io::Write) {
     self
       .constants
@@ -143,12 +197,33 @@ pub struct Chunk {
 }
 
 impl Chunk {
+  /// Create a chunk directly from its parts
+  pub fn new(instructions: Box<[u8]>, constants: Box<[Value]>, lines: Box<[Line]>) -> Self {
+    Self {
+      instructions,
+      constants,
+      lines,
+    }
+  }
+
   /// instruction in this code chunk
   #[inline]
   pub fn instructions(&self) -> &[u8] {
     &self.instructions
   }
 
+  /// constants in this code chunk
+  #[inline]
+  pub fn constants(&self) -> &[Value] {
+    &self.constants
+  }
+
+  /// debug line information
+  #[inline]
+  pub fn lines(&self) -> &[Line] {
+    &self.lines
+  }
+
   /// Retrieve a constant in the constants table at
   /// the provided offset
   #[inline]
@@ -180,14 +255,16 @@ impl Chunk {
   /// chunk.get_line(3);
   /// ```
   pub fn get_line(&self, offset: usize) -> u32 {
-    let result = self
-      .lines
-      .binary_search_by_key(&(offset), |line| line.offset as usize);
+    span_at(&self.lines, offset).line
+  }
 
-    match result {
-      Ok(index) => self.lines[index].line,
-      Err(index) => self.lines[cmp::min(index, self.lines.len() - 1)].line,
-    }
+  /// Get the source span at a token offset
+  ///
+  /// # Panics
+  ///
+  /// This method panics if an offset is past the last instruction
+  pub fn get_span(&self, offset: usize) -> SourceSpan {
+    span_at(&self.lines, offset)
   }
 
   /// Get the size of this chunk in bytes
@@ -199,11 +276,28 @@ impl Chunk {
   }
 }
 
+/// Find the source span containing the provided offset
+fn span_at(lines: &[Line], offset: usize) -> SourceSpan {
+  let result = lines.binary_search_by_key(&(offset), |line| line.offset as usize);
+
+  match result {
+    Ok(index) => lines[index].span,
+    Err(index) => lines[cmp::min(index, lines.len() - 1)].span,
+  }
+}
+
 impl Trace for Chunk {
   fn trace(&self) {
     self.constants.iter().for_each(|constant| constant.trace());
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self
+      .constants
+      .iter()
+      .for_each(|constant| constant.visit_refs(visitor));
+  }
+
   fn trace_debug(&self, log: &mut dyn std::io::Write) {
     self
       .constants
@@ -230,8 +324,10 @@ mod test {
 
     #[test]
     fn line_new() {
-      let line = Line::new(10, 5);
-      assert_eq!(line.line, 10);
+      let line = Line::new(SourceSpan::new(10, 4, 3), 5);
+      assert_eq!(line.span.line, 10);
+      assert_eq!(line.span.column, 4);
+      assert_eq!(line.span.length, 3);
       assert_eq!(line.offset, 5);
     }
   }
@@ -250,7 +346,7 @@ mod test {
     #[test]
     fn write_instruction() {
       let mut chunk = ChunkBuilder::default();
-      chunk.write_instruction(Encodable(), 0);
+      chunk.write_instruction(Encodable(), SourceSpan::default());
 
       assert_eq!(chunk.instructions.len(), 1);
       assert_eq!(chunk.instructions[0], 7)
@@ -270,15 +366,28 @@ mod test {
 
   #[cfg(test)]
   mod chunk {
-    use crate::chunk::ChunkBuilder;
+    use crate::chunk::{ChunkBuilder, SourceSpan};
 
     use super::Encodable;
 
     #[test]
     fn get_line() {
       let mut builder = ChunkBuilder::default();
-      builder.write_instruction(Encodable(), 0);
+      builder.write_instruction(Encodable(), SourceSpan::default());
       assert_eq!(builder.build().get_line(0), 0);
     }
+
+    #[test]
+    fn get_span() {
+      let mut builder = ChunkBuilder::default();
+      builder.write_instruction(Encodable(), SourceSpan::new(1, 3, 2));
+      builder.write_instruction(Encodable(), SourceSpan::new(1, 3, 2));
+      builder.write_instruction(Encodable(), SourceSpan::new(2, 1, 5));
+
+      let chunk = builder.build();
+      assert_eq!(chunk.lines().len(), 2);
+      assert_eq!(chunk.get_span(2), SourceSpan::new(1, 3, 2));
+      assert_eq!(chunk.get_span(3), SourceSpan::new(2, 1, 5));
+    }
   }
 }
@@ -5,7 +5,11 @@ pub const SUPER: &str = "super";
 pub const SELF: &str = "self";
 pub const OBJECT: &str = "Object";
 pub const ITER_VAR: &str = "$iter";
+pub const WITH_VAR: &str = "$with";
 pub const ITER: &str = "iter";
 pub const SCRIPT: &str = "script";
 pub const PLACEHOLDER_NAME: &str = "placeholder";
 pub const IMPORT_SEPARATOR: &str = "/";
+pub const ON_MISSING_METHOD: &str = "onMissingMethod";
+pub const ANONYMOUS_CLASS: &str = "anonymous";
+pub const CLASS_VAR: &str = "$class";
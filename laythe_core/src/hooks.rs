@@ -1,15 +1,20 @@
 use std::{
+  any::Any,
   cell::{RefCell, RefMut},
-  io::Write,
+  io::{self, Write},
+  path::Path,
+  time::Duration,
 };
 
 use crate::{
   managed::{Gc, GcObj, GcStr, Manage, Object, Trace, TraceRoot},
   memory::Allocator,
+  object::{Channel, Fiber, Instance},
   value::{Value, VALUE_NIL},
+  worker::{Message, PoolJob, WorkerHandle, WorkerPort},
   Call,
 };
-use laythe_env::io::Io;
+use laythe_env::{io::Io, signal::ProcessSignal};
 
 /// A set of commands that a native function to request from it's surrounding
 /// context
@@ -74,6 +79,123 @@ impl<'a> Hooks<'a> {
     self.context.value_context().get_class(this)
   }
 
+  /// Retrieve the fiber currently being executed
+  pub fn current_fiber(&mut self) -> GcObj<Fiber> {
+    self.context.value_context().current_fiber()
+  }
+
+  /// Schedule a blocked fiber to resume, returning false if
+  /// the fiber was not blocked
+  pub fn wake_fiber(&mut self, fiber: GcObj<Fiber>) -> bool {
+    self.context.value_context().wake_fiber(fiber)
+  }
+
+  /// Schedule a newly created fiber to run
+  pub fn spawn_fiber(&mut self, fiber: GcObj<Fiber>) {
+    self.context.value_context().spawn_fiber(fiber)
+  }
+
+  /// Cancel a fiber by raising the provided error in it once it resumes,
+  /// waking the fiber if it is blocked. Returns false if the fiber has
+  /// already completed
+  pub fn cancel_fiber(&mut self, fiber: GcObj<Fiber>, error: GcObj<Instance>) -> bool {
+    self.context.value_context().cancel_fiber(fiber, error)
+  }
+
+  /// Put the current fiber to sleep for the provided duration. Returns
+  /// false once the fiber has woken from this sleep
+  pub fn sleep_fiber(&mut self, duration: Duration) -> bool {
+    self.context.value_context().sleep_fiber(duration)
+  }
+
+  /// Clear the current fiber's pending sleep so a fiber woken
+  /// early is not woken again once the sleep's deadline passes
+  pub fn clear_sleep(&mut self) {
+    self.context.value_context().clear_sleep()
+  }
+
+  /// Send to a channel once the duration has passed, and then every
+  /// duration after if the timer repeats
+  pub fn add_timer(&mut self, channel: GcObj<Channel>, duration: Duration, repeat: bool) {
+    self
+      .context
+      .value_context()
+      .add_timer(channel, duration, repeat)
+  }
+
+  /// Block the current fiber until the host has input for it. Returns
+  /// false if the context can't wait for input
+  pub fn await_input(&mut self) -> bool {
+    self.context.value_context().await_input()
+  }
+
+  /// Register a callable to run when the context shuts down cleanly
+  pub fn add_exit_hook(&mut self, hook: Value) {
+    self.context.value_context().add_exit_hook(hook)
+  }
+
+  /// Register a callable to run on the main fiber each time the
+  /// process receives the signal
+  pub fn add_signal_handler(
+    &mut self,
+    signal: ProcessSignal,
+    handler: Value,
+  ) -> Result<(), String> {
+    self
+      .context
+      .value_context()
+      .add_signal_handler(signal, handler)
+  }
+
+  /// Run the script at the provided path in a worker on its own thread.
+  /// A relative path is resolved from the calling module's directory
+  pub fn spawn_worker(&mut self, path: &Path) -> Result<WorkerHandle, String> {
+    self.context.value_context().spawn_worker(path)
+  }
+
+  /// The port to the context that spawned this one if this
+  /// context is running in a worker
+  pub fn worker_port(&mut self) -> Option<&WorkerPort> {
+    self.context.value_context().worker_port()
+  }
+
+  /// Call a function with each item on a pool of worker threads,
+  /// collecting what each call returns if `collect` is set
+  pub fn run_in_pool(
+    &mut self,
+    fun: Value,
+    items: Vec<Message>,
+    collect: bool,
+  ) -> Result<PoolJob, String> {
+    self
+      .context
+      .value_context()
+      .run_in_pool(fun, items, collect)
+  }
+
+  /// Get the state the embedding application provided to the context if
+  /// it is of type `T`
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_core::hooks::{Hooks, NoContext};
+  /// use laythe_core::managed::Trace;
+  ///
+  /// struct Settings;
+  /// impl Trace for Settings {}
+  ///
+  /// let mut context = NoContext::default();
+  /// let mut hooks = Hooks::new(&mut context);
+  ///
+  /// assert!(hooks.host_data::<Settings>().is_none());
+  /// ```
+  pub fn host_data<T: HostData>(&mut self) -> Option<&mut T> {
+    self
+      .context
+      .host_data()
+      .and_then(|data| data.as_any_mut().downcast_mut::<T>())
+  }
+
   /// Request an object be managed by the context's garbage collector
   pub fn manage<T: 'static + Manage>(&self, data: T) -> Gc<T> {
     self.as_gc().manage(data)
@@ -112,12 +234,36 @@ impl<'a> Hooks<'a> {
   pub fn pop_roots(&self, count: usize) {
     self.as_gc().pop_roots(count)
   }
+
+  /// Collect all garbage then write a snapshot of the remaining heap to `out`
+  pub fn heap_dump(&self, out: &mut dyn Write) -> io::Result<()> {
+    self.as_gc().heap_dump(out)
+  }
 }
 
 pub trait HookContext {
   fn gc_context(&self) -> &dyn GcContext;
   fn value_context(&mut self) -> &mut dyn ValueContext;
   fn io(&mut self) -> Io;
+  fn host_data(&mut self) -> Option<&mut dyn HostData>;
+}
+
+/// State an embedding application hands to a context so its native
+/// functions can reach it through `Hooks::host_data`. The data is traced
+/// with the context's other roots so it may hold managed values
+pub trait HostData: Any + Trace {
+  fn as_any(&self) -> &dyn Any;
+  fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any + Trace> HostData for T {
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+
+  fn as_any_mut(&mut self) -> &mut dyn Any {
+    self
+  }
 }
 
 /// A set of hooks that provide a gc and tracing roots
@@ -194,6 +340,13 @@ impl<'a> GcHooks<'a> {
   pub fn pop_roots(&self, count: usize) {
     self.context.gc().pop_roots(count);
   }
+
+  /// Collect all garbage then write a snapshot of the remaining heap to `out`
+  pub fn heap_dump(&self, out: &mut dyn Write) -> io::Result<()> {
+    let mut gc = self.context.gc();
+    gc.collect_full(self.context);
+    gc.heap_dump(out)
+  }
 }
 
 pub struct ValueHooks<'a> {
@@ -244,6 +397,61 @@ pub trait ValueContext {
 
   /// Retrieve the class for this value
   fn get_class(&mut self, this: Value) -> Value;
+
+  /// Retrieve the fiber currently being executed
+  fn current_fiber(&mut self) -> GcObj<Fiber>;
+
+  /// Schedule a blocked fiber to resume, returning false if
+  /// the fiber was not blocked
+  fn wake_fiber(&mut self, fiber: GcObj<Fiber>) -> bool;
+
+  /// Schedule a newly created fiber to run
+  fn spawn_fiber(&mut self, fiber: GcObj<Fiber>);
+
+  /// Cancel a fiber by raising the provided error in it once it resumes,
+  /// waking the fiber if it is blocked. Returns false if the fiber has
+  /// already completed
+  fn cancel_fiber(&mut self, fiber: GcObj<Fiber>, error: GcObj<Instance>) -> bool;
+
+  /// Put the current fiber to sleep for the provided duration. Returns
+  /// false once the fiber has woken from this sleep
+  fn sleep_fiber(&mut self, duration: Duration) -> bool;
+
+  /// Clear the current fiber's pending sleep so a fiber woken
+  /// early is not woken again once the sleep's deadline passes
+  fn clear_sleep(&mut self);
+
+  /// Send to a channel once the duration has passed, and then every
+  /// duration after if the timer repeats
+  fn add_timer(&mut self, channel: GcObj<Channel>, duration: Duration, repeat: bool);
+
+  /// Block the current fiber until the host has input for it. Returns
+  /// false if the context can't wait for input
+  fn await_input(&mut self) -> bool;
+
+  /// Register a callable to run when the context shuts down cleanly
+  fn add_exit_hook(&mut self, hook: Value);
+
+  /// Register a callable to run on the main fiber each time the
+  /// process receives the signal
+  fn add_signal_handler(&mut self, signal: ProcessSignal, handler: Value) -> Result<(), String>;
+
+  /// Run the script at the provided path in a worker on its own thread.
+  /// A relative path is resolved from the calling module's directory
+  fn spawn_worker(&mut self, path: &Path) -> Result<WorkerHandle, String>;
+
+  /// The port to the context that spawned this one if this
+  /// context is running in a worker
+  fn worker_port(&mut self) -> Option<&WorkerPort>;
+
+  /// Call a function with each item on a pool of worker threads,
+  /// collecting what each call returns if `collect` is set
+  fn run_in_pool(
+    &mut self,
+    fun: Value,
+    items: Vec<Message>,
+    collect: bool,
+  ) -> Result<PoolJob, String>;
 }
 
 /// A set of functionality required by the hooks objects in order to operate
@@ -293,6 +501,10 @@ impl HookContext for NoContext {
   fn io(&mut self) -> Io {
     Io::default()
   }
+
+  fn host_data(&mut self) -> Option<&mut dyn HostData> {
+    None
+  }
 }
 
 impl GcContext for NoContext {
@@ -317,4 +529,53 @@ impl ValueContext for NoContext {
   fn get_class(&mut self, _this: Value) -> Value {
     VALUE_NIL
   }
+
+  fn current_fiber(&mut self) -> GcObj<Fiber> {
+    GcObj::dangling()
+  }
+
+  fn wake_fiber(&mut self, _fiber: GcObj<Fiber>) -> bool {
+    false
+  }
+
+  fn spawn_fiber(&mut self, _fiber: GcObj<Fiber>) {}
+
+  fn cancel_fiber(&mut self, _fiber: GcObj<Fiber>, _error: GcObj<Instance>) -> bool {
+    false
+  }
+
+  fn sleep_fiber(&mut self, _duration: Duration) -> bool {
+    false
+  }
+
+  fn clear_sleep(&mut self) {}
+
+  fn add_timer(&mut self, _channel: GcObj<Channel>, _duration: Duration, _repeat: bool) {}
+
+  fn await_input(&mut self) -> bool {
+    false
+  }
+
+  fn add_exit_hook(&mut self, _hook: Value) {}
+
+  fn add_signal_handler(&mut self, _signal: ProcessSignal, _handler: Value) -> Result<(), String> {
+    Err("Signals are not supported in this context.".to_string())
+  }
+
+  fn spawn_worker(&mut self, _path: &Path) -> Result<WorkerHandle, String> {
+    Err("Workers are not supported in this context.".to_string())
+  }
+
+  fn worker_port(&mut self) -> Option<&WorkerPort> {
+    None
+  }
+
+  fn run_in_pool(
+    &mut self,
+    _fun: Value,
+    _items: Vec<Message>,
+    _collect: bool,
+  ) -> Result<PoolJob, String> {
+    Err("Workers are not supported in this context.".to_string())
+  }
 }
@@ -8,10 +8,15 @@ pub mod managed;
 pub mod memory;
 pub mod module;
 pub mod object;
+pub mod parse;
+pub mod plugin;
+#[cfg(feature = "serde")]
+pub mod serialize;
 pub mod signature;
 pub mod support;
 pub mod utils;
 pub mod value;
+pub mod worker;
 
 pub type Call = LyResult<value::Value>;
 pub type LyHashSet<K> = HashSet<K, FnvBuildHasher>;
@@ -20,14 +25,20 @@ use std::fmt;
 
 use fnv::FnvBuildHasher;
 use hashbrown::HashSet;
+use hooks::GcHooks;
 use managed::GcObj;
-use object::Instance;
+use object::{Instance, Tuple};
+use value::Value;
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum LyResult<T> {
   Ok(T),
   Err(GcObj<Instance>),
   Exit(u16),
+
+  /// The current fiber must block until it is woken, after
+  /// which the call is retried
+  Block,
 }
 
 #[macro_export]
@@ -37,6 +48,7 @@ macro_rules! get {
       Call::Ok(val) => val,
       Call::Err(e) => return Call::Err(e),
       Call::Exit(e) => return Call::Exit(e),
+      Call::Block => return Call::Block,
     }
   };
 }
@@ -65,12 +77,18 @@ impl<T> LyResult<T> {
     matches!(self, Self::Exit(_))
   }
 
+  #[inline]
+  pub fn is_block(&self) -> bool {
+    matches!(self, Self::Block)
+  }
+
   #[inline]
   pub fn map<U, F: FnOnce(T) -> U>(self, op: F) -> LyResult<U> {
     match self {
       Self::Ok(t) => LyResult::Ok(op(t)),
       Self::Err(e) => LyResult::Err(e),
       Self::Exit(e) => LyResult::Exit(e),
+      Self::Block => LyResult::Block,
     }
   }
 
@@ -80,6 +98,7 @@ impl<T> LyResult<T> {
       Self::Ok(t) => op(t),
       Self::Err(e) => LyResult::Err(e),
       Self::Exit(e) => LyResult::Exit(e),
+      Self::Block => LyResult::Block,
     }
   }
 
@@ -90,6 +109,7 @@ impl<T> LyResult<T> {
       Self::Ok(t) => t,
       Self::Err(e) => unwrap_failed(msg, &e),
       Self::Exit(e) => unwrap_failed(msg, &e),
+      Self::Block => unwrap_failed(msg, &"Block"),
     }
   }
 
@@ -98,6 +118,7 @@ impl<T> LyResult<T> {
       Self::Ok(t) => t,
       Self::Err(e) => unwrap_failed("called `LyResult::unwrap()` on an `Err` value", &e),
       Self::Exit(e) => unwrap_failed("called `LyResult::unwrap()` on an `Exit` value", &e),
+      Self::Block => unwrap_failed("called `LyResult::unwrap()` on a `Block` value", &"Block"),
     }
   }
 }
@@ -110,6 +131,15 @@ impl<T: fmt::Debug> LyResult<T> {
       Self::Ok(t) => unwrap_failed(msg, &t),
       Self::Err(e) => e,
       Self::Exit(e) => unwrap_failed(msg, &e),
+      Self::Block => unwrap_failed(msg, &"Block"),
     }
   }
 }
+
+impl LyResult<Value> {
+  /// Return several values from a native as a single tuple, which a
+  /// destructuring `let (a, b) = ...` unpacks directly onto the stack
+  pub fn tuple(hooks: &GcHooks, items: &[Value]) -> Self {
+    Self::Ok(val!(hooks.manage_obj(Tuple::new(items))))
+  }
+}
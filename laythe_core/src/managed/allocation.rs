@@ -44,6 +44,12 @@ impl<T: 'static + Manage> Allocation<T> {
     self.data.size() + mem::size_of::<Header>()
   }
 
+  /// Does this allocation need to be finalized before it is freed
+  #[inline]
+  pub fn has_finalizer(&self) -> bool {
+    self.data.has_finalizer()
+  }
+
   /// Get a trait object is can be logged for the heap
   pub fn as_debug(&self) -> &dyn DebugHeap {
     self.data.as_debug()
@@ -57,6 +63,17 @@ impl Allocation<dyn Manage> {
     self.data.size() + mem::size_of::<Header>()
   }
 
+  /// Does this allocation need to be finalized before it is freed
+  #[inline]
+  pub fn has_finalizer(&self) -> bool {
+    self.data.has_finalizer()
+  }
+
+  /// Run the finalizer of the underlying data
+  pub fn finalize(&mut self) {
+    self.data.finalize()
+  }
+
   /// Get a trait object is can be logged for the heap
   pub fn as_debug(&self) -> &dyn DebugHeap {
     self.data.as_debug()
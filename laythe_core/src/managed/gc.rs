@@ -57,6 +57,10 @@ impl<T: 'static + Manage> Trace for Gc<T> {
     self.obj().data.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    visitor(self.to_usize());
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     if self.obj().mark() {
       return;
@@ -115,6 +115,10 @@ impl<T: Trace, H: Send + Mark> Trace for GcArray<T, H> {
     self.iter().for_each(|i| i.trace());
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    visitor(self.as_alloc_ptr() as usize);
+  }
+
   fn trace_debug(&self, log: &mut dyn std::io::Write) {
     if self.mark() {
       return;
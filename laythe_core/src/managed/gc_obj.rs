@@ -2,17 +2,19 @@ extern crate alloc;
 
 use super::{
   manage::{DebugHeap, DebugWrap, Manage, Trace},
+  pool::ObjPool,
   utils::{get_offset, make_layout},
   GcStr, Mark, Marked, Unmark,
 };
 use crate::{
   object::{
-    Class, Closure, Enumerator, Fiber, Fun, Instance, List, Map, Method, Native, ObjectKind,
-    Upvalue,
+    Channel, Class, Closure, Enumerator, Fiber, Fun, Instance, List, Map, Method, Native,
+    ObjectKind, Tuple, Upvalue,
   },
   value::Value,
 };
 use std::{
+  alloc::Layout,
   cmp, fmt,
   hash::{Hash, Hasher},
   io::Write,
@@ -29,6 +31,9 @@ pub trait Object: Manage {
 
 #[macro_export]
 macro_rules! to_obj_kind {
+  ($o:expr, Channel) => {
+    $o.to_channel()
+  };
   ($o:expr, Class) => {
     $o.to_class()
   };
@@ -62,6 +67,9 @@ macro_rules! to_obj_kind {
   ($o:expr, String) => {
     $o.to_str()
   };
+  ($o:expr, Tuple) => {
+    $o.to_tuple()
+  };
   ($o:expr, Upvalue) => {
     $o.to_upvalue()
   };
@@ -98,6 +106,12 @@ pub struct ObjHeader {
 
   /// The underlying value kind of this object
   kind: ObjectKind,
+
+  /// Was this object allocated from an `ObjPool`
+  pooled: bool,
+
+  /// Has this object been frozen against further mutation
+  frozen: AtomicBool,
 }
 
 impl ObjHeader {
@@ -107,6 +121,8 @@ impl ObjHeader {
     Self {
       marked: AtomicBool::new(false),
       kind,
+      pooled: false,
+      frozen: AtomicBool::new(false),
     }
   }
 
@@ -115,6 +131,24 @@ impl ObjHeader {
   pub fn kind(&self) -> ObjectKind {
     self.kind
   }
+
+  /// Was this object allocated from an `ObjPool`
+  #[inline]
+  pub fn pooled(&self) -> bool {
+    self.pooled
+  }
+
+  /// Has this object been frozen
+  #[inline]
+  pub fn frozen(&self) -> bool {
+    self.frozen.load(Ordering::Acquire)
+  }
+
+  /// Freeze this object, a frozen object is never unfrozen
+  #[inline]
+  pub fn freeze(&self) {
+    self.frozen.store(true, Ordering::Release)
+  }
 }
 
 impl Mark for ObjHeader {
@@ -185,6 +219,18 @@ impl<T: 'static + Object> GcObj<T> {
     &*(self.ptr.as_ptr() as *const T)
   }
 
+  /// Has this object been frozen
+  #[inline]
+  pub fn is_frozen(&self) -> bool {
+    self.header().frozen()
+  }
+
+  /// Freeze this object so it rejects further mutation
+  #[inline]
+  pub fn freeze(&self) {
+    self.header().freeze()
+  }
+
   /// Degrade this `GcObj<T>` into a `GcObject`
   #[inline]
   pub fn degrade(self) -> GcObject {
@@ -217,6 +263,10 @@ impl<T: 'static + Object> Trace for GcObj<T> {
     self.data().trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    visitor(self.to_usize());
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     if self.mark() {
       return;
@@ -392,11 +442,30 @@ impl GcObject {
     return self.header().kind() == kind;
   }
 
+  /// Has this object been frozen
+  #[inline]
+  pub fn is_frozen(&self) -> bool {
+    self.header().frozen()
+  }
+
+  /// Freeze this object so it rejects further mutation
+  #[inline]
+  pub fn freeze(&self) {
+    self.header().freeze()
+  }
+
   #[inline]
   pub fn to_str(self) -> GcStr {
     unsafe { GcStr::from_alloc_ptr(self.ptr) }
   }
 
+  #[inline]
+  pub fn to_channel(self) -> GcObj<Channel> {
+    GcObj {
+      ptr: unsafe { self.data_ptr::<Channel>() },
+    }
+  }
+
   #[inline]
   pub fn to_class(self) -> GcObj<Class> {
     GcObj {
@@ -467,6 +536,13 @@ impl GcObject {
     }
   }
 
+  #[inline]
+  pub fn to_tuple(self) -> GcObj<Tuple> {
+    GcObj {
+      ptr: unsafe { self.data_ptr::<Tuple>() },
+    }
+  }
+
   #[inline]
   pub fn to_upvalue(self) -> GcObj<Upvalue> {
     GcObj {
@@ -480,9 +556,11 @@ impl fmt::Display for GcObject {
     match_obj!((self) {
       ObjectKind::String(string) => write!(f, "{}", string),
       ObjectKind::List(list) => write!(f, "{}", list),
+      ObjectKind::Tuple(tuple) => write!(f, "{}", tuple),
       ObjectKind::Map(map) => write!(f, "{}", map),
       ObjectKind::Fun(fun) => write!(f, "{}", fun),
       ObjectKind::Fiber(fiber) => write!(f, "{}", fiber),
+      ObjectKind::Channel(channel) => write!(f, "{}", channel),
       ObjectKind::Upvalue(upvalue) => write!(f, "{}", upvalue),
       ObjectKind::Closure(closure) => write!(f, "{}", closure),
       ObjectKind::Method(method) => write!(f, "{}", method),
@@ -499,9 +577,11 @@ impl fmt::Debug for GcObject {
     match_obj!((self) {
       ObjectKind::String(string) => write!(f, "{:?}", string),
       ObjectKind::List(list) => write!(f, "{:?}", list),
+      ObjectKind::Tuple(tuple) => write!(f, "{:?}", tuple),
       ObjectKind::Map(map) => write!(f, "{:?}", map),
       ObjectKind::Fun(fun) => write!(f, "{:?}", fun),
       ObjectKind::Fiber(fiber) => write!(f, "{:?}", fiber),
+      ObjectKind::Channel(channel) => write!(f, "{:?}", channel),
       ObjectKind::Upvalue(upvalue) => write!(f, "{:?}", upvalue),
       ObjectKind::Closure(closure) => write!(f, "{:?}", closure),
       ObjectKind::Method(method) => write!(f, "{:?}", method),
@@ -550,6 +630,9 @@ impl Trace for GcObject {
       ObjectKind::Fiber(fiber) => {
         fiber.trace();
       },
+      ObjectKind::Channel(channel) => {
+        channel.trace();
+      },
       ObjectKind::Instance(instance) => {
         instance.trace();
       },
@@ -568,12 +651,19 @@ impl Trace for GcObject {
       ObjectKind::String(string) => {
         string.trace();
       },
+      ObjectKind::Tuple(tuple) => {
+        tuple.trace();
+      },
       ObjectKind::Upvalue(upvalue) => {
         upvalue.trace();
       },
     });
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    visitor(self.ptr.as_ptr() as usize);
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     if self.marked() {
       return;
@@ -609,6 +699,9 @@ impl Trace for GcObject {
       ObjectKind::Fiber(fiber) => {
         trace_debug!(fiber);
       },
+      ObjectKind::Channel(channel) => {
+        trace_debug!(channel);
+      },
       ObjectKind::Instance(instance) => {
         trace_debug!(instance);
       },
@@ -627,6 +720,9 @@ impl Trace for GcObject {
       ObjectKind::String(string) => {
         trace_debug!(string);
       },
+      ObjectKind::Tuple(tuple) => {
+        trace_debug!(tuple);
+      },
       ObjectKind::Upvalue(upvalue) => {
         trace_debug!(upvalue);
       },
@@ -656,6 +752,9 @@ impl DebugHeap for GcObject {
       ObjectKind::Fiber(fiber) => {
         fiber.fmt_heap(f, depth)
       },
+      ObjectKind::Channel(channel) => {
+        channel.fmt_heap(f, depth)
+      },
       ObjectKind::Instance(instance) => {
         instance.fmt_heap(f, depth)
       },
@@ -674,6 +773,9 @@ impl DebugHeap for GcObject {
       ObjectKind::String(string) => {
         string.fmt_heap(f, depth)
       },
+      ObjectKind::Tuple(tuple) => {
+        tuple.fmt_heap(f, depth)
+      },
       ObjectKind::Upvalue(upvalue) => {
         upvalue.fmt_heap(f, depth)
       },
@@ -703,6 +805,12 @@ impl GcObjectHandle {
     GcObject { ptr: self.ptr }
   }
 
+  /// Return the address of this object's allocation
+  #[inline]
+  pub fn to_usize(&self) -> usize {
+    self.ptr.as_ptr() as usize
+  }
+
   #[inline]
   pub fn kind(&self) -> ObjectKind {
     self.header().kind()
@@ -710,27 +818,77 @@ impl GcObjectHandle {
 
   #[inline]
   pub fn size(&self) -> usize {
-    macro_rules! kind_size {
+    mem::size_of::<Self>() + self.layout().size()
+  }
+
+  /// The layout of this object's allocation
+  #[inline]
+  fn layout(&self) -> Layout {
+    macro_rules! kind_layout {
       ($o:ty) => {{
-        make_layout::<ObjHeader, $o>().size()
+        make_layout::<ObjHeader, $o>()
       }};
     }
 
-    mem::size_of::<Self>()
-      + match self.kind() {
-        ObjectKind::Fiber => kind_size!(Fiber),
-        ObjectKind::List => kind_size!(List<Value>),
-        ObjectKind::Map => kind_size!(Map<Value, Value>),
-        ObjectKind::Fun => kind_size!(Fun),
-        ObjectKind::Closure => kind_size!(Closure),
-        ObjectKind::Class => kind_size!(Class),
-        ObjectKind::Instance => kind_size!(Instance),
-        ObjectKind::Enumerator => kind_size!(Enumerator),
-        ObjectKind::Method => kind_size!(Method),
-        ObjectKind::Native => kind_size!(Native),
-        ObjectKind::Upvalue => kind_size!(Upvalue),
-        _ => panic!("Boolean, number, string or nil should be in a GcObjectHandle"),
-      }
+    match self.kind() {
+      ObjectKind::Fiber => kind_layout!(Fiber),
+      ObjectKind::Channel => kind_layout!(Channel),
+      ObjectKind::List => kind_layout!(List<Value>),
+      ObjectKind::Map => kind_layout!(Map<Value, Value>),
+      ObjectKind::Fun => kind_layout!(Fun),
+      ObjectKind::Closure => kind_layout!(Closure),
+      ObjectKind::Class => kind_layout!(Class),
+      ObjectKind::Instance => kind_layout!(Instance),
+      ObjectKind::Enumerator => kind_layout!(Enumerator),
+      ObjectKind::Method => kind_layout!(Method),
+      ObjectKind::Native => kind_layout!(Native),
+      ObjectKind::Tuple => kind_layout!(Tuple),
+      ObjectKind::Upvalue => kind_layout!(Upvalue),
+      _ => panic!("Boolean, number, string or nil should be in a GcObjectHandle"),
+    }
+  }
+
+  /// Drop this object returning its memory to the pool
+  /// it was allocated from
+  pub fn free(self, pool: &mut ObjPool) {
+    let pooled = self.header().pooled();
+    let layout = self.layout();
+    let ptr = self.ptr;
+
+    drop(self);
+
+    if pooled {
+      unsafe { pool.free(ptr, layout) }
+    }
+  }
+
+  /// Report the address of each managed allocation
+  /// directly referenced by this object
+  pub fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    macro_rules! visit_kind {
+      ($o:ty) => {{
+        let offset = get_offset::<ObjHeader, $o>();
+        let data = unsafe { &*(self.ptr.as_ptr().add(offset) as *const $o) };
+        data.visit_refs(visitor)
+      }};
+    }
+
+    match self.kind() {
+      ObjectKind::Fiber => visit_kind!(Fiber),
+      ObjectKind::Channel => visit_kind!(Channel),
+      ObjectKind::List => visit_kind!(List<Value>),
+      ObjectKind::Map => visit_kind!(Map<Value, Value>),
+      ObjectKind::Fun => visit_kind!(Fun),
+      ObjectKind::Closure => visit_kind!(Closure),
+      ObjectKind::Class => visit_kind!(Class),
+      ObjectKind::Instance => visit_kind!(Instance),
+      ObjectKind::Enumerator => visit_kind!(Enumerator),
+      ObjectKind::Method => visit_kind!(Method),
+      ObjectKind::Native => visit_kind!(Native),
+      ObjectKind::Tuple => visit_kind!(Tuple),
+      ObjectKind::Upvalue => visit_kind!(Upvalue),
+      _ => panic!("Boolean, number, string or nil should be in a GcObjectHandle"),
+    }
   }
 }
 
@@ -739,6 +897,7 @@ impl Drop for GcObjectHandle {
   fn drop(&mut self) {
     unsafe {
       let kind = self.kind();
+      let pooled = self.header().pooled();
 
       #[allow(clippy::cast_ptr_alignment)]
       ptr::read(self.ptr.as_ptr() as *const ObjHeader);
@@ -747,7 +906,11 @@ impl Drop for GcObjectHandle {
         ($o:ty) => {{
           let offset = get_offset::<ObjHeader, $o>();
           ptr::read(self.ptr.as_ptr().add(offset) as *const $o);
-          alloc::alloc::dealloc(self.ptr.as_ptr(), make_layout::<ObjHeader, $o>());
+
+          // pooled memory is returned to its pool by `free`
+          if !pooled {
+            alloc::alloc::dealloc(self.ptr.as_ptr(), make_layout::<ObjHeader, $o>());
+          }
         }};
       }
 
@@ -755,6 +918,7 @@ impl Drop for GcObjectHandle {
         ObjectKind::List => drop_kind!(List<Value>),
         ObjectKind::Map => drop_kind!(Map<Value, Value>),
         ObjectKind::Fiber => drop_kind!(Fiber),
+        ObjectKind::Channel => drop_kind!(Channel),
         ObjectKind::Fun => drop_kind!(Fun),
         ObjectKind::Closure => drop_kind!(Closure),
         ObjectKind::Class => drop_kind!(Class),
@@ -762,6 +926,7 @@ impl Drop for GcObjectHandle {
         ObjectKind::Enumerator => drop_kind!(Enumerator),
         ObjectKind::Method => drop_kind!(Method),
         ObjectKind::Native => drop_kind!(Native),
+        ObjectKind::Tuple => drop_kind!(Tuple),
         ObjectKind::Upvalue => drop_kind!(Upvalue),
         _ => panic!("Boolean, number, string or nil should not be in a GcObjectHandle"),
       }
@@ -828,6 +993,34 @@ impl<T> GcObjectHandleBuilder<T> {
   }
 }
 
+impl<T: 'static + Object> GcObjectHandleBuilder<T> {
+  /// Create a new object allocating from `pool` when the
+  /// object is small enough
+  #[inline]
+  pub fn from_pool(item: T, pool: &mut ObjPool) -> Self {
+    match pool.alloc(make_layout::<ObjHeader, T>()) {
+      Some(buf) => unsafe { Self::write(buf.as_ptr(), item, true) },
+      None => Self::from(item),
+    }
+  }
+
+  /// Write the header and item into the provided buffer
+  #[inline]
+  unsafe fn write(buf: *mut u8, item: T, pooled: bool) -> Self {
+    let mut header = ObjHeader::new(item.kind());
+    header.pooled = pooled;
+
+    #[allow(clippy::cast_ptr_alignment)]
+    ptr::write(buf as *mut ObjHeader, header);
+    ptr::write(buf.add(get_offset::<ObjHeader, T>()) as *mut T, item);
+
+    GcObjectHandleBuilder {
+      ptr: NonNull::new_unchecked(buf),
+      phantom: PhantomData,
+    }
+  }
+}
+
 impl<T: 'static + Object> From<T> for GcObjectHandleBuilder<T> {
   #[inline]
   fn from(item: T) -> Self {
@@ -838,18 +1031,7 @@ impl<T: 'static + Object> From<T> for GcObjectHandleBuilder<T> {
       alloc::alloc::handle_alloc_error(new_layout);
     }
 
-    let header = ObjHeader::new(item.kind());
-
-    #[allow(clippy::cast_ptr_alignment)]
-    unsafe {
-      ptr::write(buf as *mut ObjHeader, header);
-      ptr::write(buf.add(get_offset::<ObjHeader, T>()) as *mut T, item);
-
-      GcObjectHandleBuilder {
-        ptr: NonNull::new_unchecked(buf),
-        phantom: PhantomData,
-      }
-    }
+    unsafe { Self::write(buf, item, false) }
   }
 }
 
@@ -865,6 +1047,15 @@ mod test {
       let header = ObjHeader::new(ObjectKind::Class);
       assert_eq!(header.kind(), ObjectKind::Class);
     }
+
+    #[test]
+    pub fn freeze() {
+      let header = ObjHeader::new(ObjectKind::List);
+      assert!(!header.frozen());
+
+      header.freeze();
+      assert!(header.frozen());
+    }
   }
 
   mod gc_object_handle_builder {
@@ -112,6 +112,10 @@ impl Trace for GcStr {
     self.mark();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    visitor(self.to_usize());
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     if self.mark() {
       return;
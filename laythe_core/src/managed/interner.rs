@@ -0,0 +1,140 @@
+use super::{GcStr, Marked};
+use hashbrown::{
+  hash_map::{DefaultHashBuilder, RawEntryMut},
+  HashMap,
+};
+use std::hash::BuildHasher;
+
+/// The intern table for strings. Every `GcStr` is interned so each distinct
+/// string has exactly one allocation. The allocation acts as the string's
+/// id which is stable for as long as the string is alive, letting equality
+/// and hashing of two strings be a pointer comparison instead of comparing
+/// their contents.
+///
+/// Strings are looked up by content while the table itself only holds
+/// the managed strings, so no borrowed key needs to outlive its string
+pub struct StringInterner {
+  /// The interned strings. `GcStr` hashes by id so this table is only
+  /// accessed through raw entries keyed by the string's content hash
+  table: HashMap<GcStr, ()>,
+
+  /// The hasher for string contents
+  hasher: DefaultHashBuilder,
+}
+
+impl Default for StringInterner {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl StringInterner {
+  /// Create a new empty interner
+  pub fn new() -> Self {
+    Self {
+      table: HashMap::new(),
+      hasher: DefaultHashBuilder::default(),
+    }
+  }
+
+  /// The number of interned strings
+  pub fn len(&self) -> usize {
+    self.table.len()
+  }
+
+  /// Is the interner empty
+  pub fn is_empty(&self) -> bool {
+    self.table.is_empty()
+  }
+
+  /// Get the interned string matching `string` if present
+  pub fn get(&self, string: &str) -> Option<GcStr> {
+    let hash = content_hash(&self.hasher, string);
+
+    self
+      .table
+      .raw_entry()
+      .from_hash(hash, |interned| &**interned == string)
+      .map(|(interned, _)| *interned)
+  }
+
+  /// Intern a newly allocated string. The string must
+  /// not match any string already interned
+  pub fn insert(&mut self, string: GcStr) {
+    let hasher = &self.hasher;
+    let hash = content_hash(hasher, &string);
+
+    match self
+      .table
+      .raw_entry_mut()
+      .from_hash(hash, |interned| **interned == *string)
+    {
+      RawEntryMut::Vacant(entry) => {
+        // growing the table must rehash by content rather than by id
+        entry.insert_with_hasher(hash, string, (), |interned| content_hash(hasher, interned));
+      },
+      RawEntryMut::Occupied(_) => debug_assert!(false, "String already interned"),
+    }
+  }
+
+  /// Remove strings that were not marked during the last trace
+  pub fn retain_marked(&mut self) {
+    self.table.retain(|string, _| string.marked());
+  }
+}
+
+/// Hash the contents of a string
+fn content_hash(hasher: &DefaultHashBuilder, string: &str) -> u64 {
+  hasher.hash_one(string)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::managed::{GcStrHandle, Mark};
+
+  #[test]
+  fn get() {
+    let mut interner = StringInterner::new();
+    let handle = GcStrHandle::from("example");
+
+    assert_eq!(interner.get("example"), None);
+    interner.insert(handle.value());
+
+    assert_eq!(interner.get("example"), Some(handle.value()));
+    assert_eq!(interner.get("other"), None);
+    assert_eq!(interner.len(), 1);
+  }
+
+  #[test]
+  fn grow() {
+    let mut interner = StringInterner::new();
+    let handles: Vec<GcStrHandle> = (0..1000)
+      .map(|i| GcStrHandle::from(i.to_string().as_str()))
+      .collect();
+
+    for handle in &handles {
+      interner.insert(handle.value());
+    }
+
+    for (i, handle) in handles.iter().enumerate() {
+      assert_eq!(interner.get(&i.to_string()), Some(handle.value()));
+    }
+  }
+
+  #[test]
+  fn retain_marked() {
+    let mut interner = StringInterner::new();
+    let kept = GcStrHandle::from("kept");
+    let removed = GcStrHandle::from("removed");
+
+    interner.insert(kept.value());
+    interner.insert(removed.value());
+    kept.value().mark();
+
+    interner.retain_marked();
+    assert_eq!(interner.get("kept"), Some(kept.value()));
+    assert_eq!(interner.get("removed"), None);
+    assert_eq!(interner.len(), 1);
+  }
+}
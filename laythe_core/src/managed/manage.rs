@@ -52,6 +52,10 @@ pub trait Trace: Send {
   /// Mark all objects that are reachable from this object
   fn trace(&self) {}
 
+  /// Report the address of each managed allocation directly referenced
+  /// by this entity without marking them. Used to produce heap dumps
+  fn visit_refs(&self, _visitor: &mut dyn FnMut(usize)) {}
+
   /// Mark all objects that are reachable printing debugging information
   /// for each object
   fn trace_debug(&self, _log: &mut dyn Write) {}
@@ -72,10 +76,44 @@ pub trait TraceRoot {
 
 /// An entity that can be managed and collected by the garbage collector.
 /// This trait provided debugging capabilities and statistics for the gc.
+///
+/// # Finalization
+/// Entities holding external resources such as file handles or sockets can
+/// release them by implementing `finalize` and returning true from
+/// `has_finalizer`. The collector guarantees
+///
+/// - A finalizer is run at most once, either when the entity is found to be
+///   unreachable or when the allocator itself is dropped.
+/// - All finalizers of a collection run after marking and before any
+///   allocation is freed, so a finalizer may still read managed data it
+///   references even if that data is also unreachable.
+/// - Finalizers within a collection run in allocation order.
+///
+/// A finalizer must not allocate or store references to managed data
+/// elsewhere as the entity is freed regardless once it completes.
+///
+/// Only entities allocated with `Allocator::manage` are finalized. Objects
+/// allocated with `Allocator::manage_obj` are a fixed set of kinds that hold
+/// no external resources, so allocating one with a finalizer panics.
 pub trait Manage: Trace + DebugHeap {
   /// What is the size of this allocation
   fn size(&self) -> usize;
 
   /// Helper function to get a trait object for Debug Heap
   fn as_debug(&self) -> &dyn DebugHeap;
+
+  /// The name of this entity's type without its module path or generics
+  fn type_name(&self) -> &'static str {
+    let name = std::any::type_name::<Self>();
+    let name = &name[..name.find('<').unwrap_or(name.len())];
+    name.rsplit("::").next().unwrap_or(name)
+  }
+
+  /// Does this entity need to be finalized before it is freed
+  fn has_finalizer(&self) -> bool {
+    false
+  }
+
+  /// Release any external resources held by this entity
+  fn finalize(&mut self) {}
 }
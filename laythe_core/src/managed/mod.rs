@@ -3,7 +3,9 @@ mod gc;
 mod gc_array;
 mod gc_obj;
 mod gc_str;
+mod interner;
 mod manage;
+mod pool;
 mod utils;
 
 pub use allocation::Allocation;
@@ -11,6 +13,8 @@ pub use gc::Gc;
 pub use gc_array::{GcArray, GcArrayHandle};
 pub use gc_obj::{GcObj, GcObject, GcObjectHandle, GcObjectHandleBuilder, Object};
 pub use gc_str::{GcStr, GcStrHandle};
+pub use interner::StringInterner;
 pub use manage::{
   DebugHeap, DebugWrap, DebugWrapDyn, Manage, Mark, Marked, Trace, TraceRoot, Unmark,
 };
+pub use pool::{ObjPool, MAX_POOLED_SIZE};
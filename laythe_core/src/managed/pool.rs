@@ -0,0 +1,254 @@
+use std::{
+  alloc::{self, Layout},
+  array, mem,
+  ptr::{self, NonNull},
+};
+
+/// The granularity and maximum alignment of each size class
+const CLASS_ALIGN: usize = mem::align_of::<usize>();
+
+/// The number of size classes in the pool
+const CLASS_COUNT: usize = 8;
+
+/// The largest allocation in bytes served by the pool
+pub const MAX_POOLED_SIZE: usize = CLASS_ALIGN * CLASS_COUNT;
+
+/// The size in bytes of each chunk carved into blocks
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// A pool of fixed size blocks for small objects. Each size class carves
+/// blocks out of large chunks so objects of similar size sit next to each
+/// other, and freed blocks are recycled through a free list instead of being
+/// returned to the system allocator. Chunks are only released once the
+/// pool is dropped
+pub struct ObjPool {
+  classes: [SizeClass; CLASS_COUNT],
+}
+
+impl Default for ObjPool {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl ObjPool {
+  /// Create a new empty pool
+  pub fn new() -> Self {
+    Self {
+      classes: array::from_fn(|index| SizeClass::new((index + 1) * CLASS_ALIGN)),
+    }
+  }
+
+  /// Can an allocation with this layout be served by the pool
+  #[inline]
+  pub fn is_pooled(layout: Layout) -> bool {
+    layout.size() != 0 && layout.size() <= MAX_POOLED_SIZE && layout.align() <= CLASS_ALIGN
+  }
+
+  /// Allocate a block for the provided layout, returning None
+  /// if the layout is too large for the pool
+  #[inline]
+  pub fn alloc(&mut self, layout: Layout) -> Option<NonNull<u8>> {
+    if !Self::is_pooled(layout) {
+      return None;
+    }
+
+    Some(self.classes[class_index(layout)].alloc())
+  }
+
+  /// Return a block to the pool
+  ///
+  /// # Safety
+  /// The block must have been allocated from this pool with the same layout
+  /// and must not be used after this call
+  #[inline]
+  pub unsafe fn free(&mut self, block: NonNull<u8>, layout: Layout) {
+    debug_assert!(Self::is_pooled(layout));
+    self.classes[class_index(layout)].free(block)
+  }
+
+  /// The total bytes reserved by the pool from the system allocator
+  pub fn reserved(&self) -> usize {
+    self
+      .classes
+      .iter()
+      .map(|class| class.chunks.len() * CHUNK_SIZE)
+      .sum()
+  }
+}
+
+impl Drop for ObjPool {
+  /// Release every chunk. Blocks still in use are invalidated so the
+  /// pool must outlive every object allocated from it
+  fn drop(&mut self) {
+    self.classes.iter_mut().for_each(SizeClass::release);
+  }
+}
+
+/// The size class serving this layout
+#[inline]
+fn class_index(layout: Layout) -> usize {
+  layout.size().div_ceil(CLASS_ALIGN) - 1
+}
+
+/// A block on the free list. The link to the next
+/// free block is stored in the block itself
+struct FreeBlock {
+  next: Option<NonNull<FreeBlock>>,
+}
+
+/// A set of blocks of a single size
+struct SizeClass {
+  /// The size in bytes of each block
+  block_size: usize,
+
+  /// The most recently freed block
+  free: Option<NonNull<FreeBlock>>,
+
+  /// The next unused block in the current chunk
+  bump: *mut u8,
+
+  /// The bytes remaining after bump in the current chunk
+  remaining: usize,
+
+  /// Every chunk allocated by this class
+  chunks: Vec<NonNull<u8>>,
+}
+
+impl SizeClass {
+  fn new(block_size: usize) -> Self {
+    Self {
+      block_size,
+      free: None,
+      bump: ptr::null_mut(),
+      remaining: 0,
+      chunks: vec![],
+    }
+  }
+
+  #[inline]
+  fn alloc(&mut self) -> NonNull<u8> {
+    if let Some(block) = self.free {
+      self.free = unsafe { block.as_ref().next };
+      return block.cast();
+    }
+
+    if self.remaining < self.block_size {
+      self.grow();
+    }
+
+    let block = self.bump;
+    self.bump = unsafe { block.add(self.block_size) };
+    self.remaining -= self.block_size;
+    unsafe { NonNull::new_unchecked(block) }
+  }
+
+  #[inline]
+  unsafe fn free(&mut self, block: NonNull<u8>) {
+    let block = block.cast::<FreeBlock>();
+    block.as_ptr().write(FreeBlock { next: self.free });
+    self.free = Some(block);
+  }
+
+  /// Start carving blocks from a new chunk
+  #[inline(never)]
+  fn grow(&mut self) {
+    let layout = chunk_layout();
+    let chunk = unsafe { alloc::alloc(layout) };
+
+    let chunk = match NonNull::new(chunk) {
+      Some(chunk) => chunk,
+      None => alloc::handle_alloc_error(layout),
+    };
+
+    self.chunks.push(chunk);
+    self.bump = chunk.as_ptr();
+    self.remaining = CHUNK_SIZE;
+  }
+
+  /// Return every chunk to the system allocator
+  fn release(&mut self) {
+    for chunk in self.chunks.drain(..) {
+      unsafe { alloc::dealloc(chunk.as_ptr(), chunk_layout()) }
+    }
+
+    self.free = None;
+    self.bump = ptr::null_mut();
+    self.remaining = 0;
+  }
+}
+
+fn chunk_layout() -> Layout {
+  Layout::from_size_align(CHUNK_SIZE, CLASS_ALIGN).expect("Invalid chunk layout")
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn layout(size: usize) -> Layout {
+    Layout::from_size_align(size, CLASS_ALIGN).unwrap()
+  }
+
+  #[test]
+  fn is_pooled() {
+    assert!(ObjPool::is_pooled(layout(1)));
+    assert!(ObjPool::is_pooled(layout(MAX_POOLED_SIZE)));
+    assert!(!ObjPool::is_pooled(layout(0)));
+    assert!(!ObjPool::is_pooled(layout(MAX_POOLED_SIZE + 1)));
+    assert!(!ObjPool::is_pooled(
+      Layout::from_size_align(16, CLASS_ALIGN * 2).unwrap()
+    ));
+  }
+
+  #[test]
+  fn alloc() {
+    let mut pool = ObjPool::new();
+
+    let a = pool.alloc(layout(24)).unwrap();
+    let b = pool.alloc(layout(20)).unwrap();
+    let c = pool.alloc(layout(32)).unwrap();
+
+    assert_eq!(unsafe { b.as_ptr().offset_from(a.as_ptr()) }, 24);
+    assert_eq!(a.as_ptr() as usize % CLASS_ALIGN, 0);
+    assert_eq!(c.as_ptr() as usize % CLASS_ALIGN, 0);
+    assert!(pool.alloc(layout(MAX_POOLED_SIZE + 1)).is_none());
+  }
+
+  #[test]
+  fn free_reuses() {
+    let mut pool = ObjPool::new();
+
+    let a = pool.alloc(layout(32)).unwrap();
+    let b = pool.alloc(layout(32)).unwrap();
+    unsafe {
+      pool.free(a, layout(32));
+      pool.free(b, layout(32));
+    }
+
+    assert_eq!(pool.alloc(layout(32)), Some(b));
+    assert_eq!(pool.alloc(layout(32)), Some(a));
+  }
+
+  #[test]
+  fn grow() {
+    let mut pool = ObjPool::new();
+
+    for _ in 0..(CHUNK_SIZE / 64 + 1) {
+      pool.alloc(layout(64)).unwrap();
+    }
+
+    assert_eq!(pool.reserved(), CHUNK_SIZE * 2);
+  }
+
+  #[test]
+  fn drop_releases_chunks() {
+    let mut pool = ObjPool::new();
+    pool.alloc(layout(8)).unwrap();
+    pool.alloc(layout(64)).unwrap();
+    assert_eq!(pool.reserved(), CHUNK_SIZE * 2);
+
+    pool.classes.iter_mut().for_each(SizeClass::release);
+    assert_eq!(pool.reserved(), 0);
+  }
+}
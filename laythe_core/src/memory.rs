@@ -1,11 +1,13 @@
 use crate::managed::{
   Allocation, Gc, GcObj, GcObjectHandle, GcObjectHandleBuilder, GcStr, GcStrHandle, Manage, Marked,
-  Object, Trace, TraceRoot, Unmark,
+  ObjPool, Object, StringInterner, Trace, TraceRoot, Unmark,
 };
-use hashbrown::HashMap;
 use laythe_env::stdio::Stdio;
 use std::ptr::NonNull;
-use std::{cell::RefCell, io::Write};
+use std::{
+  cell::RefCell,
+  io::{self, Write},
+};
 
 #[cfg(any(feature = "gc_log_free", feature = "gc_log_alloc"))]
 use crate::managed::DebugWrap;
@@ -31,54 +33,132 @@ pub struct Allocator {
   /// The heap specifically for strings
   str_heap: Vec<GcStrHandle>,
 
+  /// Pooled memory for small objects. This must be dropped after
+  /// both object heaps
+  obj_pool: ObjPool,
+
   /// A collection of temporary roots in the gc
   temp_roots: Vec<Box<dyn Trace>>,
 
   /// The total byte allocated in both heaps
   bytes_allocated: usize,
 
-  /// The intern string cache
-  intern_cache: HashMap<&'static str, GcStr>,
+  /// The intern table for strings
+  interner: StringInterner,
 
   /// The size in bytes of the gc before the next collection
   next_gc: usize,
 
+  /// How much the heap may grow relative to the live heap before the next collection
+  grow_factor: usize,
+
   /// The total number of garbage collections that have occured
   gc_count: u128,
+
+  /// The maximum size in bytes the heap may grow to
+  max_heap_bytes: Option<usize>,
+
+  /// Did the heap exceed its maximum size after a full collection
+  out_of_memory: bool,
+
+  /// Called when the heap is flagged as out of memory
+  on_out_of_memory: Option<Box<dyn Fn()>>,
+
+  /// The number of allocations in the heap with a finalizer
+  finalizers: usize,
+
+  /// Collect on every allocation to shake out missing roots
+  stress: bool,
 }
 
-const GC_HEAP_GROW_FACTOR: usize = 2;
+/// The default factor the heap may grow by before the next collection
+pub const GC_HEAP_GROW_FACTOR: usize = 2;
+
+/// The default size in bytes of the heap before the first collection
+pub const INITIAL_GC: usize = 1024 * 1024;
 
 impl<'a> Allocator {
-  /// Create a new manged heap for laythe for objects.
+  /// Create a new manged heap for laythe for objects. When `max_heap_bytes` is
+  /// provided the heap is flagged as out of memory once a full collection can
+  /// no longer bring it under this size.
   ///
   /// # Examples
   /// ```
   /// use laythe_core::memory::Allocator;
   /// use laythe_env::stdio::Stdio;
   ///
-  /// let gc = Allocator::new(Stdio::default());
+  /// let gc = Allocator::new(Stdio::default(), Some(64 * 1024 * 1024));
   /// ```
-  pub fn new(stdio: Stdio) -> Self {
+  pub fn new(stdio: Stdio, max_heap_bytes: Option<usize>) -> Self {
     Self {
       stdio: RefCell::new(stdio),
       heap: vec![],
       str_heap: vec![],
       obj_heap: vec![],
       nursery_obj_heap: vec![],
+      obj_pool: ObjPool::new(),
       bytes_allocated: 0,
       temp_roots: vec![],
-      intern_cache: HashMap::new(),
-      next_gc: 1024 * 1024,
+      interner: StringInterner::new(),
+      next_gc: next_gc(0, INITIAL_GC, max_heap_bytes),
+      grow_factor: GC_HEAP_GROW_FACTOR,
       gc_count: 0,
+      max_heap_bytes,
+      out_of_memory: false,
+      on_out_of_memory: None,
+      finalizers: 0,
+      stress: cfg!(feature = "gc_stress"),
     }
   }
 
+  /// Set the heap size in bytes that triggers the first collection and
+  /// the factor the heap may grow by between later collections
+  pub fn with_thresholds(mut self, initial_gc: usize, grow_factor: usize) -> Self {
+    self.next_gc = next_gc(self.bytes_allocated, initial_gc, self.max_heap_bytes);
+    self.grow_factor = grow_factor.max(1);
+    self
+  }
+
+  /// Collect garbage on every allocation. Each collection is also a full
+  /// collection of the object heap
+  pub fn with_stress(mut self, stress: bool) -> Self {
+    self.stress = stress || cfg!(feature = "gc_stress");
+    self
+  }
+
+  /// Call the provided hook each time the heap is flagged as out of
+  /// memory so the owner can raise the error at its next opportunity
+  pub fn with_out_of_memory_hook(mut self, hook: impl Fn() + 'static) -> Self {
+    self.on_out_of_memory = Some(Box::new(hook));
+    self
+  }
+
   /// Get the number of bytes allocated
   pub fn allocated(&self) -> usize {
     self.bytes_allocated
   }
 
+  /// The number of collections run so far
+  pub fn collections(&self) -> u128 {
+    self.gc_count
+  }
+
+  /// The maximum size in bytes the heap may grow to
+  pub fn max_heap_bytes(&self) -> Option<usize> {
+    self.max_heap_bytes
+  }
+
+  /// Has the heap exceeded its maximum size after a full collection
+  #[inline]
+  pub fn is_out_of_memory(&self) -> bool {
+    self.out_of_memory
+  }
+
+  /// Clear the out of memory flag once it has been reported
+  pub fn clear_out_of_memory(&mut self) {
+    self.out_of_memory = false;
+  }
+
   /// How many temporary roots are present
   pub fn temp_roots(&self) -> usize {
     self.temp_roots.len()
@@ -99,6 +179,9 @@ impl<'a> Allocator {
   /// for `data` and return a pointer to it. In case of a gc the provided `context` is
   /// used to annotate active roots
   ///
+  /// # Panics
+  /// Objects are never finalized so this panics if `data` has a finalizer
+  ///
   /// # Examples
   /// ```
   /// use laythe_core::memory::{Allocator, NO_GC};
@@ -118,10 +201,9 @@ impl<'a> Allocator {
     self.allocate_obj(data, context)
   }
 
-  /// Create a `Managed<String>` from a str slice. This creates
-  /// or returns an interned string and allocates a pointer to the intern
-  /// cache. A Managed<String> can be created from `.manage` but will
-  /// not intern the string.
+  /// Create a `GcStr` from a str slice. This returns the interned string
+  /// if one exists, otherwise allocating and interning a new string so each
+  /// distinct string has a single allocation.
   ///
   /// # Examples
   /// ```
@@ -134,16 +216,20 @@ impl<'a> Allocator {
   /// ```
   pub fn manage_str<S: AsRef<str>, C: TraceRoot + ?Sized>(&mut self, src: S, context: &C) -> GcStr {
     let string = src.as_ref();
-    if let Some(cached) = self.intern_cache.get(string) {
-      return *cached;
+    if let Some(interned) = self.interner.get(string) {
+      return interned;
     }
 
     let managed = self.allocate_str(string, context);
-    let static_str: &'static str = unsafe { &*(&*managed as *const str) };
-    self.intern_cache.insert(&static_str, managed);
+    self.interner.insert(managed);
     managed
   }
 
+  /// Find the interned string matching a str slice without allocating
+  pub fn get_str(&self, src: &str) -> Option<GcStr> {
+    self.interner.get(src)
+  }
+
   /// track events that may grow the size of the heap. If
   /// a heap grows beyond the current threshold will trigger a gc
   pub fn grow<T: 'static + Manage, R, F: FnOnce(&mut T) -> R, C: TraceRoot + ?Sized>(
@@ -163,11 +249,11 @@ impl<'a> Allocator {
     // collect if need be
     #[cfg(feature = "debug_stress_gc")]
     {
-      self.collect_garbage(context);
+      self.collect_garbage(context, false);
     }
 
     if self.bytes_allocated > self.next_gc {
-      self.collect_garbage(context);
+      self.collect_garbage(context, false);
     }
 
     result
@@ -190,6 +276,83 @@ impl<'a> Allocator {
     result
   }
 
+  /// Run a full collection freeing every object unreachable from
+  /// the provided context
+  pub fn collect_full<C: TraceRoot + ?Sized>(&mut self, context: &C) {
+    self.collect_garbage(context, true);
+  }
+
+  /// Write a snapshot of every allocation in the heap as json. Each
+  /// allocation lists its kind, size in bytes and the addresses of the
+  /// allocations it directly references
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_core::memory::{Allocator, NO_GC};
+  /// use laythe_core::object::List;
+  /// use laythe_core::value::Value;
+  ///
+  /// let mut gc = Allocator::default();
+  /// let string = gc.manage_str("hi!", &NO_GC);
+  /// gc.manage_obj(List::from(vec![Value::from(string)]), &NO_GC);
+  ///
+  /// let mut dump = vec![];
+  /// gc.heap_dump(&mut dump).unwrap();
+  ///
+  /// let dump = String::from_utf8(dump).unwrap();
+  /// assert!(dump.contains("\"kind\":\"List\""));
+  /// assert!(dump.contains(&format!("\"refs\":[\"{:p}\"]", string)));
+  /// ```
+  pub fn heap_dump(&self, out: &mut dyn Write) -> io::Result<()> {
+    write!(
+      out,
+      "{{\"bytes_allocated\":{},\"objects\":[",
+      self.bytes_allocated
+    )?;
+
+    let mut first = true;
+
+    for item in &self.heap {
+      let kind = item.data.type_name();
+
+      let mut refs = vec![];
+      item.data.visit_refs(&mut |reference| refs.push(reference));
+
+      let id = &**item as *const Allocation<dyn Manage> as *const () as usize;
+      write_dump_entry(out, &mut first, "heap", id, kind, item.size(), refs)?;
+    }
+
+    let obj_heaps = [
+      ("nursery", &self.nursery_obj_heap),
+      ("object", &self.obj_heap),
+    ];
+    for (heap, objects) in obj_heaps.iter() {
+      for obj in objects.iter() {
+        let mut refs = vec![];
+        obj.visit_refs(&mut |reference| refs.push(reference));
+
+        let kind = format!("{:?}", obj.kind());
+        let id = obj.to_usize();
+        write_dump_entry(out, &mut first, heap, id, &kind, obj.size(), refs)?;
+      }
+    }
+
+    for string in &self.str_heap {
+      let id = string.value().to_usize();
+      write_dump_entry(
+        out,
+        &mut first,
+        "string",
+        id,
+        "String",
+        string.size(),
+        vec![],
+      )?;
+    }
+
+    writeln!(out, "\n]}}")
+  }
+
   /// Push a new temporary root onto the gc to avoid collection
   pub fn push_root<T: 'static + Trace>(&mut self, managed: T) {
     self.temp_roots.push(Box::new(managed));
@@ -220,6 +383,10 @@ impl<'a> Allocator {
 
     let size = alloc.size();
 
+    if alloc.has_finalizer() {
+      self.finalizers += 1;
+    }
+
     // push onto heap
     self.bytes_allocated += size;
     self.heap.push(alloc);
@@ -229,16 +396,9 @@ impl<'a> Allocator {
     #[cfg(feature = "gc_log_alloc")]
     self.debug_allocate(ptr, size);
 
-    #[cfg(feature = "gc_stress")]
-    {
+    if self.should_collect() {
       self.push_root(managed);
-      self.collect_garbage(context);
-      self.pop_roots(1)
-    }
-
-    if self.bytes_allocated > self.next_gc {
-      self.push_root(managed);
-      self.collect_garbage(context);
+      self.collect_garbage(context, false);
       self.pop_roots(1)
     }
 
@@ -250,8 +410,13 @@ impl<'a> Allocator {
     data: T,
     context: &C,
   ) -> GcObj<T> {
+    assert!(
+      !data.has_finalizer(),
+      "Objects are not finalized, allocate with manage instead."
+    );
+
     // create own store of allocation
-    let object_handle = GcObjectHandleBuilder::from(data);
+    let object_handle = GcObjectHandleBuilder::from_pool(data, &mut self.obj_pool);
     let size = object_handle.size();
 
     let obj = object_handle.value();
@@ -263,16 +428,9 @@ impl<'a> Allocator {
     #[cfg(feature = "gc_log_alloc")]
     self.debug_allocate_obj(obj, size);
 
-    #[cfg(feature = "gc_stress")]
-    {
+    if self.should_collect() {
       self.push_root(obj);
-      self.collect_garbage(context);
-      self.pop_roots(1)
-    }
-
-    if self.bytes_allocated > self.next_gc {
-      self.push_root(obj);
-      self.collect_garbage(context);
+      self.collect_garbage(context, false);
       self.pop_roots(1)
     }
 
@@ -296,16 +454,9 @@ impl<'a> Allocator {
     #[cfg(feature = "gc_log_alloc")]
     self.debug_allocate_str(gc_string, size);
 
-    #[cfg(feature = "gc_stress")]
-    {
-      self.push_root(gc_string);
-      self.collect_garbage(context);
-      self.pop_roots(1)
-    }
-
-    if self.bytes_allocated > self.next_gc {
+    if self.should_collect() {
       self.push_root(gc_string);
-      self.collect_garbage(context);
+      self.collect_garbage(context, false);
       self.pop_roots(1)
     }
 
@@ -314,7 +465,7 @@ impl<'a> Allocator {
 
   /// Collect garbage present in the heap for unreachable objects. Use the provided context
   /// to mark a set of initial roots into the vm.
-  fn collect_garbage<C: TraceRoot + ?Sized>(&mut self, context: &C) {
+  fn collect_garbage<C: TraceRoot + ?Sized>(&mut self, context: &C, full: bool) {
     #[cfg(any(
       feature = "gc_log_mark",
       feature = "gc_log_free",
@@ -335,17 +486,27 @@ impl<'a> Allocator {
     }
 
     if context.can_collect() {
-      self.trace_root(context);
-      self.temp_roots.iter().for_each(|root| {
-        self.trace(&**root);
-      });
+      let full = self.mark_and_sweep(context, full);
+
+      // only report out of memory if a full collection can't recover enough space
+      if self.exceeds_max_heap() {
+        if !full {
+          self.mark_and_sweep(context, true);
+        }
 
-      let string_heap_size = self.sweep_string_heap();
-      let obj_heap_size = self.sweep_obj_heap();
-      let heap_size = self.sweep_heap();
+        self.out_of_memory = self.exceeds_max_heap();
+        if self.out_of_memory {
+          if let Some(hook) = &self.on_out_of_memory {
+            hook();
+          }
+        }
+      }
 
-      self.bytes_allocated = string_heap_size + heap_size + obj_heap_size;
-      self.next_gc = self.bytes_allocated * GC_HEAP_GROW_FACTOR
+      self.next_gc = next_gc(
+        self.bytes_allocated,
+        self.bytes_allocated * self.grow_factor,
+        self.max_heap_bytes,
+      );
     }
 
     #[cfg(any(
@@ -378,6 +539,60 @@ impl<'a> Allocator {
     }
   }
 
+  /// Mark all live objects from the context and temporary roots then sweep
+  /// the heaps. Returns whether the collection included the full object heap
+  fn mark_and_sweep<C: TraceRoot + ?Sized>(&mut self, context: &C, full: bool) -> bool {
+    self.trace_root(context);
+    self.temp_roots.iter().for_each(|root| {
+      self.trace(&**root);
+    });
+
+    let full = full || self.is_full_collection();
+
+    // finalizers may read managed data so must run before anything is freed
+    self.finalize_heap();
+
+    let string_heap_size = self.sweep_string_heap();
+    let obj_heap_size = self.sweep_obj_heap(full);
+    let heap_size = self.sweep_heap();
+
+    self.bytes_allocated = string_heap_size + heap_size + obj_heap_size;
+    full
+  }
+
+  /// Should an allocation trigger a collection
+  #[inline]
+  fn should_collect(&self) -> bool {
+    self.stress || self.bytes_allocated > self.next_gc
+  }
+
+  /// Is the current collection a full collection of the object heap
+  fn is_full_collection(&self) -> bool {
+    self.stress || self.gc_count % 10 == 0
+  }
+
+  /// Has the heap grown beyond its maximum size
+  fn exceeds_max_heap(&self) -> bool {
+    match self.max_heap_bytes {
+      Some(max_heap_bytes) => self.bytes_allocated > max_heap_bytes,
+      None => false,
+    }
+  }
+
+  /// Run the finalizer of each unreachable allocation in the heap
+  fn finalize_heap(&mut self) {
+    if self.finalizers == 0 {
+      return;
+    }
+
+    for item in self.heap.iter_mut() {
+      if !item.marked() && item.has_finalizer() {
+        item.finalize();
+        self.finalizers -= 1;
+      }
+    }
+  }
+
   /// wrapper around a roots trace method to select either normal
   /// or debug trace at compile time.
   fn trace_root<C: TraceRoot + ?Sized>(&self, context: &C) {
@@ -409,12 +624,8 @@ impl<'a> Allocator {
 
   /// Remove unmarked objects from the heap. This calculates the remaining
   /// memory present in the heap
-  fn sweep_obj_heap(&mut self) -> usize {
-    #[cfg(feature = "gc_stress")]
-    return self.sweep_obj_full();
-
-    #[cfg(not(feature = "gc_stress"))]
-    if self.gc_count % 10 == 0 {
+  fn sweep_obj_heap(&mut self, full: bool) -> usize {
+    if full {
       self.sweep_obj_full()
     } else {
       self.sweep_obj_nursery()
@@ -423,7 +634,6 @@ impl<'a> Allocator {
 
   /// Remove unmarked objects from the nursery heap. Promoting surviving objects
   /// to the normal heap
-  #[cfg(not(feature = "gc_stress"))]
   fn sweep_obj_nursery(&mut self) -> usize {
     let mut remaining: usize = 0;
 
@@ -432,20 +642,8 @@ impl<'a> Allocator {
       remaining += obj.size();
     });
 
-    self
-      .obj_heap
-      .extend(self.nursery_obj_heap.drain(..).filter(|obj| {
-        let retain = (*obj).unmark();
-
-        #[cfg(feature = "gc_log_free")]
-        debug_free_obj(&obj, !retain);
-
-        if retain {
-          remaining += obj.size();
-        }
-
-        retain
-      }));
+    remaining += sweep_objs(&mut self.nursery_obj_heap, &mut self.obj_pool);
+    self.obj_heap.append(&mut self.nursery_obj_heap);
 
     remaining
   }
@@ -453,35 +651,10 @@ impl<'a> Allocator {
   /// Remove unmarked objects from the both heaps. Promoting surviving objects
   /// to the normal heap
   fn sweep_obj_full(&mut self) -> usize {
-    let mut remaining: usize = 0;
-
-    self.obj_heap.retain(|obj| {
-      let retain = (*obj).unmark();
-
-      #[cfg(feature = "gc_log_free")]
-      debug_free_obj(&obj, !retain);
-
-      if retain {
-        remaining += obj.size();
-      }
-
-      retain
-    });
+    let remaining = sweep_objs(&mut self.obj_heap, &mut self.obj_pool)
+      + sweep_objs(&mut self.nursery_obj_heap, &mut self.obj_pool);
 
-    self
-      .obj_heap
-      .extend(self.nursery_obj_heap.drain(..).filter(|obj| {
-        let retain = (*obj).unmark();
-
-        #[cfg(feature = "gc_log_free")]
-        debug_free_obj(&obj, !retain);
-
-        if retain {
-          remaining += obj.size();
-        }
-
-        retain
-      }));
+    self.obj_heap.append(&mut self.nursery_obj_heap);
 
     remaining
   }
@@ -511,7 +684,7 @@ impl<'a> Allocator {
   /// Remove strings from the cache that no longer have any references
   /// in the heap
   fn sweep_string_heap(&mut self) -> usize {
-    self.intern_cache.retain(|_, &mut string| string.marked());
+    self.interner.retain_marked();
 
     let mut remaining: usize = 0;
 
@@ -617,9 +790,92 @@ fn debug_free_obj(obj: &GcObjectHandle, free: bool) {
   }
 }
 
+/// Write a single allocation of a heap dump
+fn write_dump_entry(
+  out: &mut dyn Write,
+  first: &mut bool,
+  heap: &str,
+  id: usize,
+  kind: &str,
+  size: usize,
+  mut refs: Vec<usize>,
+) -> io::Result<()> {
+  refs.sort_unstable();
+  refs.dedup();
+
+  if !*first {
+    write!(out, ",")?;
+  }
+  *first = false;
+
+  write!(
+    out,
+    "\n{{\"id\":\"{:#x}\",\"heap\":\"{}\",\"kind\":\"{}\",\"size\":{},\"refs\":[",
+    id, heap, kind, size
+  )?;
+
+  for (index, reference) in refs.iter().enumerate() {
+    if index > 0 {
+      write!(out, ",")?;
+    }
+    write!(out, "\"{:#x}\"", reference)?;
+  }
+
+  write!(out, "]}}")
+}
+
+/// Remove unmarked objects from an object heap returning their memory to
+/// the pool. Survivors keep their relative order. This calculates the
+/// remaining memory present in the heap
+fn sweep_objs(heap: &mut Vec<GcObjectHandle>, pool: &mut ObjPool) -> usize {
+  let mut remaining: usize = 0;
+  let mut kept = 0;
+
+  for index in 0..heap.len() {
+    let obj = &heap[index];
+    let retain = obj.unmark();
+
+    #[cfg(feature = "gc_log_free")]
+    debug_free_obj(obj, !retain);
+
+    if retain {
+      remaining += obj.size();
+      heap.swap(kept, index);
+      kept += 1;
+    }
+  }
+
+  heap.drain(kept..).for_each(|obj| obj.free(pool));
+  remaining
+}
+
+/// Cap the heap size that triggers the next collection at the maximum
+/// heap size so the limit is enforced promptly
+fn next_gc(bytes_allocated: usize, next_gc: usize, max_heap_bytes: Option<usize>) -> usize {
+  match max_heap_bytes {
+    Some(max_heap_bytes) if bytes_allocated <= max_heap_bytes => next_gc.min(max_heap_bytes),
+    _ => next_gc,
+  }
+}
+
 impl<'a> Default for Allocator {
   fn default() -> Self {
-    Allocator::new(Stdio::default())
+    Allocator::new(Stdio::default(), None)
+  }
+}
+
+impl Drop for Allocator {
+  /// Finalize any remaining allocations while the rest of the heap is still valid
+  fn drop(&mut self) {
+    if self.finalizers == 0 {
+      return;
+    }
+
+    self
+      .heap
+      .iter_mut()
+      .filter(|item| item.has_finalizer())
+      .for_each(|item| item.finalize());
   }
 }
 pub struct NoGc();
@@ -635,3 +891,239 @@ impl TraceRoot for NoGc {
 }
 
 pub static NO_GC: NoGc = NoGc();
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{
+    managed::{DebugHeap, DebugWrap},
+    object::{ObjectKind, Upvalue},
+    value::VALUE_NIL,
+  };
+  use laythe_env::resource::{ResourceLog, ResourceMock};
+  use std::{fmt, mem};
+
+  struct Handle {
+    resource: ResourceMock,
+    inner: Option<Gc<Handle>>,
+  }
+
+  impl Trace for Handle {
+    fn trace(&self) {
+      if let Some(inner) = &self.inner {
+        inner.trace();
+      }
+    }
+  }
+
+  impl DebugHeap for Handle {
+    fn fmt_heap(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+      f.debug_struct("Handle")
+        .field("resource", &self.resource)
+        .field(
+          "inner",
+          &self.inner.as_ref().map(|inner| DebugWrap(inner, depth)),
+        )
+        .finish()
+    }
+  }
+
+  impl Manage for Handle {
+    fn size(&self) -> usize {
+      mem::size_of::<Self>()
+    }
+
+    fn as_debug(&self) -> &dyn DebugHeap {
+      self
+    }
+
+    fn has_finalizer(&self) -> bool {
+      true
+    }
+
+    fn finalize(&mut self) {
+      // the inner handle must still be valid even when also unreachable
+      if let Some(inner) = &self.inner {
+        assert!(inner.resource.id() < self.resource.id());
+      }
+
+      self.resource.release();
+    }
+  }
+
+  #[derive(Default)]
+  struct Roots(RefCell<Vec<Gc<Handle>>>);
+
+  impl TraceRoot for Roots {
+    fn trace(&self) {
+      self.0.borrow().iter().for_each(|root| root.trace());
+    }
+
+    fn trace_debug(&self, _log: &mut dyn Write) {}
+
+    fn can_collect(&self) -> bool {
+      true
+    }
+  }
+
+  fn handle(gc: &mut Allocator, log: &ResourceLog, inner: Option<Gc<Handle>>) -> Gc<Handle> {
+    gc.manage(
+      Handle {
+        resource: log.acquire(),
+        inner,
+      },
+      &NO_GC,
+    )
+  }
+
+  #[test]
+  fn finalize_unreachable() {
+    let log = ResourceLog::new();
+    let roots = Roots::default();
+    let mut gc = Allocator::default();
+
+    let kept = handle(&mut gc, &log, None);
+    handle(&mut gc, &log, None);
+    handle(&mut gc, &log, None);
+    roots.0.borrow_mut().push(kept);
+
+    gc.collect_full(&roots);
+    assert_eq!(log.released(), vec![1, 2]);
+    assert!(!kept.resource.is_released());
+
+    gc.collect_full(&roots);
+    assert_eq!(log.released(), vec![1, 2]);
+  }
+
+  #[test]
+  fn finalize_before_free() {
+    let log = ResourceLog::new();
+    let roots = Roots::default();
+    let mut gc = Allocator::default();
+
+    let inner = handle(&mut gc, &log, None);
+    let outer = handle(&mut gc, &log, Some(inner));
+    roots.0.borrow_mut().push(outer);
+
+    gc.collect_full(&roots);
+    assert_eq!(log.open(), 2);
+
+    roots.0.borrow_mut().clear();
+    gc.collect_full(&roots);
+    assert_eq!(log.released(), vec![0, 1]);
+    assert_eq!(log.open(), 0);
+  }
+
+  #[test]
+  fn reuse_pooled_objects() {
+    let roots = Roots::default();
+    let mut gc = Allocator::default();
+
+    let first = gc.manage_obj(Upvalue::Closed(VALUE_NIL), &NO_GC).degrade();
+    gc.collect_full(&roots);
+
+    let second = gc.manage_obj(Upvalue::Closed(VALUE_NIL), &NO_GC).degrade();
+    assert_eq!(first, second);
+  }
+
+  #[test]
+  fn with_thresholds() {
+    let log = ResourceLog::new();
+    let roots = Roots::default();
+    let mut gc = Allocator::default().with_thresholds(0, 1);
+
+    gc.manage(
+      Handle {
+        resource: log.acquire(),
+        inner: None,
+      },
+      &roots,
+    );
+    assert_eq!(log.open(), 1);
+
+    gc.manage(
+      Handle {
+        resource: log.acquire(),
+        inner: None,
+      },
+      &roots,
+    );
+    assert_eq!(log.released(), vec![0]);
+  }
+
+  #[test]
+  fn with_stress() {
+    let log = ResourceLog::new();
+    let roots = Roots::default();
+    let mut gc = Allocator::default().with_stress(true);
+
+    handle(&mut gc, &log, None);
+    assert_eq!(log.open(), 1);
+
+    gc.manage_str("collect", &roots);
+    assert_eq!(log.released(), vec![0]);
+  }
+
+  #[test]
+  fn finalize_on_drop() {
+    let log = ResourceLog::new();
+    let mut gc = Allocator::default();
+
+    let inner = handle(&mut gc, &log, None);
+    handle(&mut gc, &log, Some(inner));
+    assert_eq!(log.open(), 2);
+
+    drop(gc);
+    assert_eq!(log.released(), vec![0, 1]);
+  }
+
+  #[test]
+  #[should_panic(expected = "Objects are not finalized")]
+  fn manage_obj_with_finalizer() {
+    struct Finalized;
+
+    impl Trace for Finalized {}
+
+    impl DebugHeap for Finalized {
+      fn fmt_heap(&self, f: &mut fmt::Formatter, _: usize) -> fmt::Result {
+        f.write_str("Finalized")
+      }
+    }
+
+    impl Manage for Finalized {
+      fn size(&self) -> usize {
+        mem::size_of::<Self>()
+      }
+
+      fn as_debug(&self) -> &dyn DebugHeap {
+        self
+      }
+
+      fn has_finalizer(&self) -> bool {
+        true
+      }
+    }
+
+    impl Object for Finalized {
+      fn kind(&self) -> ObjectKind {
+        ObjectKind::Upvalue
+      }
+    }
+
+    let mut gc = Allocator::default();
+    gc.manage_obj(Finalized, &NO_GC);
+  }
+
+  #[test]
+  fn heap_dump_kinds() {
+    let log = ResourceLog::new();
+    let mut gc = Allocator::default();
+    handle(&mut gc, &log, None);
+
+    let mut dump = vec![];
+    gc.heap_dump(&mut dump).unwrap();
+
+    let dump = String::from_utf8(dump).unwrap();
+    assert!(dump.contains("\"kind\":\"Handle\""));
+  }
+}
@@ -12,6 +12,8 @@ pub enum ModuleError {
   SymbolAlreadyExists,
   SymbolNotExported,
   InvalidImport,
+  ModuleCycle,
+  InitializationFailed,
 }
 
 impl Display for ModuleError {
@@ -29,6 +31,8 @@ impl Display for ModuleError {
       ModuleError::SymbolAlreadyExists => write!(f, "Symbol already exists."),
       ModuleError::SymbolNotExported => write!(f, "Symbol not exported."),
       ModuleError::InvalidImport => write!(f, "Invalid import."),
+      ModuleError::ModuleCycle => write!(f, "Module imports itself while initializing."),
+      ModuleError::InitializationFailed => write!(f, "Module failed to initialize."),
     }
   }
 }
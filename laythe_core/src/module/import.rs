@@ -60,6 +60,11 @@ impl Trace for Import {
     self.path.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.package.visit_refs(visitor);
+    self.path.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, log: &mut dyn std::io::Write) {
     self.package.trace_debug(log);
     self.path.trace_debug(log);
@@ -4,7 +4,7 @@ mod package;
 
 pub use error::{ModuleError, ModuleResult};
 pub use import::Import;
-pub use package::Package;
+pub use package::{ModuleInit, Package, PACKAGE_ENTRY};
 
 use crate::{
   hooks::GcHooks,
@@ -67,6 +67,11 @@ impl Module {
     self.id
   }
 
+  /// The class of the instance that represents this module when imported
+  pub fn module_class(&self) -> GcObj<Class> {
+    self.module_class
+  }
+
   /// Create a module from a filepath
   pub fn from_path(
     hooks: &GcHooks,
@@ -97,6 +102,11 @@ impl Module {
     self.symbols.iter()
   }
 
+  /// The names of the symbols this module exports
+  pub fn exports(&self) -> impl Iterator<Item = &GcStr> {
+    self.exports.iter()
+  }
+
   /// A symbols iterator
   pub fn modules(&self) -> hash_map::Iter<'_, GcStr, Gc<Module>> {
     self.modules.iter()
@@ -124,6 +134,24 @@ impl Module {
     import
   }
 
+  /// Clear the symbols and exports of this module so its source can be
+  /// run again. The module takes a new id so functions compiled from the
+  /// previous source keep their own inline cache
+  pub fn reset(&mut self, hooks: &GcHooks, id: usize) {
+    let name = self.name();
+    let module_class = match self.module_class.super_class() {
+      Some(super_class) => Class::with_inheritance(hooks, name, *super_class),
+      None => hooks.manage_obj(Class::bare(name)),
+    };
+
+    hooks.shrink(self, |module| {
+      module.id = id;
+      module.module_class = module_class;
+      module.exports = LyHashSet::default();
+      module.symbols = Map::default();
+    });
+  }
+
   /// Insert a module into this module
   pub fn insert_module(&mut self, hooks: &GcHooks, sub_module: Gc<Module>) -> ModuleResult<()> {
     let relative = sub_module
@@ -275,6 +303,16 @@ impl Trace for Module {
     self.modules.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.module_class.visit_refs(visitor);
+
+    self.exports.iter().for_each(|key| {
+      key.visit_refs(visitor);
+    });
+    self.symbols.visit_refs(visitor);
+    self.modules.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     self.module_class.trace_debug(log);
 
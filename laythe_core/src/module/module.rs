@@ -269,6 +269,16 @@ impl Trace for Module {
     self.modules.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.module_class.visit_refs(visitor);
+
+    self.exports.iter().for_each(|key| {
+      key.visit_refs(visitor);
+    });
+    self.symbols.visit_refs(visitor);
+    self.modules.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     self.module_class.trace_debug(log);
 
@@ -3,10 +3,32 @@ use crate::{
   hooks::GcHooks,
   managed::{DebugHeap, DebugWrap, Gc, GcObj, GcStr, Manage, Trace},
   object::Instance,
+  utils::IdEmitter,
   value::Value,
 };
 use std::mem;
-use std::{fmt, io::Write};
+use std::{
+  fmt,
+  io::Write,
+  path::{Path, PathBuf},
+};
+
+/// The module loaded when a source package is imported without a path
+pub const PACKAGE_ENTRY: &str = "lib";
+
+/// Constructs a module of a package and inserts it into the package's
+/// root module. Run the first time the module is imported
+pub type ModuleInit = fn(&GcHooks, &mut Package, &mut IdEmitter) -> ModuleResult<()>;
+
+/// A module of a package that has not been imported yet
+#[derive(Clone, Copy)]
+enum LazyModule {
+  /// The module has not been constructed
+  Pending(ModuleInit),
+
+  /// The module is being constructed
+  Initializing,
+}
 
 #[derive(Clone)]
 pub struct Package {
@@ -15,13 +37,42 @@ pub struct Package {
 
   /// A hash of names to sub packages and modules
   root_module: Gc<Module>,
+
+  /// The directory the source modules of this package are loaded from.
+  /// Native packages such as std have none
+  root_dir: Option<PathBuf>,
+
+  /// The names of the packages this package depends on
+  dependencies: Vec<GcStr>,
+
+  /// Modules of the root module constructed on their first import
+  lazy_modules: Vec<(GcStr, LazyModule)>,
 }
 
 impl Package {
   /// Create a new package
   pub fn new(name: GcStr, root_module: Gc<Module>) -> Self {
     assert_eq!(name, root_module.name());
-    Self { name, root_module }
+    Self {
+      name,
+      root_module,
+      root_dir: None,
+      dependencies: vec![],
+      lazy_modules: vec![],
+    }
+  }
+
+  /// Create a new package of source modules found in the directory
+  /// of its root module
+  pub fn from_dir(name: GcStr, root_module: Gc<Module>, dependencies: Vec<GcStr>) -> Self {
+    assert_eq!(name, root_module.name());
+    Self {
+      name,
+      root_module,
+      root_dir: Some(root_module.path().clone()),
+      dependencies,
+      lazy_modules: vec![],
+    }
   }
 
   /// Retrieve the name of this package
@@ -34,6 +85,107 @@ impl Package {
     self.root_module
   }
 
+  /// The directory the source modules of this package are loaded from
+  pub fn root_dir(&self) -> Option<&Path> {
+    self.root_dir.as_deref()
+  }
+
+  /// The names of the packages this package depends on
+  pub fn dependencies(&self) -> &[GcStr] {
+    &self.dependencies
+  }
+
+  /// Can modules in this package import from the named package
+  pub fn can_import(&self, name: GcStr) -> bool {
+    self.name == name || self.dependencies.contains(&name)
+  }
+
+  /// The file of the source module at the provided path within this
+  /// package. An empty path is the package's `lib.lay` entry module
+  pub fn module_path(&self, path: &[GcStr]) -> Option<PathBuf> {
+    let mut module_path = self.root_dir.clone()?;
+
+    if path.is_empty() {
+      module_path.push(PACKAGE_ENTRY);
+    } else {
+      module_path.extend(path.iter().map(|segment| &**segment));
+    }
+
+    module_path.set_extension("lay");
+    Some(module_path)
+  }
+
+  /// Insert a module into the root module the first time it is imported.
+  /// The constructor must insert a module with the provided name
+  pub fn insert_module(
+    &mut self,
+    hooks: &GcHooks,
+    name: &str,
+    init: ModuleInit,
+  ) -> ModuleResult<()> {
+    let name = hooks.manage_str(name);
+
+    if self
+      .root_module
+      .modules()
+      .any(|(module, _)| *module == name)
+      || self.lazy_modules.iter().any(|(module, _)| *module == name)
+    {
+      return Err(ModuleError::SymbolAlreadyExists);
+    }
+
+    self.lazy_modules.push((name, LazyModule::Pending(init)));
+    Ok(())
+  }
+
+  /// Construct the module a requested import resolves through if it has
+  /// not been imported yet. A constructor importing its own module fails
+  /// with a cycle error
+  pub fn initialize(
+    &mut self,
+    hooks: &GcHooks,
+    emitter: &mut IdEmitter,
+    import: Gc<Import>,
+  ) -> ModuleResult<()> {
+    if import.package() != self.name {
+      return Err(ModuleError::PackageDoesNotMatch);
+    }
+
+    let name = match import.path().first() {
+      Some(name) => *name,
+      None => return Ok(()),
+    };
+
+    let init = match self.lazy_module(name) {
+      Some(lazy) => match mem::replace(lazy, LazyModule::Initializing) {
+        LazyModule::Pending(init) => init,
+        LazyModule::Initializing => return Err(ModuleError::ModuleCycle),
+      },
+      None => return Ok(()),
+    };
+
+    let result = init(hooks, self, emitter);
+    match result {
+      Ok(()) => self.lazy_modules.retain(|(module, _)| *module != name),
+      Err(_) => {
+        if let Some(lazy) = self.lazy_module(name) {
+          *lazy = LazyModule::Pending(init);
+        }
+      },
+    }
+
+    result
+  }
+
+  /// The state of a module not yet imported
+  fn lazy_module(&mut self, name: GcStr) -> Option<&mut LazyModule> {
+    self
+      .lazy_modules
+      .iter_mut()
+      .find(|(module, _)| *module == name)
+      .map(|(_, lazy)| lazy)
+  }
+
   /// Get a set of symbols from this package using a requested import. This
   /// operation can fail if some or all of the symbols are not found.
   pub fn import(&self, hooks: &GcHooks, import: Gc<Import>) -> ModuleResult<GcObj<Instance>> {
@@ -70,11 +222,36 @@ impl Trace for Package {
   fn trace(&self) {
     self.name.trace();
     self.root_module.trace();
+    self.dependencies.iter().for_each(|dependency| {
+      dependency.trace();
+    });
+    self.lazy_modules.iter().for_each(|(name, _)| {
+      name.trace();
+    });
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.name.visit_refs(visitor);
+    self.root_module.visit_refs(visitor);
+    self
+      .dependencies
+      .iter()
+      .for_each(|dependency| dependency.visit_refs(visitor));
+    self
+      .lazy_modules
+      .iter()
+      .for_each(|(name, _)| name.visit_refs(visitor));
   }
 
   fn trace_debug(&self, log: &mut dyn Write) {
     self.name.trace_debug(log);
     self.root_module.trace_debug(log);
+    self.dependencies.iter().for_each(|dependency| {
+      dependency.trace_debug(log);
+    });
+    self.lazy_modules.iter().for_each(|(name, _)| {
+      name.trace_debug(log);
+    });
   }
 }
 
@@ -83,6 +260,7 @@ impl DebugHeap for Package {
     f.debug_struct("Package")
       .field("name", &DebugWrap(&self.name, depth))
       .field("module", &DebugWrap(&self.root_module, depth))
+      .field("root_dir", &self.root_dir)
       .finish()
   }
 }
@@ -90,6 +268,8 @@ impl DebugHeap for Package {
 impl Manage for Package {
   fn size(&self) -> usize {
     mem::size_of::<Self>()
+      + mem::size_of::<GcStr>() * self.dependencies.capacity()
+      + mem::size_of::<(GcStr, LazyModule)>() * self.lazy_modules.capacity()
   }
 
   fn as_debug(&self) -> &dyn DebugHeap {
@@ -103,10 +283,12 @@ mod test {
 
   use super::Package;
   use crate::{
+    hooks::GcHooks,
     managed::Gc,
     memory::{Allocator, NO_GC},
-    module::{Import, Module, ModuleError},
+    module::{Import, Module, ModuleError, ModuleResult},
     object::{test_class, Class},
+    utils::IdEmitter,
     val,
   };
 
@@ -136,6 +318,39 @@ mod test {
     assert_eq!(&*package.name(), name);
   }
 
+  #[test]
+  fn from_dir() {
+    let mut gc = Allocator::default();
+    let name = gc.manage_str("shapes", &NO_GC);
+    let class = gc.manage_obj(Class::bare(name), &NO_GC);
+    let module = gc.manage(Module::new(class, PathBuf::from("/pkgs/shapes"), 0), &NO_GC);
+    let math = gc.manage_str("math", &NO_GC);
+    let other = gc.manage_str("other", &NO_GC);
+
+    let package = Package::from_dir(name, module, vec![math]);
+
+    assert_eq!(
+      package.root_dir(),
+      Some(PathBuf::from("/pkgs/shapes").as_path())
+    );
+    assert!(package.can_import(name));
+    assert!(package.can_import(math));
+    assert!(!package.can_import(other));
+
+    let circle = gc.manage_str("circle", &NO_GC);
+    assert_eq!(
+      package.module_path(&[circle]),
+      Some(PathBuf::from("/pkgs/shapes/circle.lay"))
+    );
+    assert_eq!(
+      package.module_path(&[]),
+      Some(PathBuf::from("/pkgs/shapes/lib.lay"))
+    );
+
+    let native = Package::new(name, module);
+    assert_eq!(native.module_path(&[circle]), None);
+  }
+
   #[test]
   fn import() {
     use crate::hooks::{GcHooks, NoContext};
@@ -223,4 +438,71 @@ mod test {
     assert_eq!(symbols1, Ok(val!(true)));
     assert_eq!(symbols2, Err(ModuleError::ModuleDoesNotExist));
   }
+
+  fn init_lazy(
+    hooks: &GcHooks,
+    package: &mut Package,
+    emitter: &mut IdEmitter,
+  ) -> ModuleResult<()> {
+    let module_class = test_class(hooks, "Module");
+    let module = hooks.manage(Module::from_path(
+      hooks,
+      PathBuf::from("my_package/lazy"),
+      module_class,
+      emitter.emit(),
+    )?);
+
+    package.root_module().insert_module(hooks, module)
+  }
+
+  fn init_cycle(
+    hooks: &GcHooks,
+    package: &mut Package,
+    emitter: &mut IdEmitter,
+  ) -> ModuleResult<()> {
+    let import = Import::from_str(hooks, "my_package/cycle")?;
+    package.initialize(hooks, emitter, import)
+  }
+
+  #[test]
+  fn initialize() {
+    use crate::hooks::{GcHooks, NoContext};
+
+    let mut context = NoContext::default();
+    let hooks = GcHooks::new(&mut context);
+    let mut emitter = IdEmitter::default();
+
+    let module_class = test_class(&hooks, "Module");
+    let module = hooks
+      .manage(Module::from_path(&hooks, PathBuf::from("my_package"), module_class, 0).unwrap());
+    let mut package = Package::new(hooks.manage_str("my_package"), module);
+
+    assert!(package.insert_module(&hooks, "lazy", init_lazy).is_ok());
+    assert_eq!(
+      package.insert_module(&hooks, "lazy", init_lazy),
+      Err(ModuleError::SymbolAlreadyExists)
+    );
+
+    let lazy = Import::from_str(&hooks, "my_package/lazy").unwrap();
+    assert_eq!(
+      package.import(&hooks, lazy).map(|_| ()),
+      Err(ModuleError::ModuleDoesNotExist)
+    );
+
+    assert_eq!(package.initialize(&hooks, &mut emitter, lazy), Ok(()));
+    assert_eq!(package.initialize(&hooks, &mut emitter, lazy), Ok(()));
+    assert_eq!(emitter.id_count(), 1);
+    assert!(package.import(&hooks, lazy).is_ok());
+
+    assert!(package.insert_module(&hooks, "cycle", init_cycle).is_ok());
+    let cycle = Import::from_str(&hooks, "my_package/cycle").unwrap();
+    assert_eq!(
+      package.initialize(&hooks, &mut emitter, cycle),
+      Err(ModuleError::ModuleCycle)
+    );
+    assert_eq!(
+      package.initialize(&hooks, &mut emitter, cycle),
+      Err(ModuleError::ModuleCycle)
+    );
+  }
 }
@@ -0,0 +1,250 @@
+use crate::{
+  managed::{DebugHeap, GcObj, Manage, Object, Trace},
+  value::Value,
+};
+use std::{collections::VecDeque, fmt, io::Write, mem};
+
+use super::{Fiber, ObjectKind};
+
+/// A channel used to pass values between fibers. A channel with
+/// a capacity of zero is a rendezvous channel where a send only
+/// completes once a receiver is ready
+pub struct Channel {
+  /// Values sent but not yet received
+  queue: VecDeque<Value>,
+
+  /// The number of values this channel can buffer
+  capacity: usize,
+
+  /// Has this channel been closed
+  closed: bool,
+
+  /// Fibers blocked trying to send to this channel
+  senders: VecDeque<GcObj<Fiber>>,
+
+  /// Fibers blocked trying to receive from this channel
+  receivers: VecDeque<GcObj<Fiber>>,
+}
+
+impl Channel {
+  /// Create a new channel with the given buffer capacity
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      queue: VecDeque::with_capacity(capacity),
+      capacity,
+      closed: false,
+      senders: VecDeque::new(),
+      receivers: VecDeque::new(),
+    }
+  }
+
+  /// The number of values currently buffered in this channel
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.queue.len()
+  }
+
+  /// Is this channel currently empty
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.queue.is_empty()
+  }
+
+  /// The number of values this channel can buffer
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  /// Is this a zero capacity rendezvous channel
+  #[inline]
+  pub fn is_rendezvous(&self) -> bool {
+    self.capacity == 0
+  }
+
+  /// Has this channel been closed
+  #[inline]
+  pub fn is_closed(&self) -> bool {
+    self.closed
+  }
+
+  /// Can a value be buffered without a waiting receiver
+  #[inline]
+  pub fn has_space(&self) -> bool {
+    self.queue.len() < self.capacity
+  }
+
+  /// Close this channel. Any value already buffered can
+  /// still be received
+  pub fn close(&mut self) {
+    self.closed = true;
+  }
+
+  /// Buffer a value in this channel
+  pub fn push(&mut self, value: Value) {
+    self.queue.push_back(value);
+  }
+
+  /// Take the oldest buffered value from this channel
+  pub fn pop(&mut self) -> Option<Value> {
+    self.queue.pop_front()
+  }
+
+  /// Is any fiber blocked receiving from this channel
+  #[inline]
+  pub fn has_receivers(&self) -> bool {
+    !self.receivers.is_empty()
+  }
+
+  /// Record a fiber blocked sending to this channel
+  pub fn add_sender(&mut self, fiber: GcObj<Fiber>) {
+    self.senders.push_back(fiber);
+  }
+
+  /// Record a fiber blocked receiving from this channel
+  pub fn add_receiver(&mut self, fiber: GcObj<Fiber>) {
+    self.receivers.push_back(fiber);
+  }
+
+  /// Take the longest waiting sender
+  pub fn take_sender(&mut self) -> Option<GcObj<Fiber>> {
+    self.senders.pop_front()
+  }
+
+  /// Take the longest waiting receiver
+  pub fn take_receiver(&mut self) -> Option<GcObj<Fiber>> {
+    self.receivers.pop_front()
+  }
+
+  /// Take every fiber blocked on this channel
+  pub fn take_blocked(&mut self) -> Vec<GcObj<Fiber>> {
+    self
+      .receivers
+      .drain(..)
+      .chain(self.senders.drain(..))
+      .collect()
+  }
+}
+
+impl fmt::Display for Channel {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "<channel {:p}>", self)
+  }
+}
+
+impl fmt::Debug for Channel {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self.fmt_heap(f, 2)
+  }
+}
+
+impl Trace for Channel {
+  fn trace(&self) {
+    self.queue.iter().for_each(|value| value.trace());
+    self.senders.iter().for_each(|fiber| {
+      fiber.trace();
+    });
+    self.receivers.iter().for_each(|fiber| {
+      fiber.trace();
+    });
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.queue.iter().for_each(|value| value.visit_refs(visitor));
+    self.senders.iter().for_each(|fiber| {
+      fiber.visit_refs(visitor);
+    });
+    self.receivers.iter().for_each(|fiber| {
+      fiber.visit_refs(visitor);
+    });
+  }
+
+  fn trace_debug(&self, log: &mut dyn Write) {
+    self.queue.iter().for_each(|value| value.trace_debug(log));
+    self.senders.iter().for_each(|fiber| {
+      fiber.trace_debug(log);
+    });
+    self.receivers.iter().for_each(|fiber| {
+      fiber.trace_debug(log);
+    });
+  }
+}
+
+impl DebugHeap for Channel {
+  fn fmt_heap(&self, f: &mut fmt::Formatter, _: usize) -> fmt::Result {
+    f.debug_struct("Channel")
+      .field("queue", &self.queue.len())
+      .field("capacity", &self.capacity)
+      .field("closed", &self.closed)
+      .field("senders", &self.senders.len())
+      .field("receivers", &self.receivers.len())
+      .finish()
+  }
+}
+
+impl Manage for Channel {
+  fn size(&self) -> usize {
+    mem::size_of::<Self>()
+      + mem::size_of::<Value>() * self.queue.capacity()
+      + mem::size_of::<GcObj<Fiber>>() * (self.senders.capacity() + self.receivers.capacity())
+  }
+
+  fn as_debug(&self) -> &dyn DebugHeap {
+    self
+  }
+}
+
+impl Object for Channel {
+  fn kind(&self) -> ObjectKind {
+    ObjectKind::Channel
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::val;
+
+  #[test]
+  fn new() {
+    let channel = Channel::new(2);
+
+    assert_eq!(channel.len(), 0);
+    assert_eq!(channel.capacity(), 2);
+    assert!(!channel.is_rendezvous());
+    assert!(!channel.is_closed());
+    assert!(channel.has_space());
+  }
+
+  #[test]
+  fn rendezvous() {
+    let channel = Channel::new(0);
+
+    assert!(channel.is_rendezvous());
+    assert!(!channel.has_space());
+  }
+
+  #[test]
+  fn push_pop() {
+    let mut channel = Channel::new(2);
+
+    channel.push(val!(1.0));
+    channel.push(val!(2.0));
+    assert!(!channel.has_space());
+    assert_eq!(channel.len(), 2);
+
+    assert_eq!(channel.pop(), Some(val!(1.0)));
+    assert_eq!(channel.pop(), Some(val!(2.0)));
+    assert_eq!(channel.pop(), None);
+  }
+
+  #[test]
+  fn close() {
+    let mut channel = Channel::new(1);
+    channel.push(val!(true));
+    channel.close();
+
+    assert!(channel.is_closed());
+    assert_eq!(channel.pop(), Some(val!(true)));
+  }
+}
@@ -1,11 +1,11 @@
 use crate::{
   constants::INIT,
-  managed::{DebugHeap, DebugWrap, Gc, GcObj, GcStr, Manage, Object, Trace},
+  managed::{DebugHeap, DebugWrap, GcObj, GcStr, Manage, Object, Trace},
 };
 use crate::{hooks::GcHooks, value::Value};
 use fnv::FnvBuildHasher;
 use hashbrown::HashMap;
-use std::{fmt, io::Write, mem};
+use std::{fmt, io::Write, mem, ptr};
 
 use super::ObjectKind;
 
@@ -17,6 +17,13 @@ pub struct Class {
   fields: HashMap<GcStr, u16, FnvBuildHasher>,
   meta_class: Option<GcObj<Class>>,
   super_class: Option<GcObj<Class>>,
+
+  /// Is this class closed to extensions, subclasses and dynamic fields
+  sealed: bool,
+
+  /// The methods added after this class was sealed in the order they
+  /// were added. A method shadowed by a field has no entry
+  vtable: Vec<(GcStr, Option<Value>)>,
 }
 
 impl Class {
@@ -28,6 +35,8 @@ impl Class {
       fields: HashMap::default(),
       meta_class: None,
       super_class: None,
+      sealed: false,
+      vtable: vec![],
     });
 
     hooks.push_root(class);
@@ -46,6 +55,8 @@ impl Class {
       fields: HashMap::default(),
       meta_class: None,
       super_class: None,
+      sealed: false,
+      vtable: vec![],
     }
   }
 
@@ -72,8 +83,9 @@ impl Class {
     &self.super_class
   }
 
-  pub fn is_subclass(&self, class: Gc<Class>) -> bool {
-    if self == &*class {
+  /// Is this class the provided class or one of its subclasses
+  pub fn is_subclass(&self, class: GcObj<Class>) -> bool {
+    if ptr::eq(self, &*class) {
       return true;
     }
 
@@ -92,10 +104,17 @@ impl Class {
     self
   }
 
-  pub fn add_field(&mut self, hooks: &GcHooks, name: GcStr) -> Option<u16> {
-    let len = self.fields.len();
+  /// Register a field on this class returning its slot. A field keeps its
+  /// slot once registered so redeclaring an inherited field reuses the
+  /// slot assigned by the super class
+  pub fn add_field(&mut self, hooks: &GcHooks, name: GcStr) -> u16 {
+    if let Some(index) = self.get_field_index(&name) {
+      return index;
+    }
 
-    hooks.grow(self, |class| class.fields.insert(name, len as u16))
+    let len = self.fields.len() as u16;
+    hooks.grow(self, |class| class.fields.insert(name, len));
+    len
   }
 
   pub fn add_method(&mut self, hooks: &GcHooks, name: GcStr, method: Value) -> Option<Value> {
@@ -103,11 +122,58 @@ impl Class {
       self.init = Some(method)
     }
 
+    if self.sealed {
+      let entry = match self.get_field_index(&name) {
+        Some(_) => None,
+        None => Some(method),
+      };
+      hooks.grow(self, |class| class.vtable.push((name, entry)));
+    }
+
     hooks.grow(self, |class| class.methods.insert(name, method))
   }
 
+  /// Is this class sealed
+  #[inline]
+  pub fn is_sealed(&self) -> bool {
+    self.sealed
+  }
+
+  /// Seal this class. Methods added from here on are given the next
+  /// vtable index so they can be invoked without a lookup
+  pub fn seal(&mut self) {
+    self.sealed = true;
+  }
+
+  /// Get the method at a vtable index if it has the expected name
+  #[inline]
+  pub fn sealed_method(&self, index: usize, name: GcStr) -> Option<Value> {
+    match self.vtable.get(index) {
+      Some((method_name, method)) if *method_name == name => *method,
+      _ => None,
+    }
+  }
+
+  /// Get a method on this class. Methods are copied down when a class
+  /// inherits so this only falls back to the super class for methods
+  /// added to it by an extension after this class was created
   pub fn get_method(&self, name: &GcStr) -> Option<Value> {
-    self.methods.get(name).copied()
+    match self.methods.get(name) {
+      Some(method) => Some(*method),
+      None => self
+        .super_class
+        .and_then(|super_class| super_class.get_method(name)),
+    }
+  }
+
+  /// Iterate the methods defined on or inherited by this class
+  pub fn methods(&self) -> impl Iterator<Item = (&GcStr, &Value)> {
+    self.methods.iter()
+  }
+
+  /// Iterate the fields of this class's layout with their slots
+  pub fn field_slots(&self) -> impl Iterator<Item = (&GcStr, &u16)> {
+    self.fields.iter()
   }
 
   #[inline]
@@ -115,6 +181,42 @@ impl Class {
     self.fields.get(name).copied()
   }
 
+  /// Do instances of this class and the other class store the same
+  /// fields in the same slots
+  pub fn same_layout(&self, other: &Class) -> bool {
+    self.fields == other.fields
+  }
+
+  /// Take the methods of a class reloaded from the same source so
+  /// existing instances of this class call the reloaded methods. The
+  /// reloaded class should have the same layout as this class
+  pub fn reload(&mut self, hooks: &GcHooks, reloaded: GcObj<Class>) {
+    debug_assert!(self.same_layout(&reloaded));
+
+    let methods = reloaded.methods.clone();
+    let vtable = reloaded.vtable.clone();
+    hooks.grow(self, |class| {
+      class.methods = methods;
+      class.vtable = vtable;
+    });
+    self.init = reloaded.init;
+    self.sealed = reloaded.sealed;
+
+    // static methods live on the meta class
+    if let (Some(mut meta_class), Some(reloaded_meta)) = (self.meta_class, reloaded.meta_class) {
+      if meta_class != reloaded_meta {
+        meta_class.reload(hooks, reloaded_meta);
+      }
+    }
+  }
+
+  /// Set the super class without inheriting its methods or fields, for
+  /// a class whose methods and fields are restored separately
+  pub fn set_super(&mut self, super_class: GcObj<Class>) -> &mut Self {
+    self.super_class = Some(super_class);
+    self
+  }
+
   pub fn inherit(&mut self, hooks: &GcHooks, super_class: GcObj<Class>) {
     debug_assert!(self.methods.is_empty());
     debug_assert!(self.fields.is_empty());
@@ -127,13 +229,14 @@ impl Class {
         }
       });
 
-      class.fields.reserve(super_class.fields.len());
-      super_class.fields.iter().for_each(|(field, _index)| {
-        if class.fields.get(&field).is_none() {
-          let len = class.fields.len();
-          class.fields.insert(*field, len as u16);
-        }
-      })
+      // inherited fields keep their slots so the super class's layout
+      // is a prefix of this class's layout
+      class.fields.extend(
+        super_class
+          .fields
+          .iter()
+          .map(|(field, index)| (*field, *index)),
+      );
     });
 
     debug_assert!(self
@@ -170,6 +273,8 @@ impl Class {
       fields: HashMap::default(),
       meta_class: Some(class_class),
       super_class: None,
+      sealed: false,
+      vtable: vec![],
     });
 
     hooks.push_root(meta_class);
@@ -203,6 +308,12 @@ impl Trace for Class {
     self.fields.iter().for_each(|(key, _)| {
       key.trace();
     });
+    self.vtable.iter().for_each(|(key, val)| {
+      key.trace();
+      if let Some(val) = val {
+        val.trace();
+      }
+    });
 
     if let Some(super_class) = self.super_class {
       super_class.trace();
@@ -212,6 +323,31 @@ impl Trace for Class {
     }
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.name.visit_refs(visitor);
+
+    self.methods.iter().for_each(|(key, val)| {
+      key.visit_refs(visitor);
+      val.visit_refs(visitor);
+    });
+    self.fields.iter().for_each(|(key, _)| {
+      key.visit_refs(visitor);
+    });
+    self.vtable.iter().for_each(|(key, val)| {
+      key.visit_refs(visitor);
+      if let Some(val) = val {
+        val.visit_refs(visitor);
+      }
+    });
+
+    if let Some(super_class) = self.super_class {
+      super_class.visit_refs(visitor);
+    }
+    if let Some(meta_class) = self.meta_class {
+      meta_class.visit_refs(visitor);
+    }
+  }
+
   fn trace_debug(&self, stdio: &mut dyn Write) {
     self.name.trace_debug(stdio);
 
@@ -222,6 +358,12 @@ impl Trace for Class {
     self.fields.iter().for_each(|(key, _)| {
       key.trace_debug(stdio);
     });
+    self.vtable.iter().for_each(|(key, val)| {
+      key.trace_debug(stdio);
+      if let Some(val) = val {
+        val.trace_debug(stdio);
+      }
+    });
 
     if let Some(super_class) = self.super_class {
       super_class.trace_debug(stdio);
@@ -246,6 +388,7 @@ impl Manage for Class {
   fn size(&self) -> usize {
     mem::size_of::<Class>()
       + (mem::size_of::<GcStr>() + mem::size_of::<Value>()) * self.methods.capacity()
+      + mem::size_of::<(GcStr, Option<Value>)>() * self.vtable.capacity()
   }
 
   fn as_debug(&self) -> &dyn DebugHeap {
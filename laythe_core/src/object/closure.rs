@@ -96,6 +96,14 @@ impl Trace for Closure {
     self.fun.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.upvalues.iter().for_each(|upvalue| {
+      upvalue.visit_refs(visitor);
+    });
+
+    self.fun.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdio: &mut dyn Write) {
     self.upvalues.iter().for_each(|upvalue| {
       upvalue.trace_debug(stdio);
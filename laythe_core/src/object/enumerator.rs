@@ -65,6 +65,11 @@ impl Trace for Enumerator {
     self.iterator.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.current.visit_refs(visitor);
+    self.iterator.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.current.trace_debug(stdout);
     self.iterator.trace_debug(stdout);
@@ -1,6 +1,6 @@
 use std::{fmt, io::Write, mem, usize};
 
-use super::{Closure, Fun, Instance, ObjectKind, Upvalue};
+use super::{Closure, Fun, Instance, ObjectKind, TryBlock, Upvalue};
 use crate::{
   call_frame::CallFrame,
   constants::SCRIPT,
@@ -12,11 +12,13 @@ use crate::{
 
 const INITIAL_FRAME_SIZE: usize = 4;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FiberState {
   Running,
   Pending,
+  Blocked,
   Complete,
+  Failed,
 }
 
 #[derive(Debug)]
@@ -30,6 +32,11 @@ pub struct Fiber {
   /// A stack holding all local variable currently in use
   stack: Vec<Value>,
 
+  /// Stacks replaced when the stack grew. Native functions may still hold
+  /// argument slices into these so they are kept until the fiber returns
+  /// to its base frame
+  retired: Vec<Vec<Value>>,
+
   /// A stack holding call frames currently in use
   frames: Vec<CallFrame>,
 
@@ -47,6 +54,21 @@ pub struct Fiber {
 
   /// The current error if one is active
   error: Option<GcObj<Instance>>,
+
+  /// The value this fiber completed with
+  result: Value,
+
+  /// Fibers blocked until this fiber completes
+  waiters: Vec<GcObj<Fiber>>,
+
+  /// Is this fiber sleeping in the scheduler
+  sleeping: bool,
+
+  /// Is an unhandled error in this fiber reported by the fiber supervising it
+  supervised: bool,
+
+  /// An error to raise in this fiber the next time it is resumed
+  cancel: Option<GcObj<Instance>>,
 }
 
 impl Fiber {
@@ -54,10 +76,16 @@ impl Fiber {
   /// this initial closure to determine how much stack space to initially
   /// reserve
   pub fn new(closure: GcObj<Closure>) -> FiberResult<Self> {
+    Self::with_stack_size(closure, 0)
+  }
+
+  /// Create a new fiber from the provided closure reserving at least
+  /// `stack_size` slots for its stack
+  pub fn with_stack_size(closure: GcObj<Closure>, stack_size: usize) -> FiberResult<Self> {
     // reserve resources
     let fun = closure.fun();
     let mut frames = Vec::<CallFrame>::with_capacity(INITIAL_FRAME_SIZE);
-    let mut stack = vec![VALUE_NIL; fun.max_slots() + 1];
+    let mut stack = vec![VALUE_NIL; (fun.max_slots() + 1).max(stack_size)];
 
     let instructions = fun.chunk().instructions();
     if instructions.is_empty() {
@@ -78,15 +106,33 @@ impl Fiber {
 
     Ok(Self {
       stack,
+      retired: vec![],
       frames,
       state: FiberState::Pending,
       error: None,
+      result: VALUE_NIL,
+      waiters: vec![],
+      sleeping: false,
+      supervised: false,
+      cancel: None,
       open_upvalues: vec![],
       frame: current_frame,
       stack_top,
     })
   }
 
+  /// Create a new fiber that starts by calling the provided closure
+  /// with the provided arguments
+  pub fn with_args(closure: GcObj<Closure>, args: &[Value]) -> FiberResult<Self> {
+    let mut fiber = Self::with_stack_size(closure, args.len() + 1)?;
+
+    for arg in args {
+      unsafe { fiber.push(*arg) };
+    }
+
+    Ok(fiber)
+  }
+
   /// TODO remove, convert upvalue back to straight pointer
   /// Get an immutable stack slice
   #[inline]
@@ -119,11 +165,117 @@ impl Fiber {
     self.frame().closure
   }
 
+  /// The current state of this fiber
+  #[inline]
+  pub fn state(&self) -> FiberState {
+    self.state
+  }
+
+  /// Has this fiber run to completion, either normally or from an error
+  #[inline]
+  pub fn is_complete(&self) -> bool {
+    matches!(self.state, FiberState::Complete | FiberState::Failed)
+  }
+
   /// Activate the current fiber
   pub fn activate(&mut self) {
     self.state = FiberState::Running;
   }
 
+  /// Block this fiber until it is woken
+  pub fn block(&mut self) {
+    self.state = FiberState::Blocked;
+  }
+
+  /// Wake this fiber if it is currently blocked. Returns
+  /// true if the fiber was blocked
+  pub fn wake(&mut self) -> bool {
+    match self.state {
+      FiberState::Blocked => {
+        self.state = FiberState::Pending;
+        true
+      },
+      _ => false,
+    }
+  }
+
+  /// Mark this fiber as sleeping in the scheduler
+  pub fn sleep(&mut self) {
+    self.sleeping = true;
+  }
+
+  /// Clear this fiber's sleeping mark. Returns true if
+  /// the fiber was sleeping
+  pub fn wake_from_sleep(&mut self) -> bool {
+    mem::replace(&mut self.sleeping, false)
+  }
+
+  /// Mark this fiber as supervised so an unhandled error is
+  /// left for its supervisor to report
+  pub fn supervise(&mut self) {
+    self.supervised = true;
+  }
+
+  /// Is this fiber's unhandled error reported by a supervisor
+  #[inline]
+  pub fn is_supervised(&self) -> bool {
+    self.supervised
+  }
+
+  /// Cancel this fiber by raising the provided error once it resumes.
+  /// Returns false if the fiber has already completed or been cancelled
+  pub fn cancel(&mut self, error: GcObj<Instance>) -> bool {
+    if self.is_complete() || self.cancel.is_some() {
+      return false;
+    }
+
+    self.cancel = Some(error);
+    true
+  }
+
+  /// Take the error this fiber was cancelled with if any
+  pub fn take_cancel(&mut self) -> Option<GcObj<Instance>> {
+    self.cancel.take()
+  }
+
+  /// Mark this fiber as failed from an unhandled error
+  pub fn fail(&mut self, error: GcObj<Instance>) {
+    self.error = Some(error);
+    self.state = FiberState::Failed;
+  }
+
+  /// The value this fiber completed with
+  #[inline]
+  pub fn result(&self) -> Value {
+    self.result
+  }
+
+  /// Set the value this fiber completed with
+  pub fn set_result(&mut self, result: Value) {
+    self.result = result;
+  }
+
+  /// Add a fiber that is waiting on this fiber's completion
+  pub fn add_waiter(&mut self, waiter: GcObj<Fiber>) {
+    self.waiters.push(waiter);
+  }
+
+  /// Remove a fiber that no longer waits on this fiber's completion
+  pub fn remove_waiter(&mut self, waiter: GcObj<Fiber>) {
+    self.waiters.retain(|fiber| *fiber != waiter);
+  }
+
+  /// Does this fiber have any fibers waiting on it
+  #[inline]
+  pub fn has_waiters(&self) -> bool {
+    !self.waiters.is_empty()
+  }
+
+  /// Take the fibers waiting on this fiber's completion
+  pub fn take_waiters(&mut self) -> Vec<GcObj<Fiber>> {
+    mem::take(&mut self.waiters)
+  }
+
   /// push a value onto the stack
   ///
   /// ## Safety
@@ -174,6 +326,12 @@ impl Fiber {
     self.assert_stack_inbounds();
   }
 
+  /// The number of values currently on the stack
+  #[inline]
+  pub fn stack_height(&self) -> usize {
+    unsafe { self.stack_top.offset_from(self.stack.as_ptr()) as usize }
+  }
+
   /// Retrieve a value n slots from the stack head
   ///
   /// ## Safety
@@ -290,6 +448,10 @@ impl Fiber {
     }
 
     self.frames.pop();
+    if self.frames.len() <= 1 {
+      self.retired.clear();
+    }
+
     Some(match self.frames.last() {
       Some(frame) => {
         #[cfg(debug_assertions)]
@@ -305,36 +467,36 @@ impl Fiber {
   }
 
   /// Ensure the stack has enough space. If more space is required
-  /// additional space is allocated. All pointers into the stack
-  /// are then updated
+  /// the stack is moved to a larger allocation and all pointers into the
+  /// stack are updated. Upvalues refer to the stack by index so they
+  /// remain valid. The old stack is retired rather than freed as native
+  /// functions further down the call stack may still read their arguments
+  /// from it
   pub fn ensure_stack(&mut self, additional: usize) {
     // check is we already have enought space
-    let len = unsafe { self.stack_top.offset_from(self.stack.as_ptr()) };
-    if self.stack.capacity() >= len as usize + additional {
+    let len = unsafe { self.stack_top.offset_from(self.stack.as_ptr()) } as usize;
+    if self.stack.len() >= len + additional {
       return;
     }
 
-    let stack_old = self.stack.as_ptr();
-    self.stack.reserve(additional);
-    let stack_new = self.stack.as_ptr();
-
-    // If we relocated instead of extended updated pointers
-    if stack_old != stack_new {
-      unsafe {
-        let offset = stack_new.offset_from(stack_old);
-        self.stack_top = self.stack_top.offset(offset);
-
-        self.frames.iter_mut().for_each(|frame| {
-          frame.stack_start = frame.stack_start.offset(offset);
-        });
-      }
-    }
+    let capacity = (self.stack.len() * 2).max(len + additional);
+    let mut stack = Vec::with_capacity(capacity);
+    stack.extend_from_slice(&self.stack);
 
     // eagerly fill stack will nil
-    while self.stack.len() != self.stack.capacity() {
-      self.stack.push(VALUE_NIL);
+    stack.resize(capacity, VALUE_NIL);
+
+    let stack_old = self.stack.as_ptr();
+    self.retired.push(mem::replace(&mut self.stack, stack));
+    let stack_new = self.stack.as_mut_ptr();
+
+    unsafe {
+      self.stack_top = stack_new.offset(self.stack_top.offset_from(stack_old));
+
+      self.frames.iter_mut().for_each(|frame| {
+        frame.stack_start = stack_new.offset(frame.stack_start.offset_from(stack_old));
+      });
     }
-    debug_assert_eq!(self.stack.len(), self.stack.capacity());
   }
 
   // hoist all upvalues above the the stack top
@@ -370,52 +532,59 @@ impl Fiber {
     created_upvalue
   }
 
+  /// The current value of an upvalue if it is still open on this fiber
+  pub fn open_value(&self, upvalue: GcObj<Upvalue>) -> Option<Value> {
+    self
+      .open_upvalues
+      .contains(&upvalue)
+      .then(|| upvalue.value(&self.stack))
+  }
+
   /// Unwind the stack searching for catch blocks to handle the unwind.
-  /// If a handler is found returns the call frame that handles the exception
-  /// if not found returns none
-  pub fn stack_unwind(&mut self) -> Option<&mut CallFrame> {
-    let mut stack_top = self.frame().stack_start;
+  /// If a handler is found the stack is reset to the depth the try block
+  /// was entered at and the current error is pushed for the catch block.
+  /// Frames below floor are not searched. Returns the call frame that
+  /// handles the exception if one is found
+  pub fn stack_unwind(&mut self, floor: usize) -> Option<&mut CallFrame> {
     let mut drop: usize = 0;
-    let mut catch_offset: Option<u16> = None;
+    let mut catch_block: Option<TryBlock> = None;
 
-    for frame in self.frames.iter().rev() {
+    for frame in self.frames.iter().skip(floor).rev() {
       let fun = frame.closure.fun();
       let instructions = fun.chunk().instructions();
 
       // see if the current functions has a catch block at
       // this offset
       let offset = unsafe { frame.ip.offset_from(instructions.as_ptr()) } as usize;
-      if let Some(offset) = fun.has_catch_jump(offset as u16) {
-        catch_offset = Some(offset);
+      if let Some(try_block) = fun.catch_block(offset as u16) {
+        catch_block = Some(*try_block);
         break;
       }
 
       drop += 1;
-      stack_top = frame.stack_start;
     }
 
-    match catch_offset {
-      Some(catch_offset) => {
-        // truncate the unwound frames
-        self.frames.truncate(self.frames.len() - drop);
+    let catch_block = catch_block?;
+    let error = self.error.take()?;
 
-        let frame = self
-          .frames
-          .last_mut()
-          .expect("expected at least 1 frame to remain");
+    // truncate the unwound frames
+    self.frames.truncate(self.frames.len() - drop);
+    self.frame = unsafe { self.frames.as_mut_ptr().add(self.frames.len() - 1) };
 
-        let fun = frame.closure.fun();
-        let instructions = fun.chunk().instructions();
+    unsafe {
+      // reset the stack to the depth the try block was entered
+      // hoisting any values captured by the unwound frames
+      let stack_top = self.frame().stack_start.add(catch_block.slots() as usize);
+      self.close_upvalues_internal(stack_top);
+      self.stack_top = stack_top;
+      self.push(val!(error));
+    }
 
-        // set the current ip frame and stack pointer
-        frame.ip = &instructions[catch_offset as usize] as *const u8;
-        self.frame = frame as *mut CallFrame;
-        self.stack_top = stack_top;
+    let frame = self.frame_mut();
+    let fun = frame.closure.fun();
+    frame.ip = &fun.chunk().instructions()[catch_block.end() as usize] as *const u8;
 
-        Some(frame)
-      },
-      None => None,
-    }
+    Some(frame)
   }
 
   /// Print a error message with the associated stack track if found
@@ -423,6 +592,11 @@ impl Fiber {
     let message = error[0].to_obj().to_str();
     writeln!(log, "{}: {}", &*error.class().name(), &*message).expect("Unable to write to stderr");
 
+    self.print_stack(log);
+  }
+
+  /// Print the current call stack to the provided log
+  pub fn print_stack(&self, log: &mut dyn Write) {
     for frame in self.frames.iter().rev() {
       let fun = frame.closure.fun();
       let location: String = match &*fun.name() {
@@ -430,14 +604,8 @@ impl Fiber {
         _ => format!("{}()", &*fun.name()),
       };
 
-      let offset = unsafe { frame.ip.offset_from(fun.chunk().instructions().as_ptr()) } as usize;
-      writeln!(
-        log,
-        "  [line {}] in {}",
-        fun.chunk().get_line(offset),
-        location
-      )
-      .expect("Unable to write to stderr");
+      writeln!(log, "  [line {}] in {}", frame.line(), location)
+        .expect("Unable to write to stderr");
     }
   }
 
@@ -546,7 +714,14 @@ impl Manage for Fiber {
     mem::size_of::<Self>()
       + mem::size_of::<GcObj<Upvalue>>() * self.open_upvalues.capacity()
       + mem::size_of::<CallFrame>() * self.frames.capacity()
+      + mem::size_of::<GcObj<Fiber>>() * self.waiters.capacity()
       + mem::size_of::<Value>() * self.stack.capacity()
+      + mem::size_of::<Value>()
+        * self
+          .retired
+          .iter()
+          .map(|stack| stack.capacity())
+          .sum::<usize>()
   }
 
   fn as_debug(&self) -> &dyn crate::managed::DebugHeap {
@@ -564,6 +739,8 @@ impl DebugHeap for Fiber {
       .field("state", &self.state)
       .field("open_upvalues", &DebugWrap(&&*self.open_upvalues, depth))
       .field("current_error", &DebugWrap(&self.error, depth))
+      .field("result", &DebugWrap(&self.result, depth))
+      .field("waiters", &DebugWrap(&&*self.waiters, depth))
       .finish()
   }
 }
@@ -580,7 +757,7 @@ impl Trace for Fiber {
       });
     }
 
-    self.frames.iter().skip(1).for_each(|frame| {
+    self.frames.iter().for_each(|frame| {
       frame.closure.trace();
     });
 
@@ -591,6 +768,48 @@ impl Trace for Fiber {
     if let Some(error) = self.error {
       error.trace();
     }
+
+    self.result.trace();
+    self.waiters.iter().for_each(|waiter| {
+      waiter.trace();
+    });
+
+    if let Some(cancel) = self.cancel {
+      cancel.trace();
+    }
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    unsafe {
+      let start = self.stack.as_ptr();
+      let len = self.stack_top.offset_from(start) as usize;
+      let slice = std::slice::from_raw_parts(start, len);
+
+      slice.iter().for_each(|value| {
+        value.visit_refs(visitor);
+      });
+    }
+
+    self.frames.iter().for_each(|frame| {
+      frame.closure.visit_refs(visitor);
+    });
+
+    self.open_upvalues.iter().for_each(|upvalue| {
+      upvalue.visit_refs(visitor);
+    });
+
+    if let Some(error) = self.error {
+      error.visit_refs(visitor);
+    }
+
+    self.result.visit_refs(visitor);
+    self.waiters.iter().for_each(|waiter| {
+      waiter.visit_refs(visitor);
+    });
+
+    if let Some(cancel) = self.cancel {
+      cancel.visit_refs(visitor);
+    }
   }
 
   fn trace_debug(&self, log: &mut dyn std::io::Write) {
@@ -604,7 +823,7 @@ impl Trace for Fiber {
       });
     }
 
-    self.frames.iter().skip(1).for_each(|frame| {
+    self.frames.iter().for_each(|frame| {
       frame.closure.trace_debug(log);
     });
 
@@ -615,6 +834,15 @@ impl Trace for Fiber {
     if let Some(error) = self.error {
       error.trace_debug(log);
     }
+
+    self.result.trace_debug(log);
+    self.waiters.iter().for_each(|waiter| {
+      waiter.trace_debug(log);
+    });
+
+    if let Some(cancel) = self.cancel {
+      cancel.trace_debug(log);
+    }
   }
 }
 
@@ -638,6 +866,25 @@ mod test {
     assert!(fiber.is_ok())
   }
 
+  #[test]
+  fn with_args() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let fiber = FiberBuilder::<u8>::default()
+      .build(&hooks)
+      .expect("Expected to build");
+
+    let closure = fiber.closure();
+    let fiber = Fiber::with_args(closure, &[val!(10.5), val!(true)]).expect("Expected to build");
+
+    unsafe {
+      assert_eq!(fiber.peek(0), val!(true));
+      assert_eq!(fiber.peek(1), val!(10.5));
+      assert_eq!(fiber.peek(2), val!(closure));
+    }
+  }
+
   #[test]
   fn push() {
     let context = NoContext::default();
@@ -1114,6 +1361,32 @@ mod test {
     }
   }
 
+  #[test]
+  fn ensure_stack_retires_old_stack() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let mut fiber = FiberBuilder::<u8>::default()
+      .max_slots(2)
+      .build(&hooks)
+      .expect("Expected to build");
+
+    unsafe {
+      fiber.push(val!(1.0));
+      fiber.push(val!(2.0));
+    }
+
+    unsafe {
+      let args = fiber.stack_slice(2).as_ptr();
+      fiber.ensure_stack(100);
+
+      let args = std::slice::from_raw_parts(args, 2);
+      assert_eq!(args, &[val!(1.0), val!(2.0)]);
+      assert_ne!(args.as_ptr(), fiber.stack_slice(2).as_ptr());
+      assert_eq!(fiber.stack_slice(2), &[val!(1.0), val!(2.0)]);
+    }
+  }
+
   #[test]
   fn capture_upvalue() {
     let context = NoContext::default();
@@ -1,7 +1,7 @@
 use std::{fmt, io::Write, mem};
 
 use crate::{
-  chunk::{Chunk, ChunkBuilder, Encode},
+  chunk::{Chunk, ChunkBuilder, Encode, SourceSpan},
   managed::{DebugHeap, DebugWrap, Gc, GcStr, Manage, Object, Trace},
   module::Module,
   signature::Arity,
@@ -33,18 +33,39 @@ impl fmt::Display for FunKind {
   }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 pub struct TryBlock {
   /// Start of the try block
   start: u16,
 
   /// End of the try block
   end: u16,
+
+  /// The number of stack slots in use when the try block is entered
+  slots: u16,
 }
 
 impl TryBlock {
-  pub fn new(start: u16, end: u16) -> Self {
-    TryBlock { start, end }
+  pub fn new(start: u16, end: u16, slots: u16) -> Self {
+    TryBlock { start, end, slots }
+  }
+
+  /// Start of the try block
+  #[inline]
+  pub fn start(&self) -> u16 {
+    self.start
+  }
+
+  /// End of the try block
+  #[inline]
+  pub fn end(&self) -> u16 {
+    self.end
+  }
+
+  /// The number of stack slots in use when the try block is entered
+  #[inline]
+  pub fn slots(&self) -> u16 {
+    self.slots
   }
 }
 
@@ -68,6 +89,9 @@ pub struct FunBuilder {
   /// Catch block present in this function
   try_blocks: Vec<TryBlock>,
 
+  /// Is this function async
+  is_async: bool,
+
   /// Code for the function body
   chunk: ChunkBuilder,
 }
@@ -82,6 +106,7 @@ impl FunBuilder {
       module,
       name,
       try_blocks: Vec::new(),
+      is_async: false,
     }
   }
 
@@ -107,6 +132,16 @@ impl FunBuilder {
     self.arity = arity;
   }
 
+  /// Set whether this function is async
+  pub fn set_async(&mut self, is_async: bool) {
+    self.is_async = is_async;
+  }
+
+  /// Set the number of upvalues this function captures
+  pub fn set_upvalue_count(&mut self, upvalue_count: u8) {
+    self.upvalue_count = upvalue_count;
+  }
+
   /// Retrieve the current count of upvalues
   #[inline]
   pub fn upvalue_count(&self) -> u8 {
@@ -121,8 +156,8 @@ impl FunBuilder {
 
   /// Write an aligned byte code to this function
   #[inline]
-  pub fn write_instruction<T: Encode>(&mut self, item: T, line: u32) {
-    self.chunk.write_instruction(item, line)
+  pub fn write_instruction<T: Encode>(&mut self, item: T, span: SourceSpan) {
+    self.chunk.write_instruction(item, span)
   }
 
   /// Patch an instruction on this function
@@ -137,13 +172,35 @@ impl FunBuilder {
     self.chunk.add_constant(constant)
   }
 
+  /// Clear the instructions written to this function
+  pub fn clear_instructions(&mut self) {
+    self.chunk.clear_instructions();
+  }
+
   /// Add a try block to this function
   pub fn add_try(&mut self, try_block: TryBlock) {
     self.try_blocks.push(try_block)
   }
 
+  /// The try blocks added to this function
+  pub fn try_blocks(&self) -> &[TryBlock] {
+    &self.try_blocks
+  }
+
+  /// Replace the try blocks in this function
+  pub fn set_try_blocks(&mut self, try_blocks: Vec<TryBlock>) {
+    self.try_blocks = try_blocks;
+  }
+
   /// Build a final immutable Fun from this builder
-  pub fn build(self) -> Fun {
+  pub fn build(mut self) -> Fun {
+    let chunk = mem::take(&mut self.chunk).build();
+    self.build_with_chunk(chunk)
+  }
+
+  /// Build a final immutable Fun from this builder using a
+  /// previously built chunk in place of the builder's chunk
+  pub fn build_with_chunk(self, chunk: Chunk) -> Fun {
     Fun {
       name: self.name,
       arity: self.arity,
@@ -152,7 +209,8 @@ impl FunBuilder {
       module_id: self.module.id(),
       module: self.module,
       try_blocks: self.try_blocks.into_boxed_slice(),
-      chunk: self.chunk.build(),
+      is_async: self.is_async,
+      chunk,
     }
   }
 }
@@ -164,6 +222,12 @@ impl Trace for FunBuilder {
     self.module.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.name.visit_refs(visitor);
+    self.chunk.visit_refs(visitor);
+    self.module.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     self.name.trace_debug(log);
     self.chunk.trace_debug(log);
@@ -194,6 +258,9 @@ pub struct Fun {
   /// Catch block present in this function
   try_blocks: Box<[TryBlock]>,
 
+  /// Is this function async
+  is_async: bool,
+
   /// Code for the function body
   chunk: Chunk,
 }
@@ -249,9 +316,26 @@ impl Fun {
     self.max_slot as usize
   }
 
+  /// Is this function async
+  #[inline]
+  pub fn is_async(&self) -> bool {
+    self.is_async
+  }
+
+  /// Catch blocks present in this function
+  #[inline]
+  pub fn try_blocks(&self) -> &[TryBlock] {
+    &self.try_blocks
+  }
+
   pub fn has_catch_jump(&self, ip: u16) -> Option<u16> {
+    self.catch_block(ip).map(|try_block| try_block.end)
+  }
+
+  /// The innermost try block containing this instruction offset
+  pub fn catch_block(&self, ip: u16) -> Option<&TryBlock> {
     let mut min_range = std::u16::MAX;
-    let mut jump = None;
+    let mut catch = None;
 
     for try_block in self.try_blocks.iter() {
       if ip >= try_block.start && ip < try_block.end {
@@ -259,12 +343,12 @@ impl Fun {
 
         if len < min_range {
           min_range = len;
-          jump = Some(try_block.end);
+          catch = Some(try_block);
         }
       }
     }
 
-    jump
+    catch
   }
 }
 
@@ -287,6 +371,12 @@ impl Trace for Fun {
     self.module.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.name.visit_refs(visitor);
+    self.chunk.visit_refs(visitor);
+    self.module.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     self.name.trace_debug(log);
     self.chunk.trace_debug(log);
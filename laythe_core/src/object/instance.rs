@@ -1,8 +1,11 @@
 use super::{Class, ObjectKind};
 use crate::{
+  hooks::GcHooks,
   managed::{DebugHeap, DebugWrap, GcObj, GcStr, Manage, Object, Trace},
   value::{Value, VALUE_NIL},
 };
+use fnv::FnvBuildHasher;
+use hashbrown::HashMap;
 use std::{
   fmt,
   io::Write,
@@ -14,6 +17,9 @@ use std::{
 pub struct Instance {
   class: GcObj<Class>,
   fields: Box<[Value]>,
+
+  /// Fields set on this instance that are not part of its class's layout
+  dynamic: Option<Box<HashMap<GcStr, Value, FnvBuildHasher>>>,
 }
 
 impl Instance {
@@ -22,6 +28,7 @@ impl Instance {
     Instance {
       class,
       fields: vec![VALUE_NIL; class.fields()].into_boxed_slice(),
+      dynamic: None,
     }
   }
 
@@ -35,6 +42,13 @@ impl Instance {
     &self.fields
   }
 
+  #[inline]
+  pub fn fields_mut(&mut self) -> &mut [Value] {
+    &mut self.fields
+  }
+
+  /// Set a field already present on this instance, returning
+  /// false if neither its class nor this instance has the field
   #[inline]
   pub fn set_field(&mut self, name: GcStr, value: Value) -> bool {
     match self.class.get_field_index(&name) {
@@ -42,16 +56,50 @@ impl Instance {
         self.fields[index as usize] = value;
         true
       },
-      None => false,
+      None => match self
+        .dynamic
+        .as_mut()
+        .and_then(|dynamic| dynamic.get_mut(&name))
+      {
+        Some(field) => {
+          *field = value;
+          true
+        },
+        None => false,
+      },
+    }
+  }
+
+  /// Set a field on this instance. Fields outside of its class's
+  /// layout are stored in a table on this instance
+  pub fn set_dynamic_field(&mut self, hooks: &GcHooks, name: GcStr, value: Value) {
+    if !self.set_field(name, value) {
+      hooks.grow(self, |instance| {
+        instance
+          .dynamic
+          .get_or_insert_with(Default::default)
+          .insert(name, value);
+      });
     }
   }
 
   #[inline]
   pub fn get_field(&self, name: &GcStr) -> Option<&Value> {
-    self
-      .class
-      .get_field_index(&name)
-      .map(|index| &self.fields[index as usize])
+    match self.class.get_field_index(name) {
+      Some(index) => Some(&self.fields[index as usize]),
+      None => self.get_dynamic_field(name),
+    }
+  }
+
+  /// Get a field set outside of this instance's class layout
+  #[inline]
+  pub fn get_dynamic_field(&self, name: &GcStr) -> Option<&Value> {
+    self.dynamic.as_ref().and_then(|dynamic| dynamic.get(name))
+  }
+
+  /// Iterate the fields set outside of this instance's class layout
+  pub fn dynamic_fields(&self) -> impl Iterator<Item = (&GcStr, &Value)> {
+    self.dynamic.iter().flat_map(|dynamic| dynamic.iter())
   }
 }
 
@@ -90,6 +138,28 @@ impl Trace for Instance {
     self.fields.iter().for_each(|val| {
       val.trace();
     });
+
+    if let Some(dynamic) = &self.dynamic {
+      dynamic.iter().for_each(|(key, val)| {
+        key.trace();
+        val.trace();
+      });
+    }
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.class.visit_refs(visitor);
+
+    self.fields.iter().for_each(|val| {
+      val.visit_refs(visitor);
+    });
+
+    if let Some(dynamic) = &self.dynamic {
+      dynamic.iter().for_each(|(key, val)| {
+        key.visit_refs(visitor);
+        val.visit_refs(visitor);
+      });
+    }
   }
 
   fn trace_debug(&self, stdio: &mut dyn Write) {
@@ -98,22 +168,39 @@ impl Trace for Instance {
     self.fields.iter().for_each(|val| {
       val.trace_debug(stdio);
     });
+
+    if let Some(dynamic) = &self.dynamic {
+      dynamic.iter().for_each(|(key, val)| {
+        key.trace_debug(stdio);
+        val.trace_debug(stdio);
+      });
+    }
   }
 }
 
 impl DebugHeap for Instance {
   fn fmt_heap(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
-    f.debug_struct("Instance")
+    let mut debug = f.debug_struct("Instance");
+    debug
       .field("class", &DebugWrap(&self.class, depth))
-      .field("fields", &DebugWrap(&&*self.fields, depth))
-      .finish()
+      .field("fields", &DebugWrap(&&*self.fields, depth));
+
+    if let Some(dynamic) = &self.dynamic {
+      debug.field("dynamic", &DebugWrap(&**dynamic, depth));
+    }
+
+    debug.finish()
   }
 }
 
 impl Manage for Instance {
   fn size(&self) -> usize {
-    mem::size_of::<Instance>()
-      + (mem::size_of::<GcStr>() + mem::size_of::<Value>()) * self.fields.len()
+    let dynamic = self.dynamic.as_ref().map_or(0, |dynamic| {
+      mem::size_of::<HashMap<GcStr, Value, FnvBuildHasher>>()
+        + (mem::size_of::<GcStr>() + mem::size_of::<Value>()) * dynamic.capacity()
+    });
+
+    mem::size_of::<Instance>() + mem::size_of::<Value>() * self.fields.len() + dynamic
   }
 
   fn as_debug(&self) -> &dyn DebugHeap {
@@ -165,6 +165,12 @@ impl<T: 'static + Trace> Trace for List<T> {
     });
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.iter().for_each(|value| {
+      value.visit_refs(visitor);
+    });
+  }
+
   fn trace_debug(&self, stdio: &mut dyn Write) {
     self.iter().for_each(|value| {
       value.trace_debug(stdio);
@@ -110,6 +110,13 @@ impl<K: 'static + Trace, V: 'static + Trace> Trace for Map<K, V> {
     });
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.iter().for_each(|(key, value)| {
+      key.visit_refs(visitor);
+      value.visit_refs(visitor);
+    });
+  }
+
   fn trace_debug(&self, stdio: &mut dyn Write) {
     self.iter().for_each(|(key, value)| {
       key.trace_debug(stdio);
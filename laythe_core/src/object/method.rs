@@ -50,6 +50,11 @@ impl Trace for Method {
     self.method.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.receiver.visit_refs(visitor);
+    self.method.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdio: &mut dyn Write) {
     self.receiver.trace_debug(stdio);
     self.method.trace_debug(stdio);
@@ -1,3 +1,4 @@
+mod channel;
 mod class;
 mod closure;
 mod enumerator;
@@ -8,18 +9,21 @@ mod list;
 mod map;
 mod method;
 mod native;
+mod tuple;
 mod upvalue;
 
+pub use channel::Channel;
 pub use class::Class;
 pub use closure::Closure;
 pub use enumerator::{Enumerate, Enumerator};
-pub use fiber::{Fiber, FiberResult};
+pub use fiber::{Fiber, FiberResult, FiberState};
 pub use fun::{Fun, FunBuilder, FunKind, TryBlock};
 pub use instance::Instance;
 pub use list::List;
 pub use map::Map;
 pub use method::Method;
 pub use native::{LyNative, Native, NativeMeta, NativeMetaBuilder};
+pub use tuple::Tuple;
 pub use upvalue::Upvalue;
 
 #[cfg(test)]
@@ -38,6 +42,8 @@ pub enum ObjectKind {
   Method,
   Native,
   String,
+  Tuple,
   Upvalue,
   Fiber,
+  Channel,
 }
@@ -6,7 +6,7 @@ use crate::{
   Call,
 };
 use std::mem;
-use std::{fmt, io::Write};
+use std::{any::Any, fmt, io::Write};
 
 use super::ObjectKind;
 
@@ -21,6 +21,9 @@ pub struct NativeMetaBuilder {
   /// Does this
   pub environment: Environment,
 
+  /// Does this method mutate its receiver, rejected on frozen objects
+  pub mutates: bool,
+
   /// The signature of this native function or method
   pub signature: SignatureBuilder,
 }
@@ -32,6 +35,7 @@ impl NativeMetaBuilder {
       name,
       is_method: false,
       environment: Environment::StackLess,
+      mutates: false,
       signature: SignatureBuilder::new(arity),
     }
   }
@@ -42,6 +46,7 @@ impl NativeMetaBuilder {
       name,
       is_method: true,
       environment: Environment::StackLess,
+      mutates: false,
       signature: SignatureBuilder::new(arity),
     }
   }
@@ -52,6 +57,7 @@ impl NativeMetaBuilder {
       name: self.name,
       is_method: self.is_method,
       environment: self.environment,
+      mutates: self.mutates,
       signature: self.signature.with_params(parameters),
     }
   }
@@ -62,6 +68,18 @@ impl NativeMetaBuilder {
       name: self.name,
       is_method: self.is_method,
       environment: Environment::Normal,
+      mutates: self.mutates,
+      signature: self.signature,
+    }
+  }
+
+  /// Indicate this native method mutates its receiver
+  pub const fn with_mutation(self) -> Self {
+    Self {
+      name: self.name,
+      is_method: self.is_method,
+      environment: self.environment,
+      mutates: true,
       signature: self.signature,
     }
   }
@@ -72,6 +90,7 @@ impl NativeMetaBuilder {
       name: hooks.manage_str(self.name),
       is_method: self.is_method,
       environment: self.environment,
+      mutates: self.mutates,
       signature: self.signature.to_sig(hooks),
     }
   }
@@ -88,6 +107,9 @@ pub struct NativeMeta {
   /// Does this
   pub environment: Environment,
 
+  /// Does this method mutate its receiver, rejected on frozen objects
+  pub mutates: bool,
+
   /// The signature of this native function or method
   pub signature: Signature,
 }
@@ -98,6 +120,11 @@ impl Trace for NativeMeta {
     self.signature.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.name.visit_refs(visitor);
+    self.signature.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     self.name.trace_debug(log);
     self.signature.trace_debug(log);
@@ -126,6 +153,14 @@ impl Native {
   pub fn call(&self, hooks: &mut Hooks, this: Option<Value>, values: &[Value]) -> Call {
     self.native.call(hooks, this, values)
   }
+
+  /// Get the underlying native function if it is a `T`
+  pub fn downcast_ref<T: LyNative + 'static>(&self) -> Option<&T> {
+    self
+      .native
+      .as_any()
+      .and_then(|native| native.downcast_ref::<T>())
+  }
 }
 
 impl fmt::Display for Native {
@@ -147,6 +182,11 @@ impl Trace for Native {
     self.native.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.meta.visit_refs(visitor);
+    self.native.visit_refs(visitor);
+  }
+
   #[inline]
   fn trace_debug(&self, log: &mut dyn Write) {
     self.meta.trace_debug(log);
@@ -184,6 +224,14 @@ impl Object for Native {
 pub trait LyNative: Trace {
   /// Call the native functions
   fn call(&self, hooks: &mut Hooks, this: Option<Value>, values: &[Value]) -> Call;
+
+  /// Expose this native function so it can be downcast with
+  /// `Native::downcast_ref`. Natives that carry state other natives read
+  /// back, such as a compiled pattern stored in an instance field, return
+  /// themselves here
+  fn as_any(&self) -> Option<&dyn Any> {
+    None
+  }
 }
 
 impl Trace for Box<dyn LyNative> {
@@ -193,6 +241,11 @@ impl Trace for Box<dyn LyNative> {
     inner.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    let inner: &dyn LyNative = &**self;
+    inner.visit_refs(visitor);
+  }
+
   #[inline]
   fn trace_debug(&self, log: &mut dyn Write) {
     let inner: &dyn LyNative = &**self;
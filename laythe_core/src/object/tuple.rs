@@ -0,0 +1,105 @@
+use std::{
+  fmt::{self, Display},
+  io::Write,
+  mem,
+  ops::Deref,
+};
+
+use crate::{
+  managed::{DebugHeap, DebugWrap, Manage, Object, Trace},
+  value::Value,
+};
+
+use super::ObjectKind;
+
+/// An immutable fixed length sequence of values. Unlike lists two tuples
+/// are equal when their items are equal
+#[derive(Clone, Debug)]
+pub struct Tuple(Box<[Value]>);
+
+impl Tuple {
+  pub fn new(items: &[Value]) -> Self {
+    Self(Box::from(items))
+  }
+}
+
+impl Display for Tuple {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "(")?;
+
+    match self.split_last() {
+      Some((last, [])) => write!(f, "{},", last)?,
+      Some((last, rest)) => {
+        for item in rest.iter() {
+          write!(f, "{}, ", item)?;
+        }
+
+        write!(f, "{}", last)?;
+      },
+      None => (),
+    }
+
+    write!(f, ")")
+  }
+}
+
+impl Deref for Tuple {
+  type Target = [Value];
+
+  #[inline]
+  fn deref(&self) -> &[Value] {
+    &self.0
+  }
+}
+
+impl From<Vec<Value>> for Tuple {
+  fn from(vec: Vec<Value>) -> Self {
+    Tuple(vec.into_boxed_slice())
+  }
+}
+
+impl Trace for Tuple {
+  fn trace(&self) {
+    self.iter().for_each(|value| {
+      value.trace();
+    });
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.iter().for_each(|value| {
+      value.visit_refs(visitor);
+    });
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.iter().for_each(|value| {
+      value.trace_debug(stdio);
+    });
+  }
+}
+
+impl DebugHeap for Tuple {
+  fn fmt_heap(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+    let mut tuple = f.debug_tuple("");
+    for item in self.iter() {
+      tuple.field(&DebugWrap(item, depth));
+    }
+    tuple.finish()
+  }
+}
+
+impl Manage for Tuple {
+  fn size(&self) -> usize {
+    mem::size_of::<Self>() + mem::size_of::<Value>() * self.len()
+  }
+
+  fn as_debug(&self) -> &dyn DebugHeap {
+    self
+  }
+}
+
+impl Object for Tuple {
+  fn kind(&self) -> ObjectKind {
+    ObjectKind::Tuple
+  }
+}
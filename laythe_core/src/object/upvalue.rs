@@ -98,6 +98,12 @@ impl Trace for Upvalue {
     }
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    if let Upvalue::Closed(upvalue) = self {
+      upvalue.visit_refs(visitor);
+    }
+  }
+
   fn trace_debug(&self, stdio: &mut dyn Write) {
     if let Upvalue::Closed(upvalue) = self {
       upvalue.trace_debug(stdio);
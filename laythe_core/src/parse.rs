@@ -0,0 +1,351 @@
+//! Locale independent parsing of numbers shared by the scanner and the
+//! standard library. Only ascii digits and `.` as a decimal point are
+//! accepted so parsing never depends on the host locale.
+//!
+//! A number literal follows the grammar below. Runtime parsing additionally
+//! accepts a single leading `+` or `-` and a fraction without whole digits
+//! such as `.5`
+//!
+//! ```text
+//! number   := digits fraction? exponent?
+//! fraction := '.' digits
+//! exponent := ('e' | 'E') ('+' | '-')? digits
+//! digits   := [0-9]+
+//! ```
+use std::fmt;
+
+/// An error encountered while parsing a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberError {
+  /// No digits were found at the start of the input
+  Empty,
+
+  /// An exponent was not followed by any digits. Contains
+  /// the byte offset where the digits were expected
+  MissingExponent(usize),
+
+  /// An unexpected character was found at the byte offset
+  Unexpected(usize),
+
+  /// The number does not fit in the requested type
+  Overflow,
+}
+
+impl fmt::Display for NumberError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      NumberError::Empty => write!(f, "Expected a digit."),
+      NumberError::MissingExponent(offset) => {
+        write!(f, "Expected exponent digits at offset {}.", offset)
+      },
+      NumberError::Unexpected(offset) => write!(f, "Unexpected character at offset {}.", offset),
+      NumberError::Overflow => write!(f, "Number is too large."),
+    }
+  }
+}
+
+/// Find the length in bytes of the number at the start of `source`. A `.` is
+/// only part of the number when followed by a digit so `1.str()` scans as `1`
+pub fn scan_number(source: &str) -> Result<usize, NumberError> {
+  scan(source.as_bytes(), false)
+}
+
+/// Scan a number optionally allowing the whole digits to be omitted
+/// when a fraction is present
+fn scan(bytes: &[u8], bare_fraction: bool) -> Result<usize, NumberError> {
+  let mut end = scan_digits(bytes, 0);
+  if end == 0 && !(bare_fraction && bytes.first() == Some(&b'.')) {
+    return Err(NumberError::Empty);
+  }
+
+  if bytes.get(end) == Some(&b'.') {
+    let fraction_end = scan_digits(bytes, end + 1);
+    if fraction_end > end + 1 {
+      end = fraction_end;
+    }
+  }
+
+  if end == 0 {
+    return Err(NumberError::Empty);
+  }
+
+  if let Some(b'e') | Some(b'E') = bytes.get(end) {
+    end += 1;
+
+    if let Some(b'+') | Some(b'-') = bytes.get(end) {
+      end += 1;
+    }
+
+    let exponent_end = scan_digits(bytes, end);
+    if exponent_end == end {
+      return Err(NumberError::MissingExponent(end));
+    }
+    end = exponent_end;
+  }
+
+  Ok(end)
+}
+
+/// Parse the whole of `source` as a number with an optional leading sign
+pub fn parse_number(source: &str) -> Result<f64, NumberError> {
+  let (negative, unsigned) = match source.as_bytes().first() {
+    Some(b'-') => (true, &source[1..]),
+    Some(b'+') => (false, &source[1..]),
+    _ => (false, source),
+  };
+
+  let sign_len = source.len() - unsigned.len();
+  let len = scan(unsigned.as_bytes(), true).map_err(|error| match error {
+    NumberError::MissingExponent(offset) => NumberError::MissingExponent(offset + sign_len),
+    error => error,
+  })?;
+
+  if len != unsigned.len() {
+    return Err(NumberError::Unexpected(len + sign_len));
+  }
+
+  // the grammar is a strict subset of what rust accepts so this cannot fail
+  let value = unsigned
+    .parse::<f64>()
+    .expect("Number grammar should be accepted by f64 parsing");
+
+  Ok(if negative { -value } else { value })
+}
+
+/// Parse the whole of `source` as an unsigned integer made only of ascii
+/// digits, such as the fields of a date
+pub fn parse_digits(source: &str) -> Result<u32, NumberError> {
+  let bytes = source.as_bytes();
+
+  let len = scan_digits(bytes, 0);
+  if len == 0 {
+    return Err(NumberError::Empty);
+  }
+  if len != bytes.len() {
+    return Err(NumberError::Unexpected(len));
+  }
+
+  bytes.iter().try_fold(0u32, |value, byte| {
+    value
+      .checked_mul(10)
+      .and_then(|value| value.checked_add((byte - b'0') as u32))
+      .ok_or(NumberError::Overflow)
+  })
+}
+
+/// Find the end of a run of ascii digits starting at `start`
+fn scan_digits(bytes: &[u8], start: usize) -> usize {
+  let mut end = start;
+  while end < bytes.len() && bytes[end].is_ascii_digit() {
+    end += 1;
+  }
+
+  end
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn scan() {
+    assert_eq!(scan_number("10"), Ok(2));
+    assert_eq!(scan_number("10.5"), Ok(4));
+    assert_eq!(scan_number("10.str()"), Ok(2));
+    assert_eq!(scan_number("10."), Ok(2));
+    assert_eq!(scan_number("1e10"), Ok(4));
+    assert_eq!(scan_number("1.5E-3 + 2"), Ok(6));
+    assert_eq!(scan_number("1e+"), Err(NumberError::MissingExponent(3)));
+    assert_eq!(scan_number("1ex"), Err(NumberError::MissingExponent(2)));
+    assert_eq!(scan_number(".5"), Err(NumberError::Empty));
+    assert_eq!(scan_number(""), Err(NumberError::Empty));
+  }
+
+  #[test]
+  fn parse() {
+    assert_eq!(parse_number("10"), Ok(10.0));
+    assert_eq!(parse_number("-10.25"), Ok(-10.25));
+    assert_eq!(parse_number("+3e2"), Ok(300.0));
+    assert_eq!(parse_number("2.5e-1"), Ok(0.25));
+    assert_eq!(parse_number("0.1"), Ok(0.1));
+    assert_eq!(parse_number("-.5"), Ok(-0.5));
+    assert_eq!(parse_number("1e400"), Ok(f64::INFINITY));
+  }
+
+  #[test]
+  fn parse_rejects() {
+    assert_eq!(parse_number(""), Err(NumberError::Empty));
+    assert_eq!(parse_number("-"), Err(NumberError::Empty));
+    assert_eq!(parse_number("1,5"), Err(NumberError::Unexpected(1)));
+    assert_eq!(parse_number("1."), Err(NumberError::Unexpected(1)));
+    assert_eq!(parse_number("."), Err(NumberError::Empty));
+    assert_eq!(parse_number(".e1"), Err(NumberError::Empty));
+    assert_eq!(parse_number(" 1"), Err(NumberError::Empty));
+    assert_eq!(parse_number("1 "), Err(NumberError::Unexpected(1)));
+    assert_eq!(parse_number("1_000"), Err(NumberError::Unexpected(1)));
+    assert_eq!(parse_number("-1e"), Err(NumberError::MissingExponent(3)));
+    assert_eq!(parse_number("--1"), Err(NumberError::Empty));
+    assert_eq!(parse_number("inf"), Err(NumberError::Empty));
+    assert_eq!(parse_number("NaN"), Err(NumberError::Empty));
+    assert_eq!(parse_number("0x10"), Err(NumberError::Unexpected(1)));
+    assert_eq!(parse_number("١٢"), Err(NumberError::Empty));
+  }
+
+  #[test]
+  fn digits() {
+    assert_eq!(parse_digits("2021"), Ok(2021));
+    assert_eq!(parse_digits("007"), Ok(7));
+    assert_eq!(parse_digits("4294967295"), Ok(u32::MAX));
+    assert_eq!(parse_digits("4294967296"), Err(NumberError::Overflow));
+    assert_eq!(parse_digits(""), Err(NumberError::Empty));
+    assert_eq!(parse_digits("-1"), Err(NumberError::Empty));
+    assert_eq!(parse_digits("12:30"), Err(NumberError::Unexpected(2)));
+    assert_eq!(parse_digits("１２"), Err(NumberError::Empty));
+  }
+
+  mod fuzz {
+    use super::*;
+
+    const ITERATIONS: usize = 20_000;
+    const ALPHABET: &[u8] = b"0123456789.eE+-, _xa";
+
+    /// A small xorshift generator so fuzz runs are reproducible
+    struct Rng(u64);
+
+    impl Rng {
+      fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+      }
+
+      fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+      }
+
+      fn digits(&mut self, buffer: &mut String) {
+        for _ in 0..=self.below(12) {
+          buffer.push((b'0' + self.below(10) as u8) as char);
+        }
+      }
+    }
+
+    /// Does this string match the number grammar
+    fn matches_grammar(source: &str) -> bool {
+      let unsigned = source
+        .strip_prefix('-')
+        .or_else(|| source.strip_prefix('+'))
+        .unwrap_or(source);
+
+      let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(index) => (&unsigned[..index], Some(&unsigned[index + 1..])),
+        None => (unsigned, None),
+      };
+
+      let all_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+      let mantissa_valid = match mantissa.split_once('.') {
+        Some((whole, fraction)) => (whole.is_empty() || all_digits(whole)) && all_digits(fraction),
+        None => all_digits(mantissa),
+      };
+
+      let exponent_valid = match exponent {
+        Some(exponent) => {
+          let digits = exponent
+            .strip_prefix('-')
+            .or_else(|| exponent.strip_prefix('+'))
+            .unwrap_or(exponent);
+          all_digits(digits)
+        },
+        None => true,
+      };
+
+      mantissa_valid && exponent_valid
+    }
+
+    #[test]
+    fn random_strings() {
+      let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+      for _ in 0..ITERATIONS {
+        let len = rng.below(10);
+        let source: String = (0..len)
+          .map(|_| ALPHABET[rng.below(ALPHABET.len())] as char)
+          .collect();
+
+        let result = parse_number(&source);
+        assert_eq!(
+          result.is_ok(),
+          matches_grammar(&source),
+          "disagreement on {:?}",
+          source
+        );
+
+        if let Ok(value) = result {
+          assert_eq!(
+            value,
+            source.parse::<f64>().unwrap(),
+            "value of {:?}",
+            source
+          );
+        }
+      }
+    }
+
+    #[test]
+    fn generated_numbers() {
+      let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+      for _ in 0..ITERATIONS {
+        let mut source = String::new();
+        match rng.below(3) {
+          0 => source.push('-'),
+          1 => source.push('+'),
+          _ => (),
+        }
+
+        rng.digits(&mut source);
+        if rng.below(2) == 0 {
+          source.push('.');
+          rng.digits(&mut source);
+        }
+        if rng.below(2) == 0 {
+          source.push(if rng.below(2) == 0 { 'e' } else { 'E' });
+          match rng.below(3) {
+            0 => source.push('-'),
+            1 => source.push('+'),
+            _ => (),
+          }
+          rng.digits(&mut source);
+        }
+
+        let expected = source.parse::<f64>().unwrap();
+        assert_eq!(parse_number(&source), Ok(expected), "value of {:?}", source);
+
+        let unsigned = source.trim_start_matches(['-', '+']);
+        assert_eq!(
+          scan_number(unsigned),
+          Ok(unsigned.len()),
+          "scan of {:?}",
+          source
+        );
+      }
+    }
+
+    #[test]
+    fn round_trip() {
+      let mut rng = Rng(0xdead_beef_cafe_f00d);
+
+      for _ in 0..ITERATIONS {
+        let value = f64::from_bits(rng.next());
+        if !value.is_finite() {
+          continue;
+        }
+
+        assert_eq!(parse_number(&format!("{}", value)), Ok(value));
+        assert_eq!(parse_number(&format!("{:e}", value)), Ok(value));
+      }
+    }
+  }
+}
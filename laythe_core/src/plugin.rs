@@ -0,0 +1,109 @@
+use crate::{
+  hooks::GcHooks,
+  module::{ModuleResult, Package},
+};
+use fnv::FnvHasher;
+use std::hash::Hasher;
+
+/// The version of the interface between laythe and its plugins. Bumped
+/// whenever the functions a plugin exports change
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// The function a plugin exports describing the build it was compiled against
+pub const PLUGIN_ABI_SYMBOL: &[u8] = b"laythe_plugin_abi";
+
+/// The function a plugin exports registering its native modules
+pub const PLUGIN_INIT_SYMBOL: &[u8] = b"laythe_plugin_init";
+
+/// The version of the compiler that built this crate
+const RUSTC_VERSION: &str = env!("LAYTHE_RUSTC_VERSION");
+
+/// Returns the `plugin_abi_key` of the build a plugin was compiled against.
+/// This uses the C calling convention so it can be called safely before
+/// the rest of the plugin is known to match
+pub type PluginAbi = extern "C" fn() -> u64;
+
+/// Registers a plugin's native modules in the package created for it. Symbols
+/// exported from the package's root module are imported with the package's
+/// name and further modules are added with `Package::insert_module`
+pub type PluginInit = fn(&GcHooks, &mut Package) -> ModuleResult<()>;
+
+/// A key identifying the plugin interface, the version of laythe_core, the
+/// compiler that built it and the features that change how values are laid
+/// out. A plugin is only initialized by a host with the same key as the
+/// types they share could otherwise differ
+pub fn plugin_abi_key() -> u64 {
+  let mut hasher = FnvHasher::default();
+  hasher.write_u32(PLUGIN_ABI_VERSION);
+  hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
+  hasher.write(RUSTC_VERSION.as_bytes());
+  hasher.write_u8(cfg!(feature = "nan_boxing") as u8);
+  hasher.finish()
+}
+
+/// Export the functions laythe looks for when loading a plugin from a
+/// dynamic library. The provided function registers the plugin's modules
+///
+/// ```ignore
+/// fn init(hooks: &GcHooks, package: &mut Package) -> ModuleResult<()> {
+///   let mut root = package.root_module();
+///   let name = hooks.manage_str("area");
+///   root.insert_symbol(hooks, name, val!(Area::native(hooks)))?;
+///   root.export_symbol(hooks, name)
+/// }
+///
+/// export_plugin!(init);
+/// ```
+#[macro_export]
+macro_rules! export_plugin {
+  ( $init:path ) => {
+    #[no_mangle]
+    pub extern "C" fn laythe_plugin_abi() -> u64 {
+      $crate::plugin::plugin_abi_key()
+    }
+
+    #[no_mangle]
+    pub fn laythe_plugin_init(
+      hooks: &$crate::hooks::GcHooks,
+      package: &mut $crate::module::Package,
+    ) -> $crate::module::ModuleResult<()> {
+      let init: $crate::plugin::PluginInit = $init;
+      init(hooks, package)
+    }
+  };
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{hooks::NoContext, module::Module, object::Class, val, value::Value};
+  use std::path::PathBuf;
+
+  fn init(hooks: &GcHooks, package: &mut Package) -> ModuleResult<()> {
+    let mut root = package.root_module();
+    let name = hooks.manage_str("answer");
+    root.insert_symbol(hooks, name, val!(42.0))?;
+    root.export_symbol(hooks, name)
+  }
+
+  export_plugin!(init);
+
+  #[test]
+  fn exported_plugin() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let name = hooks.manage_str("answers");
+    let module_class = Class::bare(name);
+    let module = Module::new(hooks.manage_obj(module_class), PathBuf::from("answers"), 0);
+    let mut package = Package::new(name, hooks.manage(module));
+
+    assert_eq!(laythe_plugin_abi(), plugin_abi_key());
+    assert!(laythe_plugin_init(&hooks, &mut package).is_ok());
+
+    let answer = package
+      .root_module()
+      .get_exported_symbol(hooks.manage_str("answer"));
+    assert_eq!(answer, Ok(val!(42.0)));
+  }
+}
@@ -0,0 +1,250 @@
+use crate::{
+  hooks::GcHooks,
+  object::{List, Map, ObjectKind},
+  val,
+  value::{Value, VALUE_NIL},
+};
+use serde::{
+  de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+  ser::{self, SerializeMap, SerializeSeq},
+  Deserializer, Serialize, Serializer,
+};
+use std::fmt;
+
+/// How deeply lists and maps may nest before serialization fails. Guards
+/// against values that contain themselves
+const MAX_DEPTH: usize = 128;
+
+/// Serialize nil, bools, numbers, strings and the lists and maps built from
+/// them. Numbers without a fractional part are written as integers. Other
+/// values such as functions and instances fail to serialize
+impl Serialize for Value {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Nested(*self, 0).serialize(serializer)
+  }
+}
+
+/// A value found at a depth within the value being serialized
+struct Nested(Value, usize);
+
+impl Serialize for Nested {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let Nested(value, depth) = *self;
+
+    if value.is_nil() {
+      return serializer.serialize_unit();
+    }
+
+    if value.is_bool() {
+      return serializer.serialize_bool(value.to_bool());
+    }
+
+    if value.is_num() {
+      let num = value.to_num();
+      return if num.fract() == 0.0 && num >= i64::MIN as f64 && num <= i64::MAX as f64 {
+        serializer.serialize_i64(num as i64)
+      } else {
+        serializer.serialize_f64(num)
+      };
+    }
+
+    if depth >= MAX_DEPTH {
+      return Err(ser::Error::custom(
+        "Value nests too deeply to serialize, it may contain itself.",
+      ));
+    }
+
+    match value.to_obj().kind() {
+      ObjectKind::String => serializer.serialize_str(&value.to_obj().to_str()),
+      ObjectKind::List => {
+        let list = value.to_obj().to_list();
+        let mut seq = serializer.serialize_seq(Some(list.len()))?;
+        for item in list.iter() {
+          seq.serialize_element(&Nested(*item, depth + 1))?;
+        }
+        seq.end()
+      },
+      ObjectKind::Tuple => {
+        let tuple = value.to_obj().to_tuple();
+        let mut seq = serializer.serialize_seq(Some(tuple.len()))?;
+        for item in tuple.iter() {
+          seq.serialize_element(&Nested(*item, depth + 1))?;
+        }
+        seq.end()
+      },
+      ObjectKind::Map => {
+        let map = value.to_obj().to_map();
+        let mut entries = serializer.serialize_map(Some(map.len()))?;
+        for (key, value) in map.iter() {
+          entries.serialize_entry(&Nested(*key, depth + 1), &Nested(*value, depth + 1))?;
+        }
+        entries.end()
+      },
+      _ => Err(ser::Error::custom(format!(
+        "Unable to serialize a {}.",
+        value.value_type()
+      ))),
+    }
+  }
+}
+
+/// Deserialize a value from any serde format, allocating its strings,
+/// lists and maps through the provided hooks
+///
+/// # Examples
+/// ```
+/// use laythe_core::hooks::{GcHooks, NoContext};
+/// use laythe_core::serialize::ValueSeed;
+/// use serde::de::{value::Error, DeserializeSeed, IntoDeserializer};
+///
+/// let context = NoContext::default();
+/// let hooks = GcHooks::new(&context);
+///
+/// let deserializer = IntoDeserializer::<Error>::into_deserializer("example");
+/// let value = ValueSeed::new(&hooks).deserialize(deserializer).unwrap();
+/// assert_eq!(&*value.to_obj().to_str(), "example");
+/// ```
+#[derive(Clone, Copy)]
+pub struct ValueSeed<'a> {
+  hooks: &'a GcHooks<'a>,
+}
+
+impl<'a> ValueSeed<'a> {
+  /// Create a new seed allocating through the provided hooks
+  pub fn new(hooks: &'a GcHooks<'a>) -> Self {
+    Self { hooks }
+  }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for ValueSeed<'a> {
+  type Value = Value;
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+    deserializer.deserialize_any(self)
+  }
+}
+
+impl<'de, 'a> Visitor<'de> for ValueSeed<'a> {
+  type Value = Value;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "nil, a bool, number, string, list or map")
+  }
+
+  fn visit_bool<E: de::Error>(self, value: bool) -> Result<Value, E> {
+    Ok(val!(value))
+  }
+
+  fn visit_i64<E: de::Error>(self, value: i64) -> Result<Value, E> {
+    Ok(val!(value as f64))
+  }
+
+  fn visit_u64<E: de::Error>(self, value: u64) -> Result<Value, E> {
+    Ok(val!(value as f64))
+  }
+
+  fn visit_f64<E: de::Error>(self, value: f64) -> Result<Value, E> {
+    Ok(val!(value))
+  }
+
+  fn visit_str<E: de::Error>(self, value: &str) -> Result<Value, E> {
+    Ok(val!(self.hooks.manage_str(value)))
+  }
+
+  fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+    Ok(VALUE_NIL)
+  }
+
+  fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+    Ok(VALUE_NIL)
+  }
+
+  fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+    self.deserialize(deserializer)
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+    let hooks = self.hooks;
+    let mut list = hooks.manage_obj(List::with_capacity(seq.size_hint().unwrap_or(0)));
+    hooks.push_root(list);
+
+    let result = (|| {
+      while let Some(item) = seq.next_element_seed(self)? {
+        hooks.grow(&mut *list, |list| list.push(item));
+      }
+      Ok(val!(list))
+    })();
+
+    hooks.pop_roots(1);
+    result
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Value, A::Error> {
+    let hooks = self.hooks;
+    let mut map = hooks.manage_obj(Map::with_capacity(entries.size_hint().unwrap_or(0)));
+    hooks.push_root(map);
+
+    let result = (|| {
+      while let Some(key) = entries.next_key_seed(self)? {
+        hooks.push_root(key);
+        let value = entries.next_value_seed(self);
+        hooks.pop_roots(1);
+
+        let value = value?;
+        hooks.grow(&mut *map, |map| map.insert(key, value));
+      }
+      Ok(val!(map))
+    })();
+
+    hooks.pop_roots(1);
+    result
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::hooks::NoContext;
+
+  #[test]
+  fn round_trip() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let json = r#"{"name":"laythe","tags":["fast",null,true],"version":1,"ratio":0.5}"#;
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let value = ValueSeed::new(&hooks)
+      .deserialize(&mut deserializer)
+      .expect("Unable to deserialize");
+
+    let map = value.to_obj().to_map();
+    assert_eq!(map.len(), 4);
+
+    let name = map.get(&val!(hooks.manage_str("name"))).unwrap();
+    assert_eq!(&*name.to_obj().to_str(), "laythe");
+
+    let tags = map.get(&val!(hooks.manage_str("tags"))).unwrap();
+    assert_eq!(tags.to_obj().to_list().len(), 3);
+
+    let serialized: serde_json::Value = serde_json::to_value(value).expect("Unable to serialize");
+    let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(serialized, expected);
+  }
+
+  #[test]
+  fn unsupported() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let mut list = hooks.manage_obj(List::new());
+    let value = val!(list);
+    list.push(value);
+
+    let error = serde_json::to_string(&val!(list)).unwrap_err();
+    assert!(error.to_string().contains("nests too deeply"));
+
+    let class = hooks.manage_obj(crate::object::Class::bare(hooks.manage_str("Foo")));
+    let error = serde_json::to_string(&val!(class)).unwrap_err();
+    assert_eq!(error.to_string(), "Unable to serialize a class.");
+  }
+}
@@ -68,6 +68,24 @@ impl Arity {
 
     Ok(())
   }
+
+  /// The number of arguments that must be provided for
+  /// this Arity Kind
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_core::signature::Arity;
+  ///
+  /// assert_eq!(Arity::Fixed(3).required(), 3);
+  /// assert_eq!(Arity::Default(1, 2).required(), 1);
+  /// ```
+  pub fn required(&self) -> u8 {
+    match *self {
+      Self::Fixed(arity) => arity,
+      Self::Variadic(arity) => arity,
+      Self::Default(min_arity, _) => min_arity,
+    }
+  }
 }
 
 /// A native parameter indicating the name and kind of the parameter
@@ -111,6 +129,10 @@ impl Trace for Parameter {
     self.name.trace()
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.name.visit_refs(visitor)
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     self.name.trace_debug(log)
   }
@@ -131,6 +153,8 @@ pub enum ParameterKind {
   Nil,
   Fun,
   Fiber,
+  Channel,
+  Tuple,
 }
 
 impl ParameterKind {
@@ -155,6 +179,8 @@ impl ParameterKind {
           | (ParameterKind::Fun, ObjectKind::Method)
           | (ParameterKind::Fun, ObjectKind::Native)
           | (ParameterKind::String, ObjectKind::String)
+          | (ParameterKind::Channel, ObjectKind::Channel)
+          | (ParameterKind::Tuple, ObjectKind::Tuple)
       ),
       _ => false,
     }
@@ -173,12 +199,14 @@ impl From<Value> for ParameterKind {
         ObjectKind::Enumerator => ParameterKind::Enumerator,
         ObjectKind::Fun => ParameterKind::Fun,
         ObjectKind::Fiber => ParameterKind::Fiber,
+        ObjectKind::Channel => ParameterKind::Channel,
         ObjectKind::Instance => ParameterKind::Instance,
         ObjectKind::List => ParameterKind::List,
         ObjectKind::Map => ParameterKind::Map,
         ObjectKind::Method => ParameterKind::Fun,
         ObjectKind::Native => ParameterKind::Fun,
         ObjectKind::String => ParameterKind::String,
+        ObjectKind::Tuple => ParameterKind::Tuple,
         ObjectKind::Upvalue => panic!("Should not pass in upvalue directly"),
       },
     }
@@ -194,8 +222,10 @@ impl Display for ParameterKind {
       ParameterKind::Number => write!(f, "number"),
       ParameterKind::String => write!(f, "string"),
       ParameterKind::Fiber => write!(f, "fiber"),
+      ParameterKind::Channel => write!(f, "channel"),
       ParameterKind::List => write!(f, "list"),
       ParameterKind::Map => write!(f, "map"),
+      ParameterKind::Tuple => write!(f, "tuple"),
       ParameterKind::Class => write!(f, "class"),
       ParameterKind::Instance => write!(f, "instance"),
       ParameterKind::Enumerator => write!(f, "iterator"),
@@ -346,6 +376,13 @@ impl Trace for Signature {
       p.trace();
     });
   }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.parameters.iter().for_each(|p| {
+      p.visit_refs(visitor);
+    });
+  }
+
   fn trace_debug(&self, stdio: &mut dyn Write) {
     self.parameters.iter().for_each(|p| {
       p.trace_debug(stdio);
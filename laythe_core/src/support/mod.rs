@@ -4,7 +4,7 @@ use fnv::FnvBuildHasher;
 use hashbrown::HashMap;
 
 use crate::{
-  chunk::Encode,
+  chunk::{Encode, SourceSpan},
   hooks::GcHooks,
   managed::{GcObj, GcStr},
   module::Module,
@@ -65,7 +65,7 @@ where
     let mut fun = test_fun_builder(&hooks, &self.name, &self.module_name);
 
     for instruction in self.instructions {
-      fun.write_instruction(instruction, 0);
+      fun.write_instruction(instruction, SourceSpan::default());
     }
     fun.update_max_slots(self.max_slots);
 
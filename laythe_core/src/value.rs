@@ -75,8 +75,8 @@ mod unboxed {
   use crate::{
     managed::{DebugHeap, DebugWrap, GcObj, GcObject, GcStr, Trace},
     object::{
-      Class, Closure, Enumerator, Fiber, Fun, Instance, List, Map, Method, Native, ObjectKind,
-      Upvalue,
+      Channel, Class, Closure, Enumerator, Fiber, Fun, Instance, List, Map, Method, Native,
+      ObjectKind, Tuple, Upvalue,
     },
   };
 
@@ -227,6 +227,7 @@ mod unboxed {
           ObjectKind::String => "string",
           ObjectKind::List => "list",
           ObjectKind::Fiber => "fiber",
+          ObjectKind::Channel => "channel",
           ObjectKind::Map => "map",
           ObjectKind::Fun => "function",
           ObjectKind::Closure => "closure",
@@ -235,6 +236,7 @@ mod unboxed {
           ObjectKind::Enumerator => "enumerator",
           ObjectKind::Method => "method",
           ObjectKind::Native => "native",
+          ObjectKind::Tuple => "tuple",
           ObjectKind::Upvalue => "upvalue",
         },
       }
@@ -280,6 +282,12 @@ mod unboxed {
     }
   }
 
+  impl From<GcObj<Channel>> for Value {
+    fn from(managed: GcObj<Channel>) -> Value {
+      Value::Obj(managed.degrade())
+    }
+  }
+
   impl From<GcObj<List<Value>>> for Value {
     fn from(managed: GcObj<List<Value>>) -> Value {
       Value::Obj(managed.degrade())
@@ -292,6 +300,12 @@ mod unboxed {
     }
   }
 
+  impl From<GcObj<Tuple>> for Value {
+    fn from(managed: GcObj<Tuple>) -> Value {
+      Value::Obj(managed.degrade())
+    }
+  }
+
   impl From<GcObj<Enumerator>> for Value {
     fn from(managed: GcObj<Enumerator>) -> Value {
       Value::Obj(managed.degrade())
@@ -371,7 +385,7 @@ mod unboxed {
         (Self::Number(num1), Self::Number(num2)) => num1 == num2,
         (Self::Bool(b1), Self::Bool(b2)) => b1 == b2,
         (Self::Nil, Self::Nil) => true,
-        (Self::Obj(obj1), Self::Obj(obj2)) => obj1 == obj2,
+        (Self::Obj(obj1), Self::Obj(obj2)) => obj1 == obj2 || tuple_eq(*obj1, *obj2),
         _ => false,
       }
     }
@@ -394,12 +408,24 @@ mod unboxed {
         Self::Nil => ValueKind::Nil.hash(state),
         Self::Obj(obj) => {
           ValueKind::Obj.hash(state);
-          obj.hash(state);
+
+          if obj.is_kind(ObjectKind::Tuple) {
+            obj.to_tuple().iter().for_each(|item| item.hash(state));
+          } else {
+            obj.hash(state);
+          }
         }
       };
     }
   }
 
+  /// Tuples are compared by their items rather than their identity
+  fn tuple_eq(obj1: GcObject, obj2: GcObject) -> bool {
+    obj1.is_kind(ObjectKind::Tuple)
+      && obj2.is_kind(ObjectKind::Tuple)
+      && obj1.to_tuple().iter().eq(obj2.to_tuple().iter())
+  }
+
   impl Trace for Value {
     fn trace(&self) {
       if let Value::Obj(obj) = self {
@@ -407,6 +433,12 @@ mod unboxed {
       }
     }
 
+    fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+      if let Value::Obj(obj) = self {
+        obj.visit_refs(visitor);
+      }
+    }
+
     fn trace_debug(&self, stdout: &mut dyn Write) {
       if let Value::Obj(obj) = self {
         obj.trace_debug(stdout);
@@ -437,7 +469,7 @@ mod unboxed {
       assert_eq!(mem::size_of::<Closure>(), 24);
       assert_eq!(mem::size_of::<Fun>(), 104);
       assert_eq!(mem::size_of::<Class>(), 136);
-      assert_eq!(mem::size_of::<Instance>(), 24);
+      assert_eq!(mem::size_of::<Instance>(), 32);
       assert_eq!(mem::size_of::<Method>(), 32);
       assert_eq!(mem::size_of::<Enumerator>(), 32);
       assert_eq!(mem::size_of::<Native>(), 56);
@@ -468,8 +500,8 @@ mod boxed {
   use crate::{
     managed::{DebugHeap, GcObj, GcObject, GcStr, Trace},
     object::{
-      Class, Closure, Enumerator, Fiber, Fun, Instance, List, Map, Method, Native, ObjectKind,
-      Upvalue,
+      Channel, Class, Closure, Enumerator, Fiber, Fun, Instance, List, Map, Method, Native,
+      ObjectKind, Tuple, Upvalue,
     },
   };
 
@@ -509,9 +541,32 @@ mod boxed {
   // 0111 1111 1111 1100 0000 0000 0000 0000 0000 0000 0000 0000 0000 0000 0000 0011
   pub const VALUE_FALSE: Value = Value(TAG_FALSE);
 
-  #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+  #[derive(Copy, Clone, Debug)]
   pub struct Value(u64);
 
+  impl PartialEq for Value {
+    #[inline]
+    fn eq(&self, other: &Value) -> bool {
+      self.0 == other.0 || self.tuple_eq(*other)
+    }
+  }
+
+  impl Eq for Value {}
+
+  impl std::hash::Hash for Value {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+      if self.is_obj_kind(ObjectKind::Tuple) {
+        self
+          .to_obj()
+          .to_tuple()
+          .iter()
+          .for_each(|item| item.hash(state));
+      } else {
+        self.0.hash(state);
+      }
+    }
+  }
+
   impl Value {
     #[inline]
     pub fn is_nil(&self) -> bool {
@@ -546,6 +601,17 @@ mod boxed {
       self.to_obj().is_kind(kind)
     }
 
+    /// Tuples are compared by their items rather than their identity
+    fn tuple_eq(&self, other: Value) -> bool {
+      self.is_obj_kind(ObjectKind::Tuple)
+        && other.is_obj_kind(ObjectKind::Tuple)
+        && self
+          .to_obj()
+          .to_tuple()
+          .iter()
+          .eq(other.to_obj().to_tuple().iter())
+    }
+
     #[inline]
     pub fn to_bool(self) -> bool {
       self == VALUE_TRUE
@@ -607,12 +673,14 @@ mod boxed {
           ObjectKind::Map => "map",
           ObjectKind::Fun => "function",
           ObjectKind::Fiber => "fiber",
+          ObjectKind::Channel => "channel",
           ObjectKind::Closure => "closure",
           ObjectKind::Class => "class",
           ObjectKind::Instance => "instance",
           ObjectKind::Enumerator => "enumerator",
           ObjectKind::Method => "method",
           ObjectKind::Native => "native",
+          ObjectKind::Tuple => "tuple",
           ObjectKind::Upvalue => "upvalue",
         },
       }
@@ -625,6 +693,13 @@ mod boxed {
         self.to_obj().trace();
       }
     }
+
+    fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+      if self.is_obj() {
+        self.to_obj().visit_refs(visitor);
+      }
+    }
+
     fn trace_debug(&self, log: &mut dyn Write) {
       if self.is_obj() {
         self.to_obj().trace_debug(log);
@@ -677,6 +752,12 @@ mod boxed {
       Self(managed.to_usize() as u64 | TAG_OBJ)
     }
   }
+
+  impl From<GcObj<Channel>> for Value {
+    fn from(managed: GcObj<Channel>) -> Value {
+      Self(managed.to_usize() as u64 | TAG_OBJ)
+    }
+  }
   impl From<GcObj<List<Value>>> for Value {
     fn from(managed: GcObj<List<Value>>) -> Value {
       Self(managed.to_usize() as u64 | TAG_OBJ)
@@ -689,6 +770,12 @@ mod boxed {
     }
   }
 
+  impl From<GcObj<Tuple>> for Value {
+    fn from(managed: GcObj<Tuple>) -> Value {
+      Self(managed.to_usize() as u64 | TAG_OBJ)
+    }
+  }
+
   impl From<GcObj<Enumerator>> for Value {
     fn from(managed: GcObj<Enumerator>) -> Value {
       Self(managed.to_usize() as u64 | TAG_OBJ)
@@ -759,10 +846,11 @@ mod boxed {
       assert_eq!(mem::size_of::<List<Value>>(), 24);
       assert_eq!(mem::size_of::<Map<Value, Value>>(), 32);
       assert_eq!(mem::size_of::<Closure>(), 24);
-      assert_eq!(mem::size_of::<Fun>(), 96);
-      assert_eq!(mem::size_of::<Fiber>(), 104);
-      assert_eq!(mem::size_of::<Class>(), 104);
-      assert_eq!(mem::size_of::<Instance>(), 24);
+      assert_eq!(mem::size_of::<Fun>(), 104);
+      assert_eq!(mem::size_of::<Fiber>(), 168);
+      assert_eq!(mem::size_of::<Channel>(), 112);
+      assert_eq!(mem::size_of::<Class>(), 136);
+      assert_eq!(mem::size_of::<Instance>(), 32);
       assert_eq!(mem::size_of::<Method>(), 16);
       assert_eq!(mem::size_of::<Enumerator>(), 24);
       assert_eq!(mem::size_of::<Native>(), 56);
@@ -0,0 +1,483 @@
+use crate::{
+  hooks::GcHooks,
+  object::{List, Map, ObjectKind, Tuple},
+  val,
+  value::{Value, VALUE_NIL},
+};
+use std::{
+  fmt,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver, Sender, TryRecvError},
+    Arc,
+  },
+  thread::JoinHandle,
+};
+
+/// How deeply lists, tuples and maps may nest in a message. Guards
+/// against values that contain themselves
+const MAX_DEPTH: usize = 128;
+
+/// A value copied out of one vm's heap so it can be sent to a vm on
+/// another thread. Only nil, bools, numbers, strings and the lists,
+/// tuples and maps built from them can be sent
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+  Nil,
+  Bool(bool),
+  Number(f64),
+  String(String),
+  List(Vec<Message>),
+  Tuple(Vec<Message>),
+  Map(Vec<(Message, Message)>),
+}
+
+impl Message {
+  /// Copy a value into a message, failing if the value or anything
+  /// it holds cannot be sent
+  pub fn from_value(value: Value) -> Result<Message, String> {
+    Self::nested(value, 0)
+  }
+
+  fn nested(value: Value, depth: usize) -> Result<Message, String> {
+    if value.is_nil() {
+      return Ok(Message::Nil);
+    }
+
+    if value.is_bool() {
+      return Ok(Message::Bool(value.to_bool()));
+    }
+
+    if value.is_num() {
+      return Ok(Message::Number(value.to_num()));
+    }
+
+    if depth >= MAX_DEPTH {
+      return Err("Value nests too deeply to send, it may contain itself.".to_string());
+    }
+
+    let obj = value.to_obj();
+    match obj.kind() {
+      ObjectKind::String => Ok(Message::String(String::from(&*obj.to_str()))),
+      ObjectKind::List => obj
+        .to_list()
+        .iter()
+        .map(|item| Self::nested(*item, depth + 1))
+        .collect::<Result<Vec<Message>, String>>()
+        .map(Message::List),
+      ObjectKind::Tuple => obj
+        .to_tuple()
+        .iter()
+        .map(|item| Self::nested(*item, depth + 1))
+        .collect::<Result<Vec<Message>, String>>()
+        .map(Message::Tuple),
+      ObjectKind::Map => obj
+        .to_map()
+        .iter()
+        .map(|(key, value)| {
+          Ok((
+            Self::nested(*key, depth + 1)?,
+            Self::nested(*value, depth + 1)?,
+          ))
+        })
+        .collect::<Result<Vec<(Message, Message)>, String>>()
+        .map(Message::Map),
+      _ => Err(format!("Unable to send a {}.", value.value_type())),
+    }
+  }
+
+  /// Allocate this message as a value through the provided hooks
+  pub fn to_value(&self, hooks: &GcHooks) -> Value {
+    match self {
+      Message::Nil => VALUE_NIL,
+      Message::Bool(value) => val!(*value),
+      Message::Number(value) => val!(*value),
+      Message::String(value) => val!(hooks.manage_str(value)),
+      Message::List(items) => {
+        let mut list = hooks.manage_obj(List::with_capacity(items.len()));
+        hooks.push_root(list);
+
+        for item in items {
+          let item = item.to_value(hooks);
+          hooks.grow(&mut *list, |list| list.push(item));
+        }
+
+        hooks.pop_roots(1);
+        val!(list)
+      },
+      Message::Tuple(items) => {
+        let items: Vec<Value> = items
+          .iter()
+          .map(|item| {
+            let item = item.to_value(hooks);
+            hooks.push_root(item);
+            item
+          })
+          .collect();
+
+        let len = items.len();
+        let tuple = val!(hooks.manage_obj(Tuple::from(items)));
+        hooks.pop_roots(len);
+        tuple
+      },
+      Message::Map(entries) => {
+        let mut map = hooks.manage_obj(Map::with_capacity(entries.len()));
+        hooks.push_root(map);
+
+        for (key, value) in entries {
+          let key = key.to_value(hooks);
+          hooks.push_root(key);
+          let value = value.to_value(hooks);
+          hooks.pop_roots(1);
+
+          hooks.grow(&mut *map, |map| map.insert(key, value));
+        }
+
+        hooks.pop_roots(1);
+        val!(map)
+      },
+    }
+  }
+}
+
+/// The outcome of checking a port for a message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Received {
+  /// The next message sent to this port
+  Message(Message),
+
+  /// No message is waiting but more may still be sent
+  Empty,
+
+  /// No message is waiting and the other end has hung up
+  Closed,
+}
+
+/// One end of the pair of channels between a vm and a worker it spawned
+#[derive(Debug)]
+pub struct WorkerPort {
+  sender: Option<Sender<Message>>,
+  receiver: Receiver<Message>,
+}
+
+impl WorkerPort {
+  /// Create the two connected ends of a port
+  pub fn pair() -> (WorkerPort, WorkerPort) {
+    let (sender_a, receiver_a) = mpsc::channel();
+    let (sender_b, receiver_b) = mpsc::channel();
+
+    (
+      WorkerPort {
+        sender: Some(sender_a),
+        receiver: receiver_b,
+      },
+      WorkerPort {
+        sender: Some(sender_b),
+        receiver: receiver_a,
+      },
+    )
+  }
+
+  /// Send a message to the other end. Returns false if this end
+  /// was closed or the other end has hung up
+  pub fn send(&self, message: Message) -> bool {
+    match &self.sender {
+      Some(sender) => sender.send(message).is_ok(),
+      None => false,
+    }
+  }
+
+  /// Check for a message from the other end without blocking
+  pub fn try_receive(&self) -> Received {
+    match self.receiver.try_recv() {
+      Ok(message) => Received::Message(message),
+      Err(TryRecvError::Empty) => Received::Empty,
+      Err(TryRecvError::Disconnected) => Received::Closed,
+    }
+  }
+
+  /// Stop sending from this end. The other end sees the port
+  /// closed once it has received every message already sent
+  pub fn close(&mut self) {
+    self.sender = None;
+  }
+}
+
+/// A worker vm running on its own thread, as seen by the vm that spawned it
+pub struct WorkerHandle {
+  port: WorkerPort,
+  thread: Option<JoinHandle<Result<u16, String>>>,
+  result: Option<Result<u16, String>>,
+  terminate: Box<dyn Fn() + Send>,
+}
+
+impl WorkerHandle {
+  /// Create a handle from the port to the worker, its thread and how it
+  /// is stopped. The thread returns the worker's exit code or why it failed
+  pub fn new(
+    port: WorkerPort,
+    thread: JoinHandle<Result<u16, String>>,
+    terminate: Box<dyn Fn() + Send>,
+  ) -> Self {
+    Self {
+      port,
+      thread: Some(thread),
+      result: None,
+      terminate,
+    }
+  }
+
+  /// The port to the worker
+  pub fn port(&self) -> &WorkerPort {
+    &self.port
+  }
+
+  /// Stop sending to the worker, see `WorkerPort::close`
+  pub fn close(&mut self) {
+    self.port.close();
+  }
+
+  /// Close the port to the worker and interrupt it
+  pub fn terminate(&mut self) {
+    self.port.close();
+    (self.terminate)();
+  }
+
+  /// The result of the worker's thread if it has finished
+  pub fn try_join(&mut self) -> Option<Result<u16, String>> {
+    if let Some(thread) = self.thread.take() {
+      if !thread.is_finished() {
+        self.thread = Some(thread);
+        return None;
+      }
+
+      self.result = Some(match thread.join() {
+        Ok(result) => result,
+        Err(_) => Err("Worker thread panicked.".to_string()),
+      });
+    }
+
+    self.result.clone()
+  }
+}
+
+impl fmt::Debug for WorkerHandle {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("WorkerHandle")
+      .field("port", &self.port)
+      .field("result", &self.result)
+      .finish()
+  }
+}
+
+/// The results of one chunk of a pool job tagged with the chunk's index
+pub type ChunkResult = (usize, Result<Vec<Message>, String>);
+
+/// Work split into chunks across a pool of worker vms, as seen by the vm
+/// waiting on it. Results are put back in the order of their chunks
+/// however the pool happens to finish them
+pub struct PoolJob {
+  results: Receiver<ChunkResult>,
+  chunks: Vec<Option<Result<Vec<Message>, String>>>,
+  cancelled: Arc<AtomicBool>,
+}
+
+impl PoolJob {
+  /// Create a job of the provided number of chunks whose results arrive
+  /// on `results`. `cancelled` is set once the job no longer needs results
+  pub fn new(chunks: usize, results: Receiver<ChunkResult>, cancelled: Arc<AtomicBool>) -> Self {
+    Self {
+      results,
+      chunks: vec![None; chunks],
+      cancelled,
+    }
+  }
+
+  /// Collect any chunks that have finished. Once every chunk has the job
+  /// returns the results in order, or the error of the first chunk that
+  /// failed as soon as each chunk before it has finished
+  pub fn try_finish(&mut self) -> Option<Result<Vec<Message>, String>> {
+    let mut stopped = false;
+    loop {
+      match self.results.try_recv() {
+        Ok((index, result)) => {
+          if let Some(chunk) = self.chunks.get_mut(index) {
+            *chunk = Some(result);
+          }
+        },
+        Err(TryRecvError::Empty) => break,
+        Err(TryRecvError::Disconnected) => {
+          stopped = true;
+          break;
+        },
+      }
+    }
+
+    for chunk in &self.chunks {
+      match chunk {
+        Some(Ok(_)) => (),
+        Some(Err(err)) => {
+          self.cancel();
+          return Some(Err(err.clone()));
+        },
+        None if stopped => {
+          self.cancel();
+          return Some(Err(
+            "Worker pool stopped before the work finished.".to_string(),
+          ));
+        },
+        None => return None,
+      }
+    }
+
+    Some(Ok(
+      self
+        .chunks
+        .iter_mut()
+        .filter_map(Option::take)
+        .flat_map(|chunk| chunk.unwrap_or_default())
+        .collect(),
+    ))
+  }
+
+  /// Stop the pool starting chunks of this job
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::Relaxed);
+  }
+}
+
+impl Drop for PoolJob {
+  fn drop(&mut self) {
+    self.cancel();
+  }
+}
+
+impl fmt::Debug for PoolJob {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("PoolJob")
+      .field("chunks", &self.chunks.len())
+      .field("cancelled", &self.cancelled)
+      .finish()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{hooks::NoContext, object::Class};
+
+  #[test]
+  fn round_trip() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let message = Message::Map(vec![
+      (
+        Message::String("tags".to_string()),
+        Message::List(vec![Message::Bool(true), Message::Nil]),
+      ),
+      (
+        Message::Number(1.5),
+        Message::Tuple(vec![Message::String("laythe".to_string())]),
+      ),
+    ]);
+
+    let value = message.to_value(&hooks);
+    let map = value.to_obj().to_map();
+    assert_eq!(map.len(), 2);
+
+    let tags = map.get(&val!(hooks.manage_str("tags"))).unwrap();
+    assert_eq!(tags.to_obj().to_list().len(), 2);
+
+    let mut copied = Message::from_value(value).expect("Unable to copy");
+    if let Message::Map(entries) = &mut copied {
+      entries.sort_by_key(|(key, _)| matches!(key, Message::Number(_)));
+    }
+    assert_eq!(copied, message);
+  }
+
+  #[test]
+  fn unsupported() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let mut list = hooks.manage_obj(List::new());
+    let value = val!(list);
+    list.push(value);
+
+    let error = Message::from_value(val!(list)).unwrap_err();
+    assert!(error.contains("nests too deeply"));
+
+    let class = hooks.manage_obj(Class::bare(hooks.manage_str("Foo")));
+    let error = Message::from_value(val!(class)).unwrap_err();
+    assert_eq!(error, "Unable to send a class.");
+  }
+
+  #[test]
+  fn port() {
+    let (mut parent, child) = WorkerPort::pair();
+    assert_eq!(child.try_receive(), Received::Empty);
+
+    assert!(parent.send(Message::Number(1.0)));
+    parent.close();
+    assert!(!parent.send(Message::Number(2.0)));
+
+    assert_eq!(child.try_receive(), Received::Message(Message::Number(1.0)));
+    assert_eq!(child.try_receive(), Received::Closed);
+
+    assert!(child.send(Message::Nil));
+    assert_eq!(parent.try_receive(), Received::Message(Message::Nil));
+    drop(child);
+    assert_eq!(parent.try_receive(), Received::Closed);
+  }
+
+  #[test]
+  fn pool_job() {
+    let (sender, results) = mpsc::channel();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let mut job = PoolJob::new(2, results, Arc::clone(&cancelled));
+    assert_eq!(job.try_finish(), None);
+
+    sender
+      .send((1, Ok(vec![Message::Number(3.0)])))
+      .expect("Unable to send");
+    assert_eq!(job.try_finish(), None);
+
+    sender
+      .send((0, Ok(vec![Message::Number(1.0), Message::Number(2.0)])))
+      .expect("Unable to send");
+    assert_eq!(
+      job.try_finish(),
+      Some(Ok(vec![
+        Message::Number(1.0),
+        Message::Number(2.0),
+        Message::Number(3.0)
+      ]))
+    );
+    assert!(!cancelled.load(Ordering::Relaxed));
+
+    let (sender, results) = mpsc::channel();
+    let mut job = PoolJob::new(3, results, Arc::clone(&cancelled));
+    sender
+      .send((2, Err("late".to_string())))
+      .expect("Unable to send");
+    sender
+      .send((1, Err("early".to_string())))
+      .expect("Unable to send");
+    assert_eq!(job.try_finish(), None);
+
+    sender.send((0, Ok(vec![]))).expect("Unable to send");
+    assert_eq!(job.try_finish(), Some(Err("early".to_string())));
+    assert!(cancelled.load(Ordering::Relaxed));
+
+    let (sender, results) = mpsc::channel();
+    let mut job = PoolJob::new(1, results, Arc::new(AtomicBool::new(false)));
+    drop(sender);
+    assert_eq!(
+      job.try_finish(),
+      Some(Err(
+        "Worker pool stopped before the work finished.".to_string()
+      ))
+    );
+  }
+}
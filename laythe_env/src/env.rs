@@ -1,15 +1,22 @@
 use crate::io::IoImpl;
-use std::{io, path::PathBuf};
+use hashbrown::HashMap;
+use std::{
+  io,
+  path::PathBuf,
+  sync::{Arc, Mutex},
+};
 
 /// A wrapper around environmental facilities provided to Laythe
 pub struct Env {
   env: Box<dyn EnvImpl>,
+  args: Option<Arc<Vec<String>>>,
 }
 
 impl Default for Env {
   fn default() -> Self {
     Self {
-      env: Box::new(EnvMock()),
+      env: Box::new(EnvMock::default()),
+      args: None,
     }
   }
 }
@@ -17,7 +24,16 @@ impl Default for Env {
 impl Env {
   /// Create a new wrapper around the provided environmental facilities
   pub fn new(env: Box<dyn EnvImpl>) -> Self {
-    Self { env }
+    Self { env, args: None }
+  }
+
+  /// Report the provided arguments in place of the arguments
+  /// of the environment
+  pub fn with_args(self, args: Arc<Vec<String>>) -> Self {
+    Self {
+      args: Some(args),
+      ..self
+    }
   }
 
   /// Get the current working directory
@@ -27,25 +43,71 @@ impl Env {
 
   /// Get the arguments pass to this script
   pub fn args(&self) -> Vec<String> {
-    self.env.args()
+    match &self.args {
+      Some(args) => args.to_vec(),
+      None => self.env.args(),
+    }
+  }
+
+  /// Get the value of an environment variable if it is set
+  pub fn var(&self, name: &str) -> Option<String> {
+    self.env.var(name)
+  }
+
+  /// Set an environment variable for this process. Names must be non
+  /// empty and can't contain `=` and neither can contain a nul character
+  pub fn set_var(&self, name: &str, value: &str) -> io::Result<()> {
+    if name.is_empty() || name.contains('=') || name.contains('\0') {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Invalid environment variable name {}.", name),
+      ));
+    }
+
+    if value.contains('\0') {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "Environment variable values can't contain a nul character.",
+      ));
+    }
+
+    self.env.set_var(name, value)
+  }
+
+  /// Get the name of the platform this script is running on
+  pub fn platform(&self) -> &str {
+    self.env.platform()
   }
 }
 
 pub trait EnvImpl: Send + Sync {
   fn current_dir(&self) -> io::Result<PathBuf>;
   fn args(&self) -> Vec<String>;
+  fn var(&self, name: &str) -> Option<String>;
+  fn set_var(&self, name: &str, value: &str) -> io::Result<()>;
+  fn platform(&self) -> &str;
 }
 
-#[derive(Debug)]
-pub struct IoEnvMock();
+/// Environment variables shared by each `Env` an `Io` makes
+pub type EnvVars = Arc<Mutex<HashMap<String, String>>>;
+
+#[derive(Debug, Default)]
+pub struct IoEnvMock {
+  vars: EnvVars,
+}
 
 impl IoImpl<Env> for IoEnvMock {
   fn make(&self) -> Env {
-    Env::new(Box::new(EnvMock()))
+    Env::new(Box::new(EnvMock {
+      vars: Arc::clone(&self.vars),
+    }))
   }
 }
 
-pub struct EnvMock();
+#[derive(Default)]
+pub struct EnvMock {
+  vars: EnvVars,
+}
 
 impl EnvImpl for EnvMock {
   fn current_dir(&self) -> io::Result<PathBuf> {
@@ -55,4 +117,21 @@ impl EnvImpl for EnvMock {
   fn args(&self) -> Vec<String> {
     vec![]
   }
+
+  fn var(&self, name: &str) -> Option<String> {
+    self.vars.lock().unwrap().get(name).cloned()
+  }
+
+  fn set_var(&self, name: &str, value: &str) -> io::Result<()> {
+    self
+      .vars
+      .lock()
+      .unwrap()
+      .insert(name.to_string(), value.to_string());
+    Ok(())
+  }
+
+  fn platform(&self) -> &str {
+    "mock"
+  }
 }
@@ -1,26 +1,113 @@
-use crate::io::IoImpl;
+use crate::{io::IoImpl, stdio::read_utf8_char};
 use std::{
-  io,
+  collections::HashMap,
+  fmt,
+  io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write},
   path::{Path, PathBuf},
+  sync::{Arc, Mutex},
 };
 
 /// A wrapper around file system facilities provided to Laythe
 pub struct Fs {
   fs: Box<dyn FsImpl>,
+  files: Arc<Mutex<FileTable>>,
 }
 
 impl Default for Fs {
   fn default() -> Self {
-    Self {
-      fs: Box::new(FsMock()),
-    }
+    Self::new(Box::new(FsMock()))
   }
 }
 
 impl Fs {
   /// Create a new file system wrapper
   pub fn new(fs: Box<dyn FsImpl>) -> Self {
-    Self { fs }
+    Self {
+      fs,
+      files: Arc::default(),
+    }
+  }
+
+  /// Use the provided table for files opened through this wrapper
+  pub fn with_files(self, files: Arc<Mutex<FileTable>>) -> Self {
+    Self { files, ..self }
+  }
+
+  /// Open a file for streaming, returning the handle later
+  /// operations on it use
+  pub fn open(&self, path: &Path, mode: OpenMode) -> io::Result<FileHandle> {
+    let stream = self.fs.open(path, mode)?;
+    Ok(self.table().insert(stream))
+  }
+
+  /// Read up to count characters from an open file
+  pub fn read_chars(&self, handle: FileHandle, count: usize) -> io::Result<String> {
+    let mut table = self.table();
+    let file = table.get(handle)?;
+
+    let mut result = String::new();
+    for _ in 0..count {
+      match read_utf8_char(file)? {
+        Some(c) => result.push(c),
+        None => break,
+      }
+    }
+
+    Ok(result)
+  }
+
+  /// Read the remainder of an open file
+  pub fn read_rest(&self, handle: FileHandle) -> io::Result<String> {
+    let mut result = String::new();
+    self.table().get(handle)?.read_to_string(&mut result)?;
+    Ok(result)
+  }
+
+  /// Read the next line of an open file without its line ending,
+  /// returning None once the end of the file is reached
+  pub fn read_file_line(&self, handle: FileHandle) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if self.table().get(handle)?.read_line(&mut line)? == 0 {
+      return Ok(None);
+    }
+
+    if line.ends_with('\n') {
+      line.pop();
+      if line.ends_with('\r') {
+        line.pop();
+      }
+    }
+
+    Ok(Some(line))
+  }
+
+  /// Write a byte buffer to an open file at its current position
+  pub fn write_file(&self, handle: FileHandle, contents: &[u8]) -> io::Result<()> {
+    let mut table = self.table();
+    let file = table.get(handle)?;
+
+    // seeking drops anything buffered by earlier reads so the write
+    // lands at the position the script sees
+    let position = file.stream_position()?;
+    file.seek(SeekFrom::Start(position))?;
+    file.get_mut().write_all(contents)
+  }
+
+  /// Move the position of an open file, returning the new position
+  pub fn seek(&self, handle: FileHandle, position: SeekFrom) -> io::Result<u64> {
+    self.table().get(handle)?.seek(position)
+  }
+
+  /// Flush and close an open file, invalidating its handle
+  pub fn close(&self, handle: FileHandle) -> io::Result<()> {
+    let mut table = self.table();
+    table.get(handle)?.get_mut().flush()?;
+    table.files.remove(&handle);
+    Ok(())
+  }
+
+  fn table(&self) -> std::sync::MutexGuard<'_, FileTable> {
+    self.files.lock().expect("File table was poisoned")
   }
 
   /// Read a file into String
@@ -28,8 +115,23 @@ impl Fs {
     self.fs.read_to_string(path)
   }
 
+  /// Read a file into a byte buffer
+  pub fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+    self.fs.read(path)
+  }
+
+  /// Write a byte buffer to a file, replacing any existing contents
+  pub fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+    self.fs.write(path, contents)
+  }
+
+  /// Create a directory and any missing parent directories
+  pub fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+    self.fs.create_dir_all(path)
+  }
+
   /// Read a directory for files and sub directories
-  pub fn read_directory(&self, path: &Path) -> io::Result<SlDirEntry> {
+  pub fn read_directory(&self, path: &Path) -> io::Result<Vec<SlDirEntry>> {
     self.fs.read_directory(path)
   }
 
@@ -44,13 +146,95 @@ impl Fs {
   }
 }
 
-pub struct SlDirEntry();
+/// A file or sub directory found while reading a directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlDirEntry {
+  /// The path to this entry, the directory read joined with its name
+  pub path: PathBuf,
+
+  /// Is this entry a directory
+  pub is_dir: bool,
+}
+
+/// How a file is opened for streaming
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenMode {
+  /// Read an existing file
+  Read,
+
+  /// Create or truncate a file for writing
+  Write,
+
+  /// Create a file or write to the end of an existing one
+  Append,
+
+  /// Read and write an existing file
+  ReadWrite,
+}
+
+impl OpenMode {
+  /// Parse a mode from the strings scripts use, `r`, `w`, `a` and `r+`
+  pub fn parse(mode: &str) -> Option<Self> {
+    match mode {
+      "r" => Some(Self::Read),
+      "w" => Some(Self::Write),
+      "a" => Some(Self::Append),
+      "r+" => Some(Self::ReadWrite),
+      _ => None,
+    }
+  }
+}
+
+/// A file opened for streaming by an fs implementation
+pub trait FileStream: Read + Write + Seek + Send {}
+
+impl<T: Read + Write + Seek + Send> FileStream for T {}
+
+/// Identifies a file in a file table
+pub type FileHandle = usize;
+
+/// The files currently open, shared by every fs wrapper an io makes
+#[derive(Default)]
+pub struct FileTable {
+  files: HashMap<FileHandle, BufReader<Box<dyn FileStream>>>,
+  next: FileHandle,
+}
+
+impl fmt::Debug for FileTable {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("FileTable")
+      .field("open", &self.files.len())
+      .field("next", &self.next)
+      .finish()
+  }
+}
+
+impl FileTable {
+  fn insert(&mut self, stream: Box<dyn FileStream>) -> FileHandle {
+    let handle = self.next;
+    self.next += 1;
+
+    self.files.insert(handle, BufReader::new(stream));
+    handle
+  }
+
+  fn get(&mut self, handle: FileHandle) -> io::Result<&mut BufReader<Box<dyn FileStream>>> {
+    self
+      .files
+      .get_mut(&handle)
+      .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "File is closed."))
+  }
+}
 
 pub trait FsImpl: Send + Sync {
   fn read_to_string(&self, path: &Path) -> io::Result<String>;
-  fn read_directory(&self, path: &Path) -> io::Result<SlDirEntry>;
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+  fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+  fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+  fn read_directory(&self, path: &Path) -> io::Result<Vec<SlDirEntry>>;
   fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
   fn relative_path(&self, base: &Path, import: &Path) -> io::Result<PathBuf>;
+  fn open(&self, path: &Path, mode: OpenMode) -> io::Result<Box<dyn FileStream>>;
 }
 
 #[derive(Debug)]
@@ -68,8 +252,17 @@ impl FsImpl for FsMock {
   fn read_to_string(&self, _path: &Path) -> io::Result<String> {
     Ok("let x = 10;".to_string())
   }
-  fn read_directory(&self, _path: &Path) -> io::Result<SlDirEntry> {
-    Ok(SlDirEntry())
+  fn read(&self, _path: &Path) -> io::Result<Vec<u8>> {
+    Err(io::Error::new(io::ErrorKind::NotFound, "Mock file system has no files"))
+  }
+  fn write(&self, _path: &Path, _contents: &[u8]) -> io::Result<()> {
+    Ok(())
+  }
+  fn create_dir_all(&self, _path: &Path) -> io::Result<()> {
+    Ok(())
+  }
+  fn read_directory(&self, _path: &Path) -> io::Result<Vec<SlDirEntry>> {
+    Ok(vec![])
   }
   fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
     Ok(path.to_path_buf())
@@ -77,4 +270,10 @@ impl FsImpl for FsMock {
   fn relative_path(&self, _base: &Path, import: &Path) -> io::Result<PathBuf> {
     Ok(import.to_path_buf())
   }
+  fn open(&self, _path: &Path, _mode: OpenMode) -> io::Result<Box<dyn FileStream>> {
+    Err(io::Error::new(
+      io::ErrorKind::NotFound,
+      "Mock file system has no files",
+    ))
+  }
 }
@@ -1,10 +1,17 @@
 use crate::{
   env::{Env, IoEnvMock},
-  fs::{Fs, IoFsMock},
-  stdio::{IoStdioMock, Stdio},
+  fs::{FileTable, Fs, IoFsMock},
+  net::{IoNetMock, Net},
+  resolver::{ImportResolver, ResolvedModule},
+  signal::{IoSignalsMock, Signals},
+  stdio::{IoStdioMock, Stdio, StdioBuffer},
   time::{IoTimeMock, Time},
 };
-use std::{fmt, sync::Arc};
+use std::{
+  fmt, io,
+  path::Path,
+  sync::{Arc, Mutex},
+};
 
 #[derive(Debug)]
 /// A struct wrapping the externally provided io to Laythe
@@ -13,6 +20,20 @@ pub struct Io {
   fs_impl: Arc<dyn IoImpl<Fs>>,
   env_impl: Arc<dyn IoImpl<Env>>,
   time_impl: Arc<dyn IoImpl<Time>>,
+  net_impl: Arc<dyn IoImpl<Net>>,
+  signals_impl: Arc<dyn IoImpl<Signals>>,
+  resolver: Option<Arc<dyn ImportResolver>>,
+  args: Option<Arc<Vec<String>>>,
+  redirects: Arc<Redirects>,
+  files: Arc<Mutex<FileTable>>,
+}
+
+/// The buffers stdout and stderr are currently redirected into. Redirections
+/// nest with the most recent receiving output until it is restored
+#[derive(Debug, Default)]
+struct Redirects {
+  stdout: Mutex<Vec<StdioBuffer>>,
+  stderr: Mutex<Vec<StdioBuffer>>,
 }
 
 impl Default for Io {
@@ -20,8 +41,14 @@ impl Default for Io {
     Self {
       stdio_impl: Arc::new(IoStdioMock()),
       fs_impl: Arc::new(IoFsMock()),
-      env_impl: Arc::new(IoEnvMock()),
+      env_impl: Arc::new(IoEnvMock::default()),
       time_impl: Arc::new(IoTimeMock()),
+      net_impl: Arc::new(IoNetMock()),
+      signals_impl: Arc::new(IoSignalsMock::default()),
+      resolver: None,
+      args: None,
+      redirects: Arc::new(Redirects::default()),
+      files: Arc::default(),
     }
   }
 }
@@ -39,6 +66,12 @@ impl Io {
       fs_impl,
       env_impl,
       time_impl,
+      net_impl: Arc::new(IoNetMock()),
+      signals_impl: Arc::new(IoSignalsMock::default()),
+      resolver: None,
+      args: None,
+      redirects: Arc::new(Redirects::default()),
+      files: Arc::default(),
     }
   }
 
@@ -49,6 +82,12 @@ impl Io {
       fs_impl: self.fs_impl,
       env_impl: self.env_impl,
       time_impl: self.time_impl,
+      net_impl: self.net_impl,
+      signals_impl: self.signals_impl,
+      resolver: self.resolver,
+      args: self.args,
+      redirects: self.redirects,
+      files: self.files,
     }
   }
 
@@ -59,6 +98,12 @@ impl Io {
       fs_impl,
       env_impl: self.env_impl,
       time_impl: self.time_impl,
+      net_impl: self.net_impl,
+      signals_impl: self.signals_impl,
+      resolver: self.resolver,
+      args: self.args,
+      redirects: self.redirects,
+      files: self.files,
     }
   }
 
@@ -69,6 +114,12 @@ impl Io {
       fs_impl: self.fs_impl,
       env_impl,
       time_impl: self.time_impl,
+      net_impl: self.net_impl,
+      signals_impl: self.signals_impl,
+      resolver: self.resolver,
+      args: self.args,
+      redirects: self.redirects,
+      files: self.files,
     }
   }
 
@@ -79,41 +130,165 @@ impl Io {
       fs_impl: self.fs_impl,
       env_impl: self.env_impl,
       time_impl,
+      net_impl: self.net_impl,
+      signals_impl: self.signals_impl,
+      resolver: self.resolver,
+      args: self.args,
+      redirects: self.redirects,
+      files: self.files,
+    }
+  }
+
+  /// Replace this net implementation
+  pub fn with_net(self, net_impl: Arc<dyn IoImpl<Net>>) -> Self {
+    Self { net_impl, ..self }
+  }
+
+  /// Replace this signal implementation
+  pub fn with_signals(self, signals_impl: Arc<dyn IoImpl<Signals>>) -> Self {
+    Self {
+      signals_impl,
+      ..self
+    }
+  }
+
+  /// Replace how imported modules are loaded
+  pub fn with_resolver(self, resolver: Arc<dyn ImportResolver>) -> Self {
+    Self {
+      resolver: Some(resolver),
+      ..self
+    }
+  }
+
+  /// Report the provided arguments to scripts in place
+  /// of the arguments of the environment
+  pub fn with_args(self, args: Vec<String>) -> Self {
+    Self {
+      args: Some(Arc::new(args)),
+      ..self
+    }
+  }
+
+  /// Load the module at the provided path with the import resolver,
+  /// reading its source from the file system if none was provided
+  pub fn resolve_import(&self, path: &Path) -> io::Result<ResolvedModule> {
+    match &self.resolver {
+      Some(resolver) => resolver.resolve(path),
+      None => self.fs().read_to_string(path).map(ResolvedModule::Source),
     }
   }
 
   /// Generate a wrapper to stdio facilities
   pub fn stdio(&self) -> Stdio {
-    self.stdio_impl.make()
+    let stdout = self
+      .redirects
+      .stdout
+      .lock()
+      .expect("Redirects were poisoned");
+    let stderr = self
+      .redirects
+      .stderr
+      .lock()
+      .expect("Redirects were poisoned");
+
+    self
+      .stdio_impl
+      .make()
+      .redirect(stdout.last().cloned(), stderr.last().cloned())
+  }
+
+  /// Send stdout to the provided buffer until it is restored. This
+  /// applies to every clone of this io, so an embedder can capture
+  /// a running vm's output
+  pub fn redirect_stdout(&self, buffer: StdioBuffer) {
+    self
+      .redirects
+      .stdout
+      .lock()
+      .expect("Redirects were poisoned")
+      .push(buffer);
+  }
+
+  /// Restore stdout to where it was before the most recent redirection,
+  /// returning that redirection's buffer
+  pub fn restore_stdout(&self) -> Option<StdioBuffer> {
+    self
+      .redirects
+      .stdout
+      .lock()
+      .expect("Redirects were poisoned")
+      .pop()
+  }
+
+  /// Send stderr to the provided buffer until it is restored. This
+  /// applies to every clone of this io
+  pub fn redirect_stderr(&self, buffer: StdioBuffer) {
+    self
+      .redirects
+      .stderr
+      .lock()
+      .expect("Redirects were poisoned")
+      .push(buffer);
+  }
+
+  /// Restore stderr to where it was before the most recent redirection,
+  /// returning that redirection's buffer
+  pub fn restore_stderr(&self) -> Option<StdioBuffer> {
+    self
+      .redirects
+      .stderr
+      .lock()
+      .expect("Redirects were poisoned")
+      .pop()
   }
 
   /// Generate a wrapper to file system facilities
   pub fn fs(&self) -> Fs {
-    self.fs_impl.make()
+    self.fs_impl.make().with_files(Arc::clone(&self.files))
   }
 
   /// Generate a wrapper to environment facilities
   pub fn env(&self) -> Env {
-    self.env_impl.make()
+    let env = self.env_impl.make();
+    match &self.args {
+      Some(args) => env.with_args(Arc::clone(args)),
+      None => env,
+    }
   }
 
   /// Generate a wrapper to time facilities
   pub fn time(&self) -> Time {
     self.time_impl.make()
   }
+
+  /// Generate a wrapper to network facilities
+  pub fn net(&self) -> Net {
+    self.net_impl.make()
+  }
+
+  /// Generate a wrapper to process signal facilities
+  pub fn signals(&self) -> Signals {
+    self.signals_impl.make()
+  }
 }
 
 impl Clone for Io {
   fn clone(&self) -> Self {
-    Io::new(
-      Arc::clone(&self.stdio_impl),
-      Arc::clone(&self.fs_impl),
-      Arc::clone(&self.env_impl),
-      Arc::clone(&self.time_impl),
-    )
+    Self {
+      stdio_impl: Arc::clone(&self.stdio_impl),
+      fs_impl: Arc::clone(&self.fs_impl),
+      env_impl: Arc::clone(&self.env_impl),
+      time_impl: Arc::clone(&self.time_impl),
+      net_impl: Arc::clone(&self.net_impl),
+      signals_impl: Arc::clone(&self.signals_impl),
+      resolver: self.resolver.as_ref().map(Arc::clone),
+      args: self.args.as_ref().map(Arc::clone),
+      redirects: Arc::clone(&self.redirects),
+      files: Arc::clone(&self.files),
+    }
   }
 }
 
-pub trait IoImpl<T>: fmt::Debug {
+pub trait IoImpl<T>: fmt::Debug + Send + Sync {
   fn make(&self) -> T;
 }
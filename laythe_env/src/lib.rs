@@ -3,5 +3,10 @@
 pub mod env;
 pub mod fs;
 pub mod io;
+pub mod net;
+pub mod path;
+pub mod resolver;
+pub mod resource;
+pub mod signal;
 pub mod stdio;
 pub mod time;
@@ -0,0 +1,107 @@
+use crate::io::IoImpl;
+use std::{
+  fmt,
+  io::{self, Read, Write},
+};
+
+/// A wrapper around network facilities provided to Laythe
+pub struct Net {
+  net: Box<dyn NetImpl>,
+}
+
+impl Default for Net {
+  fn default() -> Self {
+    Self {
+      net: Box::new(NetMock()),
+    }
+  }
+}
+
+impl Net {
+  /// Create a new wrapper around the network
+  pub fn new(net: Box<dyn NetImpl>) -> Self {
+    Self { net }
+  }
+
+  /// Listen for tcp connections on an address such as `127.0.0.1:8080`.
+  /// Neither the listener nor the streams it accepts block, reporting
+  /// `WouldBlock` instead
+  pub fn listen(&self, address: &str) -> io::Result<Box<dyn TcpListen>> {
+    self.net.listen(address)
+  }
+
+  /// Resolve a host name to the ip addresses it refers to
+  pub fn resolve(&self, host: &str) -> io::Result<Vec<String>> {
+    self.net.resolve(host)
+  }
+
+  /// Connect to a tcp address such as `example.com:80`. Connecting
+  /// blocks but the returned stream does not, reporting `WouldBlock`
+  pub fn connect(&self, address: &str) -> io::Result<Box<dyn TcpStream>> {
+    self.net.connect(address)
+  }
+
+  /// Encrypt a connected stream with tls, verifying the server's
+  /// certificate against the host name. The handshake happens as the
+  /// returned stream is first read or written
+  pub fn tls(&self, stream: Box<dyn TcpStream>, host: &str) -> io::Result<Box<dyn TcpStream>> {
+    self.net.tls(stream, host)
+  }
+}
+
+/// A listening tcp socket
+pub trait TcpListen: fmt::Debug + Send {
+  /// Accept a pending connection, returning None if none are waiting
+  fn accept(&mut self) -> io::Result<Option<Box<dyn TcpStream>>>;
+
+  /// The address this listener is bound to
+  fn local_address(&self) -> io::Result<String>;
+}
+
+/// A connected tcp socket
+pub trait TcpStream: Read + Write + fmt::Debug + Send {
+  /// The address of the other end of this connection
+  fn peer_address(&self) -> io::Result<String>;
+}
+
+impl TcpStream for std::net::TcpStream {
+  fn peer_address(&self) -> io::Result<String> {
+    self.peer_addr().map(|address| address.to_string())
+  }
+}
+
+pub trait NetImpl: Send {
+  fn listen(&self, address: &str) -> io::Result<Box<dyn TcpListen>>;
+  fn resolve(&self, host: &str) -> io::Result<Vec<String>>;
+  fn connect(&self, address: &str) -> io::Result<Box<dyn TcpStream>>;
+  fn tls(&self, stream: Box<dyn TcpStream>, host: &str) -> io::Result<Box<dyn TcpStream>>;
+}
+
+#[derive(Debug)]
+pub struct IoNetMock();
+
+impl IoImpl<Net> for IoNetMock {
+  fn make(&self) -> Net {
+    Net::new(Box::new(NetMock()))
+  }
+}
+
+pub struct NetMock();
+
+impl NetImpl for NetMock {
+  fn listen(&self, _address: &str) -> io::Result<Box<dyn TcpListen>> {
+    Err(io::Error::other("Mock network has no sockets"))
+  }
+
+  fn resolve(&self, _host: &str) -> io::Result<Vec<String>> {
+    Err(io::Error::other("Mock network can't resolve hosts"))
+  }
+
+  fn connect(&self, _address: &str) -> io::Result<Box<dyn TcpStream>> {
+    Err(io::Error::other("Mock network has no sockets"))
+  }
+
+  fn tls(&self, _stream: Box<dyn TcpStream>, _host: &str) -> io::Result<Box<dyn TcpStream>> {
+    Err(io::Error::other("Mock network has no tls"))
+  }
+}
@@ -0,0 +1,85 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Lexically normalize a path, removing `.` components and resolving `..`
+/// against the components before it. This does not touch the file system so
+/// symlinks are not followed. Leading `..` are kept for relative paths and
+/// dropped at the root of absolute paths
+pub fn normalize(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  let mut depth = 0;
+
+  for component in path.components() {
+    match component {
+      Component::Prefix(_) | Component::RootDir => normalized.push(component.as_os_str()),
+      Component::CurDir => (),
+      Component::ParentDir => {
+        if depth > 0 {
+          normalized.pop();
+          depth -= 1;
+        } else if !normalized.has_root() {
+          normalized.push(Component::ParentDir.as_os_str());
+        }
+      },
+      Component::Normal(part) => {
+        normalized.push(part);
+        depth += 1;
+      },
+    }
+  }
+
+  if normalized.as_os_str().is_empty() {
+    normalized.push(Component::CurDir.as_os_str());
+  }
+
+  normalized
+}
+
+/// Find the path that leads from base to path. Both paths are normalized
+/// first and must either both be absolute or both be relative. None is
+/// returned when no such path exists, for example when they are on different
+/// drives or base climbs above the start of path with `..`
+pub fn relative_to(path: &Path, base: &Path) -> Option<PathBuf> {
+  let path = normalize(path);
+  let base = normalize(base);
+
+  if path.has_root() != base.has_root() {
+    return None;
+  }
+
+  let mut path_components = path.components().peekable();
+  let mut base_components = base.components().peekable();
+
+  while let (Some(path_component), Some(base_component)) =
+    (path_components.peek(), base_components.peek())
+  {
+    if path_component != base_component {
+      break;
+    }
+
+    path_components.next();
+    base_components.next();
+  }
+
+  let mut relative = PathBuf::new();
+  for component in base_components {
+    match component {
+      Component::CurDir => (),
+      Component::Normal(_) => relative.push(Component::ParentDir.as_os_str()),
+      _ => return None,
+    }
+  }
+
+  for component in path_components {
+    match component {
+      Component::CurDir => (),
+      Component::ParentDir | Component::Normal(_) => relative.push(component.as_os_str()),
+      _ => return None,
+    }
+  }
+
+  if relative.as_os_str().is_empty() {
+    relative.push(Component::CurDir.as_os_str());
+  }
+
+  Some(relative)
+}
@@ -0,0 +1,21 @@
+use std::{fmt, io, path::Path};
+
+/// A module's contents as loaded by an import resolver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedModule {
+  /// Laythe source compiled when the module is imported
+  Source(String),
+
+  /// A module compiled ahead of time with `Vm::precompile`
+  Compiled(Vec<u8>),
+}
+
+/// Loads the modules imported by a script. Embedders can provide their
+/// own resolver to load modules from somewhere other than the file system
+pub trait ImportResolver: fmt::Debug + Send + Sync {
+  /// Load the module at the provided path. The path is the import's
+  /// path joined to the importing module's directory with a `.lay`
+  /// extension, for example `./utils` imported by `/app/main.lay`
+  /// resolves `/app/utils.lay`
+  fn resolve(&self, path: &Path) -> io::Result<ResolvedModule>;
+}
@@ -0,0 +1,93 @@
+use std::sync::{Arc, Mutex};
+
+/// A log of mock external resources such as file handles or sockets. Used
+/// to verify managed objects release their resources when finalized
+#[derive(Debug, Clone, Default)]
+pub struct ResourceLog {
+  inner: Arc<Mutex<ResourceLogInner>>,
+}
+
+#[derive(Debug, Default)]
+struct ResourceLogInner {
+  /// The number of resources acquired
+  acquired: usize,
+
+  /// The ids of released resources in the order they were released
+  released: Vec<usize>,
+}
+
+impl ResourceLog {
+  /// Create a new empty resource log
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Acquire a new resource tracked by this log
+  pub fn acquire(&self) -> ResourceMock {
+    let mut inner = self.inner.lock().expect("Resource log poisoned");
+    let id = inner.acquired;
+    inner.acquired += 1;
+
+    ResourceMock {
+      id,
+      released: false,
+      log: self.clone(),
+    }
+  }
+
+  /// The number of resources acquired
+  pub fn acquired(&self) -> usize {
+    self.inner.lock().expect("Resource log poisoned").acquired
+  }
+
+  /// The ids of released resources in the order they were released
+  pub fn released(&self) -> Vec<usize> {
+    self
+      .inner
+      .lock()
+      .expect("Resource log poisoned")
+      .released
+      .clone()
+  }
+
+  /// The number of resources acquired but not yet released
+  pub fn open(&self) -> usize {
+    let inner = self.inner.lock().expect("Resource log poisoned");
+    inner.acquired - inner.released.len()
+  }
+}
+
+/// A mock external resource recording when it is released
+#[derive(Debug)]
+pub struct ResourceMock {
+  id: usize,
+  released: bool,
+  log: ResourceLog,
+}
+
+impl ResourceMock {
+  /// The id of this resource in acquisition order
+  pub fn id(&self) -> usize {
+    self.id
+  }
+
+  /// Has this resource been released
+  pub fn is_released(&self) -> bool {
+    self.released
+  }
+
+  /// Release this resource. Panics if the resource
+  /// has already been released
+  pub fn release(&mut self) {
+    assert!(!self.released, "Resource {} released twice", self.id);
+    self.released = true;
+
+    self
+      .log
+      .inner
+      .lock()
+      .expect("Resource log poisoned")
+      .released
+      .push(self.id);
+  }
+}
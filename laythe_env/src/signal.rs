@@ -0,0 +1,117 @@
+use crate::io::IoImpl;
+use std::sync::{
+  atomic::{AtomicUsize, Ordering},
+  Arc,
+};
+
+/// A process signal a script can handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSignal {
+  Interrupt,
+  Terminate,
+}
+
+impl ProcessSignal {
+  /// Every signal a script can handle
+  pub const ALL: [ProcessSignal; 2] = [ProcessSignal::Interrupt, ProcessSignal::Terminate];
+
+  /// The conventional name of this signal
+  pub fn name(&self) -> &'static str {
+    match self {
+      ProcessSignal::Interrupt => "SIGINT",
+      ProcessSignal::Terminate => "SIGTERM",
+    }
+  }
+
+  /// The signal with the provided conventional name
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::ALL
+      .iter()
+      .copied()
+      .find(|signal| signal.name() == name)
+  }
+
+  /// The position of this signal in `ALL`
+  pub fn index(&self) -> usize {
+    *self as usize
+  }
+}
+
+/// A wrapper around the process signal facilities provided to Laythe
+pub struct Signals {
+  signals: Box<dyn SignalsImpl>,
+}
+
+impl Default for Signals {
+  fn default() -> Self {
+    Self {
+      signals: Box::new(SignalsMock::default()),
+    }
+  }
+}
+
+impl Signals {
+  /// Create a new wrapper around the provided signal facilities
+  pub fn new(signals: Box<dyn SignalsImpl>) -> Self {
+    Self { signals }
+  }
+
+  /// Count deliveries of a signal instead of taking its default action
+  pub fn watch(&self, signal: ProcessSignal) -> Result<(), String> {
+    self.signals.watch(signal)
+  }
+
+  /// How many times a watched signal has been delivered to this process
+  pub fn received(&self, signal: ProcessSignal) -> usize {
+    self.signals.received(signal)
+  }
+
+  /// Send a signal to this process
+  pub fn raise(&self, signal: ProcessSignal) -> Result<(), String> {
+    self.signals.raise(signal)
+  }
+}
+
+pub trait SignalsImpl: Send {
+  fn watch(&self, signal: ProcessSignal) -> Result<(), String>;
+  fn received(&self, signal: ProcessSignal) -> usize;
+  fn raise(&self, signal: ProcessSignal) -> Result<(), String>;
+}
+
+/// Deliveries of each signal shared by each `Signals` an `Io` makes
+type Received = Arc<[AtomicUsize; ProcessSignal::ALL.len()]>;
+
+#[derive(Debug, Default)]
+pub struct IoSignalsMock {
+  received: Received,
+}
+
+impl IoImpl<Signals> for IoSignalsMock {
+  fn make(&self) -> Signals {
+    Signals::new(Box::new(SignalsMock {
+      received: Arc::clone(&self.received),
+    }))
+  }
+}
+
+/// Signals raised by a script are only delivered to the
+/// `Signals` of the same `Io`, never to the process
+#[derive(Default)]
+pub struct SignalsMock {
+  received: Received,
+}
+
+impl SignalsImpl for SignalsMock {
+  fn watch(&self, _signal: ProcessSignal) -> Result<(), String> {
+    Ok(())
+  }
+
+  fn received(&self, signal: ProcessSignal) -> usize {
+    self.received[signal.index()].load(Ordering::SeqCst)
+  }
+
+  fn raise(&self, signal: ProcessSignal) -> Result<(), String> {
+    self.received[signal.index()].fetch_add(1, Ordering::SeqCst);
+    Ok(())
+  }
+}
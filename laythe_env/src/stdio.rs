@@ -1,39 +1,65 @@
 use crate::io::IoImpl;
-use std::io::{self, Read, Write};
+use std::{
+  io::{self, Read, Write},
+  sync::{Arc, Mutex},
+};
 use termcolor::WriteColor;
 
 /// A wrapper the provided facilities around standard input output and err
 pub struct Stdio {
   stdio: Box<dyn StdioImpl>,
+  stdout_redirect: Option<StdioBuffer>,
+  stderr_redirect: Option<StdioBuffer>,
 }
 
 impl Default for Stdio {
   fn default() -> Self {
-    Self {
-      stdio: Box::new(StdioMock::default()),
-    }
+    Self::new(Box::new(StdioMock::default()))
   }
 }
 
 impl Stdio {
   /// Create a new wrapper from the provided stdio facilities
   pub fn new(stdio: Box<dyn StdioImpl>) -> Self {
-    Self { stdio }
+    Self {
+      stdio,
+      stdout_redirect: None,
+      stderr_redirect: None,
+    }
+  }
+
+  /// Send stdout and stderr to the provided buffers instead of
+  /// the underlying facilities
+  pub fn redirect(self, stdout: Option<StdioBuffer>, stderr: Option<StdioBuffer>) -> Self {
+    Self {
+      stdout_redirect: stdout,
+      stderr_redirect: stderr,
+      ..self
+    }
   }
 
   /// Get a Write to stdout
   pub fn stdout(&mut self) -> &mut dyn Write {
-    self.stdio.stdout()
+    match &mut self.stdout_redirect {
+      Some(buffer) => buffer,
+      None => self.stdio.stdout(),
+    }
   }
 
   /// Get a Write to stderr
   pub fn stderr(&mut self) -> &mut dyn Write {
-    self.stdio.stderr()
+    match &mut self.stderr_redirect {
+      Some(buffer) => buffer,
+      None => self.stdio.stderr(),
+    }
   }
 
   /// Get a WriteColor to stderr
   pub fn stderr_color(&mut self) -> &mut dyn WriteColor {
-    self.stdio.stderr_color()
+    match &mut self.stderr_redirect {
+      Some(buffer) => buffer,
+      None => self.stdio.stderr_color(),
+    }
   }
 
   /// Get a Read to stdin
@@ -41,19 +67,240 @@ impl Stdio {
     self.stdio.stdin()
   }
 
-  /// Read a line from standard in
+  /// Read a line from standard in. Hosts that receive input
+  /// asynchronously return `io::ErrorKind::WouldBlock` until a line
+  /// is available
   pub fn read_line(&self, buffer: &mut String) -> io::Result<usize> {
     self.stdio.read_line(buffer)
   }
+
+  /// Read a single character from standard in, returning None at the
+  /// end of input
+  pub fn read_char(&mut self) -> io::Result<Option<char>> {
+    self.stdio.read_char()
+  }
+
+  /// Is standard in attached to an interactive terminal
+  pub fn is_tty(&self) -> bool {
+    self.stdio.is_tty()
+  }
+
+  /// Read a single key from standard in, returning None at the end
+  /// of input
+  pub fn read_key(&mut self) -> io::Result<Option<Key>> {
+    self.stdio.read_key()
+  }
+
+  /// The size of the terminal as columns and rows
+  pub fn terminal_size(&self) -> io::Result<(u16, u16)> {
+    self.stdio.terminal_size()
+  }
+
+  /// Enter or leave the terminal's raw mode
+  pub fn set_raw_mode(&mut self, enabled: bool) -> io::Result<()> {
+    self.stdio.set_raw_mode(enabled)
+  }
 }
 
-pub trait StdioImpl {
+pub trait StdioImpl: Send {
   fn stdout(&mut self) -> &mut dyn Write;
   fn stderr(&mut self) -> &mut dyn Write;
   fn stderr_color(&mut self) -> &mut dyn WriteColor;
   fn stdin(&mut self) -> &mut dyn Read;
 
+  /// Read a line into the buffer. An implementation whose input arrives
+  /// asynchronously, such as from a browser, returns
+  /// `io::ErrorKind::WouldBlock` while no line is available. The reading
+  /// fiber then waits until the host resumes the vm
   fn read_line(&self, buffer: &mut String) -> io::Result<usize>;
+
+  /// Read a single character. By default this decodes the next utf-8
+  /// character from stdin, an implementation that receives input
+  /// asynchronously may instead return `io::ErrorKind::WouldBlock`
+  fn read_char(&mut self) -> io::Result<Option<char>> {
+    read_utf8_char(self.stdin())
+  }
+
+  /// Is input coming from an interactive terminal
+  fn is_tty(&self) -> bool;
+
+  /// Read a single key. By default this decodes the key from the
+  /// characters of `read_char`, so an escape on its own waits for the
+  /// character after it
+  fn read_key(&mut self) -> io::Result<Option<Key>> {
+    match self.read_char()? {
+      Some(first) => decode_key(first, &mut || self.read_char()).map(Some),
+      None => Ok(None),
+    }
+  }
+
+  /// The size of the terminal as columns and rows
+  fn terminal_size(&self) -> io::Result<(u16, u16)> {
+    Err(io::Error::new(
+      io::ErrorKind::Unsupported,
+      "terminal size is not available",
+    ))
+  }
+
+  /// Enter or leave raw mode, where input is read a key at a time
+  /// without being echoed or interpreted by the terminal
+  fn set_raw_mode(&mut self, _enabled: bool) -> io::Result<()> {
+    Err(io::Error::new(
+      io::ErrorKind::Unsupported,
+      "raw mode is not available",
+    ))
+  }
+}
+
+/// A key read from a terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+  Char(char),
+  Ctrl(char),
+  Alt(char),
+  Enter,
+  Tab,
+  Backspace,
+  Escape,
+  Up,
+  Down,
+  Left,
+  Right,
+  Home,
+  End,
+  PageUp,
+  PageDown,
+  Insert,
+  Delete,
+}
+
+/// Decode the key starting with the first character, reading the rest
+/// of an escape sequence with next. Next returns None when no more of
+/// the sequence is coming, which reads a lone escape as the escape key
+pub fn decode_key(
+  first: char,
+  next: &mut dyn FnMut() -> io::Result<Option<char>>,
+) -> io::Result<Key> {
+  let key = match first {
+    '\r' | '\n' => Key::Enter,
+    '\t' => Key::Tab,
+    '\x7f' | '\x08' => Key::Backspace,
+    '\x1b' => match next()? {
+      Some('[') => decode_csi(next)?,
+      Some('O') => match next()? {
+        Some(last) => csi_key(last, ""),
+        None => Key::Alt('O'),
+      },
+      Some(c) => Key::Alt(c),
+      None => Key::Escape,
+    },
+    '\x01'..='\x1a' => Key::Ctrl((b'a' + first as u8 - 1) as char),
+    c => Key::Char(c),
+  };
+
+  Ok(key)
+}
+
+/// Decode a control sequence such as `ESC [ A` for up or `ESC [ 3 ~`
+/// for delete. Sequences for keys without a variant read as escape
+fn decode_csi(next: &mut dyn FnMut() -> io::Result<Option<char>>) -> io::Result<Key> {
+  let mut params = String::new();
+
+  while let Some(c) = next()? {
+    if ('@'..='~').contains(&c) {
+      return Ok(csi_key(c, &params));
+    }
+    params.push(c);
+  }
+
+  Ok(Key::Escape)
+}
+
+/// The key for the final character and parameters of a control sequence
+fn csi_key(last: char, params: &str) -> Key {
+  match (last, params.split(';').next().unwrap_or("")) {
+    ('A', _) => Key::Up,
+    ('B', _) => Key::Down,
+    ('C', _) => Key::Right,
+    ('D', _) => Key::Left,
+    ('H', _) | ('~', "1") | ('~', "7") => Key::Home,
+    ('F', _) | ('~', "4") | ('~', "8") => Key::End,
+    ('~', "2") => Key::Insert,
+    ('~', "3") => Key::Delete,
+    ('~', "5") => Key::PageUp,
+    ('~', "6") => Key::PageDown,
+    _ => Key::Escape,
+  }
+}
+
+/// Read the next utf-8 encoded character from a reader, returning None
+/// if the reader is already exhausted
+pub fn read_utf8_char(read: &mut dyn Read) -> io::Result<Option<char>> {
+  let mut buf = [0; 4];
+  if read.read(&mut buf[..1])? == 0 {
+    return Ok(None);
+  }
+
+  let len = match buf[0] {
+    0x00..=0x7f => 1,
+    0xc0..=0xdf => 2,
+    0xe0..=0xef => 3,
+    0xf0..=0xf7 => 4,
+    _ => 0,
+  };
+
+  if len == 0 || read.read_exact(&mut buf[1..len]).is_err() {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      "stream did not contain valid UTF-8",
+    ));
+  }
+
+  std::str::from_utf8(&buf[..len])
+    .map(|string| string.chars().next())
+    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// An in memory stream stdout or stderr can be redirected into. Clones
+/// share the same underlying bytes so output can be read back after the
+/// redirection ends
+#[derive(Debug, Clone, Default)]
+pub struct StdioBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl StdioBuffer {
+  /// The bytes written so far
+  pub fn contents(&self) -> Vec<u8> {
+    self.0.lock().expect("Stdio buffer was poisoned").clone()
+  }
+
+  /// Take the bytes written so far leaving the buffer empty
+  pub fn take(&self) -> Vec<u8> {
+    std::mem::take(&mut *self.0.lock().expect("Stdio buffer was poisoned"))
+  }
+}
+
+impl Write for StdioBuffer {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.lock().expect("Stdio buffer was poisoned").write(buf)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+impl WriteColor for StdioBuffer {
+  fn supports_color(&self) -> bool {
+    false
+  }
+
+  fn set_color(&mut self, _: &termcolor::ColorSpec) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn reset(&mut self) -> io::Result<()> {
+    Ok(())
+  }
 }
 
 #[derive(Debug)]
@@ -98,6 +345,9 @@ impl StdioImpl for StdioMock {
 
     Ok(LINE.len())
   }
+  fn is_tty(&self) -> bool {
+    false
+  }
 }
 
 pub struct MockWrite();
@@ -282,6 +532,9 @@ pub mod support {
     line_index: *mut usize,
   }
 
+  // A test stdio is only sent along with the single vm reading its container
+  unsafe impl Send for StdioTest {}
+
   impl StdioImpl for StdioTest {
     fn stdout(&mut self) -> &mut dyn Write {
       unsafe { &mut *self.stdout }
@@ -308,5 +561,14 @@ pub mod support {
         Ok(line.len())
       }
     }
+    fn is_tty(&self) -> bool {
+      false
+    }
+    fn terminal_size(&self) -> std::io::Result<(u16, u16)> {
+      Ok((80, 24))
+    }
+    fn set_raw_mode(&mut self, _enabled: bool) -> std::io::Result<()> {
+      Ok(())
+    }
   }
 }
@@ -24,10 +24,22 @@ impl Time {
   pub fn elapsed(&self) -> Result<Duration, String> {
     self.time.elapsed()
   }
+
+  /// Get the wall clock time as a duration since the unix epoch
+  pub fn now(&self) -> Result<Duration, String> {
+    self.time.now()
+  }
+
+  /// Block the current thread for the provided duration
+  pub fn sleep(&self, duration: Duration) {
+    self.time.sleep(duration)
+  }
 }
 
-pub trait TimeImpl {
+pub trait TimeImpl: Send {
   fn elapsed(&self) -> Result<Duration, String>;
+  fn now(&self) -> Result<Duration, String>;
+  fn sleep(&self, duration: Duration);
 }
 
 #[derive(Debug)]
@@ -45,4 +57,10 @@ impl TimeImpl for TimeMock {
   fn elapsed(&self) -> Result<Duration, String> {
     Ok(Duration::new(3, 14236))
   }
+
+  fn now(&self) -> Result<Duration, String> {
+    Ok(Duration::new(1_600_000_000, 0))
+  }
+
+  fn sleep(&self, _duration: Duration) {}
 }
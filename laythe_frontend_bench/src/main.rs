@@ -53,7 +53,7 @@ fn compiler_bench(src: &str) {
     let module = Module::from_path(&hooks, PathBuf::from("/Benchmark.ly"), class, 0).unwrap();
     let module = hooks.manage(module);
 
-    let compiler = Compiler::new(module, &ast, &line_offsets, 0, &NO_GC, gc);
+    let compiler = Compiler::new(module, &ast, &source, &line_offsets, 0, &NO_GC, gc);
     compiler.compile().0.unwrap();
   }
 }
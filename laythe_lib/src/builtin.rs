@@ -8,7 +8,7 @@ use laythe_core::{
   value::{Value, ValueKind},
 };
 
-use crate::global::{BOOL_CLASS_NAME, CLASS_CLASS_NAME, CLOSURE_CLASS_NAME, EXPORT_ERROR_NAME, FIBER_CLASS_NAME, IMPORT_ERROR_NAME, ITER_CLASS_NAME, LIST_CLASS_NAME, MAP_CLASS_NAME, METHOD_CLASS_NAME, METHOD_NOT_FOUND_ERROR_NAME, MODULE_CLASS_NAME, NATIVE_CLASS_NAME, NIL_CLASS_NAME, NUMBER_CLASS_NAME, OBJECT_CLASS_NAME, PROPERTY_ERROR_NAME, RUNTIME_ERROR_NAME, STRING_CLASS_NAME};
+use crate::global::{BOOL_CLASS_NAME, CHANNEL_CLASS_NAME, CLASS_CLASS_NAME, CLOSURE_CLASS_NAME, ERROR_CLASS_NAME, EXPORT_ERROR_NAME, FIBER_CLASS_NAME, IMPORT_ERROR_NAME, ITER_CLASS_NAME, LIST_CLASS_NAME, MAP_CLASS_NAME, METHOD_CLASS_NAME, METHOD_NOT_FOUND_ERROR_NAME, MODULE_CLASS_NAME, NATIVE_CLASS_NAME, NIL_CLASS_NAME, NUMBER_CLASS_NAME, OBJECT_CLASS_NAME, OUT_OF_MEMORY_ERROR_NAME, PROPERTY_ERROR_NAME, RUNTIME_ERROR_NAME, STRING_CLASS_NAME, TUPLE_CLASS_NAME};
 
 pub struct BuiltIn {
   /// built in classes related to dependencies
@@ -66,6 +66,9 @@ pub struct BuiltInPrimitives {
   /// the Fiber class
   pub fiber: GcObj<Class>,
 
+  /// the Channel class
+  pub channel: GcObj<Class>,
+
   /// the Number class
   pub number: GcObj<Class>,
 
@@ -78,6 +81,9 @@ pub struct BuiltInPrimitives {
   /// the Map class
   pub map: GcObj<Class>,
 
+  /// the Tuple class
+  pub tuple: GcObj<Class>,
+
   /// the Iter class
   pub iter: GcObj<Class>,
 
@@ -106,12 +112,14 @@ impl BuiltInPrimitives {
           ObjectKind::Enumerator => self.iter,
           ObjectKind::Fun => panic!("Function should not be directly accessible"),
           ObjectKind::Fiber => self.fiber,
+          ObjectKind::Channel => self.channel,
           ObjectKind::Instance => obj.to_instance().class(),
           ObjectKind::List => self.list,
           ObjectKind::Map => self.map,
           ObjectKind::Method => self.method,
           ObjectKind::Native => self.native_fun,
           ObjectKind::String => self.string,
+          ObjectKind::Tuple => self.tuple,
           ObjectKind::Upvalue => {
             let value = obj.to_upvalue().value(stack);
             self.for_value(value, stack)
@@ -128,11 +136,13 @@ impl Trace for BuiltInPrimitives {
     self.nil.trace();
     self.class.trace();
     self.fiber.trace();
+    self.channel.trace();
     self.number.trace();
     self.string.trace();
     self.list.trace();
     self.iter.trace();
     self.map.trace();
+    self.tuple.trace();
     self.closure.trace();
     self.method.trace();
     self.native_fun.trace();
@@ -143,11 +153,13 @@ impl Trace for BuiltInPrimitives {
     self.nil.trace_debug(stdio);
     self.class.trace_debug(stdio);
     self.fiber.trace_debug(stdio);
+    self.channel.trace_debug(stdio);
     self.number.trace_debug(stdio);
     self.string.trace_debug(stdio);
     self.list.trace_debug(stdio);
     self.iter.trace_debug(stdio);
     self.map.trace_debug(stdio);
+    self.tuple.trace_debug(stdio);
     self.closure.trace_debug(stdio);
     self.method.trace_debug(stdio);
     self.native_fun.trace_debug(stdio);
@@ -155,6 +167,8 @@ impl Trace for BuiltInPrimitives {
 }
 
 pub struct BuiltInErrors {
+  pub error: GcObj<Class>,
+
   pub runtime: GcObj<Class>,
 
   pub method_not_found: GcObj<Class>,
@@ -164,6 +178,8 @@ pub struct BuiltInErrors {
   pub import: GcObj<Class>,
 
   pub export: GcObj<Class>,
+
+  pub out_of_memory: GcObj<Class>,
 }
 
 impl Trace for BuiltInErrors {
@@ -199,6 +215,10 @@ pub fn builtin_from_module(hooks: &GcHooks, module: &Module) -> Option<BuiltIn>
         .get_symbol(hooks.manage_str(FIBER_CLASS_NAME))?
         .to_obj()
         .to_class(),
+      channel: module
+        .get_symbol(hooks.manage_str(CHANNEL_CLASS_NAME))?
+        .to_obj()
+        .to_class(),
       number: module
         .get_symbol(hooks.manage_str(NUMBER_CLASS_NAME))?
         .to_obj()
@@ -215,6 +235,10 @@ pub fn builtin_from_module(hooks: &GcHooks, module: &Module) -> Option<BuiltIn>
         .get_symbol(hooks.manage_str(MAP_CLASS_NAME))?
         .to_obj()
         .to_class(),
+      tuple: module
+        .get_symbol(hooks.manage_str(TUPLE_CLASS_NAME))?
+        .to_obj()
+        .to_class(),
       iter: module
         .get_symbol(hooks.manage_str(ITER_CLASS_NAME))?
         .to_obj()
@@ -239,6 +263,10 @@ pub fn builtin_from_module(hooks: &GcHooks, module: &Module) -> Option<BuiltIn>
         .to_class(),
     },
     errors: BuiltInErrors {
+      error: module
+        .get_symbol(hooks.manage_str(ERROR_CLASS_NAME))?
+        .to_obj()
+        .to_class(),
       runtime: module
         .get_symbol(hooks.manage_str(RUNTIME_ERROR_NAME))?
         .to_obj()
@@ -259,6 +287,10 @@ pub fn builtin_from_module(hooks: &GcHooks, module: &Module) -> Option<BuiltIn>
         .get_symbol(hooks.manage_str(EXPORT_ERROR_NAME))?
         .to_obj()
         .to_class(),
+      out_of_memory: module
+        .get_symbol(hooks.manage_str(OUT_OF_MEMORY_ERROR_NAME))?
+        .to_obj()
+        .to_class(),
     },
   })
 }
@@ -0,0 +1,45 @@
+mod parser;
+mod utils;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use std::path::PathBuf;
+use utils::{declare_cli_module, define_cli_module};
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+
+const CLI_PATH: &str = "std/cli";
+const CLI_ERROR: &str = "CliError";
+
+pub fn cli_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(CLI_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let cli_error = default_error_inheritance(hooks, std, CLI_ERROR)?;
+  export_and_insert(hooks, &mut module, cli_error.name(), val!(cli_error))?;
+
+  declare_cli_module(hooks, &mut module)?;
+  define_cli_module(hooks, &mut module)?;
+
+  Ok(module)
+}
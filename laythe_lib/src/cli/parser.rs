@@ -0,0 +1,577 @@
+use laythe_core::value::Value;
+use std::fmt::Write;
+
+/// The kind of value an argument takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+  /// A switch that is true when present and takes no value
+  Flag,
+  String,
+  Number,
+}
+
+impl ArgKind {
+  /// The placeholder shown for an option's value in help text
+  fn placeholder(&self) -> &'static str {
+    match self {
+      ArgKind::Flag => "",
+      ArgKind::String => "<string>",
+      ArgKind::Number => "<number>",
+    }
+  }
+}
+
+/// A declared flag, option or positional argument
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arg {
+  pub name: String,
+  pub short: Option<char>,
+  pub help: Option<String>,
+  pub kind: ArgKind,
+  pub default: Option<Value>,
+  pub required: bool,
+  pub multiple: bool,
+}
+
+/// A declared program or subcommand
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Spec {
+  pub name: String,
+  pub about: Option<String>,
+  pub options: Vec<Arg>,
+  pub positionals: Vec<Arg>,
+  pub commands: Vec<Spec>,
+}
+
+/// A parsed value for a name in the matches
+#[derive(Debug, Clone, PartialEq)]
+pub enum Parsed {
+  Bool(bool),
+  Text(String),
+  Number(f64),
+  List(Vec<Parsed>),
+  Default(Value),
+  Nil,
+  Command(Matches),
+}
+
+/// The value of each declared name in declaration order
+pub type Matches = Vec<(String, Parsed)>;
+
+/// The result of parsing the arguments of a program
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+  Matches(Matches),
+
+  /// Help was requested, with the help text of the program or subcommand
+  Help(String),
+}
+
+/// Parse the arguments of the program `path` names. Errors include the
+/// usage of the program or subcommand the error was found in
+pub fn parse(spec: &Spec, path: &str, args: &[String]) -> Result<Outcome, String> {
+  let fail = |message: String| format!("{}\n\n{}", message, usage(spec, path));
+
+  let mut values: Vec<Vec<Parsed>> = vec![vec![]; spec.options.len()];
+  let mut positionals: Vec<&str> = vec![];
+  let mut command = None;
+  let mut only_positionals = false;
+
+  let mut index = 0;
+  while index < args.len() {
+    let arg = args[index].as_str();
+    index += 1;
+
+    if only_positionals || !is_option(arg) {
+      if !only_positionals && positionals.is_empty() && !spec.commands.is_empty() {
+        if let Some(sub) = spec.commands.iter().find(|sub| sub.name == arg) {
+          let sub_path = format!("{} {}", path, sub.name);
+          match parse(sub, &sub_path, &args[index..])? {
+            Outcome::Matches(matches) => command = Some((sub.name.clone(), matches)),
+            help => return Ok(help),
+          }
+          break;
+        }
+
+        if spec.positionals.is_empty() {
+          return Err(fail(format!("Unknown command {}.", arg)));
+        }
+      }
+
+      positionals.push(arg);
+      continue;
+    }
+
+    if arg == "--" {
+      only_positionals = true;
+      continue;
+    }
+
+    if let Some(long) = arg.strip_prefix("--") {
+      let (name, inline) = match long.find('=') {
+        Some(split) => (&long[..split], Some(&long[split + 1..])),
+        None => (long, None),
+      };
+
+      if name == "help" && !spec.options.iter().any(|option| option.name == "help") {
+        return Ok(Outcome::Help(help(spec, path)));
+      }
+
+      let position = spec
+        .options
+        .iter()
+        .position(|option| option.name == name)
+        .ok_or_else(|| fail(format!("Unknown option --{}.", name)))?;
+      let option = &spec.options[position];
+
+      let parsed = match (option.kind, inline) {
+        (ArgKind::Flag, Some(_)) => {
+          return Err(fail(format!("Flag --{} does not take a value.", name)))
+        },
+        (ArgKind::Flag, None) => Parsed::Bool(true),
+        (kind, Some(value)) => convert(kind, value).map_err(|err| fail(err(arg)))?,
+        (kind, None) => match args.get(index) {
+          Some(value) => {
+            index += 1;
+            convert(kind, value).map_err(|err| fail(err(arg)))?
+          },
+          None => return Err(fail(format!("Option {} expects a value.", arg))),
+        },
+      };
+      values[position].push(parsed);
+      continue;
+    }
+
+    // a group of short options such as -vc 2 or -c2
+    let shorts = &arg[1..];
+    for (offset, short) in shorts.char_indices() {
+      if short == 'h' && !spec.options.iter().any(|option| option.short == Some('h')) {
+        return Ok(Outcome::Help(help(spec, path)));
+      }
+
+      let position = spec
+        .options
+        .iter()
+        .position(|option| option.short == Some(short))
+        .ok_or_else(|| fail(format!("Unknown option -{}.", short)))?;
+      let option = &spec.options[position];
+      let flag = format!("-{}", short);
+
+      if option.kind == ArgKind::Flag {
+        values[position].push(Parsed::Bool(true));
+        continue;
+      }
+
+      let attached = &shorts[offset + short.len_utf8()..];
+      let value = if !attached.is_empty() {
+        attached
+      } else {
+        match args.get(index) {
+          Some(value) => {
+            index += 1;
+            value
+          },
+          None => return Err(fail(format!("Option {} expects a value.", flag))),
+        }
+      };
+
+      let parsed = convert(option.kind, value).map_err(|err| fail(err(&flag)))?;
+      values[position].push(parsed);
+      break;
+    }
+  }
+
+  let mut matches = Matches::new();
+
+  for (option, mut values) in spec.options.iter().zip(values) {
+    let parsed = match (option.kind, values.pop()) {
+      (ArgKind::Flag, value) => Parsed::Bool(value.is_some()),
+      (_, Some(last)) if option.multiple => {
+        values.push(last);
+        Parsed::List(values)
+      },
+      (_, Some(last)) => last,
+      (_, None) => match option.default {
+        Some(default) => Parsed::Default(default),
+        None if option.required => {
+          return Err(fail(format!("Missing required option --{}.", option.name)))
+        },
+        None if option.multiple => Parsed::List(vec![]),
+        None => Parsed::Nil,
+      },
+    };
+    matches.push((option.name.clone(), parsed));
+  }
+
+  let mut remaining = positionals.into_iter();
+  for positional in &spec.positionals {
+    let values = if positional.multiple {
+      remaining.by_ref().collect()
+    } else {
+      remaining.next().into_iter().collect::<Vec<&str>>()
+    };
+
+    let parsed = if values.is_empty() {
+      match positional.default {
+        Some(default) => Parsed::Default(default),
+        None if positional.required => {
+          return Err(fail(format!(
+            "Missing required argument {}.",
+            placeholder(positional)
+          )))
+        },
+        None if positional.multiple => Parsed::List(vec![]),
+        None => Parsed::Nil,
+      }
+    } else {
+      let mut parsed = values
+        .iter()
+        .map(|value| convert(positional.kind, value))
+        .collect::<Result<Vec<Parsed>, _>>()
+        .map_err(|err| fail(err(&placeholder(positional))))?;
+
+      if positional.multiple {
+        Parsed::List(parsed)
+      } else {
+        parsed.remove(0)
+      }
+    };
+    matches.push((positional.name.clone(), parsed));
+  }
+
+  if let Some(extra) = remaining.next() {
+    return Err(fail(format!("Unexpected argument {}.", extra)));
+  }
+
+  // the chosen command maps to its own matches and the others to nil
+  if !spec.commands.is_empty() {
+    let first = matches.len();
+    for sub in &spec.commands {
+      matches.push((sub.name.clone(), Parsed::Nil));
+    }
+
+    match command {
+      Some((name, sub_matches)) => {
+        if let Some(entry) = matches[first..].iter_mut().find(|(key, _)| *key == name) {
+          entry.1 = Parsed::Command(sub_matches);
+        }
+        matches.push(("command".to_string(), Parsed::Text(name)));
+      },
+      None => matches.push(("command".to_string(), Parsed::Nil)),
+    }
+  }
+
+  Ok(Outcome::Matches(matches))
+}
+
+/// The help text of the program `path` names
+pub fn help(spec: &Spec, path: &str) -> String {
+  let mut text = String::new();
+  if let Some(about) = &spec.about {
+    text.push_str(about);
+    text.push_str("\n\n");
+  }
+  text.push_str(&usage(spec, path));
+
+  let arguments: Vec<(String, String)> = spec
+    .positionals
+    .iter()
+    .map(|positional| (placeholder(positional), description(positional)))
+    .collect();
+
+  let mut options: Vec<(String, String)> = spec
+    .options
+    .iter()
+    .map(|option| {
+      let short = match option.short {
+        Some(short) => format!("-{}, ", short),
+        None => "    ".to_string(),
+      };
+      let name = match option.kind {
+        ArgKind::Flag => format!("{}--{}", short, option.name),
+        kind => format!("{}--{} {}", short, option.name, kind.placeholder()),
+      };
+      (name, description(option))
+    })
+    .collect();
+
+  if !spec.options.iter().any(|option| option.name == "help") {
+    let short = if spec.options.iter().any(|option| option.short == Some('h')) {
+      "    "
+    } else {
+      "-h, "
+    };
+    options.push((format!("{}--help", short), "Print this message".to_string()));
+  }
+
+  let commands: Vec<(String, String)> = spec
+    .commands
+    .iter()
+    .map(|sub| (sub.name.clone(), sub.about.clone().unwrap_or_default()))
+    .collect();
+
+  // every section's descriptions start in the same column
+  let width = arguments
+    .iter()
+    .chain(&options)
+    .chain(&commands)
+    .map(|(name, _)| name.len())
+    .max()
+    .unwrap_or(0);
+
+  for (title, entries) in [
+    ("Arguments", &arguments),
+    ("Options", &options),
+    ("Commands", &commands),
+  ]
+  .iter()
+  {
+    if entries.is_empty() {
+      continue;
+    }
+
+    let _ = write!(text, "\n\n{}:", title);
+    for (name, description) in entries.iter() {
+      let _ = write!(text, "\n  {:width$}  {}", name, description, width = width);
+    }
+  }
+
+  text
+    .lines()
+    .map(str::trim_end)
+    .collect::<Vec<&str>>()
+    .join("\n")
+}
+
+/// The usage line of the program `path` names
+pub fn usage(spec: &Spec, path: &str) -> String {
+  let mut usage = format!("Usage: {} [options]", path);
+  if !spec.commands.is_empty() {
+    usage.push_str(" [command]");
+  }
+
+  for positional in &spec.positionals {
+    usage.push(' ');
+    usage.push_str(&placeholder(positional));
+  }
+
+  usage
+}
+
+/// The placeholder of a positional argument, bracketed by whether it
+/// is required and followed by an ellipsis if it takes many values
+fn placeholder(positional: &Arg) -> String {
+  let ellipsis = if positional.multiple { "..." } else { "" };
+
+  if positional.required {
+    format!("<{}>{}", positional.name, ellipsis)
+  } else {
+    format!("[{}]{}", positional.name, ellipsis)
+  }
+}
+
+/// The help of an argument followed by its default if it has one
+fn description(arg: &Arg) -> String {
+  let help = arg.help.clone().unwrap_or_default();
+
+  match arg.default {
+    Some(default) if help.is_empty() => format!("(default: {})", default),
+    Some(default) => format!("{} (default: {})", help, default),
+    None => help,
+  }
+}
+
+/// Is the argument an option rather than a value. Negative numbers
+/// and a lone dash are values
+fn is_option(arg: &str) -> bool {
+  arg.starts_with('-') && arg.len() > 1 && arg.parse::<f64>().is_err()
+}
+
+/// Convert a value to the kind of its argument, returning a function
+/// that builds the error for the argument's name if it can't be
+fn convert(kind: ArgKind, value: &str) -> Result<Parsed, impl Fn(&str) -> String> {
+  let invalid = value.to_string();
+
+  match kind {
+    ArgKind::Number => value
+      .parse::<f64>()
+      .map(Parsed::Number)
+      .map_err(|_| move |name: &str| format!("{} expects a number, found {}.", name, invalid)),
+    _ => Ok(Parsed::Text(value.to_string())),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use laythe_core::val;
+
+  fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+  }
+
+  fn expect_matches(outcome: Result<Outcome, String>) -> Matches {
+    match outcome {
+      Ok(Outcome::Matches(matches)) => matches,
+      outcome => panic!("Expected matches found {:?}", outcome),
+    }
+  }
+
+  fn arg(name: &str, kind: ArgKind) -> Arg {
+    Arg {
+      name: name.to_string(),
+      short: None,
+      help: None,
+      kind,
+      default: None,
+      required: false,
+      multiple: false,
+    }
+  }
+
+  fn get<'a>(matches: &'a Matches, name: &str) -> &'a Parsed {
+    &matches.iter().find(|(key, _)| key == name).unwrap().1
+  }
+
+  fn spec() -> Spec {
+    let mut verbose = arg("verbose", ArgKind::Flag);
+    verbose.short = Some('v');
+    verbose.help = Some("Print more".to_string());
+
+    let mut count = arg("count", ArgKind::Number);
+    count.short = Some('c');
+    count.default = Some(val!(1.0));
+
+    let mut tag = arg("tag", ArgKind::String);
+    tag.multiple = true;
+
+    let mut who = arg("who", ArgKind::String);
+    who.required = true;
+
+    let mut rest = arg("rest", ArgKind::String);
+    rest.multiple = true;
+
+    Spec {
+      name: "greet".to_string(),
+      about: Some("Greet someone".to_string()),
+      options: vec![verbose, count, tag],
+      positionals: vec![who, rest],
+      commands: vec![],
+    }
+  }
+
+  #[test]
+  fn options() {
+    let spec = spec();
+    let matches = expect_matches(parse(
+      &spec,
+      "greet",
+      &args(&["-vc3", "--tag=a", "ada", "--tag", "b", "x", "--", "-y"]),
+    ));
+
+    assert_eq!(*get(&matches, "verbose"), Parsed::Bool(true));
+    assert_eq!(*get(&matches, "count"), Parsed::Number(3.0));
+    assert_eq!(
+      *get(&matches, "tag"),
+      Parsed::List(vec![
+        Parsed::Text("a".to_string()),
+        Parsed::Text("b".to_string())
+      ])
+    );
+    assert_eq!(*get(&matches, "who"), Parsed::Text("ada".to_string()));
+    assert_eq!(
+      *get(&matches, "rest"),
+      Parsed::List(vec![
+        Parsed::Text("x".to_string()),
+        Parsed::Text("-y".to_string())
+      ])
+    );
+  }
+
+  #[test]
+  fn defaults() {
+    let spec = spec();
+    let matches = expect_matches(parse(&spec, "greet", &args(&["-5"])));
+
+    assert_eq!(*get(&matches, "verbose"), Parsed::Bool(false));
+    assert_eq!(*get(&matches, "count"), Parsed::Default(val!(1.0)));
+    assert_eq!(*get(&matches, "tag"), Parsed::List(vec![]));
+    assert_eq!(*get(&matches, "who"), Parsed::Text("-5".to_string()));
+    assert_eq!(*get(&matches, "rest"), Parsed::List(vec![]));
+  }
+
+  #[test]
+  fn errors() {
+    let spec = spec();
+
+    let error = parse(&spec, "greet", &args(&["--missing"])).unwrap_err();
+    assert!(error.starts_with("Unknown option --missing."));
+    assert!(error.ends_with("Usage: greet [options] <who> [rest]..."));
+
+    let error = parse(&spec, "greet", &args(&["-c", "many", "ada"])).unwrap_err();
+    assert!(error.starts_with("-c expects a number, found many."));
+
+    let error = parse(&spec, "greet", &args(&["--verbose=yes"])).unwrap_err();
+    assert!(error.starts_with("Flag --verbose does not take a value."));
+
+    let error = parse(&spec, "greet", &args(&["--count"])).unwrap_err();
+    assert!(error.starts_with("Option --count expects a value."));
+
+    let error = parse(&spec, "greet", &args(&[])).unwrap_err();
+    assert!(error.starts_with("Missing required argument <who>."));
+  }
+
+  #[test]
+  fn commands() {
+    let mut add = Spec {
+      name: "add".to_string(),
+      about: Some("Add an item".to_string()),
+      ..Spec::default()
+    };
+    add.positionals.push(arg("item", ArgKind::String));
+
+    let spec = Spec {
+      name: "todo".to_string(),
+      commands: vec![add],
+      ..Spec::default()
+    };
+
+    let matches = expect_matches(parse(&spec, "todo", &args(&["add", "milk"])));
+    assert_eq!(*get(&matches, "command"), Parsed::Text("add".to_string()));
+    assert_eq!(
+      *get(&matches, "add"),
+      Parsed::Command(vec![("item".to_string(), Parsed::Text("milk".to_string()))])
+    );
+
+    let matches = expect_matches(parse(&spec, "todo", &args(&[])));
+    assert_eq!(*get(&matches, "command"), Parsed::Nil);
+
+    let error = parse(&spec, "todo", &args(&["remove"])).unwrap_err();
+    assert!(error.starts_with("Unknown command remove."));
+
+    match parse(&spec, "todo", &args(&["add", "--help"])) {
+      Ok(Outcome::Help(help)) => assert!(help.contains("Usage: todo add [options] [item]")),
+      outcome => panic!("Expected help found {:?}", outcome),
+    }
+  }
+
+  #[test]
+  fn help_text() {
+    assert_eq!(
+      help(&spec(), "greet"),
+      "Greet someone
+
+Usage: greet [options] <who> [rest]...
+
+Arguments:
+  <who>
+  [rest]...
+
+Options:
+  -v, --verbose         Print more
+  -c, --count <number>  (default: 1)
+      --tag <string>
+  -h, --help            Print this message"
+    );
+  }
+}
@@ -0,0 +1,519 @@
+use super::{
+  parser::{help, parse, Arg, ArgKind, Matches, Outcome, Parsed, Spec},
+  CLI_ERROR,
+};
+use crate::{
+  native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::Module,
+  object::{List, LyNative, Map, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{io::Write, path::Path};
+
+const PARSE_META: NativeMetaBuilder = NativeMetaBuilder::fun("parse", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("spec", ParameterKind::Map),
+    ParameterBuilder::new("args", ParameterKind::List),
+  ]);
+
+const HELP_META: NativeMetaBuilder = NativeMetaBuilder::fun("help", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("spec", ParameterKind::Map)]);
+
+const SPEC_KEYS: [&str; 6] = [
+  "name",
+  "about",
+  "flags",
+  "options",
+  "positionals",
+  "commands",
+];
+const FLAG_KEYS: [&str; 3] = ["name", "short", "help"];
+const OPTION_KEYS: [&str; 7] = [
+  "name", "short", "help", "type", "default", "required", "multiple",
+];
+const POSITIONAL_KEYS: [&str; 6] = ["name", "help", "type", "default", "required", "multiple"];
+
+pub fn declare_cli_module(hooks: &GcHooks, self_module: &mut Module) -> StdResult<()> {
+  let cli_error = val!(load_class_from_module(hooks, self_module, CLI_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(PARSE_META.name),
+    val!(Parse::native(hooks, cli_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(HELP_META.name),
+    val!(Help::native(hooks, cli_error)),
+  )
+}
+
+pub fn define_cli_module(_: &GcHooks, _: &mut Module) -> StdResult<()> {
+  Ok(())
+}
+
+/// The value of a string key in a map
+fn field(map: &Map<Value, Value>, name: &str) -> Option<Value> {
+  map
+    .iter()
+    .find(|(key, _)| key.is_obj_kind(ObjectKind::String) && &*key.to_obj().to_str() == name)
+    .map(|(_, value)| *value)
+}
+
+/// Check a map only has the expected keys, returning it if so
+fn read_map(value: Value, keys: &[&str], within: &str) -> Result<GcObj<Map<Value, Value>>, String> {
+  if !value.is_obj_kind(ObjectKind::Map) {
+    return Err(format!("Expected {} to be a map.", within));
+  }
+
+  let map = value.to_obj().to_map();
+  for key in map.keys() {
+    let known = key.is_obj_kind(ObjectKind::String) && keys.contains(&&*key.to_obj().to_str());
+    if !known {
+      return Err(format!(
+        "Unknown key {} in {}, expected one of {}.",
+        key,
+        within,
+        keys.join(", ")
+      ));
+    }
+  }
+
+  Ok(map)
+}
+
+/// The string at a key, if present
+fn read_string(map: &Map<Value, Value>, key: &str, within: &str) -> Result<Option<String>, String> {
+  match field(map, key) {
+    None => Ok(None),
+    Some(value) if value.is_nil() => Ok(None),
+    Some(value) if value.is_obj_kind(ObjectKind::String) => {
+      Ok(Some(String::from(&*value.to_obj().to_str())))
+    },
+    Some(_) => Err(format!("Expected {} of {} to be a string.", key, within)),
+  }
+}
+
+/// The boolean at a key, if present
+fn read_bool(map: &Map<Value, Value>, key: &str, within: &str) -> Result<Option<bool>, String> {
+  match field(map, key) {
+    None => Ok(None),
+    Some(value) if value.is_nil() => Ok(None),
+    Some(value) if value.is_bool() => Ok(Some(value.to_bool())),
+    Some(_) => Err(format!("Expected {} of {} to be a bool.", key, within)),
+  }
+}
+
+/// The list at a key, empty if not present
+fn read_list(map: &Map<Value, Value>, key: &str, within: &str) -> Result<Vec<Value>, String> {
+  match field(map, key) {
+    None => Ok(vec![]),
+    Some(value) if value.is_nil() => Ok(vec![]),
+    Some(value) if value.is_obj_kind(ObjectKind::List) => Ok(value.to_obj().to_list().to_vec()),
+    Some(_) => Err(format!("Expected {} of {} to be a list.", key, within)),
+  }
+}
+
+/// Read a declared flag, option or positional argument
+fn read_arg(value: Value, keys: &[&str], within: &str) -> Result<Arg, String> {
+  let map = read_map(value, keys, within)?;
+
+  let name = match read_string(&map, "name", within)? {
+    Some(name) if !name.is_empty() => name,
+    _ => return Err(format!("Expected a name for each of {}.", within)),
+  };
+  let within = format!("{} {}", within, name);
+
+  let kind = if keys == FLAG_KEYS {
+    ArgKind::Flag
+  } else {
+    match read_string(&map, "type", &within)?.as_deref() {
+      None | Some("string") => ArgKind::String,
+      Some("number") => ArgKind::Number,
+      Some(kind) => {
+        return Err(format!(
+          "Unknown type {} for {}, expected string or number.",
+          kind, within
+        ))
+      },
+    }
+  };
+
+  let short = match read_string(&map, "short", &within)? {
+    None => None,
+    Some(short) => {
+      let mut chars = short.chars();
+      match (chars.next(), chars.next()) {
+        (Some(short), None) if short != '-' => Some(short),
+        _ => {
+          return Err(format!(
+            "Expected short of {} to be a single character.",
+            within
+          ))
+        },
+      }
+    },
+  };
+
+  let default = field(&map, "default").filter(|default| !default.is_nil());
+  let positional = keys == POSITIONAL_KEYS;
+
+  Ok(Arg {
+    name,
+    short,
+    help: read_string(&map, "help", &within)?,
+    kind,
+    default,
+    required: read_bool(&map, "required", &within)?.unwrap_or(positional && default.is_none()),
+    multiple: read_bool(&map, "multiple", &within)?.unwrap_or(false),
+  })
+}
+
+/// Read the declaration of a program or one of its subcommands
+fn read_spec(value: Value, within: &str) -> Result<Spec, String> {
+  let map = read_map(value, &SPEC_KEYS, within)?;
+  let name = read_string(&map, "name", within)?;
+  let within = match &name {
+    Some(name) => format!("{} {}", within, name),
+    None => within.to_string(),
+  };
+
+  let mut options = vec![];
+  for flag in read_list(&map, "flags", &within)? {
+    options.push(read_arg(flag, &FLAG_KEYS, &format!("flags of {}", within))?);
+  }
+  for option in read_list(&map, "options", &within)? {
+    options.push(read_arg(
+      option,
+      &OPTION_KEYS,
+      &format!("options of {}", within),
+    )?);
+  }
+
+  let mut positionals = vec![];
+  for positional in read_list(&map, "positionals", &within)? {
+    positionals.push(read_arg(
+      positional,
+      &POSITIONAL_KEYS,
+      &format!("positionals of {}", within),
+    )?);
+  }
+
+  let mut commands = vec![];
+  for command in read_list(&map, "commands", &within)? {
+    let command = read_spec(command, &format!("commands of {}", within))?;
+    if command.name.is_empty() {
+      return Err(format!(
+        "Expected a name for each of commands of {}.",
+        within
+      ));
+    }
+    commands.push(command);
+  }
+
+  if let Some(positional) = positionals
+    .iter()
+    .rev()
+    .skip(1)
+    .find(|positional| positional.multiple)
+  {
+    return Err(format!(
+      "Only the last positional of {} can be multiple, found {}.",
+      within, positional.name
+    ));
+  }
+
+  // each name is a key in the parsed map so they can't be shared
+  let mut names: Vec<&str> = vec![];
+  let mut shorts: Vec<char> = vec![];
+  if !commands.is_empty() {
+    names.push("command");
+  }
+
+  for arg in options.iter().chain(&positionals) {
+    if names.contains(&arg.name.as_str()) {
+      return Err(format!("Duplicate name {} in {}.", arg.name, within));
+    }
+    names.push(&arg.name);
+
+    if let Some(short) = arg.short {
+      if shorts.contains(&short) {
+        return Err(format!("Duplicate short {} in {}.", short, within));
+      }
+      shorts.push(short);
+    }
+  }
+
+  for command in &commands {
+    if names.contains(&command.name.as_str()) {
+      return Err(format!("Duplicate name {} in {}.", command.name, within));
+    }
+    names.push(&command.name);
+  }
+
+  Ok(Spec {
+    name: name.unwrap_or_default(),
+    about: read_string(&map, "about", &within)?,
+    options,
+    positionals,
+    commands,
+  })
+}
+
+/// The name the program is run as, the spec's name if it
+/// has one otherwise the file name of the running script
+fn program_name(hooks: &mut Hooks, spec: &Spec) -> String {
+  if !spec.name.is_empty() {
+    return spec.name.clone();
+  }
+
+  hooks
+    .as_io()
+    .env()
+    .args()
+    .first()
+    .and_then(|script| Path::new(script).file_stem())
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_else(|| "script".to_string())
+}
+
+/// Convert the parsed matches into a map of each name to its value
+fn matches_to_map(hooks: &GcHooks, matches: &Matches) -> Value {
+  let mut map: GcObj<Map<Value, Value>> = hooks.manage_obj(Map::default());
+  hooks.push_root(map);
+
+  for (name, parsed) in matches {
+    let key = val!(hooks.manage_str(name));
+    hooks.push_root(key);
+
+    let value = parsed_to_value(hooks, parsed);
+    hooks.grow(&mut map, |map| map.insert(key, value));
+    hooks.pop_roots(1);
+  }
+
+  hooks.pop_roots(1);
+  val!(map)
+}
+
+/// Convert a parsed value into a laythe value
+fn parsed_to_value(hooks: &GcHooks, parsed: &Parsed) -> Value {
+  match parsed {
+    Parsed::Bool(bool) => val!(*bool),
+    Parsed::Text(text) => val!(hooks.manage_str(text)),
+    Parsed::Number(number) => val!(*number),
+    Parsed::Default(default) => *default,
+    Parsed::Nil => VALUE_NIL,
+    Parsed::Command(matches) => matches_to_map(hooks, matches),
+    Parsed::List(items) => {
+      let mut list: GcObj<List<Value>> = hooks.manage_obj(List::with_capacity(items.len()));
+      hooks.push_root(list);
+
+      for item in items {
+        let value = parsed_to_value(hooks, item);
+        hooks.grow(&mut list, |list| list.push(value));
+      }
+
+      hooks.pop_roots(1);
+      val!(list)
+    },
+  }
+}
+
+native_with_error!(Parse, PARSE_META);
+
+impl LyNative for Parse {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let spec = match read_spec(args[0], "spec") {
+      Ok(spec) => spec,
+      Err(err) => return self.call_error(hooks, err),
+    };
+
+    let arguments: Vec<String> = if args.len() > 1 {
+      let list = args[1].to_obj().to_list();
+      if !list.iter().all(|arg| arg.is_obj_kind(ObjectKind::String)) {
+        return self.call_error(hooks, "Expected each argument to be a string.");
+      }
+
+      list
+        .iter()
+        .map(|arg| String::from(&*arg.to_obj().to_str()))
+        .collect()
+    } else {
+      hooks.as_io().env().args().into_iter().skip(1).collect()
+    };
+
+    let program = program_name(hooks, &spec);
+    match parse(&spec, &program, &arguments) {
+      Ok(Outcome::Matches(matches)) => Call::Ok(matches_to_map(&hooks.as_gc(), &matches)),
+      Ok(Outcome::Help(help)) => {
+        let mut stdio = hooks.as_io().stdio();
+        match writeln!(stdio.stdout(), "{}", help) {
+          Ok(_) => Call::Exit(0),
+          Err(err) => self.call_error(hooks, err.to_string()),
+        }
+      },
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native_with_error!(Help, HELP_META);
+
+impl LyNative for Help {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    match read_spec(args[0], "spec") {
+      Ok(spec) => {
+        let program = program_name(hooks, &spec);
+        Call::Ok(val!(hooks.manage_str(help(&spec, &program))))
+      },
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  fn string(hooks: &GcHooks, string: &str) -> Value {
+    val!(hooks.manage_str(string))
+  }
+
+  fn map(hooks: &GcHooks, entries: &[(&str, Value)]) -> Value {
+    let mut map = Map::default();
+    for (key, value) in entries {
+      map.insert(string(hooks, key), *value);
+    }
+    val!(hooks.manage_obj(map))
+  }
+
+  fn list(hooks: &GcHooks, items: &[Value]) -> Value {
+    val!(hooks.manage_obj(List::from(items)))
+  }
+
+  mod parse {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let parse = Parse::native(&hooks, error);
+
+      assert_eq!(parse.meta().name, "parse");
+      assert_eq!(parse.meta().signature.arity, Arity::Default(1, 2));
+      assert_eq!(
+        parse.meta().signature.parameters[0].kind,
+        ParameterKind::Map
+      );
+      assert_eq!(
+        parse.meta().signature.parameters[1].kind,
+        ParameterKind::List
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let parse = Parse::native(&hooks.as_gc(), error);
+
+      let gc = hooks.as_gc();
+      let flag = map(
+        &gc,
+        &[("name", string(&gc, "loud")), ("short", string(&gc, "l"))],
+      );
+      let who = map(&gc, &[("name", string(&gc, "who"))]);
+      let spec = map(
+        &gc,
+        &[
+          ("name", string(&gc, "greet")),
+          ("flags", list(&gc, &[flag])),
+          ("positionals", list(&gc, &[who])),
+        ],
+      );
+      let args = list(&gc, &[string(&gc, "-l"), string(&gc, "ada")]);
+
+      let result = parse.call(&mut hooks, None, &[spec, args]).unwrap();
+      let result = result.to_obj().to_map();
+      assert_eq!(result.len(), 2);
+      assert_eq!(field(&result, "loud"), Some(val!(true)));
+      assert_eq!(field(&result, "who"), Some(string(&hooks.as_gc(), "ada")));
+    }
+  }
+
+  mod help {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let help = Help::native(&hooks, error);
+
+      assert_eq!(help.meta().name, "help");
+      assert_eq!(help.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(help.meta().signature.parameters[0].kind, ParameterKind::Map);
+    }
+  }
+
+  #[test]
+  fn spec_errors() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let spec = map(&hooks, &[("flag", VALUE_NIL)]);
+    assert!(read_spec(spec, "spec")
+      .unwrap_err()
+      .starts_with("Unknown key 'flag' in spec"));
+
+    let first = map(
+      &hooks,
+      &[("name", string(&hooks, "a")), ("multiple", val!(true))],
+    );
+    let second = map(&hooks, &[("name", string(&hooks, "b"))]);
+    let spec = map(&hooks, &[("positionals", list(&hooks, &[first, second]))]);
+    assert_eq!(
+      read_spec(spec, "spec").unwrap_err(),
+      "Only the last positional of spec can be multiple, found a."
+    );
+
+    let option = map(
+      &hooks,
+      &[
+        ("name", string(&hooks, "a")),
+        ("type", string(&hooks, "int")),
+      ],
+    );
+    let spec = map(&hooks, &[("options", list(&hooks, &[option]))]);
+    assert_eq!(
+      read_spec(spec, "spec").unwrap_err(),
+      "Unknown type int for options of spec a, expected string or number."
+    );
+
+    let flag = map(&hooks, &[("name", string(&hooks, "a"))]);
+    let spec = map(&hooks, &[("flags", list(&hooks, &[flag, flag]))]);
+    assert_eq!(
+      read_spec(spec, "spec").unwrap_err(),
+      "Duplicate name a in spec."
+    );
+  }
+}
@@ -0,0 +1,56 @@
+mod stream;
+mod utils;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use std::path::PathBuf;
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+
+use self::{
+  stream::{declare_stream_classes, define_stream_classes},
+  utils::declare_compress_funs,
+};
+
+const COMPRESS_PATH: &str = "std/compress";
+const COMPRESS_ERROR: &str = "CompressError";
+
+pub fn compress_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(COMPRESS_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let compress_error = default_error_inheritance(hooks, std, COMPRESS_ERROR)?;
+  export_and_insert(
+    hooks,
+    &mut module,
+    compress_error.name(),
+    val!(compress_error),
+  )?;
+
+  declare_compress_funs(hooks, &mut module, std)?;
+  declare_stream_classes(hooks, &mut module, std)?;
+
+  define_stream_classes(hooks, &module, std)?;
+
+  Ok(module)
+}
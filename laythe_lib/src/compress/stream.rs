@@ -0,0 +1,486 @@
+use super::{utils::Direction, COMPRESS_ERROR};
+use crate::{
+  create_error,
+  global::{bytes_data, create_bytes, input_bytes, BYTES_CLASS_NAME},
+  native_with_bytes,
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use flate2::{
+  write::{GzEncoder, MultiGzDecoder, ZlibEncoder},
+  Compression, Decompress, FlushDecompress, Status,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, Instance, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{any::Any, cell::RefCell, io::Write, mem};
+
+const COMPRESSOR_CLASS_NAME: &str = "Compressor";
+const DECOMPRESSOR_CLASS_NAME: &str = "Decompressor";
+
+const STREAM_FIELD_FORMAT: &str = "format";
+const STREAM_FIELD_STATE: &str = "state";
+
+const STREAM_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("format", ParameterKind::String)]);
+
+const STREAM_WRITE: NativeMetaBuilder = NativeMetaBuilder::method("write", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("chunk", ParameterKind::Any)]);
+
+const STREAM_FINISH: NativeMetaBuilder = NativeMetaBuilder::method("finish", Arity::Fixed(0));
+
+const STREAM_STATE: NativeMetaBuilder = NativeMetaBuilder::fun("state", Arity::Fixed(0));
+
+pub fn declare_stream_classes(
+  hooks: &GcHooks,
+  module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let compressor = default_class_inheritance(hooks, std, COMPRESSOR_CLASS_NAME)?;
+  export_and_insert(hooks, module, compressor.name(), val!(compressor))?;
+
+  let decompressor = default_class_inheritance(hooks, std, DECOMPRESSOR_CLASS_NAME)?;
+  export_and_insert(hooks, module, decompressor.name(), val!(decompressor))
+}
+
+pub fn define_stream_classes(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let bytes = load_class_from_package(hooks, std, STD, BYTES_CLASS_NAME)?;
+  let error = val!(load_class_from_module(hooks, module, COMPRESS_ERROR)?);
+
+  let classes = [
+    (COMPRESSOR_CLASS_NAME, Direction::Compress),
+    (DECOMPRESSOR_CLASS_NAME, Direction::Decompress),
+  ];
+
+  for (name, direction) in classes.iter() {
+    let mut class = load_class_from_module(hooks, module, name)?;
+    class.add_field(hooks, hooks.manage_str(STREAM_FIELD_FORMAT));
+    class.add_field(hooks, hooks.manage_str(STREAM_FIELD_STATE));
+
+    class.add_method(
+      hooks,
+      hooks.manage_str(STREAM_INIT.name),
+      val!(StreamInit::native(hooks, *direction, error)),
+    );
+
+    class.add_method(
+      hooks,
+      hooks.manage_str(STREAM_WRITE.name),
+      val!(StreamWrite::native(hooks, bytes, error)),
+    );
+
+    class.add_method(
+      hooks,
+      hooks.manage_str(STREAM_FINISH.name),
+      val!(StreamFinish::native(hooks, bytes, error)),
+    );
+  }
+
+  Ok(())
+}
+
+/// The container wrapped around a deflate stream
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+  Gzip,
+  Zlib,
+}
+
+impl Format {
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "gzip" => Some(Format::Gzip),
+      "zlib" => Some(Format::Zlib),
+      _ => None,
+    }
+  }
+}
+
+/// Compresses data into a gzip or zlib stream as it arrives
+#[derive(Debug)]
+pub enum Compressor {
+  Gzip(GzEncoder<Vec<u8>>),
+  Zlib(ZlibEncoder<Vec<u8>>),
+  Finished,
+}
+
+impl Compressor {
+  pub fn new(format: Format) -> Self {
+    match format {
+      Format::Gzip => Compressor::Gzip(GzEncoder::new(vec![], Compression::default())),
+      Format::Zlib => Compressor::Zlib(ZlibEncoder::new(vec![], Compression::default())),
+    }
+  }
+
+  /// Add more data, returning any compressed data that is ready
+  pub fn write(&mut self, data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let written = match self {
+      Compressor::Gzip(encoder) => encoder
+        .write_all(data)
+        .map(|()| mem::take(encoder.get_mut())),
+      Compressor::Zlib(encoder) => encoder
+        .write_all(data)
+        .map(|()| mem::take(encoder.get_mut())),
+      Compressor::Finished => return Err("Compressor has already finished."),
+    };
+
+    written.map_err(|_| "Unable to compress data.")
+  }
+
+  /// Compress any remaining data and close the stream
+  pub fn finish(&mut self) -> Result<Vec<u8>, &'static str> {
+    let finished = match mem::replace(self, Compressor::Finished) {
+      Compressor::Gzip(encoder) => encoder.finish(),
+      Compressor::Zlib(encoder) => encoder.finish(),
+      Compressor::Finished => return Err("Compressor has already finished."),
+    };
+
+    finished.map_err(|_| "Unable to compress data.")
+  }
+}
+
+/// Decompresses a gzip or zlib stream as it arrives. Gzip streams made
+/// of several members are decompressed one after the other
+#[derive(Debug)]
+pub enum Decompressor {
+  Gzip(MultiGzDecoder<Vec<u8>>),
+  Zlib { inflater: Decompress, ended: bool },
+  Finished,
+}
+
+impl Decompressor {
+  pub fn new(format: Format) -> Self {
+    match format {
+      Format::Gzip => Decompressor::Gzip(MultiGzDecoder::new(vec![])),
+      Format::Zlib => Decompressor::Zlib {
+        inflater: Decompress::new(true),
+        ended: false,
+      },
+    }
+  }
+
+  /// Add more compressed data, returning any newly decompressed data
+  pub fn write(&mut self, data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    match self {
+      Decompressor::Gzip(decoder) => decoder
+        .write_all(data)
+        .map(|()| mem::take(decoder.get_mut()))
+        .map_err(|_| "Invalid gzip data."),
+      Decompressor::Zlib { inflater, ended } => inflate(inflater, ended, data),
+      Decompressor::Finished => Err("Decompressor has already finished."),
+    }
+  }
+
+  /// Check the whole stream has been decompressed
+  pub fn finish(&mut self) -> Result<Vec<u8>, &'static str> {
+    match mem::replace(self, Decompressor::Finished) {
+      // the gzip trailer is only checked once the stream is finished
+      Decompressor::Gzip(decoder) => decoder
+        .finish()
+        .map_err(|_| "Unexpected end of compressed data or mismatched checksum."),
+      Decompressor::Zlib { ended: true, .. } => Ok(vec![]),
+      Decompressor::Zlib { ended: false, .. } => Err("Unexpected end of compressed data."),
+      Decompressor::Finished => Err("Decompressor has already finished."),
+    }
+  }
+}
+
+/// Inflate a chunk of a zlib stream, noting when the stream has ended
+fn inflate(
+  inflater: &mut Decompress,
+  ended: &mut bool,
+  mut data: &[u8],
+) -> Result<Vec<u8>, &'static str> {
+  let mut out = Vec::with_capacity(data.len() * 2);
+
+  loop {
+    if *ended {
+      if data.is_empty() {
+        return Ok(out);
+      }
+      return Err("Unexpected data after the end of the compressed stream.");
+    }
+
+    out.reserve(data.len().max(1024));
+    let read = inflater.total_in();
+    let status = inflater
+      .decompress_vec(data, &mut out, FlushDecompress::None)
+      .map_err(|_| "Invalid zlib data.")?;
+    data = &data[(inflater.total_in() - read) as usize..];
+
+    match status {
+      Status::StreamEnd => *ended = true,
+      // without more input or a full buffer nothing more can be inflated
+      Status::Ok | Status::BufError if data.is_empty() && out.len() < out.capacity() => {
+        return Ok(out)
+      },
+      Status::Ok | Status::BufError => (),
+    }
+  }
+}
+
+/// Compress data in one go
+pub fn compress(format: Format, data: &[u8]) -> Vec<u8> {
+  let mut compressor = Compressor::new(format);
+  let mut out = compressor.write(data).expect("Expected new compressor");
+  out.extend(compressor.finish().expect("Expected unfinished compressor"));
+  out
+}
+
+/// Decompress a whole stream in one go
+pub fn decompress(format: Format, data: &[u8]) -> Result<Vec<u8>, &'static str> {
+  let mut decompressor = Decompressor::new(format);
+  let mut out = decompressor.write(data)?;
+  out.extend(decompressor.finish()?);
+  Ok(out)
+}
+
+/// The compressor or decompressor behind a stream instance
+#[derive(Debug)]
+enum Stream {
+  Compress(Compressor),
+  Decompress(Decompressor),
+}
+
+/// The state of a Compressor or Decompressor instance
+#[derive(Debug)]
+pub struct StreamState {
+  stream: RefCell<Stream>,
+}
+
+impl StreamState {
+  fn native(hooks: &GcHooks, stream: Stream) -> GcObj<Native> {
+    let native = Box::new(Self {
+      stream: RefCell::new(stream),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(STREAM_STATE.to_meta(hooks), native))
+  }
+}
+
+impl Trace for StreamState {}
+
+impl LyNative for StreamState {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+/// Run an action against the stream held by an instance
+fn with_stream<R>(instance: GcObj<Instance>, action: impl FnOnce(&mut Stream) -> R) -> Option<R> {
+  let state = instance[1];
+  if !state.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  let native = state.to_obj().to_native();
+  native
+    .downcast_ref::<StreamState>()
+    .map(|state| action(&mut state.stream.borrow_mut()))
+}
+
+/// Initialize a Compressor or Decompressor with a format of gzip or zlib
+#[derive(Debug)]
+pub struct StreamInit {
+  direction: Direction,
+  error: Value,
+}
+
+impl StreamInit {
+  fn native(hooks: &GcHooks, direction: Direction, error: Value) -> GcObj<Native> {
+    let native = Box::new(Self { direction, error }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(STREAM_INIT.to_meta(hooks), native))
+  }
+}
+
+impl Trace for StreamInit {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for StreamInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let name = match args.first() {
+      Some(name) => name.to_obj().to_str(),
+      None => hooks.manage_str("gzip"),
+    };
+
+    let format = match Format::from_name(&name) {
+      Some(format) => format,
+      None => {
+        return create_error!(
+          self.error,
+          hooks,
+          format!(
+            "Unknown compression format {}. Expected gzip or zlib.",
+            &*name
+          )
+        )
+      },
+    };
+
+    let stream = match self.direction {
+      Direction::Compress => Stream::Compress(Compressor::new(format)),
+      Direction::Decompress => Stream::Decompress(Decompressor::new(format)),
+    };
+
+    hooks.push_root(name);
+    let state = StreamState::native(&hooks.as_gc(), stream);
+    hooks.pop_roots(1);
+
+    this[0] = val!(name);
+    this[1] = val!(state);
+
+    Call::Ok(val!(this))
+  }
+}
+
+native_with_bytes!(StreamWrite, STREAM_WRITE);
+
+impl LyNative for StreamWrite {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let instance = this.unwrap().to_obj().to_instance();
+
+    let result = with_stream(instance, |stream| match stream {
+      Stream::Compress(compressor) => match input_bytes(args[0]) {
+        Some(chunk) => compressor.write(&chunk),
+        None => Err("Can only compress a String or Bytes."),
+      },
+      Stream::Decompress(decompressor) => match bytes_data(args[0]) {
+        Some(chunk) => decompressor.write(&chunk),
+        None => Err("Can only decompress Bytes."),
+      },
+    });
+
+    match result {
+      Some(Ok(data)) => Call::Ok(val!(create_bytes(&hooks.as_gc(), self.bytes, &data))),
+      Some(Err(message)) => create_error!(self.error, hooks, message),
+      None => create_error!(self.error, hooks, "Stream was not initialized."),
+    }
+  }
+}
+
+native_with_bytes!(StreamFinish, STREAM_FINISH);
+
+impl LyNative for StreamFinish {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let instance = this.unwrap().to_obj().to_instance();
+
+    let result = with_stream(instance, |stream| match stream {
+      Stream::Compress(compressor) => compressor.finish(),
+      Stream::Decompress(decompressor) => decompressor.finish(),
+    });
+
+    match result {
+      Some(Ok(data)) => Call::Ok(val!(create_bytes(&hooks.as_gc(), self.bytes, &data))),
+      Some(Err(message)) => create_error!(self.error, hooks, message),
+      None => create_error!(self.error, hooks, "Stream was not initialized."),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn zlib_known() {
+    // zlib.compress(b"abc") from python's zlib
+    let known = [
+      0x78, 0x9c, 0x4b, 0x4c, 0x4a, 0x06, 0x00, 0x02, 0x4d, 0x01, 0x27,
+    ];
+    assert_eq!(decompress(Format::Zlib, &known).unwrap(), b"abc");
+    assert_eq!(compress(Format::Zlib, b"abc"), known);
+  }
+
+  #[test]
+  fn gzip_round_trip() {
+    let data = b"some log line\nanother log line\nsome log line\n".repeat(50);
+    let compressed = compress(Format::Gzip, &data);
+
+    assert_eq!(compressed[..2], [0x1f, 0x8b]);
+    assert!(compressed.len() < data.len());
+    assert_eq!(decompress(Format::Gzip, &compressed).unwrap(), data);
+  }
+
+  #[test]
+  fn gzip_members() {
+    let mut compressed = compress(Format::Gzip, b"first ");
+    compressed.extend(compress(Format::Gzip, b"second"));
+
+    assert_eq!(
+      decompress(Format::Gzip, &compressed).unwrap(),
+      b"first second"
+    );
+  }
+
+  #[test]
+  fn streaming() {
+    let data = b"0123456789".repeat(20000);
+
+    for format in [Format::Gzip, Format::Zlib].iter() {
+      let compressed = compress(*format, &data);
+
+      let mut decompressor = Decompressor::new(*format);
+      let mut out = vec![];
+      for chunk in compressed.chunks(3) {
+        out.extend(decompressor.write(chunk).unwrap());
+      }
+      out.extend(decompressor.finish().unwrap());
+
+      assert_eq!(out, data);
+    }
+  }
+
+  #[test]
+  fn errors() {
+    let zipped = compress(Format::Gzip, b"hello");
+    assert!(decompress(Format::Gzip, &zipped[..zipped.len() - 1]).is_err());
+    assert!(decompress(Format::Gzip, b"not gzipped").is_err());
+
+    let deflated = compress(Format::Zlib, b"hello");
+    assert_eq!(
+      decompress(Format::Zlib, &deflated[..deflated.len() - 1]),
+      Err("Unexpected end of compressed data.")
+    );
+
+    let mut trailing = deflated.clone();
+    trailing.push(0);
+    assert_eq!(
+      decompress(Format::Zlib, &trailing),
+      Err("Unexpected data after the end of the compressed stream.")
+    );
+    assert_eq!(decompress(Format::Zlib, &zipped), Err("Invalid zlib data."));
+
+    let mut compressor = Compressor::new(Format::Zlib);
+    compressor.finish().unwrap();
+    assert_eq!(
+      compressor.write(b"late"),
+      Err("Compressor has already finished.")
+    );
+  }
+}
@@ -0,0 +1,174 @@
+use super::{
+  stream::{compress, decompress, Format},
+  COMPRESS_ERROR,
+};
+use crate::{
+  create_error,
+  global::{bytes_data, create_bytes, input_bytes, BYTES_CLASS_NAME},
+  support::{export_and_insert, load_class_from_module, load_class_from_package},
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use std::io::Write;
+
+const GZIP_META: NativeMetaBuilder = NativeMetaBuilder::fun("gzip", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const GUNZIP_META: NativeMetaBuilder = NativeMetaBuilder::fun("gunzip", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("bytes", ParameterKind::Instance)]);
+
+const DEFLATE_META: NativeMetaBuilder = NativeMetaBuilder::fun("deflate", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const INFLATE_META: NativeMetaBuilder = NativeMetaBuilder::fun("inflate", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("bytes", ParameterKind::Instance)]);
+
+pub fn declare_compress_funs(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let bytes = load_class_from_package(hooks, std, STD, BYTES_CLASS_NAME)?;
+  let error = val!(load_class_from_module(hooks, module, COMPRESS_ERROR)?);
+
+  let funs = [
+    (&GZIP_META, Format::Gzip, Direction::Compress),
+    (&GUNZIP_META, Format::Gzip, Direction::Decompress),
+    (&DEFLATE_META, Format::Zlib, Direction::Compress),
+    (&INFLATE_META, Format::Zlib, Direction::Decompress),
+  ];
+
+  for (meta, format, direction) in funs.iter() {
+    export_and_insert(
+      hooks,
+      module,
+      hooks.manage_str(meta.name),
+      val!(CompressFun::native(
+        hooks, meta, *format, *direction, bytes, error
+      )),
+    )?;
+  }
+
+  Ok(())
+}
+
+/// Whether a native compresses or decompresses its input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Direction {
+  Compress,
+  Decompress,
+}
+
+/// Compress or decompress a string or Bytes in one go
+#[derive(Debug)]
+pub struct CompressFun {
+  format: Format,
+  direction: Direction,
+  bytes: GcObj<Class>,
+  error: Value,
+}
+
+impl CompressFun {
+  fn native(
+    hooks: &GcHooks,
+    meta: &NativeMetaBuilder,
+    format: Format,
+    direction: Direction,
+    bytes: GcObj<Class>,
+    error: Value,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self {
+      format,
+      direction,
+      bytes,
+      error,
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+}
+
+impl Trace for CompressFun {
+  fn trace(&self) {
+    self.bytes.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.bytes.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.bytes.trace_debug(stdio);
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for CompressFun {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let result = match self.direction {
+      Direction::Compress => match input_bytes(args[0]) {
+        Some(data) => Ok(compress(self.format, &data)),
+        None => return create_error!(self.error, hooks, "Can only compress a String or Bytes."),
+      },
+      Direction::Decompress => match bytes_data(args[0]) {
+        Some(data) => decompress(self.format, &data),
+        None => return create_error!(self.error, hooks, "Can only decompress Bytes."),
+      },
+    };
+
+    match result {
+      Ok(data) => Call::Ok(val!(create_bytes(&hooks.as_gc(), self.bytes, &data))),
+      Err(message) => create_error!(self.error, hooks, message),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let error = val!(test_error_class(&hooks));
+    let bytes = test_error_class(&hooks);
+
+    let gzip = CompressFun::native(
+      &hooks,
+      &GZIP_META,
+      Format::Gzip,
+      Direction::Compress,
+      bytes,
+      error,
+    );
+
+    assert_eq!(gzip.meta().name, "gzip");
+    assert_eq!(gzip.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(gzip.meta().signature.parameters[0].kind, ParameterKind::Any);
+
+    let inflate = CompressFun::native(
+      &hooks,
+      &INFLATE_META,
+      Format::Zlib,
+      Direction::Decompress,
+      bytes,
+      error,
+    );
+
+    assert_eq!(inflate.meta().name, "inflate");
+    assert_eq!(inflate.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(
+      inflate.meta().signature.parameters[0].kind,
+      ParameterKind::Instance
+    );
+  }
+}
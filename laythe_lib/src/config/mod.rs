@@ -0,0 +1,44 @@
+mod utils;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use std::path::PathBuf;
+use utils::{declare_config_module, define_config_module};
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+
+const CONFIG_PATH: &str = "std/config";
+const CONFIG_ERROR: &str = "ConfigError";
+
+pub fn config_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(CONFIG_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let config_error = default_error_inheritance(hooks, std, CONFIG_ERROR)?;
+  export_and_insert(hooks, &mut module, config_error.name(), val!(config_error))?;
+
+  declare_config_module(hooks, &mut module)?;
+  define_config_module(hooks, &mut module)?;
+
+  Ok(module)
+}
@@ -0,0 +1,210 @@
+use super::CONFIG_ERROR;
+use crate::{
+  native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::Module,
+  object::{List, LyNative, Map, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::io::Write;
+use yaml_rust::{Yaml, YamlLoader};
+
+const PARSE_TOML_META: NativeMetaBuilder = NativeMetaBuilder::fun("parseToml", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
+const PARSE_YAML_META: NativeMetaBuilder = NativeMetaBuilder::fun("parseYaml", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
+pub fn declare_config_module(hooks: &GcHooks, self_module: &mut Module) -> StdResult<()> {
+  let config_error = val!(load_class_from_module(hooks, self_module, CONFIG_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(PARSE_TOML_META.name),
+    val!(ParseToml::native(hooks, config_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(PARSE_YAML_META.name),
+    val!(ParseYaml::native(hooks, config_error)),
+  )
+}
+
+pub fn define_config_module(_: &GcHooks, _: &mut Module) -> StdResult<()> {
+  Ok(())
+}
+
+/// Build a list from some items, converting each item as it's pushed
+fn to_list<T>(hooks: &GcHooks, items: &[T], convert: fn(&GcHooks, &T) -> Value) -> Value {
+  let mut list: GcObj<List<Value>> = hooks.manage_obj(List::with_capacity(items.len()));
+  hooks.push_root(list);
+
+  for item in items {
+    let value = convert(hooks, item);
+    hooks.grow(&mut list, |list| list.push(value));
+  }
+
+  hooks.pop_roots(1);
+  val!(list)
+}
+
+/// Convert a parsed toml value into nested maps and lists. Dates
+/// and times are kept as the strings they were written as
+fn toml_to_value(hooks: &GcHooks, toml: &toml::Value) -> Value {
+  match toml {
+    toml::Value::String(string) => val!(hooks.manage_str(string)),
+    toml::Value::Integer(integer) => val!(*integer as f64),
+    toml::Value::Float(float) => val!(*float),
+    toml::Value::Boolean(boolean) => val!(*boolean),
+    toml::Value::Datetime(date_time) => val!(hooks.manage_str(date_time.to_string())),
+    toml::Value::Array(items) => to_list(hooks, items, toml_to_value),
+    toml::Value::Table(table) => {
+      let mut map: GcObj<Map<Value, Value>> = hooks.manage_obj(Map::default());
+      hooks.push_root(map);
+
+      for (key, item) in table {
+        let key = val!(hooks.manage_str(key));
+        hooks.push_root(key);
+
+        let value = toml_to_value(hooks, item);
+        hooks.grow(&mut map, |map| map.insert(key, value));
+        hooks.pop_roots(1);
+      }
+
+      hooks.pop_roots(1);
+      val!(map)
+    },
+  }
+}
+
+/// Convert a parsed yaml document into nested maps and lists
+fn yaml_to_value(hooks: &GcHooks, yaml: &Yaml) -> Value {
+  match yaml {
+    Yaml::String(string) => val!(hooks.manage_str(string)),
+    Yaml::Integer(integer) => val!(*integer as f64),
+    Yaml::Real(_) => yaml.as_f64().map_or(VALUE_NIL, |real| val!(real)),
+    Yaml::Boolean(boolean) => val!(*boolean),
+    Yaml::Array(items) => to_list(hooks, items, yaml_to_value),
+    Yaml::Hash(hash) => {
+      let mut map: GcObj<Map<Value, Value>> = hooks.manage_obj(Map::default());
+      hooks.push_root(map);
+
+      for (key, item) in hash {
+        let key = yaml_to_value(hooks, key);
+        hooks.push_root(key);
+
+        let value = yaml_to_value(hooks, item);
+        hooks.grow(&mut map, |map| map.insert(key, value));
+        hooks.pop_roots(1);
+      }
+
+      hooks.pop_roots(1);
+      val!(map)
+    },
+    Yaml::Null | Yaml::Alias(_) | Yaml::BadValue => VALUE_NIL,
+  }
+}
+
+native_with_error!(ParseToml, PARSE_TOML_META);
+
+impl LyNative for ParseToml {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let string = args[0].to_obj().to_str();
+
+    match string.parse::<toml::Value>() {
+      Ok(toml) => Call::Ok(toml_to_value(&hooks.as_gc(), &toml)),
+      Err(err) => self.call_error(hooks, format!("Invalid TOML, {}.", err)),
+    }
+  }
+}
+
+native_with_error!(ParseYaml, PARSE_YAML_META);
+
+impl LyNative for ParseYaml {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let string = args[0].to_obj().to_str();
+
+    match YamlLoader::load_from_str(&string) {
+      Ok(documents) => match documents.first() {
+        Some(document) => Call::Ok(yaml_to_value(&hooks.as_gc(), document)),
+        None => Call::Ok(VALUE_NIL),
+      },
+      Err(err) => self.call_error(hooks, format!("Invalid YAML, {}.", err)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::MockedContext;
+
+  #[test]
+  fn toml_table() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let toml: toml::Value = "name = \"laythe\"\n[server]\nport = 8080\nhosts = [\"a\", \"b\"]"
+      .parse()
+      .unwrap();
+    let value = toml_to_value(&hooks, &toml);
+
+    assert!(value.is_obj_kind(ObjectKind::Map));
+    let map = value.to_obj().to_map();
+    assert_eq!(map.len(), 2);
+
+    let server = map
+      .get(&val!(hooks.manage_str("server")))
+      .unwrap()
+      .to_obj()
+      .to_map();
+    assert_eq!(
+      *server.get(&val!(hooks.manage_str("port"))).unwrap(),
+      val!(8080.0)
+    );
+    assert_eq!(
+      server
+        .get(&val!(hooks.manage_str("hosts")))
+        .unwrap()
+        .to_obj()
+        .to_list()
+        .len(),
+      2
+    );
+  }
+
+  #[test]
+  fn yaml_document() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let documents = YamlLoader::load_from_str("items:\n  - 1\n  - 2.5\n  - ~\nflag: true").unwrap();
+    let value = yaml_to_value(&hooks, &documents[0]);
+
+    let map = value.to_obj().to_map();
+    assert_eq!(
+      *map.get(&val!(hooks.manage_str("flag"))).unwrap(),
+      val!(true)
+    );
+
+    let items = map
+      .get(&val!(hooks.manage_str("items")))
+      .unwrap()
+      .to_obj()
+      .to_list();
+    assert_eq!(items[0], val!(1.0));
+    assert_eq!(items[1], val!(2.5));
+    assert_eq!(items[2], VALUE_NIL);
+  }
+}
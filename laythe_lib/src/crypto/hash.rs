@@ -0,0 +1,413 @@
+use super::{CRYPTO_ERROR, CRYPTO_MODULE_PATH};
+use crate::{
+  create_error,
+  global::{input_bytes, MODULE_CLASS_NAME},
+  native_with_error,
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{Gc, GcObj, Trace},
+  module::{Module, Package},
+  object::{Instance, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  utils::IdEmitter,
+  val,
+  value::Value,
+  Call,
+};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::{any::Any, cell::RefCell, io::Write, path::PathBuf};
+
+const HASH_PATH: &str = "std/crypto/hash";
+
+const HASHER_CLASS_NAME: &str = "Hasher";
+const HASHER_FIELD_ALGORITHM: &str = "algorithm";
+const HASHER_FIELD_STATE: &str = "state";
+
+const SHA256_META: NativeMetaBuilder = NativeMetaBuilder::fun("sha256", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const SHA1_META: NativeMetaBuilder = NativeMetaBuilder::fun("sha1", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const MD5_META: NativeMetaBuilder = NativeMetaBuilder::fun("md5", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const CRC32_META: NativeMetaBuilder = NativeMetaBuilder::fun("crc32", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const HASHER_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("algorithm", ParameterKind::String)]);
+
+const HASHER_UPDATE: NativeMetaBuilder = NativeMetaBuilder::method("update", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const HASHER_DIGEST: NativeMetaBuilder = NativeMetaBuilder::method("digest", Arity::Fixed(0));
+
+const HASHER_STATE: NativeMetaBuilder = NativeMetaBuilder::fun("state", Arity::Fixed(0));
+
+pub fn hash_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(HASH_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  declare_hash_module(hooks, &mut module, std)?;
+  define_hash_module(hooks, &module, std)?;
+
+  Ok(module)
+}
+
+fn declare_hash_module(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let error = val!(load_class_from_package(
+    hooks,
+    std,
+    CRYPTO_MODULE_PATH,
+    CRYPTO_ERROR
+  )?);
+
+  let funs = [
+    (&SHA256_META, Algorithm::Sha256),
+    (&SHA1_META, Algorithm::Sha1),
+    (&MD5_META, Algorithm::Md5),
+    (&CRC32_META, Algorithm::Crc32),
+  ];
+
+  for (meta, algorithm) in funs.iter() {
+    export_and_insert(
+      hooks,
+      module,
+      hooks.manage_str(meta.name),
+      val!(HashFun::native(hooks, meta, *algorithm, error)),
+    )?;
+  }
+
+  let class = default_class_inheritance(hooks, std, HASHER_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+fn define_hash_module(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, HASHER_CLASS_NAME)?;
+  let error = val!(load_class_from_package(
+    hooks,
+    std,
+    CRYPTO_MODULE_PATH,
+    CRYPTO_ERROR
+  )?);
+
+  class.add_field(hooks, hooks.manage_str(HASHER_FIELD_ALGORITHM));
+  class.add_field(hooks, hooks.manage_str(HASHER_FIELD_STATE));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(HASHER_INIT.name),
+    val!(HasherInit::native(hooks, error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(HASHER_UPDATE.name),
+    val!(HasherUpdate::native(hooks, error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(HASHER_DIGEST.name),
+    val!(HasherDigest::native(hooks, error)),
+  );
+
+  Ok(())
+}
+
+/// The hashing algorithms available
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Algorithm {
+  Sha256,
+  Sha1,
+  Md5,
+  Crc32,
+}
+
+impl Algorithm {
+  fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "sha256" => Some(Algorithm::Sha256),
+      "sha1" => Some(Algorithm::Sha1),
+      "md5" => Some(Algorithm::Md5),
+      "crc32" => Some(Algorithm::Crc32),
+      _ => None,
+    }
+  }
+}
+
+/// The running state of one of the hashing algorithms
+#[derive(Clone)]
+enum HashState {
+  Sha256(Sha256),
+  Sha1(Sha1),
+  Md5(Md5),
+  Crc32(crc32fast::Hasher),
+}
+
+impl HashState {
+  fn new(algorithm: Algorithm) -> Self {
+    match algorithm {
+      Algorithm::Sha256 => HashState::Sha256(Sha256::new()),
+      Algorithm::Sha1 => HashState::Sha1(Sha1::new()),
+      Algorithm::Md5 => HashState::Md5(Md5::new()),
+      Algorithm::Crc32 => HashState::Crc32(crc32fast::Hasher::new()),
+    }
+  }
+
+  fn update(&mut self, data: &[u8]) {
+    match self {
+      HashState::Sha256(hasher) => hasher.update(data),
+      HashState::Sha1(hasher) => hasher.update(data),
+      HashState::Md5(hasher) => hasher.update(data),
+      HashState::Crc32(hasher) => hasher.update(data),
+    }
+  }
+
+  /// The hex digest of everything hashed so far. The state is left
+  /// untouched so more data can still be added
+  fn hex_digest(&self) -> String {
+    match self.clone() {
+      HashState::Sha256(hasher) => hex::encode(hasher.finalize()),
+      HashState::Sha1(hasher) => hex::encode(hasher.finalize()),
+      HashState::Md5(hasher) => hex::encode(hasher.finalize()),
+      HashState::Crc32(hasher) => format!("{:08x}", hasher.finalize()),
+    }
+  }
+}
+
+impl std::fmt::Debug for HashState {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      HashState::Sha256(_) => write!(f, "HashState::Sha256"),
+      HashState::Sha1(_) => write!(f, "HashState::Sha1"),
+      HashState::Md5(_) => write!(f, "HashState::Md5"),
+      HashState::Crc32(_) => write!(f, "HashState::Crc32"),
+    }
+  }
+}
+
+/// Hash a string or Bytes in one go with one of the algorithms
+#[derive(Debug)]
+pub struct HashFun {
+  algorithm: Algorithm,
+  error: Value,
+}
+
+impl HashFun {
+  fn native(
+    hooks: &GcHooks,
+    meta: &NativeMetaBuilder,
+    algorithm: Algorithm,
+    error: Value,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self { algorithm, error }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+}
+
+impl Trace for HashFun {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for HashFun {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let data = match input_bytes(args[0]) {
+      Some(data) => data,
+      None => return create_error!(self.error, hooks, "Can only hash a String or Bytes."),
+    };
+
+    let mut state = HashState::new(self.algorithm);
+    state.update(&data);
+
+    Call::Ok(val!(hooks.manage_str(state.hex_digest())))
+  }
+}
+
+/// The state of a Hasher instance. Calling it returns the current digest
+#[derive(Debug)]
+pub struct HasherState {
+  state: RefCell<HashState>,
+}
+
+impl HasherState {
+  fn native(hooks: &GcHooks, state: HashState) -> GcObj<Native> {
+    let native = Box::new(Self {
+      state: RefCell::new(state),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(HASHER_STATE.to_meta(hooks), native))
+  }
+}
+
+impl Trace for HasherState {}
+
+impl LyNative for HasherState {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    let digest = self.state.borrow().hex_digest();
+    Call::Ok(val!(hooks.manage_str(digest)))
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+/// Run an action against the state held by a Hasher instance
+fn with_state<R>(instance: GcObj<Instance>, action: impl FnOnce(&mut HashState) -> R) -> Option<R> {
+  let state = instance[1];
+  if !state.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  let native = state.to_obj().to_native();
+  native
+    .downcast_ref::<HasherState>()
+    .map(|state| action(&mut state.state.borrow_mut()))
+}
+
+native_with_error!(HasherInit, HASHER_INIT);
+
+impl LyNative for HasherInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+    let name = args[0].to_obj().to_str();
+
+    let algorithm = match Algorithm::from_name(&name) {
+      Some(algorithm) => algorithm,
+      None => {
+        return self.call_error(
+          hooks,
+          format!(
+            "Unknown hash algorithm {}. Expected one of sha256, sha1, md5 or crc32.",
+            &*name
+          ),
+        )
+      },
+    };
+
+    this[0] = args[0];
+    this[1] = val!(HasherState::native(
+      &hooks.as_gc(),
+      HashState::new(algorithm)
+    ));
+
+    Call::Ok(val!(this))
+  }
+}
+
+native_with_error!(HasherUpdate, HASHER_UPDATE);
+
+impl LyNative for HasherUpdate {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let instance = this.unwrap().to_obj().to_instance();
+
+    let data = match input_bytes(args[0]) {
+      Some(data) => data,
+      None => return self.call_error(hooks, "Can only hash a String or Bytes."),
+    };
+
+    match with_state(instance, |state| state.update(&data)) {
+      Some(()) => Call::Ok(this.unwrap()),
+      None => self.call_error(hooks, "Hasher was not initialized."),
+    }
+  }
+}
+
+native_with_error!(HasherDigest, HASHER_DIGEST);
+
+impl LyNative for HasherDigest {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let instance = this.unwrap().to_obj().to_instance();
+
+    match with_state(instance, |state| state.hex_digest()) {
+      Some(digest) => Call::Ok(val!(hooks.manage_str(digest))),
+      None => self.call_error(hooks, "Hasher was not initialized."),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn digest(algorithm: Algorithm, chunks: &[&str]) -> String {
+    let mut state = HashState::new(algorithm);
+    for chunk in chunks {
+      state.update(chunk.as_bytes());
+    }
+    state.hex_digest()
+  }
+
+  #[test]
+  fn known_digests() {
+    assert_eq!(
+      digest(Algorithm::Sha256, &["abc"]),
+      "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+      digest(Algorithm::Sha1, &["abc"]),
+      "a9993e364706816aba3e25717850c26c9cd0d89d"
+    );
+    assert_eq!(
+      digest(Algorithm::Md5, &["abc"]),
+      "900150983cd24fb0d6963f7d28e17f72"
+    );
+    assert_eq!(digest(Algorithm::Crc32, &["abc"]), "352441c2");
+  }
+
+  #[test]
+  fn incremental() {
+    for algorithm in [
+      Algorithm::Sha256,
+      Algorithm::Sha1,
+      Algorithm::Md5,
+      Algorithm::Crc32,
+    ]
+    .iter()
+    {
+      assert_eq!(
+        digest(*algorithm, &["hello", " ", "world"]),
+        digest(*algorithm, &["hello world"])
+      );
+    }
+  }
+
+  #[test]
+  fn digest_keeps_state() {
+    let mut state = HashState::new(Algorithm::Sha256);
+    state.update(b"ab");
+    state.hex_digest();
+    state.update(b"c");
+
+    assert_eq!(state.hex_digest(), digest(Algorithm::Sha256, &["abc"]));
+  }
+}
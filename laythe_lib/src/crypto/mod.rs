@@ -0,0 +1,53 @@
+mod hash;
+
+use hash::hash_module;
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use std::path::PathBuf;
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdError, StdResult, STD,
+};
+
+pub const CRYPTO_MODULE_PATH: &str = "std/crypto";
+pub const CRYPTO_ERROR: &str = "CryptoError";
+
+pub fn add_crypto_package(
+  hooks: &GcHooks,
+  std: &mut Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<()> {
+  let mut root = std.root_module();
+
+  let mut crypto_module = crypto_module(hooks, std, emitter)?;
+  root.insert_module(hooks, crypto_module)?;
+
+  let hash = hash_module(hooks, std, emitter)?;
+  crypto_module
+    .insert_module(hooks, hash)
+    .map_err(StdError::from)
+}
+
+fn crypto_module(hooks: &GcHooks, std: &Package, emitter: &mut IdEmitter) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(CRYPTO_MODULE_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let crypto_error = default_error_inheritance(hooks, std, CRYPTO_ERROR)?;
+  export_and_insert(hooks, &mut module, crypto_error.name(), val!(crypto_error))?;
+
+  Ok(module)
+}
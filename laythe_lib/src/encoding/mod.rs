@@ -0,0 +1,66 @@
+mod stream;
+mod utils;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use std::path::PathBuf;
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+
+use self::{
+  stream::{declare_stream_classes, define_stream_classes},
+  utils::{declare_encoding_funs, define_encoding_funs},
+};
+
+const ENCODING_PATH: &str = "std/encoding";
+const ENCODING_ERROR: &str = "EncodingError";
+
+pub fn encoding_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(ENCODING_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let encoding_error = default_error_inheritance(hooks, std, ENCODING_ERROR)?;
+  export_and_insert(
+    hooks,
+    &mut module,
+    encoding_error.name(),
+    val!(encoding_error),
+  )?;
+
+  declare_encoding_funs(hooks, &mut module, std)?;
+  declare_stream_classes(hooks, &mut module, std)?;
+
+  define_encoding_funs(hooks, &module, std)?;
+  define_stream_classes(hooks, &module, std)?;
+
+  Ok(module)
+}
+
+/// The base64 alphabet to use
+fn base64_config(url_safe: bool) -> base64::Config {
+  if url_safe {
+    base64::URL_SAFE
+  } else {
+    base64::STANDARD
+  }
+}
@@ -0,0 +1,298 @@
+use super::{base64_config, utils::utf8_error, ENCODING_ERROR};
+use crate::{
+  create_error,
+  global::{bytes_data, create_bytes, input_bytes, BYTES_CLASS_NAME},
+  native, native_with_bytes, native_with_error,
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{io::Write, str};
+
+const UTF8_DECODER_CLASS_NAME: &str = "Utf8Decoder";
+const BASE64_ENCODER_CLASS_NAME: &str = "Base64Encoder";
+const BASE64_DECODER_CLASS_NAME: &str = "Base64Decoder";
+
+const STREAM_FIELD_URL_SAFE: &str = "urlSafe";
+const STREAM_FIELD_PENDING: &str = "pending";
+
+const UTF8_DECODER_WRITE: NativeMetaBuilder = NativeMetaBuilder::method("write", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("chunk", ParameterKind::Instance)]);
+
+const UTF8_DECODER_FINISH: NativeMetaBuilder = NativeMetaBuilder::method("finish", Arity::Fixed(0));
+
+const BASE64_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("urlSafe", ParameterKind::Bool)]);
+
+const BASE64_ENCODER_WRITE: NativeMetaBuilder = NativeMetaBuilder::method("write", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("chunk", ParameterKind::Any)]);
+
+const BASE64_ENCODER_FINISH: NativeMetaBuilder =
+  NativeMetaBuilder::method("finish", Arity::Fixed(0));
+
+const BASE64_DECODER_WRITE: NativeMetaBuilder = NativeMetaBuilder::method("write", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("chunk", ParameterKind::String)]);
+
+const BASE64_DECODER_FINISH: NativeMetaBuilder =
+  NativeMetaBuilder::method("finish", Arity::Fixed(0));
+
+pub fn declare_stream_classes(
+  hooks: &GcHooks,
+  module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let utf8_decoder = default_class_inheritance(hooks, std, UTF8_DECODER_CLASS_NAME)?;
+  export_and_insert(hooks, module, utf8_decoder.name(), val!(utf8_decoder))?;
+
+  let base64_encoder = default_class_inheritance(hooks, std, BASE64_ENCODER_CLASS_NAME)?;
+  export_and_insert(hooks, module, base64_encoder.name(), val!(base64_encoder))?;
+
+  let base64_decoder = default_class_inheritance(hooks, std, BASE64_DECODER_CLASS_NAME)?;
+  export_and_insert(hooks, module, base64_decoder.name(), val!(base64_decoder))
+}
+
+pub fn define_stream_classes(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let bytes = load_class_from_package(hooks, std, STD, BYTES_CLASS_NAME)?;
+  let error = val!(load_class_from_module(hooks, module, ENCODING_ERROR)?);
+
+  let mut utf8_decoder = load_class_from_module(hooks, module, UTF8_DECODER_CLASS_NAME)?;
+  utf8_decoder.add_field(hooks, hooks.manage_str(STREAM_FIELD_PENDING));
+
+  utf8_decoder.add_method(
+    hooks,
+    hooks.manage_str(UTF8_DECODER_WRITE.name),
+    val!(Utf8DecoderWrite::native(hooks, bytes, error)),
+  );
+
+  utf8_decoder.add_method(
+    hooks,
+    hooks.manage_str(UTF8_DECODER_FINISH.name),
+    val!(Utf8DecoderFinish::native(hooks, error)),
+  );
+
+  let mut base64_encoder = load_class_from_module(hooks, module, BASE64_ENCODER_CLASS_NAME)?;
+  base64_encoder.add_field(hooks, hooks.manage_str(STREAM_FIELD_URL_SAFE));
+  base64_encoder.add_field(hooks, hooks.manage_str(STREAM_FIELD_PENDING));
+
+  base64_encoder.add_method(
+    hooks,
+    hooks.manage_str(BASE64_INIT.name),
+    val!(Base64Init::native(hooks)),
+  );
+
+  base64_encoder.add_method(
+    hooks,
+    hooks.manage_str(BASE64_ENCODER_WRITE.name),
+    val!(Base64EncoderWrite::native(hooks, bytes, error)),
+  );
+
+  base64_encoder.add_method(
+    hooks,
+    hooks.manage_str(BASE64_ENCODER_FINISH.name),
+    val!(Base64EncoderFinish::native(hooks)),
+  );
+
+  let mut base64_decoder = load_class_from_module(hooks, module, BASE64_DECODER_CLASS_NAME)?;
+  base64_decoder.add_field(hooks, hooks.manage_str(STREAM_FIELD_URL_SAFE));
+  base64_decoder.add_field(hooks, hooks.manage_str(STREAM_FIELD_PENDING));
+
+  base64_decoder.add_method(
+    hooks,
+    hooks.manage_str(BASE64_INIT.name),
+    val!(Base64Init::native(hooks)),
+  );
+
+  base64_decoder.add_method(
+    hooks,
+    hooks.manage_str(BASE64_DECODER_WRITE.name),
+    val!(Base64DecoderWrite::native(hooks, bytes, error)),
+  );
+
+  base64_decoder.add_method(
+    hooks,
+    hooks.manage_str(BASE64_DECODER_FINISH.name),
+    val!(Base64DecoderFinish::native(hooks, bytes, error)),
+  );
+
+  Ok(())
+}
+
+/// The bytes held back by a stream until more input arrives
+fn pending_bytes(value: Value) -> Vec<u8> {
+  bytes_data(value)
+    .map(|bytes| bytes.to_vec())
+    .unwrap_or_default()
+}
+
+/// The characters held back by a base64 decoder until more input arrives
+fn pending_chars(value: Value) -> String {
+  if value.is_obj_kind(ObjectKind::String) {
+    String::from(&*value.to_obj().to_str())
+  } else {
+    String::new()
+  }
+}
+
+native_with_bytes!(Utf8DecoderWrite, UTF8_DECODER_WRITE);
+
+impl LyNative for Utf8DecoderWrite {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let chunk = match bytes_data(args[0]) {
+      Some(chunk) => chunk,
+      None => return create_error!(self.error, hooks, "Can only decode Bytes."),
+    };
+
+    let mut data = pending_bytes(this[0]);
+    data.extend_from_slice(&chunk);
+
+    // an incomplete sequence at the end of the chunk may be completed by
+    // the next chunk so it's held back instead of being reported
+    let valid = match str::from_utf8(&data) {
+      Ok(_) => data.len(),
+      Err(err) if err.error_len().is_none() => err.valid_up_to(),
+      Err(err) => return create_error!(self.error, hooks, utf8_error(err)),
+    };
+
+    this[0] = if valid < data.len() {
+      val!(create_bytes(&hooks.as_gc(), self.bytes, &data[valid..]))
+    } else {
+      VALUE_NIL
+    };
+
+    let decoded = str::from_utf8(&data[..valid]).expect("Expected valid utf-8");
+    Call::Ok(val!(hooks.manage_str(decoded)))
+  }
+}
+
+native_with_error!(Utf8DecoderFinish, UTF8_DECODER_FINISH);
+
+impl LyNative for Utf8DecoderFinish {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let pending = pending_bytes(this[0]);
+    this[0] = VALUE_NIL;
+
+    if pending.is_empty() {
+      Call::Ok(val!(hooks.manage_str("")))
+    } else {
+      self.call_error(hooks, "Incomplete utf-8 sequence at the end of the input.")
+    }
+  }
+}
+
+native!(Base64Init, BASE64_INIT);
+
+impl LyNative for Base64Init {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    this[0] = val!(!args.is_empty() && args[0].to_bool());
+    this[1] = VALUE_NIL;
+
+    Call::Ok(val!(this))
+  }
+}
+
+native_with_bytes!(Base64EncoderWrite, BASE64_ENCODER_WRITE);
+
+impl LyNative for Base64EncoderWrite {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let chunk = match input_bytes(args[0]) {
+      Some(chunk) => chunk,
+      None => return create_error!(self.error, hooks, "Can only encode a String or Bytes."),
+    };
+
+    let mut data = pending_bytes(this[1]);
+    data.extend_from_slice(&chunk);
+
+    // only whole groups of three bytes can be encoded without padding
+    let whole = data.len() - data.len() % 3;
+    this[1] = if whole < data.len() {
+      val!(create_bytes(&hooks.as_gc(), self.bytes, &data[whole..]))
+    } else {
+      VALUE_NIL
+    };
+
+    let encoded = base64::encode_config(&data[..whole], base64_config(this[0] == val!(true)));
+    Call::Ok(val!(hooks.manage_str(encoded)))
+  }
+}
+
+native!(Base64EncoderFinish, BASE64_ENCODER_FINISH);
+
+impl LyNative for Base64EncoderFinish {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let pending = pending_bytes(this[1]);
+    this[1] = VALUE_NIL;
+
+    let encoded = base64::encode_config(pending, base64_config(this[0] == val!(true)));
+    Call::Ok(val!(hooks.manage_str(encoded)))
+  }
+}
+
+native_with_bytes!(Base64DecoderWrite, BASE64_DECODER_WRITE);
+
+impl LyNative for Base64DecoderWrite {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let mut chars = pending_chars(this[1]);
+    chars.extend(
+      args[0]
+        .to_obj()
+        .to_str()
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace()),
+    );
+
+    // only whole groups of four characters can be decoded
+    let whole = chars.len() - chars.len() % 4;
+    let decoded = match base64::decode_config(&chars[..whole], base64_config(this[0] == val!(true)))
+    {
+      Ok(decoded) => decoded,
+      Err(err) => return create_error!(self.error, hooks, err.to_string()),
+    };
+
+    this[1] = if whole < chars.len() {
+      val!(hooks.manage_str(&chars[whole..]))
+    } else {
+      VALUE_NIL
+    };
+
+    Call::Ok(val!(create_bytes(&hooks.as_gc(), self.bytes, &decoded)))
+  }
+}
+
+native_with_bytes!(Base64DecoderFinish, BASE64_DECODER_FINISH);
+
+impl LyNative for Base64DecoderFinish {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let chars = pending_chars(this[1]);
+    this[1] = VALUE_NIL;
+
+    match base64::decode_config(&chars, base64_config(this[0] == val!(true))) {
+      Ok(decoded) => Call::Ok(val!(create_bytes(&hooks.as_gc(), self.bytes, &decoded))),
+      Err(err) => create_error!(self.error, hooks, err.to_string()),
+    }
+  }
+}
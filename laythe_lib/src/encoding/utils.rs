@@ -0,0 +1,281 @@
+use super::{base64_config, ENCODING_ERROR};
+use crate::{
+  create_error,
+  global::{bytes_data, create_bytes, input_bytes, BYTES_CLASS_NAME},
+  native_with_bytes, native_with_error,
+  support::{export_and_insert, load_class_from_module, load_class_from_package},
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::{io::Write, str};
+
+/// Every character other than the unreserved characters of RFC 3986
+const URL_RESERVED: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'_')
+  .remove(b'~');
+
+const BASE64_ENCODE_META: NativeMetaBuilder =
+  NativeMetaBuilder::fun("base64Encode", Arity::Default(1, 2)).with_params(&[
+    ParameterBuilder::new("data", ParameterKind::Any),
+    ParameterBuilder::new("urlSafe", ParameterKind::Bool),
+  ]);
+
+const BASE64_DECODE_META: NativeMetaBuilder =
+  NativeMetaBuilder::fun("base64Decode", Arity::Default(1, 2)).with_params(&[
+    ParameterBuilder::new("string", ParameterKind::String),
+    ParameterBuilder::new("urlSafe", ParameterKind::Bool),
+  ]);
+
+const HEX_ENCODE_META: NativeMetaBuilder = NativeMetaBuilder::fun("hexEncode", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const HEX_DECODE_META: NativeMetaBuilder = NativeMetaBuilder::fun("hexDecode", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
+const URL_ENCODE_META: NativeMetaBuilder = NativeMetaBuilder::fun("urlEncode", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const URL_DECODE_META: NativeMetaBuilder = NativeMetaBuilder::fun("urlDecode", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
+const UTF8_ENCODE_META: NativeMetaBuilder = NativeMetaBuilder::fun("utf8Encode", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
+const UTF8_DECODE_META: NativeMetaBuilder = NativeMetaBuilder::fun("utf8Decode", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("bytes", ParameterKind::Instance)]);
+
+const IS_UTF8_META: NativeMetaBuilder = NativeMetaBuilder::fun("isUtf8", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("bytes", ParameterKind::Instance)]);
+
+pub fn declare_encoding_funs(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let bytes = load_class_from_package(hooks, std, STD, BYTES_CLASS_NAME)?;
+  let error = val!(load_class_from_module(hooks, module, ENCODING_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(BASE64_ENCODE_META.name),
+    val!(Base64Encode::native(hooks, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(BASE64_DECODE_META.name),
+    val!(Base64Decode::native(hooks, bytes, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(HEX_ENCODE_META.name),
+    val!(HexEncode::native(hooks, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(HEX_DECODE_META.name),
+    val!(HexDecode::native(hooks, bytes, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(URL_ENCODE_META.name),
+    val!(UrlEncode::native(hooks, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(URL_DECODE_META.name),
+    val!(UrlDecode::native(hooks, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(UTF8_ENCODE_META.name),
+    val!(Utf8Encode::native(hooks, bytes, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(UTF8_DECODE_META.name),
+    val!(Utf8Decode::native(hooks, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(IS_UTF8_META.name),
+    val!(IsUtf8::native(hooks, error)),
+  )
+}
+
+pub fn define_encoding_funs(_: &GcHooks, _: &Module, _: &Package) -> StdResult<()> {
+  Ok(())
+}
+
+/// Describe where a byte sequence stopped being valid utf-8
+pub(super) fn utf8_error(err: str::Utf8Error) -> String {
+  format!("Invalid utf-8 sequence at byte {}.", err.valid_up_to())
+}
+
+native_with_error!(Base64Encode, BASE64_ENCODE_META);
+
+impl LyNative for Base64Encode {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let data = match input_bytes(args[0]) {
+      Some(data) => data,
+      None => return self.call_error(hooks, "Can only encode a String or Bytes."),
+    };
+    let url_safe = args.len() > 1 && args[1].to_bool();
+
+    let encoded = base64::encode_config(data, base64_config(url_safe));
+    Call::Ok(val!(hooks.manage_str(encoded)))
+  }
+}
+
+native_with_bytes!(Base64Decode, BASE64_DECODE_META);
+
+impl LyNative for Base64Decode {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let string = args[0].to_obj().to_str();
+    let url_safe = args.len() > 1 && args[1].to_bool();
+
+    match base64::decode_config(&*string, base64_config(url_safe)) {
+      Ok(decoded) => Call::Ok(val!(create_bytes(&hooks.as_gc(), self.bytes, &decoded))),
+      Err(err) => create_error!(self.error, hooks, err.to_string()),
+    }
+  }
+}
+
+native_with_error!(HexEncode, HEX_ENCODE_META);
+
+impl LyNative for HexEncode {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    match input_bytes(args[0]) {
+      Some(data) => Call::Ok(val!(hooks.manage_str(hex::encode(data)))),
+      None => self.call_error(hooks, "Can only encode a String or Bytes."),
+    }
+  }
+}
+
+native_with_bytes!(HexDecode, HEX_DECODE_META);
+
+impl LyNative for HexDecode {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let string = args[0].to_obj().to_str();
+
+    match hex::decode(&*string) {
+      Ok(decoded) => Call::Ok(val!(create_bytes(&hooks.as_gc(), self.bytes, &decoded))),
+      Err(err) => create_error!(self.error, hooks, format!("Invalid hex. {}.", err)),
+    }
+  }
+}
+
+native_with_error!(UrlEncode, URL_ENCODE_META);
+
+impl LyNative for UrlEncode {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    if args[0].is_obj_kind(ObjectKind::String) {
+      let string = args[0].to_obj().to_str();
+      let encoded = utf8_percent_encode(&string, URL_RESERVED).to_string();
+      return Call::Ok(val!(hooks.manage_str(encoded)));
+    }
+
+    match bytes_data(args[0]) {
+      Some(data) => {
+        let encoded = percent_encoding::percent_encode(&data, URL_RESERVED).to_string();
+        Call::Ok(val!(hooks.manage_str(encoded)))
+      },
+      None => self.call_error(hooks, "Can only encode a String or Bytes."),
+    }
+  }
+}
+
+native_with_error!(UrlDecode, URL_DECODE_META);
+
+impl LyNative for UrlDecode {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let string = args[0].to_obj().to_str();
+
+    match percent_decode_str(&string).decode_utf8() {
+      Ok(decoded) => Call::Ok(val!(hooks.manage_str(decoded))),
+      Err(err) => self.call_error(hooks, utf8_error(err)),
+    }
+  }
+}
+
+native_with_bytes!(Utf8Encode, UTF8_ENCODE_META);
+
+impl LyNative for Utf8Encode {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let string = args[0].to_obj().to_str();
+    Call::Ok(val!(create_bytes(
+      &hooks.as_gc(),
+      self.bytes,
+      string.as_bytes()
+    )))
+  }
+}
+
+native_with_error!(Utf8Decode, UTF8_DECODE_META);
+
+impl LyNative for Utf8Decode {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let data = match bytes_data(args[0]) {
+      Some(data) => data,
+      None => return self.call_error(hooks, "Can only decode Bytes."),
+    };
+
+    match str::from_utf8(&data) {
+      Ok(string) => Call::Ok(val!(hooks.manage_str(string))),
+      Err(err) => self.call_error(hooks, utf8_error(err)),
+    }
+  }
+}
+
+native_with_error!(IsUtf8, IS_UTF8_META);
+
+impl LyNative for IsUtf8 {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    match bytes_data(args[0]) {
+      Some(data) => Call::Ok(val!(str::from_utf8(&data).is_ok())),
+      None => self.call_error(hooks, "Can only check Bytes."),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn url_reserved() {
+    let encoded = utf8_percent_encode("a b/c-d.e_f~g?h=é", URL_RESERVED).to_string();
+    assert_eq!(encoded, "a%20b%2Fc-d.e_f~g%3Fh%3D%C3%A9");
+  }
+
+  #[test]
+  fn utf8_error_position() {
+    let bytes = vec![b'a', b'b', 0xff];
+    let err = str::from_utf8(&bytes).unwrap_err();
+    assert_eq!(utf8_error(err), "Invalid utf-8 sequence at byte 2.");
+  }
+}
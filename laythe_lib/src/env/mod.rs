@@ -9,6 +9,8 @@ use laythe_core::{
 use std::path::PathBuf;
 use utils::{declare_env_module, define_env_module};
 
+pub(crate) use utils::{Args, Cwd};
+
 use crate::{global::MODULE_CLASS_NAME, support::load_class_from_package, StdResult, STD};
 
 const ENV_PATH: &str = "std/env";
@@ -0,0 +1,249 @@
+use super::FFI_ERROR;
+use crate::{
+  create_error, native_with_error,
+  support::{default_class_inheritance, export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{LyNative, Native, NativeMeta, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, Environment, Parameter, ParameterBuilder, ParameterKind, Signature},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use laythe_native::ffi::{FfiType, FfiValue, ForeignFn, ForeignLibrary};
+use std::{io::Write, path::Path};
+
+const LIBRARY_CLASS_NAME: &str = "Library";
+const LIBRARY_FIELD_PATH: &str = "path";
+
+const LIBRARY_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
+
+const LIBRARY_DECLARE: NativeMetaBuilder =
+  NativeMetaBuilder::method("declare", Arity::Default(2, 3)).with_params(&[
+    ParameterBuilder::new("name", ParameterKind::String),
+    ParameterBuilder::new("parameters", ParameterKind::List),
+    ParameterBuilder::new("result", ParameterKind::String),
+  ]);
+
+pub fn declare_library_class(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let class = default_class_inheritance(hooks, std, LIBRARY_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_library_class(hooks: &GcHooks, module: &Module, _: &Package) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, LIBRARY_CLASS_NAME)?;
+  let ffi_error = val!(load_class_from_module(hooks, module, FFI_ERROR)?);
+
+  class.add_field(hooks, hooks.manage_str(LIBRARY_FIELD_PATH));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(LIBRARY_INIT.name),
+    val!(LibraryInit::native(hooks, ffi_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(LIBRARY_DECLARE.name),
+    val!(LibraryDeclare::native(hooks, ffi_error)),
+  );
+
+  Ok(())
+}
+
+native_with_error!(LibraryInit, LIBRARY_INIT);
+
+impl LyNative for LibraryInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let path = args[0].to_obj().to_str();
+
+    // open the library up front so a missing library is reported where
+    // it is named rather than on the first declare
+    if let Err(err) = ForeignLibrary::open(Path::new(&*path)) {
+      return self.call_error(hooks, err);
+    }
+
+    let mut this = this.unwrap().to_obj().to_instance();
+    this[0] = args[0];
+    Call::Ok(val!(this))
+  }
+}
+
+native_with_error!(LibraryDeclare, LIBRARY_DECLARE);
+
+impl LyNative for LibraryDeclare {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let path = this.unwrap().to_obj().to_instance()[0].to_obj().to_str();
+    let name = args[0].to_obj().to_str();
+
+    let mut parameters = vec![];
+    for kind in args[1].to_obj().to_list().iter() {
+      match parse_type(*kind) {
+        Some(kind) => parameters.push(kind),
+        None => {
+          return self.call_error(
+            hooks,
+            format!(
+              "Parameter types of {} must be one of {}.",
+              &*name, TYPE_NAMES
+            ),
+          )
+        },
+      }
+    }
+
+    let result = if args.len() > 2 {
+      match parse_type(args[2]) {
+        Some(result) => result,
+        None => {
+          return self.call_error(
+            hooks,
+            format!("Result type of {} must be one of {}.", &*name, TYPE_NAMES),
+          )
+        },
+      }
+    } else {
+      FfiType::Void
+    };
+
+    let fun = match ForeignLibrary::open(Path::new(&*path))
+      .and_then(|library| library.declare(&name, parameters, result))
+    {
+      Ok(fun) => fun,
+      Err(err) => return self.call_error(hooks, err),
+    };
+
+    Call::Ok(val!(ForeignNative::native(&hooks.as_gc(), fun, self.error)))
+  }
+}
+
+const TYPE_NAMES: &str = "i32, i64, f32, f64, pointer, string or void";
+
+fn parse_type(value: Value) -> Option<FfiType> {
+  if value.is_obj_kind(ObjectKind::String) {
+    FfiType::parse(&value.to_obj().to_str())
+  } else {
+    None
+  }
+}
+
+/// A foreign function declared from a library, called like any other
+/// native function
+#[derive(Debug)]
+struct ForeignNative {
+  fun: ForeignFn,
+  error: Value,
+}
+
+impl ForeignNative {
+  fn native(hooks: &GcHooks, fun: ForeignFn, error: Value) -> GcObj<Native> {
+    let roots = fun.parameters().len() + 1;
+    let name = hooks.manage_str(fun.name());
+    hooks.push_root(name);
+
+    let mut parameters = vec![];
+    for (index, kind) in fun.parameters().iter().enumerate() {
+      let parameter = hooks.manage_str(format!("arg{}", index));
+      hooks.push_root(parameter);
+
+      let kind = match kind {
+        FfiType::I32 | FfiType::I64 | FfiType::F32 | FfiType::F64 => ParameterKind::Number,
+        FfiType::Pointer | FfiType::String | FfiType::Void => ParameterKind::Any,
+      };
+      parameters.push(Parameter::new(parameter, kind));
+    }
+
+    let meta = NativeMeta {
+      name,
+      is_method: false,
+      environment: Environment::StackLess,
+      mutates: false,
+      signature: Signature {
+        arity: Arity::Fixed(parameters.len() as u8),
+        parameters: parameters.into_boxed_slice(),
+      },
+    };
+
+    let native = hooks.manage_obj(Native::new(meta, Box::new(Self { fun, error })));
+    hooks.pop_roots(roots);
+    native
+  }
+
+  /// Convert a laythe value to the value passed for a parameter
+  fn to_ffi(&self, kind: FfiType, value: Value) -> Option<FfiValue> {
+    if value.is_nil() {
+      return match kind {
+        FfiType::Pointer | FfiType::String => Some(FfiValue::Null),
+        _ => None,
+      };
+    }
+
+    match kind {
+      FfiType::I32 | FfiType::I64 | FfiType::Pointer if value.is_num() => {
+        let num = value.to_num();
+        if num.fract() != 0.0 || (kind == FfiType::Pointer && num < 0.0) {
+          None
+        } else if kind == FfiType::Pointer {
+          Some(FfiValue::Pointer(num as usize))
+        } else {
+          Some(FfiValue::Int(num as i64))
+        }
+      },
+      FfiType::F32 | FfiType::F64 if value.is_num() => Some(FfiValue::Float(value.to_num())),
+      FfiType::String if value.is_obj_kind(ObjectKind::String) => {
+        Some(FfiValue::String(String::from(&*value.to_obj().to_str())))
+      },
+      _ => None,
+    }
+  }
+}
+
+impl LyNative for ForeignNative {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let mut values = vec![];
+    for (index, (kind, arg)) in self.fun.parameters().iter().zip(args).enumerate() {
+      match self.to_ffi(*kind, *arg) {
+        Some(value) => values.push(value),
+        None => {
+          let message = format!(
+            "Argument {} of {} cannot be passed as {}.",
+            index,
+            self.fun.name(),
+            kind
+          );
+          return create_error!(self.error, hooks, message);
+        },
+      }
+    }
+
+    // the script declared this signature, calling it is as safe as the
+    // declaration is accurate
+    match unsafe { self.fun.call(&values) } {
+      Ok(FfiValue::Int(value)) => Call::Ok(val!(value as f64)),
+      Ok(FfiValue::Float(value)) => Call::Ok(val!(value)),
+      Ok(FfiValue::Pointer(address)) => Call::Ok(val!(address as f64)),
+      Ok(FfiValue::String(string)) => Call::Ok(val!(hooks.manage_str(string))),
+      Ok(FfiValue::Null) | Ok(FfiValue::Void) => Call::Ok(VALUE_NIL),
+      Err(err) => create_error!(self.error, hooks, err),
+    }
+  }
+}
+
+impl Trace for ForeignNative {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
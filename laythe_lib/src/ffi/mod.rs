@@ -0,0 +1,45 @@
+mod library;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use std::path::PathBuf;
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+
+use self::library::{declare_library_class, define_library_class};
+
+const FFI_PATH: &str = "std/ffi";
+const FFI_ERROR: &str = "FfiError";
+
+pub fn ffi_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(FFI_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let ffi_error = default_error_inheritance(hooks, std, FFI_ERROR)?;
+  export_and_insert(hooks, &mut module, ffi_error.name(), val!(ffi_error))?;
+
+  declare_library_class(hooks, &mut module, std)?;
+  define_library_class(hooks, &module, std)?;
+
+  Ok(module)
+}
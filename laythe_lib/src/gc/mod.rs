@@ -0,0 +1,39 @@
+mod utils;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Import, Module, Package},
+  utils::IdEmitter,
+};
+use std::path::PathBuf;
+use utils::{declare_gc_module, define_gc_module};
+
+use crate::{
+  global::MODULE_CLASS_NAME, io::IO_MODULE_PATH, support::load_class_from_package, StdResult, STD,
+};
+
+const GC_PATH: &str = "std/gc";
+
+pub fn gc_module(
+  hooks: &GcHooks,
+  std: &mut Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  // gc.dump raises io errors
+  std.initialize(hooks, emitter, Import::from_str(hooks, IO_MODULE_PATH)?)?;
+
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(GC_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  declare_gc_module(hooks, &mut module, std)?;
+  define_gc_module(hooks, &mut module)?;
+
+  Ok(module)
+}
@@ -0,0 +1,102 @@
+use crate::{
+  io::{IO_ERROR, IO_MODULE_PATH},
+  native_with_error,
+  support::{export_and_insert, load_class_from_package},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{io::Write, path::Path};
+
+const DUMP_META: NativeMetaBuilder = NativeMetaBuilder::fun("dump", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
+
+pub fn declare_gc_module(
+  hooks: &GcHooks,
+  self_module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let io_error = val!(load_class_from_package(
+    hooks,
+    std,
+    IO_MODULE_PATH,
+    IO_ERROR
+  )?);
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(DUMP_META.name),
+    val!(Dump::native(hooks, io_error)),
+  )
+}
+
+pub fn define_gc_module(_: &GcHooks, _: &mut Module) -> StdResult<()> {
+  Ok(())
+}
+
+native_with_error!(Dump, DUMP_META);
+
+impl LyNative for Dump {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let path = args[0].to_obj().to_str();
+
+    let mut dump = vec![];
+    if let Err(err) = hooks.heap_dump(&mut dump) {
+      return self.call_error(hooks, err.to_string());
+    }
+
+    let io = hooks.as_io();
+    match io.fs().write(Path::new(&*path), &dump) {
+      Ok(()) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  mod dump {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let mut context = MockedContext::default();
+      let hooks = GcHooks::new(&mut context);
+      let error = val!(test_error_class(&hooks));
+
+      let dump = Dump::native(&hooks, error);
+
+      assert_eq!(dump.meta().name, "dump");
+      assert_eq!(dump.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        dump.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let dump = Dump::native(&hooks.as_gc(), error);
+      let path = val!(hooks.manage_str("heap.json"));
+
+      let result = dump.call(&mut hooks, None, &[path]);
+      assert_eq!(result, Call::Ok(VALUE_NIL));
+    }
+  }
+}
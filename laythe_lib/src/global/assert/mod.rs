@@ -48,39 +48,85 @@ pub(crate) fn add_assert_funs(
     module,
     hooks.manage_str(ASSERTNE_META.name),
     val!(AssertNe::native(hooks, str_name, error_val)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(ASSERTALMOSTEQ_META.name),
+    val!(AssertAlmostEq::native(hooks, str_name, error_val)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(ASSERTRAISES_META.name),
+    val!(AssertRaises::native(hooks, error_val)),
   )
   .map_err(StdError::from)
 }
 
-const ASSERT_META: NativeMetaBuilder = NativeMetaBuilder::fun("assert", Arity::Fixed(1))
-  .with_params(&[ParameterBuilder::new("value", ParameterKind::Bool)]);
+const ASSERT_META: NativeMetaBuilder = NativeMetaBuilder::fun("assert", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("value", ParameterKind::Bool),
+    ParameterBuilder::new("message", ParameterKind::String),
+  ]);
 
-const ASSERTEQ_META: NativeMetaBuilder = NativeMetaBuilder::fun("assertEq", Arity::Fixed(2))
+const ASSERTEQ_META: NativeMetaBuilder = NativeMetaBuilder::fun("assertEq", Arity::Default(2, 3))
   .with_params(&[
     ParameterBuilder::new("actual", ParameterKind::Any),
     ParameterBuilder::new("expected", ParameterKind::Any),
+    ParameterBuilder::new("message", ParameterKind::String),
   ]);
 
-const ASSERTNE_META: NativeMetaBuilder = NativeMetaBuilder::fun("assertNe", Arity::Fixed(2))
+const ASSERTNE_META: NativeMetaBuilder = NativeMetaBuilder::fun("assertNe", Arity::Default(2, 3))
   .with_params(&[
     ParameterBuilder::new("actual", ParameterKind::Any),
     ParameterBuilder::new("unexpected", ParameterKind::Any),
+    ParameterBuilder::new("message", ParameterKind::String),
+  ]);
+
+const ASSERTALMOSTEQ_META: NativeMetaBuilder =
+  NativeMetaBuilder::fun("assertAlmostEq", Arity::Default(3, 4)).with_params(&[
+    ParameterBuilder::new("actual", ParameterKind::Number),
+    ParameterBuilder::new("expected", ParameterKind::Number),
+    ParameterBuilder::new("epsilon", ParameterKind::Number),
+    ParameterBuilder::new("message", ParameterKind::String),
   ]);
 
-fn to_str(hooks: &mut Hooks, value: Value) -> GcStr {
-  hooks
-    .get_method(value, hooks.manage_str("str"))
-    .map(|method| hooks.call_method(value, method, &[]))
-    .map(|string| {
-      if let Call::Ok(ok) = string {
-        if_let_obj!(ObjectKind::String(string) = (ok) {
-          return string;
-        });
-      }
-
-      hooks.manage_str(format!("{:?}", string))
-    })
-    .expect("No str method")
+const ASSERTRAISES_META: NativeMetaBuilder =
+  NativeMetaBuilder::fun("assertRaises", Arity::Fixed(2)).with_params(&[
+    ParameterBuilder::new("errorClass", ParameterKind::Class),
+    ParameterBuilder::new("fun", ParameterKind::Fun),
+  ]);
+
+/// Stringify a value through its 'str' method, falling back to the
+/// value's default formatting if the method fails or does not return
+/// a string
+fn to_str(hooks: &mut Hooks, method_str: GcStr, value: Value) -> String {
+  let result = hooks
+    .get_method(value, method_str)
+    .and_then(|method| hooks.call_method(value, method, &[]));
+
+  if let Call::Ok(string) = result {
+    if_let_obj!(ObjectKind::String(string) = (string) {
+      return String::from(&*string);
+    });
+  }
+
+  value.to_string()
+}
+
+/// Format a failed comparison listing each labeled value under the
+/// user provided message
+fn comparison_failure(message: Value, values: &[(&str, &str)]) -> String {
+  let mut buf = format!("Assertion failed: {}", message.to_obj().to_str());
+
+  for (label, value) in values {
+    buf.push_str(&format!("\n  {} = {}", label, value));
+  }
+
+  buf
 }
 
 #[derive(Debug)]
@@ -104,13 +150,20 @@ impl Assert {
 impl LyNative for Assert {
   fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
     if args[0].to_bool() {
-      Call::Ok(VALUE_NIL)
-    } else {
-      create_error!(
+      return Call::Ok(VALUE_NIL);
+    }
+
+    match args.get(1) {
+      Some(message) => create_error!(
+        self.error,
+        hooks,
+        format!("Assertion failed: {}", message.to_obj().to_str())
+      ),
+      None => create_error!(
         self.error,
         hooks,
         "Assertion failed expected true received false"
-      )
+      ),
     }
   }
 }
@@ -120,6 +173,10 @@ impl Trace for Assert {
     self.method_str.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_str.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.method_str.trace_debug(stdout);
   }
@@ -147,14 +204,21 @@ impl LyNative for AssertEq {
       return Call::Ok(VALUE_NIL);
     }
 
-    let arg0 = to_str(hooks, args[0]);
-    let arg1 = to_str(hooks, args[1]);
+    let arg0 = to_str(hooks, self.method_str, args[0]);
+    let arg1 = to_str(hooks, self.method_str, args[1]);
 
-    create_error!(
-      self.error,
-      hooks,
-      format!("Assertion failed {} and {} are not equal.", arg0, arg1)
-    )
+    match args.get(2) {
+      Some(message) => create_error!(
+        self.error,
+        hooks,
+        comparison_failure(*message, &[("actual", &arg0), ("expected", &arg1)])
+      ),
+      None => create_error!(
+        self.error,
+        hooks,
+        format!("Assertion failed {} and {} are not equal.", arg0, arg1)
+      ),
+    }
   }
 }
 
@@ -164,6 +228,11 @@ impl Trace for AssertEq {
     self.error.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_str.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.method_str.trace_debug(stdout);
     self.error.trace_debug(stdout);
@@ -192,14 +261,21 @@ impl LyNative for AssertNe {
       return Call::Ok(VALUE_NIL);
     }
 
-    let arg0 = to_str(hooks, args[0]);
-    let arg1 = to_str(hooks, args[1]);
+    let arg0 = to_str(hooks, self.method_str, args[0]);
+    let arg1 = to_str(hooks, self.method_str, args[1]);
 
-    create_error!(
-      self.error,
-      hooks,
-      format!("Assertion failed {} and {} are equal.", arg0, arg1)
-    )
+    match args.get(2) {
+      Some(message) => create_error!(
+        self.error,
+        hooks,
+        comparison_failure(*message, &[("actual", &arg0), ("unexpected", &arg1)])
+      ),
+      None => create_error!(
+        self.error,
+        hooks,
+        format!("Assertion failed {} and {} are equal.", arg0, arg1)
+      ),
+    }
   }
 }
 
@@ -209,12 +285,150 @@ impl Trace for AssertNe {
     self.error.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_str.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.method_str.trace_debug(stdout);
+    self.error.trace_debug(stdout);
+  }
+}
+
+#[derive(Debug)]
+pub struct AssertAlmostEq {
+  method_str: GcStr,
+  error: Value,
+}
+
+impl AssertAlmostEq {
+  /// Construct a new instance of the native assertAlmostEq function
+  pub fn native(hooks: &GcHooks, method_str: GcStr, error: Value) -> GcObj<Native> {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    let native = Box::new(Self { method_str, error }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(ASSERTALMOSTEQ_META.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for AssertAlmostEq {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let actual = args[0].to_num();
+    let expected = args[1].to_num();
+    let epsilon = args[2].to_num();
+
+    if (actual - expected).abs() <= epsilon {
+      return Call::Ok(VALUE_NIL);
+    }
+
+    let arg0 = to_str(hooks, self.method_str, args[0]);
+    let arg1 = to_str(hooks, self.method_str, args[1]);
+    let arg2 = to_str(hooks, self.method_str, args[2]);
+
+    match args.get(3) {
+      Some(message) => create_error!(
+        self.error,
+        hooks,
+        comparison_failure(
+          *message,
+          &[("actual", &arg0), ("expected", &arg1), ("epsilon", &arg2)]
+        )
+      ),
+      None => create_error!(
+        self.error,
+        hooks,
+        format!(
+          "Assertion failed {} and {} are not within {}.",
+          arg0, arg1, arg2
+        )
+      ),
+    }
+  }
+}
+
+impl Trace for AssertAlmostEq {
+  fn trace(&self) {
+    self.method_str.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_str.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.method_str.trace_debug(stdout);
     self.error.trace_debug(stdout);
   }
 }
 
+#[derive(Debug)]
+pub struct AssertRaises {
+  error: Value,
+}
+
+impl AssertRaises {
+  /// Construct a new instance of the native assertRaises function
+  pub fn native(hooks: &GcHooks, error: Value) -> GcObj<Native> {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    let native = Box::new(Self { error }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(ASSERTRAISES_META.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for AssertRaises {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let expected = args[0].to_obj().to_class();
+
+    match hooks.call(args[1], &[]) {
+      Call::Ok(_) => create_error!(
+        self.error,
+        hooks,
+        format!(
+          "Assertion failed expected {} to be raised.",
+          &*expected.name()
+        )
+      ),
+      Call::Err(error) => {
+        if error.class().is_subclass(expected) {
+          return Call::Ok(val!(error));
+        }
+
+        // report the unexpected error as the cause of the failure
+        let message = format!(
+          "Assertion failed expected {} to be raised but received {}.",
+          &*expected.name(),
+          &*error.class().name()
+        );
+        let message = val!(hooks.manage_str(message));
+        match hooks.call(self.error, &[message, val!(error)]) {
+          Call::Ok(err) => Call::Err(err.to_obj().to_instance()),
+          result => result,
+        }
+      },
+      Call::Exit(code) => Call::Exit(code),
+      Call::Block => Call::Block,
+    }
+  }
+}
+
+impl Trace for AssertRaises {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.error.trace_debug(stdout);
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -235,11 +449,15 @@ mod test {
       let assert = Assert::native(&hooks.as_gc(), hooks.manage_str("str".to_string()), error);
 
       assert_eq!(&*assert.meta().name, "assert");
-      assert_eq!(assert.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(assert.meta().signature.arity, Arity::Default(1, 2));
       assert_eq!(
         assert.meta().signature.parameters[0].kind,
         ParameterKind::Bool
       );
+      assert_eq!(
+        assert.meta().signature.parameters[1].kind,
+        ParameterKind::String
+      );
     }
 
     #[test]
@@ -275,7 +493,7 @@ mod test {
       let assert_eq = AssertEq::native(&hooks, hooks.manage_str("str".to_string()), error);
 
       assert_eq!(&*assert_eq.meta().name, "assertEq");
-      assert_eq!(assert_eq.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(assert_eq.meta().signature.arity, Arity::Default(2, 3));
       assert_eq!(
         assert_eq.meta().signature.parameters[0].kind,
         ParameterKind::Any
@@ -284,6 +502,10 @@ mod test {
         assert_eq.meta().signature.parameters[1].kind,
         ParameterKind::Any
       );
+      assert_eq!(
+        assert_eq.meta().signature.parameters[2].kind,
+        ParameterKind::String
+      );
     }
 
     #[test]
@@ -320,7 +542,7 @@ mod test {
       let assert_eq = AssertNe::native(&hooks, hooks.manage_str("str".to_string()), error);
 
       assert_eq!(&*assert_eq.meta().name, "assertNe");
-      assert_eq!(assert_eq.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(assert_eq.meta().signature.arity, Arity::Default(2, 3));
       assert_eq!(
         assert_eq.meta().signature.parameters[0].kind,
         ParameterKind::Any
@@ -329,6 +551,10 @@ mod test {
         assert_eq.meta().signature.parameters[1].kind,
         ParameterKind::Any
       );
+      assert_eq!(
+        assert_eq.meta().signature.parameters[2].kind,
+        ParameterKind::String
+      );
     }
 
     #[test]
@@ -349,4 +575,87 @@ mod test {
       assert_eq!(result, VALUE_NIL);
     }
   }
+
+  #[cfg(test)]
+  mod assert_almost_eq {
+    use crate::support::test_error_class;
+
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = NoContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let error = val!(test_error_class(&hooks));
+      let assert_almost_eq = AssertAlmostEq::native(&hooks, hooks.manage_str("str"), error);
+
+      assert_eq!(&*assert_almost_eq.meta().name, "assertAlmostEq");
+      assert_eq!(
+        assert_almost_eq.meta().signature.arity,
+        Arity::Default(3, 4)
+      );
+      assert_eq!(
+        assert_almost_eq.meta().signature.parameters[0].kind,
+        ParameterKind::Number
+      );
+      assert_eq!(
+        assert_almost_eq.meta().signature.parameters[1].kind,
+        ParameterKind::Number
+      );
+      assert_eq!(
+        assert_almost_eq.meta().signature.parameters[2].kind,
+        ParameterKind::Number
+      );
+      assert_eq!(
+        assert_almost_eq.meta().signature.parameters[3].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = NoContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let assert_almost_eq = AssertAlmostEq::native(&hooks.as_gc(), hooks.manage_str("str"), error);
+
+      let values = &[val!(0.1 + 0.2), val!(0.3), val!(0.0001)];
+
+      let result = match assert_almost_eq.call(&mut hooks, None, values) {
+        Call::Ok(res) => res,
+        _ => panic!(),
+      };
+
+      assert_eq!(result, VALUE_NIL);
+    }
+  }
+
+  #[cfg(test)]
+  mod assert_raises {
+    use crate::support::test_error_class;
+
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = NoContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let error = val!(test_error_class(&hooks));
+      let assert_raises = AssertRaises::native(&hooks, error);
+
+      assert_eq!(&*assert_raises.meta().name, "assertRaises");
+      assert_eq!(assert_raises.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(
+        assert_raises.meta().signature.parameters[0].kind,
+        ParameterKind::Class
+      );
+      assert_eq!(
+        assert_raises.meta().signature.parameters[1].kind,
+        ParameterKind::Fun
+      );
+    }
+  }
 }
@@ -0,0 +1,462 @@
+use crate::{
+  native, native_with_error,
+  support::{default_class_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+use laythe_core::{
+  constants::INDEX_GET,
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{
+    Class, Enumerate, Enumerator, Instance, List, LyNative, Native, NativeMetaBuilder, ObjectKind,
+  },
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{any::Any, io::Write, mem, sync::Arc};
+
+use super::{INDEX_ERROR_NAME, TYPE_ERROR_NAME, VALUE_ERROR_NAME};
+
+pub const BYTES_CLASS_NAME: &str = "Bytes";
+const BYTES_FIELD_DATA: &str = "data";
+
+const BYTES_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("values", ParameterKind::List)]);
+
+const BYTES_INDEX_GET: NativeMetaBuilder = NativeMetaBuilder::method(INDEX_GET, Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("index", ParameterKind::Number)]);
+
+const BYTES_LEN: NativeMetaBuilder = NativeMetaBuilder::method("len", Arity::Fixed(0));
+
+const BYTES_SLICE: NativeMetaBuilder = NativeMetaBuilder::method("slice", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("start", ParameterKind::Number),
+    ParameterBuilder::new("end", ParameterKind::Number),
+  ]);
+
+const BYTES_CONCAT: NativeMetaBuilder = NativeMetaBuilder::method("concat", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Instance)]);
+
+const BYTES_LIST: NativeMetaBuilder = NativeMetaBuilder::method("list", Arity::Fixed(0));
+const BYTES_ITER: NativeMetaBuilder = NativeMetaBuilder::method("iter", Arity::Fixed(0));
+const BYTES_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
+
+const BYTES_DATA: NativeMetaBuilder = NativeMetaBuilder::fun("data", Arity::Fixed(0));
+
+pub(crate) fn add_bytes_class(
+  hooks: &GcHooks,
+  module: &mut Module,
+  package: &Package,
+) -> StdResult<()> {
+  let mut class = default_class_inheritance(hooks, package, BYTES_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))?;
+
+  let index_error = val!(load_class_from_package(
+    hooks,
+    package,
+    STD,
+    INDEX_ERROR_NAME
+  )?);
+  let type_error = val!(load_class_from_package(
+    hooks,
+    package,
+    STD,
+    TYPE_ERROR_NAME
+  )?);
+  let value_error = val!(load_class_from_package(
+    hooks,
+    package,
+    STD,
+    VALUE_ERROR_NAME
+  )?);
+
+  class.add_field(hooks, hooks.manage_str(BYTES_FIELD_DATA));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BYTES_INIT.name),
+    val!(BytesInit::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BYTES_INDEX_GET.name),
+    val!(BytesIndexGet::native(hooks, index_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BYTES_LEN.name),
+    val!(BytesLen::native(hooks, type_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BYTES_SLICE.name),
+    val!(BytesSlice::native(hooks, index_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BYTES_CONCAT.name),
+    val!(BytesConcat::native(hooks, type_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BYTES_LIST.name),
+    val!(BytesList::native(hooks, type_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BYTES_ITER.name),
+    val!(BytesIter::native(hooks, type_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BYTES_STR.name),
+    val!(BytesStr::native(hooks)),
+  );
+
+  Ok(())
+}
+
+/// Create a new instance of the Bytes class holding the provided bytes
+pub(crate) fn create_bytes(hooks: &GcHooks, class: GcObj<Class>, bytes: &[u8]) -> GcObj<Instance> {
+  let data = BytesData::native(hooks, Arc::from(bytes));
+  hooks.push_root(data);
+
+  let mut instance = hooks.manage_obj(Instance::new(class));
+  instance[0] = val!(data);
+
+  hooks.pop_roots(1);
+  instance
+}
+
+/// The bytes held by a value if it is an instance of Bytes
+pub(crate) fn bytes_data(value: Value) -> Option<Arc<[u8]>> {
+  if !value.is_obj_kind(ObjectKind::Instance) {
+    return None;
+  }
+
+  let instance = value.to_obj().to_instance();
+  let data = *instance.fields().first()?;
+  if !data.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  data
+    .to_obj()
+    .to_native()
+    .downcast_ref::<BytesData>()
+    .map(|data| Arc::clone(&data.bytes))
+}
+
+/// The raw bytes of a value that can be treated as binary data, either the
+/// utf-8 bytes of a string or the contents of a Bytes instance
+pub(crate) fn input_bytes(value: Value) -> Option<Vec<u8>> {
+  if value.is_obj_kind(ObjectKind::String) {
+    Some(value.to_obj().to_str().as_bytes().to_vec())
+  } else {
+    bytes_data(value).map(|bytes| bytes.to_vec())
+  }
+}
+
+/// Convert a number into a byte if it is an integer in the range 0 to 255
+fn to_byte(value: Value) -> Option<u8> {
+  if !value.is_num() {
+    return None;
+  }
+
+  let num = value.to_num();
+  if num.fract() == 0.0 && (0.0..=255.0).contains(&num) {
+    Some(num as u8)
+  } else {
+    None
+  }
+}
+
+/// The bytes backing a Bytes instance. Calling it returns the number of bytes
+#[derive(Debug)]
+pub struct BytesData {
+  bytes: Arc<[u8]>,
+}
+
+impl BytesData {
+  fn native(hooks: &GcHooks, bytes: Arc<[u8]>) -> GcObj<Native> {
+    let native = Box::new(Self { bytes }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(BYTES_DATA.to_meta(hooks), native))
+  }
+}
+
+impl Trace for BytesData {}
+
+impl LyNative for BytesData {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(val!(self.bytes.len() as f64))
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+macro_rules! get_bytes {
+  ( $self:ident, $this:ident, $hooks:ident ) => {{
+    match bytes_data($this.unwrap()) {
+      Some(bytes) => bytes,
+      None => return $self.call_error($hooks, "Bytes instance is missing its data."),
+    }
+  }};
+}
+
+native_with_error!(BytesInit, BYTES_INIT);
+
+impl LyNative for BytesInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let bytes = if args.is_empty() {
+      vec![]
+    } else {
+      let values = args[0].to_obj().to_list();
+      let mut bytes = Vec::with_capacity(values.len());
+
+      for value in values.iter() {
+        match to_byte(*value) {
+          Some(byte) => bytes.push(byte),
+          None => {
+            return self.call_error(hooks, "Bytes can only contain integers between 0 and 255.")
+          },
+        }
+      }
+
+      bytes
+    };
+
+    this[0] = val!(BytesData::native(&hooks.as_gc(), Arc::from(bytes)));
+    Call::Ok(val!(this))
+  }
+}
+
+native_with_error!(BytesIndexGet, BYTES_INDEX_GET);
+
+impl LyNative for BytesIndexGet {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let bytes = get_bytes!(self, this, hooks);
+    let index = args[0].to_num();
+
+    if index.fract() != 0.0 || index < 0.0 {
+      return self.call_error(hooks, "Index must be a non negative integer.");
+    }
+
+    match bytes.get(index as usize) {
+      Some(byte) => Call::Ok(val!(*byte as f64)),
+      None => self.call_error(
+        hooks,
+        format!(
+          "Index out of bounds. bytes was length {} but attempted to index with {}.",
+          bytes.len(),
+          index
+        ),
+      ),
+    }
+  }
+}
+
+native_with_error!(BytesLen, BYTES_LEN);
+
+impl LyNative for BytesLen {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let bytes = get_bytes!(self, this, hooks);
+    Call::Ok(val!(bytes.len() as f64))
+  }
+}
+
+native_with_error!(BytesSlice, BYTES_SLICE);
+
+impl LyNative for BytesSlice {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let bytes = get_bytes!(self, this, hooks);
+
+    let start = args[0].to_num();
+    let end = if args.len() > 1 {
+      args[1].to_num()
+    } else {
+      bytes.len() as f64
+    };
+
+    if start.fract() != 0.0 || end.fract() != 0.0 || start < 0.0 || end < 0.0 {
+      return self.call_error(hooks, "Slice bounds must be non negative integers.");
+    }
+
+    let end = (end as usize).min(bytes.len());
+    let start = (start as usize).min(end);
+
+    let class = this.unwrap().to_obj().to_instance().class();
+    Call::Ok(val!(create_bytes(
+      &hooks.as_gc(),
+      class,
+      &bytes[start..end]
+    )))
+  }
+}
+
+native_with_error!(BytesConcat, BYTES_CONCAT);
+
+impl LyNative for BytesConcat {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let bytes = get_bytes!(self, this, hooks);
+    let other = match bytes_data(args[0]) {
+      Some(other) => other,
+      None => return self.call_error(hooks, "Bytes can only be concatenated with Bytes."),
+    };
+
+    let mut joined = Vec::with_capacity(bytes.len() + other.len());
+    joined.extend_from_slice(&bytes);
+    joined.extend_from_slice(&other);
+
+    let class = this.unwrap().to_obj().to_instance().class();
+    Call::Ok(val!(create_bytes(&hooks.as_gc(), class, &joined)))
+  }
+}
+
+native_with_error!(BytesList, BYTES_LIST);
+
+impl LyNative for BytesList {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let bytes = get_bytes!(self, this, hooks);
+    let list: List<Value> = bytes.iter().map(|byte| val!(*byte as f64)).collect();
+
+    Call::Ok(val!(hooks.manage_obj(list)))
+  }
+}
+
+native_with_error!(BytesIter, BYTES_ITER);
+
+impl LyNative for BytesIter {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let bytes = get_bytes!(self, this, hooks);
+
+    let inner_iter: Box<dyn Enumerate> = Box::new(BytesIterator::new(bytes));
+    let iter = Enumerator::new(inner_iter);
+
+    Call::Ok(val!(hooks.manage_obj(iter)))
+  }
+}
+
+native!(BytesStr, BYTES_STR);
+
+impl LyNative for BytesStr {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let string = match bytes_data(this.unwrap()) {
+      Some(bytes) => bytes
+        .iter()
+        .fold(String::from("<Bytes"), |mut string, byte| {
+          string.push_str(&format!(" {:02x}", byte));
+          string
+        }),
+      None => String::from("<Bytes"),
+    };
+
+    Call::Ok(val!(hooks.manage_str(string + ">")))
+  }
+}
+
+/// Iterate over each byte in a Bytes instance
+#[derive(Debug)]
+struct BytesIterator {
+  bytes: Arc<[u8]>,
+  index: usize,
+  current: Value,
+}
+
+impl BytesIterator {
+  fn new(bytes: Arc<[u8]>) -> Self {
+    Self {
+      bytes,
+      index: 0,
+      current: VALUE_NIL,
+    }
+  }
+}
+
+impl Enumerate for BytesIterator {
+  fn name(&self) -> &str {
+    "BytesIterator"
+  }
+
+  fn current(&self) -> Value {
+    self.current
+  }
+
+  fn next(&mut self, _hooks: &mut Hooks) -> Call {
+    match self.bytes.get(self.index) {
+      Some(byte) => {
+        self.index += 1;
+        self.current = val!(*byte as f64);
+        Call::Ok(val!(true))
+      },
+      None => {
+        self.current = VALUE_NIL;
+        Call::Ok(val!(false))
+      },
+    }
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.bytes.len() - self.index)
+  }
+
+  fn size(&self) -> usize {
+    mem::size_of::<Self>() + self.bytes.len()
+  }
+}
+
+impl Trace for BytesIterator {}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_class, MockedContext};
+
+  #[test]
+  fn create_and_read() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let mut class = test_class(&hooks, BYTES_CLASS_NAME);
+    class.add_field(&hooks, hooks.manage_str(BYTES_FIELD_DATA));
+    let bytes = create_bytes(&hooks, class, &[1, 2, 255]);
+
+    let data = bytes_data(val!(bytes)).expect("Expected bytes");
+    assert_eq!(&*data, &[1, 2, 255]);
+  }
+
+  #[test]
+  fn not_bytes() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let class = test_class(&hooks, "Example");
+    let instance = hooks.manage_obj(Instance::new(class));
+
+    assert!(bytes_data(val!(instance)).is_none());
+    assert!(bytes_data(val!(10.0)).is_none());
+  }
+
+  #[test]
+  fn to_byte_range() {
+    assert_eq!(to_byte(val!(0.0)), Some(0));
+    assert_eq!(to_byte(val!(255.0)), Some(255));
+    assert_eq!(to_byte(val!(256.0)), None);
+    assert_eq!(to_byte(val!(-1.0)), None);
+    assert_eq!(to_byte(val!(1.5)), None);
+    assert_eq!(to_byte(VALUE_NIL), None);
+  }
+}
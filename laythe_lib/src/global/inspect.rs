@@ -0,0 +1,354 @@
+use super::TYPE_ERROR_NAME;
+use crate::{
+  create_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  get,
+  hooks::{GcHooks, Hooks},
+  if_let_obj,
+  managed::{GcObj, GcObject, GcStr, Trace},
+  module::Module,
+  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  to_obj_kind, val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::io::Write;
+
+/// The default depth collections are expanded to before they are elided
+pub const INSPECT_DEPTH: usize = 8;
+
+const INSPECT_META: NativeMetaBuilder = NativeMetaBuilder::fun("inspect", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("value", ParameterKind::Any),
+    ParameterBuilder::new("depth", ParameterKind::Number),
+  ]);
+
+pub(crate) fn add_inspect_funs(hooks: &GcHooks, module: &mut Module) -> StdResult<()> {
+  let type_error = val!(load_class_from_module(hooks, module, TYPE_ERROR_NAME)?);
+  let inspector = Inspector::new(hooks, type_error);
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(INSPECT_META.name),
+    val!(Inspect::native(hooks, inspector)),
+  )
+}
+
+/// Formats values through the `repr` protocol. Lists, maps and tuples are
+/// expanded directly so collections that contain themselves are elided
+/// instead of recursing forever, while instances defer to their class's
+/// `repr` method, falling back to `str`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Inspector {
+  /// reference to 'str'
+  method_str: GcStr,
+
+  /// reference to 'repr'
+  method_repr: GcStr,
+
+  /// error raised when a method does not return a string
+  error: Value,
+}
+
+impl Inspector {
+  /// Create a new inspector raising error when a method does not
+  /// return a string
+  pub fn new(hooks: &GcHooks, error: Value) -> Self {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    Self {
+      method_str: hooks.manage_str("str"),
+      method_repr: hooks.manage_str("repr"),
+      error,
+    }
+  }
+
+  /// Format the provided value into buf, expanding at most depth
+  /// levels of nested collections
+  pub fn inspect(&self, hooks: &mut Hooks, value: Value, depth: usize, buf: &mut String) -> Call {
+    let mut seen = vec![];
+    self.format(hooks, value, depth, &mut seen, buf)
+  }
+
+  fn format(
+    &self,
+    hooks: &mut Hooks,
+    value: Value,
+    depth: usize,
+    seen: &mut Vec<GcObject>,
+    buf: &mut String,
+  ) -> Call {
+    if !value.is_obj() {
+      return self.format_method(hooks, value, self.method_str, buf);
+    }
+
+    let obj = value.to_obj();
+    let elided = match obj.kind() {
+      ObjectKind::List => "[...]",
+      ObjectKind::Map => "{...}",
+      ObjectKind::Tuple => "(...)",
+      ObjectKind::String => {
+        buf.push_str(&format!("{}", obj.to_str()));
+        return Call::Ok(VALUE_NIL);
+      },
+      ObjectKind::Instance => {
+        let method = match obj.to_instance().class().get_method(&self.method_repr) {
+          Some(_) => self.method_repr,
+          None => self.method_str,
+        };
+        return self.format_method(hooks, value, method, buf);
+      },
+      _ => return self.format_method(hooks, value, self.method_str, buf),
+    };
+
+    if depth == 0 || seen.contains(&obj) {
+      buf.push_str(elided);
+      return Call::Ok(VALUE_NIL);
+    }
+
+    seen.push(obj);
+    let result = match obj.kind() {
+      ObjectKind::List => self.format_list(hooks, obj, depth, seen, buf),
+      ObjectKind::Map => self.format_map(hooks, obj, depth, seen, buf),
+      _ => self.format_tuple(hooks, obj, depth, seen, buf),
+    };
+    seen.pop();
+
+    result
+  }
+
+  fn format_list(
+    &self,
+    hooks: &mut Hooks,
+    obj: GcObject,
+    depth: usize,
+    seen: &mut Vec<GcObject>,
+    buf: &mut String,
+  ) -> Call {
+    let list = obj.to_list();
+
+    buf.push('[');
+    for (index, item) in list.iter().enumerate() {
+      if index > 0 {
+        buf.push_str(", ");
+      }
+      get!(self.format(hooks, *item, depth - 1, seen, buf));
+    }
+    buf.push(']');
+
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn format_map(
+    &self,
+    hooks: &mut Hooks,
+    obj: GcObject,
+    depth: usize,
+    seen: &mut Vec<GcObject>,
+    buf: &mut String,
+  ) -> Call {
+    let map = obj.to_map();
+
+    if map.is_empty() {
+      buf.push_str("{}");
+      return Call::Ok(VALUE_NIL);
+    }
+
+    buf.push_str("{ ");
+    for (index, (key, value)) in map.iter().enumerate() {
+      if index > 0 {
+        buf.push_str(", ");
+      }
+      get!(self.format(hooks, *key, depth - 1, seen, buf));
+      buf.push_str(": ");
+      get!(self.format(hooks, *value, depth - 1, seen, buf));
+    }
+    buf.push_str(" }");
+
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn format_tuple(
+    &self,
+    hooks: &mut Hooks,
+    obj: GcObject,
+    depth: usize,
+    seen: &mut Vec<GcObject>,
+    buf: &mut String,
+  ) -> Call {
+    let tuple = obj.to_tuple();
+
+    buf.push('(');
+    for (index, item) in tuple.iter().enumerate() {
+      if index > 0 {
+        buf.push_str(", ");
+      }
+      get!(self.format(hooks, *item, depth - 1, seen, buf));
+    }
+
+    // a single item tuple keeps its trailing comma to tell it apart from a grouping
+    if tuple.len() == 1 {
+      buf.push(',');
+    }
+    buf.push(')');
+
+    Call::Ok(VALUE_NIL)
+  }
+
+  /// Call the named method on value, appending the resulting string to buf
+  fn format_method(
+    &self,
+    hooks: &mut Hooks,
+    value: Value,
+    method: GcStr,
+    buf: &mut String,
+  ) -> Call {
+    let result = get!(hooks
+      .get_method(value, method)
+      .and_then(|method| hooks.call_method(value, method, &[])));
+
+    if_let_obj!(ObjectKind::String(string) = (result) {
+      buf.push_str(&string);
+      Call::Ok(VALUE_NIL)
+    } else {
+      create_error!(
+        self.error,
+        hooks,
+        format!("Expected type str from {}.{}()", value, &*method)
+      )
+    })
+  }
+}
+
+impl Trace for Inspector {
+  fn trace(&self) {
+    self.method_str.trace();
+    self.method_repr.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_str.visit_refs(visitor);
+    self.method_repr.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.method_str.trace_debug(stdout);
+    self.method_repr.trace_debug(stdout);
+    self.error.trace_debug(stdout);
+  }
+}
+
+#[derive(Debug)]
+/// A native function producing a cycle safe representation of a value
+pub struct Inspect {
+  inspector: Inspector,
+}
+
+impl Inspect {
+  /// Construct a new instance of the native inspect function
+  pub fn native(hooks: &GcHooks, inspector: Inspector) -> GcObj<Native> {
+    let native = Box::new(Self { inspector }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(INSPECT_META.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for Inspect {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let depth = match args.get(1) {
+      Some(depth) => depth.to_num().max(0.0) as usize,
+      None => INSPECT_DEPTH,
+    };
+
+    let mut buf = String::new();
+    get!(self.inspector.inspect(hooks, args[0], depth, &mut buf));
+
+    Call::Ok(val!(hooks.manage_str(buf)))
+  }
+}
+
+impl Trace for Inspect {
+  fn trace(&self) {
+    self.inspector.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.inspector.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.inspector.trace_debug(stdout);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::test_error_class;
+  use laythe_core::{hooks::NoContext, object::List};
+
+  fn test_inspector(hooks: &GcHooks) -> Inspector {
+    Inspector::new(hooks, val!(test_error_class(hooks)))
+  }
+
+  mod inspect {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = NoContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let inspect = Inspect::native(&hooks, test_inspector(&hooks));
+
+      assert_eq!(&*inspect.meta().name, "inspect");
+      assert_eq!(inspect.meta().signature.arity, Arity::Default(1, 2));
+      assert_eq!(
+        inspect.meta().signature.parameters[0].kind,
+        ParameterKind::Any
+      );
+      assert_eq!(
+        inspect.meta().signature.parameters[1].kind,
+        ParameterKind::Number
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = NoContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let inspect = Inspect::native(&hooks.as_gc(), test_inspector(&hooks.as_gc()));
+      let inner = hooks.manage_obj(List::from(vec![val!(hooks.manage_str("b"))]));
+      let list = hooks.manage_obj(List::from(vec![val!(hooks.manage_str("a")), val!(inner)]));
+
+      let result = inspect.call(&mut hooks, None, &[val!(list)]).unwrap();
+      assert_eq!(&*result.to_obj().to_str(), "['a', ['b']]");
+
+      let result = inspect
+        .call(&mut hooks, None, &[val!(list), val!(1.0)])
+        .unwrap();
+      assert_eq!(&*result.to_obj().to_str(), "['a', [...]]");
+    }
+
+    #[test]
+    fn cycle() {
+      let mut context = NoContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let inspect = Inspect::native(&hooks.as_gc(), test_inspector(&hooks.as_gc()));
+      let mut list = hooks.manage_obj(List::from(vec![val!(hooks.manage_str("a"))]));
+      let value = val!(list);
+      list.push(value);
+
+      let result = inspect.call(&mut hooks, None, &[val!(list)]).unwrap();
+      assert_eq!(&*result.to_obj().to_str(), "['a', [...]]");
+    }
+  }
+}
@@ -1,13 +1,22 @@
-use crate::{native, support::export_and_insert, StdError, StdResult};
+use super::{
+  primitives::{error::FORMAT_CLASS_NAME, string::format_template},
+  Inspector, INSPECT_DEPTH, PROPERTY_ERROR_NAME, RUNTIME_ERROR_NAME, TYPE_ERROR_NAME,
+};
+use crate::{
+  native, native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdError, StdResult,
+};
 use laythe_core::{
   get,
   hooks::{GcHooks, Hooks},
+  if_let_obj,
   managed::GcObj,
-  managed::{GcStr, Trace},
+  managed::Trace,
   module::Module,
-  object::{LyNative, Native, NativeMetaBuilder},
+  object::{LyNative, Method, Native, NativeMetaBuilder, ObjectKind},
   signature::{Arity, ParameterBuilder, ParameterKind},
-  val,
+  to_obj_kind, val,
   value::{Value, VALUE_NIL},
   Call,
 };
@@ -21,17 +30,63 @@ const PRINT: NativeMetaBuilder = NativeMetaBuilder::fun("print", Arity::Variadic
   .with_params(&[ParameterBuilder::new("values", ParameterKind::Any)]);
 // .with_stack();
 
+const PRINT_ERR_META: NativeMetaBuilder = NativeMetaBuilder::fun("printErr", Arity::Variadic(0))
+  .with_params(&[ParameterBuilder::new("values", ParameterKind::Any)]);
+
+const FMT_META: NativeMetaBuilder =
+  NativeMetaBuilder::fun("fmt", Arity::Variadic(1)).with_params(&[
+    ParameterBuilder::new("template", ParameterKind::String),
+    ParameterBuilder::new("values", ParameterKind::Any),
+  ]);
+
 const EXIT_META: NativeMetaBuilder = NativeMetaBuilder::fun("exit", Arity::Default(0, 1))
   .with_params(&[ParameterBuilder::new("code", ParameterKind::Number)]);
 
+const ON_EXIT_META: NativeMetaBuilder = NativeMetaBuilder::fun("onExit", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("hook", ParameterKind::Fun)]);
+
+const TYPE_META: NativeMetaBuilder = NativeMetaBuilder::fun("type", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("value", ParameterKind::Any)]);
+
+const GET_ATTR_META: NativeMetaBuilder = NativeMetaBuilder::fun("getAttr", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("obj", ParameterKind::Any),
+    ParameterBuilder::new("name", ParameterKind::String),
+  ]);
+
+const SET_ATTR_META: NativeMetaBuilder = NativeMetaBuilder::fun("setAttr", Arity::Fixed(3))
+  .with_params(&[
+    ParameterBuilder::new("obj", ParameterKind::Any),
+    ParameterBuilder::new("name", ParameterKind::String),
+    ParameterBuilder::new("value", ParameterKind::Any),
+  ]);
+
 pub fn declare_misc_funs(hooks: &GcHooks, module: &mut Module) -> StdResult<()> {
-  let str_name = hooks.manage_str("str");
+  let property_error = val!(load_class_from_module(hooks, module, PROPERTY_ERROR_NAME)?);
+  let runtime_error = val!(load_class_from_module(hooks, module, RUNTIME_ERROR_NAME)?);
+  let type_error = val!(load_class_from_module(hooks, module, TYPE_ERROR_NAME)?);
+  let format_error = val!(load_class_from_module(hooks, module, FORMAT_CLASS_NAME)?);
+  let inspector = Inspector::new(hooks, type_error);
 
   export_and_insert(
     hooks,
     module,
     hooks.manage_str(PRINT.name),
-    val!(Print::native(hooks, str_name)),
+    val!(Print::native(hooks, inspector)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(PRINT_ERR_META.name),
+    val!(PrintErr::native(hooks, inspector)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(FMT_META.name),
+    val!(Fmt::native(hooks, inspector, format_error)),
   )?;
 
   export_and_insert(
@@ -39,46 +94,82 @@ pub fn declare_misc_funs(hooks: &GcHooks, module: &mut Module) -> StdResult<()>
     module,
     hooks.manage_str(EXIT_META.name),
     val!(Exit::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(ON_EXIT_META.name),
+    val!(OnExit::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(TYPE_META.name),
+    val!(Type::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(GET_ATTR_META.name),
+    val!(GetAttr::native(hooks, property_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(SET_ATTR_META.name),
+    val!(SetAttr::native(hooks, runtime_error)),
   )
   .map_err(StdError::from)
 }
 
 #[derive(Debug)]
-/// A native method to assert that for a boolean true value
+/// A native function to print values to stdout
 pub struct Print {
-  /// reference to 'str'
-  method_str: GcStr,
+  /// formatter for non string values
+  inspector: Inspector,
 }
 
 impl Print {
-  /// Construct a new instance of the native assert function
-  pub fn native(hooks: &GcHooks, method_str: GcStr) -> GcObj<Native> {
-    let native = Box::new(Self { method_str }) as Box<dyn LyNative>;
+  /// Construct a new instance of the native print function
+  pub fn native(hooks: &GcHooks, inspector: Inspector) -> GcObj<Native> {
+    let native = Box::new(Self { inspector }) as Box<dyn LyNative>;
 
     hooks.manage_obj(Native::new(PRINT.to_meta(hooks), native))
   }
 }
 
-impl LyNative for Print {
-  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
-    let str_method = get!(hooks.get_method(args[0], self.method_str));
-    let mut output = String::from(
-      &*get!(hooks.call_method(args[0], str_method, &[]))
-        .to_obj()
-        .to_str(),
-    );
-
-    for s in args.iter().skip(1) {
-      let str_method = get!(hooks.get_method(*s, self.method_str));
-
+/// Join values with a space between each. Strings are written as
+/// is while other values are inspected
+fn join_values(
+  inspector: &Inspector,
+  hooks: &mut Hooks,
+  args: &[Value],
+  output: &mut String,
+) -> Call {
+  for (index, arg) in args.iter().enumerate() {
+    if index > 0 {
       output.push(' ');
-      output.push_str(
-        &get!(hooks.call_method(*s, str_method, &[]))
-          .to_obj()
-          .to_str(),
-      )
     }
 
+    if_let_obj!(ObjectKind::String(string) = (*arg) {
+      output.push_str(&string);
+    } else {
+      get!(inspector.inspect(hooks, *arg, INSPECT_DEPTH, output));
+    });
+  }
+
+  Call::Ok(VALUE_NIL)
+}
+
+impl LyNative for Print {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let mut output = String::new();
+    get!(join_values(&self.inspector, hooks, args, &mut output));
+
     let mut stdio = hooks.as_io().stdio();
     match writeln!(stdio.stdout(), "{}", output) {
       Ok(_) => Call::Ok(VALUE_NIL),
@@ -89,11 +180,100 @@ impl LyNative for Print {
 
 impl Trace for Print {
   fn trace(&self) {
-    self.method_str.trace();
+    self.inspector.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.inspector.visit_refs(visitor);
   }
 
   fn trace_debug(&self, stdout: &mut dyn Write) {
-    self.method_str.trace_debug(stdout);
+    self.inspector.trace_debug(stdout);
+  }
+}
+
+#[derive(Debug)]
+/// A native function to print values to stderr
+pub struct PrintErr {
+  /// formatter for non string values
+  inspector: Inspector,
+}
+
+impl PrintErr {
+  /// Construct a new instance of the native printErr function
+  pub fn native(hooks: &GcHooks, inspector: Inspector) -> GcObj<Native> {
+    let native = Box::new(Self { inspector }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(PRINT_ERR_META.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for PrintErr {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let mut output = String::new();
+    get!(join_values(&self.inspector, hooks, args, &mut output));
+
+    let mut stdio = hooks.as_io().stdio();
+    match writeln!(stdio.stderr(), "{}", output) {
+      Ok(_) => Call::Ok(VALUE_NIL),
+      Err(err) => panic!("TODO return some sort of io error {}", err),
+    }
+  }
+}
+
+impl Trace for PrintErr {
+  fn trace(&self) {
+    self.inspector.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.inspector.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.inspector.trace_debug(stdout);
+  }
+}
+
+#[derive(Debug)]
+/// A native function to fill a template's placeholders with values
+pub struct Fmt {
+  /// formatter for non string values
+  inspector: Inspector,
+  error: Value,
+}
+
+impl Fmt {
+  /// Construct a new instance of the native fmt function
+  pub fn native(hooks: &GcHooks, inspector: Inspector, error: Value) -> GcObj<Native> {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    let native = Box::new(Self { inspector, error }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(FMT_META.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for Fmt {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let template = args[0].to_obj().to_str();
+    format_template(hooks, &self.inspector, self.error, &template, &args[1..])
+  }
+}
+
+impl Trace for Fmt {
+  fn trace(&self) {
+    self.inspector.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.inspector.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.inspector.trace_debug(stdout);
+    self.error.trace_debug(stdout);
   }
 }
 
@@ -111,6 +291,76 @@ impl LyNative for Exit {
   }
 }
 
+native!(OnExit, ON_EXIT_META);
+
+impl LyNative for OnExit {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    hooks.add_exit_hook(args[0]);
+    Call::Ok(VALUE_NIL)
+  }
+}
+
+native!(Type, TYPE_META);
+
+impl LyNative for Type {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    Call::Ok(hooks.get_class(args[0]))
+  }
+}
+
+native_with_error!(GetAttr, GET_ATTR_META);
+
+impl LyNative for GetAttr {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let name = args[1].to_obj().to_str();
+
+    if_let_obj!(ObjectKind::Instance(instance) = (args[0]) {
+      if let Some(field) = instance.get_field(&name) {
+        return Call::Ok(*field);
+      }
+    });
+
+    let class = hooks.get_class(args[0]).to_obj().to_class();
+    match class.get_method(&name) {
+      Some(method) => Call::Ok(val!(hooks.manage_obj(Method::new(args[0], method)))),
+      None => self.call_error(
+        hooks,
+        format!("Undefined property {} on class {}.", name, class.name()),
+      ),
+    }
+  }
+}
+
+native_with_error!(SetAttr, SET_ATTR_META);
+
+impl LyNative for SetAttr {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let name = args[1].to_obj().to_str();
+
+    if_let_obj!(ObjectKind::Instance(mut instance) = (args[0]) {
+      if instance.is_frozen() {
+        return self.call_error(
+          hooks,
+          format!("Cannot set property {} on a frozen {} instance.", name, instance.class().name()),
+        );
+      }
+
+      let class = instance.class();
+      if class.is_sealed() && class.get_field_index(&name).is_none() {
+        return self.call_error(
+          hooks,
+          format!("Cannot add field {} to a sealed {} instance.", name, class.name()),
+        );
+      }
+
+      instance.set_dynamic_field(&hooks.as_gc(), name, args[2]);
+      return Call::Ok(args[2]);
+    });
+
+    self.call_error(hooks, "Only instances have settable fields.")
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -120,13 +370,14 @@ mod test {
     use laythe_core::memory::NO_GC;
 
     use super::*;
-    use crate::support::MockedContext;
+    use crate::support::{test_error_class, MockedContext};
 
     #[test]
     fn new() {
       let mut context = MockedContext::default();
       let hooks = GcHooks::new(&mut context);
-      let assert = Print::native(&hooks, hooks.manage_str("str".to_string()));
+      let error = val!(test_error_class(&hooks));
+      let assert = Print::native(&hooks, Inspector::new(&hooks, error));
 
       assert_eq!(&*assert.meta().name, "print");
       assert_eq!(assert.meta().signature.arity, Arity::Variadic(0));
@@ -144,7 +395,8 @@ mod test {
 
       let mut hooks = Hooks::new(&mut context);
 
-      let print = Print::native(&hooks.as_gc(), hooks.manage_str("str".to_string()));
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let print = Print::native(&hooks.as_gc(), Inspector::new(&hooks.as_gc(), error));
       let values = &[val!(true)];
 
       let result = print.call(&mut hooks, None, values).unwrap();
@@ -152,4 +404,219 @@ mod test {
       assert_eq!(result, VALUE_NIL);
     }
   }
+  #[cfg(test)]
+  mod print_err {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+      let print_err = PrintErr::native(&hooks, Inspector::new(&hooks, error));
+
+      assert_eq!(&*print_err.meta().name, "printErr");
+      assert_eq!(print_err.meta().signature.arity, Arity::Variadic(0));
+      assert_eq!(
+        print_err.meta().signature.parameters[0].kind,
+        ParameterKind::Any
+      );
+    }
+  }
+
+  #[cfg(test)]
+  mod fmt {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+    use laythe_core::object::Map;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+      let fmt = Fmt::native(&hooks, Inspector::new(&hooks, error), error);
+
+      assert_eq!(&*fmt.meta().name, "fmt");
+      assert_eq!(fmt.meta().signature.arity, Arity::Variadic(1));
+      assert_eq!(
+        fmt.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+      assert_eq!(fmt.meta().signature.parameters[1].kind, ParameterKind::Any);
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let fmt = Fmt::native(&hooks.as_gc(), Inspector::new(&hooks.as_gc(), error), error);
+
+      let template = val!(hooks.manage_str("{greeting} {}"));
+      let mut map = hooks.manage_obj(Map::default());
+      map.insert(
+        val!(hooks.manage_str("greeting")),
+        val!(hooks.manage_str("hi")),
+      );
+
+      let result = fmt.call(&mut hooks, None, &[template, val!(map)]).unwrap();
+      assert_eq!(&*result.to_obj().to_str(), "hi { 'greeting': 'hi' }");
+    }
+  }
+
+  #[cfg(test)]
+  mod on_exit {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let mut context = MockedContext::default();
+      let hooks = GcHooks::new(&mut context);
+      let on_exit = OnExit::native(&hooks);
+
+      assert_eq!(&*on_exit.meta().name, "onExit");
+      assert_eq!(on_exit.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        on_exit.meta().signature.parameters[0].kind,
+        ParameterKind::Fun
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let hook = val!(Exit::native(&GcHooks::new(&mut context)));
+      let mut hooks = Hooks::new(&mut context);
+
+      let on_exit = OnExit::native(&hooks.as_gc());
+      let result = on_exit.call(&mut hooks, None, &[hook]).unwrap();
+
+      assert_eq!(result, VALUE_NIL);
+      assert_eq!(context.exit_hooks, vec![hook]);
+    }
+  }
+
+  #[cfg(test)]
+  mod type_ {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let type_ = Type::native(&hooks);
+
+      assert_eq!(&*type_.meta().name, "type");
+      assert_eq!(type_.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        type_.meta().signature.parameters[0].kind,
+        ParameterKind::Any
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::with_std(&[]).unwrap();
+      let mut hooks = Hooks::new(&mut context);
+
+      let type_ = Type::native(&hooks.as_gc());
+      let result = type_.call(&mut hooks, None, &[val!(10.0)]).unwrap();
+
+      assert_eq!(&*result.to_obj().to_class().name(), "Number");
+    }
+  }
+
+  #[cfg(test)]
+  mod get_attr {
+    use super::*;
+    use crate::support::{test_class, test_error_class, MockedContext};
+    use laythe_core::object::Instance;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+      let get_attr = GetAttr::native(&hooks, error);
+
+      assert_eq!(&*get_attr.meta().name, "getAttr");
+      assert_eq!(get_attr.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(
+        get_attr.meta().signature.parameters[0].kind,
+        ParameterKind::Any
+      );
+      assert_eq!(
+        get_attr.meta().signature.parameters[1].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let get_attr = GetAttr::native(&hooks.as_gc(), error);
+
+      let class = test_class(&hooks.as_gc(), "Point");
+      let mut instance = hooks.manage_obj(Instance::new(class));
+      let name = hooks.manage_str("x");
+      instance.set_dynamic_field(&hooks.as_gc(), name, val!(3.0));
+
+      let result = get_attr
+        .call(&mut hooks, None, &[val!(instance), val!(name)])
+        .unwrap();
+
+      assert_eq!(result, val!(3.0));
+    }
+  }
+
+  #[cfg(test)]
+  mod set_attr {
+    use super::*;
+    use crate::support::{test_class, test_error_class, MockedContext};
+    use laythe_core::object::Instance;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+      let set_attr = SetAttr::native(&hooks, error);
+
+      assert_eq!(&*set_attr.meta().name, "setAttr");
+      assert_eq!(set_attr.meta().signature.arity, Arity::Fixed(3));
+      assert_eq!(
+        set_attr.meta().signature.parameters[1].kind,
+        ParameterKind::String
+      );
+      assert_eq!(
+        set_attr.meta().signature.parameters[2].kind,
+        ParameterKind::Any
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let set_attr = SetAttr::native(&hooks.as_gc(), error);
+
+      let class = test_class(&hooks.as_gc(), "Point");
+      let instance = hooks.manage_obj(Instance::new(class));
+      let name = hooks.manage_str("x");
+
+      let result = set_attr
+        .call(&mut hooks, None, &[val!(instance), val!(name), val!(2.0)])
+        .unwrap();
+
+      assert_eq!(result, val!(2.0));
+      assert_eq!(instance.get_field(&name), Some(&val!(2.0)));
+    }
+  }
 }
@@ -1,4 +1,6 @@
 mod assert;
+mod bytes;
+mod inspect;
 mod misc;
 mod primitives;
 mod time;
@@ -8,16 +10,23 @@ mod support;
 
 use crate::{StdResult, STD};
 use self::assert::add_assert_funs;
+use bytes::add_bytes_class;
+use inspect::add_inspect_funs;
 use laythe_core::{hooks::GcHooks, managed::Gc, module::Package, utils::IdEmitter};
 use misc::add_misc_funs;
 use time::add_clock_funs;
 
+pub use assert::ASSERT_ERROR_NAME;
+pub use bytes::BYTES_CLASS_NAME;
+pub(crate) use bytes::{bytes_data, create_bytes, input_bytes};
+pub(crate) use inspect::{Inspector, INSPECT_DEPTH};
+pub(crate) use misc::Exit;
 pub use primitives::{
-  bool::BOOL_CLASS_NAME, class::CLASS_CLASS_NAME, closure::CLOSURE_CLASS_NAME,
+  bool::BOOL_CLASS_NAME, channel::CHANNEL_CLASS_NAME, class::CLASS_CLASS_NAME, closure::CLOSURE_CLASS_NAME,
   fiber::FIBER_CLASS_NAME, iter::ITER_CLASS_NAME, list::LIST_CLASS_NAME, map::MAP_CLASS_NAME,
   method::METHOD_CLASS_NAME, module::MODULE_CLASS_NAME, native::NATIVE_CLASS_NAME,
   nil::NIL_CLASS_NAME, number::NUMBER_CLASS_NAME, object::OBJECT_CLASS_NAME,
-  string::STRING_CLASS_NAME,
+  string::STRING_CLASS_NAME, tuple::TUPLE_CLASS_NAME,
 };
 
 pub fn create_std_core(hooks: &GcHooks, emitter: &mut IdEmitter) -> StdResult<Gc<Package>> {
@@ -28,7 +37,9 @@ pub fn create_std_core(hooks: &GcHooks, emitter: &mut IdEmitter) -> StdResult<Gc
   ));
 
   add_assert_funs(hooks, &mut global_module, &std)?;
+  add_bytes_class(hooks, &mut global_module, &std)?;
   add_clock_funs(hooks, &mut global_module)?;
+  add_inspect_funs(hooks, &mut global_module)?;
   add_misc_funs(hooks, &mut global_module)?;
 
   Ok(std)
@@ -36,8 +47,8 @@ pub fn create_std_core(hooks: &GcHooks, emitter: &mut IdEmitter) -> StdResult<Gc
 
 pub use primitives::error::{
   ERROR_CLASS_NAME, EXPORT_ERROR_NAME, IMPORT_ERROR_NAME, INDEX_ERROR_NAME,
-  METHOD_NOT_FOUND_ERROR_NAME, PROPERTY_ERROR_NAME, RUNTIME_ERROR_NAME, SYNTAX_ERROR_NAME,
-  TYPE_ERROR_NAME, VALUE_ERROR_NAME,
+  METHOD_NOT_FOUND_ERROR_NAME, OUT_OF_MEMORY_ERROR_NAME, PROPERTY_ERROR_NAME, RUNTIME_ERROR_NAME,
+  SYNTAX_ERROR_NAME, TIMEOUT_ERROR_NAME, TYPE_ERROR_NAME, VALUE_ERROR_NAME,
 };
 
 use self::primitives::create_primitives;
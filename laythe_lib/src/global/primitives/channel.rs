@@ -0,0 +1,559 @@
+use super::{
+  class_inheritance,
+  error::{RUNTIME_ERROR_NAME, VALUE_ERROR_NAME},
+};
+use crate::{
+  native, native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::Module,
+  object::{Channel, Enumerate, Enumerator, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call, LyResult,
+};
+use std::io::Write;
+use std::mem;
+
+pub const CHANNEL_CLASS_NAME: &str = "Channel";
+
+const CHANNEL_NEW: NativeMetaBuilder = NativeMetaBuilder::fun("new", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("capacity", ParameterKind::Number)]);
+
+const CHANNEL_SEND: NativeMetaBuilder = NativeMetaBuilder::method("send", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("value", ParameterKind::Any)]);
+
+const CHANNEL_RECEIVE: NativeMetaBuilder = NativeMetaBuilder::method("receive", Arity::Fixed(0));
+const CHANNEL_CLOSE: NativeMetaBuilder = NativeMetaBuilder::method("close", Arity::Fixed(0));
+const CHANNEL_LEN: NativeMetaBuilder = NativeMetaBuilder::method("len", Arity::Fixed(0));
+const CHANNEL_CAPACITY: NativeMetaBuilder = NativeMetaBuilder::method("capacity", Arity::Fixed(0));
+const CHANNEL_ITER: NativeMetaBuilder = NativeMetaBuilder::method("iter", Arity::Fixed(0));
+const CHANNEL_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
+
+pub fn declare_channel_class(hooks: &GcHooks, module: &mut Module) -> StdResult<()> {
+  let class = class_inheritance(hooks, module, CHANNEL_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_channel_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, CHANNEL_CLASS_NAME)?;
+  let runtime_error = val!(load_class_from_module(hooks, module, RUNTIME_ERROR_NAME)?);
+  let value_error = val!(load_class_from_module(hooks, module, VALUE_ERROR_NAME)?);
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(CHANNEL_SEND.name),
+    val!(ChannelSend::native(hooks, runtime_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(CHANNEL_RECEIVE.name),
+    val!(ChannelReceive::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(CHANNEL_CLOSE.name),
+    val!(ChannelClose::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(CHANNEL_LEN.name),
+    val!(ChannelLen::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(CHANNEL_CAPACITY.name),
+    val!(ChannelCapacity::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(CHANNEL_ITER.name),
+    val!(ChannelIter::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(CHANNEL_STR.name),
+    val!(ChannelStr::native(hooks)),
+  );
+
+  class.meta_class().expect("Meta class not set.").add_method(
+    hooks,
+    hooks.manage_str(CHANNEL_NEW.name),
+    val!(ChannelNew::native(hooks, value_error)),
+  );
+
+  Ok(())
+}
+
+/// Wake the longest waiting receiver that is still blocked
+fn wake_receiver(hooks: &mut Hooks, channel: &mut Channel) -> bool {
+  while let Some(fiber) = channel.take_receiver() {
+    if hooks.wake_fiber(fiber) {
+      return true;
+    }
+  }
+
+  false
+}
+
+/// Wake the longest waiting sender that is still blocked
+fn wake_sender(hooks: &mut Hooks, channel: &mut Channel) -> bool {
+  while let Some(fiber) = channel.take_sender() {
+    if hooks.wake_fiber(fiber) {
+      return true;
+    }
+  }
+
+  false
+}
+
+/// Receive a value from a channel. None indicates the channel is closed
+/// and drained, otherwise the current fiber blocks until a value arrives
+fn receive(hooks: &mut Hooks, mut channel: GcObj<Channel>) -> LyResult<Option<Value>> {
+  match channel.pop() {
+    Some(value) => {
+      wake_sender(hooks, &mut channel);
+      LyResult::Ok(Some(value))
+    },
+    None => {
+      if channel.is_closed() {
+        return LyResult::Ok(None);
+      }
+
+      let fiber = hooks.current_fiber();
+      hooks.grow(&mut *channel, |channel| channel.add_receiver(fiber));
+
+      // a rendezvous sender can now hand off its value
+      wake_sender(hooks, &mut channel);
+      LyResult::Block
+    },
+  }
+}
+
+native_with_error!(ChannelNew, CHANNEL_NEW);
+
+impl LyNative for ChannelNew {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let capacity = if args.is_empty() {
+      0.0
+    } else {
+      args[0].to_num()
+    };
+
+    if capacity < 0.0 || capacity.fract() != 0.0 {
+      return self.call_error(hooks, "Channel capacity must be a non negative integer.");
+    }
+
+    Call::Ok(val!(hooks.manage_obj(Channel::new(capacity as usize))))
+  }
+}
+
+native_with_error!(ChannelSend, CHANNEL_SEND);
+
+impl LyNative for ChannelSend {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut channel = this.unwrap().to_obj().to_channel();
+
+    if channel.is_closed() {
+      return self.call_error(hooks, "Cannot send on a closed channel.");
+    }
+
+    if channel.has_space() {
+      hooks.grow(&mut *channel, |channel| channel.push(args[0]));
+      wake_receiver(hooks, &mut channel);
+      return Call::Ok(VALUE_NIL);
+    }
+
+    // a rendezvous send completes only when handed to a waiting receiver
+    if channel.is_rendezvous() && channel.is_empty() && wake_receiver(hooks, &mut channel) {
+      hooks.grow(&mut *channel, |channel| channel.push(args[0]));
+      return Call::Ok(VALUE_NIL);
+    }
+
+    let fiber = hooks.current_fiber();
+    hooks.grow(&mut *channel, |channel| channel.add_sender(fiber));
+    Call::Block
+  }
+}
+
+native!(ChannelReceive, CHANNEL_RECEIVE);
+
+impl LyNative for ChannelReceive {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    receive(hooks, this.unwrap().to_obj().to_channel()).map(|value| value.unwrap_or(VALUE_NIL))
+  }
+}
+
+native!(ChannelClose, CHANNEL_CLOSE);
+
+impl LyNative for ChannelClose {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let mut channel = this.unwrap().to_obj().to_channel();
+    channel.close();
+
+    // blocked fibers retry and observe the closed channel
+    for fiber in channel.take_blocked() {
+      hooks.wake_fiber(fiber);
+    }
+
+    Call::Ok(VALUE_NIL)
+  }
+}
+
+native!(ChannelLen, CHANNEL_LEN);
+
+impl LyNative for ChannelLen {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(val!(this.unwrap().to_obj().to_channel().len() as f64))
+  }
+}
+
+native!(ChannelCapacity, CHANNEL_CAPACITY);
+
+impl LyNative for ChannelCapacity {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(val!(this.unwrap().to_obj().to_channel().capacity() as f64))
+  }
+}
+
+native!(ChannelIter, CHANNEL_ITER);
+
+impl LyNative for ChannelIter {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let inner_iter: Box<dyn Enumerate> =
+      Box::new(ChannelIterator::new(this.unwrap().to_obj().to_channel()));
+    let iter = Enumerator::new(inner_iter);
+    let iter = hooks.manage_obj(iter);
+
+    Call::Ok(val!(iter))
+  }
+}
+
+native!(ChannelStr, CHANNEL_STR);
+
+impl LyNative for ChannelStr {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap();
+    let class = hooks.get_class(this).to_obj().to_class();
+    let channel = this.to_obj().to_channel();
+
+    Call::Ok(val!(hooks.manage_str(format!(
+      "<{} {:p}>",
+      &*class.name(),
+      &*channel
+    ))))
+  }
+}
+
+#[derive(Debug)]
+struct ChannelIterator {
+  channel: GcObj<Channel>,
+  current: Value,
+}
+
+impl ChannelIterator {
+  fn new(channel: GcObj<Channel>) -> Self {
+    Self {
+      channel,
+      current: VALUE_NIL,
+    }
+  }
+}
+
+impl Enumerate for ChannelIterator {
+  fn name(&self) -> &str {
+    "ChannelIterator"
+  }
+
+  fn current(&self) -> Value {
+    self.current
+  }
+
+  fn next(&mut self, hooks: &mut Hooks) -> Call {
+    match receive(hooks, self.channel) {
+      LyResult::Ok(Some(value)) => {
+        self.current = value;
+        Call::Ok(val!(true))
+      },
+      LyResult::Ok(None) => {
+        self.current = VALUE_NIL;
+        Call::Ok(val!(false))
+      },
+      LyResult::Err(err) => Call::Err(err),
+      LyResult::Exit(code) => Call::Exit(code),
+      LyResult::Block => Call::Block,
+    }
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    None
+  }
+
+  fn size(&self) -> usize {
+    mem::size_of::<Self>()
+  }
+}
+
+impl Trace for ChannelIterator {
+  fn trace(&self) {
+    self.channel.trace();
+    self.current.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.channel.visit_refs(visitor);
+    self.current.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.channel.trace_debug(stdout);
+    self.current.trace_debug(stdout);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  mod new {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let channel_new = ChannelNew::native(&hooks, error);
+
+      assert_eq!(channel_new.meta().name, "new");
+      assert_eq!(channel_new.meta().signature.arity, Arity::Default(0, 1));
+      assert_eq!(
+        channel_new.meta().signature.parameters[0].kind,
+        ParameterKind::Number
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let channel_new = ChannelNew::native(&hooks.as_gc(), error);
+
+      let result = channel_new.call(&mut hooks, None, &[]).unwrap();
+      assert!(result.to_obj().to_channel().is_rendezvous());
+
+      let result = channel_new.call(&mut hooks, None, &[val!(3.0)]).unwrap();
+      assert_eq!(result.to_obj().to_channel().capacity(), 3);
+    }
+  }
+
+  mod send {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let channel_send = ChannelSend::native(&hooks, error);
+
+      assert_eq!(channel_send.meta().name, "send");
+      assert_eq!(channel_send.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        channel_send.meta().signature.parameters[0].kind,
+        ParameterKind::Any
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let channel_send = ChannelSend::native(&hooks.as_gc(), error);
+      let channel = hooks.manage_obj(Channel::new(1));
+
+      let result = channel_send.call(&mut hooks, Some(val!(channel)), &[val!(1.0)]);
+      assert_eq!(result, Call::Ok(VALUE_NIL));
+      assert_eq!(channel.len(), 1);
+
+      let result = channel_send.call(&mut hooks, Some(val!(channel)), &[val!(2.0)]);
+      assert!(result.is_block());
+      assert_eq!(channel.len(), 1);
+    }
+  }
+
+  mod receive {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let channel_receive = ChannelReceive::native(&hooks);
+
+      assert_eq!(channel_receive.meta().name, "receive");
+      assert_eq!(channel_receive.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let channel_receive = ChannelReceive::native(&hooks.as_gc());
+      let mut channel = hooks.manage_obj(Channel::new(1));
+      channel.push(val!(true));
+
+      let result = channel_receive.call(&mut hooks, Some(val!(channel)), &[]);
+      assert_eq!(result, Call::Ok(val!(true)));
+
+      let result = channel_receive.call(&mut hooks, Some(val!(channel)), &[]);
+      assert!(result.is_block());
+
+      channel.close();
+      let result = channel_receive.call(&mut hooks, Some(val!(channel)), &[]);
+      assert_eq!(result, Call::Ok(VALUE_NIL));
+    }
+  }
+
+  mod close {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let channel_close = ChannelClose::native(&hooks);
+
+      assert_eq!(channel_close.meta().name, "close");
+      assert_eq!(channel_close.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let channel_close = ChannelClose::native(&hooks.as_gc());
+      let channel = hooks.manage_obj(Channel::new(0));
+
+      let result = channel_close.call(&mut hooks, Some(val!(channel)), &[]);
+      assert_eq!(result, Call::Ok(VALUE_NIL));
+      assert!(channel.is_closed());
+    }
+  }
+
+  mod len {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let channel_len = ChannelLen::native(&hooks);
+
+      assert_eq!(channel_len.meta().name, "len");
+      assert_eq!(channel_len.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let channel_len = ChannelLen::native(&hooks.as_gc());
+      let mut channel = hooks.manage_obj(Channel::new(2));
+      channel.push(VALUE_NIL);
+
+      let result = channel_len.call(&mut hooks, Some(val!(channel)), &[]);
+      assert_eq!(result, Call::Ok(val!(1.0)));
+    }
+  }
+
+  mod capacity {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let channel_capacity = ChannelCapacity::native(&hooks);
+
+      assert_eq!(channel_capacity.meta().name, "capacity");
+      assert_eq!(channel_capacity.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let channel_capacity = ChannelCapacity::native(&hooks.as_gc());
+      let channel = hooks.manage_obj(Channel::new(2));
+
+      let result = channel_capacity.call(&mut hooks, Some(val!(channel)), &[]);
+      assert_eq!(result, Call::Ok(val!(2.0)));
+    }
+  }
+
+  mod iter {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let channel_iter = ChannelIter::native(&hooks);
+
+      assert_eq!(channel_iter.meta().name, "iter");
+      assert_eq!(channel_iter.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let channel_iter = ChannelIter::native(&hooks.as_gc());
+      let mut channel = hooks.manage_obj(Channel::new(2));
+      channel.push(val!(1.0));
+      channel.close();
+
+      let result = channel_iter.call(&mut hooks, Some(val!(channel)), &[]);
+      let mut iter = result.unwrap().to_obj().to_enumerator();
+
+      assert_eq!(iter.next(&mut hooks).unwrap(), val!(true));
+      assert_eq!(iter.current(), val!(1.0));
+      assert_eq!(iter.next(&mut hooks).unwrap(), val!(false));
+    }
+  }
+}
@@ -2,7 +2,7 @@ use crate::native;
 use laythe_core::{
   hooks::{GcHooks, Hooks},
   managed::{GcObj, Trace},
-  object::{Class, LyNative, Native, NativeMetaBuilder},
+  object::{Class, List, LyNative, Native, NativeMetaBuilder},
   signature::Arity,
   val,
   value::{Value, VALUE_NIL},
@@ -15,6 +15,8 @@ pub const CLASS_CLASS_NAME: &str = "Class";
 const CLASS_SUPER_CLS: NativeMetaBuilder = NativeMetaBuilder::method("superCls", Arity::Fixed(0));
 const CLASS_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
 const CLASS_NAME: NativeMetaBuilder = NativeMetaBuilder::method("name", Arity::Fixed(0));
+const CLASS_METHODS: NativeMetaBuilder = NativeMetaBuilder::method("methods", Arity::Fixed(0));
+const CLASS_FIELDS: NativeMetaBuilder = NativeMetaBuilder::method("fields", Arity::Fixed(0));
 
 pub fn create_class_class(hooks: &GcHooks, object: GcObj<Class>) -> GcObj<Class> {
   let name = hooks.manage_str(CLASS_CLASS_NAME);
@@ -39,6 +41,18 @@ pub fn create_class_class(hooks: &GcHooks, object: GcObj<Class>) -> GcObj<Class>
     val!(ClassName::native(hooks)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(CLASS_METHODS.name),
+    val!(ClassMethods::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(CLASS_FIELDS.name),
+    val!(ClassFields::native(hooks)),
+  );
+
   class
 }
 
@@ -81,6 +95,36 @@ impl LyNative for ClassName {
   }
 }
 
+native!(ClassMethods, CLASS_METHODS);
+
+impl LyNative for ClassMethods {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let class = this.unwrap().to_obj().to_class();
+
+    // methods are stored in a hash map so sort them for a stable order
+    let mut names: Vec<_> = class.methods().map(|(name, _)| *name).collect();
+    names.sort_by_key(|name| name.to_string());
+
+    let names: Vec<Value> = names.into_iter().map(|name| val!(name)).collect();
+    Call::Ok(val!(hooks.manage_obj(List::from(names))))
+  }
+}
+
+native!(ClassFields, CLASS_FIELDS);
+
+impl LyNative for ClassFields {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let class = this.unwrap().to_obj().to_class();
+
+    // fields are listed in the order they were declared
+    let mut fields: Vec<_> = class.field_slots().collect();
+    fields.sort_by_key(|(_, slot)| **slot);
+
+    let names: Vec<Value> = fields.into_iter().map(|(name, _)| val!(*name)).collect();
+    Call::Ok(val!(hooks.manage_obj(List::from(names))))
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -186,4 +230,73 @@ mod test {
       assert_eq!(result.to_obj().to_str(), "someClass");
     }
   }
+  mod methods {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let class_methods = ClassMethods::native(&hooks);
+
+      assert_eq!(class_methods.meta().name, "methods");
+      assert_eq!(class_methods.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let class_methods = ClassMethods::native(&hooks.as_gc());
+
+      let mut class = hooks.manage_obj(Class::bare(hooks.manage_str("someClass")));
+      class.add_method(&hooks.as_gc(), hooks.manage_str("b"), VALUE_NIL);
+      class.add_method(&hooks.as_gc(), hooks.manage_str("a"), VALUE_NIL);
+
+      let result = class_methods
+        .call(&mut hooks, Some(val!(class)), &[])
+        .unwrap();
+      let list = result.to_obj().to_list();
+
+      assert_eq!(list.len(), 2);
+      assert_eq!(list[0].to_obj().to_str(), "a");
+      assert_eq!(list[1].to_obj().to_str(), "b");
+    }
+  }
+
+  mod fields {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let class_fields = ClassFields::native(&hooks);
+
+      assert_eq!(class_fields.meta().name, "fields");
+      assert_eq!(class_fields.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let class_fields = ClassFields::native(&hooks.as_gc());
+
+      let mut class = hooks.manage_obj(Class::bare(hooks.manage_str("someClass")));
+      class.add_field(&hooks.as_gc(), hooks.manage_str("y"));
+      class.add_field(&hooks.as_gc(), hooks.manage_str("x"));
+
+      let result = class_fields
+        .call(&mut hooks, Some(val!(class)), &[])
+        .unwrap();
+      let list = result.to_obj().to_list();
+
+      assert_eq!(list.len(), 2);
+      assert_eq!(list[0].to_obj().to_str(), "y");
+      assert_eq!(list[1].to_obj().to_str(), "x");
+    }
+  }
 }
@@ -8,7 +8,7 @@ use laythe_core::{
   managed::GcObj,
   managed::Trace,
   module::Module,
-  object::{LyNative, Native, NativeMetaBuilder},
+  object::{LyNative, Method, Native, NativeMetaBuilder},
   signature::{Arity, ParameterBuilder, ParameterKind},
   val,
   value::Value,
@@ -22,6 +22,10 @@ pub const CLOSURE_CLASS_NAME: &str = "Fun";
 
 const CLOSURE_NAME: NativeMetaBuilder = NativeMetaBuilder::method("name", Arity::Fixed(0));
 const CLOSURE_LEN: NativeMetaBuilder = NativeMetaBuilder::method("len", Arity::Fixed(0));
+const CLOSURE_ARITY: NativeMetaBuilder = NativeMetaBuilder::method("arity", Arity::Fixed(0));
+
+const CLOSURE_BIND: NativeMetaBuilder = NativeMetaBuilder::method("bind", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("this", ParameterKind::Any)]);
 
 const CLOSURE_CALL: NativeMetaBuilder = NativeMetaBuilder::method("call", Arity::Variadic(0))
   .with_params(&[ParameterBuilder::new("args", ParameterKind::Any)])
@@ -47,6 +51,18 @@ pub fn define_closure_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
     val!(ClosureLen::native(hooks)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(CLOSURE_ARITY.name),
+    val!(ClosureArity::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(CLOSURE_BIND.name),
+    val!(ClosureBind::native(hooks)),
+  );
+
   class.add_method(
     hooks,
     hooks.manage_str(CLOSURE_CALL.name),
@@ -68,16 +84,28 @@ native!(ClosureLen, CLOSURE_LEN);
 
 impl LyNative for ClosureLen {
   fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
-    let req = match this.unwrap().to_obj().to_closure().fun().arity() {
-      Arity::Default(req, _) => *req,
-      Arity::Fixed(req) => *req,
-      Arity::Variadic(req) => *req,
-    };
+    let req = this.unwrap().to_obj().to_closure().fun().arity().required();
+    Call::Ok(val!(req as f64))
+  }
+}
 
+native!(ClosureArity, CLOSURE_ARITY);
+
+impl LyNative for ClosureArity {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let req = this.unwrap().to_obj().to_closure().fun().arity().required();
     Call::Ok(val!(req as f64))
   }
 }
 
+native!(ClosureBind, CLOSURE_BIND);
+
+impl LyNative for ClosureBind {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    Call::Ok(val!(hooks.manage_obj(Method::new(args[0], this.unwrap()))))
+  }
+}
+
 native!(ClosureCall, CLOSURE_CALL);
 
 impl LyNative for ClosureCall {
@@ -169,6 +197,73 @@ mod test {
     }
   }
 
+  mod arity {
+    use super::*;
+    use crate::support::{test_fun_builder, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let closure_arity = ClosureArity::native(&hooks);
+
+      assert_eq!(closure_arity.meta().name, "arity");
+      assert_eq!(closure_arity.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let closure_arity = ClosureArity::native(&hooks.as_gc());
+
+      let mut builder = test_fun_builder(&hooks.as_gc(), "example", "module");
+      builder.set_arity(Arity::Default(1, 3));
+      let closure = hooks.manage_obj(Closure::without_upvalues(hooks.manage_obj(builder.build())));
+
+      let result = closure_arity.call(&mut hooks, Some(val!(closure)), &[]);
+      assert_eq!(result.unwrap().to_num(), 1.0);
+    }
+  }
+
+  mod bind {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let closure_bind = ClosureBind::native(&hooks);
+
+      assert_eq!(closure_bind.meta().name, "bind");
+      assert_eq!(closure_bind.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        closure_bind.meta().signature.parameters[0].kind,
+        ParameterKind::Any
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let closure_bind = ClosureBind::native(&hooks.as_gc());
+
+      let fun = test_fun(&hooks.as_gc(), "example", "module");
+      let closure = hooks.manage_obj(Closure::without_upvalues(fun));
+
+      let result = closure_bind
+        .call(&mut hooks, Some(val!(closure)), &[val!(true)])
+        .unwrap();
+
+      let method = result.to_obj().to_method();
+      assert_eq!(method.receiver(), val!(true));
+      assert_eq!(method.method(), val!(closure));
+    }
+  }
+
   mod call {
     use super::*;
     use crate::support::{test_fun_builder, MockedContext};
@@ -16,7 +16,7 @@ use super::error_inheritance;
 pub const ERROR_CLASS_NAME: &str = "Error";
 const ERROR_FIELD_MESSAGE: &str = "message";
 const ERROR_FIELD_STACK: &str = "stack";
-const ERROR_FIELD_INNER: &str = "inner";
+const ERROR_FIELD_CAUSE: &str = "cause";
 
 pub const TYPE_ERROR_NAME: &str = "TypeError";
 pub const FORMAT_CLASS_NAME: &str = "FormatError";
@@ -28,19 +28,24 @@ pub const EXPORT_ERROR_NAME: &str = "ExportError";
 pub const RUNTIME_ERROR_NAME: &str = "RuntimeError";
 pub const PROPERTY_ERROR_NAME: &str = "PropertyError";
 pub const METHOD_NOT_FOUND_ERROR_NAME: &str = "MethodNotFoundError";
+pub const OUT_OF_MEMORY_ERROR_NAME: &str = "OutOfMemoryError";
+pub const TIMEOUT_ERROR_NAME: &str = "TimeoutError";
 
 const ERROR_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(1, 2))
   .with_params(&[
     ParameterBuilder::new("message", ParameterKind::String),
-    ParameterBuilder::new("inner", ParameterKind::Instance),
+    ParameterBuilder::new("cause", ParameterKind::Instance),
   ]);
 
+const ERROR_STACKTRACE: NativeMetaBuilder =
+  NativeMetaBuilder::method("stacktrace", Arity::Fixed(0));
+
 pub fn create_error_class(hooks: &GcHooks, object: GcObj<Class>) -> GcObj<Class> {
   let mut class = Class::with_inheritance(hooks, hooks.manage_str(ERROR_CLASS_NAME), object);
 
   class.add_field(hooks, hooks.manage_str(ERROR_FIELD_MESSAGE));
   class.add_field(hooks, hooks.manage_str(ERROR_FIELD_STACK));
-  class.add_field(hooks, hooks.manage_str(ERROR_FIELD_INNER));
+  class.add_field(hooks, hooks.manage_str(ERROR_FIELD_CAUSE));
 
   class.add_method(
     hooks,
@@ -48,6 +53,12 @@ pub fn create_error_class(hooks: &GcHooks, object: GcObj<Class>) -> GcObj<Class>
     val!(ErrorInit::native(hooks)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(ERROR_STACKTRACE.name),
+    val!(ErrorStacktrace::native(hooks)),
+  );
+
   class
 }
 
@@ -62,6 +73,8 @@ pub fn declare_global_errors(hooks: &GcHooks, module: &mut Module) -> StdResult<
   let export_error = error_inheritance(hooks, module, EXPORT_ERROR_NAME)?;
   let property_error = error_inheritance(hooks, module, PROPERTY_ERROR_NAME)?;
   let method_not_found_error = error_inheritance(hooks, module, METHOD_NOT_FOUND_ERROR_NAME)?;
+  let out_of_memory_error = error_inheritance(hooks, module, OUT_OF_MEMORY_ERROR_NAME)?;
+  let timeout_error = error_inheritance(hooks, module, TIMEOUT_ERROR_NAME)?;
 
   export_and_insert(hooks, module, type_error.name(), val!(type_error))?;
   export_and_insert(hooks, module, format_error.name(), val!(format_error))?;
@@ -72,6 +85,13 @@ pub fn declare_global_errors(hooks: &GcHooks, module: &mut Module) -> StdResult<
   export_and_insert(hooks, module, export_error.name(), val!(export_error))?;
   export_and_insert(hooks, module, runtime_error.name(), val!(runtime_error))?;
   export_and_insert(hooks, module, property_error.name(), val!(property_error))?;
+  export_and_insert(hooks, module, timeout_error.name(), val!(timeout_error))?;
+  export_and_insert(
+    hooks,
+    module,
+    out_of_memory_error.name(),
+    val!(out_of_memory_error),
+  )?;
   export_and_insert(
     hooks,
     module,
@@ -101,6 +121,14 @@ impl LyNative for ErrorInit {
   }
 }
 
+native!(ErrorStacktrace, ERROR_STACKTRACE);
+
+impl LyNative for ErrorStacktrace {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(this.unwrap().to_obj().to_instance()[1])
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -139,7 +167,7 @@ mod test {
       let mut test_class = hooks.manage_obj(Class::bare(hooks.manage_str("test")));
       test_class.add_field(&hooks.as_gc(), hooks.manage_str(ERROR_FIELD_MESSAGE));
       test_class.add_field(&hooks.as_gc(), hooks.manage_str(ERROR_FIELD_STACK));
-      test_class.add_field(&hooks.as_gc(), hooks.manage_str(ERROR_FIELD_INNER));
+      test_class.add_field(&hooks.as_gc(), hooks.manage_str(ERROR_FIELD_CAUSE));
 
       let instance = hooks.manage_obj(Instance::new(test_class));
 
@@ -157,4 +185,44 @@ mod test {
       assert!(result.to_instance()[2].is_nil());
     }
   }
+
+  mod stacktrace {
+    use laythe_core::object::{Class, Instance};
+
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let mut context = MockedContext::default();
+      let hooks = GcHooks::new(&mut context);
+
+      let error_stacktrace = ErrorStacktrace::native(&hooks);
+
+      assert_eq!(error_stacktrace.meta().name, "stacktrace");
+      assert_eq!(error_stacktrace.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let error_stacktrace = ErrorStacktrace::native(&hooks.as_gc());
+      let mut test_class = hooks.manage_obj(Class::bare(hooks.manage_str("test")));
+      test_class.add_field(&hooks.as_gc(), hooks.manage_str(ERROR_FIELD_MESSAGE));
+      test_class.add_field(&hooks.as_gc(), hooks.manage_str(ERROR_FIELD_STACK));
+      test_class.add_field(&hooks.as_gc(), hooks.manage_str(ERROR_FIELD_CAUSE));
+
+      let mut instance = hooks.manage_obj(Instance::new(test_class));
+      let stack = val!(hooks.manage_obj(List::new()));
+      instance[1] = stack;
+
+      let result = error_stacktrace
+        .call(&mut hooks, Some(val!(instance)), &[])
+        .unwrap();
+
+      assert_eq!(result, stack);
+    }
+  }
 }
@@ -1,6 +1,9 @@
-use super::class_inheritance;
+use super::{
+  class_inheritance,
+  error::{TYPE_ERROR_NAME, VALUE_ERROR_NAME},
+};
 use crate::{
-  native,
+  native, native_with_error,
   support::{export_and_insert, load_class_from_module},
   StdError, StdResult,
 };
@@ -9,16 +12,25 @@ use laythe_core::{
   managed::GcObj,
   managed::Trace,
   module::Module,
-  object::{LyNative, Native, NativeMetaBuilder},
-  signature::Arity,
+  object::{Fiber, FiberState, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
   val,
-  value::Value,
+  value::{Value, VALUE_NIL},
   Call,
 };
-use std::io::Write;
+use std::{io::Write, time::Duration};
 
 pub const FIBER_CLASS_NAME: &str = "Fiber";
 const FIBER_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
+const FIBER_JOIN: NativeMetaBuilder = NativeMetaBuilder::method("join", Arity::Fixed(0));
+
+const FIBER_SPAWN: NativeMetaBuilder = NativeMetaBuilder::fun("spawn", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("fun", ParameterKind::Fun)]);
+
+const FIBER_YIELD: NativeMetaBuilder = NativeMetaBuilder::fun("yield", Arity::Fixed(0));
+
+const FIBER_SLEEP: NativeMetaBuilder = NativeMetaBuilder::fun("sleep", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("ms", ParameterKind::Number)]);
 
 pub fn declare_fiber_class(hooks: &GcHooks, module: &mut Module) -> StdResult<()> {
   let bool_class = class_inheritance(hooks, module, FIBER_CLASS_NAME)?;
@@ -27,6 +39,8 @@ pub fn declare_fiber_class(hooks: &GcHooks, module: &mut Module) -> StdResult<()
 
 pub fn define_fiber_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
   let mut bool_class = load_class_from_module(hooks, module, FIBER_CLASS_NAME)?;
+  let type_error = val!(load_class_from_module(hooks, module, TYPE_ERROR_NAME)?);
+  let value_error = val!(load_class_from_module(hooks, module, VALUE_ERROR_NAME)?);
 
   bool_class.add_method(
     &hooks,
@@ -34,9 +48,118 @@ pub fn define_fiber_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
     val!(FiberStr::native(hooks)),
   );
 
+  bool_class.add_method(
+    hooks,
+    hooks.manage_str(FIBER_JOIN.name),
+    val!(FiberJoin::native(hooks, type_error)),
+  );
+
+  let mut meta_class = bool_class.meta_class().expect("Meta class not set.");
+
+  meta_class.add_method(
+    hooks,
+    hooks.manage_str(FIBER_SPAWN.name),
+    val!(FiberSpawn::native(hooks, type_error)),
+  );
+
+  meta_class.add_method(
+    hooks,
+    hooks.manage_str(FIBER_YIELD.name),
+    val!(FiberYield::native(hooks)),
+  );
+
+  meta_class.add_method(
+    hooks,
+    hooks.manage_str(FIBER_SLEEP.name),
+    val!(FiberSleep::native(hooks, value_error)),
+  );
+
   Ok(())
 }
 
+native_with_error!(FiberSpawn, FIBER_SPAWN);
+
+impl LyNative for FiberSpawn {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    if !args[0].is_obj_kind(ObjectKind::Closure) {
+      return self.call_error(hooks, "Fiber.spawn expects a laythe function.");
+    }
+
+    let closure = args[0].to_obj().to_closure();
+    if closure.fun().arity().check(0).is_err() {
+      return self.call_error(
+        hooks,
+        format!(
+          "Fiber.spawn expects a function without parameters but {} has parameters.",
+          &*closure.fun().name()
+        ),
+      );
+    }
+
+    match Fiber::new(closure) {
+      Ok(fiber) => {
+        let fiber = hooks.manage_obj(fiber);
+        hooks.spawn_fiber(fiber);
+        Call::Ok(val!(fiber))
+      },
+      Err(_) => self.call_error(hooks, "Unable to create fiber."),
+    }
+  }
+}
+
+native!(FiberYield, FIBER_YIELD);
+
+impl LyNative for FiberYield {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    // yielding is sleeping until every other ready fiber has run
+    if hooks.sleep_fiber(Duration::default()) {
+      Call::Block
+    } else {
+      Call::Ok(VALUE_NIL)
+    }
+  }
+}
+
+native_with_error!(FiberSleep, FIBER_SLEEP);
+
+impl LyNative for FiberSleep {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let ms = args[0].to_num();
+
+    if !ms.is_finite() || ms < 0.0 {
+      return self.call_error(hooks, "Fiber.sleep duration must be a non negative number.");
+    }
+
+    if hooks.sleep_fiber(Duration::from_secs_f64(ms / 1000.0)) {
+      Call::Block
+    } else {
+      Call::Ok(VALUE_NIL)
+    }
+  }
+}
+
+native_with_error!(FiberJoin, FIBER_JOIN);
+
+impl LyNative for FiberJoin {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let mut fiber = this.unwrap().to_obj().to_fiber();
+
+    match fiber.state() {
+      FiberState::Complete => Call::Ok(fiber.result()),
+      FiberState::Failed => Call::Err(fiber.error().expect("Failed fiber did not set an error.")),
+      _ => {
+        let current = hooks.current_fiber();
+        if fiber == current {
+          return self.call_error(hooks, "Fiber cannot join itself.");
+        }
+
+        hooks.grow(&mut *fiber, |fiber| fiber.add_waiter(current));
+        Call::Block
+      },
+    }
+  }
+}
+
 native!(FiberStr, FIBER_STR);
 
 impl LyNative for FiberStr {
@@ -90,4 +213,122 @@ mod test {
       assert!(result.to_obj().to_str().contains("<Fiber "));
     }
   }
+  mod spawn {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let mut context = MockedContext::default();
+      let hooks = GcHooks::new(&mut context);
+      let error = val!(test_error_class(&hooks));
+
+      let fiber_spawn = FiberSpawn::native(&hooks, error);
+
+      assert_eq!(fiber_spawn.meta().name, "spawn");
+      assert_eq!(fiber_spawn.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        fiber_spawn.meta().signature.parameters[0].kind,
+        ParameterKind::Fun
+      );
+    }
+  }
+
+  mod yield_ {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let mut context = MockedContext::default();
+      let hooks = GcHooks::new(&mut context);
+
+      let fiber_yield = FiberYield::native(&hooks);
+
+      assert_eq!(fiber_yield.meta().name, "yield");
+      assert_eq!(fiber_yield.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let fiber_yield = FiberYield::native(&hooks.as_gc());
+
+      let result = fiber_yield.call(&mut hooks, None, &[]).unwrap();
+      assert!(result.is_nil());
+    }
+  }
+
+  mod sleep {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let mut context = MockedContext::default();
+      let hooks = GcHooks::new(&mut context);
+      let error = val!(test_error_class(&hooks));
+
+      let fiber_sleep = FiberSleep::native(&hooks, error);
+
+      assert_eq!(fiber_sleep.meta().name, "sleep");
+      assert_eq!(fiber_sleep.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        fiber_sleep.meta().signature.parameters[0].kind,
+        ParameterKind::Number
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let fiber_sleep = FiberSleep::native(&hooks.as_gc(), error);
+
+      let result = fiber_sleep.call(&mut hooks, None, &[val!(10.0)]).unwrap();
+      assert!(result.is_nil());
+    }
+  }
+
+  mod join {
+    use laythe_core::support::FiberBuilder;
+
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let mut context = MockedContext::default();
+      let hooks = GcHooks::new(&mut context);
+      let error = val!(test_error_class(&hooks));
+
+      let fiber_join = FiberJoin::native(&hooks, error);
+
+      assert_eq!(fiber_join.meta().name, "join");
+      assert_eq!(fiber_join.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let fiber_join = FiberJoin::native(&hooks.as_gc(), error);
+
+      let mut fiber = FiberBuilder::<u8>::default()
+        .instructions(vec![0])
+        .build(&hooks.as_gc())
+        .unwrap();
+      fiber.pop_frame();
+      fiber.set_result(val!(5.0));
+
+      let result = fiber_join.call(&mut hooks, Some(val!(fiber)), &[]).unwrap();
+      assert_eq!(result, val!(5.0));
+    }
+  }
 }
@@ -1,5 +1,5 @@
 use crate::{
-  global::VALUE_ERROR_NAME,
+  global::{RUNTIME_ERROR_NAME, TYPE_ERROR_NAME, VALUE_ERROR_NAME},
   native, native_with_error,
   support::{export_and_insert, load_class_from_module},
   StdResult,
@@ -14,12 +14,13 @@ use laythe_core::{
   utils::is_falsey,
   val,
   value::{Value, VALUE_NIL},
+  worker::Message,
   Call,
 };
 use std::io::Write;
 use std::mem;
 
-use super::class_inheritance;
+use super::{class_inheritance, parallel::PoolCalls};
 
 pub const ITER_CLASS_NAME: &str = "Iter";
 const ITER_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
@@ -59,6 +60,9 @@ const ITER_EACH: NativeMetaBuilder = NativeMetaBuilder::method("each", Arity::Fi
   .with_params(&[ParameterBuilder::new("fun", ParameterKind::Fun)])
   .with_stack();
 
+const ITER_PAR_EACH: NativeMetaBuilder = NativeMetaBuilder::method("parEach", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("fun", ParameterKind::Fun)]);
+
 const ITER_ZIP: NativeMetaBuilder = NativeMetaBuilder::method("zip", Arity::Variadic(0))
   .with_params(&[ParameterBuilder::new(
     "iterators",
@@ -91,6 +95,8 @@ pub fn declare_iter_class(hooks: &GcHooks, module: &mut Module) -> StdResult<()>
 pub fn define_iter_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
   let mut class = load_class_from_module(hooks, module, ITER_CLASS_NAME)?;
   let value_error = val!(load_class_from_module(hooks, module, VALUE_ERROR_NAME)?);
+  let type_error = val!(load_class_from_module(hooks, module, TYPE_ERROR_NAME)?);
+  let runtime_error = val!(load_class_from_module(hooks, module, RUNTIME_ERROR_NAME)?);
 
   class.add_method(
     hooks,
@@ -170,6 +176,12 @@ pub fn define_iter_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
     val!(IterEach::native(hooks)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(ITER_PAR_EACH.name),
+    val!(IterParEach::native(hooks, type_error, runtime_error)),
+  );
+
   class.add_method(
     hooks,
     hooks.manage_str(ITER_ZIP.name),
@@ -338,6 +350,10 @@ impl Trace for TakeIterator {
     self.iter.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.iter.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     self.iter.trace_debug(log)
   }
@@ -427,6 +443,10 @@ impl Trace for SkipIterator {
     self.iter.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.iter.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     self.iter.trace_debug(log)
   }
@@ -499,6 +519,12 @@ impl Trace for MapIterator {
     self.callable.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.current.visit_refs(visitor);
+    self.iter.visit_refs(visitor);
+    self.callable.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.current.trace_debug(stdout);
     self.iter.trace_debug(stdout);
@@ -577,6 +603,12 @@ impl Trace for FilterIterator {
     self.callable.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.current.visit_refs(visitor);
+    self.iter.visit_refs(visitor);
+    self.callable.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.current.trace_debug(stdout);
     self.iter.trace_debug(stdout);
@@ -647,6 +679,63 @@ impl LyNative for IterEach {
   }
 }
 
+/// Call a function with each item of an iterator on the worker pool. The
+/// iterator is run to completion first so each item can be copied to the pool
+#[derive(Debug)]
+struct IterParEach {
+  calls: PoolCalls,
+}
+
+impl IterParEach {
+  fn native(hooks: &GcHooks, type_error: Value, error: Value) -> GcObj<Native> {
+    let native = Box::new(Self {
+      calls: PoolCalls::new(type_error, error),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(ITER_PAR_EACH.to_meta(hooks), native))
+  }
+}
+
+impl Trace for IterParEach {
+  fn trace(&self) {
+    self.calls.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.calls.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, log: &mut dyn Write) {
+    self.calls.trace_debug(log);
+  }
+}
+
+impl LyNative for IterParEach {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap();
+    let mut iter = this.to_obj().to_enumerator();
+
+    let results = self.calls.call(hooks, this, args[0], false, |hooks| {
+      let mut items = vec![];
+      loop {
+        match iter.next(hooks) {
+          Call::Ok(more) if is_falsey(more) => return Ok(items),
+          Call::Ok(_) => match Message::from_value(iter.current()) {
+            Ok(item) => items.push(item),
+            Err(err) => return Err(self.calls.type_error(hooks, &err)),
+          },
+          call => return Err(call),
+        }
+      }
+    });
+
+    match results {
+      Ok(_) => Call::Ok(VALUE_NIL),
+      Err(call) => call,
+    }
+  }
+}
+
 native!(IterZip, ITER_ZIP);
 
 impl LyNative for IterZip {
@@ -730,6 +819,13 @@ impl Trace for ZipIterator {
     });
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.current.visit_refs(visitor);
+    self.iters.iter().for_each(|iter| {
+      iter.visit_refs(visitor);
+    });
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.current.trace_debug(stdout);
     self.iters.iter().for_each(|iter| {
@@ -824,6 +920,13 @@ impl Trace for ChainIterator {
     });
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.current.visit_refs(visitor);
+    self.iters.iter().for_each(|iter| {
+      iter.visit_refs(visitor);
+    });
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.current.trace_debug(stdout);
     self.iters.iter().for_each(|iter| {
@@ -1421,6 +1524,27 @@ mod test {
     }
   }
 
+  mod par_each {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let iter_par_each = IterParEach::native(&hooks, error, error);
+
+      assert_eq!(iter_par_each.meta().name, "parEach");
+      assert_eq!(iter_par_each.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        iter_par_each.meta().signature.parameters[0].kind,
+        ParameterKind::Fun
+      );
+    }
+  }
+
   mod zip {
     use super::*;
     use crate::support::MockedContext;
@@ -16,6 +16,7 @@ use laythe_core::{
   utils::is_falsey,
   val,
   value::{Value, VALUE_NIL},
+  worker::Message,
   Call, LyResult,
 };
 use std::{cmp::Ordering, io::Write};
@@ -23,7 +24,8 @@ use std::{mem, slice::Iter};
 
 use super::{
   class_inheritance,
-  error::{INDEX_ERROR_NAME, TYPE_ERROR_NAME},
+  error::{INDEX_ERROR_NAME, RUNTIME_ERROR_NAME, TYPE_ERROR_NAME},
+  parallel::PoolCalls,
 };
 
 pub const LIST_CLASS_NAME: &str = "List";
@@ -35,9 +37,11 @@ const LIST_INDEX_SET: NativeMetaBuilder = NativeMetaBuilder::method(INDEX_SET, A
   .with_params(&[
     ParameterBuilder::new("val", ParameterKind::Any),
     ParameterBuilder::new("index", ParameterKind::Number),
-  ]);
+  ])
+  .with_mutation();
 
-const LIST_CLEAR: NativeMetaBuilder = NativeMetaBuilder::method("clear", Arity::Fixed(0));
+const LIST_CLEAR: NativeMetaBuilder =
+  NativeMetaBuilder::method("clear", Arity::Fixed(0)).with_mutation();
 
 const LIST_HAS: NativeMetaBuilder = NativeMetaBuilder::method("has", Arity::Fixed(1))
   .with_params(&[ParameterBuilder::new("val", ParameterKind::Any)]);
@@ -46,16 +50,20 @@ const LIST_INSERT: NativeMetaBuilder = NativeMetaBuilder::method("insert", Arity
   .with_params(&[
     ParameterBuilder::new("index", ParameterKind::Number),
     ParameterBuilder::new("val", ParameterKind::Any),
-  ]);
+  ])
+  .with_mutation();
 
 const LIST_ITER: NativeMetaBuilder = NativeMetaBuilder::method("iter", Arity::Fixed(0));
-const LIST_POP: NativeMetaBuilder = NativeMetaBuilder::method("pop", Arity::Fixed(0));
+const LIST_POP: NativeMetaBuilder =
+  NativeMetaBuilder::method("pop", Arity::Fixed(0)).with_mutation();
 
 const LIST_PUSH: NativeMetaBuilder = NativeMetaBuilder::method("push", Arity::Variadic(0))
-  .with_params(&[ParameterBuilder::new("values", ParameterKind::Any)]);
+  .with_params(&[ParameterBuilder::new("values", ParameterKind::Any)])
+  .with_mutation();
 
 const LIST_REMOVE: NativeMetaBuilder = NativeMetaBuilder::method("remove", Arity::Fixed(1))
-  .with_params(&[ParameterBuilder::new("index", ParameterKind::Number)]);
+  .with_params(&[ParameterBuilder::new("index", ParameterKind::Number)])
+  .with_mutation();
 
 const LIST_INDEX: NativeMetaBuilder = NativeMetaBuilder::method("index", Arity::Fixed(1))
   .with_params(&[ParameterBuilder::new("value", ParameterKind::Any)]);
@@ -75,6 +83,9 @@ const LIST_SORT: NativeMetaBuilder = NativeMetaBuilder::method("sort", Arity::Fi
   .with_params(&[ParameterBuilder::new("comparator", ParameterKind::Fun)])
   .with_stack();
 
+const LIST_PAR_MAP: NativeMetaBuilder = NativeMetaBuilder::method("parMap", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("fun", ParameterKind::Fun)]);
+
 // this may need a stack
 const LIST_COLLECT: NativeMetaBuilder = NativeMetaBuilder::fun("collect", Arity::Fixed(1))
   .with_params(&[ParameterBuilder::new("iter", ParameterKind::Enumerator)]);
@@ -89,6 +100,7 @@ pub fn define_list_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
   let mut class = load_class_from_module(hooks, module, LIST_CLASS_NAME)?;
   let index_error = val!(load_class_from_module(hooks, module, INDEX_ERROR_NAME)?);
   let type_error = val!(load_class_from_module(hooks, module, TYPE_ERROR_NAME)?);
+  let runtime_error = val!(load_class_from_module(hooks, module, RUNTIME_ERROR_NAME)?);
 
   class.add_method(
     hooks,
@@ -184,6 +196,12 @@ pub fn define_list_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
     val!(ListSort::native(hooks, type_error)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(LIST_PAR_MAP.name),
+    val!(ListParMap::native(hooks, type_error, runtime_error)),
+  );
+
   class.meta_class().expect("Meta class not set.").add_method(
     hooks,
     hooks.manage_str(LIST_COLLECT.name),
@@ -213,6 +231,10 @@ impl Trace for ListStr {
     self.method_name.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_name.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, log: &mut dyn Write) {
     self.method_name.trace_debug(log);
   }
@@ -263,6 +285,9 @@ impl LyNative for ListStr {
           Call::Exit(exit) => {
             return Call::Exit(exit);
           },
+          Call::Block => {
+            return Call::Block;
+          },
         }
       }
 
@@ -295,6 +320,9 @@ impl LyNative for ListStr {
           Call::Exit(exit) => {
             return Call::Exit(exit);
           },
+          Call::Block => {
+            return Call::Block;
+          },
         }
       })
     }
@@ -323,12 +351,14 @@ impl LyNative for ListSlice {
       LyResult::Ok(index) => index,
       LyResult::Err(err) => return LyResult::Err(err),
       LyResult::Exit(exit) => return LyResult::Exit(exit),
+      LyResult::Block => return LyResult::Block,
     };
 
     let end_index = match self.index(hooks, &list, end) {
       LyResult::Ok(index) => index,
       LyResult::Err(err) => return LyResult::Err(err),
       LyResult::Exit(exit) => return LyResult::Exit(exit),
+      LyResult::Block => return LyResult::Block,
     };
 
     let start_index = start_index.max(0);
@@ -609,6 +639,10 @@ impl LyNative for ListSort {
           failure.get_or_insert(Call::Exit(code));
           Ordering::Equal
         },
+        laythe_core::LyResult::Block => {
+          failure.get_or_insert(Call::Block);
+          Ordering::Equal
+        },
       }
     });
 
@@ -617,6 +651,57 @@ impl LyNative for ListSort {
   }
 }
 
+/// Map each item of a list on the worker pool. The function and items
+/// are copied to the pool and the results are copied back in order
+#[derive(Debug)]
+struct ListParMap {
+  calls: PoolCalls,
+}
+
+impl ListParMap {
+  fn native(hooks: &GcHooks, type_error: Value, error: Value) -> GcObj<Native> {
+    let native = Box::new(Self {
+      calls: PoolCalls::new(type_error, error),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(LIST_PAR_MAP.to_meta(hooks), native))
+  }
+}
+
+impl Trace for ListParMap {
+  fn trace(&self) {
+    self.calls.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.calls.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, log: &mut dyn Write) {
+    self.calls.trace_debug(log);
+  }
+}
+
+impl LyNative for ListParMap {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap();
+    let list = this.to_obj().to_list();
+
+    let results = self.calls.call(hooks, this, args[0], true, |hooks| {
+      list
+        .iter()
+        .map(|item| Message::from_value(*item))
+        .collect::<Result<Vec<Message>, String>>()
+        .map_err(|err| self.calls.type_error(hooks, &err))
+    });
+
+    match results {
+      Ok(results) => Call::Ok(Message::List(results).to_value(&hooks.as_gc())),
+      Err(call) => call,
+    }
+  }
+}
+
 native!(ListCollect, LIST_COLLECT);
 
 impl LyNative for ListCollect {
@@ -694,6 +779,10 @@ impl Trace for ListIterator {
     self.list.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.list.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.list.trace_debug(stdout);
   }
@@ -1392,6 +1481,27 @@ mod test {
     }
   }
 
+  mod par_map {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let list_par_map = ListParMap::native(&hooks, error, error);
+
+      assert_eq!(list_par_map.meta().name, "parMap");
+      assert_eq!(list_par_map.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        list_par_map.meta().signature.parameters[0].kind,
+        ParameterKind::Fun
+      );
+    }
+  }
+
   mod collect {
     use super::*;
     use crate::support::{test_iter, MockedContext};
@@ -34,16 +34,18 @@ const MAP_INDEX_SET: NativeMetaBuilder = NativeMetaBuilder::method(INDEX_SET, Ar
   .with_params(&[
     ParameterBuilder::new("key", ParameterKind::Any),
     ParameterBuilder::new("val", ParameterKind::Any),
-  ]);
+  ])
+  .with_mutation();
 
 const MAP_GET: NativeMetaBuilder = NativeMetaBuilder::method("get", Arity::Fixed(1))
   .with_params(&[ParameterBuilder::new("key", ParameterKind::Any)]);
 
-const MAP_SET: NativeMetaBuilder =
-  NativeMetaBuilder::method("set", Arity::Fixed(2)).with_params(&[
+const MAP_SET: NativeMetaBuilder = NativeMetaBuilder::method("set", Arity::Fixed(2))
+  .with_params(&[
     ParameterBuilder::new("key", ParameterKind::Any),
     ParameterBuilder::new("value", ParameterKind::Any),
-  ]);
+  ])
+  .with_mutation();
 
 const MAP_HAS: NativeMetaBuilder = NativeMetaBuilder::method("has", Arity::Fixed(1))
   .with_params(&[ParameterBuilder::new("key", ParameterKind::Any)]);
@@ -52,10 +54,12 @@ const MAP_INSERT: NativeMetaBuilder = NativeMetaBuilder::method("insert", Arity:
   .with_params(&[
     ParameterBuilder::new("key", ParameterKind::Any),
     ParameterBuilder::new("val", ParameterKind::Any),
-  ]);
+  ])
+  .with_mutation();
 
 const MAP_REMOVE: NativeMetaBuilder = NativeMetaBuilder::method("remove", Arity::Fixed(1))
-  .with_params(&[ParameterBuilder::new("key", ParameterKind::Any)]);
+  .with_params(&[ParameterBuilder::new("key", ParameterKind::Any)])
+  .with_mutation();
 
 const MAP_LEN: NativeMetaBuilder = NativeMetaBuilder::method("len", Arity::Fixed(0));
 const MAP_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
@@ -233,6 +237,10 @@ impl Trace for MapStr {
     self.method_name.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_name.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.method_name.trace_debug(stdout);
   }
@@ -420,6 +428,10 @@ impl Trace for MapIterator {
     self.map.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.map.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.map.trace_debug(stdout);
   }
@@ -7,7 +7,7 @@ use laythe_core::{
   hooks::{GcHooks, Hooks},
   managed::{GcObj, GcStr, Trace},
   module::Module,
-  object::{LyNative, Native, NativeMetaBuilder},
+  object::{LyNative, Method, Native, NativeMetaBuilder, ObjectKind},
   signature::{Arity, ParameterBuilder, ParameterKind},
   val,
   value::Value,
@@ -20,6 +20,10 @@ use super::class_inheritance;
 pub const METHOD_CLASS_NAME: &str = "Method";
 
 const METHOD_NAME: NativeMetaBuilder = NativeMetaBuilder::method("name", Arity::Fixed(0));
+const METHOD_ARITY: NativeMetaBuilder = NativeMetaBuilder::method("arity", Arity::Fixed(0));
+
+const METHOD_BIND: NativeMetaBuilder = NativeMetaBuilder::method("bind", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("this", ParameterKind::Any)]);
 
 const METHOD_CALL: NativeMetaBuilder = NativeMetaBuilder::method("call", Arity::Variadic(0))
   .with_params(&[ParameterBuilder::new("args", ParameterKind::Any)])
@@ -42,6 +46,18 @@ pub fn define_method_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
     )),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(METHOD_ARITY.name),
+    val!(MethodArity::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(METHOD_BIND.name),
+    val!(MethodBind::native(hooks)),
+  );
+
   class.add_method(
     hooks,
     hooks.manage_str(METHOD_CALL.name),
@@ -82,11 +98,40 @@ impl Trace for MethodName {
     self.method_name.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_name.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.method_name.trace_debug(stdout);
   }
 }
 
+native!(MethodArity, METHOD_ARITY);
+
+impl LyNative for MethodArity {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let method = this.unwrap().to_obj().to_method().method();
+
+    let arity = if method.is_obj_kind(ObjectKind::Closure) {
+      *method.to_obj().to_closure().fun().arity()
+    } else {
+      method.to_obj().to_native().meta().signature.arity
+    };
+
+    Call::Ok(val!(arity.required() as f64))
+  }
+}
+
+native!(MethodBind, METHOD_BIND);
+
+impl LyNative for MethodBind {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let method = this.unwrap().to_obj().to_method().method();
+    Call::Ok(val!(hooks.manage_obj(Method::new(args[0], method))))
+  }
+}
+
 native!(MethodCall, METHOD_CALL);
 
 impl LyNative for MethodCall {
@@ -143,6 +188,83 @@ mod test {
     }
   }
 
+  mod arity {
+    use super::*;
+    use crate::support::{test_fun_builder, MockedContext};
+    use laythe_core::{object::Closure, value::VALUE_NIL};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let method_arity = MethodArity::native(&hooks);
+
+      assert_eq!(method_arity.meta().name, "arity");
+      assert_eq!(method_arity.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let method_arity = MethodArity::native(&hooks.as_gc());
+
+      let mut builder = test_fun_builder(&hooks.as_gc(), "example", "module");
+      builder.set_arity(Arity::Fixed(2));
+      let closure = hooks.manage_obj(Closure::without_upvalues(hooks.manage_obj(builder.build())));
+      let method = hooks.manage_obj(Method::new(VALUE_NIL, val!(closure)));
+
+      let result = method_arity.call(&mut hooks, Some(val!(method)), &[]);
+      assert_eq!(result.unwrap().to_num(), 2.0);
+
+      let native = MethodBind::native(&hooks.as_gc());
+      let native = hooks.manage_obj(Method::new(VALUE_NIL, val!(native)));
+      let result = method_arity.call(&mut hooks, Some(val!(native)), &[]);
+      assert_eq!(result.unwrap().to_num(), 1.0);
+    }
+  }
+
+  mod bind {
+    use super::*;
+    use crate::support::{test_fun, MockedContext};
+    use laythe_core::{object::Closure, value::VALUE_NIL};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let method_bind = MethodBind::native(&hooks);
+
+      assert_eq!(method_bind.meta().name, "bind");
+      assert_eq!(method_bind.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        method_bind.meta().signature.parameters[0].kind,
+        ParameterKind::Any
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let method_bind = MethodBind::native(&hooks.as_gc());
+
+      let fun = test_fun(&hooks.as_gc(), "example", "module");
+      let closure = hooks.manage_obj(Closure::without_upvalues(fun));
+      let method = hooks.manage_obj(Method::new(VALUE_NIL, val!(closure)));
+
+      let result = method_bind
+        .call(&mut hooks, Some(val!(method)), &[val!(5.0)])
+        .unwrap();
+
+      let bound = result.to_obj().to_method();
+      assert_eq!(bound.receiver(), val!(5.0));
+      assert_eq!(bound.method(), val!(closure));
+    }
+  }
+
   mod call {
     use super::*;
     use crate::support::{test_fun, MockedContext};
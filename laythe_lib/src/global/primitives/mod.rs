@@ -1,4 +1,5 @@
 pub mod bool;
+pub mod channel;
 pub mod class;
 pub mod closure;
 pub mod fiber;
@@ -12,11 +13,13 @@ pub mod native;
 pub mod nil;
 pub mod number;
 pub mod object;
+mod parallel;
 pub mod string;
+pub mod tuple;
 
 use std::path::PathBuf;
 
-use self::{bool::{declare_bool_class, define_bool_class}, error::{create_error_class, declare_global_errors, define_global_errors, ERROR_CLASS_NAME}, fiber::{declare_fiber_class, define_fiber_class}, module::create_module_class};
+use self::{bool::{declare_bool_class, define_bool_class}, channel::{declare_channel_class, define_channel_class}, error::{create_error_class, declare_global_errors, define_global_errors, ERROR_CLASS_NAME}, fiber::{declare_fiber_class, define_fiber_class}, module::create_module_class};
 use crate::{support::export_and_insert, StdError, StdResult, STD};
 use class::create_class_class;
 use closure::{declare_closure_class, define_closure_class};
@@ -40,6 +43,7 @@ use nil::{declare_nil_class, define_nil_class};
 use number::{declare_number_class, define_number_class};
 use object::create_object_class;
 use string::{declare_string_class, define_string_class};
+use tuple::{declare_tuple_class, define_tuple_class};
 
 use super::OBJECT_CLASS_NAME;
 
@@ -97,7 +101,9 @@ pub(crate) fn create_primitives(hooks: &GcHooks, emitter: &mut IdEmitter) -> Std
   declare_nil_class(hooks, &mut module)?;
   declare_number_class(hooks, &mut module)?;
   declare_string_class(hooks, &mut module)?;
+  declare_tuple_class(hooks, &mut module)?;
   declare_fiber_class(hooks, &mut module)?;
+  declare_channel_class(hooks, &mut module)?;
 
   define_global_errors(hooks, &module)?;
   define_bool_class(hooks, &module)?;
@@ -110,7 +116,9 @@ pub(crate) fn create_primitives(hooks: &GcHooks, emitter: &mut IdEmitter) -> Std
   define_nil_class(hooks, &module)?;
   define_number_class(hooks, &module)?;
   define_string_class(hooks, &module)?;
+  define_tuple_class(hooks, &module)?;
   define_fiber_class(hooks, &module)?;
+  define_channel_class(hooks, &module)?;
 
   Ok(module)
 }
@@ -8,7 +8,7 @@ use laythe_core::{
   managed::GcObj,
   managed::Trace,
   module::Module,
-  object::{LyNative, Native, NativeMetaBuilder},
+  object::{LyNative, Method, Native, NativeMetaBuilder},
   signature::{Arity, ParameterBuilder, ParameterKind},
   val,
   value::Value,
@@ -21,6 +21,10 @@ use super::class_inheritance;
 pub const NATIVE_CLASS_NAME: &str = "Native";
 
 const NATIVE_NAME: NativeMetaBuilder = NativeMetaBuilder::method("name", Arity::Fixed(0));
+const NATIVE_ARITY: NativeMetaBuilder = NativeMetaBuilder::method("arity", Arity::Fixed(0));
+
+const NATIVE_BIND: NativeMetaBuilder = NativeMetaBuilder::method("bind", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("this", ParameterKind::Any)]);
 
 const NATIVE_CALL: NativeMetaBuilder = NativeMetaBuilder::method("call", Arity::Variadic(0))
   .with_params(&[ParameterBuilder::new("args", ParameterKind::Any)])
@@ -40,6 +44,18 @@ pub fn define_native_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
     val!(NativeName::native(hooks)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(NATIVE_ARITY.name),
+    val!(NativeArity::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(NATIVE_BIND.name),
+    val!(NativeBind::native(hooks)),
+  );
+
   class.add_method(
     hooks,
     hooks.manage_str(NATIVE_CALL.name),
@@ -57,6 +73,24 @@ impl LyNative for NativeName {
   }
 }
 
+native!(NativeArity, NATIVE_ARITY);
+
+impl LyNative for NativeArity {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let native = this.unwrap().to_obj().to_native();
+    let req = native.meta().signature.arity.required();
+    Call::Ok(val!(req as f64))
+  }
+}
+
+native!(NativeBind, NATIVE_BIND);
+
+impl LyNative for NativeBind {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    Call::Ok(val!(hooks.manage_obj(Method::new(args[0], this.unwrap()))))
+  }
+}
+
 native!(NativeCall, NATIVE_CALL);
 
 impl LyNative for NativeCall {
@@ -99,6 +133,67 @@ mod test {
     }
   }
 
+  mod arity {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let native_arity = NativeArity::native(&hooks);
+
+      assert_eq!(native_arity.meta().name, "arity");
+      assert_eq!(native_arity.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let native_arity = NativeArity::native(&hooks.as_gc());
+
+      let managed = NativeBind::native(&hooks.as_gc());
+      let result = native_arity.call(&mut hooks, Some(val!(managed)), &[]);
+      assert_eq!(result.unwrap(), val!(1.0));
+    }
+  }
+
+  mod bind {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let native_bind = NativeBind::native(&hooks);
+
+      assert_eq!(native_bind.meta().name, "bind");
+      assert_eq!(native_bind.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        native_bind.meta().signature.parameters[0].kind,
+        ParameterKind::Any
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let native_bind = NativeBind::native(&hooks.as_gc());
+
+      let managed = TestNative::native(&hooks.as_gc());
+      let result = native_bind
+        .call(&mut hooks, Some(val!(managed)), &[val!(10.0)])
+        .unwrap();
+
+      let method = result.to_obj().to_method();
+      assert_eq!(method.receiver(), val!(10.0));
+      assert_eq!(method.method(), val!(managed));
+    }
+  }
+
   mod call {
     use super::*;
     use crate::{global::support::TestNative, support::MockedContext};
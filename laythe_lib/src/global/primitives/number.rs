@@ -9,6 +9,7 @@ use laythe_core::{
   managed::Trace,
   module::Module,
   object::{Enumerate, Enumerator, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  parse::parse_number,
   signature::{Arity, ParameterBuilder, ParameterKind},
   val,
   value::Value,
@@ -36,6 +37,20 @@ const NUMBER_FLOOR: NativeMetaBuilder = NativeMetaBuilder::method("floor", Arity
 const NUMBER_CEIL: NativeMetaBuilder = NativeMetaBuilder::method("ceil", Arity::Fixed(0));
 const NUMBER_ROUND: NativeMetaBuilder = NativeMetaBuilder::method("round", Arity::Fixed(0));
 
+const NUMBER_TO_FIXED: NativeMetaBuilder = NativeMetaBuilder::method("toFixed", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("digits", ParameterKind::Number)]);
+
+const NUMBER_TO_PRECISION: NativeMetaBuilder =
+  NativeMetaBuilder::method("toPrecision", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("precision", ParameterKind::Number)]);
+
+const NUMBER_TO_STRING: NativeMetaBuilder =
+  NativeMetaBuilder::method("toString", Arity::Default(0, 1))
+    .with_params(&[ParameterBuilder::new("radix", ParameterKind::Number)]);
+
+const NUMBER_DIV_REM: NativeMetaBuilder = NativeMetaBuilder::method("divRem", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("divisor", ParameterKind::Number)]);
+
 const NUMBER_CMP: NativeMetaBuilder =
   NativeMetaBuilder::fun("cmp", Arity::Fixed(2)).with_params(&[
     ParameterBuilder::new("a", ParameterKind::Number),
@@ -91,6 +106,30 @@ pub fn define_number_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
     val!(NumberUntil::native(hooks, value_error)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(NUMBER_TO_FIXED.name),
+    val!(NumberToFixed::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(NUMBER_TO_PRECISION.name),
+    val!(NumberToPrecision::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(NUMBER_TO_STRING.name),
+    val!(NumberToString::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(NUMBER_DIV_REM.name),
+    val!(NumberDivRem::native(hooks, value_error)),
+  );
+
   class.meta_class().expect("Meta class not set.").add_method(
     hooks,
     hooks.manage_str(NUMBER_PARSE.name),
@@ -138,12 +177,162 @@ impl LyNative for NumberRound {
   }
 }
 
+/// The most digits toFixed and toPrecision will produce
+const MAX_DIGITS: f64 = 100.0;
+
+/// Check a requested number of digits is an integer within the provided range
+fn digit_count(digits: f64, min: f64) -> Option<usize> {
+  if digits.fract() == 0.0 && digits >= min && digits <= MAX_DIGITS {
+    Some(digits as usize)
+  } else {
+    None
+  }
+}
+
+/// Format a number with the provided number of significant digits. Numbers
+/// with large or small exponents are written in exponential notation
+fn to_precision(num: f64, precision: usize) -> String {
+  if !num.is_finite() {
+    return num.to_string();
+  }
+
+  if num == 0.0 {
+    return format!("{:.*}", precision - 1, 0.0);
+  }
+
+  // rounding to the precision may carry into the next power of ten
+  // so the exponent is read back from the rounded number
+  let scientific = format!("{:.*e}", precision - 1, num);
+  let (mantissa, exponent) = scientific
+    .split_once('e')
+    .expect("Expected exponential notation");
+  let exponent: i32 = exponent.parse().expect("Expected integer exponent");
+
+  if exponent < -6 || exponent >= precision as i32 {
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{}e{}{}", mantissa, sign, exponent.abs())
+  } else {
+    format!("{:.*}", (precision as i32 - 1 - exponent) as usize, num)
+  }
+}
+
+/// Format a number in the provided radix. Fractions are written with
+/// as many digits as the radix needs to represent a double
+fn to_radix(num: f64, radix: u32) -> String {
+  if !num.is_finite() || radix == 10 {
+    return num.to_string();
+  }
+
+  let radix_f = radix as f64;
+  let mut whole = num.abs().trunc();
+  let mut fraction = num.abs().fract();
+
+  let mut digits = vec![];
+  loop {
+    let digit = (whole % radix_f) as u32;
+    digits.push(std::char::from_digit(digit, radix).expect("Expected digit in radix"));
+    whole = (whole / radix_f).trunc();
+
+    if whole == 0.0 {
+      break;
+    }
+  }
+
+  if num < 0.0 {
+    digits.push('-');
+  }
+  digits.reverse();
+
+  if fraction > 0.0 {
+    digits.push('.');
+
+    let max_fraction = (f64::MANTISSA_DIGITS as f64 / radix_f.log2()).ceil() as usize;
+    for _ in 0..max_fraction {
+      fraction *= radix_f;
+      let digit = fraction.trunc();
+      digits.push(std::char::from_digit(digit as u32, radix).expect("Expected digit in radix"));
+      fraction -= digit;
+
+      if fraction == 0.0 {
+        break;
+      }
+    }
+  }
+
+  digits.into_iter().collect()
+}
+
+// formatting never depends on the host locale. Exact ties such as 2.5 round
+// to the even digit
+native_with_error!(NumberToFixed, NUMBER_TO_FIXED);
+
+impl LyNative for NumberToFixed {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let num = this.unwrap().to_num();
+
+    match digit_count(args[0].to_num(), 0.0) {
+      Some(digits) => Call::Ok(val!(hooks.manage_str(format!("{:.*}", digits, num)))),
+      None => self.call_error(hooks, "toFixed requires an integer between 0 and 100."),
+    }
+  }
+}
+
+native_with_error!(NumberToPrecision, NUMBER_TO_PRECISION);
+
+impl LyNative for NumberToPrecision {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let num = this.unwrap().to_num();
+
+    match digit_count(args[0].to_num(), 1.0) {
+      Some(precision) => Call::Ok(val!(hooks.manage_str(to_precision(num, precision)))),
+      None => self.call_error(hooks, "toPrecision requires an integer between 1 and 100."),
+    }
+  }
+}
+
+native_with_error!(NumberToString, NUMBER_TO_STRING);
+
+impl LyNative for NumberToString {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let num = this.unwrap().to_num();
+    let radix = if args.is_empty() {
+      10.0
+    } else {
+      args[0].to_num()
+    };
+
+    if radix.fract() != 0.0 || !(2.0..=36.0).contains(&radix) {
+      return self.call_error(hooks, "toString requires a radix between 2 and 36.");
+    }
+
+    Call::Ok(val!(hooks.manage_str(to_radix(num, radix as u32))))
+  }
+}
+
+// the quotient is floored so the remainder always takes the sign of the divisor
+native_with_error!(NumberDivRem, NUMBER_DIV_REM);
+
+impl LyNative for NumberDivRem {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let num = this.unwrap().to_num();
+    let divisor = args[0].to_num();
+
+    if divisor == 0.0 {
+      return self.call_error(hooks, "divRem cannot divide by zero.");
+    }
+
+    let quotient = (num / divisor).floor();
+    let remainder = num - quotient * divisor;
+    Call::tuple(&hooks.as_gc(), &[val!(quotient), val!(remainder)])
+  }
+}
+
 native_with_error!(NumberParse, NUMBER_PARSE);
 
 impl LyNative for NumberParse {
   fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
     let str = args[0].to_obj().to_str();
-    match str.parse::<f64>() {
+    match parse_number(&str) {
       Ok(num) => Call::Ok(val!(num)),
       Err(_) => self.call_error(hooks, format!("Unable to parse number from {}", str)),
     }
@@ -525,6 +714,92 @@ mod test {
     }
   }
 
+  mod to_fixed {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let number_to_fixed = NumberToFixed::native(&hooks.as_gc(), error);
+
+      let result = number_to_fixed
+        .call(&mut hooks, Some(val!(1.005)), &[val!(2.0)])
+        .unwrap();
+      assert_eq!(&*result.to_obj().to_str(), "1.00");
+
+      let result = number_to_fixed
+        .call(&mut hooks, Some(val!(-12.5)), &[val!(0.0)])
+        .unwrap();
+      assert_eq!(&*result.to_obj().to_str(), "-12");
+    }
+  }
+
+  mod div_rem {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let number_div_rem = NumberDivRem::native(&hooks.as_gc(), error);
+
+      let result = number_div_rem
+        .call(&mut hooks, Some(val!(7.0)), &[val!(2.0)])
+        .unwrap();
+      assert_eq!(&**result.to_obj().to_tuple(), &[val!(3.0), val!(1.0)]);
+
+      let result = number_div_rem
+        .call(&mut hooks, Some(val!(-7.0)), &[val!(2.0)])
+        .unwrap();
+      assert_eq!(&**result.to_obj().to_tuple(), &[val!(-4.0), val!(1.0)]);
+    }
+  }
+
+  mod to_precision {
+    use super::*;
+
+    #[test]
+    fn fixed() {
+      assert_eq!(to_precision(123.456, 4), "123.5");
+      assert_eq!(to_precision(0.000123, 2), "0.00012");
+      assert_eq!(to_precision(0.0, 3), "0.00");
+      assert_eq!(to_precision(9.99, 2), "10");
+    }
+
+    #[test]
+    fn exponential() {
+      assert_eq!(to_precision(123456.0, 2), "1.2e+5");
+      assert_eq!(to_precision(-0.00000012, 1), "-1e-7");
+      assert_eq!(to_precision(f64::INFINITY, 3), "inf");
+    }
+  }
+
+  mod to_radix {
+    use super::*;
+
+    #[test]
+    fn whole() {
+      assert_eq!(to_radix(255.0, 16), "ff");
+      assert_eq!(to_radix(-10.0, 2), "-1010");
+      assert_eq!(to_radix(0.0, 36), "0");
+      assert_eq!(to_radix(35.0, 36), "z");
+    }
+
+    #[test]
+    fn fraction() {
+      assert_eq!(to_radix(0.5, 2), "0.1");
+      assert_eq!(to_radix(2.75, 16), "2.c");
+      assert_eq!(to_radix(1.5, 10), "1.5");
+    }
+  }
+
   mod until {
     use super::*;
     use crate::support::{test_error_class, MockedContext};
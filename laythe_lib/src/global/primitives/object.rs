@@ -1,10 +1,12 @@
 use crate::native;
+use fnv::FnvBuildHasher;
+use hashbrown::HashMap;
 use laythe_core::{
   constants::OBJECT,
   hooks::{GcHooks, Hooks},
   managed::{GcObj, GcObject, Trace},
   match_obj,
-  object::{Class, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  object::{Class, Instance, List, LyNative, Map, Native, NativeMetaBuilder, ObjectKind, Tuple},
   signature::{Arity, ParameterBuilder, ParameterKind},
   to_obj_kind, val,
   value::{Value, ValueKind},
@@ -21,6 +23,16 @@ const OBJECT_CLASS: NativeMetaBuilder = NativeMetaBuilder::method("cls", Arity::
 
 const OBJECT_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
 
+const OBJECT_COPY: NativeMetaBuilder = NativeMetaBuilder::method("copy", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("deep", ParameterKind::Bool)]);
+
+const OBJECT_FREEZE: NativeMetaBuilder = NativeMetaBuilder::method("freeze", Arity::Fixed(0));
+
+const OBJECT_IS_FROZEN: NativeMetaBuilder = NativeMetaBuilder::method("isFrozen", Arity::Fixed(0));
+
+const OBJECT_IS_A: NativeMetaBuilder = NativeMetaBuilder::method("isA", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("class", ParameterKind::Class)]);
+
 pub fn create_object_class(hooks: &GcHooks) -> GcObj<Class> {
   let name = hooks.manage_str(OBJECT_CLASS_NAME);
   let mut object = hooks.manage_obj(Class::bare(name));
@@ -43,6 +55,30 @@ pub fn create_object_class(hooks: &GcHooks) -> GcObj<Class> {
     val!(ObjectStr::native(hooks)),
   );
 
+  object.add_method(
+    hooks,
+    hooks.manage_str(OBJECT_COPY.name),
+    val!(ObjectCopy::native(hooks)),
+  );
+
+  object.add_method(
+    hooks,
+    hooks.manage_str(OBJECT_FREEZE.name),
+    val!(ObjectFreeze::native(hooks)),
+  );
+
+  object.add_method(
+    hooks,
+    hooks.manage_str(OBJECT_IS_FROZEN.name),
+    val!(ObjectIsFrozen::native(hooks)),
+  );
+
+  object.add_method(
+    hooks,
+    hooks.manage_str(OBJECT_IS_A.name),
+    val!(ObjectIsA::native(hooks)),
+  );
+
   object
 }
 
@@ -89,6 +125,9 @@ impl LyNative for ObjectStr {
         ObjectKind::Fiber(fiber) => {
           format!("<{} {:p}>", &*class.name(), &*fiber)
         },
+        ObjectKind::Channel(channel) => {
+          format!("<{} {:p}>", &*class.name(), &*channel)
+        },
         ObjectKind::Instance(instance) => {
           format!("<{} {:p}>", &*class.name(), &*instance)
         },
@@ -107,6 +146,9 @@ impl LyNative for ObjectStr {
         ObjectKind::String(string) => {
           format!("<{} {}>", &*class.name(), string)
         },
+        ObjectKind::Tuple(tuple) => {
+          format!("<{} {:p}>", &*class.name(), &*tuple)
+        },
         ObjectKind::Upvalue(upvalue) => {
           format!("<{} {:p}>", &*class.name(), &upvalue)
         },
@@ -117,6 +159,180 @@ impl LyNative for ObjectStr {
   }
 }
 
+native!(ObjectCopy, OBJECT_COPY);
+
+impl LyNative for ObjectCopy {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let deep = !args.is_empty() && args[0].to_bool();
+    let hooks = hooks.as_gc();
+    let mut copier = Copier::new(&hooks, deep);
+
+    Call::Ok(copier.copy(this.unwrap()))
+  }
+}
+
+/// Copies lists, maps, instances and tuples. A deep copy tracks the objects it
+/// has already copied so shared and cyclic references are preserved in the copy.
+/// Copies are never frozen and all other values are returned as is
+struct Copier<'a> {
+  hooks: &'a GcHooks<'a>,
+  deep: bool,
+  copies: HashMap<GcObject, Value, FnvBuildHasher>,
+}
+
+impl<'a> Copier<'a> {
+  fn new(hooks: &'a GcHooks<'a>, deep: bool) -> Self {
+    Self {
+      hooks,
+      deep,
+      copies: HashMap::default(),
+    }
+  }
+
+  fn copy(&mut self, value: Value) -> Value {
+    if !value.is_obj() {
+      return value;
+    }
+
+    let obj = value.to_obj();
+    if let Some(copy) = self.copies.get(&obj) {
+      return *copy;
+    }
+
+    match obj.kind() {
+      ObjectKind::List => self.copy_list(obj),
+      ObjectKind::Map => self.copy_map(obj),
+      ObjectKind::Instance => self.copy_instance(obj),
+      ObjectKind::Tuple if self.deep => self.copy_tuple(obj),
+      _ => value,
+    }
+  }
+
+  fn item(&mut self, value: Value) -> Value {
+    if self.deep {
+      self.copy(value)
+    } else {
+      value
+    }
+  }
+
+  fn copy_list(&mut self, obj: GcObject) -> Value {
+    let list = obj.to_list();
+    let mut copy = self.hooks.manage_obj(List::with_capacity(list.len()));
+    self.hooks.push_root(copy);
+    self.copies.insert(obj, val!(copy));
+
+    for item in list.iter() {
+      let item = self.item(*item);
+      self.hooks.grow(&mut *copy, |copy| copy.push(item));
+    }
+
+    self.hooks.pop_roots(1);
+    val!(copy)
+  }
+
+  fn copy_map(&mut self, obj: GcObject) -> Value {
+    let map = obj.to_map();
+    let mut copy = self.hooks.manage_obj(Map::with_capacity(map.len()));
+    self.hooks.push_root(copy);
+    self.copies.insert(obj, val!(copy));
+
+    // keys are shared as copying them could change how they hash
+    for (key, value) in map.iter() {
+      let value = self.item(*value);
+      self.hooks.grow(&mut *copy, |copy| copy.insert(*key, value));
+    }
+
+    self.hooks.pop_roots(1);
+    val!(copy)
+  }
+
+  fn copy_instance(&mut self, obj: GcObject) -> Value {
+    let instance = obj.to_instance();
+    let mut copy = self.hooks.manage_obj(Instance::new(instance.class()));
+    self.hooks.push_root(copy);
+    self.copies.insert(obj, val!(copy));
+
+    for (index, field) in instance.fields().iter().enumerate() {
+      copy[index] = self.item(*field);
+    }
+
+    for (name, field) in instance.dynamic_fields() {
+      let field = self.item(*field);
+      copy.set_dynamic_field(self.hooks, *name, field);
+    }
+
+    self.hooks.pop_roots(1);
+    val!(copy)
+  }
+
+  fn copy_tuple(&mut self, obj: GcObject) -> Value {
+    let tuple = obj.to_tuple();
+    let items: Vec<Value> = tuple
+      .iter()
+      .map(|item| {
+        let item = self.copy(*item);
+        self.hooks.push_root(item);
+        item
+      })
+      .collect();
+
+    let copy = val!(self.hooks.manage_obj(Tuple::from(items)));
+    self.hooks.pop_roots(tuple.len());
+    self.copies.insert(obj, copy);
+    copy
+  }
+}
+
+native!(ObjectFreeze, OBJECT_FREEZE);
+
+impl LyNative for ObjectFreeze {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap();
+
+    // non objects are already immutable
+    if this.is_obj() {
+      this.to_obj().freeze();
+    }
+
+    Call::Ok(this)
+  }
+}
+
+native!(ObjectIsFrozen, OBJECT_IS_FROZEN);
+
+impl LyNative for ObjectIsFrozen {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap();
+
+    let frozen = !this.is_obj()
+      || this.to_obj().is_frozen()
+      || this.is_obj_kind(ObjectKind::String)
+      || this.is_obj_kind(ObjectKind::Tuple);
+
+    Call::Ok(val!(frozen))
+  }
+}
+
+native!(ObjectIsA, OBJECT_IS_A);
+
+impl LyNative for ObjectIsA {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let class = args[0].to_obj().to_class();
+    let mut current = Some(hooks.get_class(this.unwrap()).to_obj().to_class());
+
+    while let Some(ancestor) = current {
+      if ancestor == class {
+        return Call::Ok(val!(true));
+      }
+
+      current = *ancestor.super_class();
+    }
+
+    Call::Ok(val!(false))
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -226,4 +442,150 @@ mod test {
       }
     }
   }
+
+  mod copy {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let object_copy = ObjectCopy::native(&hooks);
+
+      assert_eq!(object_copy.meta().name, "copy");
+      assert_eq!(object_copy.meta().signature.arity, Arity::Default(0, 1));
+      assert_eq!(
+        object_copy.meta().signature.parameters[0].kind,
+        ParameterKind::Bool
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let object_copy = ObjectCopy::native(&hooks.as_gc());
+
+      let inner = hooks.manage_obj(List::from(vec![val!(1.0)]));
+      let list = hooks.manage_obj(List::from(vec![val!(inner)]));
+
+      let shallow = object_copy.call(&mut hooks, Some(val!(list)), &[]).unwrap();
+      assert_ne!(shallow, val!(list));
+      assert_eq!(shallow.to_obj().to_list()[0], val!(inner));
+
+      let deep = object_copy
+        .call(&mut hooks, Some(val!(list)), &[val!(true)])
+        .unwrap();
+      assert_ne!(deep.to_obj().to_list()[0], val!(inner));
+      assert_eq!(deep.to_obj().to_list()[0].to_obj().to_list()[0], val!(1.0));
+    }
+  }
+
+  mod freeze {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let object_freeze = ObjectFreeze::native(&hooks);
+
+      assert_eq!(object_freeze.meta().name, "freeze");
+      assert_eq!(object_freeze.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let object_freeze = ObjectFreeze::native(&hooks.as_gc());
+
+      let list = hooks.manage_obj(List::<Value>::new());
+
+      let result = object_freeze
+        .call(&mut hooks, Some(val!(list)), &[])
+        .unwrap();
+      assert_eq!(result, val!(list));
+      assert!(list.is_frozen());
+    }
+  }
+
+  mod is_frozen {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let object_is_frozen = ObjectIsFrozen::native(&hooks);
+
+      assert_eq!(object_is_frozen.meta().name, "isFrozen");
+      assert_eq!(object_is_frozen.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let object_is_frozen = ObjectIsFrozen::native(&hooks.as_gc());
+
+      let list = hooks.manage_obj(List::<Value>::new());
+
+      let result = object_is_frozen.call(&mut hooks, Some(val!(list)), &[]);
+      assert!(!result.unwrap().to_bool());
+
+      list.freeze();
+      let result = object_is_frozen.call(&mut hooks, Some(val!(list)), &[]);
+      assert!(result.unwrap().to_bool());
+
+      let result = object_is_frozen.call(&mut hooks, Some(val!(10.0)), &[]);
+      assert!(result.unwrap().to_bool());
+    }
+  }
+  mod is_a {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let object_is_a = ObjectIsA::native(&hooks);
+
+      assert_eq!(object_is_a.meta().name, "isA");
+      assert_eq!(object_is_a.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        object_is_a.meta().signature.parameters[0].kind,
+        ParameterKind::Class
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::with_std(&[]).unwrap();
+      let mut hooks = Hooks::new(&mut context);
+      let object_is_a = ObjectIsA::native(&hooks.as_gc());
+
+      let ten = val!(10.0);
+      let number = hooks.get_class(ten);
+      let object = val!(number.to_obj().to_class().super_class().unwrap());
+      let other = val!(hooks.manage_obj(Class::bare(hooks.manage_str("other"))));
+
+      let result = object_is_a.call(&mut hooks, Some(ten), &[number]);
+      assert!(result.unwrap().to_bool());
+
+      let result = object_is_a.call(&mut hooks, Some(ten), &[object]);
+      assert!(result.unwrap().to_bool());
+
+      let result = object_is_a.call(&mut hooks, Some(ten), &[other]);
+      assert!(!result.unwrap().to_bool());
+    }
+  }
 }
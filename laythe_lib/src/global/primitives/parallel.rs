@@ -0,0 +1,151 @@
+use crate::create_error;
+use laythe_core::{
+  hooks::Hooks,
+  managed::{GcObj, Trace},
+  object::{Fiber, ObjectKind},
+  value::Value,
+  worker::{Message, PoolJob},
+  Call,
+};
+use std::{cell::RefCell, io::Write, time::Duration};
+
+/// How often a fiber waiting on the worker pool checks its job again
+const POOL_POLL: Duration = Duration::from_millis(2);
+
+/// A call waiting on the worker pool
+#[derive(Debug)]
+struct Pending {
+  caller: GcObj<Fiber>,
+  this: Value,
+  fun: Value,
+  job: PoolJob,
+}
+
+/// Calls of a native that hand their work to the worker pool, keyed by
+/// the fiber waiting on each. The fiber sleeps between checks of its job
+/// so the native is called again with the same arguments once it wakes
+#[derive(Debug)]
+pub struct PoolCalls {
+  pending: RefCell<Vec<Pending>>,
+  type_error: Value,
+  error: Value,
+}
+
+impl PoolCalls {
+  /// Create calls raising `type_error` for items or functions that can't
+  /// be sent to the pool and `error` when the function fails
+  pub fn new(type_error: Value, error: Value) -> Self {
+    debug_assert!(type_error.is_obj_kind(ObjectKind::Class));
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+
+    Self {
+      pending: RefCell::new(vec![]),
+      type_error,
+      error,
+    }
+  }
+
+  /// Call `fun` with each item on the worker pool, returning what each call
+  /// returned in the order of the items once every call has finished.
+  /// `items` copies the items and is only called when the job starts
+  pub fn call(
+    &self,
+    hooks: &mut Hooks,
+    this: Value,
+    fun: Value,
+    collect: bool,
+    items: impl FnOnce(&mut Hooks) -> Result<Vec<Message>, Call>,
+  ) -> Result<Vec<Message>, Call> {
+    let caller = hooks.current_fiber();
+    if !self.waiting(caller, this, fun) {
+      let items = items(hooks)?;
+
+      match hooks.run_in_pool(fun, items, collect) {
+        Ok(job) => self.pending.borrow_mut().push(Pending {
+          caller,
+          this,
+          fun,
+          job,
+        }),
+        Err(err) => return Err(self.type_error(hooks, &err)),
+      }
+    }
+
+    let finished = self
+      .pending
+      .borrow_mut()
+      .iter_mut()
+      .find(|pending| pending.caller == caller)
+      .and_then(|pending| pending.job.try_finish());
+
+    match finished {
+      Some(result) => {
+        self.remove(caller);
+        hooks.current_fiber().wake_from_sleep();
+        result.map_err(|err| create_error!(self.error, hooks, &err))
+      },
+      None => {
+        if !hooks.sleep_fiber(POOL_POLL) {
+          hooks.sleep_fiber(POOL_POLL);
+        }
+        Err(Call::Block)
+      },
+    }
+  }
+
+  /// Raise a type error for an item or function that can't be sent
+  pub fn type_error(&self, hooks: &mut Hooks, message: &str) -> Call {
+    create_error!(self.type_error, hooks, message)
+  }
+
+  /// Is the caller waiting on a job from this call, dropping jobs whose
+  /// caller has completed or was cancelled and has since made a new call
+  fn waiting(&self, caller: GcObj<Fiber>, this: Value, fun: Value) -> bool {
+    let mut pending = self.pending.borrow_mut();
+    pending.retain(|pending| {
+      !pending.caller.is_complete()
+        && (pending.caller != caller || (pending.this == this && pending.fun == fun))
+    });
+    pending.iter().any(|pending| pending.caller == caller)
+  }
+
+  /// Remove a caller's pending call
+  fn remove(&self, caller: GcObj<Fiber>) {
+    self
+      .pending
+      .borrow_mut()
+      .retain(|pending| pending.caller != caller);
+  }
+}
+
+impl Trace for PoolCalls {
+  fn trace(&self) {
+    self.pending.borrow().iter().for_each(|pending| {
+      pending.caller.trace();
+      pending.this.trace();
+      pending.fun.trace();
+    });
+    self.type_error.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.pending.borrow().iter().for_each(|pending| {
+      pending.caller.visit_refs(visitor);
+      pending.this.visit_refs(visitor);
+      pending.fun.visit_refs(visitor);
+    });
+    self.type_error.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, log: &mut dyn Write) {
+    self.pending.borrow().iter().for_each(|pending| {
+      pending.caller.trace_debug(log);
+      pending.this.trace_debug(log);
+      pending.fun.trace_debug(log);
+    });
+    self.type_error.trace_debug(log);
+    self.error.trace_debug(log);
+  }
+}
@@ -1,24 +1,32 @@
 use crate::{
+  create_error,
+  global::{Inspector, INSPECT_DEPTH},
   native, native_with_error,
   support::{export_and_insert, load_class_from_module},
   StdResult,
 };
 use laythe_core::{
   constants::INDEX_GET,
+  get,
   hooks::{GcHooks, Hooks},
+  if_let_obj,
   managed::GcObj,
   managed::{GcStr, Trace},
   module::Module,
   object::{Enumerate, Enumerator, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  parse::parse_number,
   signature::{Arity, ParameterBuilder, ParameterKind},
-  val,
+  to_obj_kind, val,
   value::{Value, VALUE_NIL},
   Call, LyResult,
 };
 use std::{io::Write, str::Split};
 use std::{mem, str::Chars};
 
-use super::{class_inheritance, error::INDEX_ERROR_NAME};
+use super::{
+  class_inheritance,
+  error::{FORMAT_CLASS_NAME, INDEX_ERROR_NAME, TYPE_ERROR_NAME},
+};
 
 pub const STRING_CLASS_NAME: &str = "String";
 
@@ -43,6 +51,12 @@ const STRING_SLICE: NativeMetaBuilder = NativeMetaBuilder::method("slice", Arity
 
 const STRING_ITER: NativeMetaBuilder = NativeMetaBuilder::method("iter", Arity::Fixed(0));
 
+const STRING_PARSE_NUMBER: NativeMetaBuilder =
+  NativeMetaBuilder::method("parseNumber", Arity::Fixed(0));
+
+const STRING_FORMAT: NativeMetaBuilder = NativeMetaBuilder::method("format", Arity::Variadic(0))
+  .with_params(&[ParameterBuilder::new("values", ParameterKind::Any)]);
+
 pub fn declare_string_class(hooks: &GcHooks, module: &mut Module) -> StdResult<()> {
   let class = class_inheritance(hooks, module, STRING_CLASS_NAME)?;
   export_and_insert(hooks, module, class.name(), val!(class))
@@ -51,6 +65,8 @@ pub fn declare_string_class(hooks: &GcHooks, module: &mut Module) -> StdResult<(
 pub fn define_string_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
   let mut class = load_class_from_module(hooks, module, STRING_CLASS_NAME)?;
   let index_error = val!(load_class_from_module(hooks, module, INDEX_ERROR_NAME)?);
+  let format_error = val!(load_class_from_module(hooks, module, FORMAT_CLASS_NAME)?);
+  let type_error = val!(load_class_from_module(hooks, module, TYPE_ERROR_NAME)?);
 
   class.add_method(
     hooks,
@@ -94,9 +110,151 @@ pub fn define_string_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
     val!(StringIter::native(hooks)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(STRING_PARSE_NUMBER.name),
+    val!(StringParseNumber::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(STRING_FORMAT.name),
+    val!(StringFormat::native(
+      hooks,
+      Inspector::new(hooks, type_error),
+      format_error
+    )),
+  );
+
   Ok(())
 }
 
+/// Fill the placeholders in template with the provided values. An empty
+/// placeholder `{}` takes the next value, `{1}` takes the value at that
+/// position and `{name}` reads the key from a map passed as the last
+/// value. Braces are escaped by doubling them as `{{` and `}}`
+pub(crate) fn format_template(
+  hooks: &mut Hooks,
+  inspector: &Inspector,
+  error: Value,
+  template: &str,
+  args: &[Value],
+) -> Call {
+  let mut buf = String::with_capacity(template.len());
+  let mut chars = template.chars().peekable();
+  let mut next = 0;
+
+  while let Some(c) = chars.next() {
+    match c {
+      '{' if chars.peek() == Some(&'{') => {
+        chars.next();
+        buf.push('{');
+      },
+      '}' if chars.peek() == Some(&'}') => {
+        chars.next();
+        buf.push('}');
+      },
+      '{' => {
+        let mut field = String::new();
+        loop {
+          match chars.next() {
+            Some('}') => break,
+            Some(c) => field.push(c),
+            None => return create_error!(error, hooks, "Unmatched '{' in format string."),
+          }
+        }
+
+        let value = if field.is_empty() {
+          next += 1;
+          args.get(next - 1).copied()
+        } else if let Ok(index) = field.parse::<usize>() {
+          args.get(index).copied()
+        } else {
+          named_value(hooks, args, &field)
+        };
+
+        let value = match value {
+          Some(value) => value,
+          None if field.is_empty() || field.parse::<usize>().is_ok() => {
+            return create_error!(
+              error,
+              hooks,
+              format!("Format string expected more than {} value(s).", args.len())
+            )
+          },
+          None => {
+            return create_error!(
+              error,
+              hooks,
+              format!("Format string references missing name '{}'.", field)
+            )
+          },
+        };
+
+        if_let_obj!(ObjectKind::String(string) = (value) {
+          buf.push_str(&string);
+        } else {
+          get!(inspector.inspect(hooks, value, INSPECT_DEPTH, &mut buf));
+        });
+      },
+      '}' => return create_error!(error, hooks, "Unmatched '}' in format string."),
+      c => buf.push(c),
+    }
+  }
+
+  Call::Ok(val!(hooks.manage_str(buf)))
+}
+
+/// Look up a named placeholder in a map passed as the last value
+fn named_value(hooks: &mut Hooks, args: &[Value], name: &str) -> Option<Value> {
+  let map = args.last()?;
+  if !map.is_obj_kind(ObjectKind::Map) {
+    return None;
+  }
+
+  let key = val!(hooks.manage_str(name));
+  map.to_obj().to_map().get(&key).copied()
+}
+
+#[derive(Debug)]
+struct StringFormat {
+  inspector: Inspector,
+  error: Value,
+}
+
+impl StringFormat {
+  fn native(hooks: &GcHooks, inspector: Inspector, error: Value) -> GcObj<Native> {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    let native = Box::new(Self { inspector, error }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(STRING_FORMAT.to_meta(hooks), native))
+  }
+}
+
+impl Trace for StringFormat {
+  fn trace(&self) {
+    self.inspector.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.inspector.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, log: &mut dyn Write) {
+    self.inspector.trace_debug(log);
+    self.error.trace_debug(log);
+  }
+}
+
+impl LyNative for StringFormat {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let template = this.unwrap().to_obj().to_str();
+    format_template(hooks, &self.inspector, self.error, &template, args)
+  }
+}
+
 native!(StringStr, STRING_STR);
 
 impl LyNative for StringStr {
@@ -230,6 +388,12 @@ impl Trace for SplitIterator {
     self.current.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.string.visit_refs(visitor);
+    self.separator.visit_refs(visitor);
+    self.current.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.string.trace_debug(stdout);
     self.separator.trace_debug(stdout);
@@ -256,6 +420,7 @@ impl LyNative for StringSlice {
       LyResult::Ok(index) => index,
       LyResult::Err(err) => return LyResult::Err(err),
       LyResult::Exit(exit) => return LyResult::Exit(exit),
+      LyResult::Block => return LyResult::Block,
     };
 
     // let end_index = if let Some(end) = end {
@@ -263,6 +428,7 @@ impl LyNative for StringSlice {
       LyResult::Ok(index) => index,
       LyResult::Err(err) => return LyResult::Err(err),
       LyResult::Exit(exit) => return LyResult::Exit(exit),
+      LyResult::Block => return LyResult::Block,
     };
 
     if start_index <= end_index {
@@ -304,6 +470,20 @@ impl StringSlice {
   }
 }
 
+native!(StringParseNumber, STRING_PARSE_NUMBER);
+
+impl LyNative for StringParseNumber {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let str = this.unwrap().to_obj().to_str();
+
+    // same grammar as Number.parse, so the result never depends on the host locale
+    match parse_number(&str) {
+      Ok(num) => Call::Ok(val!(num)),
+      Err(_) => Call::Ok(VALUE_NIL),
+    }
+  }
+}
+
 native!(StringIter, STRING_ITER);
 
 impl LyNative for StringIter {
@@ -375,6 +555,11 @@ impl Trace for StringIterator {
     self.current.trace();
   }
 
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.string.visit_refs(visitor);
+    self.current.visit_refs(visitor);
+  }
+
   fn trace_debug(&self, stdout: &mut dyn Write) {
     self.string.trace_debug(stdout);
     self.current.trace_debug(stdout);
@@ -669,4 +854,69 @@ mod test {
       assert_eq!(iter.next(&mut hooks).unwrap(), VALUE_FALSE);
     }
   }
+
+  mod parse_number {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let parse_number = StringParseNumber::native(&hooks.as_gc());
+
+      let this = val!(hooks.manage_str("-12.5e2"));
+      let result = parse_number.call(&mut hooks, Some(this), &[]);
+      match result {
+        Call::Ok(r) => assert_eq!(r, val!(-1250.0)),
+        _ => panic!(),
+      }
+
+      let this = val!(hooks.manage_str("1,5"));
+      let result = parse_number.call(&mut hooks, Some(this), &[]);
+      match result {
+        Call::Ok(r) => assert_eq!(r, VALUE_NIL),
+        _ => panic!(),
+      }
+    }
+  }
+
+  mod format {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let error = val!(test_error_class(&hooks));
+      let string_format = StringFormat::native(&hooks, Inspector::new(&hooks, error), error);
+
+      assert_eq!(string_format.meta().name, "format");
+      assert_eq!(string_format.meta().signature.arity, Arity::Variadic(0));
+      assert_eq!(
+        string_format.meta().signature.parameters[0].kind,
+        ParameterKind::Any
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let string_format =
+        StringFormat::native(&hooks.as_gc(), Inspector::new(&hooks.as_gc(), error), error);
+
+      let this = val!(hooks.manage_str("{1}, {} {{}}"));
+      let args = &[val!(hooks.manage_str("a")), val!(hooks.manage_str("b"))];
+      let result = string_format.call(&mut hooks, Some(this), args);
+      match result {
+        Call::Ok(r) => assert_eq!(&*r.to_obj().to_str(), "b, a {}"),
+        _ => panic!(),
+      }
+    }
+  }
 }
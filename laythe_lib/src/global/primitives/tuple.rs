@@ -0,0 +1,345 @@
+use crate::{
+  native, native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  constants::INDEX_GET,
+  hooks::{GcHooks, Hooks},
+  if_let_obj,
+  managed::{GcObj, GcStr, Trace},
+  module::Module,
+  object::{Enumerate, Enumerator, List, LyNative, Native, NativeMetaBuilder, ObjectKind, Tuple},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  to_obj_kind, val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{io::Write, mem};
+
+use super::{
+  class_inheritance,
+  error::{INDEX_ERROR_NAME, TYPE_ERROR_NAME},
+};
+
+pub const TUPLE_CLASS_NAME: &str = "Tuple";
+
+const TUPLE_INDEX_GET: NativeMetaBuilder = NativeMetaBuilder::method(INDEX_GET, Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("index", ParameterKind::Number)]);
+
+const TUPLE_HAS: NativeMetaBuilder = NativeMetaBuilder::method("has", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("val", ParameterKind::Any)]);
+
+const TUPLE_ITER: NativeMetaBuilder = NativeMetaBuilder::method("iter", Arity::Fixed(0));
+const TUPLE_LEN: NativeMetaBuilder = NativeMetaBuilder::method("len", Arity::Fixed(0));
+const TUPLE_LIST: NativeMetaBuilder = NativeMetaBuilder::method("list", Arity::Fixed(0));
+const TUPLE_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
+
+pub fn declare_tuple_class(hooks: &GcHooks, module: &mut Module) -> StdResult<()> {
+  let class = class_inheritance(hooks, module, TUPLE_CLASS_NAME)?;
+
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_tuple_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, TUPLE_CLASS_NAME)?;
+  let index_error = val!(load_class_from_module(hooks, module, INDEX_ERROR_NAME)?);
+  let type_error = val!(load_class_from_module(hooks, module, TYPE_ERROR_NAME)?);
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(TUPLE_INDEX_GET.name),
+    val!(TupleIndexGet::native(hooks, index_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(TUPLE_LEN.name),
+    val!(TupleLen::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(TUPLE_HAS.name),
+    val!(TupleHas::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(TUPLE_ITER.name),
+    val!(TupleIter::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(TUPLE_LIST.name),
+    val!(TupleList::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(TUPLE_STR.name),
+    val!(TupleStr::native(
+      hooks,
+      hooks.manage_str(TUPLE_STR.name),
+      type_error,
+    )),
+  );
+
+  Ok(())
+}
+
+native_with_error!(TupleIndexGet, TUPLE_INDEX_GET);
+
+impl LyNative for TupleIndexGet {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let index = args[0].to_num();
+    let tuple = this.unwrap().to_obj().to_tuple();
+
+    if index.fract() != 0.0 || index < 0.0 {
+      return self.call_error(hooks, "Index must be a non negative integer.");
+    }
+
+    match tuple.get(index as usize) {
+      Some(item) => Call::Ok(*item),
+      None => self.call_error(
+        hooks,
+        format!(
+          "Index out of bounds. tuple was length {} but attempted to index with {}.",
+          tuple.len(),
+          index
+        ),
+      ),
+    }
+  }
+}
+
+native!(TupleLen, TUPLE_LEN);
+
+impl LyNative for TupleLen {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(val!(this.unwrap().to_obj().to_tuple().len() as f64))
+  }
+}
+
+native!(TupleHas, TUPLE_HAS);
+
+impl LyNative for TupleHas {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    Call::Ok(val!(this.unwrap().to_obj().to_tuple().contains(&args[0])))
+  }
+}
+
+native!(TupleIter, TUPLE_ITER);
+
+impl LyNative for TupleIter {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let inner_iter: Box<dyn Enumerate> =
+      Box::new(TupleIterator::new(this.unwrap().to_obj().to_tuple()));
+    let iter = Enumerator::new(inner_iter);
+
+    Call::Ok(val!(hooks.manage_obj(iter)))
+  }
+}
+
+native!(TupleList, TUPLE_LIST);
+
+impl LyNative for TupleList {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let tuple = this.unwrap().to_obj().to_tuple();
+    Call::Ok(val!(hooks.manage_obj(List::from(&**tuple))))
+  }
+}
+
+#[derive(Debug)]
+struct TupleStr {
+  method_name: GcStr,
+  error: Value,
+}
+
+impl TupleStr {
+  fn native(hooks: &GcHooks, method_name: GcStr, error: Value) -> GcObj<Native> {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    let native = Box::new(Self { method_name, error }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(TUPLE_STR.to_meta(hooks), native))
+  }
+
+  /// The string form of a single item, strings are quoted and all other
+  /// values use their '.str' method
+  fn item_str(&self, hooks: &mut Hooks, item: Value) -> Result<String, Call> {
+    if_let_obj!(ObjectKind::String(string) = (item) {
+      return Ok(format!("{}", string));
+    });
+
+    let str_result = hooks
+      .get_method(item, self.method_name)
+      .and_then(|method| hooks.call_method(item, method, &[]));
+
+    match str_result {
+      Call::Ok(result) => {
+        if_let_obj!(ObjectKind::String(string) = (result) {
+          Ok(String::from(&*string))
+        } else {
+          Err(hooks.call(
+            self.error,
+            &[val!(hooks.manage_str(format!(
+              "Expected type str from {}.str()",
+              item
+            )))],
+          ))
+        })
+      },
+      other => Err(other),
+    }
+  }
+}
+
+impl Trace for TupleStr {
+  fn trace(&self) {
+    self.method_name.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_name.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, log: &mut dyn Write) {
+    self.method_name.trace_debug(log);
+    self.error.trace_debug(log);
+  }
+}
+
+impl LyNative for TupleStr {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let tuple = this.unwrap().to_obj().to_tuple();
+
+    let mut items = Vec::with_capacity(tuple.len());
+    for item in tuple.iter() {
+      match self.item_str(hooks, *item) {
+        Ok(string) => items.push(string),
+        Err(call) => return call,
+      }
+    }
+
+    // a single item tuple keeps its trailing comma to tell it apart from a grouping
+    let buf = match items.as_slice() {
+      [single] => format!("({},)", single),
+      _ => format!("({})", items.join(", ")),
+    };
+
+    Call::Ok(val!(hooks.manage_str(buf)))
+  }
+}
+
+#[derive(Debug)]
+struct TupleIterator {
+  tuple: GcObj<Tuple>,
+  index: usize,
+  current: Value,
+}
+
+impl TupleIterator {
+  fn new(tuple: GcObj<Tuple>) -> Self {
+    Self {
+      tuple,
+      index: 0,
+      current: VALUE_NIL,
+    }
+  }
+}
+
+impl Enumerate for TupleIterator {
+  fn name(&self) -> &str {
+    "TupleIterator"
+  }
+
+  fn current(&self) -> Value {
+    self.current
+  }
+
+  fn next(&mut self, _hooks: &mut Hooks) -> Call {
+    match self.tuple.get(self.index) {
+      Some(value) => {
+        self.index += 1;
+        self.current = *value;
+        Call::Ok(val!(true))
+      },
+      None => {
+        self.current = VALUE_NIL;
+        Call::Ok(val!(false))
+      },
+    }
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.tuple.len())
+  }
+
+  fn size(&self) -> usize {
+    mem::size_of::<Self>()
+  }
+}
+
+impl Trace for TupleIterator {
+  fn trace(&self) {
+    self.tuple.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.tuple.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.tuple.trace_debug(stdout);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  mod index_get {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let index_get = TupleIndexGet::native(&hooks.as_gc(), error);
+
+      let tuple = hooks.manage_obj(Tuple::new(&[val!(1.0), VALUE_NIL]));
+      let result = index_get.call(&mut hooks, Some(val!(tuple)), &[val!(1.0)]);
+      match result {
+        Call::Ok(r) => assert!(r.is_nil()),
+        _ => panic!(),
+      }
+    }
+  }
+
+  mod has {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let has = TupleHas::native(&hooks.as_gc());
+
+      let tuple = hooks.manage_obj(Tuple::new(&[val!(1.0), val!(true)]));
+      match has.call(&mut hooks, Some(val!(tuple)), &[val!(true)]) {
+        Call::Ok(r) => assert_eq!(r, val!(true)),
+        _ => panic!(),
+      }
+      match has.call(&mut hooks, Some(val!(tuple)), &[val!(2.0)]) {
+        Call::Ok(r) => assert_eq!(r, val!(false)),
+        _ => panic!(),
+      }
+    }
+  }
+}
@@ -1,43 +1,92 @@
 use crate::{
+  create_error,
+  global::{create_bytes, BYTES_CLASS_NAME},
   io::{global::IO_ERROR, IO_MODULE_PATH},
-  native_with_error,
+  native, native_with_bytes, native_with_error,
   support::load_class_from_package,
   support::{default_class_inheritance, export_and_insert, load_class_from_module},
-  StdResult,
+  StdResult, STD,
 };
 use laythe_core::{
   hooks::{GcHooks, Hooks},
   managed::GcObj,
   managed::Trace,
   module::{Module, Package},
-  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  object::{
+    Class, Enumerate, Enumerator, Instance, LyNative, Native, NativeMetaBuilder, ObjectKind,
+  },
   signature::{Arity, ParameterBuilder, ParameterKind},
   val,
-  value::Value,
+  value::{Value, VALUE_NIL},
   Call,
 };
-use std::io::Write;
-use std::path::Path;
+use laythe_env::fs::{FileHandle, OpenMode};
+use std::io::{SeekFrom, Write};
+use std::{mem, path::Path};
 
 const FILE_CLASS_NAME: &str = "File";
 
+const FILE_FIELD_PATH: &str = "path";
+const FILE_FIELD_MODE: &str = "mode";
+const FILE_FIELD_HANDLE: &str = "handle";
+
 const FILE_READ_ALL_TEXT: NativeMetaBuilder =
   NativeMetaBuilder::fun("readAllText", Arity::Fixed(1))
     .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
 
+const FILE_READ_ALL_BYTES: NativeMetaBuilder =
+  NativeMetaBuilder::fun("readAllBytes", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
+
+const FILE_OPEN: NativeMetaBuilder = NativeMetaBuilder::method("open", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("path", ParameterKind::String),
+    ParameterBuilder::new("mode", ParameterKind::String),
+  ]);
+
+const FILE_READ: NativeMetaBuilder = NativeMetaBuilder::method("read", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("count", ParameterKind::Number)]);
+
+const FILE_READ_LINE: NativeMetaBuilder = NativeMetaBuilder::method("readLine", Arity::Fixed(0));
+
+const FILE_ITER: NativeMetaBuilder = NativeMetaBuilder::method("iter", Arity::Fixed(0));
+
+const FILE_WRITE: NativeMetaBuilder = NativeMetaBuilder::method("write", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
+const FILE_SEEK: NativeMetaBuilder = NativeMetaBuilder::method("seek", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("offset", ParameterKind::Number),
+    ParameterBuilder::new("from", ParameterKind::String),
+  ]);
+
+const FILE_TELL: NativeMetaBuilder = NativeMetaBuilder::method("tell", Arity::Fixed(0));
+
+const FILE_CLOSE: NativeMetaBuilder = NativeMetaBuilder::method("close", Arity::Fixed(0));
+
+const FILE_ENTER: NativeMetaBuilder = NativeMetaBuilder::method("enter", Arity::Fixed(0));
+
+const FILE_EXIT: NativeMetaBuilder = NativeMetaBuilder::method("exit", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("error", ParameterKind::Any)]);
+
 pub fn declare_file(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
   let class = default_class_inheritance(hooks, std, FILE_CLASS_NAME)?;
   export_and_insert(hooks, module, class.name(), val!(class))
 }
 
 pub fn define_file(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
-  let class = load_class_from_module(hooks, module, FILE_CLASS_NAME)?;
+  let mut class = load_class_from_module(hooks, module, FILE_CLASS_NAME)?;
   let io_error = val!(load_class_from_package(
     hooks,
     std,
     IO_MODULE_PATH,
     IO_ERROR
   )?);
+  let bytes = load_class_from_package(hooks, std, STD, BYTES_CLASS_NAME)?;
+
+  class.add_field(hooks, hooks.manage_str(FILE_FIELD_PATH));
+  class.add_field(hooks, hooks.manage_str(FILE_FIELD_MODE));
+  class.add_field(hooks, hooks.manage_str(FILE_FIELD_HANDLE));
 
   class.meta_class().expect("Meta class not set.").add_method(
     hooks,
@@ -45,9 +94,86 @@ pub fn define_file(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult
     val!(FileReadAllText::native(hooks, io_error)),
   );
 
+  class.meta_class().expect("Meta class not set.").add_method(
+    hooks,
+    hooks.manage_str(FILE_READ_ALL_BYTES.name),
+    val!(FileReadAllBytes::native(hooks, bytes, io_error)),
+  );
+
+  class.meta_class().expect("Meta class not set.").add_method(
+    hooks,
+    hooks.manage_str(FILE_OPEN.name),
+    val!(FileOpen::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(FILE_READ.name),
+    val!(FileRead::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(FILE_READ_LINE.name),
+    val!(FileReadLine::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(FILE_ITER.name),
+    val!(FileIter::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(FILE_WRITE.name),
+    val!(FileWrite::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(FILE_SEEK.name),
+    val!(FileSeek::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(FILE_TELL.name),
+    val!(FileTell::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(FILE_CLOSE.name),
+    val!(FileClose::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(FILE_ENTER.name),
+    val!(FileEnter::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(FILE_EXIT.name),
+    val!(FileExit::native(hooks, io_error)),
+  );
+
   Ok(())
 }
 
+/// The handle of an open file, None once it has been closed
+fn file_handle(this: Option<Value>) -> Option<FileHandle> {
+  let handle = this.unwrap().to_obj().to_instance()[2];
+
+  if handle.is_num() {
+    Some(handle.to_num() as FileHandle)
+  } else {
+    None
+  }
+}
+
 native_with_error!(FileReadAllText, FILE_READ_ALL_TEXT);
 
 impl LyNative for FileReadAllText {
@@ -62,6 +188,294 @@ impl LyNative for FileReadAllText {
   }
 }
 
+native_with_bytes!(FileReadAllBytes, FILE_READ_ALL_BYTES);
+
+impl LyNative for FileReadAllBytes {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let io = hooks.as_io();
+    let path = args[0].to_obj().to_str();
+
+    match io.fs().read(Path::new(&*path)) {
+      Ok(result) => Call::Ok(val!(create_bytes(&hooks.as_gc(), self.bytes, &result))),
+      Err(err) => create_error!(self.error, hooks, err.to_string()),
+    }
+  }
+}
+
+native_with_error!(FileOpen, FILE_OPEN);
+
+impl LyNative for FileOpen {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let class = this.unwrap().to_obj().to_class();
+    let path = args[0].to_obj().to_str();
+
+    let mode = match args.get(1) {
+      Some(mode) => mode.to_obj().to_str(),
+      None => hooks.manage_str("r"),
+    };
+
+    let open_mode = match OpenMode::parse(&mode) {
+      Some(open_mode) => open_mode,
+      None => {
+        return self.call_error(
+          hooks,
+          format!(
+            "Invalid file mode '{}', expected 'r', 'w', 'a' or 'r+'.",
+            &*mode
+          ),
+        )
+      },
+    };
+
+    let handle = match hooks.as_io().fs().open(Path::new(&*path), open_mode) {
+      Ok(handle) => handle,
+      Err(err) => return self.call_error(hooks, err.to_string()),
+    };
+
+    hooks.push_root(mode);
+    let mut instance = hooks.manage_obj(Instance::new(class));
+    hooks.pop_roots(1);
+
+    instance[0] = args[0];
+    instance[1] = val!(mode);
+    instance[2] = val!(handle as f64);
+
+    Call::Ok(val!(instance))
+  }
+}
+
+native_with_error!(FileRead, FILE_READ);
+
+impl LyNative for FileRead {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let handle = match file_handle(this) {
+      Some(handle) => handle,
+      None => return self.call_error(hooks, "File is closed."),
+    };
+
+    let fs = hooks.as_io().fs();
+    let result = match args.first() {
+      Some(count) => fs.read_chars(handle, count.to_num().max(0.0) as usize),
+      None => fs.read_rest(handle),
+    };
+
+    match result {
+      Ok(read) => Call::Ok(val!(hooks.manage_str(read))),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+native_with_error!(FileReadLine, FILE_READ_LINE);
+
+impl LyNative for FileReadLine {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let handle = match file_handle(this) {
+      Some(handle) => handle,
+      None => return self.call_error(hooks, "File is closed."),
+    };
+
+    match hooks.as_io().fs().read_file_line(handle) {
+      Ok(Some(line)) => Call::Ok(val!(hooks.manage_str(line))),
+      Ok(None) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+native_with_error!(FileIter, FILE_ITER);
+
+impl LyNative for FileIter {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let handle = match file_handle(this) {
+      Some(handle) => handle,
+      None => return self.call_error(hooks, "File is closed."),
+    };
+
+    let inner_iter: Box<dyn Enumerate> = Box::new(FileLineIterator::new(handle, self.error));
+    let iter = hooks.manage_obj(Enumerator::new(inner_iter));
+
+    Call::Ok(val!(iter))
+  }
+}
+
+native_with_error!(FileWrite, FILE_WRITE);
+
+impl LyNative for FileWrite {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let handle = match file_handle(this) {
+      Some(handle) => handle,
+      None => return self.call_error(hooks, "File is closed."),
+    };
+
+    let contents = args[0].to_obj().to_str();
+    match hooks.as_io().fs().write_file(handle, contents.as_bytes()) {
+      Ok(_) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+native_with_error!(FileSeek, FILE_SEEK);
+
+impl LyNative for FileSeek {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let handle = match file_handle(this) {
+      Some(handle) => handle,
+      None => return self.call_error(hooks, "File is closed."),
+    };
+
+    let offset = args[0].to_num();
+    let from = args.get(1).map(|from| from.to_obj().to_str());
+
+    let position = match from.as_deref() {
+      None | Some("start") if offset >= 0.0 => SeekFrom::Start(offset as u64),
+      Some("current") => SeekFrom::Current(offset as i64),
+      Some("end") => SeekFrom::End(offset as i64),
+      None | Some("start") => {
+        return self.call_error(hooks, "Cannot seek before the start of a file.")
+      },
+      Some(from) => {
+        return self.call_error(
+          hooks,
+          format!(
+            "Invalid seek origin '{}', expected 'start', 'current' or 'end'.",
+            from
+          ),
+        )
+      },
+    };
+
+    match hooks.as_io().fs().seek(handle, position) {
+      Ok(position) => Call::Ok(val!(position as f64)),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+native_with_error!(FileTell, FILE_TELL);
+
+impl LyNative for FileTell {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let handle = match file_handle(this) {
+      Some(handle) => handle,
+      None => return self.call_error(hooks, "File is closed."),
+    };
+
+    match hooks.as_io().fs().seek(handle, SeekFrom::Current(0)) {
+      Ok(position) => Call::Ok(val!(position as f64)),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+/// Close a file if it's still open
+fn close_file(hooks: &mut Hooks, this: Option<Value>) -> std::io::Result<()> {
+  match file_handle(this) {
+    Some(handle) => {
+      this.unwrap().to_obj().to_instance()[2] = VALUE_NIL;
+      hooks.as_io().fs().close(handle)
+    },
+    None => Ok(()),
+  }
+}
+
+native_with_error!(FileClose, FILE_CLOSE);
+
+impl LyNative for FileClose {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    match close_file(hooks, this) {
+      Ok(_) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+native!(FileEnter, FILE_ENTER);
+
+impl LyNative for FileEnter {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(this.unwrap())
+  }
+}
+
+native_with_error!(FileExit, FILE_EXIT);
+
+impl LyNative for FileExit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    match close_file(hooks, this) {
+      Ok(_) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+#[derive(Debug)]
+struct FileLineIterator {
+  handle: FileHandle,
+  error: Value,
+  current: Value,
+}
+
+impl FileLineIterator {
+  fn new(handle: FileHandle, error: Value) -> Self {
+    Self {
+      handle,
+      error,
+      current: VALUE_NIL,
+    }
+  }
+}
+
+impl Enumerate for FileLineIterator {
+  fn name(&self) -> &str {
+    "FileLineIterator"
+  }
+
+  fn current(&self) -> Value {
+    self.current
+  }
+
+  fn next(&mut self, hooks: &mut Hooks) -> Call {
+    match hooks.as_io().fs().read_file_line(self.handle) {
+      Ok(Some(line)) => {
+        self.current = val!(hooks.manage_str(line));
+        Call::Ok(val!(true))
+      },
+      Ok(None) => {
+        self.current = VALUE_NIL;
+        Call::Ok(val!(false))
+      },
+      Err(err) => create_error!(self.error, hooks, err.to_string()),
+    }
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    None
+  }
+
+  fn size(&self) -> usize {
+    mem::size_of::<Self>()
+  }
+}
+
+impl Trace for FileLineIterator {
+  fn trace(&self) {
+    self.error.trace();
+    self.current.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+    self.current.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.error.trace_debug(stdout);
+    self.current.trace_debug(stdout);
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -88,4 +502,97 @@ mod test {
 
     // TODO call
   }
+
+  mod read_all_bytes {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+      let bytes = test_error_class(&hooks);
+
+      let read_all_bytes = FileReadAllBytes::native(&hooks, bytes, error);
+
+      assert_eq!(read_all_bytes.meta().name, "readAllBytes");
+      assert_eq!(read_all_bytes.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        read_all_bytes.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+  }
+
+  mod open {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let file_open = FileOpen::native(&hooks, error);
+
+      assert_eq!(file_open.meta().name, "open");
+      assert_eq!(file_open.meta().signature.arity, Arity::Default(1, 2));
+      assert_eq!(
+        file_open.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+      assert_eq!(
+        file_open.meta().signature.parameters[1].kind,
+        ParameterKind::String
+      );
+    }
+  }
+
+  mod read {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let file_read = FileRead::native(&hooks, error);
+
+      assert_eq!(file_read.meta().name, "read");
+      assert_eq!(file_read.meta().signature.arity, Arity::Default(0, 1));
+      assert_eq!(
+        file_read.meta().signature.parameters[0].kind,
+        ParameterKind::Number
+      );
+    }
+  }
+
+  mod seek {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let file_seek = FileSeek::native(&hooks, error);
+
+      assert_eq!(file_seek.meta().name, "seek");
+      assert_eq!(file_seek.meta().signature.arity, Arity::Default(1, 2));
+      assert_eq!(
+        file_seek.meta().signature.parameters[0].kind,
+        ParameterKind::Number
+      );
+      assert_eq!(
+        file_seek.meta().signature.parameters[1].kind,
+        ParameterKind::String
+      );
+    }
+  }
 }
@@ -0,0 +1,502 @@
+use crate::{
+  create_error,
+  global::VALUE_ERROR_NAME,
+  io::{global::IO_ERROR, IO_MODULE_PATH},
+  support::{export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Enumerate, Enumerator, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use laythe_env::fs::SlDirEntry;
+use std::{
+  io::{self, Write},
+  mem,
+  path::{Component, Path, PathBuf},
+};
+
+const GLOB_META: NativeMetaBuilder = NativeMetaBuilder::fun("glob", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("pattern", ParameterKind::String)]);
+
+pub fn declare_glob(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let io_error = val!(load_class_from_package(
+    hooks,
+    std,
+    IO_MODULE_PATH,
+    IO_ERROR
+  )?);
+  let value_error = val!(load_class_from_package(hooks, std, STD, VALUE_ERROR_NAME)?);
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(GLOB_META.name),
+    val!(Glob::native(hooks, io_error, value_error)),
+  )
+}
+
+/// A single character matcher within a path component
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  /// A literal character
+  Char(char),
+
+  /// `?` matching any one character
+  Any,
+
+  /// `*` matching any run of characters
+  Star,
+
+  /// `[...]` matching one character in, or with `!` not in, the ranges
+  Class {
+    negated: bool,
+    ranges: Vec<(char, char)>,
+  },
+}
+
+impl Token {
+  fn matches(&self, c: char) -> bool {
+    match self {
+      Token::Char(expected) => *expected == c,
+      Token::Any => true,
+      Token::Star => true,
+      Token::Class { negated, ranges } => {
+        let found = ranges
+          .iter()
+          .any(|(start, end)| (*start..=*end).contains(&c));
+        found != *negated
+      },
+    }
+  }
+}
+
+/// A path component of a glob pattern
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+  /// `**` matching any number of nested directories
+  Recursive,
+
+  /// A component matched against a single file or directory name
+  Name(Vec<Token>),
+}
+
+impl Segment {
+  fn parse(component: &str) -> Result<Self, String> {
+    if component == "**" {
+      return Ok(Segment::Recursive);
+    }
+
+    let mut tokens = vec![];
+    let mut chars = component.chars();
+
+    while let Some(c) = chars.next() {
+      match c {
+        '*' => {
+          if tokens.last() != Some(&Token::Star) {
+            tokens.push(Token::Star);
+          }
+        },
+        '?' => tokens.push(Token::Any),
+        '[' => tokens.push(Segment::parse_class(&mut chars, component)?),
+        _ => tokens.push(Token::Char(c)),
+      }
+    }
+
+    Ok(Segment::Name(tokens))
+  }
+
+  /// Parse a character class after its opening `[`
+  fn parse_class(chars: &mut std::str::Chars, component: &str) -> Result<Token, String> {
+    let mut negated = false;
+    let mut ranges: Vec<(char, char)> = vec![];
+
+    let mut peek = chars.clone();
+    if let Some('!') = peek.next() {
+      negated = true;
+      chars.next();
+    }
+
+    loop {
+      match chars.next() {
+        // a leading ] is part of the class rather than closing it
+        Some(']') if !ranges.is_empty() => return Ok(Token::Class { negated, ranges }),
+        Some(start) => {
+          let mut peek = chars.clone();
+          match (peek.next(), peek.next()) {
+            (Some('-'), Some(end)) if end != ']' => {
+              chars.next();
+              chars.next();
+              ranges.push((start, end));
+            },
+            _ => ranges.push((start, start)),
+          }
+        },
+        None => return Err(format!("Unclosed '[' in glob pattern {}.", component)),
+      }
+    }
+  }
+
+  /// Does this segment match the provided file or directory name. Names
+  /// starting with a `.` are hidden and only matched by a literal `.`
+  fn matches(&self, name: &str) -> bool {
+    match self {
+      Segment::Recursive => !name.starts_with('.'),
+      Segment::Name(tokens) => {
+        if name.starts_with('.') && tokens.first() != Some(&Token::Char('.')) {
+          return false;
+        }
+
+        let name: Vec<char> = name.chars().collect();
+        match_tokens(tokens, &name)
+      },
+    }
+  }
+}
+
+fn match_tokens(tokens: &[Token], name: &[char]) -> bool {
+  match tokens.split_first() {
+    None => name.is_empty(),
+    Some((Token::Star, rest)) => (0..=name.len()).any(|skip| match_tokens(rest, &name[skip..])),
+    Some((token, rest)) => match name.split_first() {
+      Some((c, name_rest)) => token.matches(*c) && match_tokens(rest, name_rest),
+      None => false,
+    },
+  }
+}
+
+/// A parsed glob pattern, made of the literal directory the search starts
+/// in and the segments matched below it
+#[derive(Debug, PartialEq)]
+struct Pattern {
+  base: PathBuf,
+  segments: Vec<Segment>,
+}
+
+impl Pattern {
+  fn parse(pattern: &str) -> Result<Self, String> {
+    let components: Vec<Component> = Path::new(pattern).components().collect();
+    if components.is_empty() {
+      return Err("Glob pattern can't be empty.".to_string());
+    }
+
+    let is_wild = |component: &Component| match component {
+      Component::Normal(part) => part.to_string_lossy().contains(&['*', '?', '['][..]),
+      _ => false,
+    };
+
+    // keep a trailing literal name as a segment so a match is only
+    // produced when the path actually exists
+    let split = match components.iter().position(is_wild) {
+      Some(split) => split,
+      None => match components.last() {
+        Some(Component::Normal(_)) => components.len() - 1,
+        _ => components.len(),
+      },
+    };
+
+    let base: PathBuf = components[..split].iter().collect();
+    let mut segments = vec![];
+
+    for component in &components[split..] {
+      let segment = match component {
+        Component::Normal(part) => Segment::parse(&part.to_string_lossy())?,
+        _ => {
+          return Err(format!(
+            "Glob pattern {} can't use '..' after a wildcard.",
+            pattern
+          ))
+        },
+      };
+
+      if segment == Segment::Recursive && segments.last() == Some(&Segment::Recursive) {
+        continue;
+      }
+      segments.push(segment);
+    }
+
+    // a trailing ** matches everything beneath it
+    if segments.last() == Some(&Segment::Recursive) {
+      segments.push(Segment::Name(vec![Token::Star]));
+    }
+
+    Ok(Self { base, segments })
+  }
+}
+
+#[derive(Debug)]
+/// A native function iterating the paths matching a glob pattern
+pub struct Glob {
+  io_error: Value,
+  value_error: Value,
+}
+
+impl Glob {
+  fn native(hooks: &GcHooks, io_error: Value, value_error: Value) -> GcObj<Native> {
+    debug_assert!(io_error.is_obj_kind(ObjectKind::Class));
+    debug_assert!(value_error.is_obj_kind(ObjectKind::Class));
+    let native = Box::new(Self {
+      io_error,
+      value_error,
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(GLOB_META.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for Glob {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let pattern = match Pattern::parse(&args[0].to_obj().to_str()) {
+      Ok(pattern) => pattern,
+      Err(message) => return create_error!(self.value_error, hooks, message),
+    };
+
+    let inner_iter: Box<dyn Enumerate> = Box::new(GlobIterator::new(pattern, self.io_error));
+    let iter = hooks.manage_obj(Enumerator::new(inner_iter));
+
+    Call::Ok(val!(iter))
+  }
+}
+
+impl Trace for Glob {
+  fn trace(&self) {
+    self.io_error.trace();
+    self.value_error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.io_error.visit_refs(visitor);
+    self.value_error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.io_error.trace_debug(stdout);
+    self.value_error.trace_debug(stdout);
+  }
+}
+
+/// Walks the file system depth first, reading a directory only when the
+/// iterator reaches it. Paths are produced in name order
+#[derive(Debug)]
+struct GlobIterator {
+  segments: Vec<Segment>,
+  pending: Vec<(PathBuf, usize)>,
+  error: Value,
+  current: Value,
+}
+
+impl GlobIterator {
+  fn new(pattern: Pattern, error: Value) -> Self {
+    Self {
+      segments: pattern.segments,
+      pending: vec![(pattern.base, 0)],
+      error,
+      current: VALUE_NIL,
+    }
+  }
+
+  /// The entries of a directory sorted by name and joined onto path
+  fn read_directory(hooks: &mut Hooks, path: &Path) -> io::Result<Vec<(String, SlDirEntry)>> {
+    let directory = if path.as_os_str().is_empty() {
+      Path::new(".")
+    } else {
+      path
+    };
+
+    let mut entries: Vec<(String, SlDirEntry)> = hooks
+      .as_io()
+      .fs()
+      .read_directory(directory)?
+      .into_iter()
+      .filter_map(|entry| {
+        let name = entry.path.file_name()?.to_string_lossy().into_owned();
+        let path = path.join(&name);
+        Some((name, SlDirEntry { path, ..entry }))
+      })
+      .collect();
+
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(entries)
+  }
+}
+
+impl Enumerate for GlobIterator {
+  fn name(&self) -> &str {
+    "GlobIterator"
+  }
+
+  fn current(&self) -> Value {
+    self.current
+  }
+
+  fn next(&mut self, hooks: &mut Hooks) -> Call {
+    while let Some((path, index)) = self.pending.pop() {
+      let segment = match self.segments.get(index) {
+        Some(segment) => segment,
+        None => {
+          self.current = val!(hooks.manage_str(path.to_string_lossy()));
+          return Call::Ok(val!(true));
+        },
+      };
+
+      let entries = match GlobIterator::read_directory(hooks, &path) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+        Err(err) => return create_error!(self.error, hooks, err.to_string()),
+      };
+
+      let last = index + 1 == self.segments.len();
+      let before = self.pending.len();
+
+      match segment {
+        Segment::Recursive => {
+          self.pending.push((path, index + 1));
+          self.pending.extend(
+            entries
+              .into_iter()
+              .filter(|(name, entry)| entry.is_dir && segment.matches(name))
+              .map(|(_, entry)| (entry.path, index)),
+          );
+        },
+        Segment::Name(_) => {
+          self.pending.extend(
+            entries
+              .into_iter()
+              .filter(|(name, entry)| (last || entry.is_dir) && segment.matches(name))
+              .map(|(_, entry)| (entry.path, index + 1)),
+          );
+        },
+      }
+
+      // the pending stack is popped from the back so reverse to keep name order
+      self.pending[before..].reverse();
+    }
+
+    self.current = VALUE_NIL;
+    Call::Ok(val!(false))
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    None
+  }
+
+  fn size(&self) -> usize {
+    mem::size_of::<Self>()
+  }
+}
+
+impl Trace for GlobIterator {
+  fn trace(&self) {
+    self.error.trace();
+    self.current.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+    self.current.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.error.trace_debug(stdout);
+    self.current.trace_debug(stdout);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  fn matches(pattern: &str, name: &str) -> bool {
+    Segment::parse(pattern).unwrap().matches(name)
+  }
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let error = val!(test_error_class(&hooks));
+
+    let glob = Glob::native(&hooks, error, error);
+
+    assert_eq!(glob.meta().name, "glob");
+    assert_eq!(glob.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(
+      glob.meta().signature.parameters[0].kind,
+      ParameterKind::String
+    );
+  }
+
+  #[test]
+  fn call() {
+    let mut context = MockedContext::default();
+    let mut hooks = Hooks::new(&mut context);
+    let error = val!(test_error_class(&hooks.as_gc()));
+
+    let glob = Glob::native(&hooks.as_gc(), error, error);
+
+    let pattern = val!(hooks.manage_str("src/**/*.lay"));
+    let result = glob.call(&mut hooks, None, &[pattern]).unwrap();
+    assert!(result.is_obj_kind(ObjectKind::Enumerator));
+
+    let mut iter = result.to_obj().to_enumerator();
+    assert_eq!(iter.next(&mut hooks).unwrap(), val!(false));
+  }
+
+  #[test]
+  fn parse() {
+    let pattern = Pattern::parse("src/**/**/*.lay").unwrap();
+    assert_eq!(pattern.base, PathBuf::from("src"));
+    assert_eq!(
+      pattern.segments,
+      vec![
+        Segment::Recursive,
+        Segment::Name(vec![
+          Token::Star,
+          Token::Char('.'),
+          Token::Char('l'),
+          Token::Char('a'),
+          Token::Char('y')
+        ])
+      ]
+    );
+
+    let pattern = Pattern::parse("src/main.lay").unwrap();
+    assert_eq!(pattern.base, PathBuf::from("src"));
+    assert_eq!(pattern.segments.len(), 1);
+
+    let pattern = Pattern::parse("src/**").unwrap();
+    assert_eq!(
+      pattern.segments,
+      vec![Segment::Recursive, Segment::Name(vec![Token::Star])]
+    );
+
+    assert!(Pattern::parse("").is_err());
+    assert!(Pattern::parse("*/../a").is_err());
+    assert!(Pattern::parse("src/[a-").is_err());
+  }
+
+  #[test]
+  fn segment_matches() {
+    assert!(matches("*.lay", "main.lay"));
+    assert!(!matches("*.lay", "main.rs"));
+    assert!(matches("m??n.*", "main.lay"));
+    assert!(matches("*a*b*", "xaybz"));
+    assert!(!matches("*a*b*", "xbya"));
+
+    assert!(matches("[abc].lay", "b.lay"));
+    assert!(matches("[a-c]x", "cx"));
+    assert!(!matches("[!a-c]x", "cx"));
+    assert!(matches("[]]", "]"));
+    assert!(matches("[a-]", "-"));
+
+    assert!(!matches("*", ".hidden"));
+    assert!(matches(".*", ".hidden"));
+  }
+}
@@ -1,6 +1,8 @@
 mod file;
+mod glob;
 
 use file::{declare_file, define_file};
+use glob::declare_glob;
 use laythe_core::{
   hooks::GcHooks,
   managed::Gc,
@@ -25,6 +27,7 @@ pub fn fs_module(hooks: &GcHooks, std: &Package, emitter: &mut IdEmitter) -> Std
 
   declare_file(hooks, &mut module, &*std)?;
   define_file(hooks, &module, &*std)?;
+  declare_glob(hooks, &mut module, std)?;
 
   Ok(module)
 }
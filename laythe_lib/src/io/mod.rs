@@ -1,12 +1,16 @@
 mod fs;
 mod global;
+mod path;
 mod stdio;
 
 use self::global::io_module;
 use crate::{StdError, StdResult};
 use fs::fs_module;
 use laythe_core::{hooks::GcHooks, module::Package, utils::IdEmitter};
+use path::path_module;
 use stdio::stdio_module;
+
+pub use global::IO_ERROR;
 pub const IO_MODULE_PATH: &str = "std/io";
 
 pub fn add_io_package(
@@ -21,7 +25,9 @@ pub fn add_io_package(
 
   let stdio = stdio_module(hooks, std, emitter)?;
   let fs = fs_module(hooks, std, emitter)?;
+  let path = path_module(hooks, std, emitter)?;
 
   io_module.insert_module(hooks, stdio)?;
+  io_module.insert_module(hooks, path)?;
   io_module.insert_module(hooks, fs).map_err(StdError::from)
 }
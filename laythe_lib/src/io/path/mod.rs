@@ -0,0 +1,34 @@
+mod utils;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+};
+use std::path::PathBuf;
+use utils::{declare_path_module, define_path_module};
+
+use crate::{global::MODULE_CLASS_NAME, support::load_class_from_package, StdResult, STD};
+
+const PATH_PATH: &str = "std/io/path";
+
+pub fn path_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(PATH_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  declare_path_module(hooks, &mut module, std)?;
+  define_path_module(hooks, &mut module)?;
+
+  Ok(module)
+}
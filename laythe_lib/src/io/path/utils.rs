@@ -0,0 +1,495 @@
+use crate::{
+  io::{global::IO_ERROR, IO_MODULE_PATH},
+  native, native_with_error,
+  support::{export_and_insert, load_class_from_package},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use laythe_env::path::{normalize, relative_to};
+use std::{
+  io::Write,
+  path::{Path, PathBuf},
+};
+
+const JOIN_META: NativeMetaBuilder = NativeMetaBuilder::fun("join", Arity::Variadic(1))
+  .with_params(&[
+    ParameterBuilder::new("path", ParameterKind::String),
+    ParameterBuilder::new("paths", ParameterKind::String),
+  ]);
+
+const DIRNAME_META: NativeMetaBuilder = NativeMetaBuilder::fun("dirname", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
+
+const BASENAME_META: NativeMetaBuilder = NativeMetaBuilder::fun("basename", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
+
+const EXTENSION_META: NativeMetaBuilder = NativeMetaBuilder::fun("extension", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
+
+const NORMALIZE_META: NativeMetaBuilder = NativeMetaBuilder::fun("normalize", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
+
+const IS_ABSOLUTE_META: NativeMetaBuilder = NativeMetaBuilder::fun("isAbsolute", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
+
+const RELATIVE_TO_META: NativeMetaBuilder = NativeMetaBuilder::fun("relativeTo", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("path", ParameterKind::String),
+    ParameterBuilder::new("base", ParameterKind::String),
+  ]);
+
+pub fn declare_path_module(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let io_error = val!(load_class_from_package(
+    hooks,
+    std,
+    IO_MODULE_PATH,
+    IO_ERROR
+  )?);
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(JOIN_META.name),
+    val!(Join::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(DIRNAME_META.name),
+    val!(Dirname::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(BASENAME_META.name),
+    val!(Basename::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(EXTENSION_META.name),
+    val!(Extension::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(NORMALIZE_META.name),
+    val!(Normalize::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(IS_ABSOLUTE_META.name),
+    val!(IsAbsolute::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(RELATIVE_TO_META.name),
+    val!(RelativeTo::native(hooks, io_error)),
+  )
+}
+
+pub fn define_path_module(_: &GcHooks, _: &mut Module) -> StdResult<()> {
+  Ok(())
+}
+
+/// Create a laythe string from a path built out of laythe strings
+fn path_value(hooks: &mut Hooks, path: &Path) -> Value {
+  val!(hooks.manage_str(path.to_string_lossy()))
+}
+
+native!(Join, JOIN_META);
+
+impl LyNative for Join {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let path: PathBuf = args
+      .iter()
+      .map(|arg| arg.to_obj().to_str())
+      .fold(PathBuf::new(), |path, part| path.join(&*part));
+
+    Call::Ok(path_value(hooks, &path))
+  }
+}
+
+native!(Dirname, DIRNAME_META);
+
+impl LyNative for Dirname {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let path = args[0].to_obj().to_str();
+    let path = Path::new(&*path);
+
+    let dirname = match path.parent() {
+      Some(parent) if !parent.as_os_str().is_empty() => parent,
+      // the root is its own directory
+      None if path.has_root() => path,
+      _ => Path::new("."),
+    };
+
+    Call::Ok(path_value(hooks, dirname))
+  }
+}
+
+native!(Basename, BASENAME_META);
+
+impl LyNative for Basename {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let path = args[0].to_obj().to_str();
+
+    match Path::new(&*path).file_name() {
+      Some(name) => Call::Ok(val!(hooks.manage_str(name.to_string_lossy()))),
+      None => Call::Ok(val!(hooks.manage_str(""))),
+    }
+  }
+}
+
+native!(Extension, EXTENSION_META);
+
+impl LyNative for Extension {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let path = args[0].to_obj().to_str();
+
+    match Path::new(&*path).extension() {
+      Some(extension) => Call::Ok(val!(hooks.manage_str(extension.to_string_lossy()))),
+      None => Call::Ok(val!(hooks.manage_str(""))),
+    }
+  }
+}
+
+native!(Normalize, NORMALIZE_META);
+
+impl LyNative for Normalize {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let path = args[0].to_obj().to_str();
+    let normalized = normalize(Path::new(&*path));
+
+    Call::Ok(path_value(hooks, &normalized))
+  }
+}
+
+native!(IsAbsolute, IS_ABSOLUTE_META);
+
+impl LyNative for IsAbsolute {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let path = args[0].to_obj().to_str();
+    Call::Ok(val!(Path::new(&*path).is_absolute()))
+  }
+}
+
+native_with_error!(RelativeTo, RELATIVE_TO_META);
+
+impl LyNative for RelativeTo {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let path = args[0].to_obj().to_str();
+    let base = args[1].to_obj().to_str();
+
+    let path = Path::new(&*path);
+    let base = Path::new(&*base);
+
+    // anchor relative paths to the current directory when the other is absolute
+    let (path, base) = if path.is_absolute() == base.is_absolute() {
+      (path.to_path_buf(), base.to_path_buf())
+    } else {
+      match hooks.as_io().env().current_dir() {
+        Ok(cwd) => (cwd.join(path), cwd.join(base)),
+        Err(err) => return self.call_error(hooks, err.to_string()),
+      }
+    };
+
+    match relative_to(&path, &base) {
+      Some(relative) => Call::Ok(path_value(hooks, &relative)),
+      None => self.call_error(
+        hooks,
+        format!(
+          "Unable to find a path from {} to {}.",
+          base.display(),
+          path.display()
+        ),
+      ),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::MockedContext;
+
+  fn call_path(hooks: &mut Hooks, native: GcObj<Native>, args: &[&str]) -> Value {
+    let args: Vec<Value> = args
+      .iter()
+      .map(|arg| val!(hooks.manage_str(*arg)))
+      .collect();
+
+    native.call(hooks, None, &args).unwrap()
+  }
+
+  mod join {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let join = Join::native(&hooks);
+
+      assert_eq!(join.meta().name, "join");
+      assert_eq!(join.meta().signature.arity, Arity::Variadic(1));
+      assert_eq!(
+        join.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+      assert_eq!(
+        join.meta().signature.parameters[1].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let join = Join::native(&hooks.as_gc());
+
+      let result = call_path(&mut hooks, join, &["a", "b", "c.lay"]);
+      assert_eq!(result.to_obj().to_str(), "a/b/c.lay");
+
+      let result = call_path(&mut hooks, join, &["a", "/b"]);
+      assert_eq!(result.to_obj().to_str(), "/b");
+    }
+  }
+
+  mod dirname {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let dirname = Dirname::native(&hooks);
+
+      assert_eq!(dirname.meta().name, "dirname");
+      assert_eq!(dirname.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        dirname.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let dirname = Dirname::native(&hooks.as_gc());
+
+      let result = call_path(&mut hooks, dirname, &["a/b/c.lay"]);
+      assert_eq!(result.to_obj().to_str(), "a/b");
+
+      let result = call_path(&mut hooks, dirname, &["c.lay"]);
+      assert_eq!(result.to_obj().to_str(), ".");
+
+      let result = call_path(&mut hooks, dirname, &["/"]);
+      assert_eq!(result.to_obj().to_str(), "/");
+    }
+  }
+
+  mod basename {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let basename = Basename::native(&hooks);
+
+      assert_eq!(basename.meta().name, "basename");
+      assert_eq!(basename.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        basename.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let basename = Basename::native(&hooks.as_gc());
+
+      let result = call_path(&mut hooks, basename, &["a/b/c.lay"]);
+      assert_eq!(result.to_obj().to_str(), "c.lay");
+
+      let result = call_path(&mut hooks, basename, &["a/.."]);
+      assert_eq!(result.to_obj().to_str(), "");
+    }
+  }
+
+  mod extension {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let extension = Extension::native(&hooks);
+
+      assert_eq!(extension.meta().name, "extension");
+      assert_eq!(extension.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        extension.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let extension = Extension::native(&hooks.as_gc());
+
+      let result = call_path(&mut hooks, extension, &["a/b.tar.gz"]);
+      assert_eq!(result.to_obj().to_str(), "gz");
+
+      let result = call_path(&mut hooks, extension, &["a/.hidden"]);
+      assert_eq!(result.to_obj().to_str(), "");
+    }
+  }
+
+  mod normalize {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let normalize = Normalize::native(&hooks);
+
+      assert_eq!(normalize.meta().name, "normalize");
+      assert_eq!(normalize.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        normalize.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let normalize = Normalize::native(&hooks.as_gc());
+
+      let result = call_path(&mut hooks, normalize, &["a/./b/../c"]);
+      assert_eq!(result.to_obj().to_str(), "a/c");
+
+      let result = call_path(&mut hooks, normalize, &["../a/../../b"]);
+      assert_eq!(result.to_obj().to_str(), "../../b");
+
+      let result = call_path(&mut hooks, normalize, &["/../a"]);
+      assert_eq!(result.to_obj().to_str(), "/a");
+
+      let result = call_path(&mut hooks, normalize, &["a/.."]);
+      assert_eq!(result.to_obj().to_str(), ".");
+    }
+  }
+
+  mod is_absolute {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let is_absolute = IsAbsolute::native(&hooks);
+
+      assert_eq!(is_absolute.meta().name, "isAbsolute");
+      assert_eq!(is_absolute.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        is_absolute.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let is_absolute = IsAbsolute::native(&hooks.as_gc());
+
+      let result = call_path(&mut hooks, is_absolute, &["/a/b"]);
+      assert!(result.to_bool());
+
+      let result = call_path(&mut hooks, is_absolute, &["a/b"]);
+      assert!(!result.to_bool());
+    }
+  }
+
+  mod relative_to {
+    use super::*;
+    use crate::support::test_error_class;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let relative_to = RelativeTo::native(&hooks, error);
+
+      assert_eq!(relative_to.meta().name, "relativeTo");
+      assert_eq!(relative_to.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(
+        relative_to.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+      assert_eq!(
+        relative_to.meta().signature.parameters[1].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let relative_to = RelativeTo::native(&hooks.as_gc(), error);
+
+      let result = call_path(&mut hooks, relative_to, &["/a/b/c", "/a/d"]);
+      assert_eq!(result.to_obj().to_str(), "../b/c");
+
+      let result = call_path(&mut hooks, relative_to, &["a/b", "a/b"]);
+      assert_eq!(result.to_obj().to_str(), ".");
+
+      let result = call_path(&mut hooks, relative_to, &["a", "a/b/c"]);
+      assert_eq!(result.to_obj().to_str(), "../..");
+    }
+  }
+}
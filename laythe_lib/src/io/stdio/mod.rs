@@ -6,7 +6,9 @@ use laythe_core::{
   hooks::GcHooks,
   managed::Gc,
   module::{Module, Package},
+  object::ObjectKind,
   utils::IdEmitter,
+  value::Value,
 };
 use std::path::PathBuf;
 use stderr::{declare_stderr, define_stderr};
@@ -41,3 +43,13 @@ pub fn stdio_module(
 
   Ok(module)
 }
+
+/// Find the first line that isn't a string, so a bad list is rejected
+/// before anything is written
+fn non_string_line(lines: &[Value]) -> Option<(usize, Value)> {
+  lines
+    .iter()
+    .enumerate()
+    .find(|(_, line)| !line.is_obj_kind(ObjectKind::String))
+    .map(|(index, line)| (index, *line))
+}
@@ -1,13 +1,17 @@
+use super::non_string_line;
 use crate::{
+  create_error,
+  global::TYPE_ERROR_NAME,
   io::{global::IO_ERROR, IO_MODULE_PATH},
-  native_with_error,
+  native, native_with_error,
   support::load_class_from_package,
   support::{
     default_class_inheritance, export_and_insert, load_instance_from_module,
   },
-  StdResult,
+  StdResult, STD,
 };
 use laythe_core::{
+  get,
   hooks::{GcHooks, Hooks},
   managed::Trace,
   module::{Module, Package},
@@ -18,6 +22,7 @@ use laythe_core::{
   value::{Value, VALUE_NIL},
   Call,
 };
+use laythe_env::stdio::StdioBuffer;
 use std::io::Write;
 
 const STDERR_CLASS_NAME: &str = "Stderr";
@@ -29,8 +34,15 @@ const STDERR_WRITE: NativeMetaBuilder = NativeMetaBuilder::method("write", Arity
 const STDERR_WRITELN: NativeMetaBuilder = NativeMetaBuilder::method("writeln", Arity::Fixed(1))
   .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
 
+const STDERR_WRITE_LINES: NativeMetaBuilder =
+  NativeMetaBuilder::method("writeLines", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("lines", ParameterKind::List)]);
+
 const STDERR_FLUSH: NativeMetaBuilder = NativeMetaBuilder::method("flush", Arity::Fixed(0));
 
+const STDERR_CAPTURE: NativeMetaBuilder = NativeMetaBuilder::method("capture", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("fun", ParameterKind::Fun)]);
+
 pub fn declare_stderr(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
   let class = default_class_inheritance(hooks, std, STDERR_CLASS_NAME)?;
   let instance = hooks.manage_obj(Instance::new(class));
@@ -52,6 +64,12 @@ pub fn define_stderr(hooks: &GcHooks, module: &Module, package: &Package) -> Std
     IO_MODULE_PATH,
     IO_ERROR
   )?);
+  let type_error = val!(load_class_from_package(
+    hooks,
+    package,
+    STD,
+    TYPE_ERROR_NAME
+  )?);
 
   class.add_method(
     hooks,
@@ -65,12 +83,24 @@ pub fn define_stderr(hooks: &GcHooks, module: &Module, package: &Package) -> Std
     val!(StderrWriteln::native(hooks, io_error)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(STDERR_WRITE_LINES.name),
+    val!(StderrWriteLines::native(hooks, io_error, type_error)),
+  );
+
   class.add_method(
     hooks,
     hooks.manage_str(STDERR_FLUSH.name),
     val!(StderrFlush::native(hooks, io_error)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(STDERR_CAPTURE.name),
+    val!(StderrCapture::native(hooks)),
+  );
+
   Ok(())
 }
 
@@ -104,6 +134,69 @@ impl LyNative for StderrWriteln {
   }
 }
 
+#[derive(Debug)]
+struct StderrWriteLines {
+  io_error: Value,
+  type_error: Value,
+}
+
+impl StderrWriteLines {
+  fn native(hooks: &GcHooks, io_error: Value, type_error: Value) -> GcObj<Native> {
+    let native = Box::new(Self {
+      io_error,
+      type_error,
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(STDERR_WRITE_LINES.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for StderrWriteLines {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let lines = args[0].to_obj().to_list();
+
+    if let Some((index, line)) = non_string_line(&lines) {
+      return create_error!(
+        self.type_error,
+        hooks,
+        format!(
+          "Expected list of str but found {} at index {}.",
+          line, index
+        )
+      );
+    }
+
+    let io = hooks.as_io();
+    let mut stdio = io.stdio();
+    let stderr = stdio.stderr();
+
+    for line in lines.iter() {
+      if let Err(err) = writeln!(stderr, "{}", &*line.to_obj().to_str()) {
+        return create_error!(self.io_error, hooks, err.to_string());
+      }
+    }
+
+    Call::Ok(VALUE_NIL)
+  }
+}
+
+impl Trace for StderrWriteLines {
+  fn trace(&self) {
+    self.io_error.trace();
+    self.type_error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.io_error.visit_refs(visitor);
+    self.type_error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.io_error.trace_debug(stdio);
+    self.type_error.trace_debug(stdio);
+  }
+}
+
 native_with_error!(StderrFlush, STDERR_FLUSH);
 
 impl LyNative for StderrFlush {
@@ -119,6 +212,24 @@ impl LyNative for StderrFlush {
   }
 }
 
+native!(StderrCapture, STDERR_CAPTURE);
+
+impl LyNative for StderrCapture {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let io = hooks.as_io();
+    io.redirect_stderr(StdioBuffer::default());
+
+    let result = hooks.call(args[0], &[]);
+    let buffer = io
+      .restore_stderr()
+      .expect("Expected stderr redirection to still be active");
+
+    get!(result);
+    let captured = String::from_utf8_lossy(&buffer.take()).into_owned();
+    Call::Ok(val!(hooks.manage_str(captured)))
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -210,4 +321,72 @@ mod test {
       assert_eq!(stderr.unwrap(), "some string\n");
     }
   }
+
+  mod write_lines {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+    use laythe_core::object::List;
+    use laythe_env::stdio::support::StdioTestContainer;
+    use std::{str, sync::Arc};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let stderr_write_lines = StderrWriteLines::native(&hooks, error, error);
+
+      assert_eq!(stderr_write_lines.meta().name, "writeLines");
+      assert_eq!(stderr_write_lines.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        stderr_write_lines.meta().signature.parameters[0].kind,
+        ParameterKind::List
+      );
+    }
+
+    #[test]
+    fn call() {
+      let stdio_container = Arc::new(StdioTestContainer::default());
+
+      let mut context = MockedContext::with_test_stdio(&stdio_container);
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let stderr_write_lines = StderrWriteLines::native(&hooks.as_gc(), error, error);
+
+      let lines = hooks.manage_obj(List::from(vec![
+        val!(hooks.manage_str("line 1")),
+        val!(hooks.manage_str("line 2")),
+      ]));
+      let result = stderr_write_lines.call(&mut hooks, Some(VALUE_NIL), &[val!(lines)]);
+
+      assert!(result.is_ok());
+      assert!(result.unwrap().is_nil());
+
+      let stderr = str::from_utf8(&stdio_container.stderr);
+      assert!(stderr.is_ok());
+      assert_eq!(stderr.unwrap(), "line 1\nline 2\n");
+    }
+  }
+
+  mod capture {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let stderr_capture = StderrCapture::native(&hooks);
+
+      assert_eq!(stderr_capture.meta().name, "capture");
+      assert_eq!(stderr_capture.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        stderr_capture.meta().signature.parameters[0].kind,
+        ParameterKind::Fun
+      );
+    }
+  }
 }
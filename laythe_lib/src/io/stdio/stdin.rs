@@ -1,6 +1,7 @@
 use crate::{
+  create_error,
   io::{global::IO_ERROR, IO_MODULE_PATH},
-  native_with_error,
+  native, native_with_error,
   support::load_class_from_package,
   support::{default_class_inheritance, export_and_insert, load_instance_from_module},
   StdResult,
@@ -10,19 +11,33 @@ use laythe_core::{
   managed::Trace,
   module::{Module, Package},
   object::{Instance, ObjectKind, LyNative, Native, NativeMetaBuilder},
-  signature::Arity,
+  parse::parse_number,
+  signature::{Arity, ParameterBuilder, ParameterKind},
   managed::GcObj,
   val,
-  value::Value,
+  value::{Value, VALUE_FALSE, VALUE_NIL, VALUE_TRUE},
   Call,
 };
-use std::io::Write;
+use std::{
+  cell::Cell,
+  io::{self, Write},
+};
 
 const STDIN_CLASS_NAME: &str = "Stdin";
 const STDIN_INSTANCE_NAME: &str = "stdin";
 
 const STDIN_READ: NativeMetaBuilder = NativeMetaBuilder::method("read", Arity::Fixed(0));
-const STDIN_READ_LINE: NativeMetaBuilder = NativeMetaBuilder::method("readLine", Arity::Fixed(0));
+const STDIN_READ_ALL: NativeMetaBuilder = NativeMetaBuilder::method("readAll", Arity::Fixed(0));
+const STDIN_READ_CHAR: NativeMetaBuilder = NativeMetaBuilder::method("readChar", Arity::Fixed(0));
+const STDIN_IS_TTY: NativeMetaBuilder = NativeMetaBuilder::method("isTty", Arity::Fixed(0));
+
+const STDIN_READ_LINE: NativeMetaBuilder =
+  NativeMetaBuilder::method("readLine", Arity::Default(0, 1))
+    .with_params(&[ParameterBuilder::new("prompt", ParameterKind::String)]);
+
+const STDIN_READ_NUMBER: NativeMetaBuilder =
+  NativeMetaBuilder::method("readNumber", Arity::Default(0, 1))
+    .with_params(&[ParameterBuilder::new("prompt", ParameterKind::String)]);
 
 pub fn declare_stdin(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
   let class = default_class_inheritance(hooks, std, STDIN_CLASS_NAME)?;
@@ -52,15 +67,71 @@ pub fn define_stdin(hooks: &GcHooks, module: &Module, std: &Package) -> StdResul
     val!(StdinRead::native(hooks, io_error)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(STDIN_READ_ALL.name),
+    val!(StdinReadAll::native(hooks, io_error)),
+  );
+
   class.add_method(
     hooks,
     hooks.manage_str(STDIN_READ_LINE.name),
     val!(StdinReadLine::native(hooks, io_error)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(STDIN_READ_NUMBER.name),
+    val!(StdinReadNumber::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(STDIN_READ_CHAR.name),
+    val!(StdinReadChar::native(hooks, io_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(STDIN_IS_TTY.name),
+    val!(StdinIsTty::native(hooks)),
+  );
+
   Ok(())
 }
 
+/// Write the prompt if there is one then read a line without its line
+/// ending. A read that blocks is retried once the host has input, so
+/// retried is set when the prompt was already written by the last attempt
+fn read_line(
+  hooks: &mut Hooks,
+  prompt: Option<&Value>,
+  retried: &Cell<bool>,
+) -> io::Result<String> {
+  let io = hooks.as_io();
+  let mut stdio = io.stdio();
+
+  if let Some(prompt) = prompt {
+    if !retried.replace(false) {
+      let stdout = stdio.stdout();
+      stdout.write_all(prompt.to_obj().to_str().as_bytes())?;
+      stdout.flush()?;
+    }
+  }
+
+  let mut buf = String::new();
+  stdio.read_line(&mut buf)?;
+
+  if buf.ends_with('\n') {
+    buf.pop();
+    if buf.ends_with('\r') {
+      buf.pop();
+    }
+  }
+
+  Ok(buf)
+}
+
 native_with_error!(StdinRead, STDIN_READ);
 
 impl LyNative for StdinRead {
@@ -77,27 +148,148 @@ impl LyNative for StdinRead {
   }
 }
 
-native_with_error!(StdinReadLine, STDIN_READ_LINE);
+native_with_error!(StdinReadAll, STDIN_READ_ALL);
 
-impl LyNative for StdinReadLine {
+impl LyNative for StdinReadAll {
   fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
     let io = hooks.as_io();
-    let stdio = io.stdio();
+    let mut stdio = io.stdio();
+    let stdin = stdio.stdin();
 
     let mut buf = String::new();
+    match stdin.read_to_string(&mut buf) {
+      Ok(_) => Call::Ok(val!(hooks.manage_str(buf))),
+      // the read is retried once the host resumes the vm with input
+      Err(err) if err.kind() == io::ErrorKind::WouldBlock && hooks.await_input() => Call::Block,
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct StdinReadLine {
+  error: Value,
+  retried: Cell<bool>,
+}
+
+impl StdinReadLine {
+  fn native(hooks: &GcHooks, error: Value) -> GcObj<Native> {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    let native = Box::new(Self {
+      error,
+      retried: Cell::new(false),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(STDIN_READ_LINE.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for StdinReadLine {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    match read_line(hooks, args.first(), &self.retried) {
+      Ok(line) => Call::Ok(val!(hooks.manage_str(line))),
+      // the read is retried once the host resumes the vm with input
+      Err(err) if err.kind() == io::ErrorKind::WouldBlock && hooks.await_input() => {
+        self.retried.set(true);
+        Call::Block
+      },
+      Err(err) => create_error!(self.error, hooks, err.to_string()),
+    }
+  }
+}
 
-    match stdio.read_line(&mut buf) {
-      Ok(_) => {
-        if buf.ends_with('\n') {
-          buf.pop();
-        }
-        Call::Ok(val!(hooks.manage_str(buf)))
-      }
+impl Trace for StdinReadLine {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+#[derive(Debug)]
+pub struct StdinReadNumber {
+  error: Value,
+  retried: Cell<bool>,
+}
+
+impl StdinReadNumber {
+  fn native(hooks: &GcHooks, error: Value) -> GcObj<Native> {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    let native = Box::new(Self {
+      error,
+      retried: Cell::new(false),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(STDIN_READ_NUMBER.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for StdinReadNumber {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    match read_line(hooks, args.first(), &self.retried) {
+      // like String.parseNumber a line that isn't a number reads as nil
+      Ok(line) => match parse_number(line.trim()) {
+        Ok(num) => Call::Ok(val!(num)),
+        Err(_) => Call::Ok(VALUE_NIL),
+      },
+      Err(err) if err.kind() == io::ErrorKind::WouldBlock && hooks.await_input() => {
+        self.retried.set(true);
+        Call::Block
+      },
+      Err(err) => create_error!(self.error, hooks, err.to_string()),
+    }
+  }
+}
+
+impl Trace for StdinReadNumber {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+native_with_error!(StdinReadChar, STDIN_READ_CHAR);
+
+impl LyNative for StdinReadChar {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    let io = hooks.as_io();
+    let mut stdio = io.stdio();
+
+    match stdio.read_char() {
+      Ok(Some(c)) => Call::Ok(val!(hooks.manage_str(c.to_string()))),
+      Ok(None) => Call::Ok(VALUE_NIL),
+      // the read is retried once the host resumes the vm with input
+      Err(err) if err.kind() == io::ErrorKind::WouldBlock && hooks.await_input() => Call::Block,
       Err(err) => self.call_error(hooks, err.to_string()),
     }
   }
 }
 
+native!(StdinIsTty, STDIN_IS_TTY);
+
+impl LyNative for StdinIsTty {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    if hooks.as_io().stdio().is_tty() {
+      Call::Ok(VALUE_TRUE)
+    } else {
+      Call::Ok(VALUE_FALSE)
+    }
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -157,7 +349,11 @@ mod test {
       let stdin_readline = StdinReadLine::native(&hooks, error);
 
       assert_eq!(stdin_readline.meta().name, "readLine");
-      assert_eq!(stdin_readline.meta().signature.arity, Arity::Fixed(0));
+      assert_eq!(stdin_readline.meta().signature.arity, Arity::Default(0, 1));
+      assert_eq!(
+        stdin_readline.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
     }
 
     #[test]
@@ -182,4 +378,163 @@ mod test {
       assert_eq!(unwrapped.to_obj().to_str(), "dude");
     }
   }
+
+  mod read_line_prompt {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+    use laythe_core::value::VALUE_NIL;
+    use laythe_env::stdio::support::StdioTestContainer;
+    use std::{str, sync::Arc};
+
+    #[test]
+    fn call() {
+      let stdio_container = Arc::new(StdioTestContainer::with_lines(vec!["ada".to_string()]));
+
+      let mut context = MockedContext::with_test_stdio(&stdio_container);
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let stdin_readline = StdinReadLine::native(&hooks.as_gc(), error);
+
+      let prompt = val!(hooks.manage_str("name? "));
+      let result = stdin_readline.call(&mut hooks, Some(VALUE_NIL), &[prompt]);
+
+      assert_eq!(result.unwrap().to_obj().to_str(), "ada");
+      assert_eq!(str::from_utf8(&stdio_container.stdout), Ok("name? "));
+    }
+  }
+
+  mod read_all {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+    use laythe_core::value::VALUE_NIL;
+    use laythe_env::stdio::support::StdioTestContainer;
+    use std::sync::Arc;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let stdin_read_all = StdinReadAll::native(&hooks, error);
+
+      assert_eq!(stdin_read_all.meta().name, "readAll");
+      assert_eq!(stdin_read_all.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let stdio_container = Arc::new(StdioTestContainer::with_stdin(b"line 1\nline 2"));
+
+      let mut context = MockedContext::with_test_stdio(&stdio_container);
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let stdin_read_all = StdinReadAll::native(&hooks.as_gc(), error);
+
+      let result = stdin_read_all.call(&mut hooks, Some(VALUE_NIL), &[]);
+      assert_eq!(result.unwrap().to_obj().to_str(), "line 1\nline 2");
+    }
+  }
+
+  mod read_number {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+    use laythe_core::value::VALUE_NIL;
+    use laythe_env::stdio::support::StdioTestContainer;
+    use std::sync::Arc;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let stdin_read_number = StdinReadNumber::native(&hooks, error);
+
+      assert_eq!(stdin_read_number.meta().name, "readNumber");
+      assert_eq!(
+        stdin_read_number.meta().signature.arity,
+        Arity::Default(0, 1)
+      );
+    }
+
+    #[test]
+    fn call() {
+      let stdio_container = Arc::new(StdioTestContainer::with_lines(vec![
+        " 12.5 ".to_string(),
+        "twelve".to_string(),
+      ]));
+
+      let mut context = MockedContext::with_test_stdio(&stdio_container);
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let stdin_read_number = StdinReadNumber::native(&hooks.as_gc(), error);
+
+      let result = stdin_read_number.call(&mut hooks, Some(VALUE_NIL), &[]);
+      assert_eq!(result.unwrap(), val!(12.5));
+
+      let result = stdin_read_number.call(&mut hooks, Some(VALUE_NIL), &[]);
+      assert!(result.unwrap().is_nil());
+    }
+  }
+
+  mod read_char {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+    use laythe_core::value::VALUE_NIL;
+    use laythe_env::stdio::support::StdioTestContainer;
+    use std::sync::Arc;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let stdin_read_char = StdinReadChar::native(&hooks, error);
+
+      assert_eq!(stdin_read_char.meta().name, "readChar");
+      assert_eq!(stdin_read_char.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let stdio_container = Arc::new(StdioTestContainer::with_stdin("é!".as_bytes()));
+
+      let mut context = MockedContext::with_test_stdio(&stdio_container);
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+
+      let stdin_read_char = StdinReadChar::native(&hooks.as_gc(), error);
+
+      let result = stdin_read_char.call(&mut hooks, Some(VALUE_NIL), &[]);
+      assert_eq!(result.unwrap().to_obj().to_str(), "é");
+
+      let result = stdin_read_char.call(&mut hooks, Some(VALUE_NIL), &[]);
+      assert_eq!(result.unwrap().to_obj().to_str(), "!");
+
+      let result = stdin_read_char.call(&mut hooks, Some(VALUE_NIL), &[]);
+      assert!(result.unwrap().is_nil());
+    }
+  }
+
+  mod is_tty {
+    use super::*;
+    use crate::support::MockedContext;
+    use laythe_core::value::VALUE_NIL;
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let stdin_is_tty = StdinIsTty::native(&hooks.as_gc());
+
+      let result = stdin_is_tty.call(&mut hooks, Some(VALUE_NIL), &[]);
+      assert_eq!(result.unwrap(), VALUE_FALSE);
+    }
+  }
 }
@@ -1,13 +1,36 @@
 #![deny(clippy::all)]
 mod builtin;
+mod cli;
+mod compress;
+mod config;
+mod crypto;
+mod encoding;
 mod env;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod gc;
 pub mod global;
 mod io;
 mod math;
+mod net;
+mod os;
 mod regexp;
 mod support;
-
+mod sync;
+mod term;
+mod testing;
+mod time;
+mod worker;
+
+use cli::cli_module;
+use compress::compress_module;
+use config::config_module;
+use crypto::add_crypto_package;
+use encoding::encoding_module;
 use env::env_module;
+#[cfg(feature = "ffi")]
+use ffi::ffi_module;
+use gc::gc_module;
 use global::create_std_core;
 use io::add_io_package;
 use laythe_core::{
@@ -17,11 +40,19 @@ use laythe_core::{
   utils::IdEmitter,
 };
 use math::add_math_module;
+use net::add_net_package;
+use os::os_module;
 use regexp::regexp_module;
+use sync::sync_module;
+use term::term_module;
+use testing::add_test_module;
+use time::time_module;
+use worker::worker_module;
 
 pub use builtin::{
   builtin_from_module, BuiltIn, BuiltInDependencies, BuiltInErrors, BuiltInPrimitives,
 };
+pub use testing::{test_results, TestResults};
 
 type StdResult<T> = Result<T, StdError>;
 
@@ -62,6 +93,7 @@ macro_rules! create_error {
       },
       Call::Err(err) => Call::Err(err),
       Call::Exit(exit) => Call::Exit(exit),
+      Call::Block => Call::Block,
     }
   };
 }
@@ -96,6 +128,7 @@ macro_rules! native_with_error {
           },
           Call::Err(err) => Call::Err(err),
           Call::Exit(err) => Call::Exit(err),
+          Call::Block => Call::Block,
         }
       }
     }
@@ -105,7 +138,48 @@ macro_rules! native_with_error {
         self.error.trace();
       }
 
+      fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+        self.error.visit_refs(visitor);
+      }
+
+      fn trace_debug(&self, stdio: &mut dyn Write) {
+        self.error.trace_debug(stdio);
+      }
+    }
+  };
+}
+
+/// Define a native function that accepts or returns bytes, holding
+/// the bytes class and the error it raises
+#[macro_export]
+macro_rules! native_with_bytes {
+  ( $st:ident, $meta:ident ) => {
+    #[derive(Debug)]
+    pub struct $st {
+      bytes: GcObj<Class>,
+      error: Value,
+    }
+
+    impl $st {
+      fn native(hooks: &GcHooks, bytes: GcObj<Class>, error: Value) -> GcObj<Native> {
+        let native = Box::new(Self { bytes, error }) as Box<dyn LyNative>;
+        hooks.manage_obj(Native::new($meta.to_meta(hooks), native))
+      }
+    }
+
+    impl Trace for $st {
+      fn trace(&self) {
+        self.bytes.trace();
+        self.error.trace();
+      }
+
+      fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+        self.bytes.visit_refs(visitor);
+        self.error.visit_refs(visitor);
+      }
+
       fn trace_debug(&self, stdio: &mut dyn Write) {
+        self.bytes.trace_debug(stdio);
         self.error.trace_debug(stdio);
       }
     }
@@ -125,23 +199,91 @@ impl From<ModuleError> for StdError {
   }
 }
 
+impl From<StdError> for ModuleError {
+  fn from(err: StdError) -> Self {
+    match err {
+      StdError::ModuleError(err) => err,
+      StdError::SymbolNotClass | StdError::SymbolNotInstance => ModuleError::InitializationFailed,
+    }
+  }
+}
+
 pub const STD: &str = "std";
 pub const GLOBAL: &str = "global";
 
+/// Create the std package. Only the global module is constructed up
+/// front, the remaining modules are constructed on their first import
 pub fn create_std_lib(hooks: &GcHooks, emitter: &mut IdEmitter) -> StdResult<Gc<Package>> {
-  // let mut std = hooks.manage(Package::new(hooks.manage_str(STD.to_string())));
-
   let mut std = create_std_core(hooks, emitter)?;
 
-  add_math_module(hooks, &mut std, emitter)?;
-  add_io_package(hooks, &mut std, emitter)?;
-  let env = env_module(hooks, &std, emitter)?;
-  let regexp = regexp_module(hooks, &std, emitter)?;
-
-  let mut root_module = std.root_module();
-
-  root_module.insert_module(hooks, env)?;
-  root_module.insert_module(hooks, regexp)?;
+  std.insert_module(hooks, "math", |hooks, std, emitter| {
+    Ok(add_math_module(hooks, std, emitter)?)
+  })?;
+  std.insert_module(hooks, "io", |hooks, std, emitter| {
+    Ok(add_io_package(hooks, std, emitter)?)
+  })?;
+  std.insert_module(hooks, "net", |hooks, std, emitter| {
+    Ok(add_net_package(hooks, std, emitter)?)
+  })?;
+  std.insert_module(hooks, "test", |hooks, std, emitter| {
+    Ok(add_test_module(hooks, std, emitter)?)
+  })?;
+  std.insert_module(hooks, "env", |hooks, std, emitter| {
+    let env = env_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, env)
+  })?;
+  std.insert_module(hooks, "os", |hooks, std, emitter| {
+    let os = os_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, os)
+  })?;
+  std.insert_module(hooks, "time", |hooks, std, emitter| {
+    let time = time_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, time)
+  })?;
+  std.insert_module(hooks, "encoding", |hooks, std, emitter| {
+    let encoding = encoding_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, encoding)
+  })?;
+  std.insert_module(hooks, "compress", |hooks, std, emitter| {
+    let compress = compress_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, compress)
+  })?;
+  std.insert_module(hooks, "config", |hooks, std, emitter| {
+    let config = config_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, config)
+  })?;
+  std.insert_module(hooks, "crypto", |hooks, std, emitter| {
+    Ok(add_crypto_package(hooks, std, emitter)?)
+  })?;
+  std.insert_module(hooks, "gc", |hooks, std, emitter| {
+    let gc = gc_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, gc)
+  })?;
+  std.insert_module(hooks, "regexp", |hooks, std, emitter| {
+    let regexp = regexp_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, regexp)
+  })?;
+  std.insert_module(hooks, "sync", |hooks, std, emitter| {
+    let sync = sync_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, sync)
+  })?;
+  std.insert_module(hooks, "worker", |hooks, std, emitter| {
+    let worker = worker_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, worker)
+  })?;
+  std.insert_module(hooks, "cli", |hooks, std, emitter| {
+    let cli = cli_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, cli)
+  })?;
+  std.insert_module(hooks, "term", |hooks, std, emitter| {
+    let term = term_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, term)
+  })?;
+  #[cfg(feature = "ffi")]
+  std.insert_module(hooks, "ffi", |hooks, std, emitter| {
+    let ffi = ffi_module(hooks, std, emitter)?;
+    std.root_module().insert_module(hooks, ffi)
+  })?;
 
   Ok(std)
 }
@@ -154,7 +296,7 @@ mod test {
   use laythe_core::{
     managed::{GcObj, GcObject},
     match_obj,
-    module::Module,
+    module::{Import, Module},
     object::{Class, ObjectKind},
     signature::Arity,
     to_obj_kind,
@@ -231,6 +373,64 @@ mod test {
       .for_each(|(_name, module)| class_setup_inner(*module, class_class))
   }
 
+  const LAZY_MODULES: [&str; 16] = [
+    "std/math",
+    "std/io",
+    "std/net",
+    "std/test",
+    "std/env",
+    "std/os",
+    "std/time",
+    "std/encoding",
+    "std/compress",
+    "std/crypto",
+    "std/config",
+    "std/gc",
+    "std/regexp",
+    "std/worker",
+    "std/cli",
+    "std/term",
+  ];
+
+  fn initialize_all(hooks: &GcHooks, emitter: &mut IdEmitter, std_lib: &mut Package) {
+    for path in LAZY_MODULES {
+      let import = Import::from_str(hooks, path).unwrap();
+      std_lib.initialize(hooks, emitter, import).unwrap();
+    }
+  }
+
+  #[test]
+  fn lazy() {
+    let mut context = MockedContext::default();
+    let hooks = GcHooks::new(&mut context);
+    let mut emitter = IdEmitter::default();
+
+    let mut std_lib = create_std_lib(&hooks, &mut emitter).unwrap();
+    let global_count = emitter.id_count();
+
+    for path in LAZY_MODULES {
+      let import = Import::from_str(&hooks, path).unwrap();
+      assert_eq!(
+        std_lib.import(&hooks, import).map(|_| ()),
+        Err(ModuleError::ModuleDoesNotExist)
+      );
+    }
+
+    // gc raises io errors so io is constructed with it
+    let gc = Import::from_str(&hooks, "std/gc").unwrap();
+    std_lib.initialize(&hooks, &mut emitter, gc).unwrap();
+    assert!(std_lib.import(&hooks, gc).is_ok());
+
+    let io = Import::from_str(&hooks, "std/io").unwrap();
+    assert!(std_lib.import(&hooks, io).is_ok());
+
+    let io_count = emitter.id_count();
+    assert!(io_count > global_count);
+
+    std_lib.initialize(&hooks, &mut emitter, io).unwrap();
+    assert_eq!(emitter.id_count(), io_count);
+  }
+
   #[test]
   fn new() {
     let mut context = MockedContext::default();
@@ -240,7 +440,8 @@ mod test {
     let std_lib = create_std_lib(&hooks, &mut emitter);
     assert!(std_lib.is_ok());
 
-    let std_lib = std_lib.unwrap();
+    let mut std_lib = std_lib.unwrap();
+    initialize_all(&hooks, &mut emitter, &mut std_lib);
     let root_module = std_lib.root_module();
     new_inner(root_module);
   }
@@ -254,7 +455,8 @@ mod test {
     let std_lib = create_std_lib(&hooks, &mut emitter);
     assert!(std_lib.is_ok());
 
-    let std_lib = std_lib.unwrap();
+    let mut std_lib = std_lib.unwrap();
+    initialize_all(&hooks, &mut emitter, &mut std_lib);
     let root_module = std_lib.root_module();
 
     let class_class = load_class_from_module(&hooks, &root_module, CLASS_CLASS_NAME).unwrap();
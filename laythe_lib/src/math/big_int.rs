@@ -0,0 +1,569 @@
+use crate::{
+  create_error, native, native_with_error,
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, Instance, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, Signed, ToPrimitive, Zero};
+use std::{any::Any, cmp::Ordering, io::Write};
+
+use crate::global::VALUE_ERROR_NAME;
+
+pub const BIG_INT_CLASS_NAME: &str = "BigInt";
+const BIG_INT_FIELD_VALUE: &str = "value";
+
+const BIG_INT_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("value", ParameterKind::Any),
+    ParameterBuilder::new("radix", ParameterKind::Number),
+  ]);
+
+const BIG_INT_PARSE: NativeMetaBuilder = NativeMetaBuilder::method("parse", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("string", ParameterKind::String),
+    ParameterBuilder::new("radix", ParameterKind::Number),
+  ]);
+
+const BIG_INT_ADD: NativeMetaBuilder = NativeMetaBuilder::method("add", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Any)]);
+
+const BIG_INT_SUB: NativeMetaBuilder = NativeMetaBuilder::method("sub", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Any)]);
+
+const BIG_INT_MUL: NativeMetaBuilder = NativeMetaBuilder::method("mul", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Any)]);
+
+const BIG_INT_DIV: NativeMetaBuilder = NativeMetaBuilder::method("div", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Any)]);
+
+const BIG_INT_REM: NativeMetaBuilder = NativeMetaBuilder::method("rem", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Any)]);
+
+const BIG_INT_DIV_REM: NativeMetaBuilder = NativeMetaBuilder::method("divRem", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Any)]);
+
+const BIG_INT_POW: NativeMetaBuilder = NativeMetaBuilder::method("pow", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("exponent", ParameterKind::Number)]);
+
+const BIG_INT_CMP: NativeMetaBuilder = NativeMetaBuilder::method("cmp", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Any)]);
+
+const BIG_INT_EQ: NativeMetaBuilder = NativeMetaBuilder::method("eq", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Any)]);
+
+const BIG_INT_NEG: NativeMetaBuilder = NativeMetaBuilder::method("neg", Arity::Fixed(0));
+const BIG_INT_ABS: NativeMetaBuilder = NativeMetaBuilder::method("abs", Arity::Fixed(0));
+const BIG_INT_TO_NUMBER: NativeMetaBuilder = NativeMetaBuilder::method("toNumber", Arity::Fixed(0));
+
+const BIG_INT_TO_STRING: NativeMetaBuilder =
+  NativeMetaBuilder::method("toString", Arity::Default(0, 1))
+    .with_params(&[ParameterBuilder::new("radix", ParameterKind::Number)]);
+
+const BIG_INT_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
+
+const BIG_INT_DATA: NativeMetaBuilder = NativeMetaBuilder::fun("value", Arity::Fixed(0));
+
+pub fn declare_big_int_class(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let class = default_class_inheritance(hooks, std, BIG_INT_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_big_int_class(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, BIG_INT_CLASS_NAME)?;
+  let value_error = val!(load_class_from_package(hooks, std, STD, VALUE_ERROR_NAME)?);
+
+  class.add_field(hooks, hooks.manage_str(BIG_INT_FIELD_VALUE));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_INIT.name),
+    val!(BigIntInit::native(hooks, value_error)),
+  );
+
+  let binary_ops: [(&NativeMetaBuilder, BinaryOp); 5] = [
+    (&BIG_INT_ADD, |a, b| Some(a + b)),
+    (&BIG_INT_SUB, |a, b| Some(a - b)),
+    (&BIG_INT_MUL, |a, b| Some(a * b)),
+    (&BIG_INT_DIV, |a, b| a.checked_div(b)),
+    (&BIG_INT_REM, checked_rem),
+  ];
+
+  for (meta, op) in &binary_ops {
+    class.add_method(
+      hooks,
+      hooks.manage_str(meta.name),
+      val!(BigIntBinary::native(hooks, meta, *op, value_error)),
+    );
+  }
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_DIV_REM.name),
+    val!(BigIntDivRem::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_POW.name),
+    val!(BigIntPow::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_CMP.name),
+    val!(BigIntCmp::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_EQ.name),
+    val!(BigIntEq::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_NEG.name),
+    val!(BigIntNeg::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_ABS.name),
+    val!(BigIntAbs::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_TO_NUMBER.name),
+    val!(BigIntToNumber::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_TO_STRING.name),
+    val!(BigIntToString::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_STR.name),
+    val!(BigIntStr::native(hooks)),
+  );
+
+  class.meta_class().expect("Meta class not set.").add_method(
+    hooks,
+    hooks.manage_str(BIG_INT_PARSE.name),
+    val!(BigIntParse::native(hooks, value_error)),
+  );
+
+  Ok(())
+}
+
+/// An arithmetic operation between two big integers, returning None if
+/// the operation is undefined for its operands
+type BinaryOp = fn(&BigInt, &BigInt) -> Option<BigInt>;
+
+/// The remainder of a truncated division, None if dividing by zero
+fn checked_rem(a: &BigInt, b: &BigInt) -> Option<BigInt> {
+  if b.is_zero() {
+    None
+  } else {
+    Some(a % b)
+  }
+}
+
+/// Create a new instance of the BigInt class holding the provided integer
+fn create_big_int(hooks: &GcHooks, class: GcObj<Class>, value: BigInt) -> Value {
+  let data = BigIntData::native(hooks, value);
+  hooks.push_root(data);
+
+  let mut instance = hooks.manage_obj(Instance::new(class));
+  instance[0] = val!(data);
+
+  hooks.pop_roots(1);
+  val!(instance)
+}
+
+/// The integer held by a value if it is an instance of BigInt
+fn big_int_data(value: Value) -> Option<BigInt> {
+  if !value.is_obj_kind(ObjectKind::Instance) {
+    return None;
+  }
+
+  let instance = value.to_obj().to_instance();
+  let data = *instance.fields().first()?;
+  if !data.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  data
+    .to_obj()
+    .to_native()
+    .downcast_ref::<BigIntData>()
+    .map(|data| data.value.clone())
+}
+
+/// Convert an operand into a big integer. Numbers are only accepted if
+/// they are integers so no precision is silently dropped
+fn to_big_int(value: Value) -> Option<BigInt> {
+  if value.is_num() {
+    let num = value.to_num();
+    if num.fract() == 0.0 {
+      BigInt::from_f64(num)
+    } else {
+      None
+    }
+  } else {
+    big_int_data(value)
+  }
+}
+
+/// Read an optional radix argument, defaulting to base 10
+fn radix_arg(args: &[Value], index: usize) -> Option<u32> {
+  match args.get(index) {
+    Some(radix) => {
+      let radix = radix.to_num();
+      if radix.fract() == 0.0 && (2.0..=36.0).contains(&radix) {
+        Some(radix as u32)
+      } else {
+        None
+      }
+    },
+    None => Some(10),
+  }
+}
+
+/// Parse a big integer from a string with an optional leading sign
+fn parse_big_int(string: &str, radix: u32) -> Option<BigInt> {
+  BigInt::parse_bytes(string.as_bytes(), radix)
+}
+
+/// The integer backing a BigInt instance
+#[derive(Debug)]
+pub struct BigIntData {
+  value: BigInt,
+}
+
+impl BigIntData {
+  fn native(hooks: &GcHooks, value: BigInt) -> GcObj<Native> {
+    let native = Box::new(Self { value }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(BIG_INT_DATA.to_meta(hooks), native))
+  }
+}
+
+impl Trace for BigIntData {}
+
+impl LyNative for BigIntData {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(val!(hooks.manage_str(self.value.to_string())))
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+macro_rules! get_big_int {
+  ( $self:ident, $this:ident, $hooks:ident ) => {{
+    match big_int_data($this.unwrap()) {
+      Some(value) => value,
+      None => return $self.call_error($hooks, "BigInt instance is missing its value."),
+    }
+  }};
+}
+
+macro_rules! get_operand {
+  ( $self:ident, $value:expr, $hooks:ident ) => {{
+    match to_big_int($value) {
+      Some(value) => value,
+      None => {
+        return $self.call_error(
+          $hooks,
+          "BigInt operand must be a BigInt or an integer Number.",
+        )
+      },
+    }
+  }};
+}
+
+native_with_error!(BigIntInit, BIG_INT_INIT);
+
+impl LyNative for BigIntInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let value = if args[0].is_obj_kind(ObjectKind::String) {
+      let radix = match radix_arg(args, 1) {
+        Some(radix) => radix,
+        None => return self.call_error(hooks, "BigInt requires a radix between 2 and 36."),
+      };
+
+      let string = args[0].to_obj().to_str();
+      match parse_big_int(&string, radix) {
+        Some(value) => value,
+        None => return self.call_error(hooks, format!("Unable to parse BigInt from {}.", string)),
+      }
+    } else {
+      get_operand!(self, args[0], hooks)
+    };
+
+    this[0] = val!(BigIntData::native(&hooks.as_gc(), value));
+    Call::Ok(val!(this))
+  }
+}
+
+native_with_error!(BigIntParse, BIG_INT_PARSE);
+
+impl LyNative for BigIntParse {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let class = this.unwrap().to_obj().to_class();
+    let radix = match radix_arg(args, 1) {
+      Some(radix) => radix,
+      None => return self.call_error(hooks, "parse requires a radix between 2 and 36."),
+    };
+
+    let string = args[0].to_obj().to_str();
+    match parse_big_int(&string, radix) {
+      Some(value) => Call::Ok(create_big_int(&hooks.as_gc(), class, value)),
+      None => self.call_error(hooks, format!("Unable to parse BigInt from {}.", string)),
+    }
+  }
+}
+
+/// Apply an arithmetic operation to a BigInt and another operand
+#[derive(Debug)]
+pub struct BigIntBinary {
+  op: BinaryOp,
+  error: Value,
+}
+
+impl BigIntBinary {
+  fn native(
+    hooks: &GcHooks,
+    meta: &NativeMetaBuilder,
+    op: BinaryOp,
+    error: Value,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self { op, error }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+
+  fn call_error<T: Into<String> + AsRef<str>>(&self, hooks: &mut Hooks, message: T) -> Call {
+    create_error!(self.error, hooks, message)
+  }
+}
+
+impl Trace for BigIntBinary {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for BigIntBinary {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let value = get_big_int!(self, this, hooks);
+    let other = get_operand!(self, args[0], hooks);
+
+    match (self.op)(&value, &other) {
+      Some(result) => {
+        let class = this.unwrap().to_obj().to_instance().class();
+        Call::Ok(create_big_int(&hooks.as_gc(), class, result))
+      },
+      None => self.call_error(hooks, "Attempted to divide by zero."),
+    }
+  }
+}
+
+// matching div and rem the quotient truncates toward zero
+native_with_error!(BigIntDivRem, BIG_INT_DIV_REM);
+
+impl LyNative for BigIntDivRem {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let value = get_big_int!(self, this, hooks);
+    let other = get_operand!(self, args[0], hooks);
+
+    if other.is_zero() {
+      return self.call_error(hooks, "Attempted to divide by zero.");
+    }
+
+    let hooks = hooks.as_gc();
+    let class = this.unwrap().to_obj().to_instance().class();
+
+    let quotient = create_big_int(&hooks, class, &value / &other);
+    hooks.push_root(quotient);
+    let remainder = create_big_int(&hooks, class, &value % &other);
+    hooks.pop_roots(1);
+
+    Call::tuple(&hooks, &[quotient, remainder])
+  }
+}
+
+native_with_error!(BigIntPow, BIG_INT_POW);
+
+impl LyNative for BigIntPow {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let value = get_big_int!(self, this, hooks);
+    let exponent = args[0].to_num();
+
+    if exponent.fract() != 0.0 || !(0.0..=f64::from(u32::MAX)).contains(&exponent) {
+      return self.call_error(hooks, "pow requires a non negative integer exponent.");
+    }
+
+    let class = this.unwrap().to_obj().to_instance().class();
+    Call::Ok(create_big_int(
+      &hooks.as_gc(),
+      class,
+      value.pow(exponent as u32),
+    ))
+  }
+}
+
+native_with_error!(BigIntCmp, BIG_INT_CMP);
+
+impl LyNative for BigIntCmp {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let value = get_big_int!(self, this, hooks);
+    let other = get_operand!(self, args[0], hooks);
+
+    Call::Ok(val!(match value.cmp(&other) {
+      Ordering::Less => -1.0,
+      Ordering::Equal => 0.0,
+      Ordering::Greater => 1.0,
+    }))
+  }
+}
+
+native!(BigIntEq, BIG_INT_EQ);
+
+impl LyNative for BigIntEq {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let value = big_int_data(this.unwrap());
+    let other = to_big_int(args[0]);
+
+    Call::Ok(val!(value.is_some() && value == other))
+  }
+}
+
+native_with_error!(BigIntNeg, BIG_INT_NEG);
+
+impl LyNative for BigIntNeg {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let value = get_big_int!(self, this, hooks);
+    let class = this.unwrap().to_obj().to_instance().class();
+    Call::Ok(create_big_int(&hooks.as_gc(), class, -value))
+  }
+}
+
+native_with_error!(BigIntAbs, BIG_INT_ABS);
+
+impl LyNative for BigIntAbs {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let value = get_big_int!(self, this, hooks);
+    let class = this.unwrap().to_obj().to_instance().class();
+    Call::Ok(create_big_int(&hooks.as_gc(), class, value.abs()))
+  }
+}
+
+native_with_error!(BigIntToNumber, BIG_INT_TO_NUMBER);
+
+impl LyNative for BigIntToNumber {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let value = get_big_int!(self, this, hooks);
+    Call::Ok(val!(value.to_f64().unwrap_or(f64::NAN)))
+  }
+}
+
+native_with_error!(BigIntToString, BIG_INT_TO_STRING);
+
+impl LyNative for BigIntToString {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let value = get_big_int!(self, this, hooks);
+
+    match radix_arg(args, 0) {
+      Some(radix) => Call::Ok(val!(hooks.manage_str(value.to_str_radix(radix)))),
+      None => self.call_error(hooks, "toString requires a radix between 2 and 36."),
+    }
+  }
+}
+
+native!(BigIntStr, BIG_INT_STR);
+
+impl LyNative for BigIntStr {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let string = match big_int_data(this.unwrap()) {
+      Some(value) => value.to_string(),
+      None => String::from("<BigInt>"),
+    };
+
+    Call::Ok(val!(hooks.manage_str(string)))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_class, MockedContext};
+
+  #[test]
+  fn create_and_read() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let mut class = test_class(&hooks, BIG_INT_CLASS_NAME);
+    class.add_field(&hooks, hooks.manage_str(BIG_INT_FIELD_VALUE));
+
+    let value = parse_big_int("-123456789012345678901234567890", 10).unwrap();
+    let big_int = create_big_int(&hooks, class, value.clone());
+
+    assert_eq!(big_int_data(big_int), Some(value));
+    assert_eq!(big_int_data(val!(10.0)), None);
+  }
+
+  #[test]
+  fn operands() {
+    assert_eq!(
+      to_big_int(val!(9007199254740992.0)),
+      BigInt::from_u64(1 << 53)
+    );
+    assert_eq!(to_big_int(val!(-3.0)), BigInt::from_i64(-3));
+    assert_eq!(to_big_int(val!(1.5)), None);
+    assert_eq!(to_big_int(val!(f64::INFINITY)), None);
+    assert_eq!(to_big_int(val!(f64::NAN)), None);
+  }
+
+  #[test]
+  fn parse() {
+    assert_eq!(parse_big_int("ff", 16), BigInt::from_u32(255));
+    assert_eq!(parse_big_int("+42", 10), BigInt::from_u32(42));
+    assert_eq!(parse_big_int("1.5", 10), None);
+    assert_eq!(parse_big_int("", 10), None);
+  }
+
+  #[test]
+  fn radix() {
+    assert_eq!(radix_arg(&[], 0), Some(10));
+    assert_eq!(radix_arg(&[val!(16.0)], 0), Some(16));
+    assert_eq!(radix_arg(&[val!(37.0)], 0), None);
+    assert_eq!(radix_arg(&[val!(2.5)], 0), None);
+  }
+}
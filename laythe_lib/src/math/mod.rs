@@ -1,5 +1,7 @@
+mod big_int;
 mod utils;
 
+use big_int::{declare_big_int_class, define_big_int_class};
 use laythe_core::{
   hooks::GcHooks,
   module::{Module, Package},
@@ -30,5 +32,8 @@ pub fn add_math_module(
   root.insert_module(hooks, module)?;
 
   declare_math_module(hooks, &mut module)?;
-  define_math_module(hooks, &mut module)
+  declare_big_int_class(hooks, &mut module, std)?;
+
+  define_math_module(hooks, &mut module)?;
+  define_big_int_class(hooks, &module, std)
 }
@@ -0,0 +1,528 @@
+use super::NET_ERROR;
+use crate::{
+  create_error,
+  global::VALUE_ERROR_NAME,
+  native, native_with_error,
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, Instance, List, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use std::{
+  io::Write,
+  net::{IpAddr, SocketAddr},
+};
+
+/// A property of an ip address exposed as a method of IpAddr
+type IpCheck = fn(IpAddr) -> bool;
+
+pub const IP_ADDR_CLASS_NAME: &str = "IpAddr";
+pub const SOCKET_ADDR_CLASS_NAME: &str = "SocketAddr";
+
+const IP_ADDR_FIELD_ADDRESS: &str = "address";
+const IP_ADDR_FIELD_VERSION: &str = "version";
+
+const SOCKET_ADDR_FIELD_IP: &str = "ip";
+const SOCKET_ADDR_FIELD_PORT: &str = "port";
+
+const RESOLVE_META: NativeMetaBuilder = NativeMetaBuilder::fun("resolve", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("host", ParameterKind::String)]);
+
+const IP_ADDR_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("address", ParameterKind::String)]);
+
+const IP_ADDR_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
+
+const IP_ADDR_IS_LOOPBACK: NativeMetaBuilder =
+  NativeMetaBuilder::method("isLoopback", Arity::Fixed(0));
+
+const IP_ADDR_IS_PRIVATE: NativeMetaBuilder =
+  NativeMetaBuilder::method("isPrivate", Arity::Fixed(0));
+
+const IP_ADDR_IS_UNSPECIFIED: NativeMetaBuilder =
+  NativeMetaBuilder::method("isUnspecified", Arity::Fixed(0));
+
+const SOCKET_ADDR_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("address", ParameterKind::Any),
+    ParameterBuilder::new("port", ParameterKind::Number),
+  ]);
+
+const SOCKET_ADDR_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
+
+const IS_VALID: NativeMetaBuilder = NativeMetaBuilder::fun("isValid", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("address", ParameterKind::String)]);
+
+pub fn declare_address_classes(
+  hooks: &GcHooks,
+  module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let ip_addr = default_class_inheritance(hooks, std, IP_ADDR_CLASS_NAME)?;
+  export_and_insert(hooks, module, ip_addr.name(), val!(ip_addr))?;
+
+  let socket_addr = default_class_inheritance(hooks, std, SOCKET_ADDR_CLASS_NAME)?;
+  export_and_insert(hooks, module, socket_addr.name(), val!(socket_addr))
+}
+
+pub fn declare_address_funs(hooks: &GcHooks, module: &mut Module) -> StdResult<()> {
+  let net_error = val!(load_class_from_module(hooks, module, NET_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(RESOLVE_META.name),
+    val!(Resolve::native(hooks, net_error)),
+  )
+}
+
+pub fn define_address_classes(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let mut ip_addr = load_class_from_module(hooks, module, IP_ADDR_CLASS_NAME)?;
+  let mut socket_addr = load_class_from_module(hooks, module, SOCKET_ADDR_CLASS_NAME)?;
+  let value_error = val!(load_class_from_package(hooks, std, STD, VALUE_ERROR_NAME)?);
+
+  ip_addr.add_field(hooks, hooks.manage_str(IP_ADDR_FIELD_ADDRESS));
+  ip_addr.add_field(hooks, hooks.manage_str(IP_ADDR_FIELD_VERSION));
+
+  ip_addr.add_method(
+    hooks,
+    hooks.manage_str(IP_ADDR_INIT.name),
+    val!(IpAddrInit::native(hooks, value_error)),
+  );
+
+  ip_addr.add_method(
+    hooks,
+    hooks.manage_str(IP_ADDR_STR.name),
+    val!(IpAddrStr::native(hooks, value_error)),
+  );
+
+  let checks: [(&NativeMetaBuilder, IpCheck); 3] = [
+    (&IP_ADDR_IS_LOOPBACK, |ip| ip.is_loopback()),
+    (&IP_ADDR_IS_PRIVATE, is_private),
+    (&IP_ADDR_IS_UNSPECIFIED, |ip| ip.is_unspecified()),
+  ];
+
+  for (meta, check) in checks.iter() {
+    ip_addr.add_method(
+      hooks,
+      hooks.manage_str(meta.name),
+      val!(IpAddrCheck::native(hooks, meta, *check, value_error)),
+    );
+  }
+
+  ip_addr
+    .meta_class()
+    .expect("Meta class not set.")
+    .add_method(
+      hooks,
+      hooks.manage_str(IS_VALID.name),
+      val!(IpAddrIsValid::native(hooks)),
+    );
+
+  socket_addr.add_field(hooks, hooks.manage_str(SOCKET_ADDR_FIELD_IP));
+  socket_addr.add_field(hooks, hooks.manage_str(SOCKET_ADDR_FIELD_PORT));
+
+  socket_addr.add_method(
+    hooks,
+    hooks.manage_str(SOCKET_ADDR_INIT.name),
+    val!(SocketAddrInit::native(hooks, ip_addr, value_error)),
+  );
+
+  socket_addr.add_method(
+    hooks,
+    hooks.manage_str(SOCKET_ADDR_STR.name),
+    val!(SocketAddrStr::native(hooks, value_error)),
+  );
+
+  socket_addr
+    .meta_class()
+    .expect("Meta class not set.")
+    .add_method(
+      hooks,
+      hooks.manage_str(IS_VALID.name),
+      val!(SocketAddrIsValid::native(hooks)),
+    );
+
+  Ok(())
+}
+
+/// Private ipv4 ranges and ipv6 unique local addresses
+fn is_private(ip: IpAddr) -> bool {
+  match ip {
+    IpAddr::V4(ip) => ip.is_private(),
+    IpAddr::V6(ip) => (ip.segments()[0] & 0xfe00) == 0xfc00,
+  }
+}
+
+/// Create an IpAddr instance
+pub(crate) fn create_ip_addr(hooks: &GcHooks, class: GcObj<Class>, ip: IpAddr) -> GcObj<Instance> {
+  let instance = hooks.manage_obj(Instance::new(class));
+  hooks.push_root(instance);
+
+  set_ip_fields(hooks, instance, ip);
+  hooks.pop_roots(1);
+
+  instance
+}
+
+/// Create a SocketAddr instance along with the IpAddr it holds
+pub(crate) fn create_socket_addr(
+  hooks: &GcHooks,
+  ip_class: GcObj<Class>,
+  class: GcObj<Class>,
+  address: SocketAddr,
+) -> GcObj<Instance> {
+  let mut instance = hooks.manage_obj(Instance::new(class));
+  hooks.push_root(instance);
+
+  instance[0] = val!(create_ip_addr(hooks, ip_class, address.ip()));
+  instance[1] = val!(address.port() as f64);
+  hooks.pop_roots(1);
+
+  instance
+}
+
+/// Read a socket address from a SocketAddr instance, returning None for
+/// any other value
+pub(crate) fn read_socket_addr(
+  value: Value,
+  class: GcObj<Class>,
+) -> Option<Result<SocketAddr, String>> {
+  if !value.is_obj_kind(ObjectKind::Instance) {
+    return None;
+  }
+
+  let instance = value.to_obj().to_instance();
+  if instance.class() != class && !instance.class().is_subclass(class) {
+    return None;
+  }
+
+  Some(socket_addr_fields(instance))
+}
+
+fn set_ip_fields(hooks: &GcHooks, mut instance: GcObj<Instance>, ip: IpAddr) {
+  instance[0] = val!(hooks.manage_str(ip.to_string()));
+  instance[1] = val!(if ip.is_ipv4() { 4.0 } else { 6.0 });
+}
+
+fn parse_ip(address: &str) -> Result<IpAddr, String> {
+  address
+    .parse()
+    .map_err(|_| format!("{} is not a valid ip address.", address))
+}
+
+fn parse_socket_addr(address: &str) -> Result<SocketAddr, String> {
+  address.parse().map_err(|_| {
+    format!(
+      "{} is not a valid socket address such as 127.0.0.1:8080 or [::1]:8080.",
+      address
+    )
+  })
+}
+
+/// Read the ip address held by an IpAddr instance, which scripts may
+/// have changed since it was created
+fn ip_addr_field(instance: GcObj<Instance>) -> Result<IpAddr, String> {
+  let address = instance[0];
+
+  if address.is_obj_kind(ObjectKind::String) {
+    parse_ip(&address.to_obj().to_str())
+  } else {
+    Err("IpAddr address must be a string.".to_string())
+  }
+}
+
+fn socket_addr_fields(instance: GcObj<Instance>) -> Result<SocketAddr, String> {
+  let ip = instance[0];
+  let ip = if ip.is_obj_kind(ObjectKind::Instance) {
+    ip_addr_field(ip.to_obj().to_instance())?
+  } else {
+    return Err("SocketAddr ip must be an IpAddr.".to_string());
+  };
+
+  Ok(SocketAddr::new(ip, port_number(instance[1])?))
+}
+
+fn port_number(port: Value) -> Result<u16, String> {
+  if port.is_num() {
+    let port = port.to_num();
+    if port.fract() == 0.0 && (0.0..=65535.0).contains(&port) {
+      return Ok(port as u16);
+    }
+  }
+
+  Err("Port must be an integer between 0 and 65535.".to_string())
+}
+
+native_with_error!(Resolve, RESOLVE_META);
+
+impl LyNative for Resolve {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let host = args[0].to_obj().to_str();
+
+    let addresses = match hooks.as_io().net().resolve(&host) {
+      Ok(addresses) => addresses,
+      Err(err) => return self.call_error(hooks, format!("Unable to resolve {}: {}", &*host, err)),
+    };
+
+    let mut list = hooks.manage_obj(List::with_capacity(addresses.len()));
+    hooks.push_root(list);
+
+    for address in addresses {
+      let address = val!(hooks.manage_str(address));
+      hooks.grow(&mut list, |list| list.push(address));
+    }
+
+    hooks.pop_roots(1);
+    Call::Ok(val!(list))
+  }
+}
+
+native_with_error!(IpAddrInit, IP_ADDR_INIT);
+
+impl LyNative for IpAddrInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match parse_ip(&args[0].to_obj().to_str()) {
+      Ok(ip) => {
+        set_ip_fields(&hooks.as_gc(), this, ip);
+        Call::Ok(val!(this))
+      },
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native_with_error!(IpAddrStr, IP_ADDR_STR);
+
+impl LyNative for IpAddrStr {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    match ip_addr_field(this.unwrap().to_obj().to_instance()) {
+      Ok(ip) => Call::Ok(val!(hooks.manage_str(ip.to_string()))),
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+/// Check a property of an IpAddr such as whether it is a loopback address
+#[derive(Debug)]
+pub struct IpAddrCheck {
+  check: IpCheck,
+  error: Value,
+}
+
+impl IpAddrCheck {
+  fn native(
+    hooks: &GcHooks,
+    meta: &NativeMetaBuilder,
+    check: IpCheck,
+    error: Value,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self { check, error }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+}
+
+impl Trace for IpAddrCheck {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for IpAddrCheck {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    match ip_addr_field(this.unwrap().to_obj().to_instance()) {
+      Ok(ip) => Call::Ok(val!((self.check)(ip))),
+      Err(err) => create_error!(self.error, hooks, err),
+    }
+  }
+}
+
+native!(IpAddrIsValid, IS_VALID);
+
+impl LyNative for IpAddrIsValid {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    Call::Ok(val!(parse_ip(&args[0].to_obj().to_str()).is_ok()))
+  }
+}
+
+/// Initialize a SocketAddr from a string such as `127.0.0.1:8080` or
+/// from an ip address and a port
+#[derive(Debug)]
+pub struct SocketAddrInit {
+  ip_class: GcObj<Class>,
+  error: Value,
+}
+
+impl SocketAddrInit {
+  fn native(hooks: &GcHooks, ip_class: GcObj<Class>, error: Value) -> GcObj<Native> {
+    let native = Box::new(Self { ip_class, error }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(SOCKET_ADDR_INIT.to_meta(hooks), native))
+  }
+
+  fn address(&self, args: &[Value]) -> Result<SocketAddr, String> {
+    let address = args[0];
+
+    let port = match args.get(1) {
+      Some(port) => port_number(*port)?,
+      None if address.is_obj_kind(ObjectKind::String) => {
+        return parse_socket_addr(&address.to_obj().to_str())
+      },
+      None => return Err("SocketAddr expects a string or an ip address and port.".to_string()),
+    };
+
+    let ip = if address.is_obj_kind(ObjectKind::String) {
+      parse_ip(&address.to_obj().to_str())?
+    } else if address.is_obj_kind(ObjectKind::Instance)
+      && address.to_obj().to_instance().class() == self.ip_class
+    {
+      ip_addr_field(address.to_obj().to_instance())?
+    } else {
+      return Err("SocketAddr ip must be a string or an IpAddr.".to_string());
+    };
+
+    Ok(SocketAddr::new(ip, port))
+  }
+}
+
+impl Trace for SocketAddrInit {
+  fn trace(&self) {
+    self.ip_class.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.ip_class.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.ip_class.trace_debug(stdio);
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for SocketAddrInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    match self.address(args) {
+      Ok(address) => {
+        this[0] = val!(create_ip_addr(&hooks.as_gc(), self.ip_class, address.ip()));
+        this[1] = val!(address.port() as f64);
+        Call::Ok(val!(this))
+      },
+      Err(err) => create_error!(self.error, hooks, err),
+    }
+  }
+}
+
+native_with_error!(SocketAddrStr, SOCKET_ADDR_STR);
+
+impl LyNative for SocketAddrStr {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    match socket_addr_fields(this.unwrap().to_obj().to_instance()) {
+      Ok(address) => Call::Ok(val!(hooks.manage_str(address.to_string()))),
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native!(SocketAddrIsValid, IS_VALID);
+
+impl LyNative for SocketAddrIsValid {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    Call::Ok(val!(parse_socket_addr(&args[0].to_obj().to_str()).is_ok()))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+  use laythe_core::value::VALUE_NIL;
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let error = val!(test_error_class(&hooks));
+
+    let resolve = Resolve::native(&hooks, error);
+    assert_eq!(resolve.meta().name, "resolve");
+    assert_eq!(resolve.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(
+      resolve.meta().signature.parameters[0].kind,
+      ParameterKind::String
+    );
+
+    let is_loopback =
+      IpAddrCheck::native(&hooks, &IP_ADDR_IS_LOOPBACK, |ip| ip.is_loopback(), error);
+    assert_eq!(is_loopback.meta().name, "isLoopback");
+    assert_eq!(is_loopback.meta().signature.arity, Arity::Fixed(0));
+
+    let ip_class = hooks.manage_obj(Class::bare(hooks.manage_str(IP_ADDR_CLASS_NAME)));
+    let socket_addr_init = SocketAddrInit::native(&hooks, ip_class, error);
+    assert_eq!(socket_addr_init.meta().name, "init");
+    assert_eq!(
+      socket_addr_init.meta().signature.arity,
+      Arity::Default(1, 2)
+    );
+  }
+
+  #[test]
+  fn private() {
+    assert!(is_private("10.1.2.3".parse().unwrap()));
+    assert!(is_private("192.168.0.1".parse().unwrap()));
+    assert!(is_private("fd00::1".parse().unwrap()));
+    assert!(!is_private("8.8.8.8".parse().unwrap()));
+    assert!(!is_private("2001:db8::1".parse().unwrap()));
+  }
+
+  #[test]
+  fn socket_addr() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let mut ip_class = Class::bare(hooks.manage_str(IP_ADDR_CLASS_NAME));
+    ip_class.add_field(&hooks, hooks.manage_str(IP_ADDR_FIELD_ADDRESS));
+    ip_class.add_field(&hooks, hooks.manage_str(IP_ADDR_FIELD_VERSION));
+    let ip_class = hooks.manage_obj(ip_class);
+
+    let mut class = Class::bare(hooks.manage_str(SOCKET_ADDR_CLASS_NAME));
+    class.add_field(&hooks, hooks.manage_str(SOCKET_ADDR_FIELD_IP));
+    class.add_field(&hooks, hooks.manage_str(SOCKET_ADDR_FIELD_PORT));
+    let class = hooks.manage_obj(class);
+
+    let address: SocketAddr = "[::1]:8080".parse().unwrap();
+    let instance = create_socket_addr(&hooks, ip_class, class, address);
+
+    let ip = instance[0].to_obj().to_instance();
+    assert_eq!(ip[0].to_obj().to_str(), "::1");
+    assert_eq!(ip[1].to_num(), 6.0);
+    assert_eq!(instance[1].to_num(), 8080.0);
+
+    assert_eq!(read_socket_addr(val!(instance), class), Some(Ok(address)));
+    assert_eq!(read_socket_addr(val!(ip), class), None);
+    assert_eq!(read_socket_addr(VALUE_NIL, class), None);
+  }
+}
@@ -0,0 +1,367 @@
+use super::parse;
+use crate::{
+  global::{bytes_data, VALUE_ERROR_NAME},
+  native_with_error,
+  net::url::query_map,
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, Instance, LyNative, Map, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use std::{io::Write, net::SocketAddr};
+
+pub const REQUEST_CLASS_NAME: &str = "Request";
+pub const RESPONSE_CLASS_NAME: &str = "Response";
+
+const REQUEST_FIELD_METHOD: &str = "method";
+const REQUEST_FIELD_PATH: &str = "path";
+const REQUEST_FIELD_QUERY: &str = "query";
+const REQUEST_FIELD_VERSION: &str = "version";
+const REQUEST_FIELD_HEADERS: &str = "headers";
+const REQUEST_FIELD_BODY: &str = "body";
+const REQUEST_FIELD_REMOTE: &str = "remote";
+
+const RESPONSE_FIELD_STATUS: &str = "status";
+const RESPONSE_FIELD_HEADERS: &str = "headers";
+const RESPONSE_FIELD_BODY: &str = "body";
+
+const RESPONSE_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(0, 3))
+  .with_params(&[
+    ParameterBuilder::new("status", ParameterKind::Number),
+    ParameterBuilder::new("body", ParameterKind::Any),
+    ParameterBuilder::new("headers", ParameterKind::Any),
+  ]);
+
+pub fn declare_message_classes(
+  hooks: &GcHooks,
+  module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let request = default_class_inheritance(hooks, std, REQUEST_CLASS_NAME)?;
+  export_and_insert(hooks, module, request.name(), val!(request))?;
+
+  let response = default_class_inheritance(hooks, std, RESPONSE_CLASS_NAME)?;
+  export_and_insert(hooks, module, response.name(), val!(response))
+}
+
+pub fn define_message_classes(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let mut request = load_class_from_module(hooks, module, REQUEST_CLASS_NAME)?;
+  let mut response = load_class_from_module(hooks, module, RESPONSE_CLASS_NAME)?;
+  let value_error = val!(load_class_from_package(hooks, std, STD, VALUE_ERROR_NAME)?);
+
+  request.add_field(hooks, hooks.manage_str(REQUEST_FIELD_METHOD));
+  request.add_field(hooks, hooks.manage_str(REQUEST_FIELD_PATH));
+  request.add_field(hooks, hooks.manage_str(REQUEST_FIELD_QUERY));
+  request.add_field(hooks, hooks.manage_str(REQUEST_FIELD_VERSION));
+  request.add_field(hooks, hooks.manage_str(REQUEST_FIELD_HEADERS));
+  request.add_field(hooks, hooks.manage_str(REQUEST_FIELD_BODY));
+  request.add_field(hooks, hooks.manage_str(REQUEST_FIELD_REMOTE));
+
+  response.add_field(hooks, hooks.manage_str(RESPONSE_FIELD_STATUS));
+  response.add_field(hooks, hooks.manage_str(RESPONSE_FIELD_HEADERS));
+  response.add_field(hooks, hooks.manage_str(RESPONSE_FIELD_BODY));
+
+  response.add_method(
+    hooks,
+    hooks.manage_str(RESPONSE_INIT.name),
+    val!(ResponseInit::native(hooks, value_error)),
+  );
+
+  Ok(())
+}
+
+/// A request read by the server along with its decoded query
+#[derive(Debug)]
+pub struct Incoming {
+  pub request: parse::Request,
+  pub path: String,
+  pub query: Vec<(String, String)>,
+  pub remote: Option<SocketAddr>,
+}
+
+/// The parts of a response a handler returned
+#[derive(Debug, PartialEq)]
+pub struct Outgoing {
+  pub status: u16,
+  pub headers: Vec<(String, String)>,
+  pub body: Vec<u8>,
+}
+
+impl Outgoing {
+  /// A plain text response
+  pub fn text(status: u16, body: &str) -> Self {
+    Self {
+      status,
+      headers: vec![(
+        "content-type".to_string(),
+        "text/plain; charset=utf-8".to_string(),
+      )],
+      body: body.as_bytes().to_vec(),
+    }
+  }
+}
+
+/// Create a Request instance for a handler with the SocketAddr it came
+/// from. The body is decoded as utf8 replacing any invalid sequences
+pub fn create_request(
+  hooks: &GcHooks,
+  class: GcObj<Class>,
+  incoming: &Incoming,
+  remote: Value,
+) -> GcObj<Instance> {
+  let mut instance = hooks.manage_obj(Instance::new(class));
+  hooks.push_root(instance);
+
+  let request = &incoming.request;
+  instance[0] = val!(hooks.manage_str(&request.method));
+  instance[1] = val!(hooks.manage_str(&incoming.path));
+  instance[2] = val!(query_map(hooks, &incoming.query));
+  instance[3] = val!(hooks.manage_str(&request.version));
+
+  let mut headers: GcObj<Map<Value, Value>> = hooks.manage_obj(Map::default());
+  instance[4] = val!(headers);
+
+  let mut joined: Vec<(&str, String)> = vec![];
+  for (name, value) in &request.headers {
+    match joined.iter_mut().find(|(existing, _)| existing == name) {
+      Some((_, existing)) => {
+        existing.push_str(", ");
+        existing.push_str(value);
+      },
+      None => joined.push((name, value.clone())),
+    }
+  }
+
+  for (name, value) in joined {
+    let name = val!(hooks.manage_str(name));
+    hooks.push_root(name);
+    let value = val!(hooks.manage_str(value));
+    hooks.grow(&mut headers, |headers| headers.insert(name, value));
+    hooks.pop_roots(1);
+  }
+
+  instance[5] = val!(hooks.manage_str(String::from_utf8_lossy(&request.body)));
+  instance[6] = remote;
+
+  hooks.pop_roots(1);
+  instance
+}
+
+/// Read the response a handler returned. A string is sent as plain text
+/// while a Response instance supplies its own status, headers and body
+pub fn read_response(value: Value, class: GcObj<Class>) -> Result<Outgoing, String> {
+  if value.is_obj_kind(ObjectKind::String) {
+    return Ok(Outgoing::text(200, &value.to_obj().to_str()));
+  }
+
+  if !value.is_obj_kind(ObjectKind::Instance) {
+    return Err("Http handler must return a String or Response.".to_string());
+  }
+
+  let instance = value.to_obj().to_instance();
+  let instance_class = instance.class();
+  if instance_class != class && !instance_class.is_subclass(class) {
+    return Err("Http handler must return a String or Response.".to_string());
+  }
+
+  let status = status_code(instance[0])?;
+
+  let mut headers = vec![];
+  let header_map = instance[1];
+  if header_map.is_obj_kind(ObjectKind::Map) {
+    for (name, value) in header_map.to_obj().to_map().iter() {
+      if !name.is_obj_kind(ObjectKind::String) {
+        return Err("Response header names must be strings.".to_string());
+      }
+
+      let value = if value.is_obj_kind(ObjectKind::String) {
+        String::from(&*value.to_obj().to_str())
+      } else if value.is_num() {
+        value.to_num().to_string()
+      } else {
+        return Err("Response header values must be strings or numbers.".to_string());
+      };
+
+      headers.push((String::from(&*name.to_obj().to_str()), value));
+    }
+  } else if !header_map.is_nil() {
+    return Err("Response headers must be a map or nil.".to_string());
+  }
+
+  let body = instance[2];
+  let (body, content_type) = if body.is_nil() {
+    (vec![], None)
+  } else if body.is_obj_kind(ObjectKind::String) {
+    (
+      body.to_obj().to_str().as_bytes().to_vec(),
+      Some("text/plain; charset=utf-8"),
+    )
+  } else {
+    match bytes_data(body) {
+      Some(bytes) => (bytes.to_vec(), Some("application/octet-stream")),
+      None => return Err("Response body must be a String, Bytes or nil.".to_string()),
+    }
+  };
+
+  if let Some(content_type) = content_type {
+    if !headers
+      .iter()
+      .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+      headers.push(("content-type".to_string(), content_type.to_string()));
+    }
+  }
+
+  Ok(Outgoing {
+    status,
+    headers,
+    body,
+  })
+}
+
+fn status_code(value: Value) -> Result<u16, String> {
+  if value.is_num() {
+    let status = value.to_num();
+    if status.fract() == 0.0 && (100.0..=999.0).contains(&status) {
+      return Ok(status as u16);
+    }
+  }
+
+  Err("Response status must be an integer between 100 and 999.".to_string())
+}
+
+native_with_error!(ResponseInit, RESPONSE_INIT);
+
+impl LyNative for ResponseInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    let status = args.first().copied().unwrap_or_else(|| val!(200.0));
+    if let Err(err) = status_code(status) {
+      return self.call_error(hooks, err);
+    }
+
+    let headers = match args.get(2) {
+      Some(headers) if headers.is_obj_kind(ObjectKind::Map) => *headers,
+      Some(headers) if !headers.is_nil() => {
+        return self.call_error(hooks, "Response headers must be a map or nil.")
+      },
+      _ => val!(hooks.manage_obj(Map::default())),
+    };
+
+    this[0] = status;
+    this[1] = headers;
+    this[2] = match args.get(1) {
+      Some(body) => *body,
+      None => val!(hooks.manage_str("")),
+    };
+
+    Call::Ok(val!(this))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+  use laythe_core::value::VALUE_NIL;
+
+  fn response_class(hooks: &GcHooks) -> GcObj<Class> {
+    let mut class = Class::bare(hooks.manage_str(RESPONSE_CLASS_NAME));
+    for field in [
+      RESPONSE_FIELD_STATUS,
+      RESPONSE_FIELD_HEADERS,
+      RESPONSE_FIELD_BODY,
+    ]
+    .iter()
+    {
+      class.add_field(hooks, hooks.manage_str(field));
+    }
+
+    hooks.manage_obj(class)
+  }
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let error = val!(test_error_class(&hooks));
+
+    let response_init = ResponseInit::native(&hooks, error);
+    assert_eq!(response_init.meta().name, "init");
+    assert_eq!(response_init.meta().signature.arity, Arity::Default(0, 3));
+    assert_eq!(
+      response_init.meta().signature.parameters[0].kind,
+      ParameterKind::Number
+    );
+  }
+
+  #[test]
+  fn init() {
+    let mut context = MockedContext::default();
+    let mut hooks = Hooks::new(&mut context);
+    let error = val!(test_error_class(&hooks.as_gc()));
+
+    let class = response_class(&hooks.as_gc());
+    let response_init = ResponseInit::native(&hooks.as_gc(), error);
+
+    let instance = hooks.manage_obj(Instance::new(class));
+    let result = response_init
+      .call(&mut hooks, Some(val!(instance)), &[])
+      .unwrap();
+
+    let instance = result.to_obj().to_instance();
+    assert_eq!(instance[0].to_num(), 200.0);
+    assert!(instance[1].is_obj_kind(ObjectKind::Map));
+    assert_eq!(instance[2].to_obj().to_str(), "");
+  }
+
+  #[test]
+  fn read() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let class = response_class(&hooks);
+
+    let text = read_response(val!(hooks.manage_str("hi")), class).unwrap();
+    assert_eq!(text, Outgoing::text(200, "hi"));
+
+    let mut headers: GcObj<Map<Value, Value>> = hooks.manage_obj(Map::default());
+    headers.insert(
+      val!(hooks.manage_str("Content-Type")),
+      val!(hooks.manage_str("application/json")),
+    );
+
+    let mut instance = hooks.manage_obj(Instance::new(class));
+    instance[0] = val!(201.0);
+    instance[1] = val!(headers);
+    instance[2] = val!(hooks.manage_str("{}"));
+
+    let response = read_response(val!(instance), class).unwrap();
+    assert_eq!(response.status, 201);
+    assert_eq!(
+      response.headers,
+      vec![("Content-Type".to_string(), "application/json".to_string())]
+    );
+    assert_eq!(response.body, b"{}");
+
+    instance[2] = VALUE_NIL;
+    assert!(read_response(val!(instance), class)
+      .unwrap()
+      .body
+      .is_empty());
+
+    instance[0] = val!(99.0);
+    assert!(read_response(val!(instance), class).is_err());
+    assert!(read_response(val!(10.0), class).is_err());
+  }
+}
@@ -0,0 +1,43 @@
+mod message;
+mod parse;
+mod server;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+};
+use std::path::PathBuf;
+
+use crate::{global::MODULE_CLASS_NAME, support::load_class_from_package, StdResult, STD};
+
+use self::{
+  message::{declare_message_classes, define_message_classes},
+  server::{declare_server_class, define_server_class},
+};
+
+const HTTP_PATH: &str = "std/net/http";
+
+pub fn http_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(HTTP_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  declare_message_classes(hooks, &mut module, std)?;
+  declare_server_class(hooks, &mut module, std)?;
+
+  define_message_classes(hooks, &module, std)?;
+  define_server_class(hooks, &module, std)?;
+
+  Ok(module)
+}
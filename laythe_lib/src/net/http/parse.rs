@@ -0,0 +1,328 @@
+/// The largest request head, the request line and headers, accepted
+const MAX_HEAD: usize = 64 * 1024;
+
+/// The largest request body accepted
+const MAX_BODY: usize = 8 * 1024 * 1024;
+
+/// The most a connection buffers before a request must be complete
+pub const MAX_REQUEST: usize = MAX_HEAD + MAX_BODY + 4;
+
+/// A request read from a connection
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+  pub method: String,
+  pub target: String,
+  pub version: String,
+  pub headers: Vec<(String, String)>,
+  pub body: Vec<u8>,
+}
+
+impl Request {
+  /// The value of a header, matched without regard to case
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Should the connection stay open after this request. Http/1.1
+  /// connections stay open unless closed and http/1.0 connections
+  /// close unless kept alive
+  pub fn keep_alive(&self) -> bool {
+    let connection = self.header("connection").unwrap_or("");
+
+    if self.version == "HTTP/1.0" {
+      connection.eq_ignore_ascii_case("keep-alive")
+    } else {
+      !connection.eq_ignore_ascii_case("close")
+    }
+  }
+}
+
+/// The result of reading a request from the start of a buffer
+#[derive(Debug, PartialEq)]
+pub enum Parsed {
+  /// More data is needed before the request is complete
+  Incomplete,
+
+  /// A complete request and the number of bytes it used
+  Complete(Request, usize),
+
+  /// The request can't be read, answered with a status and message
+  Invalid(u16, &'static str),
+}
+
+/// Read a request from the start of a buffer
+pub fn parse_request(buffer: &[u8]) -> Parsed {
+  let head_end = match find(buffer, b"\r\n\r\n") {
+    Some(index) => index,
+    None if buffer.len() > MAX_HEAD => {
+      return Parsed::Invalid(431, "Request header fields too large.")
+    },
+    None => return Parsed::Incomplete,
+  };
+
+  let head = match std::str::from_utf8(&buffer[..head_end]) {
+    Ok(head) => head,
+    Err(_) => return Parsed::Invalid(400, "Request head is not valid utf8."),
+  };
+
+  let mut lines = head.split("\r\n");
+  let request_line = lines.next().unwrap_or("");
+  let mut parts = request_line.split(' ');
+
+  let (method, target, version) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+    (Some(method), Some(target), Some(version), None)
+      if !method.is_empty() && !target.is_empty() && version.starts_with("HTTP/1.") =>
+    {
+      (method, target, version)
+    },
+    _ => return Parsed::Invalid(400, "Malformed request line."),
+  };
+
+  let mut headers = vec![];
+  for line in lines {
+    match line.find(':') {
+      Some(index) if index > 0 => headers.push((
+        line[..index].trim().to_ascii_lowercase(),
+        line[index + 1..].trim().to_string(),
+      )),
+      _ => return Parsed::Invalid(400, "Malformed header."),
+    }
+  }
+
+  let mut request = Request {
+    method: method.to_string(),
+    target: target.to_string(),
+    version: version.to_string(),
+    headers,
+    body: vec![],
+  };
+
+  if request.header("transfer-encoding").is_some() {
+    return Parsed::Invalid(501, "Transfer encodings are not supported.");
+  }
+
+  let length = match request.header("content-length") {
+    Some(length) => match length.parse::<usize>() {
+      Ok(length) => length,
+      Err(_) => return Parsed::Invalid(400, "Invalid content length."),
+    },
+    None => 0,
+  };
+
+  if length > MAX_BODY {
+    return Parsed::Invalid(413, "Request body too large.");
+  }
+
+  let body_start = head_end + 4;
+  if buffer.len() < body_start + length {
+    return Parsed::Incomplete;
+  }
+
+  request.body = buffer[body_start..body_start + length].to_vec();
+  Parsed::Complete(request, body_start + length)
+}
+
+/// Split a request target into its path and query, dropping any fragment
+pub fn split_target(target: &str) -> (&str, &str) {
+  let target = target.split('#').next().unwrap_or("");
+
+  match target.find('?') {
+    Some(index) => (&target[..index], &target[index + 1..]),
+    None => (target, ""),
+  }
+}
+
+/// Write a response with its status line, headers and body. The content
+/// length and connection headers are always set by the server
+pub fn write_response(
+  status: u16,
+  headers: &[(String, String)],
+  body: &[u8],
+  keep_alive: bool,
+  include_body: bool,
+) -> Vec<u8> {
+  let mut head = format!("HTTP/1.1 {} {}\r\n", status, reason(status));
+
+  for (name, value) in headers {
+    if name.eq_ignore_ascii_case("content-length") || name.eq_ignore_ascii_case("connection") {
+      continue;
+    }
+
+    head.push_str(&format!("{}: {}\r\n", name, value));
+  }
+
+  head.push_str(&format!("content-length: {}\r\n", body.len()));
+  head.push_str(if keep_alive {
+    "connection: keep-alive\r\n\r\n"
+  } else {
+    "connection: close\r\n\r\n"
+  });
+
+  let mut response = head.into_bytes();
+  if include_body {
+    response.extend_from_slice(body);
+  }
+
+  response
+}
+
+/// The reason phrase of common status codes
+pub fn reason(status: u16) -> &'static str {
+  match status {
+    100 => "Continue",
+    200 => "OK",
+    201 => "Created",
+    202 => "Accepted",
+    204 => "No Content",
+    301 => "Moved Permanently",
+    302 => "Found",
+    303 => "See Other",
+    304 => "Not Modified",
+    307 => "Temporary Redirect",
+    308 => "Permanent Redirect",
+    400 => "Bad Request",
+    401 => "Unauthorized",
+    403 => "Forbidden",
+    404 => "Not Found",
+    405 => "Method Not Allowed",
+    409 => "Conflict",
+    411 => "Length Required",
+    413 => "Payload Too Large",
+    415 => "Unsupported Media Type",
+    422 => "Unprocessable Entity",
+    429 => "Too Many Requests",
+    431 => "Request Header Fields Too Large",
+    500 => "Internal Server Error",
+    501 => "Not Implemented",
+    502 => "Bad Gateway",
+    503 => "Service Unavailable",
+    504 => "Gateway Timeout",
+    _ => "",
+  }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  haystack
+    .windows(needle.len())
+    .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn complete(buffer: &[u8]) -> (Request, usize) {
+    match parse_request(buffer) {
+      Parsed::Complete(request, used) => (request, used),
+      parsed => panic!("Expected a complete request found {:?}", parsed),
+    }
+  }
+
+  #[test]
+  fn parse_get() {
+    let buffer = b"GET /status?verbose=1 HTTP/1.1\r\nHost: example.com\r\nX-Trace:  abc \r\n\r\n";
+    let (request, used) = complete(buffer);
+
+    assert_eq!(used, buffer.len());
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.target, "/status?verbose=1");
+    assert_eq!(request.version, "HTTP/1.1");
+    assert_eq!(request.header("HOST"), Some("example.com"));
+    assert_eq!(request.header("x-trace"), Some("abc"));
+    assert!(request.body.is_empty());
+    assert!(request.keep_alive());
+  }
+
+  #[test]
+  fn parse_body() {
+    let buffer = b"POST /items HTTP/1.1\r\nContent-Length: 5\r\n\r\nhelloGET";
+    let (request, used) = complete(buffer);
+
+    assert_eq!(request.body, b"hello");
+    assert_eq!(&buffer[used..], b"GET");
+  }
+
+  #[test]
+  fn parse_incomplete() {
+    assert_eq!(parse_request(b"GET / HTTP/1.1\r\nHost"), Parsed::Incomplete);
+    assert_eq!(
+      parse_request(b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort"),
+      Parsed::Incomplete
+    );
+  }
+
+  #[test]
+  fn parse_invalid() {
+    let invalid = |buffer: &[u8]| match parse_request(buffer) {
+      Parsed::Invalid(status, _) => status,
+      parsed => panic!("Expected an invalid request found {:?}", parsed),
+    };
+
+    assert_eq!(invalid(b"GET /\r\n\r\n"), 400);
+    assert_eq!(invalid(b"GET / SPDY/3\r\n\r\n"), 400);
+    assert_eq!(invalid(b"GET / HTTP/1.1\r\nno colon\r\n\r\n"), 400);
+    assert_eq!(
+      invalid(b"GET / HTTP/1.1\r\nContent-Length: ten\r\n\r\n"),
+      400
+    );
+    assert_eq!(
+      invalid(b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n"),
+      501
+    );
+    assert_eq!(invalid(&vec![b'a'; MAX_HEAD + 1]), 431);
+  }
+
+  #[test]
+  fn keep_alive() {
+    let request = |version: &str, connection: Option<&str>| {
+      let mut headers = vec![];
+      if let Some(connection) = connection {
+        headers.push(("connection".to_string(), connection.to_string()));
+      }
+
+      Request {
+        method: "GET".to_string(),
+        target: "/".to_string(),
+        version: version.to_string(),
+        headers,
+        body: vec![],
+      }
+    };
+
+    assert!(request("HTTP/1.1", None).keep_alive());
+    assert!(!request("HTTP/1.1", Some("close")).keep_alive());
+    assert!(!request("HTTP/1.0", None).keep_alive());
+    assert!(request("HTTP/1.0", Some("Keep-Alive")).keep_alive());
+  }
+
+  #[test]
+  fn target() {
+    assert_eq!(split_target("/a/b?c=d"), ("/a/b", "c=d"));
+    assert_eq!(split_target("/a#top"), ("/a", ""));
+    assert_eq!(split_target("*"), ("*", ""));
+  }
+
+  #[test]
+  fn write() {
+    let headers = vec![
+      ("content-type".to_string(), "text/plain".to_string()),
+      ("content-length".to_string(), "100".to_string()),
+    ];
+
+    let response = write_response(200, &headers, b"hi", true, true);
+    assert_eq!(
+      String::from_utf8(response).unwrap(),
+      "HTTP/1.1 200 OK\r\ncontent-type: text/plain\r\ncontent-length: 2\r\nconnection: keep-alive\r\n\r\nhi"
+    );
+
+    let response = write_response(404, &[], b"missing", false, false);
+    assert_eq!(
+      String::from_utf8(response).unwrap(),
+      "HTTP/1.1 404 Not Found\r\ncontent-length: 7\r\nconnection: close\r\n\r\n"
+    );
+  }
+}
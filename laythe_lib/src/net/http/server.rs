@@ -0,0 +1,756 @@
+use super::{
+  message::{create_request, read_response, Incoming, Outgoing, RESPONSE_CLASS_NAME},
+  parse::{parse_request, split_target, write_response, Parsed, MAX_REQUEST},
+};
+use crate::{
+  create_error,
+  global::VALUE_ERROR_NAME,
+  native, native_with_error,
+  net::{
+    address::{create_socket_addr, read_socket_addr, IP_ADDR_CLASS_NAME, SOCKET_ADDR_CLASS_NAME},
+    http::message::REQUEST_CLASS_NAME,
+    url::parse_query,
+    NET_ERROR, NET_MODULE_PATH,
+  },
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{
+    Class, Closure, Fiber, FiberState, Instance, LyNative, Native, NativeMetaBuilder, ObjectKind,
+  },
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use laythe_env::net::{TcpListen, TcpStream};
+use std::{
+  any::Any,
+  cell::RefCell,
+  io::{self, Read, Write},
+  net::SocketAddr,
+  time::Duration,
+};
+
+const SERVER_CLASS_NAME: &str = "Server";
+
+const SERVER_FIELD_HANDLER: &str = "handler";
+const SERVER_FIELD_ADDRESS: &str = "address";
+const SERVER_FIELD_STATE: &str = "state";
+
+/// How long a connection may sit idle before it is closed
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long the server sleeps when a tick found nothing to do
+const IDLE_POLL: Duration = Duration::from_millis(2);
+
+const READ_CHUNK: usize = 8 * 1024;
+
+const SERVER_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("handler", ParameterKind::Fun)]);
+
+const SERVER_LISTEN: NativeMetaBuilder = NativeMetaBuilder::method("listen", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("address", ParameterKind::Any)]);
+
+const SERVER_CLOSE: NativeMetaBuilder = NativeMetaBuilder::method("close", Arity::Fixed(0));
+
+const SERVER_STATE: NativeMetaBuilder = NativeMetaBuilder::fun("state", Arity::Fixed(0));
+
+pub fn declare_server_class(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let class = default_class_inheritance(hooks, std, SERVER_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_server_class(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, SERVER_CLASS_NAME)?;
+  let request = load_class_from_module(hooks, module, REQUEST_CLASS_NAME)?;
+  let response = load_class_from_module(hooks, module, RESPONSE_CLASS_NAME)?;
+  let ip_addr = load_class_from_package(hooks, std, NET_MODULE_PATH, IP_ADDR_CLASS_NAME)?;
+  let socket_addr = load_class_from_package(hooks, std, NET_MODULE_PATH, SOCKET_ADDR_CLASS_NAME)?;
+  let value_error = val!(load_class_from_package(hooks, std, STD, VALUE_ERROR_NAME)?);
+  let net_error = val!(load_class_from_package(
+    hooks,
+    std,
+    NET_MODULE_PATH,
+    NET_ERROR
+  )?);
+
+  class.add_field(hooks, hooks.manage_str(SERVER_FIELD_HANDLER));
+  class.add_field(hooks, hooks.manage_str(SERVER_FIELD_ADDRESS));
+  class.add_field(hooks, hooks.manage_str(SERVER_FIELD_STATE));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(SERVER_INIT.name),
+    val!(ServerInit::native(hooks, value_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(SERVER_LISTEN.name),
+    val!(ServerListen::native(
+      hooks,
+      [request, response, ip_addr, socket_addr],
+      value_error,
+      net_error
+    )),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(SERVER_CLOSE.name),
+    val!(ServerClose::native(hooks)),
+  );
+
+  Ok(())
+}
+
+/// A handler must be a laythe function taking the request
+fn handler_closure(handler: Value) -> Result<GcObj<Closure>, &'static str> {
+  if handler.is_obj_kind(ObjectKind::Closure) {
+    let closure = handler.to_obj().to_closure();
+    if closure.fun().arity().check(1).is_ok() {
+      return Ok(closure);
+    }
+  }
+
+  Err("Server handler must be a laythe function taking one request.")
+}
+
+/// The handler running for a connection's current request
+#[derive(Debug)]
+struct Handler {
+  fiber: Option<GcObj<Fiber>>,
+  head: bool,
+  keep_alive: bool,
+}
+
+/// A handler whose fiber has completed or failed
+struct Finished {
+  id: usize,
+  fiber: GcObj<Fiber>,
+  head: bool,
+  keep_alive: bool,
+}
+
+/// The work found by polling the listener and connections
+#[derive(Default)]
+struct Poll {
+  incoming: Vec<(usize, Incoming)>,
+  finished: Vec<Finished>,
+  active: bool,
+}
+
+#[derive(Debug)]
+struct Connection {
+  id: usize,
+  stream: Box<dyn TcpStream>,
+  remote: Option<SocketAddr>,
+  input: Vec<u8>,
+  output: Vec<u8>,
+  handler: Option<Handler>,
+  close: bool,
+  eof: bool,
+  failed: bool,
+  last_active: Duration,
+}
+
+impl Connection {
+  fn poll(&mut self, server_closed: bool, now: Duration, poll: &mut Poll) {
+    match &self.handler {
+      Some(Handler {
+        fiber: Some(fiber),
+        head,
+        keep_alive,
+      }) => {
+        if let FiberState::Complete | FiberState::Failed = fiber.state() {
+          poll.finished.push(Finished {
+            id: self.id,
+            fiber: *fiber,
+            head: *head,
+            keep_alive: *keep_alive,
+          });
+          poll.active = true;
+        }
+      },
+      Some(_) => (),
+      None => {
+        if server_closed {
+          self.close = true;
+        }
+
+        if self.close || self.failed {
+          return;
+        }
+
+        self.read(now, poll);
+        self.parse(poll);
+      },
+    }
+  }
+
+  fn read(&mut self, now: Duration, poll: &mut Poll) {
+    let mut buffer = [0; READ_CHUNK];
+
+    while !self.eof && self.input.len() < MAX_REQUEST {
+      match self.stream.read(&mut buffer) {
+        Ok(0) => self.eof = true,
+        Ok(count) => {
+          self.input.extend_from_slice(&buffer[..count]);
+          self.last_active = now;
+          poll.active = true;
+        },
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+        Err(_) => {
+          self.failed = true;
+          break;
+        },
+      }
+    }
+  }
+
+  fn parse(&mut self, poll: &mut Poll) {
+    match parse_request(&self.input) {
+      Parsed::Complete(request, used) => {
+        self.input.drain(..used);
+
+        let (path, query) = split_target(&request.target);
+        let query = match parse_query(query) {
+          Ok(query) => query,
+          Err(_) => return self.reject(400, "Invalid query string."),
+        };
+
+        self.handler = Some(Handler {
+          fiber: None,
+          head: request.method == "HEAD",
+          keep_alive: request.keep_alive(),
+        });
+
+        let incoming = Incoming {
+          path: path.to_string(),
+          query,
+          remote: self.remote,
+          request,
+        };
+
+        poll.incoming.push((self.id, incoming));
+        poll.active = true;
+      },
+      Parsed::Invalid(status, message) => self.reject(status, message),
+      Parsed::Incomplete => {
+        if self.eof {
+          self.close = true;
+        }
+      },
+    }
+  }
+
+  /// Answer a request that can't be handled and close the connection
+  fn reject(&mut self, status: u16, message: &str) {
+    let response = Outgoing::text(status, message);
+    self.output.extend(write_response(
+      status,
+      &response.headers,
+      &response.body,
+      false,
+      true,
+    ));
+    self.close = true;
+  }
+
+  fn flush(&mut self, now: Duration) -> bool {
+    let mut active = false;
+
+    while !self.output.is_empty() && !self.failed {
+      match self.stream.write(&self.output) {
+        Ok(0) => self.failed = true,
+        Ok(count) => {
+          self.output.drain(..count);
+          self.last_active = now;
+          active = true;
+        },
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+        Err(_) => self.failed = true,
+      }
+    }
+
+    active
+  }
+
+  /// Should this connection be dropped after flushing its output
+  fn finished(&self, now: Duration) -> bool {
+    if self.failed {
+      return true;
+    }
+
+    if self.handler.is_some() {
+      return false;
+    }
+
+    (self.output.is_empty() && (self.close || self.eof))
+      || now.saturating_sub(self.last_active) > IDLE_TIMEOUT
+  }
+}
+
+#[derive(Debug)]
+struct Server {
+  listener: Option<Box<dyn TcpListen>>,
+  connections: Vec<Connection>,
+  next_id: usize,
+  closed: bool,
+  fiber: GcObj<Fiber>,
+}
+
+impl Server {
+  fn new(listener: Box<dyn TcpListen>, fiber: GcObj<Fiber>) -> Self {
+    Self {
+      listener: Some(listener),
+      connections: vec![],
+      next_id: 0,
+      closed: false,
+      fiber,
+    }
+  }
+
+  /// Accept new connections, read what has arrived and collect requests
+  /// ready for a handler along with handlers that have finished
+  fn poll(&mut self, now: Duration) -> Poll {
+    let mut poll = Poll::default();
+
+    if self.closed {
+      self.listener = None;
+    }
+
+    if let Some(listener) = &mut self.listener {
+      while let Ok(Some(stream)) = listener.accept() {
+        let remote = stream
+          .peer_address()
+          .ok()
+          .and_then(|address| address.parse().ok());
+
+        self.connections.push(Connection {
+          id: self.next_id,
+          stream,
+          remote,
+          input: vec![],
+          output: vec![],
+          handler: None,
+          close: false,
+          eof: false,
+          failed: false,
+          last_active: now,
+        });
+
+        self.next_id += 1;
+        poll.active = true;
+      }
+    }
+
+    for connection in &mut self.connections {
+      connection.poll(self.closed, now, &mut poll);
+    }
+
+    poll
+  }
+
+  /// Record the fiber running a connection's request
+  fn start(&mut self, id: usize, fiber: GcObj<Fiber>) {
+    if let Some(connection) = self.connection(id) {
+      if let Some(handler) = &mut connection.handler {
+        handler.fiber = Some(fiber);
+      }
+    }
+  }
+
+  /// Queue the response to a connection's current request
+  fn respond(&mut self, id: usize, response: Outgoing, head: bool, keep_alive: bool) {
+    let keep_alive = keep_alive && !self.closed;
+
+    if let Some(connection) = self.connection(id) {
+      connection.output.extend(write_response(
+        response.status,
+        &response.headers,
+        &response.body,
+        keep_alive,
+        !head,
+      ));
+
+      connection.handler = None;
+      connection.close |= !keep_alive;
+    }
+  }
+
+  /// Write queued responses and drop finished connections, reporting if
+  /// any data was written
+  fn flush(&mut self, now: Duration) -> bool {
+    let mut active = false;
+
+    for connection in &mut self.connections {
+      active |= connection.flush(now);
+    }
+
+    self
+      .connections
+      .retain(|connection| !connection.finished(now));
+
+    active
+  }
+
+  /// Has the server been closed with every connection finished
+  fn done(&self) -> bool {
+    self.closed && self.connections.is_empty()
+  }
+
+  fn connection(&mut self, id: usize) -> Option<&mut Connection> {
+    self
+      .connections
+      .iter_mut()
+      .find(|connection| connection.id == id)
+  }
+
+  fn for_each_fiber(&self, mut action: impl FnMut(&GcObj<Fiber>)) {
+    action(&self.fiber);
+
+    for connection in &self.connections {
+      if let Some(Handler {
+        fiber: Some(fiber), ..
+      }) = &connection.handler
+      {
+        action(fiber);
+      }
+    }
+  }
+}
+
+/// The listener and connections of a Server instance. Nothing may be
+/// allocated while the server is borrowed as tracing borrows it
+#[derive(Debug)]
+pub struct ServerState {
+  server: RefCell<Server>,
+}
+
+impl ServerState {
+  fn native(hooks: &GcHooks, server: Server) -> GcObj<Native> {
+    let native = Box::new(Self {
+      server: RefCell::new(server),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(SERVER_STATE.to_meta(hooks), native))
+  }
+}
+
+impl Trace for ServerState {
+  fn trace(&self) {
+    self.server.borrow().for_each_fiber(|fiber| fiber.trace());
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self
+      .server
+      .borrow()
+      .for_each_fiber(|fiber| fiber.visit_refs(visitor));
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self
+      .server
+      .borrow()
+      .for_each_fiber(|fiber| fiber.trace_debug(stdio));
+  }
+}
+
+impl LyNative for ServerState {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+/// Run an action against the server held by an instance
+fn with_server<R>(instance: GcObj<Instance>, action: impl FnOnce(&mut Server) -> R) -> Option<R> {
+  let state = instance[2];
+  if !state.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  let native = state.to_obj().to_native();
+  native
+    .downcast_ref::<ServerState>()
+    .map(|state| action(&mut state.server.borrow_mut()))
+}
+
+native_with_error!(ServerInit, SERVER_INIT);
+
+impl LyNative for ServerInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    if let Err(err) = handler_closure(args[0]) {
+      return self.call_error(hooks, err);
+    }
+
+    this[0] = args[0];
+    this[1] = VALUE_NIL;
+    this[2] = VALUE_NIL;
+
+    Call::Ok(val!(this))
+  }
+}
+
+/// Serve connections until the server is closed. Each call runs the
+/// server once then sleeps the listening fiber, which is retried when it
+/// wakes, so handler fibers run in between
+#[derive(Debug)]
+pub struct ServerListen {
+  request: GcObj<Class>,
+  response: GcObj<Class>,
+  ip_addr: GcObj<Class>,
+  socket_addr: GcObj<Class>,
+  value_error: Value,
+  net_error: Value,
+}
+
+impl ServerListen {
+  fn native(
+    hooks: &GcHooks,
+    classes: [GcObj<Class>; 4],
+    value_error: Value,
+    net_error: Value,
+  ) -> GcObj<Native> {
+    let [request, response, ip_addr, socket_addr] = classes;
+    let native = Box::new(Self {
+      request,
+      response,
+      ip_addr,
+      socket_addr,
+      value_error,
+      net_error,
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(SERVER_LISTEN.to_meta(hooks), native))
+  }
+
+  /// A SocketAddr instance for an address or nil
+  fn socket_addr(&self, hooks: &GcHooks, address: Option<SocketAddr>) -> Value {
+    match address {
+      Some(address) => val!(create_socket_addr(
+        hooks,
+        self.ip_addr,
+        self.socket_addr,
+        address
+      )),
+      None => VALUE_NIL,
+    }
+  }
+
+  /// Bind the server's listener and store its state in the instance.
+  /// The address is either a SocketAddr or a string, which may name a host
+  fn start(&self, hooks: &mut Hooks, mut server: GcObj<Instance>, address: Value) -> Option<Call> {
+    let address = match read_socket_addr(address, self.socket_addr) {
+      Some(Ok(address)) => address.to_string(),
+      Some(Err(err)) => return Some(create_error!(self.value_error, hooks, err)),
+      None if address.is_obj_kind(ObjectKind::String) => String::from(&*address.to_obj().to_str()),
+      None => {
+        return Some(create_error!(
+          self.value_error,
+          hooks,
+          "Server can only listen on a String or SocketAddr."
+        ))
+      },
+    };
+
+    let listener = match hooks.as_io().net().listen(&address) {
+      Ok(listener) => listener,
+      Err(err) => {
+        return Some(create_error!(
+          self.net_error,
+          hooks,
+          format!("Unable to listen on {}: {}", address, err)
+        ))
+      },
+    };
+
+    let local = listener
+      .local_address()
+      .ok()
+      .and_then(|local| local.parse().ok());
+
+    let fiber = hooks.current_fiber();
+    server[2] = val!(ServerState::native(
+      &hooks.as_gc(),
+      Server::new(listener, fiber)
+    ));
+    server[1] = self.socket_addr(&hooks.as_gc(), local);
+
+    None
+  }
+
+  /// Turn a finished handler's result into a response
+  fn response(&self, hooks: &mut Hooks, fiber: GcObj<Fiber>) -> Outgoing {
+    // a failed handler has already reported its error
+    if fiber.state() != FiberState::Complete {
+      return Outgoing::text(500, "Internal Server Error");
+    }
+
+    match read_response(fiber.result(), self.response) {
+      Ok(response) => response,
+      Err(err) => {
+        let mut stdio = hooks.as_io().stdio();
+        let _ = writeln!(stdio.stderr(), "{}", err);
+        Outgoing::text(500, "Internal Server Error")
+      },
+    }
+  }
+}
+
+impl Trace for ServerListen {
+  fn trace(&self) {
+    self.request.trace();
+    self.response.trace();
+    self.ip_addr.trace();
+    self.socket_addr.trace();
+    self.value_error.trace();
+    self.net_error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.request.visit_refs(visitor);
+    self.response.visit_refs(visitor);
+    self.ip_addr.visit_refs(visitor);
+    self.socket_addr.visit_refs(visitor);
+    self.value_error.visit_refs(visitor);
+    self.net_error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.request.trace_debug(stdio);
+    self.response.trace_debug(stdio);
+    self.ip_addr.trace_debug(stdio);
+    self.socket_addr.trace_debug(stdio);
+    self.value_error.trace_debug(stdio);
+    self.net_error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for ServerListen {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut server = this.unwrap().to_obj().to_instance();
+
+    let handler = match handler_closure(server[0]) {
+      Ok(handler) => handler,
+      Err(err) => return create_error!(self.value_error, hooks, err),
+    };
+
+    let current = hooks.current_fiber();
+    match with_server(server, |state| state.fiber == current) {
+      Some(true) => (),
+      Some(false) => return create_error!(self.net_error, hooks, "Server is already listening."),
+      None => {
+        if let Some(error) = self.start(hooks, server, args[0]) {
+          return error;
+        }
+      },
+    }
+
+    let now = hooks.as_io().time().elapsed().unwrap_or_default();
+    let poll = with_server(server, |state| state.poll(now)).expect("Server state missing.");
+    let mut active = poll.active;
+
+    for finished in poll.finished {
+      let response = self.response(hooks, finished.fiber);
+      with_server(server, |state| {
+        state.respond(finished.id, response, finished.head, finished.keep_alive)
+      });
+    }
+
+    for (id, incoming) in poll.incoming {
+      let remote = self.socket_addr(&hooks.as_gc(), incoming.remote);
+      hooks.push_root(remote);
+      let request = create_request(&hooks.as_gc(), self.request, &incoming, remote);
+      hooks.pop_roots(1);
+
+      hooks.push_root(request);
+      let fiber = Fiber::with_args(handler, &[val!(request)]).map(|fiber| hooks.manage_obj(fiber));
+      hooks.pop_roots(1);
+
+      match fiber {
+        Ok(fiber) => {
+          hooks.spawn_fiber(fiber);
+          with_server(server, |state| state.start(id, fiber));
+        },
+        Err(_) => {
+          let response = Outgoing::text(500, "Internal Server Error");
+          with_server(server, |state| state.respond(id, response, false, false));
+        },
+      }
+    }
+
+    let (flushed, done) =
+      with_server(server, |state| (state.flush(now), state.done())).expect("Server state missing.");
+    active |= flushed;
+
+    if done {
+      server[2] = VALUE_NIL;
+      return Call::Ok(VALUE_NIL);
+    }
+
+    // yield to handlers while busy otherwise wait briefly for new data
+    let wait = if active {
+      Duration::default()
+    } else {
+      IDLE_POLL
+    };
+
+    if !hooks.sleep_fiber(wait) {
+      hooks.sleep_fiber(wait);
+    }
+
+    Call::Block
+  }
+}
+
+native!(ServerClose, SERVER_CLOSE);
+
+impl LyNative for ServerClose {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let server = this.unwrap().to_obj().to_instance();
+    with_server(server, |state| state.closed = true);
+    Call::Ok(VALUE_NIL)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let error = val!(test_error_class(&hooks));
+
+    let server_init = ServerInit::native(&hooks, error);
+    assert_eq!(server_init.meta().name, "init");
+    assert_eq!(server_init.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(
+      server_init.meta().signature.parameters[0].kind,
+      ParameterKind::Fun
+    );
+
+    let server_close = ServerClose::native(&hooks);
+    assert_eq!(server_close.meta().name, "close");
+    assert_eq!(server_close.meta().signature.arity, Arity::Fixed(0));
+  }
+}
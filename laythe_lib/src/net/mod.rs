@@ -0,0 +1,59 @@
+mod address;
+mod http;
+mod tcp;
+mod url;
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdError, StdResult, STD,
+};
+use address::{declare_address_classes, declare_address_funs, define_address_classes};
+use http::http_module;
+use laythe_core::{
+  hooks::GcHooks,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use std::path::PathBuf;
+use tcp::tcp_module;
+use url::url_module;
+
+pub const NET_MODULE_PATH: &str = "std/net";
+pub const NET_ERROR: &str = "NetError";
+
+pub fn add_net_package(
+  hooks: &GcHooks,
+  std: &mut Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<()> {
+  let mut root = std.root_module();
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut net_module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(NET_MODULE_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let net_error = default_error_inheritance(hooks, std, NET_ERROR)?;
+  export_and_insert(hooks, &mut net_module, net_error.name(), val!(net_error))?;
+
+  declare_address_classes(hooks, &mut net_module, std)?;
+  declare_address_funs(hooks, &mut net_module)?;
+  define_address_classes(hooks, &net_module, std)?;
+  root.insert_module(hooks, net_module)?;
+
+  let url = url_module(hooks, std, emitter)?;
+  let tcp = tcp_module(hooks, std, emitter)?;
+  let http = http_module(hooks, std, emitter)?;
+
+  net_module.insert_module(hooks, url)?;
+  net_module.insert_module(hooks, tcp)?;
+  net_module
+    .insert_module(hooks, http)
+    .map_err(StdError::from)
+}
@@ -0,0 +1,621 @@
+use crate::{
+  create_error,
+  global::{create_bytes, input_bytes, BYTES_CLASS_NAME, MODULE_CLASS_NAME, VALUE_ERROR_NAME},
+  native, native_with_bytes, native_with_error,
+  net::{
+    address::{create_socket_addr, read_socket_addr, IP_ADDR_CLASS_NAME, SOCKET_ADDR_CLASS_NAME},
+    NET_ERROR, NET_MODULE_PATH,
+  },
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{Gc, GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, Fiber, Instance, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  utils::IdEmitter,
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use laythe_env::net::TcpStream;
+use std::{
+  any::Any,
+  cell::RefCell,
+  io::{self, Read, Write},
+  net::SocketAddr,
+  path::PathBuf,
+  time::Duration,
+};
+
+const TCP_PATH: &str = "std/net/tcp";
+
+const TCP_STREAM_CLASS_NAME: &str = "TcpStream";
+
+#[cfg(feature = "tls")]
+const TLS_STREAM_CLASS_NAME: &str = "TlsStream";
+
+const STREAM_FIELD_PEER: &str = "peer";
+const STREAM_FIELD_STATE: &str = "state";
+
+/// How long a fiber sleeps before retrying a read or write that would block
+const IO_POLL: Duration = Duration::from_millis(2);
+
+const READ_CHUNK: usize = 8 * 1024;
+
+const TCP_STREAM_CONNECT: NativeMetaBuilder = NativeMetaBuilder::fun("connect", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("address", ParameterKind::Any)]);
+
+#[cfg(feature = "tls")]
+const TLS_STREAM_WRAP: NativeMetaBuilder = NativeMetaBuilder::fun("wrap", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("stream", ParameterKind::Instance),
+    ParameterBuilder::new("host", ParameterKind::String),
+  ]);
+
+const STREAM_READ: NativeMetaBuilder = NativeMetaBuilder::method("read", Arity::Fixed(0));
+
+const STREAM_WRITE: NativeMetaBuilder = NativeMetaBuilder::method("write", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("data", ParameterKind::Any)]);
+
+const STREAM_CLOSE: NativeMetaBuilder = NativeMetaBuilder::method("close", Arity::Fixed(0));
+
+const STREAM_STATE: NativeMetaBuilder = NativeMetaBuilder::fun("state", Arity::Fixed(0));
+
+pub fn tcp_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(TCP_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  declare_stream_classes(hooks, &mut module, std)?;
+  define_stream_classes(hooks, &module, std)?;
+
+  Ok(module)
+}
+
+fn declare_stream_classes(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let tcp_stream = default_class_inheritance(hooks, std, TCP_STREAM_CLASS_NAME)?;
+  export_and_insert(hooks, module, tcp_stream.name(), val!(tcp_stream))?;
+
+  #[cfg(feature = "tls")]
+  {
+    let tls_stream = default_class_inheritance(hooks, std, TLS_STREAM_CLASS_NAME)?;
+    export_and_insert(hooks, module, tls_stream.name(), val!(tls_stream))?;
+  }
+
+  Ok(())
+}
+
+fn define_stream_classes(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let tcp_stream = load_class_from_module(hooks, module, TCP_STREAM_CLASS_NAME)?;
+  let bytes = load_class_from_package(hooks, std, STD, BYTES_CLASS_NAME)?;
+  let ip_addr = load_class_from_package(hooks, std, NET_MODULE_PATH, IP_ADDR_CLASS_NAME)?;
+  let socket_addr = load_class_from_package(hooks, std, NET_MODULE_PATH, SOCKET_ADDR_CLASS_NAME)?;
+  let value_error = val!(load_class_from_package(hooks, std, STD, VALUE_ERROR_NAME)?);
+  let net_error = val!(load_class_from_package(
+    hooks,
+    std,
+    NET_MODULE_PATH,
+    NET_ERROR
+  )?);
+
+  tcp_stream
+    .meta_class()
+    .expect("Meta class not set.")
+    .add_method(
+      hooks,
+      hooks.manage_str(TCP_STREAM_CONNECT.name),
+      val!(TcpStreamConnect::native(
+        hooks,
+        [tcp_stream, ip_addr, socket_addr],
+        value_error,
+        net_error
+      )),
+    );
+
+  #[allow(unused_mut)]
+  let mut classes = vec![tcp_stream];
+
+  #[cfg(feature = "tls")]
+  {
+    let tls_stream = load_class_from_module(hooks, module, TLS_STREAM_CLASS_NAME)?;
+
+    tls_stream
+      .meta_class()
+      .expect("Meta class not set.")
+      .add_method(
+        hooks,
+        hooks.manage_str(TLS_STREAM_WRAP.name),
+        val!(TlsStreamWrap::native(
+          hooks,
+          [tcp_stream, tls_stream],
+          net_error
+        )),
+      );
+
+    classes.push(tls_stream);
+  }
+
+  for mut class in classes {
+    class.add_field(hooks, hooks.manage_str(STREAM_FIELD_PEER));
+    class.add_field(hooks, hooks.manage_str(STREAM_FIELD_STATE));
+
+    class.add_method(
+      hooks,
+      hooks.manage_str(STREAM_READ.name),
+      val!(StreamRead::native(hooks, bytes, net_error)),
+    );
+
+    class.add_method(
+      hooks,
+      hooks.manage_str(STREAM_WRITE.name),
+      val!(StreamWrite::native(hooks, net_error)),
+    );
+
+    class.add_method(
+      hooks,
+      hooks.manage_str(STREAM_CLOSE.name),
+      val!(StreamClose::native(hooks)),
+    );
+  }
+
+  Ok(())
+}
+
+/// The outcome of a read or write that may have to wait on the socket
+enum Progress<T> {
+  Done(T),
+  Pending,
+}
+
+/// A connection along with any write still in progress. The fiber
+/// writing is kept so other writers wait their turn
+#[derive(Debug)]
+struct Stream {
+  stream: Option<Box<dyn TcpStream>>,
+  output: Vec<u8>,
+  writer: Option<GcObj<Fiber>>,
+}
+
+impl Stream {
+  fn new(stream: Box<dyn TcpStream>) -> Self {
+    Self {
+      stream: Some(stream),
+      output: vec![],
+      writer: None,
+    }
+  }
+
+  /// Read the data that has arrived, None once the other end has closed
+  fn read(&mut self) -> Result<Progress<Option<Vec<u8>>>, String> {
+    let stream = self.stream.as_mut().ok_or("Stream is closed.")?;
+    let mut buffer = vec![0; READ_CHUNK];
+
+    loop {
+      match stream.read(&mut buffer) {
+        Ok(0) => return Ok(Progress::Done(None)),
+        Ok(count) => {
+          buffer.truncate(count);
+          return Ok(Progress::Done(Some(buffer)));
+        },
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(Progress::Pending),
+        Err(err) => return Err(format!("Unable to read from stream: {}", err)),
+      }
+    }
+  }
+
+  /// Write data for a fiber, queuing it when the fiber starts writing
+  fn write(&mut self, fiber: GcObj<Fiber>, data: &[u8]) -> Result<Progress<()>, String> {
+    let stream = self.stream.as_mut().ok_or("Stream is closed.")?;
+
+    match self.writer {
+      Some(writer) if writer != fiber => return Ok(Progress::Pending),
+      Some(_) => (),
+      None => {
+        self.writer = Some(fiber);
+        self.output.extend_from_slice(data);
+      },
+    }
+
+    let result = loop {
+      if self.output.is_empty() {
+        break stream.flush();
+      }
+
+      match stream.write(&self.output) {
+        Ok(0) => break Err(io::ErrorKind::WriteZero.into()),
+        Ok(count) => {
+          self.output.drain(..count);
+        },
+        Err(err) => break Err(err),
+      }
+    };
+
+    match result {
+      Ok(()) => {
+        self.writer = None;
+        Ok(Progress::Done(()))
+      },
+      Err(err)
+        if err.kind() == io::ErrorKind::WouldBlock || err.kind() == io::ErrorKind::Interrupted =>
+      {
+        Ok(Progress::Pending)
+      },
+      Err(err) => {
+        self.writer = None;
+        self.output.clear();
+        Err(format!("Unable to write to stream: {}", err))
+      },
+    }
+  }
+
+  fn close(&mut self) {
+    self.stream = None;
+    self.output.clear();
+    self.writer = None;
+  }
+}
+
+/// The connection behind a TcpStream or TlsStream instance. Nothing may
+/// be allocated while the stream is borrowed as tracing borrows it
+#[derive(Debug)]
+pub struct StreamState {
+  stream: RefCell<Stream>,
+}
+
+impl StreamState {
+  fn native(hooks: &GcHooks, stream: Stream) -> GcObj<Native> {
+    let native = Box::new(Self {
+      stream: RefCell::new(stream),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(STREAM_STATE.to_meta(hooks), native))
+  }
+}
+
+impl Trace for StreamState {
+  fn trace(&self) {
+    if let Some(writer) = &self.stream.borrow().writer {
+      writer.trace();
+    }
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    if let Some(writer) = &self.stream.borrow().writer {
+      writer.visit_refs(visitor);
+    }
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    if let Some(writer) = &self.stream.borrow().writer {
+      writer.trace_debug(stdio);
+    }
+  }
+}
+
+impl LyNative for StreamState {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+/// Run an action against the stream held by an instance
+fn with_stream<R>(instance: GcObj<Instance>, action: impl FnOnce(&mut Stream) -> R) -> Option<R> {
+  let state = instance[1];
+  if !state.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  let native = state.to_obj().to_native();
+  native
+    .downcast_ref::<StreamState>()
+    .map(|state| action(&mut state.stream.borrow_mut()))
+}
+
+/// Create a stream instance of a class holding a connection
+fn create_stream(
+  hooks: &GcHooks,
+  class: GcObj<Class>,
+  peer: Value,
+  stream: Box<dyn TcpStream>,
+) -> GcObj<Instance> {
+  let mut instance = hooks.manage_obj(Instance::new(class));
+  hooks.push_root(instance);
+
+  instance[0] = peer;
+  instance[1] = val!(StreamState::native(hooks, Stream::new(stream)));
+  hooks.pop_roots(1);
+
+  instance
+}
+
+/// Sleep the current fiber so a read or write is retried once it wakes
+fn block(hooks: &mut Hooks) -> Call {
+  if !hooks.sleep_fiber(IO_POLL) {
+    hooks.sleep_fiber(IO_POLL);
+  }
+
+  Call::Block
+}
+
+/// Connect to an address, either a SocketAddr or a string such as
+/// example.com:80
+#[derive(Debug)]
+pub struct TcpStreamConnect {
+  tcp_stream: GcObj<Class>,
+  ip_addr: GcObj<Class>,
+  socket_addr: GcObj<Class>,
+  value_error: Value,
+  net_error: Value,
+}
+
+impl TcpStreamConnect {
+  fn native(
+    hooks: &GcHooks,
+    classes: [GcObj<Class>; 3],
+    value_error: Value,
+    net_error: Value,
+  ) -> GcObj<Native> {
+    let [tcp_stream, ip_addr, socket_addr] = classes;
+    let native = Box::new(Self {
+      tcp_stream,
+      ip_addr,
+      socket_addr,
+      value_error,
+      net_error,
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(TCP_STREAM_CONNECT.to_meta(hooks), native))
+  }
+}
+
+impl Trace for TcpStreamConnect {
+  fn trace(&self) {
+    self.tcp_stream.trace();
+    self.ip_addr.trace();
+    self.socket_addr.trace();
+    self.value_error.trace();
+    self.net_error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.tcp_stream.visit_refs(visitor);
+    self.ip_addr.visit_refs(visitor);
+    self.socket_addr.visit_refs(visitor);
+    self.value_error.visit_refs(visitor);
+    self.net_error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.tcp_stream.trace_debug(stdio);
+    self.ip_addr.trace_debug(stdio);
+    self.socket_addr.trace_debug(stdio);
+    self.value_error.trace_debug(stdio);
+    self.net_error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for TcpStreamConnect {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let address = match read_socket_addr(args[0], self.socket_addr) {
+      Some(Ok(address)) => address.to_string(),
+      Some(Err(err)) => return create_error!(self.value_error, hooks, err),
+      None if args[0].is_obj_kind(ObjectKind::String) => String::from(&*args[0].to_obj().to_str()),
+      None => {
+        return create_error!(
+          self.value_error,
+          hooks,
+          "TcpStream can only connect to a String or SocketAddr."
+        )
+      },
+    };
+
+    let stream = match hooks.as_io().net().connect(&address) {
+      Ok(stream) => stream,
+      Err(err) => {
+        return create_error!(
+          self.net_error,
+          hooks,
+          format!("Unable to connect to {}: {}", address, err)
+        )
+      },
+    };
+
+    let peer = match stream
+      .peer_address()
+      .ok()
+      .and_then(|peer| peer.parse::<SocketAddr>().ok())
+    {
+      Some(peer) => val!(create_socket_addr(
+        &hooks.as_gc(),
+        self.ip_addr,
+        self.socket_addr,
+        peer
+      )),
+      None => VALUE_NIL,
+    };
+
+    hooks.push_root(peer);
+    let instance = create_stream(&hooks.as_gc(), self.tcp_stream, peer, stream);
+    hooks.pop_roots(1);
+
+    Call::Ok(val!(instance))
+  }
+}
+
+/// Encrypt an open TcpStream, which is closed as its connection moves to
+/// the new TlsStream
+#[cfg(feature = "tls")]
+#[derive(Debug)]
+pub struct TlsStreamWrap {
+  tcp_stream: GcObj<Class>,
+  tls_stream: GcObj<Class>,
+  error: Value,
+}
+
+#[cfg(feature = "tls")]
+impl TlsStreamWrap {
+  fn native(hooks: &GcHooks, classes: [GcObj<Class>; 2], error: Value) -> GcObj<Native> {
+    let [tcp_stream, tls_stream] = classes;
+    let native = Box::new(Self {
+      tcp_stream,
+      tls_stream,
+      error,
+    }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(TLS_STREAM_WRAP.to_meta(hooks), native))
+  }
+}
+
+#[cfg(feature = "tls")]
+impl Trace for TlsStreamWrap {
+  fn trace(&self) {
+    self.tcp_stream.trace();
+    self.tls_stream.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.tcp_stream.visit_refs(visitor);
+    self.tls_stream.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.tcp_stream.trace_debug(stdio);
+    self.tls_stream.trace_debug(stdio);
+    self.error.trace_debug(stdio);
+  }
+}
+
+#[cfg(feature = "tls")]
+impl LyNative for TlsStreamWrap {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let tcp = args[0].to_obj().to_instance();
+    let host = args[1].to_obj().to_str();
+
+    if tcp.class() != self.tcp_stream {
+      return create_error!(self.error, hooks, "Can only wrap a TcpStream.");
+    }
+
+    let stream = with_stream(tcp, |stream| match stream.writer {
+      Some(_) => Err("Can't wrap a stream while it is writing."),
+      None => stream.stream.take().ok_or("Stream is closed."),
+    });
+
+    let stream = match stream {
+      Some(Ok(stream)) => stream,
+      Some(Err(err)) => return create_error!(self.error, hooks, err),
+      None => return create_error!(self.error, hooks, "Stream is closed."),
+    };
+
+    let stream = match hooks.as_io().net().tls(stream, &host) {
+      Ok(stream) => stream,
+      Err(err) => {
+        return create_error!(
+          self.error,
+          hooks,
+          format!("Unable to start tls with {}: {}", &*host, err)
+        )
+      },
+    };
+
+    Call::Ok(val!(create_stream(
+      &hooks.as_gc(),
+      self.tls_stream,
+      tcp[0],
+      stream
+    )))
+  }
+}
+
+native_with_bytes!(StreamRead, STREAM_READ);
+
+impl LyNative for StreamRead {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let instance = this.unwrap().to_obj().to_instance();
+
+    match with_stream(instance, |stream| stream.read()) {
+      Some(Ok(Progress::Done(Some(data)))) => {
+        Call::Ok(val!(create_bytes(&hooks.as_gc(), self.bytes, &data)))
+      },
+      Some(Ok(Progress::Done(None))) => Call::Ok(VALUE_NIL),
+      Some(Ok(Progress::Pending)) => block(hooks),
+      Some(Err(err)) => create_error!(self.error, hooks, err),
+      None => create_error!(self.error, hooks, "Stream is closed."),
+    }
+  }
+}
+
+native_with_error!(StreamWrite, STREAM_WRITE);
+
+impl LyNative for StreamWrite {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let instance = this.unwrap().to_obj().to_instance();
+
+    let data = match input_bytes(args[0]) {
+      Some(data) => data,
+      None => return self.call_error(hooks, "Can only write a String or Bytes."),
+    };
+
+    let fiber = hooks.current_fiber();
+    match with_stream(instance, |stream| stream.write(fiber, &data)) {
+      Some(Ok(Progress::Done(()))) => Call::Ok(VALUE_NIL),
+      Some(Ok(Progress::Pending)) => block(hooks),
+      Some(Err(err)) => self.call_error(hooks, err),
+      None => self.call_error(hooks, "Stream is closed."),
+    }
+  }
+}
+
+native!(StreamClose, STREAM_CLOSE);
+
+impl LyNative for StreamClose {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let instance = this.unwrap().to_obj().to_instance();
+    with_stream(instance, |stream| stream.close());
+    Call::Ok(VALUE_NIL)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let error = val!(test_error_class(&hooks));
+
+    let stream_write = StreamWrite::native(&hooks, error);
+    assert_eq!(stream_write.meta().name, "write");
+    assert_eq!(stream_write.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(
+      stream_write.meta().signature.parameters[0].kind,
+      ParameterKind::Any
+    );
+
+    let stream_close = StreamClose::native(&hooks);
+    assert_eq!(stream_close.meta().name, "close");
+    assert_eq!(stream_close.meta().signature.arity, Arity::Fixed(0));
+  }
+}
@@ -0,0 +1,43 @@
+mod class;
+mod utils;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+};
+use std::path::PathBuf;
+
+use crate::{global::MODULE_CLASS_NAME, support::load_class_from_package, StdResult, STD};
+
+use self::{
+  class::{declare_url_class, define_url_class},
+  utils::declare_url_funs,
+};
+
+pub(super) use self::utils::{parse_query, query_map};
+
+const URL_PATH: &str = "std/net/url";
+
+pub fn url_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(URL_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  declare_url_class(hooks, &mut module, std)?;
+  declare_url_funs(hooks, &mut module, std)?;
+
+  define_url_class(hooks, &module, std)?;
+
+  Ok(module)
+}
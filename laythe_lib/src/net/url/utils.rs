@@ -0,0 +1,301 @@
+use crate::{
+  global::VALUE_ERROR_NAME,
+  native, native_with_error,
+  support::{export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{List, LyNative, Map, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::io::Write;
+use url::form_urlencoded;
+
+/// Everything but the unreserved characters of RFC 3986
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'_')
+  .remove(b'~');
+
+const ENCODE_COMPONENT_META: NativeMetaBuilder =
+  NativeMetaBuilder::fun("encodeComponent", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("component", ParameterKind::String)]);
+
+const DECODE_COMPONENT_META: NativeMetaBuilder =
+  NativeMetaBuilder::fun("decodeComponent", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("component", ParameterKind::String)]);
+
+const PARSE_QUERY_META: NativeMetaBuilder = NativeMetaBuilder::fun("parseQuery", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("query", ParameterKind::String)]);
+
+const ENCODE_QUERY_META: NativeMetaBuilder = NativeMetaBuilder::fun("encodeQuery", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("query", ParameterKind::Map)]);
+
+pub fn declare_url_funs(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let value_error = val!(load_class_from_package(hooks, std, STD, VALUE_ERROR_NAME)?);
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(ENCODE_COMPONENT_META.name),
+    val!(EncodeComponent::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(DECODE_COMPONENT_META.name),
+    val!(DecodeComponent::native(hooks, value_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(PARSE_QUERY_META.name),
+    val!(ParseQuery::native(hooks, value_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(ENCODE_QUERY_META.name),
+    val!(EncodeQuery::native(hooks, value_error)),
+  )
+}
+
+/// Percent encode everything but letters, digits and `-._~`
+fn encode_component(component: &str) -> String {
+  utf8_percent_encode(component, COMPONENT).to_string()
+}
+
+/// Decode the percent escapes of a component
+fn decode_component(component: &str) -> Result<String, String> {
+  percent_decode_str(component)
+    .decode_utf8()
+    .map(|decoded| decoded.into_owned())
+    .map_err(|_| format!("Decoding {} did not produce valid utf8.", component))
+}
+
+/// Parse the key value pairs of a query string, decoding `+` as a space.
+/// A leading `?` is ignored and a key without `=` has an empty value
+pub(crate) fn parse_query(query: &str) -> Result<Vec<(String, String)>, String> {
+  let query = query.strip_prefix('?').unwrap_or(query);
+
+  query
+    .split('&')
+    .filter(|pair| !pair.is_empty())
+    .map(|pair| {
+      let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+      Ok((
+        decode_component(&key.replace('+', " "))?,
+        decode_component(&value.replace('+', " "))?,
+      ))
+    })
+    .collect()
+}
+
+/// Write key value pairs as a form encoded query string
+fn encode_query(pairs: &[(String, String)]) -> String {
+  form_urlencoded::Serializer::new(String::new())
+    .extend_pairs(pairs)
+    .finish()
+}
+
+/// Convert decoded query pairs into a map. A key that appears more than
+/// once maps to a list of its values in the order they were written
+pub(crate) fn query_map(hooks: &GcHooks, pairs: &[(String, String)]) -> GcObj<Map<Value, Value>> {
+  let mut map: GcObj<Map<Value, Value>> = hooks.manage_obj(Map::default());
+  hooks.push_root(map);
+
+  for (key, value) in pairs {
+    let key = val!(hooks.manage_str(key));
+    hooks.push_root(key);
+
+    let value = val!(hooks.manage_str(value));
+    hooks.push_root(value);
+
+    match map.get(&key).copied() {
+      Some(existing) if existing.is_obj_kind(ObjectKind::List) => {
+        let mut list = existing.to_obj().to_list();
+        hooks.grow(&mut list, |list| list.push(value));
+      },
+      Some(existing) => {
+        let list = val!(hooks.manage_obj(List::from(vec![existing, value])));
+        hooks.grow(&mut map, |map| map.insert(key, list));
+      },
+      None => {
+        hooks.grow(&mut map, |map| map.insert(key, value));
+      },
+    }
+
+    hooks.pop_roots(2);
+  }
+
+  hooks.pop_roots(1);
+  map
+}
+
+/// Convert a query map back into pairs sorted by key. Values may be
+/// strings, numbers, bools or lists of them
+pub(super) fn query_pairs(query: Value) -> Result<Vec<(String, String)>, String> {
+  if query.is_nil() {
+    return Ok(vec![]);
+  }
+
+  if !query.is_obj_kind(ObjectKind::Map) {
+    return Err("Url query must be a map or nil.".to_string());
+  }
+
+  let mut pairs = vec![];
+  for (key, value) in query.to_obj().to_map().iter() {
+    if !key.is_obj_kind(ObjectKind::String) {
+      return Err("Url query keys must be strings.".to_string());
+    }
+
+    let key = String::from(&*key.to_obj().to_str());
+    if value.is_obj_kind(ObjectKind::List) {
+      for item in value.to_obj().to_list().iter() {
+        pairs.push((key.clone(), query_value(*item)?));
+      }
+    } else {
+      pairs.push((key, query_value(*value)?));
+    }
+  }
+
+  pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+  Ok(pairs)
+}
+
+fn query_value(value: Value) -> Result<String, String> {
+  if value.is_obj_kind(ObjectKind::String) {
+    Ok(String::from(&*value.to_obj().to_str()))
+  } else if value.is_num() {
+    Ok(value.to_num().to_string())
+  } else if value.is_bool() {
+    Ok(value.to_bool().to_string())
+  } else {
+    Err("Url query values must be strings, numbers, bools or lists of them.".to_string())
+  }
+}
+
+native!(EncodeComponent, ENCODE_COMPONENT_META);
+
+impl LyNative for EncodeComponent {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let component = args[0].to_obj().to_str();
+    Call::Ok(val!(hooks.manage_str(encode_component(&component))))
+  }
+}
+
+native_with_error!(DecodeComponent, DECODE_COMPONENT_META);
+
+impl LyNative for DecodeComponent {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let component = args[0].to_obj().to_str();
+
+    match decode_component(&component) {
+      Ok(decoded) => Call::Ok(val!(hooks.manage_str(decoded))),
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native_with_error!(ParseQuery, PARSE_QUERY_META);
+
+impl LyNative for ParseQuery {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let query = args[0].to_obj().to_str();
+
+    match parse_query(&query) {
+      Ok(pairs) => Call::Ok(val!(query_map(&hooks.as_gc(), &pairs))),
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native_with_error!(EncodeQuery, ENCODE_QUERY_META);
+
+impl LyNative for EncodeQuery {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    match query_pairs(args[0]) {
+      Ok(pairs) => Call::Ok(val!(hooks.manage_str(encode_query(&pairs)))),
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let error = val!(test_error_class(&hooks));
+
+    let encode_component = EncodeComponent::native(&hooks);
+    assert_eq!(encode_component.meta().name, "encodeComponent");
+    assert_eq!(encode_component.meta().signature.arity, Arity::Fixed(1));
+
+    let encode_query = EncodeQuery::native(&hooks, error);
+    assert_eq!(encode_query.meta().name, "encodeQuery");
+    assert_eq!(
+      encode_query.meta().signature.parameters[0].kind,
+      ParameterKind::Map
+    );
+  }
+
+  #[test]
+  fn component() {
+    assert_eq!(encode_component("a b/c?é~"), "a%20b%2Fc%3F%C3%A9~");
+    assert_eq!(decode_component("a%20b%2Fc%3F%C3%A9~").unwrap(), "a b/c?é~");
+    assert!(decode_component("%FF").is_err());
+  }
+
+  #[test]
+  fn query() {
+    let pairs = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+      pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+    };
+
+    assert_eq!(
+      parse_query("?a=1&&b&c=x%3Dy&d=two+words").unwrap(),
+      pairs(&[("a", "1"), ("b", ""), ("c", "x=y"), ("d", "two words")])
+    );
+    assert!(parse_query("q=%FF").is_err());
+    assert_eq!(encode_query(&pairs(&[("a b", "c/d&e")])), "a+b=c%2Fd%26e");
+  }
+
+  #[test]
+  fn query_round_trip() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let pairs = parse_query("tag=b&page=2&tag=a").unwrap();
+    let map = query_map(&hooks, &pairs);
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(
+      query_pairs(val!(map)).unwrap(),
+      vec![
+        ("page".to_string(), "2".to_string()),
+        ("tag".to_string(), "b".to_string()),
+        ("tag".to_string(), "a".to_string()),
+      ]
+    );
+  }
+}
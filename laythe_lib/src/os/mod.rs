@@ -0,0 +1,45 @@
+mod signal;
+mod utils;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use signal::signal_module;
+use std::path::PathBuf;
+use utils::{declare_os_module, define_os_module};
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+
+const OS_PATH: &str = "std/os";
+const OS_ERROR: &str = "OsError";
+
+pub fn os_module(hooks: &GcHooks, std: &Package, emitter: &mut IdEmitter) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(OS_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let os_error = default_error_inheritance(hooks, std, OS_ERROR)?;
+  export_and_insert(hooks, &mut module, os_error.name(), val!(os_error))?;
+
+  declare_os_module(hooks, &mut module)?;
+  define_os_module(hooks, &mut module)?;
+
+  let signal = signal_module(hooks, std, &module, emitter)?;
+  module.insert_module(hooks, signal)?;
+
+  Ok(module)
+}
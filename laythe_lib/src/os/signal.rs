@@ -0,0 +1,179 @@
+use super::OS_ERROR;
+use crate::{
+  global::MODULE_CLASS_NAME,
+  native_with_error,
+  support::{export_and_insert, load_class_from_module, load_class_from_package},
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{Gc, GcObj, Trace},
+  module::{Module, Package},
+  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  utils::IdEmitter,
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use laythe_env::signal::ProcessSignal;
+use std::{io::Write, path::PathBuf};
+
+const SIGNAL_PATH: &str = "std/os/signal";
+
+const ON_META: NativeMetaBuilder = NativeMetaBuilder::fun("on", Arity::Fixed(2)).with_params(&[
+  ParameterBuilder::new("signal", ParameterKind::String),
+  ParameterBuilder::new("handler", ParameterKind::Fun),
+]);
+
+const SEND_META: NativeMetaBuilder = NativeMetaBuilder::fun("send", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("signal", ParameterKind::String)]);
+
+pub fn signal_module(
+  hooks: &GcHooks,
+  std: &Package,
+  os: &Module,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(SIGNAL_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let os_error = val!(load_class_from_module(hooks, os, OS_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    &mut module,
+    hooks.manage_str(ON_META.name),
+    val!(On::native(hooks, os_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    &mut module,
+    hooks.manage_str(SEND_META.name),
+    val!(SendSignal::native(hooks, os_error)),
+  )?;
+
+  Ok(module)
+}
+
+/// The signal named by a native's argument
+fn signal_arg(arg: Value) -> Result<ProcessSignal, String> {
+  let name = arg.to_obj().to_str();
+
+  ProcessSignal::from_name(&name).ok_or_else(|| {
+    let names: Vec<&str> = ProcessSignal::ALL
+      .iter()
+      .map(|signal| signal.name())
+      .collect();
+    format!(
+      "Unknown signal {}, expected one of {}.",
+      name,
+      names.join(", ")
+    )
+  })
+}
+
+native_with_error!(On, ON_META);
+
+impl LyNative for On {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let signal = match signal_arg(args[0]) {
+      Ok(signal) => signal,
+      Err(err) => return self.call_error(hooks, err),
+    };
+
+    match hooks.add_signal_handler(signal, args[1]) {
+      Ok(()) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native_with_error!(SendSignal, SEND_META);
+
+impl LyNative for SendSignal {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let signal = match signal_arg(args[0]) {
+      Ok(signal) => signal,
+      Err(err) => return self.call_error(hooks, err),
+    };
+
+    match hooks.as_io().signals().raise(signal) {
+      Ok(()) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  mod on {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let on = On::native(&hooks, error);
+
+      assert_eq!(on.meta().name, "on");
+      assert_eq!(on.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(
+        on.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+      assert_eq!(on.meta().signature.parameters[1].kind, ParameterKind::Fun);
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let on = On::native(&hooks.as_gc(), error);
+
+      let handler = val!(SendSignal::native(&hooks.as_gc(), error));
+      let signal = val!(hooks.manage_str("SIGTERM"));
+      let result = on.call(&mut hooks, None, &[signal, handler]).unwrap();
+      assert!(result.is_nil());
+
+      assert_eq!(
+        context.signal_handlers,
+        vec![(ProcessSignal::Terminate, handler)]
+      );
+    }
+  }
+
+  mod send {
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let send = SendSignal::native(&hooks, error);
+
+      assert_eq!(send.meta().name, "send");
+      assert_eq!(send.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        send.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+  }
+}
@@ -0,0 +1,167 @@
+use super::OS_ERROR;
+use crate::{
+  env::{Args, Cwd},
+  global::Exit,
+  native, native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::Module,
+  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::io::Write;
+
+const ENV_META: NativeMetaBuilder = NativeMetaBuilder::fun("env", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("name", ParameterKind::String)]);
+
+const SET_ENV_META: NativeMetaBuilder = NativeMetaBuilder::fun("setEnv", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("name", ParameterKind::String),
+    ParameterBuilder::new("value", ParameterKind::String),
+  ]);
+
+const PLATFORM_META: NativeMetaBuilder = NativeMetaBuilder::fun("platform", Arity::Fixed(0));
+
+pub fn declare_os_module(hooks: &GcHooks, self_module: &mut Module) -> StdResult<()> {
+  let os_error = val!(load_class_from_module(hooks, self_module, OS_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(ENV_META.name),
+    val!(Env::native(hooks)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(SET_ENV_META.name),
+    val!(SetEnv::native(hooks, os_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(PLATFORM_META.name),
+    val!(Platform::native(hooks)),
+  )?;
+
+  let args = Args::native(hooks);
+  export_and_insert(hooks, self_module, args.meta().name, val!(args))?;
+
+  let cwd = Cwd::native(hooks);
+  export_and_insert(hooks, self_module, cwd.meta().name, val!(cwd))?;
+
+  let exit = Exit::native(hooks);
+  export_and_insert(hooks, self_module, exit.meta().name, val!(exit))
+}
+
+pub fn define_os_module(_: &GcHooks, _: &mut Module) -> StdResult<()> {
+  Ok(())
+}
+
+native!(Env, ENV_META);
+
+impl LyNative for Env {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let name = args[0].to_obj().to_str();
+
+    match hooks.as_io().env().var(&name) {
+      Some(value) => Call::Ok(val!(hooks.manage_str(value))),
+      None => Call::Ok(VALUE_NIL),
+    }
+  }
+}
+
+native_with_error!(SetEnv, SET_ENV_META);
+
+impl LyNative for SetEnv {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let name = args[0].to_obj().to_str();
+    let value = args[1].to_obj().to_str();
+
+    match hooks.as_io().env().set_var(&name, &value) {
+      Ok(()) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+native!(Platform, PLATFORM_META);
+
+impl LyNative for Platform {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    let env = hooks.as_io().env();
+    Call::Ok(val!(hooks.manage_str(env.platform())))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  mod env {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let env = Env::native(&hooks);
+
+      assert_eq!(env.meta().name, "env");
+      assert_eq!(env.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        env.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let env = Env::native(&hooks.as_gc());
+
+      let name = val!(hooks.manage_str("LAYTHE_MISSING"));
+      let result = env.call(&mut hooks, None, &[name]).unwrap();
+
+      assert!(result.is_nil());
+    }
+  }
+
+  mod platform {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let platform = Platform::native(&hooks);
+
+      assert_eq!(platform.meta().name, "platform");
+      assert_eq!(platform.meta().signature.arity, Arity::Fixed(0));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let platform = Platform::native(&hooks.as_gc());
+
+      let result = platform.call(&mut hooks, None, &[]).unwrap();
+      assert_eq!(result.to_obj().to_str(), "mock");
+    }
+  }
+}
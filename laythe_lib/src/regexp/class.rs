@@ -1,27 +1,30 @@
 use crate::{
   global::SYNTAX_ERROR_NAME,
-  native, native_with_error,
+  native_with_error,
   support::load_class_from_package,
   support::{default_class_inheritance, export_and_insert, load_class_from_module},
   StdResult, STD,
 };
 use laythe_core::{
   hooks::{GcHooks, Hooks},
-  managed::{GcObj, Trace},
+  managed::{GcObj, GcStr, Trace},
   module::{Module, Package},
-  object::{List, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  object::{
+    Enumerate, Enumerator, Instance, List, LyNative, Map, Native, NativeMetaBuilder, ObjectKind,
+  },
   signature::{Arity, ParameterBuilder, ParameterKind},
   val,
   value::Value,
   value::VALUE_NIL,
   Call,
 };
-use regex::Regex;
-use std::io::Write;
+use regex::{CaptureLocations, Regex, RegexBuilder};
+use std::{any::Any, io::Write, mem};
 
 const REGEXP_CLASS_NAME: &str = "RegExp";
 const REGEXP_FIELD_PATTERN: &str = "pattern";
 const REGEXP_FIELD_FLAGS: &str = "flags";
+const REGEXP_FIELD_COMPILED: &str = "compiled";
 
 const REGEXP_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(1, 2))
   .with_params(&[
@@ -35,9 +38,34 @@ const REGEXP_TEST: NativeMetaBuilder = NativeMetaBuilder::method("test", Arity::
 const REGEXP_MATCH: NativeMetaBuilder = NativeMetaBuilder::method("match", Arity::Fixed(1))
   .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
 
+const REGEXP_MATCH_ALL: NativeMetaBuilder = NativeMetaBuilder::method("matchAll", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
 const REGEXP_CAPTURES: NativeMetaBuilder = NativeMetaBuilder::method("captures", Arity::Fixed(1))
   .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
 
+const REGEXP_NAMED_CAPTURES: NativeMetaBuilder =
+  NativeMetaBuilder::method("namedCaptures", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
+const REGEXP_REPLACE: NativeMetaBuilder = NativeMetaBuilder::method("replace", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("string", ParameterKind::String),
+    ParameterBuilder::new("replacement", ParameterKind::String),
+  ]);
+
+const REGEXP_REPLACE_ALL: NativeMetaBuilder =
+  NativeMetaBuilder::method("replaceAll", Arity::Fixed(2)).with_params(&[
+    ParameterBuilder::new("string", ParameterKind::String),
+    ParameterBuilder::new("replacement", ParameterKind::String),
+  ]);
+
+const REGEXP_SPLIT: NativeMetaBuilder = NativeMetaBuilder::method("split", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
+const REGEXP_COMPILED: NativeMetaBuilder = NativeMetaBuilder::fun("compiled", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("string", ParameterKind::String)]);
+
 pub fn declare_regexp_class(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
   let class = default_class_inheritance(hooks, std, REGEXP_CLASS_NAME)?;
   export_and_insert(hooks, module, class.name(), val!(class))
@@ -49,11 +77,12 @@ pub fn define_regexp_class(hooks: &GcHooks, module: &Module, std: &Package) -> S
 
   class.add_field(hooks, hooks.manage_str(REGEXP_FIELD_PATTERN));
   class.add_field(hooks, hooks.manage_str(REGEXP_FIELD_FLAGS));
+  class.add_field(hooks, hooks.manage_str(REGEXP_FIELD_COMPILED));
 
   class.add_method(
     hooks,
     hooks.manage_str(REGEXP_INIT.name),
-    val!(RegExpInit::native(hooks)),
+    val!(RegExpInit::native(hooks, syntax_error)),
   );
 
   class.add_method(
@@ -68,40 +97,169 @@ pub fn define_regexp_class(hooks: &GcHooks, module: &Module, std: &Package) -> S
     val!(RegExpMatch::native(hooks, syntax_error)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(REGEXP_MATCH_ALL.name),
+    val!(RegExpMatchAll::native(hooks, syntax_error)),
+  );
+
   class.add_method(
     hooks,
     hooks.manage_str(REGEXP_CAPTURES.name),
     val!(RegExpCaptures::native(hooks, syntax_error)),
   );
 
+  class.add_method(
+    hooks,
+    hooks.manage_str(REGEXP_NAMED_CAPTURES.name),
+    val!(RegExpNamedCaptures::native(hooks, syntax_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(REGEXP_REPLACE.name),
+    val!(RegExpReplace::native(hooks, syntax_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(REGEXP_REPLACE_ALL.name),
+    val!(RegExpReplaceAll::native(hooks, syntax_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(REGEXP_SPLIT.name),
+    val!(RegExpSplit::native(hooks, syntax_error)),
+  );
+
   Ok(())
 }
 
-native!(RegExpInit, REGEXP_INIT);
+/// Compile a pattern with the provided flags. `i` ignores case, `m` matches
+/// `^` and `$` at line boundaries, `s` lets `.` match new lines and `x`
+/// ignores whitespace and allows comments in the pattern
+fn compile(pattern: &str, flags: &str) -> Result<Regex, String> {
+  let mut builder = RegexBuilder::new(pattern);
+
+  for flag in flags.chars() {
+    match flag {
+      'i' => builder.case_insensitive(true),
+      'm' => builder.multi_line(true),
+      's' => builder.dot_matches_new_line(true),
+      'x' => builder.ignore_whitespace(true),
+      _ => return Err(format!("Unknown RegExp flag {}.", flag)),
+    };
+  }
+
+  builder.build().map_err(|err| err.to_string())
+}
+
+/// The pattern an instance was compiled from. The compiled pattern is
+/// cached in the instance's compiled field and is only compiled again if
+/// that field has been replaced
+fn instance_regex(instance: GcObj<Instance>) -> Result<Regex, String> {
+  let compiled = instance[2];
+  if compiled.is_obj_kind(ObjectKind::Native) {
+    let native = compiled.to_obj().to_native();
+    if let Some(compiled) = native.downcast_ref::<RegExpCompiled>() {
+      return Ok(compiled.regex.clone());
+    }
+  }
+
+  let pattern = instance[0];
+  if !pattern.is_obj_kind(ObjectKind::String) {
+    return Err("RegExp pattern must be a string.".to_string());
+  }
+
+  let flags = instance[1];
+  if flags.is_obj_kind(ObjectKind::String) {
+    compile(&pattern.to_obj().to_str(), &flags.to_obj().to_str())
+  } else {
+    compile(&pattern.to_obj().to_str(), "")
+  }
+}
+
+/// A compiled pattern cached in a RegExp instance. Calling it tests if a
+/// string matches
+#[derive(Debug)]
+pub struct RegExpCompiled {
+  regex: Regex,
+}
+
+impl RegExpCompiled {
+  fn native(hooks: &GcHooks, regex: Regex) -> GcObj<Native> {
+    let native = Box::new(Self { regex }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(REGEXP_COMPILED.to_meta(hooks), native))
+  }
+}
+
+impl Trace for RegExpCompiled {}
+
+impl LyNative for RegExpCompiled {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    Call::Ok(val!(self.regex.is_match(&args[0].to_obj().to_str())))
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+native_with_error!(RegExpInit, REGEXP_INIT);
 
 impl LyNative for RegExpInit {
-  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
     let mut this = this.unwrap().to_obj().to_instance();
     this[0] = args[0];
     if args.len() > 1 {
       this[1] = args[1];
     }
 
-    Call::Ok(val!(this))
+    let flags = if args.len() > 1 {
+      args[1].to_obj().to_str()
+    } else {
+      hooks.manage_str("")
+    };
+
+    match compile(&args[0].to_obj().to_str(), &flags) {
+      Ok(regex) => {
+        this[2] = val!(RegExpCompiled::native(&hooks.as_gc(), regex));
+        Call::Ok(val!(this))
+      },
+      Err(err) => self.call_error(hooks, err),
+    }
   }
 }
 
 macro_rules! get_regex {
   ( $self:ident, $this:ident, $hooks:ident ) => {{
-    let instance = $this.unwrap().to_obj().to_instance();
-
-    match Regex::new(&*instance[0].to_obj().to_str()) {
+    match instance_regex($this.unwrap().to_obj().to_instance()) {
       Ok(regexp) => regexp,
-      Err(err) => return $self.call_error($hooks, err.to_string()),
+      Err(err) => return $self.call_error($hooks, err),
     }
   }};
 }
 
+/// The groups of a capture as a list, with nil for groups that didn't
+/// participate in the match
+fn capture_list(hooks: &mut Hooks, string: &str, locations: &CaptureLocations) -> Value {
+  let mut results: GcObj<List<Value>> = hooks.manage_obj(List::new());
+  hooks.push_root(results);
+
+  for index in 0..locations.len() {
+    let capture = match locations.get(index) {
+      Some((start, end)) => val!(hooks.manage_str(&string[start..end])),
+      None => VALUE_NIL,
+    };
+
+    hooks.grow(&mut results, |results| results.push(capture));
+  }
+
+  hooks.pop_roots(1);
+  val!(results)
+}
+
 native_with_error!(RegExpTest, REGEXP_TEST);
 
 impl LyNative for RegExpTest {
@@ -125,44 +283,234 @@ impl LyNative for RegExpMatch {
   }
 }
 
+native_with_error!(RegExpMatchAll, REGEXP_MATCH_ALL);
+
+impl LyNative for RegExpMatchAll {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let regexp = get_regex!(self, this, hooks);
+
+    let inner_iter: Box<dyn Enumerate> =
+      Box::new(MatchIterator::new(regexp, args[0].to_obj().to_str()));
+    let iter = Enumerator::new(inner_iter);
+
+    Call::Ok(val!(hooks.manage_obj(iter)))
+  }
+}
+
 native_with_error!(RegExpCaptures, REGEXP_CAPTURES);
 
 impl LyNative for RegExpCaptures {
   fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
     let regexp = get_regex!(self, this, hooks);
+    let string = args[0].to_obj().to_str();
 
-    match regexp.captures(&args[0].to_obj().to_str()) {
-      Some(captures) => {
-        let mut results: GcObj<List<Value>> = hooks.manage_obj(List::new());
-        hooks.push_root(results);
+    let mut locations = regexp.capture_locations();
+    match regexp.captures_read(&mut locations, &string) {
+      Some(_) => Call::Ok(capture_list(hooks, &string, &locations)),
+      None => Call::Ok(VALUE_NIL),
+    }
+  }
+}
 
-        for capture in captures.iter().map(|sub_capture| match sub_capture {
-          Some(sub_capture) => val!(hooks.manage_str(sub_capture.as_str())),
-          None => VALUE_NIL,
-        }) {
-          results.push(capture);
-        }
+native_with_error!(RegExpNamedCaptures, REGEXP_NAMED_CAPTURES);
 
-        hooks.pop_roots(1);
-        Call::Ok(val!(results))
-      },
-      None => Call::Ok(VALUE_NIL),
+impl LyNative for RegExpNamedCaptures {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let regexp = get_regex!(self, this, hooks);
+    let string = args[0].to_obj().to_str();
+
+    let captures = match regexp.captures(&string) {
+      Some(captures) => captures,
+      None => return Call::Ok(VALUE_NIL),
+    };
+
+    let mut results: GcObj<Map<Value, Value>> = hooks.manage_obj(Map::default());
+    hooks.push_root(results);
+
+    for name in regexp.capture_names().flatten() {
+      let key = val!(hooks.manage_str(name));
+      hooks.push_root(key);
+
+      let capture = match captures.name(name) {
+        Some(capture) => val!(hooks.manage_str(capture.as_str())),
+        None => VALUE_NIL,
+      };
+
+      hooks.grow(&mut results, |results| results.insert(key, capture));
+      hooks.pop_roots(1);
     }
+
+    hooks.pop_roots(1);
+    Call::Ok(val!(results))
+  }
+}
+
+native_with_error!(RegExpReplace, REGEXP_REPLACE);
+
+impl LyNative for RegExpReplace {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let regexp = get_regex!(self, this, hooks);
+    let string = args[0].to_obj().to_str();
+    let replacement = args[1].to_obj().to_str();
+
+    let replaced = regexp.replace(&string, &*replacement);
+    Call::Ok(val!(hooks.manage_str(replaced)))
+  }
+}
+
+native_with_error!(RegExpReplaceAll, REGEXP_REPLACE_ALL);
+
+impl LyNative for RegExpReplaceAll {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let regexp = get_regex!(self, this, hooks);
+    let string = args[0].to_obj().to_str();
+    let replacement = args[1].to_obj().to_str();
+
+    let replaced = regexp.replace_all(&string, &*replacement);
+    Call::Ok(val!(hooks.manage_str(replaced)))
+  }
+}
+
+native_with_error!(RegExpSplit, REGEXP_SPLIT);
+
+impl LyNative for RegExpSplit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let regexp = get_regex!(self, this, hooks);
+    let string = args[0].to_obj().to_str();
+
+    let mut results: GcObj<List<Value>> = hooks.manage_obj(List::new());
+    hooks.push_root(results);
+
+    for part in regexp.split(&string) {
+      let part = val!(hooks.manage_str(part));
+      hooks.grow(&mut results, |results| results.push(part));
+    }
+
+    hooks.pop_roots(1);
+    Call::Ok(val!(results))
+  }
+}
+
+/// Iterate over each non overlapping match in a string, producing the
+/// captures of each match
+#[derive(Debug)]
+struct MatchIterator {
+  regex: Regex,
+  string: GcStr,
+  locations: CaptureLocations,
+  start: usize,
+  last_end: Option<usize>,
+  current: Value,
+}
+
+impl MatchIterator {
+  fn new(regex: Regex, string: GcStr) -> Self {
+    let locations = regex.capture_locations();
+
+    Self {
+      regex,
+      string,
+      locations,
+      start: 0,
+      last_end: None,
+      current: VALUE_NIL,
+    }
+  }
+
+  /// The index of the character after the provided index
+  fn next_char(&self, index: usize) -> usize {
+    index
+      + self.string[index..]
+        .chars()
+        .next()
+        .map_or(1, |next| next.len_utf8())
+  }
+}
+
+impl Enumerate for MatchIterator {
+  fn name(&self) -> &str {
+    "MatchIterator"
+  }
+
+  fn current(&self) -> Value {
+    self.current
+  }
+
+  fn next(&mut self, hooks: &mut Hooks) -> Call {
+    let string = self.string;
+
+    while self.start <= string.len() {
+      let found = match self
+        .regex
+        .captures_read_at(&mut self.locations, &string, self.start)
+      {
+        Some(found) => found,
+        None => break,
+      };
+
+      // like the regex crate skip an empty match directly after the last
+      if found.start() == found.end() && Some(found.end()) == self.last_end {
+        self.start = self.next_char(found.end());
+        continue;
+      }
+
+      self.start = if found.start() == found.end() {
+        self.next_char(found.end())
+      } else {
+        found.end()
+      };
+      self.last_end = Some(found.end());
+
+      self.current = capture_list(hooks, &string, &self.locations);
+      return Call::Ok(val!(true));
+    }
+
+    self.start = string.len() + 1;
+    self.current = VALUE_NIL;
+    Call::Ok(val!(false))
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    None
+  }
+
+  fn size(&self) -> usize {
+    mem::size_of::<Self>()
+  }
+}
+
+impl Trace for MatchIterator {
+  fn trace(&self) {
+    self.string.trace();
+    self.current.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.string.visit_refs(visitor);
+    self.current.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.string.trace_debug(stdout);
+    self.current.trace_debug(stdout);
   }
 }
 
 #[cfg(test)]
 mod test {
   use super::*;
-  use laythe_core::object::{Class, Instance};
+  use crate::support::test_error_class;
+  use laythe_core::object::Class;
 
   fn regexp_instance(hooks: &mut Hooks, pattern: &str) -> Value {
     let mut regexp_class = Class::bare(hooks.manage_str(REGEXP_CLASS_NAME));
     regexp_class.add_field(&hooks.as_gc(), hooks.manage_str(REGEXP_FIELD_PATTERN));
     regexp_class.add_field(&hooks.as_gc(), hooks.manage_str(REGEXP_FIELD_FLAGS));
+    regexp_class.add_field(&hooks.as_gc(), hooks.manage_str(REGEXP_FIELD_COMPILED));
 
     let regexp = hooks.manage_obj(Instance::new(hooks.manage_obj(regexp_class)));
-    let init = RegExpInit::native(&hooks.as_gc());
+    let error = val!(test_error_class(&hooks.as_gc()));
+    let init = RegExpInit::native(&hooks.as_gc(), error);
     init
       .call(
         hooks,
@@ -313,4 +661,94 @@ mod test {
       assert_eq!(list[1].to_obj().to_str(), hooks.manage_str("123"));
     }
   }
+
+  mod split {
+    use laythe_core::hooks::GcHooks;
+
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let error = val!(test_error_class(&hooks));
+      let regexp_split = RegExpSplit::native(&hooks, error);
+
+      assert_eq!(regexp_split.meta().name, "split");
+      assert_eq!(regexp_split.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        regexp_split.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let this = regexp_instance(&mut hooks, "[,;] *");
+      let regexp_split = RegExpSplit::native(&hooks.as_gc(), error);
+
+      let example = val!(hooks.manage_str("a, b;c"));
+
+      let r = regexp_split
+        .call(&mut hooks, Some(this), &[example])
+        .unwrap();
+
+      assert!(r.is_obj_kind(ObjectKind::List));
+      let list = r.to_obj().to_list();
+
+      assert_eq!(list.len(), 3);
+      assert_eq!(list[0].to_obj().to_str(), hooks.manage_str("a"));
+      assert_eq!(list[1].to_obj().to_str(), hooks.manage_str("b"));
+      assert_eq!(list[2].to_obj().to_str(), hooks.manage_str("c"));
+    }
+  }
+
+  mod named_captures {
+    use laythe_core::hooks::GcHooks;
+
+    use super::*;
+    use crate::support::{test_error_class, MockedContext};
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let error = val!(test_error_class(&hooks));
+      let regexp_named = RegExpNamedCaptures::native(&hooks, error);
+
+      assert_eq!(regexp_named.meta().name, "namedCaptures");
+      assert_eq!(regexp_named.meta().signature.arity, Arity::Fixed(1));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let this = regexp_instance(&mut hooks, "(?P<area>[0-9]{3})-(?P<ext>x[0-9]+)?");
+      let regexp_named = RegExpNamedCaptures::native(&hooks.as_gc(), error);
+
+      let example = val!(hooks.manage_str("call 555-"));
+
+      let r = regexp_named
+        .call(&mut hooks, Some(this), &[example])
+        .unwrap();
+
+      assert!(r.is_obj_kind(ObjectKind::Map));
+      let map = r.to_obj().to_map();
+
+      assert_eq!(map.len(), 2);
+      let area = map.get(&val!(hooks.manage_str("area"))).unwrap();
+      assert_eq!(area.to_obj().to_str(), hooks.manage_str("555"));
+      assert!(map.get(&val!(hooks.manage_str("ext"))).unwrap().is_nil());
+    }
+  }
 }
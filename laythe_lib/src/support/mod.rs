@@ -126,29 +126,41 @@ mod test {
     create_std_lib, native,
   };
   use laythe_core::{
-    hooks::{GcContext, GcHooks, HookContext, Hooks, ValueContext},
+    hooks::{GcContext, GcHooks, HookContext, Hooks, HostData, ValueContext},
     managed::{GcObj, GcObject, GcStr, Trace, TraceRoot},
     match_obj,
     memory::{Allocator, NoGc},
     module::{Module, ModuleResult},
-    object::{Class, Enumerate, Fun, FunBuilder, List, LyNative, Native, NativeMetaBuilder},
+    object::{
+      Channel, Class, Enumerate, Fiber, Fun, FunBuilder, List, LyNative, Native, NativeMetaBuilder,
+    },
     signature::Arity,
     signature::{ParameterBuilder, ParameterKind},
     to_obj_kind,
     utils::IdEmitter,
     val,
     value::{Value, VALUE_NIL},
+    worker::{Message, PoolJob, WorkerHandle, WorkerPort},
     Call,
   };
   use laythe_env::{
     io::Io,
+    signal::ProcessSignal,
     stdio::support::{IoStdioTest, StdioTestContainer},
   };
-  use std::{cell::RefCell, io::Write, path::PathBuf, sync::Arc};
+  use std::{
+    cell::RefCell,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+  };
 
   pub struct MockedContext {
     pub gc: RefCell<Allocator>,
     pub responses: Vec<Value>,
+    pub exit_hooks: Vec<Value>,
+    pub signal_handlers: Vec<(ProcessSignal, Value)>,
     io: Io,
     no_gc: NoGc,
     builtin: Option<BuiltIn>,
@@ -161,6 +173,8 @@ mod test {
         gc: RefCell::default(),
         no_gc: NoGc(),
         responses: vec![],
+        exit_hooks: vec![],
+        signal_handlers: vec![],
         io: Io::default(),
         builtin: None,
         response_count: 0,
@@ -174,6 +188,8 @@ mod test {
         gc: RefCell::default(),
         no_gc: NoGc(),
         responses: Vec::from(responses),
+        exit_hooks: vec![],
+        signal_handlers: vec![],
         io: Io::default(),
         builtin: None,
         response_count: 0,
@@ -185,6 +201,8 @@ mod test {
         gc: RefCell::default(),
         no_gc: NoGc(),
         responses: Vec::from(responses),
+        exit_hooks: vec![],
+        signal_handlers: vec![],
         io: Io::default(),
         builtin: None,
         response_count: 0,
@@ -205,6 +223,8 @@ mod test {
         gc: RefCell::default(),
         no_gc: NoGc(),
         responses: Vec::from(vec![]),
+        exit_hooks: vec![],
+        signal_handlers: vec![],
         io: Io::default().with_stdio(Arc::new(IoStdioTest::new(stdio_container))),
         builtin: None,
         response_count: 0,
@@ -228,6 +248,10 @@ mod test {
     fn io(&mut self) -> Io {
       self.io.clone()
     }
+
+    fn host_data(&mut self) -> Option<&mut dyn HostData> {
+      None
+    }
   }
 
   impl GcContext for MockedContext {
@@ -326,6 +350,58 @@ mod test {
 
       val!(b.primitives.for_value(this, &[]))
     }
+
+    fn current_fiber(&mut self) -> GcObj<Fiber> {
+      GcObj::dangling()
+    }
+
+    fn wake_fiber(&mut self, _fiber: GcObj<Fiber>) -> bool {
+      true
+    }
+
+    fn spawn_fiber(&mut self, _fiber: GcObj<Fiber>) {}
+
+    fn cancel_fiber(&mut self, _fiber: GcObj<Fiber>, _error: GcObj<Instance>) -> bool {
+      false
+    }
+
+    fn sleep_fiber(&mut self, _duration: Duration) -> bool {
+      false
+    }
+
+    fn clear_sleep(&mut self) {}
+
+    fn add_timer(&mut self, _channel: GcObj<Channel>, _duration: Duration, _repeat: bool) {}
+
+    fn await_input(&mut self) -> bool {
+      false
+    }
+
+    fn add_exit_hook(&mut self, hook: Value) {
+      self.exit_hooks.push(hook);
+    }
+
+    fn add_signal_handler(&mut self, signal: ProcessSignal, handler: Value) -> Result<(), String> {
+      self.signal_handlers.push((signal, handler));
+      Ok(())
+    }
+
+    fn spawn_worker(&mut self, _path: &Path) -> Result<WorkerHandle, String> {
+      Err("Workers are not supported in this context.".to_string())
+    }
+
+    fn worker_port(&mut self) -> Option<&WorkerPort> {
+      None
+    }
+
+    fn run_in_pool(
+      &mut self,
+      _fun: Value,
+      _items: Vec<Message>,
+      _collect: bool,
+    ) -> Result<PoolJob, String> {
+      Err("Workers are not supported in this context.".to_string())
+    }
   }
 
   impl TraceRoot for MockedContext {
@@ -0,0 +1,234 @@
+use crate::{
+  create_error,
+  global::RUNTIME_ERROR_NAME,
+  native,
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Instance, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{any::Any, cell::Cell, io::Write};
+
+const ATOMIC_CLASS_NAME: &str = "Atomic";
+
+const ATOMIC_FIELD_STATE: &str = "state";
+
+const ATOMIC_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("value", ParameterKind::Number)]);
+
+const ATOMIC_GET: NativeMetaBuilder = NativeMetaBuilder::method("get", Arity::Fixed(0));
+
+const ATOMIC_SET: NativeMetaBuilder = NativeMetaBuilder::method("set", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("value", ParameterKind::Number)]);
+
+const ATOMIC_ADD: NativeMetaBuilder = NativeMetaBuilder::method("add", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("amount", ParameterKind::Number)]);
+
+const ATOMIC_SUB: NativeMetaBuilder = NativeMetaBuilder::method("sub", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("amount", ParameterKind::Number)]);
+
+const ATOMIC_SWAP: NativeMetaBuilder = NativeMetaBuilder::method("swap", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("value", ParameterKind::Number)]);
+
+const ATOMIC_COMPARE_AND_SWAP: NativeMetaBuilder =
+  NativeMetaBuilder::method("compareAndSwap", Arity::Fixed(2)).with_params(&[
+    ParameterBuilder::new("expected", ParameterKind::Number),
+    ParameterBuilder::new("value", ParameterKind::Number),
+  ]);
+
+const ATOMIC_STATE: NativeMetaBuilder = NativeMetaBuilder::fun("state", Arity::Fixed(0));
+
+/// An update to an atomic's number returning the method's result
+type Update = fn(&Cell<f64>, &[Value]) -> Value;
+
+pub fn declare_atomic_class(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let class = default_class_inheritance(hooks, std, ATOMIC_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_atomic_class(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, ATOMIC_CLASS_NAME)?;
+  let runtime_error = val!(load_class_from_package(
+    hooks,
+    std,
+    STD,
+    RUNTIME_ERROR_NAME
+  )?);
+
+  class.add_field(hooks, hooks.manage_str(ATOMIC_FIELD_STATE));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(ATOMIC_INIT.name),
+    val!(AtomicInit::native(hooks)),
+  );
+
+  let updates: [(&NativeMetaBuilder, Update); 6] = [
+    (&ATOMIC_GET, |value, _| val!(value.get())),
+    (&ATOMIC_SET, |value, args| {
+      value.set(args[0].to_num());
+      VALUE_NIL
+    }),
+    (&ATOMIC_ADD, |value, args| {
+      value.set(value.get() + amount(args));
+      val!(value.get())
+    }),
+    (&ATOMIC_SUB, |value, args| {
+      value.set(value.get() - amount(args));
+      val!(value.get())
+    }),
+    (&ATOMIC_SWAP, |value, args| {
+      val!(value.replace(args[0].to_num()))
+    }),
+    (&ATOMIC_COMPARE_AND_SWAP, |value, args| {
+      let swapped = value.get() == args[0].to_num();
+      if swapped {
+        value.set(args[1].to_num());
+      }
+      val!(swapped)
+    }),
+  ];
+
+  for (meta, update) in updates.iter() {
+    class.add_method(
+      hooks,
+      hooks.manage_str(meta.name),
+      val!(AtomicUpdate::native(hooks, meta, *update, runtime_error)),
+    );
+  }
+
+  Ok(())
+}
+
+/// The amount to add or subtract, one by default
+fn amount(args: &[Value]) -> f64 {
+  args.first().map(|amount| amount.to_num()).unwrap_or(1.0)
+}
+
+/// The number held by an Atomic instance
+#[derive(Debug)]
+pub struct AtomicState {
+  value: Cell<f64>,
+}
+
+impl AtomicState {
+  fn native(hooks: &GcHooks, value: f64) -> GcObj<Native> {
+    let native = Box::new(Self {
+      value: Cell::new(value),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(ATOMIC_STATE.to_meta(hooks), native))
+  }
+}
+
+impl Trace for AtomicState {}
+
+impl LyNative for AtomicState {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+/// Run an action against the number held by an instance
+fn with_atomic<R>(instance: GcObj<Instance>, action: impl FnOnce(&Cell<f64>) -> R) -> Option<R> {
+  let state = instance[0];
+  if !state.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  let native = state.to_obj().to_native();
+  native
+    .downcast_ref::<AtomicState>()
+    .map(|state| action(&state.value))
+}
+
+native!(AtomicInit, ATOMIC_INIT);
+
+impl LyNative for AtomicInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+    let value = args.first().map(|value| value.to_num()).unwrap_or(0.0);
+
+    this[0] = val!(AtomicState::native(&hooks.as_gc(), value));
+    Call::Ok(val!(this))
+  }
+}
+
+/// Read or update the number held by an Atomic. As fibers only switch
+/// between calls each update is seen whole by every other fiber
+#[derive(Debug)]
+pub struct AtomicUpdate {
+  update: Update,
+  error: Value,
+}
+
+impl AtomicUpdate {
+  fn native(
+    hooks: &GcHooks,
+    meta: &NativeMetaBuilder,
+    update: Update,
+    error: Value,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self { update, error }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+}
+
+impl Trace for AtomicUpdate {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for AtomicUpdate {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match with_atomic(this, |value| (self.update)(value, args)) {
+      Some(result) => Call::Ok(result),
+      None => create_error!(self.error, hooks, "Atomic was not initialized."),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::MockedContext;
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let atomic_init = AtomicInit::native(&hooks);
+    assert_eq!(atomic_init.meta().name, "init");
+    assert_eq!(atomic_init.meta().signature.arity, Arity::Default(0, 1));
+    assert_eq!(
+      atomic_init.meta().signature.parameters[0].kind,
+      ParameterKind::Number
+    );
+  }
+}
@@ -0,0 +1,515 @@
+use crate::{
+  create_error,
+  global::RUNTIME_ERROR_NAME,
+  native, native_with_error,
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, Fiber, FiberState, Instance, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{
+  any::Any,
+  cell::{Cell, RefCell},
+  collections::VecDeque,
+  io::Write,
+};
+
+const MUTEX_CLASS_NAME: &str = "Mutex";
+const RW_LOCK_CLASS_NAME: &str = "RwLock";
+const LOCK_GUARD_CLASS_NAME: &str = "LockGuard";
+
+const LOCK_FIELD_VALUE: &str = "value";
+const LOCK_FIELD_STATE: &str = "state";
+
+const GUARD_FIELD_LOCK: &str = "lock";
+const GUARD_FIELD_STATE: &str = "state";
+
+const LOCK_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("value", ParameterKind::Any)]);
+
+const MUTEX_LOCK: NativeMetaBuilder = NativeMetaBuilder::method("lock", Arity::Fixed(0));
+const MUTEX_TRY_LOCK: NativeMetaBuilder = NativeMetaBuilder::method("tryLock", Arity::Fixed(0));
+
+const RW_LOCK_READ: NativeMetaBuilder = NativeMetaBuilder::method("read", Arity::Fixed(0));
+const RW_LOCK_WRITE: NativeMetaBuilder = NativeMetaBuilder::method("write", Arity::Fixed(0));
+const RW_LOCK_TRY_READ: NativeMetaBuilder = NativeMetaBuilder::method("tryRead", Arity::Fixed(0));
+const RW_LOCK_TRY_WRITE: NativeMetaBuilder = NativeMetaBuilder::method("tryWrite", Arity::Fixed(0));
+
+const LOCK_IS_LOCKED: NativeMetaBuilder = NativeMetaBuilder::method("isLocked", Arity::Fixed(0));
+
+const GUARD_ENTER: NativeMetaBuilder = NativeMetaBuilder::method("enter", Arity::Fixed(0));
+
+const GUARD_EXIT: NativeMetaBuilder = NativeMetaBuilder::method("exit", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("error", ParameterKind::Any)]);
+
+const GUARD_UNLOCK: NativeMetaBuilder = NativeMetaBuilder::method("unlock", Arity::Fixed(0));
+
+const LOCK_STATE: NativeMetaBuilder = NativeMetaBuilder::fun("state", Arity::Fixed(0));
+const GUARD_STATE: NativeMetaBuilder = NativeMetaBuilder::fun("state", Arity::Fixed(0));
+
+pub fn declare_lock_classes(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let mutex = default_class_inheritance(hooks, std, MUTEX_CLASS_NAME)?;
+  export_and_insert(hooks, module, mutex.name(), val!(mutex))?;
+
+  let rw_lock = default_class_inheritance(hooks, std, RW_LOCK_CLASS_NAME)?;
+  export_and_insert(hooks, module, rw_lock.name(), val!(rw_lock))?;
+
+  let guard = default_class_inheritance(hooks, std, LOCK_GUARD_CLASS_NAME)?;
+  export_and_insert(hooks, module, guard.name(), val!(guard))
+}
+
+pub fn define_lock_classes(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let mutex = load_class_from_module(hooks, module, MUTEX_CLASS_NAME)?;
+  let rw_lock = load_class_from_module(hooks, module, RW_LOCK_CLASS_NAME)?;
+  let mut guard = load_class_from_module(hooks, module, LOCK_GUARD_CLASS_NAME)?;
+  let runtime_error = val!(load_class_from_package(
+    hooks,
+    std,
+    STD,
+    RUNTIME_ERROR_NAME
+  )?);
+
+  for mut class in [mutex, rw_lock].iter().copied() {
+    class.add_field(hooks, hooks.manage_str(LOCK_FIELD_VALUE));
+    class.add_field(hooks, hooks.manage_str(LOCK_FIELD_STATE));
+
+    class.add_method(
+      hooks,
+      hooks.manage_str(LOCK_INIT.name),
+      val!(LockInit::native(hooks)),
+    );
+
+    class.add_method(
+      hooks,
+      hooks.manage_str(LOCK_IS_LOCKED.name),
+      val!(LockIsLocked::native(hooks, runtime_error)),
+    );
+  }
+
+  let acquires = [
+    (mutex, &MUTEX_LOCK, Mode::Write, true),
+    (mutex, &MUTEX_TRY_LOCK, Mode::Write, false),
+    (rw_lock, &RW_LOCK_READ, Mode::Read, true),
+    (rw_lock, &RW_LOCK_WRITE, Mode::Write, true),
+    (rw_lock, &RW_LOCK_TRY_READ, Mode::Read, false),
+    (rw_lock, &RW_LOCK_TRY_WRITE, Mode::Write, false),
+  ];
+
+  for (mut class, meta, mode, wait) in acquires.iter().copied() {
+    class.add_method(
+      hooks,
+      hooks.manage_str(meta.name),
+      val!(LockAcquire::native(
+        hooks,
+        meta,
+        Acquire { mode, wait },
+        guard,
+        runtime_error
+      )),
+    );
+  }
+
+  guard.add_field(hooks, hooks.manage_str(GUARD_FIELD_LOCK));
+  guard.add_field(hooks, hooks.manage_str(GUARD_FIELD_STATE));
+
+  guard.add_method(
+    hooks,
+    hooks.manage_str(GUARD_ENTER.name),
+    val!(GuardEnter::native(hooks)),
+  );
+
+  guard.add_method(
+    hooks,
+    hooks.manage_str(GUARD_EXIT.name),
+    val!(GuardExit::native(hooks)),
+  );
+
+  guard.add_method(
+    hooks,
+    hooks.manage_str(GUARD_UNLOCK.name),
+    val!(GuardUnlock::native(hooks)),
+  );
+
+  Ok(())
+}
+
+/// How a guard holds a lock, shared with other readers or alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+  Read,
+  Write,
+}
+
+/// How a lock method acquires its lock
+#[derive(Debug, Clone, Copy)]
+struct Acquire {
+  mode: Mode,
+  wait: bool,
+}
+
+/// The outcome of trying to acquire a lock
+#[derive(Debug, PartialEq)]
+enum Acquired {
+  Held,
+  Waiting,
+  Busy,
+}
+
+/// The holders of a lock and the fibers parked waiting on it. A released
+/// lock is handed directly to the next waiters so a fiber arriving later
+/// can't take it first
+#[derive(Debug, Default)]
+struct Lock {
+  readers: usize,
+  writer: bool,
+  waiting: VecDeque<(GcObj<Fiber>, Mode)>,
+  granted: Vec<GcObj<Fiber>>,
+}
+
+impl Lock {
+  fn is_free(&self, mode: Mode) -> bool {
+    match mode {
+      Mode::Read => !self.writer,
+      Mode::Write => !self.writer && self.readers == 0,
+    }
+  }
+
+  fn hold(&mut self, mode: Mode) {
+    match mode {
+      Mode::Read => self.readers += 1,
+      Mode::Write => self.writer = true,
+    }
+  }
+
+  /// Acquire the lock for a fiber, which joins the waiters if it can wait
+  fn acquire(&mut self, fiber: GcObj<Fiber>, acquire: Acquire) -> Acquired {
+    if let Some(index) = self.granted.iter().position(|granted| *granted == fiber) {
+      self.granted.swap_remove(index);
+      return Acquired::Held;
+    }
+
+    if self.waiting.iter().any(|(waiter, _)| *waiter == fiber) {
+      return Acquired::Waiting;
+    }
+
+    if self.waiting.is_empty() && self.is_free(acquire.mode) {
+      self.hold(acquire.mode);
+      return Acquired::Held;
+    }
+
+    if !acquire.wait {
+      return Acquired::Busy;
+    }
+
+    self.waiting.push_back((fiber, acquire.mode));
+    Acquired::Waiting
+  }
+
+  /// Release a hold on the lock, returning the waiters it was handed to
+  fn release(&mut self, mode: Mode) -> Vec<GcObj<Fiber>> {
+    match mode {
+      Mode::Read => self.readers -= 1,
+      Mode::Write => self.writer = false,
+    }
+
+    let mut woken = vec![];
+    while let Some((fiber, mode)) = self.waiting.front().copied() {
+      // a waiter woken by cancellation no longer waits on the lock
+      if fiber.state() != FiberState::Blocked {
+        self.waiting.pop_front();
+        continue;
+      }
+
+      if !self.is_free(mode) {
+        break;
+      }
+
+      self.waiting.pop_front();
+      self.hold(mode);
+      self.granted.push(fiber);
+      woken.push(fiber);
+    }
+
+    woken
+  }
+
+  fn is_locked(&self) -> bool {
+    self.writer || self.readers > 0
+  }
+
+  fn for_each_fiber(&self, mut action: impl FnMut(&GcObj<Fiber>)) {
+    self.waiting.iter().for_each(|(fiber, _)| action(fiber));
+    self.granted.iter().for_each(action);
+  }
+}
+
+/// The state of a Mutex or RwLock instance. Nothing may be allocated
+/// while the lock is borrowed as tracing borrows it
+#[derive(Debug)]
+pub struct LockState {
+  lock: RefCell<Lock>,
+}
+
+impl LockState {
+  fn native(hooks: &GcHooks) -> GcObj<Native> {
+    let native = Box::new(Self {
+      lock: RefCell::new(Lock::default()),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(LOCK_STATE.to_meta(hooks), native))
+  }
+}
+
+impl Trace for LockState {
+  fn trace(&self) {
+    self.lock.borrow().for_each_fiber(|fiber| fiber.trace());
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self
+      .lock
+      .borrow()
+      .for_each_fiber(|fiber| fiber.visit_refs(visitor));
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self
+      .lock
+      .borrow()
+      .for_each_fiber(|fiber| fiber.trace_debug(stdio));
+  }
+}
+
+impl LyNative for LockState {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+/// Run an action against the lock held by a Mutex or RwLock instance
+fn with_lock<R>(instance: GcObj<Instance>, action: impl FnOnce(&mut Lock) -> R) -> Option<R> {
+  let state = instance[1];
+  if !state.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  let native = state.to_obj().to_native();
+  native
+    .downcast_ref::<LockState>()
+    .map(|state| action(&mut state.lock.borrow_mut()))
+}
+
+/// The hold a LockGuard instance has on its lock, None once released
+#[derive(Debug)]
+pub struct GuardState {
+  mode: Cell<Option<Mode>>,
+}
+
+impl GuardState {
+  fn native(hooks: &GcHooks, mode: Mode) -> GcObj<Native> {
+    let native = Box::new(Self {
+      mode: Cell::new(Some(mode)),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(GUARD_STATE.to_meta(hooks), native))
+  }
+}
+
+impl Trace for GuardState {}
+
+impl LyNative for GuardState {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+/// Release the lock held by a guard, waking the fibers it was handed to.
+/// Releasing a guard a second time does nothing
+fn release(hooks: &mut Hooks, guard: GcObj<Instance>) {
+  let state = guard[1];
+  if !state.is_obj_kind(ObjectKind::Native) || !guard[0].is_obj_kind(ObjectKind::Instance) {
+    return;
+  }
+
+  let native = state.to_obj().to_native();
+  let mode = match native.downcast_ref::<GuardState>() {
+    Some(state) => state.mode.take(),
+    None => return,
+  };
+
+  if let Some(mode) = mode {
+    let woken = with_lock(guard[0].to_obj().to_instance(), |lock| lock.release(mode));
+
+    for fiber in woken.unwrap_or_default() {
+      hooks.wake_fiber(fiber);
+    }
+  }
+}
+
+native!(LockInit, LOCK_INIT);
+
+impl LyNative for LockInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    this[0] = args.first().copied().unwrap_or(VALUE_NIL);
+    this[1] = val!(LockState::native(&hooks.as_gc()));
+
+    Call::Ok(val!(this))
+  }
+}
+
+/// Acquire a lock returning a LockGuard. Waiting fibers are parked until
+/// the lock is handed to them while the try methods return nil instead
+#[derive(Debug)]
+pub struct LockAcquire {
+  acquire: Acquire,
+  guard: GcObj<Class>,
+  error: Value,
+}
+
+impl LockAcquire {
+  fn native(
+    hooks: &GcHooks,
+    meta: &NativeMetaBuilder,
+    acquire: Acquire,
+    guard: GcObj<Class>,
+    error: Value,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self {
+      acquire,
+      guard,
+      error,
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+}
+
+impl Trace for LockAcquire {
+  fn trace(&self) {
+    self.guard.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.guard.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.guard.trace_debug(stdio);
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for LockAcquire {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+    let fiber = hooks.current_fiber();
+
+    match with_lock(this, |lock| lock.acquire(fiber, self.acquire)) {
+      Some(Acquired::Held) => (),
+      Some(Acquired::Waiting) => return Call::Block,
+      Some(Acquired::Busy) => return Call::Ok(VALUE_NIL),
+      None => return create_error!(self.error, hooks, "Lock was not initialized."),
+    }
+
+    let state = GuardState::native(&hooks.as_gc(), self.acquire.mode);
+    hooks.push_root(state);
+
+    let mut guard = hooks.manage_obj(Instance::new(self.guard));
+    guard[0] = val!(this);
+    guard[1] = val!(state);
+    hooks.pop_roots(1);
+
+    Call::Ok(val!(guard))
+  }
+}
+
+native_with_error!(LockIsLocked, LOCK_IS_LOCKED);
+
+impl LyNative for LockIsLocked {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match with_lock(this, |lock| lock.is_locked()) {
+      Some(locked) => Call::Ok(val!(locked)),
+      None => self.call_error(hooks, "Lock was not initialized."),
+    }
+  }
+}
+
+native!(GuardEnter, GUARD_ENTER);
+
+impl LyNative for GuardEnter {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let guard = this.unwrap().to_obj().to_instance();
+
+    if guard[0].is_obj_kind(ObjectKind::Instance) {
+      Call::Ok(guard[0].to_obj().to_instance()[0])
+    } else {
+      Call::Ok(VALUE_NIL)
+    }
+  }
+}
+
+native!(GuardExit, GUARD_EXIT);
+
+impl LyNative for GuardExit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    release(hooks, this.unwrap().to_obj().to_instance());
+    Call::Ok(VALUE_NIL)
+  }
+}
+
+native!(GuardUnlock, GUARD_UNLOCK);
+
+impl LyNative for GuardUnlock {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    release(hooks, this.unwrap().to_obj().to_instance());
+    Call::Ok(VALUE_NIL)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::MockedContext;
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let lock_init = LockInit::native(&hooks);
+    assert_eq!(lock_init.meta().name, "init");
+    assert_eq!(lock_init.meta().signature.arity, Arity::Default(0, 1));
+
+    let guard_exit = GuardExit::native(&hooks);
+    assert_eq!(guard_exit.meta().name, "exit");
+    assert_eq!(guard_exit.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(
+      guard_exit.meta().signature.parameters[0].kind,
+      ParameterKind::Any
+    );
+  }
+}
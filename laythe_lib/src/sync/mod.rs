@@ -0,0 +1,46 @@
+mod atomic;
+mod lock;
+mod task_group;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+};
+use std::path::PathBuf;
+
+use crate::{global::MODULE_CLASS_NAME, support::load_class_from_package, StdResult, STD};
+
+use self::{
+  atomic::{declare_atomic_class, define_atomic_class},
+  lock::{declare_lock_classes, define_lock_classes},
+  task_group::{declare_task_group_class, define_task_group_class},
+};
+
+const SYNC_PATH: &str = "std/sync";
+
+pub fn sync_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(SYNC_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  declare_lock_classes(hooks, &mut module, std)?;
+  declare_atomic_class(hooks, &mut module, std)?;
+  declare_task_group_class(hooks, &mut module, std)?;
+
+  define_lock_classes(hooks, &module, std)?;
+  define_atomic_class(hooks, &module, std)?;
+  define_task_group_class(hooks, &module, std)?;
+
+  Ok(module)
+}
@@ -0,0 +1,361 @@
+use crate::{
+  create_error,
+  global::{RUNTIME_ERROR_NAME, TYPE_ERROR_NAME},
+  native, native_with_error,
+  support::{
+    default_class_inheritance, export_and_insert, load_class_from_module, load_class_from_package,
+  },
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Fiber, FiberState, Instance, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{any::Any, cell::RefCell, io::Write};
+
+const TASK_GROUP_CLASS_NAME: &str = "TaskGroup";
+
+const TASK_GROUP_FIELD_STATE: &str = "state";
+
+const TASK_GROUP_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Fixed(0));
+
+const TASK_GROUP_SPAWN: NativeMetaBuilder = NativeMetaBuilder::method("spawn", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("fun", ParameterKind::Fun)]);
+
+const TASK_GROUP_WAIT: NativeMetaBuilder = NativeMetaBuilder::method("wait", Arity::Fixed(0));
+
+const TASK_GROUP_STATE: NativeMetaBuilder = NativeMetaBuilder::fun("state", Arity::Fixed(0));
+
+pub fn declare_task_group_class(
+  hooks: &GcHooks,
+  module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let class = default_class_inheritance(hooks, std, TASK_GROUP_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_task_group_class(hooks: &GcHooks, module: &Module, std: &Package) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, TASK_GROUP_CLASS_NAME)?;
+  let runtime_error = val!(load_class_from_package(
+    hooks,
+    std,
+    STD,
+    RUNTIME_ERROR_NAME
+  )?);
+  let type_error = val!(load_class_from_package(hooks, std, STD, TYPE_ERROR_NAME)?);
+
+  class.add_field(hooks, hooks.manage_str(TASK_GROUP_FIELD_STATE));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(TASK_GROUP_INIT.name),
+    val!(TaskGroupInit::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(TASK_GROUP_SPAWN.name),
+    val!(TaskGroupSpawn::native(hooks, type_error, runtime_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(TASK_GROUP_WAIT.name),
+    val!(TaskGroupWait::native(hooks, runtime_error)),
+  );
+
+  Ok(())
+}
+
+/// A fiber spawned by a task group and the fiber registered to be
+/// woken once it completes
+#[derive(Debug)]
+struct Child {
+  fiber: GcObj<Fiber>,
+  watcher: Option<GcObj<Fiber>>,
+}
+
+/// What a waiting fiber must do after the group's children were checked
+enum Progress {
+  /// Cancel these children then wait on the group again
+  Cancel(Vec<GcObj<Fiber>>),
+
+  /// Register to be woken by these children then block
+  Wait(Vec<GcObj<Fiber>>),
+
+  /// Every child completed, re-raising the first error if any failed
+  Done(Option<GcObj<Instance>>),
+}
+
+/// The children of a task group and the first error raised by one of them
+#[derive(Debug, Default)]
+struct Group {
+  children: Vec<Child>,
+  error: Option<GcObj<Instance>>,
+  cancelled: bool,
+}
+
+impl Group {
+  /// Drop completed children noting the first failure, then determine
+  /// how the waiting fiber proceeds
+  fn poll(&mut self, current: GcObj<Fiber>) -> Progress {
+    let error = &mut self.error;
+    self.children.retain(|child| match child.fiber.state() {
+      FiberState::Complete => false,
+      FiberState::Failed => {
+        if error.is_none() {
+          *error = child.fiber.error();
+        }
+        false
+      },
+      _ => true,
+    });
+
+    if self.children.is_empty() {
+      self.cancelled = false;
+      return Progress::Done(self.error.take());
+    }
+
+    if self.error.is_some() && !self.cancelled {
+      self.cancelled = true;
+      return Progress::Cancel(self.children.iter().map(|child| child.fiber).collect());
+    }
+
+    Progress::Wait(
+      self
+        .children
+        .iter_mut()
+        .filter(|child| child.watcher != Some(current))
+        .map(|child| {
+          child.watcher = Some(current);
+          child.fiber
+        })
+        .collect(),
+    )
+  }
+
+  fn for_each_obj(&self, mut action: impl FnMut(&dyn Trace)) {
+    for child in &self.children {
+      action(&child.fiber);
+      if let Some(watcher) = &child.watcher {
+        action(watcher);
+      }
+    }
+
+    if let Some(error) = &self.error {
+      action(error);
+    }
+  }
+}
+
+/// The state of a TaskGroup instance. Nothing may be allocated
+/// while the group is borrowed as tracing borrows it
+#[derive(Debug)]
+pub struct TaskGroupState {
+  group: RefCell<Group>,
+}
+
+impl TaskGroupState {
+  fn native(hooks: &GcHooks) -> GcObj<Native> {
+    let native = Box::new(Self {
+      group: RefCell::new(Group::default()),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(TASK_GROUP_STATE.to_meta(hooks), native))
+  }
+}
+
+impl Trace for TaskGroupState {
+  fn trace(&self) {
+    self.group.borrow().for_each_obj(|obj| obj.trace());
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self
+      .group
+      .borrow()
+      .for_each_obj(|obj| obj.visit_refs(visitor));
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self
+      .group
+      .borrow()
+      .for_each_obj(|obj| obj.trace_debug(stdio));
+  }
+}
+
+impl LyNative for TaskGroupState {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+/// Run an action against the group held by a TaskGroup instance
+fn with_group<R>(instance: GcObj<Instance>, action: impl FnOnce(&mut Group) -> R) -> Option<R> {
+  let state = instance[0];
+  if !state.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  let native = state.to_obj().to_native();
+  native
+    .downcast_ref::<TaskGroupState>()
+    .map(|state| action(&mut state.group.borrow_mut()))
+}
+
+native!(TaskGroupInit, TASK_GROUP_INIT);
+
+impl LyNative for TaskGroupInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+
+    this[0] = val!(TaskGroupState::native(&hooks.as_gc()));
+    Call::Ok(val!(this))
+  }
+}
+
+/// Spawn a fiber as a child of the group. The group supervises the
+/// child so its unhandled error is raised by wait rather than printed
+#[derive(Debug)]
+pub struct TaskGroupSpawn {
+  type_error: Value,
+  runtime_error: Value,
+}
+
+impl TaskGroupSpawn {
+  fn native(hooks: &GcHooks, type_error: Value, runtime_error: Value) -> GcObj<Native> {
+    let native = Box::new(Self {
+      type_error,
+      runtime_error,
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(TASK_GROUP_SPAWN.to_meta(hooks), native))
+  }
+}
+
+impl Trace for TaskGroupSpawn {
+  fn trace(&self) {
+    self.type_error.trace();
+    self.runtime_error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.type_error.visit_refs(visitor);
+    self.runtime_error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.type_error.trace_debug(stdio);
+    self.runtime_error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for TaskGroupSpawn {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+    if with_group(this, |_| ()).is_none() {
+      return create_error!(self.runtime_error, hooks, "TaskGroup was not initialized.");
+    }
+
+    if !args[0].is_obj_kind(ObjectKind::Closure) {
+      return create_error!(
+        self.type_error,
+        hooks,
+        "TaskGroup.spawn expects a laythe function."
+      );
+    }
+
+    let closure = args[0].to_obj().to_closure();
+    if closure.fun().arity().check(0).is_err() {
+      return create_error!(
+        self.type_error,
+        hooks,
+        &format!(
+          "TaskGroup.spawn expects a function without parameters but {} has parameters.",
+          &*closure.fun().name()
+        )
+      );
+    }
+
+    let mut fiber = match Fiber::new(closure) {
+      Ok(fiber) => hooks.manage_obj(fiber),
+      Err(_) => return create_error!(self.runtime_error, hooks, "Unable to create fiber."),
+    };
+
+    fiber.supervise();
+    hooks.spawn_fiber(fiber);
+    with_group(this, |group| {
+      group.children.push(Child {
+        fiber,
+        watcher: None,
+      })
+    });
+
+    Call::Ok(val!(fiber))
+  }
+}
+
+native_with_error!(TaskGroupWait, TASK_GROUP_WAIT);
+
+impl LyNative for TaskGroupWait {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+    let current = hooks.current_fiber();
+
+    loop {
+      match with_group(this, |group| group.poll(current)) {
+        Some(Progress::Cancel(children)) => {
+          let error = match self.call_error(hooks, "Task was cancelled.") {
+            Call::Err(error) => error,
+            call => return call,
+          };
+
+          for child in children {
+            hooks.cancel_fiber(child, error);
+          }
+        },
+        Some(Progress::Wait(children)) => {
+          for mut child in children {
+            hooks.grow(&mut *child, |child| child.add_waiter(current));
+          }
+          return Call::Block;
+        },
+        Some(Progress::Done(Some(error))) => return Call::Err(error),
+        Some(Progress::Done(None)) => return Call::Ok(VALUE_NIL),
+        None => return self.call_error(hooks, "TaskGroup was not initialized."),
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::MockedContext;
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let task_group_spawn = TaskGroupSpawn::native(&hooks, VALUE_NIL, VALUE_NIL);
+    assert_eq!(task_group_spawn.meta().name, "spawn");
+    assert_eq!(task_group_spawn.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(
+      task_group_spawn.meta().signature.parameters[0].kind,
+      ParameterKind::Fun
+    );
+  }
+}
@@ -0,0 +1,145 @@
+use super::TERM_ERROR;
+use crate::{
+  native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::Module,
+  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use laythe_env::stdio::Key;
+use std::io::{self, Write};
+
+const RAW_MODE_META: NativeMetaBuilder = NativeMetaBuilder::fun("rawMode", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("enabled", ParameterKind::Bool)]);
+
+const READ_KEY_META: NativeMetaBuilder = NativeMetaBuilder::fun("readKey", Arity::Fixed(0));
+
+pub fn declare_input(hooks: &GcHooks, self_module: &mut Module) -> StdResult<()> {
+  let term_error = val!(load_class_from_module(hooks, self_module, TERM_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(RAW_MODE_META.name),
+    val!(RawMode::native(hooks, term_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(READ_KEY_META.name),
+    val!(ReadKey::native(hooks, term_error)),
+  )
+}
+
+/// The name a key is given to scripts. Printable keys are the
+/// character itself and other keys are named in camel case
+fn key_name(key: Key) -> String {
+  let name = match key {
+    Key::Char(c) => return c.to_string(),
+    Key::Ctrl(c) => return format!("ctrl+{}", c),
+    Key::Alt(c) => return format!("alt+{}", c),
+    Key::Enter => "enter",
+    Key::Tab => "tab",
+    Key::Backspace => "backspace",
+    Key::Escape => "escape",
+    Key::Up => "up",
+    Key::Down => "down",
+    Key::Left => "left",
+    Key::Right => "right",
+    Key::Home => "home",
+    Key::End => "end",
+    Key::PageUp => "pageUp",
+    Key::PageDown => "pageDown",
+    Key::Insert => "insert",
+    Key::Delete => "delete",
+  };
+
+  name.to_string()
+}
+
+native_with_error!(RawMode, RAW_MODE_META);
+
+impl LyNative for RawMode {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    match hooks.as_io().stdio().set_raw_mode(args[0].to_bool()) {
+      Ok(()) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+native_with_error!(ReadKey, READ_KEY_META);
+
+impl LyNative for ReadKey {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    let io = hooks.as_io();
+    let mut stdio = io.stdio();
+
+    match stdio.read_key() {
+      Ok(Some(key)) => Call::Ok(val!(hooks.manage_str(key_name(key)))),
+      Ok(None) => Call::Ok(VALUE_NIL),
+      // the read is retried once the host resumes the vm with input
+      Err(err) if err.kind() == io::ErrorKind::WouldBlock && hooks.await_input() => Call::Block,
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  #[test]
+  fn key_names() {
+    assert_eq!(key_name(Key::Char('q')), "q");
+    assert_eq!(key_name(Key::Ctrl('c')), "ctrl+c");
+    assert_eq!(key_name(Key::Alt('x')), "alt+x");
+    assert_eq!(key_name(Key::PageDown), "pageDown");
+  }
+
+  mod raw_mode {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let raw_mode = RawMode::native(&hooks, error);
+
+      assert_eq!(raw_mode.meta().name, "rawMode");
+      assert_eq!(raw_mode.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        raw_mode.meta().signature.parameters[0].kind,
+        ParameterKind::Bool
+      );
+    }
+  }
+
+  mod read_key {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let read_key = ReadKey::native(&hooks, error);
+
+      assert_eq!(read_key.meta().name, "readKey");
+      assert_eq!(read_key.meta().signature.arity, Arity::Fixed(0));
+    }
+  }
+}
@@ -0,0 +1,49 @@
+mod input;
+mod screen;
+mod style;
+
+use input::declare_input;
+use laythe_core::{
+  hooks::GcHooks,
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use screen::declare_screen;
+use std::path::PathBuf;
+use style::declare_style;
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+
+const TERM_PATH: &str = "std/term";
+const TERM_ERROR: &str = "TermError";
+
+pub fn term_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(TERM_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let term_error = default_error_inheritance(hooks, std, TERM_ERROR)?;
+  export_and_insert(hooks, &mut module, term_error.name(), val!(term_error))?;
+
+  declare_style(hooks, &mut module)?;
+  declare_screen(hooks, &mut module)?;
+  declare_input(hooks, &mut module)?;
+
+  Ok(module)
+}
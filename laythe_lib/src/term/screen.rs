@@ -0,0 +1,324 @@
+use super::TERM_ERROR;
+use crate::{
+  create_error, native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::Module,
+  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::io::{self, Write};
+
+const MOVE_TO_META: NativeMetaBuilder = NativeMetaBuilder::fun("moveTo", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("column", ParameterKind::Number),
+    ParameterBuilder::new("row", ParameterKind::Number),
+  ]);
+
+const MOVE_UP_META: NativeMetaBuilder = NativeMetaBuilder::fun("moveUp", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("count", ParameterKind::Number)]);
+
+const MOVE_DOWN_META: NativeMetaBuilder = NativeMetaBuilder::fun("moveDown", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("count", ParameterKind::Number)]);
+
+const MOVE_RIGHT_META: NativeMetaBuilder =
+  NativeMetaBuilder::fun("moveRight", Arity::Default(0, 1))
+    .with_params(&[ParameterBuilder::new("count", ParameterKind::Number)]);
+
+const MOVE_LEFT_META: NativeMetaBuilder = NativeMetaBuilder::fun("moveLeft", Arity::Default(0, 1))
+  .with_params(&[ParameterBuilder::new("count", ParameterKind::Number)]);
+
+const HIDE_CURSOR_META: NativeMetaBuilder = NativeMetaBuilder::fun("hideCursor", Arity::Fixed(0));
+const SHOW_CURSOR_META: NativeMetaBuilder = NativeMetaBuilder::fun("showCursor", Arity::Fixed(0));
+const CLEAR_META: NativeMetaBuilder = NativeMetaBuilder::fun("clear", Arity::Fixed(0));
+const CLEAR_LINE_META: NativeMetaBuilder = NativeMetaBuilder::fun("clearLine", Arity::Fixed(0));
+const SIZE_META: NativeMetaBuilder = NativeMetaBuilder::fun("size", Arity::Fixed(0));
+
+/// Each relative cursor movement with the final character of its sequence
+const MOVES: [(&NativeMetaBuilder, char); 4] = [
+  (&MOVE_UP_META, 'A'),
+  (&MOVE_DOWN_META, 'B'),
+  (&MOVE_RIGHT_META, 'C'),
+  (&MOVE_LEFT_META, 'D'),
+];
+
+/// Each command that writes a fixed sequence
+const SEQUENCES: [(&NativeMetaBuilder, &str); 4] = [
+  (&HIDE_CURSOR_META, "\x1b[?25l"),
+  (&SHOW_CURSOR_META, "\x1b[?25h"),
+  (&CLEAR_META, "\x1b[2J\x1b[H"),
+  (&CLEAR_LINE_META, "\x1b[2K\r"),
+];
+
+pub fn declare_screen(hooks: &GcHooks, self_module: &mut Module) -> StdResult<()> {
+  let term_error = val!(load_class_from_module(hooks, self_module, TERM_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(MOVE_TO_META.name),
+    val!(MoveTo::native(hooks, term_error)),
+  )?;
+
+  for (meta, direction) in &MOVES {
+    export_and_insert(
+      hooks,
+      self_module,
+      hooks.manage_str(meta.name),
+      val!(Move::native(hooks, meta, *direction, term_error)),
+    )?;
+  }
+
+  for (meta, sequence) in &SEQUENCES {
+    export_and_insert(
+      hooks,
+      self_module,
+      hooks.manage_str(meta.name),
+      val!(Sequence::native(hooks, meta, sequence, term_error)),
+    )?;
+  }
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(SIZE_META.name),
+    val!(Size::native(hooks, term_error)),
+  )
+}
+
+/// Write an escape sequence to stdout, flushing so it takes
+/// effect without waiting for the end of the line
+fn write_sequence(hooks: &mut Hooks, sequence: &str) -> io::Result<()> {
+  let mut stdio = hooks.as_io().stdio();
+  let stdout = stdio.stdout();
+
+  stdout.write_all(sequence.as_bytes())?;
+  stdout.flush()
+}
+
+/// A count or position argument as a whole number
+fn whole(value: Value, name: &str) -> Result<usize, String> {
+  let num = value.to_num();
+
+  if num.fract() != 0.0 || num < 0.0 {
+    return Err(format!(
+      "Expected {} to be a whole number, found {}.",
+      name, num
+    ));
+  }
+  Ok(num as usize)
+}
+
+native_with_error!(MoveTo, MOVE_TO_META);
+
+impl LyNative for MoveTo {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let position =
+      whole(args[0], "column").and_then(|column| whole(args[1], "row").map(|row| (column, row)));
+
+    let (column, row) = match position {
+      Ok(position) => position,
+      Err(err) => return self.call_error(hooks, err),
+    };
+
+    // positions are from zero while the terminal counts from one
+    match write_sequence(hooks, &format!("\x1b[{};{}H", row + 1, column + 1)) {
+      Ok(()) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+/// Move the cursor a number of cells in a direction
+#[derive(Debug)]
+pub struct Move {
+  direction: char,
+  error: Value,
+}
+
+impl Move {
+  fn native(
+    hooks: &GcHooks,
+    meta: &NativeMetaBuilder,
+    direction: char,
+    error: Value,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self { direction, error }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+
+  fn call_error<T: Into<String> + AsRef<str>>(&self, hooks: &mut Hooks, message: T) -> Call {
+    create_error!(self.error, hooks, message)
+  }
+}
+
+impl Trace for Move {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for Move {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let count = match args.first().map_or(Ok(1), |count| whole(*count, "count")) {
+      Ok(count) => count,
+      Err(err) => return self.call_error(hooks, err),
+    };
+
+    // the terminal reads a count of zero as one
+    if count == 0 {
+      return Call::Ok(VALUE_NIL);
+    }
+
+    match write_sequence(hooks, &format!("\x1b[{}{}", count, self.direction)) {
+      Ok(()) => Call::Ok(VALUE_NIL),
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+/// Write a fixed escape sequence such as the one clearing the screen
+#[derive(Debug)]
+pub struct Sequence {
+  sequence: &'static str,
+  error: Value,
+}
+
+impl Sequence {
+  fn native(
+    hooks: &GcHooks,
+    meta: &NativeMetaBuilder,
+    sequence: &'static str,
+    error: Value,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self { sequence, error }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+}
+
+impl Trace for Sequence {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for Sequence {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    match write_sequence(hooks, self.sequence) {
+      Ok(()) => Call::Ok(VALUE_NIL),
+      Err(err) => create_error!(self.error, hooks, err.to_string()),
+    }
+  }
+}
+
+native_with_error!(Size, SIZE_META);
+
+impl LyNative for Size {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    match hooks.as_io().stdio().terminal_size() {
+      Ok((columns, rows)) => {
+        Call::tuple(&hooks.as_gc(), &[val!(columns as f64), val!(rows as f64)])
+      },
+      Err(err) => self.call_error(hooks, err.to_string()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  mod move_to {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let move_to = MoveTo::native(&hooks, error);
+
+      assert_eq!(move_to.meta().name, "moveTo");
+      assert_eq!(move_to.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(
+        move_to.meta().signature.parameters[0].kind,
+        ParameterKind::Number
+      );
+      assert_eq!(
+        move_to.meta().signature.parameters[1].kind,
+        ParameterKind::Number
+      );
+    }
+  }
+
+  mod moves {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let move_up = Move::native(&hooks, &MOVE_UP_META, 'A', error);
+
+      assert_eq!(move_up.meta().name, "moveUp");
+      assert_eq!(move_up.meta().signature.arity, Arity::Default(0, 1));
+      assert_eq!(
+        move_up.meta().signature.parameters[0].kind,
+        ParameterKind::Number
+      );
+    }
+  }
+
+  mod size {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let size = Size::native(&hooks, error);
+
+      assert_eq!(size.meta().name, "size");
+      assert_eq!(size.meta().signature.arity, Arity::Fixed(0));
+    }
+  }
+
+  #[test]
+  fn whole_numbers() {
+    assert_eq!(whole(val!(3.0), "count"), Ok(3));
+    assert_eq!(
+      whole(val!(1.5), "row"),
+      Err("Expected row to be a whole number, found 1.5.".to_string())
+    );
+    assert!(whole(val!(-1.0), "column").is_err());
+  }
+}
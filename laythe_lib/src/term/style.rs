@@ -0,0 +1,306 @@
+use super::TERM_ERROR;
+use crate::{
+  native, native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::Module,
+  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use std::io::Write;
+
+const FG_META: NativeMetaBuilder = NativeMetaBuilder::fun("fg", Arity::Fixed(2)).with_params(&[
+  ParameterBuilder::new("text", ParameterKind::String),
+  ParameterBuilder::new("color", ParameterKind::Any),
+]);
+
+const BG_META: NativeMetaBuilder = NativeMetaBuilder::fun("bg", Arity::Fixed(2)).with_params(&[
+  ParameterBuilder::new("text", ParameterKind::String),
+  ParameterBuilder::new("color", ParameterKind::Any),
+]);
+
+const BOLD_META: NativeMetaBuilder = NativeMetaBuilder::fun("bold", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("text", ParameterKind::String)]);
+
+const DIM_META: NativeMetaBuilder = NativeMetaBuilder::fun("dim", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("text", ParameterKind::String)]);
+
+const ITALIC_META: NativeMetaBuilder = NativeMetaBuilder::fun("italic", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("text", ParameterKind::String)]);
+
+const UNDERLINE_META: NativeMetaBuilder = NativeMetaBuilder::fun("underline", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("text", ParameterKind::String)]);
+
+const INVERSE_META: NativeMetaBuilder = NativeMetaBuilder::fun("inverse", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("text", ParameterKind::String)]);
+
+const STRIP_META: NativeMetaBuilder = NativeMetaBuilder::fun("strip", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("text", ParameterKind::String)]);
+
+/// The colors in the order of their ansi codes
+const COLORS: [&str; 8] = [
+  "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// Each text style with the codes that start and end it
+const STYLES: [(&NativeMetaBuilder, u8, u8); 5] = [
+  (&BOLD_META, 1, 22),
+  (&DIM_META, 2, 22),
+  (&ITALIC_META, 3, 23),
+  (&UNDERLINE_META, 4, 24),
+  (&INVERSE_META, 7, 27),
+];
+
+pub fn declare_style(hooks: &GcHooks, self_module: &mut Module) -> StdResult<()> {
+  let term_error = val!(load_class_from_module(hooks, self_module, TERM_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(FG_META.name),
+    val!(Fg::native(hooks, term_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(BG_META.name),
+    val!(Bg::native(hooks, term_error)),
+  )?;
+
+  for (meta, open, close) in &STYLES {
+    export_and_insert(
+      hooks,
+      self_module,
+      hooks.manage_str(meta.name),
+      val!(Style::native(hooks, meta, *open, *close)),
+    )?;
+  }
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(STRIP_META.name),
+    val!(Strip::native(hooks)),
+  )
+}
+
+/// The select graphic rendition code for a color, offset from the
+/// foreground codes to the background codes by layer
+fn color_code(color: Value, layer: u8) -> Result<String, String> {
+  if color.is_num() {
+    let index = color.to_num();
+    if index.fract() != 0.0 || !(0.0..=255.0).contains(&index) {
+      return Err(format!(
+        "Expected a color index from 0 to 255, found {}.",
+        index
+      ));
+    }
+    return Ok(format!("{};5;{}", 38 + layer, index as u8));
+  }
+
+  if color.is_obj_kind(ObjectKind::String) {
+    let name = color.to_obj().to_str();
+    let (base, name) = match name.strip_prefix("bright") {
+      Some(bright) => (90, bright.to_lowercase()),
+      None => (30, String::from(&*name)),
+    };
+
+    if let Some(index) = COLORS.iter().position(|color| *color == name) {
+      return Ok((base + layer as usize + index).to_string());
+    }
+  }
+
+  Err(format!(
+    "Unknown color {}, expected one of {} optionally prefixed with bright, or an index from 0 to 255.",
+    color,
+    COLORS.join(", ")
+  ))
+}
+
+/// Wrap text in the codes that start and end a style
+fn styled(text: &str, open: &str, close: u8) -> String {
+  format!("\x1b[{}m{}\x1b[{}m", open, text, close)
+}
+
+/// Remove the escape sequences from text
+fn strip(text: &str) -> String {
+  let mut stripped = String::with_capacity(text.len());
+  let mut chars = text.chars();
+
+  while let Some(c) = chars.next() {
+    if c != '\x1b' {
+      stripped.push(c);
+      continue;
+    }
+
+    // a control sequence ends at its first character from @ to ~
+    if chars.next() == Some('[') {
+      for c in chars.by_ref() {
+        if ('@'..='~').contains(&c) {
+          break;
+        }
+      }
+    }
+  }
+
+  stripped
+}
+
+native_with_error!(Fg, FG_META);
+
+impl LyNative for Fg {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    match color_code(args[1], 0) {
+      Ok(code) => {
+        let text = styled(&args[0].to_obj().to_str(), &code, 39);
+        Call::Ok(val!(hooks.manage_str(text)))
+      },
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native_with_error!(Bg, BG_META);
+
+impl LyNative for Bg {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    match color_code(args[1], 10) {
+      Ok(code) => {
+        let text = styled(&args[0].to_obj().to_str(), &code, 49);
+        Call::Ok(val!(hooks.manage_str(text)))
+      },
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+/// Apply a text style such as bold or underline
+#[derive(Debug)]
+pub struct Style {
+  open: u8,
+  close: u8,
+}
+
+impl Style {
+  fn native(hooks: &GcHooks, meta: &NativeMetaBuilder, open: u8, close: u8) -> GcObj<Native> {
+    let native = Box::new(Self { open, close }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+}
+
+impl Trace for Style {
+  fn trace(&self) {}
+
+  fn trace_debug(&self, _stdio: &mut dyn Write) {}
+}
+
+impl LyNative for Style {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let text = styled(
+      &args[0].to_obj().to_str(),
+      &self.open.to_string(),
+      self.close,
+    );
+    Call::Ok(val!(hooks.manage_str(text)))
+  }
+}
+
+native!(Strip, STRIP_META);
+
+impl LyNative for Strip {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let text = strip(&args[0].to_obj().to_str());
+    Call::Ok(val!(hooks.manage_str(text)))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+  use laythe_core::value::VALUE_NIL;
+
+  #[test]
+  fn colors() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    assert_eq!(
+      color_code(val!(hooks.manage_str("red")), 0),
+      Ok("31".to_string())
+    );
+    assert_eq!(
+      color_code(val!(hooks.manage_str("brightCyan")), 10),
+      Ok("106".to_string())
+    );
+    assert_eq!(color_code(val!(208.0), 10), Ok("48;5;208".to_string()));
+    assert!(color_code(val!(256.0), 0).is_err());
+    assert!(color_code(val!(hooks.manage_str("mauve")), 0).is_err());
+    assert!(color_code(VALUE_NIL, 0).is_err());
+  }
+
+  #[test]
+  fn strips() {
+    assert_eq!(strip(&styled("hi", "1;31", 0)), "hi");
+    assert_eq!(strip("a\x1b[2Jb\x1b[10;4Hc"), "abc");
+  }
+
+  mod fg {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(test_error_class(&hooks));
+
+      let fg = Fg::native(&hooks, error);
+
+      assert_eq!(fg.meta().name, "fg");
+      assert_eq!(fg.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(
+        fg.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+      assert_eq!(fg.meta().signature.parameters[1].kind, ParameterKind::Any);
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let fg = Fg::native(&hooks.as_gc(), error);
+
+      let text = val!(hooks.manage_str("go"));
+      let color = val!(hooks.manage_str("green"));
+      let result = fg.call(&mut hooks, None, &[text, color]).unwrap();
+      assert_eq!(&*result.to_obj().to_str(), "\x1b[32mgo\x1b[39m");
+    }
+  }
+
+  mod style {
+    use super::*;
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+      let bold = Style::native(&hooks.as_gc(), &BOLD_META, 1, 22);
+
+      assert_eq!(bold.meta().name, "bold");
+
+      let text = val!(hooks.manage_str("loud"));
+      let result = bold.call(&mut hooks, None, &[text]).unwrap();
+      assert_eq!(&*result.to_obj().to_str(), "\x1b[1mloud\x1b[22m");
+    }
+  }
+}
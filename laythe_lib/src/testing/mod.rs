@@ -0,0 +1,36 @@
+mod utils;
+
+use laythe_core::{
+  hooks::GcHooks,
+  module::{Module, Package},
+  utils::IdEmitter,
+};
+use std::path::PathBuf;
+use utils::{declare_test_module, define_test_module};
+
+use crate::{global::MODULE_CLASS_NAME, support::load_class_from_package, StdResult, STD};
+
+pub use utils::{test_results, TestResults};
+
+pub const TEST_PATH: &str = "std/test";
+
+pub fn add_test_module(
+  hooks: &GcHooks,
+  std: &mut Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<()> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(TEST_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let mut root = std.root_module();
+  root.insert_module(hooks, module)?;
+
+  declare_test_module(hooks, &mut module, std)?;
+  define_test_module(hooks, &mut module)
+}
@@ -0,0 +1,676 @@
+use crate::{
+  create_error,
+  global::ASSERT_ERROR_NAME,
+  support::{export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+use laythe_core::{
+  get,
+  hooks::{GcHooks, Hooks},
+  if_let_obj,
+  managed::{GcObj, GcStr, Trace},
+  module::{Import, Module, Package},
+  object::{LyNative, Map, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  to_obj_kind, val,
+  value::{Value, VALUE_NIL},
+  Call,
+};
+use std::{
+  io::Write,
+  sync::{Arc, Mutex},
+};
+
+use super::TEST_PATH;
+
+const RESULTS: &str = "results";
+const PASSED: &str = "passed";
+const FAILED: &str = "failed";
+
+const DESCRIBE_META: NativeMetaBuilder = NativeMetaBuilder::fun("describe", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("name", ParameterKind::String),
+    ParameterBuilder::new("block", ParameterKind::Fun),
+  ]);
+
+const IT_META: NativeMetaBuilder = NativeMetaBuilder::fun("it", Arity::Fixed(2)).with_params(&[
+  ParameterBuilder::new("name", ParameterKind::String),
+  ParameterBuilder::new("block", ParameterKind::Fun),
+]);
+
+const TEST_META: NativeMetaBuilder =
+  NativeMetaBuilder::fun("test", Arity::Fixed(2)).with_params(&[
+    ParameterBuilder::new("name", ParameterKind::String),
+    ParameterBuilder::new("block", ParameterKind::Fun),
+  ]);
+
+const EXPECT_META: NativeMetaBuilder = NativeMetaBuilder::fun("expect", Arity::Default(1, 2))
+  .with_params(&[
+    ParameterBuilder::new("value", ParameterKind::Bool),
+    ParameterBuilder::new("message", ParameterKind::String),
+  ]);
+
+const EXPECT_EQ_META: NativeMetaBuilder = NativeMetaBuilder::fun("expectEq", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("actual", ParameterKind::Any),
+    ParameterBuilder::new("expected", ParameterKind::Any),
+  ]);
+
+const EXPECT_NE_META: NativeMetaBuilder = NativeMetaBuilder::fun("expectNe", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("actual", ParameterKind::Any),
+    ParameterBuilder::new("unexpected", ParameterKind::Any),
+  ]);
+
+/// The number of tests that passed and failed while running a script
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TestResults {
+  pub passed: usize,
+  pub failed: usize,
+}
+
+/// Read the test results recorded by the std/test module of this package
+pub fn test_results(hooks: &GcHooks, std: &Package) -> Option<TestResults> {
+  let import = Import::from_str(hooks, TEST_PATH).ok()?;
+  let results = std
+    .import_symbol(hooks, import, hooks.manage_str(RESULTS))
+    .ok()?;
+
+  if !results.is_obj_kind(ObjectKind::Map) {
+    return None;
+  }
+
+  let results = results.to_obj().to_map();
+  let count = |key: &str| {
+    results
+      .get(&val!(hooks.manage_str(key)))
+      .filter(|count| count.is_num())
+      .map_or(0, |count| count.to_num() as usize)
+  };
+
+  Some(TestResults {
+    passed: count(PASSED),
+    failed: count(FAILED),
+  })
+}
+
+pub fn declare_test_module(
+  hooks: &GcHooks,
+  self_module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let error = val!(load_class_from_package(hooks, std, STD, ASSERT_ERROR_NAME)?);
+  let str_name = hooks.manage_str("str");
+  let passed = hooks.manage_str(PASSED);
+  let failed = hooks.manage_str(FAILED);
+
+  let mut results: Map<Value, Value> = Map::default();
+  results.insert(val!(passed), val!(0.0));
+  results.insert(val!(failed), val!(0.0));
+  let results = hooks.manage_obj(results);
+  hooks.push_root(results);
+
+  let scope = Arc::new(Mutex::new(vec![]));
+
+  export_and_insert(hooks, self_module, hooks.manage_str(RESULTS), val!(results))?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(DESCRIBE_META.name),
+    val!(Describe::native(hooks, Arc::clone(&scope))),
+  )?;
+
+  let counts = TestCounts {
+    results,
+    passed,
+    failed,
+  };
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(IT_META.name),
+    val!(It::native(&IT_META, hooks, Arc::clone(&scope), counts)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(TEST_META.name),
+    val!(It::native(&TEST_META, hooks, scope, counts)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(EXPECT_META.name),
+    val!(Expect::native(hooks, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(EXPECT_EQ_META.name),
+    val!(ExpectEq::native(&EXPECT_EQ_META, hooks, str_name, error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    self_module,
+    hooks.manage_str(EXPECT_NE_META.name),
+    val!(ExpectEq::native(&EXPECT_NE_META, hooks, str_name, error)),
+  )?;
+
+  hooks.pop_roots(1);
+  Ok(())
+}
+
+pub fn define_test_module(_: &GcHooks, _: &mut Module) -> StdResult<()> {
+  Ok(())
+}
+
+/// The names of the enclosing describe blocks
+type Scope = Arc<Mutex<Vec<String>>>;
+
+#[derive(Debug)]
+/// A native function to group tests under a name
+pub struct Describe {
+  scope: Scope,
+}
+
+impl Describe {
+  /// Construct a new instance of the native describe function
+  pub fn native(hooks: &GcHooks, scope: Scope) -> GcObj<Native> {
+    let native = Box::new(Self { scope }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(DESCRIBE_META.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for Describe {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    self
+      .scope
+      .lock()
+      .expect("Unable to lock test scope")
+      .push(String::from(&*args[0].to_obj().to_str()));
+    let result = hooks.call(args[1], &[]);
+    self.scope.lock().expect("Unable to lock test scope").pop();
+
+    get!(result);
+    Call::Ok(VALUE_NIL)
+  }
+}
+
+impl Trace for Describe {
+  fn trace(&self) {}
+
+  fn trace_debug(&self, _stdout: &mut dyn Write) {}
+}
+
+/// The shared tally of passed and failed tests
+#[derive(Debug, Clone, Copy)]
+struct TestCounts {
+  results: GcObj<Map<Value, Value>>,
+  passed: GcStr,
+  failed: GcStr,
+}
+
+impl TestCounts {
+  fn record(&mut self, passed: bool) {
+    let key = val!(if passed { self.passed } else { self.failed });
+    let count = self.results.get(&key).map_or(0.0, |count| count.to_num());
+
+    self.results.insert(key, val!(count + 1.0));
+  }
+}
+
+#[derive(Debug)]
+/// A native function to run a single test and report its outcome
+pub struct It {
+  scope: Scope,
+  counts: TestCounts,
+}
+
+impl It {
+  /// Construct a new instance of the native it or test function
+  fn native(
+    meta: &NativeMetaBuilder,
+    hooks: &GcHooks,
+    scope: Scope,
+    counts: TestCounts,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self { scope, counts }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for It {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let mut name = self
+      .scope
+      .lock()
+      .expect("Unable to lock test scope")
+      .join(" > ");
+    if !name.is_empty() {
+      name.push_str(" > ");
+    }
+    name.push_str(&args[0].to_obj().to_str());
+
+    let result = hooks.call(args[1], &[]);
+    let mut counts = self.counts;
+
+    let report = match result {
+      Call::Ok(_) => {
+        counts.record(true);
+        format!("test {} ... ok", name)
+      },
+      Call::Err(error) => {
+        counts.record(false);
+
+        let message = error[0];
+        let message = if message.is_obj_kind(ObjectKind::String) {
+          String::from(&*message.to_obj().to_str())
+        } else {
+          String::new()
+        };
+
+        let mut report = format!("test {} ... FAILED", name);
+        let message = format!("{}: {}", &*error.class().name(), message);
+        for line in message.lines() {
+          report.push('\n');
+          if !line.is_empty() {
+            report.push_str("    ");
+            report.push_str(line);
+          }
+        }
+        report
+      },
+      Call::Exit(code) => return Call::Exit(code),
+      Call::Block => return Call::Block,
+    };
+
+    let mut stdio = hooks.as_io().stdio();
+    match writeln!(stdio.stdout(), "{}", report) {
+      Ok(_) => Call::Ok(VALUE_NIL),
+      Err(err) => panic!("TODO return some sort of io error {}", err),
+    }
+  }
+}
+
+impl Trace for It {
+  fn trace(&self) {
+    self.counts.results.trace();
+    self.counts.passed.trace();
+    self.counts.failed.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.counts.results.visit_refs(visitor);
+    self.counts.passed.visit_refs(visitor);
+    self.counts.failed.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.counts.results.trace_debug(stdout);
+    self.counts.passed.trace_debug(stdout);
+    self.counts.failed.trace_debug(stdout);
+  }
+}
+
+#[derive(Debug)]
+/// A native function to expect a true value
+pub struct Expect {
+  error: Value,
+}
+
+impl Expect {
+  /// Construct a new instance of the native expect function
+  pub fn native(hooks: &GcHooks, error: Value) -> GcObj<Native> {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    let native = Box::new(Self { error }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(EXPECT_META.to_meta(hooks), native))
+  }
+}
+
+impl LyNative for Expect {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    if args[0].to_bool() {
+      return Call::Ok(VALUE_NIL);
+    }
+
+    match args.get(1) {
+      Some(message) => create_error!(
+        self.error,
+        hooks,
+        format!("Expectation failed: {}", message.to_obj().to_str())
+      ),
+      None => create_error!(
+        self.error,
+        hooks,
+        "Expectation failed expected true received false"
+      ),
+    }
+  }
+}
+
+impl Trace for Expect {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.error.trace_debug(stdout);
+  }
+}
+
+#[derive(Debug)]
+/// A native function to expect two values to be deeply equal, or not
+/// equal for expectNe
+pub struct ExpectEq {
+  /// reference to 'str'
+  method_str: GcStr,
+  error: Value,
+  equal: bool,
+}
+
+impl ExpectEq {
+  /// Construct a new instance of the native expectEq or expectNe function
+  fn native(
+    meta: &NativeMetaBuilder,
+    hooks: &GcHooks,
+    method_str: GcStr,
+    error: Value,
+  ) -> GcObj<Native> {
+    debug_assert!(error.is_obj_kind(ObjectKind::Class));
+    let equal = meta.name == EXPECT_EQ_META.name;
+    let native = Box::new(Self {
+      method_str,
+      error,
+      equal,
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+
+  /// The lines used to display a value in a diff
+  fn lines(&self, hooks: &mut Hooks, value: Value) -> Result<Vec<String>, Call> {
+    if_let_obj!(ObjectKind::String(string) = (value) {
+      return Ok(string.lines().map(String::from).collect());
+    });
+
+    if_let_obj!(ObjectKind::List(list) = (value) {
+      let mut lines = vec![];
+      for item in list.iter() {
+        lines.push(self.str(hooks, *item)?);
+      }
+      return Ok(lines);
+    });
+
+    if_let_obj!(ObjectKind::Map(map) = (value) {
+      let mut lines = vec![];
+      for (key, item) in map.iter() {
+        lines.push(format!("{}: {}", self.str(hooks, *key)?, self.str(hooks, *item)?));
+      }
+      lines.sort();
+      return Ok(lines);
+    });
+
+    Ok(vec![self.str(hooks, value)?])
+  }
+
+  /// Call str on this value
+  fn str(&self, hooks: &mut Hooks, value: Value) -> Result<String, Call> {
+    let str_method = match hooks.get_method(value, self.method_str) {
+      Call::Ok(method) => method,
+      call => return Err(call),
+    };
+
+    match hooks.call_method(value, str_method, &[]) {
+      Call::Ok(string) if string.is_obj_kind(ObjectKind::String) => {
+        Ok(String::from(&*string.to_obj().to_str()))
+      },
+      Call::Ok(string) => Ok(format!("{:?}", string)),
+      call => Err(call),
+    }
+  }
+}
+
+impl LyNative for ExpectEq {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    if deep_eq(args[0], args[1]) == self.equal {
+      return Call::Ok(VALUE_NIL);
+    }
+
+    let message = if self.equal {
+      let actual = match self.lines(hooks, args[0]) {
+        Ok(lines) => lines,
+        Err(call) => return call,
+      };
+      let expected = match self.lines(hooks, args[1]) {
+        Ok(lines) => lines,
+        Err(call) => return call,
+      };
+
+      format!(
+        "Values are not equal\n- expected\n+ actual\n\n{}",
+        line_diff(&expected, &actual)
+      )
+    } else {
+      match self.str(hooks, args[0]) {
+        Ok(actual) => format!("Values are equal: {}", actual),
+        Err(call) => return call,
+      }
+    };
+
+    create_error!(self.error, hooks, message)
+  }
+}
+
+impl Trace for ExpectEq {
+  fn trace(&self) {
+    self.method_str.trace();
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.method_str.visit_refs(visitor);
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdout: &mut dyn Write) {
+    self.method_str.trace_debug(stdout);
+    self.error.trace_debug(stdout);
+  }
+}
+
+/// Are these two values equal, comparing lists and maps by their contents
+fn deep_eq(lhs: Value, rhs: Value) -> bool {
+  if lhs == rhs {
+    return true;
+  }
+
+  if lhs.is_obj_kind(ObjectKind::List) && rhs.is_obj_kind(ObjectKind::List) {
+    let lhs = lhs.to_obj().to_list();
+    let rhs = rhs.to_obj().to_list();
+
+    return lhs.len() == rhs.len()
+      && lhs
+        .iter()
+        .zip(rhs.iter())
+        .all(|(lhs, rhs)| deep_eq(*lhs, *rhs));
+  }
+
+  if lhs.is_obj_kind(ObjectKind::Map) && rhs.is_obj_kind(ObjectKind::Map) {
+    let lhs = lhs.to_obj().to_map();
+    let rhs = rhs.to_obj().to_map();
+
+    return lhs.len() == rhs.len()
+      && lhs
+        .iter()
+        .all(|(key, lhs)| rhs.get(key).is_some_and(|rhs| deep_eq(*lhs, *rhs)));
+  }
+
+  false
+}
+
+/// Produce a line diff from the expected to the actual lines. Removed
+/// lines are prefixed with '-' and added lines with '+'
+fn line_diff(expected: &[String], actual: &[String]) -> String {
+  let n = expected.len();
+  let m = actual.len();
+
+  // longest common subsequence of each pair of suffixes
+  let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+  for i in (0..n).rev() {
+    for j in (0..m).rev() {
+      lcs[i][j] = if expected[i] == actual[j] {
+        lcs[i + 1][j + 1] + 1
+      } else {
+        lcs[i + 1][j].max(lcs[i][j + 1])
+      };
+    }
+  }
+
+  let mut diff = String::new();
+  let (mut i, mut j) = (0, 0);
+  while i < n || j < m {
+    if i < n && j < m && expected[i] == actual[j] {
+      diff.push_str(&format!("  {}\n", expected[i]));
+      i += 1;
+      j += 1;
+    } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+      diff.push_str(&format!("- {}\n", expected[i]));
+      i += 1;
+    } else {
+      diff.push_str(&format!("+ {}\n", actual[j]));
+      j += 1;
+    }
+  }
+
+  diff
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  mod describe {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let describe = Describe::native(&hooks, Arc::new(Mutex::new(vec![])));
+
+      assert_eq!(&*describe.meta().name, "describe");
+      assert_eq!(describe.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(
+        describe.meta().signature.parameters[0].kind,
+        ParameterKind::String
+      );
+      assert_eq!(
+        describe.meta().signature.parameters[1].kind,
+        ParameterKind::Fun
+      );
+    }
+  }
+
+  mod expect {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let error = val!(test_error_class(&hooks));
+      let expect = Expect::native(&hooks, error);
+
+      assert_eq!(&*expect.meta().name, "expect");
+      assert_eq!(expect.meta().signature.arity, Arity::Default(1, 2));
+      assert_eq!(
+        expect.meta().signature.parameters[0].kind,
+        ParameterKind::Bool
+      );
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let expect = Expect::native(&hooks.as_gc(), error);
+
+      let result = match expect.call(&mut hooks, None, &[val!(true)]) {
+        Call::Ok(res) => res,
+        _ => panic!(),
+      };
+
+      assert_eq!(result, VALUE_NIL);
+    }
+  }
+
+  mod expect_eq {
+    use super::*;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let error = val!(test_error_class(&hooks));
+      let str_name = hooks.manage_str("str");
+      let expect_eq = ExpectEq::native(&EXPECT_EQ_META, &hooks, str_name, error);
+      let expect_ne = ExpectEq::native(&EXPECT_NE_META, &hooks, str_name, error);
+
+      assert_eq!(&*expect_eq.meta().name, "expectEq");
+      assert_eq!(expect_eq.meta().signature.arity, Arity::Fixed(2));
+      assert_eq!(&*expect_ne.meta().name, "expectNe");
+      assert_eq!(expect_ne.meta().signature.arity, Arity::Fixed(2));
+    }
+
+    #[test]
+    fn call() {
+      let mut context = MockedContext::default();
+      let mut hooks = Hooks::new(&mut context);
+
+      let error = val!(test_error_class(&hooks.as_gc()));
+      let str_name = hooks.manage_str("str");
+      let expect_eq = ExpectEq::native(&EXPECT_EQ_META, &hooks.as_gc(), str_name, error);
+
+      let lhs = val!(hooks.manage_obj(laythe_core::object::List::from(vec![val!(1.0)])));
+      let rhs = val!(hooks.manage_obj(laythe_core::object::List::from(vec![val!(1.0)])));
+
+      let result = match expect_eq.call(&mut hooks, None, &[lhs, rhs]) {
+        Call::Ok(res) => res,
+        _ => panic!(),
+      };
+
+      assert_eq!(result, VALUE_NIL);
+    }
+  }
+
+  #[test]
+  fn diff() {
+    let lines = |lines: &[&str]| -> Vec<String> { lines.iter().map(|l| l.to_string()).collect() };
+
+    assert_eq!(
+      line_diff(&lines(&["a", "b", "c"]), &lines(&["a", "x", "c"])),
+      "  a\n- b\n+ x\n  c\n"
+    );
+    assert_eq!(line_diff(&lines(&[]), &lines(&["a"])), "+ a\n");
+    assert_eq!(line_diff(&lines(&["a"]), &lines(&[])), "- a\n");
+  }
+}
@@ -0,0 +1,490 @@
+use super::{duration::DURATION_CLASS_NAME, time_instance, time_seconds, TIME_ERROR};
+use crate::{
+  create_error, native, native_with_error,
+  support::{default_class_inheritance, export_and_insert, load_class_from_module},
+  StdResult,
+};
+use chrono::{
+  format::{Item, StrftimeItems},
+  Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use std::io::Write;
+
+const DATE_TIME_CLASS_NAME: &str = "DateTime";
+const DATE_TIME_FIELD_TIMESTAMP: &str = "unix";
+
+/// The seconds either side of the unix epoch a date time can be
+/// represented in, a little inside the range chrono supports
+const MAX_TIMESTAMP: f64 = 8e12;
+
+const DATE_TIME_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Default(3, 6))
+  .with_params(&[
+    ParameterBuilder::new("year", ParameterKind::Number),
+    ParameterBuilder::new("month", ParameterKind::Number),
+    ParameterBuilder::new("day", ParameterKind::Number),
+    ParameterBuilder::new("hour", ParameterKind::Number),
+    ParameterBuilder::new("minute", ParameterKind::Number),
+    ParameterBuilder::new("second", ParameterKind::Number),
+  ]);
+
+const DATE_TIME_NOW: NativeMetaBuilder = NativeMetaBuilder::method("now", Arity::Fixed(0));
+
+const DATE_TIME_FROM_TIMESTAMP: NativeMetaBuilder =
+  NativeMetaBuilder::method("fromTimestamp", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("seconds", ParameterKind::Number)]);
+
+const DATE_TIME_PARSE: NativeMetaBuilder = NativeMetaBuilder::method("parse", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("pattern", ParameterKind::String),
+    ParameterBuilder::new("string", ParameterKind::String),
+  ]);
+
+const DATE_TIME_TIMESTAMP: NativeMetaBuilder =
+  NativeMetaBuilder::method("timestamp", Arity::Fixed(0));
+
+const DATE_TIME_YEAR: NativeMetaBuilder = NativeMetaBuilder::method("year", Arity::Fixed(0));
+const DATE_TIME_MONTH: NativeMetaBuilder = NativeMetaBuilder::method("month", Arity::Fixed(0));
+const DATE_TIME_DAY: NativeMetaBuilder = NativeMetaBuilder::method("day", Arity::Fixed(0));
+const DATE_TIME_HOUR: NativeMetaBuilder = NativeMetaBuilder::method("hour", Arity::Fixed(0));
+const DATE_TIME_MINUTE: NativeMetaBuilder = NativeMetaBuilder::method("minute", Arity::Fixed(0));
+const DATE_TIME_SECOND: NativeMetaBuilder = NativeMetaBuilder::method("second", Arity::Fixed(0));
+const DATE_TIME_WEEKDAY: NativeMetaBuilder = NativeMetaBuilder::method("weekday", Arity::Fixed(0));
+
+const DATE_TIME_FORMAT: NativeMetaBuilder = NativeMetaBuilder::method("format", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("pattern", ParameterKind::String)]);
+
+const DATE_TIME_ADD: NativeMetaBuilder = NativeMetaBuilder::method("add", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("duration", ParameterKind::Instance)]);
+
+const DATE_TIME_SUB: NativeMetaBuilder = NativeMetaBuilder::method("sub", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("other", ParameterKind::Instance)]);
+
+const DATE_TIME_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
+
+pub fn declare_date_time_class(
+  hooks: &GcHooks,
+  module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let class = default_class_inheritance(hooks, std, DATE_TIME_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_date_time_class(hooks: &GcHooks, module: &Module, _: &Package) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, DATE_TIME_CLASS_NAME)?;
+  let duration = load_class_from_module(hooks, module, DURATION_CLASS_NAME)?;
+  let time_error = val!(load_class_from_module(hooks, module, TIME_ERROR)?);
+
+  class.add_field(hooks, hooks.manage_str(DATE_TIME_FIELD_TIMESTAMP));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DATE_TIME_INIT.name),
+    val!(DateTimeInit::native(hooks, time_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DATE_TIME_TIMESTAMP.name),
+    val!(DateTimeTimestamp::native(hooks)),
+  );
+
+  for (meta, component) in &[
+    (DATE_TIME_YEAR, Component::Year),
+    (DATE_TIME_MONTH, Component::Month),
+    (DATE_TIME_DAY, Component::Day),
+    (DATE_TIME_HOUR, Component::Hour),
+    (DATE_TIME_MINUTE, Component::Minute),
+    (DATE_TIME_SECOND, Component::Second),
+    (DATE_TIME_WEEKDAY, Component::Weekday),
+  ] {
+    let native = DateTimeComponent::native(hooks, meta, *component, time_error);
+    class.add_method(hooks, hooks.manage_str(meta.name), val!(native));
+  }
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DATE_TIME_FORMAT.name),
+    val!(DateTimeFormat::native(hooks, time_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DATE_TIME_ADD.name),
+    val!(DateTimeAdd::native(hooks, duration, time_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DATE_TIME_SUB.name),
+    val!(DateTimeSub::native(hooks, duration, time_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DATE_TIME_STR.name),
+    val!(DateTimeStr::native(hooks, time_error)),
+  );
+
+  let mut meta_class = class.meta_class().expect("Meta class not set.");
+
+  meta_class.add_method(
+    hooks,
+    hooks.manage_str(DATE_TIME_NOW.name),
+    val!(DateTimeNow::native(hooks, time_error)),
+  );
+
+  meta_class.add_method(
+    hooks,
+    hooks.manage_str(DATE_TIME_FROM_TIMESTAMP.name),
+    val!(DateTimeFromTimestamp::native(hooks, time_error)),
+  );
+
+  meta_class.add_method(
+    hooks,
+    hooks.manage_str(DATE_TIME_PARSE.name),
+    val!(DateTimeParse::native(hooks, time_error)),
+  );
+
+  Ok(())
+}
+
+fn unix_epoch() -> NaiveDateTime {
+  NaiveDate::from_ymd_opt(1970, 1, 1)
+    .and_then(|date| date.and_hms_opt(0, 0, 0))
+    .expect("Unix epoch is a valid date.")
+}
+
+/// The utc date time a number of seconds from the unix epoch
+fn to_date_time(seconds: f64) -> Option<NaiveDateTime> {
+  if !seconds.is_finite() || seconds.abs() > MAX_TIMESTAMP {
+    return None;
+  }
+
+  let whole = seconds.floor();
+  let nanos = ((seconds - whole) * 1e9).round() as i64;
+
+  unix_epoch()
+    .checked_add_signed(Duration::seconds(whole as i64))
+    .and_then(|date_time| date_time.checked_add_signed(Duration::nanoseconds(nanos)))
+}
+
+/// The seconds from the unix epoch to a utc date time
+fn to_timestamp(date_time: &NaiveDateTime) -> f64 {
+  let days = (date_time.date() - unix_epoch().date()).num_days() as f64;
+
+  days * 86_400.0
+    + date_time.num_seconds_from_midnight() as f64
+    + date_time.nanosecond() as f64 / 1e9
+}
+
+/// Read the date time out of an instance, raising an error if its
+/// timestamp has been set to something out of range
+macro_rules! get_date_time {
+  ( $self:ident, $this:ident, $hooks:ident ) => {{
+    let timestamp = $this.unwrap().to_obj().to_instance()[0];
+
+    match Some(timestamp)
+      .filter(|timestamp| timestamp.is_num())
+      .and_then(|timestamp| to_date_time(timestamp.to_num()))
+    {
+      Some(date_time) => date_time,
+      None => return $self.call_error($hooks, "DateTime timestamp is invalid."),
+    }
+  }};
+}
+
+native_with_error!(DateTimeInit, DATE_TIME_INIT);
+
+impl LyNative for DateTimeInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut components = [1970.0, 1.0, 1.0, 0.0, 0.0, 0.0];
+    for (component, arg) in components.iter_mut().zip(args) {
+      *component = arg.to_num();
+    }
+
+    if components[..5]
+      .iter()
+      .any(|component| component.fract() != 0.0 || component.abs() > i32::MAX as f64)
+    {
+      return self.call_error(
+        hooks,
+        "DateTime components other than second must be integers.",
+      );
+    }
+
+    let [year, month, day, hour, minute, second] = components;
+    let date_time = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+      .and_then(|date| date.and_hms_opt(hour as u32, minute as u32, 0))
+      .filter(|_| (0.0..60.0).contains(&second));
+
+    match date_time {
+      Some(date_time) => {
+        let mut this = this.unwrap().to_obj().to_instance();
+        this[0] = val!(to_timestamp(&date_time) + second);
+        Call::Ok(val!(this))
+      },
+      None => self.call_error(hooks, "Invalid DateTime components."),
+    }
+  }
+}
+
+native_with_error!(DateTimeNow, DATE_TIME_NOW);
+
+impl LyNative for DateTimeNow {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let class = this.unwrap().to_obj().to_class();
+
+    match hooks.as_io().time().now() {
+      Ok(now) => Call::Ok(time_instance(&hooks.as_gc(), class, now.as_secs_f64())),
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native_with_error!(DateTimeFromTimestamp, DATE_TIME_FROM_TIMESTAMP);
+
+impl LyNative for DateTimeFromTimestamp {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let class = this.unwrap().to_obj().to_class();
+    let seconds = args[0].to_num();
+
+    match to_date_time(seconds) {
+      Some(_) => Call::Ok(time_instance(&hooks.as_gc(), class, seconds)),
+      None => self.call_error(hooks, format!("Timestamp {} is out of range.", seconds)),
+    }
+  }
+}
+
+native_with_error!(DateTimeParse, DATE_TIME_PARSE);
+
+impl LyNative for DateTimeParse {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let class = this.unwrap().to_obj().to_class();
+    let pattern = args[0].to_obj().to_str();
+    let string = args[1].to_obj().to_str();
+
+    // patterns with only a date are parsed as midnight on that day
+    let parsed = NaiveDateTime::parse_from_str(&string, &pattern).or_else(|err| {
+      NaiveDate::parse_from_str(&string, &pattern)
+        .map(|date| date.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap()))
+        .map_err(|_| err)
+    });
+
+    match parsed {
+      Ok(date_time) => Call::Ok(time_instance(
+        &hooks.as_gc(),
+        class,
+        to_timestamp(&date_time),
+      )),
+      Err(err) => self.call_error(
+        hooks,
+        format!("Unable to parse {} with {}: {}.", string, pattern, err),
+      ),
+    }
+  }
+}
+
+native!(DateTimeTimestamp, DATE_TIME_TIMESTAMP);
+
+impl LyNative for DateTimeTimestamp {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(this.unwrap().to_obj().to_instance()[0])
+  }
+}
+
+/// A single component of a date time
+#[derive(Debug, Clone, Copy)]
+enum Component {
+  Year,
+  Month,
+  Day,
+  Hour,
+  Minute,
+  Second,
+  Weekday,
+}
+
+/// Read one component of a date time
+#[derive(Debug)]
+pub struct DateTimeComponent {
+  component: Component,
+  error: Value,
+}
+
+impl DateTimeComponent {
+  fn native(
+    hooks: &GcHooks,
+    meta: &NativeMetaBuilder,
+    component: Component,
+    error: Value,
+  ) -> GcObj<Native> {
+    let native = Box::new(Self { component, error }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+
+  fn call_error(&self, hooks: &mut Hooks, message: &str) -> Call {
+    create_error!(self.error, hooks, message)
+  }
+}
+
+impl Trace for DateTimeComponent {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for DateTimeComponent {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let date_time = get_date_time!(self, this, hooks);
+
+    let component = match self.component {
+      Component::Year => date_time.year() as f64,
+      Component::Month => date_time.month() as f64,
+      Component::Day => date_time.day() as f64,
+      Component::Hour => date_time.hour() as f64,
+      Component::Minute => date_time.minute() as f64,
+      Component::Second => date_time.second() as f64 + date_time.nanosecond() as f64 / 1e9,
+      Component::Weekday => date_time.weekday().number_from_monday() as f64,
+    };
+
+    Call::Ok(val!(component))
+  }
+}
+
+native_with_error!(DateTimeFormat, DATE_TIME_FORMAT);
+
+impl LyNative for DateTimeFormat {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let date_time = get_date_time!(self, this, hooks);
+    let pattern = args[0].to_obj().to_str();
+
+    // chrono only reports an invalid pattern once it's written out
+    if StrftimeItems::new(&pattern).any(|item| item == Item::Error) {
+      return self.call_error(hooks, format!("Invalid format pattern {}.", pattern));
+    }
+
+    let formatted = date_time.format(&pattern).to_string();
+    Call::Ok(val!(hooks.manage_str(formatted)))
+  }
+}
+
+native_with_duration!(DateTimeAdd, DATE_TIME_ADD);
+
+impl LyNative for DateTimeAdd {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match time_seconds(args[0], self.duration) {
+      Some(seconds) => {
+        let timestamp = this[0].to_num() + seconds;
+        match to_date_time(timestamp) {
+          Some(_) => Call::Ok(time_instance(&hooks.as_gc(), this.class(), timestamp)),
+          None => create_error!(self.error, hooks, "DateTime out of range."),
+        }
+      },
+      None => create_error!(self.error, hooks, "Can only add a Duration to a DateTime."),
+    }
+  }
+}
+
+native_with_duration!(DateTimeSub, DATE_TIME_SUB);
+
+impl LyNative for DateTimeSub {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    // the difference of two date times is a duration
+    if let Some(other) = time_seconds(args[0], this.class()) {
+      return Call::Ok(time_instance(
+        &hooks.as_gc(),
+        self.duration,
+        this[0].to_num() - other,
+      ));
+    }
+
+    match time_seconds(args[0], self.duration) {
+      Some(seconds) => {
+        let timestamp = this[0].to_num() - seconds;
+        match to_date_time(timestamp) {
+          Some(_) => Call::Ok(time_instance(&hooks.as_gc(), this.class(), timestamp)),
+          None => create_error!(self.error, hooks, "DateTime out of range."),
+        }
+      },
+      None => create_error!(
+        self.error,
+        hooks,
+        "Can only subtract a Duration or DateTime from a DateTime."
+      ),
+    }
+  }
+}
+
+native_with_error!(DateTimeStr, DATE_TIME_STR);
+
+impl LyNative for DateTimeStr {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let date_time = get_date_time!(self, this, hooks);
+    let formatted = date_time.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string();
+
+    Call::Ok(val!(hooks.manage_str(formatted)))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn timestamps() {
+    let date_time = to_date_time(1615734566.5).unwrap();
+    assert_eq!(date_time.to_string(), "2021-03-14 15:09:26.500");
+    assert_eq!(to_timestamp(&date_time), 1615734566.5);
+
+    let before_epoch = to_date_time(-1.25).unwrap();
+    assert_eq!(before_epoch.to_string(), "1969-12-31 23:59:58.750");
+    assert_eq!(to_timestamp(&before_epoch), -1.25);
+
+    assert!(to_date_time(f64::NAN).is_none());
+    assert!(to_date_time(1e300).is_none());
+  }
+
+  mod init {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+      let error = val!(hooks.manage_obj(Class::bare(hooks.manage_str("TimeError"))));
+
+      let init = DateTimeInit::native(&hooks, error);
+
+      assert_eq!(init.meta().name, "init");
+      assert_eq!(init.meta().signature.arity, Arity::Default(3, 6));
+      assert_eq!(
+        init.meta().signature.parameters[0].kind,
+        ParameterKind::Number
+      );
+    }
+  }
+}
@@ -0,0 +1,270 @@
+use super::{time_instance, time_seconds, TIME_ERROR};
+use crate::{
+  create_error, native,
+  support::{default_class_inheritance, export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use std::io::Write;
+
+pub const DURATION_CLASS_NAME: &str = "Duration";
+const DURATION_FIELD_SECONDS: &str = "secs";
+
+const DURATION_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("seconds", ParameterKind::Number)]);
+
+const DURATION_FROM_MILLIS: NativeMetaBuilder =
+  NativeMetaBuilder::method("fromMillis", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("millis", ParameterKind::Number)]);
+
+const DURATION_FROM_MINUTES: NativeMetaBuilder =
+  NativeMetaBuilder::method("fromMinutes", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("minutes", ParameterKind::Number)]);
+
+const DURATION_FROM_HOURS: NativeMetaBuilder =
+  NativeMetaBuilder::method("fromHours", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("hours", ParameterKind::Number)]);
+
+const DURATION_FROM_DAYS: NativeMetaBuilder =
+  NativeMetaBuilder::method("fromDays", Arity::Fixed(1))
+    .with_params(&[ParameterBuilder::new("days", ParameterKind::Number)]);
+
+const DURATION_SECONDS: NativeMetaBuilder = NativeMetaBuilder::method("seconds", Arity::Fixed(0));
+
+const DURATION_MILLIS: NativeMetaBuilder = NativeMetaBuilder::method("millis", Arity::Fixed(0));
+
+const DURATION_ADD: NativeMetaBuilder = NativeMetaBuilder::method("add", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("duration", ParameterKind::Instance)]);
+
+const DURATION_SUB: NativeMetaBuilder = NativeMetaBuilder::method("sub", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("duration", ParameterKind::Instance)]);
+
+const DURATION_MUL: NativeMetaBuilder = NativeMetaBuilder::method("mul", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("factor", ParameterKind::Number)]);
+
+const DURATION_STR: NativeMetaBuilder = NativeMetaBuilder::method("str", Arity::Fixed(0));
+
+pub fn declare_duration_class(
+  hooks: &GcHooks,
+  module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let class = default_class_inheritance(hooks, std, DURATION_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_duration_class(hooks: &GcHooks, module: &Module, _: &Package) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, DURATION_CLASS_NAME)?;
+  let duration = class;
+  let time_error = val!(load_class_from_module(hooks, module, TIME_ERROR)?);
+
+  class.add_field(hooks, hooks.manage_str(DURATION_FIELD_SECONDS));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DURATION_INIT.name),
+    val!(DurationInit::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DURATION_SECONDS.name),
+    val!(DurationSeconds::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DURATION_MILLIS.name),
+    val!(DurationMillis::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DURATION_ADD.name),
+    val!(DurationAdd::native(hooks, duration, time_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DURATION_SUB.name),
+    val!(DurationSub::native(hooks, duration, time_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DURATION_MUL.name),
+    val!(DurationMul::native(hooks)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(DURATION_STR.name),
+    val!(DurationStr::native(hooks)),
+  );
+
+  let mut meta_class = class.meta_class().expect("Meta class not set.");
+  for (meta, factor) in &[
+    (DURATION_FROM_MILLIS, 0.001),
+    (DURATION_FROM_MINUTES, 60.0),
+    (DURATION_FROM_HOURS, 3_600.0),
+    (DURATION_FROM_DAYS, 86_400.0),
+  ] {
+    meta_class.add_method(
+      hooks,
+      hooks.manage_str(meta.name),
+      val!(DurationFrom::native(hooks, meta, *factor)),
+    );
+  }
+
+  Ok(())
+}
+
+native!(DurationInit, DURATION_INIT);
+
+impl LyNative for DurationInit {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+    this[0] = args[0];
+
+    Call::Ok(val!(this))
+  }
+}
+
+/// Construct a duration from a number in some other unit
+#[derive(Debug)]
+pub struct DurationFrom {
+  /// The seconds in one of the unit
+  factor: f64,
+}
+
+impl DurationFrom {
+  fn native(hooks: &GcHooks, meta: &NativeMetaBuilder, factor: f64) -> GcObj<Native> {
+    let native = Box::new(Self { factor }) as Box<dyn LyNative>;
+    hooks.manage_obj(Native::new(meta.to_meta(hooks), native))
+  }
+}
+
+impl Trace for DurationFrom {}
+
+impl LyNative for DurationFrom {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let class = this.unwrap().to_obj().to_class();
+    Call::Ok(time_instance(
+      &hooks.as_gc(),
+      class,
+      args[0].to_num() * self.factor,
+    ))
+  }
+}
+
+native!(DurationSeconds, DURATION_SECONDS);
+
+impl LyNative for DurationSeconds {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(this.unwrap().to_obj().to_instance()[0])
+  }
+}
+
+native!(DurationMillis, DURATION_MILLIS);
+
+impl LyNative for DurationMillis {
+  fn call(&self, _hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(val!(
+      this.unwrap().to_obj().to_instance()[0].to_num() * 1_000.0
+    ))
+  }
+}
+
+native_with_duration!(DurationAdd, DURATION_ADD);
+
+impl LyNative for DurationAdd {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match time_seconds(args[0], self.duration) {
+      Some(seconds) => Call::Ok(time_instance(
+        &hooks.as_gc(),
+        this.class(),
+        this[0].to_num() + seconds,
+      )),
+      None => create_error!(self.error, hooks, "Can only add a Duration to a Duration."),
+    }
+  }
+}
+
+native_with_duration!(DurationSub, DURATION_SUB);
+
+impl LyNative for DurationSub {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match time_seconds(args[0], self.duration) {
+      Some(seconds) => Call::Ok(time_instance(
+        &hooks.as_gc(),
+        this.class(),
+        this[0].to_num() - seconds,
+      )),
+      None => create_error!(
+        self.error,
+        hooks,
+        "Can only subtract a Duration from a Duration."
+      ),
+    }
+  }
+}
+
+native!(DurationMul, DURATION_MUL);
+
+impl LyNative for DurationMul {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+    Call::Ok(time_instance(
+      &hooks.as_gc(),
+      this.class(),
+      this[0].to_num() * args[0].to_num(),
+    ))
+  }
+}
+
+native!(DurationStr, DURATION_STR);
+
+impl LyNative for DurationStr {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let seconds = this.unwrap().to_obj().to_instance()[0].to_num();
+    Call::Ok(val!(hooks.manage_str(format!("{}s", seconds))))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  mod from {
+    use super::*;
+    use crate::support::MockedContext;
+
+    #[test]
+    fn new() {
+      let context = MockedContext::default();
+      let hooks = GcHooks::new(&context);
+
+      let from_days = DurationFrom::native(&hooks, &DURATION_FROM_DAYS, 86_400.0);
+
+      assert_eq!(from_days.meta().name, "fromDays");
+      assert_eq!(from_days.meta().signature.arity, Arity::Fixed(1));
+      assert_eq!(
+        from_days.meta().signature.parameters[0].kind,
+        ParameterKind::Number
+      );
+    }
+  }
+}
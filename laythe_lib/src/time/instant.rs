@@ -0,0 +1,111 @@
+use super::{duration::DURATION_CLASS_NAME, time_instance, time_seconds, TIME_ERROR};
+use crate::{
+  create_error, native_with_error,
+  support::{default_class_inheritance, export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Class, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use std::io::Write;
+
+const INSTANT_CLASS_NAME: &str = "Instant";
+const INSTANT_FIELD_OFFSET: &str = "offset";
+
+const INSTANT_NOW: NativeMetaBuilder = NativeMetaBuilder::method("now", Arity::Fixed(0));
+
+const INSTANT_ELAPSED: NativeMetaBuilder = NativeMetaBuilder::method("elapsed", Arity::Fixed(0));
+
+const INSTANT_SINCE: NativeMetaBuilder = NativeMetaBuilder::method("since", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("earlier", ParameterKind::Instance)]);
+
+pub fn declare_instant_class(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let class = default_class_inheritance(hooks, std, INSTANT_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_instant_class(hooks: &GcHooks, module: &Module, _: &Package) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, INSTANT_CLASS_NAME)?;
+  let duration = load_class_from_module(hooks, module, DURATION_CLASS_NAME)?;
+  let time_error = val!(load_class_from_module(hooks, module, TIME_ERROR)?);
+
+  class.add_field(hooks, hooks.manage_str(INSTANT_FIELD_OFFSET));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(INSTANT_ELAPSED.name),
+    val!(InstantElapsed::native(hooks, duration, time_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(INSTANT_SINCE.name),
+    val!(InstantSince::native(hooks, duration, time_error)),
+  );
+
+  class.meta_class().expect("Meta class not set.").add_method(
+    hooks,
+    hooks.manage_str(INSTANT_NOW.name),
+    val!(InstantNow::native(hooks, time_error)),
+  );
+
+  Ok(())
+}
+
+native_with_error!(InstantNow, INSTANT_NOW);
+
+impl LyNative for InstantNow {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let class = this.unwrap().to_obj().to_class();
+
+    match hooks.as_io().time().elapsed() {
+      Ok(elapsed) => Call::Ok(time_instance(&hooks.as_gc(), class, elapsed.as_secs_f64())),
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native_with_duration!(InstantElapsed, INSTANT_ELAPSED);
+
+impl LyNative for InstantElapsed {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let offset = this.unwrap().to_obj().to_instance()[0].to_num();
+
+    match hooks.as_io().time().elapsed() {
+      Ok(elapsed) => Call::Ok(time_instance(
+        &hooks.as_gc(),
+        self.duration,
+        elapsed.as_secs_f64() - offset,
+      )),
+      Err(err) => create_error!(self.error, hooks, err),
+    }
+  }
+}
+
+native_with_duration!(InstantSince, INSTANT_SINCE);
+
+impl LyNative for InstantSince {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match time_seconds(args[0], this.class()) {
+      Some(earlier) => Call::Ok(time_instance(
+        &hooks.as_gc(),
+        self.duration,
+        this[0].to_num() - earlier,
+      )),
+      None => create_error!(
+        self.error,
+        hooks,
+        "Can only find the time since an Instant."
+      ),
+    }
+  }
+}
@@ -0,0 +1,127 @@
+/// Define a native function that accepts or returns durations, holding
+/// the duration class and the error it raises
+macro_rules! native_with_duration {
+  ( $st:ident, $meta:ident ) => {
+    #[derive(Debug)]
+    pub struct $st {
+      duration: GcObj<Class>,
+      error: Value,
+    }
+
+    impl $st {
+      fn native(hooks: &GcHooks, duration: GcObj<Class>, error: Value) -> GcObj<Native> {
+        let native = Box::new(Self { duration, error }) as Box<dyn LyNative>;
+        hooks.manage_obj(Native::new($meta.to_meta(hooks), native))
+      }
+    }
+
+    impl Trace for $st {
+      fn trace(&self) {
+        self.duration.trace();
+        self.error.trace();
+      }
+
+      fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+        self.duration.visit_refs(visitor);
+        self.error.visit_refs(visitor);
+      }
+
+      fn trace_debug(&self, stdio: &mut dyn Write) {
+        self.duration.trace_debug(stdio);
+        self.error.trace_debug(stdio);
+      }
+    }
+  };
+}
+
+mod date_time;
+mod duration;
+mod instant;
+mod timer;
+
+use laythe_core::{
+  hooks::GcHooks,
+  managed::{Gc, GcObj},
+  module::{Module, Package},
+  object::{Class, Instance, ObjectKind},
+  utils::IdEmitter,
+  val,
+  value::Value,
+};
+use std::path::PathBuf;
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+
+use self::{
+  date_time::{declare_date_time_class, define_date_time_class},
+  duration::{declare_duration_class, define_duration_class},
+  instant::{declare_instant_class, define_instant_class},
+  timer::declare_timer_functions,
+};
+
+const TIME_PATH: &str = "std/time";
+const TIME_ERROR: &str = "TimeError";
+
+pub fn time_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(TIME_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let time_error = default_error_inheritance(hooks, std, TIME_ERROR)?;
+  export_and_insert(hooks, &mut module, time_error.name(), val!(time_error))?;
+
+  declare_duration_class(hooks, &mut module, std)?;
+  declare_date_time_class(hooks, &mut module, std)?;
+  declare_instant_class(hooks, &mut module, std)?;
+
+  define_duration_class(hooks, &module, std)?;
+  define_date_time_class(hooks, &module, std)?;
+  define_instant_class(hooks, &module, std)?;
+
+  declare_timer_functions(hooks, &mut module, std)?;
+
+  Ok(module)
+}
+
+/// Each of the time classes holds a number of seconds in its only field.
+/// Create an instance of one of them
+fn time_instance(hooks: &GcHooks, class: GcObj<Class>, seconds: f64) -> Value {
+  let mut instance = hooks.manage_obj(Instance::new(class));
+  instance[0] = val!(seconds);
+  val!(instance)
+}
+
+/// The seconds held by a value if it is an instance of the provided time
+/// class or one of its subclasses
+fn time_seconds(value: Value, class: GcObj<Class>) -> Option<f64> {
+  if !value.is_obj_kind(ObjectKind::Instance) {
+    return None;
+  }
+
+  let instance = value.to_obj().to_instance();
+  let mut current = Some(instance.class());
+
+  while let Some(cls) = current {
+    if cls == class {
+      return Some(instance[0])
+        .filter(|seconds| seconds.is_num())
+        .map(|seconds| seconds.to_num());
+    }
+    current = *cls.super_class();
+  }
+
+  None
+}
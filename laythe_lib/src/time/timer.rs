@@ -0,0 +1,309 @@
+use super::TIME_ERROR;
+use crate::{
+  create_error,
+  global::TIMEOUT_ERROR_NAME,
+  native_with_error,
+  support::{export_and_insert, load_class_from_module, load_class_from_package},
+  StdResult, STD,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Channel, Fiber, FiberState, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::Value,
+  Call,
+};
+use std::{cell::RefCell, io::Write, time::Duration};
+
+const AFTER: NativeMetaBuilder = NativeMetaBuilder::fun("after", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("ms", ParameterKind::Number)]);
+
+const EVERY: NativeMetaBuilder = NativeMetaBuilder::fun("every", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("ms", ParameterKind::Number)]);
+
+const WITH_TIMEOUT: NativeMetaBuilder = NativeMetaBuilder::fun("withTimeout", Arity::Fixed(2))
+  .with_params(&[
+    ParameterBuilder::new("ms", ParameterKind::Number),
+    ParameterBuilder::new("fun", ParameterKind::Fun),
+  ]);
+
+pub fn declare_timer_functions(
+  hooks: &GcHooks,
+  module: &mut Module,
+  std: &Package,
+) -> StdResult<()> {
+  let time_error = val!(load_class_from_module(hooks, module, TIME_ERROR)?);
+  let timeout_error = val!(load_class_from_package(
+    hooks,
+    std,
+    STD,
+    TIMEOUT_ERROR_NAME
+  )?);
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(AFTER.name),
+    val!(After::native(hooks, time_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(EVERY.name),
+    val!(Every::native(hooks, time_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(WITH_TIMEOUT.name),
+    val!(WithTimeout::native(hooks, time_error, timeout_error)),
+  )
+}
+
+/// The duration of a number of milliseconds if it is finite and not negative
+fn millis(ms: f64) -> Option<Duration> {
+  if ms.is_finite() && ms >= 0.0 {
+    Some(Duration::from_secs_f64(ms / 1000.0))
+  } else {
+    None
+  }
+}
+
+native_with_error!(After, AFTER);
+
+impl LyNative for After {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let duration = match millis(args[0].to_num()) {
+      Some(duration) => duration,
+      None => return self.call_error(hooks, "after duration must be a non negative number."),
+    };
+
+    let channel = hooks.manage_obj(Channel::new(1));
+    hooks.add_timer(channel, duration, false);
+    Call::Ok(val!(channel))
+  }
+}
+
+native_with_error!(Every, EVERY);
+
+impl LyNative for Every {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let duration = match millis(args[0].to_num()) {
+      Some(duration) if duration > Duration::default() => duration,
+      _ => return self.call_error(hooks, "every interval must be a positive number."),
+    };
+
+    let channel = hooks.manage_obj(Channel::new(1));
+    hooks.add_timer(channel, duration, true);
+    Call::Ok(val!(channel))
+  }
+}
+
+/// A withTimeout call waiting on the fiber running its function
+#[derive(Debug, Clone, Copy)]
+struct Pending {
+  caller: GcObj<Fiber>,
+  fiber: GcObj<Fiber>,
+  fun: Value,
+}
+
+/// Run a function on its own fiber, raising a TimeoutError and cancelling
+/// the fiber if it has not completed within the duration. The caller
+/// sleeps for the duration and is woken early if the fiber completes
+#[derive(Debug)]
+pub struct WithTimeout {
+  pending: RefCell<Vec<Pending>>,
+  error: Value,
+  timeout_error: Value,
+}
+
+impl WithTimeout {
+  fn native(hooks: &GcHooks, error: Value, timeout_error: Value) -> GcObj<Native> {
+    let native = Box::new(Self {
+      pending: RefCell::new(vec![]),
+      error,
+      timeout_error,
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(WITH_TIMEOUT.to_meta(hooks), native))
+  }
+
+  /// Start running the function on a new fiber
+  fn start(&self, hooks: &mut Hooks, caller: GcObj<Fiber>, duration: Duration, fun: Value) -> Call {
+    if !fun.is_obj_kind(ObjectKind::Closure) {
+      return create_error!(self.error, hooks, "withTimeout expects a laythe function.");
+    }
+
+    let closure = fun.to_obj().to_closure();
+    if closure.fun().arity().check(0).is_err() {
+      return create_error!(
+        self.error,
+        hooks,
+        &format!(
+          "withTimeout expects a function without parameters but {} has parameters.",
+          &*closure.fun().name()
+        )
+      );
+    }
+
+    let mut fiber = match Fiber::new(closure) {
+      Ok(fiber) => hooks.manage_obj(fiber),
+      Err(_) => return create_error!(self.error, hooks, "Unable to create fiber."),
+    };
+
+    // the caller raises the fiber's error so the fiber never reports it
+    fiber.supervise();
+    hooks.spawn_fiber(fiber);
+    hooks.grow(&mut *fiber, |fiber| fiber.add_waiter(caller));
+    self
+      .pending
+      .borrow_mut()
+      .push(Pending { caller, fiber, fun });
+
+    if !hooks.sleep_fiber(duration) {
+      hooks.sleep_fiber(duration);
+    }
+    Call::Block
+  }
+
+  /// Resume a caller woken by its fiber completing or its sleep ending
+  fn resume(&self, hooks: &mut Hooks, pending: Pending, duration: Duration) -> Call {
+    let Pending {
+      caller, mut fiber, ..
+    } = pending;
+
+    match fiber.state() {
+      FiberState::Complete => {
+        self.remove(caller);
+        hooks.clear_sleep();
+        return Call::Ok(fiber.result());
+      },
+      FiberState::Failed => {
+        self.remove(caller);
+        hooks.clear_sleep();
+        return Call::Err(fiber.error().expect("Failed fiber did not set an error."));
+      },
+      _ => (),
+    }
+
+    // woken for another reason, keep waiting
+    if hooks.sleep_fiber(duration) {
+      return Call::Block;
+    }
+
+    fiber.remove_waiter(caller);
+    let message = format!("withTimeout timed out after {}ms.", duration.as_millis());
+
+    let cancel = match create_error!(self.timeout_error, hooks, &message) {
+      Call::Err(error) => error,
+      call => return call,
+    };
+    hooks.cancel_fiber(fiber, cancel);
+
+    self.remove(caller);
+    create_error!(self.timeout_error, hooks, &message)
+  }
+
+  /// Take a caller's pending call, dropping calls whose caller has completed
+  fn find(&self, caller: GcObj<Fiber>) -> Option<Pending> {
+    let mut pending = self.pending.borrow_mut();
+    pending.retain(|pending| !pending.caller.is_complete());
+    pending
+      .iter()
+      .find(|pending| pending.caller == caller)
+      .copied()
+  }
+
+  /// Remove a caller's pending call
+  fn remove(&self, caller: GcObj<Fiber>) {
+    self
+      .pending
+      .borrow_mut()
+      .retain(|pending| pending.caller != caller);
+  }
+}
+
+impl Trace for WithTimeout {
+  fn trace(&self) {
+    self.pending.borrow().iter().for_each(|pending| {
+      pending.caller.trace();
+      pending.fiber.trace();
+      pending.fun.trace();
+    });
+    self.error.trace();
+    self.timeout_error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.pending.borrow().iter().for_each(|pending| {
+      pending.caller.visit_refs(visitor);
+      pending.fiber.visit_refs(visitor);
+      pending.fun.visit_refs(visitor);
+    });
+    self.error.visit_refs(visitor);
+    self.timeout_error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, stdio: &mut dyn Write) {
+    self.pending.borrow().iter().for_each(|pending| {
+      pending.caller.trace_debug(stdio);
+      pending.fiber.trace_debug(stdio);
+      pending.fun.trace_debug(stdio);
+    });
+    self.error.trace_debug(stdio);
+    self.timeout_error.trace_debug(stdio);
+  }
+}
+
+impl LyNative for WithTimeout {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let duration = match millis(args[0].to_num()) {
+      Some(duration) => duration,
+      None => {
+        return create_error!(
+          self.error,
+          hooks,
+          "withTimeout duration must be a non negative number."
+        )
+      },
+    };
+
+    let caller = hooks.current_fiber();
+    match self.find(caller) {
+      Some(pending) if pending.fun == args[1] => self.resume(hooks, pending, duration),
+      Some(Pending { mut fiber, .. }) => {
+        // the caller was cancelled while waiting so this is a new call
+        fiber.remove_waiter(caller);
+        self.remove(caller);
+        self.start(hooks, caller, duration, args[1])
+      },
+      None => self.start(hooks, caller, duration, args[1]),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::MockedContext;
+  use laythe_core::value::VALUE_NIL;
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let with_timeout = WithTimeout::native(&hooks, VALUE_NIL, VALUE_NIL);
+    assert_eq!(with_timeout.meta().name, "withTimeout");
+    assert_eq!(with_timeout.meta().signature.arity, Arity::Fixed(2));
+    assert_eq!(
+      with_timeout.meta().signature.parameters[1].kind,
+      ParameterKind::Fun
+    );
+  }
+}
@@ -0,0 +1,249 @@
+use super::{block, receive, WORKER_ERROR};
+use crate::{
+  native_with_error,
+  support::{default_class_inheritance, export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::{Module, Package},
+  object::{Instance, LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  worker::{Message, WorkerHandle},
+  Call,
+};
+use std::{any::Any, cell::RefCell, io::Write, path::Path};
+
+const WORKER_CLASS_NAME: &str = "Worker";
+
+const WORKER_FIELD_STATE: &str = "state";
+
+const WORKER_INIT: NativeMetaBuilder = NativeMetaBuilder::method("init", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("path", ParameterKind::String)]);
+
+const WORKER_SEND: NativeMetaBuilder = NativeMetaBuilder::method("send", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("value", ParameterKind::Any)]);
+
+const WORKER_RECEIVE: NativeMetaBuilder = NativeMetaBuilder::method("receive", Arity::Fixed(0));
+
+const WORKER_CLOSE: NativeMetaBuilder = NativeMetaBuilder::method("close", Arity::Fixed(0));
+
+const WORKER_JOIN: NativeMetaBuilder = NativeMetaBuilder::method("join", Arity::Fixed(0));
+
+const WORKER_TERMINATE: NativeMetaBuilder = NativeMetaBuilder::method("terminate", Arity::Fixed(0));
+
+const WORKER_STATE: NativeMetaBuilder = NativeMetaBuilder::fun("state", Arity::Fixed(0));
+
+pub fn declare_worker_class(hooks: &GcHooks, module: &mut Module, std: &Package) -> StdResult<()> {
+  let class = default_class_inheritance(hooks, std, WORKER_CLASS_NAME)?;
+  export_and_insert(hooks, module, class.name(), val!(class))
+}
+
+pub fn define_worker_class(hooks: &GcHooks, module: &Module) -> StdResult<()> {
+  let mut class = load_class_from_module(hooks, module, WORKER_CLASS_NAME)?;
+  let worker_error = val!(load_class_from_module(hooks, module, WORKER_ERROR)?);
+
+  class.add_field(hooks, hooks.manage_str(WORKER_FIELD_STATE));
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(WORKER_INIT.name),
+    val!(WorkerInit::native(hooks, worker_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(WORKER_SEND.name),
+    val!(WorkerSend::native(hooks, worker_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(WORKER_RECEIVE.name),
+    val!(WorkerReceive::native(hooks, worker_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(WORKER_CLOSE.name),
+    val!(WorkerClose::native(hooks, worker_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(WORKER_JOIN.name),
+    val!(WorkerJoin::native(hooks, worker_error)),
+  );
+
+  class.add_method(
+    hooks,
+    hooks.manage_str(WORKER_TERMINATE.name),
+    val!(WorkerTerminate::native(hooks, worker_error)),
+  );
+
+  Ok(())
+}
+
+/// The worker thread held by a Worker instance. The worker only holds
+/// messages copied out of the heap so there is nothing to trace
+#[derive(Debug)]
+pub struct WorkerState {
+  worker: RefCell<WorkerHandle>,
+}
+
+impl WorkerState {
+  fn native(hooks: &GcHooks, worker: WorkerHandle) -> GcObj<Native> {
+    let native = Box::new(Self {
+      worker: RefCell::new(worker),
+    }) as Box<dyn LyNative>;
+
+    hooks.manage_obj(Native::new(WORKER_STATE.to_meta(hooks), native))
+  }
+}
+
+impl Trace for WorkerState {}
+
+impl LyNative for WorkerState {
+  fn call(&self, _hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    Call::Ok(VALUE_NIL)
+  }
+
+  fn as_any(&self) -> Option<&dyn Any> {
+    Some(self)
+  }
+}
+
+/// Run an action against the worker held by a Worker instance
+fn with_worker<R>(
+  instance: GcObj<Instance>,
+  action: impl FnOnce(&mut WorkerHandle) -> R,
+) -> Option<R> {
+  let state = instance[0];
+  if !state.is_obj_kind(ObjectKind::Native) {
+    return None;
+  }
+
+  let native = state.to_obj().to_native();
+  native
+    .downcast_ref::<WorkerState>()
+    .map(|state| action(&mut state.worker.borrow_mut()))
+}
+
+native_with_error!(WorkerInit, WORKER_INIT);
+
+impl LyNative for WorkerInit {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let mut this = this.unwrap().to_obj().to_instance();
+    let path = args[0].to_obj().to_str();
+
+    match hooks.spawn_worker(Path::new(&*path)) {
+      Ok(worker) => {
+        this[0] = val!(WorkerState::native(&hooks.as_gc(), worker));
+        Call::Ok(val!(this))
+      },
+      Err(err) => self.call_error(hooks, err),
+    }
+  }
+}
+
+native_with_error!(WorkerSend, WORKER_SEND);
+
+impl LyNative for WorkerSend {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+    let message = match Message::from_value(args[0]) {
+      Ok(message) => message,
+      Err(err) => return self.call_error(hooks, err),
+    };
+
+    match with_worker(this, |worker| worker.port().send(message)) {
+      Some(true) => Call::Ok(VALUE_NIL),
+      Some(false) => self.call_error(hooks, "Unable to send, the worker has closed or exited."),
+      None => self.call_error(hooks, "Worker was not initialized."),
+    }
+  }
+}
+
+native_with_error!(WorkerReceive, WORKER_RECEIVE);
+
+impl LyNative for WorkerReceive {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match with_worker(this, |worker| worker.port().try_receive()) {
+      Some(received) => receive(hooks, received),
+      None => self.call_error(hooks, "Worker was not initialized."),
+    }
+  }
+}
+
+native_with_error!(WorkerClose, WORKER_CLOSE);
+
+impl LyNative for WorkerClose {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match with_worker(this, |worker| worker.close()) {
+      Some(()) => Call::Ok(VALUE_NIL),
+      None => self.call_error(hooks, "Worker was not initialized."),
+    }
+  }
+}
+
+native_with_error!(WorkerJoin, WORKER_JOIN);
+
+impl LyNative for WorkerJoin {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match with_worker(this, |worker| worker.try_join()) {
+      Some(Some(result)) => {
+        hooks.current_fiber().wake_from_sleep();
+
+        match result {
+          Ok(exit_code) => Call::Ok(val!(exit_code as f64)),
+          Err(err) => self.call_error(hooks, format!("Worker failed. {}", err)),
+        }
+      },
+      Some(None) => block(hooks),
+      None => self.call_error(hooks, "Worker was not initialized."),
+    }
+  }
+}
+
+native_with_error!(WorkerTerminate, WORKER_TERMINATE);
+
+impl LyNative for WorkerTerminate {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, _args: &[Value]) -> Call {
+    let this = this.unwrap().to_obj().to_instance();
+
+    match with_worker(this, |worker| worker.terminate()) {
+      Some(()) => Call::Ok(VALUE_NIL),
+      None => self.call_error(hooks, "Worker was not initialized."),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let error = val!(test_error_class(&hooks));
+
+    let worker_init = WorkerInit::native(&hooks, error);
+    assert_eq!(worker_init.meta().name, "init");
+    assert_eq!(worker_init.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(
+      worker_init.meta().signature.parameters[0].kind,
+      ParameterKind::String
+    );
+  }
+}
@@ -0,0 +1,81 @@
+mod class;
+mod port;
+
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::Gc,
+  module::{Module, Package},
+  utils::IdEmitter,
+  val,
+  value::{Value, VALUE_NIL},
+  worker::Received,
+  Call,
+};
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+  global::MODULE_CLASS_NAME,
+  support::{default_error_inheritance, export_and_insert, load_class_from_package},
+  StdResult, STD,
+};
+
+use self::{
+  class::{declare_worker_class, define_worker_class},
+  port::declare_port_functions,
+};
+
+const WORKER_PATH: &str = "std/worker";
+const WORKER_ERROR: &str = "WorkerError";
+
+/// How often a fiber waiting on a worker checks it again
+const WORKER_POLL: Duration = Duration::from_millis(2);
+
+pub fn worker_module(
+  hooks: &GcHooks,
+  std: &Package,
+  emitter: &mut IdEmitter,
+) -> StdResult<Gc<Module>> {
+  let module_class = load_class_from_package(hooks, std, STD, MODULE_CLASS_NAME)?;
+
+  let mut module = hooks.manage(Module::from_path(
+    hooks,
+    PathBuf::from(WORKER_PATH),
+    module_class,
+    emitter.emit(),
+  )?);
+
+  let worker_error = default_error_inheritance(hooks, std, WORKER_ERROR)?;
+  export_and_insert(hooks, &mut module, worker_error.name(), val!(worker_error))?;
+
+  declare_worker_class(hooks, &mut module, std)?;
+  define_worker_class(hooks, &module)?;
+
+  declare_port_functions(hooks, &mut module)?;
+
+  Ok(module)
+}
+
+/// Return a message received from a port, blocking the fiber to check
+/// again while no message is waiting. Nil is returned once the port closes
+fn receive(hooks: &mut Hooks, received: Received) -> Call {
+  match received {
+    Received::Message(message) => {
+      hooks.current_fiber().wake_from_sleep();
+      Call::Ok(message.to_value(&hooks.as_gc()))
+    },
+    Received::Empty => block(hooks),
+    Received::Closed => {
+      hooks.current_fiber().wake_from_sleep();
+      Call::Ok(VALUE_NIL)
+    },
+  }
+}
+
+/// Sleep the current fiber so a call waiting on a worker is retried once it wakes
+fn block(hooks: &mut Hooks) -> Call {
+  if !hooks.sleep_fiber(WORKER_POLL) {
+    hooks.sleep_fiber(WORKER_POLL);
+  }
+
+  Call::Block
+}
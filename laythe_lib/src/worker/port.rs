@@ -0,0 +1,91 @@
+use super::{receive, WORKER_ERROR};
+use crate::{
+  native_with_error,
+  support::{export_and_insert, load_class_from_module},
+  StdResult,
+};
+use laythe_core::{
+  hooks::{GcHooks, Hooks},
+  managed::{GcObj, Trace},
+  module::Module,
+  object::{LyNative, Native, NativeMetaBuilder, ObjectKind},
+  signature::{Arity, ParameterBuilder, ParameterKind},
+  val,
+  value::{Value, VALUE_NIL},
+  worker::Message,
+  Call,
+};
+use std::io::Write;
+
+const SEND: NativeMetaBuilder = NativeMetaBuilder::fun("send", Arity::Fixed(1))
+  .with_params(&[ParameterBuilder::new("value", ParameterKind::Any)]);
+
+const RECEIVE: NativeMetaBuilder = NativeMetaBuilder::fun("receive", Arity::Fixed(0));
+
+pub fn declare_port_functions(hooks: &GcHooks, module: &mut Module) -> StdResult<()> {
+  let worker_error = val!(load_class_from_module(hooks, module, WORKER_ERROR)?);
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(SEND.name),
+    val!(PortSend::native(hooks, worker_error)),
+  )?;
+
+  export_and_insert(
+    hooks,
+    module,
+    hooks.manage_str(RECEIVE.name),
+    val!(PortReceive::native(hooks, worker_error)),
+  )
+}
+
+native_with_error!(PortSend, SEND);
+
+impl LyNative for PortSend {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, args: &[Value]) -> Call {
+    let message = match Message::from_value(args[0]) {
+      Ok(message) => message,
+      Err(err) => return self.call_error(hooks, err),
+    };
+
+    match hooks.worker_port().map(|port| port.send(message)) {
+      Some(true) => Call::Ok(VALUE_NIL),
+      Some(false) => self.call_error(hooks, "Unable to send, the parent has hung up."),
+      None => self.call_error(hooks, "send can only be called from a worker."),
+    }
+  }
+}
+
+native_with_error!(PortReceive, RECEIVE);
+
+impl LyNative for PortReceive {
+  fn call(&self, hooks: &mut Hooks, _this: Option<Value>, _args: &[Value]) -> Call {
+    match hooks.worker_port().map(|port| port.try_receive()) {
+      Some(received) => receive(hooks, received),
+      None => self.call_error(hooks, "receive can only be called from a worker."),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::support::{test_error_class, MockedContext};
+
+  #[test]
+  fn new() {
+    let context = MockedContext::default();
+    let hooks = GcHooks::new(&context);
+    let error = val!(test_error_class(&hooks));
+
+    let receive = PortReceive::native(&hooks, error);
+    assert_eq!(receive.meta().name, "receive");
+    assert_eq!(receive.meta().signature.arity, Arity::Fixed(0));
+
+    let send = PortSend::native(&hooks, error);
+    assert_eq!(send.meta().name, "send");
+    assert_eq!(send.meta().signature.arity, Arity::Fixed(1));
+    assert_eq!(send.meta().signature.parameters[0].kind, ParameterKind::Any);
+  }
+}
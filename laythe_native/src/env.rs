@@ -30,4 +30,17 @@ impl EnvImpl for EnvNative {
   fn args(&self) -> Vec<String> {
     env::args().collect()
   }
+
+  fn var(&self, name: &str) -> Option<String> {
+    env::var(name).ok()
+  }
+
+  fn set_var(&self, name: &str, value: &str) -> io::Result<()> {
+    env::set_var(name, value);
+    Ok(())
+  }
+
+  fn platform(&self) -> &str {
+    env::consts::OS
+  }
 }
@@ -0,0 +1,339 @@
+use libffi::middle::{Arg, Cif, CodePtr, Type};
+use libloading::{Library, Symbol};
+use std::{
+  ffi::{CStr, CString},
+  fmt,
+  os::raw::{c_char, c_void},
+  path::Path,
+  ptr,
+  sync::Arc,
+};
+
+/// The C types a foreign function can take or return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiType {
+  I32,
+  I64,
+  F32,
+  F64,
+  Pointer,
+  String,
+  Void,
+}
+
+impl FfiType {
+  /// Parse the name of a type as written in a signature
+  pub fn parse(name: &str) -> Option<Self> {
+    match name {
+      "i32" => Some(FfiType::I32),
+      "i64" => Some(FfiType::I64),
+      "f32" => Some(FfiType::F32),
+      "f64" => Some(FfiType::F64),
+      "pointer" => Some(FfiType::Pointer),
+      "string" => Some(FfiType::String),
+      "void" => Some(FfiType::Void),
+      _ => None,
+    }
+  }
+
+  /// The name of this type as written in a signature
+  pub fn name(&self) -> &'static str {
+    match self {
+      FfiType::I32 => "i32",
+      FfiType::I64 => "i64",
+      FfiType::F32 => "f32",
+      FfiType::F64 => "f64",
+      FfiType::Pointer => "pointer",
+      FfiType::String => "string",
+      FfiType::Void => "void",
+    }
+  }
+
+  fn to_ffi(self) -> Type {
+    match self {
+      FfiType::I32 => Type::i32(),
+      FfiType::I64 => Type::i64(),
+      FfiType::F32 => Type::f32(),
+      FfiType::F64 => Type::f64(),
+      FfiType::Pointer | FfiType::String => Type::pointer(),
+      FfiType::Void => Type::void(),
+    }
+  }
+}
+
+impl fmt::Display for FfiType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+/// A value passed to or returned from a foreign function
+#[derive(Debug, Clone, PartialEq)]
+pub enum FfiValue {
+  Int(i64),
+  Float(f64),
+  Pointer(usize),
+  String(String),
+  Null,
+  Void,
+}
+
+/// An argument converted to the C type of its parameter. Strings own
+/// the buffer passed to the function for the length of the call
+enum Slot {
+  I32(i32),
+  I64(i64),
+  F32(f32),
+  F64(f64),
+  Pointer(*const c_void),
+  String {
+    _buffer: CString,
+    pointer: *const c_char,
+  },
+}
+
+impl Slot {
+  fn arg(&self) -> Arg {
+    match self {
+      Slot::I32(value) => Arg::new(value),
+      Slot::I64(value) => Arg::new(value),
+      Slot::F32(value) => Arg::new(value),
+      Slot::F64(value) => Arg::new(value),
+      Slot::Pointer(value) => Arg::new(value),
+      Slot::String { pointer, .. } => Arg::new(pointer),
+    }
+  }
+}
+
+/// A shared library opened for calling foreign functions. The library
+/// stays loaded until every function declared from it is dropped
+#[derive(Clone)]
+pub struct ForeignLibrary {
+  library: Arc<Library>,
+}
+
+impl ForeignLibrary {
+  /// Open the shared library at the provided path. A bare file name is
+  /// searched for the way the platform's dynamic loader would
+  pub fn open(path: &Path) -> Result<Self, String> {
+    let library = unsafe { Library::new(path) }
+      .map_err(|err| format!("Unable to open library {}: {}", path.display(), err))?;
+
+    Ok(Self {
+      library: Arc::new(library),
+    })
+  }
+
+  /// Declare the signature of a function exported by this library
+  pub fn declare(
+    &self,
+    name: &str,
+    parameters: Vec<FfiType>,
+    result: FfiType,
+  ) -> Result<ForeignFn, String> {
+    if let Some(void) = parameters.iter().find(|kind| **kind == FfiType::Void) {
+      return Err(format!("Parameters of {} cannot be {}.", name, void));
+    }
+
+    let code = unsafe {
+      let symbol: Symbol<*mut c_void> = self
+        .library
+        .get(name.as_bytes())
+        .map_err(|_| format!("Library does not export {}.", name))?;
+      CodePtr(*symbol)
+    };
+
+    let cif = Cif::new(parameters.iter().map(|kind| kind.to_ffi()), result.to_ffi());
+
+    Ok(ForeignFn {
+      _library: Arc::clone(&self.library),
+      name: name.to_string(),
+      code,
+      cif,
+      parameters,
+      result,
+    })
+  }
+}
+
+impl fmt::Debug for ForeignLibrary {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ForeignLibrary").finish()
+  }
+}
+
+/// A function exported by a shared library with a declared signature
+pub struct ForeignFn {
+  _library: Arc<Library>,
+  name: String,
+  code: CodePtr,
+  cif: Cif,
+  parameters: Vec<FfiType>,
+  result: FfiType,
+}
+
+// The library is kept loaded by the handle and the cif and code pointer
+// are only read once the function is declared
+unsafe impl Send for ForeignFn {}
+
+impl ForeignFn {
+  /// The name of the function in its library
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// The types of the function's parameters
+  pub fn parameters(&self) -> &[FfiType] {
+    &self.parameters
+  }
+
+  /// The type the function returns
+  pub fn result(&self) -> FfiType {
+    self.result
+  }
+
+  /// Call the function, converting each argument to the C type of its
+  /// parameter and the result back from the declared return type.
+  /// Returned strings are copied and left owned by the library
+  ///
+  /// # Safety
+  ///
+  /// The declared signature must match the function's actual signature
+  /// and the function must accept the provided arguments. Nothing here
+  /// can check either
+  pub unsafe fn call(&self, args: &[FfiValue]) -> Result<FfiValue, String> {
+    if args.len() != self.parameters.len() {
+      return Err(format!(
+        "{} expected {} argument(s) but received {}.",
+        self.name,
+        self.parameters.len(),
+        args.len()
+      ));
+    }
+
+    let slots = self
+      .parameters
+      .iter()
+      .zip(args)
+      .enumerate()
+      .map(|(index, (kind, arg))| self.slot(index, *kind, arg))
+      .collect::<Result<Vec<Slot>, String>>()?;
+    let args: Vec<Arg> = slots.iter().map(Slot::arg).collect();
+
+    // integers smaller than a register are widened when returned
+    // so they are read back through a full register
+    Ok(match self.result {
+      FfiType::I32 => FfiValue::Int(self.cif.call::<i64>(self.code, &args) as i32 as i64),
+      FfiType::I64 => FfiValue::Int(self.cif.call::<i64>(self.code, &args)),
+      FfiType::F32 => FfiValue::Float(self.cif.call::<f32>(self.code, &args) as f64),
+      FfiType::F64 => FfiValue::Float(self.cif.call::<f64>(self.code, &args)),
+      FfiType::Pointer => match self.cif.call::<usize>(self.code, &args) {
+        0 => FfiValue::Null,
+        address => FfiValue::Pointer(address),
+      },
+      FfiType::String => {
+        let string = self.cif.call::<*const c_char>(self.code, &args);
+        if string.is_null() {
+          FfiValue::Null
+        } else {
+          FfiValue::String(CStr::from_ptr(string).to_string_lossy().into_owned())
+        }
+      },
+      FfiType::Void => {
+        self.cif.call::<u64>(self.code, &args);
+        FfiValue::Void
+      },
+    })
+  }
+
+  fn slot(&self, index: usize, kind: FfiType, arg: &FfiValue) -> Result<Slot, String> {
+    match (kind, arg) {
+      (FfiType::I32, FfiValue::Int(value))
+        if i32::MIN as i64 <= *value && *value <= i32::MAX as i64 =>
+      {
+        Ok(Slot::I32(*value as i32))
+      },
+      (FfiType::I64, FfiValue::Int(value)) => Ok(Slot::I64(*value)),
+      (FfiType::F32, FfiValue::Float(value)) => Ok(Slot::F32(*value as f32)),
+      (FfiType::F32, FfiValue::Int(value)) => Ok(Slot::F32(*value as f32)),
+      (FfiType::F64, FfiValue::Float(value)) => Ok(Slot::F64(*value)),
+      (FfiType::F64, FfiValue::Int(value)) => Ok(Slot::F64(*value as f64)),
+      (FfiType::Pointer, FfiValue::Pointer(address)) => {
+        Ok(Slot::Pointer(*address as *const c_void))
+      },
+      (FfiType::Pointer, FfiValue::Null) | (FfiType::String, FfiValue::Null) => {
+        Ok(Slot::Pointer(ptr::null()))
+      },
+      (FfiType::String, FfiValue::String(string)) => {
+        let string = CString::new(string.as_str())
+          .map_err(|_| format!("Argument {} of {} contains a nul byte.", index, self.name))?;
+        let pointer = string.as_ptr();
+        Ok(Slot::String {
+          _buffer: string,
+          pointer,
+        })
+      },
+      (kind, arg) => Err(format!(
+        "Argument {} of {} cannot be passed {:?} as {}.",
+        index, self.name, arg, kind
+      )),
+    }
+  }
+}
+
+impl fmt::Debug for ForeignFn {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ForeignFn")
+      .field("name", &self.name)
+      .field("parameters", &self.parameters)
+      .field("result", &self.result)
+      .finish()
+  }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+  use super::*;
+
+  fn libc() -> ForeignLibrary {
+    ForeignLibrary::open(Path::new("libc.so.6")).expect("Unable to open libc")
+  }
+
+  #[test]
+  fn parse() {
+    assert_eq!(FfiType::parse("f64"), Some(FfiType::F64));
+    assert_eq!(FfiType::parse("pointer"), Some(FfiType::Pointer));
+    assert_eq!(FfiType::parse("double"), None);
+  }
+
+  #[test]
+  fn call() {
+    let strlen = libc()
+      .declare("strlen", vec![FfiType::String], FfiType::I64)
+      .unwrap();
+    let abs = libc()
+      .declare("abs", vec![FfiType::I32], FfiType::I32)
+      .unwrap();
+
+    unsafe {
+      assert_eq!(
+        strlen.call(&[FfiValue::String("laythe".to_string())]),
+        Ok(FfiValue::Int(6))
+      );
+      assert_eq!(abs.call(&[FfiValue::Int(-3)]), Ok(FfiValue::Int(3)));
+      assert!(abs.call(&[FfiValue::Int(1 << 40)]).is_err());
+      assert!(abs.call(&[]).is_err());
+    }
+  }
+
+  #[test]
+  fn declare() {
+    assert!(libc()
+      .declare("strlen", vec![FfiType::Void], FfiType::I64)
+      .is_err());
+    assert!(libc()
+      .declare("not_a_libc_function", vec![], FfiType::Void)
+      .is_err());
+    assert!(ForeignLibrary::open(Path::new("libnot_a_library.so")).is_err());
+  }
+}
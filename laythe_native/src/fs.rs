@@ -1,9 +1,9 @@
 use laythe_env::{
-  fs::{Fs, FsImpl, SlDirEntry},
+  fs::{FileStream, Fs, FsImpl, OpenMode, SlDirEntry},
   io::IoImpl,
 };
 use std::{
-  fs::{canonicalize, read_to_string},
+  fs::{canonicalize, create_dir_all, read, read_dir, read_to_string, write, OpenOptions},
   io,
   path::{Path, PathBuf},
 };
@@ -31,12 +31,33 @@ impl FsImpl for FsNative {
     read_to_string(path)
   }
 
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+    read(path)
+  }
+
+  fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+    write(path, contents)
+  }
+
+  fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+    create_dir_all(path)
+  }
+
   fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
     canonicalize(path)
   }
 
-  fn read_directory(&self, _path: &Path) -> io::Result<SlDirEntry> {
-    todo!()
+  fn read_directory(&self, path: &Path) -> io::Result<Vec<SlDirEntry>> {
+    read_dir(path)?
+      .map(|entry| {
+        let entry = entry?;
+
+        Ok(SlDirEntry {
+          path: entry.path(),
+          is_dir: entry.file_type()?.is_dir(),
+        })
+      })
+      .collect()
   }
 
   fn relative_path(&self, base: &Path, import: &Path) -> io::Result<PathBuf> {
@@ -45,4 +66,15 @@ impl FsImpl for FsNative {
       .map(|prefix| prefix.to_path_buf())
       .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))
   }
+  fn open(&self, path: &Path, mode: OpenMode) -> io::Result<Box<dyn FileStream>> {
+    let mut options = OpenOptions::new();
+    match mode {
+      OpenMode::Read => options.read(true),
+      OpenMode::Write => options.write(true).create(true).truncate(true),
+      OpenMode::Append => options.append(true).create(true),
+      OpenMode::ReadWrite => options.read(true).write(true),
+    };
+
+    Ok(Box::new(options.open(path)?))
+  }
 }
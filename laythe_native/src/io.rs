@@ -1,4 +1,7 @@
-use crate::{env::IoEnvNative, fs::IoFsNative, stdio::IoStdioNative, time::IoTimeNative};
+use crate::{
+  env::IoEnvNative, fs::IoFsNative, net::IoNetNative, signal::IoSignalsNative,
+  stdio::IoStdioNative, time::IoTimeNative,
+};
 use laythe_env::io::Io;
 use std::sync::Arc;
 
@@ -9,4 +12,6 @@ pub fn io_native() -> Io {
     Arc::new(IoEnvNative()),
     Arc::new(IoTimeNative::default()),
   )
+  .with_net(Arc::new(IoNetNative()))
+  .with_signals(Arc::new(IoSignalsNative()))
 }
@@ -1,7 +1,11 @@
 #![deny(clippy::all)]
 
 pub mod env;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod fs;
 pub mod io;
+pub mod net;
+pub mod signal;
 pub mod stdio;
 pub mod time;
@@ -0,0 +1,159 @@
+use laythe_env::{
+  io::IoImpl,
+  net::{Net, NetImpl, TcpListen, TcpStream},
+};
+use std::{
+  io,
+  net::{self, ToSocketAddrs},
+};
+
+#[derive(Debug)]
+pub struct IoNetNative();
+
+impl IoImpl<Net> for IoNetNative {
+  fn make(&self) -> Net {
+    Net::new(Box::new(NetNative()))
+  }
+}
+
+pub struct NetNative();
+
+impl NetImpl for NetNative {
+  fn listen(&self, address: &str) -> io::Result<Box<dyn TcpListen>> {
+    let listener = net::TcpListener::bind(address)?;
+    listener.set_nonblocking(true)?;
+    Ok(Box::new(TcpListenNative(listener)))
+  }
+
+  fn resolve(&self, host: &str) -> io::Result<Vec<String>> {
+    let mut addresses: Vec<String> = vec![];
+
+    for address in (host, 0).to_socket_addrs()? {
+      let ip = address.ip().to_string();
+      if !addresses.contains(&ip) {
+        addresses.push(ip);
+      }
+    }
+
+    Ok(addresses)
+  }
+
+  fn connect(&self, address: &str) -> io::Result<Box<dyn TcpStream>> {
+    let stream = net::TcpStream::connect(address)?;
+    stream.set_nonblocking(true)?;
+    stream.set_nodelay(true)?;
+    Ok(Box::new(stream))
+  }
+
+  #[cfg(feature = "tls")]
+  fn tls(&self, stream: Box<dyn TcpStream>, host: &str) -> io::Result<Box<dyn TcpStream>> {
+    tls::wrap(stream, host)
+  }
+
+  #[cfg(not(feature = "tls"))]
+  fn tls(&self, _stream: Box<dyn TcpStream>, _host: &str) -> io::Result<Box<dyn TcpStream>> {
+    Err(io::Error::new(
+      io::ErrorKind::Unsupported,
+      "Laythe was built without tls support",
+    ))
+  }
+}
+
+#[derive(Debug)]
+struct TcpListenNative(net::TcpListener);
+
+impl TcpListen for TcpListenNative {
+  fn accept(&mut self) -> io::Result<Option<Box<dyn TcpStream>>> {
+    match self.0.accept() {
+      Ok((stream, _)) => {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Some(Box::new(stream)))
+      },
+      Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(None),
+      Err(err) => Err(err),
+    }
+  }
+
+  fn local_address(&self) -> io::Result<String> {
+    self.0.local_addr().map(|address| address.to_string())
+  }
+}
+
+#[cfg(feature = "tls")]
+mod tls {
+  use laythe_env::net::TcpStream;
+  use rustls::{
+    crypto::ring, pki_types::ServerName, ClientConfig, ClientConnection, RootCertStore, StreamOwned,
+  };
+  use std::{
+    convert::TryFrom,
+    fmt,
+    io::{self, Read, Write},
+    sync::{Arc, OnceLock},
+  };
+
+  /// The client configuration shared by every tls stream, trusting the
+  /// Mozilla root certificates
+  fn config() -> io::Result<Arc<ClientConfig>> {
+    static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+
+    if let Some(config) = CONFIG.get() {
+      return Ok(Arc::clone(config));
+    }
+
+    let roots = RootCertStore {
+      roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+
+    let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+      .with_safe_default_protocol_versions()
+      .map_err(io::Error::other)?
+      .with_root_certificates(roots)
+      .with_no_client_auth();
+
+    Ok(Arc::clone(CONFIG.get_or_init(|| Arc::new(config))))
+  }
+
+  pub fn wrap(stream: Box<dyn TcpStream>, host: &str) -> io::Result<Box<dyn TcpStream>> {
+    let name = ServerName::try_from(host.to_string())
+      .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Invalid tls host name"))?;
+
+    let connection = ClientConnection::new(config()?, name).map_err(io::Error::other)?;
+    Ok(Box::new(TlsStreamNative(StreamOwned::new(
+      connection, stream,
+    ))))
+  }
+
+  struct TlsStreamNative(StreamOwned<ClientConnection, Box<dyn TcpStream>>);
+
+  impl fmt::Debug for TlsStreamNative {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      f.debug_tuple("TlsStreamNative")
+        .field(&self.0.sock)
+        .finish()
+    }
+  }
+
+  impl Read for TlsStreamNative {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+      self.0.read(buf)
+    }
+  }
+
+  impl Write for TlsStreamNative {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      self.0.flush()
+    }
+  }
+
+  impl TcpStream for TlsStreamNative {
+    fn peer_address(&self) -> io::Result<String> {
+      self.0.sock.peer_address()
+    }
+  }
+}
@@ -0,0 +1,102 @@
+use laythe_env::{
+  io::IoImpl,
+  signal::{ProcessSignal, Signals, SignalsImpl},
+};
+
+#[derive(Debug)]
+pub struct IoSignalsNative();
+
+impl IoImpl<Signals> for IoSignalsNative {
+  fn make(&self) -> Signals {
+    Signals::new(Box::new(SignalsNative()))
+  }
+}
+
+pub struct SignalsNative();
+
+#[cfg(unix)]
+mod unix {
+  use laythe_env::signal::ProcessSignal;
+  use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+  /// Deliveries of each watched signal to this process
+  static RECEIVED: [AtomicUsize; 2] = [AtomicUsize::new(0), AtomicUsize::new(0)];
+
+  /// Has a handler been installed for each signal
+  static WATCHED: [AtomicBool; 2] = [AtomicBool::new(false), AtomicBool::new(false)];
+
+  fn number(signal: ProcessSignal) -> libc::c_int {
+    match signal {
+      ProcessSignal::Interrupt => libc::SIGINT,
+      ProcessSignal::Terminate => libc::SIGTERM,
+    }
+  }
+
+  extern "C" fn on_signal(delivered: libc::c_int) {
+    // only stores to an atomic so it is safe in a signal handler
+    if let Some(signal) = ProcessSignal::ALL
+      .iter()
+      .find(|signal| number(**signal) == delivered)
+    {
+      RECEIVED[signal.index()].fetch_add(1, Ordering::SeqCst);
+    }
+  }
+
+  /// Install the counting handler, replacing any handler the
+  /// host installed such as the cli's ctrl-c interrupt
+  pub fn watch(signal: ProcessSignal) -> Result<(), String> {
+    if WATCHED[signal.index()].swap(true, Ordering::SeqCst) {
+      return Ok(());
+    }
+
+    let handler = on_signal as extern "C" fn(libc::c_int);
+    if unsafe { libc::signal(number(signal), handler as libc::sighandler_t) } == libc::SIG_ERR {
+      WATCHED[signal.index()].store(false, Ordering::SeqCst);
+      return Err(format!("Unable to handle {}.", signal.name()));
+    }
+
+    Ok(())
+  }
+
+  pub fn received(signal: ProcessSignal) -> usize {
+    RECEIVED[signal.index()].load(Ordering::SeqCst)
+  }
+
+  pub fn raise(signal: ProcessSignal) -> Result<(), String> {
+    if unsafe { libc::raise(number(signal)) } != 0 {
+      return Err(format!("Unable to raise {}.", signal.name()));
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(unix)]
+impl SignalsImpl for SignalsNative {
+  fn watch(&self, signal: ProcessSignal) -> Result<(), String> {
+    unix::watch(signal)
+  }
+
+  fn received(&self, signal: ProcessSignal) -> usize {
+    unix::received(signal)
+  }
+
+  fn raise(&self, signal: ProcessSignal) -> Result<(), String> {
+    unix::raise(signal)
+  }
+}
+
+#[cfg(not(unix))]
+impl SignalsImpl for SignalsNative {
+  fn watch(&self, _signal: ProcessSignal) -> Result<(), String> {
+    Err("Signals are not supported on this platform.".to_string())
+  }
+
+  fn received(&self, _signal: ProcessSignal) -> usize {
+    0
+  }
+
+  fn raise(&self, _signal: ProcessSignal) -> Result<(), String> {
+    Err("Signals are not supported on this platform.".to_string())
+  }
+}
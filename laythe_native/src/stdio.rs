@@ -1,9 +1,9 @@
 use io::Stdin;
 use laythe_env::{
   io::IoImpl,
-  stdio::{Stdio, StdioImpl},
+  stdio::{decode_key, read_utf8_char, Key, Stdio, StdioImpl},
 };
-use std::io::{self, stdin, Write};
+use std::io::{self, stdin, IsTerminal, Write};
 use termcolor::{StandardStream, WriteColor};
 
 #[derive(Debug)]
@@ -51,4 +51,108 @@ impl StdioImpl for StdioNative {
   fn read_line(&self, buffer: &mut String) -> io::Result<usize> {
     stdin().read_line(buffer)
   }
+
+  fn is_tty(&self) -> bool {
+    self.stdin.is_terminal()
+  }
+
+  fn read_key(&mut self) -> io::Result<Option<Key>> {
+    #[cfg(any(unix, windows))]
+    if terminal::is_raw_mode_enabled()? {
+      return terminal::read_key();
+    }
+
+    match read_utf8_char(&mut self.stdin)? {
+      Some(first) => decode_key(first, &mut || read_utf8_char(&mut self.stdin)).map(Some),
+      None => Ok(None),
+    }
+  }
+
+  #[cfg(any(unix, windows))]
+  fn terminal_size(&self) -> io::Result<(u16, u16)> {
+    crossterm::terminal::size()
+  }
+
+  #[cfg(any(unix, windows))]
+  fn set_raw_mode(&mut self, enabled: bool) -> io::Result<()> {
+    terminal::set_raw_mode(enabled)
+  }
+}
+
+#[cfg(any(unix, windows))]
+mod terminal {
+  use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+  use laythe_env::stdio::Key;
+  use std::io;
+
+  pub use crossterm::terminal::is_raw_mode_enabled;
+
+  pub fn set_raw_mode(enabled: bool) -> io::Result<()> {
+    if !enabled {
+      return crossterm::terminal::disable_raw_mode();
+    }
+
+    crossterm::terminal::enable_raw_mode()?;
+
+    #[cfg(unix)]
+    {
+      static RESTORE_AT_EXIT: std::sync::Once = std::sync::Once::new();
+      RESTORE_AT_EXIT.call_once(|| unsafe {
+        libc::atexit(restore);
+      });
+    }
+
+    Ok(())
+  }
+
+  /// Read terminal events until one is a key press
+  pub fn read_key() -> io::Result<Option<Key>> {
+    loop {
+      if let Event::Key(event) = event::read()? {
+        if let Some(key) = to_key(event) {
+          return Ok(Some(key));
+        }
+      }
+    }
+  }
+
+  /// The key for a key press, or none for releases and
+  /// keys scripts aren't given such as the function keys
+  fn to_key(event: KeyEvent) -> Option<Key> {
+    if event.kind == KeyEventKind::Release {
+      return None;
+    }
+
+    let key = match event.code {
+      KeyCode::Char(c) if event.modifiers.contains(KeyModifiers::CONTROL) => {
+        Key::Ctrl(c.to_ascii_lowercase())
+      },
+      KeyCode::Char(c) if event.modifiers.contains(KeyModifiers::ALT) => Key::Alt(c),
+      KeyCode::Char(c) => Key::Char(c),
+      KeyCode::Enter => Key::Enter,
+      KeyCode::Tab => Key::Tab,
+      KeyCode::Backspace => Key::Backspace,
+      KeyCode::Esc => Key::Escape,
+      KeyCode::Up => Key::Up,
+      KeyCode::Down => Key::Down,
+      KeyCode::Left => Key::Left,
+      KeyCode::Right => Key::Right,
+      KeyCode::Home => Key::Home,
+      KeyCode::End => Key::End,
+      KeyCode::PageUp => Key::PageUp,
+      KeyCode::PageDown => Key::PageDown,
+      KeyCode::Insert => Key::Insert,
+      KeyCode::Delete => Key::Delete,
+      _ => return None,
+    };
+
+    Some(key)
+  }
+
+  /// Leave raw mode when the process exits so the shell
+  /// isn't left with a terminal that doesn't echo
+  #[cfg(unix)]
+  extern "C" fn restore() {
+    let _ = crossterm::terminal::disable_raw_mode();
+  }
 }
@@ -2,14 +2,17 @@ use laythe_env::{
   io::IoImpl,
   time::{Time, TimeImpl},
 };
-use std::time::{Duration, SystemTime};
+use std::{
+  thread,
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Debug, Clone)]
-pub struct IoTimeNative(SystemTime);
+pub struct IoTimeNative(Instant);
 
 impl Default for IoTimeNative {
   fn default() -> Self {
-    Self(SystemTime::now())
+    Self(Instant::now())
   }
 }
 
@@ -21,17 +24,27 @@ impl IoImpl<Time> for IoTimeNative {
 
 #[derive(Debug, Clone)]
 pub struct TimeNative {
-  start: SystemTime,
+  start: Instant,
 }
 
 impl TimeNative {
-  pub fn new(start: SystemTime) -> Self {
+  pub fn new(start: Instant) -> Self {
     Self { start }
   }
 }
 
 impl TimeImpl for TimeNative {
   fn elapsed(&self) -> Result<Duration, String> {
-    self.start.elapsed().map_err(|err| err.to_string())
+    Ok(self.start.elapsed())
+  }
+
+  fn now(&self) -> Result<Duration, String> {
+    SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_err(|err| err.to_string())
+  }
+
+  fn sleep(&self, duration: Duration) {
+    thread::sleep(duration)
   }
 }
@@ -7,7 +7,7 @@ use laythe_core::{
 };
 use laythe_core::{managed::GcObj, module::Module};
 use laythe_vm::compiler::Parser;
-use laythe_vm::{compiler::Compiler, source::Source};
+use laythe_vm::{arena::Arena, compiler::Compiler, source::Source};
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
@@ -58,12 +58,13 @@ fn compile_source(source: GcStr) {
 
   let module_class = test_class(&hooks, "Module");
   let module = hooks.manage(Module::from_path(&hooks, path, module_class, 0).unwrap());
-  let source = Source::new(source);
-  let (ast, line_offsets) = Parser::new(&source, 0).parse();
+  let source = Source::new(&source);
+  let arena = Arena::for_source(&source);
+  let (ast, line_offsets) = Parser::new(&source, &arena, 0).parse();
   let ast = ast.unwrap();
 
   let gc = context.done();
-  let compiler = Compiler::new(module, &ast, &line_offsets, 0, &NO_GC, gc);
+  let compiler = Compiler::new(module, &ast, &source, &line_offsets, 0, &NO_GC, gc);
   compiler.compile().0.unwrap();
 }
 
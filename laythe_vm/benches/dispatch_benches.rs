@@ -0,0 +1,56 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use laythe_vm::vm::{default_native_vm, Dispatch};
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+const FILE_PATH: &str = file!();
+
+fn fixture_path(bench_path: &str) -> Option<PathBuf> {
+  let test_path = Path::new(FILE_PATH);
+
+  test_path
+    .parent()
+    .and_then(|path| path.parent())
+    .and_then(|path| path.parent())
+    .map(|path| path.join("fixture").join("criterion").join(bench_path))
+}
+
+fn load_source(path: &PathBuf) -> String {
+  let mut file = File::open(path).unwrap();
+  let mut source = String::new();
+  file.read_to_string(&mut source).unwrap();
+  source
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+  let benches = [
+    "fib.lay",
+    "equality.lay",
+    "method_call.lay",
+    "properties.lay",
+  ];
+  let mut group = c.benchmark_group("dispatch");
+
+  for bench in benches.iter() {
+    let path = fixture_path(bench).expect("Unable to load benchmark file.");
+    let source = load_source(&path);
+
+    for dispatch in [Dispatch::Match, Dispatch::Table].iter() {
+      group.bench_with_input(
+        BenchmarkId::new(format!("{:?}", dispatch), bench),
+        &source,
+        |b, s| {
+          let mut vm = default_native_vm().expect("Unable to create vm");
+          vm.set_dispatch(*dispatch);
+          b.iter(|| vm.run(path.clone(), &s));
+        },
+      );
+    }
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
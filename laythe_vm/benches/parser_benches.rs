@@ -3,7 +3,7 @@ use laythe_core::{
   managed::GcStr,
   memory::{Allocator, NO_GC},
 };
-use laythe_vm::{compiler::Parser, source::Source};
+use laythe_vm::{arena::Arena, compiler::Parser, source::Source};
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
@@ -29,8 +29,9 @@ fn load_source<P: AsRef<Path>>(gc: &mut Allocator, dir: P) -> GcStr {
 }
 
 fn parse_source(source: GcStr) {
-  let source = Source::new(source);
-  let parser = Parser::new(&source, 0);
+  let source = Source::new(&source);
+  let arena = Arena::for_source(&source);
+  let parser = Parser::new(&source, &arena, 0);
   parser.parse().0.unwrap();
 }
 
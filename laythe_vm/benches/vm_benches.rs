@@ -53,23 +53,23 @@ fn criterion_benchmark(c: &mut Criterion) {
     BenchmarkId::new("run binary_trees", 1),
     &binary_trees,
     |b, s| {
-      let mut vm = default_native_vm();
+      let mut vm = default_native_vm().expect("Unable to create vm");
       b.iter(|| vm.run(binary_trees_path.clone(), &s));
     },
   );
   c.bench_with_input(BenchmarkId::new("run equality", 2), &equality, |b, s| {
-    let mut vm = default_native_vm();
+    let mut vm = default_native_vm().expect("Unable to create vm");
     b.iter(|| vm.run(equality_path.clone(), &s));
   });
   c.bench_with_input(BenchmarkId::new("run fib", 3), &fib, |b, s| {
-    let mut vm = default_native_vm();
+    let mut vm = default_native_vm().expect("Unable to create vm");
     b.iter(|| vm.run(fib_path.clone(), &s));
   });
   c.bench_with_input(
     BenchmarkId::new("run invocation", 4),
     &invocation,
     |b, s| {
-      let mut vm = default_native_vm();
+      let mut vm = default_native_vm().expect("Unable to create vm");
       b.iter(|| vm.run(invocation_path.clone(), &s));
     },
   );
@@ -77,7 +77,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     BenchmarkId::new("run instantiation", 5),
     &instantiation,
     |b, s| {
-      let mut vm = default_native_vm();
+      let mut vm = default_native_vm().expect("Unable to create vm");
       b.iter(|| vm.run(instantiation_path.clone(), &s));
     },
   );
@@ -85,7 +85,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     BenchmarkId::new("run method_call", 6),
     &method_call,
     |b, s| {
-      let mut vm = default_native_vm();
+      let mut vm = default_native_vm().expect("Unable to create vm");
       b.iter(|| vm.run(method_call_path.clone(), &s));
     },
   );
@@ -93,24 +93,24 @@ fn criterion_benchmark(c: &mut Criterion) {
     BenchmarkId::new("run properties", 7),
     &properties,
     |b, s| {
-      let mut vm = default_native_vm();
+      let mut vm = default_native_vm().expect("Unable to create vm");
       b.iter(|| vm.run(properties_path.clone(), &s));
     },
   );
   c.bench_with_input(BenchmarkId::new("run trees", 8), &trees, |b, s| {
-    let mut vm = default_native_vm();
+    let mut vm = default_native_vm().expect("Unable to create vm");
     b.iter(|| vm.run(trees_path.clone(), &s));
   });
   c.bench_with_input(BenchmarkId::new("run zoo", 9), &zoo, |b, s| {
-    let mut vm = default_native_vm();
+    let mut vm = default_native_vm().expect("Unable to create vm");
     b.iter(|| vm.run(zoo_path.clone(), &s));
   });
   c.bench_with_input(BenchmarkId::new("run string", 10), &string, |b, s| {
-    let mut vm = default_native_vm();
+    let mut vm = default_native_vm().expect("Unable to create vm");
     b.iter(|| vm.run(string_path.clone(), &s));
   });
   c.bench_with_input(BenchmarkId::new("run fluent", 10), &fluent, |b, s| {
-    let mut vm = default_native_vm();
+    let mut vm = default_native_vm().expect("Unable to create vm");
     b.iter(|| vm.run(fluent_path.clone(), &s));
   });
 }
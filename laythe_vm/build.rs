@@ -0,0 +1,50 @@
+use std::{
+  env, fs, io,
+  path::{Path, PathBuf},
+};
+
+/// The sources that determine what the compiler emits. Cached chunks
+/// are only loaded by a vm built from the same sources
+const COMPILER_SOURCES: &[&str] = &["src", "../laythe_core/src"];
+
+fn main() -> io::Result<()> {
+  let manifest_dir =
+    PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("Cargo sets the manifest dir"));
+
+  let mut files = vec![];
+  for source in COMPILER_SOURCES {
+    let dir = manifest_dir.join(source);
+    println!("cargo:rerun-if-changed={}", dir.display());
+    collect_files(&dir, &mut files)?;
+  }
+  files.sort();
+
+  // fnv-1a over each file's relative path and contents
+  let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+  for file in &files {
+    let relative = file.strip_prefix(&manifest_dir).unwrap_or(file);
+    let contents = fs::read(file)?;
+
+    for byte in relative.to_string_lossy().bytes().chain(contents) {
+      hash ^= byte as u64;
+      hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+  }
+
+  println!("cargo:rustc-env=LAYTHE_BUILD_HASH={:016x}", hash);
+  Ok(())
+}
+
+/// Collect every file below the provided directory
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      collect_files(&path, files)?;
+    } else {
+      files.push(path);
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,63 @@
+use bumpalo::{boxed::Box, Bump};
+
+/// An arena that owns every node of a parsed ast. The ast
+/// borrows from the arena so the arena must outlive any
+/// ast produced from it. Allocation is independent of
+/// the garbage collector
+pub struct Arena {
+  bump: Bump,
+}
+
+impl Default for Arena {
+  fn default() -> Self {
+    Self { bump: Bump::new() }
+  }
+}
+
+impl Arena {
+  /// Create a new arena with an initial capacity in bytes
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self {
+      bump: Bump::with_capacity(capacity),
+    }
+  }
+
+  /// Create a new arena sized for a source of the given length
+  pub fn for_source(source: &str) -> Self {
+    Self::with_capacity(source.len())
+  }
+
+  /// Allocate an ast node in this arena
+  pub fn node<T>(&self, node: T) -> Box<'_, T> {
+    Box::new_in(node, &self.bump)
+  }
+
+  /// The number of bytes currently allocated by this arena
+  pub fn allocated_bytes(&self) -> usize {
+    self.bump.allocated_bytes()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn node() {
+    let arena = Arena::default();
+    let node = arena.node(10);
+
+    assert_eq!(*node, 10);
+    assert!(arena.allocated_bytes() > 0);
+  }
+
+  #[test]
+  fn for_source() {
+    let source = "let x = 10;";
+    let arena = Arena::for_source(source);
+    let node = arena.node(source);
+
+    assert_eq!(*node, source);
+    assert!(arena.allocated_bytes() > 0);
+  }
+}
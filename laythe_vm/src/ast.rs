@@ -21,8 +21,11 @@ pub trait Visitor<'a> {
   fn visit_error(&mut self, error: &[Token<'a>]) -> Self::Result;
 
   fn visit_class(&mut self, class: &Class) -> Self::Result;
+  fn visit_extension(&mut self, extension: &Extension) -> Self::Result;
   fn visit_fun(&mut self, fun: &Fun) -> Self::Result;
   fn visit_let(&mut self, let_: &Let) -> Self::Result;
+  fn visit_let_tuple(&mut self, let_tuple: &LetTuple) -> Self::Result;
+  fn visit_const(&mut self, const_: &Const) -> Self::Result;
   fn visit_method(&mut self, method: &Fun) -> Self::Result;
   fn visit_static_method(&mut self, static_method: &Fun) -> Self::Result;
 
@@ -31,9 +34,11 @@ pub trait Visitor<'a> {
   fn visit_while(&mut self, while_: &While) -> Self::Result;
   fn visit_if(&mut self, if_: &If) -> Self::Result;
   fn visit_return(&mut self, return_: &Return) -> Self::Result;
+  fn visit_raise(&mut self, raise: &Raise) -> Self::Result;
   fn visit_continue(&mut self, continue_: &Token<'a>) -> Self::Result;
   fn visit_break(&mut self, break_: &Token<'a>) -> Self::Result;
   fn visit_try(&mut self, try_: &Try) -> Self::Result;
+  fn visit_with(&mut self, with: &With) -> Self::Result;
   fn visit_block(&mut self, block: &Block) -> Self::Result;
 
   fn visit_assign(&mut self, assign: &Assign) -> Self::Result;
@@ -58,7 +63,9 @@ pub trait Visitor<'a> {
   fn visit_self(&mut self, token: &Token<'a>) -> Self::Result;
   fn visit_super(&mut self, token: &Super) -> Self::Result;
   fn visit_lambda(&mut self, fun: &Fun) -> Self::Result;
+  fn visit_class_expr(&mut self, class: &Class) -> Self::Result;
   fn visit_list(&mut self, items: &List) -> Self::Result;
+  fn visit_tuple(&mut self, items: &Tuple) -> Self::Result;
   fn visit_map(&mut self, kvps: &Map) -> Self::Result;
 }
 
@@ -102,7 +109,7 @@ pub trait Spanned {
 
 /// Representing the start and end of a node. Typically this would
 /// be a multi line control flow or a function declaration
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub struct Span {
   pub start: u32,
   pub end: u32,
@@ -164,30 +171,42 @@ impl<'a> Spanned for Decl<'a> {
 
 pub enum Symbol<'a> {
   Class(Class<'a>),
+  Extension(Extension<'a>),
   Fun(Fun<'a>),
   Let(Let<'a>),
+  LetTuple(LetTuple<'a>),
+  Const(Const<'a>),
   Trait(Trait<'a>),
   TypeDecl(TypeDecl<'a>),
+  Import(Import<'a>),
 }
 
 impl<'a> Spanned for Symbol<'a> {
   fn start(&self) -> u32 {
     match self {
       Symbol::Class(class) => class.start(),
+      Symbol::Extension(extension) => extension.start(),
       Symbol::Fun(fun) => fun.start(),
       Symbol::Let(let_) => let_.start(),
+      Symbol::LetTuple(let_tuple) => let_tuple.start(),
+      Symbol::Const(const_) => const_.start(),
       Symbol::Trait(trait_) => trait_.start(),
       Symbol::TypeDecl(type_) => type_.start(),
+      Symbol::Import(import) => import.start(),
     }
   }
 
   fn end(&self) -> u32 {
     match self {
       Symbol::Class(class) => class.end(),
+      Symbol::Extension(extension) => extension.end(),
       Symbol::Fun(fun) => fun.end(),
       Symbol::Let(let_) => let_.end(),
+      Symbol::LetTuple(let_tuple) => let_tuple.end(),
+      Symbol::Const(const_) => const_.end(),
       Symbol::Trait(trait_) => trait_.end(),
       Symbol::TypeDecl(type_) => type_.end(),
+      Symbol::Import(import) => import.end(),
     }
   }
 }
@@ -201,6 +220,7 @@ pub struct Class<'a> {
   pub init: Option<Fun<'a>>,
   pub methods: Vec<Fun<'a>>,
   pub static_methods: Vec<Fun<'a>>,
+  pub sealed: bool,
 }
 
 impl<'a> Class<'a> {
@@ -224,8 +244,15 @@ impl<'a> Class<'a> {
       init,
       methods,
       static_methods,
+      sealed: false,
     }
   }
+
+  /// Mark this class as sealed or not
+  pub fn with_sealed(mut self, sealed: bool) -> Self {
+    self.sealed = sealed;
+    self
+  }
 }
 
 impl<'a> Spanned for Class<'a> {
@@ -242,10 +269,48 @@ impl<'a> Spanned for Class<'a> {
   }
 }
 
+pub struct Extension<'a> {
+  pub class: Token<'a>,
+  pub range: Span,
+  pub methods: Vec<Fun<'a>>,
+  pub static_methods: Vec<Fun<'a>>,
+}
+
+impl<'a> Extension<'a> {
+  pub fn new(
+    class: Token<'a>,
+    range: Span,
+    methods: Vec<Fun<'a>>,
+    static_methods: Vec<Fun<'a>>,
+  ) -> Self {
+    Self {
+      class,
+      range,
+      methods,
+      static_methods,
+    }
+  }
+}
+
+impl<'a> Spanned for Extension<'a> {
+  fn span(&self) -> Span {
+    self.range
+  }
+
+  fn start(&self) -> u32 {
+    self.range.start
+  }
+
+  fn end(&self) -> u32 {
+    self.range.end
+  }
+}
+
 pub struct Fun<'a> {
   pub name: Option<Token<'a>>,
   pub call_sig: CallSignature<'a>,
   pub body: FunBody<'a>,
+  pub is_async: bool,
 }
 
 impl<'a> Fun<'a> {
@@ -254,8 +319,15 @@ impl<'a> Fun<'a> {
       name,
       call_sig,
       body,
+      is_async: false,
     }
   }
+
+  /// Mark this function as async or not
+  pub fn with_async(mut self, is_async: bool) -> Self {
+    self.is_async = is_async;
+    self
+  }
 }
 
 impl<'a> Spanned for Fun<'a> {
@@ -307,6 +379,57 @@ impl<'a> Spanned for Let<'a> {
   }
 }
 
+pub struct Const<'a> {
+  pub name: Token<'a>,
+  pub value: Expr<'a>,
+}
+
+impl<'a> Const<'a> {
+  pub fn new(name: Token<'a>, value: Expr<'a>) -> Self {
+    Self { name, value }
+  }
+}
+
+impl<'a> Spanned for Const<'a> {
+  fn start(&self) -> u32 {
+    self.name.start()
+  }
+
+  fn end(&self) -> u32 {
+    self.value.end()
+  }
+}
+
+pub struct LetTuple<'a> {
+  pub range: Span,
+  pub names: Vec<Token<'a>>,
+  pub value: Expr<'a>,
+}
+
+impl<'a> LetTuple<'a> {
+  pub fn new(range: Span, names: Vec<Token<'a>>, value: Expr<'a>) -> Self {
+    Self {
+      range,
+      names,
+      value,
+    }
+  }
+}
+
+impl<'a> Spanned for LetTuple<'a> {
+  fn span(&self) -> Span {
+    self.range
+  }
+
+  fn start(&self) -> u32 {
+    self.range.start
+  }
+
+  fn end(&self) -> u32 {
+    self.value.end()
+  }
+}
+
 pub struct Trait<'a> {
   pub range: Span,
   pub name: Token<'a>,
@@ -380,10 +503,12 @@ pub enum Stmt<'a> {
   For(Box<'a, For<'a>>),
   If(Box<'a, If<'a>>),
   Return(Box<'a, Return<'a>>),
+  Raise(Box<'a, Raise<'a>>),
   Continue(Box<'a, Token<'a>>),
   Break(Box<'a, Token<'a>>),
   While(Box<'a, While<'a>>),
   Try(Box<'a, Try<'a>>),
+  With(Box<'a, With<'a>>),
 }
 
 impl<'a> Spanned for Stmt<'a> {
@@ -395,10 +520,12 @@ impl<'a> Spanned for Stmt<'a> {
       Stmt::For(for_) => for_.start(),
       Stmt::If(if_) => if_.start(),
       Stmt::Return(return_) => return_.start(),
+      Stmt::Raise(raise) => raise.start(),
       Stmt::Continue(continue_) => continue_.start(),
       Stmt::Break(break_) => break_.start(),
       Stmt::While(while_) => while_.start(),
       Stmt::Try(try_) => try_.start(),
+      Stmt::With(with) => with.start(),
     }
   }
 
@@ -410,10 +537,12 @@ impl<'a> Spanned for Stmt<'a> {
       Stmt::For(for_) => for_.end(),
       Stmt::If(if_) => if_.end(),
       Stmt::Return(return_) => return_.end(),
+      Stmt::Raise(raise) => raise.end(),
       Stmt::Continue(continue_) => continue_.end(),
       Stmt::Break(break_) => break_.end(),
       Stmt::While(while_) => while_.end(),
       Stmt::Try(try_) => try_.end(),
+      Stmt::With(with) => with.end(),
     }
   }
 }
@@ -564,6 +693,35 @@ impl<'a> Spanned for Return<'a> {
   }
 }
 
+pub struct Raise<'a> {
+  pub raise: Token<'a>,
+  pub error: Expr<'a>,
+  pub cause: Option<Expr<'a>>,
+}
+
+impl<'a> Raise<'a> {
+  pub fn new(raise: Token<'a>, error: Expr<'a>, cause: Option<Expr<'a>>) -> Self {
+    Self {
+      raise,
+      error,
+      cause,
+    }
+  }
+}
+
+impl<'a> Spanned for Raise<'a> {
+  fn start(&self) -> u32 {
+    self.raise.start()
+  }
+
+  fn end(&self) -> u32 {
+    self
+      .cause
+      .as_ref()
+      .map_or_else(|| self.error.end(), |cause| cause.end())
+  }
+}
+
 pub struct While<'a> {
   pub cond: Expr<'a>,
   pub body: Block<'a>,
@@ -587,12 +745,17 @@ impl<'a> Spanned for While<'a> {
 
 pub struct Try<'a> {
   pub block: Block<'a>,
+  pub error: Option<Token<'a>>,
   pub catch: Block<'a>,
 }
 
 impl<'a> Try<'a> {
-  pub fn new(block: Block<'a>, catch: Block<'a>) -> Self {
-    Self { block, catch }
+  pub fn new(block: Block<'a>, error: Option<Token<'a>>, catch: Block<'a>) -> Self {
+    Self {
+      block,
+      error,
+      catch,
+    }
   }
 }
 
@@ -606,6 +769,32 @@ impl<'a> Spanned for Try<'a> {
   }
 }
 
+pub struct With<'a> {
+  pub manager: Expr<'a>,
+  pub name: Option<Token<'a>>,
+  pub body: Block<'a>,
+}
+
+impl<'a> With<'a> {
+  pub fn new(manager: Expr<'a>, name: Option<Token<'a>>, body: Block<'a>) -> Self {
+    Self {
+      manager,
+      name,
+      body,
+    }
+  }
+}
+
+impl<'a> Spanned for With<'a> {
+  fn start(&self) -> u32 {
+    self.manager.start()
+  }
+
+  fn end(&self) -> u32 {
+    self.body.end()
+  }
+}
+
 pub struct Block<'a> {
   pub range: Span,
   pub decls: Vec<Decl<'a>>,
@@ -813,6 +1002,7 @@ impl<'a> Spanned for Binary<'a> {
 pub enum UnaryOp {
   Not,
   Negate,
+  Await,
 }
 
 pub struct Unary<'a> {
@@ -917,26 +1107,27 @@ impl<'a> Spanned for Call<'a> {
 }
 
 pub struct Index<'a> {
+  pub range: Span,
   pub index: Expr<'a>,
 }
 
 impl<'a> Index<'a> {
-  pub fn new(index: Expr<'a>) -> Self {
-    Self { index }
+  pub fn new(range: Span, index: Expr<'a>) -> Self {
+    Self { range, index }
   }
 }
 
 impl<'a> Spanned for Index<'a> {
   fn span(&self) -> Span {
-    self.index.span()
+    self.range
   }
 
   fn start(&self) -> u32 {
-    self.index.start()
+    self.range.start
   }
 
   fn end(&self) -> u32 {
-    self.index.end()
+    self.range.end
   }
 }
 
@@ -973,7 +1164,9 @@ pub enum Primary<'a> {
   Self_(Token<'a>),
   Super(Super<'a>),
   Lambda(Box<'a, Fun<'a>>),
+  Class(Box<'a, Class<'a>>),
   List(List<'a>),
+  Tuple(Tuple<'a>),
   Map(Map<'a>),
 }
 
@@ -992,7 +1185,9 @@ impl<'a> Spanned for Primary<'a> {
       Primary::Self_(self_) => self_.start(),
       Primary::Super(super_) => super_.start(),
       Primary::Lambda(lambda) => lambda.start(),
+      Primary::Class(class) => class.name.start(),
       Primary::List(list) => list.start(),
+      Primary::Tuple(tuple) => tuple.start(),
       Primary::Map(map) => map.start(),
     }
   }
@@ -1011,7 +1206,9 @@ impl<'a> Spanned for Primary<'a> {
       Primary::Self_(self_) => self_.end(),
       Primary::Super(super_) => super_.end(),
       Primary::Lambda(lambda) => lambda.end(),
+      Primary::Class(class) => class.end(),
       Primary::List(list) => list.end(),
+      Primary::Tuple(tuple) => tuple.end(),
       Primary::Map(map) => map.end(),
     }
   }
@@ -1073,6 +1270,31 @@ impl<'a> Spanned for List<'a> {
   }
 }
 
+pub struct Tuple<'a> {
+  pub range: Span,
+  pub items: Vec<Expr<'a>>,
+}
+
+impl<'a> Tuple<'a> {
+  pub fn new(range: Span, items: Vec<Expr<'a>>) -> Self {
+    Self { range, items }
+  }
+}
+
+impl<'a> Spanned for Tuple<'a> {
+  fn span(&self) -> Span {
+    self.range
+  }
+
+  fn start(&self) -> u32 {
+    self.range.start
+  }
+
+  fn end(&self) -> u32 {
+    self.range.end
+  }
+}
+
 pub struct Map<'a> {
   pub range: Span,
   pub entries: Vec<(Expr<'a>, Expr<'a>)>,
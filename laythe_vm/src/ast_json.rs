@@ -0,0 +1,866 @@
+use crate::ast::*;
+use crate::json::Json;
+use crate::token::Token;
+
+/// Serializes a parsed module to json for external tooling. Each node is
+/// an object with its `kind` and the byte offsets of its `span`
+pub struct AstJson;
+
+/// The json object for a node with the provided kind and fields
+fn node<S: Spanned>(kind: &'static str, spanned: &S, fields: Vec<(&'static str, Json)>) -> Json {
+  let mut node = vec![("kind", Json::from(kind)), ("span", span(spanned))];
+  node.extend(fields);
+  Json::object(node)
+}
+
+/// The json object for the span of a node
+fn span<S: Spanned>(spanned: &S) -> Json {
+  let span = spanned.span();
+  Json::object(vec![("start", span.start.into()), ("end", span.end.into())])
+}
+
+/// The json object for a name or literal token
+fn token(token: &Token) -> Json {
+  Json::object(vec![("value", token.str().into()), ("span", span(token))])
+}
+
+fn binary_op(op: &BinaryOp) -> &'static str {
+  match op {
+    BinaryOp::Add => "+",
+    BinaryOp::Sub => "-",
+    BinaryOp::Mul => "*",
+    BinaryOp::Div => "/",
+    BinaryOp::Lt => "<",
+    BinaryOp::LtEq => "<=",
+    BinaryOp::Gt => ">",
+    BinaryOp::GtEq => ">=",
+    BinaryOp::Eq => "==",
+    BinaryOp::Ne => "!=",
+    BinaryOp::And => "and",
+    BinaryOp::Or => "or",
+  }
+}
+
+fn assign_binary_op(op: &AssignBinaryOp) -> &'static str {
+  match op {
+    AssignBinaryOp::Add => "+=",
+    AssignBinaryOp::Sub => "-=",
+    AssignBinaryOp::Mul => "*=",
+    AssignBinaryOp::Div => "/=",
+  }
+}
+
+fn unary_op(op: &UnaryOp) -> &'static str {
+  match op {
+    UnaryOp::Not => "!",
+    UnaryOp::Negate => "-",
+    UnaryOp::Await => "await",
+  }
+}
+
+impl AstJson {
+  fn decls(&mut self, decls: &[Decl]) -> Json {
+    Json::Array(decls.iter().map(|decl| self.visit_decl(decl)).collect())
+  }
+
+  fn exprs(&mut self, exprs: &[Expr]) -> Json {
+    Json::Array(exprs.iter().map(|expr| self.visit_expr(expr)).collect())
+  }
+
+  fn types(&mut self, types: &[Type]) -> Json {
+    Json::Array(types.iter().map(|type_| self.visit_type(type_)).collect())
+  }
+
+  fn opt_type(&mut self, type_: &Option<Type>) -> Json {
+    type_
+      .as_ref()
+      .map_or(Json::Null, |type_| self.visit_type(type_))
+  }
+
+  fn fun_body(&mut self, body: &FunBody) -> Json {
+    match body {
+      FunBody::Block(block) => self.visit_block(block),
+      FunBody::Expr(expr) => self.visit_expr(expr),
+    }
+  }
+
+  fn fun_like(&mut self, kind: &'static str, fun: &Fun) -> Json {
+    node(
+      kind,
+      fun,
+      vec![
+        ("name", fun.name.as_ref().map(token).into()),
+        ("async", fun.is_async.into()),
+        ("call_sig", self.visit_call_sig(&fun.call_sig)),
+        ("body", self.fun_body(&fun.body)),
+      ],
+    )
+  }
+
+  fn class_like(&mut self, kind: &'static str, class: &Class) -> Json {
+    let super_class = match &class.super_class {
+      Some(super_class) => self.visit_type_ref(&super_class.type_ref),
+      None => Json::Null,
+    };
+    let type_members = class
+      .type_members
+      .iter()
+      .map(|member| self.visit_type_member(member))
+      .collect::<Vec<Json>>();
+    let init = match &class.init {
+      Some(init) => self.visit_method(init),
+      None => Json::Null,
+    };
+    let methods = class
+      .methods
+      .iter()
+      .map(|method| self.visit_method(method))
+      .collect::<Vec<Json>>();
+    let static_methods = class
+      .static_methods
+      .iter()
+      .map(|method| self.visit_static_method(method))
+      .collect::<Vec<Json>>();
+
+    node(
+      kind,
+      class,
+      vec![
+        ("name", token(&class.name)),
+        ("type_params", self.visit_type_params(&class.type_params)),
+        ("super_class", super_class),
+        ("type_members", type_members.into()),
+        ("init", init),
+        ("methods", methods.into()),
+        ("static_methods", static_methods.into()),
+        ("sealed", class.sealed.into()),
+      ],
+    )
+  }
+
+  fn trailer(&mut self, trailer: &Trailer) -> Json {
+    match trailer {
+      Trailer::Call(call) => self.visit_call(call),
+      Trailer::Index(index) => self.visit_index(index),
+      Trailer::Access(access) => self.visit_access(access),
+    }
+  }
+
+  fn else_(&mut self, else_: &Else) -> Json {
+    match else_ {
+      Else::If(if_) => self.visit_if(if_),
+      Else::Block(block) => self.visit_block(block),
+    }
+  }
+}
+
+impl<'a> Visitor<'a> for AstJson {
+  type Result = Json;
+
+  fn visit(&mut self, module: &Module) -> Self::Result {
+    node("Module", module, vec![("decls", self.decls(&module.decls))])
+  }
+
+  fn visit_decl(&mut self, decl: &Decl) -> Self::Result {
+    match decl {
+      Decl::Symbol(symbol) => self.visit_symbol(symbol),
+      Decl::Export(export) => self.visit_export(export),
+      Decl::Stmt(stmt) => self.visit_stmt(stmt),
+      Decl::Error(error) => self.visit_error(error),
+    }
+  }
+
+  fn visit_stmt(&mut self, stmt: &Stmt) -> Self::Result {
+    match stmt {
+      Stmt::Expr(expr) => node("ExprStmt", stmt, vec![("expr", self.visit_expr(expr))]),
+      Stmt::ImplicitReturn(expr) => node(
+        "ImplicitReturn",
+        stmt,
+        vec![("expr", self.visit_expr(expr))],
+      ),
+      Stmt::Import(import) => self.visit_import(import),
+      Stmt::For(for_) => self.visit_for(for_),
+      Stmt::If(if_) => self.visit_if(if_),
+      Stmt::Return(return_) => self.visit_return(return_),
+      Stmt::Raise(raise) => self.visit_raise(raise),
+      Stmt::Continue(continue_) => self.visit_continue(continue_),
+      Stmt::Break(break_) => self.visit_break(break_),
+      Stmt::While(while_) => self.visit_while(while_),
+      Stmt::Try(try_) => self.visit_try(try_),
+      Stmt::With(with) => self.visit_with(with),
+    }
+  }
+
+  fn visit_expr(&mut self, expr: &Expr) -> Self::Result {
+    match expr {
+      Expr::Assign(assign) => self.visit_assign(assign),
+      Expr::AssignBinary(assign_binary) => self.visit_assign_binary(assign_binary),
+      Expr::Binary(binary) => self.visit_binary(binary),
+      Expr::Unary(unary) => self.visit_unary(unary),
+      Expr::Atom(atom) => self.visit_atom(atom),
+    }
+  }
+
+  fn visit_primary(&mut self, primary: &Primary) -> Self::Result {
+    match primary {
+      Primary::AssignBlock(block) => self.visit_assign_block(block),
+      Primary::True(token) => self.visit_true(token),
+      Primary::False(token) => self.visit_false(token),
+      Primary::Nil(token) => self.visit_nil(token),
+      Primary::Number(token) => self.visit_number(token),
+      Primary::Grouping(expr) => node("Grouping", primary, vec![("expr", self.visit_expr(expr))]),
+      Primary::String(token) => self.visit_string(token),
+      Primary::Interpolation(string_interp) => self.visit_interpolation(string_interp),
+      Primary::Ident(token) => self.visit_ident(token),
+      Primary::Self_(token) => self.visit_self(token),
+      Primary::Super(super_) => self.visit_super(super_),
+      Primary::Lambda(fun) => self.visit_lambda(fun),
+      Primary::Class(class) => self.visit_class_expr(class),
+      Primary::List(items) => self.visit_list(items),
+      Primary::Tuple(items) => self.visit_tuple(items),
+      Primary::Map(kvps) => self.visit_map(kvps),
+    }
+  }
+
+  fn visit_symbol(&mut self, symbol: &Symbol) -> Self::Result {
+    match symbol {
+      Symbol::Class(class) => self.visit_class(class),
+      Symbol::Extension(extension) => self.visit_extension(extension),
+      Symbol::Fun(fun) => self.visit_fun(fun),
+      Symbol::Let(let_) => self.visit_let(let_),
+      Symbol::LetTuple(let_tuple) => self.visit_let_tuple(let_tuple),
+      Symbol::Const(const_) => self.visit_const(const_),
+      Symbol::Trait(trait_) => self.visit_trait(trait_),
+      Symbol::TypeDecl(type_decl) => self.visit_type_decl(type_decl),
+      Symbol::Import(import) => self.visit_import(import),
+    }
+  }
+
+  fn visit_export(&mut self, export: &Symbol) -> Self::Result {
+    node(
+      "Export",
+      export,
+      vec![("symbol", self.visit_symbol(export))],
+    )
+  }
+
+  fn visit_error(&mut self, error: &[Token<'a>]) -> Self::Result {
+    let tokens = error.iter().map(token).collect::<Vec<Json>>();
+    let span = match (error.first(), error.last()) {
+      (Some(first), Some(last)) => Json::object(vec![
+        ("start", first.start().into()),
+        ("end", last.end().into()),
+      ]),
+      _ => Json::Null,
+    };
+
+    Json::object(vec![
+      ("kind", "Error".into()),
+      ("span", span),
+      ("tokens", tokens.into()),
+    ])
+  }
+
+  fn visit_class(&mut self, class: &Class) -> Self::Result {
+    self.class_like("Class", class)
+  }
+
+  fn visit_extension(&mut self, extension: &Extension) -> Self::Result {
+    let methods = extension
+      .methods
+      .iter()
+      .map(|method| self.visit_method(method))
+      .collect::<Vec<Json>>();
+    let static_methods = extension
+      .static_methods
+      .iter()
+      .map(|method| self.visit_static_method(method))
+      .collect::<Vec<Json>>();
+
+    node(
+      "Extension",
+      extension,
+      vec![
+        ("class", token(&extension.class)),
+        ("methods", methods.into()),
+        ("static_methods", static_methods.into()),
+      ],
+    )
+  }
+
+  fn visit_fun(&mut self, fun: &Fun) -> Self::Result {
+    self.fun_like("Fun", fun)
+  }
+
+  fn visit_let(&mut self, let_: &Let) -> Self::Result {
+    let value = match &let_.value {
+      Some(value) => self.visit_expr(value),
+      None => Json::Null,
+    };
+
+    node(
+      "Let",
+      let_,
+      vec![
+        ("name", token(&let_.name)),
+        ("type", self.opt_type(&let_.type_)),
+        ("value", value),
+      ],
+    )
+  }
+
+  fn visit_let_tuple(&mut self, let_tuple: &LetTuple) -> Self::Result {
+    let names = let_tuple.names.iter().map(token).collect::<Vec<Json>>();
+
+    node(
+      "LetTuple",
+      let_tuple,
+      vec![
+        ("names", names.into()),
+        ("value", self.visit_expr(&let_tuple.value)),
+      ],
+    )
+  }
+
+  fn visit_const(&mut self, const_: &Const) -> Self::Result {
+    node(
+      "Const",
+      const_,
+      vec![
+        ("name", token(&const_.name)),
+        ("value", self.visit_expr(&const_.value)),
+      ],
+    )
+  }
+
+  fn visit_method(&mut self, method: &Fun) -> Self::Result {
+    self.fun_like("Method", method)
+  }
+
+  fn visit_static_method(&mut self, static_method: &Fun) -> Self::Result {
+    self.fun_like("StaticMethod", static_method)
+  }
+
+  fn visit_import(&mut self, import: &Import) -> Self::Result {
+    let path = import.path().iter().map(token).collect::<Vec<Json>>();
+    let (rename, symbols) = match &import.stem {
+      ImportStem::None => (Json::Null, Json::Null),
+      ImportStem::Rename(rename) => (token(rename), Json::Null),
+      ImportStem::Symbols(symbols) => {
+        let symbols = symbols
+          .iter()
+          .map(|symbol| {
+            node(
+              "ImportSymbol",
+              symbol,
+              vec![
+                ("symbol", token(&symbol.symbol)),
+                ("rename", symbol.rename.as_ref().map(token).into()),
+              ],
+            )
+          })
+          .collect::<Vec<Json>>();
+        (Json::Null, symbols.into())
+      },
+    };
+
+    node(
+      "Import",
+      import,
+      vec![
+        ("path", path.into()),
+        ("rename", rename),
+        ("symbols", symbols),
+      ],
+    )
+  }
+
+  fn visit_for(&mut self, for_: &For) -> Self::Result {
+    node(
+      "For",
+      for_,
+      vec![
+        ("item", token(&for_.item)),
+        ("iter", self.visit_expr(&for_.iter)),
+        ("body", self.visit_block(&for_.body)),
+      ],
+    )
+  }
+
+  fn visit_while(&mut self, while_: &While) -> Self::Result {
+    node(
+      "While",
+      while_,
+      vec![
+        ("cond", self.visit_expr(&while_.cond)),
+        ("body", self.visit_block(&while_.body)),
+      ],
+    )
+  }
+
+  fn visit_if(&mut self, if_: &If) -> Self::Result {
+    let else_ = match &if_.else_ {
+      Some(else_) => self.else_(else_),
+      None => Json::Null,
+    };
+
+    node(
+      "If",
+      if_,
+      vec![
+        ("cond", self.visit_expr(&if_.cond)),
+        ("body", self.visit_block(&if_.body)),
+        ("else", else_),
+      ],
+    )
+  }
+
+  fn visit_return(&mut self, return_: &Return) -> Self::Result {
+    let value = match &return_.value {
+      Some(value) => self.visit_expr(value),
+      None => Json::Null,
+    };
+
+    node("Return", return_, vec![("value", value)])
+  }
+
+  fn visit_raise(&mut self, raise: &Raise) -> Self::Result {
+    let cause = match &raise.cause {
+      Some(cause) => self.visit_expr(cause),
+      None => Json::Null,
+    };
+
+    node(
+      "Raise",
+      raise,
+      vec![("error", self.visit_expr(&raise.error)), ("cause", cause)],
+    )
+  }
+
+  fn visit_continue(&mut self, continue_: &Token<'a>) -> Self::Result {
+    node("Continue", continue_, vec![])
+  }
+
+  fn visit_break(&mut self, break_: &Token<'a>) -> Self::Result {
+    node("Break", break_, vec![])
+  }
+
+  fn visit_try(&mut self, try_: &Try) -> Self::Result {
+    node(
+      "Try",
+      try_,
+      vec![
+        ("block", self.visit_block(&try_.block)),
+        ("error", try_.error.as_ref().map(token).into()),
+        ("catch", self.visit_block(&try_.catch)),
+      ],
+    )
+  }
+
+  fn visit_with(&mut self, with: &With) -> Self::Result {
+    node(
+      "With",
+      with,
+      vec![
+        ("manager", self.visit_expr(&with.manager)),
+        ("name", with.name.as_ref().map(token).into()),
+        ("body", self.visit_block(&with.body)),
+      ],
+    )
+  }
+
+  fn visit_block(&mut self, block: &Block) -> Self::Result {
+    node("Block", block, vec![("decls", self.decls(&block.decls))])
+  }
+
+  fn visit_assign(&mut self, assign: &Assign) -> Self::Result {
+    node(
+      "Assign",
+      assign,
+      vec![
+        ("lhs", self.visit_expr(&assign.lhs)),
+        ("rhs", self.visit_expr(&assign.rhs)),
+      ],
+    )
+  }
+
+  fn visit_assign_binary(&mut self, assign: &AssignBinary) -> Self::Result {
+    node(
+      "AssignBinary",
+      assign,
+      vec![
+        ("op", assign_binary_op(&assign.op).into()),
+        ("lhs", self.visit_expr(&assign.lhs)),
+        ("rhs", self.visit_expr(&assign.rhs)),
+      ],
+    )
+  }
+
+  fn visit_binary(&mut self, binary: &Binary) -> Self::Result {
+    node(
+      "Binary",
+      binary,
+      vec![
+        ("op", binary_op(&binary.op).into()),
+        ("lhs", self.visit_expr(&binary.lhs)),
+        ("rhs", self.visit_expr(&binary.rhs)),
+      ],
+    )
+  }
+
+  fn visit_unary(&mut self, unary: &Unary) -> Self::Result {
+    node(
+      "Unary",
+      unary,
+      vec![
+        ("op", unary_op(&unary.op).into()),
+        ("expr", self.visit_expr(&unary.expr)),
+      ],
+    )
+  }
+
+  fn visit_atom(&mut self, atom: &Atom) -> Self::Result {
+    if atom.trailers.is_empty() {
+      return self.visit_primary(&atom.primary);
+    }
+
+    let trailers = atom
+      .trailers
+      .iter()
+      .map(|trailer| self.trailer(trailer))
+      .collect::<Vec<Json>>();
+
+    node(
+      "Atom",
+      atom,
+      vec![
+        ("primary", self.visit_primary(&atom.primary)),
+        ("trailers", trailers.into()),
+      ],
+    )
+  }
+
+  fn visit_index(&mut self, index: &Index) -> Self::Result {
+    node(
+      "Index",
+      index,
+      vec![("index", self.visit_expr(&index.index))],
+    )
+  }
+
+  fn visit_call(&mut self, call: &Call) -> Self::Result {
+    node("Call", call, vec![("args", self.exprs(&call.args))])
+  }
+
+  fn visit_access(&mut self, access: &Access) -> Self::Result {
+    node("Access", access, vec![("prop", token(&access.prop))])
+  }
+
+  fn visit_call_sig(&mut self, call_sig: &CallSignature) -> Self::Result {
+    let params = call_sig
+      .params
+      .iter()
+      .map(|param| {
+        node(
+          "Param",
+          param,
+          vec![
+            ("name", token(&param.name)),
+            ("type", self.opt_type(&param.type_)),
+          ],
+        )
+      })
+      .collect::<Vec<Json>>();
+
+    node(
+      "CallSignature",
+      call_sig,
+      vec![
+        ("type_params", self.visit_type_params(&call_sig.type_params)),
+        ("params", params.into()),
+        ("return_type", self.opt_type(&call_sig.return_type)),
+      ],
+    )
+  }
+
+  fn visit_assign_block(&mut self, block: &Block) -> Self::Result {
+    node(
+      "AssignBlock",
+      block,
+      vec![("decls", self.decls(&block.decls))],
+    )
+  }
+
+  fn visit_true(&mut self, token: &Token<'a>) -> Self::Result {
+    node("True", token, vec![])
+  }
+
+  fn visit_false(&mut self, token: &Token<'a>) -> Self::Result {
+    node("False", token, vec![])
+  }
+
+  fn visit_nil(&mut self, token: &Token<'a>) -> Self::Result {
+    node("Nil", token, vec![])
+  }
+
+  fn visit_number(&mut self, token: &Token<'a>) -> Self::Result {
+    node("Number", token, vec![("value", token.str().into())])
+  }
+
+  fn visit_string(&mut self, token: &Token<'a>) -> Self::Result {
+    node("String", token, vec![("value", token.str().into())])
+  }
+
+  fn visit_interpolation(&mut self, string_interp: &Interpolation) -> Self::Result {
+    let mut segments = vec![token(&string_interp.start)];
+    for segment in &string_interp.segments {
+      segments.push(match segment {
+        StringSegments::Token(segment) => token(segment),
+        StringSegments::Expr(expr) => self.visit_expr(expr),
+      });
+    }
+    segments.push(token(&string_interp.end));
+
+    node(
+      "Interpolation",
+      string_interp,
+      vec![("segments", segments.into())],
+    )
+  }
+
+  fn visit_ident(&mut self, token: &Token<'a>) -> Self::Result {
+    node("Ident", token, vec![("name", token.str().into())])
+  }
+
+  fn visit_self(&mut self, token: &Token<'a>) -> Self::Result {
+    node("Self", token, vec![])
+  }
+
+  fn visit_super(&mut self, super_: &Super) -> Self::Result {
+    node("Super", super_, vec![("access", token(&super_.access))])
+  }
+
+  fn visit_lambda(&mut self, fun: &Fun) -> Self::Result {
+    self.fun_like("Lambda", fun)
+  }
+
+  fn visit_class_expr(&mut self, class: &Class) -> Self::Result {
+    self.class_like("ClassExpr", class)
+  }
+
+  fn visit_list(&mut self, list: &List) -> Self::Result {
+    node("List", list, vec![("items", self.exprs(&list.items))])
+  }
+
+  fn visit_tuple(&mut self, tuple: &Tuple) -> Self::Result {
+    node("Tuple", tuple, vec![("items", self.exprs(&tuple.items))])
+  }
+
+  fn visit_map(&mut self, map: &Map) -> Self::Result {
+    let entries = map
+      .entries
+      .iter()
+      .map(|(key, value)| Json::Array(vec![self.visit_expr(key), self.visit_expr(value)]))
+      .collect::<Vec<Json>>();
+
+    node("Map", map, vec![("entries", entries.into())])
+  }
+}
+
+impl TypeVisitor for AstJson {
+  type Result = Json;
+
+  fn visit_trait(&mut self, trait_: &Trait) -> Self::Result {
+    let members = trait_
+      .members
+      .iter()
+      .map(|member| self.visit_type_member(member))
+      .collect::<Vec<Json>>();
+    let methods = trait_
+      .methods
+      .iter()
+      .map(|method| self.visit_type_method(method))
+      .collect::<Vec<Json>>();
+
+    node(
+      "Trait",
+      trait_,
+      vec![
+        ("name", token(&trait_.name)),
+        ("type_params", self.visit_type_params(&trait_.params)),
+        ("members", members.into()),
+        ("methods", methods.into()),
+      ],
+    )
+  }
+
+  fn visit_type_decl(&mut self, type_decl: &TypeDecl) -> Self::Result {
+    node(
+      "TypeDecl",
+      type_decl,
+      vec![
+        ("name", token(&type_decl.name)),
+        (
+          "type_params",
+          self.visit_type_params(&type_decl.type_params),
+        ),
+        ("type", self.visit_type(&type_decl.type_)),
+      ],
+    )
+  }
+
+  fn visit_type(&mut self, type_: &Type) -> Self::Result {
+    match type_ {
+      Type::Union(union) => self.visit_union(union),
+      Type::Intersection(intersection) => self.visit_intersection(intersection),
+      Type::Fun(fun) => self.visit_call_sig(fun),
+      Type::List(list) => self.visit_list_type(list),
+      Type::Ref(type_ref) => self.visit_type_ref(type_ref),
+      Type::Primitive(primitive) => self.visit_primitive(primitive),
+    }
+  }
+
+  fn visit_type_params(&mut self, type_params: &[TypeParam]) -> Self::Result {
+    Json::Array(
+      type_params
+        .iter()
+        .map(|type_param| {
+          node(
+            "TypeParam",
+            type_param,
+            vec![
+              ("name", token(&type_param.name)),
+              ("constraint", self.opt_type(&type_param.constraint)),
+            ],
+          )
+        })
+        .collect(),
+    )
+  }
+
+  fn visit_type_member(&mut self, type_member: &TypeMember) -> Self::Result {
+    node(
+      "TypeMember",
+      type_member,
+      vec![
+        ("name", token(&type_member.name)),
+        ("type", self.visit_type(&type_member.type_)),
+      ],
+    )
+  }
+
+  fn visit_type_method(&mut self, type_method: &TypeMethod) -> Self::Result {
+    node(
+      "TypeMethod",
+      type_method,
+      vec![
+        ("name", token(&type_method.name)),
+        ("call_sig", self.visit_call_sig(&type_method.call_sig)),
+      ],
+    )
+  }
+
+  fn visit_union(&mut self, union: &Union) -> Self::Result {
+    node(
+      "Union",
+      union,
+      vec![
+        ("lhs", self.visit_type(&union.lhs)),
+        ("rhs", self.visit_type(&union.rhs)),
+      ],
+    )
+  }
+
+  fn visit_intersection(&mut self, intersection: &Intersection) -> Self::Result {
+    node(
+      "Intersection",
+      intersection,
+      vec![
+        ("lhs", self.visit_type(&intersection.lhs)),
+        ("rhs", self.visit_type(&intersection.rhs)),
+      ],
+    )
+  }
+
+  fn visit_list_type(&mut self, list_type: &ListType) -> Self::Result {
+    node(
+      "ListType",
+      list_type,
+      vec![("item_type", self.visit_type(&list_type.item_type))],
+    )
+  }
+
+  fn visit_type_ref(&mut self, type_ref: &TypeRef) -> Self::Result {
+    node(
+      "TypeRef",
+      type_ref,
+      vec![
+        ("name", token(&type_ref.name)),
+        ("type_args", self.types(&type_ref.type_args)),
+      ],
+    )
+  }
+
+  fn visit_primitive(&mut self, primitive: &Primitive) -> Self::Result {
+    let name = match primitive {
+      Primitive::Nil(_) => "nil",
+      Primitive::Number(_) => "number",
+      Primitive::Bool(_) => "bool",
+      Primitive::String(_) => "string",
+      Primitive::Any(_) => "any",
+    };
+
+    node("Primitive", primitive, vec![("name", name.into())])
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{arena::Arena, compiler::Parser, source::Source};
+
+  fn ast_json(source: &str) -> Json {
+    let source = Source::new(source);
+    let arena = Arena::for_source(&source);
+
+    let (ast, _) = Parser::new(&source, &arena, 0).parse();
+    let ast = ast.expect("Expected source to parse");
+    AstJson.visit(&ast)
+  }
+
+  #[test]
+  fn let_binary() {
+    let json = ast_json("let a = 1 + b;");
+
+    assert_eq!(
+      json.to_string(),
+      concat!(
+        r#"{"kind":"Module","span":{"start":4,"end":13},"decls":[{"kind":"Let","#,
+        r#""span":{"start":4,"end":13},"name":{"value":"a","span":{"start":4,"end":5}},"#,
+        r#""type":null,"value":{"kind":"Binary","span":{"start":8,"end":13},"op":"+","#,
+        r#""lhs":{"kind":"Number","span":{"start":8,"end":9},"value":"1"},"#,
+        r#""rhs":{"kind":"Ident","span":{"start":12,"end":13},"name":"b"}}}]}"#,
+      )
+    );
+  }
+
+  #[test]
+  fn covers_nodes() {
+    let examples = [
+      "class A : B { field: number; init(a) { self.a = a; } m() { super.m(); } static s() {} }",
+      "async fn f<T>(a: T, b: number | string) -> list<T> { await a; return [a, b]; }",
+      "import std.io:{ stdout as out }; import std.math as m;",
+      "for x in [1, 2] { if x == 1 { continue; } else if x { break; } else {} }",
+      "while true { try { raise(); } catch e { print('${e}!'); } }",
+      "with open('a') as file { file.read(); } with lock {}",
+      "const MAX = 10; export const MIN = -1;",
+      "let m = { 'a': -1 }; m['a'] += 2; let f = |x| x * 2; f(m['a']);",
+      "trait T { a: nil; b() -> any; } type U<V> = V & bool;",
+    ];
+
+    for example in examples.iter() {
+      let json = ast_json(example);
+      assert_eq!(json.get("kind"), Some(&Json::from("Module")), "{}", example);
+    }
+  }
+}
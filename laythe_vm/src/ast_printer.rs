@@ -1,5 +1,5 @@
 use crate::ast::*;
-use crate::token::Token;
+use crate::token::{Token, TokenKind};
 
 #[derive(Default)]
 pub struct AstPrint {
@@ -22,6 +22,39 @@ impl AstPrint {
   pub fn str(&self) -> &str {
     &self.buffer
   }
+
+  /// Print a class from its name to the closing brace
+  fn class_body(&mut self, class: &Class) {
+    self.buffer.push_str("class ");
+    self.buffer.push_str(&class.name.str());
+
+    self.buffer.push_str(" {\n");
+    self.depth += 1;
+
+    for member in &class.type_members {
+      self.visit_type_member(member);
+      self.buffer.push('\n');
+    }
+
+    if let Some(init) = &class.init {
+      self.visit_method(&init);
+      self.buffer.push('\n');
+    }
+
+    for method in &class.methods {
+      self.visit_method(&method);
+      self.buffer.push('\n');
+    }
+
+    for static_method in &class.static_methods {
+      self.visit_static_method(&static_method);
+      self.buffer.push('\n');
+    }
+
+    self.depth -= 1;
+    self.pad();
+    self.buffer.push('}');
+  }
 }
 
 impl<'a> Visitor<'a> for AstPrint {
@@ -60,10 +93,12 @@ impl<'a> Visitor<'a> for AstPrint {
       Stmt::For(for_) => self.visit_for(for_),
       Stmt::If(if_) => self.visit_if(if_),
       Stmt::Return(return_) => self.visit_return(return_),
+      Stmt::Raise(raise) => self.visit_raise(raise),
       Stmt::Continue(continue_) => self.visit_continue(continue_),
       Stmt::Break(break_) => self.visit_break(break_),
       Stmt::While(while_) => self.visit_while(while_),
       Stmt::Try(try_) => self.visit_try(try_),
+      Stmt::With(with) => self.visit_with(with),
     }
   }
 
@@ -95,7 +130,9 @@ impl<'a> Visitor<'a> for AstPrint {
       Primary::Self_(token) => self.visit_self(token),
       Primary::Super(token) => self.visit_super(token),
       Primary::Lambda(fun) => self.visit_lambda(fun),
+      Primary::Class(class) => self.visit_class_expr(class),
       Primary::List(items) => self.visit_list(items),
+      Primary::Tuple(items) => self.visit_tuple(items),
       Primary::Map(kvps) => self.visit_map(kvps),
     }
   }
@@ -103,20 +140,28 @@ impl<'a> Visitor<'a> for AstPrint {
   fn visit_symbol(&mut self, symbol: &Symbol) -> Self::Result {
     match symbol {
       Symbol::Class(class) => self.visit_class(class),
+      Symbol::Extension(extension) => self.visit_extension(extension),
       Symbol::Fun(fun) => self.visit_fun(fun),
       Symbol::Let(let_) => self.visit_let(let_),
+      Symbol::LetTuple(let_tuple) => self.visit_let_tuple(let_tuple),
+      Symbol::Const(const_) => self.visit_const(const_),
       Symbol::Trait(trait_) => self.visit_trait(trait_),
       Symbol::TypeDecl(type_decl) => self.visit_type_decl(type_decl),
+      Symbol::Import(import) => self.visit_import(import),
     }
   }
   fn visit_export(&mut self, export: &Symbol) -> Self::Result {
     self.buffer.push_str("export ");
     match &export {
       Symbol::Class(class) => self.visit_class(class),
+      Symbol::Extension(extension) => self.visit_extension(extension),
       Symbol::Fun(fun) => self.visit_fun(fun),
       Symbol::Let(let_) => self.visit_let(let_),
+      Symbol::LetTuple(let_tuple) => self.visit_let_tuple(let_tuple),
+      Symbol::Const(const_) => self.visit_const(const_),
       Symbol::Trait(trait_) => self.visit_trait(trait_),
       Symbol::TypeDecl(type_decl) => self.visit_type_decl(type_decl),
+      Symbol::Import(import) => self.visit_import(import),
     }
   }
 
@@ -129,36 +174,34 @@ impl<'a> Visitor<'a> for AstPrint {
 
   fn visit_class(&mut self, class: &Class) -> Self::Result {
     self.pad();
+    if class.sealed {
+      self.buffer.push_str("sealed ");
+    }
+    self.class_body(class);
+    self.buffer.push('\n')
+  }
 
-    self.buffer.push_str("class ");
-    self.buffer.push_str(&class.name.str());
+  fn visit_extension(&mut self, extension: &Extension) -> Self::Result {
+    self.pad();
+    self.buffer.push_str("extend ");
+    self.buffer.push_str(extension.class.str());
 
     self.buffer.push_str(" {\n");
     self.depth += 1;
 
-    for member in &class.type_members {
-      self.visit_type_member(member);
-      self.buffer.push('\n');
-    }
-
-    if let Some(init) = &class.init {
-      self.visit_method(&init);
+    for method in &extension.methods {
+      self.visit_method(method);
       self.buffer.push('\n');
     }
 
-    for method in &class.methods {
-      self.visit_method(&method);
-      self.buffer.push('\n');
-    }
-
-    for static_method in &class.static_methods {
-      self.visit_static_method(&static_method);
+    for static_method in &extension.static_methods {
+      self.visit_static_method(static_method);
       self.buffer.push('\n');
     }
 
     self.depth -= 1;
     self.pad();
-    self.buffer.push_str("}\n")
+    self.buffer.push_str("}\n");
   }
 
   fn visit_method(&mut self, method: &Fun) -> Self::Result {
@@ -166,6 +209,9 @@ impl<'a> Visitor<'a> for AstPrint {
 
     match &method.name {
       Some(name) => {
+        if method.is_async {
+          self.buffer.push_str("async ");
+        }
         self.buffer.push_str(&name.str());
       }
       None => unreachable!(),
@@ -189,7 +235,11 @@ impl<'a> Visitor<'a> for AstPrint {
     self.buffer.push_str(") ");
     match &method.body {
       FunBody::Block(block) => self.visit_block(&block),
-      FunBody::Expr(expr) => self.visit_expr(&expr),
+      FunBody::Expr(expr) => {
+        self.buffer.push_str("=> ");
+        self.visit_expr(&expr);
+        self.buffer.push(';');
+      },
     };
   }
   fn visit_static_method(&mut self, static_method: &Fun) -> Self::Result {
@@ -198,6 +248,9 @@ impl<'a> Visitor<'a> for AstPrint {
     match &static_method.name {
       Some(name) => {
         self.buffer.push_str("static ");
+        if static_method.is_async {
+          self.buffer.push_str("async ");
+        }
         self.buffer.push_str(&name.str());
       }
       None => unreachable!(),
@@ -220,13 +273,21 @@ impl<'a> Visitor<'a> for AstPrint {
     self.buffer.push_str(") ");
     match &static_method.body {
       FunBody::Block(block) => self.visit_block(&block),
-      FunBody::Expr(expr) => self.visit_expr(&expr),
+      FunBody::Expr(expr) => {
+        self.buffer.push_str("=> ");
+        self.visit_expr(&expr);
+        self.buffer.push(';');
+      },
     };
   }
 
   fn visit_fun(&mut self, fun: &Fun) -> Self::Result {
     self.pad();
 
+    if fun.is_async {
+      self.buffer.push_str("async ");
+    }
+
     match &fun.name {
       Some(name) => {
         self.buffer.push_str("fn ");
@@ -240,7 +301,11 @@ impl<'a> Visitor<'a> for AstPrint {
 
     match &fun.body {
       FunBody::Block(block) => self.visit_block(&block),
-      FunBody::Expr(expr) => self.visit_expr(&expr),
+      FunBody::Expr(expr) => {
+        self.buffer.push_str("=> ");
+        self.visit_expr(&expr);
+        self.buffer.push(';');
+      },
     };
   }
   fn visit_let(&mut self, let_: &Let) -> Self::Result {
@@ -263,14 +328,39 @@ impl<'a> Visitor<'a> for AstPrint {
     }
   }
 
+  fn visit_let_tuple(&mut self, let_tuple: &LetTuple) -> Self::Result {
+    self.pad();
+    self.buffer.push_str("let (");
+    let names: Vec<&str> = let_tuple.names.iter().map(|name| name.str()).collect();
+    self.buffer.push_str(&names.join(", "));
+    self.buffer.push_str(") = ");
+    self.visit_expr(&let_tuple.value);
+    self.buffer.push(';');
+  }
+
+  fn visit_const(&mut self, const_: &Const) -> Self::Result {
+    self.pad();
+    self.buffer.push_str("const ");
+    self.buffer.push_str(const_.name.str());
+    self.buffer.push_str(" = ");
+    self.visit_expr(&const_.value);
+    self.buffer.push(';');
+  }
+
   fn visit_import(&mut self, import: &Import) -> Self::Result {
     self.pad();
     self.buffer.push_str("import ");
+    // relative paths such as ./utils separate segments with '/'
+    let separator = match import.path[0].kind() {
+      TokenKind::Dot => '/',
+      _ => '.',
+    };
+
     match import.path.split_last() {
       Some((last, start)) => {
         for segment in start {
           self.buffer.push_str(segment.str());
-          self.buffer.push('.');
+          self.buffer.push(separator);
         }
         self.buffer.push_str(last.str());
       }
@@ -293,7 +383,7 @@ impl<'a> Visitor<'a> for AstPrint {
         self.buffer.push_str(rename.str());
       }
       ImportStem::Symbols(symbols) => {
-        self.buffer.push('{');
+        self.buffer.push_str(":{");
         match symbols.split_last() {
           Some((last, start)) => {
             for symbol in start {
@@ -354,6 +444,17 @@ impl<'a> Visitor<'a> for AstPrint {
       None => self.buffer.push_str("return;"),
     }
   }
+  fn visit_raise(&mut self, raise: &Raise) -> Self::Result {
+    self.pad();
+    self.buffer.push_str("raise ");
+    self.visit_expr(&raise.error);
+
+    if let Some(cause) = &raise.cause {
+      self.buffer.push_str(" from ");
+      self.visit_expr(cause);
+    }
+    self.buffer.push(';');
+  }
   fn visit_continue(&mut self, _: &Token) -> Self::Result {
     self.pad();
     self.buffer.push_str("continue;")
@@ -370,9 +471,27 @@ impl<'a> Visitor<'a> for AstPrint {
     &self.visit_block(&try_.block);
 
     self.buffer.push_str(" catch ");
+    if let Some(error) = &try_.error {
+      self.buffer.push_str(error.str());
+      self.buffer.push(' ');
+    }
     self.visit_block(&try_.catch);
   }
 
+  fn visit_with(&mut self, with: &With) -> Self::Result {
+    self.pad();
+    self.buffer.push_str("with ");
+    self.visit_expr(&with.manager);
+
+    if let Some(name) = &with.name {
+      self.buffer.push_str(" as ");
+      self.buffer.push_str(name.str());
+    }
+
+    self.buffer.push(' ');
+    self.visit_block(&with.body);
+  }
+
   fn visit_block(&mut self, block: &Block) -> Self::Result {
     self.buffer.push_str("{\n");
     self.depth += 1;
@@ -430,6 +549,7 @@ impl<'a> Visitor<'a> for AstPrint {
     match &unary.op {
       UnaryOp::Not => self.buffer.push('!'),
       UnaryOp::Negate => self.buffer.push('-'),
+      UnaryOp::Await => self.buffer.push_str("await "),
     }
 
     self.visit_expr(&unary.expr)
@@ -545,7 +665,13 @@ impl<'a> Visitor<'a> for AstPrint {
     self.buffer.push_str("super.");
     self.buffer.push_str(&super_.access.str());
   }
+  fn visit_class_expr(&mut self, class: &Class) -> Self::Result {
+    self.class_body(class);
+  }
   fn visit_lambda(&mut self, fun: &Fun) -> Self::Result {
+    if fun.is_async {
+      self.buffer.push_str("async ");
+    }
     self.buffer.push('|');
     let len = fun.call_sig.params.len();
     for (idx, param) in fun.call_sig.params.iter().enumerate() {
@@ -579,6 +705,19 @@ impl<'a> Visitor<'a> for AstPrint {
 
     self.buffer.push(']');
   }
+  fn visit_tuple(&mut self, tuple: &Tuple) -> Self::Result {
+    self.buffer.push('(');
+    let len = tuple.items.len();
+    for (idx, arg) in tuple.items.iter().enumerate() {
+      self.visit_expr(arg);
+
+      if idx < len - 1 || len == 1 {
+        self.buffer.push_str(", ");
+      }
+    }
+
+    self.buffer.push(')');
+  }
   fn visit_map(&mut self, map: &Map) -> Self::Result {
     self.buffer.push('{');
     let len = map.entries.len();
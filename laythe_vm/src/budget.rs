@@ -0,0 +1,272 @@
+use laythe_env::time::Time;
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+/// The number of instructions executed between budget checks
+const CHECK_INTERVAL: u32 = 1024;
+
+/// Limits placed on the vm. Execution stops with a timeout once any
+/// budget is exhausted, while exceeding the maximum heap size raises
+/// an `OutOfMemoryError`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VmConfig {
+  /// The maximum number of instructions to execute
+  pub instruction_budget: Option<u64>,
+
+  /// The maximum wall clock time to execute for
+  pub wall_clock_budget: Option<Duration>,
+
+  /// The maximum size in bytes of the heap
+  pub max_heap_bytes: Option<usize>,
+}
+
+/// The outcome of accounting for an instruction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tick {
+  /// Execution may continue
+  Continue,
+
+  /// A budget has been exhausted
+  Exhausted,
+
+  /// The budget was woken before its interval ended
+  Woken,
+}
+
+/// A handle that forces the budget's next tick onto its slow path,
+/// letting events outside the dispatch loop be observed without a
+/// check on every instruction
+#[derive(Clone)]
+pub struct BudgetWake {
+  /// Instructions remaining in the current interval
+  countdown: Rc<Cell<u32>>,
+
+  /// Instructions of the current interval skipped by waking early
+  skipped: Rc<Cell<u32>>,
+}
+
+impl BudgetWake {
+  /// End the current interval early. The skipped
+  /// instructions resume once the budget is woken
+  pub fn wake(&self) {
+    let remaining = self.countdown.replace(0);
+    self.skipped.set(self.skipped.get() + remaining);
+  }
+}
+
+/// Tracks the execution budget of the current run. The budget is
+/// only checked periodically to keep the dispatch loop cheap
+pub struct Budget {
+  /// The limits of each run
+  config: VmConfig,
+
+  /// The length of the current check interval
+  interval: u32,
+
+  /// Instructions remaining in the current interval and
+  /// those skipped when woken early
+  wake: BudgetWake,
+
+  /// Instructions executed prior to the current interval
+  executed: u64,
+
+  /// When the current run started
+  start: Duration,
+
+  /// Has a budget been exhausted during this run
+  exhausted: bool,
+
+  /// The time facilities used to track the wall clock
+  time: Time,
+}
+
+impl Budget {
+  /// Create a new budget with the provided limits
+  pub fn new(config: VmConfig, time: Time) -> Self {
+    let mut budget = Self {
+      config,
+      interval: 0,
+      wake: BudgetWake {
+        countdown: Rc::new(Cell::new(0)),
+        skipped: Rc::new(Cell::new(0)),
+      },
+      executed: 0,
+      start: Duration::default(),
+      exhausted: false,
+      time,
+    };
+
+    budget.reset();
+    budget
+  }
+
+  /// Reset the budget at the start of a new run
+  pub fn reset(&mut self) {
+    self.executed = 0;
+    self.exhausted = false;
+    self.start = self.time.elapsed().unwrap_or_default();
+    self.interval = self.next_interval();
+    self.wake.countdown.set(self.interval);
+    self.wake.skipped.set(0);
+  }
+
+  /// A handle that wakes this budget before its interval ends
+  pub fn wake_handle(&self) -> BudgetWake {
+    self.wake.clone()
+  }
+
+  /// Has a budget been exhausted during this run
+  #[inline]
+  pub fn is_exhausted(&self) -> bool {
+    self.exhausted
+  }
+
+  /// Account for the next instruction, reporting whether a
+  /// budget has been exhausted or the budget was woken
+  #[inline]
+  pub fn tick(&mut self) -> Tick {
+    let countdown = self.wake.countdown.get();
+    if countdown == 0 {
+      return self.check();
+    }
+
+    self.wake.countdown.set(countdown - 1);
+    Tick::Continue
+  }
+
+  /// Check each budget once an interval has elapsed
+  #[inline(never)]
+  fn check(&mut self) -> Tick {
+    if self.exhausted {
+      return Tick::Exhausted;
+    }
+
+    // woken early so resume the current interval, accounting for this instruction
+    let skipped = self.wake.skipped.replace(0);
+    if skipped > 0 {
+      self.wake.countdown.set(skipped - 1);
+      return Tick::Woken;
+    }
+
+    self.executed += self.interval as u64;
+
+    if let Some(instruction_budget) = self.config.instruction_budget {
+      if self.executed >= instruction_budget {
+        self.exhausted = true;
+        return Tick::Exhausted;
+      }
+    }
+
+    if let Some(wall_clock_budget) = self.config.wall_clock_budget {
+      let now = self.time.elapsed().unwrap_or_default();
+      if now.saturating_sub(self.start) >= wall_clock_budget {
+        self.exhausted = true;
+        return Tick::Exhausted;
+      }
+    }
+
+    // this check accounts for the first instruction of the next interval
+    self.interval = self.next_interval();
+    self.wake.countdown.set(self.interval - 1);
+    Tick::Continue
+  }
+
+  /// The length of the next interval, ending early enough
+  /// to land exactly on the instruction budget
+  fn next_interval(&self) -> u32 {
+    match self.config.instruction_budget {
+      Some(budget) => budget
+        .saturating_sub(self.executed)
+        .min(CHECK_INTERVAL as u64) as u32,
+      None => CHECK_INTERVAL,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn run(budget: &mut Budget, instructions: u64) -> Option<u64> {
+    (0..instructions).find(|_| budget.tick() == Tick::Exhausted)
+  }
+
+  #[test]
+  fn unlimited() {
+    let mut budget = Budget::new(VmConfig::default(), Time::default());
+
+    assert_eq!(run(&mut budget, 10_000), None);
+    assert!(!budget.is_exhausted());
+  }
+
+  #[test]
+  fn instruction_budget() {
+    let config = VmConfig {
+      instruction_budget: Some(2500),
+      wall_clock_budget: None,
+      max_heap_bytes: None,
+    };
+    let mut budget = Budget::new(config, Time::default());
+
+    assert_eq!(run(&mut budget, 10_000), Some(2500));
+    assert!(budget.is_exhausted());
+    assert_eq!(budget.tick(), Tick::Exhausted);
+  }
+
+  #[test]
+  fn zero_instruction_budget() {
+    let config = VmConfig {
+      instruction_budget: Some(0),
+      wall_clock_budget: None,
+      max_heap_bytes: None,
+    };
+    let mut budget = Budget::new(config, Time::default());
+
+    assert_eq!(run(&mut budget, 10), Some(0));
+  }
+
+  #[test]
+  fn wall_clock_budget() {
+    let config = VmConfig {
+      instruction_budget: None,
+      wall_clock_budget: Some(Duration::default()),
+      max_heap_bytes: None,
+    };
+    let mut budget = Budget::new(config, Time::default());
+
+    assert_eq!(run(&mut budget, 10_000), Some(CHECK_INTERVAL as u64));
+  }
+
+  #[test]
+  fn reset() {
+    let config = VmConfig {
+      instruction_budget: Some(10),
+      wall_clock_budget: None,
+      max_heap_bytes: None,
+    };
+    let mut budget = Budget::new(config, Time::default());
+
+    assert_eq!(run(&mut budget, 100), Some(10));
+    budget.reset();
+    assert!(!budget.is_exhausted());
+    assert_eq!(run(&mut budget, 100), Some(10));
+  }
+
+  #[test]
+  fn woken() {
+    let config = VmConfig {
+      instruction_budget: Some(2500),
+      wall_clock_budget: None,
+      max_heap_bytes: None,
+    };
+    let mut budget = Budget::new(config, Time::default());
+    let wake = budget.wake_handle();
+
+    assert_eq!(run(&mut budget, 100), None);
+    wake.wake();
+    assert_eq!(budget.tick(), Tick::Woken);
+    assert_eq!(budget.tick(), Tick::Continue);
+
+    // waking does not change where the instruction budget lands
+    assert_eq!(run(&mut budget, 10_000), Some(2500 - 102));
+  }
+}
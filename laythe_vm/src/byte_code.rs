@@ -1,8 +1,5 @@
 use laythe_core::chunk::Encode;
-use std::mem;
-
-#[cfg(any(test, feature = "debug"))]
-use std::convert::TryInto;
+use std::{convert::TryInto, fmt, io, io::Write, mem};
 
 /// Space Lox virtual machine byte codes
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -55,6 +52,12 @@ pub enum AlignedByteCode {
   /// Initialize map from literal
   Map(u16),
 
+  /// Initialize tuple from literal
+  Tuple(u16),
+
+  /// Unpack a tuple onto the stack
+  Unpack(u16),
+
   /// Combine string interpolation
   Interpolate(u16),
 
@@ -64,6 +67,15 @@ pub enum AlignedByteCode {
   /// Get the current value from an iterator
   IterCurrent(u16),
 
+  /// Await the completion of a fiber
+  Await,
+
+  /// Raise the error on top of the stack again
+  Raise,
+
+  /// Raise the error below the top of the stack, caused by the top
+  RaiseFrom,
+
   /// Drop a value
   Drop,
 
@@ -127,6 +139,9 @@ pub enum AlignedByteCode {
   /// Invoke a method on a super class
   SuperInvoke((u16, u8)),
 
+  /// Invoke a method of a sealed class by its vtable index
+  InvokeSealed((u16, u8)),
+
   /// Create a closure
   Closure(u16),
 
@@ -145,6 +160,12 @@ pub enum AlignedByteCode {
   /// Inherit from another class
   Inherit,
 
+  /// Open an existing class to add methods
+  Extend,
+
+  /// Seal a class against further extension
+  Seal,
+
   /// Access this classes super
   GetSuper(u16),
 
@@ -163,6 +184,12 @@ pub enum AlignedByteCode {
   /// Check if the top two operands on the stack are not equal
   NotEqual,
 
+  /// Check if the top of the stack is equal to a string constant
+  EqualConst(u16),
+
+  /// Check if the top of the stack is not equal to a string constant
+  NotEqualConst(u16),
+
   /// Apply greater between the top two operands on the stack
   Greater,
 
@@ -174,17 +201,25 @@ pub enum AlignedByteCode {
 
   /// Check if the 2nd from the top operand is <= the top
   LessEqual,
+
+  /// Add a number constant to a local in place
+  AddConstToLocal((u16, u8)),
+
+  /// Jump if the 2nd from the top operand is not < the top
+  JumpIfNotLess(u16),
 }
 
 impl AlignedByteCode {
-  /// Decode unaligned bytecode to aligned bytecode. Primarily for testing purposes
-  #[cfg(any(test, feature = "debug"))]
+  /// Decode unaligned bytecode to aligned bytecode
   pub fn decode(store: &[u8], offset: usize) -> (AlignedByteCode, usize) {
     let byte_code = ByteCode::from(store[offset]);
 
     match byte_code {
       ByteCode::Return => (AlignedByteCode::Return, offset + 1),
       ByteCode::Negate => (AlignedByteCode::Negate, offset + 1),
+      ByteCode::Await => (AlignedByteCode::Await, offset + 1),
+      ByteCode::Raise => (AlignedByteCode::Raise, offset + 1),
+      ByteCode::RaiseFrom => (AlignedByteCode::RaiseFrom, offset + 1),
       ByteCode::Add => (AlignedByteCode::Add, offset + 1),
       ByteCode::Subtract => (AlignedByteCode::Subtract, offset + 1),
       ByteCode::Multiply => (AlignedByteCode::Multiply, offset + 1),
@@ -214,6 +249,14 @@ impl AlignedByteCode {
         AlignedByteCode::Map(decode_u16(&store[offset + 1..offset + 3])),
         offset + 3,
       ),
+      ByteCode::Tuple => (
+        AlignedByteCode::Tuple(decode_u16(&store[offset + 1..offset + 3])),
+        offset + 3,
+      ),
+      ByteCode::Unpack => (
+        AlignedByteCode::Unpack(decode_u16(&store[offset + 1..offset + 3])),
+        offset + 3,
+      ),
       ByteCode::Interpolate => (
         AlignedByteCode::Interpolate(decode_u16(&store[offset + 1..offset + 3])),
         offset + 3,
@@ -295,6 +338,13 @@ impl AlignedByteCode {
         )),
         offset + 4,
       ),
+      ByteCode::InvokeSealed => (
+        AlignedByteCode::InvokeSealed((
+          decode_u16(&store[offset + 1..offset + 3]),
+          store[offset + 3],
+        )),
+        offset + 4,
+      ),
       ByteCode::Closure => (
         AlignedByteCode::Closure(decode_u16(&store[offset + 1..offset + 3])),
         offset + 3,
@@ -316,6 +366,8 @@ impl AlignedByteCode {
         offset + 3,
       ),
       ByteCode::Inherit => (AlignedByteCode::Inherit, offset + 1),
+      ByteCode::Extend => (AlignedByteCode::Extend, offset + 1),
+      ByteCode::Seal => (AlignedByteCode::Seal, offset + 1),
       ByteCode::GetSuper => (
         AlignedByteCode::GetSuper(decode_u16(&store[offset + 1..offset + 3])),
         offset + 3,
@@ -323,10 +375,29 @@ impl AlignedByteCode {
       ByteCode::CloseUpvalue => (AlignedByteCode::CloseUpvalue, offset + 1),
       ByteCode::Equal => (AlignedByteCode::Equal, offset + 1),
       ByteCode::NotEqual => (AlignedByteCode::NotEqual, offset + 1),
+      ByteCode::EqualConst => (
+        AlignedByteCode::EqualConst(decode_u16(&store[offset + 1..offset + 3])),
+        offset + 3,
+      ),
+      ByteCode::NotEqualConst => (
+        AlignedByteCode::NotEqualConst(decode_u16(&store[offset + 1..offset + 3])),
+        offset + 3,
+      ),
       ByteCode::Greater => (AlignedByteCode::Greater, offset + 1),
       ByteCode::GreaterEqual => (AlignedByteCode::GreaterEqual, offset + 1),
       ByteCode::Less => (AlignedByteCode::Less, offset + 1),
       ByteCode::LessEqual => (AlignedByteCode::LessEqual, offset + 1),
+      ByteCode::AddConstToLocal => (
+        AlignedByteCode::AddConstToLocal((
+          decode_u16(&store[offset + 1..offset + 3]),
+          store[offset + 3],
+        )),
+        offset + 4,
+      ),
+      ByteCode::JumpIfNotLess => (
+        AlignedByteCode::JumpIfNotLess(decode_u16(&store[offset + 1..offset + 3])),
+        offset + 3,
+      ),
     }
   }
 
@@ -349,9 +420,14 @@ impl AlignedByteCode {
       AlignedByteCode::False => 1,
       AlignedByteCode::List(cnt) => -(*cnt as i32) + 1,
       AlignedByteCode::Map(cnt) => -(*cnt as i32 * 2) + 1,
+      AlignedByteCode::Tuple(cnt) => -(*cnt as i32) + 1,
+      AlignedByteCode::Unpack(cnt) => *cnt as i32 - 1,
       AlignedByteCode::Interpolate(cnt) => -(*cnt as i32) + 1,
       AlignedByteCode::IterNext(_) => 0,
       AlignedByteCode::IterCurrent(_) => 0,
+      AlignedByteCode::Await => 0,
+      AlignedByteCode::Raise => -1,
+      AlignedByteCode::RaiseFrom => -2,
       AlignedByteCode::Drop => -1,
       AlignedByteCode::DropN(cnt) => -(*cnt as i32),
       AlignedByteCode::Dup => 1,
@@ -373,22 +449,29 @@ impl AlignedByteCode {
       AlignedByteCode::Call(args) => -(*args as i32),
       AlignedByteCode::Invoke((_, args)) => -(*args as i32),
       AlignedByteCode::SuperInvoke((_, args)) => -(*args as i32 + 1),
+      AlignedByteCode::InvokeSealed((_, args)) => -(*args as i32),
       AlignedByteCode::Closure(_) => 1,
       AlignedByteCode::Method(_) => -1,
       AlignedByteCode::Field(_) => 0,
       AlignedByteCode::StaticMethod(_) => -1,
       AlignedByteCode::Class(_) => 1,
       AlignedByteCode::Inherit => 0,
+      AlignedByteCode::Extend => 0,
+      AlignedByteCode::Seal => 0,
       AlignedByteCode::GetSuper(_) => -1,
       AlignedByteCode::CloseUpvalue => -1,
       AlignedByteCode::UpvalueIndex(_) => 0,
       AlignedByteCode::Slot(_) => 0,
       AlignedByteCode::Equal => -1,
       AlignedByteCode::NotEqual => -1,
+      AlignedByteCode::EqualConst(_) => 0,
+      AlignedByteCode::NotEqualConst(_) => 0,
       AlignedByteCode::Greater => -1,
       AlignedByteCode::GreaterEqual => -1,
       AlignedByteCode::Less => -1,
       AlignedByteCode::LessEqual => -1,
+      AlignedByteCode::AddConstToLocal(_) => 0,
+      AlignedByteCode::JumpIfNotLess(_) => -2,
     }
   }
 }
@@ -411,11 +494,18 @@ impl Encode for AlignedByteCode {
       Self::False => op(code, ByteCode::False),
       Self::List(slot) => op_short(code, ByteCode::List, slot),
       Self::Map(slot) => op_short(code, ByteCode::Map, slot),
+      Self::Tuple(slot) => op_short(code, ByteCode::Tuple, slot),
+      Self::Unpack(slot) => op_short(code, ByteCode::Unpack, slot),
       Self::Interpolate(slot) => op_short(code, ByteCode::Interpolate, slot),
       Self::IterNext(slot) => op_short(code, ByteCode::IterNext, slot),
       Self::IterCurrent(slot) => op_short(code, ByteCode::IterCurrent, slot),
+      Self::Await => op(code, ByteCode::Await),
+      Self::Raise => op(code, ByteCode::Raise),
+      Self::RaiseFrom => op(code, ByteCode::RaiseFrom),
       Self::Equal => op(code, ByteCode::Equal),
       Self::NotEqual => op(code, ByteCode::NotEqual),
+      Self::EqualConst(slot) => op_short(code, ByteCode::EqualConst, slot),
+      Self::NotEqualConst(slot) => op_short(code, ByteCode::NotEqualConst, slot),
       Self::Greater => op(code, ByteCode::Greater),
       Self::GreaterEqual => op(code, ByteCode::GreaterEqual),
       Self::Less => op(code, ByteCode::Less),
@@ -452,25 +542,36 @@ impl Encode for AlignedByteCode {
         push_op_u16_u8_tuple(code, ByteCode::SuperInvoke, slot1, slot2);
         4
       }
+      Self::InvokeSealed((slot1, slot2)) => {
+        push_op_u16_u8_tuple(code, ByteCode::InvokeSealed, slot1, slot2);
+        4
+      }
       Self::Closure(slot) => op_short(code, ByteCode::Closure, slot),
       Self::Method(slot) => op_short(code, ByteCode::Method, slot),
       Self::Field(slot) => op_short(code, ByteCode::Field, slot),
       Self::StaticMethod(slot) => op_short(code, ByteCode::StaticMethod, slot),
       Self::Class(slot) => op_short(code, ByteCode::Class, slot),
       Self::Inherit => op(code, ByteCode::Inherit),
+      Self::Extend => op(code, ByteCode::Extend),
+      Self::Seal => op(code, ByteCode::Seal),
       Self::GetSuper(slot) => op_short(code, ByteCode::GetSuper, slot),
       Self::CloseUpvalue => op(code, ByteCode::CloseUpvalue),
       Self::UpvalueIndex(index) => {
         let encoded: u16 = unsafe { mem::transmute(index) };
         let bytes = encoded.to_ne_bytes();
         code.extend_from_slice(&bytes);
-        3
+        2
       }
       Self::Slot(slot) => {
         let bytes = slot.to_ne_bytes();
         code.extend_from_slice(&bytes);
-        5
+        4
       }
+      Self::AddConstToLocal((constant, local)) => {
+        push_op_u16_u8_tuple(code, ByteCode::AddConstToLocal, constant, local);
+        4
+      }
+      Self::JumpIfNotLess(slot) => op_short(code, ByteCode::JumpIfNotLess, slot),
     }
   }
 }
@@ -490,170 +591,306 @@ fn op_short(code: &mut Vec<u8>, byte_code: ByteCode, short: u16) -> u32 {
   3
 }
 
-/// Space Lox virtual machine byte codes
+/// The encoded layout of a single operand following an op code
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Operand {
+  /// A single byte operand
+  U8,
+
+  /// A two byte operand
+  U16,
+
+  /// A four byte inline cache slot
+  Slot,
+
+  /// A two byte upvalue index for each upvalue of the referenced function
+  UpvalueIndices,
+}
+
+impl Operand {
+  /// The number of bytes this operand occupies, none if the width
+  /// depends on the referenced function
+  pub fn width(&self) -> Option<usize> {
+    match self {
+      Operand::U8 => Some(1),
+      Operand::U16 => Some(2),
+      Operand::Slot => Some(4),
+      Operand::UpvalueIndices => None,
+    }
+  }
+
+  /// The name of this operand as it appears in the op code table
+  pub fn name(&self) -> &'static str {
+    match self {
+      Operand::U8 => "u8",
+      Operand::U16 => "u16",
+      Operand::Slot => "slot",
+      Operand::UpvalueIndices => "upvalues",
+    }
+  }
+}
+
+/// What effect an op code has on the stack
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StackEffect {
+  /// The op code always moves the stack by this amount
+  Fixed(i32),
+
+  /// The op code moves the stack by an amount depending on its operands
+  Variable(&'static str),
+}
+
+impl fmt::Display for StackEffect {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      StackEffect::Fixed(effect) => write!(f, "{:+}", effect),
+      StackEffect::Variable(effect) => write!(f, "{}", effect),
+    }
+  }
+}
+
+/// Documentation for a single op code
 #[derive(Debug, PartialEq, Clone, Copy)]
-pub enum ByteCode {
+pub struct OpCodeInfo {
+  /// The op code being described
+  pub byte_code: ByteCode,
+
+  /// The name of the op code
+  pub name: &'static str,
+
+  /// The operands that follow the op code in order
+  pub operands: &'static [Operand],
+
+  /// The effect this op code has on the stack
+  pub stack_effect: StackEffect,
+
+  /// A short description of the op code
+  pub description: &'static str,
+}
+
+/// Define the `ByteCode` enum along side the `OP_CODES` table
+/// so the two are always generated from the same source
+macro_rules! define_byte_codes {
+  ( $( $(#[doc = $doc:literal])* $name:ident [$($operand:ident),*] => $effect:ident($($arg:expr)?), )* ) => {
+    /// Space Lox virtual machine byte codes
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub enum ByteCode {
+      $( $(#[doc = $doc])* $name, )*
+    }
+
+    /// A description of each op code indexed by its byte value
+    pub const OP_CODES: &[OpCodeInfo] = &[
+      $(
+        OpCodeInfo {
+          byte_code: ByteCode::$name,
+          name: stringify!($name),
+          operands: &[$(Operand::$operand),*],
+          stack_effect: StackEffect::$effect($($arg)?),
+          description: concat!($($doc),*),
+        },
+      )*
+    ];
+  };
+}
+
+define_byte_codes! {
   /// Return from script or function
-  Return,
+  Return [] => Fixed(0),
 
   /// Negate a value
-  Negate,
+  Negate [] => Fixed(0),
 
   /// Add the top two operands on the stack
-  Add,
+  Add [] => Fixed(-1),
 
   /// Subtract the top two operands on the stack
-  Subtract,
+  Subtract [] => Fixed(-1),
 
   /// Multiply the top two operands on the stack
-  Multiply,
+  Multiply [] => Fixed(-1),
 
   /// Divide the top two operands on the stack
-  Divide,
+  Divide [] => Fixed(-1),
 
   /// Apply Not operator to top stack element
-  Not,
+  Not [] => Fixed(0),
 
   /// Perform a logical and operator
-  And,
+  And [U16] => Fixed(-1),
 
   /// Perform a logical or operator
-  Or,
+  Or [U16] => Fixed(-1),
 
   /// Retrieve a constant from the constants table
-  Constant,
+  Constant [U8] => Fixed(1),
 
   /// Retrieve a constant of higher number from the constants table
-  ConstantLong,
+  ConstantLong [U16] => Fixed(1),
 
   /// Nil literal
-  Nil,
+  Nil [] => Fixed(1),
 
   /// True Literal
-  True,
+  True [] => Fixed(1),
 
   /// False ByteCode
-  False,
+  False [] => Fixed(1),
 
   /// Initialize List
-  List,
+  List [U16] => Variable("1 - n"),
 
   /// Initialize map
-  Map,
+  Map [U16] => Variable("1 - 2n"),
+
+  /// Initialize tuple
+  Tuple [U16] => Variable("1 - n"),
+
+  /// Unpack a tuple onto the stack
+  Unpack [U16] => Variable("n - 1"),
 
   /// Combine string interpolation
-  Interpolate,
+  Interpolate [U16] => Variable("1 - n"),
 
   /// Get the next element from an iterator
-  IterNext,
+  IterNext [U16] => Fixed(0),
 
   /// Get the current value from an iterator
-  IterCurrent,
+  IterCurrent [U16] => Fixed(0),
+
+  /// Await the completion of a fiber
+  Await [] => Fixed(0),
+
+  /// Raise the error on top of the stack again
+  Raise [] => Fixed(-1),
+
+  /// Raise the error below the top of the stack, caused by the top
+  RaiseFrom [] => Fixed(-2),
 
   /// Drop a value
-  Drop,
+  Drop [] => Fixed(-1),
 
   /// Drop n values
-  DropN,
+  DropN [U8] => Variable("-n"),
 
   /// Duplicate top of the stack
-  Dup,
+  Dup [] => Fixed(1),
 
   /// Import all symbols
-  Import,
+  Import [U16] => Fixed(1),
 
   /// Import a single symbol
-  ImportSymbol,
+  ImportSymbol [U16, U16] => Fixed(1),
 
   /// Export a symbol from the current module
-  Export,
+  Export [U16] => Fixed(0),
 
   /// Define a global in the globals table at a index
-  DefineGlobal,
+  DefineGlobal [U16] => Fixed(-1),
 
   /// Retrieve a global at the given index
-  GetGlobal,
+  GetGlobal [U16] => Fixed(1),
 
   /// Set a global at the given index
-  SetGlobal,
+  SetGlobal [U16] => Fixed(0),
 
   /// Retrieve an upvalue at the given index
-  GetUpvalue,
+  GetUpvalue [U8] => Fixed(1),
 
   /// Set an upvalue at the given index
-  SetUpvalue,
+  SetUpvalue [U8] => Fixed(0),
 
   /// Get a local at the given index
-  GetLocal,
+  GetLocal [U8] => Fixed(1),
 
   /// Set a local at the given index
-  SetLocal,
+  SetLocal [U8] => Fixed(0),
 
   /// Get a property off a class instance
-  GetProperty,
+  GetProperty [U16, Slot] => Fixed(0),
 
   /// Set a property on a class instance
-  SetProperty,
+  SetProperty [U16, Slot] => Fixed(-1),
 
   /// Jump to end of if block if false
-  JumpIfFalse,
+  JumpIfFalse [U16] => Fixed(-1),
 
   /// Jump conditionally to the ip
-  Jump,
+  Jump [U16] => Fixed(0),
 
   /// Jump to loop beginning
-  Loop,
+  Loop [U16] => Fixed(0),
 
   /// Call a function
-  Call,
+  Call [U8] => Variable("-n"),
 
   /// Invoke a method
-  Invoke,
+  Invoke [U16, U8, Slot] => Variable("-n"),
 
   /// Invoke a method on a super class
-  SuperInvoke,
+  SuperInvoke [U16, U8, Slot] => Variable("-n - 1"),
+
+  /// Invoke a method of a sealed class by its vtable index
+  InvokeSealed [U16, U8, Slot] => Variable("-n"),
 
   /// Create a closure
-  Closure,
+  Closure [U16, UpvalueIndices] => Fixed(1),
 
   /// Create a method
-  Method,
+  Method [U16] => Fixed(-1),
 
   /// Create a field
-  Field,
+  Field [U16] => Fixed(0),
 
   /// Create a static method
-  StaticMethod,
+  StaticMethod [U16] => Fixed(-1),
 
   /// Create a class
-  Class,
+  Class [U16] => Fixed(1),
 
   /// Inherit from another class
-  Inherit,
+  Inherit [] => Fixed(0),
+
+  /// Open an existing class to add methods
+  Extend [] => Fixed(0),
+
+  /// Seal a class against further extension
+  Seal [] => Fixed(0),
 
   /// Access this classes super
-  GetSuper,
+  GetSuper [U16] => Fixed(-1),
 
   /// Close an upvalue by moving it to the stack
-  CloseUpvalue,
+  CloseUpvalue [] => Fixed(-1),
 
   /// Apply equality between the top two operands on the stack
-  Equal,
+  Equal [] => Fixed(-1),
 
   /// Check if the top two operands on the stack are not equal
-  NotEqual,
+  NotEqual [] => Fixed(-1),
+
+  /// Check if the top of the stack is equal to a string constant
+  EqualConst [U16] => Fixed(0),
+
+  /// Check if the top of the stack is not equal to a string constant
+  NotEqualConst [U16] => Fixed(0),
 
   /// Apply greater between the top two operands on the stack
-  Greater,
+  Greater [] => Fixed(-1),
 
   /// Check if the 2nd from the top operand is >= the top
-  GreaterEqual,
+  GreaterEqual [] => Fixed(-1),
 
   /// Less greater between the top two operands on the stack
-  Less,
+  Less [] => Fixed(-1),
 
   /// Check if the 2nd from the top operand is <= the top
-  LessEqual,
+  LessEqual [] => Fixed(-1),
+
+  /// Add a number constant to a local in place
+  AddConstToLocal [U16, U8] => Fixed(0),
+
+  /// Jump if the 2nd from the top operand is not < the top
+  JumpIfNotLess [U16] => Fixed(-2),
 }
 
 impl ByteCode {
@@ -661,6 +898,11 @@ impl ByteCode {
   fn to_byte(self) -> u8 {
     unsafe { mem::transmute(self) }
   }
+
+  /// Retrieve the documentation for this bytecode
+  pub fn info(self) -> &'static OpCodeInfo {
+    &OP_CODES[self.to_byte() as usize]
+  }
 }
 
 impl From<u8> for ByteCode {
@@ -671,6 +913,37 @@ impl From<u8> for ByteCode {
   }
 }
 
+/// Write a markdown table describing every op code, its operands and its stack effect
+pub fn dump_op_codes(out: &mut dyn Write) -> io::Result<()> {
+  writeln!(out, "| Byte | Op Code | Operands | Stack Effect | Description |")?;
+  writeln!(out, "| ---- | ------- | -------- | ------------ | ----------- |")?;
+
+  for (byte, info) in OP_CODES.iter().enumerate() {
+    let operands = if info.operands.is_empty() {
+      String::from("-")
+    } else {
+      info
+        .operands
+        .iter()
+        .map(|operand| operand.name())
+        .collect::<Vec<&str>>()
+        .join(", ")
+    };
+
+    writeln!(
+      out,
+      "| {} | {} | {} | {} | {} |",
+      byte,
+      info.name,
+      operands,
+      info.stack_effect,
+      info.description.trim()
+    )?;
+  }
+
+  Ok(())
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum UpvalueIndex {
   /// The upvalue is actually local
@@ -680,13 +953,11 @@ pub enum UpvalueIndex {
   Upvalue(u8),
 }
 
-#[cfg(any(test, feature = "debug"))]
 pub fn decode_u32(buffer: &[u8]) -> u32 {
   let arr: [u8; 4] = buffer.try_into().expect("slice of incorrect length.");
   u32::from_ne_bytes(arr)
 }
 
-#[cfg(any(test, feature = "debug"))]
 pub fn decode_u16(buffer: &[u8]) -> u16 {
   let arr: [u8; 2] = buffer.try_into().expect("slice of incorrect length.");
   u16::from_ne_bytes(arr)
@@ -726,9 +997,8 @@ fn push_op_u16_tuple(code: &mut Vec<u8>, byte: ByteCode, param1: u16, param2: u1
 mod test {
   use super::*;
 
-  #[test]
-  fn encode_decode() {
-    let code: Vec<(usize, AlignedByteCode)> = vec![
+  fn sample_byte_code() -> Vec<(usize, AlignedByteCode)> {
+    vec![
       (1, AlignedByteCode::Return),
       (1, AlignedByteCode::Negate),
       (1, AlignedByteCode::Add),
@@ -746,9 +1016,14 @@ mod test {
       (1, AlignedByteCode::False),
       (3, AlignedByteCode::List(54782)),
       (3, AlignedByteCode::Map(1923)),
+      (3, AlignedByteCode::Tuple(2817)),
+      (3, AlignedByteCode::Unpack(3)),
       (3, AlignedByteCode::Interpolate(3389)),
       (3, AlignedByteCode::IterNext(81)),
       (3, AlignedByteCode::IterCurrent(49882)),
+      (1, AlignedByteCode::Await),
+      (1, AlignedByteCode::Raise),
+      (1, AlignedByteCode::RaiseFrom),
       (1, AlignedByteCode::Drop),
       (3, AlignedByteCode::DefineGlobal(42)),
       (3, AlignedByteCode::GetGlobal(14119)),
@@ -765,20 +1040,37 @@ mod test {
       (2, AlignedByteCode::Call(77)),
       (4, AlignedByteCode::Invoke((5591, 19))),
       (4, AlignedByteCode::SuperInvoke((2105, 15))),
+      (4, AlignedByteCode::InvokeSealed((3117, 4))),
       (3, AlignedByteCode::Closure(3638)),
       (3, AlignedByteCode::Method(188)),
       (3, AlignedByteCode::Field(6634)),
       (3, AlignedByteCode::StaticMethod(4912)),
       (3, AlignedByteCode::Class(64136)),
       (1, AlignedByteCode::Inherit),
+      (1, AlignedByteCode::Extend),
+      (1, AlignedByteCode::Seal),
       (3, AlignedByteCode::GetSuper(24)),
       (1, AlignedByteCode::CloseUpvalue),
       (1, AlignedByteCode::Equal),
       (1, AlignedByteCode::NotEqual),
+      (3, AlignedByteCode::EqualConst(4021)),
+      (3, AlignedByteCode::NotEqualConst(17)),
       (1, AlignedByteCode::Greater),
       (1, AlignedByteCode::GreaterEqual),
+      (1, AlignedByteCode::Less),
       (1, AlignedByteCode::LessEqual),
-    ];
+      (4, AlignedByteCode::AddConstToLocal((4913, 3))),
+      (3, AlignedByteCode::JumpIfNotLess(611)),
+      (3, AlignedByteCode::And(7712)),
+      (3, AlignedByteCode::Or(213)),
+      (2, AlignedByteCode::DropN(8)),
+      (1, AlignedByteCode::Dup),
+    ]
+  }
+
+  #[test]
+  fn encode_decode() {
+    let code = sample_byte_code();
 
     let mut buffer: Vec<u8> = Vec::new();
     for (size1, byte_code1) in &code {
@@ -798,4 +1090,77 @@ mod test {
       }
     }
   }
+
+  #[test]
+  fn encode_trailing_size() {
+    let mut buffer: Vec<u8> = Vec::new();
+
+    let written = AlignedByteCode::Slot(7).encode(&mut buffer);
+    assert_eq!(written as usize, buffer.len());
+
+    buffer.clear();
+    let written = AlignedByteCode::UpvalueIndex(UpvalueIndex::Local(3)).encode(&mut buffer);
+    assert_eq!(written as usize, buffer.len());
+  }
+
+  #[test]
+  fn op_codes_indexed_by_byte() {
+    for (byte, info) in OP_CODES.iter().enumerate() {
+      assert_eq!(info.byte_code.to_byte() as usize, byte);
+      assert_eq!(ByteCode::from(byte as u8), info.byte_code);
+      assert_eq!(info.byte_code.info(), info);
+      assert_eq!(format!("{:?}", info.byte_code), info.name);
+      assert!(!info.description.trim().is_empty());
+    }
+  }
+
+  #[test]
+  fn op_codes_match_encoding() {
+    let mut covered = vec![false; OP_CODES.len()];
+    let mut buffer: Vec<u8> = Vec::new();
+
+    for (_, byte_code) in sample_byte_code() {
+      byte_code.encode(&mut buffer);
+      let info = ByteCode::from(buffer[0]).info();
+      covered[buffer[0] as usize] = true;
+
+      // cache slots and upvalue indices are written as separate instructions
+      let expected: usize = 1 + info
+        .operands
+        .iter()
+        .filter(|operand| !matches!(operand, Operand::Slot | Operand::UpvalueIndices))
+        .map(|operand| operand.width().expect("Expected fixed width operand"))
+        .sum::<usize>();
+
+      assert_eq!(buffer.len(), expected, "for op code {}", info.name);
+
+      if let StackEffect::Fixed(effect) = info.stack_effect {
+        assert_eq!(byte_code.stack_effect(), effect, "for op code {}", info.name);
+      }
+
+      buffer.clear();
+    }
+
+    for (byte, covered) in covered.iter().enumerate() {
+      assert!(covered, "op code {} has no sample", OP_CODES[byte].name);
+    }
+  }
+
+  #[test]
+  fn dump_op_codes_table() {
+    let mut buffer: Vec<u8> = Vec::new();
+    dump_op_codes(&mut buffer).expect("Unable to write table");
+
+    let table = String::from_utf8(buffer).expect("Table was not utf8");
+    let lines: Vec<&str> = table.lines().collect();
+
+    assert_eq!(lines.len(), OP_CODES.len() + 2);
+    assert_eq!(
+      lines[2],
+      "| 0 | Return | - | +0 | Return from script or function |"
+    );
+    assert!(lines
+      .iter()
+      .any(|line| line.contains("| Invoke | u16, u8, slot | -n |")));
+  }
 }
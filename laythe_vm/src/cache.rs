@@ -48,6 +48,16 @@ impl InlineCache {
     }
   }
 
+  /// The number of property cache slots
+  pub fn property_slots(&self) -> usize {
+    self.property.len()
+  }
+
+  /// The number of invoke cache slots
+  pub fn invoke_slots(&self) -> usize {
+    self.invoke.len()
+  }
+
   /// Attempt to retrieve the property cache at a given slot
   /// for the provided class
   pub fn get_property_cache(&self, inline_slot: usize, class: GcObj<Class>) -> Option<usize> {
@@ -195,6 +205,7 @@ mod test {
   mod inline_cache {
     use crate::{byte_code::AlignedByteCode, cache::InlineCache};
     use laythe_core::{
+      chunk::SourceSpan,
       memory::{Allocator, NO_GC},
       module::Module,
       object::{Class, FunBuilder},
@@ -241,7 +252,7 @@ mod test {
       let module = alloc.manage(Module::new(class, PathBuf::new(), 0), &NO_GC);
 
       let mut fun = FunBuilder::new(fun_name, module);
-      fun.write_instruction(AlignedByteCode::Nil, 0);
+      fun.write_instruction(AlignedByteCode::Nil, SourceSpan::default());
       let fun = val!(alloc.manage_obj(fun.build(), &NO_GC));
 
       assert_eq!(inline_cache.get_invoke_cache(0, class), None);
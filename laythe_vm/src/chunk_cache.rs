@@ -0,0 +1,678 @@
+use crate::byte_code::OP_CODES;
+use fnv::FnvHasher;
+use laythe_core::{
+  chunk::{Chunk, Line, SourceSpan},
+  hooks::GcHooks,
+  managed::{Gc, GcObj, GcStr},
+  module::Module,
+  object::{Fun, FunBuilder, List, ObjectKind, TryBlock},
+  signature::Arity,
+  val,
+  value::{Value, VALUE_NIL},
+};
+use laythe_env::fs::Fs;
+use std::{
+  convert::TryInto,
+  hash::Hasher,
+  io,
+  path::{Path, PathBuf},
+};
+
+/// The directory compiled chunks are cached in relative to the script
+pub const CACHE_DIR: &str = ".laythe-cache";
+
+/// Leading bytes of every cache file
+const MAGIC: &[u8; 4] = b"LYCC";
+
+/// The version of the cache file layout
+const FORMAT_VERSION: u32 = 2;
+
+/// A hash of the sources the compiler was built from
+const BUILD_HASH: &str = env!("LAYTHE_BUILD_HASH");
+
+/// Tags identifying each kind of constant in a cached chunk
+const TAG_NIL: u8 = 0;
+const TAG_FALSE: u8 = 1;
+const TAG_TRUE: u8 = 2;
+const TAG_NUMBER: u8 = 3;
+const TAG_STRING: u8 = 4;
+const TAG_FUN: u8 = 5;
+const TAG_LIST: u8 = 6;
+
+/// Tags identifying each kind of arity in a cached function
+const ARITY_FIXED: u8 = 0;
+const ARITY_VARIADIC: u8 = 1;
+const ARITY_DEFAULT: u8 = 2;
+
+/// A script loaded from the chunk cache
+pub struct CachedScript {
+  /// The top level script function
+  pub fun: GcObj<Fun>,
+
+  /// The number of property inline cache slots the script uses
+  pub property_count: usize,
+
+  /// The number of invoke inline cache slots the script uses
+  pub invoke_count: usize,
+}
+
+/// An on disk cache of compiled scripts. Entries are keyed by a hash of the
+/// source content, the compiler version, the compiler build and the layout
+/// of the op code table so any change to these invalidates previously
+/// cached chunks
+pub struct ChunkCache {
+  /// The directory cache entries are stored in
+  dir: PathBuf,
+
+  /// The compiler version chunks are cached for
+  version: &'static str,
+
+  /// The compiler build chunks are cached for
+  build: &'static str,
+}
+
+impl ChunkCache {
+  /// Create a new chunk cache for scripts located in the provided directory
+  pub fn new(root_dir: &Path, version: &'static str) -> Self {
+    Self {
+      dir: root_dir.join(CACHE_DIR),
+      version,
+      build: BUILD_HASH,
+    }
+  }
+
+  /// Load a previously compiled script for this source. Any missing,
+  /// stale or corrupt entry is treated as a cache miss
+  pub fn load(
+    &self,
+    fs: &Fs,
+    hooks: &GcHooks,
+    module: Gc<Module>,
+    source: &str,
+  ) -> Option<CachedScript> {
+    let key = self.key(source);
+    let bytes = fs.read(&self.path(key)).ok()?;
+    decode(hooks, module, key, &bytes)
+  }
+
+  /// Store a compiled script for this source. Scripts with
+  /// constants that cannot be cached are skipped
+  pub fn store(
+    &self,
+    fs: &Fs,
+    source: &str,
+    fun: &Fun,
+    property_count: usize,
+    invoke_count: usize,
+  ) -> io::Result<()> {
+    let key = self.key(source);
+    let buffer = match encode(key, fun, property_count, invoke_count) {
+      Some(buffer) => buffer,
+      None => return Ok(()),
+    };
+
+    fs.create_dir_all(&self.dir)?;
+    fs.write(&self.path(key), &buffer)
+  }
+
+  /// Load a script precompiled by this compiler version. Unlike cache
+  /// entries precompiled scripts are not tied to their source
+  pub fn load_precompiled(
+    &self,
+    hooks: &GcHooks,
+    module: Gc<Module>,
+    bytes: &[u8],
+  ) -> Option<CachedScript> {
+    decode(hooks, module, self.layout_key(), bytes)
+  }
+
+  /// Encode a compiled script so it can be loaded without its source,
+  /// returning none if it has constants that cannot be encoded
+  pub fn precompile(
+    &self,
+    fun: &Fun,
+    property_count: usize,
+    invoke_count: usize,
+  ) -> Option<Vec<u8>> {
+    encode(self.layout_key(), fun, property_count, invoke_count)
+  }
+
+  /// A key identifying the compiler version, build and op code layout
+  /// that encoded functions are tied to
+  pub(crate) fn layout_key(&self) -> u64 {
+    self.layout_hasher().finish()
+  }
+
+  /// A hasher seeded with the compiler version, build and op code layout
+  fn layout_hasher(&self) -> FnvHasher {
+    let mut hasher = FnvHasher::default();
+    hasher.write(self.version.as_bytes());
+    hasher.write(self.build.as_bytes());
+    hasher.write_u32(FORMAT_VERSION);
+
+    for info in OP_CODES {
+      hasher.write(info.name.as_bytes());
+      for operand in info.operands {
+        hasher.write(operand.name().as_bytes());
+      }
+    }
+
+    hasher
+  }
+
+  /// The cache key for the provided source
+  fn key(&self, source: &str) -> u64 {
+    let mut hasher = self.layout_hasher();
+    hasher.write(source.as_bytes());
+    hasher.finish()
+  }
+
+  /// The path of the cache entry for the provided key
+  fn path(&self, key: u64) -> PathBuf {
+    self.dir.join(format!("{:016x}.lyc", key))
+  }
+}
+
+/// Decode a script from an entry written with the provided key
+fn decode(hooks: &GcHooks, module: Gc<Module>, key: u64, bytes: &[u8]) -> Option<CachedScript> {
+  let mut reader = Reader::new(bytes);
+  if reader.bytes(MAGIC.len())? != MAGIC || reader.u64()? != key {
+    return None;
+  }
+
+  let checksum = reader.u64()?;
+  if checksum != hash_bytes(reader.remaining()) {
+    return None;
+  }
+
+  let property_count = reader.u32()? as usize;
+  let invoke_count = reader.u32()? as usize;
+
+  let mut decoder = Decoder {
+    hooks,
+    module,
+    reader,
+    roots: 0,
+  };
+
+  let fun = decoder.fun();
+  hooks.pop_roots(decoder.roots);
+
+  Some(CachedScript {
+    fun: fun?,
+    property_count,
+    invoke_count,
+  })
+}
+
+/// Encode a script into an entry with the provided key, returning
+/// none if a constant cannot be cached
+fn encode(key: u64, fun: &Fun, property_count: usize, invoke_count: usize) -> Option<Vec<u8>> {
+  let mut payload = vec![];
+  write_u32(&mut payload, property_count as u32);
+  write_u32(&mut payload, invoke_count as u32);
+  encode_fun(&mut payload, fun)?;
+
+  let mut buffer = Vec::with_capacity(payload.len() + 20);
+  buffer.extend_from_slice(MAGIC);
+  write_u64(&mut buffer, key);
+  write_u64(&mut buffer, hash_bytes(&payload));
+  buffer.extend_from_slice(&payload);
+  Some(buffer)
+}
+
+/// Hash a byte slice to detect partially written entries
+pub(crate) fn hash_bytes(bytes: &[u8]) -> u64 {
+  let mut hasher = FnvHasher::default();
+  hasher.write(bytes);
+  hasher.finish()
+}
+
+pub(crate) fn write_u8(buffer: &mut Vec<u8>, value: u8) {
+  buffer.push(value);
+}
+
+pub(crate) fn write_u16(buffer: &mut Vec<u8>, value: u16) {
+  buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn write_u32(buffer: &mut Vec<u8>, value: u32) {
+  buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn write_u64(buffer: &mut Vec<u8>, value: u64) {
+  buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn write_str(buffer: &mut Vec<u8>, value: &str) {
+  write_u32(buffer, value.len() as u32);
+  buffer.extend_from_slice(value.as_bytes());
+}
+
+/// Encode a function and all of its constants, returning
+/// none if a constant cannot be cached
+fn encode_fun(buffer: &mut Vec<u8>, fun: &Fun) -> Option<()> {
+  encode_fun_layout(buffer, fun);
+
+  let constants = fun.chunk().constants();
+  write_u32(buffer, constants.len() as u32);
+  for constant in constants {
+    encode_value(buffer, *constant)?;
+  }
+
+  Some(())
+}
+
+/// Encode a function without its constants
+pub(crate) fn encode_fun_layout(buffer: &mut Vec<u8>, fun: &Fun) {
+  write_str(buffer, &fun.name());
+
+  match *fun.arity() {
+    Arity::Fixed(arity) => {
+      write_u8(buffer, ARITY_FIXED);
+      write_u8(buffer, arity);
+    },
+    Arity::Variadic(arity) => {
+      write_u8(buffer, ARITY_VARIADIC);
+      write_u8(buffer, arity);
+    },
+    Arity::Default(required, total) => {
+      write_u8(buffer, ARITY_DEFAULT);
+      write_u8(buffer, required);
+      write_u8(buffer, total);
+    },
+  }
+
+  write_u8(buffer, fun.upvalue_count() as u8);
+  write_u32(buffer, fun.max_slots() as u32);
+  write_u8(buffer, fun.is_async() as u8);
+
+  write_u32(buffer, fun.try_blocks().len() as u32);
+  for try_block in fun.try_blocks() {
+    write_u16(buffer, try_block.start());
+    write_u16(buffer, try_block.end());
+    write_u16(buffer, try_block.slots());
+  }
+
+  let chunk = fun.chunk();
+  write_u32(buffer, chunk.instructions().len() as u32);
+  buffer.extend_from_slice(chunk.instructions());
+
+  write_u32(buffer, chunk.lines().len() as u32);
+  for line in chunk.lines() {
+    write_u32(buffer, line.span.line);
+    write_u32(buffer, line.span.column);
+    write_u32(buffer, line.span.length);
+    write_u32(buffer, line.offset);
+  }
+}
+
+/// Encode a single constant
+fn encode_value(buffer: &mut Vec<u8>, value: Value) -> Option<()> {
+  if value.is_nil() {
+    write_u8(buffer, TAG_NIL);
+  } else if value.is_bool() {
+    write_u8(buffer, if value.to_bool() { TAG_TRUE } else { TAG_FALSE });
+  } else if value.is_num() {
+    write_u8(buffer, TAG_NUMBER);
+    write_u64(buffer, value.to_num().to_bits());
+  } else if value.is_obj_kind(ObjectKind::String) {
+    write_u8(buffer, TAG_STRING);
+    write_str(buffer, &value.to_obj().to_str());
+  } else if value.is_obj_kind(ObjectKind::Fun) {
+    write_u8(buffer, TAG_FUN);
+    encode_fun(buffer, &value.to_obj().to_fun())?;
+  } else if value.is_obj_kind(ObjectKind::List) {
+    let list = value.to_obj().to_list();
+    write_u8(buffer, TAG_LIST);
+    write_u32(buffer, list.len() as u32);
+    for item in list.iter() {
+      encode_value(buffer, *item)?;
+    }
+  } else {
+    return None;
+  }
+
+  Some(())
+}
+
+/// A cursor over the bytes of a cache entry
+pub(crate) struct Reader<'a> {
+  buffer: &'a [u8],
+  position: usize,
+}
+
+impl<'a> Reader<'a> {
+  pub(crate) fn new(buffer: &'a [u8]) -> Self {
+    Self {
+      buffer,
+      position: 0,
+    }
+  }
+
+  pub(crate) fn remaining(&self) -> &'a [u8] {
+    &self.buffer[self.position..]
+  }
+
+  pub(crate) fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+    let end = self.position.checked_add(len)?;
+    let bytes = self.buffer.get(self.position..end)?;
+    self.position = end;
+    Some(bytes)
+  }
+
+  pub(crate) fn u8(&mut self) -> Option<u8> {
+    self.bytes(1).map(|bytes| bytes[0])
+  }
+
+  pub(crate) fn u16(&mut self) -> Option<u16> {
+    self
+      .bytes(2)
+      .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+  }
+
+  pub(crate) fn u32(&mut self) -> Option<u32> {
+    self
+      .bytes(4)
+      .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+  }
+
+  pub(crate) fn u64(&mut self) -> Option<u64> {
+    self
+      .bytes(8)
+      .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+  }
+
+  pub(crate) fn str(&mut self) -> Option<&'a str> {
+    let len = self.u32()? as usize;
+    std::str::from_utf8(self.bytes(len)?).ok()
+  }
+}
+
+/// A function read from an entry without its constants
+pub(crate) struct FunLayout<'a> {
+  pub(crate) name: &'a str,
+  pub(crate) arity: Arity,
+  pub(crate) upvalue_count: u8,
+  pub(crate) max_slots: u32,
+  pub(crate) is_async: bool,
+  pub(crate) try_blocks: Vec<TryBlock>,
+  pub(crate) instructions: &'a [u8],
+  pub(crate) lines: Vec<Line>,
+}
+
+impl<'a> FunLayout<'a> {
+  /// Read a function written by `encode_fun_layout`
+  pub(crate) fn read(reader: &mut Reader<'a>) -> Option<Self> {
+    let name = reader.str()?;
+
+    let arity = match reader.u8()? {
+      ARITY_FIXED => Arity::Fixed(reader.u8()?),
+      ARITY_VARIADIC => Arity::Variadic(reader.u8()?),
+      ARITY_DEFAULT => Arity::Default(reader.u8()?, reader.u8()?),
+      _ => return None,
+    };
+
+    let upvalue_count = reader.u8()?;
+    let max_slots = reader.u32()?;
+    let is_async = reader.u8()? != 0;
+
+    let try_count = reader.u32()? as usize;
+    let mut try_blocks = Vec::with_capacity(try_count.min(reader.remaining().len()));
+    for _ in 0..try_count {
+      try_blocks.push(TryBlock::new(reader.u16()?, reader.u16()?, reader.u16()?));
+    }
+
+    let instruction_count = reader.u32()? as usize;
+    let instructions = reader.bytes(instruction_count)?;
+
+    let line_count = reader.u32()? as usize;
+    let mut lines = Vec::with_capacity(line_count.min(instruction_count));
+    for _ in 0..line_count {
+      let span = SourceSpan::new(reader.u32()?, reader.u32()?, reader.u32()?);
+      lines.push(Line::new(span, reader.u32()?));
+    }
+
+    Some(Self {
+      name,
+      arity,
+      upvalue_count,
+      max_slots,
+      is_async,
+      try_blocks,
+      instructions,
+      lines,
+    })
+  }
+
+  /// Build the function in the provided module with its constants
+  pub(crate) fn build(self, name: GcStr, module: Gc<Module>, constants: Vec<Value>) -> Fun {
+    let mut builder = FunBuilder::new(name, module);
+    builder.set_arity(self.arity);
+    builder.set_upvalue_count(self.upvalue_count);
+    builder.update_max_slots(self.max_slots as i32);
+    builder.set_async(self.is_async);
+
+    for try_block in self.try_blocks {
+      builder.add_try(try_block);
+    }
+
+    let chunk = Chunk::new(
+      Box::from(self.instructions),
+      constants.into_boxed_slice(),
+      self.lines.into_boxed_slice(),
+    );
+
+    builder.build_with_chunk(chunk)
+  }
+}
+
+/// Rebuilds managed functions from a cache entry. Every object
+/// allocated while decoding is rooted until decoding completes
+struct Decoder<'a, 'b> {
+  hooks: &'a GcHooks<'a>,
+  module: Gc<Module>,
+  reader: Reader<'b>,
+  roots: usize,
+}
+
+impl<'a, 'b> Decoder<'a, 'b> {
+  fn root(&mut self, value: Value) -> Value {
+    self.hooks.push_root(value);
+    self.roots += 1;
+    value
+  }
+
+  fn fun(&mut self) -> Option<GcObj<Fun>> {
+    let layout = FunLayout::read(&mut self.reader)?;
+    let name = self.hooks.manage_str(layout.name);
+    self.root(val!(name));
+
+    let constant_count = self.reader.u32()? as usize;
+    let mut constants = Vec::with_capacity(constant_count.min(self.reader.remaining().len()));
+    for _ in 0..constant_count {
+      constants.push(self.value()?);
+    }
+
+    let fun = self
+      .hooks
+      .manage_obj(layout.build(name, self.module, constants));
+    self.root(val!(fun));
+    Some(fun)
+  }
+
+  fn value(&mut self) -> Option<Value> {
+    match self.reader.u8()? {
+      TAG_NIL => Some(VALUE_NIL),
+      TAG_FALSE => Some(val!(false)),
+      TAG_TRUE => Some(val!(true)),
+      TAG_NUMBER => Some(val!(f64::from_bits(self.reader.u64()?))),
+      TAG_STRING => {
+        let string = self.hooks.manage_str(self.reader.str()?);
+        Some(self.root(val!(string)))
+      },
+      TAG_FUN => self.fun().map(|fun| val!(fun)),
+      TAG_LIST => {
+        let len = self.reader.u32()? as usize;
+        let mut list = self
+          .hooks
+          .manage_obj(List::with_capacity(len.min(self.reader.remaining().len())));
+        self.root(val!(list));
+
+        for _ in 0..len {
+          let item = self.value()?;
+          self.hooks.grow(&mut *list, |list| list.push(item));
+        }
+
+        Some(val!(list))
+      },
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::byte_code::AlignedByteCode;
+  use laythe_core::{hooks::NoContext, module::Module, object::Class};
+  use laythe_env::fs::Fs;
+  use std::path::PathBuf;
+
+  fn test_module(hooks: &GcHooks) -> Gc<Module> {
+    let class = hooks.manage_obj(Class::bare(hooks.manage_str("Module")));
+    hooks.manage(Module::new(class, PathBuf::from("test.ly"), 0))
+  }
+
+  fn test_fun(hooks: &GcHooks, module: Gc<Module>) -> Fun {
+    let mut inner = FunBuilder::new(hooks.manage_str("inner"), module);
+    inner.set_arity(Arity::Default(1, 2));
+    inner.set_async(true);
+    inner.write_instruction(AlignedByteCode::Nil, SourceSpan::new(2, 1, 0));
+    inner.write_instruction(AlignedByteCode::Return, SourceSpan::new(2, 1, 0));
+    let inner = hooks.manage_obj(inner.build());
+
+    let mut builder = FunBuilder::new(hooks.manage_str("script"), module);
+    builder.update_max_slots(3);
+    builder.add_try(TryBlock::new(0, 4, 1));
+    builder.add_constant(val!(10.5));
+    builder.add_constant(val!(hooks.manage_str("example")));
+    builder.add_constant(val!(inner));
+    builder.add_constant(val!(
+      hooks.manage_obj(List::from(vec![val!(hooks.manage_str("std"))]))
+    ));
+    builder.write_instruction(AlignedByteCode::Constant(0), SourceSpan::new(1, 1, 3));
+    builder.write_instruction(AlignedByteCode::Drop, SourceSpan::new(1, 1, 3));
+    builder.write_instruction(AlignedByteCode::Nil, SourceSpan::new(3, 5, 2));
+    builder.write_instruction(AlignedByteCode::Return, SourceSpan::new(3, 5, 2));
+    builder.build()
+  }
+
+  #[test]
+  fn encode_decode() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+    let module = test_module(&hooks);
+    let fun = test_fun(&hooks, module);
+
+    let mut buffer = vec![];
+    assert!(encode_fun(&mut buffer, &fun).is_some());
+
+    let mut decoder = Decoder {
+      hooks: &hooks,
+      module,
+      reader: Reader::new(&buffer),
+      roots: 0,
+    };
+
+    let decoded = decoder.fun().expect("Unable to decode fun");
+    hooks.pop_roots(decoder.roots);
+
+    assert_eq!(&*decoded.name(), "script");
+    assert_eq!(decoded.max_slots(), 3);
+    assert_eq!(decoded.try_blocks().len(), 1);
+    assert_eq!(decoded.try_blocks()[0].slots(), 1);
+    assert_eq!(decoded.chunk().instructions(), fun.chunk().instructions());
+    assert_eq!(decoded.chunk().lines(), fun.chunk().lines());
+    assert_eq!(decoded.chunk().get_constant(0), val!(10.5));
+    assert_eq!(
+      &*decoded.chunk().get_constant(1).to_obj().to_str(),
+      "example"
+    );
+
+    let inner = decoded.chunk().get_constant(2).to_obj().to_fun();
+    assert_eq!(&*inner.name(), "inner");
+    assert_eq!(*inner.arity(), Arity::Default(1, 2));
+    assert!(inner.is_async());
+
+    let list = decoded.chunk().get_constant(3).to_obj().to_list();
+    assert_eq!(&*list[0].to_obj().to_str(), "std");
+  }
+
+  #[test]
+  fn truncated() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+    let module = test_module(&hooks);
+    let fun = test_fun(&hooks, module);
+
+    let mut buffer = vec![];
+    assert!(encode_fun(&mut buffer, &fun).is_some());
+    buffer.truncate(buffer.len() / 2);
+
+    let mut decoder = Decoder {
+      hooks: &hooks,
+      module,
+      reader: Reader::new(&buffer),
+      roots: 0,
+    };
+
+    assert!(decoder.fun().is_none());
+    hooks.pop_roots(decoder.roots);
+  }
+
+  #[test]
+  fn precompiled() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+    let module = test_module(&hooks);
+    let fun = test_fun(&hooks, module);
+
+    let cache = ChunkCache::new(&PathBuf::from("root"), "0.0.0");
+    let bytes = cache
+      .precompile(&fun, 2, 1)
+      .expect("Unable to precompile fun");
+
+    let script = cache
+      .load_precompiled(&hooks, module, &bytes)
+      .expect("Unable to load precompiled fun");
+    assert_eq!(&*script.fun.name(), "script");
+    assert_eq!(script.property_count, 2);
+    assert_eq!(script.invoke_count, 1);
+
+    let other_version = ChunkCache::new(&PathBuf::from("root"), "0.0.1");
+    assert!(other_version
+      .load_precompiled(&hooks, module, &bytes)
+      .is_none());
+
+    let other_build = ChunkCache {
+      build: "other",
+      ..ChunkCache::new(&PathBuf::from("root"), "0.0.0")
+    };
+    assert!(other_build
+      .load_precompiled(&hooks, module, &bytes)
+      .is_none());
+  }
+
+  #[test]
+  fn mock_fs_misses() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+    let module = test_module(&hooks);
+
+    let cache = ChunkCache::new(&PathBuf::from("root"), "0.0.0");
+    let fs = Fs::default();
+
+    assert!(cache.load(&fs, &hooks, module, "let x = 10;").is_none());
+  }
+}
@@ -0,0 +1,836 @@
+use crate::{
+  ast::*,
+  token::{Token, TokenKind},
+};
+use codespan_reporting::diagnostic::{Diagnostic, Label, Severity};
+use std::collections::HashSet;
+
+/// How the warnings produced by the linter are reported
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LintLevel {
+  /// Do not lint
+  Allow,
+
+  /// Report lints as warnings
+  #[default]
+  Warn,
+
+  /// Report lints as errors failing compilation
+  Deny,
+}
+
+impl LintLevel {
+  /// Apply this level to the warnings produced by the linter
+  pub fn apply<FileId>(self, warnings: Vec<Diagnostic<FileId>>) -> Vec<Diagnostic<FileId>> {
+    match self {
+      LintLevel::Allow => vec![],
+      LintLevel::Warn => warnings,
+      LintLevel::Deny => warnings
+        .into_iter()
+        .map(|mut warning| {
+          warning.severity = Severity::Error;
+          warning
+        })
+        .collect(),
+    }
+  }
+}
+
+/// The kind of name a binding introduces
+#[derive(Clone, Copy, PartialEq)]
+enum BindingKind {
+  /// A let, fn, class or loop variable
+  Variable,
+
+  /// A function or method parameter
+  Param,
+
+  /// A name bound by an import
+  Import,
+}
+
+/// A name declared in some scope
+struct Binding {
+  name: String,
+  span: Span,
+  kind: BindingKind,
+  used: bool,
+}
+
+/// The Laythe linter. This pass walks the AST producing non fatal
+/// warnings for unused locals and imports, unreachable code and
+/// shadowed variables
+pub struct Linter<FileId> {
+  /// The id of the file being linted
+  file_id: FileId,
+
+  /// The bindings of each scope, the first being the module scope
+  scopes: Vec<Vec<Binding>>,
+
+  /// Names that were referenced before any declaration was seen
+  unresolved: HashSet<String>,
+
+  /// All the warnings found so far
+  warnings: Vec<Diagnostic<FileId>>,
+}
+
+impl<FileId: Copy> Linter<FileId> {
+  /// Create a new linter for the provided file
+  pub fn new(file_id: FileId) -> Self {
+    Self {
+      file_id,
+      scopes: vec![],
+      unresolved: HashSet::new(),
+      warnings: vec![],
+    }
+  }
+
+  /// Lint the provided module returning all warnings in source order
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_vm::{
+  ///   arena::Arena,
+  ///   compiler::{Linter, Parser},
+  ///   source::Source,
+  /// };
+  ///
+  /// let source = Source::new("fn example() { let a = 10; }");
+  /// let arena = Arena::for_source(&source);
+  ///
+  /// let (ast, _) = Parser::new(&source, &arena, 0).parse();
+  /// let warnings = Linter::new(0).lint(&ast.ok().unwrap());
+  /// assert_eq!(warnings.len(), 1);
+  /// ```
+  pub fn lint(mut self, module: &Module) -> Vec<Diagnostic<FileId>> {
+    self.visit(module);
+
+    self
+      .warnings
+      .sort_by_key(|warning| warning.labels.first().map(|label| label.range.start));
+    self.warnings
+  }
+
+  fn begin_scope(&mut self) {
+    self.scopes.push(vec![]);
+  }
+
+  fn end_scope(&mut self) {
+    let is_module = self.scopes.len() == 1;
+    let scope = self.scopes.pop().expect("Expected scope.");
+
+    for binding in scope {
+      if binding.used || binding.name.starts_with('_') {
+        continue;
+      }
+
+      match binding.kind {
+        // module names may be referenced in a function declared earlier
+        BindingKind::Import if !self.unresolved.contains(&binding.name) => {
+          self.warn(
+            &format!("Unused import '{}'.", binding.name),
+            vec![Label::primary(self.file_id, binding.span)],
+          );
+        },
+        BindingKind::Variable if !is_module => {
+          let note = format!(
+            "If this is intentional prefix it with an underscore: '_{}'",
+            binding.name
+          );
+          self.warnings.push(
+            Diagnostic::warning()
+              .with_message(format!("Unused variable '{}'.", binding.name))
+              .with_labels(vec![Label::primary(self.file_id, binding.span)])
+              .with_notes(vec![note]),
+          );
+        },
+        _ => (),
+      }
+    }
+  }
+
+  /// Declare a name in the current scope
+  fn declare(&mut self, name: &Token, kind: BindingKind) {
+    let name_str = name.str();
+
+    if kind == BindingKind::Variable && self.scopes.len() > 1 && !name_str.starts_with('_') {
+      let enclosing = &self.scopes[..self.scopes.len() - 1];
+      let shadowed = enclosing
+        .iter()
+        .rev()
+        .flat_map(|scope| scope.iter().rev())
+        .find(|binding| binding.name == name_str)
+        .map(|binding| binding.span);
+
+      if let Some(span) = shadowed {
+        self.warn(
+          &format!(
+            "Variable '{}' shadows a variable in an outer scope.",
+            name_str
+          ),
+          vec![
+            Label::primary(self.file_id, name.span()),
+            Label::secondary(self.file_id, span)
+              .with_message(format!("{} was originally declared here", name_str)),
+          ],
+        );
+      }
+    }
+
+    let binding = Binding {
+      name: name_str.to_string(),
+      span: name.span(),
+      kind,
+      used: false,
+    };
+
+    match kind {
+      // imports always define module level names
+      BindingKind::Import => self.scopes[0].push(binding),
+      _ => self
+        .scopes
+        .last_mut()
+        .expect("Expected scope.")
+        .push(binding),
+    }
+  }
+
+  /// Mark the binding for a name as used
+  fn resolve(&mut self, name: &str) {
+    let binding = self
+      .scopes
+      .iter_mut()
+      .rev()
+      .flat_map(|scope| scope.iter_mut().rev())
+      .find(|binding| binding.name == name);
+
+    match binding {
+      Some(binding) => binding.used = true,
+      None => {
+        self.unresolved.insert(name.to_string());
+      },
+    }
+  }
+
+  fn warn(&mut self, message: &str, labels: Vec<Label<FileId>>) {
+    self.warnings.push(
+      Diagnostic::warning()
+        .with_message(message)
+        .with_labels(labels),
+    );
+  }
+
+  /// Visit a list of declarations warning about any
+  /// that follow a statement that always exits
+  fn decls(&mut self, decls: &[Decl]) {
+    let mut reported = false;
+
+    for (idx, decl) in decls.iter().enumerate() {
+      self.visit_decl(decl);
+
+      if reported || !diverges(decl) {
+        continue;
+      }
+
+      if let (Some(first), Some(last)) = (decls.get(idx + 1), decls.last()) {
+        reported = true;
+        self.warn(
+          "Unreachable code.",
+          vec![
+            Label::primary(self.file_id, first.start() as usize..last.end() as usize),
+            Label::secondary(self.file_id, decl.span())
+              .with_message("any code following this statement is unreachable"),
+          ],
+        );
+      }
+    }
+  }
+
+  /// Visit a function's parameters and body in a new scope
+  fn fun_body(&mut self, fun: &Fun) {
+    self.begin_scope();
+    self.visit_call_sig(&fun.call_sig);
+
+    match &fun.body {
+      FunBody::Block(block) => self.decls(&block.decls),
+      FunBody::Expr(expr) => self.visit_expr(expr),
+    }
+
+    self.end_scope();
+  }
+
+  /// Visit each of a class's methods
+  fn class_body(&mut self, class: &Class) {
+    if let Some(init) = &class.init {
+      self.visit_method(init);
+    }
+
+    for method in &class.methods {
+      self.visit_method(method);
+    }
+
+    for static_method in &class.static_methods {
+      self.visit_static_method(static_method);
+    }
+  }
+}
+
+/// Does this declaration always exit the enclosing block
+fn diverges(decl: &Decl) -> bool {
+  match decl {
+    Decl::Stmt(stmt) => match &**stmt {
+      Stmt::Return(_) | Stmt::Raise(_) | Stmt::Break(_) | Stmt::Continue(_) => true,
+      Stmt::If(if_) => if_diverges(if_),
+      _ => false,
+    },
+    _ => false,
+  }
+}
+
+/// Does every branch of this if statement exit the enclosing block
+fn if_diverges(if_: &If) -> bool {
+  let block_diverges = |block: &Block| block.decls.iter().any(diverges);
+
+  block_diverges(&if_.body)
+    && match &if_.else_ {
+      Some(Else::If(else_if)) => if_diverges(else_if),
+      Some(Else::Block(block)) => block_diverges(block),
+      None => false,
+    }
+}
+
+impl<'a, FileId: Copy> Visitor<'a> for Linter<FileId> {
+  type Result = ();
+
+  fn visit(&mut self, module: &Module) -> Self::Result {
+    self.begin_scope();
+    self.decls(&module.decls);
+    self.end_scope();
+  }
+
+  fn visit_decl(&mut self, decl: &Decl) -> Self::Result {
+    match decl {
+      Decl::Symbol(symbol) => self.visit_symbol(symbol),
+      Decl::Export(export) => self.visit_export(export),
+      Decl::Stmt(stmt) => self.visit_stmt(stmt),
+      Decl::Error(error) => self.visit_error(error),
+    }
+  }
+
+  fn visit_stmt(&mut self, stmt: &Stmt) -> Self::Result {
+    match stmt {
+      Stmt::Expr(expr) => self.visit_expr(expr),
+      Stmt::ImplicitReturn(expr) => self.visit_expr(expr),
+      Stmt::Import(import) => self.visit_import(import),
+      Stmt::For(for_) => self.visit_for(for_),
+      Stmt::If(if_) => self.visit_if(if_),
+      Stmt::Return(return_) => self.visit_return(return_),
+      Stmt::Raise(raise) => self.visit_raise(raise),
+      Stmt::Continue(continue_) => self.visit_continue(continue_),
+      Stmt::Break(break_) => self.visit_break(break_),
+      Stmt::While(while_) => self.visit_while(while_),
+      Stmt::Try(try_) => self.visit_try(try_),
+      Stmt::With(with) => self.visit_with(with),
+    }
+  }
+
+  fn visit_expr(&mut self, expr: &Expr) -> Self::Result {
+    match expr {
+      Expr::Assign(assign) => self.visit_assign(assign),
+      Expr::AssignBinary(assign_binary) => self.visit_assign_binary(assign_binary),
+      Expr::Binary(binary) => self.visit_binary(binary),
+      Expr::Unary(unary) => self.visit_unary(unary),
+      Expr::Atom(atom) => self.visit_atom(atom),
+    }
+  }
+
+  fn visit_primary(&mut self, primary: &Primary) -> Self::Result {
+    match primary {
+      Primary::AssignBlock(block) => self.visit_assign_block(block),
+      Primary::True(token) => self.visit_true(token),
+      Primary::False(token) => self.visit_false(token),
+      Primary::Nil(token) => self.visit_nil(token),
+      Primary::Number(token) => self.visit_number(token),
+      Primary::Grouping(expr) => self.visit_expr(expr),
+      Primary::String(token) => self.visit_string(token),
+      Primary::Interpolation(string_interp) => self.visit_interpolation(string_interp),
+      Primary::Ident(token) => self.visit_ident(token),
+      Primary::Self_(token) => self.visit_self(token),
+      Primary::Super(token) => self.visit_super(token),
+      Primary::Lambda(fun) => self.visit_lambda(fun),
+      Primary::Class(class) => self.visit_class_expr(class),
+      Primary::List(items) => self.visit_list(items),
+      Primary::Tuple(items) => self.visit_tuple(items),
+      Primary::Map(kvps) => self.visit_map(kvps),
+    }
+  }
+
+  fn visit_symbol(&mut self, symbol: &Symbol) -> Self::Result {
+    match symbol {
+      Symbol::Class(class) => self.visit_class(class),
+      Symbol::Extension(extension) => self.visit_extension(extension),
+      Symbol::Fun(fun) => self.visit_fun(fun),
+      Symbol::Let(let_) => self.visit_let(let_),
+      Symbol::LetTuple(let_tuple) => self.visit_let_tuple(let_tuple),
+      Symbol::Const(const_) => self.visit_const(const_),
+      Symbol::Import(import) => self.visit_import(import),
+      Symbol::Trait(_) | Symbol::TypeDecl(_) => (),
+    }
+  }
+
+  fn visit_export(&mut self, export: &Symbol) -> Self::Result {
+    self.visit_symbol(export);
+
+    // re-exported imports are used by the importing modules
+    if let Symbol::Import(import) = export {
+      match &import.stem {
+        ImportStem::None => self.resolve(import.path()[import.path().len() - 1].str()),
+        ImportStem::Rename(rename) => self.resolve(rename.str()),
+        ImportStem::Symbols(symbols) => {
+          for symbol in symbols {
+            self.resolve(symbol.rename.as_ref().unwrap_or(&symbol.symbol).str());
+          }
+        },
+      }
+    }
+  }
+
+  fn visit_error(&mut self, _: &[Token<'a>]) -> Self::Result {}
+
+  fn visit_class(&mut self, class: &Class) -> Self::Result {
+    if let Some(super_class) = &class.super_class {
+      self.resolve(super_class.type_ref.name.str());
+    }
+
+    self.declare(&class.name, BindingKind::Variable);
+    self.class_body(class);
+  }
+
+  fn visit_extension(&mut self, extension: &Extension) -> Self::Result {
+    self.resolve(extension.class.str());
+
+    for method in &extension.methods {
+      self.visit_method(method);
+    }
+
+    for static_method in &extension.static_methods {
+      self.visit_static_method(static_method);
+    }
+  }
+
+  fn visit_fun(&mut self, fun: &Fun) -> Self::Result {
+    if let Some(name) = &fun.name {
+      self.declare(name, BindingKind::Variable);
+    }
+
+    self.fun_body(fun);
+  }
+
+  fn visit_let(&mut self, let_: &Let) -> Self::Result {
+    if let Some(value) = &let_.value {
+      self.visit_expr(value);
+    }
+
+    self.declare(&let_.name, BindingKind::Variable);
+  }
+
+  fn visit_let_tuple(&mut self, let_tuple: &LetTuple) -> Self::Result {
+    self.visit_expr(&let_tuple.value);
+
+    for name in &let_tuple.names {
+      self.declare(name, BindingKind::Variable);
+    }
+  }
+
+  fn visit_const(&mut self, const_: &Const) -> Self::Result {
+    self.visit_expr(&const_.value);
+    self.declare(&const_.name, BindingKind::Variable);
+  }
+
+  fn visit_method(&mut self, method: &Fun) -> Self::Result {
+    self.fun_body(method);
+  }
+
+  fn visit_static_method(&mut self, static_method: &Fun) -> Self::Result {
+    self.fun_body(static_method);
+  }
+
+  fn visit_import(&mut self, import: &Import) -> Self::Result {
+    match &import.stem {
+      ImportStem::None => {
+        let name = &import.path()[import.path().len() - 1];
+        self.declare(name, BindingKind::Import);
+      },
+      ImportStem::Rename(rename) => self.declare(rename, BindingKind::Import),
+      ImportStem::Symbols(symbols) => {
+        for symbol in symbols {
+          let name = symbol.rename.as_ref().unwrap_or(&symbol.symbol);
+          self.declare(name, BindingKind::Import);
+        }
+      },
+    }
+  }
+
+  fn visit_for(&mut self, for_: &For) -> Self::Result {
+    self.visit_expr(&for_.iter);
+
+    self.begin_scope();
+    self.declare(&for_.item, BindingKind::Variable);
+    self.visit_block(&for_.body);
+    self.end_scope();
+  }
+
+  fn visit_while(&mut self, while_: &While) -> Self::Result {
+    self.visit_expr(&while_.cond);
+    self.visit_block(&while_.body);
+  }
+
+  fn visit_if(&mut self, if_: &If) -> Self::Result {
+    self.visit_expr(&if_.cond);
+    self.visit_block(&if_.body);
+
+    match &if_.else_ {
+      Some(Else::If(else_if)) => self.visit_if(else_if),
+      Some(Else::Block(block)) => self.visit_block(block),
+      None => (),
+    }
+  }
+
+  fn visit_return(&mut self, return_: &Return) -> Self::Result {
+    if let Some(value) = &return_.value {
+      self.visit_expr(value);
+    }
+  }
+
+  fn visit_raise(&mut self, raise: &Raise) -> Self::Result {
+    self.visit_expr(&raise.error);
+    if let Some(cause) = &raise.cause {
+      self.visit_expr(cause);
+    }
+  }
+
+  fn visit_continue(&mut self, _: &Token<'a>) -> Self::Result {}
+
+  fn visit_break(&mut self, _: &Token<'a>) -> Self::Result {}
+
+  fn visit_try(&mut self, try_: &Try) -> Self::Result {
+    self.visit_block(&try_.block);
+
+    self.begin_scope();
+    if let Some(error) = &try_.error {
+      self.declare(error, BindingKind::Variable);
+    }
+    self.decls(&try_.catch.decls);
+    self.end_scope();
+  }
+
+  fn visit_with(&mut self, with: &With) -> Self::Result {
+    self.visit_expr(&with.manager);
+
+    self.begin_scope();
+    if let Some(name) = &with.name {
+      self.declare(name, BindingKind::Variable);
+    }
+    self.visit_block(&with.body);
+    self.end_scope();
+  }
+
+  fn visit_block(&mut self, block: &Block) -> Self::Result {
+    self.begin_scope();
+    self.decls(&block.decls);
+    self.end_scope();
+  }
+
+  fn visit_assign(&mut self, assign: &Assign) -> Self::Result {
+    self.visit_expr(&assign.lhs);
+    self.visit_expr(&assign.rhs);
+  }
+
+  fn visit_assign_binary(&mut self, assign_binary: &AssignBinary) -> Self::Result {
+    self.visit_expr(&assign_binary.lhs);
+    self.visit_expr(&assign_binary.rhs);
+  }
+
+  fn visit_binary(&mut self, binary: &Binary) -> Self::Result {
+    self.visit_expr(&binary.lhs);
+    self.visit_expr(&binary.rhs);
+  }
+
+  fn visit_unary(&mut self, unary: &Unary) -> Self::Result {
+    self.visit_expr(&unary.expr);
+  }
+
+  fn visit_atom(&mut self, atom: &Atom) -> Self::Result {
+    self.visit_primary(&atom.primary);
+
+    for trailer in &atom.trailers {
+      match trailer {
+        Trailer::Call(call) => self.visit_call(call),
+        Trailer::Index(index) => self.visit_index(index),
+        Trailer::Access(access) => self.visit_access(access),
+      }
+    }
+  }
+
+  fn visit_index(&mut self, index: &Index) -> Self::Result {
+    self.visit_expr(&index.index);
+  }
+
+  fn visit_call(&mut self, call: &Call) -> Self::Result {
+    for arg in &call.args {
+      self.visit_expr(arg);
+    }
+  }
+
+  fn visit_access(&mut self, _: &Access) -> Self::Result {}
+
+  fn visit_call_sig(&mut self, call_sig: &CallSignature) -> Self::Result {
+    for param in &call_sig.params {
+      self.declare(&param.name, BindingKind::Param);
+    }
+  }
+
+  fn visit_assign_block(&mut self, block: &Block) -> Self::Result {
+    self.visit_block(block);
+  }
+
+  fn visit_true(&mut self, _: &Token<'a>) -> Self::Result {}
+
+  fn visit_false(&mut self, _: &Token<'a>) -> Self::Result {}
+
+  fn visit_nil(&mut self, _: &Token<'a>) -> Self::Result {}
+
+  fn visit_number(&mut self, _: &Token<'a>) -> Self::Result {}
+
+  fn visit_string(&mut self, _: &Token<'a>) -> Self::Result {}
+
+  fn visit_interpolation(&mut self, string_interp: &Interpolation) -> Self::Result {
+    for segment in &string_interp.segments {
+      if let StringSegments::Expr(expr) = segment {
+        self.visit_expr(expr);
+      }
+    }
+  }
+
+  fn visit_ident(&mut self, token: &Token<'a>) -> Self::Result {
+    self.resolve(token.str());
+  }
+
+  fn visit_self(&mut self, _: &Token<'a>) -> Self::Result {}
+
+  fn visit_super(&mut self, _: &Super) -> Self::Result {}
+
+  fn visit_lambda(&mut self, fun: &Fun) -> Self::Result {
+    self.fun_body(fun);
+  }
+
+  fn visit_class_expr(&mut self, class: &Class) -> Self::Result {
+    if let Some(super_class) = &class.super_class {
+      self.resolve(super_class.type_ref.name.str());
+    }
+
+    // a named class expression is only visible inside its own body and,
+    // like a parameter, isn't expected to be used
+    self.begin_scope();
+    if class.name.kind() == TokenKind::Identifier {
+      self.declare(&class.name, BindingKind::Param);
+    }
+
+    self.class_body(class);
+    self.end_scope();
+  }
+
+  fn visit_list(&mut self, items: &List) -> Self::Result {
+    for item in &items.items {
+      self.visit_expr(item);
+    }
+  }
+
+  fn visit_tuple(&mut self, items: &Tuple) -> Self::Result {
+    for item in &items.items {
+      self.visit_expr(item);
+    }
+  }
+
+  fn visit_map(&mut self, kvps: &Map) -> Self::Result {
+    for (key, value) in &kvps.entries {
+      self.visit_expr(key);
+      self.visit_expr(value);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{arena::Arena, compiler::Parser, source::Source};
+
+  fn lint(source: &str) -> Vec<String> {
+    let source = Source::new(source);
+    let arena = Arena::for_source(&source);
+
+    let (ast, _) = Parser::new(&source, &arena, 0).parse();
+    let ast = match ast {
+      Ok(ast) => ast,
+      Err(_) => panic!("expected source to parse"),
+    };
+
+    Linter::new(0)
+      .lint(&ast)
+      .into_iter()
+      .map(|warning| {
+        assert_eq!(warning.severity, Severity::Warning);
+        warning.message
+      })
+      .collect()
+  }
+
+  #[test]
+  fn clean() {
+    let example = "
+      import std.time;
+      fn example(a) {
+        let b = a + 1;
+        for c in [1, 2, 3] {
+          print(b + c);
+        }
+
+        return b;
+      }
+
+      print(time.now());
+      example(10);
+    ";
+
+    assert!(lint(example).is_empty());
+  }
+
+  #[test]
+  fn unused_local() {
+    let example = "
+      fn example(a) {
+        let b = 10;
+        let _c = 10;
+        try {
+          a();
+        } catch e {
+          print(a);
+        }
+      }
+
+      let d = 10;
+    ";
+
+    assert_eq!(
+      lint(example),
+      vec!["Unused variable 'b'.", "Unused variable 'e'."]
+    );
+  }
+
+  #[test]
+  fn unused_import() {
+    let example = "
+      import std.time;
+      import std.io.stdio:{ stdout, stderr as err };
+      import std.regexp as re;
+
+      fn example() {
+        return stdout;
+      }
+    ";
+
+    assert_eq!(
+      lint(example),
+      vec![
+        "Unused import 'time'.",
+        "Unused import 'err'.",
+        "Unused import 're'.",
+      ]
+    );
+  }
+
+  #[test]
+  fn import_used_before_declared() {
+    let example = "
+      fn example() {
+        return time.now();
+      }
+
+      import std.time;
+    ";
+
+    assert!(lint(example).is_empty());
+  }
+
+  #[test]
+  fn unreachable() {
+    let example = "
+      fn example(a) {
+        return a;
+        print(a);
+        print(a);
+      }
+
+      fn branches(a) {
+        if a {
+          return 1;
+        } else {
+          return 2;
+        }
+        print(a);
+      }
+
+      while true {
+        break;
+        print(1);
+      }
+    ";
+
+    assert_eq!(
+      lint(example),
+      vec![
+        "Unreachable code.",
+        "Unreachable code.",
+        "Unreachable code."
+      ]
+    );
+  }
+
+  #[test]
+  fn shadowing() {
+    let example = "
+      let a = 10;
+      fn example(b) {
+        let a = 20;
+        for b in [1, 2] {
+          let _a = b;
+          print(b);
+        }
+        return a;
+      }
+    ";
+
+    assert_eq!(
+      lint(example),
+      vec![
+        "Variable 'a' shadows a variable in an outer scope.",
+        "Variable 'b' shadows a variable in an outer scope.",
+      ]
+    );
+  }
+
+  #[test]
+  fn deny_promotes_to_errors() {
+    let warnings = vec![Diagnostic::warning().with_message("example")];
+
+    assert_eq!(LintLevel::Allow.apply(warnings.clone()).len(), 0);
+    assert_eq!(
+      LintLevel::Warn.apply(warnings.clone())[0].severity,
+      Severity::Warning
+    );
+    assert_eq!(
+      LintLevel::Deny.apply::<usize>(warnings)[0].severity,
+      Severity::Error
+    );
+  }
+}
@@ -1,40 +1,50 @@
 // mod compiler;
+mod lint;
+mod optimizer;
 mod parser;
 mod scanner;
+mod symbols;
+mod type_check;
 
-pub use parser::Parser;
+pub use lint::{LintLevel, Linter};
+pub use parser::{is_incomplete, Parser};
 pub use scanner::Scanner;
+pub use symbols::{ModuleSymbol, SymbolKind, SymbolTable};
+pub use type_check::TypeChecker;
 
 use crate::{
   ast::{self, Decl, Expr, Primary, Span, Spanned, Stmt, Symbol, Trailer},
   byte_code::{AlignedByteCode, UpvalueIndex},
   cache::CacheIdEmitter,
-  source::LineOffsets,
+  diagnostics,
+  source::{LineOffsets, Source},
   token::{Lexeme, Token, TokenKind},
   FeResult,
 };
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use laythe_core::{
-  chunk::ChunkBuilder,
+  chunk::{ChunkBuilder, SourceSpan},
+  constants::{CLASS_VAR, ITER, ITER_VAR, SCRIPT, SELF, SUPER, WITH_VAR},
   constants::{INDEX_GET, INDEX_SET, OBJECT},
-  constants::{ITER, ITER_VAR, SCRIPT, SELF, SUPER},
   hooks::{GcContext, GcHooks},
   managed::{DebugHeap, Gc, GcObj, GcStr, Manage, Trace, TraceRoot},
   memory::Allocator,
   module, object,
   object::{FunBuilder, FunKind, List, Map},
+  parse::parse_number,
   signature::Arity,
   val,
-  value::Value,
+  value::{Value, VALUE_NIL},
 };
 use laythe_env::io::Io;
 use object::{Fun, TryBlock};
 use std::{
   cell::{RefCell, RefMut},
+  collections::HashMap,
   convert::TryInto,
   io::Write,
   mem,
-  ptr::NonNull,
+  ptr::{self, NonNull},
   rc::Rc,
 };
 
@@ -51,6 +61,24 @@ const UNINITIALIZED_TOKEN: &Token<'static> =
 /// A placeholder token to fill the first slot for non method functions
 const SELF_TOKEN: &Token<'static> = &Token::new(TokenKind::Error, Lexeme::Slice(SELF), 0, 0);
 
+/// The name of the global assert function
+const ASSERT: &str = "assert";
+
+/// The name of the global print function
+const PRINT: &str = "print";
+
+/// The name the last repl result is bound to
+const REPL_LAST: &str = "_";
+
+/// The method called on a context manager entering a with statement
+const ENTER: &str = "enter";
+
+/// The method called on a context manager leaving a with statement
+const EXIT: &str = "exit";
+
+/// The maximum number of subexpressions captured by a single assert
+const MAX_ASSERT_CAPTURES: usize = 32;
+
 #[derive(Debug, Clone)]
 pub struct Local<'a, 'src: 'a> {
   /// name of the local
@@ -63,11 +91,23 @@ pub struct Local<'a, 'src: 'a> {
   is_captured: bool,
 }
 
+/// A subexpression of an assert whose value is stored
+/// in a hidden local for the failure message
+struct AssertCapture<'a, 'src: 'a> {
+  /// The captured expression
+  expr: &'a Expr<'src>,
+
+  /// The local slot holding the captured value
+  slot: u8,
+}
+
 #[derive(Debug, Clone)]
 pub struct ClassInfo {
   fun_kind: Option<FunKind>,
   fields: Vec<GcStr>,
   name: GcStr,
+  extension: bool,
+  vtable: Vec<GcStr>,
 }
 
 impl ClassInfo {
@@ -76,12 +116,24 @@ impl ClassInfo {
       fun_kind: None,
       fields: vec![],
       name,
+      extension: false,
+      vtable: vec![],
     }
   }
 
   fn add_field(&mut self, hooks: &GcHooks, field: GcStr) {
     hooks.grow(self, |self_| self_.fields.push(field));
   }
+
+  fn add_vtable_entry(&mut self, hooks: &GcHooks, method: GcStr) {
+    hooks.grow(self, |self_| self_.vtable.push(method));
+  }
+
+  /// The vtable index of a method if this class is sealed. Later
+  /// declarations of the same name replace earlier ones
+  fn vtable_index(&self, name: &str) -> Option<usize> {
+    self.vtable.iter().rposition(|method| &**method == name)
+  }
 }
 
 impl DebugHeap for ClassInfo {
@@ -90,13 +142,16 @@ impl DebugHeap for ClassInfo {
       .field("fun_kind", &self.fun_kind)
       .field("fields", &self.fields)
       .field("name", &self.name)
+      .field("extension", &self.extension)
+      .field("vtable", &self.vtable)
       .finish()
   }
 }
 
 impl Manage for ClassInfo {
   fn size(&self) -> usize {
-    mem::size_of::<Self>() + mem::size_of::<GcStr>() * self.fields.capacity()
+    mem::size_of::<Self>()
+      + mem::size_of::<GcStr>() * (self.fields.capacity() + self.vtable.capacity())
   }
 
   fn as_debug(&self) -> &dyn DebugHeap {
@@ -108,14 +163,30 @@ impl Trace for ClassInfo {
   fn trace(&self) {
     self.name.trace();
     self.fields.iter().for_each(|field| field.trace());
+    self.vtable.iter().for_each(|method| method.trace());
   }
 
   fn trace_debug(&self, log: &mut dyn Write) {
     self.name.trace_debug(log);
     self.fields.iter().for_each(|field| field.trace_debug(log));
+    self
+      .vtable
+      .iter()
+      .for_each(|method| method.trace_debug(log));
   }
 }
 
+/// A with statement whose context manager is exited when
+/// a return, break or continue leaves its body early
+#[derive(Debug, Clone, Copy)]
+struct WithInfo {
+  /// The local slot holding the context manager
+  slot: u8,
+
+  /// The scope depth of the with statement's body
+  scope_depth: i16,
+}
+
 #[derive(Debug, Clone)]
 pub struct LoopInfo {
   scope_depth: i16,
@@ -173,6 +244,9 @@ pub struct Compiler<'a, 'src, FileId> {
   /// The ast for this module
   ast: &'a ast::Module<'src>,
 
+  /// The source text for this module
+  source: &'a Source<'src>,
+
   /// line offsets for the current file
   line_offsets: &'a LineOffsets,
 
@@ -189,6 +263,9 @@ pub struct Compiler<'a, 'src, FileId> {
   /// The info on the current loop
   loop_info: Option<Gc<LoopInfo>>,
 
+  /// The with statements enclosing the current position in this function
+  with_info: Vec<WithInfo>,
+
   /// Should we early exit scope (break / continue)
   exit_scope: ScopeExit,
 
@@ -219,8 +296,23 @@ pub struct Compiler<'a, 'src, FileId> {
   /// temporary tokens
   temp_tokens: Vec<Gc<Token<'static>>>,
 
+  /// subexpressions captured by the assert currently being compiled
+  assert_captures: Vec<AssertCapture<'a, 'src>>,
+
   /// A set of constants used in the current function
   constants: Map<Value, usize>,
+
+  /// The values of the constants declared at the module scope
+  module_consts: HashMap<String, Value>,
+
+  /// Should the optimization pass be run on each function
+  optimize: bool,
+
+  /// Are we compiling a line from the repl
+  repl: bool,
+
+  /// The module symbols and references found while compiling
+  symbols: Option<Rc<RefCell<SymbolTable>>>,
 }
 
 impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
@@ -231,7 +323,7 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
   /// ```
   /// use laythe_vm::{
   ///   compiler::Compiler,
-  ///   source::LineOffsets,
+  ///   source::{LineOffsets, Source},
   ///   ast,
   /// };
   /// use laythe_core::{
@@ -248,12 +340,14 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
   ///
   /// let module = gc.manage(Module::new(class, path, 0), &NO_GC);
   /// let ast = ast::Module::new(vec![]);
+  /// let source = Source::new("");
   ///
-  /// let compiler = Compiler::new(module, &ast, &LineOffsets::default(), 0, &NO_GC, gc);
+  /// let compiler = Compiler::new(module, &ast, &source, &LineOffsets::default(), 0, &NO_GC, gc);
   /// ```
   pub fn new(
     module: Gc<module::Module>,
     ast: &'a ast::Module<'src>,
+    source: &'a Source<'src>,
     line_offsets: &'a LineOffsets,
     file_id: FileId,
     root_trace: &'a dyn TraceRoot,
@@ -271,6 +365,7 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       root_trace,
       module,
       ast,
+      source,
       line_offsets,
       cache_id_emitter: Rc::new(RefCell::new(CacheIdEmitter::default())),
       errors: vec![],
@@ -279,6 +374,7 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       slots: 1,
       class_info: None,
       loop_info: None,
+      with_info: vec![],
       exit_scope: ScopeExit::Normal,
       gc: RefCell::new(gc),
       enclosing: None,
@@ -290,10 +386,35 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       }],
       upvalues: vec![],
       temp_tokens: vec![],
+      assert_captures: vec![],
       constants: object::Map::default(),
+      module_consts: HashMap::new(),
+      optimize: false,
+      repl: false,
+      symbols: None,
     }
   }
 
+  /// Run the optimization pass over each compiled function
+  pub fn with_optimize(mut self, optimize: bool) -> Self {
+    self.optimize = optimize;
+    self
+  }
+
+  /// Compile as a line from the repl. Top level expression statements
+  /// print their value if not nil and bind it to `_`
+  pub fn with_repl(mut self, repl: bool) -> Self {
+    self.repl = repl;
+    self
+  }
+
+  /// Record the module symbols and each global reference into the
+  /// provided symbol table
+  pub fn with_symbols(mut self, symbols: Rc<RefCell<SymbolTable>>) -> Self {
+    self.symbols = Some(symbols);
+    self
+  }
+
   /// Compile the provided ast into managed function objects that
   /// contain the vm bytecode
   pub fn compile(mut self) -> (FeResult<Fun, FileId>, Allocator, CacheIdEmitter) {
@@ -322,6 +443,8 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
     let gc = RefCell::new(Allocator::default());
     gc.swap(&enclosing.gc);
+    let optimize = enclosing.optimize;
+    let symbols = enclosing.symbols.clone();
 
     #[cfg(feature = "debug")]
     let io: Option<Io> = enclosing.io.clone();
@@ -335,6 +458,7 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       file_id: enclosing.file_id,
       module: enclosing.module,
       ast: enclosing.ast,
+      source: enclosing.source,
       line_offsets: enclosing.line_offsets,
       cache_id_emitter: Rc::clone(&enclosing.cache_id_emitter),
       errors: vec![],
@@ -344,6 +468,7 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       slots: 1,
       class_info: enclosing.class_info,
       loop_info: enclosing.loop_info,
+      with_info: vec![],
       exit_scope: ScopeExit::Normal,
       gc,
       enclosing: Some(NonNull::from(enclosing)),
@@ -351,7 +476,12 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       locals: vec![first_local],
       upvalues: vec![],
       temp_tokens: vec![],
+      assert_captures: vec![],
       constants: object::Map::default(),
+      module_consts: HashMap::new(),
+      optimize,
+      repl: false,
+      symbols,
     }
   }
 
@@ -372,6 +502,10 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       self.emit_return(line);
     }
 
+    if self.optimize && self.errors.is_empty() {
+      optimizer::optimize(&mut self.fun, &mut self.constants);
+    }
+
     let fun = self.fun.build();
 
     #[cfg(feature = "debug")]
@@ -544,6 +678,14 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
           AlignedByteCode::SetUpvalue(upvalue),
         ),
         None => {
+          if let Some(symbols) = &self.symbols {
+            symbols.borrow_mut().reference(name.str(), name.span());
+          }
+
+          if let Some(value) = self.resolve_const(name.str()) {
+            return self.const_variable(name, value, can_assign);
+          }
+
           let global_index = self.identifier_constant(name.str());
           (
             AlignedByteCode::GetGlobal(global_index),
@@ -582,6 +724,26 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     Option::None
   }
 
+  /// resolve a name to the value of a module constant if it exists
+  fn resolve_const(&self, name: &str) -> Option<Value> {
+    match self.enclosing {
+      Some(enclosing) => unsafe { enclosing.as_ref().resolve_const(name) },
+      None => self.module_consts.get(name).copied(),
+    }
+  }
+
+  /// Inline the value of a module constant, which cannot be assigned to
+  fn const_variable(&mut self, name: &Token<'src>, value: Value, can_assign: bool) {
+    if can_assign {
+      self.error(
+        &format!("Cannot assign to constant {}.", name.str()),
+        Some(name),
+      );
+    } else {
+      self.emit_value(value, name.end());
+    }
+  }
+
   /// resolve a token to an upvalue in an enclosing scope if it exists
   fn resolve_upvalue(&mut self, name: &Token<'src>) -> Option<u8> {
     match self.enclosing {
@@ -630,6 +792,25 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     upvalue_count
   }
 
+  /// Record a declaration in the symbol table if it is at the module scope.
+  /// Module constants cannot be redeclared
+  fn declare_symbol(&mut self, name: &Token<'src>, kind: SymbolKind) {
+    if self.scope_depth > 0 || self.enclosing.is_some() {
+      return;
+    }
+
+    if self.module_consts.contains_key(name.str()) {
+      self.error(
+        &format!("Cannot redeclare constant {}.", name.str()),
+        Some(name),
+      );
+    }
+
+    if let Some(symbols) = &self.symbols {
+      symbols.borrow_mut().declare(name.str(), kind, name.span());
+    }
+  }
+
   /// Define a variable
   fn define_variable(&mut self, variable: u16, offset: u32) {
     if self.scope_depth > 0 {
@@ -652,15 +833,36 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
   /// Emit a provided instruction
   fn emit_byte(&mut self, op_code: AlignedByteCode, offset: u32) {
+    self.emit_span(
+      op_code,
+      Span {
+        start: offset,
+        end: offset,
+      },
+    );
+  }
+
+  /// Emit a provided instruction attributed to a span of the source
+  fn emit_span(&mut self, op_code: AlignedByteCode, span: Span) {
     let line = self
       .line_offsets
-      .offset_line(offset as usize)
+      .offset_line(span.start as usize)
       .expect("Line offset out of bounds");
+    let line_start = self
+      .line_offsets
+      .line_range(line)
+      .expect("Line out of bounds")
+      .start as u32;
 
     self.slots += op_code.stack_effect();
     self.fun.update_max_slots(self.slots);
 
-    self.write_instruction(op_code, line as u32 + 1);
+    let span = SourceSpan::new(
+      line as u32 + 1,
+      span.start - line_start + 1,
+      span.end - span.start,
+    );
+    self.write_instruction(op_code, span);
   }
 
   /// Emit a jump instruction
@@ -713,8 +915,8 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
   }
 
   /// write instruction to the current function
-  fn write_instruction(&mut self, op_code: AlignedByteCode, line: u32) {
-    self.fun.write_instruction(op_code, line)
+  fn write_instruction(&mut self, op_code: AlignedByteCode, span: SourceSpan) {
+    self.fun.write_instruction(op_code, span)
   }
 
   /// Parse a variable from the provided token return it's new constant
@@ -766,6 +968,21 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     }
   }
 
+  /// Emit byte code for a literal value
+  fn emit_value(&mut self, value: Value, offset: u32) {
+    if value.is_nil() {
+      self.emit_byte(AlignedByteCode::Nil, offset);
+    } else if value.is_bool() {
+      if value.to_bool() {
+        self.emit_byte(AlignedByteCode::True, offset);
+      } else {
+        self.emit_byte(AlignedByteCode::False, offset);
+      }
+    } else {
+      self.emit_constant(value, offset);
+    }
+  }
+
   /// Indicate an error with additional context
   fn error_with_context(&mut self, message_primary: &str, labels: Vec<Label<FileId>>) {
     let error = Diagnostic::error()
@@ -787,11 +1004,9 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
   /// Print an error to the console for a user to address
   fn error_at(&mut self, message: &str, token: Option<&Token>) {
-    let error = Diagnostic::error().with_message(message);
-
     let error = match token {
-      Some(token) => error.with_labels(vec![Label::primary(self.file_id, token.span())]),
-      None => error,
+      Some(token) => diagnostics::error_at(message, self.file_id, token.span()),
+      None => Diagnostic::error().with_message(message),
     };
 
     self.errors.push(error);
@@ -810,23 +1025,34 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
   /// Compile a statement
   fn stmt(&mut self, stmt: &'a Stmt<'src>) {
     match stmt {
-      Stmt::Expr(expr) => {
-        self.expr(expr);
-        self.emit_byte(AlignedByteCode::Drop, expr.end());
-      }
+      Stmt::Expr(expr) => match self.assert_call(expr) {
+        Some((assert, value)) => self.assert(assert, value, expr.end()),
+        None => {
+          if self.repl && self.fun_kind == FunKind::Script && self.scope_depth == 0 {
+            self.repl_echo(expr);
+          } else {
+            self.expr(expr);
+            self.emit_byte(AlignedByteCode::Drop, expr.end());
+          }
+        },
+      },
       Stmt::ImplicitReturn(expr) => {
         self.expr(expr);
         self.emit_byte(AlignedByteCode::Return, expr.end());
         self.exit_scope = ScopeExit::Early
       }
-      Stmt::Import(import) => self.import(import),
+      Stmt::Import(import) => {
+        self.import(import);
+      }
       Stmt::For(for_) => self.for_(for_),
       Stmt::If(if_) => self.if_(if_),
       Stmt::Return(return_) => self.return_(return_),
+      Stmt::Raise(raise) => self.raise(raise),
       Stmt::Break(break_) => self.break_(break_),
       Stmt::Continue(continue_) => self.continue_(continue_),
       Stmt::While(while_) => self.while_(while_),
       Stmt::Try(try_) => self.try_(try_),
+      Stmt::With(with) => self.with(with),
     }
   }
 
@@ -839,6 +1065,10 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       Expr::Unary(unary) => self.unary(unary),
       Expr::Atom(atom) => self.atom(atom),
     }
+
+    if !self.assert_captures.is_empty() {
+      self.capture_assert(expr);
+    }
   }
 
   /// Compile a the base of an expression
@@ -859,7 +1089,9 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       Primary::Self_(token) => self.self_(token),
       Primary::Super(token) => self.super_(token, trailers),
       Primary::Lambda(fun) => self.lambda(fun),
+      Primary::Class(class) => self.class_expr(class),
       Primary::List(list) => self.list(list),
+      Primary::Tuple(tuple) => self.tuple(tuple),
       Primary::Map(map) => self.map(map),
     }
   }
@@ -868,24 +1100,36 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
   fn symbol(&mut self, symbol: &'a Symbol<'src>) {
     match symbol {
       Symbol::Class(class) => self.class(class),
+      Symbol::Extension(extension) => {
+        self.extension(extension);
+        0
+      },
       Symbol::Fun(fun) => self.fun(fun),
       Symbol::Let(let_) => self.let_(let_),
+      Symbol::LetTuple(let_tuple) => {
+        self.let_tuple(let_tuple);
+        0
+      }
+      Symbol::Const(const_) => self.const_(const_),
       _ => 0,
     };
   }
 
   /// Compile an export declaration
   fn export(&mut self, export: &'a Symbol<'src>) {
-    let symbol = match &export {
-      Symbol::Class(class) => Some(self.class(class)),
-      Symbol::Fun(fun) => Some(self.fun(fun)),
-      Symbol::Let(let_) => Some(self.let_(let_)),
-      _ => None,
+    let symbols = match &export {
+      Symbol::Class(class) => vec![self.class(class)],
+      Symbol::Fun(fun) => vec![self.fun(fun)],
+      Symbol::Let(let_) => vec![self.let_(let_)],
+      Symbol::LetTuple(let_tuple) => self.let_tuple(let_tuple),
+      Symbol::Const(const_) => vec![self.const_(const_)],
+      Symbol::Import(import) => self.import(import),
+      _ => vec![],
     };
 
     // emit error if not at module level
     if self.scope_depth == 0 {
-      if let Some(symbol) = symbol {
+      for symbol in symbols {
         self.emit_byte(AlignedByteCode::Export(symbol), export.end());
       }
     } else {
@@ -899,10 +1143,62 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     let name = &class.name;
     let name_constant = self.identifier_constant(name.str());
     self.declare_variable(&name);
+    self.declare_symbol(name, SymbolKind::Class);
 
     self.emit_byte(AlignedByteCode::Class(name_constant), name.end());
     self.define_variable(name_constant, name.end());
 
+    self.class_body(class, name);
+    name_constant
+  }
+
+  /// Compile a class expression leaving the class on the stack
+  fn class_expr(&mut self, class: &'a ast::Class<'src>) -> bool {
+    let name = &class.name;
+    let name_constant = self.identifier_constant(name.str());
+    self.emit_byte(AlignedByteCode::Class(name_constant), name.end());
+
+    // reserve the temporaries already on the stack, including the new
+    // class, so the class body's locals resolve to the correct slots
+    let reserved = self.reserve_temporaries();
+    let value = (self.local_count - 1) as u8;
+
+    self.begin_scope();
+
+    // copy the class into a local the body can load it from, named
+    // classes can also refer to themselves
+    let class_token = if name.kind() == TokenKind::Identifier {
+      name
+    } else {
+      let class_token = self.gc().manage(
+        Token::new(
+          TokenKind::Identifier,
+          Lexeme::Slice(CLASS_VAR),
+          name.start(),
+          name.end(),
+        ),
+        self,
+      );
+      self.temp_tokens.push(class_token);
+      unsafe { class_token.deref_static() }
+    };
+
+    self.emit_byte(AlignedByteCode::GetLocal(value), name.end());
+    self.add_local(class_token);
+    self.mark_initialized();
+
+    self.class_body(class, class_token);
+    self.end_scope(class.end());
+
+    self.local_count -= reserved;
+    self.locals.truncate(self.local_count);
+    false
+  }
+
+  /// Compile the super class, methods and fields of a class loaded from `class_ref`
+  fn class_body(&mut self, class: &'a ast::Class<'src>, class_ref: &'a Token<'src>) {
+    let name = &class.name;
+
     // set this class as the current class compiler
     let class_info_name = self.gc.borrow_mut().manage_str(name.str(), self);
     let class_compiler = self
@@ -939,7 +1235,7 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     self.add_local(unsafe { super_token.deref_static() });
 
     self.define_variable(0, span.end);
-    self.variable(&name, false);
+    self.variable(class_ref, false);
     self.emit_byte(AlignedByteCode::Inherit, span.end);
 
     // process the initializer
@@ -952,6 +1248,21 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
     self.emit_fields(field_line);
 
+    // a sealed class's vtable is its methods in declaration order
+    if class.sealed {
+      self.emit_byte(AlignedByteCode::Seal, field_line);
+      let mut class_info = self.class_info.expect("Current class unset");
+
+      for name in class
+        .methods
+        .iter()
+        .filter_map(|method| method.name.as_ref())
+      {
+        let method = self.gc.borrow_mut().manage_str(name.str(), self);
+        class_info.add_vtable_entry(&GcHooks::new(self), method);
+      }
+    }
+
     // process methods
     for method in &class.methods {
       self.method(&method, FunKind::Method);
@@ -969,7 +1280,34 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
     // restore the enclosing class compiler
     self.class_info = enclosing_class;
-    name_constant
+  }
+
+  /// Compile a class extension adding its methods to an existing class
+  fn extension(&mut self, extension: &'a ast::Extension<'src>) {
+    let class = &extension.class;
+
+    // methods compile as if they were in the class body
+    let class_info_name = self.gc.borrow_mut().manage_str(class.str(), self);
+    let mut class_compiler = self
+      .gc
+      .borrow_mut()
+      .manage(ClassInfo::new(class_info_name), self);
+    class_compiler.extension = true;
+    let enclosing_class = self.class_info.replace(class_compiler);
+
+    self.variable(class, false);
+    self.emit_byte(AlignedByteCode::Extend, class.end());
+
+    for method in &extension.methods {
+      self.method(method, FunKind::Method);
+    }
+
+    for static_method in &extension.static_methods {
+      self.static_method(static_method);
+    }
+
+    self.emit_byte(AlignedByteCode::Drop, extension.end());
+    self.class_info = enclosing_class;
   }
 
   /// Emit field instructions
@@ -1018,6 +1356,10 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       .map(|name| self.make_identifier(name))
       .expect("Expected function name");
 
+    if let Some(name) = &fun.name {
+      self.declare_symbol(name, SymbolKind::Function);
+    }
+
     self.mark_initialized();
     self.function(fun, FunKind::Fun);
     self.define_variable(constant, fun.end());
@@ -1028,6 +1370,7 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
   /// Compile a let binding
   fn let_(&mut self, let_: &'a ast::Let<'src>) -> u16 {
     self.declare_variable(&let_.name);
+    self.declare_symbol(&let_.name, SymbolKind::Variable);
     let variable = self.identifier_constant(let_.name.str());
 
     match &let_.value {
@@ -1039,6 +1382,88 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     variable
   }
 
+  /// Compile a module constant. Its value must be a literal so later
+  /// references can be inlined instead of looked up as a global
+  fn const_(&mut self, const_: &'a ast::Const<'src>) -> u16 {
+    let name = &const_.name;
+    self.declare_symbol(name, SymbolKind::Constant);
+    let variable = self.identifier_constant(name.str());
+
+    match self.const_value(&const_.value) {
+      Some(value) => {
+        self.emit_value(value, const_.value.end());
+        self.module_consts.insert(name.str().to_string(), value);
+      },
+      None => {
+        let error = diagnostics::error_at(
+          "Constant value must be a literal.",
+          self.file_id,
+          const_.value.span(),
+        );
+        self.errors.push(error);
+      },
+    }
+
+    self.define_variable(variable, const_.end());
+    variable
+  }
+
+  /// Evaluate a literal constant expression
+  fn const_value(&mut self, expr: &Expr<'src>) -> Option<Value> {
+    match expr {
+      Expr::Atom(atom) if atom.trailers.is_empty() => match &atom.primary {
+        Primary::True(_) => Some(val!(true)),
+        Primary::False(_) => Some(val!(false)),
+        Primary::Nil(_) => Some(VALUE_NIL),
+        Primary::Number(token) => Some(val!(
+          parse_number(token.str()).expect("Unable to parse float")
+        )),
+        Primary::String(token) => Some(val!(self.gc.borrow_mut().manage_str(token.str(), self))),
+        Primary::Grouping(expr) => self.const_value(expr),
+        _ => None,
+      },
+      Expr::Unary(unary) if matches!(unary.op, ast::UnaryOp::Negate) => self
+        .const_value(&unary.expr)
+        .filter(|value| value.is_num())
+        .map(|value| val!(-value.to_num())),
+      _ => None,
+    }
+  }
+
+  /// Compile a destructuring let binding, unpacking a tuple into
+  /// each of the named variables
+  fn let_tuple(&mut self, let_tuple: &'a ast::LetTuple<'src>) -> Vec<u16> {
+    let variables: Vec<u16> = let_tuple
+      .names
+      .iter()
+      .map(|name| {
+        self.declare_variable(name);
+        self.declare_symbol(name, SymbolKind::Variable);
+        self.identifier_constant(name.str())
+      })
+      .collect();
+
+    self.expr(&let_tuple.value);
+    self.emit_byte(
+      AlignedByteCode::Unpack(variables.len() as u16),
+      let_tuple.end(),
+    );
+
+    if self.scope_depth > 0 {
+      let count = variables.len().min(self.local_count);
+      for local in &mut self.locals[self.local_count - count..self.local_count] {
+        local.depth = self.scope_depth;
+      }
+    } else {
+      // the last item is on top of the stack so globals are defined in reverse
+      for variable in variables.iter().rev() {
+        self.emit_byte(AlignedByteCode::DefineGlobal(*variable), let_tuple.end());
+      }
+    }
+
+    variables
+  }
+
   /// Compile a function objects that presents, functions, methods
   /// and lambdas
   fn function(&mut self, fun: &'a ast::Fun<'src>, fun_kind: FunKind) {
@@ -1064,6 +1489,7 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
     // create a new child compiler for this function
     let mut compiler = Compiler::child(name, fun_kind, first_local, self);
+    compiler.fun.set_async(fun.is_async);
     compiler.begin_scope();
     compiler.call_sig(&fun.call_sig);
 
@@ -1097,8 +1523,9 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       .for_each(|upvalue| self.emit_byte(AlignedByteCode::UpvalueIndex(*upvalue), end_line));
   }
 
-  /// Compile an import statement
-  fn import(&mut self, import: &'a ast::Import<'src>) {
+  /// Compile an import statement returning the identifier
+  /// of each name it binds
+  fn import(&mut self, import: &'a ast::Import<'src>) -> Vec<u16> {
     // let name = self.identifier_constant(&import.imported.str());
     let mut list: GcObj<List<Value>> = self
       .gc
@@ -1106,12 +1533,12 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       .manage_obj(List::with_capacity(import.path.len()), self);
     self.gc.borrow_mut().push_root(list);
 
-    list.extend(
-      import
-        .path
-        .iter()
-        .map(|segment| val!(self.gc.borrow_mut().manage_str(segment.str(), self))),
-    );
+    // each segment is pushed as soon as it is allocated so it is traced
+    // through the list by any collection the next segment triggers
+    for segment in import.path.iter() {
+      let segment = self.gc.borrow_mut().manage_str(segment.str(), self);
+      list.push(val!(segment));
+    }
 
     let value = val!(list);
     let path = self.make_constant(value);
@@ -1119,15 +1546,22 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     match &import.stem {
       ast::ImportStem::None => {
         self.emit_byte(AlignedByteCode::Import(path), import.start());
-        let name = self.make_identifier(&import.path()[import.path().len() - 1]);
+        let last = &import.path()[import.path().len() - 1];
+        self.declare_symbol(last, SymbolKind::Import);
+        let name = self.make_identifier(last);
         self.emit_byte(AlignedByteCode::DefineGlobal(name), import.end());
+        vec![name]
       }
       ast::ImportStem::Rename(rename) => {
         self.emit_byte(AlignedByteCode::Import(path), import.start());
+        self.declare_symbol(rename, SymbolKind::Import);
         let name = self.make_identifier(&rename);
         self.emit_byte(AlignedByteCode::DefineGlobal(name), import.end());
+        vec![name]
       }
       ast::ImportStem::Symbols(symbols) => {
+        let mut names = Vec::with_capacity(symbols.len());
+
         for symbol in symbols {
           let symbol_slot = self.make_identifier(&symbol.symbol);
           self.emit_byte(
@@ -1136,12 +1570,21 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
           );
 
           let name = match &symbol.rename {
-            Some(rename) => self.make_identifier(rename),
-            None => symbol_slot,
+            Some(rename) => {
+              self.declare_symbol(rename, SymbolKind::Import);
+              self.make_identifier(rename)
+            }
+            None => {
+              self.declare_symbol(&symbol.symbol, SymbolKind::Import);
+              symbol_slot
+            }
           };
 
           self.emit_byte(AlignedByteCode::DefineGlobal(name), import.end());
+          names.push(name);
         }
+
+        names
       }
     }
   }
@@ -1234,6 +1677,240 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     });
   }
 
+  /// Compile a top level repl expression statement. If the value is not
+  /// nil it is bound to `_` and printed
+  fn repl_echo(&mut self, expr: &'a Expr<'src>) {
+    let offset = expr.end();
+    self.expr(expr);
+
+    self.emit_byte(AlignedByteCode::Dup, offset);
+    self.emit_byte(AlignedByteCode::Nil, offset);
+    self.emit_byte(AlignedByteCode::NotEqual, offset);
+    let nil_jump = self.emit_jump(AlignedByteCode::JumpIfFalse(0), offset);
+
+    let last = self.identifier_constant(REPL_LAST);
+    let print = self.identifier_constant(PRINT);
+    self.emit_byte(AlignedByteCode::DefineGlobal(last), offset);
+    self.emit_byte(AlignedByteCode::GetGlobal(print), offset);
+    self.emit_byte(AlignedByteCode::GetGlobal(last), offset);
+    self.emit_byte(AlignedByteCode::Call(1), offset);
+    self.emit_byte(AlignedByteCode::Drop, offset);
+    let end_jump = self.emit_jump(AlignedByteCode::Jump(0), offset);
+
+    self.patch_jump(nil_jump);
+    self.emit_byte(AlignedByteCode::Drop, offset);
+    self.patch_jump(end_jump);
+  }
+
+  /// Determine if this expression is a call to the global assert
+  /// function, returning the assert token and asserted value
+  fn assert_call(&self, expr: &'a Expr<'src>) -> Option<(&'a Token<'src>, &'a Expr<'src>)> {
+    let atom = match expr {
+      Expr::Atom(atom) => atom,
+      _ => return None,
+    };
+
+    let assert = match &atom.primary {
+      Primary::Ident(token) if token.str() == ASSERT => token,
+      _ => return None,
+    };
+
+    match atom.trailers.as_slice() {
+      [Trailer::Call(call)] if call.args.len() == 1 && !self.is_local(ASSERT) => {
+        Some((assert, &call.args[0]))
+      },
+      _ => None,
+    }
+  }
+
+  /// Is this name a local in this or any enclosing function
+  fn is_local(&self, name: &str) -> bool {
+    self.locals[..self.local_count]
+      .iter()
+      .any(|local| local.name.str() == name)
+      || self
+        .enclosing
+        .is_some_and(|enclosing| unsafe { enclosing.as_ref() }.is_local(name))
+  }
+
+  /// Compile an assert statement. The values of the asserted subexpressions
+  /// are stored in hidden locals so a failed assert can report them alongside
+  /// the asserted source
+  fn assert(&mut self, assert: &'a Token<'src>, value: &'a Expr<'src>, end: u32) {
+    let mut captured = vec![];
+    assert_captures(value, &mut captured);
+
+    // the asserted value itself is known to be false on failure
+    if captured.last().is_some_and(|last| ptr::eq(*last, value)) {
+      captured.pop();
+    }
+    captured.truncate(MAX_ASSERT_CAPTURES);
+
+    self.scope(end, |self_| {
+      let mut captures = Vec::with_capacity(captured.len());
+
+      // declare a hidden local for each captured value
+      for (idx, expr) in captured.into_iter().enumerate() {
+        let capture_token = self_.gc().manage(
+          Token::new(
+            TokenKind::Identifier,
+            Lexeme::Owned(format!("${}{}", ASSERT, idx)),
+            expr.start(),
+            expr.end(),
+          ),
+          self_,
+        );
+        self_.temp_tokens.push(capture_token);
+
+        self_.declare_variable(unsafe { capture_token.deref_static() });
+        self_.emit_byte(AlignedByteCode::Nil, expr.start());
+        self_.mark_initialized();
+
+        let slot = self_
+          .resolve_local(&capture_token)
+          .expect("Assert capture was not defined.");
+        captures.push(AssertCapture { expr, slot });
+      }
+
+      // evaluate the asserted value storing each captured value
+      self_.variable(assert, false);
+      let enclosing_captures = mem::replace(&mut self_.assert_captures, captures);
+      self_.expr(value);
+      let captures = mem::replace(&mut self_.assert_captures, enclosing_captures);
+
+      let value_line = value.end();
+      self_.emit_byte(AlignedByteCode::Dup, value_line);
+      let fail_jump = self_.emit_jump(AlignedByteCode::JumpIfFalse(0), value_line);
+      let fail_slots = self_.slots;
+
+      let call_span = Span {
+        start: assert.start(),
+        end,
+      };
+      self_.emit_span(AlignedByteCode::Call(1), call_span);
+      let end_jump = self_.emit_jump(AlignedByteCode::Jump(0), end);
+
+      // on failure call assert again with a message describing the values
+      self_.patch_jump(fail_jump);
+      self_.slots = fail_slots;
+      self_.emit_byte(AlignedByteCode::Drop, value_line);
+      self_.emit_byte(AlignedByteCode::False, value_line);
+      self_.assert_message(value, &captures, value_line);
+      self_.emit_span(AlignedByteCode::Call(2), call_span);
+
+      self_.patch_jump(end_jump);
+      self_.emit_byte(AlignedByteCode::Drop, end);
+    });
+  }
+
+  /// Store the value of an expression if it is captured by the current assert
+  fn capture_assert(&mut self, expr: &Expr<'src>) {
+    let slot = self
+      .assert_captures
+      .iter()
+      .find(|capture| ptr::eq(capture.expr, expr))
+      .map(|capture| capture.slot);
+
+    if let Some(slot) = slot {
+      self.emit_byte(AlignedByteCode::SetLocal(slot), expr.end());
+    }
+  }
+
+  /// Emit the message of a failed assert, the asserted source
+  /// followed by the value of each captured subexpression
+  fn assert_message(
+    &mut self,
+    value: &Expr<'src>,
+    captures: &[AssertCapture<'a, 'src>],
+    line: u32,
+  ) {
+    const STR: &str = "str";
+
+    let source = self.expr_text(value);
+    if captures.is_empty() {
+      let message = val!(self.gc.borrow_mut().manage_str(source, self));
+      self.emit_constant(message, line);
+      return;
+    }
+
+    let str_constant = self.string_constant(STR);
+    for (idx, capture) in captures.iter().enumerate() {
+      let prefix = if idx == 0 { source } else { "" };
+      let label = format!("{}\n  {} = ", prefix, self.expr_text(capture.expr));
+      let label = val!(self.gc.borrow_mut().manage_str(label, self));
+
+      self.emit_constant(label, line);
+      self.emit_byte(AlignedByteCode::GetLocal(capture.slot), line);
+      self.emit_byte(AlignedByteCode::Invoke((str_constant, 0)), line);
+      self.emit_byte(AlignedByteCode::Slot(self.emit_invoke_id()), line);
+    }
+
+    self.emit_byte(
+      AlignedByteCode::Interpolate((captures.len() * 2) as u16),
+      line,
+    );
+  }
+
+  /// The source text of an expression. The ast spans omit unary operators,
+  /// grouping parentheses and closing brackets so these are recovered
+  /// from the source
+  fn expr_text(&self, expr: &Expr<'src>) -> &'src str {
+    &self.source.content()[self.expr_start(expr)..self.expr_end(expr)]
+  }
+
+  /// The start of an expression in the source
+  fn expr_start(&self, expr: &Expr<'src>) -> usize {
+    let content = self.source.content();
+
+    let (start, prefix) = match expr {
+      Expr::Binary(binary) => return self.expr_start(&binary.lhs),
+      Expr::Unary(unary) => {
+        let prefix = match unary.op {
+          ast::UnaryOp::Not => "!",
+          ast::UnaryOp::Negate => "-",
+          ast::UnaryOp::Await => "await",
+        };
+        (self.expr_start(&unary.expr), prefix)
+      },
+      Expr::Atom(atom) => match &atom.primary {
+        Primary::Grouping(inner) => (self.expr_start(inner), "("),
+        _ => return atom.start() as usize,
+      },
+      _ => return expr.start() as usize,
+    };
+
+    let before = content[..start].trim_end();
+    if before.ends_with(prefix) {
+      before.len() - prefix.len()
+    } else {
+      start
+    }
+  }
+
+  /// The end of an expression in the source
+  fn expr_end(&self, expr: &Expr<'src>) -> usize {
+    let content = self.source.content();
+
+    let (end, suffix) = match expr {
+      Expr::Binary(binary) => return self.expr_end(&binary.rhs),
+      Expr::Unary(unary) => return self.expr_end(&unary.expr),
+      Expr::Atom(atom) => match (&atom.primary, atom.trailers.last()) {
+        (Primary::Grouping(inner), None) => (self.expr_end(inner), ')'),
+        (_, Some(Trailer::Index(_))) => (atom.end() as usize, ']'),
+        _ => return atom.end() as usize,
+      },
+      _ => return expr.end() as usize,
+    };
+
+    let after = &content[end..];
+    let trimmed = after.trim_start();
+    if trimmed.starts_with(suffix) {
+      end + (after.len() - trimmed.len()) + 1
+    } else {
+      end
+    }
+  }
+
   /// Compile a while loop
   fn while_(&mut self, while_: &'a ast::While<'src>) {
     let loop_start = self.current_chunk().instructions().len();
@@ -1302,9 +1979,31 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     match &return_.value {
       Some(v) => {
         self.expr(&v);
+        self.exit_withs(0, v.end());
         self.emit_byte(AlignedByteCode::Return, v.end());
       }
-      None => self.emit_return(return_.start()),
+      None => {
+        self.exit_withs(0, return_.start());
+        self.emit_return(return_.start());
+      }
+    }
+    self.exit_scope = ScopeExit::Early;
+  }
+
+  /// Compile a raise statement
+  fn raise(&mut self, raise: &'a ast::Raise<'src>) {
+    self.expr(&raise.error);
+
+    let span = Span {
+      start: raise.start(),
+      end: raise.end(),
+    };
+    match &raise.cause {
+      Some(cause) => {
+        self.expr(cause);
+        self.emit_span(AlignedByteCode::RaiseFrom, span);
+      },
+      None => self.emit_span(AlignedByteCode::Raise, span),
     }
     self.exit_scope = ScopeExit::Early;
   }
@@ -1315,8 +2014,13 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       .loop_info
       .expect("Parser should have caught the loop constraint");
 
+    // the locals are only dropped on this path so the enclosing
+    // scopes still account for them
+    let slots = self.slots;
+    self.exit_withs(loop_info.scope_depth, continue_.start());
     self.drop_locals(continue_.end(), loop_info.scope_depth);
     self.emit_loop(loop_info.start, continue_.start());
+    self.slots = slots;
     self.exit_scope = ScopeExit::Early;
   }
 
@@ -1326,8 +2030,11 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       .loop_info
       .expect("Parser should have caught the loop constraint");
 
+    let slots = self.slots;
+    self.exit_withs(loop_info.scope_depth, break_.start());
     self.drop_locals(break_.end(), loop_info.scope_depth);
     let offset = self.emit_jump(AlignedByteCode::Jump(0), break_.start());
+    self.slots = slots;
     loop_info.breaks.push(offset);
     self.exit_scope = ScopeExit::Early;
   }
@@ -1335,21 +2042,138 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
   /// Compile a try catch block
   fn try_(&mut self, try_: &'a ast::Try<'src>) {
     let start = self.current_chunk().instructions().len();
+    let slots = self.local_count;
 
     self.scope(try_.block.end(), |self_| self_.block(&try_.block));
 
     let catch_jump = self.emit_jump(AlignedByteCode::Jump(0), try_.block.end());
     let end = self.current_chunk().instructions().len();
 
-    self.scope(try_.catch.end(), |self_| self_.block(&try_.catch));
+    // the vm pushes the caught error before entering the catch block
+    self.slots = slots as i32 + 1;
+    self.fun.update_max_slots(self.slots);
+
+    self.scope(try_.catch.end(), |self_| {
+      match &try_.error {
+        Some(error) => {
+          self_.declare_variable(error);
+          self_.mark_initialized();
+        },
+        None => self_.emit_byte(AlignedByteCode::Drop, try_.catch.start()),
+      }
+
+      self_.block(&try_.catch)
+    });
 
     self.patch_jump(catch_jump);
-    self.fun.add_try(TryBlock::new(start as u16, end as u16));
+    self
+      .fun
+      .add_try(TryBlock::new(start as u16, end as u16, slots as u16));
   }
 
-  /// Compile a block
-  fn block(&mut self, block: &'a ast::Block<'src>) -> ScopeExit {
-    for decl in &block.decls {
+  /// Compile a with statement. The context manager's enter method is called
+  /// before the body and its exit method after it, receiving nil or the error
+  /// raised in the body. An error raised in the body is raised again after exit
+  fn with(&mut self, with: &'a ast::With<'src>) {
+    self.scope(with.end(), |self_| {
+      let line = with.manager.end();
+      self_.expr(&with.manager);
+
+      // token for hidden $with variable
+      let manager_token = self_.gc().manage(
+        Token::new(
+          TokenKind::Identifier,
+          Lexeme::Slice(WITH_VAR),
+          with.manager.start(),
+          with.manager.end(),
+        ),
+        self_,
+      );
+      self_.temp_tokens.push(manager_token);
+
+      // declare the hidden local $with variable
+      self_.declare_variable(unsafe { manager_token.deref_static() });
+      self_.mark_initialized();
+      let manager = self_
+        .resolve_local(&manager_token)
+        .expect("Context manager variable was not defined.");
+
+      let enter_const = self_.string_constant(ENTER);
+      self_.emit_byte(AlignedByteCode::GetLocal(manager), line);
+      self_.emit_byte(AlignedByteCode::Invoke((enter_const, 0)), line);
+      self_.emit_byte(AlignedByteCode::Slot(self_.emit_invoke_id()), line);
+
+      match &with.name {
+        Some(name) => {
+          self_.declare_variable(name);
+          self_.mark_initialized();
+        },
+        None => self_.emit_byte(AlignedByteCode::Drop, line),
+      }
+
+      let start = self_.current_chunk().instructions().len();
+      let slots = self_.local_count;
+
+      self_.with_info.push(WithInfo {
+        slot: manager,
+        scope_depth: self_.scope_depth,
+      });
+      self_.scope(with.body.end(), |self_| self_.block(&with.body));
+      self_.with_info.pop();
+
+      let exit_jump = self_.emit_jump(AlignedByteCode::Jump(0), with.body.end());
+      let end = self_.current_chunk().instructions().len();
+
+      // the vm pushes the caught error into the next slot, which is
+      // passed to exit then raised again
+      self_.slots = slots as i32 + 1;
+      self_.fun.update_max_slots(self_.slots);
+
+      let error = slots as u8;
+      self_.emit_exit(manager, AlignedByteCode::GetLocal(error), with.body.end());
+      self_.emit_byte(AlignedByteCode::GetLocal(error), with.body.end());
+      self_.emit_byte(AlignedByteCode::Raise, with.body.end());
+
+      self_.patch_jump(exit_jump);
+      self_.slots = slots as i32;
+      self_.emit_exit(manager, AlignedByteCode::Nil, with.body.end());
+
+      self_
+        .fun
+        .add_try(TryBlock::new(start as u16, end as u16, slots as u16));
+    });
+  }
+
+  /// Call exit on the context manager in the provided slot with
+  /// the value pushed by the provided instruction
+  fn emit_exit(&mut self, manager: u8, error: AlignedByteCode, line: u32) {
+    let exit_const = self.string_constant(EXIT);
+    self.emit_byte(AlignedByteCode::GetLocal(manager), line);
+    self.emit_byte(error, line);
+    self.emit_byte(AlignedByteCode::Invoke((exit_const, 1)), line);
+    self.emit_byte(AlignedByteCode::Slot(self.emit_invoke_id()), line);
+    self.emit_byte(AlignedByteCode::Drop, line);
+  }
+
+  /// Exit the context managers of any with statements that an early
+  /// exit to the provided scope depth leaves, innermost first
+  fn exit_withs(&mut self, scope_depth: i16, line: u32) {
+    let managers: Vec<u8> = self
+      .with_info
+      .iter()
+      .rev()
+      .take_while(|with| with.scope_depth > scope_depth)
+      .map(|with| with.slot)
+      .collect();
+
+    for manager in managers {
+      self.emit_exit(manager, AlignedByteCode::Nil, line);
+    }
+  }
+
+  /// Compile a block
+  fn block(&mut self, block: &'a ast::Block<'src>) -> ScopeExit {
+    for decl in &block.decls {
       if let ScopeExit::Early = self.exit_scope {
         break;
       }
@@ -1367,7 +2191,11 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
         // set instruction
         Some(last) => {
           let skip_first = self.primary(&atom.primary, &atom.trailers);
-          self.apply_trailers(skip_first, &atom.trailers[..atom.trailers.len() - 1]);
+          self.apply_trailers(
+            skip_first,
+            atom.start(),
+            &atom.trailers[..atom.trailers.len() - 1],
+          );
 
           match last {
             Trailer::Index(index) => {
@@ -1436,7 +2264,11 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
         // set instruction
         Some(last) => {
           let skip_first = self.primary(&atom.primary, &atom.trailers);
-          self.apply_trailers(skip_first, &atom.trailers[..atom.trailers.len() - 1]);
+          self.apply_trailers(
+            skip_first,
+            atom.start(),
+            &atom.trailers[..atom.trailers.len() - 1],
+          );
 
           match last {
             Trailer::Index(index) => {
@@ -1509,6 +2341,10 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
   /// Compile a binary expression
   fn binary(&mut self, binary: &'a ast::Binary<'src>) {
+    if self.const_equality(binary) {
+      return;
+    }
+
     self.expr(&binary.lhs);
 
     // emit for rhs if we're not an "and" or "or"
@@ -1519,16 +2355,16 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
     // emit for binary operation
     match &binary.op {
-      ast::BinaryOp::Add => self.emit_byte(AlignedByteCode::Add, binary.rhs.end()),
-      ast::BinaryOp::Sub => self.emit_byte(AlignedByteCode::Subtract, binary.rhs.end()),
-      ast::BinaryOp::Mul => self.emit_byte(AlignedByteCode::Multiply, binary.rhs.end()),
-      ast::BinaryOp::Div => self.emit_byte(AlignedByteCode::Divide, binary.rhs.end()),
-      ast::BinaryOp::Lt => self.emit_byte(AlignedByteCode::Less, binary.rhs.end()),
-      ast::BinaryOp::LtEq => self.emit_byte(AlignedByteCode::LessEqual, binary.rhs.end()),
-      ast::BinaryOp::Gt => self.emit_byte(AlignedByteCode::Greater, binary.rhs.end()),
-      ast::BinaryOp::GtEq => self.emit_byte(AlignedByteCode::GreaterEqual, binary.rhs.end()),
-      ast::BinaryOp::Eq => self.emit_byte(AlignedByteCode::Equal, binary.rhs.end()),
-      ast::BinaryOp::Ne => self.emit_byte(AlignedByteCode::NotEqual, binary.rhs.end()),
+      ast::BinaryOp::Add => self.emit_span(AlignedByteCode::Add, binary.span()),
+      ast::BinaryOp::Sub => self.emit_span(AlignedByteCode::Subtract, binary.span()),
+      ast::BinaryOp::Mul => self.emit_span(AlignedByteCode::Multiply, binary.span()),
+      ast::BinaryOp::Div => self.emit_span(AlignedByteCode::Divide, binary.span()),
+      ast::BinaryOp::Lt => self.emit_span(AlignedByteCode::Less, binary.span()),
+      ast::BinaryOp::LtEq => self.emit_span(AlignedByteCode::LessEqual, binary.span()),
+      ast::BinaryOp::Gt => self.emit_span(AlignedByteCode::Greater, binary.span()),
+      ast::BinaryOp::GtEq => self.emit_span(AlignedByteCode::GreaterEqual, binary.span()),
+      ast::BinaryOp::Eq => self.emit_span(AlignedByteCode::Equal, binary.span()),
+      ast::BinaryOp::Ne => self.emit_span(AlignedByteCode::NotEqual, binary.span()),
       ast::BinaryOp::And => {
         let and_jump = self.emit_jump(AlignedByteCode::And(0), binary.lhs.end());
         self.expr(&binary.rhs);
@@ -1542,39 +2378,75 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     }
   }
 
+  /// Compile an equality check against a string literal into a single
+  /// comparison with the constant. Returns false if this binary
+  /// expression is not such a check
+  fn const_equality(&mut self, binary: &'a ast::Binary<'src>) -> bool {
+    match binary.op {
+      ast::BinaryOp::Eq | ast::BinaryOp::Ne => (),
+      _ => return false,
+    }
+
+    // a literal has no side effects so either operand may be the constant
+    let (expr, literal) = match (string_literal(&binary.lhs), string_literal(&binary.rhs)) {
+      (_, Some(literal)) => (&binary.lhs, literal),
+      (Some(literal), None) => (&binary.rhs, literal),
+      (None, None) => return false,
+    };
+
+    self.expr(expr);
+    let constant = self.string_constant(literal.str());
+
+    match binary.op {
+      ast::BinaryOp::Eq => self.emit_span(AlignedByteCode::EqualConst(constant), binary.span()),
+      _ => self.emit_span(AlignedByteCode::NotEqualConst(constant), binary.span()),
+    }
+
+    true
+  }
+
   /// Compile a unary expression
   fn unary(&mut self, unary: &'a ast::Unary<'src>) {
     self.expr(&unary.expr);
 
     match &unary.op {
       ast::UnaryOp::Not => self.emit_byte(AlignedByteCode::Not, unary.expr.end()),
-      ast::UnaryOp::Negate => self.emit_byte(AlignedByteCode::Negate, unary.expr.end()),
+      ast::UnaryOp::Negate => self.emit_span(AlignedByteCode::Negate, unary.span()),
+      ast::UnaryOp::Await => self.emit_span(AlignedByteCode::Await, unary.span()),
     }
   }
 
   /// Compile a call expression
-  fn call(&mut self, call: &'a ast::Call<'src>) -> bool {
+  fn call(&mut self, call: &'a ast::Call<'src>, start: u32) -> bool {
     for expr in &call.args {
       self.expr(expr);
     }
 
-    self.emit_byte(AlignedByteCode::Call(call.args.len() as u8), call.end());
+    let span = Span {
+      start,
+      end: call.end(),
+    };
+    self.emit_span(AlignedByteCode::Call(call.args.len() as u8), span);
     false
   }
 
   /// Compile an indexing expression
-  fn index(&mut self, index: &'a ast::Index<'src>) -> bool {
+  fn index(&mut self, index: &'a ast::Index<'src>, start: u32) -> bool {
     self.expr(&index.index);
 
+    let span = Span {
+      start,
+      end: index.end(),
+    };
     let name = self.identifier_constant(INDEX_GET);
-    self.emit_byte(AlignedByteCode::Invoke((name, 1)), index.end());
-    self.emit_byte(AlignedByteCode::Slot(self.emit_invoke_id()), index.end());
+    self.emit_span(AlignedByteCode::Invoke((name, 1)), span);
+    self.emit_span(AlignedByteCode::Slot(self.emit_invoke_id()), span);
 
     false
   }
 
   /// Compile an access expression
-  fn access(&mut self, access: &ast::Access, trailers: &'a [Trailer<'src>]) -> bool {
+  fn access(&mut self, access: &ast::Access, start: u32, trailers: &'a [Trailer<'src>]) -> bool {
     let name = self.identifier_constant(access.prop.str());
 
     match trailers.first() {
@@ -1583,34 +2455,91 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
           for expr in &call.args {
             self.expr(expr);
           }
-          self.emit_byte(
-            AlignedByteCode::Invoke((name, call.args.len() as u8)),
-            trailer.end(),
-          );
-          self.emit_byte(AlignedByteCode::Slot(self.emit_invoke_id()), trailer.end());
+
+          let span = Span {
+            start,
+            end: trailer.end(),
+          };
+          self.emit_span(AlignedByteCode::Invoke((name, call.args.len() as u8)), span);
+          self.emit_span(AlignedByteCode::Slot(self.emit_invoke_id()), span);
           true
         } else {
-          self.emit_byte(AlignedByteCode::GetProperty(name), access.prop.end());
-          self.emit_byte(AlignedByteCode::Slot(self.emit_property_id()), access.end());
+          self.get_property(name, access, start);
           false
         }
       }
       None => {
-        self.emit_byte(AlignedByteCode::GetProperty(name), access.prop.end());
-        self.emit_byte(AlignedByteCode::Slot(self.emit_property_id()), access.end());
+        self.get_property(name, access, start);
         false
       }
     }
   }
 
+  /// The vtable index of a method called on 'self' if the current
+  /// class is sealed and declares it
+  fn sealed_index(&self, access: &ast::Access) -> Option<usize> {
+    let class_info = self.class_info?;
+    match class_info.fun_kind {
+      Some(FunKind::Method) | Some(FunKind::Initializer) => {
+        class_info.vtable_index(access.prop.str())
+      },
+      _ => None,
+    }
+  }
+
+  /// Compile a devirtualized method invoke on a sealed class
+  fn invoke_sealed(
+    &mut self,
+    access: &ast::Access,
+    call: &'a ast::Call<'src>,
+    index: usize,
+    start: u32,
+  ) {
+    let name = self.identifier_constant(access.prop.str());
+    for expr in &call.args {
+      self.expr(expr);
+    }
+
+    let span = Span {
+      start,
+      end: call.end(),
+    };
+    self.emit_span(
+      AlignedByteCode::InvokeSealed((name, call.args.len() as u8)),
+      span,
+    );
+    self.emit_span(AlignedByteCode::Slot(index as u32), span);
+  }
+
+  /// Compile a property lookup
+  fn get_property(&mut self, name: u16, access: &ast::Access, start: u32) {
+    let span = Span {
+      start,
+      end: access.end(),
+    };
+    self.emit_span(AlignedByteCode::GetProperty(name), span);
+    self.emit_span(AlignedByteCode::Slot(self.emit_property_id()), span);
+  }
+
   /// Compile an atom expression
   fn atom(&mut self, atom: &'a ast::Atom<'src>) {
+    if let Primary::Self_(self_) = &atom.primary {
+      if let [Trailer::Access(access), Trailer::Call(call), rest @ ..] = &atom.trailers[..] {
+        if let Some(index) = self.sealed_index(access) {
+          self.self_(self_);
+          self.invoke_sealed(access, call, index, atom.start());
+          self.apply_trailers(false, atom.start(), rest);
+          return;
+        }
+      }
+    }
+
     let skip_first = self.primary(&atom.primary, &atom.trailers);
-    self.apply_trailers(skip_first, &atom.trailers);
+    self.apply_trailers(skip_first, atom.start(), &atom.trailers);
   }
 
-  /// Compile trailers onto a base primary
-  fn apply_trailers(&mut self, skip_first: bool, trailers: &'a [Trailer<'src>]) {
+  /// Compile trailers onto a base primary starting at the provided offset
+  fn apply_trailers(&mut self, skip_first: bool, start: u32, trailers: &'a [Trailer<'src>]) {
     let mut skip = skip_first;
     for (idx, trailer) in trailers.iter().enumerate() {
       if skip {
@@ -1619,22 +2548,63 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       }
 
       skip = match trailer {
-        Trailer::Call(call) => self.call(&call),
-        Trailer::Index(index) => self.index(&index),
-        Trailer::Access(access) => self.access(&access, &trailers[(idx + 1)..]),
+        Trailer::Call(call) => self.call(&call, start),
+        Trailer::Index(index) => self.index(&index, start),
+        Trailer::Access(access) => self.access(&access, start, &trailers[(idx + 1)..]),
       }
     }
   }
 
-  /// Compile an assignment block
+  /// Compile an assignment block. The block evaluates to its trailing
+  /// expression or nil if it does not end in one
   fn assign_block(&mut self, block: &'a ast::Block<'src>) -> bool {
+    self.emit_byte(AlignedByteCode::Nil, block.start());
+
+    // reserve the temporaries already on the stack, including the block's
+    // value, so locals declared in the block resolve to the correct slots
+    let reserved = self.reserve_temporaries();
+    let value = (self.local_count - 1) as u8;
+
     self.scope(block.end(), |self_| {
-      self_.block(block);
+      for decl in &block.decls {
+        if let ScopeExit::Early = self_.exit_scope {
+          break;
+        }
+
+        if let Decl::Stmt(stmt) = decl {
+          if let Stmt::ImplicitReturn(expr) = &**stmt {
+            self_.expr(expr);
+            self_.emit_byte(AlignedByteCode::SetLocal(value), expr.end());
+            self_.emit_byte(AlignedByteCode::Drop, expr.end());
+            continue;
+          }
+        }
+
+        self_.decl(decl);
+      }
+
+      self_.exit_scope = ScopeExit::Normal;
     });
-    self.emit_byte(AlignedByteCode::Nil, block.end());
+
+    self.local_count -= reserved;
+    self.locals.truncate(self.local_count);
     false
   }
 
+  /// Add a placeholder local for each temporary on the stack above the
+  /// current locals, returning the number of placeholders added
+  fn reserve_temporaries(&mut self) -> usize {
+    let local_count = self.local_count;
+    let temporaries = (self.slots.max(0) as usize).saturating_sub(local_count);
+
+    for _ in 0..temporaries {
+      self.add_local(UNINITIALIZED_TOKEN);
+      self.mark_initialized();
+    }
+
+    self.local_count - local_count
+  }
+
   /// Compile a true token
   fn true_(&mut self, true_: &Token) -> bool {
     self.emit_byte(AlignedByteCode::True, true_.end());
@@ -1655,7 +2625,7 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
   /// Compile a number token
   fn number(&mut self, token: &Token) -> bool {
-    let value = val!(token.str().parse::<f64>().expect("Unable to parse float"));
+    let value = val!(parse_number(token.str()).expect("Unable to parse float"));
     self.emit_constant(value, token.end());
     false
   }
@@ -1738,11 +2708,16 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
 
   /// Compile the super token
   fn super_(&mut self, super_: &ast::Super<'src>, trailers: &'a [Trailer<'src>]) -> bool {
-    if self.class_info.is_none() {
-      self.error(
+    match self.class_info {
+      Some(class_info) if class_info.extension => self.error(
+        "Cannot use 'super' in a class extension.",
+        Some(&super_.super_),
+      ),
+      Some(_) => (),
+      None => self.error(
         "Cannot use 'super' outside of a class.",
         Some(&super_.super_),
-      );
+      ),
     }
 
     let name = self.identifier_constant(super_.access.str());
@@ -1808,6 +2783,20 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
     false
   }
 
+  /// Compile a tuple literal
+  fn tuple(&mut self, tuple: &'a ast::Tuple<'src>) -> bool {
+    for item in tuple.items.iter() {
+      self.expr(item);
+    }
+
+    self.emit_byte(
+      AlignedByteCode::Tuple(tuple.items.len() as u16),
+      tuple.end(),
+    );
+
+    false
+  }
+
   /// Compile a map literal
   fn map(&mut self, map: &'a ast::Map<'src>) -> bool {
     for (key, value) in map.entries.iter() {
@@ -1827,6 +2816,9 @@ impl<'a, 'src: 'a, FileId: Copy> Compiler<'a, 'src, FileId> {
       self.define_variable(param_constant, param.name.end());
     }
 
+    // the arguments are already on the stack when the function is called
+    self.slots += call_sig.params.len() as i32;
+
     self
       .fun
       .set_arity(Arity::Fixed(call_sig.params.len() as u8))
@@ -1862,6 +2854,9 @@ impl<'a, 'src: 'a, FileId> TraceRoot for Compiler<'a, 'src, FileId> {
     self.constants.keys().for_each(|key| {
       key.trace();
     });
+    self.module_consts.values().for_each(|value| {
+      value.trace();
+    });
     self.temp_tokens.iter().for_each(|token| token.trace());
   }
 
@@ -1884,6 +2879,9 @@ impl<'a, 'src: 'a, FileId> TraceRoot for Compiler<'a, 'src, FileId> {
     self.constants.keys().for_each(|key| {
       key.trace_debug(log);
     });
+    self.module_consts.values().for_each(|value| {
+      value.trace_debug(log);
+    });
     self
       .temp_tokens
       .iter()
@@ -1895,10 +2893,70 @@ impl<'a, 'src: 'a, FileId> TraceRoot for Compiler<'a, 'src, FileId> {
   }
 }
 
+/// The token of an expression that is only a string literal
+fn string_literal<'a, 'src>(expr: &'a Expr<'src>) -> Option<&'a Token<'src>> {
+  match expr {
+    Expr::Atom(atom) if atom.trailers.is_empty() => match &atom.primary {
+      Primary::String(token) => Some(token),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// Collect the subexpressions of an assert whose values are reported on
+/// failure in the order they are evaluated. Literals are not captured
+/// and the right hand side of `and` and `or` may not be evaluated
+fn assert_captures<'a, 'src>(expr: &'a Expr<'src>, captures: &mut Vec<&'a Expr<'src>>) {
+  match expr {
+    Expr::Binary(binary) => {
+      assert_captures(&binary.lhs, captures);
+
+      match binary.op {
+        ast::BinaryOp::And | ast::BinaryOp::Or => (),
+        _ => assert_captures(&binary.rhs, captures),
+      }
+    },
+    Expr::Unary(unary) => {
+      assert_captures(&unary.expr, captures);
+      return;
+    },
+    Expr::Atom(atom) => {
+      let literal = match &atom.primary {
+        Primary::Grouping(inner) => {
+          assert_captures(inner, captures);
+          true
+        },
+        Primary::Ident(_) | Primary::Self_(_) | Primary::Super(_) => false,
+        _ => true,
+      };
+
+      for trailer in &atom.trailers {
+        match trailer {
+          Trailer::Call(call) => call
+            .args
+            .iter()
+            .for_each(|arg| assert_captures(arg, captures)),
+          Trailer::Index(index) => assert_captures(&index.index, captures),
+          Trailer::Access(_) => (),
+        }
+      }
+
+      if literal && atom.trailers.is_empty() {
+        return;
+      }
+    },
+    Expr::Assign(_) | Expr::AssignBinary(_) => return,
+  }
+
+  captures.push(expr);
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
   use crate::{
+    arena::Arena,
     byte_code::{decode_u16, decode_u32},
     compiler::Parser,
     debug::disassemble_chunk,
@@ -1944,10 +3002,23 @@ mod test {
   }
 
   fn test_compile(src: &str, context: &NoContext) -> Fun {
+    compile_with(src, context, false, false)
+  }
+
+  fn test_compile_optimized(src: &str, context: &NoContext) -> Fun {
+    compile_with(src, context, true, false)
+  }
+
+  fn test_compile_repl(src: &str, context: &NoContext) -> Fun {
+    compile_with(src, context, false, true)
+  }
+
+  fn compile_with(src: &str, context: &NoContext, optimize: bool, repl: bool) -> Fun {
     let hooks = &GcHooks::new(context);
 
-    let src = Source::new(hooks.manage_str(src));
-    let (ast, line_offsets) = Parser::new(&src, 0).parse();
+    let src = Source::new(src);
+    let arena = Arena::for_source(&src);
+    let (ast, line_offsets) = Parser::new(&src, &arena, 0).with_repl(repl).parse();
     assert!(ast.is_ok());
     let ast = ast.unwrap();
 
@@ -1959,7 +3030,9 @@ mod test {
     let gc = context.gc.replace(Allocator::default());
 
     let stuff: &NoGc = &NO_GC;
-    let compiler = Compiler::new(module, &ast, &line_offsets, 0, stuff, gc);
+    let compiler = Compiler::new(module, &ast, &src, &line_offsets, 0, stuff, gc)
+      .with_optimize(optimize)
+      .with_repl(repl);
     #[cfg(feature = "debug")]
     let compiler = compiler.with_io(io_native());
 
@@ -1986,7 +3059,8 @@ mod test {
         AlignedByteCode::GetProperty(_)
         | AlignedByteCode::SetProperty(_)
         | AlignedByteCode::Invoke(_)
-        | AlignedByteCode::SuperInvoke(_) => {
+        | AlignedByteCode::SuperInvoke(_)
+        | AlignedByteCode::InvokeSealed(_) => {
           decoded.push(byte_code);
           offset = decode_byte_code_slot(fun, &mut decoded, new_offset)
         }
@@ -2102,6 +3176,92 @@ mod test {
     );
   }
 
+  #[test]
+  fn repl_echo() {
+    let example = "
+      1;
+      if true { 2; }
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile_repl(example, &context);
+
+    assert_simple_bytecode(
+      &fun,
+      4,
+      &vec![
+        AlignedByteCode::Constant(0),
+        AlignedByteCode::Dup,
+        AlignedByteCode::Nil,
+        AlignedByteCode::NotEqual,
+        AlignedByteCode::JumpIfFalse(15),
+        AlignedByteCode::DefineGlobal(1),
+        AlignedByteCode::GetGlobal(2),
+        AlignedByteCode::GetGlobal(1),
+        AlignedByteCode::Call(1),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Jump(1),
+        AlignedByteCode::Drop,
+        AlignedByteCode::True,
+        AlignedByteCode::JumpIfFalse(3),
+        AlignedByteCode::Constant(3),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
+  #[test]
+  fn symbols() {
+    let example = "import std.math;\nlet a = 1;\nfn b(c) { let d = a; return d; }\nclass E {}\nb(a);";
+
+    let context = NoContext::default();
+    let hooks = &GcHooks::new(&context);
+
+    let src = Source::new(example);
+    let arena = Arena::for_source(&src);
+    let (ast, line_offsets) = Parser::new(&src, &arena, 0).parse();
+    let ast = ast.expect("Expected example to parse");
+
+    let path = PathBuf::from("path/module.ly");
+    let module_class = test_class(hooks, "Module");
+    let module = hooks.manage(Module::from_path(&hooks, path, module_class, 0).unwrap());
+
+    let symbols = Rc::new(RefCell::new(SymbolTable::default()));
+    let gc = context.gc.replace(Allocator::default());
+    let compiler = Compiler::new(module, &ast, &src, &line_offsets, 0, &NO_GC, gc)
+      .with_symbols(Rc::clone(&symbols));
+
+    let (result, gc, _) = compiler.compile();
+    context.gc.replace(gc);
+    assert!(result.is_ok());
+
+    let symbols = symbols.borrow();
+    let declared = symbols
+      .symbols()
+      .iter()
+      .map(|symbol| (symbol.name.as_str(), symbol.kind))
+      .collect::<Vec<(&str, SymbolKind)>>();
+    assert_eq!(
+      declared,
+      vec![
+        ("math", SymbolKind::Import),
+        ("a", SymbolKind::Variable),
+        ("b", SymbolKind::Function),
+        ("E", SymbolKind::Class),
+      ]
+    );
+
+    // the reference to a inside of b resolves to the module variable
+    let reference = example.find("= a").unwrap() as u32 + 2;
+    assert_eq!(symbols.definition(reference).unwrap().span.start, 21);
+    assert_eq!(symbols.definition(example.rfind("b(").unwrap() as u32).unwrap().name, "b");
+
+    // locals are not module symbols
+    assert!(symbols.definition(example.find("d =").unwrap() as u32).is_none());
+  }
+
   #[test]
   fn import() {
     let example = r#"
@@ -2201,6 +3361,31 @@ mod test {
     );
   }
 
+  #[test]
+  fn export_import() {
+    let example = "
+      export import ./parts:{Thing, Other as O};
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::ImportSymbol((0, 1)),
+        AlignedByteCode::DefineGlobal(1),
+        AlignedByteCode::ImportSymbol((0, 2)),
+        AlignedByteCode::DefineGlobal(3),
+        AlignedByteCode::Export(1),
+        AlignedByteCode::Export(3),
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
   #[test]
   fn empty_try_catch() {
     let example = "
@@ -2218,7 +3403,8 @@ mod test {
       &fun,
       2,
       &vec![
-        AlignedByteCode::Jump(0),
+        AlignedByteCode::Jump(1),
+        AlignedByteCode::Drop,
         AlignedByteCode::Nil,
         AlignedByteCode::Return,
       ],
@@ -2252,8 +3438,9 @@ mod test {
         AlignedByteCode::Slot(0),        // 7
         AlignedByteCode::Drop,           // 8
         AlignedByteCode::Drop,           // 9
-        AlignedByteCode::Jump(8),        // 10
-        AlignedByteCode::GetGlobal(3),   // 13
+        AlignedByteCode::Jump(9),        // 10
+        AlignedByteCode::Drop,           // 13
+        AlignedByteCode::GetGlobal(3),   // 14
         AlignedByteCode::Constant(4),    // 16
         AlignedByteCode::Call(1),        // 18
         AlignedByteCode::Drop,           // 20
@@ -2297,13 +3484,15 @@ mod test {
         AlignedByteCode::Invoke((1, 1)), // 22
         AlignedByteCode::Slot(1),        // 26
         AlignedByteCode::Drop,           // 31
-        AlignedByteCode::Jump(8),        // 32
-        AlignedByteCode::GetGlobal(3),   // 35
+        AlignedByteCode::Jump(9),        // 32
+        AlignedByteCode::Drop,           // 35
+        AlignedByteCode::GetGlobal(3),   // 36
         AlignedByteCode::Constant(4),    // 38
         AlignedByteCode::Call(1),        // 40
         AlignedByteCode::Drop,           // 42
-        AlignedByteCode::Jump(8),        // 43
-        AlignedByteCode::GetGlobal(3),   // 46
+        AlignedByteCode::Jump(9),        // 43
+        AlignedByteCode::Drop,           // 46
+        AlignedByteCode::GetGlobal(3),   // 47
         AlignedByteCode::Constant(5),    // 49
         AlignedByteCode::Call(1),        // 52
         AlignedByteCode::Drop,           // 54
@@ -2312,8 +3501,8 @@ mod test {
       ],
     );
 
-    assert_eq!(fun.has_catch_jump(5), Some(42));
-    assert_eq!(fun.has_catch_jump(31), Some(42));
+    assert_eq!(fun.has_catch_jump(5), Some(43));
+    assert_eq!(fun.has_catch_jump(31), Some(43));
     assert_eq!(fun.has_catch_jump(19), Some(31));
   }
 
@@ -2352,6 +3541,69 @@ mod test {
     );
   }
 
+  #[test]
+  fn class_expression() {
+    let example = "
+      let A = class {};
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+
+    assert_simple_bytecode(
+      &fun,
+      5,
+      &[
+        AlignedByteCode::Class(1),
+        AlignedByteCode::GetLocal(1),
+        AlignedByteCode::GetGlobal(2),
+        AlignedByteCode::GetLocal(2),
+        AlignedByteCode::Inherit,
+        AlignedByteCode::Drop,
+        AlignedByteCode::Drop,
+        AlignedByteCode::Drop,
+        AlignedByteCode::DefineGlobal(0),
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
+  #[test]
+  fn extension() {
+    let example = "
+      extend String {
+        shout() {
+          return self;
+        }
+      }
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+
+    assert_fun_bytecode(
+      &fun,
+      3,
+      &[
+        ByteCodeTest::Code(AlignedByteCode::GetGlobal(0)),
+        ByteCodeTest::Code(AlignedByteCode::Extend),
+        ByteCodeTest::Fun((
+          2,
+          2,
+          vec![
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(0)),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+          ],
+        )),
+        ByteCodeTest::Code(AlignedByteCode::Method(1)),
+        ByteCodeTest::Code(AlignedByteCode::Drop),
+        ByteCodeTest::Code(AlignedByteCode::Nil),
+        ByteCodeTest::Code(AlignedByteCode::Return),
+      ],
+    );
+  }
+
   #[test]
   fn class_empty() {
     let example = "
@@ -2454,15 +3706,91 @@ mod test {
   }
 
   #[test]
-  fn class_with_methods_implicit() {
+  fn sealed_class() {
     let example = "
-      class A {
+      sealed class A {
         init() {
           self.field = true;
         }
 
         getField() {
-          self.field
+          return self.field;
+        }
+
+        getGetField() {
+          return self.getField();
+        }
+      }
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+
+    assert_fun_bytecode(
+      &fun,
+      4,
+      &[
+        ByteCodeTest::Code(AlignedByteCode::Class(0)),
+        ByteCodeTest::Code(AlignedByteCode::DefineGlobal(0)),
+        ByteCodeTest::Code(AlignedByteCode::GetGlobal(1)),
+        ByteCodeTest::Code(AlignedByteCode::GetGlobal(0)),
+        ByteCodeTest::Code(AlignedByteCode::Inherit),
+        ByteCodeTest::Fun((
+          3,
+          3,
+          vec![
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(0)),
+            ByteCodeTest::Code(AlignedByteCode::True),
+            ByteCodeTest::Code(AlignedByteCode::SetProperty(0)),
+            ByteCodeTest::Code(AlignedByteCode::Slot(0)),
+            ByteCodeTest::Code(AlignedByteCode::Drop),
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(0)),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+          ],
+        )),
+        ByteCodeTest::Code(AlignedByteCode::Method(2)),
+        ByteCodeTest::Code(AlignedByteCode::Field(4)),
+        ByteCodeTest::Code(AlignedByteCode::Seal),
+        ByteCodeTest::Fun((
+          6,
+          2,
+          vec![
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(0)),
+            ByteCodeTest::Code(AlignedByteCode::GetProperty(0)),
+            ByteCodeTest::Code(AlignedByteCode::Slot(1)),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+          ],
+        )),
+        ByteCodeTest::Code(AlignedByteCode::Method(5)),
+        ByteCodeTest::Fun((
+          8,
+          2,
+          vec![
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(0)),
+            ByteCodeTest::Code(AlignedByteCode::InvokeSealed((0, 0))),
+            ByteCodeTest::Code(AlignedByteCode::Slot(0)),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+          ],
+        )),
+        ByteCodeTest::Code(AlignedByteCode::Method(7)),
+        ByteCodeTest::Code(AlignedByteCode::Drop),
+        ByteCodeTest::Code(AlignedByteCode::Drop),
+        ByteCodeTest::Code(AlignedByteCode::Nil),
+        ByteCodeTest::Code(AlignedByteCode::Return),
+      ],
+    );
+  }
+
+  #[test]
+  fn class_with_methods_implicit() {
+    let example = "
+      class A {
+        init() {
+          self.field = true;
+        }
+
+        getField() {
+          self.field
         }
 
         getGetField() {
@@ -2913,7 +4241,7 @@ mod test {
         ByteCodeTest::Fun((
           // example
           1,
-          3,
+          6,
           vec![
             ByteCodeTest::Code(AlignedByteCode::GetLocal(1)),
             ByteCodeTest::Code(AlignedByteCode::GetLocal(2)),
@@ -3099,7 +4427,7 @@ mod test {
       &vec![
         ByteCodeTest::Fun((
           1,
-          2,
+          3,
           vec![
             ByteCodeTest::Code(AlignedByteCode::GetLocal(1)),
             ByteCodeTest::Code(AlignedByteCode::Return),
@@ -3221,6 +4549,29 @@ mod test {
     );
   }
 
+  #[test]
+  fn tuple() {
+    let example = "let (a, b) = (1, 2);";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+
+    assert_simple_bytecode(
+      &fun,
+      3,
+      &vec![
+        AlignedByteCode::Constant(2),     // 0
+        AlignedByteCode::Constant(3),     // 2
+        AlignedByteCode::Tuple(2),        // 4
+        AlignedByteCode::Unpack(2),       // 7
+        AlignedByteCode::DefineGlobal(1), // 10
+        AlignedByteCode::DefineGlobal(0), // 13
+        AlignedByteCode::Nil,             // 16
+        AlignedByteCode::Return,          // 17
+      ],
+    );
+  }
+
   #[test]
   fn for_loop() {
     let example = "for x in [1, 2, 3] { print(x); }";
@@ -3321,6 +4672,41 @@ mod test {
     );
   }
 
+  #[test]
+  fn assert_captures() {
+    let example = "assert(!a);";
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+
+    assert_simple_bytecode(
+      &fun,
+      6,
+      &vec![
+        AlignedByteCode::Nil,            // local 1 = $assert0
+        AlignedByteCode::GetGlobal(0),   // const 0 = "assert"
+        AlignedByteCode::GetGlobal(1),   // const 1 = "a"
+        AlignedByteCode::SetLocal(1),    //
+        AlignedByteCode::Not,            //
+        AlignedByteCode::Dup,            //
+        AlignedByteCode::JumpIfFalse(5), //
+        AlignedByteCode::Call(1),        //
+        AlignedByteCode::Jump(19),       //
+        AlignedByteCode::Drop,           //
+        AlignedByteCode::False,          //
+        AlignedByteCode::Constant(3),    // const 2 = "str"
+        AlignedByteCode::GetLocal(1),    // const 3 = "!a\n  a = "
+        AlignedByteCode::Invoke((2, 0)), //
+        AlignedByteCode::Slot(0),        //
+        AlignedByteCode::Interpolate(2), //
+        AlignedByteCode::Call(2),        //
+        AlignedByteCode::Drop,           //
+        AlignedByteCode::Drop,           // drop $assert0
+        AlignedByteCode::Nil,            //
+        AlignedByteCode::Return,         //
+      ],
+    );
+  }
+
   #[test]
   fn and_operator() {
     let example = "true and false;";
@@ -3424,12 +4810,57 @@ mod test {
     let fun = test_compile(example, &context);
     assert_simple_bytecode(
       &fun,
-      2,
+      3,
+      &vec![
+        AlignedByteCode::Nil,
+        AlignedByteCode::Constant(1),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
+  #[test]
+  fn const_inlined() {
+    let example = "const MAX = 10; print(MAX);";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      3,
       &vec![
         AlignedByteCode::Constant(1),
+        AlignedByteCode::DefineGlobal(0),
+        AlignedByteCode::GetGlobal(2),
+        AlignedByteCode::Constant(1),
+        AlignedByteCode::Call(1),
         AlignedByteCode::Drop,
         AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
+  #[test]
+  fn assign_block_value() {
+    let example = "let x = :{ let y = 10; y };";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      4,
+      &vec![
+        AlignedByteCode::Nil,
+        AlignedByteCode::Constant(2),
+        AlignedByteCode::GetLocal(2),
+        AlignedByteCode::SetLocal(1),
         AlignedByteCode::Drop,
+        AlignedByteCode::Drop,
+        AlignedByteCode::DefineGlobal(0),
         AlignedByteCode::Nil,
         AlignedByteCode::Return,
       ],
@@ -3444,15 +4875,15 @@ mod test {
     let fun = test_compile(example, &context);
     assert_simple_bytecode(
       &fun,
-      4,
+      5,
       &vec![
+        AlignedByteCode::Nil,
         AlignedByteCode::Constant(1),
         AlignedByteCode::GetGlobal(2),
-        AlignedByteCode::GetLocal(1),
+        AlignedByteCode::GetLocal(2),
         AlignedByteCode::Call(1),
         AlignedByteCode::Drop,
         AlignedByteCode::Drop,
-        AlignedByteCode::Nil,
         AlignedByteCode::Drop,
         AlignedByteCode::Nil,
         AlignedByteCode::Return,
@@ -3469,14 +4900,14 @@ mod test {
 
     assert_simple_bytecode(
       &fun,
-      3,
+      4,
       &vec![
+        AlignedByteCode::Nil,
         AlignedByteCode::Constant(1),
         AlignedByteCode::Constant(2),
-        AlignedByteCode::SetLocal(1),
+        AlignedByteCode::SetLocal(2),
         AlignedByteCode::Drop,
         AlignedByteCode::Drop,
-        AlignedByteCode::Nil,
         AlignedByteCode::Drop,
         AlignedByteCode::Nil,
         AlignedByteCode::Return,
@@ -3592,6 +5023,47 @@ mod test {
     );
   }
 
+  #[test]
+  fn op_raise() {
+    let example = "raise Error('error');";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      3,
+      &[
+        AlignedByteCode::GetGlobal(0),
+        AlignedByteCode::Constant(1),
+        AlignedByteCode::Call(1),
+        AlignedByteCode::Raise,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
+  #[test]
+  fn op_raise_from() {
+    let example = "raise Error('error') from nil;";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      3,
+      &[
+        AlignedByteCode::GetGlobal(0),
+        AlignedByteCode::Constant(1),
+        AlignedByteCode::Call(1),
+        AlignedByteCode::Nil,
+        AlignedByteCode::RaiseFrom,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
   #[test]
   fn op_number() {
     let example = "5.18;";
@@ -3747,6 +5219,42 @@ mod test {
     );
   }
 
+  #[test]
+  fn op_await() {
+    let example = "await 15;";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::Constant(0),
+        AlignedByteCode::Await,
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
+  #[test]
+  fn async_fun() {
+    let example = "
+    async fn example() {}
+    fn sync() {}
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+
+    let example = fun.chunk().get_constant(1).to_obj().to_fun();
+    assert!(example.is_async());
+
+    let sync = fun.chunk().get_constant(3).to_obj().to_fun();
+    assert!(!sync.is_async());
+  }
+
   #[test]
   fn op_add() {
     let example = "10 + 4;";
@@ -3867,6 +5375,44 @@ mod test {
     );
   }
 
+  #[test]
+  fn op_equal_const() {
+    let example = "true == \"example\";";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::True,
+        AlignedByteCode::EqualConst(0),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
+  #[test]
+  fn op_not_equal_const() {
+    let example = "\"example\" != nil;";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::Nil,
+        AlignedByteCode::NotEqualConst(0),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
   #[test]
   fn op_less() {
     let example = "3 < 5;";
@@ -3946,4 +5492,366 @@ mod test {
       ],
     );
   }
+
+  #[test]
+  fn optimize_fold_constants() {
+    let example = "1 + 2 * 3;";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      4,
+      &vec![
+        AlignedByteCode::Constant(0),
+        AlignedByteCode::Constant(1),
+        AlignedByteCode::Constant(2),
+        AlignedByteCode::Multiply,
+        AlignedByteCode::Add,
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+
+    let fun = test_compile_optimized(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      4,
+      &vec![
+        AlignedByteCode::Constant(4),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+    assert_eq!(fun.chunk().get_constant(4), val!(7.0));
+  }
+
+  #[test]
+  fn optimize_fold_negate() {
+    let example = "-5;";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::Constant(0),
+        AlignedByteCode::Negate,
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+
+    let fun = test_compile_optimized(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::Constant(1),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+    assert_eq!(fun.chunk().get_constant(1), val!(-5.0));
+  }
+
+  #[test]
+  fn optimize_not_not() {
+    let example = "
+      !!true;
+      let x = 1;
+      !!x;
+      if (!!x) {}
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::True,
+        AlignedByteCode::Not,
+        AlignedByteCode::Not,
+        AlignedByteCode::Drop,
+        AlignedByteCode::Constant(1),
+        AlignedByteCode::DefineGlobal(0),
+        AlignedByteCode::GetGlobal(0),
+        AlignedByteCode::Not,
+        AlignedByteCode::Not,
+        AlignedByteCode::Drop,
+        AlignedByteCode::GetGlobal(0),
+        AlignedByteCode::Not,
+        AlignedByteCode::Not,
+        AlignedByteCode::JumpIfFalse(0),
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+
+    let fun = test_compile_optimized(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::True,
+        AlignedByteCode::Drop,
+        AlignedByteCode::Constant(1),
+        AlignedByteCode::DefineGlobal(0),
+        AlignedByteCode::GetGlobal(0),
+        AlignedByteCode::Not,
+        AlignedByteCode::Not,
+        AlignedByteCode::Drop,
+        AlignedByteCode::GetGlobal(0),
+        AlignedByteCode::JumpIfFalse(0),
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
+  #[test]
+  fn optimize_jump_to_jump() {
+    let example = "
+      let a = true;
+      let b = false;
+      if (a) { if (b) { 1; } else { 2; } } else { 3; }
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::True,
+        AlignedByteCode::DefineGlobal(0),
+        AlignedByteCode::False,
+        AlignedByteCode::DefineGlobal(1),
+        AlignedByteCode::GetGlobal(0),
+        AlignedByteCode::JumpIfFalse(18),
+        AlignedByteCode::GetGlobal(1),
+        AlignedByteCode::JumpIfFalse(6),
+        AlignedByteCode::Constant(2),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Jump(3),
+        AlignedByteCode::Constant(3),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Jump(3),
+        AlignedByteCode::Constant(4),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+
+    let fun = test_compile_optimized(example, &context);
+    assert_simple_bytecode(
+      &fun,
+      2,
+      &vec![
+        AlignedByteCode::True,
+        AlignedByteCode::DefineGlobal(0),
+        AlignedByteCode::False,
+        AlignedByteCode::DefineGlobal(1),
+        AlignedByteCode::GetGlobal(0),
+        AlignedByteCode::JumpIfFalse(18),
+        AlignedByteCode::GetGlobal(1),
+        AlignedByteCode::JumpIfFalse(6),
+        AlignedByteCode::Constant(2),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Jump(9),
+        AlignedByteCode::Constant(3),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Jump(3),
+        AlignedByteCode::Constant(4),
+        AlignedByteCode::Drop,
+        AlignedByteCode::Nil,
+        AlignedByteCode::Return,
+      ],
+    );
+  }
+
+  #[test]
+  fn optimize_dead_code() {
+    let example = "
+      fn f(a) {
+        if (a) { return 1; } else { return 2; }
+      }
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_fun_bytecode(
+      &fun,
+      2,
+      &vec![
+        ByteCodeTest::Fun((
+          1,
+          5,
+          vec![
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(1)),
+            ByteCodeTest::Code(AlignedByteCode::JumpIfFalse(6)),
+            ByteCodeTest::Code(AlignedByteCode::Constant(0)),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+            ByteCodeTest::Code(AlignedByteCode::Jump(3)),
+            ByteCodeTest::Code(AlignedByteCode::Constant(1)),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+            ByteCodeTest::Code(AlignedByteCode::Nil),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+          ],
+        )),
+        ByteCodeTest::Code(AlignedByteCode::DefineGlobal(0)),
+        ByteCodeTest::Code(AlignedByteCode::Nil),
+        ByteCodeTest::Code(AlignedByteCode::Return),
+      ],
+    );
+
+    let fun = test_compile_optimized(example, &context);
+    assert_fun_bytecode(
+      &fun,
+      2,
+      &vec![
+        ByteCodeTest::Fun((
+          1,
+          5,
+          vec![
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(1)),
+            ByteCodeTest::Code(AlignedByteCode::JumpIfFalse(3)),
+            ByteCodeTest::Code(AlignedByteCode::Constant(0)),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+            ByteCodeTest::Code(AlignedByteCode::Constant(1)),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+          ],
+        )),
+        ByteCodeTest::Code(AlignedByteCode::DefineGlobal(0)),
+        ByteCodeTest::Code(AlignedByteCode::Nil),
+        ByteCodeTest::Code(AlignedByteCode::Return),
+      ],
+    );
+  }
+
+  #[test]
+  fn optimize_try_block() {
+    let example = "
+      fn f() {
+        try { return 1; } catch { return 2; }
+      }
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile_optimized(example, &context);
+    let inner = fun.chunk().get_constant(1).to_obj().to_fun();
+    let try_block = &inner.try_blocks()[0];
+
+    assert_eq!(try_block.start(), 0);
+    assert_eq!(try_block.end(), 6);
+  }
+
+  #[test]
+  fn optimize_fuse_loop() {
+    let example = "
+      fn f() {
+        let i = 0;
+        while (i < 10) { i += 1; }
+      }
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile(example, &context);
+    assert_fun_bytecode(
+      &fun,
+      2,
+      &vec![
+        ByteCodeTest::Fun((
+          1,
+          4,
+          vec![
+            ByteCodeTest::Code(AlignedByteCode::Constant(1)),
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(1)),
+            ByteCodeTest::Code(AlignedByteCode::Constant(2)),
+            ByteCodeTest::Code(AlignedByteCode::Less),
+            ByteCodeTest::Code(AlignedByteCode::JumpIfFalse(11)),
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(1)),
+            ByteCodeTest::Code(AlignedByteCode::Constant(3)),
+            ByteCodeTest::Code(AlignedByteCode::Add),
+            ByteCodeTest::Code(AlignedByteCode::SetLocal(1)),
+            ByteCodeTest::Code(AlignedByteCode::Drop),
+            ByteCodeTest::Code(AlignedByteCode::Loop(19)),
+            ByteCodeTest::Code(AlignedByteCode::Nil),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+          ],
+        )),
+        ByteCodeTest::Code(AlignedByteCode::DefineGlobal(0)),
+        ByteCodeTest::Code(AlignedByteCode::Nil),
+        ByteCodeTest::Code(AlignedByteCode::Return),
+      ],
+    );
+
+    let fun = test_compile_optimized(example, &context);
+    assert_fun_bytecode(
+      &fun,
+      2,
+      &vec![
+        ByteCodeTest::Fun((
+          1,
+          4,
+          vec![
+            ByteCodeTest::Code(AlignedByteCode::Constant(1)),
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(1)),
+            ByteCodeTest::Code(AlignedByteCode::Constant(2)),
+            ByteCodeTest::Code(AlignedByteCode::JumpIfNotLess(7)),
+            ByteCodeTest::Code(AlignedByteCode::AddConstToLocal((3, 1))),
+            ByteCodeTest::Code(AlignedByteCode::Loop(14)),
+            ByteCodeTest::Code(AlignedByteCode::Nil),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+          ],
+        )),
+        ByteCodeTest::Code(AlignedByteCode::DefineGlobal(0)),
+        ByteCodeTest::Code(AlignedByteCode::Nil),
+        ByteCodeTest::Code(AlignedByteCode::Return),
+      ],
+    );
+  }
+
+  #[test]
+  fn optimize_fuse_requires_number() {
+    let example = "
+      fn f() {
+        let s = \"\";
+        s = s + \"a\";
+      }
+    ";
+
+    let context = NoContext::default();
+    let fun = test_compile_optimized(example, &context);
+    assert_fun_bytecode(
+      &fun,
+      2,
+      &vec![
+        ByteCodeTest::Fun((
+          1,
+          4,
+          vec![
+            ByteCodeTest::Code(AlignedByteCode::Constant(1)),
+            ByteCodeTest::Code(AlignedByteCode::GetLocal(1)),
+            ByteCodeTest::Code(AlignedByteCode::Constant(2)),
+            ByteCodeTest::Code(AlignedByteCode::Add),
+            ByteCodeTest::Code(AlignedByteCode::SetLocal(1)),
+            ByteCodeTest::Code(AlignedByteCode::Drop),
+            ByteCodeTest::Code(AlignedByteCode::Nil),
+            ByteCodeTest::Code(AlignedByteCode::Return),
+          ],
+        )),
+        ByteCodeTest::Code(AlignedByteCode::DefineGlobal(0)),
+        ByteCodeTest::Code(AlignedByteCode::Nil),
+        ByteCodeTest::Code(AlignedByteCode::Return),
+      ],
+    );
+  }
 }
@@ -0,0 +1,603 @@
+use crate::byte_code::{decode_u16, decode_u32, AlignedByteCode, UpvalueIndex};
+use laythe_core::{
+  chunk::{Encode, SourceSpan},
+  object::{FunBuilder, Map, TryBlock},
+  val,
+  value::Value,
+};
+use std::mem;
+
+/// Run the peephole optimizer over a function that has finished compiling,
+/// rewriting its instructions and try blocks in place. The following
+/// optimizations are applied
+///
+/// - arithmetic and negation of number constants is folded into a single constant
+/// - `Not Not` is removed when only the truthiness of the result is used
+///   or the operand is already a boolean
+/// - common sequences in hot loops are fused into superinstructions
+/// - jumps landing on an unconditional jump are redirected to its target
+/// - unreachable instructions such as those after a `Return` are removed
+pub fn optimize(fun: &mut FunBuilder, constants: &mut Map<Value, usize>) {
+  let mut optimizer = match Optimizer::decode(fun) {
+    Some(optimizer) => optimizer,
+    None => return,
+  };
+
+  let mut changed = optimizer.fold(fun, constants);
+  changed |= optimizer.fuse(fun);
+  changed |= optimizer.thread_jumps();
+  changed |= optimizer.remove_unreachable();
+
+  if changed {
+    optimizer.encode(fun);
+  }
+}
+
+/// A decoded instruction
+struct Instruction {
+  /// The instruction itself
+  code: AlignedByteCode,
+
+  /// The source span this instruction was emitted from
+  span: SourceSpan,
+
+  /// Inline cache slots or upvalue indices following this instruction
+  trailing: Vec<(AlignedByteCode, SourceSpan)>,
+
+  /// The index of the instruction this instruction jumps to
+  target: Option<usize>,
+}
+
+struct Optimizer {
+  /// The decoded instructions. Removed instructions are left as
+  /// `None` so jump targets and try blocks remain valid indices
+  instructions: Vec<Option<Instruction>>,
+
+  /// The original offset of each instruction along with the end of the chunk
+  offsets: Vec<usize>,
+
+  /// Instructions that may be reached by something other than falling
+  /// through from the previous instruction
+  labels: Vec<bool>,
+
+  /// The start and end index and entry slots of each try block
+  try_blocks: Vec<(usize, usize, u16)>,
+}
+
+impl Optimizer {
+  /// Decode the function's instructions. Returns None if a jump or try
+  /// block does not land on an instruction boundary
+  fn decode(fun: &FunBuilder) -> Option<Self> {
+    let chunk = fun.chunk();
+    let bytes = chunk.instructions();
+
+    let mut instructions = vec![];
+    let mut offsets = vec![];
+    let mut jumps = vec![];
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+      offsets.push(offset);
+
+      let (code, end) = AlignedByteCode::decode(bytes, offset);
+      let span = chunk.get_span(end);
+      let mut trailing = vec![];
+      offset = end;
+
+      match code {
+        AlignedByteCode::Closure(slot) => {
+          let inner = chunk.constants()[slot as usize].to_obj().to_fun();
+
+          for _ in 0..inner.upvalue_count() {
+            let scalar = decode_u16(&bytes[offset..offset + 2]);
+            let upvalue_index: UpvalueIndex = unsafe { mem::transmute(scalar) };
+            offset += 2;
+
+            trailing.push((
+              AlignedByteCode::UpvalueIndex(upvalue_index),
+              chunk.get_span(offset),
+            ));
+          }
+        },
+        AlignedByteCode::GetProperty(_)
+        | AlignedByteCode::SetProperty(_)
+        | AlignedByteCode::Invoke(_)
+        | AlignedByteCode::SuperInvoke(_)
+        | AlignedByteCode::InvokeSealed(_) => {
+          let slot = decode_u32(&bytes[offset..offset + 4]);
+          offset += 4;
+
+          trailing.push((AlignedByteCode::Slot(slot), chunk.get_span(offset)));
+        },
+        AlignedByteCode::Jump(jump)
+        | AlignedByteCode::JumpIfFalse(jump)
+        | AlignedByteCode::And(jump)
+        | AlignedByteCode::Or(jump)
+        | AlignedByteCode::JumpIfNotLess(jump) => {
+          jumps.push((instructions.len(), end + jump as usize))
+        },
+        AlignedByteCode::Loop(jump) => {
+          jumps.push((instructions.len(), end.checked_sub(jump as usize)?))
+        },
+        _ => (),
+      }
+
+      instructions.push(Some(Instruction {
+        code,
+        span,
+        trailing,
+        target: None,
+      }));
+    }
+    offsets.push(bytes.len());
+
+    let mut labels = vec![false; offsets.len()];
+    let index_of = |offset: usize| offsets.binary_search(&offset).ok();
+
+    for (index, offset) in jumps {
+      let target = index_of(offset)?;
+      labels[target] = true;
+      instructions[index].as_mut()?.target = Some(target);
+    }
+
+    let try_blocks = fun
+      .try_blocks()
+      .iter()
+      .map(|try_block| {
+        let start = index_of(try_block.start() as usize)?;
+        let end = index_of(try_block.end() as usize)?;
+        labels[start] = true;
+        labels[end] = true;
+        Some((start, end, try_block.slots()))
+      })
+      .collect::<Option<Vec<_>>>()?;
+
+    Some(Self {
+      instructions,
+      offsets,
+      labels,
+      try_blocks,
+    })
+  }
+
+  /// Fold constant expressions and remove redundant nots
+  fn fold(&mut self, fun: &mut FunBuilder, constants: &mut Map<Value, usize>) -> bool {
+    let mut changed = false;
+    let mut index = 0;
+
+    while let Some(current) = self.live_from(index) {
+      if self.fold_at(current, fun, constants) {
+        // the result may now fold with the previous instruction
+        changed = true;
+        index = self.prev_live(current).unwrap_or(current);
+      } else {
+        index = current + 1;
+      }
+    }
+
+    changed
+  }
+
+  /// Attempt a fold of the instructions starting at index
+  fn fold_at(
+    &mut self,
+    index: usize,
+    fun: &mut FunBuilder,
+    constants: &mut Map<Value, usize>,
+  ) -> bool {
+    // nothing may jump into the middle of a folded sequence
+    let second = match self.next_live(index) {
+      Some(second) if !self.labels[second] => second,
+      _ => return false,
+    };
+    let third = self.next_live(second).filter(|third| !self.labels[*third]);
+
+    let first_code = self.code(index);
+    let second_code = self.code(second);
+
+    if let AlignedByteCode::Negate = second_code {
+      return match number_constant(first_code, fun) {
+        Some(value) => self.replace_constant(index, &[second], val!(-value), fun, constants),
+        None => false,
+      };
+    }
+
+    if let (AlignedByteCode::Not, AlignedByteCode::Not) = (first_code, second_code) {
+      let truthiness_used = matches!(
+        self.next_live(second).map(|next| self.code(next)),
+        Some(AlignedByteCode::JumpIfFalse(_))
+      );
+      let boolean_operand = !self.labels[index]
+        && matches!(self.prev_live(index).map(|prev| self.code(prev)), Some(code) if is_boolean(code));
+
+      if truthiness_used || boolean_operand {
+        self.instructions[index] = None;
+        self.instructions[second] = None;
+        return true;
+      }
+
+      return false;
+    }
+
+    let third = match third {
+      Some(third) => third,
+      None => return false,
+    };
+
+    let (left, right) = match (
+      number_constant(first_code, fun),
+      number_constant(second_code, fun),
+    ) {
+      (Some(left), Some(right)) => (left, right),
+      _ => return false,
+    };
+
+    let value = match self.code(third) {
+      AlignedByteCode::Add => left + right,
+      AlignedByteCode::Subtract => left - right,
+      AlignedByteCode::Multiply => left * right,
+      AlignedByteCode::Divide => left / right,
+      _ => return false,
+    };
+
+    self.replace_constant(index, &[second, third], val!(value), fun, constants)
+  }
+
+  /// Replace the instruction at index with a constant, removing the
+  /// provided instructions
+  fn replace_constant(
+    &mut self,
+    index: usize,
+    removed: &[usize],
+    value: Value,
+    fun: &mut FunBuilder,
+    constants: &mut Map<Value, usize>,
+  ) -> bool {
+    let slot = match constants.get(&value) {
+      Some(slot) => *slot,
+      None => {
+        if fun.chunk().constants().len() > u16::MAX as usize {
+          return false;
+        }
+
+        let slot = fun.add_constant(value);
+        constants.insert(value, slot);
+        slot
+      },
+    };
+
+    let code = if slot <= u8::MAX as usize {
+      AlignedByteCode::Constant(slot as u8)
+    } else {
+      AlignedByteCode::ConstantLong(slot as u16)
+    };
+
+    self.replace(index, code, removed);
+    true
+  }
+
+  /// Fuse common instruction sequences into a single superinstruction
+  fn fuse(&mut self, fun: &FunBuilder) -> bool {
+    let mut changed = false;
+    let mut index = 0;
+
+    while let Some(current) = self.live_from(index) {
+      changed |= self.fuse_at(current, fun);
+      index = current + 1;
+    }
+
+    changed
+  }
+
+  /// Attempt to fuse the instructions starting at index
+  fn fuse_at(&mut self, index: usize, fun: &FunBuilder) -> bool {
+    match self.code(index) {
+      AlignedByteCode::GetLocal(local) => {
+        let following = match self.following(index, 4) {
+          Some(following) => following,
+          None => return false,
+        };
+
+        let constant_code = self.code(following[0]);
+        let constant = match constant_slot(constant_code) {
+          Some(constant) if number_constant(constant_code, fun).is_some() => constant,
+          _ => return false,
+        };
+
+        match (
+          self.code(following[1]),
+          self.code(following[2]),
+          self.code(following[3]),
+        ) {
+          (AlignedByteCode::Add, AlignedByteCode::SetLocal(set), AlignedByteCode::Drop)
+            if set == local =>
+          {
+            self.replace(
+              index,
+              AlignedByteCode::AddConstToLocal((constant, local)),
+              &following,
+            );
+            true
+          },
+          _ => false,
+        }
+      },
+      AlignedByteCode::Less => {
+        let jump = match self.following(index, 1) {
+          Some(following) => following[0],
+          None => return false,
+        };
+
+        let target = match &self.instructions[jump] {
+          Some(Instruction {
+            code: AlignedByteCode::JumpIfFalse(_),
+            target,
+            ..
+          }) => *target,
+          _ => return false,
+        };
+
+        self.replace(index, AlignedByteCode::JumpIfNotLess(0), &[jump]);
+        if let Some(instruction) = &mut self.instructions[index] {
+          instruction.target = target;
+        }
+        true
+      },
+      _ => false,
+    }
+  }
+
+  /// Replace the code of the instruction at index, removing
+  /// the provided instructions
+  fn replace(&mut self, index: usize, code: AlignedByteCode, removed: &[usize]) {
+    if let Some(instruction) = &mut self.instructions[index] {
+      instruction.code = code;
+    }
+    for index in removed {
+      self.instructions[*index] = None;
+    }
+  }
+
+  /// Redirect forward jumps that land on an unconditional jump
+  fn thread_jumps(&mut self) -> bool {
+    let mut changed = false;
+
+    for index in 0..self.instructions.len() {
+      let target = match &self.instructions[index] {
+        Some(Instruction {
+          code: AlignedByteCode::Loop(_),
+          ..
+        }) => continue,
+        Some(Instruction {
+          target: Some(target),
+          ..
+        }) => *target,
+        _ => continue,
+      };
+
+      let mut threaded = target;
+      while let Some(Instruction {
+        code: AlignedByteCode::Jump(_),
+        target: Some(next),
+        ..
+      }) = self
+        .live_from(threaded)
+        .and_then(|live| self.instructions[live].as_ref())
+      {
+        threaded = *next;
+      }
+
+      // removing instructions only shortens a jump so the original distance bounds it
+      let distance = self.offsets[threaded] - self.offsets[index + 1];
+      if threaded != target && distance <= u16::MAX as usize {
+        if let Some(instruction) = &mut self.instructions[index] {
+          instruction.target = Some(threaded);
+        }
+        self.labels[threaded] = true;
+        changed = true;
+      }
+    }
+
+    changed
+  }
+
+  /// Remove instructions that can never be executed
+  fn remove_unreachable(&mut self) -> bool {
+    let mut reachable = vec![false; self.instructions.len()];
+    let mut pending = vec![0];
+
+    for (_, end, _) in &self.try_blocks {
+      pending.push(*end);
+
+      // keep the final instruction of a try block so the block never
+      // ends directly after an instruction that may raise
+      if let Some(last) = self.prev_live(*end) {
+        pending.push(last);
+      }
+    }
+
+    while let Some(index) = pending.pop() {
+      let index = match self.live_from(index) {
+        Some(index) => index,
+        None => continue,
+      };
+
+      if reachable[index] {
+        continue;
+      }
+      reachable[index] = true;
+
+      if let Some(instruction) = &self.instructions[index] {
+        if let Some(target) = instruction.target {
+          pending.push(target);
+        }
+
+        match instruction.code {
+          AlignedByteCode::Return
+          | AlignedByteCode::Raise
+          | AlignedByteCode::RaiseFrom
+          | AlignedByteCode::Jump(_)
+          | AlignedByteCode::Loop(_) => (),
+          _ => pending.push(index + 1),
+        }
+      }
+    }
+
+    let mut changed = false;
+    for (instruction, reachable) in self.instructions.iter_mut().zip(reachable) {
+      if instruction.is_some() && !reachable {
+        *instruction = None;
+        changed = true;
+      }
+    }
+
+    changed
+  }
+
+  /// Encode the remaining instructions back into the function
+  fn encode(self, fun: &mut FunBuilder) {
+    let mut buffer = vec![];
+    let mut offsets = vec![0; self.offsets.len()];
+    let mut offset = 0;
+
+    // removed instructions take the offset of the next remaining instruction
+    for (index, instruction) in self.instructions.iter().enumerate() {
+      offsets[index] = offset;
+
+      if let Some(instruction) = instruction {
+        offset += encoded_len(instruction.code, &mut buffer);
+        offset += instruction
+          .trailing
+          .iter()
+          .map(|(code, _)| encoded_len(*code, &mut buffer))
+          .sum::<usize>();
+      }
+    }
+    offsets[self.instructions.len()] = offset;
+
+    fun.clear_instructions();
+    for (index, instruction) in self.instructions.into_iter().enumerate() {
+      let instruction = match instruction {
+        Some(instruction) => instruction,
+        None => continue,
+      };
+
+      let code = match (instruction.code, instruction.target) {
+        (AlignedByteCode::Loop(_), Some(target)) => {
+          AlignedByteCode::Loop((offsets[index + 1] - offsets[target]) as u16)
+        },
+        (code, Some(target)) => {
+          let jump = (offsets[target] - offsets[index + 1]) as u16;
+
+          match code {
+            AlignedByteCode::Jump(_) => AlignedByteCode::Jump(jump),
+            AlignedByteCode::JumpIfFalse(_) => AlignedByteCode::JumpIfFalse(jump),
+            AlignedByteCode::And(_) => AlignedByteCode::And(jump),
+            AlignedByteCode::Or(_) => AlignedByteCode::Or(jump),
+            AlignedByteCode::JumpIfNotLess(_) => AlignedByteCode::JumpIfNotLess(jump),
+            _ => code,
+          }
+        },
+        (code, None) => code,
+      };
+
+      fun.write_instruction(code, instruction.span);
+      for (code, span) in instruction.trailing {
+        fun.write_instruction(code, span);
+      }
+    }
+
+    fun.set_try_blocks(
+      self
+        .try_blocks
+        .iter()
+        .map(|(start, end, slots)| {
+          TryBlock::new(offsets[*start] as u16, offsets[*end] as u16, *slots)
+        })
+        .collect(),
+    );
+  }
+
+  /// The code of a remaining instruction
+  fn code(&self, index: usize) -> AlignedByteCode {
+    self.instructions[index]
+      .as_ref()
+      .expect("Expected instruction.")
+      .code
+  }
+
+  /// The first remaining instruction at or after index
+  fn live_from(&self, index: usize) -> Option<usize> {
+    (index..self.instructions.len()).find(|index| self.instructions[*index].is_some())
+  }
+
+  /// The next remaining instruction after index
+  fn next_live(&self, index: usize) -> Option<usize> {
+    self.live_from(index + 1)
+  }
+
+  /// The next count remaining instructions after index if
+  /// none of them may be reached by a jump
+  fn following(&self, index: usize, count: usize) -> Option<Vec<usize>> {
+    let mut following = Vec::with_capacity(count);
+    let mut current = index;
+
+    for _ in 0..count {
+      current = self.next_live(current).filter(|next| !self.labels[*next])?;
+      following.push(current);
+    }
+
+    Some(following)
+  }
+
+  /// The previous remaining instruction before index
+  fn prev_live(&self, index: usize) -> Option<usize> {
+    (0..index)
+      .rev()
+      .find(|index| self.instructions[*index].is_some())
+  }
+}
+
+/// The number loaded by a constant instruction if any
+fn number_constant(code: AlignedByteCode, fun: &FunBuilder) -> Option<f64> {
+  let value = fun.chunk().constants()[constant_slot(code)? as usize];
+  if value.is_num() {
+    Some(value.to_num())
+  } else {
+    None
+  }
+}
+
+/// The constant slot loaded by a constant instruction if any
+fn constant_slot(code: AlignedByteCode) -> Option<u16> {
+  match code {
+    AlignedByteCode::Constant(slot) => Some(slot as u16),
+    AlignedByteCode::ConstantLong(slot) => Some(slot),
+    _ => None,
+  }
+}
+
+/// Does this instruction always produce a boolean
+fn is_boolean(code: AlignedByteCode) -> bool {
+  matches!(
+    code,
+    AlignedByteCode::True
+      | AlignedByteCode::False
+      | AlignedByteCode::Not
+      | AlignedByteCode::Equal
+      | AlignedByteCode::NotEqual
+      | AlignedByteCode::EqualConst(_)
+      | AlignedByteCode::NotEqualConst(_)
+      | AlignedByteCode::Greater
+      | AlignedByteCode::GreaterEqual
+      | AlignedByteCode::Less
+      | AlignedByteCode::LessEqual
+  )
+}
+
+/// The number of bytes an instruction encodes to
+fn encoded_len(code: AlignedByteCode, buffer: &mut Vec<u8>) -> usize {
+  buffer.clear();
+  code.encode(buffer);
+  buffer.len()
+}
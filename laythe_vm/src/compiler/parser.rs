@@ -1,25 +1,24 @@
 use crate::{
+  arena::Arena,
   ast::*,
+  diagnostics,
   source::{LineOffsets, Source},
   token::{Lexeme, Token, TokenKind},
   FeResult,
 };
 use bumpalo::boxed::Box;
 use codespan_reporting::diagnostic::{Diagnostic, Label};
-use laythe_core::{constants::INIT, object::FunKind};
+use laythe_core::{
+  constants::{ANONYMOUS_CLASS, INIT},
+  object::FunKind,
+};
 use std::mem;
 
-use super::Scanner;
+use super::{scanner::UNTERMINATED_STRING, Scanner};
 
 type ParseResult<T, F> = Result<T, Diagnostic<F>>;
 
-fn to_fe_result<T, F>(result: ParseResult<T, F>) -> FeResult<T, F> {
-  match result {
-    Ok(res) => Ok(res),
-    Err(err) => Err(vec![err]),
-  }
-}
-
+#[derive(Clone, Copy)]
 enum BlockReturn {
   Can,
   Cannot,
@@ -29,6 +28,7 @@ const NUMBER_TYPE: &str = "number";
 const BOOL_TYPE: &str = "bool";
 const STRING_TYPE: &str = "string";
 const ANY_TYPE: &str = "any";
+const FROM: &str = "from";
 
 /// The Laythe parser. This structure produces the Laythe
 /// AST
@@ -39,12 +39,15 @@ pub struct Parser<'a, FileId> {
   /// The previous token
   previous: Token<'a>,
 
-  /// The source
-  source: &'a Source,
+  /// The arena ast nodes are allocated in
+  arena: &'a Arena,
 
   /// All errors that have been during parsing
   errors: Vec<Diagnostic<FileId>>,
 
+  /// Did the first error occur because the source ended early
+  incomplete: bool,
+
   /// Can we currently implicitly return
   block_return: BlockReturn,
 
@@ -57,6 +60,9 @@ pub struct Parser<'a, FileId> {
   /// Current scope depth
   scope_depth: u16,
 
+  /// Are we parsing a line from the repl
+  repl: bool,
+
   /// Struct for providing a stream of tokens
   scanner: Scanner<'a>,
 
@@ -65,24 +71,34 @@ pub struct Parser<'a, FileId> {
 }
 
 impl<'a, FileId: Copy> Parser<'a, FileId> {
-  /// Create a new instance of the parser from a source str
-  pub fn new(source: &'a Source, file_id: FileId) -> Self {
+  /// Create a new instance of the parser from a source str. Ast
+  /// nodes are allocated into the provided arena
+  pub fn new(source: &Source<'a>, arena: &'a Arena, file_id: FileId) -> Self {
     Self {
-      scanner: Scanner::new(source),
+      scanner: Scanner::new(source.content()),
       file_id,
-      source,
+      arena,
       errors: vec![],
+      incomplete: false,
       fun_kind: FunKind::Script,
       block_return: BlockReturn::Cannot,
       scope_depth: 0,
       loop_depth: 0,
+      repl: false,
       previous: Token::new(TokenKind::Error, Lexeme::Slice("error"), 0, 0),
       current: Token::new(TokenKind::Error, Lexeme::Slice("error"), 0, 0),
     }
   }
 
+  /// Parse as a line from the repl. A top level expression statement
+  /// at the end of the line may omit its semicolon
+  pub fn with_repl(mut self, repl: bool) -> Self {
+    self.repl = repl;
+    self
+  }
+
   fn node<T>(&self, node: T) -> Box<'a, T> {
-    self.source.node(node)
+    self.arena.node(node)
   }
 
   /// Parse the provide source string into a Laythe AST
@@ -91,17 +107,16 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
   /// # Examples
   /// ```
   /// use laythe_vm::{
+  ///   arena::Arena,
   ///   compiler::Parser,
   ///   source::Source,
   /// };
-  /// use laythe_native::stdio::StdioNative;
-  /// use laythe_core::memory::{Allocator, NO_GC};
   ///
   /// // an expression
-  /// let mut gc = Allocator::default();
-  /// let source = Source::new(gc.manage_str("3 / 2 + 10;", &NO_GC));
+  /// let source = Source::new("3 / 2 + 10;");
+  /// let arena = Arena::for_source(&source);
   ///
-  /// let parser = Parser::new(&source, 0);
+  /// let parser = Parser::new(&source, &arena, 0);
   /// let (ast, _) = parser.parse();
   /// assert_eq!(ast.is_ok(), true);
   /// ```
@@ -110,86 +125,152 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
   }
 
   fn parse_inner(&mut self) -> FeResult<Module<'a>, FileId> {
-    to_fe_result(self.advance())?;
+    self.advance_recover();
+    while self.current.kind() == TokenKind::Error {
+      self.advance_recover();
+    }
 
     // early exit if ""
-    if let TokenKind::Eof = self.current.kind() {
+    if self.errors.is_empty() && self.current.kind() == TokenKind::Eof {
       return Ok(Module::default());
     }
 
     let mut decls = Vec::new();
-    while !to_fe_result(self.match_kind(TokenKind::Eof))? {
-      decls.push(to_fe_result(self.decl())?)
+    while self.current.kind() != TokenKind::Eof {
+      match self.decl() {
+        Ok(decl) => decls.push(decl),
+        Err(error) => {
+          self.errors.push(error);
+          break;
+        },
+      }
     }
 
-    self
-      .consume(TokenKind::Eof, "Expected end of expression.")
-      .map_err(|err| vec![err])?;
-
     if self.errors.is_empty() {
       Ok(Module::new(decls))
     } else {
-      Err(self.errors.clone())
+      Err(mem::take(&mut self.errors))
     }
   }
 
   /// Parse a Laythe declaration, if an error occurred at a lower level attempt
   /// synchronize to provide more error messages
   fn decl(&mut self) -> ParseResult<Decl<'a>, FileId> {
+    let start = self.current.start();
+    let fun_kind = self.fun_kind;
+    let block_return = self.block_return;
+    let loop_depth = self.loop_depth;
+    let scope_depth = self.scope_depth;
+
     let decl = match self.current.kind() {
       TokenKind::Class => self
         .advance()
         .and_then(|()| self.class())
         .map(|class| Decl::Symbol(self.node(class))),
+      TokenKind::Sealed => self
+        .advance()
+        .and_then(|()| self.sealed_class())
+        .map(|class| Decl::Symbol(self.node(class))),
+      TokenKind::Extend => self
+        .advance()
+        .and_then(|()| self.extension())
+        .map(|extension| Decl::Symbol(self.node(extension))),
       TokenKind::Fun => self
         .advance()
-        .and_then(|()| self.fun())
+        .and_then(|()| self.fun(false))
+        .map(|fun| Decl::Symbol(self.node(fun))),
+      TokenKind::Async => self
+        .advance()
+        .and_then(|()| self.async_fun())
         .map(|fun| Decl::Symbol(self.node(fun))),
       TokenKind::Let => self
         .advance()
         .and_then(|()| self.let_())
         .map(|let_| Decl::Symbol(self.node(let_))),
+      TokenKind::Const => self
+        .advance()
+        .and_then(|()| self.const_())
+        .map(|const_| Decl::Symbol(self.node(const_))),
       TokenKind::Trait => self
         .advance()
         .and_then(|()| self.trait_())
         .map(|trait_| Decl::Symbol(self.node(trait_))),
-      TokenKind::Type => self
-        .advance()
-        .and_then(|()| self.type_decl())
-        .map(|type_decl| Decl::Symbol(self.node(type_decl))),
+      TokenKind::Type => self.advance().and_then(|()| {
+        // type is only a keyword when it starts a type declaration
+        if self.check(TokenKind::LeftParen) {
+          self
+            .parse_prefixed(Precedence::Assignment)
+            .and_then(|expr| self.expr_stmt_end(expr))
+            .map(|stmt| Decl::Stmt(self.node(stmt)))
+        } else {
+          self
+            .type_decl()
+            .map(|type_decl| Decl::Symbol(self.node(type_decl)))
+        }
+      }),
       TokenKind::Export => self.advance().and_then(|()| self.export_declaration()),
       _ => self.stmt().map(|stmt| Decl::Stmt(self.node(stmt))),
     };
 
-    decl.or_else(|error| self.synchronize(error))
+    decl.or_else(|error| {
+      // restore the context this declaration started in as the failed
+      // parse may have bailed out of a nested function or loop
+      self.fun_kind = fun_kind;
+      self.block_return = block_return;
+      self.loop_depth = loop_depth;
+      self.scope_depth = scope_depth;
+
+      Ok(self.synchronize(error, start))
+    })
   }
 
-  /// Synchronize the parser to a sentinel token
-  fn synchronize(&mut self, error: Diagnostic<FileId>) -> ParseResult<Decl<'a>, FileId> {
+  /// Synchronize the parser to the next statement boundary. Parsing resumes
+  /// after a semicolon, before a keyword that starts a declaration or statement,
+  /// or before the closing brace of the enclosing block
+  fn synchronize(&mut self, error: Diagnostic<FileId>, start: u32) -> Decl<'a> {
     self.errors.push(error);
 
     let mut tokens: Vec<Token> = vec![];
 
-    while self.current.kind() != TokenKind::Eof || self.previous.kind() == TokenKind::Semicolon {
-      tokens.push(self.previous.clone());
+    // ensure we always make progress past the token that started this declaration
+    if self.current.start() == start && self.current.kind() != TokenKind::Eof {
+      tokens.push(self.current.clone());
+      self.advance_recover();
+    }
 
+    while self.current.kind() != TokenKind::Eof && self.previous.kind() != TokenKind::Semicolon {
       match self.current.kind() {
         TokenKind::Class
+        | TokenKind::Sealed
+        | TokenKind::Extend
         | TokenKind::Fun
+        | TokenKind::Async
         | TokenKind::Let
+        | TokenKind::Const
+        | TokenKind::Trait
+        | TokenKind::Type
+        | TokenKind::Export
+        | TokenKind::Import
+        | TokenKind::Try
+        | TokenKind::With
         | TokenKind::For
         | TokenKind::If
         | TokenKind::While
-        | TokenKind::Return => {
+        | TokenKind::Return
+        | TokenKind::Raise => {
+          break;
+        },
+        TokenKind::RightBrace if self.scope_depth > 0 => {
           break;
         },
         _ => (),
       }
 
-      self.advance()?;
+      tokens.push(self.current.clone());
+      self.advance_recover();
     }
 
-    Ok(Decl::Error(tokens.into_boxed_slice()))
+    Decl::Error(tokens.into_boxed_slice())
   }
 
   /// Parse a statement
@@ -197,10 +278,12 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
     match self.current.kind() {
       TokenKind::Import => self.advance().and_then(|()| self.import()),
       TokenKind::Try => self.advance().and_then(|()| self.try_block()),
+      TokenKind::With => self.advance().and_then(|()| self.with()),
       TokenKind::If => self.advance().and_then(|()| self.if_()),
       TokenKind::For => self.advance().and_then(|()| self.for_()),
       TokenKind::While => self.advance().and_then(|()| self.while_()),
       TokenKind::Return => self.advance().and_then(|()| self.return_()),
+      TokenKind::Raise => self.advance().and_then(|()| self.raise()),
       TokenKind::Continue => self.advance().and_then(|()| self.continue_()),
       TokenKind::Break => self.advance().and_then(|()| self.break_()),
       _ => self.expr_stmt(),
@@ -217,6 +300,41 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
     self.consume(TokenKind::Identifier, "Expected class name.")?;
     let name = self.previous.clone();
 
+    self.class_body(name).map(Symbol::Class)
+  }
+
+  /// Parse a sealed class declaration
+  fn sealed_class(&mut self) -> ParseResult<Symbol<'a>, FileId> {
+    self.consume_basic(TokenKind::Class, "Expected 'class' after 'sealed'.")?;
+    self.consume(TokenKind::Identifier, "Expected class name.")?;
+    let name = self.previous.clone();
+
+    self
+      .class_body(name)
+      .map(|class| Symbol::Class(class.with_sealed(true)))
+  }
+
+  /// Parse a class expression, which may omit its name
+  fn class_expr(&mut self) -> ParseResult<Expr<'a>, FileId> {
+    // an anonymous class keeps the 'class' keyword's kind so it is never bound by name
+    let name = if self.match_kind(TokenKind::Identifier)? {
+      self.previous.clone()
+    } else {
+      Token::new(
+        TokenKind::Class,
+        Lexeme::Slice(ANONYMOUS_CLASS),
+        self.previous.start(),
+        self.previous.end(),
+      )
+    };
+
+    self
+      .class_body(name)
+      .map(|class| self.atom(Primary::Class(self.node(class))))
+  }
+
+  /// Parse the remainder of a class after its name
+  fn class_body(&mut self, name: Token<'a>) -> ParseResult<Class<'a>, FileId> {
     let type_params = if self.match_kind(TokenKind::Less)? {
       self.type_params()?
     } else {
@@ -275,16 +393,33 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
           }
         },
 
+        // async we know must be a method
+        TokenKind::Async => {
+          self.advance()?;
+          self.consume(
+            TokenKind::Identifier,
+            "Expected method name after 'async' keyword.",
+          )?;
+          let name = self.previous.clone();
+          let (fun_kind, method) = self.method(name, false)?;
+          match fun_kind {
+            FunKind::Method => methods.push(method.with_async(true)),
+            FunKind::Initializer => return self.error("Class initializer cannot be async."),
+            _ => unreachable!(),
+          }
+        },
+
         // static we know must be a method
         TokenKind::Static => {
           self.advance()?;
+          let is_async = self.match_kind(TokenKind::Async)?;
           self.consume(
             TokenKind::Identifier,
             "Expected method name after 'static' keyword.",
           )?;
           let name = self.previous.clone();
           let (_, method) = self.method(name, true)?;
-          static_methods.push(method);
+          static_methods.push(method.with_async(is_async));
         },
         _ => return self.error_current("Expected method or member declaration inside of class."),
       }
@@ -293,7 +428,7 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
     self.consume_basic(TokenKind::RightBrace, "Expected '}' after class body.")?;
     let end = self.previous.end();
 
-    Ok(Symbol::Class(Class::new(
+    Ok(Class::new(
       name,
       Span { start, end },
       type_params,
@@ -302,11 +437,70 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
       init,
       methods,
       static_methods,
+    ))
+  }
+
+  /// Parse a class extension adding methods to an existing class
+  fn extension(&mut self) -> ParseResult<Symbol<'a>, FileId> {
+    self.consume(TokenKind::Identifier, "Expected class name after 'extend'.")?;
+    let class = self.previous.clone();
+    let start = class.start();
+
+    self.consume_basic(TokenKind::LeftBrace, "Expected '{' before extension body.")?;
+    let mut methods: Vec<Fun> = vec![];
+    let mut static_methods: Vec<Fun> = vec![];
+
+    while !self.check(TokenKind::RightBrace) && !self.check(TokenKind::Eof) {
+      match self.current.kind() {
+        TokenKind::Identifier | TokenKind::Async => {
+          let is_async = self.match_kind(TokenKind::Async)?;
+          self.consume(TokenKind::Identifier, "Expected method name.")?;
+          let name = self.previous.clone();
+
+          if name.str() == INIT {
+            return self.error("Cannot declare an initializer in a class extension.");
+          }
+          if self.check(TokenKind::Colon) {
+            return self.error_current("Cannot declare members in a class extension.");
+          }
+
+          let (_, method) = self.method(name, false)?;
+          methods.push(method.with_async(is_async));
+        },
+        TokenKind::Static => {
+          self.advance()?;
+          let is_async = self.match_kind(TokenKind::Async)?;
+          self.consume(
+            TokenKind::Identifier,
+            "Expected method name after 'static' keyword.",
+          )?;
+          let name = self.previous.clone();
+          let (_, method) = self.method(name, true)?;
+          static_methods.push(method.with_async(is_async));
+        },
+        _ => return self.error_current("Expected method declaration inside of extension."),
+      }
+    }
+
+    self.consume_basic(TokenKind::RightBrace, "Expected '}' after extension body.")?;
+    let end = self.previous.end();
+
+    Ok(Symbol::Extension(Extension::new(
+      class,
+      Span { start, end },
+      methods,
+      static_methods,
     )))
   }
 
+  /// Parse an async function declaration
+  fn async_fun(&mut self) -> ParseResult<Symbol<'a>, FileId> {
+    self.consume_basic(TokenKind::Fun, "Expected 'fn' after 'async'.")?;
+    self.fun(true)
+  }
+
   /// Parse a function declaration
-  fn fun(&mut self) -> ParseResult<Symbol<'a>, FileId> {
+  fn fun(&mut self, is_async: bool) -> ParseResult<Symbol<'a>, FileId> {
     let previous = mem::replace(&mut self.fun_kind, FunKind::Fun);
 
     self.consume(TokenKind::Identifier, "Expected function name.")?;
@@ -320,7 +514,7 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
 
     let fun = self
       .function(name, type_params, BlockReturn::Can)
-      .map(Symbol::Fun);
+      .map(|fun| Symbol::Fun(fun.with_async(is_async)));
 
     self.fun_kind = previous;
     fun
@@ -328,6 +522,10 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
 
   /// Parse a variable declaration
   fn let_(&mut self) -> ParseResult<Symbol<'a>, FileId> {
+    if self.match_kind(TokenKind::LeftParen)? {
+      return self.let_tuple();
+    }
+
     self.consume(TokenKind::Identifier, "Expected variable name.")?;
     let name = self.previous.clone();
 
@@ -351,6 +549,70 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
       .map(|()| Symbol::Let(Let::new(name, type_, value)))
   }
 
+  /// Parse a destructuring variable declaration such as `let (a, b) = pair;`
+  fn let_tuple(&mut self) -> ParseResult<Symbol<'a>, FileId> {
+    let start = self.previous.start();
+    let mut names = vec![];
+
+    while !self.check(TokenKind::RightParen) {
+      self.consume(TokenKind::Identifier, "Expected variable name.")?;
+      names.push(self.previous.clone());
+
+      if names.len() == u16::MAX as usize {
+        return self.error(&format!(
+          "Cannot destructure more than {} variables",
+          u16::MAX
+        ));
+      }
+
+      if !self.match_kind(TokenKind::Comma)? {
+        break;
+      }
+    }
+
+    if names.is_empty() {
+      return self.error_current("Expected variable name.");
+    }
+
+    self.consume_basic(TokenKind::RightParen, "Expected ')' after variable names.")?;
+    self.consume_basic(
+      TokenKind::Equal,
+      "Expected '=' after destructured variable names.",
+    )?;
+    let value = self.expr()?;
+    let range = Span {
+      start,
+      end: self.previous.end(),
+    };
+
+    self
+      .consume_basic(
+        TokenKind::Semicolon,
+        "Expected ';' after variable declaration.",
+      )
+      .map(|()| Symbol::LetTuple(LetTuple::new(range, names, value)))
+  }
+
+  /// Parse a module level constant declaration
+  fn const_(&mut self) -> ParseResult<Symbol<'a>, FileId> {
+    if self.scope_depth > 0 {
+      return self.error("Can only declare constants in the module scope.");
+    }
+
+    self.consume(TokenKind::Identifier, "Expected constant name.")?;
+    let name = self.previous.clone();
+
+    self.consume_basic(TokenKind::Equal, "Expected '=' after constant name.")?;
+    let value = self.expr()?;
+
+    self
+      .consume_basic(
+        TokenKind::Semicolon,
+        "Expected ';' after constant declaration.",
+      )
+      .map(|()| Symbol::Const(Const::new(name, value)))
+  }
+
   /// Parse a trait declaration
   fn trait_(&mut self) -> ParseResult<Symbol<'a>, FileId> {
     self.consume(TokenKind::Identifier, "Expected trait name after 'trait'.")?;
@@ -441,11 +703,20 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
   fn export_declaration(&mut self) -> ParseResult<Decl<'a>, FileId> {
     let symbol = match self.current.kind() {
       TokenKind::Class => self.advance().and_then(|()| self.class()),
-      TokenKind::Fun => self.advance().and_then(|()| self.fun()),
+      TokenKind::Sealed => self.advance().and_then(|()| self.sealed_class()),
+      TokenKind::Fun => self.advance().and_then(|()| self.fun(false)),
+      TokenKind::Async => self.advance().and_then(|()| self.async_fun()),
       TokenKind::Let => self.advance().and_then(|()| self.let_()),
+      TokenKind::Const => self.advance().and_then(|()| self.const_()),
       TokenKind::Trait => self.advance().and_then(|()| self.trait_()),
       TokenKind::Type => self.advance().and_then(|()| self.type_decl()),
-      _ => self.error_current("Can only export variable, function or class declarations."),
+      TokenKind::Import => self
+        .advance()
+        .and_then(|()| self.import_declaration())
+        .map(Symbol::Import),
+      _ => self.error_current(
+        "Can only export variable, constant, function, class or import declarations.",
+      ),
     }?;
 
     Ok(Decl::Export(self.node(symbol)))
@@ -453,20 +724,35 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
 
   /// Parse an import statement
   fn import(&mut self) -> ParseResult<Stmt<'a>, FileId> {
+    self
+      .import_declaration()
+      .map(|import| Stmt::Import(self.node(import)))
+  }
+
+  /// Parse the path and bindings of an import, shared by import
+  /// statements and re-exports such as `export import ./utils:{trim};`
+  fn import_declaration(&mut self) -> ParseResult<Import<'a>, FileId> {
     if self.scope_depth > 0 {
       return self.error_current("Can only import from the module scope.");
     }
 
-    self.consume(
-      TokenKind::Identifier,
-      "Expected package name following import.",
-    )?;
-    let mut path = vec![self.previous.clone()];
+    let path = if self.check(TokenKind::Dot) {
+      self.relative_import_path()?
+    } else {
+      self.consume(
+        TokenKind::Identifier,
+        "Expected package name following import.",
+      )?;
+      let mut path = vec![self.previous.clone()];
 
-    while self.match_kind(TokenKind::Dot)? {
-      self.consume(TokenKind::Identifier, "Expect import path after '.'")?;
-      path.push(self.previous.clone())
-    }
+      // package paths separate modules with either '.' or '/'
+      while self.match_kind(TokenKind::Dot)? || self.match_kind(TokenKind::Slash)? {
+        self.consume(TokenKind::Identifier, "Expect import path after '.' or '/'")?;
+        path.push(self.previous.clone())
+      }
+
+      path
+    };
 
     let stem = match self.current.kind() {
       TokenKind::Colon => self.advance().and_then(|()| {
@@ -507,7 +793,49 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
 
     self
       .consume_basic(TokenKind::Semicolon, "Expected ';' after value.")
-      .map(|()| Stmt::Import(self.node(Import::new(path, stem))))
+      .map(|()| Import::new(path, stem))
+  }
+
+  /// Parse an import path relative to the importing module such as
+  /// `./utils` or `../shared/helpers`. The path starts with `.` or `..`
+  /// segments followed by module names all separated by `/`
+  fn relative_import_path(&mut self) -> ParseResult<Vec<Token<'a>>, FileId> {
+    let mut path = vec![];
+
+    while self.match_kind(TokenKind::Dot)? {
+      let dot = self.previous.clone();
+
+      // the scanner produces two adjacent dots for a parent segment
+      if self.check(TokenKind::Dot) && self.current.start() == dot.end() {
+        self.advance()?;
+        path.push(Token::new(
+          TokenKind::Dot,
+          Lexeme::Slice(".."),
+          dot.start(),
+          self.previous.end(),
+        ));
+      } else {
+        path.push(dot);
+      }
+
+      self.consume(
+        TokenKind::Slash,
+        "Expected '/' after '.' or '..' in import path.",
+      )?;
+    }
+
+    self.consume(
+      TokenKind::Identifier,
+      "Expected module name in relative import path.",
+    )?;
+    path.push(self.previous.clone());
+
+    while self.match_kind(TokenKind::Slash)? {
+      self.consume(TokenKind::Identifier, "Expected module name after '/'.")?;
+      path.push(self.previous.clone());
+    }
+
+    Ok(path)
   }
 
   /// Parse a try catch block
@@ -516,11 +844,37 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
       .consume(TokenKind::LeftBrace, "Expected '{' after try.")
       .and_then(|()| self.block(BlockReturn::Cannot))?;
 
+    self.consume(TokenKind::Catch, "Expected 'catch' after try block.")?;
+
+    // optionally bind the caught error
+    let error = if self.match_kind(TokenKind::Identifier)? {
+      Some(self.previous.clone())
+    } else {
+      None
+    };
+
     self
-      .consume(TokenKind::Catch, "Expected 'catch' after try block.")
-      .and_then(|()| self.consume_basic(TokenKind::LeftBrace, "Expected '{' after catch."))
+      .consume_basic(TokenKind::LeftBrace, "Expected '{' after catch.")
       .and_then(|()| self.block(BlockReturn::Cannot))
-      .map(|catch| Stmt::Try(self.node(Try::new(block, catch))))
+      .map(|catch| Stmt::Try(self.node(Try::new(block, error, catch))))
+  }
+
+  /// Parse a with statement
+  fn with(&mut self) -> ParseResult<Stmt<'a>, FileId> {
+    let manager = self.expr()?;
+
+    // optionally bind the value returned from enter
+    let name = if self.match_kind(TokenKind::As)? {
+      self.consume(TokenKind::Identifier, "Expected identifier after 'as'.")?;
+      Some(self.previous.clone())
+    } else {
+      None
+    };
+
+    self
+      .consume(TokenKind::LeftBrace, "Expected '{' after with expression.")
+      .and_then(|()| self.block(BlockReturn::Cannot))
+      .map(|body| Stmt::With(self.node(With::new(manager, name, body))))
   }
 
   /// Parse a if statement
@@ -610,6 +964,23 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
     }
   }
 
+  /// Parse a raise statement, optionally naming the error that caused it
+  fn raise(&mut self) -> ParseResult<Stmt<'a>, FileId> {
+    let raise = self.previous.clone();
+    let error = self.expr()?;
+
+    // 'from' is only a keyword directly after the raised error
+    let cause = if self.current.kind() == TokenKind::Identifier && self.current.str() == FROM {
+      self.advance()?;
+      Some(self.expr()?)
+    } else {
+      None
+    };
+
+    self.consume_basic(TokenKind::Semicolon, "Expected ';' after raise value.")?;
+    Ok(Stmt::Raise(self.node(Raise::new(raise, error, cause))))
+  }
+
   fn continue_(&mut self) -> ParseResult<Stmt<'a>, FileId> {
     if self.loop_depth == 0 {
       return self.error("Cannot continue from outside of a loop.");
@@ -633,10 +1004,23 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
   /// Parse an expression statement
   fn expr_stmt(&mut self) -> ParseResult<Stmt<'a>, FileId> {
     let expr = self.expr()?;
+    self.expr_stmt_end(expr)
+  }
+
+  /// Finish an expression statement after its expression
+  fn expr_stmt_end(&mut self, expr: Expr<'a>) -> ParseResult<Stmt<'a>, FileId> {
     if self.match_kind(TokenKind::Semicolon)? {
       Ok(Stmt::Expr(self.node(expr)))
     } else {
       if let BlockReturn::Cannot = self.block_return {
+        if self.repl
+          && self.fun_kind == FunKind::Script
+          && self.scope_depth == 0
+          && self.current.kind() == TokenKind::Eof
+        {
+          return Ok(Stmt::Expr(self.node(expr)));
+        }
+
         return self.error("Expected ';' after expression");
       }
 
@@ -647,7 +1031,11 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
   /// Parse an expression using a Pratt parser
   fn parse_precedence(&mut self, precedence: Precedence) -> ParseResult<Expr<'a>, FileId> {
     self.advance()?;
+    self.parse_prefixed(precedence)
+  }
 
+  /// Continue parsing an expression whose first token was just consumed
+  fn parse_prefixed(&mut self, precedence: Precedence) -> ParseResult<Expr<'a>, FileId> {
     let can_assign = precedence <= Precedence::Assignment;
     let prefix_fn = get_prefix(self.previous.kind()).op;
 
@@ -682,7 +1070,9 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
       Prefix::AssignBlock => self.assign_block(),
       Prefix::List => self.list(),
       Prefix::Map => self.map(),
-      Prefix::Lambda => self.lambda(),
+      Prefix::Async => self.async_lambda(),
+      Prefix::Class => self.class_expr(),
+      Prefix::Lambda => self.lambda(false),
       Prefix::Grouping => self.grouping(),
       Prefix::Literal => Ok(self.literal()),
       Prefix::Number => Ok(self.number()),
@@ -718,7 +1108,7 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
       TokenKind::LeftBrace,
       "Expected '{' after assignment block ':'",
     )?;
-    let block = self.block(BlockReturn::Cannot)?;
+    let block = self.block(BlockReturn::Can)?;
     Ok(self.atom(Primary::AssignBlock(block)))
   }
 
@@ -814,6 +1204,7 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
     let op = match operator_kind {
       TokenKind::Minus => UnaryOp::Negate,
       TokenKind::Bang => UnaryOp::Not,
+      TokenKind::Await => UnaryOp::Await,
       _ => unimplemented!("Unexpected unary operator"),
     };
 
@@ -844,13 +1235,19 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
 
   /// Parse an index on an atom
   fn index(&mut self, mut expr: Expr<'a>, can_assign: bool) -> ParseResult<Expr<'a>, FileId> {
+    let start = self.previous.start();
     let indexer = self.expr()?;
     self.consume_basic(TokenKind::RightBracket, "Expected ']' after index")?;
 
+    let range = Span {
+      start,
+      end: self.previous.end(),
+    };
+
     if let Expr::Atom(atom) = &mut expr {
       atom
         .trailers
-        .push(Trailer::Index(self.node(Index::new(indexer))))
+        .push(Trailer::Index(self.node(Index::new(range, indexer))))
     } else {
       return self.error("Expected expression. TODO can you get here?");
     }
@@ -881,28 +1278,59 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
     Ok(expr)
   }
 
+  /// Parse an async lambda expression
+  fn async_lambda(&mut self) -> ParseResult<Expr<'a>, FileId> {
+    self.consume_basic(TokenKind::Pipe, "Expected '|' after 'async'.")?;
+    self.lambda(true)
+  }
+
   /// Parse a lambda expression
-  fn lambda(&mut self) -> ParseResult<Expr<'a>, FileId> {
+  fn lambda(&mut self, is_async: bool) -> ParseResult<Expr<'a>, FileId> {
     // parse function parameters
     let call_sig = self.call_signature(TokenKind::Pipe, vec![])?;
 
     let previous = mem::replace(&mut self.fun_kind, FunKind::Fun);
-    let lambda = self
-      .fun_body(BlockReturn::Can)
-      .map(|body| self.atom(Primary::Lambda(self.node(Fun::new(None, call_sig, body)))));
+    let lambda = self.fun_body(BlockReturn::Can).map(|body| {
+      let fun = Fun::new(None, call_sig, body).with_async(is_async);
+      self.atom(Primary::Lambda(self.node(fun)))
+    });
 
     self.fun_kind = previous;
     lambda
   }
 
-  /// Parse a grouping expression
+  /// Parse a grouping expression or a tuple literal. A tuple is
+  /// either empty `()` or has at least one comma `(a,)`
   fn grouping(&mut self) -> ParseResult<Expr<'a>, FileId> {
+    let start = self.previous.start();
+
+    if self.match_kind(TokenKind::RightParen)? {
+      return Ok(self.tuple(start, vec![]));
+    }
+
     let expr = self.expr()?;
+    if self.match_kind(TokenKind::Comma)? {
+      let mut items = vec![expr];
+      items.extend(self.consume_arguments(TokenKind::RightParen, u16::MAX as usize - 1)?);
+      self.consume_basic(TokenKind::RightParen, "Expected ')' after tuple items")?;
+
+      return Ok(self.tuple(start, items));
+    }
+
     self.consume_basic(TokenKind::RightParen, "Expected ')' after expression")?;
 
     Ok(self.atom(Primary::Grouping(self.node(expr))))
   }
 
+  /// Create a tuple literal ending at the previous token
+  fn tuple(&self, start: u32, items: Vec<Expr<'a>>) -> Expr<'a> {
+    let range = Span {
+      start,
+      end: self.previous.end(),
+    };
+    self.atom(Primary::Tuple(Tuple::new(range, items)))
+  }
+
   /// Compile a variable statement
   fn variable(&mut self, can_assign: bool) -> ParseResult<Expr<'a>, FileId> {
     let mut expr = self.atom(Primary::Ident(self.previous.clone()));
@@ -1170,14 +1598,41 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
     // parse function parameters
     let call_sig = self.call_signature(TokenKind::RightParen, type_params)?;
 
+    if self.match_kind(TokenKind::FatArrow)? {
+      return self
+        .expr_body(block_return)
+        .map(|body| Fun::new(Some(name), call_sig, body));
+    }
+
     if !self.match_kind(TokenKind::LeftBrace)? {
-      return self.error_current(&format!("Expected '{{' after {} signature.", self.fun_kind));
+      return self.error_current(&format!(
+        "Expected '{{' or '=>' after {} signature.",
+        self.fun_kind
+      ));
     }
     self
       .block(block_return)
       .map(|body| Fun::new(Some(name), call_sig, FunBody::Block(self.node(body))))
   }
 
+  /// Parse the single expression body of a function such as `fn double(x) => x * 2;`
+  fn expr_body(&mut self, block_return: BlockReturn) -> ParseResult<FunBody<'a>, FileId> {
+    if let BlockReturn::Cannot = block_return {
+      return self.error(&format!(
+        "A {} cannot have an expression body.",
+        self.fun_kind
+      ));
+    }
+
+    let expr = self.expr()?;
+    self.consume_basic(
+      TokenKind::Semicolon,
+      "Expected ';' after function expression body.",
+    )?;
+
+    Ok(FunBody::Expr(self.node(expr)))
+  }
+
   /// Parse a method declaration and body
   fn method(
     &mut self,
@@ -1421,9 +1876,23 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
     }
 
     let token = self.current.clone();
+
+    // an unterminated string always runs into the end of the source
+    if self.errors.is_empty() && token.str() == UNTERMINATED_STRING {
+      self.incomplete = true;
+    }
+
     self.error_current(token.str())
   }
 
+  /// Advance the current token index recording rather than
+  /// returning any scanner errors
+  fn advance_recover(&mut self) {
+    if let Err(error) = self.advance() {
+      self.errors.push(error);
+    }
+  }
+
   /// Consume a token and advance the current token index
   #[inline]
   fn consume(&mut self, kind: TokenKind, message: &str) -> ParseResult<(), FileId> {
@@ -1446,11 +1915,11 @@ impl<'a, FileId: Copy> Parser<'a, FileId> {
 
   /// Print an error to the console for a user to address
   fn error_at<T>(&mut self, token: Token<'a>, message: &str) -> ParseResult<T, FileId> {
-    let error = Diagnostic::error()
-      .with_message(message)
-      .with_labels(vec![Label::primary(self.file_id, token.span())]);
+    if self.errors.is_empty() && token.kind() == TokenKind::Eof {
+      self.incomplete = true;
+    }
 
-    Err(error)
+    Err(diagnostics::error_at(message, self.file_id, token.span()))
   }
 }
 
@@ -1511,6 +1980,8 @@ impl<T, P> Rule<T, P> {
 #[derive(Clone, Copy)]
 enum Prefix {
   AssignBlock,
+  Async,
+  Class,
   Grouping,
   Lambda,
   List,
@@ -1549,7 +2020,7 @@ enum TypeInfix {
   Union,
 }
 
-const TOKEN_VARIANTS: usize = 63;
+const TOKEN_VARIANTS: usize = 71;
 
 /// The rules for infix and prefix operators
 const PREFIX_TABLE: [Rule<Prefix, Precedence>; TOKEN_VARIANTS] = [
@@ -1594,6 +2065,8 @@ const PREFIX_TABLE: [Rule<Prefix, Precedence>; TOKEN_VARIANTS] = [
   Rule::new(None, Precedence::None),
   // ARROW
   Rule::new(None, Precedence::None),
+  // FAT_ARROW
+  Rule::new(None, Precedence::None),
   // EXPORT
   Rule::new(None, Precedence::None),
   // IMPORT
@@ -1631,9 +2104,11 @@ const PREFIX_TABLE: [Rule<Prefix, Precedence>; TOKEN_VARIANTS] = [
   // NUMBER
   Rule::new(None, Precedence::None),
   // AND
-  Rule::new(None, Precedence::None),
+  Rule::new(Some(Prefix::Class), Precedence::Call),
   // CLASS
   Rule::new(None, Precedence::None),
+  // EXTEND
+  Rule::new(None, Precedence::None),
   // ELSE
   Rule::new(Some(Prefix::Literal), Precedence::None),
   // FALSE
@@ -1652,6 +2127,8 @@ const PREFIX_TABLE: [Rule<Prefix, Precedence>; TOKEN_VARIANTS] = [
   Rule::new(None, Precedence::None),
   // RETURN
   Rule::new(None, Precedence::None),
+  // RAISE
+  Rule::new(None, Precedence::None),
   // BREAK
   Rule::new(None, Precedence::None),
   // CONTINUE
@@ -1661,20 +2138,30 @@ const PREFIX_TABLE: [Rule<Prefix, Precedence>; TOKEN_VARIANTS] = [
   // SELF
   Rule::new(None, Precedence::None),
   // STATIC
+  Rule::new(None, Precedence::None),
+  // SEALED
   Rule::new(Some(Prefix::Literal), Precedence::None),
   // TRUE
   Rule::new(None, Precedence::None),
   // VAR
   Rule::new(None, Precedence::None),
+  // CONST
+  Rule::new(None, Precedence::None),
   // WHILE
   Rule::new(None, Precedence::None),
   // TRY
   Rule::new(None, Precedence::None),
   // CATCH
   Rule::new(None, Precedence::None),
-  // TRAIT
+  // WITH
   Rule::new(None, Precedence::None),
+  // TRAIT
+  Rule::new(Some(Prefix::Variable), Precedence::None),
   // TYPE
+  Rule::new(Some(Prefix::Async), Precedence::Call),
+  // ASYNC
+  Rule::new(Some(Prefix::Unary), Precedence::None),
+  // AWAIT
   Rule::new(None, Precedence::None),
   // ERROR
   Rule::new(None, Precedence::None),
@@ -1724,6 +2211,8 @@ const INFIX_TABLE: [Rule<Infix, Precedence>; TOKEN_VARIANTS] = [
   Rule::new(None, Precedence::None),
   // ARROW
   Rule::new(None, Precedence::None),
+  // FAT_ARROW
+  Rule::new(None, Precedence::None),
   // EXPORT
   Rule::new(None, Precedence::None),
   // IMPORT
@@ -1764,6 +2253,8 @@ const INFIX_TABLE: [Rule<Infix, Precedence>; TOKEN_VARIANTS] = [
   Rule::new(None, Precedence::None),
   // CLASS
   Rule::new(None, Precedence::None),
+  // EXTEND
+  Rule::new(None, Precedence::None),
   // ELSE
   Rule::new(None, Precedence::None),
   // FALSE
@@ -1782,6 +2273,8 @@ const INFIX_TABLE: [Rule<Infix, Precedence>; TOKEN_VARIANTS] = [
   Rule::new(None, Precedence::None),
   // RETURN
   Rule::new(None, Precedence::None),
+  // RAISE
+  Rule::new(None, Precedence::None),
   // BREAK
   Rule::new(None, Precedence::None),
   // CONTINUE
@@ -1792,20 +2285,30 @@ const INFIX_TABLE: [Rule<Infix, Precedence>; TOKEN_VARIANTS] = [
   Rule::new(None, Precedence::None),
   // STATIC
   Rule::new(None, Precedence::None),
+  // SEALED
+  Rule::new(None, Precedence::None),
   // TRUE
   Rule::new(None, Precedence::None),
   // VAR
   Rule::new(None, Precedence::None),
+  // CONST
+  Rule::new(None, Precedence::None),
   // WHILE
   Rule::new(None, Precedence::None),
   // TRY
   Rule::new(None, Precedence::None),
   // CATCH
   Rule::new(None, Precedence::None),
+  // WITH
+  Rule::new(None, Precedence::None),
   // TRAIT
   Rule::new(None, Precedence::None),
   // TYPE
   Rule::new(None, Precedence::None),
+  // ASYNC
+  Rule::new(None, Precedence::None),
+  // AWAIT
+  Rule::new(None, Precedence::None),
   // ERROR
   Rule::new(None, Precedence::None),
   // EOF
@@ -1854,6 +2357,8 @@ const TYPE_PREFIX_TABLE: [Rule<TypePrefix, TypePrecedence>; TOKEN_VARIANTS] = [
   Rule::new(None, TypePrecedence::None),
   // ARROW
   Rule::new(None, TypePrecedence::None),
+  // FAT_ARROW
+  Rule::new(None, TypePrecedence::None),
   // EXPORT
   Rule::new(None, TypePrecedence::None),
   // IMPORT
@@ -1894,6 +2399,8 @@ const TYPE_PREFIX_TABLE: [Rule<TypePrefix, TypePrecedence>; TOKEN_VARIANTS] = [
   Rule::new(None, TypePrecedence::None),
   // CLASS
   Rule::new(None, TypePrecedence::None),
+  // EXTEND
+  Rule::new(None, TypePrecedence::None),
   // ELSE
   Rule::new(None, TypePrecedence::None),
   // FALSE
@@ -1912,6 +2419,8 @@ const TYPE_PREFIX_TABLE: [Rule<TypePrefix, TypePrecedence>; TOKEN_VARIANTS] = [
   Rule::new(None, TypePrecedence::None),
   // RETURN
   Rule::new(None, TypePrecedence::None),
+  // RAISE
+  Rule::new(None, TypePrecedence::None),
   // BREAK
   Rule::new(None, TypePrecedence::None),
   // CONTINUE
@@ -1922,20 +2431,30 @@ const TYPE_PREFIX_TABLE: [Rule<TypePrefix, TypePrecedence>; TOKEN_VARIANTS] = [
   Rule::new(None, TypePrecedence::None),
   // STATIC
   Rule::new(None, TypePrecedence::None),
+  // SEALED
+  Rule::new(None, TypePrecedence::None),
   // TRUE
   Rule::new(None, TypePrecedence::None),
   // VAR
   Rule::new(None, TypePrecedence::None),
+  // CONST
+  Rule::new(None, TypePrecedence::None),
   // WHILE
   Rule::new(None, TypePrecedence::None),
   // TRY
   Rule::new(None, TypePrecedence::None),
   // CATCH
   Rule::new(None, TypePrecedence::None),
+  // WITH
+  Rule::new(None, TypePrecedence::None),
   // TRAIT
   Rule::new(None, TypePrecedence::None),
   // TYPE
   Rule::new(None, TypePrecedence::None),
+  // ASYNC
+  Rule::new(None, TypePrecedence::None),
+  // AWAIT
+  Rule::new(None, TypePrecedence::None),
   // ERROR
   Rule::new(None, TypePrecedence::None),
   // EOF
@@ -1984,6 +2503,8 @@ const TYPE_INFIX_TABLE: [Rule<TypeInfix, TypePrecedence>; TOKEN_VARIANTS] = [
   Rule::new(None, TypePrecedence::None),
   // ARROW
   Rule::new(None, TypePrecedence::None),
+  // FAT_ARROW
+  Rule::new(None, TypePrecedence::None),
   // EXPORT
   Rule::new(None, TypePrecedence::None),
   // IMPORT
@@ -2024,6 +2545,8 @@ const TYPE_INFIX_TABLE: [Rule<TypeInfix, TypePrecedence>; TOKEN_VARIANTS] = [
   Rule::new(None, TypePrecedence::None),
   // CLASS
   Rule::new(None, TypePrecedence::None),
+  // EXTEND
+  Rule::new(None, TypePrecedence::None),
   // ELSE
   Rule::new(None, TypePrecedence::None),
   // FALSE
@@ -2042,6 +2565,8 @@ const TYPE_INFIX_TABLE: [Rule<TypeInfix, TypePrecedence>; TOKEN_VARIANTS] = [
   Rule::new(None, TypePrecedence::None),
   // RETURN
   Rule::new(None, TypePrecedence::None),
+  // RAISE
+  Rule::new(None, TypePrecedence::None),
   // BREAK
   Rule::new(None, TypePrecedence::None),
   // CONTINUE
@@ -2052,20 +2577,30 @@ const TYPE_INFIX_TABLE: [Rule<TypeInfix, TypePrecedence>; TOKEN_VARIANTS] = [
   Rule::new(None, TypePrecedence::None),
   // STATIC
   Rule::new(None, TypePrecedence::None),
+  // SEALED
+  Rule::new(None, TypePrecedence::None),
   // TRUE
   Rule::new(None, TypePrecedence::None),
   // VAR
   Rule::new(None, TypePrecedence::None),
+  // CONST
+  Rule::new(None, TypePrecedence::None),
   // WHILE
   Rule::new(None, TypePrecedence::None),
   // TRY
   Rule::new(None, TypePrecedence::None),
   // CATCH
   Rule::new(None, TypePrecedence::None),
+  // WITH
+  Rule::new(None, TypePrecedence::None),
   // TRAIT
   Rule::new(None, TypePrecedence::None),
   // TYPE
   Rule::new(None, TypePrecedence::None),
+  // ASYNC
+  Rule::new(None, TypePrecedence::None),
+  // AWAIT
+  Rule::new(None, TypePrecedence::None),
   // ERROR
   Rule::new(None, TypePrecedence::None),
   // EOF
@@ -2091,28 +2626,46 @@ const fn get_type_infix(kind: TokenKind) -> &'static Rule<TypeInfix, TypePrecede
   &TYPE_INFIX_TABLE[kind as usize]
 }
 
+/// Does the provided repl line end before its statements are complete, for
+/// example with an unclosed block, grouping or string. Sources that
+/// contain an error before their end are never considered incomplete
+///
+/// # Examples
+/// ```
+/// use laythe_vm::{compiler::is_incomplete, source::Source};
+///
+/// assert!(is_incomplete(&Source::new("fn example() {")));
+/// assert!(!is_incomplete(&Source::new("fn example() {}")));
+/// assert!(!is_incomplete(&Source::new("let a = ;")));
+/// assert!(!is_incomplete(&Source::new("1 + 2")));
+/// ```
+pub fn is_incomplete(source: &Source) -> bool {
+  let arena = Arena::for_source(source);
+  let mut parser = Parser::new(source, &arena, ()).with_repl(true);
+
+  parser.parse_inner().is_err() && parser.incomplete
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
   use crate::ast_printer::AstPrint;
-  use laythe_core::memory::{Allocator, NO_GC};
 
   fn test(source: &str) {
-    let mut gc = Allocator::default();
-    let source = gc.manage_str(source, &NO_GC);
     let source = Source::new(source);
+    let arena = Arena::for_source(&source);
 
-    let (ast, _) = Parser::new(&source, 0).parse();
+    let (ast, _) = Parser::new(&source, &arena, 0).parse();
     assert!(ast.is_ok());
     let ast = ast.unwrap();
 
     let mut printer = AstPrint::default();
     printer.visit(&ast);
 
-    let reproduced = gc.manage_str(printer.str(), &NO_GC);
-    let reproduced = Source::new(reproduced);
+    let reproduced = Source::new(printer.str());
+    let reproduced_arena = Arena::for_source(&reproduced);
 
-    let (ast2, _) = Parser::new(&reproduced, 0).parse();
+    let (ast2, _) = Parser::new(&reproduced, &reproduced_arena, 0).parse();
     assert!(
       ast2.is_ok(),
       "expected:\n{}, \ngenerated: \n{}",
@@ -2121,6 +2674,113 @@ mod test {
     );
   }
 
+  fn test_errors(source: &str) -> Vec<Diagnostic<usize>> {
+    let source = Source::new(source);
+    let arena = Arena::for_source(&source);
+
+    let (ast, _) = Parser::new(&source, &arena, 0).parse();
+    match ast {
+      Ok(_) => panic!("expected parse errors"),
+      Err(errors) => errors,
+    }
+  }
+
+  #[test]
+  fn incomplete() {
+    let examples = [
+      "fn example() {",
+      "fn example() {\n  let a = 10;\n",
+      "class A {\n  init() {}\n",
+      "print(1,",
+      "let a = [1, 2",
+      "let a = \"unterminated",
+      "if true {\n} else {",
+      "let a = 10",
+    ];
+
+    for example in examples.iter() {
+      assert!(is_incomplete(&Source::new(example)), "{}", example);
+    }
+  }
+
+  #[test]
+  fn complete() {
+    let examples = [
+      "",
+      "let a = 10;",
+      "fn example() {\n  let a = 10;\n}",
+      "let a = ;\nfn example() {",
+      "let a = 10 $",
+      "}",
+      "1 + 2",
+      "let a = 10;\na",
+    ];
+
+    for example in examples.iter() {
+      assert!(!is_incomplete(&Source::new(example)), "{}", example);
+    }
+  }
+
+  #[test]
+  fn repl_trailing_expr() {
+    let repl = |example| {
+      let source = Source::new(example);
+      let arena = Arena::for_source(&source);
+      let (ast, _) = Parser::new(&source, &arena, 0).with_repl(true).parse();
+      ast.is_ok()
+    };
+
+    assert!(repl("1 + 2"));
+    assert!(repl("let a = 10;\na"));
+    assert!(!repl("1 + 2\nlet a = 10;"));
+    assert!(!repl("if true { 1 }"));
+
+    test_errors("1 + 2");
+  }
+
+  #[test]
+  fn multiple_errors() {
+    let example = "
+      let a = ;
+      let b = 10;
+      print(b +);
+      let c = );
+    ";
+
+    assert_eq!(test_errors(example).len(), 3);
+  }
+
+  #[test]
+  fn multiple_errors_in_block() {
+    let example = "
+      fn example() {
+        let a = ;
+        if a {
+          a.b.(;
+        }
+        return a
+      }
+
+      let b = ;
+    ";
+
+    assert_eq!(test_errors(example).len(), 4);
+  }
+
+  #[test]
+  fn scanner_errors_recovered() {
+    let example = "
+      let a = \"unterminated;
+    ";
+    assert_eq!(test_errors(example).len(), 1);
+
+    let example = "
+      let a = 10 $ 3;
+      let b = ;
+    ";
+    assert_eq!(test_errors(example).len(), 2);
+  }
+
   #[test]
   fn fib() {
     let example = "
@@ -2207,6 +2867,27 @@ mod test {
     test(example);
   }
 
+  #[test]
+  fn import_package() {
+    let example = r#"
+      import shapes/circle;
+      import shapes/geometry/area:{ square };
+    "#;
+
+    test(example);
+  }
+
+  #[test]
+  fn import_relative() {
+    let example = r#"
+      import ./utils;
+      import ../shared/helpers as h;
+      import ../../lib/strings:{ trim, pad as padLeft };
+    "#;
+
+    test(example);
+  }
+
   #[test]
   fn export_variable() {
     let example = "
@@ -2255,6 +2936,17 @@ mod test {
     test(example);
   }
 
+  #[test]
+  fn export_import() {
+    let example = r#"
+      export import std.time;
+      export import ./parts/thing as t;
+      export import ../shared/helpers:{ trim, pad as padLeft };
+    "#;
+
+    test(example);
+  }
+
   #[test]
   fn export_class_typed() {
     let examples = [
@@ -2447,6 +3139,100 @@ mod test {
     test(example);
   }
 
+  #[test]
+  fn const_() {
+    let example = r#"
+      const MAX = 100;
+      export const NAME = "laythe";
+    "#;
+
+    test(example);
+  }
+
+  #[test]
+  fn expression_body() {
+    let example = r#"
+      fn double(x) => x * 2;
+
+      class Point {
+        sum() => self.x + self.y;
+        static origin() => Point(0, 0);
+      }
+
+      let area = :{ let width = 3; width * 4 };
+    "#;
+
+    test(example);
+  }
+
+  #[test]
+  fn with_() {
+    let example = r#"
+      with open("file") as file {
+        print(file.read());
+      }
+
+      with lock {
+        print("locked");
+      }
+    "#;
+
+    test(example);
+  }
+
+  #[test]
+  fn type_call() {
+    let example = "
+      type Id = number | string;
+      type(10);
+      let cls = type(\"example\");
+      print(type(cls).name());
+    ";
+
+    test(example);
+  }
+
+  #[test]
+  fn class_expression() {
+    let example = "
+      class Base {}
+      let Anonymous = class : Base {
+        init() { self.x = 10; }
+      };
+      let Named = class Named { static make() { return Named(); } };
+      print([class {}, class {}].len());
+    ";
+
+    test(example);
+  }
+
+  #[test]
+  fn extension() {
+    let example = "
+      extend String {
+        shout() => self + '!';
+        async wait() { return self; }
+        static make() { return ''; }
+      }
+    ";
+
+    test(example);
+  }
+
+  #[test]
+  fn sealed_class() {
+    let example = "
+      sealed class A {
+        init() { self.x = 1; }
+        method() => self.x;
+        static make() => A();
+      }
+      export sealed class B {}
+    ";
+
+    test(example);
+  }
+
   #[test]
   fn open_upvalue() {
     let example = "
@@ -2579,6 +3365,17 @@ mod test {
     test(example);
   }
 
+  #[test]
+  fn raise() {
+    let example = "
+    raise Error('error');
+    fn example(err) { raise ValueError('value') from err; }
+    let from = 10;
+    ";
+
+    test(example);
+  }
+
   #[test]
   fn block_empty() {
     let example = ":{};";
@@ -2633,6 +3430,38 @@ mod test {
     test(example);
   }
 
+  #[test]
+  fn async_fun() {
+    let example = "
+    async fn example(a) { return await other(a); }
+    export async fn exported() {}
+    ";
+
+    test(example);
+  }
+
+  #[test]
+  fn async_lambda() {
+    let example = "
+    let example = async |a| await a;
+    let block = async || { return 10; };
+    ";
+
+    test(example);
+  }
+
+  #[test]
+  fn async_method() {
+    let example = "
+    class A {
+      async method() { return 10; }
+      static async staticMethod() { return await self.method(); }
+    }
+    ";
+
+    test(example);
+  }
+
   #[test]
   fn map() {
     let example = "let a = { \"cat\": \"bat\", 10: nil };";
@@ -2646,6 +3475,13 @@ mod test {
 
     test(example);
   }
+
+  #[test]
+  fn tuple() {
+    let example = "let (a, b) = (1, ((2,), ()));";
+
+    test(example);
+  }
   const EXAMPLE_TYPES: [&str; 7] = [
     "number | string",
     "MyThing<T, U>",
@@ -2686,7 +3522,7 @@ mod test {
   const EXAMPLE_TRAILERS: [&str; 3] = ["[2]", "(true, 10)", ".someProp"];
   const BINARY_OPS: [&str; 10] = ["!=", "==", ">", ">=", "<", "<=", "+", "-", "*", "/"];
   const ASSIGNMENTS: [&str; 5] = ["=", "+=", "-=", "/=", "*="];
-  const UNARY_OPS: [&str; 2] = ["!", "-"];
+  const UNARY_OPS: [&str; 3] = ["!", "-", "await "];
 
   #[test]
   fn expr_stmt() {
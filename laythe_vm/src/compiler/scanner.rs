@@ -4,7 +4,13 @@ use crate::{
   source::LineOffsets,
   token::{Lexeme, Token, TokenKind},
 };
-use laythe_core::utils::{next_boundary, previous_boundary};
+use laythe_core::{
+  parse::{scan_number, NumberError},
+  utils::{next_boundary, previous_boundary},
+};
+
+/// The error message for a string missing its closing quote
+pub const UNTERMINATED_STRING: &str = "Unterminated string.";
 
 /// Tracking information for one layer of string interpolation
 struct Interpolation<'a> {
@@ -204,6 +210,8 @@ impl<'a> Scanner<'a> {
       "=" => {
         if self.match_char("=") {
           self.make_token_source(TokenKind::EqualEqual)
+        } else if self.match_char(">") {
+          self.make_token_source(TokenKind::FatArrow)
         } else {
           self.make_token_source(TokenKind::Equal)
         }
@@ -256,14 +264,12 @@ impl<'a> Scanner<'a> {
   ///   token::TokenKind,
   ///   source::LineError,
   /// };
-  /// use laythe_core::memory::{Allocator, NO_GC};
   ///
-  /// let mut gc = Allocator::default();
-  /// let source = Source::new(gc.manage_str("
+  /// let source = Source::new("
   /// let x = \"something\";
   /// if x != \"something\" {
   ///   print(x);
-  /// }", &NO_GC));
+  /// }");
   ///
   /// let mut scanner = Scanner::new(&source);
   /// let token = scanner.scan_token();
@@ -320,39 +326,21 @@ impl<'a> Scanner<'a> {
 
   /// Generate a number token
   fn number(&mut self) -> Token<'a> {
-    // advance consecutive digits
-    while !self.is_at_end() && is_digit(self.peek()) {
+    let result = scan_number(&self.source[self.start..]);
+    let len = match result {
+      Ok(len) | Err(NumberError::MissingExponent(len)) => len,
+      Err(_) => unreachable!("Numbers are only scanned from a leading digit."),
+    };
+
+    // advance to the end of the literal
+    while self.char_start < self.start + len {
       self.advance_indices();
     }
 
-    // check if floating point format
-    if !self.is_at_end() && self.peek() == "." {
-      if let Some(next) = self.peek_next() {
-        if is_digit(next) {
-          self.advance_indices();
-          while !self.is_at_end() && is_digit(self.peek()) {
-            self.advance_indices();
-          }
-        }
-      }
-    }
-
-    if self.match_char("e") || self.match_char("E") {
-      match self.peek() {
-        "+" | "-" => self.advance_indices(),
-        _ => (),
-      }
-
-      if !is_digit(self.peek()) {
-        self.error_token("Unterminated scientific notation.");
-      }
-
-      while !self.is_at_end() && is_digit(self.peek()) {
-        self.advance_indices();
-      }
+    match result {
+      Ok(_) => self.make_token_source(TokenKind::Number),
+      Err(_) => self.error_token("Unterminated scientific notation."),
     }
-
-    self.make_token_source(TokenKind::Number)
   }
 
   /// Generate a string token
@@ -406,7 +394,7 @@ impl<'a> Scanner<'a> {
               }
 
               if self.is_at_end() {
-                return self.error_token("Unterminated string.");
+                return self.error_token(UNTERMINATED_STRING);
               }
               let unicode = &self.source[start..self.current - 1];
 
@@ -455,7 +443,7 @@ impl<'a> Scanner<'a> {
     }
 
     if self.is_at_end() {
-      return self.error_token("Unterminated string.");
+      return self.error_token(UNTERMINATED_STRING);
     }
 
     if self.peek() == quote_char && kind == TokenKind::StringSegment {
@@ -504,7 +492,11 @@ impl<'a> Scanner<'a> {
         "a" => match self.nth_char_from(self.start, 1) {
           Some(c2) => match c2 {
             "n" => self.check_keyword(2, "d", TokenKind::And),
-            "s" => self.check_keyword(2, "", TokenKind::As),
+            "s" => match self.nth_char_from(self.start, 2) {
+              Some("y") => self.check_keyword(3, "nc", TokenKind::Async),
+              _ => self.check_keyword(2, "", TokenKind::As),
+            },
+            "w" => self.check_keyword(2, "ait", TokenKind::Await),
             _ => TokenKind::Identifier,
           },
           None => TokenKind::Identifier,
@@ -513,7 +505,14 @@ impl<'a> Scanner<'a> {
         "c" => match self.nth_char_from(self.start, 1) {
           Some(c2) => match c2 {
             "a" => self.check_keyword(2, "tch", TokenKind::Catch),
-            "o" => self.check_keyword(2, "ntinue", TokenKind::Continue),
+            "o" => match self.nth_char_from(self.start, 3) {
+              Some(c4) => match c4 {
+                "s" => self.check_keyword(2, "nst", TokenKind::Const),
+                "t" => self.check_keyword(2, "ntinue", TokenKind::Continue),
+                _ => TokenKind::Identifier,
+              },
+              None => TokenKind::Identifier,
+            },
             "l" => self.check_keyword(2, "ass", TokenKind::Class),
             _ => TokenKind::Identifier,
           },
@@ -522,7 +521,14 @@ impl<'a> Scanner<'a> {
         "e" => match self.nth_char_from(self.start, 1) {
           Some(c2) => match c2 {
             "l" => self.check_keyword(2, "se", TokenKind::Else),
-            "x" => self.check_keyword(2, "port", TokenKind::Export),
+            "x" => match self.nth_char_from(self.start, 2) {
+              Some(c3) => match c3 {
+                "p" => self.check_keyword(3, "ort", TokenKind::Export),
+                "t" => self.check_keyword(3, "end", TokenKind::Extend),
+                _ => TokenKind::Identifier,
+              },
+              None => TokenKind::Identifier,
+            },
             _ => TokenKind::Identifier,
           },
           None => TokenKind::Identifier,
@@ -548,10 +554,24 @@ impl<'a> Scanner<'a> {
         "l" => self.check_keyword(1, "et", TokenKind::Let),
         "n" => self.check_keyword(1, "il", TokenKind::Nil),
         "o" => self.check_keyword(1, "r", TokenKind::Or),
-        "r" => self.check_keyword(1, "eturn", TokenKind::Return),
+        "r" => match self.nth_char_from(self.start, 1) {
+          Some(c2) => match c2 {
+            "e" => self.check_keyword(2, "turn", TokenKind::Return),
+            "a" => self.check_keyword(2, "ise", TokenKind::Raise),
+            _ => TokenKind::Identifier,
+          },
+          None => TokenKind::Identifier,
+        },
         "s" => match self.nth_char_from(self.start, 1) {
           Some(c2) => match c2 {
-            "e" => self.check_keyword(2, "lf", TokenKind::Self_),
+            "e" => match self.nth_char_from(self.start, 2) {
+              Some(c3) => match c3 {
+                "l" => self.check_keyword(3, "f", TokenKind::Self_),
+                "a" => self.check_keyword(3, "led", TokenKind::Sealed),
+                _ => TokenKind::Identifier,
+              },
+              None => TokenKind::Identifier,
+            },
             "u" => self.check_keyword(2, "per", TokenKind::Super),
             "t" => self.check_keyword(2, "atic", TokenKind::Static),
             _ => TokenKind::Identifier,
@@ -574,7 +594,14 @@ impl<'a> Scanner<'a> {
           },
           None => TokenKind::Identifier,
         },
-        "w" => self.check_keyword(1, "hile", TokenKind::While),
+        "w" => match self.nth_char_from(self.start, 1) {
+          Some(c2) => match c2 {
+            "h" => self.check_keyword(2, "ile", TokenKind::While),
+            "i" => self.check_keyword(2, "th", TokenKind::With),
+            _ => TokenKind::Identifier,
+          },
+          None => TokenKind::Identifier,
+        },
         _ => TokenKind::Identifier,
       },
       None => panic!(""),
@@ -772,6 +799,10 @@ mod test {
       TokenKind::Arrow,
       TokenGen::Symbol(Box::new(|| "->".to_string())),
     );
+    map.insert(
+      TokenKind::FatArrow,
+      TokenGen::Symbol(Box::new(|| "=>".to_string())),
+    );
     map.insert(
       TokenKind::Plus,
       TokenGen::Symbol(Box::new(|| "+".to_string())),
@@ -872,6 +903,10 @@ mod test {
       TokenKind::Export,
       TokenGen::ALpha(Box::new(|| "export".to_string())),
     );
+    map.insert(
+      TokenKind::Extend,
+      TokenGen::ALpha(Box::new(|| "extend".to_string())),
+    );
     map.insert(
       TokenKind::False,
       TokenGen::ALpha(Box::new(|| "false".to_string())),
@@ -912,6 +947,10 @@ mod test {
       TokenKind::Return,
       TokenGen::ALpha(Box::new(|| "return".to_string())),
     );
+    map.insert(
+      TokenKind::Raise,
+      TokenGen::ALpha(Box::new(|| "raise".to_string())),
+    );
     map.insert(
       TokenKind::Super,
       TokenGen::ALpha(Box::new(|| "super".to_string())),
@@ -924,6 +963,10 @@ mod test {
       TokenKind::Static,
       TokenGen::ALpha(Box::new(|| "static".to_string())),
     );
+    map.insert(
+      TokenKind::Sealed,
+      TokenGen::ALpha(Box::new(|| "sealed".to_string())),
+    );
     map.insert(
       TokenKind::True,
       TokenGen::ALpha(Box::new(|| "true".to_string())),
@@ -936,10 +979,18 @@ mod test {
       TokenKind::Let,
       TokenGen::ALpha(Box::new(|| "let".to_string())),
     );
+    map.insert(
+      TokenKind::Const,
+      TokenGen::ALpha(Box::new(|| "const".to_string())),
+    );
     map.insert(
       TokenKind::While,
       TokenGen::ALpha(Box::new(|| "while".to_string())),
     );
+    map.insert(
+      TokenKind::With,
+      TokenGen::ALpha(Box::new(|| "with".to_string())),
+    );
     map.insert(
       TokenKind::Trait,
       TokenGen::ALpha(Box::new(|| "trait".to_string())),
@@ -948,6 +999,14 @@ mod test {
       TokenKind::Type,
       TokenGen::ALpha(Box::new(|| "type".to_string())),
     );
+    map.insert(
+      TokenKind::Async,
+      TokenGen::ALpha(Box::new(|| "async".to_string())),
+    );
+    map.insert(
+      TokenKind::Await,
+      TokenGen::ALpha(Box::new(|| "await".to_string())),
+    );
     map.insert(
       TokenKind::Error,
       TokenGen::ALpha(Box::new(|| "$$".to_string())),
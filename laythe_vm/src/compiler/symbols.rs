@@ -0,0 +1,89 @@
+use crate::ast::Span;
+
+/// What kind of declaration introduced a module symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+  Variable,
+  Constant,
+  Function,
+  Class,
+  Import,
+}
+
+/// A symbol declared at the module scope
+#[derive(Debug, Clone)]
+pub struct ModuleSymbol {
+  /// The name of the symbol
+  pub name: String,
+
+  /// The declaration that introduced this symbol
+  pub kind: SymbolKind,
+
+  /// The span of the symbol's name in its declaration
+  pub span: Span,
+}
+
+/// The module symbols of a script and each place they are referenced. Filled
+/// in by the compiler when one is attached with `Compiler::with_symbols`
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+  /// The module symbols in the order they were declared
+  symbols: Vec<ModuleSymbol>,
+
+  /// Each reference to a global by name
+  references: Vec<(String, Span)>,
+}
+
+impl SymbolTable {
+  /// The module symbols in the order they were declared
+  pub fn symbols(&self) -> &[ModuleSymbol] {
+    &self.symbols
+  }
+
+  /// Find the declaration of the module symbol referenced or declared
+  /// at the provided source offset
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_vm::{ast::Span, compiler::{SymbolKind, SymbolTable}};
+  ///
+  /// let mut table = SymbolTable::default();
+  /// table.declare("a", SymbolKind::Variable, Span { start: 4, end: 5 });
+  /// table.reference("a", Span { start: 11, end: 12 });
+  ///
+  /// assert_eq!(table.definition(11).unwrap().span.start, 4);
+  /// assert!(table.definition(8).is_none());
+  /// ```
+  pub fn definition(&self, offset: u32) -> Option<&ModuleSymbol> {
+    let contains = |span: &Span| span.start <= offset && offset <= span.end;
+
+    let name = self
+      .symbols
+      .iter()
+      .find(|symbol| contains(&symbol.span))
+      .map(|symbol| symbol.name.as_str())
+      .or_else(|| {
+        self
+          .references
+          .iter()
+          .find(|(_, span)| contains(span))
+          .map(|(name, _)| name.as_str())
+      })?;
+
+    self.symbols.iter().find(|symbol| symbol.name == name)
+  }
+
+  /// Record a module symbol's declaration
+  pub fn declare(&mut self, name: &str, kind: SymbolKind, span: Span) {
+    self.symbols.push(ModuleSymbol {
+      name: name.to_string(),
+      kind,
+      span,
+    });
+  }
+
+  /// Record a reference to a global
+  pub fn reference(&mut self, name: &str, span: Span) {
+    self.references.push((name.to_string(), span));
+  }
+}
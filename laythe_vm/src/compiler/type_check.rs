@@ -0,0 +1,1374 @@
+use crate::{ast::*, token::Token};
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use std::{collections::HashMap, fmt, iter, rc::Rc};
+
+/// The type of a value as far as the checker can tell
+#[derive(Clone, Debug, PartialEq)]
+pub enum Ty {
+  /// A value of unknown type which is compatible with every type
+  Any,
+  Nil,
+  Bool,
+  Number,
+  String,
+  List(Box<Ty>),
+  Map,
+  Tuple,
+  Fun(Option<Rc<Sig>>),
+
+  /// A class declared in this module
+  Class(String),
+
+  /// An instance of a class declared in this module
+  Instance(String),
+
+  Union(Vec<Ty>),
+}
+
+impl Ty {
+  /// Is this a builtin type that does not support any operator overloading
+  fn is_primitive(&self) -> bool {
+    matches!(
+      self,
+      Ty::Nil
+        | Ty::Bool
+        | Ty::Number
+        | Ty::String
+        | Ty::List(_)
+        | Ty::Map
+        | Ty::Tuple
+        | Ty::Fun(_)
+        | Ty::Class(_)
+    )
+  }
+}
+
+impl fmt::Display for Ty {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Ty::Any => write!(f, "any"),
+      Ty::Nil => write!(f, "nil"),
+      Ty::Bool => write!(f, "bool"),
+      Ty::Number => write!(f, "number"),
+      Ty::String => write!(f, "string"),
+      Ty::List(item) => match &**item {
+        Ty::Any => write!(f, "list"),
+        Ty::Union(_) => write!(f, "({})[]", item),
+        item => write!(f, "{}[]", item),
+      },
+      Ty::Map => write!(f, "map"),
+      Ty::Tuple => write!(f, "tuple"),
+      Ty::Fun(_) => write!(f, "fun"),
+      Ty::Class(name) => write!(f, "class {}", name),
+      Ty::Instance(name) => write!(f, "{}", name),
+      Ty::Union(members) => {
+        for (idx, member) in members.iter().enumerate() {
+          if idx > 0 {
+            write!(f, " | ")?;
+          }
+          write!(f, "{}", member)?;
+        }
+        Ok(())
+      },
+    }
+  }
+}
+
+/// The declared signature of a function
+#[derive(Debug, PartialEq)]
+pub struct Sig {
+  /// The type of each parameter and the span of its annotation
+  params: Vec<(Ty, Option<Span>)>,
+
+  /// The declared return type
+  returns: Ty,
+}
+
+/// The members declared by a class
+#[derive(Default)]
+struct ClassInfo {
+  super_class: Option<String>,
+  init: Option<Rc<Sig>>,
+  fields: HashMap<String, (Ty, Span)>,
+  methods: HashMap<String, Rc<Sig>>,
+  static_methods: HashMap<String, Rc<Sig>>,
+}
+
+/// A name declared in some scope
+struct Binding {
+  name: String,
+  ty: Ty,
+
+  /// The span of this binding's type annotation. Only
+  /// annotated bindings have their assignments checked
+  annotation: Option<Span>,
+}
+
+/// The Laythe type checker. This pass walks the AST checking values
+/// against any type annotations that are present. Anything without an
+/// annotation is treated as `any` so untyped code is never rejected.
+/// Annotations are otherwise erased by the compiler
+pub struct TypeChecker<FileId> {
+  /// The id of the file being checked
+  file_id: FileId,
+
+  /// The bindings of each scope, the first being the module scope
+  scopes: Vec<Vec<Binding>>,
+
+  /// The classes declared so far
+  classes: HashMap<String, ClassInfo>,
+
+  /// The types declared with a type declaration
+  aliases: HashMap<String, Ty>,
+
+  /// The type parameters currently in scope
+  type_params: Vec<String>,
+
+  /// The declared return type of each enclosing function if any
+  returns: Vec<Option<(Ty, Span)>>,
+
+  /// The type of self in each enclosing method
+  self_types: Vec<Ty>,
+
+  /// All the errors found so far
+  errors: Vec<Diagnostic<FileId>>,
+}
+
+impl<FileId: Copy> TypeChecker<FileId> {
+  /// Create a new type checker for the provided file
+  pub fn new(file_id: FileId) -> Self {
+    Self {
+      file_id,
+      scopes: vec![],
+      classes: HashMap::new(),
+      aliases: HashMap::new(),
+      type_params: vec![],
+      returns: vec![],
+      self_types: vec![],
+      errors: vec![],
+    }
+  }
+
+  /// Check the provided module returning all type errors in source order
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_vm::{
+  ///   arena::Arena,
+  ///   compiler::{Parser, TypeChecker},
+  ///   source::Source,
+  /// };
+  ///
+  /// let source = Source::new("let a: number = \"example\";");
+  /// let arena = Arena::for_source(&source);
+  ///
+  /// let (ast, _) = Parser::new(&source, &arena, 0).parse();
+  /// let errors = TypeChecker::new(0).check(&ast.ok().unwrap());
+  /// assert_eq!(errors.len(), 1);
+  /// ```
+  pub fn check(mut self, module: &Module) -> Vec<Diagnostic<FileId>> {
+    self.visit(module);
+
+    self
+      .errors
+      .sort_by_key(|error| error.labels.first().map(|label| label.range.start));
+    self.errors
+  }
+
+  fn begin_scope(&mut self) {
+    self.scopes.push(vec![]);
+  }
+
+  fn end_scope(&mut self) {
+    self.scopes.pop().expect("Expected scope.");
+  }
+
+  /// Declare a name in the current scope
+  fn declare(&mut self, name: &Token, ty: Ty, annotation: Option<Span>) {
+    self
+      .scopes
+      .last_mut()
+      .expect("Expected scope.")
+      .push(Binding {
+        name: name.str().to_string(),
+        ty,
+        annotation,
+      });
+  }
+
+  /// Find the binding for a name
+  fn resolve(&self, name: &str) -> Option<&Binding> {
+    self
+      .scopes
+      .iter()
+      .rev()
+      .flat_map(|scope| scope.iter().rev())
+      .find(|binding| binding.name == name)
+  }
+
+  fn error(&mut self, message: String, labels: Vec<Label<FileId>>) {
+    self.errors.push(
+      Diagnostic::error()
+        .with_message(message)
+        .with_labels(labels),
+    );
+  }
+
+  /// Report an error if a value of type actual cannot be used where
+  /// a value of type expected is required
+  fn expect(&mut self, actual: &Ty, expected: &Ty, span: Span, annotation: Option<Span>) {
+    if self.assignable(actual, expected) {
+      return;
+    }
+
+    let mut labels = vec![Label::primary(self.file_id, span)];
+    if let Some(annotation) = annotation {
+      labels
+        .push(Label::secondary(self.file_id, annotation).with_message("expected due to this type"));
+    }
+
+    self.error(
+      format!("Expected type '{}' but found '{}'.", expected, actual),
+      labels,
+    );
+  }
+
+  /// Can a value of type actual be used where expected is required
+  fn assignable(&self, actual: &Ty, expected: &Ty) -> bool {
+    match (actual, expected) {
+      (Ty::Any, _) | (_, Ty::Any) => true,
+      (Ty::Union(members), _) => members
+        .iter()
+        .all(|member| self.assignable(member, expected)),
+      (_, Ty::Union(members)) => members.iter().any(|member| self.assignable(actual, member)),
+      (Ty::List(actual), Ty::List(expected)) => self.assignable(actual, expected),
+      (Ty::Fun(_), Ty::Fun(_)) => true,
+      (Ty::Instance(class), Ty::Instance(ancestor)) => {
+        self.ancestors(class).any(|name| name == ancestor)
+      },
+      _ => actual == expected,
+    }
+  }
+
+  /// The provided class followed by each of its super classes
+  fn ancestors<'s>(&'s self, class: &'s str) -> impl Iterator<Item = &'s str> + 's {
+    iter::successors(Some(class), move |name| {
+      self
+        .classes
+        .get(*name)
+        .and_then(|info| info.super_class.as_deref())
+    })
+    .take(self.classes.len() + 1)
+  }
+
+  /// Find a member of a class or one of its super classes
+  fn member<T>(&self, class: &str, find: impl Fn(&ClassInfo) -> Option<T>) -> Option<T> {
+    self
+      .ancestors(class)
+      .find_map(|name| self.classes.get(name).and_then(&find))
+  }
+
+  /// Resolve the annotated signature of a function
+  fn sig(&mut self, call_sig: &CallSignature) -> Rc<Sig> {
+    let type_params = self.type_params.len();
+    self.push_type_params(&call_sig.type_params);
+
+    let params = call_sig
+      .params
+      .iter()
+      .map(|param| match &param.type_ {
+        Some(type_) => (self.visit_type(type_), Some(type_.span())),
+        None => (Ty::Any, None),
+      })
+      .collect();
+
+    let returns = match &call_sig.return_type {
+      Some(return_type) => self.visit_type(return_type),
+      None => Ty::Any,
+    };
+
+    self.type_params.truncate(type_params);
+    Rc::new(Sig { params, returns })
+  }
+
+  fn push_type_params(&mut self, type_params: &[TypeParam]) {
+    self
+      .type_params
+      .extend(type_params.iter().map(|param| param.name.str().to_string()));
+  }
+
+  /// Record the members a class declares
+  fn declare_class(&mut self, class: &Class) {
+    let type_params = self.type_params.len();
+    self.push_type_params(&class.type_params);
+
+    let mut info = ClassInfo {
+      super_class: class
+        .super_class
+        .as_ref()
+        .map(|super_class| super_class.type_ref.name.str().to_string()),
+      ..ClassInfo::default()
+    };
+
+    for member in &class.type_members {
+      let ty = self.visit_type(&member.type_);
+      info
+        .fields
+        .insert(member.name.str().to_string(), (ty, member.type_.span()));
+    }
+
+    info.init = class.init.as_ref().map(|init| self.sig(&init.call_sig));
+
+    for method in &class.methods {
+      if let Some(name) = &method.name {
+        let sig = self.sig(&method.call_sig);
+        info.methods.insert(name.str().to_string(), sig);
+      }
+    }
+
+    for static_method in &class.static_methods {
+      if let Some(name) = &static_method.name {
+        let sig = self.sig(&static_method.call_sig);
+        info.static_methods.insert(name.str().to_string(), sig);
+      }
+    }
+
+    self.type_params.truncate(type_params);
+    self.classes.insert(class.name.str().to_string(), info);
+  }
+
+  /// Declare the module's classes, functions and types up front
+  /// so they can be referenced before their declaration
+  fn hoist(&mut self, decls: &[Decl]) {
+    let symbols = || {
+      decls.iter().filter_map(|decl| match decl {
+        Decl::Symbol(symbol) | Decl::Export(symbol) => Some(&**symbol),
+        _ => None,
+      })
+    };
+
+    for symbol in symbols() {
+      if let Symbol::Class(class) = symbol {
+        self
+          .classes
+          .insert(class.name.str().to_string(), ClassInfo::default());
+      }
+    }
+
+    for symbol in symbols() {
+      if let Symbol::TypeDecl(type_decl) = symbol {
+        self.visit_type_decl(type_decl);
+      }
+    }
+
+    for symbol in symbols() {
+      match symbol {
+        Symbol::Class(class) => {
+          self.declare_class(class);
+          self.declare(&class.name, Ty::Class(class.name.str().to_string()), None);
+        },
+        Symbol::Fun(fun) => {
+          if let Some(name) = &fun.name {
+            let sig = self.sig(&fun.call_sig);
+            self.declare(name, Ty::Fun(Some(sig)), None);
+          }
+        },
+        _ => (),
+      }
+    }
+  }
+
+  /// Check a value returned from the current function
+  fn returned(&mut self, ty: &Ty, span: Span) {
+    if let Some(Some((expected, annotation))) = self.returns.last().cloned() {
+      self.expect(ty, &expected, span, Some(annotation));
+    }
+  }
+
+  /// Visit a function's parameters and body in a new scope
+  fn fun_body(&mut self, fun: &Fun) {
+    let type_params = self.type_params.len();
+    self.push_type_params(&fun.call_sig.type_params);
+
+    self.begin_scope();
+    self.visit_call_sig(&fun.call_sig);
+
+    let returns = fun
+      .call_sig
+      .return_type
+      .as_ref()
+      .map(|type_| (self.visit_type(type_), type_.span()));
+    self.returns.push(returns);
+
+    match &fun.body {
+      FunBody::Block(block) => {
+        for decl in &block.decls {
+          let ty = self.visit_decl(decl);
+          if let Some(expr) = implicit_return(decl) {
+            self.returned(&ty, expr.span());
+          }
+        }
+      },
+      FunBody::Expr(expr) => {
+        let ty = self.visit_expr(expr);
+        self.returned(&ty, expr.span());
+      },
+    }
+
+    self.returns.pop();
+    self.end_scope();
+    self.type_params.truncate(type_params);
+  }
+
+  /// Visit a method body where self is an instance of the provided type
+  fn method_body(&mut self, method: &Fun, self_type: Ty) {
+    self.self_types.push(self_type);
+    self.fun_body(method);
+    self.self_types.pop();
+  }
+
+  /// Apply each trailer to a value of the provided type
+  fn trailers(&mut self, mut ty: Ty, trailers: &[Trailer]) -> Ty {
+    for trailer in trailers {
+      ty = match trailer {
+        Trailer::Call(call) => self.call(&ty, call),
+        Trailer::Index(index) => {
+          self.visit_index(index);
+          match ty {
+            Ty::List(item) => *item,
+            Ty::String => Ty::String,
+            _ => Ty::Any,
+          }
+        },
+        Trailer::Access(access) => self.access(&ty, access),
+      };
+    }
+
+    ty
+  }
+
+  /// Check a call against the callee's signature if known
+  fn call(&mut self, callee: &Ty, call: &Call) -> Ty {
+    let args: Vec<Ty> = call.args.iter().map(|arg| self.visit_expr(arg)).collect();
+
+    let (sig, result) = match callee {
+      Ty::Fun(Some(sig)) => (Some(Rc::clone(sig)), sig.returns.clone()),
+      Ty::Class(name) => (
+        self.member(name, |info| info.init.clone()),
+        Ty::Instance(name.clone()),
+      ),
+      _ => (None, Ty::Any),
+    };
+
+    if let Some(sig) = sig {
+      for ((expected, annotation), (actual, arg)) in
+        sig.params.iter().zip(args.iter().zip(&call.args))
+      {
+        self.expect(actual, expected, arg.span(), *annotation);
+      }
+    }
+
+    result
+  }
+
+  /// The type of a property of a value of the provided type
+  fn access(&mut self, ty: &Ty, access: &Access) -> Ty {
+    let prop = access.prop.str();
+
+    match ty {
+      Ty::Instance(class) => self
+        .member(class, |info| {
+          info.fields.get(prop).map(|(ty, _)| ty.clone())
+        })
+        .or_else(|| {
+          self
+            .member(class, |info| info.methods.get(prop).cloned())
+            .map(|sig| Ty::Fun(Some(sig)))
+        })
+        .unwrap_or(Ty::Any),
+      Ty::Class(class) => self
+        .member(class, |info| info.static_methods.get(prop).cloned())
+        .map_or(Ty::Any, |sig| Ty::Fun(Some(sig))),
+      _ => Ty::Any,
+    }
+  }
+
+  /// The declared type of an assignment's target if it was annotated
+  fn assign_target(&mut self, lhs: &Expr) -> Option<(Ty, Span)> {
+    if let Expr::Atom(atom) = lhs {
+      match atom.trailers.split_last() {
+        None => {
+          if let Primary::Ident(name) = &atom.primary {
+            return self.resolve(name.str()).and_then(|binding| {
+              binding
+                .annotation
+                .map(|annotation| (binding.ty.clone(), annotation))
+            });
+          }
+        },
+        Some((Trailer::Access(access), rest)) => {
+          let primary = self.visit_primary(&atom.primary);
+          let prop = access.prop.str();
+
+          return match self.trailers(primary, rest) {
+            Ty::Instance(class) => self.member(&class, |info| info.fields.get(prop).cloned()),
+            _ => None,
+          };
+        },
+        _ => (),
+      }
+    }
+
+    self.visit_expr(lhs);
+    None
+  }
+
+  /// The type resulting from a binary operator checking it
+  /// can be applied to its operands
+  fn binary_type(&mut self, op: &BinaryOp, lhs: Ty, rhs: Ty, span: Span) -> Ty {
+    let known = lhs.is_primitive() && rhs.is_primitive();
+
+    match op {
+      BinaryOp::Add | BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq => {
+        let valid = matches!(
+          (&lhs, &rhs),
+          (Ty::Number, Ty::Number) | (Ty::String, Ty::String)
+        );
+
+        if known && !valid {
+          self.operator_error(op, &lhs, &rhs, span);
+        }
+
+        match (op, &lhs, &rhs) {
+          (BinaryOp::Add, Ty::Number, _) | (BinaryOp::Add, _, Ty::Number) => Ty::Number,
+          (BinaryOp::Add, Ty::String, _) | (BinaryOp::Add, _, Ty::String) => Ty::String,
+          (BinaryOp::Add, _, _) => Ty::Any,
+          _ => Ty::Bool,
+        }
+      },
+      BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
+        if known && (lhs != Ty::Number || rhs != Ty::Number) {
+          self.operator_error(op, &lhs, &rhs, span);
+        }
+
+        Ty::Number
+      },
+      BinaryOp::Eq | BinaryOp::Ne => Ty::Bool,
+      BinaryOp::And | BinaryOp::Or => {
+        if lhs == rhs {
+          lhs
+        } else {
+          Ty::Any
+        }
+      },
+    }
+  }
+
+  fn operator_error(&mut self, op: &BinaryOp, lhs: &Ty, rhs: &Ty, span: Span) {
+    self.error(
+      format!(
+        "Operator '{}' cannot be applied to '{}' and '{}'.",
+        binary_op_str(op),
+        lhs,
+        rhs
+      ),
+      vec![Label::primary(self.file_id, span)],
+    );
+  }
+}
+
+/// The expression of an implicit return declaration
+fn implicit_return<'a, 'b>(decl: &'b Decl<'a>) -> Option<&'b Expr<'a>> {
+  match decl {
+    Decl::Stmt(stmt) => match &**stmt {
+      Stmt::ImplicitReturn(expr) => Some(expr),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn binary_op_str(op: &BinaryOp) -> &'static str {
+  match op {
+    BinaryOp::Add => "+",
+    BinaryOp::Sub => "-",
+    BinaryOp::Mul => "*",
+    BinaryOp::Div => "/",
+    BinaryOp::Lt => "<",
+    BinaryOp::LtEq => "<=",
+    BinaryOp::Gt => ">",
+    BinaryOp::GtEq => ">=",
+    BinaryOp::Eq => "==",
+    BinaryOp::Ne => "!=",
+    BinaryOp::And => "and",
+    BinaryOp::Or => "or",
+  }
+}
+
+impl<'a, FileId: Copy> Visitor<'a> for TypeChecker<FileId> {
+  type Result = Ty;
+
+  fn visit(&mut self, module: &Module) -> Self::Result {
+    self.begin_scope();
+    self.hoist(&module.decls);
+
+    for decl in &module.decls {
+      self.visit_decl(decl);
+    }
+
+    self.end_scope();
+    Ty::Any
+  }
+
+  fn visit_decl(&mut self, decl: &Decl) -> Self::Result {
+    match decl {
+      Decl::Symbol(symbol) => self.visit_symbol(symbol),
+      Decl::Export(export) => self.visit_export(export),
+      Decl::Stmt(stmt) => self.visit_stmt(stmt),
+      Decl::Error(error) => self.visit_error(error),
+    }
+  }
+
+  fn visit_stmt(&mut self, stmt: &Stmt) -> Self::Result {
+    match stmt {
+      Stmt::Expr(expr) => self.visit_expr(expr),
+      Stmt::ImplicitReturn(expr) => self.visit_expr(expr),
+      Stmt::Import(import) => self.visit_import(import),
+      Stmt::For(for_) => self.visit_for(for_),
+      Stmt::If(if_) => self.visit_if(if_),
+      Stmt::Return(return_) => self.visit_return(return_),
+      Stmt::Raise(raise) => self.visit_raise(raise),
+      Stmt::Continue(continue_) => self.visit_continue(continue_),
+      Stmt::Break(break_) => self.visit_break(break_),
+      Stmt::While(while_) => self.visit_while(while_),
+      Stmt::Try(try_) => self.visit_try(try_),
+      Stmt::With(with) => self.visit_with(with),
+    }
+  }
+
+  fn visit_expr(&mut self, expr: &Expr) -> Self::Result {
+    match expr {
+      Expr::Assign(assign) => self.visit_assign(assign),
+      Expr::AssignBinary(assign_binary) => self.visit_assign_binary(assign_binary),
+      Expr::Binary(binary) => self.visit_binary(binary),
+      Expr::Unary(unary) => self.visit_unary(unary),
+      Expr::Atom(atom) => self.visit_atom(atom),
+    }
+  }
+
+  fn visit_primary(&mut self, primary: &Primary) -> Self::Result {
+    match primary {
+      Primary::AssignBlock(block) => self.visit_assign_block(block),
+      Primary::True(token) => self.visit_true(token),
+      Primary::False(token) => self.visit_false(token),
+      Primary::Nil(token) => self.visit_nil(token),
+      Primary::Number(token) => self.visit_number(token),
+      Primary::Grouping(expr) => self.visit_expr(expr),
+      Primary::String(token) => self.visit_string(token),
+      Primary::Interpolation(string_interp) => self.visit_interpolation(string_interp),
+      Primary::Ident(token) => self.visit_ident(token),
+      Primary::Self_(token) => self.visit_self(token),
+      Primary::Super(token) => self.visit_super(token),
+      Primary::Lambda(fun) => self.visit_lambda(fun),
+      Primary::Class(class) => self.visit_class_expr(class),
+      Primary::List(items) => self.visit_list(items),
+      Primary::Tuple(items) => self.visit_tuple(items),
+      Primary::Map(kvps) => self.visit_map(kvps),
+    }
+  }
+
+  fn visit_symbol(&mut self, symbol: &Symbol) -> Self::Result {
+    match symbol {
+      Symbol::Class(class) => self.visit_class(class),
+      Symbol::Extension(extension) => self.visit_extension(extension),
+      Symbol::Fun(fun) => self.visit_fun(fun),
+      Symbol::Let(let_) => self.visit_let(let_),
+      Symbol::LetTuple(let_tuple) => self.visit_let_tuple(let_tuple),
+      Symbol::Const(const_) => self.visit_const(const_),
+      Symbol::Import(import) => self.visit_import(import),
+      Symbol::Trait(trait_) => self.visit_trait(trait_),
+      Symbol::TypeDecl(type_decl) => self.visit_type_decl(type_decl),
+    }
+  }
+
+  fn visit_export(&mut self, export: &Symbol) -> Self::Result {
+    self.visit_symbol(export)
+  }
+
+  fn visit_error(&mut self, _: &[Token<'a>]) -> Self::Result {
+    Ty::Any
+  }
+
+  fn visit_class(&mut self, class: &Class) -> Self::Result {
+    let name = class.name.str().to_string();
+
+    self.declare_class(class);
+    self.declare(&class.name, Ty::Class(name.clone()), None);
+
+    let type_params = self.type_params.len();
+    self.push_type_params(&class.type_params);
+
+    if let Some(init) = &class.init {
+      self.method_body(init, Ty::Instance(name.clone()));
+    }
+
+    for method in &class.methods {
+      self.method_body(method, Ty::Instance(name.clone()));
+    }
+
+    for static_method in &class.static_methods {
+      self.method_body(static_method, Ty::Class(name.clone()));
+    }
+
+    self.type_params.truncate(type_params);
+    Ty::Nil
+  }
+
+  fn visit_extension(&mut self, extension: &Extension) -> Self::Result {
+    let name = extension.class.str().to_string();
+
+    // only classes declared in this module have known instance types
+    let (instance, class) = if self.classes.contains_key(&name) {
+      (Ty::Instance(name.clone()), Ty::Class(name))
+    } else {
+      (Ty::Any, Ty::Any)
+    };
+
+    for method in &extension.methods {
+      self.method_body(method, instance.clone());
+    }
+
+    for static_method in &extension.static_methods {
+      self.method_body(static_method, class.clone());
+    }
+
+    Ty::Nil
+  }
+
+  fn visit_fun(&mut self, fun: &Fun) -> Self::Result {
+    if let Some(name) = &fun.name {
+      let sig = self.sig(&fun.call_sig);
+      self.declare(name, Ty::Fun(Some(sig)), None);
+    }
+
+    self.fun_body(fun);
+    Ty::Nil
+  }
+
+  fn visit_let(&mut self, let_: &Let) -> Self::Result {
+    let value = let_.value.as_ref().map(|value| self.visit_expr(value));
+
+    match &let_.type_ {
+      Some(type_) => {
+        let ty = self.visit_type(type_);
+
+        if let (Some(value), Some(expr)) = (value, &let_.value) {
+          self.expect(&value, &ty, expr.span(), Some(type_.span()));
+        }
+
+        self.declare(&let_.name, ty, Some(type_.span()));
+      },
+      None => self.declare(&let_.name, Ty::Any, None),
+    }
+
+    Ty::Nil
+  }
+
+  fn visit_let_tuple(&mut self, let_tuple: &LetTuple) -> Self::Result {
+    self.visit_expr(&let_tuple.value);
+
+    for name in &let_tuple.names {
+      self.declare(name, Ty::Any, None);
+    }
+
+    Ty::Nil
+  }
+
+  fn visit_const(&mut self, const_: &Const) -> Self::Result {
+    let ty = self.visit_expr(&const_.value);
+    self.declare(&const_.name, ty, None);
+    Ty::Nil
+  }
+
+  fn visit_method(&mut self, method: &Fun) -> Self::Result {
+    self.fun_body(method);
+    Ty::Nil
+  }
+
+  fn visit_static_method(&mut self, static_method: &Fun) -> Self::Result {
+    self.fun_body(static_method);
+    Ty::Nil
+  }
+
+  fn visit_import(&mut self, import: &Import) -> Self::Result {
+    match &import.stem {
+      ImportStem::None => {
+        let name = &import.path()[import.path().len() - 1];
+        self.declare(name, Ty::Any, None);
+      },
+      ImportStem::Rename(rename) => self.declare(rename, Ty::Any, None),
+      ImportStem::Symbols(symbols) => {
+        for symbol in symbols {
+          let name = symbol.rename.as_ref().unwrap_or(&symbol.symbol);
+          self.declare(name, Ty::Any, None);
+        }
+      },
+    }
+
+    Ty::Nil
+  }
+
+  fn visit_for(&mut self, for_: &For) -> Self::Result {
+    self.visit_expr(&for_.iter);
+
+    self.begin_scope();
+    self.declare(&for_.item, Ty::Any, None);
+    self.visit_block(&for_.body);
+    self.end_scope();
+    Ty::Nil
+  }
+
+  fn visit_while(&mut self, while_: &While) -> Self::Result {
+    self.visit_expr(&while_.cond);
+    self.visit_block(&while_.body)
+  }
+
+  fn visit_if(&mut self, if_: &If) -> Self::Result {
+    self.visit_expr(&if_.cond);
+    self.visit_block(&if_.body);
+
+    match &if_.else_ {
+      Some(Else::If(else_if)) => self.visit_if(else_if),
+      Some(Else::Block(block)) => self.visit_block(block),
+      None => Ty::Nil,
+    }
+  }
+
+  fn visit_return(&mut self, return_: &Return) -> Self::Result {
+    match &return_.value {
+      Some(value) => {
+        let ty = self.visit_expr(value);
+        self.returned(&ty, value.span());
+      },
+      None => self.returned(&Ty::Nil, return_.return_.span()),
+    }
+
+    Ty::Nil
+  }
+
+  fn visit_raise(&mut self, raise: &Raise) -> Self::Result {
+    self.visit_expr(&raise.error);
+    if let Some(cause) = &raise.cause {
+      self.visit_expr(cause);
+    }
+    Ty::Nil
+  }
+
+  fn visit_continue(&mut self, _: &Token<'a>) -> Self::Result {
+    Ty::Nil
+  }
+
+  fn visit_break(&mut self, _: &Token<'a>) -> Self::Result {
+    Ty::Nil
+  }
+
+  fn visit_try(&mut self, try_: &Try) -> Self::Result {
+    self.visit_block(&try_.block);
+
+    self.begin_scope();
+    if let Some(error) = &try_.error {
+      self.declare(error, Ty::Any, None);
+    }
+    self.visit_block(&try_.catch);
+    self.end_scope();
+    Ty::Nil
+  }
+
+  fn visit_with(&mut self, with: &With) -> Self::Result {
+    self.visit_expr(&with.manager);
+
+    self.begin_scope();
+    if let Some(name) = &with.name {
+      self.declare(name, Ty::Any, None);
+    }
+    self.visit_block(&with.body);
+    self.end_scope();
+    Ty::Nil
+  }
+
+  fn visit_block(&mut self, block: &Block) -> Self::Result {
+    self.begin_scope();
+    for decl in &block.decls {
+      self.visit_decl(decl);
+    }
+    self.end_scope();
+    Ty::Nil
+  }
+
+  fn visit_assign(&mut self, assign: &Assign) -> Self::Result {
+    let target = self.assign_target(&assign.lhs);
+    let value = self.visit_expr(&assign.rhs);
+
+    if let Some((ty, annotation)) = target {
+      self.expect(&value, &ty, assign.rhs.span(), Some(annotation));
+    }
+
+    value
+  }
+
+  fn visit_assign_binary(&mut self, assign_binary: &AssignBinary) -> Self::Result {
+    let target = self.assign_target(&assign_binary.lhs);
+    let rhs = self.visit_expr(&assign_binary.rhs);
+
+    let op = match assign_binary.op {
+      AssignBinaryOp::Add => BinaryOp::Add,
+      AssignBinaryOp::Sub => BinaryOp::Sub,
+      AssignBinaryOp::Mul => BinaryOp::Mul,
+      AssignBinaryOp::Div => BinaryOp::Div,
+    };
+
+    let lhs = target.as_ref().map_or(Ty::Any, |(ty, _)| ty.clone());
+    let value = self.binary_type(&op, lhs, rhs, assign_binary.span());
+
+    if let Some((ty, annotation)) = target {
+      self.expect(&value, &ty, assign_binary.span(), Some(annotation));
+    }
+
+    value
+  }
+
+  fn visit_binary(&mut self, binary: &Binary) -> Self::Result {
+    let lhs = self.visit_expr(&binary.lhs);
+    let rhs = self.visit_expr(&binary.rhs);
+
+    self.binary_type(&binary.op, lhs, rhs, binary.span())
+  }
+
+  fn visit_unary(&mut self, unary: &Unary) -> Self::Result {
+    let ty = self.visit_expr(&unary.expr);
+
+    match unary.op {
+      UnaryOp::Not => Ty::Bool,
+      UnaryOp::Negate => {
+        if ty.is_primitive() && ty != Ty::Number {
+          self.error(
+            format!("Operator '-' cannot be applied to '{}'.", ty),
+            vec![Label::primary(self.file_id, unary.span())],
+          );
+        }
+
+        Ty::Number
+      },
+      UnaryOp::Await => Ty::Any,
+    }
+  }
+
+  fn visit_atom(&mut self, atom: &Atom) -> Self::Result {
+    let primary = self.visit_primary(&atom.primary);
+    self.trailers(primary, &atom.trailers)
+  }
+
+  fn visit_index(&mut self, index: &Index) -> Self::Result {
+    self.visit_expr(&index.index)
+  }
+
+  fn visit_call(&mut self, call: &Call) -> Self::Result {
+    for arg in &call.args {
+      self.visit_expr(arg);
+    }
+
+    Ty::Any
+  }
+
+  fn visit_access(&mut self, _: &Access) -> Self::Result {
+    Ty::Any
+  }
+
+  fn visit_call_sig(&mut self, call_sig: &CallSignature) -> Self::Result {
+    for param in &call_sig.params {
+      match &param.type_ {
+        Some(type_) => {
+          let ty = self.visit_type(type_);
+          self.declare(&param.name, ty, Some(type_.span()));
+        },
+        None => self.declare(&param.name, Ty::Any, None),
+      }
+    }
+
+    Ty::Nil
+  }
+
+  fn visit_assign_block(&mut self, block: &Block) -> Self::Result {
+    self.begin_scope();
+
+    let mut value = Ty::Nil;
+    for decl in &block.decls {
+      let ty = self.visit_decl(decl);
+      value = match implicit_return(decl) {
+        Some(_) => ty,
+        None => Ty::Nil,
+      };
+    }
+
+    self.end_scope();
+    value
+  }
+
+  fn visit_true(&mut self, _: &Token<'a>) -> Self::Result {
+    Ty::Bool
+  }
+
+  fn visit_false(&mut self, _: &Token<'a>) -> Self::Result {
+    Ty::Bool
+  }
+
+  fn visit_nil(&mut self, _: &Token<'a>) -> Self::Result {
+    Ty::Nil
+  }
+
+  fn visit_number(&mut self, _: &Token<'a>) -> Self::Result {
+    Ty::Number
+  }
+
+  fn visit_string(&mut self, _: &Token<'a>) -> Self::Result {
+    Ty::String
+  }
+
+  fn visit_interpolation(&mut self, string_interp: &Interpolation) -> Self::Result {
+    for segment in &string_interp.segments {
+      if let StringSegments::Expr(expr) = segment {
+        self.visit_expr(expr);
+      }
+    }
+
+    Ty::String
+  }
+
+  fn visit_ident(&mut self, token: &Token<'a>) -> Self::Result {
+    self
+      .resolve(token.str())
+      .map_or(Ty::Any, |binding| binding.ty.clone())
+  }
+
+  fn visit_self(&mut self, _: &Token<'a>) -> Self::Result {
+    self.self_types.last().cloned().unwrap_or(Ty::Any)
+  }
+
+  fn visit_super(&mut self, _: &Super) -> Self::Result {
+    Ty::Any
+  }
+
+  fn visit_lambda(&mut self, fun: &Fun) -> Self::Result {
+    let sig = self.sig(&fun.call_sig);
+    self.fun_body(fun);
+    Ty::Fun(Some(sig))
+  }
+
+  fn visit_class_expr(&mut self, class: &Class) -> Self::Result {
+    // class expressions aren't named in the type environment so their
+    // instances are left unchecked
+    let type_params = self.type_params.len();
+    self.push_type_params(&class.type_params);
+
+    let methods = class.init.iter().chain(&class.methods);
+    for method in methods.chain(&class.static_methods) {
+      self.method_body(method, Ty::Any);
+    }
+
+    self.type_params.truncate(type_params);
+    Ty::Any
+  }
+
+  fn visit_list(&mut self, items: &List) -> Self::Result {
+    let items: Vec<Ty> = items
+      .items
+      .iter()
+      .map(|item| self.visit_expr(item))
+      .collect();
+
+    // lists only take an item type when every item agrees
+    match items.split_first() {
+      Some((first, rest)) if rest.iter().all(|item| item == first) => {
+        Ty::List(Box::new(first.clone()))
+      },
+      _ => Ty::List(Box::new(Ty::Any)),
+    }
+  }
+
+  fn visit_tuple(&mut self, items: &Tuple) -> Self::Result {
+    for item in &items.items {
+      self.visit_expr(item);
+    }
+
+    Ty::Tuple
+  }
+
+  fn visit_map(&mut self, kvps: &Map) -> Self::Result {
+    for (key, value) in &kvps.entries {
+      self.visit_expr(key);
+      self.visit_expr(value);
+    }
+
+    Ty::Map
+  }
+}
+
+impl<FileId: Copy> TypeVisitor for TypeChecker<FileId> {
+  type Result = Ty;
+
+  fn visit_trait(&mut self, _: &Trait) -> Self::Result {
+    Ty::Nil
+  }
+
+  fn visit_type_decl(&mut self, type_decl: &TypeDecl) -> Self::Result {
+    let type_params = self.type_params.len();
+    self.push_type_params(&type_decl.type_params);
+
+    let ty = self.visit_type(&type_decl.type_);
+    self.aliases.insert(type_decl.name.str().to_string(), ty);
+
+    self.type_params.truncate(type_params);
+    Ty::Nil
+  }
+
+  fn visit_type(&mut self, type_: &Type) -> Self::Result {
+    match type_ {
+      Type::Union(union) => self.visit_union(union),
+      Type::Intersection(intersection) => self.visit_intersection(intersection),
+      Type::Fun(fun) => Ty::Fun(Some(self.sig(fun))),
+      Type::List(list_type) => self.visit_list_type(list_type),
+      Type::Ref(type_ref) => self.visit_type_ref(type_ref),
+      Type::Primitive(primitive) => self.visit_primitive(primitive),
+    }
+  }
+
+  fn visit_type_params(&mut self, _: &[TypeParam]) -> Self::Result {
+    Ty::Any
+  }
+
+  fn visit_type_member(&mut self, type_member: &TypeMember) -> Self::Result {
+    self.visit_type(&type_member.type_)
+  }
+
+  fn visit_type_method(&mut self, type_method: &TypeMethod) -> Self::Result {
+    Ty::Fun(Some(self.sig(&type_method.call_sig)))
+  }
+
+  fn visit_union(&mut self, union: &Union) -> Self::Result {
+    let mut members = vec![];
+
+    for ty in [self.visit_type(&union.lhs), self.visit_type(&union.rhs)] {
+      match ty {
+        Ty::Union(inner) => members.extend(inner),
+        ty => members.push(ty),
+      }
+    }
+
+    Ty::Union(members)
+  }
+
+  fn visit_intersection(&mut self, _: &Intersection) -> Self::Result {
+    Ty::Any
+  }
+
+  fn visit_list_type(&mut self, list_type: &ListType) -> Self::Result {
+    Ty::List(Box::new(self.visit_type(&list_type.item_type)))
+  }
+
+  fn visit_type_ref(&mut self, type_ref: &TypeRef) -> Self::Result {
+    let name = type_ref.name.str();
+
+    if self.type_params.iter().any(|param| param == name) {
+      return Ty::Any;
+    }
+
+    if self.classes.contains_key(name) {
+      return Ty::Instance(name.to_string());
+    }
+
+    if let Some(ty) = self.aliases.get(name) {
+      return ty.clone();
+    }
+
+    // the builtin classes name the primitive types they describe
+    match name {
+      "Nil" => Ty::Nil,
+      "Bool" => Ty::Bool,
+      "Number" => Ty::Number,
+      "String" => Ty::String,
+      "List" => Ty::List(Box::new(Ty::Any)),
+      "Map" => Ty::Map,
+      "Tuple" => Ty::Tuple,
+      "Fun" => Ty::Fun(None),
+      _ => Ty::Any,
+    }
+  }
+
+  fn visit_primitive(&mut self, primitive: &Primitive) -> Self::Result {
+    match primitive {
+      Primitive::Nil(_) => Ty::Nil,
+      Primitive::Number(_) => Ty::Number,
+      Primitive::Bool(_) => Ty::Bool,
+      Primitive::String(_) => Ty::String,
+      Primitive::Any(_) => Ty::Any,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{arena::Arena, compiler::Parser, source::Source};
+
+  fn check(source: &str) -> Vec<String> {
+    let source = Source::new(source);
+    let arena = Arena::for_source(&source);
+
+    let (ast, _) = Parser::new(&source, &arena, 0).parse();
+    let ast = match ast {
+      Ok(ast) => ast,
+      Err(_) => panic!("expected source to parse"),
+    };
+
+    TypeChecker::new(0)
+      .check(&ast)
+      .into_iter()
+      .map(|error| error.message)
+      .collect()
+  }
+
+  #[test]
+  fn untyped() {
+    let example = "
+      fn add(a, b) {
+        return a + b;
+      }
+
+      let a = add(1, 2);
+      a = \"example\";
+      print(a + \"!\");
+    ";
+
+    assert!(check(example).is_empty());
+  }
+
+  #[test]
+  fn let_() {
+    let example = "
+      let a: number = 10;
+      let b: Number = \"example\";
+      let c: string | nil = nil;
+      let d: number[] = [1, 2, \"three\"];
+      let e: string[] = [1, 2];
+      a = true;
+    ";
+
+    assert_eq!(
+      check(example),
+      vec![
+        "Expected type 'number' but found 'string'.",
+        "Expected type 'string[]' but found 'number[]'.",
+        "Expected type 'number' but found 'bool'.",
+      ]
+    );
+  }
+
+  #[test]
+  fn fun() {
+    let example = "
+      fn add(a: Number, b: Number) -> Number {
+        return a + b;
+      }
+
+      fn greet(name: string) -> string => \"hi \" + name;
+      fn wrong() -> number { \"ten\" }
+
+      let a: string = add(1, \"2\");
+      greet(\"laythe\");
+    ";
+
+    assert_eq!(
+      check(example),
+      vec![
+        "Expected type 'number' but found 'string'.",
+        "Expected type 'string' but found 'number'.",
+        "Expected type 'number' but found 'string'.",
+      ]
+    );
+  }
+
+  #[test]
+  fn class() {
+    let example = "
+      class Animal {
+        name: string;
+
+        init(name: string) {
+          self.name = name;
+        }
+
+        rename(name: string) {
+          self.name = 10;
+        }
+      }
+
+      class Dog : Animal {}
+
+      fn pet(animal: Animal) -> string => animal.name;
+
+      pet(Dog(\"rex\"));
+      pet(\"rex\");
+      Animal(10).rename(false);
+    ";
+
+    assert_eq!(
+      check(example),
+      vec![
+        "Expected type 'string' but found 'number'.",
+        "Expected type 'Animal' but found 'string'.",
+        "Expected type 'string' but found 'number'.",
+        "Expected type 'string' but found 'bool'.",
+      ]
+    );
+  }
+
+  #[test]
+  fn operators() {
+    let example = "
+      let a = 1 + \"a\";
+      let b = \"a\" - \"b\";
+      let c = -\"c\";
+      let d = 1 < 2;
+      let e: number = 1;
+      e += \"e\";
+    ";
+
+    assert_eq!(
+      check(example),
+      vec![
+        "Operator '+' cannot be applied to 'number' and 'string'.",
+        "Operator '-' cannot be applied to 'string' and 'string'.",
+        "Operator '-' cannot be applied to 'string'.",
+        "Operator '+' cannot be applied to 'number' and 'string'.",
+      ]
+    );
+  }
+
+  #[test]
+  fn generics_and_aliases() {
+    let example = "
+      type Id = number | string;
+
+      fn first<T>(items: T[]) -> T => items[0];
+      fn show(id: Id) => id;
+
+      let a: number = first([1, 2]);
+      show(true);
+    ";
+
+    assert_eq!(
+      check(example),
+      vec!["Expected type 'number | string' but found 'bool'."]
+    );
+  }
+}
@@ -1,2 +1,13 @@
-pub const MAX_FRAME_SIZE: usize = 255;
+/// The default maximum number of nested call frames
+pub const DEFAULT_FRAME_LIMIT: usize = 255;
+
+/// The default initial number of slots reserved for each fiber's stack
+pub const DEFAULT_STACK_SIZE: usize = 256;
+
 pub const REPL_MODULE: &str = "repl.ly";
+
+/// The prompt shown when the repl is ready for a new statement
+pub const REPL_PROMPT: &str = "laythe:> ";
+
+/// The prompt shown when the repl is waiting for the rest of a statement
+pub const REPL_CONTINUATION_PROMPT: &str = "    ...> ";
@@ -0,0 +1,775 @@
+use crate::{
+  debugger::{Breakpoint, Breakpoints, Debugger, PauseReason, Paused, Step},
+  json::Json,
+  protocol::{read_message, write_message},
+  vm::{Interrupt, VmBuilder},
+};
+use codespan_reporting::term::termcolor::{ColorSpec, WriteColor};
+use laythe_env::{
+  io::IoImpl,
+  stdio::{Stdio, StdioImpl},
+};
+use std::{
+  cell::RefCell,
+  fmt, fs,
+  io::{self, BufRead, Read, Write},
+  path::PathBuf,
+  rc::Rc,
+  sync::{Arc, Mutex},
+};
+
+/// The id of the single thread a laythe script exposes to the client
+const THREAD_ID: u8 = 1;
+
+/// Serve a debug adapter protocol session over the provided streams. The
+/// client launches a single script which is run by a vm configured from `builder`
+pub fn serve(
+  builder: VmBuilder,
+  input: Box<dyn BufRead>,
+  output: Box<dyn Write + Send>,
+) -> io::Result<()> {
+  let writer = DapWriter::new(output);
+  let session = Rc::new(RefCell::new(DapSession::new(input, writer.clone())));
+
+  let program = match session.borrow_mut().configure()? {
+    Some(program) => program,
+    None => return Ok(()),
+  };
+
+  let source = match fs::read_to_string(&program) {
+    Ok(source) => source,
+    Err(err) => {
+      writer.output("stderr", &format!("{}: {}\n", program.display(), err))?;
+      writer.event("terminated", Json::object(vec![]))?;
+      return session.borrow_mut().drain();
+    },
+  };
+
+  let vm = builder
+    .stdio(Arc::new(IoStdioDap {
+      writer: writer.clone(),
+    }))
+    .debugger(session.clone())
+    .build();
+
+  let mut vm = match vm {
+    Ok(vm) => vm,
+    Err(error) => {
+      writer.output("stderr", &format!("{}\n", error))?;
+      writer.event("terminated", Json::object(vec![]))?;
+      return session.borrow_mut().drain();
+    },
+  };
+  session.borrow_mut().interrupt = Some(vm.interrupt_handle());
+
+  let exit_code: u16 = match vm.run(program, &source) {
+    Ok(code) => code,
+    Err(error) => {
+      vm.report(&error);
+      error.exit_code()
+    },
+  };
+
+  writer.event("exited", Json::object(vec![("exitCode", exit_code.into())]))?;
+  writer.event("terminated", Json::object(vec![]))?;
+
+  let mut session = session.borrow_mut();
+  if session.disconnected {
+    return Ok(());
+  }
+  session.drain()
+}
+
+/// Writes protocol messages framed by their content length
+#[derive(Clone)]
+pub struct DapWriter {
+  inner: Arc<Mutex<DapWriterInner>>,
+}
+
+struct DapWriterInner {
+  output: Box<dyn Write + Send>,
+  seq: usize,
+}
+
+impl fmt::Debug for DapWriter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("DapWriter").finish()
+  }
+}
+
+impl DapWriter {
+  /// Create a writer for the provided output
+  pub fn new(output: Box<dyn Write + Send>) -> Self {
+    Self {
+      inner: Arc::new(Mutex::new(DapWriterInner { output, seq: 0 })),
+    }
+  }
+
+  /// Send a message of the provided type with the next sequence number
+  fn send(&self, type_: &str, fields: Vec<(&str, Json)>) -> io::Result<()> {
+    let mut inner = self.inner.lock().expect("Dap writer was poisoned");
+    inner.seq += 1;
+
+    let mut message = vec![("seq", inner.seq.into()), ("type", type_.into())];
+    message.extend(fields);
+
+    write_message(&mut inner.output, &Json::object(message))
+  }
+
+  /// Send an event to the client
+  pub fn event(&self, event: &str, body: Json) -> io::Result<()> {
+    self.send("event", vec![("event", event.into()), ("body", body)])
+  }
+
+  /// Send script output to the client
+  pub fn output(&self, category: &str, output: &str) -> io::Result<()> {
+    self.event(
+      "output",
+      Json::object(vec![
+        ("category", category.into()),
+        ("output", output.into()),
+      ]),
+    )
+  }
+
+  /// Send a successful response to a request
+  pub fn response(&self, request: &Json, body: Json) -> io::Result<()> {
+    self.send(
+      "response",
+      vec![
+        (
+          "request_seq",
+          request.get("seq").cloned().unwrap_or(Json::Null),
+        ),
+        ("success", true.into()),
+        (
+          "command",
+          request.get("command").cloned().unwrap_or(Json::Null),
+        ),
+        ("body", body),
+      ],
+    )
+  }
+
+  /// Send a failed response to a request
+  pub fn error(&self, request: &Json, message: &str) -> io::Result<()> {
+    self.send(
+      "response",
+      vec![
+        (
+          "request_seq",
+          request.get("seq").cloned().unwrap_or(Json::Null),
+        ),
+        ("success", false.into()),
+        (
+          "command",
+          request.get("command").cloned().unwrap_or(Json::Null),
+        ),
+        ("message", message.into()),
+      ],
+    )
+  }
+}
+
+/// What handling a request asks of the session
+enum Handled {
+  /// Keep reading requests
+  Pending,
+
+  /// The client finished its initial configuration
+  Configured,
+
+  /// Resume execution with the provided step
+  Resume(Step),
+
+  /// The client ended the session
+  Disconnect,
+}
+
+/// The state of a debug adapter session. The session is attached to the
+/// vm as its debugger and serves requests while execution is paused
+pub struct DapSession {
+  input: Box<dyn BufRead>,
+  writer: DapWriter,
+
+  /// The script the client asked to launch
+  program: Option<PathBuf>,
+
+  /// Pause before the first line of the script
+  stop_on_entry: bool,
+
+  /// The breakpoint lines of each source as last set by the client
+  sources: Vec<(PathBuf, Vec<u32>)>,
+
+  /// The vm state while paused
+  paused: Option<Paused>,
+
+  /// Used to stop the vm if the client disconnects while paused
+  interrupt: Option<Interrupt>,
+
+  /// Has the client disconnected
+  disconnected: bool,
+}
+
+impl DapSession {
+  fn new(input: Box<dyn BufRead>, writer: DapWriter) -> Self {
+    Self {
+      input,
+      writer,
+      program: None,
+      stop_on_entry: false,
+      sources: vec![],
+      paused: None,
+      interrupt: None,
+      disconnected: false,
+    }
+  }
+
+  /// Serve requests until the client has launched a script and finished
+  /// configuring it, returning none if the client disconnects first
+  fn configure(&mut self) -> io::Result<Option<PathBuf>> {
+    let mut configured = false;
+
+    while !configured || self.program.is_none() {
+      let request = match read_message(&mut self.input)? {
+        Some(request) => request,
+        None => return Ok(None),
+      };
+
+      match self.handle(&request)? {
+        Handled::Configured => configured = true,
+        Handled::Disconnect => return Ok(None),
+        Handled::Pending | Handled::Resume(_) => (),
+      }
+    }
+
+    Ok(self.program.clone())
+  }
+
+  /// Serve requests after the script has finished until the client disconnects
+  fn drain(&mut self) -> io::Result<()> {
+    while let Some(request) = read_message(&mut self.input)? {
+      if let Handled::Disconnect = self.handle(&request)? {
+        break;
+      }
+    }
+
+    self.disconnected = true;
+    Ok(())
+  }
+
+  /// Replace the vm's breakpoints with those set by the client
+  fn sync(&self, breakpoints: &mut Breakpoints) {
+    breakpoints.clear();
+
+    for (path, lines) in &self.sources {
+      for line in lines {
+        breakpoints.add(Breakpoint::new(path, *line));
+      }
+    }
+  }
+
+  /// Stop the vm and ignore any later pauses
+  fn disconnect(&mut self) {
+    self.disconnected = true;
+    if let Some(interrupt) = &self.interrupt {
+      interrupt.interrupt();
+    }
+  }
+
+  /// Respond to a single request
+  fn handle(&mut self, request: &Json) -> io::Result<Handled> {
+    let arguments = request.get("arguments").cloned().unwrap_or(Json::Null);
+    let command = request.get("command").and_then(Json::as_str).unwrap_or("");
+
+    match command {
+      "initialize" => {
+        self.writer.response(
+          request,
+          Json::object(vec![("supportsConfigurationDoneRequest", true.into())]),
+        )?;
+        self.writer.event("initialized", Json::object(vec![]))?;
+      },
+      "launch" => match arguments.get("program").and_then(Json::as_str) {
+        Some(program) => {
+          self.program = Some(PathBuf::from(program));
+          self.stop_on_entry = arguments
+            .get("stopOnEntry")
+            .and_then(Json::as_bool)
+            .unwrap_or(false);
+          self.writer.response(request, Json::object(vec![]))?;
+        },
+        None => self.writer.error(request, "Launch expects a program")?,
+      },
+      "setBreakpoints" => self.set_breakpoints(request, &arguments)?,
+      "configurationDone" => {
+        self.writer.response(request, Json::object(vec![]))?;
+        return Ok(Handled::Configured);
+      },
+      "threads" => {
+        let thread = Json::object(vec![("id", THREAD_ID.into()), ("name", "main".into())]);
+        self.writer.response(
+          request,
+          Json::object(vec![("threads", Json::Array(vec![thread]))]),
+        )?;
+      },
+      "stackTrace" => self.stack_trace(request)?,
+      "scopes" => self.scopes(request, &arguments)?,
+      "variables" => self.variables(request, &arguments)?,
+      "continue" => {
+        self.writer.response(
+          request,
+          Json::object(vec![("allThreadsContinued", true.into())]),
+        )?;
+        return Ok(Handled::Resume(Step::Continue));
+      },
+      "next" => {
+        self.writer.response(request, Json::object(vec![]))?;
+        return Ok(Handled::Resume(Step::Over));
+      },
+      "stepIn" => {
+        self.writer.response(request, Json::object(vec![]))?;
+        return Ok(Handled::Resume(Step::Into));
+      },
+      "disconnect" | "terminate" => {
+        self.writer.response(request, Json::object(vec![]))?;
+        return Ok(Handled::Disconnect);
+      },
+      command => {
+        self
+          .writer
+          .error(request, &format!("Unsupported request {}", command))?;
+      },
+    }
+
+    Ok(Handled::Pending)
+  }
+
+  fn set_breakpoints(&mut self, request: &Json, arguments: &Json) -> io::Result<()> {
+    let path = match arguments
+      .get("source")
+      .and_then(|source| source.get("path"))
+      .and_then(Json::as_str)
+    {
+      Some(path) => PathBuf::from(path),
+      None => {
+        return self
+          .writer
+          .error(request, "Breakpoints expect a source path")
+      },
+    };
+
+    let lines: Vec<u32> = arguments
+      .get("breakpoints")
+      .and_then(Json::as_array)
+      .unwrap_or(&[])
+      .iter()
+      .filter_map(|breakpoint| breakpoint.get("line").and_then(Json::as_f64))
+      .map(|line| line as u32)
+      .collect();
+
+    let verified = lines
+      .iter()
+      .map(|line| Json::object(vec![("verified", true.into()), ("line", (*line).into())]))
+      .collect::<Vec<Json>>();
+
+    self.sources.retain(|(source, _)| *source != path);
+    self.sources.push((path, lines));
+
+    self.writer.response(
+      request,
+      Json::object(vec![("breakpoints", verified.into())]),
+    )
+  }
+
+  fn stack_trace(&mut self, request: &Json) -> io::Result<()> {
+    let frames = self
+      .paused
+      .iter()
+      .flat_map(|paused| paused.frames.iter())
+      .enumerate()
+      .map(|(id, frame)| {
+        let name = frame
+          .module
+          .file_name()
+          .map(|name| name.to_string_lossy().to_string())
+          .unwrap_or_default();
+
+        Json::object(vec![
+          ("id", id.into()),
+          ("name", frame.name.as_str().into()),
+          ("line", frame.line.into()),
+          ("column", 1u8.into()),
+          (
+            "source",
+            Json::object(vec![
+              ("name", name.into()),
+              ("path", frame.module.to_string_lossy().to_string().into()),
+            ]),
+          ),
+        ])
+      })
+      .collect::<Vec<Json>>();
+
+    let total = frames.len();
+    self.writer.response(
+      request,
+      Json::object(vec![
+        ("stackFrames", frames.into()),
+        ("totalFrames", total.into()),
+      ]),
+    )
+  }
+
+  /// Each frame has a locals and an upvalues scope. Their variables
+  /// references are `frame * 2 + 1` and `frame * 2 + 2`
+  fn scopes(&mut self, request: &Json, arguments: &Json) -> io::Result<()> {
+    let frame = arguments
+      .get("frameId")
+      .and_then(Json::as_f64)
+      .unwrap_or(0.0) as usize;
+
+    let scope = |name: &str, reference: usize| {
+      Json::object(vec![
+        ("name", name.into()),
+        ("variablesReference", reference.into()),
+        ("expensive", false.into()),
+      ])
+    };
+
+    self.writer.response(
+      request,
+      Json::object(vec![(
+        "scopes",
+        Json::Array(vec![
+          scope("Locals", frame * 2 + 1),
+          scope("Upvalues", frame * 2 + 2),
+        ]),
+      )]),
+    )
+  }
+
+  fn variables(&mut self, request: &Json, arguments: &Json) -> io::Result<()> {
+    let reference = arguments
+      .get("variablesReference")
+      .and_then(Json::as_f64)
+      .unwrap_or(0.0) as usize;
+
+    let frame = self
+      .paused
+      .as_ref()
+      .and_then(|paused| paused.frames.get(reference.saturating_sub(1) / 2));
+
+    let (label, values) = match frame {
+      Some(frame) if reference % 2 == 1 => ("slot", &frame.locals),
+      Some(frame) => ("upvalue", &frame.upvalues),
+      None => return self.writer.error(request, "Unknown variables reference"),
+    };
+
+    let variables = values
+      .iter()
+      .enumerate()
+      .map(|(index, value)| {
+        Json::object(vec![
+          ("name", format!("{} {}", label, index).into()),
+          ("value", value.as_str().into()),
+          ("variablesReference", 0u8.into()),
+        ])
+      })
+      .collect::<Vec<Json>>();
+
+    self
+      .writer
+      .response(request, Json::object(vec![("variables", variables.into())]))
+  }
+
+  /// Serve requests while paused until the client resumes execution
+  fn serve_paused(&mut self, breakpoints: &mut Breakpoints) -> io::Result<Step> {
+    loop {
+      let request = match read_message(&mut self.input)? {
+        Some(request) => request,
+        None => {
+          self.disconnect();
+          return Ok(Step::Continue);
+        },
+      };
+
+      match self.handle(&request)? {
+        Handled::Resume(step) => return Ok(step),
+        Handled::Disconnect => {
+          self.disconnect();
+          return Ok(Step::Continue);
+        },
+        Handled::Pending | Handled::Configured => self.sync(breakpoints),
+      }
+    }
+  }
+}
+
+impl Debugger for DapSession {
+  fn start(&mut self, breakpoints: &mut Breakpoints) -> Step {
+    self.sync(breakpoints);
+
+    if self.stop_on_entry {
+      Step::Into
+    } else {
+      Step::Continue
+    }
+  }
+
+  fn paused(&mut self, paused: &Paused, breakpoints: &mut Breakpoints) -> Step {
+    if self.disconnected {
+      return Step::Continue;
+    }
+
+    let reason = match paused.reason {
+      PauseReason::Breakpoint => "breakpoint",
+      PauseReason::Step if self.stop_on_entry => "entry",
+      PauseReason::Step => "step",
+    };
+    self.stop_on_entry = false;
+    self.paused = Some(paused.clone());
+
+    let stopped = Json::object(vec![
+      ("reason", reason.into()),
+      ("threadId", THREAD_ID.into()),
+      ("allThreadsStopped", true.into()),
+    ]);
+
+    let step = self
+      .writer
+      .event("stopped", stopped)
+      .and_then(|()| self.serve_paused(breakpoints));
+    self.paused = None;
+
+    // the script is stopped if the client can no longer be reached
+    step.unwrap_or_else(|_| {
+      self.disconnect();
+      Step::Continue
+    })
+  }
+}
+
+/// Routes the script's stdio to the client as output events
+#[derive(Debug)]
+struct IoStdioDap {
+  writer: DapWriter,
+}
+
+impl IoImpl<Stdio> for IoStdioDap {
+  fn make(&self) -> Stdio {
+    Stdio::new(Box::new(StdioDap {
+      stdout: OutputWriter::new(self.writer.clone(), "stdout"),
+      stderr: OutputWriter::new(self.writer.clone(), "stderr"),
+      stdin: io::empty(),
+    }))
+  }
+}
+
+struct StdioDap {
+  stdout: OutputWriter,
+  stderr: OutputWriter,
+  stdin: io::Empty,
+}
+
+impl StdioImpl for StdioDap {
+  fn stdout(&mut self) -> &mut dyn Write {
+    &mut self.stdout
+  }
+  fn stderr(&mut self) -> &mut dyn Write {
+    &mut self.stderr
+  }
+  fn stderr_color(&mut self) -> &mut dyn WriteColor {
+    &mut self.stderr
+  }
+  fn stdin(&mut self) -> &mut dyn Read {
+    &mut self.stdin
+  }
+  fn read_line(&self, _buffer: &mut String) -> io::Result<usize> {
+    Ok(0)
+  }
+  fn is_tty(&self) -> bool {
+    false
+  }
+}
+
+/// Buffers script output sending each complete line as an output event
+struct OutputWriter {
+  writer: DapWriter,
+  category: &'static str,
+  buffer: Vec<u8>,
+}
+
+impl OutputWriter {
+  fn new(writer: DapWriter, category: &'static str) -> Self {
+    Self {
+      writer,
+      category,
+      buffer: vec![],
+    }
+  }
+}
+
+impl Write for OutputWriter {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.buffer.extend_from_slice(buf);
+
+    if let Some(end) = self.buffer.iter().rposition(|byte| *byte == b'\n') {
+      let lines: Vec<u8> = self.buffer.drain(..=end).collect();
+      self
+        .writer
+        .output(self.category, &String::from_utf8_lossy(&lines))?;
+    }
+
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    if !self.buffer.is_empty() {
+      let rest: Vec<u8> = self.buffer.drain(..).collect();
+      self
+        .writer
+        .output(self.category, &String::from_utf8_lossy(&rest))?;
+    }
+
+    Ok(())
+  }
+}
+
+impl Drop for OutputWriter {
+  fn drop(&mut self) {
+    let _ = self.flush();
+  }
+}
+
+impl WriteColor for OutputWriter {
+  fn supports_color(&self) -> bool {
+    false
+  }
+
+  fn set_color(&mut self, _: &ColorSpec) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn reset(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::io::Cursor;
+
+  #[derive(Clone, Default)]
+  struct Output(Arc<Mutex<Vec<u8>>>);
+
+  impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  /// Frame each request with its content length
+  fn requests(requests: &[&str]) -> Box<dyn BufRead> {
+    let framed = requests
+      .iter()
+      .map(|request| format!("Content-Length: {}\r\n\r\n{}", request.len(), request))
+      .collect::<String>();
+
+    Box::new(Cursor::new(framed.into_bytes()))
+  }
+
+  /// Read each message written to the output
+  fn messages(output: &Output) -> Vec<Json> {
+    let bytes = output.0.lock().unwrap().clone();
+    let mut input = Cursor::new(bytes);
+
+    let mut messages = vec![];
+    while let Some(message) = read_message(&mut input).expect("Unable to read message") {
+      messages.push(message);
+    }
+    messages
+  }
+
+  #[test]
+  fn configure() {
+    let output = Output::default();
+    let mut session = DapSession::new(
+      requests(&[
+        r#"{"seq":1,"type":"request","command":"initialize","arguments":{}}"#,
+        r#"{"seq":2,"type":"request","command":"setBreakpoints","arguments":{"source":{"path":"/a.lay"},"breakpoints":[{"line":2},{"line":5}]}}"#,
+        r#"{"seq":3,"type":"request","command":"configurationDone"}"#,
+        r#"{"seq":4,"type":"request","command":"evaluate"}"#,
+        r#"{"seq":5,"type":"request","command":"launch","arguments":{"program":"/a.lay","stopOnEntry":true}}"#,
+      ]),
+      DapWriter::new(Box::new(output.clone())),
+    );
+
+    let program = session.configure().expect("Unable to configure");
+    assert_eq!(program, Some(PathBuf::from("/a.lay")));
+
+    let mut breakpoints = Breakpoints::default();
+    assert_eq!(session.start(&mut breakpoints), Step::Into);
+    assert_eq!(breakpoints.iter().count(), 2);
+    assert!(breakpoints.matches(&PathBuf::from("/a.lay"), 5));
+
+    let messages = messages(&output);
+    let kinds = messages
+      .iter()
+      .map(|message| {
+        let kind = message.get("command").or_else(|| message.get("event"));
+        kind.and_then(Json::as_str).unwrap_or("").to_string()
+      })
+      .collect::<Vec<String>>();
+    assert_eq!(
+      kinds,
+      vec![
+        "initialize",
+        "initialized",
+        "setBreakpoints",
+        "configurationDone",
+        "evaluate",
+        "launch"
+      ]
+    );
+
+    assert_eq!(messages[4].get("success"), Some(&Json::Bool(false)));
+    assert_eq!(
+      messages[2].to_string(),
+      concat!(
+        r#"{"seq":3,"type":"response","request_seq":2,"success":true,"command":"setBreakpoints","#,
+        r#""body":{"breakpoints":[{"verified":true,"line":2},{"verified":true,"line":5}]}}"#
+      )
+    );
+  }
+
+  #[test]
+  fn output_events() {
+    let output = Output::default();
+    {
+      let mut writer = OutputWriter::new(DapWriter::new(Box::new(output.clone())), "stdout");
+      write!(writer, "a").unwrap();
+      write!(writer, "b\nc").unwrap();
+    }
+
+    let messages = messages(&output);
+    let outputs = messages
+      .iter()
+      .map(|message| {
+        message
+          .get("body")
+          .and_then(|body| body.get("output"))
+          .cloned()
+      })
+      .collect::<Vec<Option<Json>>>();
+    assert_eq!(
+      outputs,
+      vec![Some(Json::from("ab\n")), Some(Json::from("c"))]
+    );
+  }
+}
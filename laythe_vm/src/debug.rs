@@ -1,8 +1,18 @@
-use laythe_core::{chunk::Chunk, if_let_obj, object::ObjectKind, to_obj_kind, value::Value};
+use laythe_core::{
+  chunk::Chunk,
+  if_let_obj,
+  object::{Fun, ObjectKind},
+  signature::Arity,
+  to_obj_kind,
+  value::Value,
+};
 use laythe_env::stdio::Stdio;
 use std::{io, io::Write, mem};
 
-use crate::byte_code::{decode_u16, decode_u32, AlignedByteCode, UpvalueIndex};
+use crate::{
+  byte_code::{decode_u16, decode_u32, AlignedByteCode, ByteCode, Operand, UpvalueIndex},
+  json::Json,
+};
 #[cfg(feature = "debug")]
 use laythe_core::call_frame::CallFrame;
 
@@ -24,38 +34,232 @@ pub fn disassemble_chunk(stdio: &mut Stdio, chunk: &Chunk, name: &str) -> io::Re
   writeln!(stdout, "{0}", name)?;
 
   let mut offset: usize = 0;
-  let mut last_offset: usize = 0;
-
   while offset < chunk.instructions().len() {
-    let show_line = chunk.get_line(offset) == chunk.get_line(last_offset);
-    let temp = disassemble_instruction(stdio, chunk, offset, show_line);
-    last_offset = offset;
-    offset = temp?;
+    offset = disassemble_instruction(stdio, chunk, offset, true)?;
+  }
+
+  Ok(())
+}
+
+/// Write a function's chunk to console followed by the chunk of
+/// each function defined inside of it
+pub fn disassemble_fun(stdio: &mut Stdio, fun: &Fun, name: &str) -> io::Result<()> {
+  disassemble_chunk(stdio, fun.chunk(), name)?;
+
+  for constant in fun.chunk().constants() {
+    if_let_obj!(ObjectKind::Fun(inner) = (*constant) {
+      disassemble_fun(stdio, &inner, &inner.name())?;
+    });
   }
 
   Ok(())
 }
 
-/// Write an instruction to console
+/// Serialize a function's chunk to json along with each function
+/// defined inside of it
+pub fn fun_json(fun: &Fun) -> Json {
+  let chunk = fun.chunk();
+  let arity = match fun.arity() {
+    Arity::Fixed(arity) => Json::object(vec![("fixed", (*arity).into())]),
+    Arity::Variadic(arity) => Json::object(vec![("variadic", (*arity).into())]),
+    Arity::Default(required, total) => Json::object(vec![
+      ("required", (*required).into()),
+      ("total", (*total).into()),
+    ]),
+  };
+
+  let constants = chunk
+    .constants()
+    .iter()
+    .map(|constant| {
+      Json::object(vec![
+        ("type", constant.value_type().into()),
+        ("value", constant_json(*constant)),
+      ])
+    })
+    .collect::<Vec<Json>>();
+
+  let mut instructions = vec![];
+  let mut ip = 0;
+  while ip < chunk.instructions().len() {
+    let (instruction, offset) = instruction_json(chunk, ip);
+    instructions.push(instruction);
+    ip = offset;
+  }
+
+  let mut functions = vec![];
+  for constant in chunk.constants() {
+    if_let_obj!(ObjectKind::Fun(inner) = (*constant) {
+      functions.push(fun_json(&inner));
+    });
+  }
+
+  Json::object(vec![
+    ("name", (&*fun.name()).into()),
+    ("arity", arity),
+    ("upvalue_count", fun.upvalue_count().into()),
+    ("constants", constants.into()),
+    ("instructions", instructions.into()),
+    ("functions", functions.into()),
+  ])
+}
+
+/// Serialize a constant to json, falling back to its display
+/// for objects json cannot represent
+fn constant_json(constant: Value) -> Json {
+  if constant.is_num() {
+    constant.to_num().into()
+  } else if constant.is_bool() {
+    constant.to_bool().into()
+  } else if constant.is_nil() {
+    Json::Null
+  } else if constant.is_obj_kind(ObjectKind::String) {
+    (&*constant.to_obj().to_str()).into()
+  } else {
+    constant.to_string().into()
+  }
+}
+
+/// Serialize the instruction at `ip` to json returning the offset of
+/// the following instruction
+fn instruction_json(chunk: &Chunk, ip: usize) -> (Json, usize) {
+  let instructions = chunk.instructions();
+  let info = ByteCode::from(instructions[ip]).info();
+
+  let mut operands = vec![];
+  let mut offset = ip + 1;
+  for operand in info.operands {
+    match operand {
+      Operand::U8 => operands.push(instructions[offset].into()),
+      Operand::U16 => operands.push(decode_u16(&instructions[offset..offset + 2]).into()),
+      Operand::Slot => operands.push(decode_u32(&instructions[offset..offset + 4]).into()),
+      Operand::UpvalueIndices => {
+        let constant = chunk.get_constant(decode_u16(&instructions[ip + 1..ip + 3]) as usize);
+        let upvalue_count = if_let_obj!(ObjectKind::Fun(fun) = (constant) {
+          fun.upvalue_count()
+        } else {
+          0
+        });
+
+        for index in 0..upvalue_count {
+          let start = offset + index * 2;
+          let upvalue_index: UpvalueIndex =
+            unsafe { mem::transmute(decode_u16(&instructions[start..start + 2])) };
+
+          operands.push(match upvalue_index {
+            UpvalueIndex::Local(local) => Json::object(vec![("local", local.into())]),
+            UpvalueIndex::Upvalue(upvalue) => Json::object(vec![("upvalue", upvalue.into())]),
+          });
+        }
+        offset += upvalue_count * 2;
+        continue;
+      },
+    }
+
+    offset += operand.width().expect("Expected fixed width operand");
+  }
+
+  // spans are keyed by the offset following an instruction
+  let span = chunk.get_span(offset);
+  let mut fields = vec![
+    ("offset", ip.into()),
+    ("op", info.name.into()),
+    ("line", span.line.into()),
+    ("column", span.column.into()),
+  ];
+
+  let constants = operands
+    .iter()
+    .take(constant_operands(info.byte_code))
+    .filter_map(|index| match index {
+      Json::Number(index) => Some(constant_json(chunk.get_constant(*index as usize))),
+      _ => None,
+    })
+    .collect::<Vec<Json>>();
+
+  if !constants.is_empty() {
+    fields.push(("constants", constants.into()));
+  }
+  fields.push(("operands", operands.into()));
+
+  match info.byte_code {
+    ByteCode::And
+    | ByteCode::Or
+    | ByteCode::Jump
+    | ByteCode::JumpIfFalse
+    | ByteCode::JumpIfNotLess => {
+      let jump = decode_u16(&instructions[ip + 1..ip + 3]) as usize;
+      fields.push(("target", (offset + jump).into()));
+    },
+    ByteCode::Loop => {
+      let jump = decode_u16(&instructions[ip + 1..ip + 3]) as usize;
+      fields.push(("target", (offset - jump).into()));
+    },
+    _ => (),
+  }
+
+  (Json::object(fields), offset)
+}
+
+/// The number of leading operands of an op code that index the constant table
+fn constant_operands(byte_code: ByteCode) -> usize {
+  match byte_code {
+    ByteCode::ImportSymbol => 2,
+    ByteCode::Constant
+    | ByteCode::ConstantLong
+    | ByteCode::IterNext
+    | ByteCode::IterCurrent
+    | ByteCode::Import
+    | ByteCode::Export
+    | ByteCode::Invoke
+    | ByteCode::SuperInvoke
+    | ByteCode::InvokeSealed
+    | ByteCode::Class
+    | ByteCode::GetSuper
+    | ByteCode::Closure
+    | ByteCode::Method
+    | ByteCode::Field
+    | ByteCode::StaticMethod
+    | ByteCode::DefineGlobal
+    | ByteCode::GetGlobal
+    | ByteCode::SetGlobal
+    | ByteCode::GetProperty
+    | ByteCode::SetProperty
+    | ByteCode::EqualConst
+    | ByteCode::NotEqualConst
+    | ByteCode::AddConstToLocal => 1,
+    _ => 0,
+  }
+}
+
+/// Write an instruction to console. When `group_lines` is set an instruction
+/// from the same line as the previous instruction prints `|` in place of its line
 pub fn disassemble_instruction(
   stdio: &mut Stdio,
   chunk: &Chunk,
   ip: usize,
-  show_line: bool,
+  group_lines: bool,
 ) -> io::Result<usize> {
+  let (instruction, offset) = AlignedByteCode::decode(chunk.instructions(), ip);
+
+  // spans are keyed by the offset following an instruction so the line
+  // of the previous instruction is found at this instruction's start
+  let line = chunk.get_line(offset);
   let stdout = stdio.stdout();
   write!(stdout, "  {:0>4} ", ip)?;
 
-  if ip != 0 && show_line {
+  if group_lines && ip != 0 && chunk.get_line(ip) == line {
     write!(stdout, "   | ")?;
   } else {
-    write!(stdout, "{:>4} ", chunk.get_line(ip))?;
+    write!(stdout, "{:>4} ", line)?;
   }
 
-  let (instruction, offset) = AlignedByteCode::decode(chunk.instructions(), ip as usize);
   match instruction {
     AlignedByteCode::Return => simple_instruction(stdio.stdout(), "Return", offset),
     AlignedByteCode::Negate => simple_instruction(stdio.stdout(), "Negate", offset),
+    AlignedByteCode::Await => simple_instruction(stdio.stdout(), "Await", offset),
+    AlignedByteCode::Raise => simple_instruction(stdio.stdout(), "Raise", offset),
+    AlignedByteCode::RaiseFrom => simple_instruction(stdio.stdout(), "RaiseFrom", offset),
     AlignedByteCode::Add => simple_instruction(stdio.stdout(), "Add", offset),
     AlignedByteCode::Subtract => simple_instruction(stdio.stdout(), "Subtract", offset),
     AlignedByteCode::Multiply => simple_instruction(stdio.stdout(), "Multiply", offset),
@@ -70,11 +274,17 @@ pub fn disassemble_instruction(
       short_instruction(stdio.stdout(), "List", arg_count, offset)
     }
     AlignedByteCode::Map(arg_count) => short_instruction(stdio.stdout(), "Map", arg_count, offset),
+    AlignedByteCode::Tuple(arg_count) => {
+      short_instruction(stdio.stdout(), "Tuple", arg_count, offset)
+    }
+    AlignedByteCode::Unpack(arg_count) => {
+      short_instruction(stdio.stdout(), "Unpack", arg_count, offset)
+    }
     AlignedByteCode::Interpolate(arg_count) => {
       short_instruction(stdio.stdout(), "Interpolate", arg_count, offset)
     }
     AlignedByteCode::IterNext(constant) => {
-      invoke_instruction(stdio.stdout(), "IterNext", chunk, constant, 0, offset)
+      constant_instruction(stdio.stdout(), "IterNext", chunk, constant, offset)
     }
     AlignedByteCode::IterCurrent(constant) => {
       constant_instruction(stdio.stdout(), "IterCurrent", chunk, constant, offset)
@@ -92,21 +302,39 @@ pub fn disassemble_instruction(
     AlignedByteCode::Export(constant) => {
       constant_instruction(stdio.stdout(), "Export", chunk, constant, offset)
     }
-    AlignedByteCode::Invoke((constant, arg_count)) => {
-      invoke_instruction(stdio.stdout(), "Invoke", chunk, constant, arg_count, offset)
-    }
+    AlignedByteCode::Invoke((constant, arg_count)) => invoke_instruction(
+      stdio.stdout(),
+      "Invoke",
+      chunk,
+      constant,
+      arg_count,
+      "cache",
+      offset,
+    ),
     AlignedByteCode::SuperInvoke((constant, arg_count)) => invoke_instruction(
       stdio.stdout(),
       "SuperInvoke",
       chunk,
       constant,
       arg_count,
+      "cache",
+      offset,
+    ),
+    AlignedByteCode::InvokeSealed((constant, arg_count)) => invoke_instruction(
+      stdio.stdout(),
+      "InvokeSealed",
+      chunk,
+      constant,
+      arg_count,
+      "vtable",
       offset,
     ),
     AlignedByteCode::Class(constant) => {
       constant_instruction(stdio.stdout(), "Class", chunk, constant, offset)
     }
     AlignedByteCode::Inherit => simple_instruction(stdio.stdout(), "Inherit", offset),
+    AlignedByteCode::Extend => simple_instruction(stdio.stdout(), "Extend", offset),
+    AlignedByteCode::Seal => simple_instruction(stdio.stdout(), "Seal", offset),
     AlignedByteCode::GetSuper(constant) => {
       constant_instruction(stdio.stdout(), "GetSuper", chunk, constant, offset)
     }
@@ -159,6 +387,12 @@ pub fn disassemble_instruction(
     AlignedByteCode::Loop(jump) => jump_instruction(stdio.stdout(), "Loop", -1, jump, offset),
     AlignedByteCode::Equal => simple_instruction(stdio.stdout(), "Equal", offset),
     AlignedByteCode::NotEqual => simple_instruction(stdio.stdout(), "NotEqual", offset),
+    AlignedByteCode::EqualConst(constant) => {
+      constant_instruction(stdio.stdout(), "EqualConst", chunk, constant, offset)
+    }
+    AlignedByteCode::NotEqualConst(constant) => {
+      constant_instruction(stdio.stdout(), "NotEqualConst", chunk, constant, offset)
+    }
     AlignedByteCode::Greater => simple_instruction(stdio.stdout(), "Greater", offset),
     AlignedByteCode::GreaterEqual => simple_instruction(stdio.stdout(), "GreaterEqual", offset),
     AlignedByteCode::Less => simple_instruction(stdio.stdout(), "Less", offset),
@@ -169,6 +403,17 @@ pub fn disassemble_instruction(
     AlignedByteCode::ConstantLong(constant) => {
       constant_instruction(stdio.stdout(), "ConstantLong", chunk, constant, offset)
     }
+    AlignedByteCode::AddConstToLocal((constant, local)) => constant_local_instruction(
+      stdio.stdout(),
+      "AddConstToLocal",
+      chunk,
+      constant,
+      local,
+      offset,
+    ),
+    AlignedByteCode::JumpIfNotLess(jump) => {
+      jump_instruction(stdio.stdout(), "JumpIfNotLess", 1, jump, offset)
+    }
   }
 }
 
@@ -179,14 +424,8 @@ fn jump_instruction(
   jump: u16,
   offset: usize,
 ) -> io::Result<usize> {
-  let net_jump = sign * (jump as isize);
-  writeln!(
-    stdout,
-    "{:13} {:5} -> {}",
-    name,
-    offset - 3,
-    (offset as isize) + net_jump
-  )?;
+  let target = (offset as isize) + sign * (jump as isize);
+  writeln!(stdout, "{:13} {:5} -> {:0>4}", name, jump, target)?;
   Ok(offset)
 }
 
@@ -211,7 +450,7 @@ fn constant_pair_instruction(
   constants: (u16, u16),
   offset: usize,
 ) -> io::Result<usize> {
-  write!(stdout, "{:13} {:5} {:5}", name, constants.0, constants.1)?;
+  write!(stdout, "{:13} {:5} {:5} ", name, constants.0, constants.1)?;
   writeln!(
     stdout,
     "{} {}",
@@ -221,6 +460,25 @@ fn constant_pair_instruction(
   Ok(offset)
 }
 
+/// print a constant applied to a local
+fn constant_local_instruction(
+  stdout: &mut dyn Write,
+  name: &str,
+  chunk: &Chunk,
+  constant: u16,
+  local: u8,
+  offset: usize,
+) -> io::Result<usize> {
+  write!(stdout, "{:13} {:5} ", name, constant)?;
+  writeln!(
+    stdout,
+    "{} local {}",
+    &chunk.get_constant(constant as usize),
+    local
+  )?;
+  Ok(offset)
+}
+
 /// print a constant
 fn constant_instruction_with_slot(
   stdout: &mut dyn Write,
@@ -301,13 +559,15 @@ fn invoke_instruction(
   chunk: &Chunk,
   constant: u16,
   arg_count: u8,
+  slot_kind: &str,
   offset: usize,
 ) -> io::Result<usize> {
   write!(stdout, "{:13} {:5} ({} args) ", name, constant, arg_count)?;
   write!(stdout, "{}", &chunk.get_constant(constant as usize))?;
   writeln!(
     stdout,
-    " cache slot {}",
+    " {} slot {}",
+    slot_kind,
     &decode_u32(&chunk.instructions()[offset..offset + 4])
   )?;
   Ok(offset + 4)
@@ -340,3 +600,191 @@ fn simple_instruction(stdout: &mut dyn Write, name: &str, offset: usize) -> io::
   writeln!(stdout, "{:13}", name)?;
   Ok(offset)
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::byte_code::{ByteCode, Operand, OP_CODES};
+  use laythe_core::{
+    chunk::{ChunkBuilder, Encode, SourceSpan},
+    hooks::{GcHooks, NoContext},
+    memory::Allocator,
+    support::test_fun,
+    val,
+  };
+  use laythe_env::stdio::support::StdioTestContainer;
+  use std::str;
+
+  fn sample_instruction(byte_code: ByteCode) -> AlignedByteCode {
+    match byte_code {
+      ByteCode::Return => AlignedByteCode::Return,
+      ByteCode::Negate => AlignedByteCode::Negate,
+      ByteCode::Await => AlignedByteCode::Await,
+      ByteCode::Raise => AlignedByteCode::Raise,
+      ByteCode::RaiseFrom => AlignedByteCode::RaiseFrom,
+      ByteCode::Add => AlignedByteCode::Add,
+      ByteCode::Subtract => AlignedByteCode::Subtract,
+      ByteCode::Multiply => AlignedByteCode::Multiply,
+      ByteCode::Divide => AlignedByteCode::Divide,
+      ByteCode::Not => AlignedByteCode::Not,
+      ByteCode::And => AlignedByteCode::And(3),
+      ByteCode::Or => AlignedByteCode::Or(3),
+      ByteCode::Constant => AlignedByteCode::Constant(0),
+      ByteCode::ConstantLong => AlignedByteCode::ConstantLong(0),
+      ByteCode::Nil => AlignedByteCode::Nil,
+      ByteCode::True => AlignedByteCode::True,
+      ByteCode::False => AlignedByteCode::False,
+      ByteCode::List => AlignedByteCode::List(2),
+      ByteCode::Map => AlignedByteCode::Map(2),
+      ByteCode::Tuple => AlignedByteCode::Tuple(2),
+      ByteCode::Unpack => AlignedByteCode::Unpack(2),
+      ByteCode::Interpolate => AlignedByteCode::Interpolate(2),
+      ByteCode::IterNext => AlignedByteCode::IterNext(0),
+      ByteCode::IterCurrent => AlignedByteCode::IterCurrent(0),
+      ByteCode::Drop => AlignedByteCode::Drop,
+      ByteCode::DropN => AlignedByteCode::DropN(2),
+      ByteCode::Dup => AlignedByteCode::Dup,
+      ByteCode::Import => AlignedByteCode::Import(0),
+      ByteCode::ImportSymbol => AlignedByteCode::ImportSymbol((0, 0)),
+      ByteCode::Export => AlignedByteCode::Export(0),
+      ByteCode::DefineGlobal => AlignedByteCode::DefineGlobal(0),
+      ByteCode::GetGlobal => AlignedByteCode::GetGlobal(0),
+      ByteCode::SetGlobal => AlignedByteCode::SetGlobal(0),
+      ByteCode::GetUpvalue => AlignedByteCode::GetUpvalue(1),
+      ByteCode::SetUpvalue => AlignedByteCode::SetUpvalue(1),
+      ByteCode::GetLocal => AlignedByteCode::GetLocal(1),
+      ByteCode::SetLocal => AlignedByteCode::SetLocal(1),
+      ByteCode::GetProperty => AlignedByteCode::GetProperty(0),
+      ByteCode::SetProperty => AlignedByteCode::SetProperty(0),
+      ByteCode::JumpIfFalse => AlignedByteCode::JumpIfFalse(3),
+      ByteCode::Jump => AlignedByteCode::Jump(3),
+      ByteCode::Loop => AlignedByteCode::Loop(0),
+      ByteCode::Call => AlignedByteCode::Call(1),
+      ByteCode::Invoke => AlignedByteCode::Invoke((0, 1)),
+      ByteCode::SuperInvoke => AlignedByteCode::SuperInvoke((0, 1)),
+      ByteCode::InvokeSealed => AlignedByteCode::InvokeSealed((0, 1)),
+      ByteCode::Closure => AlignedByteCode::Closure(1),
+      ByteCode::Method => AlignedByteCode::Method(0),
+      ByteCode::Field => AlignedByteCode::Field(0),
+      ByteCode::StaticMethod => AlignedByteCode::StaticMethod(0),
+      ByteCode::Class => AlignedByteCode::Class(0),
+      ByteCode::Inherit => AlignedByteCode::Inherit,
+      ByteCode::Extend => AlignedByteCode::Extend,
+      ByteCode::Seal => AlignedByteCode::Seal,
+      ByteCode::GetSuper => AlignedByteCode::GetSuper(0),
+      ByteCode::CloseUpvalue => AlignedByteCode::CloseUpvalue,
+      ByteCode::Equal => AlignedByteCode::Equal,
+      ByteCode::NotEqual => AlignedByteCode::NotEqual,
+      ByteCode::EqualConst => AlignedByteCode::EqualConst(0),
+      ByteCode::NotEqualConst => AlignedByteCode::NotEqualConst(0),
+      ByteCode::Greater => AlignedByteCode::Greater,
+      ByteCode::GreaterEqual => AlignedByteCode::GreaterEqual,
+      ByteCode::Less => AlignedByteCode::Less,
+      ByteCode::LessEqual => AlignedByteCode::LessEqual,
+      ByteCode::AddConstToLocal => AlignedByteCode::AddConstToLocal((0, 1)),
+      ByteCode::JumpIfNotLess => AlignedByteCode::JumpIfNotLess(3),
+    }
+  }
+
+  #[test]
+  fn disassemble_matches_op_codes() {
+    let context = NoContext::new(Allocator::default());
+    let hooks = GcHooks::new(&context);
+    let fun = test_fun(&hooks, "example", "module");
+
+    for info in OP_CODES {
+      let mut builder = ChunkBuilder::default();
+      builder.add_constant(val!(10.0));
+      builder.add_constant(val!(fun));
+
+      builder.write_instruction(sample_instruction(info.byte_code), SourceSpan::default());
+      if info.operands.contains(&Operand::Slot) {
+        builder.write_instruction(AlignedByteCode::Slot(0), SourceSpan::default());
+      }
+
+      let chunk = builder.build();
+      let stdio_container = StdioTestContainer::default();
+      let mut stdio = Stdio::new(Box::new(stdio_container.make_stdio()));
+
+      let offset = disassemble_instruction(&mut stdio, &chunk, 0, false)
+        .expect("Unable to disassemble instruction");
+      assert_eq!(
+        offset,
+        chunk.instructions().len(),
+        "for op code {}",
+        info.name
+      );
+
+      let output = str::from_utf8(&stdio_container.stdout).expect("Output was not utf8");
+      let name = output.split_whitespace().nth(2);
+      assert_eq!(name, Some(info.name), "for op code {}", info.name);
+    }
+  }
+
+  #[test]
+  fn disassemble_lines_and_jumps() {
+    let mut builder = ChunkBuilder::default();
+    builder.add_constant(val!(10.0));
+    builder.write_instruction(AlignedByteCode::Constant(0), SourceSpan::new(1, 1, 2));
+    builder.write_instruction(AlignedByteCode::JumpIfFalse(1), SourceSpan::new(1, 1, 2));
+    builder.write_instruction(AlignedByteCode::Drop, SourceSpan::new(2, 1, 2));
+    builder.write_instruction(AlignedByteCode::Loop(7), SourceSpan::new(2, 1, 2));
+    builder.write_instruction(AlignedByteCode::Return, SourceSpan::new(3, 1, 2));
+
+    let chunk = builder.build();
+    let stdio_container = StdioTestContainer::default();
+    let mut stdio = Stdio::new(Box::new(stdio_container.make_stdio()));
+    disassemble_chunk(&mut stdio, &chunk, "example").expect("Unable to disassemble chunk");
+
+    let output = str::from_utf8(&stdio_container.stdout).expect("Output was not utf8");
+    let lines: Vec<&str> = output.lines().map(|line| line.trim_end()).collect();
+    assert_eq!(
+      lines,
+      vec![
+        "",
+        "example",
+        "  0000    1 Constant          0 10",
+        "  0002    | JumpIfFalse       1 -> 0006",
+        "  0005    2 Drop",
+        "  0006    | Loop              7 -> 0002",
+        "  0009    3 Return",
+      ]
+    );
+  }
+
+  #[test]
+  fn instruction_json_operands() {
+    let context = NoContext::new(Allocator::default());
+    let hooks = GcHooks::new(&context);
+    let name = hooks.manage_str("name");
+
+    let mut builder = ChunkBuilder::default();
+    builder.add_constant(val!(name));
+    builder.write_instruction(AlignedByteCode::GetGlobal(0), SourceSpan::new(1, 3, 4));
+    builder.write_instruction(AlignedByteCode::Loop(3), SourceSpan::new(2, 1, 2));
+    let chunk = builder.build();
+
+    let (json, offset) = instruction_json(&chunk, 0);
+    assert_eq!(offset, 3);
+    assert_eq!(
+      json.to_string(),
+      r#"{"offset":0,"op":"GetGlobal","line":1,"column":3,"constants":["name"],"operands":[0]}"#
+    );
+
+    let (json, offset) = instruction_json(&chunk, 3);
+    assert_eq!(offset, 6);
+    assert_eq!(
+      json.to_string(),
+      r#"{"offset":3,"op":"Loop","line":2,"column":1,"operands":[3],"target":3}"#
+    );
+  }
+
+  #[test]
+  fn sample_instruction_encodes_op_code() {
+    for info in OP_CODES {
+      let mut buffer = vec![];
+      sample_instruction(info.byte_code).encode(&mut buffer);
+      assert_eq!(ByteCode::from(buffer[0]), info.byte_code);
+    }
+  }
+}
@@ -0,0 +1,591 @@
+use laythe_core::{managed::GcObj, object::Fun};
+use std::{
+  cell::RefCell,
+  fmt,
+  io::{self, BufRead, Write},
+  path::{Path, PathBuf},
+  rc::Rc,
+};
+
+/// A source location execution pauses at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakpoint {
+  /// The module path, matched against the trailing components
+  /// of each module's full path
+  pub module: PathBuf,
+
+  /// The one based source line
+  pub line: u32,
+}
+
+impl Breakpoint {
+  /// Create a new breakpoint
+  pub fn new<P: Into<PathBuf>>(module: P, line: u32) -> Self {
+    Self {
+      module: module.into(),
+      line,
+    }
+  }
+
+  /// Parse a breakpoint of the form `module:line`
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_vm::debugger::Breakpoint;
+  ///
+  /// let breakpoint = Breakpoint::parse("src/main.ly:10");
+  /// assert_eq!(breakpoint, Some(Breakpoint::new("src/main.ly", 10)));
+  /// assert_eq!(Breakpoint::parse("main.ly"), None);
+  /// ```
+  pub fn parse(breakpoint: &str) -> Option<Self> {
+    let (module, line) = breakpoint.rsplit_once(':')?;
+    let line = line.parse::<u32>().ok()?;
+
+    if module.is_empty() {
+      return None;
+    }
+
+    Some(Self::new(module, line))
+  }
+
+  /// Does this breakpoint refer to the provided module and line
+  pub fn matches(&self, module: &Path, line: u32) -> bool {
+    self.line == line && module.ends_with(&self.module)
+  }
+}
+
+impl fmt::Display for Breakpoint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}:{}", self.module.display(), self.line)
+  }
+}
+
+/// The breakpoints set for a debugging session
+#[derive(Debug, Clone, Default)]
+pub struct Breakpoints {
+  breakpoints: Vec<Breakpoint>,
+}
+
+impl Breakpoints {
+  /// Add a breakpoint returning false if it was already set
+  pub fn add(&mut self, breakpoint: Breakpoint) -> bool {
+    if self.breakpoints.contains(&breakpoint) {
+      return false;
+    }
+
+    self.breakpoints.push(breakpoint);
+    true
+  }
+
+  /// Remove a breakpoint returning false if it was not set
+  pub fn remove(&mut self, breakpoint: &Breakpoint) -> bool {
+    let len = self.breakpoints.len();
+    self.breakpoints.retain(|existing| existing != breakpoint);
+    len != self.breakpoints.len()
+  }
+
+  /// Remove every breakpoint
+  pub fn clear(&mut self) {
+    self.breakpoints.clear();
+  }
+
+  /// Is any breakpoint set
+  pub fn is_empty(&self) -> bool {
+    self.breakpoints.is_empty()
+  }
+
+  /// Iterate the set breakpoints
+  pub fn iter(&self) -> impl Iterator<Item = &Breakpoint> {
+    self.breakpoints.iter()
+  }
+
+  /// Is a breakpoint set at the provided module and line
+  pub fn matches(&self, module: &Path, line: u32) -> bool {
+    self
+      .breakpoints
+      .iter()
+      .any(|breakpoint| breakpoint.matches(module, line))
+  }
+}
+
+/// How execution proceeds after a pause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+  /// Run until the next breakpoint
+  Continue,
+
+  /// Pause at the next line of this frame or a frame below it
+  Over,
+
+  /// Pause at the next line executed in any frame
+  Into,
+}
+
+/// Why execution paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseReason {
+  /// Execution reached a breakpoint
+  Breakpoint,
+
+  /// Execution completed a step
+  Step,
+}
+
+/// A snapshot of a single call frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameInfo {
+  /// The name of the frame's function
+  pub name: String,
+
+  /// The full path of the module the function was defined in
+  pub module: PathBuf,
+
+  /// The line currently executing in this frame
+  pub line: u32,
+
+  /// The stack slots of this frame. Slot zero holds the called
+  /// function or the method receiver
+  pub locals: Vec<String>,
+
+  /// The values captured by the frame's closure
+  pub upvalues: Vec<String>,
+}
+
+/// A snapshot of the vm when execution paused
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paused {
+  /// Why execution paused
+  pub reason: PauseReason,
+
+  /// The call stack with the innermost frame first
+  pub frames: Vec<FrameInfo>,
+}
+
+impl Paused {
+  /// The frame execution paused in
+  pub fn frame(&self) -> &FrameInfo {
+    &self.frames[0]
+  }
+}
+
+/// A debugger the vm hands control to whenever execution pauses
+pub trait Debugger {
+  /// Called once before the first instruction is executed. The returned
+  /// step determines where execution first pauses
+  fn start(&mut self, _breakpoints: &mut Breakpoints) -> Step {
+    Step::Continue
+  }
+
+  /// Called each time execution pauses. The returned step determines
+  /// where execution pauses next
+  fn paused(&mut self, paused: &Paused, breakpoints: &mut Breakpoints) -> Step;
+}
+
+/// Where execution is in the call stack
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Location {
+  /// The number of frames on the current fiber
+  pub depth: usize,
+
+  /// The function of the current frame
+  pub fun: GcObj<Fun>,
+
+  /// The line of the next instruction
+  pub line: u32,
+}
+
+/// The state of a debugger attached to the vm
+pub(crate) struct DebugSession {
+  debugger: Rc<RefCell<dyn Debugger>>,
+  breakpoints: Breakpoints,
+
+  /// How execution proceeds, set once the debugger has started
+  step: Option<Step>,
+
+  /// The location of the last executed instruction
+  last: Option<Location>,
+
+  /// The location execution last paused at until execution moves
+  /// to another line in the same frame or returns from it
+  origin: Option<Location>,
+}
+
+impl DebugSession {
+  pub fn new(debugger: Rc<RefCell<dyn Debugger>>, breakpoints: Breakpoints) -> Self {
+    Self {
+      debugger,
+      breakpoints,
+      step: None,
+      last: None,
+      origin: None,
+    }
+  }
+
+  /// Determine if execution should pause before an instruction at
+  /// the provided location. Execution only pauses on entering a new line
+  pub fn should_pause(&mut self, location: Location, module: &Path) -> Option<PauseReason> {
+    let step = match self.step {
+      Some(step) => step,
+      None => {
+        let step = self.debugger.borrow_mut().start(&mut self.breakpoints);
+        self.step = Some(step);
+        step
+      },
+    };
+
+    if self.last == Some(location) {
+      return None;
+    }
+    self.last = Some(location);
+
+    if let Some(origin) = self.origin {
+      if location.depth < origin.depth || (location.depth == origin.depth && location != origin) {
+        self.origin = None;
+      }
+    }
+
+    // returning to the line execution paused at does not pause again
+    if self.origin == Some(location) {
+      return None;
+    }
+
+    if self.breakpoints.matches(module, location.line) {
+      return Some(PauseReason::Breakpoint);
+    }
+
+    match step {
+      Step::Into => Some(PauseReason::Step),
+      Step::Over if self.origin.is_none() => Some(PauseReason::Step),
+      _ => None,
+    }
+  }
+
+  /// Hand control to the debugger until it resumes execution
+  pub fn pause(&mut self, paused: &Paused, location: Location) {
+    let step = self
+      .debugger
+      .borrow_mut()
+      .paused(paused, &mut self.breakpoints);
+
+    self.step = Some(step);
+    self.origin = Some(location);
+  }
+}
+
+const CLI_HELP: &str = "Commands:
+  c, continue         Run until the next breakpoint
+  n, next             Step over the current line
+  s, step             Step into the current line
+  bt, backtrace       Print the call stack
+  l, locals [frame]   Print the stack slots of a frame
+  u, upvalues [frame] Print the captured values of a frame
+  b, break <line>     Set a breakpoint in the current module or at module:line
+  d, delete <line>    Remove a breakpoint in the current module or at module:line
+  breakpoints         List the set breakpoints
+  h, help             Print this message";
+
+/// A line based debugger driven by commands read from its input
+pub struct CliDebugger {
+  input: Box<dyn BufRead>,
+  output: Box<dyn Write>,
+
+  /// Set once the input is exhausted after which execution runs to completion
+  detached: bool,
+}
+
+impl CliDebugger {
+  /// Create a debugger reading commands from input and writing to output
+  pub fn new(input: Box<dyn BufRead>, output: Box<dyn Write>) -> Self {
+    Self {
+      input,
+      output,
+      detached: false,
+    }
+  }
+
+  /// Create a debugger attached to the process's stdin and stderr so
+  /// the debugger's output does not interleave with the script's stdout
+  pub fn stdio() -> Self {
+    Self::new(
+      Box::new(io::BufReader::new(io::stdin())),
+      Box::new(io::stderr()),
+    )
+  }
+
+  /// Read and run commands until one resumes execution
+  fn prompt(&mut self, paused: &Paused, breakpoints: &mut Breakpoints) -> io::Result<Step> {
+    loop {
+      write!(self.output, "(debug) ")?;
+      self.output.flush()?;
+
+      let mut line = String::new();
+      if self.input.read_line(&mut line)? == 0 {
+        writeln!(self.output)?;
+        self.detached = true;
+        return Ok(Step::Continue);
+      }
+
+      let mut words = line.split_whitespace();
+      let command = match words.next() {
+        Some(command) => command,
+        None => continue,
+      };
+      let arg = words.next();
+
+      match command {
+        "c" | "continue" => return Ok(Step::Continue),
+        "n" | "next" => return Ok(Step::Over),
+        "s" | "step" => return Ok(Step::Into),
+        "bt" | "backtrace" => {
+          for (index, frame) in paused.frames.iter().enumerate() {
+            writeln!(
+              self.output,
+              "  #{} {} at {}:{}",
+              index,
+              frame.name,
+              frame.module.display(),
+              frame.line
+            )?;
+          }
+        },
+        "l" | "locals" => {
+          if let Some(frame) = self.frame(paused, arg)? {
+            self.values("slot", &frame.locals)?;
+          }
+        },
+        "u" | "upvalues" => {
+          if let Some(frame) = self.frame(paused, arg)? {
+            self.values("upvalue", &frame.upvalues)?;
+          }
+        },
+        "b" | "break" => {
+          if let Some(breakpoint) = self.breakpoint(paused, arg)? {
+            let message = if breakpoints.add(breakpoint.clone()) {
+              "Breakpoint set at"
+            } else {
+              "Breakpoint already set at"
+            };
+            writeln!(self.output, "{} {}", message, breakpoint)?;
+          }
+        },
+        "d" | "delete" => {
+          if let Some(breakpoint) = self.breakpoint(paused, arg)? {
+            if breakpoints.remove(&breakpoint) {
+              writeln!(self.output, "Breakpoint removed at {}", breakpoint)?;
+            } else {
+              writeln!(self.output, "No breakpoint at {}", breakpoint)?;
+            }
+          }
+        },
+        "breakpoints" => {
+          if breakpoints.is_empty() {
+            writeln!(self.output, "No breakpoints set")?;
+          }
+          for breakpoint in breakpoints.iter() {
+            writeln!(self.output, "  {}", breakpoint)?;
+          }
+        },
+        "h" | "help" => writeln!(self.output, "{}", CLI_HELP)?,
+        command => writeln!(self.output, "Unknown command {}. Try help", command)?,
+      }
+    }
+  }
+
+  /// Resolve the frame referenced by an optional frame index
+  fn frame<'p>(
+    &mut self,
+    paused: &'p Paused,
+    arg: Option<&str>,
+  ) -> io::Result<Option<&'p FrameInfo>> {
+    let index = match arg.map(str::parse::<usize>) {
+      Some(Ok(index)) => index,
+      Some(Err(_)) => {
+        writeln!(self.output, "Expected a frame number")?;
+        return Ok(None);
+      },
+      None => 0,
+    };
+
+    match paused.frames.get(index) {
+      Some(frame) => Ok(Some(frame)),
+      None => {
+        writeln!(self.output, "No frame #{}", index)?;
+        Ok(None)
+      },
+    }
+  }
+
+  /// Resolve a breakpoint from a line in the current module or a `module:line`
+  fn breakpoint(&mut self, paused: &Paused, arg: Option<&str>) -> io::Result<Option<Breakpoint>> {
+    let arg = match arg {
+      Some(arg) => arg,
+      None => {
+        writeln!(self.output, "Expected a line or module:line")?;
+        return Ok(None);
+      },
+    };
+
+    if let Some(breakpoint) = Breakpoint::parse(arg) {
+      return Ok(Some(breakpoint));
+    }
+
+    match arg.parse::<u32>() {
+      Ok(line) => Ok(Some(Breakpoint::new(&paused.frame().module, line))),
+      Err(_) => {
+        writeln!(self.output, "Expected a line or module:line")?;
+        Ok(None)
+      },
+    }
+  }
+
+  /// Print a list of values
+  fn values(&mut self, label: &str, values: &[String]) -> io::Result<()> {
+    if values.is_empty() {
+      writeln!(self.output, "  none")?;
+    }
+
+    for (index, value) in values.iter().enumerate() {
+      writeln!(self.output, "  {} {}: {}", label, index, value)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl Debugger for CliDebugger {
+  fn start(&mut self, breakpoints: &mut Breakpoints) -> Step {
+    if !breakpoints.is_empty() {
+      return Step::Continue;
+    }
+
+    Step::Into
+  }
+
+  fn paused(&mut self, paused: &Paused, breakpoints: &mut Breakpoints) -> Step {
+    if self.detached {
+      return Step::Continue;
+    }
+
+    let frame = paused.frame();
+    let reason = match paused.reason {
+      PauseReason::Breakpoint => "Breakpoint hit",
+      PauseReason::Step => "Paused",
+    };
+
+    let step = writeln!(
+      self.output,
+      "{} at {}:{} in {}",
+      reason,
+      frame.module.display(),
+      frame.line,
+      frame.name
+    )
+    .and_then(|()| self.prompt(paused, breakpoints));
+
+    // the script runs to completion if the debugger can no longer interact
+    step.unwrap_or_else(|_| {
+      self.detached = true;
+      Step::Continue
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::{cell::RefCell, io::Cursor, rc::Rc};
+
+  #[derive(Clone, Default)]
+  struct Output(Rc<RefCell<Vec<u8>>>);
+
+  impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  fn paused() -> Paused {
+    Paused {
+      reason: PauseReason::Breakpoint,
+      frames: vec![
+        FrameInfo {
+          name: "inner".to_string(),
+          module: PathBuf::from("/src/main.ly"),
+          line: 3,
+          locals: vec!["<fn inner>".to_string(), "10".to_string()],
+          upvalues: vec!["'captured'".to_string()],
+        },
+        FrameInfo {
+          name: "script".to_string(),
+          module: PathBuf::from("/src/main.ly"),
+          line: 7,
+          locals: vec![],
+          upvalues: vec![],
+        },
+      ],
+    }
+  }
+
+  #[test]
+  fn breakpoint_matches() {
+    let breakpoint = Breakpoint::new("main.ly", 3);
+    assert!(breakpoint.matches(Path::new("/src/main.ly"), 3));
+    assert!(!breakpoint.matches(Path::new("/src/main.ly"), 4));
+    assert!(!breakpoint.matches(Path::new("/src/other_main.ly"), 3));
+
+    let mut breakpoints = Breakpoints::default();
+    assert!(breakpoints.add(breakpoint.clone()));
+    assert!(!breakpoints.add(breakpoint.clone()));
+    assert!(breakpoints.matches(Path::new("/src/main.ly"), 3));
+    assert!(breakpoints.remove(&breakpoint));
+    assert!(breakpoints.is_empty());
+  }
+
+  #[test]
+  fn cli_commands() {
+    let input = "bt\nlocals\nupvalues 1\nb 9\nbreakpoints\nlocals x\nfoo\nnext\n";
+    let output = Output::default();
+    let mut debugger = CliDebugger::new(
+      Box::new(Cursor::new(input.as_bytes().to_vec())),
+      Box::new(output.clone()),
+    );
+
+    let mut breakpoints = Breakpoints::default();
+    assert_eq!(debugger.start(&mut breakpoints), Step::Into);
+    assert_eq!(debugger.paused(&paused(), &mut breakpoints), Step::Over);
+    assert!(breakpoints.matches(Path::new("/src/main.ly"), 9));
+
+    let output = String::from_utf8(output.0.borrow().clone()).expect("Output was not utf8");
+    assert_eq!(
+      output.replace("(debug) ", ""),
+      [
+        "Breakpoint hit at /src/main.ly:3 in inner",
+        "  #0 inner at /src/main.ly:3",
+        "  #1 script at /src/main.ly:7",
+        "  slot 0: <fn inner>",
+        "  slot 1: 10",
+        "  none",
+        "Breakpoint set at /src/main.ly:9",
+        "  /src/main.ly:9",
+        "Expected a frame number",
+        "Unknown command foo. Try help",
+        "",
+      ]
+      .join("\n")
+    );
+  }
+
+  #[test]
+  fn cli_detaches_at_end_of_input() {
+    let mut debugger = CliDebugger::new(Box::new(Cursor::new(vec![])), Box::new(io::sink()));
+
+    let mut breakpoints = Breakpoints::default();
+    assert_eq!(debugger.paused(&paused(), &mut breakpoints), Step::Continue);
+    assert!(debugger.detached);
+    assert_eq!(debugger.paused(&paused(), &mut breakpoints), Step::Continue);
+  }
+}
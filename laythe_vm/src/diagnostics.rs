@@ -0,0 +1,113 @@
+use crate::source::{VmFileId, VmFiles};
+use codespan_reporting::{
+  diagnostic::{Diagnostic, Label},
+  files::{self, Files},
+  term::{self, termcolor::WriteColor, Chars, Config},
+};
+use laythe_core::chunk::SourceSpan;
+use std::ops::Range;
+
+/// Create an error diagnostic labeling a range of the provided file
+pub fn error_at<FileId, R: Into<Range<usize>>>(
+  message: &str,
+  file_id: FileId,
+  range: R,
+) -> Diagnostic<FileId> {
+  Diagnostic::error()
+    .with_message(message)
+    .with_labels(vec![Label::primary(file_id, range)])
+}
+
+/// Create an error diagnostic for a runtime error raised at the
+/// provided source span. Returns None if the span does not fall
+/// inside the file
+pub fn runtime_error(
+  files: &VmFiles,
+  file_id: VmFileId,
+  message: &str,
+  span: SourceSpan,
+) -> Option<Diagnostic<VmFileId>> {
+  let line = (span.line as usize).checked_sub(1)?;
+  let column = (span.column as usize).checked_sub(1)?;
+
+  let line_range = files.line_range(file_id, line).ok()?;
+  let start = line_range.start + column;
+  if start > line_range.end {
+    return None;
+  }
+
+  let source_len = files.source(file_id).ok()?.len();
+  let end = (start + span.length as usize).min(source_len);
+
+  Some(error_at(message, file_id, start..end))
+}
+
+/// The configuration diagnostics are rendered with
+pub fn config() -> Config {
+  Config {
+    chars: Chars::ascii(),
+    ..Config::default()
+  }
+}
+
+/// Render the provided diagnostics to a writer
+pub fn emit<'a, F: Files<'a>>(
+  writer: &mut dyn WriteColor,
+  files: &'a F,
+  diagnostics: &[Diagnostic<F::FileId>],
+) -> Result<(), files::Error> {
+  let config = config();
+
+  for diagnostic in diagnostics {
+    term::emit(writer, &config, files, diagnostic)?;
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{compiler::Scanner, source::Source};
+  use codespan_reporting::term::termcolor::NoColor;
+  use laythe_core::memory::{Allocator, NO_GC};
+
+  fn test_files(alloc: &mut Allocator, source: &str) -> (VmFiles, VmFileId) {
+    let name = alloc.manage_str("test.lay", &NO_GC);
+    let managed_source = alloc.manage_str(source, &NO_GC);
+
+    let mut files = VmFiles::default();
+    let id = files.upsert(name, managed_source);
+    files
+      .update_line_offsets(id, Scanner::new(&Source::new(source)).line_offsets())
+      .unwrap();
+
+    (files, id)
+  }
+
+  #[test]
+  fn runtime_error_snippet() {
+    let mut alloc = Allocator::default();
+    let (files, id) = test_files(&mut alloc, "let a = 1;\nlet b = a + nil;\n");
+
+    let diagnostic = runtime_error(&files, id, "bad add", SourceSpan::new(2, 9, 7)).unwrap();
+
+    let mut writer = NoColor::new(vec![]);
+    emit(&mut writer, &files, &[diagnostic]).unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+
+    assert!(output.contains("error: bad add"));
+    assert!(output.contains("test.lay:2:9"));
+    assert!(output.contains("let b = a + nil;"));
+    assert!(output.contains("^^^^^^^"));
+  }
+
+  #[test]
+  fn runtime_error_out_of_file() {
+    let mut alloc = Allocator::default();
+    let (files, id) = test_files(&mut alloc, "let a = 1;\n");
+
+    assert!(runtime_error(&files, id, "bad", SourceSpan::new(5, 1, 1)).is_none());
+    assert!(runtime_error(&files, id, "bad", SourceSpan::default()).is_none());
+  }
+}
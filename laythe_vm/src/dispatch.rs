@@ -0,0 +1,11 @@
+/// How the vm dispatches each decoded instruction to its handler
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Dispatch {
+  /// Branch to each handler through a single `match` on the op code
+  Match,
+
+  /// Call each handler through a table of function pointers indexed by
+  /// the op code
+  #[default]
+  Table,
+}
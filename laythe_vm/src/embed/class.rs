@@ -0,0 +1,393 @@
+use super::{FromValue, IntoValue, LyError};
+use laythe_core::{
+  hooks::{GcHooks, Hooks, HostData},
+  managed::{GcObj, Trace},
+  object::{Class, Instance, LyNative, Native, NativeMeta, ObjectKind},
+  signature::{Arity, Environment, Parameter, ParameterKind, Signature},
+  val,
+  value::Value,
+  Call,
+};
+use std::io::Write;
+
+/// The arguments of a native method written as a tuple of types that
+/// can be converted from Laythe values. The tuple sets the arity of the
+/// method and the kind of each argument the vm checks for
+pub trait FromArgs: Sized {
+  /// The kind of each argument
+  fn kinds() -> Vec<ParameterKind>;
+
+  /// Convert arguments that passed the signature check
+  fn from_args(args: &[Value]) -> Result<Self, LyError>;
+}
+
+macro_rules! from_args {
+  ( $( $arg:ident ),* ) => {
+    impl<$( $arg: FromValue ),*> FromArgs for ($( $arg, )*) {
+      fn kinds() -> Vec<ParameterKind> {
+        vec![$( $arg::KIND ),*]
+      }
+
+      #[allow(unused_variables, unused_mut)]
+      fn from_args(args: &[Value]) -> Result<Self, LyError> {
+        let mut args = args.iter();
+        Ok(($( $arg::from_value(*args.next().expect("Argument count checked by signature"))?, )*))
+      }
+    }
+  };
+}
+
+from_args!();
+from_args!(A);
+from_args!(A, B);
+from_args!(A, B, C);
+from_args!(A, B, C, D);
+from_args!(A, B, C, D, E);
+
+/// The instance a native method was called on
+pub struct This<'a, 'b> {
+  instance: GcObj<Instance>,
+  hooks: &'a mut Hooks<'b>,
+}
+
+impl This<'_, '_> {
+  /// The instance as a Laythe value
+  pub fn value(&self) -> Value {
+    val!(self.instance)
+  }
+
+  /// Get the value of a field converted to a Rust value
+  pub fn get<T: FromValue>(&self, field: &str) -> Result<T, LyError> {
+    match self.instance.get_field(&self.hooks.manage_str(field)) {
+      Some(value) => T::from_value(*value),
+      None => Err(self.undefined_field(field)),
+    }
+  }
+
+  /// Set a field to a Rust value. Fields outside of the class's layout
+  /// are stored on the instance like a script assignment would
+  pub fn set<T: IntoValue>(&mut self, field: &str, value: T) -> Result<(), LyError> {
+    if self.instance.is_frozen() {
+      return Err(LyError::Runtime {
+        class: "RuntimeError".to_string(),
+        message: format!(
+          "Cannot set property {} on a frozen {} instance.",
+          field,
+          &*self.instance.class().name()
+        ),
+      });
+    }
+
+    let hooks = self.hooks.as_gc();
+    let name = hooks.manage_str(field);
+
+    let class = self.instance.class();
+    if class.is_sealed() && class.get_field_index(&name).is_none() {
+      return Err(LyError::Runtime {
+        class: "RuntimeError".to_string(),
+        message: format!(
+          "Cannot add field {} to a sealed {} instance.",
+          name,
+          class.name()
+        ),
+      });
+    }
+
+    hooks.push_root(name);
+    let value = value.into_value(&hooks);
+    hooks.push_root(value);
+
+    self.instance.set_dynamic_field(&hooks, name, value);
+    hooks.pop_roots(2);
+    Ok(())
+  }
+
+  /// Get the state the embedding application provided to the vm if it
+  /// is of type `T`
+  pub fn host_data<T: HostData>(&mut self) -> Option<&mut T> {
+    self.hooks.host_data()
+  }
+
+  fn undefined_field(&self, field: &str) -> LyError {
+    LyError::Runtime {
+      class: "RuntimeError".to_string(),
+      message: format!(
+        "Undefined field {} on class {}.",
+        field,
+        &*self.instance.class().name()
+      ),
+    }
+  }
+}
+
+type HostFun = Box<dyn Fn(&mut Hooks, Option<Value>, &[Value]) -> Result<Value, LyError> + Send>;
+
+/// A method or static method of a native class
+struct HostMethod {
+  name: String,
+  is_static: bool,
+  parameters: Vec<(String, ParameterKind)>,
+  fun: HostFun,
+}
+
+/// Build a class whose methods are Rust closures so an embedder can
+/// expose its own types to scripts. Each closure takes its arguments as a
+/// tuple of Rust types which sets the arity and the argument kinds checked
+/// before it is called. Errors returned by a closure are raised in the
+/// script as a `RuntimeError`. Closures are not traced so should not hold
+/// Laythe values
+///
+/// ```ignore
+/// let point = NativeClassBuilder::new("Point")
+///   .field("x")
+///   .field("y")
+///   .init(&["x", "y"], |this, (x, y): (f64, f64)| {
+///     this.set("x", x)?;
+///     this.set("y", y)
+///   })
+///   .method("length", &[], |this, ()| {
+///     let (x, y): (f64, f64) = (this.get("x")?, this.get("y")?);
+///     Ok((x * x + y * y).sqrt())
+///   });
+///
+/// vm.register_class(point)?;
+/// ```
+pub struct NativeClassBuilder {
+  name: String,
+  fields: Vec<String>,
+  methods: Vec<HostMethod>,
+}
+
+impl NativeClassBuilder {
+  /// Start a class with the provided name
+  pub fn new(name: &str) -> Self {
+    Self {
+      name: name.to_string(),
+      fields: vec![],
+      methods: vec![],
+    }
+  }
+
+  /// Declare a field of the class's instances
+  pub fn field(mut self, name: &str) -> Self {
+    self.fields.push(name.to_string());
+    self
+  }
+
+  /// Set the initializer called when the class is instantiated
+  pub fn init<A, F>(self, params: &[&str], init: F) -> Self
+  where
+    A: FromArgs,
+    F: Fn(&mut This, A) -> Result<(), LyError> + Send + 'static,
+  {
+    self.add_method(
+      "init",
+      params,
+      A::kinds(),
+      false,
+      move |hooks, this, args| {
+        let instance = this
+          .expect("Init called without an instance")
+          .to_obj()
+          .to_instance();
+        init(&mut This { instance, hooks }, A::from_args(args)?)?;
+
+        Ok(val!(instance))
+      },
+    )
+  }
+
+  /// Add a method called on instances of the class
+  pub fn method<A, R, F>(self, name: &str, params: &[&str], method: F) -> Self
+  where
+    A: FromArgs,
+    R: IntoValue,
+    F: Fn(&mut This, A) -> Result<R, LyError> + Send + 'static,
+  {
+    self.add_method(name, params, A::kinds(), false, move |hooks, this, args| {
+      let instance = this
+        .expect("Method called without an instance")
+        .to_obj()
+        .to_instance();
+      let result = method(&mut This { instance, hooks }, A::from_args(args)?)?;
+
+      Ok(result.into_value(&hooks.as_gc()))
+    })
+  }
+
+  /// Add a method called on the class itself
+  pub fn static_method<A, R, F>(self, name: &str, params: &[&str], method: F) -> Self
+  where
+    A: FromArgs,
+    R: IntoValue,
+    F: Fn(A) -> Result<R, LyError> + Send + 'static,
+  {
+    self.add_method(name, params, A::kinds(), true, move |hooks, _this, args| {
+      Ok(method(A::from_args(args)?)?.into_value(&hooks.as_gc()))
+    })
+  }
+
+  fn add_method<F>(
+    mut self,
+    name: &str,
+    params: &[&str],
+    kinds: Vec<ParameterKind>,
+    is_static: bool,
+    fun: F,
+  ) -> Self
+  where
+    F: Fn(&mut Hooks, Option<Value>, &[Value]) -> Result<Value, LyError> + Send + 'static,
+  {
+    assert_eq!(
+      params.len(),
+      kinds.len(),
+      "Method {} of class {} names {} parameter(s) but takes {}.",
+      name,
+      self.name,
+      params.len(),
+      kinds.len()
+    );
+
+    self.methods.push(HostMethod {
+      name: name.to_string(),
+      is_static,
+      parameters: params
+        .iter()
+        .map(|param| param.to_string())
+        .zip(kinds)
+        .collect(),
+      fun: Box::new(fun),
+    });
+    self
+  }
+
+  /// Build the class inheriting from the provided super class. Errors
+  /// returned by the closures are raised as the provided error class
+  pub(crate) fn build(
+    self,
+    hooks: &GcHooks,
+    super_class: GcObj<Class>,
+    error: GcObj<Class>,
+  ) -> GcObj<Class> {
+    let mut class = Class::with_inheritance(hooks, hooks.manage_str(&self.name), super_class);
+    hooks.push_root(class);
+
+    for field in &self.fields {
+      class.add_field(hooks, hooks.manage_str(field));
+    }
+
+    for method in self.methods {
+      let name = hooks.manage_str(&method.name);
+      let parameters: Vec<Parameter> = method
+        .parameters
+        .iter()
+        .map(|(name, kind)| Parameter::new(hooks.manage_str(name), *kind))
+        .collect();
+
+      let meta = NativeMeta {
+        name,
+        is_method: true,
+        environment: Environment::StackLess,
+        mutates: false,
+        signature: Signature {
+          arity: Arity::Fixed(parameters.len() as u8),
+          parameters: parameters.into_boxed_slice(),
+        },
+      };
+
+      let native = Box::new(HostNative {
+        error: val!(error),
+        fun: method.fun,
+      }) as Box<dyn LyNative>;
+      let native = val!(hooks.manage_obj(Native::new(meta, native)));
+
+      if method.is_static {
+        class
+          .meta_class()
+          .expect("Meta class not set.")
+          .add_method(hooks, name, native);
+      } else {
+        class.add_method(hooks, name, native);
+      }
+    }
+
+    hooks.pop_roots(1);
+    class
+  }
+}
+
+/// Build a native function that calls the provided closure with any
+/// number of arguments. Errors returned by the closure are raised in the
+/// script as the provided error class
+pub(crate) fn host_fun<F>(hooks: &GcHooks, name: &str, error: GcObj<Class>, fun: F) -> GcObj<Native>
+where
+  F: Fn(&mut Hooks, &[Value]) -> Result<Value, LyError> + Send + 'static,
+{
+  let meta = NativeMeta {
+    name: hooks.manage_str(name),
+    is_method: false,
+    environment: Environment::StackLess,
+    mutates: false,
+    signature: Signature {
+      arity: Arity::Variadic(0),
+      parameters: vec![Parameter::new(hooks.manage_str("args"), ParameterKind::Any)]
+        .into_boxed_slice(),
+    },
+  };
+
+  let native = Box::new(HostNative {
+    error: val!(error),
+    fun: Box::new(move |hooks, _this, args| fun(hooks, args)),
+  }) as Box<dyn LyNative>;
+  hooks.manage_obj(Native::new(meta, native))
+}
+
+/// A native function calling an embedder's closure
+struct HostNative {
+  error: Value,
+  fun: HostFun,
+}
+
+impl LyNative for HostNative {
+  fn call(&self, hooks: &mut Hooks, this: Option<Value>, args: &[Value]) -> Call {
+    match (self.fun)(hooks, this, args) {
+      Ok(value) => Call::Ok(value),
+      Err(error) => {
+        let message = match error {
+          LyError::Runtime { message, .. } => message,
+          error => error.to_string(),
+        };
+
+        match hooks.call(self.error, &[val!(hooks.manage_str(message))]) {
+          Call::Ok(err) => {
+            if err.is_obj_kind(ObjectKind::Instance) {
+              Call::Err(err.to_obj().to_instance())
+            } else {
+              panic!(
+                "Failed to instantiate error instance\nFound value {:?}",
+                err.kind()
+              )
+            }
+          },
+          Call::Err(err) => Call::Err(err),
+          Call::Exit(exit) => Call::Exit(exit),
+          Call::Block => Call::Block,
+        }
+      },
+    }
+  }
+}
+
+impl Trace for HostNative {
+  fn trace(&self) {
+    self.error.trace();
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.error.visit_refs(visitor);
+  }
+
+  fn trace_debug(&self, log: &mut dyn Write) {
+    self.error.trace_debug(log);
+  }
+}
@@ -0,0 +1,392 @@
+mod class;
+mod rooted;
+
+use laythe_core::{
+  hooks::GcHooks,
+  object::{List, Map, ObjectKind, Tuple},
+  signature::ParameterKind,
+  val,
+  value::{Value, VALUE_NIL},
+  worker::Message,
+};
+use std::{collections::HashMap, error::Error, fmt, hash::Hash};
+
+pub(crate) use class::host_fun;
+pub use class::{FromArgs, NativeClassBuilder, This};
+pub(crate) use rooted::HostRoots;
+pub use rooted::Rooted;
+
+/// An error raised while calling into Laythe from Rust or converting
+/// the values it returns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LyError {
+  /// The call raised an error it did not catch
+  Runtime { class: String, message: String },
+
+  /// The call exited the script with this exit code
+  Exit(u16),
+
+  /// The call ran longer than the vm's time or instruction budget
+  Timeout,
+
+  /// The call was interrupted by the host
+  Interrupted,
+
+  /// The vm stopped the call after an issue in its own state
+  Internal(String),
+
+  /// A value could not be converted to the requested Rust type
+  Conversion {
+    expected: &'static str,
+    found: &'static str,
+  },
+}
+
+impl LyError {
+  fn conversion(expected: &'static str, value: Value) -> Self {
+    LyError::Conversion {
+      expected,
+      found: value.value_type(),
+    }
+  }
+}
+
+impl fmt::Display for LyError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      LyError::Runtime { class, message } => write!(f, "{}: {}", class, message),
+      LyError::Exit(code) => write!(f, "Script exited with code {}.", code),
+      LyError::Timeout => write!(f, "Call exceeded the vm's budget."),
+      LyError::Interrupted => write!(f, "Call was interrupted."),
+      LyError::Internal(message) => write!(f, "Internal Error: {}", message),
+      LyError::Conversion { expected, found } => {
+        write!(f, "Expected {} but found {}.", expected, found)
+      },
+    }
+  }
+}
+
+impl Error for LyError {}
+
+/// Convert a Rust value into a Laythe value. Values that allocate are only
+/// guaranteed to live until the vm next runs unless they are passed to it
+pub trait IntoValue {
+  fn into_value(self, hooks: &GcHooks) -> Value;
+}
+
+/// Convert a Laythe value into a Rust value
+pub trait FromValue: Sized {
+  /// The kind of argument a native method checks for before converting
+  const KIND: ParameterKind = ParameterKind::Any;
+
+  fn from_value(value: Value) -> Result<Self, LyError>;
+}
+
+impl IntoValue for Value {
+  fn into_value(self, _hooks: &GcHooks) -> Value {
+    self
+  }
+}
+
+impl IntoValue for () {
+  fn into_value(self, _hooks: &GcHooks) -> Value {
+    VALUE_NIL
+  }
+}
+
+impl IntoValue for bool {
+  fn into_value(self, _hooks: &GcHooks) -> Value {
+    val!(self)
+  }
+}
+
+impl IntoValue for f64 {
+  fn into_value(self, _hooks: &GcHooks) -> Value {
+    val!(self)
+  }
+}
+
+impl IntoValue for i64 {
+  fn into_value(self, _hooks: &GcHooks) -> Value {
+    val!(self as f64)
+  }
+}
+
+impl IntoValue for &str {
+  fn into_value(self, hooks: &GcHooks) -> Value {
+    val!(hooks.manage_str(self))
+  }
+}
+
+impl IntoValue for String {
+  fn into_value(self, hooks: &GcHooks) -> Value {
+    val!(hooks.manage_str(self))
+  }
+}
+
+impl IntoValue for Message {
+  fn into_value(self, hooks: &GcHooks) -> Value {
+    self.to_value(hooks)
+  }
+}
+
+impl<T: IntoValue> IntoValue for Option<T> {
+  fn into_value(self, hooks: &GcHooks) -> Value {
+    match self {
+      Some(value) => value.into_value(hooks),
+      None => VALUE_NIL,
+    }
+  }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+  fn into_value(self, hooks: &GcHooks) -> Value {
+    let mut list = hooks.manage_obj(List::with_capacity(self.len()));
+    hooks.push_root(list);
+
+    for item in self {
+      let item = item.into_value(hooks);
+      hooks.grow(&mut *list, |list| list.push(item));
+    }
+
+    hooks.pop_roots(1);
+    val!(list)
+  }
+}
+
+impl<K: IntoValue, V: IntoValue> IntoValue for HashMap<K, V> {
+  fn into_value(self, hooks: &GcHooks) -> Value {
+    let mut map = hooks.manage_obj(Map::with_capacity(self.len()));
+    hooks.push_root(map);
+
+    for (key, value) in self {
+      let key = key.into_value(hooks);
+      hooks.push_root(key);
+      let value = value.into_value(hooks);
+      hooks.grow(&mut *map, |map| map.insert(key, value));
+      hooks.pop_roots(1);
+    }
+
+    hooks.pop_roots(1);
+    val!(map)
+  }
+}
+
+impl FromValue for Value {
+  fn from_value(value: Value) -> Result<Self, LyError> {
+    Ok(value)
+  }
+}
+
+impl FromValue for () {
+  const KIND: ParameterKind = ParameterKind::Nil;
+
+  fn from_value(value: Value) -> Result<Self, LyError> {
+    if value.is_nil() {
+      Ok(())
+    } else {
+      Err(LyError::conversion("nil", value))
+    }
+  }
+}
+
+impl FromValue for bool {
+  const KIND: ParameterKind = ParameterKind::Bool;
+
+  fn from_value(value: Value) -> Result<Self, LyError> {
+    if value.is_bool() {
+      Ok(value.to_bool())
+    } else {
+      Err(LyError::conversion("bool", value))
+    }
+  }
+}
+
+impl FromValue for f64 {
+  const KIND: ParameterKind = ParameterKind::Number;
+
+  fn from_value(value: Value) -> Result<Self, LyError> {
+    if value.is_num() {
+      Ok(value.to_num())
+    } else {
+      Err(LyError::conversion("number", value))
+    }
+  }
+}
+
+impl FromValue for i64 {
+  const KIND: ParameterKind = ParameterKind::Number;
+
+  fn from_value(value: Value) -> Result<Self, LyError> {
+    let num = f64::from_value(value)?;
+
+    if num.fract() == 0.0 && num >= i64::MIN as f64 && num <= i64::MAX as f64 {
+      Ok(num as i64)
+    } else {
+      Err(LyError::Conversion {
+        expected: "integer",
+        found: "number",
+      })
+    }
+  }
+}
+
+impl FromValue for String {
+  const KIND: ParameterKind = ParameterKind::String;
+
+  fn from_value(value: Value) -> Result<Self, LyError> {
+    if value.is_obj_kind(ObjectKind::String) {
+      Ok(String::from(&*value.to_obj().to_str()))
+    } else {
+      Err(LyError::conversion("string", value))
+    }
+  }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+  fn from_value(value: Value) -> Result<Self, LyError> {
+    if value.is_nil() {
+      Ok(None)
+    } else {
+      T::from_value(value).map(Some)
+    }
+  }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+  const KIND: ParameterKind = ParameterKind::List;
+
+  fn from_value(value: Value) -> Result<Self, LyError> {
+    if value.is_obj_kind(ObjectKind::List) {
+      value
+        .to_obj()
+        .to_list()
+        .iter()
+        .map(|item| T::from_value(*item))
+        .collect()
+    } else {
+      Err(LyError::conversion("list", value))
+    }
+  }
+}
+
+impl<K: FromValue + Eq + Hash, V: FromValue> FromValue for HashMap<K, V> {
+  const KIND: ParameterKind = ParameterKind::Map;
+
+  fn from_value(value: Value) -> Result<Self, LyError> {
+    if value.is_obj_kind(ObjectKind::Map) {
+      value
+        .to_obj()
+        .to_map()
+        .iter()
+        .map(|(key, value)| Ok((K::from_value(*key)?, V::from_value(*value)?)))
+        .collect()
+    } else {
+      Err(LyError::conversion("map", value))
+    }
+  }
+}
+
+/// Convert Rust tuples to and from Laythe tuples so a host function can
+/// return several values that a script destructures with `let (a, b) = ...`
+macro_rules! tuple_value {
+  ( $len:literal; $( $name:ident $index:tt ),+ ) => {
+    impl<$($name: IntoValue),+> IntoValue for ($($name,)+) {
+      fn into_value(self, hooks: &GcHooks) -> Value {
+        let items = [$({
+          let item = self.$index.into_value(hooks);
+          hooks.push_root(item);
+          item
+        }),+];
+
+        let tuple = hooks.manage_obj(Tuple::new(&items));
+        hooks.pop_roots($len);
+        val!(tuple)
+      }
+    }
+
+    impl<$($name: FromValue),+> FromValue for ($($name,)+) {
+      const KIND: ParameterKind = ParameterKind::Tuple;
+
+      fn from_value(value: Value) -> Result<Self, LyError> {
+        if value.is_obj_kind(ObjectKind::Tuple) {
+          let tuple = value.to_obj().to_tuple();
+          if tuple.len() == $len {
+            return Ok(($($name::from_value(tuple[$index])?,)+));
+          }
+        }
+
+        Err(LyError::conversion(concat!("tuple of length ", $len), value))
+      }
+    }
+  };
+}
+
+tuple_value!(2; A 0, B 1);
+tuple_value!(3; A 0, B 1, C 2);
+tuple_value!(4; A 0, B 1, C 2, D 3);
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use laythe_core::hooks::NoContext;
+
+  #[test]
+  fn round_trip() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let value = vec![1.5, 2.0].into_value(&hooks);
+    assert_eq!(Vec::<f64>::from_value(value), Ok(vec![1.5, 2.0]));
+
+    let value = "laythe".into_value(&hooks);
+    assert_eq!(String::from_value(value), Ok("laythe".to_string()));
+
+    let mut scores = HashMap::new();
+    scores.insert("a".to_string(), 3_i64);
+    let value = scores.clone().into_value(&hooks);
+    assert_eq!(HashMap::<String, i64>::from_value(value), Ok(scores));
+
+    let value = Some(true).into_value(&hooks);
+    assert_eq!(Option::<bool>::from_value(value), Ok(Some(true)));
+    assert_eq!(Option::<bool>::from_value(VALUE_NIL), Ok(None));
+
+    let value = (3_i64, "rest".to_string()).into_value(&hooks);
+    assert!(value.is_obj_kind(ObjectKind::Tuple));
+    assert_eq!(
+      <(i64, String)>::from_value(value),
+      Ok((3, "rest".to_string()))
+    );
+  }
+
+  #[test]
+  fn conversion_error() {
+    let context = NoContext::default();
+    let hooks = GcHooks::new(&context);
+
+    let value = "laythe".into_value(&hooks);
+    assert_eq!(
+      f64::from_value(value),
+      Err(LyError::Conversion {
+        expected: "number",
+        found: "string",
+      })
+    );
+    assert_eq!(
+      i64::from_value(val!(1.5)),
+      Err(LyError::Conversion {
+        expected: "integer",
+        found: "number",
+      })
+    );
+
+    let value = (1.0, 2.0, 3.0).into_value(&hooks);
+    assert_eq!(
+      <(f64, f64)>::from_value(value),
+      Err(LyError::Conversion {
+        expected: "tuple of length 2",
+        found: "tuple",
+      })
+    );
+  }
+}
@@ -0,0 +1,131 @@
+use super::{FromValue, LyError};
+use laythe_core::{managed::Trace, value::Value};
+use std::{cell::RefCell, fmt, io::Write, rc::Rc};
+
+/// The values the host holds through `Rooted` handles. Each handle owns a
+/// slot that is freed for reuse when the handle is dropped
+#[derive(Default)]
+pub(crate) struct HostRoots {
+  slots: Vec<Option<Value>>,
+  free: Vec<usize>,
+}
+
+impl HostRoots {
+  /// Hold a value returning its slot
+  fn insert(&mut self, value: Value) -> usize {
+    match self.free.pop() {
+      Some(slot) => {
+        self.slots[slot] = Some(value);
+        slot
+      },
+      None => {
+        self.slots.push(Some(value));
+        self.slots.len() - 1
+      },
+    }
+  }
+
+  /// Release the value held in a slot
+  fn remove(&mut self, slot: usize) {
+    self.slots[slot] = None;
+    self.free.push(slot);
+  }
+
+  /// The values currently held
+  fn values(&self) -> impl Iterator<Item = &Value> {
+    self.slots.iter().flatten()
+  }
+}
+
+impl Trace for HostRoots {
+  fn trace(&self) {
+    self.values().for_each(|value| value.trace());
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.values().for_each(|value| value.visit_refs(visitor));
+  }
+
+  fn trace_debug(&self, log: &mut dyn Write) {
+    self.values().for_each(|value| value.trace_debug(log));
+  }
+}
+
+/// A Laythe value held by the host. The vm will not collect the value
+/// while a handle to it is alive, so it can be kept across calls
+pub struct Rooted {
+  value: Value,
+  slot: usize,
+  roots: Rc<RefCell<HostRoots>>,
+}
+
+impl Rooted {
+  /// Root a value in the provided set of host roots
+  pub(crate) fn new(roots: &Rc<RefCell<HostRoots>>, value: Value) -> Self {
+    let slot = roots.borrow_mut().insert(value);
+
+    Self {
+      value,
+      slot,
+      roots: Rc::clone(roots),
+    }
+  }
+
+  /// The rooted value. The value is only guaranteed to live as long as
+  /// this handle
+  pub fn value(&self) -> Value {
+    self.value
+  }
+
+  /// Convert the rooted value into a Rust value
+  pub fn get<T: FromValue>(&self) -> Result<T, LyError> {
+    T::from_value(self.value)
+  }
+}
+
+impl Clone for Rooted {
+  fn clone(&self) -> Self {
+    Self::new(&self.roots, self.value)
+  }
+}
+
+impl Drop for Rooted {
+  fn drop(&mut self) {
+    self.roots.borrow_mut().remove(self.slot);
+  }
+}
+
+impl PartialEq for Rooted {
+  fn eq(&self, other: &Self) -> bool {
+    self.value == other.value
+  }
+}
+
+impl fmt::Debug for Rooted {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("Rooted").field(&self.value).finish()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use laythe_core::val;
+
+  #[test]
+  fn slots_are_reused() {
+    let roots = Rc::new(RefCell::new(HostRoots::default()));
+
+    let first = Rooted::new(&roots, val!(1.0));
+    let second = first.clone();
+    assert_eq!(roots.borrow().values().count(), 2);
+
+    drop(first);
+    assert_eq!(roots.borrow().values().count(), 1);
+
+    let third = Rooted::new(&roots, val!(3.0));
+    assert_eq!(third.slot, 0);
+    assert_eq!(second.get::<f64>(), Ok(1.0));
+    assert_eq!(roots.borrow().slots.len(), 2);
+  }
+}
@@ -0,0 +1,102 @@
+use crate::source::VmFileId;
+use codespan_reporting::diagnostic::Diagnostic;
+use std::{error::Error, fmt, io};
+
+/// An error that stopped the vm from running a script to completion
+#[derive(Debug, Clone, PartialEq)]
+pub enum VmError {
+  /// The script failed to parse or compile
+  Compile(Vec<Diagnostic<VmFileId>>),
+
+  /// The script raised an error it did not catch
+  Runtime(Box<UncaughtError>),
+
+  /// The script or one of its packages could not be loaded
+  Load(String),
+
+  /// Reading or writing the vm's io failed
+  Io {
+    kind: io::ErrorKind,
+    message: String,
+  },
+
+  /// The script ran longer than the vm's time or instruction budget
+  Timeout,
+
+  /// The script was interrupted by the host, along with where it stopped
+  Interrupted(Option<Box<UncaughtError>>),
+
+  /// The vm could not produce the requested output
+  Internal(String),
+
+  /// Every fiber still running is waiting for input from the host. The
+  /// run continues with `Vm::resume` once input is available
+  AwaitingInput,
+}
+
+impl VmError {
+  /// The process exit code the cli uses for this error
+  pub fn exit_code(&self) -> u16 {
+    match self {
+      VmError::Compile(_) => 2,
+      VmError::Runtime(_) | VmError::Load(_) => 3,
+      VmError::Io { .. } | VmError::Internal(_) | VmError::AwaitingInput => 4,
+      VmError::Timeout => 5,
+      VmError::Interrupted(_) => 130,
+    }
+  }
+}
+
+impl From<io::Error> for VmError {
+  fn from(error: io::Error) -> Self {
+    VmError::Io {
+      kind: error.kind(),
+      message: error.to_string(),
+    }
+  }
+}
+
+impl fmt::Display for VmError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      VmError::Compile(errors) => {
+        write!(f, "Script failed with {} compile error(s).", errors.len())
+      },
+      VmError::Runtime(error) => write!(f, "{}", error),
+      VmError::Load(message) => write!(f, "{}", message),
+      VmError::Io { message, .. } => write!(f, "{}", message),
+      VmError::Timeout => write!(f, "Script exceeded the vm's budget."),
+      VmError::Interrupted(_) => write!(f, "Script was interrupted."),
+      VmError::Internal(message) => write!(f, "Internal Error: {}", message),
+      VmError::AwaitingInput => write!(f, "Script is waiting for input."),
+    }
+  }
+}
+
+impl Error for VmError {}
+
+/// An error object a script raised without catching it, captured
+/// when the stack was unwound
+#[derive(Debug, Clone, PartialEq)]
+pub struct UncaughtError {
+  /// The name of the error's class
+  pub class: String,
+
+  /// The error's message
+  pub message: String,
+
+  /// The offending source if the error was raised in a loaded file
+  pub diagnostic: Option<Diagnostic<VmFileId>>,
+
+  /// The call stack at the point the error was raised, innermost frame first
+  pub stack: String,
+
+  /// The error this error was raised from, if any
+  pub cause: Option<Box<UncaughtError>>,
+}
+
+impl fmt::Display for UncaughtError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.class, self.message)
+  }
+}
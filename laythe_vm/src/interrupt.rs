@@ -0,0 +1,65 @@
+use std::sync::{
+  atomic::{AtomicBool, Ordering},
+  Arc,
+};
+
+/// A handle used to interrupt a running vm from the host, another thread
+/// or a signal handler. The vm observes an interrupt on loop back edges
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt {
+  /// Has an interrupt been requested
+  requested: Arc<AtomicBool>,
+}
+
+impl Interrupt {
+  /// Create a new interrupt handle
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Request the vm stop at the next loop back edge. This only stores
+  /// to an atomic so it is safe to call from a signal handler
+  pub fn interrupt(&self) {
+    self.requested.store(true, Ordering::SeqCst);
+  }
+
+  /// Has an interrupt been requested
+  #[inline]
+  pub fn is_interrupted(&self) -> bool {
+    self.requested.load(Ordering::Relaxed)
+  }
+
+  /// Clear any pending interrupt
+  pub fn clear(&self) {
+    self.requested.store(false, Ordering::SeqCst);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn interrupt() {
+    let interrupt = Interrupt::new();
+    assert!(!interrupt.is_interrupted());
+
+    interrupt.interrupt();
+    assert!(interrupt.is_interrupted());
+
+    interrupt.clear();
+    assert!(!interrupt.is_interrupted());
+  }
+
+  #[test]
+  fn shared() {
+    let interrupt = Interrupt::new();
+    let handle = interrupt.clone();
+
+    std::thread::spawn(move || handle.interrupt())
+      .join()
+      .unwrap();
+
+    assert!(interrupt.is_interrupted());
+  }
+}
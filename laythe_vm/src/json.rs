@@ -0,0 +1,492 @@
+use std::fmt;
+
+/// The deepest nesting of arrays and objects a document may have
+const MAX_DEPTH: usize = 128;
+
+/// A minimal json value used to hand the ast and compiled chunks
+/// to external tooling and to speak the debug adapter protocol
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+  Null,
+  Bool(bool),
+  Number(f64),
+  String(String),
+  Array(Vec<Json>),
+  Object(Vec<(String, Json)>),
+}
+
+impl Json {
+  /// Create a json object from its fields
+  pub fn object(fields: Vec<(&str, Json)>) -> Self {
+    Json::Object(
+      fields
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect(),
+    )
+  }
+
+  /// Parse a json document
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_vm::json::Json;
+  ///
+  /// let json = Json::parse(r#"{"line": 3, "names": ["a", "b"]}"#).unwrap();
+  /// assert_eq!(json.get("line").and_then(Json::as_f64), Some(3.0));
+  /// assert!(Json::parse("[1,").is_err());
+  /// ```
+  pub fn parse(source: &str) -> Result<Json, String> {
+    let mut parser = JsonParser {
+      source: source.as_bytes(),
+      offset: 0,
+      depth: 0,
+    };
+
+    let json = parser.value()?;
+    parser.whitespace();
+    if parser.offset != parser.source.len() {
+      return Err(parser.error("Unexpected trailing characters"));
+    }
+
+    Ok(json)
+  }
+
+  /// Create a json array by converting each item
+  pub fn array<T: Into<Json>, I: IntoIterator<Item = T>>(items: I) -> Self {
+    Json::Array(items.into_iter().map(Into::into).collect())
+  }
+
+  /// Retrieve a field from a json object
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_vm::json::Json;
+  ///
+  /// let json = Json::object(vec![("kind", "Nil".into())]);
+  /// assert_eq!(json.get("kind"), Some(&Json::from("Nil")));
+  /// assert_eq!(json.get("span"), None);
+  /// ```
+  pub fn get(&self, key: &str) -> Option<&Json> {
+    match self {
+      Json::Object(fields) => fields
+        .iter()
+        .find(|(field, _)| field == key)
+        .map(|(_, value)| value),
+      _ => None,
+    }
+  }
+
+  /// This value as a string if it is one
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Json::String(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  /// This value as a number if it is one
+  pub fn as_f64(&self) -> Option<f64> {
+    match self {
+      Json::Number(value) => Some(*value),
+      _ => None,
+    }
+  }
+
+  /// This value as a bool if it is one
+  pub fn as_bool(&self) -> Option<bool> {
+    match self {
+      Json::Bool(value) => Some(*value),
+      _ => None,
+    }
+  }
+
+  /// This value's items if it is an array
+  pub fn as_array(&self) -> Option<&[Json]> {
+    match self {
+      Json::Array(items) => Some(items),
+      _ => None,
+    }
+  }
+}
+
+impl From<bool> for Json {
+  fn from(value: bool) -> Self {
+    Json::Bool(value)
+  }
+}
+
+impl From<f64> for Json {
+  fn from(value: f64) -> Self {
+    Json::Number(value)
+  }
+}
+
+impl From<u8> for Json {
+  fn from(value: u8) -> Self {
+    Json::Number(value as f64)
+  }
+}
+
+impl From<u16> for Json {
+  fn from(value: u16) -> Self {
+    Json::Number(value as f64)
+  }
+}
+
+impl From<u32> for Json {
+  fn from(value: u32) -> Self {
+    Json::Number(value as f64)
+  }
+}
+
+impl From<usize> for Json {
+  fn from(value: usize) -> Self {
+    Json::Number(value as f64)
+  }
+}
+
+impl From<&str> for Json {
+  fn from(value: &str) -> Self {
+    Json::String(value.to_string())
+  }
+}
+
+impl From<String> for Json {
+  fn from(value: String) -> Self {
+    Json::String(value)
+  }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+  fn from(value: Option<T>) -> Self {
+    value.map_or(Json::Null, Into::into)
+  }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+  fn from(value: Vec<T>) -> Self {
+    Json::array(value)
+  }
+}
+
+impl fmt::Display for Json {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Json::Null => write!(f, "null"),
+      Json::Bool(value) => write!(f, "{}", value),
+      Json::Number(value) if value.is_finite() => write!(f, "{}", value),
+      Json::Number(_) => write!(f, "null"),
+      Json::String(value) => write_str(f, value),
+      Json::Array(items) => {
+        write!(f, "[")?;
+        for (index, item) in items.iter().enumerate() {
+          if index > 0 {
+            write!(f, ",")?;
+          }
+          write!(f, "{}", item)?;
+        }
+        write!(f, "]")
+      },
+      Json::Object(fields) => {
+        write!(f, "{{")?;
+        for (index, (key, value)) in fields.iter().enumerate() {
+          if index > 0 {
+            write!(f, ",")?;
+          }
+          write_str(f, key)?;
+          write!(f, ":{}", value)?;
+        }
+        write!(f, "}}")
+      },
+    }
+  }
+}
+
+/// A recursive descent parser over a json document
+struct JsonParser<'a> {
+  source: &'a [u8],
+  offset: usize,
+
+  /// How many arrays and objects the parser is currently inside
+  depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+  fn error(&self, message: &str) -> String {
+    format!("{} at offset {}", message, self.offset)
+  }
+
+  fn whitespace(&mut self) {
+    while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.source.get(self.offset) {
+      self.offset += 1;
+    }
+  }
+
+  fn peek(&mut self) -> Option<u8> {
+    self.whitespace();
+    self.source.get(self.offset).copied()
+  }
+
+  fn expect(&mut self, byte: u8) -> Result<(), String> {
+    if self.peek() == Some(byte) {
+      self.offset += 1;
+      Ok(())
+    } else {
+      Err(self.error(&format!("Expected '{}'", byte as char)))
+    }
+  }
+
+  fn literal(&mut self, literal: &str, value: Json) -> Result<Json, String> {
+    if self.source[self.offset..].starts_with(literal.as_bytes()) {
+      self.offset += literal.len();
+      Ok(value)
+    } else {
+      Err(self.error("Unexpected literal"))
+    }
+  }
+
+  fn value(&mut self) -> Result<Json, String> {
+    match self.peek() {
+      Some(b'{') => self.nested(Self::object),
+      Some(b'[') => self.nested(Self::array),
+      Some(b'"') => self.string().map(Json::String),
+      Some(b't') => self.literal("true", Json::Bool(true)),
+      Some(b'f') => self.literal("false", Json::Bool(false)),
+      Some(b'n') => self.literal("null", Json::Null),
+      Some(b'-' | b'0'..=b'9') => self.number(),
+      Some(_) => Err(self.error("Unexpected character")),
+      None => Err(self.error("Unexpected end of input")),
+    }
+  }
+
+  /// Parse an array or object, failing rather than overflowing the
+  /// stack on deeply nested documents
+  fn nested(&mut self, parse: fn(&mut Self) -> Result<Json, String>) -> Result<Json, String> {
+    if self.depth == MAX_DEPTH {
+      return Err(self.error("Exceeded the maximum nesting depth"));
+    }
+
+    self.depth += 1;
+    let result = parse(self);
+    self.depth -= 1;
+    result
+  }
+
+  fn object(&mut self) -> Result<Json, String> {
+    self.expect(b'{')?;
+    let mut fields = vec![];
+
+    if self.peek() == Some(b'}') {
+      self.offset += 1;
+      return Ok(Json::Object(fields));
+    }
+
+    loop {
+      if self.peek() != Some(b'"') {
+        return Err(self.error("Expected object key"));
+      }
+      let key = self.string()?;
+      self.expect(b':')?;
+      fields.push((key, self.value()?));
+
+      match self.peek() {
+        Some(b',') => self.offset += 1,
+        Some(b'}') => {
+          self.offset += 1;
+          return Ok(Json::Object(fields));
+        },
+        _ => return Err(self.error("Expected ',' or '}'")),
+      }
+    }
+  }
+
+  fn array(&mut self) -> Result<Json, String> {
+    self.expect(b'[')?;
+    let mut items = vec![];
+
+    if self.peek() == Some(b']') {
+      self.offset += 1;
+      return Ok(Json::Array(items));
+    }
+
+    loop {
+      items.push(self.value()?);
+
+      match self.peek() {
+        Some(b',') => self.offset += 1,
+        Some(b']') => {
+          self.offset += 1;
+          return Ok(Json::Array(items));
+        },
+        _ => return Err(self.error("Expected ',' or ']'")),
+      }
+    }
+  }
+
+  fn number(&mut self) -> Result<Json, String> {
+    let start = self.offset;
+    while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.source.get(self.offset) {
+      self.offset += 1;
+    }
+
+    std::str::from_utf8(&self.source[start..self.offset])
+      .ok()
+      .and_then(|number| number.parse::<f64>().ok())
+      .map(Json::Number)
+      .ok_or_else(|| self.error("Invalid number"))
+  }
+
+  fn string(&mut self) -> Result<String, String> {
+    self.expect(b'"')?;
+    let mut bytes = vec![];
+
+    loop {
+      let byte = match self.source.get(self.offset) {
+        Some(byte) => *byte,
+        None => return Err(self.error("Unterminated string")),
+      };
+      self.offset += 1;
+
+      match byte {
+        b'"' => break,
+        b'\\' => {
+          let escape = self.source.get(self.offset).copied();
+          self.offset += 1;
+
+          let c = match escape {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => self.unicode()?,
+            _ => return Err(self.error("Invalid escape")),
+          };
+
+          let mut buffer = [0; 4];
+          bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+        },
+        byte => bytes.push(byte),
+      }
+    }
+
+    String::from_utf8(bytes).map_err(|_| self.error("Invalid utf8 in string"))
+  }
+
+  /// Decode the hex digits of a unicode escape including surrogate pairs
+  fn unicode(&mut self) -> Result<char, String> {
+    let high = self.hex()?;
+    if !(0xd800..0xdc00).contains(&high) {
+      return std::char::from_u32(high).ok_or_else(|| self.error("Invalid unicode escape"));
+    }
+
+    if !self.source[self.offset..].starts_with(b"\\u") {
+      return Err(self.error("Expected low surrogate"));
+    }
+    self.offset += 2;
+
+    let low = self.hex()?;
+    let code = 0x10000 + ((high - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+    std::char::from_u32(code).ok_or_else(|| self.error("Invalid unicode escape"))
+  }
+
+  fn hex(&mut self) -> Result<u32, String> {
+    let digits = self
+      .source
+      .get(self.offset..self.offset + 4)
+      .and_then(|digits| std::str::from_utf8(digits).ok())
+      .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+      .ok_or_else(|| self.error("Invalid unicode escape"))?;
+
+    self.offset += 4;
+    Ok(digits)
+  }
+}
+
+/// Write a string escaping any characters json requires
+fn write_str(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+  write!(f, "\"")?;
+  for c in value.chars() {
+    match c {
+      '"' => write!(f, "\\\"")?,
+      '\\' => write!(f, "\\\\")?,
+      '\n' => write!(f, "\\n")?,
+      '\r' => write!(f, "\\r")?,
+      '\t' => write!(f, "\\t")?,
+      c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+      c => write!(f, "{}", c)?,
+    }
+  }
+  write!(f, "\"")
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn display() {
+    let json = Json::object(vec![
+      ("null", Json::Null),
+      ("bool", true.into()),
+      ("number", 1.5.into()),
+      ("integer", 3u32.into()),
+      ("nan", f64::NAN.into()),
+      ("string", "a \"quote\"\n\\".into()),
+      ("control", "\u{1}".into()),
+      ("array", vec![1u8, 2u8].into()),
+      ("empty", Json::object(vec![])),
+    ]);
+
+    assert_eq!(
+      json.to_string(),
+      r#"{"null":null,"bool":true,"number":1.5,"integer":3,"nan":null,"string":"a \"quote\"\n\\","control":"\u0001","array":[1,2],"empty":{}}"#
+    );
+  }
+
+  #[test]
+  fn parse() {
+    let json = Json::parse(
+      r#" { "a": [1, -2.5e1, true, false, null], "b": {"c": "d\"\n\u00e9\ud83d\ude00"}, "e": {} } "#,
+    )
+    .expect("Expected json to parse");
+
+    assert_eq!(
+      json,
+      Json::object(vec![
+        (
+          "a",
+          Json::Array(vec![
+            1u8.into(),
+            (-25.0).into(),
+            true.into(),
+            false.into(),
+            Json::Null
+          ])
+        ),
+        (
+          "b",
+          Json::object(vec![("c", "d\"\n\u{e9}\u{1f600}".into())])
+        ),
+        ("e", Json::object(vec![])),
+      ])
+    );
+
+    for invalid in ["", "[1,]", "{\"a\" 1}", "\"abc", "tru", "{} {}", "\"\\x\""].iter() {
+      assert!(Json::parse(invalid).is_err(), "{}", invalid);
+    }
+  }
+
+  #[test]
+  fn nesting() {
+    let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+    assert!(Json::parse(&nested(MAX_DEPTH)).is_ok());
+    assert!(Json::parse(&nested(MAX_DEPTH + 1)).is_err());
+    assert!(Json::parse(&"{\"a\":".repeat(100_000)).is_err());
+  }
+}
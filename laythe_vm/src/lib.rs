@@ -1,20 +1,47 @@
 #![deny(clippy::all)]
 
+pub mod arena;
 pub mod ast;
+pub mod ast_json;
+mod budget;
 mod byte_code;
 mod cache;
+mod chunk_cache;
 pub mod compiler;
 mod constants;
+pub mod dap;
+mod debug;
+pub mod debugger;
+pub mod diagnostics;
+#[cfg(feature = "threaded_dispatch")]
+mod dispatch;
+pub mod embed;
+mod error;
+mod interrupt;
+pub mod json;
+pub mod lsp;
+pub mod manifest;
+mod profiler;
+pub mod protocol;
+mod plugin;
+mod pool;
+mod scheduler;
+mod signal;
+mod snapshot;
 pub mod source;
+mod stats;
+pub mod test_runner;
+mod timer;
 pub mod token;
+pub mod tracer;
 pub mod vm;
 use codespan_reporting::diagnostic::Diagnostic;
 
+pub use byte_code::dump_op_codes;
+pub use manifest::MANIFEST;
+
 #[cfg(test)]
 pub mod ast_printer;
 
-#[cfg(any(test, feature = "debug"))]
-mod debug;
-
 /// The result of a compilation
 pub type FeResult<T, F> = Result<T, Vec<Diagnostic<F>>>;
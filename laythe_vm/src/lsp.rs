@@ -0,0 +1,629 @@
+use crate::{
+  compiler::{SymbolKind, SymbolTable},
+  json::Json,
+  protocol::{read_message, write_message},
+  source::VmFileId,
+  vm::{StdMethod, Vm, VmBuilder},
+};
+use codespan_reporting::diagnostic::{Diagnostic, LabelStyle, Severity};
+use std::{
+  collections::HashMap,
+  io::{self, BufRead, Write},
+  path::PathBuf,
+};
+
+/// The json rpc error code for a method the server does not support
+const METHOD_NOT_FOUND: f64 = -32601.0;
+
+/// The client sends the full text of a document on each change
+const SYNC_FULL: u8 = 1;
+
+/// The lsp completion item kinds used by the server
+const KIND_METHOD: u8 = 2;
+const KIND_FUNCTION: u8 = 3;
+const KIND_VARIABLE: u8 = 6;
+const KIND_CLASS: u8 = 7;
+const KIND_MODULE: u8 = 9;
+const KIND_CONSTANT: u8 = 21;
+
+/// Serve the language server protocol over the provided streams until the
+/// client exits. Documents are analyzed by a vm configured from `builder`
+pub fn serve(
+  builder: VmBuilder,
+  mut input: Box<dyn BufRead>,
+  output: Box<dyn Write>,
+) -> io::Result<()> {
+  let vm = builder.build().map_err(io::Error::other)?;
+  let mut server = LanguageServer::new(vm, output);
+
+  while let Some(message) = read_message(&mut input)? {
+    if !server.handle(&message)? {
+      break;
+    }
+  }
+
+  Ok(())
+}
+
+/// An open document and the symbols of its last successful parse
+struct Document {
+  text: String,
+  symbols: SymbolTable,
+}
+
+struct LanguageServer {
+  vm: Vm,
+  output: Box<dyn Write>,
+
+  /// The open documents by uri
+  documents: HashMap<String, Document>,
+
+  /// The methods of the standard library classes offered as completions
+  std_methods: Vec<StdMethod>,
+}
+
+impl LanguageServer {
+  fn new(vm: Vm, output: Box<dyn Write>) -> Self {
+    let std_methods = vm.std_methods();
+
+    Self {
+      vm,
+      output,
+      documents: HashMap::new(),
+      std_methods,
+    }
+  }
+
+  /// Handle a single message returning false once the client exits
+  fn handle(&mut self, message: &Json) -> io::Result<bool> {
+    let id = message.get("id");
+    let params = message.get("params").cloned().unwrap_or(Json::Null);
+    let method = message.get("method").and_then(Json::as_str).unwrap_or("");
+
+    let result = match method {
+      "initialize" => Json::object(vec![
+        (
+          "capabilities",
+          Json::object(vec![
+            ("textDocumentSync", SYNC_FULL.into()),
+            ("definitionProvider", true.into()),
+            (
+              "completionProvider",
+              Json::object(vec![("triggerCharacters", vec!["."].into())]),
+            ),
+          ]),
+        ),
+        ("serverInfo", Json::object(vec![("name", "laythe".into())])),
+      ]),
+      "shutdown" => Json::Null,
+      "exit" => return Ok(false),
+      "textDocument/didOpen" => {
+        let document = params.get("textDocument");
+        let uri = document.and_then(|document| document.get("uri"));
+        let text = document.and_then(|document| document.get("text"));
+
+        if let (Some(uri), Some(text)) = (uri.and_then(Json::as_str), text.and_then(Json::as_str)) {
+          self.update(uri, text.to_string())?;
+        }
+        return Ok(true);
+      },
+      "textDocument/didChange" => {
+        let uri = text_document_uri(&params);
+        let text = params
+          .get("contentChanges")
+          .and_then(Json::as_array)
+          .and_then(|changes| changes.last())
+          .and_then(|change| change.get("text"))
+          .and_then(Json::as_str);
+
+        if let (Some(uri), Some(text)) = (uri, text) {
+          self.update(uri, text.to_string())?;
+        }
+        return Ok(true);
+      },
+      "textDocument/didClose" => {
+        if let Some(uri) = text_document_uri(&params) {
+          self.documents.remove(uri);
+          self.publish(uri, vec![])?;
+        }
+        return Ok(true);
+      },
+      "textDocument/definition" => self.definition(&params),
+      "textDocument/completion" => self.completion(&params),
+      method => {
+        // notifications the server does not handle are ignored
+        if let Some(id) = id {
+          let error = Json::object(vec![
+            ("code", METHOD_NOT_FOUND.into()),
+            ("message", format!("Unsupported method {}", method).into()),
+          ]);
+          self.send(vec![("id", id.clone()), ("error", error)])?;
+        }
+        return Ok(true);
+      },
+    };
+
+    if let Some(id) = id {
+      self.send(vec![("id", id.clone()), ("result", result)])?;
+    }
+    Ok(true)
+  }
+
+  /// Send a json rpc message to the client
+  fn send(&mut self, fields: Vec<(&str, Json)>) -> io::Result<()> {
+    let mut message = vec![("jsonrpc", "2.0".into())];
+    message.extend(fields);
+    write_message(&mut self.output, &Json::object(message))
+  }
+
+  /// Replace a document's text publishing its new diagnostics
+  fn update(&mut self, uri: &str, text: String) -> io::Result<()> {
+    let analysis = self.vm.analyze(uri_path(uri), &text);
+    let diagnostics = analysis
+      .diagnostics
+      .iter()
+      .map(|diagnostic| diagnostic_json(&text, diagnostic))
+      .collect();
+
+    // a script that fails to parse keeps the symbols of its last parse
+    let symbols = match self.documents.remove(uri) {
+      Some(document) if analysis.symbols.symbols().is_empty() => document.symbols,
+      _ => analysis.symbols,
+    };
+    self
+      .documents
+      .insert(uri.to_string(), Document { text, symbols });
+
+    self.publish(uri, diagnostics)
+  }
+
+  fn publish(&mut self, uri: &str, diagnostics: Vec<Json>) -> io::Result<()> {
+    self.send(vec![
+      ("method", "textDocument/publishDiagnostics".into()),
+      (
+        "params",
+        Json::object(vec![
+          ("uri", uri.into()),
+          ("diagnostics", diagnostics.into()),
+        ]),
+      ),
+    ])
+  }
+
+  /// The document and byte offset of a text document position
+  fn document_offset<'a>(&'a self, params: &'a Json) -> Option<(&'a str, &'a Document, usize)> {
+    let uri = text_document_uri(params)?;
+    let document = self.documents.get(uri)?;
+
+    let position = params.get("position")?;
+    let line = position.get("line")?.as_f64()? as usize;
+    let character = position.get("character")?.as_f64()? as usize;
+
+    Some((uri, document, offset(&document.text, line, character)))
+  }
+
+  fn definition(&self, params: &Json) -> Json {
+    let (uri, document, offset) = match self.document_offset(params) {
+      Some(position) => position,
+      None => return Json::Null,
+    };
+
+    match document.symbols.definition(offset as u32) {
+      Some(symbol) => {
+        let span = symbol.span;
+        Json::object(vec![
+          ("uri", uri.into()),
+          (
+            "range",
+            range(&document.text, span.start as usize, span.end as usize),
+          ),
+        ])
+      },
+      None => Json::Null,
+    }
+  }
+
+  /// Complete the identifier before the cursor. After a `.` the methods of
+  /// the standard library are offered, otherwise the module's symbols and
+  /// the standard library classes
+  fn completion(&self, params: &Json) -> Json {
+    let (_, document, offset) = match self.document_offset(params) {
+      Some(position) => position,
+      None => return Json::Null,
+    };
+
+    let before = &document.text[..offset];
+    let word_start = identifier_start(before);
+    let prefix = &before[word_start..];
+
+    let mut items: Vec<(String, u8, String)> = vec![];
+    match before[..word_start].strip_suffix('.') {
+      Some(receiver) => {
+        let receiver = &receiver[identifier_start(receiver)..];
+        let is_class = self
+          .std_methods
+          .iter()
+          .any(|method| method.class == receiver);
+
+        // a std class receiver offers its static methods, anything else
+        // may be an instance of any class
+        for method in &self.std_methods {
+          if is_class != method.is_static || (is_class && method.class != receiver) {
+            continue;
+          }
+
+          let detail = format!(
+            "{}.{}({})",
+            method.class,
+            method.name,
+            method.parameters.join(", ")
+          );
+          if !items.iter().any(|(name, _, _)| *name == method.name) {
+            items.push((method.name.clone(), KIND_METHOD, detail));
+          }
+        }
+      },
+      None => {
+        for symbol in document.symbols.symbols() {
+          let kind = match symbol.kind {
+            SymbolKind::Variable => KIND_VARIABLE,
+            SymbolKind::Constant => KIND_CONSTANT,
+            SymbolKind::Function => KIND_FUNCTION,
+            SymbolKind::Class => KIND_CLASS,
+            SymbolKind::Import => KIND_MODULE,
+          };
+          items.push((symbol.name.clone(), kind, String::new()));
+        }
+
+        for method in &self.std_methods {
+          if !items.iter().any(|(name, _, _)| *name == method.class) {
+            items.push((method.class.clone(), KIND_CLASS, String::new()));
+          }
+        }
+      },
+    }
+
+    let items = items
+      .into_iter()
+      .filter(|(name, _, _)| name.starts_with(prefix))
+      .map(|(name, kind, detail)| {
+        Json::object(vec![
+          ("label", name.into()),
+          ("kind", kind.into()),
+          ("detail", detail.into()),
+        ])
+      })
+      .collect::<Vec<Json>>();
+
+    Json::object(vec![
+      ("isIncomplete", false.into()),
+      ("items", items.into()),
+    ])
+  }
+}
+
+/// The uri of the text document a request refers to
+fn text_document_uri(params: &Json) -> Option<&str> {
+  params.get("textDocument")?.get("uri")?.as_str()
+}
+
+/// The file path of a file uri decoding any percent escapes
+fn uri_path(uri: &str) -> PathBuf {
+  let path = uri.strip_prefix("file://").unwrap_or(uri);
+  let mut bytes = vec![];
+
+  let mut rest = path.as_bytes();
+  while let Some((byte, tail)) = rest.split_first() {
+    let escaped = match tail {
+      [high, low, ..] if *byte == b'%' => std::str::from_utf8(&[*high, *low])
+        .ok()
+        .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+      _ => None,
+    };
+
+    match escaped {
+      Some(escaped) => {
+        bytes.push(escaped);
+        rest = &tail[2..];
+      },
+      None => {
+        bytes.push(*byte);
+        rest = tail;
+      },
+    }
+  }
+
+  PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// The byte offset where the identifier ending the provided text starts
+fn identifier_start(text: &str) -> usize {
+  text
+    .char_indices()
+    .rev()
+    .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+    .last()
+    .map_or(text.len(), |(index, _)| index)
+}
+
+/// The lsp position of a byte offset. Lsp counts characters in utf-16 code units
+fn position(text: &str, offset: usize) -> Json {
+  let mut offset = offset.min(text.len());
+  while !text.is_char_boundary(offset) {
+    offset -= 1;
+  }
+
+  let before = &text[..offset];
+  let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+  let line = before.matches('\n').count();
+  let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+
+  Json::object(vec![("line", line.into()), ("character", character.into())])
+}
+
+/// The byte offset of an lsp position
+fn offset(text: &str, line: usize, character: usize) -> usize {
+  let line_start: usize = text.split_inclusive('\n').take(line).map(str::len).sum();
+  let mut units = 0;
+
+  for (index, c) in text[line_start..].char_indices() {
+    if units >= character || c == '\n' {
+      return line_start + index;
+    }
+    units += c.len_utf16();
+  }
+
+  text.len()
+}
+
+/// The lsp range between two byte offsets
+fn range(text: &str, start: usize, end: usize) -> Json {
+  Json::object(vec![
+    ("start", position(text, start)),
+    ("end", position(text, end)),
+  ])
+}
+
+/// Convert a diagnostic to an lsp diagnostic ranged over its primary label
+fn diagnostic_json(text: &str, diagnostic: &Diagnostic<VmFileId>) -> Json {
+  let label = diagnostic
+    .labels
+    .iter()
+    .find(|label| label.style == LabelStyle::Primary)
+    .or_else(|| diagnostic.labels.first());
+
+  let (start, end) = label.map_or((0, 0), |label| (label.range.start, label.range.end));
+
+  let severity: u8 = match diagnostic.severity {
+    Severity::Bug | Severity::Error => 1,
+    Severity::Warning => 2,
+    Severity::Note => 3,
+    Severity::Help => 4,
+  };
+
+  Json::object(vec![
+    ("range", range(text, start, end)),
+    ("severity", severity.into()),
+    ("source", "laythe".into()),
+    ("message", diagnostic.message.as_str().into()),
+  ])
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+  };
+
+  #[derive(Clone, Default)]
+  struct Output(Arc<Mutex<Vec<u8>>>);
+
+  impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  /// Run a session with the provided messages returning the server's messages
+  fn session(messages: Vec<Json>) -> Vec<Json> {
+    let mut input = vec![];
+    for message in &messages {
+      write_message(&mut input, message).unwrap();
+    }
+
+    let output = Output::default();
+    serve(
+      Vm::builder(),
+      Box::new(Cursor::new(input)),
+      Box::new(output.clone()),
+    )
+    .expect("Language server failed");
+
+    let mut output = Cursor::new(output.0.lock().unwrap().clone());
+    let mut messages = vec![];
+    while let Some(message) = read_message(&mut output).unwrap() {
+      messages.push(message);
+    }
+    messages
+  }
+
+  fn request(id: u8, method: &str, params: Json) -> Json {
+    Json::object(vec![
+      ("jsonrpc", "2.0".into()),
+      ("id", id.into()),
+      ("method", method.into()),
+      ("params", params),
+    ])
+  }
+
+  fn open(uri: &str, text: &str) -> Json {
+    Json::object(vec![
+      ("jsonrpc", "2.0".into()),
+      ("method", "textDocument/didOpen".into()),
+      (
+        "params",
+        Json::object(vec![(
+          "textDocument",
+          Json::object(vec![("uri", uri.into()), ("text", text.into())]),
+        )]),
+      ),
+    ])
+  }
+
+  fn change(uri: &str, text: &str) -> Json {
+    Json::object(vec![
+      ("jsonrpc", "2.0".into()),
+      ("method", "textDocument/didChange".into()),
+      (
+        "params",
+        Json::object(vec![
+          ("textDocument", Json::object(vec![("uri", uri.into())])),
+          (
+            "contentChanges",
+            Json::array(vec![Json::object(vec![("text", text.into())])]),
+          ),
+        ]),
+      ),
+    ])
+  }
+
+  fn at(uri: &str, line: u8, character: u8) -> Json {
+    Json::object(vec![
+      ("textDocument", Json::object(vec![("uri", uri.into())])),
+      (
+        "position",
+        Json::object(vec![("line", line.into()), ("character", character.into())]),
+      ),
+    ])
+  }
+
+  /// The result of the response to the provided request id
+  fn result(messages: &[Json], id: u8) -> &Json {
+    messages
+      .iter()
+      .find(|message| message.get("id") == Some(&Json::from(id)))
+      .and_then(|message| message.get("result"))
+      .expect("No response to request")
+  }
+
+  fn labels(completion: &Json) -> Vec<&str> {
+    completion
+      .get("items")
+      .and_then(Json::as_array)
+      .unwrap()
+      .iter()
+      .filter_map(|item| item.get("label")?.as_str())
+      .collect()
+  }
+
+  #[test]
+  fn positions() {
+    let text = "let a = 'é';\nlet 😀 = a;\n";
+
+    assert_eq!(offset(text, 0, 4), 4);
+    assert_eq!(offset(text, 1, 0), 14);
+    assert_eq!(offset(text, 1, 6), 22);
+    assert_eq!(offset(text, 0, 40), 13);
+
+    assert_eq!(
+      position(text, 22).to_string(),
+      r#"{"line":1,"character":6}"#
+    );
+    assert_eq!(
+      position(text, 10).to_string(),
+      r#"{"line":0,"character":9}"#
+    );
+  }
+
+  #[test]
+  fn uri_paths() {
+    assert_eq!(
+      uri_path("file:///home/a%20b/c.lay"),
+      PathBuf::from("/home/a b/c.lay")
+    );
+    assert_eq!(uri_path("file:///c.lay%"), PathBuf::from("/c.lay%"));
+  }
+
+  #[test]
+  fn diagnostics() {
+    let messages = session(vec![open("file:///a.lay", "let a = 1;\nlet b = ;\n")]);
+
+    let params = messages[0].get("params").unwrap();
+    let diagnostics = params.get("diagnostics").and_then(Json::as_array).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+      diagnostics[0]
+        .get("range")
+        .unwrap()
+        .get("start")
+        .unwrap()
+        .to_string(),
+      r#"{"line":1,"character":8}"#
+    );
+  }
+
+  #[test]
+  fn definition() {
+    let uri = "file:///a.lay";
+    let messages = session(vec![
+      open(uri, "let a = 1;\nfn f() {\n  return a;\n}\nprint(f());\n"),
+      request(1, "textDocument/definition", at(uri, 2, 9)),
+      request(2, "textDocument/definition", at(uri, 4, 7)),
+      request(3, "textDocument/definition", at(uri, 4, 0)),
+    ]);
+
+    let a = result(&messages, 1).get("range").unwrap();
+    assert_eq!(
+      a.to_string(),
+      r#"{"start":{"line":0,"character":4},"end":{"line":0,"character":5}}"#
+    );
+
+    let f = result(&messages, 2).get("range").unwrap();
+    assert_eq!(
+      f.get("start").unwrap().to_string(),
+      r#"{"line":1,"character":3}"#
+    );
+
+    assert_eq!(result(&messages, 3), &Json::Null);
+  }
+
+  #[test]
+  fn completion() {
+    let uri = "file:///a.lay";
+    let messages = session(vec![
+      open(uri, "let apple = [1];\n"),
+      change(uri, "let apple = [1];\napple.pu\nList.\nap\n"),
+      request(1, "textDocument/completion", at(uri, 1, 8)),
+      request(2, "textDocument/completion", at(uri, 2, 5)),
+      request(3, "textDocument/completion", at(uri, 3, 2)),
+    ]);
+
+    assert!(labels(result(&messages, 1)).contains(&"push"));
+
+    let statics = labels(result(&messages, 2));
+    assert!(!statics.is_empty());
+    assert!(!statics.contains(&"push"));
+
+    assert_eq!(labels(result(&messages, 3)), vec!["apple"]);
+  }
+
+  #[test]
+  fn unsupported_method() {
+    let messages = session(vec![
+      request(1, "textDocument/hover", Json::Null),
+      request(2, "shutdown", Json::Null),
+      Json::object(vec![("jsonrpc", "2.0".into()), ("method", "exit".into())]),
+      request(3, "shutdown", Json::Null),
+    ]);
+
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].get("error").is_some());
+    assert_eq!(result(&messages, 2), &Json::Null);
+  }
+}
@@ -0,0 +1,341 @@
+use std::{error::Error, fmt, path::PathBuf};
+
+/// The file name of a package manifest
+pub const MANIFEST: &str = "laythe.toml";
+
+/// A package manifest read from a `laythe.toml` file. Manifests are a small
+/// subset of toml with a `[package]` table naming the package and its
+/// version and a `[dependencies]` table of packages found at local paths
+///
+/// ```toml
+/// [package]
+/// name = "app"
+/// version = "0.1.0"
+///
+/// [dependencies]
+/// shapes = { path = "../shapes" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+  /// The name of the package
+  pub name: String,
+
+  /// The version of the package
+  pub version: String,
+
+  /// The packages this package depends on
+  pub dependencies: Vec<Dependency>,
+}
+
+/// A package dependency found at a path relative to the manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+  /// The name the dependency is imported by
+  pub name: String,
+
+  /// The directory holding the dependency's manifest
+  pub path: PathBuf,
+}
+
+/// An error found while parsing a manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestError {
+  /// The line of the error starting at 1
+  pub line: usize,
+
+  /// What was wrong with the line
+  pub message: String,
+}
+
+impl ManifestError {
+  fn new(line: usize, message: impl Into<String>) -> Self {
+    Self {
+      line,
+      message: message.into(),
+    }
+  }
+}
+
+impl fmt::Display for ManifestError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "line {}: {}", self.line, self.message)
+  }
+}
+
+impl Error for ManifestError {}
+
+/// The tables a manifest can contain
+enum Table {
+  None,
+  Package,
+  Dependencies,
+}
+
+impl Manifest {
+  /// Parse a manifest from the contents of a `laythe.toml` file
+  pub fn parse(source: &str) -> Result<Self, ManifestError> {
+    let mut table = Table::None;
+    let mut name: Option<String> = None;
+    let mut version: Option<String> = None;
+    let mut dependencies: Vec<Dependency> = vec![];
+
+    for (index, line) in source.lines().enumerate() {
+      let line_number = index + 1;
+      let line = strip_comment(line).trim();
+
+      if line.is_empty() {
+        continue;
+      }
+
+      if let Some(header) = line.strip_prefix('[') {
+        let header = header
+          .strip_suffix(']')
+          .ok_or_else(|| ManifestError::new(line_number, "Expected ']' after table name."))?;
+
+        table = match header.trim() {
+          "package" => Table::Package,
+          "dependencies" => Table::Dependencies,
+          header => {
+            return Err(ManifestError::new(
+              line_number,
+              format!("Unknown table [{}].", header),
+            ))
+          },
+        };
+        continue;
+      }
+
+      let (key, value) = key_value(line, line_number)?;
+
+      match table {
+        Table::None => {
+          return Err(ManifestError::new(
+            line_number,
+            format!("Key {} must be in a table.", key),
+          ))
+        },
+        Table::Package => {
+          let field = match key {
+            "name" => &mut name,
+            "version" => &mut version,
+            key => {
+              return Err(ManifestError::new(
+                line_number,
+                format!("Unknown package key {}.", key),
+              ))
+            },
+          };
+
+          if field.is_some() {
+            return Err(ManifestError::new(
+              line_number,
+              format!("Package key {} is defined twice.", key),
+            ));
+          }
+          *field = Some(string(value, line_number)?);
+        },
+        Table::Dependencies => {
+          if dependencies.iter().any(|dependency| dependency.name == key) {
+            return Err(ManifestError::new(
+              line_number,
+              format!("Dependency {} is defined twice.", key),
+            ));
+          }
+
+          if !is_package_name(key) {
+            return Err(ManifestError::new(
+              line_number,
+              format!("Dependency name {} is not a valid identifier.", key),
+            ));
+          }
+
+          dependencies.push(Dependency {
+            name: key.to_string(),
+            path: PathBuf::from(dependency_path(key, value, line_number)?),
+          });
+        },
+      }
+    }
+
+    let name = name.ok_or_else(|| ManifestError::new(1, "Package name is missing."))?;
+    if !is_package_name(&name) {
+      return Err(ManifestError::new(
+        1,
+        format!("Package name {} is not a valid identifier.", name),
+      ));
+    }
+
+    let version = version.ok_or_else(|| ManifestError::new(1, "Package version is missing."))?;
+
+    Ok(Self {
+      name,
+      version,
+      dependencies,
+    })
+  }
+}
+
+/// Package names are imported so must be identifiers
+fn is_package_name(name: &str) -> bool {
+  let mut chars = name.chars();
+
+  match chars.next() {
+    Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+      chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    },
+    _ => false,
+  }
+}
+
+/// Remove a trailing comment from a line
+fn strip_comment(line: &str) -> &str {
+  let mut in_string = false;
+
+  for (index, c) in line.char_indices() {
+    match c {
+      '"' => in_string = !in_string,
+      '#' if !in_string => return &line[..index],
+      _ => (),
+    }
+  }
+
+  line
+}
+
+/// Split a `key = value` line
+fn key_value(line: &str, line_number: usize) -> Result<(&str, &str), ManifestError> {
+  let (key, value) = line
+    .split_once('=')
+    .ok_or_else(|| ManifestError::new(line_number, "Expected '=' after key."))?;
+
+  let key = key.trim();
+  if key.is_empty() {
+    return Err(ManifestError::new(line_number, "Expected key before '='."));
+  }
+
+  Ok((key, value.trim()))
+}
+
+/// Parse a quoted string value
+fn string(value: &str, line_number: usize) -> Result<String, ManifestError> {
+  value
+    .strip_prefix('"')
+    .and_then(|value| value.strip_suffix('"'))
+    .filter(|value| !value.contains('"'))
+    .map(str::to_string)
+    .ok_or_else(|| ManifestError::new(line_number, "Expected a quoted string."))
+}
+
+/// Parse the path of a dependency written as `{ path = "..." }`
+fn dependency_path(name: &str, value: &str, line_number: usize) -> Result<String, ManifestError> {
+  let expected = || {
+    ManifestError::new(
+      line_number,
+      format!(
+        "Dependency {} must be a table with a path such as {{ path = \"../{}\" }}.",
+        name, name
+      ),
+    )
+  };
+
+  let fields = value
+    .strip_prefix('{')
+    .and_then(|value| value.strip_suffix('}'))
+    .ok_or_else(expected)?;
+
+  let mut path = None;
+  for field in fields
+    .split(',')
+    .map(str::trim)
+    .filter(|field| !field.is_empty())
+  {
+    match key_value(field, line_number)? {
+      ("path", value) => path = Some(string(value, line_number)?),
+      (key, _) => {
+        return Err(ManifestError::new(
+          line_number,
+          format!("Unknown dependency key {}.", key),
+        ))
+      },
+    }
+  }
+
+  path.ok_or_else(expected)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn parse() {
+    let manifest = Manifest::parse(
+      r#"
+# an example package
+[package]
+name = "app"
+version = "0.1.0" # trailing comment
+
+[dependencies]
+shapes = { path = "../shapes" }
+math_utils = { path = "vendor/math#1" }
+"#,
+    )
+    .expect("Unable to parse manifest");
+
+    assert_eq!(manifest.name, "app");
+    assert_eq!(manifest.version, "0.1.0");
+    assert_eq!(
+      manifest.dependencies,
+      vec![
+        Dependency {
+          name: "shapes".to_string(),
+          path: PathBuf::from("../shapes"),
+        },
+        Dependency {
+          name: "math_utils".to_string(),
+          path: PathBuf::from("vendor/math#1"),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn no_dependencies() {
+    let manifest = Manifest::parse("[package]\nname = \"lib\"\nversion = \"1.0.0\"")
+      .expect("Unable to parse manifest");
+
+    assert_eq!(manifest.name, "lib");
+    assert!(manifest.dependencies.is_empty());
+  }
+
+  #[test]
+  fn errors() {
+    let error = |source: &str| Manifest::parse(source).unwrap_err();
+
+    assert_eq!(
+      error("[package]\nversion = \"1\"").message,
+      "Package name is missing."
+    );
+    assert_eq!(
+      error("[package]\nname = \"a\"").message,
+      "Package version is missing."
+    );
+    assert_eq!(error("name = \"a\"").line, 1);
+    assert_eq!(error("[package]\nname = \"a\"\n[workspace]").line, 3);
+    assert_eq!(error("[package]\nname = a").line, 2);
+    assert_eq!(error("[package]\nname = \"a\"\nname = \"b\"").line, 3);
+    assert_eq!(
+      error("[package]\nname = \"my-app\"\nversion = \"1\"").message,
+      "Package name my-app is not a valid identifier."
+    );
+    assert_eq!(
+      error("[package]\nname = \"a\"\nversion = \"1\"\n[dependencies]\nb = \"1.0\"").line,
+      5
+    );
+    assert_eq!(
+      error("[dependencies]\nb = { path = \"../b\", version = \"1\" }").message,
+      "Unknown dependency key version."
+    );
+  }
+}
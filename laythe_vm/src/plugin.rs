@@ -0,0 +1,97 @@
+use laythe_core::plugin::PluginInit;
+use std::{env::consts::DLL_PREFIX, path::Path};
+
+/// A plugin loaded from a dynamic library
+pub(crate) struct Plugin {
+  /// The name of the package the plugin's modules are registered in
+  pub name: String,
+
+  /// Registers the plugin's modules
+  pub init: PluginInit,
+}
+
+/// The package name of a plugin library, its file name without the
+/// platform's library prefix or extension. `libgeometry.so` is `geometry`
+pub(crate) fn plugin_name(path: &Path) -> Option<&str> {
+  let stem = path.file_stem()?.to_str()?;
+  let name = stem.strip_prefix(DLL_PREFIX).unwrap_or(stem);
+
+  if name.is_empty() {
+    None
+  } else {
+    Some(name)
+  }
+}
+
+/// Load the plugin library at the provided path, checking it was built
+/// against the same build of laythe_core as this vm before anything else
+/// in it is called. The library is never unloaded as the natives it
+/// registers run code from it for as long as they are reachable
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn load(path: &Path) -> Result<Plugin, String> {
+  use laythe_core::plugin::{plugin_abi_key, PluginAbi, PLUGIN_ABI_SYMBOL, PLUGIN_INIT_SYMBOL};
+  use libloading::Library;
+  use std::mem;
+
+  let name = plugin_name(path)
+    .ok_or_else(|| format!("Plugin path {} has no file name.", path.display()))?
+    .to_string();
+
+  let library = unsafe { Library::new(path) }
+    .map_err(|err| format!("Unable to load plugin {}: {}", path.display(), err))?;
+
+  let abi = unsafe { library.get::<PluginAbi>(PLUGIN_ABI_SYMBOL) }
+    .map(|abi| *abi)
+    .map_err(|_| format!("{} is not a laythe plugin.", path.display()))?;
+
+  if abi() != plugin_abi_key() {
+    return Err(format!(
+      "Plugin {} was built against a different version of laythe or the rust compiler.",
+      path.display()
+    ));
+  }
+
+  let init = unsafe { library.get::<PluginInit>(PLUGIN_INIT_SYMBOL) }
+    .map(|init| *init)
+    .map_err(|_| {
+      format!(
+        "Plugin {} does not export laythe_plugin_init.",
+        path.display()
+      )
+    })?;
+
+  mem::forget(library);
+  Ok(Plugin { name, init })
+}
+
+/// Dynamic libraries can't be loaded in the browser
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn load(path: &Path) -> Result<Plugin, String> {
+  Err(format!(
+    "Unable to load plugin {}, plugins are not supported on this platform.",
+    path.display()
+  ))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::path::PathBuf;
+
+  #[test]
+  fn name() {
+    let library = format!("{}geometry.so", DLL_PREFIX);
+    assert_eq!(plugin_name(&PathBuf::from(&library)), Some("geometry"));
+    assert_eq!(
+      plugin_name(&PathBuf::from("/plugins/shapes.dll")),
+      Some("shapes")
+    );
+    assert_eq!(plugin_name(&PathBuf::from("/")), None);
+  }
+
+  #[test]
+  fn not_a_library() {
+    let missing = load(&PathBuf::from("/missing/libgeometry.so"));
+    assert!(missing.is_err());
+  }
+}
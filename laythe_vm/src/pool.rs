@@ -0,0 +1,233 @@
+use crate::{
+  compiler::LintLevel,
+  embed::Rooted,
+  interrupt::Interrupt,
+  vm::{Vm, VmError},
+};
+use laythe_core::worker::{ChunkResult, Message, PoolJob};
+use laythe_env::io::Io;
+use std::{
+  num::NonZeroUsize,
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver, Sender},
+    Arc, Mutex,
+  },
+  thread,
+};
+
+/// How many chunks each pool thread is given for a job on average. More
+/// chunks than threads keeps threads busy when some items take longer
+const CHUNKS_PER_THREAD: usize = 4;
+
+/// The parts of a vm's configuration a vm on another thread is built with
+#[derive(Clone)]
+pub(crate) struct ThreadConfig {
+  pub io: Io,
+  pub frame_limit: usize,
+  pub stack_size: usize,
+  pub lint_level: LintLevel,
+  pub type_check: bool,
+  pub manifest: Option<PathBuf>,
+  pub chunk_cache: bool,
+}
+
+impl ThreadConfig {
+  /// Build a vm with this configuration
+  pub fn build(&self) -> Result<Vm, VmError> {
+    let mut builder = Vm::builder()
+      .io(self.io.clone())
+      .frame_limit(self.frame_limit)
+      .stack_size(self.stack_size)
+      .lint_level(self.lint_level)
+      .type_check(self.type_check)
+      .chunk_cache(self.chunk_cache);
+    if let Some(manifest) = &self.manifest {
+      builder = builder.manifest(manifest.clone());
+    }
+
+    builder.build()
+  }
+}
+
+/// A copied function and the result of restoring it on a pool thread
+type Restored = (Arc<Vec<u8>>, Result<Rooted, String>);
+
+/// A chunk of a job's items for a pool thread to call the job's function with
+struct Task {
+  fun: Arc<Vec<u8>>,
+  chunk: usize,
+  items: Vec<Message>,
+  collect: bool,
+  cancelled: Arc<AtomicBool>,
+  results: Sender<ChunkResult>,
+}
+
+/// A vm on each available core that calls copied functions with chunks
+/// of copied items. Each thread takes the next chunk of any job once it
+/// has finished its last
+pub(crate) struct WorkerPool {
+  tasks: Sender<Task>,
+  threads: usize,
+  interrupts: Vec<Interrupt>,
+  stopped: Arc<AtomicBool>,
+}
+
+impl WorkerPool {
+  /// Start a thread for each available core
+  pub fn new(config: ThreadConfig) -> Result<Self, String> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let (tasks, receiver) = mpsc::channel();
+    let receiver = Arc::new(Mutex::new(receiver));
+    let stopped = Arc::new(AtomicBool::new(false));
+
+    let interrupts = (0..threads)
+      .map(|index| {
+        let interrupt = Interrupt::new();
+        let handle = interrupt.clone();
+        let config = config.clone();
+        let receiver = Arc::clone(&receiver);
+        let stopped = Arc::clone(&stopped);
+
+        thread::Builder::new()
+          .name(format!("pool {}", index))
+          .spawn(move || work(config, interrupt, receiver, stopped))
+          .map(|_| handle)
+          .map_err(|err| format!("Unable to start the worker pool: {}", err))
+      })
+      .collect::<Result<Vec<Interrupt>, String>>()?;
+
+    Ok(Self {
+      tasks,
+      threads,
+      interrupts,
+      stopped,
+    })
+  }
+
+  /// Split the items into chunks and queue each to call the function
+  /// copied into `fun` with
+  pub fn run(&self, fun: Vec<u8>, items: Vec<Message>, collect: bool) -> PoolJob {
+    let fun = Arc::new(fun);
+    let chunk_size = items
+      .len()
+      .div_ceil(self.threads * CHUNKS_PER_THREAD)
+      .max(1);
+
+    let (results, receiver) = mpsc::channel();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let mut chunks = 0;
+    let mut items = items.into_iter().peekable();
+    while items.peek().is_some() {
+      let task = Task {
+        fun: Arc::clone(&fun),
+        chunk: chunks,
+        items: items.by_ref().take(chunk_size).collect(),
+        collect,
+        cancelled: Arc::clone(&cancelled),
+        results: results.clone(),
+      };
+
+      // a thread that failed to start drops its task and the job sees the pool stop
+      let _ = self.tasks.send(task);
+      chunks += 1;
+    }
+
+    PoolJob::new(chunks, receiver, cancelled)
+  }
+}
+
+impl Drop for WorkerPool {
+  fn drop(&mut self) {
+    self.stopped.store(true, Ordering::Relaxed);
+    self
+      .interrupts
+      .iter()
+      .for_each(|interrupt| interrupt.interrupt());
+  }
+}
+
+/// Run the tasks of a pool thread until the pool is dropped
+fn work(
+  config: ThreadConfig,
+  interrupt: Interrupt,
+  tasks: Arc<Mutex<Receiver<Task>>>,
+  stopped: Arc<AtomicBool>,
+) {
+  let mut vm = match config.build() {
+    Ok(vm) => vm,
+    Err(_) => return,
+  };
+  vm.set_interrupt(interrupt);
+
+  // the function of the last task, restored once for each job
+  let mut current: Option<Restored> = None;
+
+  loop {
+    let task = match tasks.lock().map(|tasks| tasks.recv()) {
+      Ok(Ok(task)) => task,
+      _ => return,
+    };
+
+    if task.cancelled.load(Ordering::Relaxed) {
+      continue;
+    }
+
+    let fun = match &current {
+      Some((fun, restored)) if Arc::ptr_eq(fun, &task.fun) => restored.clone(),
+      _ => {
+        let restored = vm.restore_copy(&task.fun).and_then(|values| {
+          values
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Copied function is missing.".to_string())
+        });
+        current = Some((Arc::clone(&task.fun), restored.clone()));
+        restored
+      },
+    };
+
+    let Task {
+      chunk,
+      items,
+      collect,
+      cancelled,
+      results,
+      ..
+    } = task;
+
+    let result = fun.and_then(|fun| {
+      let stop = || stopped.load(Ordering::Relaxed) || cancelled.load(Ordering::Relaxed);
+      call_each(&mut vm, &fun, items, collect, stop)
+    });
+    let _ = results.send((chunk, result));
+  }
+}
+
+/// Call the function with each item in order, stopping at the first error
+fn call_each(
+  vm: &mut Vm,
+  fun: &Rooted,
+  items: Vec<Message>,
+  collect: bool,
+  stop: impl Fn() -> bool,
+) -> Result<Vec<Message>, String> {
+  let mut results = vec![];
+
+  for item in items {
+    if stop() {
+      return Err("Worker pool job was cancelled.".to_string());
+    }
+
+    let arg = vm.to_value(item);
+    let result = vm.call(fun, &[arg]).map_err(|err| err.to_string())?;
+
+    if collect {
+      results.push(Message::from_value(result.value())?);
+    }
+  }
+
+  Ok(results)
+}
@@ -0,0 +1,120 @@
+use laythe_core::{
+  call_frame::CallFrame,
+  managed::{GcObj, Trace},
+  object::Fun,
+};
+use laythe_env::time::Time;
+use std::{
+  collections::HashMap,
+  io::{self, Write},
+  path::{Path, PathBuf},
+  time::Duration,
+};
+
+/// Records the time spent in each call stack of a run. At each frame push
+/// and pop the time since the previous event is attributed to the stack
+/// that was running, giving the self time of every stack
+pub struct Profiler {
+  /// Where the collapsed stacks are written
+  path: PathBuf,
+
+  /// The time facilities used to time each stack
+  time: Time,
+
+  /// When the last event was recorded
+  last: Duration,
+
+  /// The total time spent with each stack running
+  stacks: HashMap<Vec<GcObj<Fun>>, Duration>,
+
+  /// The stack of the current event, reused between events
+  scratch: Vec<GcObj<Fun>>,
+}
+
+impl Profiler {
+  /// Create a new profiler writing to the provided path
+  pub fn new(path: PathBuf, time: Time) -> Self {
+    let last = time.elapsed().unwrap_or_default();
+
+    Self {
+      path,
+      time,
+      last,
+      stacks: HashMap::new(),
+      scratch: vec![],
+    }
+  }
+
+  /// The path the collapsed stacks are written to
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Discard anything recorded and restart the clock
+  pub fn reset(&mut self) {
+    self.stacks.clear();
+    self.last = self.time.elapsed().unwrap_or_default();
+  }
+
+  /// Attribute the time since the last event to the provided frames
+  pub fn record(&mut self, frames: &[CallFrame]) {
+    let now = self.time.elapsed().unwrap_or_default();
+    let elapsed = now.saturating_sub(self.last);
+    self.last = now;
+
+    if frames.is_empty() {
+      return;
+    }
+
+    self.scratch.clear();
+    self
+      .scratch
+      .extend(frames.iter().map(|frame| frame.closure.fun()));
+
+    match self.stacks.get_mut(self.scratch.as_slice()) {
+      Some(total) => *total += elapsed,
+      None => {
+        self.stacks.insert(self.scratch.clone(), elapsed);
+      },
+    }
+  }
+
+  /// Write each recorded stack in the collapsed stack format read by
+  /// flamegraph tools. Each line is the stack's frames from the root
+  /// separated by `;` followed by its self time in microseconds
+  pub fn write(&self, out: &mut dyn Write, name: impl Fn(GcObj<Fun>) -> String) -> io::Result<()> {
+    let mut lines: Vec<(String, u128)> = self
+      .stacks
+      .iter()
+      .map(|(stack, total)| {
+        let stack: Vec<String> = stack
+          .iter()
+          .map(|fun| name(*fun).replace(';', ":"))
+          .collect();
+
+        (stack.join(";"), total.as_micros())
+      })
+      .collect();
+
+    lines.sort();
+    for (stack, micros) in lines {
+      writeln!(out, "{} {}", stack, micros)?;
+    }
+
+    Ok(())
+  }
+
+  /// Trace every function held in a recorded stack
+  pub fn trace(&self) {
+    self.stacks.keys().flatten().for_each(|fun| {
+      fun.trace();
+    });
+  }
+
+  /// Trace every function held in a recorded stack with debug logging
+  pub fn trace_debug(&self, log: &mut dyn Write) {
+    self.stacks.keys().flatten().for_each(|fun| {
+      fun.trace_debug(log);
+    });
+  }
+}
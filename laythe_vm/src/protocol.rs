@@ -0,0 +1,65 @@
+use crate::json::Json;
+use std::io::{self, BufRead, Write};
+
+/// Read the next message framed by its content length returning none at
+/// the end of input. Both the debug adapter and language server protocols
+/// frame their messages this way
+pub fn read_message(input: &mut dyn BufRead) -> io::Result<Option<Json>> {
+  let mut length = None;
+
+  loop {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+      return Ok(None);
+    }
+
+    let line = line.trim_end();
+    if line.is_empty() {
+      if length.is_some() {
+        break;
+      }
+      continue;
+    }
+
+    if let Some(value) = line.strip_prefix("Content-Length:") {
+      length = value.trim().parse::<usize>().ok();
+    }
+  }
+
+  let mut body = vec![0; length.unwrap_or(0)];
+  input.read_exact(&mut body)?;
+
+  let body = String::from_utf8(body)
+    .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Message was not utf8"))?;
+  Json::parse(&body)
+    .map(Some)
+    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Write a message framed by its content length
+pub fn write_message(output: &mut dyn Write, message: &Json) -> io::Result<()> {
+  let body = message.to_string();
+  write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+  output.flush()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::io::Cursor;
+
+  #[test]
+  fn framing() {
+    let mut output = vec![];
+    write_message(&mut output, &Json::object(vec![("seq", 1u8.into())])).unwrap();
+    write_message(&mut output, &Json::object(vec![("seq", 2u8.into())])).unwrap();
+    assert!(output.starts_with(b"Content-Length: 9\r\n\r\n{\"seq\":1}"));
+
+    let mut input = Cursor::new(output);
+    let first = read_message(&mut input).unwrap().unwrap();
+    let second = read_message(&mut input).unwrap().unwrap();
+    assert_eq!(first.get("seq"), Some(&Json::from(1u8)));
+    assert_eq!(second.get("seq"), Some(&Json::from(2u8)));
+    assert_eq!(read_message(&mut input).unwrap(), None);
+  }
+}
@@ -0,0 +1,278 @@
+use crate::{signal::SignalHandlers, timer::TimerWheel};
+use laythe_core::{
+  managed::{GcObj, Trace},
+  object::{Channel, Fiber},
+  val,
+  value::Value,
+};
+use laythe_env::time::Time;
+use std::{collections::VecDeque, io::Write, time::Duration};
+
+/// How long the thread sleeps between checks for signals while
+/// the main fiber has signal handlers and no fiber is ready
+const SIGNAL_POLL: Duration = Duration::from_millis(10);
+
+/// Something waiting in the scheduler's timer wheel
+enum Timer {
+  /// A fiber sleeping until the deadline
+  Sleep(GcObj<Fiber>),
+
+  /// A channel sent the number of times it has fired at the deadline,
+  /// firing again after the interval if one is set
+  Channel {
+    channel: GcObj<Channel>,
+    interval: Option<Duration>,
+    fired: u32,
+  },
+}
+
+impl Timer {
+  /// Could this timer wake a fiber once it expires
+  fn can_wake(&self) -> bool {
+    match self {
+      Timer::Sleep(_) => true,
+      Timer::Channel { channel, .. } => channel.has_receivers(),
+    }
+  }
+}
+
+/// A cooperative scheduler for the fibers of a vm. Fibers
+/// are run in the order they become ready, sleeping fibers
+/// and timer channels are driven by a timer wheel
+pub struct Scheduler {
+  /// Fibers ready to be resumed
+  ready: VecDeque<GcObj<Fiber>>,
+
+  /// Sleeping fibers and timer channels waiting on a deadline
+  timers: TimerWheel<Timer>,
+
+  /// Fibers blocked until the host has input for them
+  awaiting_input: Vec<GcObj<Fiber>>,
+
+  /// The time facilities used to track deadlines
+  time: Time,
+}
+
+impl Scheduler {
+  /// Create a new scheduler tracking time with the provided facilities
+  pub fn new(time: Time) -> Self {
+    Self {
+      ready: VecDeque::new(),
+      timers: TimerWheel::default(),
+      awaiting_input: vec![],
+      time,
+    }
+  }
+
+  /// Remove all fibers from this scheduler
+  pub fn clear(&mut self) {
+    self.ready.clear();
+    self.timers.clear();
+    self.awaiting_input.clear();
+  }
+
+  /// Add a fiber to the back of the ready queue
+  pub fn push(&mut self, fiber: GcObj<Fiber>) {
+    self.ready.push_back(fiber);
+  }
+
+  /// Put a fiber to sleep for the provided duration
+  pub fn sleep(&mut self, mut fiber: GcObj<Fiber>, duration: Duration) {
+    let deadline = self.now() + duration;
+
+    fiber.sleep();
+    self.timers.insert(deadline, Timer::Sleep(fiber));
+  }
+
+  /// Send to a channel once the duration has passed, and then every
+  /// duration after if the timer repeats. A closed channel stops the timer
+  pub fn add_timer(&mut self, channel: GcObj<Channel>, duration: Duration, repeat: bool) {
+    let deadline = self.now() + duration;
+
+    self.timers.insert(
+      deadline,
+      Timer::Channel {
+        channel,
+        interval: if repeat { Some(duration) } else { None },
+        fired: 0,
+      },
+    );
+  }
+
+  /// Remove a fiber from the sleeping fibers and those waiting on input
+  pub fn remove(&mut self, mut fiber: GcObj<Fiber>) {
+    self
+      .timers
+      .retain(|timer| !matches!(timer, Timer::Sleep(sleeper) if *sleeper == fiber));
+    self.awaiting_input.retain(|waiting| *waiting != fiber);
+    fiber.wake_from_sleep();
+  }
+
+  /// Hold a fiber until the host has input for it
+  pub fn await_input(&mut self, fiber: GcObj<Fiber>) {
+    self.awaiting_input.push(fiber);
+  }
+
+  /// Is any fiber waiting for input from the host
+  pub fn is_awaiting_input(&self) -> bool {
+    !self.awaiting_input.is_empty()
+  }
+
+  /// Move every fiber waiting for input onto the ready queue, returning
+  /// false if no fiber was waiting
+  pub fn wake_input(&mut self) -> bool {
+    if self.awaiting_input.is_empty() {
+      return false;
+    }
+
+    for mut fiber in self.awaiting_input.drain(..) {
+      if fiber.wake() {
+        self.ready.push_back(fiber);
+      }
+    }
+
+    true
+  }
+
+  /// Take the next fiber to run. If no fiber is ready but a timer could
+  /// wake one the thread sleeps until that timer's deadline. While the
+  /// main fiber has signal handlers it is woken once a signal arrives,
+  /// so the thread sleeps in short polls and waits even without timers
+  pub fn next(&mut self, mut main: GcObj<Fiber>, signals: &SignalHandlers) -> Option<GcObj<Fiber>> {
+    self.expire_timers(self.now());
+    let watching = !signals.is_empty() && !main.is_complete();
+
+    loop {
+      if let Some(fiber) = self.ready.pop_front() {
+        return Some(fiber);
+      }
+
+      // the main fiber retries the call it blocked on after dispatching
+      if watching && signals.is_pending() && main.wake() {
+        self.remove(main);
+        return Some(main);
+      }
+
+      let earliest = self
+        .timers
+        .iter()
+        .filter(|(_, timer)| timer.can_wake())
+        .map(|(deadline, _)| deadline)
+        .min();
+
+      let now = self.now();
+      let wait = match earliest {
+        Some(earliest) => earliest.saturating_sub(now),
+        None if watching && self.awaiting_input.is_empty() => SIGNAL_POLL,
+        None => return None,
+      };
+
+      let poll = watching && wait > SIGNAL_POLL;
+      self.time.sleep(if poll { SIGNAL_POLL } else { wait });
+
+      match earliest {
+        // expire from the earliest deadline directly in case time did not advance
+        Some(earliest) if !poll => self.expire_timers(earliest.max(self.now())),
+        _ => self.expire_timers(self.now()),
+      }
+    }
+  }
+
+  /// The current time since vm startup
+  fn now(&self) -> Duration {
+    self.time.elapsed().unwrap_or_default()
+  }
+
+  /// Wake every sleeper and fire every timer channel whose deadline has passed
+  fn expire_timers(&mut self, now: Duration) {
+    if self.timers.is_empty() {
+      return;
+    }
+
+    for (deadline, timer) in self.timers.expire(now) {
+      match timer {
+        Timer::Sleep(mut fiber) => {
+          if fiber.wake() {
+            self.ready.push_back(fiber);
+          } else {
+            // the fiber never blocked so it is no longer sleeping
+            fiber.wake_from_sleep();
+          }
+        },
+        Timer::Channel {
+          channel,
+          interval,
+          fired,
+        } => {
+          if channel.is_closed() {
+            continue;
+          }
+
+          let fired = fired + 1;
+          self.fire(channel, fired);
+
+          if let Some(interval) = interval {
+            // ticks missed while the vm was busy are skipped
+            let mut next = deadline + interval;
+            while next <= now {
+              next += interval;
+            }
+
+            self.timers.insert(
+              next,
+              Timer::Channel {
+                channel,
+                interval: Some(interval),
+                fired,
+              },
+            );
+          }
+        },
+      }
+    }
+  }
+
+  /// Send the number of times a timer has fired to its channel, waking
+  /// a receiver. The tick is dropped if the channel is still full
+  fn fire(&mut self, mut channel: GcObj<Channel>, fired: u32) {
+    if !channel.has_space() {
+      return;
+    }
+
+    channel.push(val!(fired as f64));
+    while let Some(mut fiber) = channel.take_receiver() {
+      if fiber.wake() {
+        self.ready.push_back(fiber);
+        break;
+      }
+    }
+  }
+
+  /// Trace every fiber held by this scheduler
+  pub fn trace(&self) {
+    self.ready.iter().for_each(|fiber| {
+      fiber.trace();
+    });
+    self.timers.iter().for_each(|(_, timer)| match timer {
+      Timer::Sleep(fiber) => fiber.trace(),
+      Timer::Channel { channel, .. } => channel.trace(),
+    });
+    self.awaiting_input.iter().for_each(|fiber| {
+      fiber.trace();
+    });
+  }
+
+  /// Trace every fiber held by this scheduler with debug logging
+  pub fn trace_debug(&self, log: &mut dyn Write) {
+    self.ready.iter().for_each(|fiber| {
+      fiber.trace_debug(log);
+    });
+    self.timers.iter().for_each(|(_, timer)| match timer {
+      Timer::Sleep(fiber) => fiber.trace_debug(log),
+      Timer::Channel { channel, .. } => channel.trace_debug(log),
+    });
+    self.awaiting_input.iter().for_each(|fiber| {
+      fiber.trace_debug(log);
+    });
+  }
+}
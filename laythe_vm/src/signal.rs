@@ -0,0 +1,129 @@
+use laythe_core::{managed::Trace, value::Value};
+use laythe_env::signal::{ProcessSignal, Signals};
+use std::io::Write;
+
+/// The handlers a script registered for process signals. Signals are
+/// only counted as they arrive, the vm calls the handlers for a signal
+/// between instructions once it sees the count has changed
+pub struct SignalHandlers {
+  /// The process signal facilities
+  signals: Signals,
+
+  /// Each handler in the order it was registered
+  handlers: Vec<(ProcessSignal, Value)>,
+
+  /// The deliveries of each signal already dispatched
+  dispatched: [usize; ProcessSignal::ALL.len()],
+}
+
+impl SignalHandlers {
+  /// Create handlers dispatching signals counted by the provided facilities
+  pub fn new(signals: Signals) -> Self {
+    Self {
+      signals,
+      handlers: vec![],
+      dispatched: [0; ProcessSignal::ALL.len()],
+    }
+  }
+
+  /// Register a handler for a signal, watching the
+  /// signal when this is its first handler
+  pub fn add(&mut self, signal: ProcessSignal, handler: Value) -> Result<(), String> {
+    if !self.handles(signal) {
+      self.signals.watch(signal)?;
+      self.dispatched[signal.index()] = self.signals.received(signal);
+    }
+
+    self.handlers.push((signal, handler));
+    Ok(())
+  }
+
+  /// Are there no handlers
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.handlers.is_empty()
+  }
+
+  /// Has a handled signal arrived since it was last dispatched
+  #[inline]
+  pub fn is_pending(&self) -> bool {
+    !self.is_empty() && self.next_pending().is_some()
+  }
+
+  /// Take the next signal that has arrived with the handlers to call.
+  /// Deliveries that arrived before the signal was dispatched are
+  /// merged into one, as the process itself would for a pending signal
+  pub fn take_pending(&mut self) -> Option<(ProcessSignal, Vec<Value>)> {
+    let signal = self.next_pending()?;
+    self.dispatched[signal.index()] = self.signals.received(signal);
+
+    let handlers = self
+      .handlers
+      .iter()
+      .filter(|(handled, _)| *handled == signal)
+      .map(|(_, handler)| *handler)
+      .collect();
+
+    Some((signal, handlers))
+  }
+
+  /// Trace each handler
+  pub fn trace(&self) {
+    self.handlers.iter().for_each(|(_, handler)| {
+      handler.trace();
+    });
+  }
+
+  /// Trace each handler with debug logging
+  pub fn trace_debug(&self, log: &mut dyn Write) {
+    self.handlers.iter().for_each(|(_, handler)| {
+      handler.trace_debug(log);
+    });
+  }
+
+  /// Does any handler handle the signal
+  fn handles(&self, signal: ProcessSignal) -> bool {
+    self.handlers.iter().any(|(handled, _)| *handled == signal)
+  }
+
+  /// The first handled signal that has arrived since it was last dispatched
+  fn next_pending(&self) -> Option<ProcessSignal> {
+    ProcessSignal::ALL.iter().copied().find(|signal| {
+      self.handles(*signal) && self.signals.received(*signal) != self.dispatched[signal.index()]
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use laythe_core::val;
+  use laythe_env::io::Io;
+
+  #[test]
+  fn dispatch() {
+    let io = Io::default();
+    let mut handlers = SignalHandlers::new(io.signals());
+    assert!(!handlers.is_pending());
+
+    // signals before the first handler are not dispatched
+    io.signals().raise(ProcessSignal::Terminate).unwrap();
+    handlers.add(ProcessSignal::Terminate, val!(1.0)).unwrap();
+    handlers.add(ProcessSignal::Terminate, val!(2.0)).unwrap();
+    assert!(!handlers.is_pending());
+
+    // unhandled signals are not dispatched
+    io.signals().raise(ProcessSignal::Interrupt).unwrap();
+    assert!(!handlers.is_pending());
+
+    io.signals().raise(ProcessSignal::Terminate).unwrap();
+    io.signals().raise(ProcessSignal::Terminate).unwrap();
+    assert!(handlers.is_pending());
+    assert_eq!(
+      handlers.take_pending(),
+      Some((ProcessSignal::Terminate, vec![val!(1.0), val!(2.0)]))
+    );
+    assert!(!handlers.is_pending());
+    assert_eq!(handlers.take_pending(), None);
+  }
+}
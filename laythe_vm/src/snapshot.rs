@@ -0,0 +1,1267 @@
+use crate::chunk_cache::{
+  encode_fun_layout, hash_bytes, write_str, write_u32, write_u64, write_u8, FunLayout, Reader,
+};
+use fnv::FnvHashMap;
+use laythe_core::{
+  chunk::Line,
+  constants::SELF,
+  hooks::GcHooks,
+  managed::{Gc, GcObj, GcObject, GcStr},
+  module::{Module, Package},
+  object::{
+    Class, Closure, Fiber, Fun, Instance, List, Map, Method, ObjectKind, TryBlock, Upvalue,
+  },
+  signature::Arity,
+  val,
+  value::{Value, VALUE_NIL},
+};
+use std::path::PathBuf;
+
+/// Leading bytes of every snapshot
+const MAGIC: &[u8; 4] = b"LYSS";
+
+/// Tags identifying each kind of value in a snapshot
+const TAG_NIL: u8 = 0;
+const TAG_FALSE: u8 = 1;
+const TAG_TRUE: u8 = 2;
+const TAG_NUMBER: u8 = 3;
+const TAG_OBJECT: u8 = 4;
+const TAG_EXTERN: u8 = 5;
+const TAG_MODULE_CLASS: u8 = 6;
+
+/// Tags identifying each kind of object in a snapshot
+const OBJ_STRING: u8 = 0;
+const OBJ_LIST: u8 = 1;
+const OBJ_MAP: u8 = 2;
+const OBJ_CLASS: u8 = 3;
+const OBJ_INSTANCE: u8 = 4;
+const OBJ_FUN: u8 = 5;
+const OBJ_CLOSURE: u8 = 6;
+const OBJ_UPVALUE: u8 = 7;
+const OBJ_METHOD: u8 = 8;
+
+/// Tags identifying how a module was loaded
+const MODULE_REACHED: u8 = 0;
+const MODULE_CACHED: u8 = 1;
+const MODULE_MAIN: u8 = 2;
+
+/// Tags identifying each step from a symbol to an extern object
+const MEMBER_META: u8 = 0;
+const MEMBER_METHOD: u8 = 1;
+
+/// A step from a symbol of a native package to an object it holds
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Member {
+  /// The meta class of a class
+  Meta,
+
+  /// A method of a class
+  Method(String),
+}
+
+/// Where an object owned by a native package such as std is found. These
+/// objects are not written to a snapshot but looked up again on restore
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ExternPath {
+  /// The package the object is found in
+  pub package: String,
+
+  /// The path of the module within the package
+  pub modules: Vec<String>,
+
+  /// The symbol of the module, or none for the module's own class
+  pub symbol: Option<String>,
+
+  /// The steps from the symbol to the object
+  pub members: Vec<Member>,
+}
+
+/// The objects of the vm's native packages
+pub(crate) struct Externs {
+  paths: Vec<ExternPath>,
+  index: FnvHashMap<GcObject, u32>,
+}
+
+impl Externs {
+  /// Collect the objects reachable from the modules of the provided
+  /// native packages that have been constructed. The package of the main
+  /// module is written to the snapshot instead
+  pub fn collect<'a>(packages: impl Iterator<Item = &'a Gc<Package>>) -> Self {
+    let mut externs = Self {
+      paths: vec![],
+      index: FnvHashMap::default(),
+    };
+
+    let native =
+      packages.filter(|package| package.root_dir().is_none() && &*package.name() != SELF);
+    for package in native {
+      let path = ExternPath {
+        package: String::from(&*package.name()),
+        modules: vec![],
+        symbol: None,
+        members: vec![],
+      };
+      externs.collect_module(package.root_module(), path);
+    }
+
+    externs
+  }
+
+  /// The paths of the collected objects
+  pub fn paths(&self) -> &[ExternPath] {
+    &self.paths
+  }
+
+  fn collect_module(&mut self, module: Gc<Module>, path: ExternPath) {
+    self.insert(val!(module.module_class()), path.clone());
+
+    for (name, symbol) in module.symbols() {
+      let symbol_path = ExternPath {
+        symbol: Some(String::from(&**name)),
+        ..path.clone()
+      };
+      self.insert(*symbol, symbol_path.clone());
+
+      if symbol.is_obj_kind(ObjectKind::Class) {
+        self.collect_class(symbol.to_obj().to_class(), symbol_path);
+      }
+    }
+
+    for (name, sub_module) in module.modules() {
+      let mut sub_path = path.clone();
+      sub_path.modules.push(String::from(&**name));
+      self.collect_module(*sub_module, sub_path);
+    }
+  }
+
+  fn collect_class(&mut self, class: GcObj<Class>, path: ExternPath) {
+    for (name, method) in class.methods() {
+      let mut method_path = path.clone();
+      method_path
+        .members
+        .push(Member::Method(String::from(&**name)));
+      self.insert(*method, method_path);
+    }
+
+    if let Some(meta_class) = class.meta_class() {
+      let mut meta_path = path.clone();
+      meta_path.members.push(Member::Meta);
+      if self.insert(val!(*meta_class), meta_path.clone()) {
+        self.collect_class(*meta_class, meta_path);
+      }
+    }
+  }
+
+  /// Record where an object is found returning false if it was already found
+  fn insert(&mut self, value: Value, path: ExternPath) -> bool {
+    if !value.is_obj() || value.is_obj_kind(ObjectKind::String) {
+      return false;
+    }
+
+    let index = self.paths.len() as u32;
+    match self.index.entry(value.to_obj()) {
+      std::collections::hash_map::Entry::Occupied(_) => false,
+      std::collections::hash_map::Entry::Vacant(entry) => {
+        entry.insert(index);
+        self.paths.push(path);
+        true
+      },
+    }
+  }
+}
+
+/// How a module was loaded and so where it is restored to
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ModuleKey {
+  /// A module only reached through its functions
+  Reached,
+
+  /// A module imported from source cached under its resolved path
+  Cached(String),
+
+  /// The main module of the last script run
+  Main,
+}
+
+/// A module to write to a snapshot
+#[derive(Clone)]
+pub(crate) struct SnapshotModule {
+  /// How the module was loaded
+  pub key: ModuleKey,
+
+  /// The module
+  pub module: Gc<Module>,
+
+  /// The number of property inline cache slots the module uses
+  pub property_slots: usize,
+
+  /// The number of invoke inline cache slots the module uses
+  pub invoke_slots: usize,
+}
+
+/// Encode the provided modules and every value reachable from them.
+/// Modules only reached through their functions are described by `reached`
+pub(crate) fn encode(
+  key: u64,
+  modules: Vec<SnapshotModule>,
+  externs: &Externs,
+  reached: impl Fn(Gc<Module>) -> SnapshotModule,
+) -> Result<Vec<u8>, String> {
+  let mut encoder = Encoder::new(externs, &reached, None);
+
+  for module in modules {
+    encoder.add_module(module);
+  }
+
+  encoder.finish(key, &[])
+}
+
+/// Encode copies of the provided values for another vm along with the
+/// modules their functions belong to. Variables still open on `fiber` are
+/// copied with their current value. Anything the values reach that cannot
+/// be snapshot, such as a channel, is copied as nil, although the values
+/// themselves must be able to be snapshot
+pub(crate) fn encode_copy(
+  key: u64,
+  values: &[Value],
+  externs: &Externs,
+  reached: impl Fn(Gc<Module>) -> SnapshotModule,
+  fiber: GcObj<Fiber>,
+) -> Result<Vec<u8>, String> {
+  Encoder::new(externs, &reached, Some(fiber)).finish(key, values)
+}
+
+fn encode_extern(buffer: &mut Vec<u8>, path: &ExternPath) {
+  write_str(buffer, &path.package);
+  write_u32(buffer, path.modules.len() as u32);
+  path
+    .modules
+    .iter()
+    .for_each(|module| write_str(buffer, module));
+
+  match &path.symbol {
+    Some(symbol) => {
+      write_u8(buffer, 1);
+      write_str(buffer, symbol);
+    },
+    None => write_u8(buffer, 0),
+  }
+
+  write_u32(buffer, path.members.len() as u32);
+  for member in &path.members {
+    match member {
+      Member::Meta => write_u8(buffer, MEMBER_META),
+      Member::Method(name) => {
+        write_u8(buffer, MEMBER_METHOD);
+        write_str(buffer, name);
+      },
+    }
+  }
+}
+
+/// Assigns each module and object reachable from the snapshot's modules
+/// an index. Objects refer to each other by index so shared and cyclic
+/// references are restored as they were
+struct Encoder<'a> {
+  externs: &'a Externs,
+  reached: &'a dyn Fn(Gc<Module>) -> SnapshotModule,
+  modules: Vec<SnapshotModule>,
+  module_index: FnvHashMap<usize, u32>,
+  module_classes: FnvHashMap<GcObject, u32>,
+  objects: Vec<Value>,
+  object_index: FnvHashMap<GcObject, u32>,
+  copy: Option<GcObj<Fiber>>,
+  lossy: bool,
+}
+
+impl<'a> Encoder<'a> {
+  fn new(
+    externs: &'a Externs,
+    reached: &'a dyn Fn(Gc<Module>) -> SnapshotModule,
+    copy: Option<GcObj<Fiber>>,
+  ) -> Self {
+    Self {
+      externs,
+      reached,
+      modules: vec![],
+      module_index: FnvHashMap::default(),
+      module_classes: FnvHashMap::default(),
+      objects: vec![],
+      object_index: FnvHashMap::default(),
+      copy,
+      lossy: false,
+    }
+  }
+
+  /// Encode the provided values followed by every module and object
+  /// reachable from them or the modules already added
+  fn finish(mut self, key: u64, values: &[Value]) -> Result<Vec<u8>, String> {
+    let mut value_payload = vec![];
+    for value in values {
+      self.value(&mut value_payload, *value)?;
+    }
+    self.lossy = self.copy.is_some();
+
+    let mut module_payloads = vec![];
+    let mut object_payloads = vec![];
+    let mut module_cursor = 0;
+
+    // encoding a module or object can discover further modules and objects
+    loop {
+      if object_payloads.len() < self.objects.len() {
+        let mut payload = vec![];
+        self.encode_object(&mut payload, self.objects[object_payloads.len()])?;
+        object_payloads.push(payload);
+      } else if module_cursor < self.modules.len() {
+        let mut payload = vec![];
+        self.encode_module(&mut payload, module_cursor)?;
+        module_payloads.push(payload);
+        module_cursor += 1;
+      } else {
+        break;
+      }
+    }
+
+    let mut payload = vec![];
+    write_u32(&mut payload, self.externs.paths().len() as u32);
+    for path in self.externs.paths() {
+      encode_extern(&mut payload, path);
+    }
+
+    write_u32(&mut payload, module_payloads.len() as u32);
+    module_payloads
+      .iter()
+      .for_each(|module| payload.extend_from_slice(module));
+
+    write_u32(&mut payload, object_payloads.len() as u32);
+    object_payloads
+      .iter()
+      .for_each(|object| payload.extend_from_slice(object));
+
+    write_u32(&mut payload, values.len() as u32);
+    payload.extend_from_slice(&value_payload);
+
+    let mut buffer = Vec::with_capacity(payload.len() + 20);
+    buffer.extend_from_slice(MAGIC);
+    write_u64(&mut buffer, key);
+    write_u64(&mut buffer, hash_bytes(&payload));
+    buffer.extend_from_slice(&payload);
+    Ok(buffer)
+  }
+
+  fn add_module(&mut self, module: SnapshotModule) {
+    let index = self.module(module.module);
+    self.modules[index as usize] = module;
+  }
+
+  /// The index of a module adding it to the snapshot if it is new
+  fn module(&mut self, module: Gc<Module>) -> u32 {
+    if let Some(index) = self.module_index.get(&module.id()) {
+      return *index;
+    }
+
+    let index = self.modules.len() as u32;
+    self.modules.push((self.reached)(module));
+    self.module_index.insert(module.id(), index);
+    self
+      .module_classes
+      .insert(val!(module.module_class()).to_obj(), index);
+    index
+  }
+
+  fn encode_module(&mut self, buffer: &mut Vec<u8>, index: usize) -> Result<(), String> {
+    let SnapshotModule {
+      key,
+      module,
+      property_slots,
+      invoke_slots,
+    } = self.modules[index].clone();
+
+    match key {
+      ModuleKey::Reached => write_u8(buffer, MODULE_REACHED),
+      ModuleKey::Cached(key) => {
+        write_u8(buffer, MODULE_CACHED);
+        write_str(buffer, &key);
+      },
+      ModuleKey::Main => write_u8(buffer, MODULE_MAIN),
+    }
+
+    write_str(buffer, &module.name());
+    write_str(buffer, &module.path().to_string_lossy());
+    write_u32(buffer, property_slots as u32);
+    write_u32(buffer, invoke_slots as u32);
+
+    write_u32(buffer, module.len() as u32);
+    for (name, symbol) in module.symbols() {
+      write_str(buffer, name);
+      self.value(buffer, *symbol)?;
+    }
+
+    let exports: Vec<GcStr> = module.exports().copied().collect();
+    write_u32(buffer, exports.len() as u32);
+    exports.iter().for_each(|export| write_str(buffer, export));
+
+    Ok(())
+  }
+
+  /// Encode a reference to a value
+  fn value(&mut self, buffer: &mut Vec<u8>, value: Value) -> Result<(), String> {
+    if value.is_nil() {
+      write_u8(buffer, TAG_NIL);
+      return Ok(());
+    }
+    if value.is_bool() {
+      write_u8(buffer, if value.to_bool() { TAG_TRUE } else { TAG_FALSE });
+      return Ok(());
+    }
+    if value.is_num() {
+      write_u8(buffer, TAG_NUMBER);
+      write_u64(buffer, value.to_num().to_bits());
+      return Ok(());
+    }
+
+    let obj = value.to_obj();
+    if let Some(index) = self.externs.index.get(&obj) {
+      write_u8(buffer, TAG_EXTERN);
+      write_u32(buffer, *index);
+      return Ok(());
+    }
+    if let Some(index) = self.module_classes.get(&obj) {
+      write_u8(buffer, TAG_MODULE_CLASS);
+      write_u32(buffer, *index);
+      return Ok(());
+    }
+
+    let index = match self.object_index.get(&obj) {
+      Some(index) => *index,
+      None => {
+        match obj.kind() {
+          ObjectKind::String
+          | ObjectKind::List
+          | ObjectKind::Map
+          | ObjectKind::Class
+          | ObjectKind::Instance
+          | ObjectKind::Fun
+          | ObjectKind::Closure
+          | ObjectKind::Upvalue
+          | ObjectKind::Method => (),
+          _ if self.lossy => {
+            write_u8(buffer, TAG_NIL);
+            return Ok(());
+          },
+          ObjectKind::Native => {
+            return Err(format!(
+              "Unable to snapshot native {} which is not part of a native package.",
+              &*obj.to_native().meta().name
+            ))
+          },
+          kind => return Err(format!("Unable to snapshot a {:?}.", kind)),
+        }
+
+        let index = self.objects.len() as u32;
+        self.objects.push(value);
+        self.object_index.insert(obj, index);
+        index
+      },
+    };
+
+    write_u8(buffer, TAG_OBJECT);
+    write_u32(buffer, index);
+    Ok(())
+  }
+
+  fn optional(&mut self, buffer: &mut Vec<u8>, value: Option<Value>) -> Result<(), String> {
+    match value {
+      Some(value) => {
+        write_u8(buffer, 1);
+        self.value(buffer, value)
+      },
+      None => {
+        write_u8(buffer, 0);
+        Ok(())
+      },
+    }
+  }
+
+  /// Encode the contents of an object
+  fn encode_object(&mut self, buffer: &mut Vec<u8>, value: Value) -> Result<(), String> {
+    let obj = value.to_obj();
+
+    match obj.kind() {
+      ObjectKind::String => {
+        write_u8(buffer, OBJ_STRING);
+        write_str(buffer, &obj.to_str());
+      },
+      ObjectKind::List => {
+        let list = obj.to_list();
+        write_u8(buffer, OBJ_LIST);
+        write_u32(buffer, list.len() as u32);
+        for item in list.iter() {
+          self.value(buffer, *item)?;
+        }
+      },
+      ObjectKind::Map => {
+        let map = obj.to_map();
+        write_u8(buffer, OBJ_MAP);
+        write_u32(buffer, map.len() as u32);
+        for (key, value) in map.iter() {
+          self.value(buffer, *key)?;
+          self.value(buffer, *value)?;
+        }
+      },
+      ObjectKind::Class => {
+        let class = obj.to_class();
+        write_u8(buffer, OBJ_CLASS);
+        write_str(buffer, &class.name());
+        self.optional(buffer, class.meta_class().map(|meta| val!(meta)))?;
+        self.optional(
+          buffer,
+          class.super_class().map(|super_class| val!(super_class)),
+        )?;
+
+        let mut fields: Vec<(&GcStr, &u16)> = class.field_slots().collect();
+        fields.sort_by_key(|(_, slot)| **slot);
+        write_u32(buffer, fields.len() as u32);
+        fields
+          .iter()
+          .for_each(|(field, _)| write_str(buffer, field));
+
+        write_u32(buffer, class.methods().count() as u32);
+        for (name, method) in class.methods() {
+          write_str(buffer, name);
+          self.value(buffer, *method)?;
+        }
+      },
+      ObjectKind::Instance => {
+        let instance = obj.to_instance();
+        write_u8(buffer, OBJ_INSTANCE);
+        self.value(buffer, val!(instance.class()))?;
+
+        write_u32(buffer, instance.fields().len() as u32);
+        for field in instance.fields() {
+          self.value(buffer, *field)?;
+        }
+
+        write_u32(buffer, instance.dynamic_fields().count() as u32);
+        for (name, field) in instance.dynamic_fields() {
+          write_str(buffer, name);
+          self.value(buffer, *field)?;
+        }
+      },
+      ObjectKind::Fun => {
+        let fun = obj.to_fun();
+        write_u8(buffer, OBJ_FUN);
+        let module = self.module(fun.module());
+        write_u32(buffer, module);
+        encode_fun_layout(buffer, &fun);
+
+        let constants = fun.chunk().constants();
+        write_u32(buffer, constants.len() as u32);
+        for constant in constants {
+          self.value(buffer, *constant)?;
+        }
+      },
+      ObjectKind::Closure => {
+        let closure = obj.to_closure();
+        write_u8(buffer, OBJ_CLOSURE);
+        self.value(buffer, val!(closure.fun()))?;
+
+        write_u32(buffer, closure.upvalues() as u32);
+        for index in 0..closure.upvalues() {
+          self.value(buffer, val!(closure.get_upvalue(index)))?;
+        }
+      },
+      ObjectKind::Upvalue => {
+        let upvalue = obj.to_upvalue();
+        let value = match &*upvalue {
+          Upvalue::Closed(value) => *value,
+          Upvalue::Open(_) => match self.copy.and_then(|fiber| fiber.open_value(upvalue)) {
+            Some(value) => value,
+            None if self.lossy => VALUE_NIL,
+            None => {
+              return Err(
+                "Unable to snapshot a variable captured from a running function.".to_string(),
+              )
+            },
+          },
+        };
+
+        write_u8(buffer, OBJ_UPVALUE);
+        self.value(buffer, value)?;
+      },
+      ObjectKind::Method => {
+        let method = obj.to_method();
+        write_u8(buffer, OBJ_METHOD);
+        self.value(buffer, method.receiver())?;
+        self.value(buffer, method.method())?;
+      },
+      kind => return Err(format!("Unable to snapshot a {:?}.", kind)),
+    }
+
+    Ok(())
+  }
+}
+
+/// A reference to a value read from a snapshot
+#[derive(Clone, Copy)]
+enum Slot {
+  Nil,
+  Bool(bool),
+  Number(f64),
+  Object(u32),
+  Extern(u32),
+  ModuleClass(u32),
+}
+
+/// A function read from a snapshot
+struct FunEntry {
+  module: u32,
+  name: String,
+  arity: Arity,
+  upvalue_count: u8,
+  max_slots: u32,
+  is_async: bool,
+  try_blocks: Vec<TryBlock>,
+  instructions: Vec<u8>,
+  lines: Vec<Line>,
+  constants: Vec<Slot>,
+}
+
+/// An object read from a snapshot
+enum Entry {
+  String(String),
+  List(Vec<Slot>),
+  Map(Vec<(Slot, Slot)>),
+  Class {
+    name: String,
+    meta_class: Option<Slot>,
+    super_class: Option<Slot>,
+    fields: Vec<String>,
+    methods: Vec<(String, Slot)>,
+  },
+  Instance {
+    class: Slot,
+    fields: Vec<Slot>,
+    dynamic: Vec<(String, Slot)>,
+  },
+  Fun(Box<FunEntry>),
+  Closure {
+    fun: Slot,
+    upvalues: Vec<Slot>,
+  },
+  Upvalue(Slot),
+  Method {
+    receiver: Slot,
+    method: Slot,
+  },
+}
+
+/// A module read from a snapshot
+pub(crate) struct ModuleEntry {
+  /// How the module was loaded
+  pub key: ModuleKey,
+
+  /// The name of the module
+  pub name: String,
+
+  /// The path of the module
+  pub path: PathBuf,
+
+  /// The number of property inline cache slots the module uses
+  pub property_slots: usize,
+
+  /// The number of invoke inline cache slots the module uses
+  pub invoke_slots: usize,
+
+  symbols: Vec<(String, Slot)>,
+  exports: Vec<String>,
+}
+
+/// The contents of a snapshot before it is restored into a vm
+pub(crate) struct Snapshot {
+  /// Objects of native packages the snapshot refers to
+  pub externs: Vec<ExternPath>,
+
+  /// The modules of the snapshot
+  pub modules: Vec<ModuleEntry>,
+
+  objects: Vec<Entry>,
+  values: Vec<Slot>,
+}
+
+impl Snapshot {
+  /// Read a snapshot written with the provided key
+  pub fn read(key: u64, bytes: &[u8]) -> Option<Self> {
+    let mut reader = Reader::new(bytes);
+    if reader.bytes(MAGIC.len())? != MAGIC || reader.u64()? != key {
+      return None;
+    }
+
+    let checksum = reader.u64()?;
+    if checksum != hash_bytes(reader.remaining()) {
+      return None;
+    }
+
+    let mut externs = vec![];
+    for _ in 0..reader.u32()? {
+      externs.push(read_extern(&mut reader)?);
+    }
+
+    let mut modules = vec![];
+    for _ in 0..reader.u32()? {
+      modules.push(read_module(&mut reader)?);
+    }
+
+    let mut objects = vec![];
+    for _ in 0..reader.u32()? {
+      objects.push(read_object(&mut reader)?);
+    }
+
+    let values = read_slots(&mut reader)?;
+
+    if !reader.remaining().is_empty() {
+      return None;
+    }
+
+    Some(Self {
+      externs,
+      modules,
+      objects,
+      values,
+    })
+  }
+
+  /// Rebuild the snapshot's modules and objects returning the modules and
+  /// the values copied with `encode_copy`. Collection must be disabled
+  /// while the snapshot is restored as the objects are not rooted
+  pub fn restore(
+    &self,
+    hooks: &GcHooks,
+    externs: &[Value],
+    base_class: GcObj<Class>,
+    mut module_id: impl FnMut() -> usize,
+  ) -> Result<(Vec<Gc<Module>>, Vec<Value>), String> {
+    let modules = self
+      .modules
+      .iter()
+      .map(|entry| {
+        let name = hooks.manage_str(&entry.name);
+        let module_class = Class::with_inheritance(hooks, name, base_class);
+        hooks.manage(Module::new(module_class, entry.path.clone(), module_id()))
+      })
+      .collect();
+
+    let mut restorer = Restorer {
+      hooks,
+      snapshot: self,
+      externs,
+      modules,
+      objects: vec![None; self.objects.len()],
+      building: vec![false; self.objects.len()],
+    };
+
+    restorer.allocate()?;
+    restorer.fill()?;
+
+    for (entry, mut module) in self.modules.iter().zip(restorer.modules.clone()) {
+      for (name, symbol) in &entry.symbols {
+        let symbol = restorer.slot(*symbol)?;
+        module
+          .insert_symbol(hooks, hooks.manage_str(name), symbol)
+          .map_err(|_| format!("Symbol {} restored twice.", name))?;
+      }
+
+      for export in &entry.exports {
+        module
+          .export_symbol(hooks, hooks.manage_str(export))
+          .map_err(|_| format!("Export {} is not a symbol.", export))?;
+      }
+    }
+
+    let values = self
+      .values
+      .iter()
+      .map(|value| restorer.slot(*value))
+      .collect::<Result<Vec<Value>, String>>()?;
+
+    Ok((restorer.modules, values))
+  }
+}
+
+fn read_string(reader: &mut Reader) -> Option<String> {
+  reader.str().map(str::to_string)
+}
+
+fn read_extern(reader: &mut Reader) -> Option<ExternPath> {
+  let package = read_string(reader)?;
+
+  let mut modules = vec![];
+  for _ in 0..reader.u32()? {
+    modules.push(read_string(reader)?);
+  }
+
+  let symbol = match reader.u8()? {
+    0 => None,
+    1 => Some(read_string(reader)?),
+    _ => return None,
+  };
+
+  let mut members = vec![];
+  for _ in 0..reader.u32()? {
+    members.push(match reader.u8()? {
+      MEMBER_META => Member::Meta,
+      MEMBER_METHOD => Member::Method(read_string(reader)?),
+      _ => return None,
+    });
+  }
+
+  Some(ExternPath {
+    package,
+    modules,
+    symbol,
+    members,
+  })
+}
+
+fn read_module(reader: &mut Reader) -> Option<ModuleEntry> {
+  let key = match reader.u8()? {
+    MODULE_REACHED => ModuleKey::Reached,
+    MODULE_CACHED => ModuleKey::Cached(read_string(reader)?),
+    MODULE_MAIN => ModuleKey::Main,
+    _ => return None,
+  };
+
+  let name = read_string(reader)?;
+  let path = PathBuf::from(reader.str()?);
+  let property_slots = reader.u32()? as usize;
+  let invoke_slots = reader.u32()? as usize;
+
+  let mut symbols = vec![];
+  for _ in 0..reader.u32()? {
+    symbols.push((read_string(reader)?, read_slot(reader)?));
+  }
+
+  let mut exports = vec![];
+  for _ in 0..reader.u32()? {
+    exports.push(read_string(reader)?);
+  }
+
+  Some(ModuleEntry {
+    key,
+    name,
+    path,
+    property_slots,
+    invoke_slots,
+    symbols,
+    exports,
+  })
+}
+
+fn read_slot(reader: &mut Reader) -> Option<Slot> {
+  Some(match reader.u8()? {
+    TAG_NIL => Slot::Nil,
+    TAG_FALSE => Slot::Bool(false),
+    TAG_TRUE => Slot::Bool(true),
+    TAG_NUMBER => Slot::Number(f64::from_bits(reader.u64()?)),
+    TAG_OBJECT => Slot::Object(reader.u32()?),
+    TAG_EXTERN => Slot::Extern(reader.u32()?),
+    TAG_MODULE_CLASS => Slot::ModuleClass(reader.u32()?),
+    _ => return None,
+  })
+}
+
+fn read_optional(reader: &mut Reader) -> Option<Option<Slot>> {
+  match reader.u8()? {
+    0 => Some(None),
+    1 => read_slot(reader).map(Some),
+    _ => None,
+  }
+}
+
+fn read_slots(reader: &mut Reader) -> Option<Vec<Slot>> {
+  let len = reader.u32()? as usize;
+  let mut slots = Vec::with_capacity(len.min(reader.remaining().len()));
+  for _ in 0..len {
+    slots.push(read_slot(reader)?);
+  }
+  Some(slots)
+}
+
+fn read_named_slots(reader: &mut Reader) -> Option<Vec<(String, Slot)>> {
+  let len = reader.u32()? as usize;
+  let mut slots = Vec::with_capacity(len.min(reader.remaining().len()));
+  for _ in 0..len {
+    slots.push((read_string(reader)?, read_slot(reader)?));
+  }
+  Some(slots)
+}
+
+fn read_object(reader: &mut Reader) -> Option<Entry> {
+  Some(match reader.u8()? {
+    OBJ_STRING => Entry::String(read_string(reader)?),
+    OBJ_LIST => Entry::List(read_slots(reader)?),
+    OBJ_MAP => {
+      let len = reader.u32()? as usize;
+      let mut entries = Vec::with_capacity(len.min(reader.remaining().len()));
+      for _ in 0..len {
+        entries.push((read_slot(reader)?, read_slot(reader)?));
+      }
+      Entry::Map(entries)
+    },
+    OBJ_CLASS => {
+      let name = read_string(reader)?;
+      let meta_class = read_optional(reader)?;
+      let super_class = read_optional(reader)?;
+
+      let mut fields = vec![];
+      for _ in 0..reader.u32()? {
+        fields.push(read_string(reader)?);
+      }
+
+      Entry::Class {
+        name,
+        meta_class,
+        super_class,
+        fields,
+        methods: read_named_slots(reader)?,
+      }
+    },
+    OBJ_INSTANCE => Entry::Instance {
+      class: read_slot(reader)?,
+      fields: read_slots(reader)?,
+      dynamic: read_named_slots(reader)?,
+    },
+    OBJ_FUN => {
+      let module = reader.u32()?;
+      let layout = FunLayout::read(reader)?;
+
+      Entry::Fun(Box::new(FunEntry {
+        module,
+        name: layout.name.to_string(),
+        arity: layout.arity,
+        upvalue_count: layout.upvalue_count,
+        max_slots: layout.max_slots,
+        is_async: layout.is_async,
+        try_blocks: layout.try_blocks,
+        instructions: layout.instructions.to_vec(),
+        lines: layout.lines,
+        constants: read_slots(reader)?,
+      }))
+    },
+    OBJ_CLOSURE => Entry::Closure {
+      fun: read_slot(reader)?,
+      upvalues: read_slots(reader)?,
+    },
+    OBJ_UPVALUE => Entry::Upvalue(read_slot(reader)?),
+    OBJ_METHOD => Entry::Method {
+      receiver: read_slot(reader)?,
+      method: read_slot(reader)?,
+    },
+    _ => return None,
+  })
+}
+
+/// Rebuilds the objects of a snapshot. Objects that can be part of a
+/// cycle are allocated empty first and filled once every object exists
+struct Restorer<'a> {
+  hooks: &'a GcHooks<'a>,
+  snapshot: &'a Snapshot,
+  externs: &'a [Value],
+  modules: Vec<Gc<Module>>,
+  objects: Vec<Option<Value>>,
+  building: Vec<bool>,
+}
+
+impl<'a> Restorer<'a> {
+  /// Allocate each string, list, map, class, instance and upvalue
+  fn allocate(&mut self) -> Result<(), String> {
+    for (index, entry) in self.snapshot.objects.iter().enumerate() {
+      self.objects[index] = match entry {
+        Entry::String(string) => Some(val!(self.hooks.manage_str(string))),
+        Entry::List(items) => Some(val!(self
+          .hooks
+          .manage_obj(List::with_capacity(items.len())))),
+        Entry::Map(_) => Some(val!(self.hooks.manage_obj(Map::<Value, Value>::new()))),
+        Entry::Class { name, .. } => {
+          let name = self.hooks.manage_str(name);
+          Some(val!(self.hooks.manage_obj(Class::bare(name))))
+        },
+        Entry::Upvalue(_) => Some(val!(self.hooks.manage_obj(Upvalue::Closed(VALUE_NIL)))),
+        _ => None,
+      };
+    }
+
+    // instances take the layout of their class so classes are laid out first
+    for (index, entry) in self.snapshot.objects.iter().enumerate() {
+      if let Entry::Class { fields, .. } = entry {
+        let mut class = self.object(index)?.to_obj().to_class();
+        for field in fields {
+          class.add_field(self.hooks, self.hooks.manage_str(field));
+        }
+      }
+    }
+
+    for (index, entry) in self.snapshot.objects.iter().enumerate() {
+      if let Entry::Instance { class, .. } = entry {
+        let class = self.class(*class)?;
+        self.objects[index] = Some(val!(self.hooks.manage_obj(Instance::new(class))));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Fill the contents of each allocated object
+  fn fill(&mut self) -> Result<(), String> {
+    for (index, entry) in self.snapshot.objects.iter().enumerate() {
+      match entry {
+        Entry::List(items) => {
+          let mut list = self.object(index)?.to_obj().to_list();
+          for item in items {
+            let item = self.slot(*item)?;
+            self.hooks.grow(&mut *list, |list| list.push(item));
+          }
+        },
+        Entry::Map(entries) => {
+          let mut map = self.object(index)?.to_obj().to_map();
+          for (key, value) in entries {
+            let key = self.slot(*key)?;
+            let value = self.slot(*value)?;
+            self.hooks.grow(&mut *map, |map| map.insert(key, value));
+          }
+        },
+        Entry::Class {
+          meta_class,
+          super_class,
+          methods,
+          ..
+        } => {
+          let mut class = self.object(index)?.to_obj().to_class();
+          if let Some(meta_class) = meta_class {
+            class.set_meta(self.class(*meta_class)?);
+          }
+          if let Some(super_class) = super_class {
+            class.set_super(self.class(*super_class)?);
+          }
+
+          for (name, method) in methods {
+            let method = self.slot(*method)?;
+            class.add_method(self.hooks, self.hooks.manage_str(name), method);
+          }
+        },
+        Entry::Instance {
+          fields, dynamic, ..
+        } => {
+          let mut instance = self.object(index)?.to_obj().to_instance();
+          if fields.len() != instance.fields().len() {
+            return Err("Instance does not match the layout of its class.".to_string());
+          }
+
+          for (slot, field) in fields.iter().enumerate() {
+            instance.fields_mut()[slot] = self.slot(*field)?;
+          }
+
+          for (name, field) in dynamic {
+            let field = self.slot(*field)?;
+            instance.set_dynamic_field(self.hooks, self.hooks.manage_str(name), field);
+          }
+        },
+        Entry::Upvalue(value) => {
+          let value = self.slot(*value)?;
+          *self.object(index)?.to_obj().to_upvalue() = Upvalue::Closed(value);
+        },
+        Entry::String(_) | Entry::Fun(_) | Entry::Closure { .. } | Entry::Method { .. } => {
+          self.object(index)?;
+        },
+      }
+    }
+
+    Ok(())
+  }
+
+  /// The value a slot refers to
+  fn slot(&mut self, slot: Slot) -> Result<Value, String> {
+    match slot {
+      Slot::Nil => Ok(VALUE_NIL),
+      Slot::Bool(value) => Ok(val!(value)),
+      Slot::Number(value) => Ok(val!(value)),
+      Slot::Object(index) => self.object(index as usize),
+      Slot::Extern(index) => self
+        .externs
+        .get(index as usize)
+        .copied()
+        .ok_or_else(|| "Snapshot refers to a missing native object.".to_string()),
+      Slot::ModuleClass(index) => self
+        .modules
+        .get(index as usize)
+        .map(|module| val!(module.module_class()))
+        .ok_or_else(|| "Snapshot refers to a missing module.".to_string()),
+    }
+  }
+
+  /// The class a slot refers to
+  fn class(&mut self, slot: Slot) -> Result<GcObj<Class>, String> {
+    let class = self.slot(slot)?;
+    if class.is_obj_kind(ObjectKind::Class) {
+      Ok(class.to_obj().to_class())
+    } else {
+      Err("Snapshot expected a class.".to_string())
+    }
+  }
+
+  /// The object at an index. Functions, closures and methods are built
+  /// the first time they are requested as they cannot be part of a cycle
+  fn object(&mut self, index: usize) -> Result<Value, String> {
+    if let Some(value) = self.objects.get(index).copied().flatten() {
+      return Ok(value);
+    }
+
+    let entry = self
+      .snapshot
+      .objects
+      .get(index)
+      .ok_or_else(|| "Snapshot refers to a missing object.".to_string())?;
+
+    if self.building[index] {
+      return Err("Snapshot has a cycle through a function.".to_string());
+    }
+    self.building[index] = true;
+
+    let value = match entry {
+      Entry::Fun(fun) => val!(self.fun(fun)?),
+      Entry::Closure { fun, upvalues } => {
+        let fun = self.slot(*fun)?;
+        if !fun.is_obj_kind(ObjectKind::Fun) {
+          return Err("Snapshot expected a function.".to_string());
+        }
+
+        let upvalues = upvalues
+          .iter()
+          .map(|upvalue| {
+            let upvalue = self.slot(*upvalue)?;
+            if upvalue.is_obj_kind(ObjectKind::Upvalue) {
+              Ok(upvalue.to_obj().to_upvalue())
+            } else {
+              Err("Snapshot expected a captured variable.".to_string())
+            }
+          })
+          .collect::<Result<Vec<GcObj<Upvalue>>, String>>()?;
+
+        val!(self.hooks.manage_obj(Closure::new(
+          fun.to_obj().to_fun(),
+          upvalues.into_boxed_slice()
+        )))
+      },
+      Entry::Method { receiver, method } => {
+        let receiver = self.slot(*receiver)?;
+        let method = self.slot(*method)?;
+        val!(self.hooks.manage_obj(Method::new(receiver, method)))
+      },
+      _ => return Err("Snapshot object was not allocated.".to_string()),
+    };
+
+    self.objects[index] = Some(value);
+    Ok(value)
+  }
+
+  fn fun(&mut self, entry: &FunEntry) -> Result<GcObj<Fun>, String> {
+    let module = *self
+      .modules
+      .get(entry.module as usize)
+      .ok_or_else(|| "Snapshot refers to a missing module.".to_string())?;
+
+    let constants = entry
+      .constants
+      .iter()
+      .map(|constant| self.slot(*constant))
+      .collect::<Result<Vec<Value>, String>>()?;
+
+    let layout = FunLayout {
+      name: &entry.name,
+      arity: entry.arity,
+      upvalue_count: entry.upvalue_count,
+      max_slots: entry.max_slots,
+      is_async: entry.is_async,
+      try_blocks: entry.try_blocks.clone(),
+      instructions: &entry.instructions,
+      lines: entry.lines.clone(),
+    };
+
+    let name = self.hooks.manage_str(&entry.name);
+    Ok(self.hooks.manage_obj(layout.build(name, module, constants)))
+  }
+}
+
+/// Look up the object an extern path refers to within a package's modules
+pub(crate) fn resolve_extern(package: Gc<Package>, path: &ExternPath) -> Option<Value> {
+  let mut module = package.root_module();
+  for name in &path.modules {
+    module = module
+      .modules()
+      .find(|(module, _)| &***module == name.as_str())
+      .map(|(_, module)| *module)?;
+  }
+
+  let mut value = match &path.symbol {
+    Some(symbol) => module
+      .symbols()
+      .find(|(name, _)| &***name == symbol.as_str())
+      .map(|(_, symbol)| *symbol)?,
+    None => return Some(val!(module.module_class())),
+  };
+
+  for member in &path.members {
+    if !value.is_obj_kind(ObjectKind::Class) {
+      return None;
+    }
+
+    let class = value.to_obj().to_class();
+    value = match member {
+      Member::Meta => val!((*class.meta_class())?),
+      Member::Method(name) => class
+        .methods()
+        .find(|(method, _)| &***method == name.as_str())
+        .map(|(_, method)| *method)?,
+    };
+  }
+
+  Some(value)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn extern_round_trip() {
+    let path = ExternPath {
+      package: "std".to_string(),
+      modules: vec!["math".to_string()],
+      symbol: Some("Random".to_string()),
+      members: vec![Member::Meta, Member::Method("seed".to_string())],
+    };
+
+    let mut buffer = vec![];
+    encode_extern(&mut buffer, &path);
+    assert_eq!(read_extern(&mut Reader::new(&buffer)), Some(path));
+  }
+
+  #[test]
+  fn rejects_other_builds() {
+    let externs = Externs {
+      paths: vec![],
+      index: FnvHashMap::default(),
+    };
+    let bytes = encode(1, vec![], &externs, |module| SnapshotModule {
+      key: ModuleKey::Reached,
+      module,
+      property_slots: 0,
+      invoke_slots: 0,
+    })
+    .expect("Unable to encode");
+
+    assert!(Snapshot::read(1, &bytes).is_some());
+    assert!(Snapshot::read(2, &bytes).is_none());
+    assert!(Snapshot::read(1, &bytes[..bytes.len() - 1]).is_none());
+  }
+}
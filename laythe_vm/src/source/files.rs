@@ -225,6 +225,31 @@ impl VmFiles {
     Ok(())
   }
 
+  /// Retrieve the id of a file by its name
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_core::memory::{Allocator, NO_GC};
+  /// use laythe_vm::source::VmFiles;
+  ///
+  /// let mut alloc = Allocator::default();
+  /// let name = alloc.manage_str("first.lay", &NO_GC);
+  /// let source = alloc.manage_str("let x = 10;", &NO_GC);
+  ///
+  /// let mut files = VmFiles::default();
+  /// let id = files.upsert(name, source);
+  ///
+  /// assert_eq!(files.id("first.lay"), Some(id));
+  /// assert_eq!(files.id("second.lay"), None);
+  /// ```
+  pub fn id(&self, name: &str) -> Option<VmFileId> {
+    self
+      .files
+      .iter()
+      .position(|file| &*file.name == name)
+      .map(VmFileId)
+  }
+
   /// Retrieve an immutable file reference from the file database. Return
   /// a file missing error if not found
   fn get(&'_ self, id: VmFileId) -> Result<&'_ VmFile, files::Error> {
@@ -248,11 +273,11 @@ impl VmFiles {
 
 impl<'a> files::Files<'a> for VmFiles {
   type FileId = VmFileId;
-  type Name = GcStr;
+  type Name = &'a str;
   type Source = GcStr;
 
   fn name(&'a self, id: Self::FileId) -> Result<Self::Name, files::Error> {
-    Ok(self.get(id)?.name)
+    Ok(&*self.get(id)?.name)
   }
 
   fn source(&'a self, id: Self::FileId) -> Result<Self::Source, files::Error> {
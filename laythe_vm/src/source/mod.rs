@@ -1,65 +1,49 @@
 mod files;
 pub use files::{LineError, LineOffsets, VmFileId, VmFiles};
 
-use bumpalo::{boxed::Box, Bump};
-use laythe_core::managed::{GcStr, Trace};
 use std::ops::Deref;
 
-pub struct Source {
-  content: GcStr,
-  alloc: Bump,
+/// The content of a laythe source file. The source only borrows
+/// its content so it can be parsed without a garbage collector
+#[derive(Clone, Copy)]
+pub struct Source<'a> {
+  content: &'a str,
 }
 
-impl Source {
-  pub fn new(content: GcStr) -> Self {
-    Self {
-      content,
-      alloc: Bump::with_capacity(content.len()),
-    }
+impl<'a> Source<'a> {
+  pub fn new(content: &'a str) -> Self {
+    Self { content }
   }
 
-  pub fn node<T>(&self, node: T) -> Box<T> {
-    Box::new_in(node, &self.alloc)
+  /// The underlying source content
+  pub fn content(&self) -> &'a str {
+    self.content
   }
 }
 
-impl Deref for Source {
+impl<'a> Deref for Source<'a> {
   type Target = str;
 
   fn deref(&self) -> &Self::Target {
-    &self.content
+    self.content
   }
 }
 
-impl AsRef<str> for Source {
+impl<'a> AsRef<str> for Source<'a> {
   fn as_ref(&self) -> &str {
-    &*self
-  }
-}
-
-impl Trace for Source {
-  fn trace(&self) {
-    self.content.trace()
-  }
-
-  fn trace_debug(&self, log: &mut dyn std::io::Write) {
-    self.content.trace_debug(log)
+    self.content
   }
 }
 
 #[cfg(test)]
 mod test {
   use super::*;
-  use laythe_core::memory::{Allocator, NO_GC};
 
   #[test]
   fn new() {
-    let mut gc = Allocator::default();
     let content = "print('example');";
-
-    let content = gc.manage_str(content, &NO_GC);
     let src = Source::new(content);
 
-    assert_eq!(&*src, &*content)
+    assert_eq!(&*src, content)
   }
 }
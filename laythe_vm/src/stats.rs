@@ -0,0 +1,105 @@
+use crate::byte_code::{ByteCode, OP_CODES};
+use laythe_core::{
+  managed::{GcObj, Trace},
+  object::Fun,
+};
+use std::{
+  collections::HashMap,
+  io::{self, Write},
+};
+
+/// Counts of the instructions executed during a run, both per op code
+/// and per call site, used to find where new instructions or caches pay off
+pub struct ExecutionStats {
+  /// The number of times each op code was executed indexed by its byte
+  op_codes: Vec<u64>,
+
+  /// The call instruction at each call site and the number of times it
+  /// was executed keyed by its function and offset
+  call_sites: HashMap<(GcObj<Fun>, usize), (ByteCode, u64)>,
+}
+
+impl Default for ExecutionStats {
+  fn default() -> Self {
+    Self {
+      op_codes: vec![0; OP_CODES.len()],
+      call_sites: HashMap::new(),
+    }
+  }
+}
+
+impl ExecutionStats {
+  /// Discard anything counted so far
+  pub fn reset(&mut self) {
+    self.op_codes.iter_mut().for_each(|count| *count = 0);
+    self.call_sites.clear();
+  }
+
+  /// Count an execution of `op_code` at `offset` in `fun`
+  #[inline]
+  pub fn record(&mut self, op_code: ByteCode, fun: GcObj<Fun>, offset: usize) {
+    self.op_codes[op_code as usize] += 1;
+
+    if let ByteCode::Call | ByteCode::Invoke | ByteCode::SuperInvoke | ByteCode::InvokeSealed =
+      op_code
+    {
+      self
+        .call_sites
+        .entry((fun, offset))
+        .or_insert((op_code, 0))
+        .1 += 1;
+    }
+  }
+
+  /// Write the op code counts followed by the call site counts, each
+  /// from most to least executed. Call sites are named by `site`
+  pub fn write(
+    &self,
+    out: &mut dyn Write,
+    site: impl Fn(GcObj<Fun>, usize) -> String,
+  ) -> io::Result<()> {
+    let total: u64 = self.op_codes.iter().sum();
+
+    let mut op_codes: Vec<(&str, u64)> = OP_CODES
+      .iter()
+      .zip(self.op_codes.iter())
+      .filter(|(_, count)| **count > 0)
+      .map(|(info, count)| (info.name, *count))
+      .collect();
+    op_codes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    writeln!(out, "Op codes executed: {}", total)?;
+    for (name, count) in op_codes {
+      let percent = count as f64 / total as f64 * 100.0;
+      writeln!(out, "  {:<20} {:>12} {:>6.2}%", name, count, percent)?;
+    }
+
+    let mut call_sites: Vec<(String, &str, u64)> = self
+      .call_sites
+      .iter()
+      .map(|((fun, offset), (op_code, count))| (site(*fun, *offset), op_code.info().name, *count))
+      .collect();
+    call_sites.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+
+    writeln!(out, "Call sites: {}", call_sites.len())?;
+    for (site, name, count) in call_sites {
+      writeln!(out, "  {:<40} {:<12} {:>12}", site, name, count)?;
+    }
+
+    Ok(())
+  }
+
+  /// Trace every function holding a counted call site
+  pub fn trace(&self) {
+    self.call_sites.keys().for_each(|(fun, _)| {
+      fun.trace();
+    });
+  }
+
+  /// Trace every function holding a counted call site with debug logging
+  pub fn trace_debug(&self, log: &mut dyn Write) {
+    self.call_sites.keys().for_each(|(fun, _)| {
+      fun.trace_debug(log);
+    });
+  }
+}
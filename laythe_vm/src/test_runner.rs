@@ -0,0 +1,136 @@
+use crate::vm::VmBuilder;
+use std::{
+  fs,
+  io::{self, Write},
+  path::{Path, PathBuf},
+};
+
+/// The suffix of a file containing tests
+const TEST_SUFFIX: &str = "_test.lay";
+
+/// The totals of a test run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestSummary {
+  /// The number of test files run
+  pub files: usize,
+
+  /// The number of tests that passed
+  pub passed: usize,
+
+  /// The number of tests that failed
+  pub failed: usize,
+
+  /// The number of files that did not run to completion
+  pub errored: usize,
+}
+
+impl TestSummary {
+  /// Did every test pass and every file run to completion
+  pub fn is_success(&self) -> bool {
+    self.failed == 0 && self.errored == 0
+  }
+}
+
+/// Find the test files in the provided paths. Directories are searched
+/// recursively for files ending in `_test.lay` while files are taken as is
+pub fn discover(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+  let mut files = vec![];
+
+  for path in paths {
+    if path.is_dir() {
+      discover_dir(path, &mut files)?;
+    } else {
+      files.push(path.clone());
+    }
+  }
+
+  files.sort();
+  files.dedup();
+  Ok(files)
+}
+
+/// Collect the test files in this directory, skipping hidden directories
+fn discover_dir(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    let name = match path.file_name().and_then(|name| name.to_str()) {
+      Some(name) => name,
+      None => continue,
+    };
+
+    if path.is_dir() {
+      if !name.starts_with('.') {
+        discover_dir(&path, files)?;
+      }
+    } else if name.ends_with(TEST_SUFFIX) {
+      files.push(path);
+    }
+  }
+
+  Ok(())
+}
+
+/// Run each test file on a fresh vm configured from `builder`, reporting
+/// progress and the final totals to `output`
+pub fn run(
+  builder: &VmBuilder,
+  files: &[PathBuf],
+  output: &mut dyn Write,
+) -> io::Result<TestSummary> {
+  let mut summary = TestSummary::default();
+
+  for file in files {
+    writeln!(output, "running {}", file.display())?;
+    output.flush()?;
+    summary.files += 1;
+
+    let source = match fs::read_to_string(file) {
+      Ok(source) => source,
+      Err(err) => {
+        writeln!(output, "error: {}", err)?;
+        summary.errored += 1;
+        continue;
+      },
+    };
+
+    let mut vm = match builder.clone().build() {
+      Ok(vm) => vm,
+      Err(error) => {
+        writeln!(output, "error: {}", error)?;
+        summary.errored += 1;
+        continue;
+      },
+    };
+    let result = vm.run(file.clone(), &source);
+
+    if let Some(results) = vm.test_results() {
+      summary.passed += results.passed;
+      summary.failed += results.failed;
+    }
+
+    if let Err(error) = &result {
+      vm.report(error);
+    }
+
+    if result != Ok(0) {
+      writeln!(
+        output,
+        "error: {} did not run to completion",
+        file.display()
+      )?;
+      summary.errored += 1;
+    }
+  }
+
+  writeln!(
+    output,
+    "\ntest result: {}. {} passed; {} failed; {} of {} files errored",
+    if summary.is_success() { "ok" } else { "FAILED" },
+    summary.passed,
+    summary.failed,
+    summary.errored,
+    summary.files,
+  )?;
+
+  Ok(summary)
+}
@@ -0,0 +1,192 @@
+use std::{mem, time::Duration};
+
+/// The number of slots in a timer wheel
+const SLOTS: usize = 256;
+
+/// The span of time covered by each slot of a timer wheel
+const RESOLUTION: Duration = Duration::from_millis(1);
+
+/// An entry in a timer wheel
+struct Entry<T> {
+  /// The time since vm startup this entry expires
+  deadline: Duration,
+
+  /// The value held until the deadline
+  value: T,
+}
+
+/// A hashed timer wheel. Entries are placed in the slot for the tick of
+/// their deadline so expiring only visits the slots for the ticks that
+/// have passed since the wheel was last advanced
+pub struct TimerWheel<T> {
+  /// Entries bucketed by the tick of their deadline
+  slots: Vec<Vec<Entry<T>>>,
+
+  /// The tick the wheel was last advanced to
+  current: u64,
+
+  /// The number of entries in the wheel
+  len: usize,
+}
+
+impl<T> Default for TimerWheel<T> {
+  fn default() -> Self {
+    Self {
+      slots: (0..SLOTS).map(|_| vec![]).collect(),
+      current: 0,
+      len: 0,
+    }
+  }
+}
+
+impl<T> TimerWheel<T> {
+  /// Is this wheel empty
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Add a value to the wheel expiring at the provided deadline
+  pub fn insert(&mut self, deadline: Duration, value: T) {
+    // an entry already past due is placed in the current slot
+    let tick = tick(deadline).max(self.current);
+
+    self.slots[slot(tick)].push(Entry { deadline, value });
+    self.len += 1;
+  }
+
+  /// Remove every entry whose deadline has passed, ordered by deadline
+  pub fn expire(&mut self, now: Duration) -> Vec<(Duration, T)> {
+    let now_tick = tick(now);
+    if self.len == 0 {
+      self.current = self.current.max(now_tick);
+      return vec![];
+    }
+
+    // every slot is visited once at most however far time has advanced
+    let visits = (now_tick.saturating_sub(self.current) as usize + 1).min(SLOTS);
+
+    let mut expired = vec![];
+    for offset in 0..visits {
+      let slot = &mut self.slots[slot(self.current + offset as u64)];
+      if slot.iter().all(|entry| entry.deadline > now) {
+        continue;
+      }
+
+      let (due, pending): (Vec<Entry<T>>, Vec<Entry<T>>) = mem::take(slot)
+        .into_iter()
+        .partition(|entry| entry.deadline <= now);
+
+      *slot = pending;
+      expired.extend(due.into_iter().map(|entry| (entry.deadline, entry.value)));
+    }
+
+    self.current = self.current.max(now_tick);
+    self.len -= expired.len();
+    expired.sort_by_key(|(deadline, _)| *deadline);
+    expired
+  }
+
+  /// Keep only the entries for which the predicate returns true
+  pub fn retain(&mut self, mut predicate: impl FnMut(&T) -> bool) {
+    for slot in &mut self.slots {
+      slot.retain(|entry| predicate(&entry.value));
+    }
+
+    self.len = self.slots.iter().map(Vec::len).sum();
+  }
+
+  /// Remove every entry from the wheel
+  pub fn clear(&mut self) {
+    self.slots.iter_mut().for_each(Vec::clear);
+    self.len = 0;
+  }
+
+  /// Iterate every entry in the wheel in no particular order
+  pub fn iter(&self) -> impl Iterator<Item = (Duration, &T)> {
+    self
+      .slots
+      .iter()
+      .flat_map(|slot| slot.iter().map(|entry| (entry.deadline, &entry.value)))
+  }
+}
+
+/// The tick a time falls in
+fn tick(time: Duration) -> u64 {
+  (time.as_nanos() / RESOLUTION.as_nanos()) as u64
+}
+
+/// The slot a tick is placed in
+fn slot(tick: u64) -> usize {
+  (tick % SLOTS as u64) as usize
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+  }
+
+  #[test]
+  fn expire_in_deadline_order() {
+    let mut wheel = TimerWheel::default();
+    wheel.insert(ms(30), 'c');
+    wheel.insert(ms(10), 'a');
+    wheel.insert(ms(20), 'b');
+
+    assert!(wheel.expire(ms(5)).is_empty());
+
+    let expired: Vec<char> = wheel.expire(ms(25)).into_iter().map(|(_, v)| v).collect();
+    assert_eq!(expired, vec!['a', 'b']);
+    assert!(!wheel.is_empty());
+
+    let expired: Vec<char> = wheel.expire(ms(30)).into_iter().map(|(_, v)| v).collect();
+    assert_eq!(expired, vec!['c']);
+    assert!(wheel.is_empty());
+  }
+
+  #[test]
+  fn expire_across_rotations() {
+    let mut wheel = TimerWheel::default();
+    wheel.insert(ms(SLOTS as u64 * 3 + 7), 2);
+    wheel.insert(ms(7), 1);
+
+    let expired: Vec<i32> = wheel.expire(ms(8)).into_iter().map(|(_, v)| v).collect();
+    assert_eq!(expired, vec![1]);
+
+    // the slot is shared with the first entry but its deadline is later
+    assert!(wheel.expire(ms(SLOTS as u64 + 7)).is_empty());
+
+    let expired: Vec<i32> = wheel
+      .expire(ms(SLOTS as u64 * 10))
+      .into_iter()
+      .map(|(_, v)| v)
+      .collect();
+    assert_eq!(expired, vec![2]);
+  }
+
+  #[test]
+  fn insert_past_due() {
+    let mut wheel = TimerWheel::default();
+    assert!(wheel.expire(ms(100)).is_empty());
+
+    wheel.insert(ms(50), 'a');
+    let expired = wheel.expire(ms(100));
+    assert_eq!(expired, vec![(ms(50), 'a')]);
+  }
+
+  #[test]
+  fn retain() {
+    let mut wheel = TimerWheel::default();
+    wheel.insert(ms(1), 1);
+    wheel.insert(ms(2), 2);
+    wheel.insert(ms(3), 3);
+
+    wheel.retain(|value| *value != 2);
+    let expired: Vec<i32> = wheel.expire(ms(3)).into_iter().map(|(_, v)| v).collect();
+    assert_eq!(expired, vec![1, 3]);
+    assert!(wheel.is_empty());
+  }
+}
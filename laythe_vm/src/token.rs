@@ -114,6 +114,7 @@ pub enum TokenKind {
   SlashEqual,
   StarEqual,
   Arrow,
+  FatArrow,
 
   // modules
   Export,
@@ -142,6 +143,7 @@ pub enum TokenKind {
   // keywords
   And,
   Class,
+  Extend,
   Else,
   False,
   For,
@@ -151,18 +153,24 @@ pub enum TokenKind {
   Nil,
   Or,
   Return,
+  Raise,
   Break,
   Continue,
   Super,
   Self_,
   Static,
+  Sealed,
   True,
   Let,
+  Const,
   While,
   Try,
   Catch,
+  With,
   Trait,
   Type,
+  Async,
+  Await,
 
   // meta
   Error,
@@ -192,6 +200,7 @@ impl fmt::Display for TokenKind {
       TokenKind::SlashEqual => "/=",
       TokenKind::StarEqual => "*=",
       TokenKind::Arrow => "->",
+      TokenKind::FatArrow => "=>",
       TokenKind::Export => "export",
       TokenKind::Import => "import",
       TokenKind::As => "as",
@@ -212,6 +221,7 @@ impl fmt::Display for TokenKind {
       TokenKind::Number => "number",
       TokenKind::And => "and",
       TokenKind::Class => "class",
+      TokenKind::Extend => "extend",
       TokenKind::Else => "else",
       TokenKind::False => "false",
       TokenKind::For => "for",
@@ -221,18 +231,24 @@ impl fmt::Display for TokenKind {
       TokenKind::Nil => "nil",
       TokenKind::Or => "or",
       TokenKind::Return => "return",
+      TokenKind::Raise => "raise",
       TokenKind::Break => "break",
       TokenKind::Continue => "continue",
       TokenKind::Super => "super",
       TokenKind::Self_ => "self",
       TokenKind::Static => "static",
+      TokenKind::Sealed => "sealed",
       TokenKind::True => "true",
       TokenKind::Let => "let",
+      TokenKind::Const => "const",
       TokenKind::While => "while",
       TokenKind::Try => "try",
       TokenKind::Catch => "catch",
+      TokenKind::With => "with",
       TokenKind::Trait => "trait",
       TokenKind::Type => "type",
+      TokenKind::Async => "async",
+      TokenKind::Await => "await",
       TokenKind::Error => "error",
       TokenKind::Eof => "eof",
     })
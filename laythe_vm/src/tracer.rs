@@ -0,0 +1,180 @@
+use std::path::Path;
+
+/// The kinds of vm events that can be traced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TraceEvents {
+  /// Each instruction along with the current stack
+  pub instructions: bool,
+
+  /// Each call frame pushed and popped
+  pub calls: bool,
+
+  /// Each garbage collection
+  pub gc: bool,
+
+  /// Each send, receive and close on a channel
+  pub channels: bool,
+}
+
+impl TraceEvents {
+  /// Trace every kind of event
+  pub fn all() -> Self {
+    Self {
+      instructions: true,
+      calls: true,
+      gc: true,
+      channels: true,
+    }
+  }
+
+  /// Are no events traced
+  pub fn is_empty(&self) -> bool {
+    *self == Self::default()
+  }
+
+  /// Are any events observed between instructions. Calls and
+  /// channels are instead traced where they occur
+  pub fn per_instruction(&self) -> bool {
+    self.instructions || self.gc
+  }
+
+  /// Trace the events traced by either
+  pub fn union(self, other: Self) -> Self {
+    Self {
+      instructions: self.instructions || other.instructions,
+      calls: self.calls || other.calls,
+      gc: self.gc || other.gc,
+      channels: self.channels || other.channels,
+    }
+  }
+
+  /// Parse a comma separated list of event kinds, one of instructions,
+  /// calls, gc, channels or all
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_vm::tracer::TraceEvents;
+  ///
+  /// let events = TraceEvents::parse("calls,gc").unwrap();
+  /// assert!(events.calls && events.gc);
+  /// assert!(!events.instructions && !events.channels);
+  ///
+  /// assert!(TraceEvents::parse("calls,frames").is_none());
+  /// ```
+  pub fn parse(events: &str) -> Option<Self> {
+    let mut parsed = Self::default();
+
+    for event in events.split(',').map(str::trim) {
+      match event {
+        "instructions" => parsed.instructions = true,
+        "calls" => parsed.calls = true,
+        "gc" => parsed.gc = true,
+        "channels" => parsed.channels = true,
+        "all" => parsed = Self::all(),
+        _ => return None,
+      }
+    }
+
+    Some(parsed)
+  }
+}
+
+/// Which vm events are traced and where. Events are only reported while
+/// running a function that passes the module and function filters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceConfig {
+  /// The kinds of events traced
+  pub events: TraceEvents,
+
+  /// Only trace functions in modules whose path ends with one of these
+  /// paths. Every module is traced when empty
+  pub modules: Vec<String>,
+
+  /// Only trace functions with one of these names. Every function is
+  /// traced when empty
+  pub functions: Vec<String>,
+}
+
+impl TraceConfig {
+  /// Trace the provided kinds of events in every function
+  pub fn new(events: TraceEvents) -> Self {
+    Self {
+      events,
+      modules: vec![],
+      functions: vec![],
+    }
+  }
+
+  /// Only trace functions in modules whose path ends with this path
+  pub fn module(mut self, module: &str) -> Self {
+    self.modules.push(module.to_string());
+    self
+  }
+
+  /// Only trace functions with this name
+  pub fn function(mut self, function: &str) -> Self {
+    self.functions.push(function.to_string());
+    self
+  }
+
+  /// Should events in this function of this module be traced
+  ///
+  /// # Examples
+  /// ```
+  /// use laythe_vm::tracer::{TraceConfig, TraceEvents};
+  /// use std::path::Path;
+  ///
+  /// let config = TraceConfig::new(TraceEvents::all())
+  ///   .module("src/main.lay")
+  ///   .function("fib");
+  ///
+  /// assert!(config.matches(Path::new("/project/src/main.lay"), "fib"));
+  /// assert!(!config.matches(Path::new("/project/src/main.lay"), "run"));
+  /// assert!(!config.matches(Path::new("/project/src/other.lay"), "fib"));
+  /// ```
+  pub fn matches(&self, module: &Path, function: &str) -> bool {
+    let module_matches =
+      self.modules.is_empty() || self.modules.iter().any(|filter| module.ends_with(filter));
+    let function_matches =
+      self.functions.is_empty() || self.functions.iter().any(|filter| filter == function);
+
+    module_matches && function_matches
+  }
+}
+
+/// The tracer state of a vm
+pub(crate) struct Tracer {
+  /// What is traced
+  config: TraceConfig,
+
+  /// The number of collections already reported
+  collections: u128,
+}
+
+impl Tracer {
+  /// Create a new tracer with the provided configuration
+  pub fn new(config: TraceConfig) -> Self {
+    Self {
+      config,
+      collections: 0,
+    }
+  }
+
+  /// The kinds of events traced
+  pub fn events(&self) -> TraceEvents {
+    self.config.events
+  }
+
+  /// Should events in this function of this module be traced
+  pub fn matches(&self, module: &Path, function: &str) -> bool {
+    self.config.matches(module, function)
+  }
+
+  /// Note the heap's collection count returning true if a
+  /// collection occurred since the last time it was noted
+  pub fn collected(&mut self, collections: u128) -> bool {
+    let collected = collections > self.collections;
+    self.collections = collections;
+    collected
+  }
+}
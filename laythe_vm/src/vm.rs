@@ -1,73 +1,130 @@
 use crate::{
+  arena::Arena,
+  ast::{self, Visitor},
+  ast_json::AstJson,
+  budget::{Budget, Tick},
   byte_code::{AlignedByteCode, ByteCode, UpvalueIndex},
   cache::InlineCache,
-  compiler::{Compiler, Parser},
-  constants::{MAX_FRAME_SIZE, REPL_MODULE},
+  chunk_cache::ChunkCache,
+  compiler::{is_incomplete, Compiler, Linter, Parser, Scanner, SymbolTable, TypeChecker},
+  constants::{
+    DEFAULT_FRAME_LIMIT, DEFAULT_STACK_SIZE, REPL_CONTINUATION_PROMPT, REPL_MODULE, REPL_PROMPT,
+  },
+  debug::{disassemble_fun, disassemble_instruction, fun_json},
+  debugger::{Breakpoint, Breakpoints, DebugSession, Debugger, FrameInfo, Location, Paused},
+  diagnostics,
+  embed::{host_fun, HostRoots, IntoValue, LyError, NativeClassBuilder, Rooted},
+  manifest::{Manifest, MANIFEST},
+  plugin,
+  pool::{ThreadConfig, WorkerPool},
+  profiler::Profiler,
+  scheduler::Scheduler,
+  signal::SignalHandlers,
+  snapshot::{self, Externs, ModuleKey, Snapshot, SnapshotModule},
   source::{Source, VmFileId, VmFiles},
+  stats::ExecutionStats,
+  tracer::{TraceConfig, Tracer},
   FeResult,
 };
-use codespan_reporting::term::{self, Config};
+use codespan_reporting::diagnostic::Diagnostic;
 use laythe_core::{
-  constants::{PLACEHOLDER_NAME, SELF},
-  hooks::{GcContext, GcHooks, HookContext, Hooks, NoContext, ValueContext},
+  chunk::SourceSpan,
+  constants::{ON_MISSING_METHOD, PLACEHOLDER_NAME, SCRIPT, SELF},
+  hooks::{GcContext, GcHooks, HookContext, Hooks, HostData, NoContext, ValueContext},
   if_let_obj,
   managed::{Gc, GcObj, GcObject, GcStr, Manage, Object, Trace, TraceRoot},
   match_obj,
-  memory::Allocator,
-  module::{Import, Module, Package},
+  memory::{Allocator, GC_HEAP_GROW_FACTOR, INITIAL_GC},
+  module::{Import, Module, ModuleResult, Package},
   object::{
-    Class, Closure, Fiber, Fun, FunBuilder, Instance, List, Map, Method, Native, NativeMeta,
-    ObjectKind, Upvalue,
+    Channel, Class, Closure, Fiber, FiberState, Fun, FunBuilder, Instance, List, Map, Method,
+    Native, NativeMeta, ObjectKind, Tuple, Upvalue,
   },
+  plugin::PluginInit,
   signature::{ArityError, Environment, ParameterKind, SignatureError},
   to_obj_kind,
   utils::{is_falsey, IdEmitter},
   val,
   value::{Value, VALUE_NIL, VALUE_TRUE},
+  worker::{Message, PoolJob, WorkerHandle, WorkerPort},
   Call,
 };
-use laythe_env::io::Io;
-use laythe_lib::{builtin_from_module, create_std_lib, BuiltIn};
+use laythe_env::{
+  io::{Io, IoImpl},
+  resolver::ResolvedModule,
+  signal::ProcessSignal,
+  stdio::Stdio,
+};
+use laythe_lib::{builtin_from_module, create_std_lib, test_results, BuiltIn, TestResults, STD};
 use laythe_native::io::io_native;
-use std::io::Write;
+use std::fmt;
+use std::io::{self, Write};
+use std::iter;
 use std::mem;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::ptr;
+use std::rc::Rc;
+use std::slice;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 use std::{cell::RefCell, cmp::Ordering};
-use std::{convert::TryInto, usize};
-
-#[cfg(feature = "debug")]
-use crate::debug::{disassemble_instruction, exception_catch};
+use std::{
+  convert::{TryFrom, TryInto},
+  usize,
+};
 
-#[cfg(feature = "debug")]
-use std::io;
+#[cfg(feature = "threaded_dispatch")]
+use crate::byte_code::OP_CODES;
 
 #[cfg(feature = "debug")]
-use laythe_env::stdio::Stdio;
+use crate::debug::exception_catch;
 
 #[cfg(feature = "debug")]
 use laythe_core::call_frame::CallFrame;
 
+#[cfg(feature = "serde")]
+use laythe_core::serialize::ValueSeed;
+
 #[cfg(feature = "debug_upvalues")]
 use std::{cmp::Ordering, io};
 
 const VERSION: &str = "0.1.0";
 
+/// The field of an error instance holding its stack trace
+const ERROR_STACK_FIELD: usize = 1;
+
+/// The field of an error instance holding the error that caused it
+const ERROR_CAUSE_FIELD: usize = 2;
+
 #[derive(Debug, Clone, PartialEq)]
 enum Signal {
   Ok,
   OkReturn,
   Exit,
   RuntimeError,
+  InternalError,
+  Suspend,
+  Block,
+  AwaitInput,
 }
 
+pub use crate::budget::VmConfig;
+pub use crate::compiler::LintLevel;
+#[cfg(feature = "threaded_dispatch")]
+pub use crate::dispatch::Dispatch;
+pub use crate::error::{UncaughtError, VmError};
+pub use crate::interrupt::Interrupt;
+
 #[derive(Debug, Clone, PartialEq)]
-pub enum ExecuteResult {
+pub(crate) enum ExecuteResult {
   Ok(u16),
   FunResult(Value),
   InternalError,
   RuntimeError,
-  CompileError,
+  Timeout,
+  Interrupted,
+  AwaitingInput,
 }
 
 pub enum ExecuteMode {
@@ -75,8 +132,283 @@ pub enum ExecuteMode {
   CallFunction(usize),
 }
 
-pub fn default_native_vm() -> Vm {
-  Vm::new(io_native())
+/// The result of analyzing a script without running it
+#[derive(Debug, Clone)]
+pub struct Analysis {
+  /// The parse errors, lint warnings and compile errors of the script
+  pub diagnostics: Vec<Diagnostic<VmFileId>>,
+
+  /// The script's module symbols and their references
+  pub symbols: SymbolTable,
+}
+
+/// A method of a class exported by the standard library
+#[derive(Debug, Clone, PartialEq)]
+pub struct StdMethod {
+  /// The name of the class defining this method
+  pub class: String,
+
+  /// The name of the method
+  pub name: String,
+
+  /// Is this a static method on the class
+  pub is_static: bool,
+
+  /// The name and kind of each parameter of a native method
+  pub parameters: Vec<String>,
+}
+
+pub fn default_native_vm() -> Result<Vm, VmError> {
+  VmBuilder::default().build()
+}
+
+/// Configures and builds a new `Vm`
+#[derive(Clone)]
+pub struct VmBuilder {
+  /// The io the vm has access to. Defaults to native io
+  io: Option<Io>,
+
+  /// Limits placed on each run
+  config: VmConfig,
+
+  /// The maximum number of nested call frames
+  frame_limit: usize,
+
+  /// The initial number of slots reserved for each fiber's stack
+  stack_size: usize,
+
+  /// The heap size in bytes that triggers the first collection
+  initial_gc: usize,
+
+  /// The factor the heap may grow by between collections
+  gc_grow_factor: usize,
+
+  /// How lint warnings found in scripts are reported
+  lint_level: LintLevel,
+
+  /// Check scripts against their type annotations
+  type_check: bool,
+
+  /// Collect garbage on every allocation
+  gc_stress: bool,
+
+  /// The vm events traced to stdout if any
+  tracer: Option<TraceConfig>,
+
+  /// The debugger control is handed to when execution pauses
+  debugger: Option<Rc<RefCell<dyn Debugger>>>,
+
+  /// The breakpoints execution initially pauses at
+  breakpoints: Breakpoints,
+
+  /// Where a profile of each run is written
+  profile: Option<PathBuf>,
+
+  /// Count the instructions executed in each run
+  stats: bool,
+
+  /// The manifest of the package scripts are run in
+  manifest: Option<PathBuf>,
+
+  /// Cache compiled scripts on disk next to their source
+  chunk_cache: bool,
+
+  /// The plugin libraries loaded into the vm
+  plugins: Vec<PathBuf>,
+}
+
+impl Default for VmBuilder {
+  fn default() -> Self {
+    Self {
+      io: None,
+      config: VmConfig::default(),
+      frame_limit: DEFAULT_FRAME_LIMIT,
+      stack_size: DEFAULT_STACK_SIZE,
+      initial_gc: INITIAL_GC,
+      gc_grow_factor: GC_HEAP_GROW_FACTOR,
+      lint_level: LintLevel::default(),
+      type_check: false,
+      gc_stress: false,
+      tracer: None,
+      debugger: None,
+      breakpoints: Breakpoints::default(),
+      profile: None,
+      stats: false,
+      manifest: None,
+      chunk_cache: false,
+      plugins: vec![],
+    }
+  }
+}
+
+impl VmBuilder {
+  /// Set the io the vm uses
+  pub fn io(mut self, io: Io) -> Self {
+    self.io = Some(io);
+    self
+  }
+
+  /// Replace the stdio of the vm's io
+  pub fn stdio(mut self, stdio_impl: Arc<dyn IoImpl<Stdio>>) -> Self {
+    let io = self.io.take().unwrap_or_else(io_native);
+    self.io = Some(io.with_stdio(stdio_impl));
+    self
+  }
+
+  /// Set the arguments scripts see in place of the process arguments
+  pub fn args(mut self, args: Vec<String>) -> Self {
+    let io = self.io.take().unwrap_or_else(io_native);
+    self.io = Some(io.with_args(args));
+    self
+  }
+
+  /// Set the budgets and heap limit of each run
+  pub fn config(mut self, config: VmConfig) -> Self {
+    self.config = config;
+    self
+  }
+
+  /// Set the maximum number of nested call frames before
+  /// a stack overflow is raised
+  pub fn frame_limit(mut self, frame_limit: usize) -> Self {
+    self.frame_limit = frame_limit;
+    self
+  }
+
+  /// Set the initial number of slots reserved for each fiber's stack
+  pub fn stack_size(mut self, stack_size: usize) -> Self {
+    self.stack_size = stack_size;
+    self
+  }
+
+  /// Set the heap size in bytes that triggers the first collection
+  pub fn initial_gc(mut self, initial_gc: usize) -> Self {
+    self.initial_gc = initial_gc;
+    self
+  }
+
+  /// Set the factor the heap may grow by between collections
+  pub fn gc_grow_factor(mut self, gc_grow_factor: usize) -> Self {
+    self.gc_grow_factor = gc_grow_factor;
+    self
+  }
+
+  /// Set how lint warnings found in scripts are reported. `LintLevel::Deny`
+  /// promotes warnings to compile errors
+  pub fn lint_level(mut self, lint_level: LintLevel) -> Self {
+    self.lint_level = lint_level;
+    self
+  }
+
+  /// Check scripts against their type annotations before they are
+  /// compiled. Any mismatch is reported as a compile error
+  pub fn type_check(mut self, type_check: bool) -> Self {
+    self.type_check = type_check;
+    self
+  }
+
+  /// Collect garbage on every allocation. This is very slow but
+  /// quickly surfaces values that are not properly rooted
+  pub fn gc_stress(mut self, gc_stress: bool) -> Self {
+    self.gc_stress = gc_stress;
+    self
+  }
+
+  /// Print the stack and each instruction to stdout as it is executed
+  pub fn trace(mut self, trace: bool) -> Self {
+    let mut config = self.tracer.take().unwrap_or_default();
+    config.events.instructions = trace;
+    self.tracer = Some(config);
+    self
+  }
+
+  /// Trace the configured events to stdout as they occur
+  pub fn tracer(mut self, config: TraceConfig) -> Self {
+    self.tracer = Some(config);
+    self
+  }
+
+  /// Attach a debugger that is handed control whenever execution pauses
+  pub fn debugger(mut self, debugger: Rc<RefCell<dyn Debugger>>) -> Self {
+    self.debugger = Some(debugger);
+    self
+  }
+
+  /// Pause execution at a breakpoint. Only used with an attached debugger
+  pub fn breakpoint(mut self, breakpoint: Breakpoint) -> Self {
+    self.breakpoints.add(breakpoint);
+    self
+  }
+
+  /// Profile each run writing the time spent in each call stack
+  /// to the provided path in the collapsed stack format
+  pub fn profile(mut self, path: PathBuf) -> Self {
+    self.profile = Some(path);
+    self
+  }
+
+  /// Count the instructions executed per op code and per call site,
+  /// writing the counts to stderr once each run completes
+  pub fn stats(mut self, stats: bool) -> Self {
+    self.stats = stats;
+    self
+  }
+
+  /// Run scripts in the package described by the provided `laythe.toml`
+  /// manifest, making its dependencies importable as `name/module`
+  pub fn manifest(mut self, path: PathBuf) -> Self {
+    self.manifest = Some(path);
+    self
+  }
+
+  /// Cache compiled scripts in a `.laythe-cache` directory next to each
+  /// script so unchanged scripts skip compilation on later runs
+  pub fn chunk_cache(mut self, chunk_cache: bool) -> Self {
+    self.chunk_cache = chunk_cache;
+    self
+  }
+
+  /// Load a plugin from the dynamic library at the provided path once
+  /// the vm is built. See `Vm::load_plugin`
+  pub fn plugin(mut self, path: PathBuf) -> Self {
+    self.plugins.push(path);
+    self
+  }
+
+  /// Build the configured vm, failing if the environment it runs in
+  /// could not be read or its standard library could not be created
+  pub fn build(self) -> Result<Vm, VmError> {
+    Vm::from_builder(self)
+  }
+}
+
+/// A vm that can be sent to another thread, created by `Vm::into_send`.
+///
+/// Each vm owns its heap. Every gc pointer held by the vm, its fibers,
+/// modules and caches points into its own allocator, and the string
+/// intern table is per allocator, so moving the vm moves everything those
+/// pointers reach. The vm is still not `Send` on its own as the host can
+/// share state with it that is not thread safe. `Vm::into_send` checks
+/// what it can of this:
+///
+/// - no `Rooted` handles to the vm's values are alive
+/// - no debugger is attached
+/// - no host data is set
+///
+/// The io facilities and native classes are required to be `Send` by their
+/// traits. Natives the host implements directly must not share state with
+/// the thread the vm left
+pub struct SendVm(Vm);
+
+// The vm only shares state with the host through the handles checked
+// by `Vm::into_send`, see `SendVm`
+unsafe impl Send for SendVm {}
+
+impl SendVm {
+  /// Take the vm back on the thread it was sent to
+  pub fn into_inner(self) -> Vm {
+    self.0
+  }
 }
 
 /// The virtual machine for the laythe programming language
@@ -84,6 +416,15 @@ pub struct Vm {
   /// The current running fiber
   fiber: GcObj<Fiber>,
 
+  /// The fiber running the main script
+  main_fiber: GcObj<Fiber>,
+
+  /// The scheduler for fibers not currently running
+  scheduler: Scheduler,
+
+  /// The execution budget of the current run
+  budget: Budget,
+
   /// The vm's garbage collector
   gc: RefCell<Allocator>,
 
@@ -102,12 +443,22 @@ pub struct Vm {
   /// A collection of packages that have already been loaded
   packages: Map<GcStr, Gc<Package>>,
 
+  /// The manifest of the package scripts are run in
+  manifest: Option<PathBuf>,
+
+  /// Are compiled scripts cached on disk
+  chunk_cache: bool,
+
   /// A utility to emit ids for modules
   emitter: IdEmitter,
 
   /// A cache for full filepath to individual modules
   module_cache: Map<GcStr, Gc<Module>>,
 
+  /// The modules still being initialized, from the main script to the
+  /// innermost import in progress
+  import_stack: Vec<Gc<Module>>,
+
   /// Inline caches for each module
   inline_cache: Vec<InlineCache>,
 
@@ -120,9 +471,68 @@ pub struct Vm {
   /// What exit code is currently set
   exit_code: u16,
 
+  /// Callables run in reverse registration order on a clean shutdown
+  exit_hooks: Vec<Value>,
+
+  /// Callables run on the main fiber when the process receives a signal
+  signals: SignalHandlers,
+
+  /// Set by the host to interrupt execution
+  interrupt: Interrupt,
+
+  /// The port to the vm that spawned this one if it is running in a worker
+  worker: Option<WorkerPort>,
+
+  /// The pool of worker threads started by the first parallel call if any
+  pool: Option<WorkerPool>,
+
+  /// State the host provided for its native functions if any
+  host_data: Option<Box<dyn HostData>>,
+
+  /// Values the host holds through `Rooted` handles
+  host_roots: Rc<RefCell<HostRoots>>,
+
+  /// The error that stopped the main fiber if any
+  uncaught: Option<UncaughtError>,
+
+  /// The internal issue that stopped execution if any
+  internal: Option<String>,
+
+  /// Checks each instruction's declared stack effect when set
+  #[cfg(test)]
+  stack_audit: Option<test::StackAudit>,
+
   /// pointer to the current instruction
   ip: *const u8,
 
+  /// The maximum number of nested call frames
+  frame_limit: usize,
+
+  /// The initial number of slots reserved for each fiber's stack
+  stack_size: usize,
+
+  /// How lint warnings found in scripts are reported
+  lint_level: LintLevel,
+
+  /// Check scripts against their type annotations
+  type_check: bool,
+
+  /// The tracer reporting vm events if any
+  tracer: Option<Tracer>,
+
+  /// The attached debugger if any
+  debug: Option<DebugSession>,
+
+  /// The profiler recording the time spent in each call stack if any
+  profiler: Option<Profiler>,
+
+  /// The instruction counts of the current run if any
+  stats: Option<ExecutionStats>,
+
+  /// How decoded instructions are dispatched to their handlers
+  #[cfg(feature = "threaded_dispatch")]
+  dispatch: Dispatch,
+
   /// TODO replace this. A fun to fill a call frame for higher order native functions
   /// may want to eventually have a function rental so native functions can set name / module
   /// for exception
@@ -130,56 +540,355 @@ pub struct Vm {
 }
 
 impl Vm {
-  pub fn new(io: Io) -> Vm {
-    let gc = Allocator::new(io.stdio());
+  /// Create a new vm with the default configuration using the provided io
+  pub fn new(io: Io) -> Result<Vm, VmError> {
+    VmBuilder::default().io(io).build()
+  }
+
+  /// Create a builder to configure a new vm
+  pub fn builder() -> VmBuilder {
+    VmBuilder::default()
+  }
+
+  fn from_builder(builder: VmBuilder) -> Result<Vm, VmError> {
+    let VmBuilder {
+      io,
+      config,
+      frame_limit,
+      stack_size,
+      initial_gc,
+      gc_grow_factor,
+      lint_level,
+      type_check,
+      gc_stress,
+      tracer,
+      debugger,
+      breakpoints,
+      profile,
+      stats,
+      manifest,
+      chunk_cache,
+      plugins,
+    } = builder;
+
+    let io = io.unwrap_or_else(io_native);
+    let budget = Budget::new(config, io.time());
+
+    // the out of memory error is raised once the budget is woken
+    let wake = budget.wake_handle();
+    let gc = Allocator::new(io.stdio(), config.max_heap_bytes)
+      .with_thresholds(initial_gc, gc_grow_factor)
+      .with_stress(gc_stress)
+      .with_out_of_memory_hook(move || wake.wake());
     let no_gc_context = NoContext::new(gc);
     let hooks = GcHooks::new(&no_gc_context);
 
-    let root_dir = io
-      .env()
-      .current_dir()
-      .expect("Could not obtain the current working directory.");
+    let root_dir = io.env().current_dir()?;
 
     let mut emitter = IdEmitter::default();
-    let std_lib = create_std_lib(&hooks, &mut emitter).expect("Standard library creation failed");
+    let std_lib = create_std_lib(&hooks, &mut emitter).map_err(|error| {
+      VmError::Internal(format!(
+        "Unable to create the standard library. {:?}",
+        error
+      ))
+    })?;
     let global = std_lib.root_module();
 
     let builder = FunBuilder::new(hooks.manage_str(PLACEHOLDER_NAME), global);
     let managed_fun = hooks.manage_obj(builder.build());
 
-    let builtin = builtin_from_module(&hooks, &global)
-      .expect("Failed to generate builtin class from global module");
+    let builtin = builtin_from_module(&hooks, &global).ok_or_else(|| {
+      VmError::Internal("Unable to find the builtin classes in the global module.".to_string())
+    })?;
 
     let mut native_builder = FunBuilder::new(hooks.manage_str("native"), global);
-    native_builder.write_instruction(AlignedByteCode::Nil, 0);
+    native_builder.write_instruction(AlignedByteCode::Nil, SourceSpan::default());
 
     let native_fun_stub = hooks.manage_obj(native_builder.build());
 
+    // start on a fiber running the native stub so the vm's roots are
+    // valid if a collection occurs before the first script is run
+    let stub = hooks.manage_obj(Closure::without_upvalues(native_fun_stub));
+    let fiber = match Fiber::with_stack_size(stub, stack_size) {
+      Ok(fiber) => hooks.manage_obj(fiber),
+      Err(_) => {
+        return Err(VmError::Internal(
+          "Unable to create the initial fiber.".to_string(),
+        ))
+      },
+    };
+
     let gc = RefCell::new(no_gc_context.done());
     let inline_cache: Vec<InlineCache> = (0..emitter.id_count())
       .map(|_| InlineCache::new(0, 0))
       .collect();
 
+    let scheduler = Scheduler::new(io.time());
+    let signals = SignalHandlers::new(io.signals());
+    let profiler = profile.map(|path| Profiler::new(path, io.time()));
+
+    let mut tracer = tracer.unwrap_or_default();
+    tracer.events.instructions |= cfg!(feature = "debug");
+    let tracer = (!tracer.events.is_empty()).then(|| Tracer::new(tracer));
+
     let mut vm = Vm {
       io,
-      fiber: GcObj::dangling(),
+      fiber,
+      main_fiber: fiber,
+      scheduler,
+      budget,
       gc,
       files: VmFiles::default(),
       builtin,
       root_dir,
       packages: Map::default(),
+      manifest,
+      chunk_cache,
       emitter,
       module_cache: Map::default(),
+      import_stack: vec![],
       inline_cache,
       global,
       current_fun: managed_fun,
       exit_code: 0,
+      exit_hooks: vec![],
+      signals,
+      interrupt: Interrupt::new(),
+      worker: None,
+      pool: None,
+      host_data: None,
+      host_roots: Rc::default(),
+      uncaught: None,
+      internal: None,
+      #[cfg(test)]
+      stack_audit: None,
       ip: ptr::null(),
+      frame_limit,
+      stack_size,
+      lint_level,
+      type_check,
+      tracer,
+      debug: debugger.map(|debugger| DebugSession::new(debugger, breakpoints)),
+      profiler,
+      stats: stats.then(ExecutionStats::default),
+      #[cfg(feature = "threaded_dispatch")]
+      dispatch: Dispatch::default(),
       native_fun_stub,
     };
     vm.add_package(std_lib);
 
-    vm
+    for plugin in &plugins {
+      vm.load_plugin(plugin)?;
+    }
+
+    Ok(vm)
+  }
+
+  /// A handle the host can use to interrupt this vm
+  pub fn interrupt_handle(&self) -> Interrupt {
+    self.interrupt.clone()
+  }
+
+  /// Replace the handle used to interrupt this vm
+  pub(crate) fn set_interrupt(&mut self, interrupt: Interrupt) {
+    self.interrupt = interrupt;
+  }
+
+  /// The configuration a vm on another thread is built with
+  pub(crate) fn thread_config(&self) -> ThreadConfig {
+    ThreadConfig {
+      io: self.io.clone(),
+      frame_limit: self.frame_limit,
+      stack_size: self.stack_size,
+      lint_level: self.lint_level,
+      type_check: self.type_check,
+      manifest: self.manifest.clone(),
+      chunk_cache: self.chunk_cache,
+    }
+  }
+
+  /// Prepare this vm to be moved to another thread. This fails returning
+  /// the vm while the host holds `Rooted` handles to its values, a debugger
+  /// is attached or host data is set, as each may share state with the
+  /// current thread
+  #[allow(clippy::result_large_err)]
+  pub fn into_send(self) -> Result<SendVm, Vm> {
+    if Rc::strong_count(&self.host_roots) > 1 || self.debug.is_some() || self.host_data.is_some() {
+      return Err(self);
+    }
+
+    Ok(SendVm(self))
+  }
+
+  /// Capture the main module and the modules imported from source by
+  /// scripts run on this vm, along with every value they hold. Restoring
+  /// the snapshot with `Vm::restore` skips loading them again. Values of
+  /// native packages such as std are looked up again rather than copied
+  pub fn snapshot(&self) -> Result<Vec<u8>, VmError> {
+    let externs = Externs::collect(self.packages.values());
+
+    let mut modules: Vec<SnapshotModule> = self
+      .module_cache
+      .iter()
+      .map(|(key, module)| self.snapshot_module(ModuleKey::Cached(String::from(&**key)), *module))
+      .collect();
+
+    let main = self.gc.borrow().get_str(SELF);
+    if let Some(package) = main.and_then(|main| self.packages.get(&main)) {
+      modules.push(self.snapshot_module(ModuleKey::Main, package.root_module()));
+    }
+
+    let key = ChunkCache::new(&self.root_dir, VERSION).layout_key();
+    snapshot::encode(key, modules, &externs, |module| {
+      self.snapshot_module(ModuleKey::Reached, module)
+    })
+    .map_err(VmError::Internal)
+  }
+
+  /// Copy values for a vm on another thread, see `snapshot::encode_copy`
+  fn copy_values(&self, values: &[Value]) -> Result<Vec<u8>, String> {
+    let externs = Externs::collect(self.packages.values());
+    let key = ChunkCache::new(&self.root_dir, VERSION).layout_key();
+
+    snapshot::encode_copy(
+      key,
+      values,
+      &externs,
+      |module| self.snapshot_module(ModuleKey::Reached, module),
+      self.fiber,
+    )
+  }
+
+  /// Restore values copied by another vm with `Vm::copy_values`
+  pub(crate) fn restore_copy(&mut self, copy: &[u8]) -> Result<Vec<Rooted>, String> {
+    let key = ChunkCache::new(&self.root_dir, VERSION).layout_key();
+    let snapshot =
+      Snapshot::read(key, copy).ok_or_else(|| "Copied values are corrupt.".to_string())?;
+
+    let no_gc_context = NoContext::new(self.gc.replace(Allocator::default()));
+    let result = self.restore_snapshot(&GcHooks::new(&no_gc_context), &snapshot);
+    self.gc.replace(no_gc_context.done());
+
+    result.map(|values| values.into_iter().map(|value| self.root(value)).collect())
+  }
+
+  /// A module to snapshot with the size of its inline cache
+  fn snapshot_module(&self, key: ModuleKey, module: Gc<Module>) -> SnapshotModule {
+    let (property_slots, invoke_slots) =
+      self.inline_cache.get(module.id()).map_or((0, 0), |cache| {
+        (cache.property_slots(), cache.invoke_slots())
+      });
+
+    SnapshotModule {
+      key,
+      module,
+      property_slots,
+      invoke_slots,
+    }
+  }
+
+  /// Restore the modules of a snapshot taken with `Vm::snapshot` by this
+  /// build of laythe. A snapshot that is corrupt, taken by another build,
+  /// or holds a module this vm has already loaded is not restored
+  pub fn restore(&mut self, snapshot: &[u8]) -> Result<(), VmError> {
+    let key = ChunkCache::new(&self.root_dir, VERSION).layout_key();
+    let snapshot = Snapshot::read(key, snapshot).ok_or_else(|| {
+      VmError::Load("Snapshot is corrupt or was taken by another build.".to_string())
+    })?;
+
+    let no_gc_context = NoContext::new(self.gc.replace(Allocator::default()));
+    let result = self.restore_snapshot(&GcHooks::new(&no_gc_context), &snapshot);
+    self.gc.replace(no_gc_context.done());
+
+    result.map(|_| ()).map_err(VmError::Load)
+  }
+
+  /// Restore a snapshot while collection is disabled returning any values
+  /// it copied
+  fn restore_snapshot(
+    &mut self,
+    hooks: &GcHooks,
+    snapshot: &Snapshot,
+  ) -> Result<Vec<Value>, String> {
+    for entry in &snapshot.modules {
+      if let ModuleKey::Cached(key) = &entry.key {
+        if self.module_cache.contains_key(&hooks.manage_str(key)) {
+          return Err(format!("Module {} is already loaded.", key));
+        }
+      }
+    }
+
+    let externs = snapshot
+      .externs
+      .iter()
+      .map(|path| {
+        let mut package = self
+          .packages
+          .get(&hooks.manage_str(&path.package))
+          .copied()
+          .ok_or_else(|| format!("Package {} does not exist.", path.package))?;
+
+        // modules of a package are only constructed once imported
+        if let Some(module) = path.modules.first() {
+          let segments = hooks.manage(List::from(&[hooks.manage_str(module)][..]));
+          let import = hooks.manage(Import::new(package.name(), segments));
+          package
+            .initialize(hooks, &mut self.emitter, import)
+            .map_err(|err| err.to_string())?;
+        }
+
+        snapshot::resolve_extern(package, path)
+          .ok_or_else(|| format!("Unable to find a value of package {}.", path.package))
+      })
+      .collect::<Result<Vec<Value>, String>>()?;
+
+    let base_class = self.builtin.dependencies.module;
+    let emitter = &mut self.emitter;
+    let (modules, values) = snapshot.restore(hooks, &externs, base_class, || emitter.emit())?;
+
+    for (entry, module) in snapshot.modules.iter().zip(modules) {
+      match &entry.key {
+        ModuleKey::Cached(key) => {
+          self.module_cache.insert(hooks.manage_str(key), module);
+        },
+        ModuleKey::Main => {
+          let name = hooks.manage_str(SELF);
+          self
+            .packages
+            .insert(name, hooks.manage(Package::new(name, module)));
+        },
+        ModuleKey::Reached => (),
+      }
+
+      self.set_inline_cache(
+        module,
+        InlineCache::new(entry.property_slots, entry.invoke_slots),
+      );
+    }
+
+    Ok(values)
+  }
+
+  /// Provide state native functions can reach through `Hooks::host_data`,
+  /// replacing any state previously provided
+  pub fn set_host_data<T: HostData>(&mut self, data: T) {
+    self.host_data = Some(Box::new(data));
+  }
+
+  /// Get the state provided by the host if it is of type `T`
+  pub fn host_data<T: HostData>(&self) -> Option<&T> {
+    self
+      .host_data
+      .as_ref()
+      .and_then(|data| data.as_any().downcast_ref::<T>())
+  }
+
+  /// Get the state provided by the host mutably if it is of type `T`
+  pub fn host_data_mut<T: HostData>(&mut self) -> Option<&mut T> {
+    self
+      .host_data
+      .as_mut()
+      .and_then(|data| data.as_any_mut().downcast_mut::<T>())
   }
 
   /// The current version of the virtual machine
@@ -187,8 +896,9 @@ impl Vm {
     VERSION
   }
 
-  /// Start the interactive repl
-  pub fn repl(&mut self) -> ExecuteResult {
+  /// Start the interactive repl. Errors from each line are reported as
+  /// the line is run, only failing to read or write stdio ends the repl
+  pub fn repl(&mut self) -> Result<u16, VmError> {
     let mut stdio = self.io.stdio();
 
     let repl_path = self.root_dir.join(PathBuf::from(REPL_MODULE));
@@ -198,99 +908,954 @@ impl Vm {
 
     loop {
       let mut buffer = String::new();
+      let mut prompt = REPL_PROMPT;
+
+      // keep reading lines while the input is an incomplete statement. A blank
+      // line or the end of input submits what has been read so far
+      let read = loop {
+        write!(stdio.stdout(), "{}", prompt)?;
+        stdio.stdout().flush()?;
+
+        match stdio.read_line(&mut buffer) {
+          Ok(0) => break Ok(buffer.len()),
+          Ok(read) => {
+            let line = &buffer[buffer.len() - read..];
+            if line.trim().is_empty() || !is_incomplete(&Source::new(&buffer)) {
+              break Ok(buffer.len());
+            }
 
-      if write!(stdio.stdout(), "laythe:> ").is_err() {
-        return ExecuteResult::InternalError;
-      }
-      stdio.stdout().flush().expect("Could not write to stdout");
+            prompt = REPL_CONTINUATION_PROMPT;
+          },
+          Err(error) => break Err(error),
+        }
+      };
 
-      match stdio.read_line(&mut buffer) {
+      match read {
+        Ok(0) => return Ok(self.exit_code),
         Ok(_) => {
           let source_content = self.manage_str(buffer);
           self.push_root(source_content);
-          let source = Source::new(source_content);
+          let source = Source::new(&source_content);
 
-          let managed_path = self.manage_str(repl_path.to_string_lossy());
+          let managed_path = self.manage_str(self.file_name(&repl_path));
           self.push_root(managed_path);
 
           let file_id = self.files.upsert(managed_path, source_content);
           self.pop_roots(2);
 
-          self.interpret(main_module, &source, file_id);
-        }
-        Err(error) => panic!("{}", error),
+          if let Err(error) = self.interpret(main_module, &source, file_id, true) {
+            self.report(&error);
+          }
+        },
+        Err(error) => return Err(error.into()),
       }
     }
   }
 
-  /// Run the provided source file
-  pub fn run(&mut self, module_path: PathBuf, source_content: &str) -> ExecuteResult {
-    match self.io.fs().canonicalize(&module_path) {
-      Ok(module_path) => {
-        let mut directory = module_path.clone();
-        directory.pop();
+  /// Run the provided source file returning its exit code. Errors are
+  /// returned rather than reported, see `Vm::report`
+  pub fn run(&mut self, module_path: PathBuf, source_content: &str) -> Result<u16, VmError> {
+    let (main_module, source_content, file_id) = self.load_main(module_path, source_content)?;
+    let source = Source::new(&source_content);
 
-        self.root_dir = directory;
-        let source_content = self.manage_str(source_content);
-        self.push_root(source_content);
-        let source = Source::new(source_content);
+    let result = self.interpret(main_module, &source, file_id, false);
+    self.finish_run(result)
+  }
 
-        let managed_path = self.manage_str(module_path.to_string_lossy());
-        self.push_root(managed_path);
+  /// Continue a run that returned `VmError::AwaitingInput` once the host
+  /// has input available. Each fiber waiting on input retries its read so
+  /// a fiber whose input has not arrived waits again
+  pub fn resume(&mut self) -> Result<u16, VmError> {
+    if !self.scheduler.wake_input() {
+      return Err(VmError::Internal(
+        "No fiber is waiting for input.".to_string(),
+      ));
+    }
 
-        let file_id = self.files.upsert(managed_path, source_content);
-        self.pop_roots(2);
+    // the time the host took to provide input is not counted
+    self.budget.reset();
+    self.interrupt.clear();
+    self.uncaught = None;
+
+    let result = match unsafe { self.schedule_next() } {
+      Signal::Ok => self.execute(ExecuteMode::Normal),
+      Signal::Exit => ExecuteResult::Ok(self.exit_code),
+      Signal::AwaitInput => ExecuteResult::AwaitingInput,
+      Signal::RuntimeError => match unsafe { self.unwind_error(&ExecuteMode::Normal) } {
+        Some(result) => result,
+        None => self.execute(ExecuteMode::Normal),
+      },
+      _ => {
+        self.internal_error("Unexpected signal resuming the vm.");
+        ExecuteResult::InternalError
+      },
+    };
+    let result = self.script_result(result);
+    self.finish_run(result)
+  }
 
-        let main_id = self.emitter.emit();
-        let main_module = self.main_module(module_path, main_id);
+  /// Shutdown after a run and write its profile and statistics. A run
+  /// waiting on input is left as it is to be resumed
+  fn finish_run(&mut self, result: Result<u16, VmError>) -> Result<u16, VmError> {
+    if let Err(VmError::AwaitingInput) = result {
+      return result;
+    }
 
-        self.interpret(main_module, &source, file_id)
-      }
-      Err(err) => {
-        writeln!(self.io.stdio().stderr(), "{}", &err.to_string())
-          .expect("Unable to write to stderr");
-        ExecuteResult::RuntimeError
-      }
+    if result.is_ok() {
+      self.shutdown();
     }
+
+    let profiled = self.write_profile();
+    let counted = self.write_stats();
+    let exit_code = result?;
+
+    profiled?;
+    counted?;
+    Ok(exit_code)
   }
 
-  /// Add a package to the vm
-  pub fn add_package(&mut self, package: Gc<Package>) {
-    self.packages.insert(package.name(), package);
+  /// Write the profile of the last run if profiling is enabled
+  fn write_profile(&mut self) -> Result<(), VmError> {
+    self.profile_event();
+
+    if let Some(profiler) = &self.profiler {
+      let mut buffer = vec![];
+      profiler.write(&mut buffer, |fun| self.fun_label(fun))?;
+
+      self
+        .io
+        .fs()
+        .write(profiler.path(), &buffer)
+        .map_err(|err| VmError::Io {
+          kind: err.kind(),
+          message: format!(
+            "Unable to write profile to {}: {}",
+            profiler.path().display(),
+            err
+          ),
+        })?;
+    }
+
+    Ok(())
   }
 
-  /// Interpret the provided laythe script returning the execution result
-  fn interpret(
-    &mut self,
-    main_module: Gc<Module>,
-    source: &Source,
-    file_id: VmFileId,
-  ) -> ExecuteResult {
-    match self.compile(main_module, source, file_id) {
-      Ok(fun) => {
-        self.prepare(fun);
-        self.execute(ExecuteMode::Normal)
-      }
-      Err(errors) => {
-        let mut stdio = self.io.stdio();
-        let stderr_color = stdio.stderr_color();
-        for error in errors.iter() {
-          term::emit(stderr_color, &Config::default(), &self.files, error)
-            .expect("Unable to write to stderr");
-        }
-        ExecuteResult::CompileError
-      }
+  /// Write the instruction counts of the last run if counting is enabled
+  fn write_stats(&self) -> Result<(), VmError> {
+    if let Some(stats) = &self.stats {
+      let mut stdio = self.io.stdio();
+      stats.write(stdio.stderr(), |fun, offset| {
+        format!(
+          "{}:{}",
+          self.fun_label(fun),
+          fun.chunk().get_line(offset + 1)
+        )
+      })?;
     }
+
+    Ok(())
   }
 
-  /// Compile the provided laythe source into the virtual machine's bytecode
-  fn compile(
+  /// Attribute the time since the last frame event to the current call stack
+  #[inline]
+  fn profile_event(&mut self) {
+    if let Some(profiler) = &mut self.profiler {
+      profiler.record(self.fiber.frames());
+    }
+  }
+
+  /// Compile the provided source file reporting any diagnostics
+  /// without running it
+  pub fn check(&mut self, module_path: PathBuf, source_content: &str) -> Result<(), VmError> {
+    let (main_module, source_content, file_id) = self.load_main(module_path, source_content)?;
+    let source = Source::new(&source_content);
+
+    self
+      .compile(main_module, &source, file_id, false)
+      .map(|_| ())
+      .map_err(VmError::Compile)
+  }
+
+  /// Compile the provided source file into a module that an import
+  /// resolver can return in place of its source. Precompiled modules can
+  /// only be loaded by this version of Laythe
+  pub fn precompile(
+    &mut self,
+    module_path: PathBuf,
+    source_content: &str,
+  ) -> Result<Vec<u8>, VmError> {
+    let (main_module, source_content, file_id) = self.load_main(module_path, source_content)?;
+    let source = Source::new(&source_content);
+
+    let fun = self
+      .compile(main_module, &source, file_id, false)
+      .map_err(VmError::Compile)?;
+
+    let inline_cache = &self.inline_cache[main_module.id()];
+    ChunkCache::new(&self.root_dir, VERSION)
+      .precompile(
+        &fun,
+        inline_cache.property_slots(),
+        inline_cache.invoke_slots(),
+      )
+      .ok_or_else(|| VmError::Internal("Unable to serialize the compiled module.".to_string()))
+  }
+
+  /// Recompile and rerun the source of a module that has already been
+  /// loaded, swapping its symbol table in place so modules importing it
+  /// see the new symbols. Classes whose fields are unchanged keep their
+  /// identity so existing instances call the new methods. A module that
+  /// fails to compile or run is left as it was
+  pub fn reload(&mut self, module_path: PathBuf, source_content: &str) -> Result<u16, VmError> {
+    let module_path = self
+      .io
+      .fs()
+      .canonicalize(&module_path)
+      .unwrap_or(module_path);
+
+    let mut module = match self.loaded_module(&module_path) {
+      Some(module) => module,
+      None => {
+        return Err(VmError::Load(format!(
+          "Module {} has not been loaded.",
+          module_path.display()
+        )))
+      },
+    };
+
+    // keep the previous symbols alive in case they need to be restored
+    let previous = self.manage((*module).clone());
+    self.push_root(previous);
+
+    let source_content = self.manage_str(source_content);
+    self.push_root(source_content);
+    let managed_path = self.manage_str(self.file_name(&module_path));
+    self.push_root(managed_path);
+    let file_id = self.files.upsert(managed_path, source_content);
+    self.pop_roots(2);
+
+    let module_id = self.emitter.emit();
+    module.reset(&GcHooks::new(self), module_id);
+    self
+      .global
+      .transfer_exported(&GcHooks::new(self), &mut module);
+
+    let result = self.interpret(module, &Source::new(&source_content), file_id, false);
+    match result {
+      Ok(_) => self.reload_classes(previous, module),
+      Err(_) => {
+        let restored = (*previous).clone();
+        GcHooks::new(self).grow(&mut *module, |module| *module = restored);
+      },
+    }
+
+    self.pop_roots(1);
+    result
+  }
+
+  /// The loaded module at this path, either the main module or a module
+  /// loaded by an import
+  fn loaded_module(&mut self, module_path: &Path) -> Option<Gc<Module>> {
+    let resolved = self.manage_str(module_path.to_string_lossy());
+    if let Some(module) = self.module_cache.get(&resolved) {
+      return Some(*module);
+    }
+
+    let main = self.manage_str(SELF);
+    self
+      .packages
+      .get(&main)
+      .map(|package| package.root_module())
+      .filter(|module| module.path() == module_path)
+  }
+
+  /// Patch the classes of a module's previous symbols with the classes of
+  /// the same name defined by reloading it. A class is only patched when
+  /// its layout and super class are unchanged as instances keep their slots
+  fn reload_classes(&mut self, previous: Gc<Module>, mut module: Gc<Module>) {
+    let mut reloaded: Vec<(GcStr, GcObj<Class>, GcObj<Class>)> = previous
+      .symbols()
+      .filter_map(|(name, symbol)| {
+        let current = module.get_symbol(*name)?;
+        if !symbol.is_obj_kind(ObjectKind::Class) || !current.is_obj_kind(ObjectKind::Class) {
+          return None;
+        }
+
+        let class = symbol.to_obj().to_class();
+        let current = current.to_obj().to_class();
+        (class != current && class.same_layout(&current)).then_some((*name, class, current))
+      })
+      .collect();
+
+    // a subclass can only be patched if its super class was also patched
+    loop {
+      let candidates = reloaded.clone();
+      reloaded.retain(
+        |(_, class, current)| match (class.super_class(), current.super_class()) {
+          (Some(super_class), Some(current_super)) => {
+            super_class == current_super
+              || candidates
+                .iter()
+                .any(|(_, class, current)| class == super_class && current == current_super)
+          },
+          (None, None) => true,
+          _ => false,
+        },
+      );
+
+      if reloaded.len() == candidates.len() {
+        break;
+      }
+    }
+
+    let hooks = GcHooks::new(self);
+    for (name, mut class, current) in reloaded {
+      class.reload(&hooks, current);
+      module
+        .set_symbol(name, val!(class))
+        .expect("Reloaded class not in symbol table.");
+    }
+
+    // cached method lookups may refer to methods that were replaced
+    self.reset_inline_caches();
+  }
+
+  /// Compile the provided source file writing the disassembled
+  /// chunk of each of its functions to stdout
+  pub fn disassemble(&mut self, module_path: PathBuf, source_content: &str) -> Result<(), VmError> {
+    let (main_module, source_content, file_id) = self.load_main(module_path, source_content)?;
+    let source = Source::new(&source_content);
+
+    let fun = self
+      .compile(main_module, &source, file_id, false)
+      .map_err(VmError::Compile)?;
+    disassemble_fun(&mut self.io.stdio(), &fun, &fun.name())?;
+    Ok(())
+  }
+
+  /// Parse the provided source file writing its ast as json to stdout
+  pub fn ast_json(&mut self, module_path: PathBuf, source_content: &str) -> Result<(), VmError> {
+    let (_, source_content, file_id) = self.load_main(module_path, source_content)?;
+    let source = Source::new(&source_content);
+    let arena = Arena::for_source(&source);
+
+    let (ast, line_offsets) = Parser::new(&source, &arena, file_id).parse();
+    self
+      .files
+      .update_line_offsets(file_id, line_offsets)
+      .expect("File id not set for line offsets");
+
+    let json = AstJson.visit(&ast.map_err(VmError::Compile)?);
+    writeln!(self.io.stdio().stdout(), "{}", json)?;
+    Ok(())
+  }
+
+  /// Compile the provided source file writing the chunk of each of
+  /// its functions as json to stdout
+  pub fn bytecode_json(
+    &mut self,
+    module_path: PathBuf,
+    source_content: &str,
+  ) -> Result<(), VmError> {
+    let (main_module, source_content, file_id) = self.load_main(module_path, source_content)?;
+    let source = Source::new(&source_content);
+
+    let fun = self
+      .compile(main_module, &source, file_id, false)
+      .map_err(VmError::Compile)?;
+    writeln!(self.io.stdio().stdout(), "{}", fun_json(&fun))?;
+    Ok(())
+  }
+
+  /// Parse, lint and compile the provided source without running it or
+  /// touching the chunk cache. Editor tooling uses this to report a
+  /// script's diagnostics and resolve its symbols as it is edited
+  pub fn analyze(&mut self, module_path: PathBuf, source_content: &str) -> Analysis {
+    let (main_module, source_content, file_id) = self.register_main(module_path, source_content);
+    let source = Source::new(&source_content);
+    let arena = Arena::for_source(&source);
+    let symbols = Rc::new(RefCell::new(SymbolTable::default()));
+
+    let (ast, line_offsets) = Parser::new(&source, &arena, file_id).parse();
+    self
+      .files
+      .update_line_offsets(file_id, line_offsets.clone())
+      .expect("File id not set for line offsets");
+
+    let mut diagnostics = vec![];
+    match ast {
+      Ok(ast) => {
+        diagnostics.extend(self.lint_level.apply(Linter::new(file_id).lint(&ast)));
+        if self.type_check {
+          diagnostics.extend(TypeChecker::new(file_id).check(&ast));
+        }
+
+        let gc = self.gc.replace(Allocator::default());
+        let compiler = Compiler::new(main_module, &ast, &source, &line_offsets, file_id, self, gc)
+          .with_symbols(Rc::clone(&symbols));
+
+        let (result, gc, _) = compiler.compile();
+        self.gc.replace(gc);
+
+        if let Err(errors) = result {
+          diagnostics.extend(errors);
+        }
+      }
+      Err(errors) => diagnostics.extend(errors),
+    }
+
+    Analysis {
+      diagnostics,
+      symbols: symbols.take(),
+    }
+  }
+
+  /// The methods of each class in the global module along with the
+  /// signatures of those implemented natively
+  pub fn std_methods(&self) -> Vec<StdMethod> {
+    let mut methods = vec![];
+
+    for symbol in self.global.symbols().map(|(_, symbol)| *symbol) {
+      if !symbol.is_obj_kind(ObjectKind::Class) {
+        continue;
+      }
+
+      let class = symbol.to_obj().to_class();
+      let static_methods = class
+        .meta_class()
+        .iter()
+        .flat_map(|meta_class| meta_class.methods().map(|method| (true, method)));
+
+      let instance_methods = class.methods().map(|method| (false, method));
+      for (is_static, (name, method)) in instance_methods.chain(static_methods) {
+        let parameters = if method.is_obj_kind(ObjectKind::Native) {
+          let native = method.to_obj().to_native();
+          native
+            .meta()
+            .signature
+            .parameters
+            .iter()
+            .map(|parameter| format!("{}: {}", &*parameter.name, parameter.kind))
+            .collect()
+        } else {
+          vec![]
+        };
+
+        methods.push(StdMethod {
+          class: String::from(&*class.name()),
+          name: String::from(&**name),
+          is_static,
+          parameters,
+        });
+      }
+    }
+
+    methods.sort_by(|a, b| (&a.class, a.is_static, &a.name).cmp(&(&b.class, b.is_static, &b.name)));
+    methods
+  }
+
+  /// The number of tests passed and failed through the std/test module
+  /// while running scripts on this vm
+  pub fn test_results(&self) -> Option<TestResults> {
+    let hooks = GcHooks::new(self);
+    let std = self.packages.get(&hooks.manage_str(STD))?;
+
+    test_results(&hooks, std)
+  }
+
+  /// Get a top level symbol of the main module of the last script run on
+  /// this vm, such as a function to call with `Vm::call`
+  pub fn get_global(&self, name: &str) -> Option<Rooted> {
+    // a name that was never interned can't be a symbol so nothing is allocated
+    let (main_name, name) = {
+      let gc = self.gc.borrow();
+      (gc.get_str(SELF)?, gc.get_str(name)?)
+    };
+
+    let main = self.packages.get(&main_name)?;
+    let symbol = main.root_module().get_symbol(name)?;
+    Some(self.root(symbol))
+  }
+
+  /// Convert a Rust value into a Laythe value to pass to `Vm::call`
+  pub fn to_value<T: IntoValue>(&self, value: T) -> Rooted {
+    let no_gc_context = NoContext::new(self.gc.replace(Allocator::default()));
+    let value = value.into_value(&GcHooks::new(&no_gc_context));
+    self.gc.replace(no_gc_context.done());
+
+    self.root(value)
+  }
+
+  /// Hold a value so it is not collected while the handle is alive
+  fn root(&self, value: Value) -> Rooted {
+    Rooted::new(&self.host_roots, value)
+  }
+
+  /// Register a class built in Rust as a global so scripts run after
+  /// this point can use it, returning the class
+  pub fn register_class(&mut self, builder: NativeClassBuilder) -> ModuleResult<Rooted> {
+    let no_gc_context = NoContext::new(self.gc.replace(Allocator::default()));
+    let hooks = GcHooks::new(&no_gc_context);
+
+    let class = builder.build(
+      &hooks,
+      self.builtin.primitives.object,
+      self.builtin.errors.runtime,
+    );
+
+    let result = self
+      .global
+      .insert_symbol(&hooks, class.name(), val!(class))
+      .and_then(|()| self.global.export_symbol(&hooks, class.name()));
+
+    self.gc.replace(no_gc_context.done());
+    result.map(|()| self.root(val!(class)))
+  }
+
+  /// Register a function implemented in Rust as a global so scripts run
+  /// after this point can call it, returning the function. The function
+  /// takes any number of arguments and errors it returns are raised in
+  /// the script as a `RuntimeError`
+  ///
+  /// ```ignore
+  /// vm.register_fun("sum", |_hooks, args| {
+  ///   let total = args.iter().map(|arg| f64::from_value(*arg)).sum::<Result<f64, _>>()?;
+  ///   Ok(val!(total))
+  /// })?;
+  /// ```
+  pub fn register_fun<F>(&mut self, name: &str, fun: F) -> ModuleResult<Rooted>
+  where
+    F: Fn(&mut Hooks, &[Value]) -> Result<Value, LyError> + Send + 'static,
+  {
+    let no_gc_context = NoContext::new(self.gc.replace(Allocator::default()));
+    let hooks = GcHooks::new(&no_gc_context);
+
+    let native = host_fun(&hooks, name, self.builtin.errors.runtime, fun);
+    let name = native.meta().name;
+
+    let result = self
+      .global
+      .insert_symbol(&hooks, name, val!(native))
+      .and_then(|()| self.global.export_symbol(&hooks, name));
+
+    self.gc.replace(no_gc_context.done());
+    result.map(|()| self.root(val!(native)))
+  }
+
+  /// Load a plugin from a dynamic library exporting its functions with
+  /// `laythe_core::export_plugin!`. The native modules the plugin registers
+  /// are imported through a package named after the library, `geometry` for
+  /// `libgeometry.so`. Plugins built against another version of laythe or
+  /// the rust compiler are rejected before any of their code runs
+  pub fn load_plugin(&mut self, path: &Path) -> Result<(), VmError> {
+    let plugin = plugin::load(path).map_err(VmError::Load)?;
+    self
+      .install_plugin(&plugin.name, plugin.init)
+      .map_err(VmError::Load)
+  }
+
+  /// Create the package a plugin registers its modules in
+  fn install_plugin(&mut self, name: &str, init: PluginInit) -> Result<(), String> {
+    let name = self.manage_str(name);
+    if self.packages.contains_key(&name) {
+      return Err(format!("Package {} already exists.", &*name));
+    }
+
+    let no_gc_context = NoContext::new(self.gc.replace(Allocator::default()));
+    let hooks = GcHooks::new(&no_gc_context);
+
+    let module_class = Class::with_inheritance(&hooks, name, self.builtin.dependencies.module);
+    let module = Module::new(module_class, PathBuf::from(&*name), self.emitter.emit());
+    let mut package = Package::new(name, hooks.manage(module));
+
+    let result = init(&hooks, &mut package);
+    if result.is_ok() {
+      self.packages.insert(name, hooks.manage(package));
+    }
+
+    self.gc.replace(no_gc_context.done());
+    result.map_err(|err| format!("Plugin {} failed to initialize: {}", &*name, err))
+  }
+
+  /// Deserialize a value with the provided seed, such as from a config
+  /// file or message, rooting the result to pass to `Vm::call`
+  ///
+  /// ```ignore
+  /// let mut json = serde_json::Deserializer::from_str(source);
+  /// let config = vm.deserialize_with(|seed| seed.deserialize(&mut json))?;
+  /// ```
+  #[cfg(feature = "serde")]
+  pub fn deserialize_with<E>(
+    &self,
+    deserialize: impl FnOnce(ValueSeed) -> Result<Value, E>,
+  ) -> Result<Rooted, E> {
+    let no_gc_context = NoContext::new(self.gc.replace(Allocator::default()));
+    let result = deserialize(ValueSeed::new(&GcHooks::new(&no_gc_context)));
+    self.gc.replace(no_gc_context.done());
+
+    result.map(|value| self.root(value))
+  }
+
+  /// Call a Laythe function, method or class from Rust with the provided
+  /// arguments returning its result. Errors the call does not catch are
+  /// returned rather than reported
+  pub fn call(&mut self, callable: &Rooted, args: &[Rooted]) -> Result<Rooted, LyError> {
+    let callable = callable.value();
+    let args: Vec<Value> = args.iter().map(Rooted::value).collect();
+
+    // the call runs on a fresh fiber so it is isolated from any previous run
+    let stub = self.manage_obj(Closure::without_upvalues(self.native_fun_stub));
+    self
+      .activate_fiber(stub)
+      .map_err(|error| LyError::Internal(error.to_string()))?;
+    self.budget.reset();
+    self.interrupt.clear();
+
+    let result = unsafe { self.run_fun(callable, &args) };
+
+    match result {
+      ExecuteResult::FunResult(value) => Ok(self.root(value)),
+      ExecuteResult::Ok(code) => Err(LyError::Exit(code)),
+      ExecuteResult::RuntimeError => match self.fiber.error() {
+        Some(error) => Err(LyError::Runtime {
+          class: String::from(&*error.class().name()),
+          message: String::from(&*error[0].to_obj().to_str()),
+        }),
+        None => Err(LyError::Internal(
+          "Error not set on vm executor.".to_string(),
+        )),
+      },
+      ExecuteResult::Timeout => Err(LyError::Timeout),
+      ExecuteResult::Interrupted => Err(LyError::Interrupted),
+      ExecuteResult::InternalError => Err(LyError::Internal(self.take_internal())),
+      ExecuteResult::AwaitingInput => {
+        Err(LyError::Internal("Call cannot wait for input.".to_string()))
+      },
+    }
+  }
+
+  /// Load the main module of the provided source file, registering the
+  /// file with the vm
+  fn load_main(
+    &mut self,
+    module_path: PathBuf,
+    source_content: &str,
+  ) -> Result<(Gc<Module>, GcStr, VmFileId), VmError> {
+    let module_path = self.io.fs().canonicalize(&module_path)?;
+    let main = self.register_main(module_path, source_content);
+
+    self.load_packages().map_err(VmError::Load)?;
+    Ok(main)
+  }
+
+  /// Load the package described by the manifest along with every package
+  /// it transitively depends on. Each dependency must have its own manifest
+  /// naming it as it is named by its dependents
+  fn load_packages(&mut self) -> Result<(), String> {
+    let manifest_path = match &self.manifest {
+      Some(manifest_path) => manifest_path.clone(),
+      None => return Ok(()),
+    };
+
+    let fs = self.io.fs();
+
+    // dependencies are found through canonical paths so the root must be
+    // too, otherwise a dependency back onto the root looks like a new package
+    let manifest_path = fs
+      .canonicalize(&manifest_path)
+      .map_err(|err| format!("Unable to read {}: {}", manifest_path.display(), err))?;
+    let mut pending: Vec<(PathBuf, Option<String>)> = vec![(manifest_path, None)];
+    let mut loaded: Vec<PathBuf> = vec![];
+
+    while let Some((manifest_path, expected)) = pending.pop() {
+      let source = fs
+        .read_to_string(&manifest_path)
+        .map_err(|err| format!("Unable to read {}: {}", manifest_path.display(), err))?;
+      let manifest = Manifest::parse(&source)
+        .map_err(|err| format!("Invalid manifest {} {}", manifest_path.display(), err))?;
+
+      let root_dir = manifest_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+      if loaded.contains(&root_dir) {
+        continue;
+      }
+
+      if let Some(expected) = expected {
+        if expected != manifest.name {
+          return Err(format!(
+            "Dependency {} at {} is named {}.",
+            expected,
+            root_dir.display(),
+            manifest.name
+          ));
+        }
+      }
+
+      for dependency in &manifest.dependencies {
+        let dependency_dir = fs
+          .canonicalize(&root_dir.join(&dependency.path))
+          .map_err(|_| {
+            format!(
+              "Dependency {} of {} was not found at {}.",
+              dependency.name,
+              manifest.name,
+              root_dir.join(&dependency.path).display()
+            )
+          })?;
+
+        pending.push((dependency_dir.join(MANIFEST), Some(dependency.name.clone())));
+      }
+
+      self.add_source_package(&manifest, root_dir.clone())?;
+      loaded.push(root_dir);
+    }
+
+    Ok(())
+  }
+
+  /// Add a package of source modules found in the provided directory
+  fn add_source_package(&mut self, manifest: &Manifest, root_dir: PathBuf) -> Result<(), String> {
+    let name = self.manage_str(&manifest.name);
+
+    if let Some(existing) = self.packages.get(&name) {
+      return match existing.root_dir() {
+        Some(existing_dir) if existing_dir == root_dir => Ok(()),
+        Some(existing_dir) => Err(format!(
+          "Package {} is defined by both {} and {}.",
+          manifest.name,
+          existing_dir.display(),
+          root_dir.display()
+        )),
+        None => Err(format!(
+          "Package name {} is reserved by a native package.",
+          manifest.name
+        )),
+      };
+    }
+
+    self.push_root(name);
+    let dependencies: Vec<GcStr> = manifest
+      .dependencies
+      .iter()
+      .map(|dependency| {
+        let dependency = self.manage_str(&dependency.name);
+        self.push_root(dependency);
+        dependency
+      })
+      .collect();
+
+    let module_class =
+      Class::with_inheritance(&GcHooks::new(self), name, self.builtin.dependencies.module);
+    self.push_root(module_class);
+
+    let module_id = self.emitter.emit();
+    let module = self.manage(Module::new(module_class, root_dir, module_id));
+    self.push_root(module);
+
+    let package = self.manage(Package::from_dir(name, module, dependencies));
+    self.packages.insert(name, package);
+    self.pop_roots(3 + manifest.dependencies.len());
+
+    Ok(())
+  }
+
+  /// Register the main module and source of a file with the vm
+  fn register_main(
+    &mut self,
+    module_path: PathBuf,
+    source_content: &str,
+  ) -> (Gc<Module>, GcStr, VmFileId) {
+    let mut directory = module_path.clone();
+    directory.pop();
+
+    self.root_dir = directory;
+    let source_content = self.manage_str(source_content);
+    self.push_root(source_content);
+
+    let managed_path = self.manage_str(self.file_name(&module_path));
+    self.push_root(managed_path);
+
+    let file_id = self.files.upsert(managed_path, source_content);
+    self.pop_roots(2);
+
+    let main_id = self.emitter.emit();
+    let main_module = self.main_module(module_path, main_id);
+
+    (main_module, source_content, file_id)
+  }
+
+  /// Shutdown the vm running each exit hook in the reverse order it was
+  /// registered. Errors are reported per hook without stopping the rest
+  pub fn shutdown(&mut self) {
+    let exit_code = self.exit_code;
+    self.budget.reset();
+
+    while let Some(hook) = self.exit_hooks.pop() {
+      self.push_root(hook);
+
+      // each hook is run on a fresh fiber so a failed hook is isolated
+      let stub = self.manage_obj(Closure::without_upvalues(self.native_fun_stub));
+      let result = match self.activate_fiber(stub) {
+        Ok(()) => unsafe { self.run_fun(hook, &[]) },
+        Err(_) => ExecuteResult::InternalError,
+      };
+      self.pop_roots(1);
+
+      match result {
+        ExecuteResult::Timeout => break,
+        ExecuteResult::RuntimeError => {
+          if let Some(error) = self.fiber.error() {
+            self.print_error(error);
+          }
+        }
+        _ => (),
+      }
+    }
+
+    self.exit_code = exit_code;
+  }
+
+  /// Add a package to the vm
+  pub fn add_package(&mut self, package: Gc<Package>) {
+    self.packages.insert(package.name(), package);
+  }
+
+  /// Interpret the provided laythe script returning its exit code
+  fn interpret(
+    &mut self,
+    main_module: Gc<Module>,
+    source: &Source<'_>,
+    file_id: VmFileId,
+    repl: bool,
+  ) -> Result<u16, VmError> {
+    let fun = self
+      .compile(main_module, source, file_id, repl)
+      .map_err(VmError::Compile)?;
+
+    self.prepare(fun)?;
+    self.uncaught = None;
+
+    let result = self.execute(ExecuteMode::Normal);
+    self.script_result(result)
+  }
+
+  /// Convert the result of executing a script into the result of its run
+  fn script_result(&mut self, result: ExecuteResult) -> Result<u16, VmError> {
+    match result {
+      ExecuteResult::Ok(exit_code) => Ok(exit_code),
+      ExecuteResult::RuntimeError => match self.uncaught.take() {
+        Some(error) => Err(VmError::Runtime(Box::new(error))),
+        None => Err(VmError::Internal(
+          "Uncaught error was not captured.".to_string(),
+        )),
+      },
+      ExecuteResult::Timeout => Err(VmError::Timeout),
+      ExecuteResult::Interrupted => Err(VmError::Interrupted(self.uncaught.take().map(Box::new))),
+      ExecuteResult::InternalError => Err(VmError::Internal(self.take_internal())),
+      ExecuteResult::AwaitingInput => Err(VmError::AwaitingInput),
+      ExecuteResult::FunResult(_) => Err(VmError::Internal(
+        "Unexpected result running a script.".to_string(),
+      )),
+    }
+  }
+
+  /// Write an error returned by the vm to stderr as a user would expect
+  /// to see it. Compile errors and uncaught errors show the offending
+  /// source while uncaught errors are followed by their call stack
+  pub fn report(&self, error: &VmError) {
+    let mut stdio = self.io.stdio();
+
+    match error {
+      VmError::Compile(errors) => {
+        diagnostics::emit(stdio.stderr_color(), &self.files, errors)
+          .expect("Unable to write to stderr");
+      },
+      VmError::Runtime(error) | VmError::Interrupted(Some(error)) => {
+        match &error.diagnostic {
+          Some(diagnostic) => {
+            diagnostics::emit(
+              stdio.stderr_color(),
+              &self.files,
+              slice::from_ref(diagnostic),
+            )
+            .expect("Unable to write to stderr");
+          },
+          None => writeln!(stdio.stderr(), "{}", error).expect("Unable to write to stderr"),
+        }
+        write!(stdio.stderr(), "{}", error.stack).expect("Unable to write to stderr");
+
+        let mut cause = &error.cause;
+        while let Some(error) = cause {
+          writeln!(stdio.stderr(), "Caused by {}", error).expect("Unable to write to stderr");
+          write!(stdio.stderr(), "{}", error.stack).expect("Unable to write to stderr");
+          cause = &error.cause;
+        }
+      },
+      VmError::Interrupted(None) => (),
+      error => writeln!(stdio.stderr(), "{}", error).expect("Unable to write to stderr"),
+    }
+  }
+
+  /// Compile the provided laythe source into the virtual machine's bytecode.
+  /// Scripts check the on disk chunk cache before compiling when enabled
+  /// while lines from the repl echo the value of their expression statements
+  fn compile(
     &mut self,
     module: Gc<Module>,
-    source: &Source,
+    source: &Source<'_>,
     file_id: VmFileId,
+    repl: bool,
   ) -> FeResult<GcObj<Fun>, VmFileId> {
-    let (ast, line_offsets) = Parser::new(&source, file_id).parse();
+    let chunk_cache = ChunkCache::new(&self.root_dir, VERSION);
+    let fs = self.io.fs();
+    let arena = Arena::for_source(source);
+    let mut parsed = None;
+
+    // the repl compiles line by line so only scripts are linted and type
+    // checked. Scripts are checked even when cached so warnings are
+    // reported on every run
+    if !repl && (self.lint_level != LintLevel::Allow || self.type_check) {
+      let (ast, line_offsets) = Parser::new(source, &arena, file_id).parse();
+      self
+        .files
+        .update_line_offsets(file_id, line_offsets.clone())
+        .expect("File id not set for line offsets");
+
+      if let Ok(ast) = &ast {
+        self.lint(ast, file_id)?;
+
+        if self.type_check {
+          let errors = TypeChecker::new(file_id).check(ast);
+          if !errors.is_empty() {
+            return Err(errors);
+          }
+        }
+      }
+      parsed = Some((ast, line_offsets));
+    }
+
+    let use_cache = self.chunk_cache && !repl;
+    if use_cache {
+      let script = chunk_cache.load(&fs, &GcHooks::new(self), module, source);
+
+      if let Some(script) = script {
+        self
+          .files
+          .update_line_offsets(file_id, Scanner::new(source).line_offsets())
+          .expect("File id not set for line offsets");
+
+        self.set_inline_cache(
+          module,
+          InlineCache::new(script.property_count, script.invoke_count),
+        );
+        return Ok(script.fun);
+      }
+    }
+
+    let (ast, line_offsets) =
+      parsed.unwrap_or_else(|| Parser::new(source, &arena, file_id).with_repl(repl).parse());
     self
       .files
       .update_line_offsets(file_id, line_offsets.clone())
@@ -298,7 +1863,9 @@ impl Vm {
 
     let ast = ast?;
     let gc = self.gc.replace(Allocator::default());
-    let compiler = Compiler::new(module, &ast, &line_offsets, file_id, self, gc);
+    let compiler = Compiler::new(module, &ast, source, &line_offsets, file_id, self, gc)
+      .with_optimize(true)
+      .with_repl(repl);
 
     #[cfg(feature = "debug")]
     let compiler = compiler.with_io(self.io.clone());
@@ -307,38 +1874,110 @@ impl Vm {
     self.gc.replace(gc);
 
     result.map(|fun| {
-      let cache = InlineCache::new(
-        cache_id_emitter.property_count(),
-        cache_id_emitter.invoke_count(),
-      );
+      let property_count = cache_id_emitter.property_count();
+      let invoke_count = cache_id_emitter.invoke_count();
 
-      if module.id() < self.inline_cache.len() {
-        self.inline_cache[module.id()] = cache;
-      } else {
-        self.inline_cache.push(cache);
+      if use_cache {
+        // a failure to write the cache only costs a recompile next run
+        let _ = chunk_cache.store(&fs, source, &fun, property_count, invoke_count);
       }
+
+      self.set_inline_cache(module, InlineCache::new(property_count, invoke_count));
       self.manage_obj(fun)
     })
   }
 
+  /// Load a script precompiled with `Vm::precompile` into the provided
+  /// module. Precompiled scripts have no source so errors raised in them
+  /// are reported without a snippet
+  fn load_precompiled(&mut self, module: Gc<Module>, bytes: &[u8]) -> Option<GcObj<Fun>> {
+    let chunk_cache = ChunkCache::new(&self.root_dir, VERSION);
+    let script = chunk_cache.load_precompiled(&GcHooks::new(self), module, bytes)?;
+
+    self.set_inline_cache(
+      module,
+      InlineCache::new(script.property_count, script.invoke_count),
+    );
+    Some(script.fun)
+  }
+
+  /// Lint a parsed script. Warnings are written to stderr unless the
+  /// lint level is deny in which case they are returned as errors
+  fn lint(&mut self, ast: &ast::Module, file_id: VmFileId) -> FeResult<(), VmFileId> {
+    let warnings = self.lint_level.apply(Linter::new(file_id).lint(ast));
+
+    if warnings.is_empty() {
+      return Ok(());
+    }
+
+    if self.lint_level == LintLevel::Deny {
+      return Err(warnings);
+    }
+
+    let mut stdio = self.io.stdio();
+    diagnostics::emit(stdio.stderr_color(), &self.files, &warnings)
+      .expect("Unable to write to stderr");
+    Ok(())
+  }
+
+  /// Set the inline cache for the provided module
+  fn set_inline_cache(&mut self, module: Gc<Module>, cache: InlineCache) {
+    // modules such as package roots take an id without being compiled
+    if module.id() >= self.inline_cache.len() {
+      self
+        .inline_cache
+        .resize_with(module.id() + 1, || InlineCache::new(0, 0));
+    }
+
+    self.inline_cache[module.id()] = cache;
+  }
+
+  /// Clear every module's inline cache, keeping their slot counts
+  fn reset_inline_caches(&mut self) {
+    for cache in &mut self.inline_cache {
+      *cache = InlineCache::new(cache.property_slots(), cache.invoke_slots());
+    }
+  }
+
   /// Reset the vm to execute another script
-  fn prepare(&mut self, script: GcObj<Fun>) {
+  fn prepare(&mut self, script: GcObj<Fun>) -> Result<(), VmError> {
+    self.import_stack = vec![script.module()];
     let script = self.manage_obj(Closure::without_upvalues(script));
-    let fiber = match Fiber::new(script) {
-      Ok(fiber) => fiber,
-      Err(_) => self.internal_error("Unable to generate initial fiber"),
-    };
+    self.activate_fiber(script)?;
+    self.budget.reset();
+    self.interrupt.clear();
 
-    self.fiber = self.manage_obj(fiber);
-    self.fiber.activate();
-    self.load_ip();
+    if let Some(profiler) = &mut self.profiler {
+      profiler.reset();
+    }
+    if let Some(stats) = &mut self.stats {
+      stats.reset();
+    }
 
-    self.current_fun = script.fun();
     let mut current_module = self.current_fun.module();
 
     self
       .global
       .transfer_exported(&GcHooks::new(self), &mut current_module);
+    Ok(())
+  }
+
+  /// Replace the main fiber with a new fiber running the provided closure
+  fn activate_fiber(&mut self, closure: GcObj<Closure>) -> Result<(), VmError> {
+    let fiber = match Fiber::with_stack_size(closure, self.stack_size) {
+      Ok(fiber) => fiber,
+      Err(_) => return Err(VmError::Internal("Unable to create a fiber.".to_string())),
+    };
+
+    self.fiber = self.manage_obj(fiber);
+    self.fiber.activate();
+    self.main_fiber = self.fiber;
+    self.scheduler.clear();
+    self.internal = None;
+    self.load_ip();
+
+    self.current_fun = closure.fun();
+    Ok(())
   }
 
   /// Prepare the main module for use
@@ -361,41 +2000,74 @@ impl Vm {
     module
   }
 
-  /// Run a laythe function on top of the current stack.
+  /// Run a laythe function on top of the current stack. The callable is
+  /// pushed so the caller's stack is left intact beneath the call.
   /// This acts as a hook for native functions to execute laythe function
   unsafe fn run_fun(&mut self, callable: Value, args: &[Value]) -> ExecuteResult {
-    self.fiber.ensure_stack(args.len());
-    for arg in args {
-      self.fiber.push(*arg);
-    }
-
     let mode = ExecuteMode::CallFunction(self.fiber.frames().len());
-    match self.resolve_call(callable, args.len() as u8) {
+    let signal = match u8::try_from(args.len()) {
+      Ok(arg_count) => {
+        self.fiber.ensure_stack(args.len() + 1);
+        self.fiber.push(callable);
+        for arg in args {
+          self.fiber.push(*arg);
+        }
+
+        self.resolve_call(callable, arg_count)
+      },
+      Err(_) => self.too_many_args(),
+    };
+
+    match signal {
       Signal::Ok => self.execute(mode),
       Signal::OkReturn => ExecuteResult::FunResult(self.fiber.pop()),
       Signal::RuntimeError => ExecuteResult::RuntimeError,
-      _ => self.internal_error("Unexpected signal in run_fun."),
+      Signal::InternalError => ExecuteResult::InternalError,
+      _ => {
+        self.internal_error("Unexpected signal in run_fun.");
+        ExecuteResult::InternalError
+      },
     }
   }
 
   /// Run a laythe method on top of the current stack.
   /// This acts as a hook for native functions to execute laythe function
   unsafe fn run_method(&mut self, this: Value, method: Value, args: &[Value]) -> ExecuteResult {
-    self.fiber.ensure_stack(args.len() + 1);
-    self.fiber.push(this);
-    for arg in args {
-      self.fiber.push(*arg);
-    }
-
     let mode = ExecuteMode::CallFunction(self.fiber.frames().len());
-    match self.resolve_call(method, args.len() as u8) {
+    let signal = match u8::try_from(args.len()) {
+      Ok(arg_count) => {
+        self.fiber.ensure_stack(args.len() + 1);
+        self.fiber.push(this);
+        for arg in args {
+          self.fiber.push(*arg);
+        }
+
+        self.resolve_call(method, arg_count)
+      },
+      Err(_) => self.too_many_args(),
+    };
+
+    match signal {
       Signal::Ok => self.execute(mode),
       Signal::OkReturn => ExecuteResult::FunResult(self.fiber.pop()),
       Signal::RuntimeError => ExecuteResult::RuntimeError,
-      _ => self.internal_error("Unexpected signal in run_method."),
+      Signal::InternalError => ExecuteResult::InternalError,
+      _ => {
+        self.internal_error("Unexpected signal in run_method.");
+        ExecuteResult::InternalError
+      },
     }
   }
 
+  /// Raise an error for a call from Rust with more arguments than
+  /// a call instruction can pass
+  unsafe fn too_many_args(&mut self) -> Signal {
+    self.runtime_error(
+      self.builtin.errors.runtime,
+      &format!("Cannot call with more than {} arguments.", u8::MAX),
+    )
+  }
+
   /// Get a method for this this value with a given method name
   unsafe fn get_method(&mut self, this: Value, method_name: GcStr) -> Call {
     let class = self.value_class(this);
@@ -411,7 +2083,7 @@ impl Vm {
 
         let result = self.run_fun(val!(self.builtin.errors.import), &[error_message]);
 
-        self.to_call_result(result)
+        self.call_result(result)
       }
     }
   }
@@ -419,77 +2091,118 @@ impl Vm {
   /// Main virtual machine execution loop. This will run the until the program interrupts
   /// from a normal exit or from a runtime error.
   fn execute(&mut self, mode: ExecuteMode) -> ExecuteResult {
+    if self.is_instrumented() {
+      self.execute_loop::<true>(mode)
+    } else {
+      self.execute_loop::<false>(mode)
+    }
+  }
+
+  /// Is any instrumentation observing each executed instruction
+  fn is_instrumented(&self) -> bool {
+    let traced = self
+      .tracer
+      .as_ref()
+      .is_some_and(|tracer| tracer.events().per_instruction());
+
+    traced || self.stats.is_some() || self.debug.is_some()
+  }
+
+  /// The execution loop, instantiated once with instrumentation
+  /// and once without so the default loop pays nothing for it
+  fn execute_loop<const INSTRUMENTED: bool>(&mut self, mode: ExecuteMode) -> ExecuteResult {
     unsafe {
       loop {
+        match self.budget.tick() {
+          Tick::Continue => (),
+          Tick::Exhausted => {
+            self.store_ip();
+            return ExecuteResult::Timeout;
+          },
+          Tick::Woken => {
+            // the heap was flagged during the previous instruction
+            if self.gc.borrow().is_out_of_memory() {
+              self.out_of_memory();
+              if let Some(execute_result) = self.unwind_error(&mode) {
+                return execute_result;
+              }
+              continue;
+            }
+          },
+        }
+
+        // a debugger may interrupt the vm to end the session while paused
+        if INSTRUMENTED && self.debug.is_some() && self.debug_check() {
+          self.interrupted();
+          return ExecuteResult::Interrupted;
+        }
+
+        #[cfg(test)]
+        if let ExecuteMode::Normal = mode {
+          self.audit_completed();
+        }
+
         // get the current instruction
         let op_code: ByteCode = ByteCode::from(self.read_byte());
 
-        #[cfg(feature = "debug")]
+        #[cfg(test)]
+        let audit = self
+          .stack_audit
+          .is_some()
+          .then(|| self.audit_started(self.ip.sub(1)));
+
+        if INSTRUMENTED && self.tracer.is_some() && self.trace_instruction(self.ip.sub(1)).is_err()
         {
-          let ip = self.ip.sub(1);
-          if let Err(_) = self.print_state(ip) {
-            return ExecuteResult::InternalError;
+          self.internal_error("Unable to write the execution trace.");
+          return ExecuteResult::InternalError;
+        }
+
+        if INSTRUMENTED {
+          if let Some(stats) = &mut self.stats {
+            let offset = self
+              .ip
+              .offset_from(self.current_fun.chunk().instructions().as_ptr());
+            stats.record(op_code, self.current_fun, offset as usize - 1);
           }
         }
 
         // execute the decoded instruction
-        let result = match op_code {
-          ByteCode::Negate => self.op_negate(),
-          ByteCode::Add => self.op_add(),
-          ByteCode::Subtract => self.op_sub(),
-          ByteCode::Multiply => self.op_mul(),
-          ByteCode::Divide => self.op_div(),
-          ByteCode::Not => self.op_not(),
-          ByteCode::And => self.op_and(),
-          ByteCode::Or => self.op_or(),
-          ByteCode::Equal => self.op_equal(),
-          ByteCode::NotEqual => self.op_not_equal(),
-          ByteCode::Greater => self.op_greater(),
-          ByteCode::GreaterEqual => self.op_greater_equal(),
-          ByteCode::Less => self.op_less(),
-          ByteCode::LessEqual => self.op_less_equal(),
-          ByteCode::JumpIfFalse => self.op_jump_if_false(),
-          ByteCode::Jump => self.op_jump(),
-          ByteCode::Loop => self.op_loop(),
-          ByteCode::DefineGlobal => self.op_define_global(),
-          ByteCode::GetGlobal => self.op_get_global(),
-          ByteCode::SetGlobal => self.op_set_global(),
-          ByteCode::GetLocal => self.op_get_local(),
-          ByteCode::SetLocal => self.op_set_local(),
-          ByteCode::GetUpvalue => self.op_get_upvalue(),
-          ByteCode::SetUpvalue => self.op_set_upvalue(),
-          ByteCode::GetProperty => self.op_get_property(),
-          ByteCode::SetProperty => self.op_set_property(),
-          ByteCode::Import => self.op_import(),
-          ByteCode::ImportSymbol => self.op_import_symbol(),
-          ByteCode::Export => self.op_export(),
-          ByteCode::Drop => self.op_drop(),
-          ByteCode::DropN => self.op_drop_n(),
-          ByteCode::Dup => self.op_dup(),
-          ByteCode::Nil => self.op_literal(VALUE_NIL),
-          ByteCode::True => self.op_literal(val!(true)),
-          ByteCode::False => self.op_literal(val!(false)),
-          ByteCode::List => self.op_list(),
-          ByteCode::Map => self.op_map(),
-          ByteCode::Interpolate => self.op_interpolate(),
-          ByteCode::IterNext => self.op_iter_next(),
-          ByteCode::IterCurrent => self.op_iter_current(),
-          ByteCode::Constant => self.op_constant(),
-          ByteCode::ConstantLong => self.op_constant_long(),
-          ByteCode::Call => self.op_call(),
-          ByteCode::Invoke => self.op_invoke(),
-          ByteCode::SuperInvoke => self.op_super_invoke(),
-          ByteCode::Closure => self.op_closure(),
-          ByteCode::Method => self.op_method(),
-          ByteCode::Field => self.op_field(),
-          ByteCode::StaticMethod => self.op_static_method(),
-          ByteCode::Class => self.op_class(),
-          ByteCode::Inherit => self.op_inherit(),
-          ByteCode::GetSuper => self.op_get_super(),
-          ByteCode::CloseUpvalue => self.op_close_upvalue(),
-          ByteCode::Return => self.op_return(),
+        #[cfg(not(feature = "threaded_dispatch"))]
+        let result = self.dispatch(op_code);
+
+        #[cfg(feature = "threaded_dispatch")]
+        let result = match self.dispatch {
+          Dispatch::Match => self.dispatch(op_code),
+          Dispatch::Table => DISPATCH_TABLE[op_code as usize](self),
         };
 
+        let result = match result {
+          Signal::Suspend => match mode {
+            ExecuteMode::Normal => self.suspend_fiber(),
+            ExecuteMode::CallFunction(_) => self.runtime_error(
+              self.builtin.errors.runtime,
+              "Cannot await an incomplete fiber from inside a native call.",
+            ),
+          },
+          Signal::Block => match mode {
+            ExecuteMode::Normal => self.block_fiber(op_code),
+            ExecuteMode::CallFunction(_) => self.runtime_error(
+              self.builtin.errors.runtime,
+              "Cannot block a fiber from inside a native call.",
+            ),
+          },
+          result => result,
+        };
+
+        #[cfg(test)]
+        if let (Some(pending), ExecuteMode::Normal, Signal::Ok | Signal::OkReturn) =
+          (audit, &mode, &result)
+        {
+          if let Some(audit) = &mut self.stack_audit {
+            audit.pending.push(pending);
+          }
+        }
+
         match result {
           Signal::OkReturn => {
             if let ExecuteMode::CallFunction(depth) = mode {
@@ -499,22 +2212,126 @@ impl Vm {
             }
           }
           Signal::Ok => (),
-          Signal::RuntimeError => match self.fiber.error() {
-            Some(error) => {
-              if let Some(execute_result) = self.stack_unwind(error) {
-                return execute_result;
-              }
+          Signal::RuntimeError => {
+            if let Some(execute_result) = self.unwind_error(&mode) {
+              return execute_result;
             }
-            None => self.internal_error("Runtime error was not set."),
-          },
+          }
           Signal::Exit => {
+            // a timeout, interrupt or internal error inside a native call
+            // unwinds as an exit
+            if self.internal.is_some() {
+              return ExecuteResult::InternalError;
+            }
+            if self.budget.is_exhausted() {
+              return ExecuteResult::Timeout;
+            }
+            if self.interrupt.is_interrupted() {
+              return ExecuteResult::Interrupted;
+            }
+
             return ExecuteResult::Ok(self.exit_code);
           }
+          Signal::InternalError => return ExecuteResult::InternalError,
+          Signal::Suspend => {
+            self.internal_error("Unexpected suspend signal.");
+            return ExecuteResult::InternalError;
+          },
+          Signal::Block => {
+            self.internal_error("Unexpected block signal.");
+            return ExecuteResult::InternalError;
+          },
+          Signal::AwaitInput => return ExecuteResult::AwaitingInput,
         }
       }
     }
   }
 
+  /// Unwind the stack after the current fiber's error was set, returning
+  /// a result if execution can no longer continue
+  unsafe fn unwind_error(&mut self, mode: &ExecuteMode) -> Option<ExecuteResult> {
+    match self.fiber.error() {
+      Some(error) => self.stack_unwind(error, mode),
+      None => {
+        self.internal_error("Runtime error was not set.");
+        Some(ExecuteResult::InternalError)
+      },
+    }
+  }
+
+  /// Execute a single decoded instruction
+  #[inline(always)]
+  unsafe fn dispatch(&mut self, op_code: ByteCode) -> Signal {
+    match op_code {
+      ByteCode::Negate => self.op_negate(),
+      ByteCode::Add => self.op_add(),
+      ByteCode::Subtract => self.op_sub(),
+      ByteCode::Multiply => self.op_mul(),
+      ByteCode::Divide => self.op_div(),
+      ByteCode::Not => self.op_not(),
+      ByteCode::And => self.op_and(),
+      ByteCode::Or => self.op_or(),
+      ByteCode::Equal => self.op_equal(),
+      ByteCode::NotEqual => self.op_not_equal(),
+      ByteCode::EqualConst => self.op_equal_const(),
+      ByteCode::NotEqualConst => self.op_not_equal_const(),
+      ByteCode::Greater => self.op_greater(),
+      ByteCode::GreaterEqual => self.op_greater_equal(),
+      ByteCode::Less => self.op_less(),
+      ByteCode::LessEqual => self.op_less_equal(),
+      ByteCode::AddConstToLocal => self.op_add_const_to_local(),
+      ByteCode::JumpIfNotLess => self.op_jump_if_not_less(),
+      ByteCode::JumpIfFalse => self.op_jump_if_false(),
+      ByteCode::Jump => self.op_jump(),
+      ByteCode::Loop => self.op_loop(),
+      ByteCode::DefineGlobal => self.op_define_global(),
+      ByteCode::GetGlobal => self.op_get_global(),
+      ByteCode::SetGlobal => self.op_set_global(),
+      ByteCode::GetLocal => self.op_get_local(),
+      ByteCode::SetLocal => self.op_set_local(),
+      ByteCode::GetUpvalue => self.op_get_upvalue(),
+      ByteCode::SetUpvalue => self.op_set_upvalue(),
+      ByteCode::GetProperty => self.op_get_property(),
+      ByteCode::SetProperty => self.op_set_property(),
+      ByteCode::Import => self.op_import(),
+      ByteCode::ImportSymbol => self.op_import_symbol(),
+      ByteCode::Export => self.op_export(),
+      ByteCode::Drop => self.op_drop(),
+      ByteCode::DropN => self.op_drop_n(),
+      ByteCode::Dup => self.op_dup(),
+      ByteCode::Nil => self.op_literal(VALUE_NIL),
+      ByteCode::True => self.op_literal(val!(true)),
+      ByteCode::False => self.op_literal(val!(false)),
+      ByteCode::List => self.op_list(),
+      ByteCode::Map => self.op_map(),
+      ByteCode::Tuple => self.op_tuple(),
+      ByteCode::Unpack => self.op_unpack(),
+      ByteCode::Interpolate => self.op_interpolate(),
+      ByteCode::IterNext => self.op_iter_next(),
+      ByteCode::IterCurrent => self.op_iter_current(),
+      ByteCode::Await => self.op_await(),
+      ByteCode::Raise => self.op_raise(),
+      ByteCode::RaiseFrom => self.op_raise_from(),
+      ByteCode::Constant => self.op_constant(),
+      ByteCode::ConstantLong => self.op_constant_long(),
+      ByteCode::Call => self.op_call(),
+      ByteCode::Invoke => self.op_invoke(),
+      ByteCode::SuperInvoke => self.op_super_invoke(),
+      ByteCode::InvokeSealed => self.op_invoke_sealed(),
+      ByteCode::Closure => self.op_closure(),
+      ByteCode::Method => self.op_method(),
+      ByteCode::Field => self.op_field(),
+      ByteCode::StaticMethod => self.op_static_method(),
+      ByteCode::Class => self.op_class(),
+      ByteCode::Inherit => self.op_inherit(),
+      ByteCode::Extend => self.op_extend(),
+      ByteCode::Seal => self.op_seal(),
+      ByteCode::GetSuper => self.op_get_super(),
+      ByteCode::CloseUpvalue => self.op_close_upvalue(),
+      ByteCode::Return => self.op_return(),
+    }
+  }
+
   #[inline]
   fn value_class(&self, value: Value) -> GcObj<Class> {
     self.builtin.primitives.for_value(value, self.fiber.stack())
@@ -677,6 +2494,53 @@ impl Vm {
     Signal::Ok
   }
 
+  /// create a tuple from a tuple literal
+  unsafe fn op_tuple(&mut self) -> Signal {
+    let arg_count = self.read_short() as usize;
+
+    let args = self.fiber.stack_slice(arg_count);
+    let tuple = val!(self.manage_obj(Tuple::new(args)));
+    self.fiber.drop_n(arg_count);
+    self.fiber.push(tuple);
+
+    Signal::Ok
+  }
+
+  /// unpack a tuple into its items for a destructuring let
+  unsafe fn op_unpack(&mut self) -> Signal {
+    let arg_count = self.read_short() as usize;
+    let value = self.fiber.peek(0);
+
+    if !value.is_obj_kind(ObjectKind::Tuple) {
+      return self.runtime_error(
+        self.builtin.errors.runtime,
+        &format!(
+          "Can only destructure a tuple, received {}.",
+          value.value_type()
+        ),
+      );
+    }
+
+    let tuple = value.to_obj().to_tuple();
+    if tuple.len() != arg_count {
+      return self.runtime_error(
+        self.builtin.errors.runtime,
+        &format!(
+          "Expected tuple of length {} to destructure but received length {}.",
+          arg_count,
+          tuple.len()
+        ),
+      );
+    }
+
+    self.fiber.drop();
+    for item in tuple.iter() {
+      self.fiber.push(*item);
+    }
+
+    Signal::Ok
+  }
+
   /// create a map from a map literal
   unsafe fn op_map(&mut self) -> Signal {
     let arg_count = self.read_short() as usize;
@@ -730,6 +2594,7 @@ impl Vm {
         },
         Call::Err(error) => self.set_error(error),
         Call::Exit(code) => self.set_exit(code),
+        Call::Block => Signal::Block,
       }
     } else {
       let constant = self.read_short();
@@ -754,6 +2619,76 @@ impl Vm {
     })
   }
 
+  /// await the completion of a fiber, other values are passed through
+  unsafe fn op_await(&mut self) -> Signal {
+    let value = self.fiber.peek(0);
+
+    if !value.is_obj_kind(ObjectKind::Fiber) {
+      return Signal::Ok;
+    }
+
+    let fiber = value.to_obj().to_fiber();
+    match fiber.state() {
+      FiberState::Complete => {
+        self.fiber.peek_set(0, fiber.result());
+        Signal::Ok
+      }
+      FiberState::Failed => match fiber.error() {
+        Some(error) => self.set_error(error),
+        None => self.internal_error("Failed fiber did not set an error."),
+      },
+      _ => {
+        if fiber == self.fiber {
+          return self.runtime_error(self.builtin.errors.runtime, "Fiber cannot await itself.");
+        }
+
+        Signal::Suspend
+      }
+    }
+  }
+
+  /// raise a caught error again, keeping the stack trace it was first raised with
+  unsafe fn op_raise(&mut self) -> Signal {
+    let error = self.fiber.pop();
+
+    match self.to_error(error) {
+      Some(error) => self.set_error(error),
+      None => self.runtime_error(self.builtin.errors.runtime, "Only errors can be raised."),
+    }
+  }
+
+  /// Raise an error recording the error that caused it
+  unsafe fn op_raise_from(&mut self) -> Signal {
+    let cause = self.fiber.pop();
+    let error = self.fiber.pop();
+
+    let mut error = match self.to_error(error) {
+      Some(error) => error,
+      None => return self.runtime_error(self.builtin.errors.runtime, "Only errors can be raised."),
+    };
+
+    if !cause.is_nil() && self.to_error(cause).is_none() {
+      return self.runtime_error(
+        self.builtin.errors.runtime,
+        "An error can only be caused by another error or nil.",
+      );
+    }
+
+    error[ERROR_CAUSE_FIELD] = cause;
+    self.set_error(error)
+  }
+
+  /// The error instance a value holds if it is one
+  fn to_error(&self, value: Value) -> Option<GcObj<Instance>> {
+    if_let_obj!(ObjectKind::Instance(instance) = (value) {
+      if instance.class().is_subclass(self.builtin.errors.error) {
+        return Some(instance);
+      }
+    });
+
+    None
+  }
+
   /// call a function or method
   unsafe fn op_call(&mut self) -> Signal {
     let arg_count = self.read_byte();
@@ -790,19 +2725,31 @@ impl Vm {
               .set_invoke_cache(inline_slot, class, method);
             self.resolve_call(method, arg_count)
           }
-          None => self.runtime_error(
-            self.builtin.errors.property,
-            &format!(
-              "Undefined property {} on class {}.",
-              method_name,
-              class.name()
-            ),
-          ),
+          None => self.invoke_missing(class, method_name, arg_count),
         }
       }
     }
   }
 
+  /// invoke a method of a sealed class by its vtable index, falling
+  /// back to a regular invoke if the receiver is some other value
+  unsafe fn op_invoke_sealed(&mut self) -> Signal {
+    let constant = self.read_short();
+    let arg_count = self.read_byte();
+    let index = self.read_slot() as usize;
+
+    let method_name = self.read_string(constant);
+    let receiver = self.fiber.peek(arg_count as usize);
+
+    if_let_obj!(ObjectKind::Instance(instance) = (receiver) {
+      if let Some(method) = instance.class().sealed_method(index, method_name) {
+        return self.resolve_call(method, arg_count);
+      }
+    });
+
+    self.invoke(receiver, method_name, arg_count)
+  }
+
   /// invoke a method
   unsafe fn invoke(&mut self, receiver: Value, method_name: GcStr, arg_count: u8) -> Signal {
     if_let_obj!(ObjectKind::Instance(instance) = (receiver) {
@@ -825,7 +2772,7 @@ impl Vm {
     let method_name = self.read_string(constant);
     let super_class = self.fiber.pop().to_obj().to_class();
 
-    match self
+    let signal = match self
       .inline_cache()
       .get_invoke_cache(inline_slot, super_class)
     {
@@ -837,16 +2784,33 @@ impl Vm {
             .set_invoke_cache(inline_slot, super_class, method);
           self.resolve_call(method, arg_count)
         }
-        None => self.runtime_error(
-          self.builtin.errors.property,
-          &format!(
-            "Undefined property {} on class {}.",
-            method_name,
-            super_class.name()
+        None => match self.super_field(
+          super_class,
+          self.fiber.peek(arg_count as usize),
+          method_name,
+        ) {
+          Some(field) => {
+            self.fiber.peek_set(arg_count as usize, field);
+            self.resolve_call(field, arg_count)
+          },
+          None => self.runtime_error(
+            self.builtin.errors.property,
+            &format!(
+              "Undefined property {} on class {}.",
+              method_name,
+              super_class.name()
+            ),
           ),
-        ),
+        },
       },
+    };
+
+    // restore the super class so the invoke can be retried
+    if signal == Signal::Block {
+      self.fiber.push(val!(super_class));
     }
+
+    signal
   }
 
   /// Generate a new class
@@ -866,28 +2830,112 @@ impl Vm {
       return self.runtime_error(self.builtin.errors.runtime, "Superclass must be a class.");
     }
 
+    let super_class = super_class.to_obj().to_class();
+    if super_class.is_sealed() {
+      return self.runtime_error(
+        self.builtin.errors.runtime,
+        &format!("Cannot inherit from sealed class {}.", super_class.name()),
+      );
+    }
+
     let hooks = GcHooks::new(self);
     let mut sub_class = self.fiber.peek(0).to_obj().to_class();
 
-    sub_class.inherit(&hooks, super_class.to_obj().to_class());
+    sub_class.inherit(&hooks, super_class);
     sub_class.meta_from_super(&hooks);
 
     Signal::Ok
   }
 
+  /// Open the class on top of the stack to have methods added to it
+  unsafe fn op_extend(&mut self) -> Signal {
+    let class = self.fiber.peek(0);
+
+    if !class.is_obj_kind(ObjectKind::Class) {
+      return self.runtime_error(
+        self.builtin.errors.runtime,
+        &format!("Can only extend classes, received {}.", class.value_type()),
+      );
+    }
+
+    let class = class.to_obj().to_class();
+    if class.is_sealed() {
+      return self.runtime_error(
+        self.builtin.errors.runtime,
+        &format!("Cannot extend sealed class {}.", class.name()),
+      );
+    }
+
+    // the extension may replace methods already cached at invoke sites
+    self.reset_inline_caches();
+    Signal::Ok
+  }
+
+  /// Seal the class on top of the stack, the remaining methods in its
+  /// body are added to its vtable
+  unsafe fn op_seal(&mut self) -> Signal {
+    let mut class = self.fiber.peek(0).to_obj().to_class();
+    class.seal();
+    Signal::Ok
+  }
+
   /// Get this classes super class
   unsafe fn op_get_super(&mut self) -> Signal {
     let slot = self.read_short();
     let name = self.read_string(slot);
     let super_class = self.fiber.pop().to_obj().to_class();
 
-    self.bind_method(super_class, name)
+    if super_class.get_method(&name).is_some() {
+      return self.bind_method(super_class, name);
+    }
+
+    match self.super_field(super_class, self.fiber.peek(0), name) {
+      Some(field) => {
+        self.fiber.peek_set(0, field);
+        Signal::Ok
+      },
+      None => self.runtime_error(
+        self.builtin.errors.property,
+        &format!(
+          "Undefined property {} on class {}.",
+          name,
+          super_class.name()
+        ),
+      ),
+    }
+  }
+
+  /// Read a field declared by the super class from the receiver
+  unsafe fn super_field(
+    &self,
+    super_class: GcObj<Class>,
+    receiver: Value,
+    name: GcStr,
+  ) -> Option<Value> {
+    super_class.get_field_index(&name)?;
+
+    if_let_obj!(ObjectKind::Instance(instance) = (receiver) {
+      return instance.get_field(&name).copied();
+    });
+
+    None
   }
 
   /// Loop by performing an unconditional jump to a new instruction
   unsafe fn op_loop(&mut self) -> Signal {
     let jump = self.read_short() as isize;
     self.update_ip(-jump);
+
+    // loop back edges are where a host interrupt is observed
+    if self.interrupt.is_interrupted() {
+      return self.interrupted();
+    }
+
+    // and where the main fiber dispatches signals
+    if self.fiber == self.main_fiber && self.signals.is_pending() {
+      return self.dispatch_signals();
+    }
+
     Signal::Ok
   }
 
@@ -954,6 +3002,13 @@ impl Vm {
     if_let_obj!(ObjectKind::Instance(mut instance) = (instance) {
       let class = instance.class();
 
+      if instance.is_frozen() {
+        return self.runtime_error(
+          self.builtin.errors.runtime,
+          &format!("Cannot set property {} on a frozen {} instance.", name, class.name()),
+        );
+      }
+
       match self.inline_cache().get_property_cache(inline_slot, class) {
         Some(property_slot) => {
           let value = self.fiber.pop();
@@ -965,24 +3020,30 @@ impl Vm {
           return Signal::Ok;
         },
         None => {
-          let property_slot = class.get_field_index(&name);
-          let value = self.fiber.pop();
-
-          self.fiber.drop();
-          self.fiber.push(value);
-
-          return match property_slot {
+          match class.get_field_index(&name) {
             Some(property_slot) => {
               let cache = self.inline_cache_mut();
               cache.set_property_cache(inline_slot, class, property_slot as usize);
-              instance[property_slot as usize] = value;
-              Signal::Ok
+              instance[property_slot as usize] = self.fiber.peek(0);
             },
-            None => self.runtime_error(
-              self.builtin.errors.property,
-              &format!("Undefined property {} on class {}.", name, class.name()),
-            ),
-          };
+            None => {
+              if class.is_sealed() {
+                return self.runtime_error(
+                  self.builtin.errors.runtime,
+                  &format!("Cannot add field {} to a sealed {} instance.", name, class.name()),
+                );
+              }
+
+              // the instance and value stay on the stack while the table grows
+              let value = self.fiber.peek(0);
+              instance.set_dynamic_field(&GcHooks::new(self), name, value);
+            },
+          }
+
+          let value = self.fiber.pop();
+          self.fiber.drop();
+          self.fiber.push(value);
+          return Signal::Ok;
         },
       }
     });
@@ -1061,6 +3122,11 @@ impl Vm {
             self.fiber.peek_set(0, instance[property_slot as usize]);
             return Signal::Ok;
           }
+
+          if let Some(value) = instance.get_dynamic_field(&name) {
+            self.fiber.peek_set(0, *value);
+            return Signal::Ok;
+          }
         },
       }
     });
@@ -1075,9 +3141,22 @@ impl Vm {
     let index_path = self.read_short();
     let path = self.read_constant(index_path).to_obj().to_list();
 
-    let mut path_segments: Gc<List<GcStr>> = self.manage(List::with_capacity(path.len()));
+    // the segments stay rooted through the current function's constants
+    let path_segments: Vec<GcStr> = path
+      .iter()
+      .map(|segment| segment.to_obj().to_str())
+      .collect();
 
-    path_segments.extend(path.iter().map(|segment| segment.to_obj().to_str()));
+    if let Some(result) = self.import_source(&path_segments) {
+      return match result {
+        Ok(module) => {
+          let imported = module.module_instance(&GcHooks::new(self));
+          self.fiber.push(val!(imported));
+          Signal::Ok
+        }
+        Err(signal) => signal,
+      };
+    }
 
     let mut buffer = String::new();
     for segment in &path_segments[..path_segments.len() - 1] {
@@ -1110,8 +3189,11 @@ impl Vm {
 
     self.gc().push_root(import);
 
-    let result = match self.packages.get(&import.package()) {
-      Some(package) => match package.import(&GcHooks::new(self), import) {
+    let result = match self.packages.get(&import.package()).copied() {
+      Some(package) => match self
+        .initialize_module(package, import)
+        .and_then(|()| package.import(&GcHooks::new(self), import))
+      {
         Ok(module) => {
           self.fiber.push(val!(module));
           Signal::Ok
@@ -1134,57 +3216,302 @@ impl Vm {
     let path = self.read_constant(index_path).to_obj().to_list();
     let name = self.read_string(index_name);
 
-    let mut path_segments: Gc<List<GcStr>> = self.manage(List::with_capacity(path.len()));
+    // the segments stay rooted through the current function's constants
+    let path_segments: Vec<GcStr> = path
+      .iter()
+      .map(|segment| segment.to_obj().to_str())
+      .collect();
+
+    if let Some(result) = self.import_source(&path_segments) {
+      return match result {
+        Ok(module) => match module.get_exported_symbol(name) {
+          Ok(symbol) => {
+            self.fiber.push(symbol);
+            Signal::Ok
+          }
+          Err(_) => self.runtime_error(
+            self.builtin.errors.import,
+            &format!("Symbol {} is not exported by {}.", &*name, &*module.name()),
+          ),
+        },
+        Err(signal) => signal,
+      };
+    }
+
+    let mut buffer = String::new();
+    for segment in &path_segments[..path_segments.len() - 1] {
+      buffer.push_str(segment);
+      buffer.push('/')
+    }
+
+    buffer.push_str(&path_segments[path_segments.len() - 1]);
+
+    // check if fully resolved module has already been loaded
+    let resolved = self.manage_str(buffer);
+    if let Some(module) = self.module_cache.get(&resolved) {
+      let imported = module.module_instance(&GcHooks::new(self));
+      self.fiber.push(val!(imported));
+      return Signal::Ok;
+    }
+
+    let import = match path_segments.split_first() {
+      Some((package, path)) => {
+        // generate a new import object
+        let path = self.manage(List::from(path));
+        self.manage(Import::new(*package, path))
+      }
+      None => {
+        // generate a new import object
+        let path = self.manage(List::new());
+        self.manage(Import::new(path_segments[0], path))
+      }
+    };
+
+    self.gc().push_root(import);
+
+    let result = match self.packages.get(&import.package()).copied() {
+      Some(package) => match self
+        .initialize_module(package, import)
+        .and_then(|()| package.import_symbol(&GcHooks::new(self), import, name))
+      {
+        Ok(module) => {
+          self.fiber.push(val!(module));
+          Signal::Ok
+        }
+        Err(err) => self.runtime_error(self.builtin.errors.runtime, &err.to_string()),
+      },
+      None => self.runtime_error(
+        self.builtin.errors.import,
+        &format!("Package {} does not exist", &import.package()),
+      ),
+    };
+
+    self.gc().pop_roots(1);
+    result
+  }
+
+  /// Construct the module of a native package an import resolves through
+  /// if this is its first import. Collection is paused while the module is
+  /// built as its objects are not rooted until it joins the package
+  fn initialize_module(
+    &mut self,
+    mut package: Gc<Package>,
+    import: Gc<Import>,
+  ) -> ModuleResult<()> {
+    let no_gc_context = NoContext::new(self.gc.replace(Allocator::default()));
+    let result = package.initialize(&GcHooks::new(&no_gc_context), &mut self.emitter, import);
+    self.gc.replace(no_gc_context.done());
+
+    result
+  }
+
+  /// Import a module compiled from source, either relative to the importing
+  /// module or from a package loaded from a manifest. None is returned for
+  /// imports from native packages such as std
+  unsafe fn import_source(&mut self, segments: &[GcStr]) -> Option<Result<Gc<Module>, Signal>> {
+    if is_relative(segments) {
+      return Some(self.import_relative(segments));
+    }
+
+    let package = *self.packages.get(&segments[0])?;
+    package.root_dir()?;
+    Some(self.import_package(package, segments))
+  }
+
+  /// Import the module at a path relative to the importing module such as
+  /// `./utils` or `../shared/helpers`
+  unsafe fn import_relative(&mut self, segments: &[GcStr]) -> Result<Gc<Module>, Signal> {
+    let importer = self.current_fun.module();
+    let relative = segments
+      .iter()
+      .map(|segment| &**segment)
+      .collect::<Vec<&str>>()
+      .join("/");
+
+    let mut path = importer.path().clone();
+    path.pop();
+    path.push(&relative);
+    path.set_extension("lay");
+
+    self.load_module(normalize_path(&path), &relative)
+  }
+
+  /// Import a module from a package loaded from a manifest such as
+  /// `shapes/circle`. Modules in a package can only import from the
+  /// packages it depends on
+  unsafe fn import_package(
+    &mut self,
+    package: Gc<Package>,
+    segments: &[GcStr],
+  ) -> Result<Gc<Module>, Signal> {
+    let importer = self.current_fun.module();
+
+    if let Some(importing) = self.package_of(importer.path()) {
+      if !importing.can_import(package.name()) {
+        return Err(self.runtime_error(
+          self.builtin.errors.import,
+          &format!(
+            "Package {} does not depend on {}.",
+            &*importing.name(),
+            &*package.name()
+          ),
+        ));
+      }
+    }
+
+    let import = segments
+      .iter()
+      .map(|segment| &**segment)
+      .collect::<Vec<&str>>()
+      .join("/");
+
+    let path = package
+      .module_path(&segments[1..])
+      .expect("Expected package with a root directory");
+    self.load_module(path, &import)
+  }
 
-    path_segments.extend(path.iter().map(|segment| segment.to_obj().to_str()));
+  /// The source package a module at this path belongs to. Packages may be
+  /// nested so the package with the deepest root directory is used
+  fn package_of(&self, path: &Path) -> Option<Gc<Package>> {
+    self
+      .packages
+      .values()
+      .filter(|package| {
+        package
+          .root_dir()
+          .is_some_and(|root_dir| path.starts_with(root_dir))
+      })
+      .max_by_key(|package| {
+        package
+          .root_dir()
+          .map_or(0, |root_dir| root_dir.as_os_str().len())
+      })
+      .copied()
+  }
 
-    let mut buffer = String::new();
-    for segment in &path_segments[..path_segments.len() - 1] {
-      buffer.push_str(segment);
-      buffer.push('/')
+  /// Load the source module at this path. A module is compiled and run the
+  /// first time it is loaded with later imports reusing the cached module.
+  /// Importing a module that is still being initialized is an import error
+  unsafe fn load_module(&mut self, path: PathBuf, import: &str) -> Result<Gc<Module>, Signal> {
+    let importer = self.current_fun.module();
+    let resolved = self.manage_str(path.to_string_lossy());
+    if let Some(module) = self.module_cache.get(&resolved) {
+      return Ok(*module);
     }
 
-    buffer.push_str(&path_segments[path_segments.len() - 1]);
+    // a module that is still being initialized was imported again
+    if let Some(start) = self
+      .import_stack
+      .iter()
+      .position(|module| module.path() == &path)
+    {
+      let cycle: Vec<String> = self.import_stack[start..]
+        .iter()
+        .map(|module| self.file_name(module.path()))
+        .chain(iter::once(self.file_name(&path)))
+        .collect();
 
-    // check if fully resolved module has already been loaded
-    let resolved = self.manage_str(buffer);
-    if let Some(module) = self.module_cache.get(&resolved) {
-      let imported = module.module_instance(&GcHooks::new(self));
-      self.fiber.push(val!(imported));
-      return Signal::Ok;
+      return Err(self.runtime_error(
+        self.builtin.errors.import,
+        &format!("Circular import {}.", cycle.join(" -> ")),
+      ));
     }
 
-    let import = match path_segments.split_first() {
-      Some((package, path)) => {
-        // generate a new import object
-        let path = self.manage(List::from(path));
-        self.manage(Import::new(*package, path))
+    let resolved_module = match self.io.resolve_import(&path) {
+      Ok(resolved_module) => resolved_module,
+      Err(_) => {
+        return Err(self.runtime_error(
+          self.builtin.errors.import,
+          &format!(
+            "Module {} imported from {} was not found at {}.",
+            import,
+            self.file_name(importer.path()),
+            self.file_name(&path)
+          ),
+        ))
       }
-      None => {
-        // generate a new import object
-        let path = self.manage(List::new());
-        self.manage(Import::new(path_segments[0], path))
+    };
+
+    self.push_root(resolved);
+    let module_id = self.emitter.emit();
+    let hooks = GcHooks::new(self);
+    let module = Module::from_path(
+      &hooks,
+      path.clone(),
+      self.builtin.dependencies.module,
+      module_id,
+    );
+
+    let mut module = match module {
+      Ok(module) => hooks.manage(module),
+      Err(err) => {
+        self.pop_roots(1);
+        return Err(self.runtime_error(self.builtin.errors.import, &err.to_string()));
       }
     };
 
-    self.gc().push_root(import);
+    self.import_stack.push(module);
+    self
+      .global
+      .transfer_exported(&GcHooks::new(self), &mut module);
 
-    let result = match self.packages.get(&import.package()) {
-      Some(package) => match package.import_symbol(&GcHooks::new(self), import, name) {
-        Ok(module) => {
-          self.fiber.push(val!(module));
-          Signal::Ok
+    let fun = match resolved_module {
+      ResolvedModule::Source(source) => {
+        let source_content = self.manage_str(source);
+        self.push_root(source_content);
+        let managed_path = self.manage_str(self.file_name(&path));
+        self.push_root(managed_path);
+        let file_id = self.files.upsert(managed_path, source_content);
+        self.pop_roots(2);
+
+        match self.compile(module, &Source::new(&source_content), file_id, false) {
+          Ok(fun) => fun,
+          Err(errors) => {
+            self.import_stack.pop();
+            self.pop_roots(1);
+            // the diagnostics are reported here as the script only sees the import error
+            self.report(&VmError::Compile(errors));
+            return Err(self.runtime_error(
+              self.builtin.errors.import,
+              &format!("Module {} failed to compile.", import),
+            ));
+          }
+        }
+      }
+      ResolvedModule::Compiled(bytes) => match self.load_precompiled(module, &bytes) {
+        Some(fun) => fun,
+        None => {
+          self.import_stack.pop();
+          self.pop_roots(1);
+          return Err(self.runtime_error(
+            self.builtin.errors.import,
+            &format!(
+              "Module {} was not precompiled by Laythe {}.",
+              import, VERSION
+            ),
+          ));
         }
-        Err(err) => self.runtime_error(self.builtin.errors.runtime, &err.to_string()),
       },
-      None => self.runtime_error(
-        self.builtin.errors.import,
-        &format!("Package {} does not exist", &import.package()),
-      ),
     };
 
-    self.gc().pop_roots(1);
-    result
+    let script = val!(self.manage_obj(Closure::without_upvalues(fun)));
+    let result = self.run_fun(script, &[]);
+    self.import_stack.pop();
+    self.pop_roots(1);
+
+    match result {
+      ExecuteResult::FunResult(_) => {
+        self.fiber.drop();
+        self.module_cache.insert(resolved, module);
+        Ok(module)
+      }
+      result => Err(match self.call_result(result) {
+        Call::Err(error) => self.set_error(error),
+        Call::Exit(code) => self.set_exit(code),
+        _ => self.internal_error("Unexpected result while importing a module."),
+      }),
+    }
   }
 
   unsafe fn op_export(&mut self) -> Signal {
@@ -1203,9 +3530,9 @@ impl Vm {
     // get the function result close upvalues and pop frame
     let result = self.fiber.pop();
 
-    // pop a frame from the call stack return signal if provided
-    if let Some(signal) = self.pop_frame() {
-      return signal;
+    // pop a frame from the call stack completing the fiber if none remain
+    if self.pop_frame().is_some() {
+      return self.complete_fiber(result);
     }
 
     // push result onto stack
@@ -1255,6 +3582,32 @@ impl Vm {
     }
   }
 
+  /// Add a constant to a local in place. Equivalent to
+  /// `GetLocal Constant Add SetLocal Drop`
+  unsafe fn op_add_const_to_local(&mut self) -> Signal {
+    let constant = self.read_short();
+    let constant = self.read_constant(constant);
+    let slot = self.read_byte() as isize;
+    let local = self.slot(slot);
+
+    if local.is_num() && constant.is_num() {
+      self.set_slot(slot, val!(local.to_num() + constant.to_num()));
+      return Signal::Ok;
+    }
+
+    self.fiber.push(local);
+    self.fiber.push(constant);
+
+    match self.op_add() {
+      Signal::Ok => {
+        let sum = self.fiber.pop();
+        self.set_slot(slot, sum);
+        Signal::Ok
+      },
+      signal => signal,
+    }
+  }
+
   unsafe fn op_sub(&mut self) -> Signal {
     let (right, left) = (self.fiber.pop(), self.fiber.pop());
 
@@ -1332,6 +3685,34 @@ impl Vm {
     self.runtime_error(self.builtin.errors.runtime, "Operands must be numbers.")
   }
 
+  /// Jump if the left operand is not less than the right.
+  /// Equivalent to `Less JumpIfFalse`
+  unsafe fn op_jump_if_not_less(&mut self) -> Signal {
+    let jump = self.read_short();
+    let (right, left) = (self.fiber.pop(), self.fiber.pop());
+
+    if right.is_num() && left.is_num() {
+      let less = left.to_num() < right.to_num();
+      if !less {
+        self.update_ip(jump as isize);
+      }
+      return Signal::Ok;
+    }
+
+    self.fiber.push(left);
+    self.fiber.push(right);
+
+    match self.op_less() {
+      Signal::Ok => {
+        if is_falsey(self.fiber.pop()) {
+          self.update_ip(jump as isize);
+        }
+        Signal::Ok
+      },
+      signal => signal,
+    }
+  }
+
   unsafe fn op_less_equal(&mut self) -> Signal {
     let (right, left) = (self.fiber.pop(), self.fiber.pop());
 
@@ -1421,6 +3802,26 @@ impl Vm {
     Signal::Ok
   }
 
+  /// Compare against a string constant. As strings are interned
+  /// this is only an id comparison
+  unsafe fn op_equal_const(&mut self) -> Signal {
+    let slot = self.read_short();
+    let constant = self.read_constant(slot);
+
+    let left = self.fiber.pop();
+    self.fiber.push(val!(left == constant));
+    Signal::Ok
+  }
+
+  unsafe fn op_not_equal_const(&mut self) -> Signal {
+    let slot = self.read_short();
+    let constant = self.read_constant(slot);
+
+    let left = self.fiber.pop();
+    self.fiber.push(val!(left != constant));
+    Signal::Ok
+  }
+
   unsafe fn op_method(&mut self) -> Signal {
     let slot = self.read_short();
     let name = self.read_string(slot);
@@ -1434,7 +3835,7 @@ impl Vm {
         .to_class()
         .add_method(&GcHooks::new(self), name, method);
     } else {
-      self.internal_error("Invalid Stack for op_method.");
+      return self.internal_error("Invalid Stack for op_method.");
     }
 
     self.fiber.drop();
@@ -1450,7 +3851,7 @@ impl Vm {
     if_let_obj!(ObjectKind::Class(mut class) = (class) {
       class.add_field(&GcHooks::new(self), name);
     } else {
-      self.internal_error("Invalid Stack for op_method.");
+      return self.internal_error("Invalid Stack for op_field.");
     });
 
     Signal::Ok
@@ -1470,10 +3871,10 @@ impl Vm {
         Some(mut meta) => {
           meta.add_method(&GcHooks::new(self), name, method);
         }
-        None => self.internal_error(&format!("{} meta class not set.", class.name())),
+        None => return self.internal_error(&format!("{} meta class not set.", class.name())),
       }
     } else {
-      self.internal_error("Invalid Stack for op_static_method.");
+      return self.internal_error("Invalid Stack for op_static_method.");
     }
 
     self.fiber.drop();
@@ -1537,7 +3938,7 @@ impl Vm {
 
     match_obj!((&callee.to_obj()) {
       ObjectKind::Closure(closure) => {
-        self.call(closure, arg_count)
+        self.call_closure(closure, arg_count)
       },
       ObjectKind::Method(method) => {
         self.call_method(method, arg_count)
@@ -1601,31 +4002,59 @@ impl Vm {
       None
     };
 
+    // methods that mutate their receiver are rejected on frozen objects
+    if let Some(this) = this {
+      if meta.mutates && this.is_obj() && this.to_obj().is_frozen() {
+        return self.runtime_error(
+          self.builtin.errors.runtime,
+          &format!(
+            "Cannot call {} on a frozen {}.",
+            meta.name,
+            self.value_class(this).name()
+          ),
+        );
+      }
+    }
+
     #[cfg(debug_assertions)]
     let roots_before = self.gc().temp_roots();
 
+    let depth = fiber.frames().len();
+    let height = fiber.stack_height();
     match meta.environment {
-      Environment::StackLess => match native.call(&mut Hooks::new(self), this, args) {
-        Call::Ok(value) => {
-          fiber.drop_n(arg_count as usize + 1);
-          fiber.push(value);
+      Environment::StackLess => {
+        let result = native.call(&mut Hooks::new(self), this, args);
+        self.trace_channel(native, this, &result);
+
+        match result {
+          Call::Ok(value) => {
+            self.restore_frames(depth, height);
+            fiber.drop_n(arg_count as usize + 1);
+            fiber.push(value);
 
-          #[cfg(debug_assertions)]
-          {
-            let roots_current = self.gc().temp_roots();
-            assert_roots(native, roots_before, roots_current);
+            #[cfg(debug_assertions)]
+            {
+              let roots_current = self.gc().temp_roots();
+              assert_roots(native, roots_before, roots_current);
+            }
+            Signal::OkReturn
+          }
+          Call::Err(error) => self.set_error(error),
+          Call::Exit(code) => self.set_exit(code),
+          Call::Block => {
+            // drop anything left by laythe calls the native made before blocking
+            self.restore_frames(depth, height);
+            Signal::Block
           }
-          Signal::OkReturn
         }
-        Call::Err(error) => self.set_error(error),
-        Call::Exit(code) => self.set_exit(code),
-      },
+      }
       Environment::Normal => {
         let native_closure = self.manage_obj(Closure::without_upvalues(self.native_fun_stub));
         self.push_frame(native_closure, arg_count);
 
         match native.call(&mut Hooks::new(self), this, args) {
           Call::Ok(value) => {
+            self.restore_frames(depth + 1, height);
             self.pop_frame();
             fiber.push(value);
 
@@ -1638,20 +4067,29 @@ impl Vm {
           }
           Call::Err(error) => self.set_error(error),
           Call::Exit(code) => self.set_exit(code),
+          Call::Block => self.internal_error(&format!(
+            "Native {} cannot block with a normal environment.",
+            native.meta().name
+          )),
         }
       }
     }
   }
 
   /// call a laythe function setting it as the new call frame
-  unsafe fn call(&mut self, closure: GcObj<Closure>, arg_count: u8) -> Signal {
+  unsafe fn call_closure(&mut self, closure: GcObj<Closure>, arg_count: u8) -> Signal {
     // check that the current function is called with the right number of args
     if let Some(error) = self.check_arity(closure.fun(), arg_count) {
       return error;
     }
 
+    // async functions run on their own fiber
+    if closure.fun().is_async() {
+      return self.spawn(closure, arg_count);
+    }
+
     // set the current current instruction pointer. check for overflow
-    if self.fiber.frames().len() == MAX_FRAME_SIZE {
+    if self.fiber.frames().len() >= self.frame_limit {
       return self.runtime_error(self.builtin.errors.runtime, "Stack overflow.");
     }
 
@@ -1659,15 +4097,178 @@ impl Vm {
     Signal::Ok
   }
 
+  /// Spawn a new fiber to run an async closure. The new fiber
+  /// is scheduled and replaces the callee on the stack
+  unsafe fn spawn(&mut self, closure: GcObj<Closure>, arg_count: u8) -> Signal {
+    let mut fiber = match Fiber::with_stack_size(closure, self.stack_size) {
+      Ok(fiber) => self.manage_obj(fiber),
+      Err(_) => return self.internal_error("Unable to generate async fiber."),
+    };
+
+    // move the callee and arguments onto the new fiber
+    let mut current = self.fiber;
+    let args = current.stack_slice(arg_count as usize + 1);
+    fiber.ensure_stack(args.len());
+    fiber.drop();
+    for arg in args {
+      fiber.push(*arg);
+    }
+    fiber.ensure_stack(closure.fun().max_slots());
+
+    self.scheduler.push(fiber);
+    current.drop_n(arg_count as usize + 1);
+    current.push(val!(fiber));
+    Signal::OkReturn
+  }
+
+  /// Suspend the current fiber until the fiber on top of the
+  /// stack has completed
+  unsafe fn suspend_fiber(&mut self) -> Signal {
+    let mut awaited = self.fiber.peek(0).to_obj().to_fiber();
+
+    // rewind so the await is retried once this fiber is resumed
+    self.update_ip(-1);
+    self.store_ip();
+
+    awaited.add_waiter(self.fiber);
+    self.fiber.block();
+    self.schedule_next()
+  }
+
+  /// Block the current fiber after a native has registered it to be
+  /// woken. The instruction that called the native is retried on resume
+  unsafe fn block_fiber(&mut self, op_code: ByteCode) -> Signal {
+    let width = match op_code {
+      ByteCode::Call => 2,
+      ByteCode::IterNext => 3,
+      ByteCode::Invoke | ByteCode::SuperInvoke | ByteCode::InvokeSealed => 8,
+      _ => return self.internal_error(&format!("{:?} cannot block a fiber.", op_code)),
+    };
+
+    self.update_ip(-width);
+    self.store_ip();
+
+    self.fiber.block();
+    self.schedule_next()
+  }
+
+  /// Complete the current fiber with the provided result
+  unsafe fn complete_fiber(&mut self, result: Value) -> Signal {
+    self.fiber.set_result(result);
+    self.wake_waiters();
+    self.schedule_next()
+  }
+
+  /// Fail the current fiber from an unhandled error. The error is
+  /// reported if no other fiber is waiting on or supervising this fiber
+  unsafe fn fail_fiber(&mut self, error: GcObj<Instance>) -> Signal {
+    if !self.fiber.has_waiters() && !self.fiber.is_supervised() {
+      self.print_error(error);
+    }
+
+    self.fiber.fail(error);
+    self.wake_waiters();
+    self.schedule_next()
+  }
+
+  /// Move any fibers waiting on the current fiber back onto the ready queue
+  fn wake_waiters(&mut self) {
+    for mut waiter in self.fiber.take_waiters() {
+      if waiter.wake() {
+        self.scheduler.push(waiter);
+      }
+    }
+  }
+
+  /// Switch to the next ready fiber. If no fiber is ready either return
+  /// to the host while fibers wait on its input, exit if the main fiber
+  /// has completed or raise a deadlock error on the main fiber. A
+  /// cancelled fiber raises its cancellation error once switched to
+  unsafe fn schedule_next(&mut self) -> Signal {
+    match self.scheduler.next(self.main_fiber, &self.signals) {
+      Some(fiber) => {
+        self.switch_fiber(fiber);
+        match self.fiber.take_cancel() {
+          Some(error) => self.set_error(error),
+          None if fiber == self.main_fiber && self.signals.is_pending() => self.dispatch_signals(),
+          None => Signal::Ok,
+        }
+      }
+      None => {
+        if self.scheduler.is_awaiting_input() {
+          return Signal::AwaitInput;
+        }
+
+        if self.main_fiber.is_complete() {
+          return Signal::Exit;
+        }
+
+        self.switch_fiber(self.main_fiber);
+        self.runtime_error(
+          self.builtin.errors.runtime,
+          "Deadlock, all fibers are blocked.",
+        )
+      }
+    }
+  }
+
+  /// Call the handlers of each signal that has arrived on the main fiber.
+  /// An error from a handler is raised where the main fiber was
+  /// interrupted and an exit from a handler exits the vm
+  unsafe fn dispatch_signals(&mut self) -> Signal {
+    while let Some((signal, handlers)) = self.signals.take_pending() {
+      let name = val!(self.manage_str(signal.name()));
+      self.push_root(name);
+
+      for handler in handlers {
+        let signal = match self.run_fun(handler, &[name]) {
+          ExecuteResult::FunResult(_) => {
+            self.fiber.drop();
+            continue;
+          },
+          ExecuteResult::Ok(_) | ExecuteResult::Timeout | ExecuteResult::Interrupted => {
+            Signal::Exit
+          },
+          ExecuteResult::RuntimeError => match self.fiber.error() {
+            Some(error) => self.set_error(error),
+            None => self.internal_error("Runtime error was not set."),
+          },
+          ExecuteResult::InternalError => Signal::InternalError,
+          ExecuteResult::AwaitingInput => {
+            self.internal_error("Signal handler cannot wait for input.")
+          },
+        };
+
+        self.pop_roots(1);
+        return signal;
+      }
+
+      self.pop_roots(1);
+    }
+
+    Signal::Ok
+  }
+
+  /// Make the provided fiber the currently running fiber
+  fn switch_fiber(&mut self, mut fiber: GcObj<Fiber>) {
+    self.profile_event();
+    fiber.activate();
+    self.fiber = fiber;
+    self.current_fun = fiber.closure().fun();
+    self.load_ip();
+  }
+
   /// Push a call frame onto the the call frame stack
   #[inline]
   unsafe fn push_frame(&mut self, closure: GcObj<Closure>, arg_count: u8) {
+    self.profile_event();
     self.store_ip();
 
     self.fiber.push_frame(closure, arg_count as usize);
     self.load_ip();
 
     self.current_fun = closure.fun();
+    self.trace_call("call", self.current_fun);
   }
 
   /// Pop a frame off the call stack. If no frame remain
@@ -1675,6 +4276,8 @@ impl Vm {
   /// pointer and current function
   #[inline]
   unsafe fn pop_frame(&mut self) -> Option<Signal> {
+    self.profile_event();
+    self.trace_call("return", self.current_fun);
     match self.fiber.pop_frame() {
       Some(current_fun) => match current_fun {
         Some(current_fun) => {
@@ -1684,7 +4287,7 @@ impl Vm {
         }
         None => Some(Signal::Exit),
       },
-      None => self.internal_error("Compilation failure attempted to pop last frame"),
+      None => Some(self.internal_error("Compilation failure attempted to pop last frame")),
     }
   }
 
@@ -1808,7 +4411,15 @@ impl Vm {
   /// Call a bound method
   unsafe fn call_method(&mut self, bound: GcObj<Method>, arg_count: u8) -> Signal {
     self.fiber.peek_set(arg_count as usize, bound.receiver());
-    self.resolve_call(bound.method(), arg_count)
+
+    match self.resolve_call(bound.method(), arg_count) {
+      Signal::Block => {
+        // restore the bound method so the call can be retried
+        self.fiber.peek_set(arg_count as usize, val!(bound));
+        Signal::Block
+      }
+      signal => signal,
+    }
   }
 
   /// bind a method to an instance
@@ -1835,6 +4446,33 @@ impl Vm {
   ) -> Signal {
     match class.get_method(&method_name) {
       Some(method) => self.resolve_call(method, arg_count),
+      None => self.invoke_missing(class, method_name, arg_count),
+    }
+  }
+
+  /// Fallback for an unresolved invoke. If the class defines
+  /// onMissingMethod it is called with the method name and a list
+  /// of the original arguments, otherwise a property error is raised
+  unsafe fn invoke_missing(
+    &mut self,
+    class: GcObj<Class>,
+    method_name: GcStr,
+    arg_count: u8,
+  ) -> Signal {
+    let hook_name = self.manage_str(ON_MISSING_METHOD);
+
+    match class.get_method(&hook_name) {
+      Some(hook) => {
+        let arg_count = arg_count as usize;
+        let args = self.fiber.stack_slice(arg_count);
+        let args = val!(self.manage_obj(List::from(args)));
+        self.fiber.drop_n(arg_count);
+
+        self.fiber.ensure_stack(2);
+        self.fiber.push(val!(method_name));
+        self.fiber.push(args);
+        self.resolve_call(hook, 2)
+      }
       None => self.runtime_error(
         self.builtin.errors.property,
         &format!(
@@ -1846,17 +4484,194 @@ impl Vm {
     }
   }
 
-  /// Print debugging information for a caught exceptions
-  #[cfg(feature = "debug")]
-  unsafe fn print_exception_debug(&self, frame: &CallFrame, idx: usize) -> io::Result<()> {
-    let mut stdio = self.io.stdio();
-    let mut stdout = stdio.stdout();
+  /// Print debugging information for a caught exceptions
+  #[cfg(feature = "debug")]
+  unsafe fn print_exception_debug(&self, frame: &CallFrame, idx: usize) -> io::Result<()> {
+    let mut stdio = self.io.stdio();
+    let mut stdout = stdio.stdout();
+
+    exception_catch(&mut stdout, frame, idx)
+  }
+
+  /// Pause execution before the next instruction if the attached
+  /// debugger requests it. Returns true if the vm was interrupted while paused
+  unsafe fn debug_check(&mut self) -> bool {
+    let chunk = self.current_fun.chunk();
+    if chunk.lines().is_empty() {
+      return false;
+    }
+
+    // spans are keyed by the offset following an instruction
+    let offset = self.ip.offset_from(chunk.instructions().as_ptr()) as usize;
+    let location = Location {
+      depth: self.fiber.frames().len(),
+      fun: self.current_fun,
+      line: chunk.get_line(offset + 1),
+    };
+    let module = self.current_fun.module();
+
+    let reason = match &mut self.debug {
+      Some(debug) => debug.should_pause(location, module.path()),
+      None => None,
+    };
+
+    if let Some(reason) = reason {
+      self.store_ip();
+      let paused = Paused {
+        reason,
+        frames: self.debug_frames(location.line),
+      };
+
+      if let Some(debug) = &mut self.debug {
+        debug.pause(&paused, location);
+      }
+
+      return self.interrupt.is_interrupted();
+    }
+
+    false
+  }
+
+  /// Snapshot the current fiber's call frames innermost first
+  unsafe fn debug_frames(&self, line: u32) -> Vec<FrameInfo> {
+    let stack = self.fiber.stack();
+    let frame_stack = self.fiber.frame_stack();
+    let mut stack_end = frame_stack.as_ptr().add(frame_stack.len());
+
+    let mut frames = vec![];
+    for frame in self.fiber.frames().iter().rev() {
+      let fun = frame.closure.fun();
+      let len = stack_end.offset_from(frame.stack_start) as usize;
+      let locals = slice::from_raw_parts(frame.stack_start, len);
+      stack_end = frame.stack_start;
+
+      let upvalues = (0..frame.closure.upvalues())
+        .map(|index| frame.closure.get_value(index, stack).to_string())
+        .collect();
+
+      // the innermost frame's ip has not yet moved past the paused instruction
+      let line = if frames.is_empty() {
+        line
+      } else {
+        frame.line()
+      };
+      frames.push(FrameInfo {
+        name: String::from(&*fun.name()),
+        module: fun.module().path().clone(),
+        line,
+        locals: locals.iter().map(|local| local.to_string()).collect(),
+        upvalues,
+      });
+    }
+
+    frames
+  }
+
+  /// Trace the instruction at `ip` and any collection that occurred
+  /// since the previous instruction if the current function is traced
+  unsafe fn trace_instruction(&mut self, ip: *const u8) -> io::Result<()> {
+    let collections = self.gc.borrow().collections();
+    let (events, collected) = match &mut self.tracer {
+      Some(tracer) => (tracer.events(), tracer.collected(collections)),
+      None => return Ok(()),
+    };
+
+    if !self.is_traced(self.current_fun) {
+      return Ok(());
+    }
+
+    if events.gc && collected {
+      let heap = self.gc.borrow().allocated();
+      self.trace_event(
+        "gc",
+        format_args!(
+          "collection {} during {} heap {} bytes",
+          collections,
+          self.fun_label(self.current_fun),
+          heap
+        ),
+      )?;
+    }
+
+    if events.instructions {
+      self.print_state(ip)?;
+    }
+
+    Ok(())
+  }
+
+  /// Trace a frame of `fun` being pushed or popped if calls are traced
+  #[inline]
+  fn trace_call(&self, kind: &str, fun: GcObj<Fun>) {
+    let traced = match &self.tracer {
+      Some(tracer) => tracer.events().calls && self.is_traced(fun),
+      None => false,
+    };
+
+    if traced {
+      self
+        .trace_event(
+          kind,
+          format_args!(
+            "{} depth {}",
+            self.fun_label(fun),
+            self.fiber.frames().len()
+          ),
+        )
+        .expect("Could not write to stdout");
+    }
+  }
+
+  /// Trace a send, receive or close on a channel if channel
+  /// operations are traced
+  fn trace_channel(&self, native: GcObj<Native>, this: Option<Value>, result: &Call) {
+    let traced = match (&self.tracer, this) {
+      (Some(tracer), Some(this)) => {
+        tracer.events().channels
+          && this.is_obj_kind(ObjectKind::Channel)
+          && matches!(&*native.meta().name, "send" | "receive" | "close")
+          && self.is_traced(self.current_fun)
+      }
+      _ => false,
+    };
+
+    if traced {
+      let outcome = match result {
+        Call::Ok(_) => "ok",
+        Call::Err(_) => "error",
+        Call::Exit(_) => "exit",
+        Call::Block => "blocked",
+      };
+
+      self
+        .trace_event(
+          "channel",
+          format_args!(
+            "{} {} in {}",
+            &*native.meta().name,
+            outcome,
+            self.fun_label(self.current_fun)
+          ),
+        )
+        .expect("Could not write to stdout");
+    }
+  }
+
+  /// Is `fun` selected by the tracer's module and function filters
+  fn is_traced(&self, fun: GcObj<Fun>) -> bool {
+    self
+      .tracer
+      .as_ref()
+      .is_some_and(|tracer| tracer.matches(fun.module().path(), &fun.name()))
+  }
 
-    exception_catch(&mut stdout, frame, idx)
+  /// Write a single trace event to stdout
+  fn trace_event(&self, kind: &str, message: fmt::Arguments) -> io::Result<()> {
+    let mut stdio = self.io.stdio();
+    writeln!(stdio.stdout(), "[{}] {}", kind, message)
   }
 
   /// Print debugging information for the current instruction
-  #[cfg(feature = "debug")]
   unsafe fn print_state(&self, ip: *const u8) -> io::Result<usize> {
     let mut stdio = self.io.stdio();
 
@@ -1867,11 +4682,10 @@ impl Vm {
 
     let start = self.current_fun.chunk().instructions().as_ptr();
     let offset = ip.offset_from(start) as usize;
-    disassemble_instruction(&mut stdio, &self.current_fun.chunk(), offset, false)
+    disassemble_instruction(&mut stdio, self.current_fun.chunk(), offset, false)
   }
 
   /// Print the current stack
-  #[cfg(feature = "debug")]
   unsafe fn print_stack_debug(&self, stdio: &mut Stdio) -> io::Result<()> {
     let stdout = stdio.stdout();
 
@@ -1887,11 +4701,7 @@ impl Vm {
     write!(stdout, "Local Stack:  ")?;
     for value in self.fiber.frame_stack() {
       let s = value.to_string();
-      write!(
-        stdout,
-        "[ {} ]",
-        s.chars().into_iter().take(60).collect::<String>()
-      )?;
+      write!(stdout, "[ {} ]", s.chars().take(60).collect::<String>())?;
     }
 
     writeln!(stdout)
@@ -1917,25 +4727,48 @@ impl Vm {
     writeln!(stdout)
   }
 
-  /// Convert an execute result to a call result
-  fn to_call_result(&self, execute_result: ExecuteResult) -> Call {
+  /// Convert an execute result to a call result. Internal errors
+  /// unwind the calling native as an exit
+  fn call_result(&mut self, execute_result: ExecuteResult) -> Call {
     match execute_result {
       ExecuteResult::FunResult(value) => Call::Ok(value),
-      ExecuteResult::Ok(_) => self.internal_error("Accidental early exit in hook call"),
-      ExecuteResult::CompileError => {
-        self.internal_error("Compiler error should occur before code is executed.")
-      }
+      ExecuteResult::Ok(_) => {
+        self.internal_error("Accidental early exit in hook call");
+        Call::Exit(self.exit_code)
+      },
       ExecuteResult::RuntimeError => match self.fiber.error() {
         Some(error) => Call::Err(error),
-        None => self.internal_error("Error not set on vm executor."),
+        None => {
+          self.internal_error("Error not set on vm executor.");
+          Call::Exit(self.exit_code)
+        },
+      },
+      ExecuteResult::InternalError => Call::Exit(self.exit_code),
+      ExecuteResult::Timeout => Call::Exit(self.exit_code),
+      ExecuteResult::Interrupted => Call::Exit(self.exit_code),
+      ExecuteResult::AwaitingInput => {
+        self.internal_error("Native call cannot wait for input.");
+        Call::Exit(self.exit_code)
       },
-      ExecuteResult::InternalError => self.internal_error("Internal error encountered"),
     }
   }
 
-  /// Report an internal issue to the user
-  fn internal_error(&self, message: &str) -> ! {
-    panic!("Internal Error: {}", message)
+  /// Record an internal issue, stopping execution so it is
+  /// returned to the host as a `VmError::Internal`
+  fn internal_error(&mut self, message: &str) -> Signal {
+    if self.internal.is_none() {
+      self.internal = Some(message.to_string());
+    }
+
+    Signal::InternalError
+  }
+
+  /// Take the internal issue that stopped execution
+  fn take_internal(&mut self) -> String {
+    self
+      .internal
+      .take()
+      .unwrap_or_else(|| "Execution stopped without recording an internal error.".to_string())
   }
 
   /// Report a known laythe runtime error to the user
@@ -1948,7 +4781,11 @@ impl Vm {
       Signal::Ok => self.execute(mode),
       Signal::OkReturn => ExecuteResult::FunResult(self.fiber.pop()),
       Signal::RuntimeError => ExecuteResult::RuntimeError,
-      _ => self.internal_error("Unexpected signal in run_fun."),
+      Signal::InternalError => ExecuteResult::InternalError,
+      _ => {
+        self.internal_error("Unexpected signal in runtime_error.");
+        ExecuteResult::InternalError
+      },
     };
 
     match result {
@@ -1963,42 +4800,410 @@ impl Vm {
     }
   }
 
+  /// Raise an out of memory error after the heap could not
+  /// be collected below its maximum size
+  unsafe fn out_of_memory(&mut self) -> Signal {
+    self.store_ip();
+
+    let max_heap_bytes = self.gc.borrow().max_heap_bytes().unwrap_or_default();
+    let signal = self.runtime_error(
+      self.builtin.errors.out_of_memory,
+      &format!("Heap exceeded the maximum of {} bytes.", max_heap_bytes),
+    );
+
+    // constructing the error may itself have flagged the heap again
+    self.gc.borrow_mut().clear_out_of_memory();
+    signal
+  }
+
+  /// Stop execution after an interrupt, capturing the
+  /// call stack where execution was interrupted
+  unsafe fn interrupted(&mut self) -> Signal {
+    self.store_ip();
+
+    match self.runtime_error(self.builtin.errors.runtime, "Execution interrupted.") {
+      Signal::RuntimeError => match self.fiber.error() {
+        Some(error) => self.uncaught = Some(self.capture_error(error)),
+        None => return self.internal_error("Runtime error was not set."),
+      },
+      _ => return self.internal_error("Unexpected signal in interrupted."),
+    }
+
+    Signal::Exit
+  }
+
   /// Set the current error place the vm signal a runtime error
   fn set_error(&mut self, error: GcObj<Instance>) -> Signal {
+    self.record_stack_trace(error);
     self.fiber.set_error(error);
     Signal::RuntimeError
   }
 
+  /// Record the call stack where an error was raised on the error's stack.
+  /// Errors that already carry a stack trace keep the original trace
+  fn record_stack_trace(&mut self, mut error: GcObj<Instance>) {
+    // the stack trace is held by the error class's second field
+    if error.fields().len() <= ERROR_STACK_FIELD {
+      return;
+    }
+
+    let stack = error[ERROR_STACK_FIELD];
+    if stack.is_obj_kind(ObjectKind::List) && !stack.to_obj().to_list().is_empty() {
+      return;
+    }
+
+    self.store_ip();
+    self.push_root(error);
+
+    let function_key = self.manage_str("function");
+    self.push_root(function_key);
+    let module_key = self.manage_str("module");
+    self.push_root(module_key);
+    let line_key = self.manage_str("line");
+    self.push_root(line_key);
+
+    let mut frames = vec![];
+    for frame in self.fiber.frames().iter().rev() {
+      let fun = frame.closure.fun();
+      let module = self.manage_str(fun.module().path().to_string_lossy());
+      self.push_root(module);
+
+      let mut entry = Map::with_capacity(3);
+      entry.insert(val!(function_key), val!(fun.name()));
+      entry.insert(val!(module_key), val!(module));
+      entry.insert(val!(line_key), val!(frame.line() as f64));
+
+      let entry = self.manage_obj(entry);
+      self.push_root(entry);
+      frames.push(val!(entry));
+    }
+
+    error[ERROR_STACK_FIELD] = val!(self.manage_obj(List::from(frames)));
+    self.pop_roots(4 + self.fiber.frames().len() * 2);
+  }
+
   /// Set the current error place the vm signal a runtime error
   fn set_exit(&mut self, code: u16) -> Signal {
     self.exit_code = code;
     Signal::Exit
   }
 
-  /// Search for a catch block up the stack, printing the error if no catch is found
-  fn stack_unwind(&mut self, error: GcObj<Instance>) -> Option<ExecuteResult> {
+  /// Search for a catch block up the stack, capturing the error if no catch is found.
+  /// An uncaught error in a spawned fiber fails only that fiber
+  unsafe fn stack_unwind(
+    &mut self,
+    error: GcObj<Instance>,
+    mode: &ExecuteMode,
+  ) -> Option<ExecuteResult> {
     self.store_ip();
+    self.profile_event();
 
-    match self.fiber.stack_unwind() {
+    // a laythe call made by a native may only be caught inside that call
+    let floor = match mode {
+      ExecuteMode::Normal => 0,
+      ExecuteMode::CallFunction(depth) => *depth,
+    };
+
+    match self.fiber.stack_unwind(floor) {
       Some(frame) => {
         self.current_fun = frame.closure.fun();
         self.ip = frame.ip;
         None
       }
       None => {
-        self.print_error(error);
+        if let ExecuteMode::Normal = mode {
+          if self.fiber != self.main_fiber {
+            return match self.fail_fiber(error) {
+              Signal::Ok => None,
+              Signal::Exit => Some(ExecuteResult::Ok(self.exit_code)),
+              Signal::RuntimeError => match self.fiber.error() {
+                Some(error) => self.stack_unwind(error, mode),
+                None => {
+                  self.internal_error("Runtime error was not set.");
+                  Some(ExecuteResult::InternalError)
+                },
+              },
+              Signal::InternalError => Some(ExecuteResult::InternalError),
+              _ => {
+                self.internal_error("Unexpected signal in stack_unwind.");
+                Some(ExecuteResult::InternalError)
+              },
+            };
+          }
+        }
+
+        // an error raised under a native call is reported only if the
+        // native returns it rather than handling it
+        if let ExecuteMode::Normal = mode {
+          self.uncaught = Some(self.capture_error(error));
+        }
         Some(ExecuteResult::RuntimeError)
       }
     }
   }
 
-  /// Print an error message and the current call stack to the user
+  /// Drop any frames left above depth by a laythe call a native
+  /// recovered from after it raised an error, returning the stack
+  /// to the height it had before the native was called. This also
+  /// drops the results laythe calls leave on the stack
+  unsafe fn restore_frames(&mut self, depth: usize, height: usize) {
+    while self.fiber.frames().len() > depth {
+      self.pop_frame();
+    }
+
+    let current = self.fiber.stack_height();
+    if current > height {
+      self.fiber.drop_n(current - height);
+    }
+    for _ in current..height {
+      self.fiber.push(VALUE_NIL);
+    }
+  }
+
+  /// Capture an error along with the current call stack. If the error
+  /// was raised in a loaded file the offending source is kept to be shown
+  fn capture_error(&self, error: GcObj<Instance>) -> UncaughtError {
+    let class = String::from(&*error.class().name());
+    let message = String::from(&*error[0].to_obj().to_str());
+
+    let diagnostic = self.fiber.frames().last().and_then(|frame| {
+      let file_name = self.file_name(frame.closure.fun().module().path());
+      let file_id = self.files.id(&file_name)?;
+
+      let message = format!("{}: {}", class, message);
+      diagnostics::runtime_error(&self.files, file_id, &message, frame.span())
+    });
+
+    let mut stack = vec![];
+    self.fiber.print_stack(&mut stack);
+
+    UncaughtError {
+      class,
+      message,
+      diagnostic,
+      stack: String::from_utf8_lossy(&stack).into_owned(),
+      cause: self.capture_cause(error, &mut vec![error]),
+    }
+  }
+
+  /// Capture the chain of errors an error was raised from. Each cause
+  /// keeps the stack trace recorded when it was first raised
+  fn capture_cause(
+    &self,
+    error: GcObj<Instance>,
+    seen: &mut Vec<GcObj<Instance>>,
+  ) -> Option<Box<UncaughtError>> {
+    if error.fields().len() <= ERROR_CAUSE_FIELD {
+      return None;
+    }
+
+    let cause = self.to_error(error[ERROR_CAUSE_FIELD])?;
+    if seen.contains(&cause) {
+      return None;
+    }
+    seen.push(cause);
+
+    let mut stack = String::new();
+    let frames = cause[ERROR_STACK_FIELD];
+    if frames.is_obj_kind(ObjectKind::List) {
+      for frame in frames.to_obj().to_list().iter() {
+        if !frame.is_obj_kind(ObjectKind::Map) {
+          continue;
+        }
+
+        let mut function = String::new();
+        let mut line = String::new();
+        for (key, value) in frame.to_obj().to_map().iter() {
+          if !key.is_obj_kind(ObjectKind::String) {
+            continue;
+          }
+
+          match &*key.to_obj().to_str() {
+            "function" if value.is_obj_kind(ObjectKind::String) => {
+              function = String::from(&*value.to_obj().to_str())
+            },
+            "line" => line = value.to_string(),
+            _ => (),
+          }
+        }
+
+        let location = match function.as_str() {
+          SCRIPT => SCRIPT.to_owned(),
+          _ => format!("{}()", function),
+        };
+        stack.push_str(&format!("  [line {}] in {}\n", line, location));
+      }
+    }
+
+    Some(Box::new(UncaughtError {
+      class: String::from(&*cause.class().name()),
+      message: String::from(&*cause[0].to_obj().to_str()),
+      diagnostic: None,
+      stack,
+      cause: self.capture_cause(cause, seen),
+    }))
+  }
+
+  /// Print an error message and the current call stack to the user. Used
+  /// for errors that do not stop the script such as those of exit hooks
   fn print_error(&mut self, error: GcObj<Instance>) {
-    let mut stdio = self.io.stdio();
-    let stderr = stdio.stderr();
+    let error = VmError::Runtime(Box::new(self.capture_error(error)));
+    self.report(&error);
+  }
+
+  /// The name a source file is reported under relative to the root directory
+  /// A function's name along with its file relative to the root directory
+  fn fun_label(&self, fun: GcObj<Fun>) -> String {
+    format!("{} ({})", &*fun.name(), self.file_name(fun.module().path()))
+  }
+
+  fn file_name(&self, path: &Path) -> String {
+    path
+      .strip_prefix(&self.root_dir)
+      .unwrap_or(path)
+      .to_string_lossy()
+      .into_owned()
+  }
+}
 
-    self.fiber.print_error(stderr, error);
+/// A handler executing a single instruction
+#[cfg(feature = "threaded_dispatch")]
+type Handler = unsafe fn(&mut Vm) -> Signal;
+
+/// The handler for each op code indexed by its byte value. Indexing this
+/// table replaces the dispatch `match` with a single indirect call per
+/// instruction, giving each handler its own branch history
+#[cfg(feature = "threaded_dispatch")]
+static DISPATCH_TABLE: [Handler; OP_CODES.len()] = dispatch_table();
+
+#[cfg(feature = "threaded_dispatch")]
+const fn dispatch_table() -> [Handler; OP_CODES.len()] {
+  let mut table: [Handler; OP_CODES.len()] = [Vm::op_nil; OP_CODES.len()];
+
+  let mut byte = 0;
+  while byte < OP_CODES.len() {
+    table[byte] = handler(OP_CODES[byte].byte_code);
+    byte += 1;
+  }
+
+  table
+}
+
+#[cfg(feature = "threaded_dispatch")]
+const fn handler(op_code: ByteCode) -> Handler {
+  match op_code {
+    ByteCode::Negate => Vm::op_negate,
+    ByteCode::Add => Vm::op_add,
+    ByteCode::Subtract => Vm::op_sub,
+    ByteCode::Multiply => Vm::op_mul,
+    ByteCode::Divide => Vm::op_div,
+    ByteCode::Not => Vm::op_not,
+    ByteCode::And => Vm::op_and,
+    ByteCode::Or => Vm::op_or,
+    ByteCode::Equal => Vm::op_equal,
+    ByteCode::NotEqual => Vm::op_not_equal,
+    ByteCode::EqualConst => Vm::op_equal_const,
+    ByteCode::NotEqualConst => Vm::op_not_equal_const,
+    ByteCode::Greater => Vm::op_greater,
+    ByteCode::GreaterEqual => Vm::op_greater_equal,
+    ByteCode::Less => Vm::op_less,
+    ByteCode::LessEqual => Vm::op_less_equal,
+    ByteCode::AddConstToLocal => Vm::op_add_const_to_local,
+    ByteCode::JumpIfNotLess => Vm::op_jump_if_not_less,
+    ByteCode::JumpIfFalse => Vm::op_jump_if_false,
+    ByteCode::Jump => Vm::op_jump,
+    ByteCode::Loop => Vm::op_loop,
+    ByteCode::DefineGlobal => Vm::op_define_global,
+    ByteCode::GetGlobal => Vm::op_get_global,
+    ByteCode::SetGlobal => Vm::op_set_global,
+    ByteCode::GetLocal => Vm::op_get_local,
+    ByteCode::SetLocal => Vm::op_set_local,
+    ByteCode::GetUpvalue => Vm::op_get_upvalue,
+    ByteCode::SetUpvalue => Vm::op_set_upvalue,
+    ByteCode::GetProperty => Vm::op_get_property,
+    ByteCode::SetProperty => Vm::op_set_property,
+    ByteCode::Import => Vm::op_import,
+    ByteCode::ImportSymbol => Vm::op_import_symbol,
+    ByteCode::Export => Vm::op_export,
+    ByteCode::Drop => Vm::op_drop,
+    ByteCode::DropN => Vm::op_drop_n,
+    ByteCode::Dup => Vm::op_dup,
+    ByteCode::Nil => Vm::op_nil,
+    ByteCode::True => Vm::op_true,
+    ByteCode::False => Vm::op_false,
+    ByteCode::List => Vm::op_list,
+    ByteCode::Map => Vm::op_map,
+    ByteCode::Tuple => Vm::op_tuple,
+    ByteCode::Unpack => Vm::op_unpack,
+    ByteCode::Interpolate => Vm::op_interpolate,
+    ByteCode::IterNext => Vm::op_iter_next,
+    ByteCode::IterCurrent => Vm::op_iter_current,
+    ByteCode::Await => Vm::op_await,
+    ByteCode::Raise => Vm::op_raise,
+    ByteCode::RaiseFrom => Vm::op_raise_from,
+    ByteCode::Constant => Vm::op_constant,
+    ByteCode::ConstantLong => Vm::op_constant_long,
+    ByteCode::Call => Vm::op_call,
+    ByteCode::Invoke => Vm::op_invoke,
+    ByteCode::SuperInvoke => Vm::op_super_invoke,
+    ByteCode::InvokeSealed => Vm::op_invoke_sealed,
+    ByteCode::Closure => Vm::op_closure,
+    ByteCode::Method => Vm::op_method,
+    ByteCode::Field => Vm::op_field,
+    ByteCode::StaticMethod => Vm::op_static_method,
+    ByteCode::Class => Vm::op_class,
+    ByteCode::Inherit => Vm::op_inherit,
+    ByteCode::Extend => Vm::op_extend,
+    ByteCode::Seal => Vm::op_seal,
+    ByteCode::GetSuper => Vm::op_get_super,
+    ByteCode::CloseUpvalue => Vm::op_close_upvalue,
+    ByteCode::Return => Vm::op_return,
+  }
+}
+
+#[cfg(feature = "threaded_dispatch")]
+impl Vm {
+  /// Set how this vm dispatches instructions
+  pub fn set_dispatch(&mut self, dispatch: Dispatch) {
+    self.dispatch = dispatch;
+  }
+
+  unsafe fn op_nil(&mut self) -> Signal {
+    self.op_literal(VALUE_NIL)
+  }
+
+  unsafe fn op_true(&mut self) -> Signal {
+    self.op_literal(val!(true))
+  }
+
+  unsafe fn op_false(&mut self) -> Signal {
+    self.op_literal(val!(false))
+  }
+}
+
+/// Is this import path relative to the importing module
+fn is_relative(path: &[GcStr]) -> bool {
+  matches!(
+    path.first().map(|segment| &**segment),
+    Some(".") | Some("..")
+  )
+}
+
+/// Lexically normalize a path resolving each `.` and `..` component
+fn normalize_path(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+
+  for component in path.components() {
+    match component {
+      Component::CurDir => (),
+      Component::ParentDir => {
+        normalized.pop();
+      }
+      component => normalized.push(component),
+    }
   }
+
+  normalized
 }
 
 #[cfg(debug_assertions)]
@@ -2014,18 +5219,56 @@ fn assert_roots(native: GcObj<Native>, roots_before: usize, roots_now: usize) {
 impl TraceRoot for Vm {
   fn trace(&self) {
     self.fiber.trace();
+    self.main_fiber.trace();
+    self.exit_hooks.iter().for_each(|hook| {
+      hook.trace();
+    });
+    self.signals.trace();
+    self.scheduler.trace();
     self.files.trace();
     self.packages.trace();
     self.module_cache.trace();
+    self.import_stack.iter().for_each(|module| {
+      module.trace();
+    });
     self.native_fun_stub.trace();
+    if let Some(host_data) = &self.host_data {
+      host_data.trace();
+    }
+    self.host_roots.borrow().trace();
+    if let Some(profiler) = &self.profiler {
+      profiler.trace();
+    }
+    if let Some(stats) = &self.stats {
+      stats.trace();
+    }
   }
 
   fn trace_debug(&self, log: &mut dyn Write) {
     self.fiber.trace_debug(log);
+    self.main_fiber.trace_debug(log);
+    self.exit_hooks.iter().for_each(|hook| {
+      hook.trace_debug(log);
+    });
+    self.signals.trace_debug(log);
+    self.scheduler.trace_debug(log);
     self.files.trace_debug(log);
     self.packages.trace_debug(log);
     self.module_cache.trace_debug(log);
+    self.import_stack.iter().for_each(|module| {
+      module.trace_debug(log);
+    });
     self.native_fun_stub.trace_debug(log);
+    if let Some(host_data) = &self.host_data {
+      host_data.trace_debug(log);
+    }
+    self.host_roots.borrow().trace_debug(log);
+    if let Some(profiler) = &self.profiler {
+      profiler.trace_debug(log);
+    }
+    if let Some(stats) = &self.stats {
+      stats.trace_debug(log);
+    }
   }
 
   fn can_collect(&self) -> bool {
@@ -2045,6 +5288,10 @@ impl HookContext for Vm {
   fn io(&mut self) -> Io {
     self.io.clone()
   }
+
+  fn host_data(&mut self) -> Option<&mut dyn HostData> {
+    self.host_data.as_deref_mut()
+  }
 }
 
 impl GcContext for Vm {
@@ -2056,12 +5303,12 @@ impl GcContext for Vm {
 impl ValueContext for Vm {
   fn call(&mut self, callable: Value, args: &[Value]) -> Call {
     let result = unsafe { self.run_fun(callable, args) };
-    self.to_call_result(result)
+    self.call_result(result)
   }
 
   fn call_method(&mut self, this: Value, method: Value, args: &[Value]) -> Call {
     let result = unsafe { self.run_method(this, method, args) };
-    self.to_call_result(result)
+    self.call_result(result)
   }
 
   fn get_method(&mut self, this: Value, method_name: GcStr) -> Call {
@@ -2071,4 +5318,439 @@ impl ValueContext for Vm {
   fn get_class(&mut self, this: Value) -> Value {
     val!(self.value_class(this))
   }
+
+  fn current_fiber(&mut self) -> GcObj<Fiber> {
+    self.fiber
+  }
+
+  fn wake_fiber(&mut self, mut fiber: GcObj<Fiber>) -> bool {
+    if fiber.wake() {
+      self.scheduler.push(fiber);
+      true
+    } else {
+      false
+    }
+  }
+
+  fn spawn_fiber(&mut self, fiber: GcObj<Fiber>) {
+    self.scheduler.push(fiber);
+  }
+
+  fn cancel_fiber(&mut self, mut fiber: GcObj<Fiber>, error: GcObj<Instance>) -> bool {
+    if !fiber.cancel(error) {
+      return false;
+    }
+
+    // a blocked fiber resumes at the call it blocked on to raise the error
+    if fiber.wake() {
+      self.scheduler.remove(fiber);
+      self.scheduler.push(fiber);
+    }
+    true
+  }
+
+  fn sleep_fiber(&mut self, duration: Duration) -> bool {
+    // the retried sleep completes once the fiber has woken
+    if self.fiber.wake_from_sleep() {
+      return false;
+    }
+
+    self.scheduler.sleep(self.fiber, duration);
+    true
+  }
+
+  fn clear_sleep(&mut self) {
+    self.scheduler.remove(self.fiber);
+  }
+
+  fn add_timer(&mut self, channel: GcObj<Channel>, duration: Duration, repeat: bool) {
+    self.scheduler.add_timer(channel, duration, repeat);
+  }
+
+  fn await_input(&mut self) -> bool {
+    self.scheduler.await_input(self.fiber);
+    true
+  }
+
+  fn add_exit_hook(&mut self, hook: Value) {
+    self.exit_hooks.push(hook);
+  }
+
+  fn add_signal_handler(&mut self, signal: ProcessSignal, handler: Value) -> Result<(), String> {
+    self.signals.add(signal, handler)
+  }
+
+  fn spawn_worker(&mut self, path: &Path) -> Result<WorkerHandle, String> {
+    let path = match self.current_fun.module().path().parent() {
+      Some(dir) if path.is_relative() => dir.join(path),
+      _ => path.to_path_buf(),
+    };
+
+    let source = self
+      .io
+      .fs()
+      .read_to_string(&path)
+      .map_err(|err| format!("Unable to read {}: {}", path.display(), err))?;
+
+    // a built vm can't be sent so the worker is built on its own thread
+    // from the parts of this vm's configuration that can be
+    let (port, worker_port) = WorkerPort::pair();
+    let interrupt = Interrupt::new();
+    let terminate = interrupt.clone();
+    let config = self.thread_config();
+
+    let thread = thread::Builder::new()
+      .name(format!("worker {}", path.display()))
+      .spawn(move || {
+        let mut vm = config.build().map_err(|err| err.to_string())?;
+        vm.interrupt = interrupt;
+        vm.worker = Some(worker_port);
+
+        match vm.run(path, &source) {
+          Ok(exit_code) => Ok(exit_code),
+          Err(err @ VmError::Interrupted(_)) => Ok(err.exit_code()),
+          Err(err) => {
+            vm.report(&err);
+            Err(err.to_string())
+          },
+        }
+      })
+      .map_err(|err| format!("Unable to start worker: {}", err))?;
+
+    Ok(WorkerHandle::new(
+      port,
+      thread,
+      Box::new(move || terminate.interrupt()),
+    ))
+  }
+
+  fn worker_port(&mut self) -> Option<&WorkerPort> {
+    self.worker.as_ref()
+  }
+
+  fn run_in_pool(
+    &mut self,
+    fun: Value,
+    items: Vec<Message>,
+    collect: bool,
+  ) -> Result<PoolJob, String> {
+    let fun = self
+      .copy_values(&[fun])
+      .map_err(|err| format!("Unable to send the function to the worker pool. {}", err))?;
+
+    if self.pool.is_none() {
+      self.pool = Some(WorkerPool::new(self.thread_config())?);
+    }
+
+    match &self.pool {
+      Some(pool) => Ok(pool.run(fun, items, collect)),
+      None => Err("Unable to start the worker pool.".to_string()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::byte_code::{Operand, OP_CODES};
+  use laythe_env::resolver::{ImportResolver, ResolvedModule};
+
+  /// An instruction that has started whose stack effect is checked
+  /// once execution reaches the instruction that follows it
+  pub(super) struct PendingEffect {
+    fiber: GcObj<Fiber>,
+    depth: usize,
+    next_ip: *const u8,
+    expected: i64,
+    byte: u8,
+  }
+
+  /// The stack effects still pending and the op codes checked so far
+  pub(super) struct StackAudit {
+    pub(super) pending: Vec<PendingEffect>,
+    checked: Vec<bool>,
+  }
+
+  impl Vm {
+    /// Note the height the stack should have once the instruction at ip
+    /// completes. Calls complete once their frame returns, awaits once
+    /// their fiber resumes
+    pub(super) unsafe fn audit_started(&self, ip: *const u8) -> PendingEffect {
+      let chunk = self.current_fun.chunk();
+      let instructions = chunk.instructions();
+      let offset = ip.offset_from(instructions.as_ptr()) as usize;
+      let (op, mut next) = AlignedByteCode::decode(instructions, offset);
+
+      // cache slots and upvalue indices are read as part of the instruction
+      let info = ByteCode::from(instructions[offset]).info();
+      if info.operands.contains(&Operand::Slot) {
+        next += 4;
+      }
+      if let AlignedByteCode::Closure(constant) = op {
+        let fun = chunk.get_constant(constant as usize).to_obj().to_fun();
+        next += 2 * fun.upvalue_count();
+      }
+
+      PendingEffect {
+        fiber: self.fiber,
+        depth: self.fiber.frames().len(),
+        next_ip: instructions.as_ptr().add(next),
+        expected: self.fiber.stack_height() as i64 + op.stack_effect() as i64,
+        byte: instructions[offset],
+      }
+    }
+
+    /// Check the instructions that completed on the current fiber. An
+    /// instruction that jumped or whose frame was unwound is not checked
+    pub(super) fn audit_completed(&mut self) {
+      let fiber = self.fiber;
+      let depth = self.fiber.frames().len();
+      let height = self.fiber.stack_height() as i64;
+      let ip = self.ip;
+
+      let audit = match &mut self.stack_audit {
+        Some(audit) => audit,
+        None => return,
+      };
+
+      let checked = &mut audit.checked;
+      audit.pending.retain(|pending| {
+        if pending.fiber != fiber || pending.depth < depth {
+          return true;
+        }
+
+        if pending.depth == depth && pending.next_ip == ip {
+          let info = &OP_CODES[pending.byte as usize];
+          assert_eq!(
+            height, pending.expected,
+            "op code {} declared a stack effect of {}",
+            info.name, info.stack_effect
+          );
+          checked[pending.byte as usize] = true;
+        }
+
+        false
+      });
+    }
+  }
+
+  /// Serves the modules imported by the audited script
+  #[derive(Debug)]
+  struct AuditResolver;
+
+  impl ImportResolver for AuditResolver {
+    fn resolve(&self, _path: &Path) -> io::Result<ResolvedModule> {
+      Ok(ResolvedModule::Source(
+        "export fn double(x) { return x * 2; }\nexport let three = 3;".to_string(),
+      ))
+    }
+  }
+
+  const SCRIPT: &str = "
+import ./shapes;
+import ./shapes:{double};
+
+assertEq(shapes.three, 3);
+assertEq(double(2), 4);
+
+let global = 1;
+global = -global + 2 * 3 - 1;
+global = global / 4;
+export let exported = global;
+assert(!false and true);
+assert(nil or true);
+assert(global == 1 and global != 0);
+assert(2 > 1 and 2 >= 2 and 1 < 2 and 1 <= 1);
+assert(global != 'one' and 'zero' == 'zero');
+
+let list = [1, 2, 3];
+list[0] = 4;
+list[1] += 1;
+let map = { 'a': 1, 'b': 2 };
+assertEq('list ${list[0]} map ${map['b']}', 'list 4 map 2');
+let (first, second) = (list[0], map['a']);
+assertEq(first + second, 5);
+
+fn counter() {
+  let count = 0;
+  let spare = 1;
+  fn step() {
+    count = count + spare;
+    return count;
+  }
+  return step;
+}
+let step = counter();
+step();
+assertEq(step(), 2);
+
+fn loop() {
+  let total = 0;
+  let i = 0;
+  while i < 10 {
+    i += 1;
+  }
+
+  for item in [1, 2] {
+    let twice = item * 2;
+    let thrice = item * 3;
+    total = total + twice + thrice;
+  }
+
+  if total > 100 {
+    total = 0;
+  } else {
+    total = total + 1;
+  }
+
+  let closures = [];
+  for item in [1, 2] {
+    closures.push(|| item);
+  }
+
+  return total + i;
+}
+assertEq(loop(), 26);
+
+class Shape {
+  init(area) {
+    self.area = area;
+  }
+
+  describe() {
+    return 'area ${self.area}';
+  }
+
+  static unit() {
+    return Shape(1);
+  }
+}
+
+class Square : Shape {
+  init(side) {
+    super.init(side * side);
+  }
+
+  describe() {
+    let parent = super.describe;
+    return parent();
+  }
+}
+
+let square = Square(2);
+square.area = square.area + 1;
+assertEq(square.describe(), 'area 5');
+assertEq(Shape.unit().area, 1);
+
+extend Shape {
+  double() {
+    return self.area * 2;
+  }
+}
+assertEq(square.double(), 10);
+
+sealed class Circle {
+  radius() {
+    return 2;
+  }
+
+  diameter() {
+    return self.radius() * 2;
+  }
+}
+assertEq(Circle().diameter(), 4);
+
+try {
+  raise ValueError('outer') from KeyError('inner');
+} catch err {
+  assertEq(err.cause.message, 'inner');
+}
+
+async fn doubled(x) {
+  return x * 2;
+}
+assertEq(await doubled(2), 4);
+
+class Guard {
+  enter() {
+    return self;
+  }
+
+  exit(err) {}
+}
+
+let raised = false;
+try {
+  with Guard() {
+    [][1];
+  }
+} catch {
+  raised = true;
+}
+assert(raised);
+";
+
+  #[test]
+  fn op_codes_match_dispatch() {
+    // enough distinct constants for the long constant op code
+    let constants: Vec<String> = (0..300).map(|i| format!("{}.5", i)).collect();
+    let source = format!("{}\nlet constants = [{}];\n", SCRIPT, constants.join(", "));
+
+    let io = Io::default().with_resolver(Arc::new(AuditResolver));
+    let mut vm = Vm::builder().io(io).build().expect("Unable to create vm");
+    vm.stack_audit = Some(StackAudit {
+      pending: vec![],
+      checked: vec![false; OP_CODES.len()],
+    });
+
+    assert_eq!(vm.run(PathBuf::from("/app/main.lay"), &source), Ok(0));
+
+    // returns leave their frame, raises unwind to a handler while
+    // jumps never reach the next instruction
+    let audit = vm.stack_audit.take().expect("Audit was removed");
+    let unchecked: Vec<&str> = OP_CODES
+      .iter()
+      .zip(audit.checked)
+      .filter(|(info, checked)| {
+        let exempt = matches!(
+          info.byte_code,
+          ByteCode::Return
+            | ByteCode::Raise
+            | ByteCode::RaiseFrom
+            | ByteCode::Jump
+            | ByteCode::Loop
+        );
+        !checked && !exempt
+      })
+      .map(|(info, _)| info.name)
+      .collect();
+
+    assert!(unchecked.is_empty(), "op codes not checked {:?}", unchecked);
+  }
+
+  fn answer_plugin(hooks: &GcHooks, package: &mut Package) -> ModuleResult<()> {
+    let mut root = package.root_module();
+    let name = hooks.manage_str("answer");
+    root.insert_symbol(hooks, name, val!(42.0))?;
+    root.export_symbol(hooks, name)
+  }
+
+  #[test]
+  fn plugin_packages() {
+    let mut vm = Vm::builder()
+      .io(Io::default())
+      .gc_stress(true)
+      .build()
+      .expect("Unable to create vm");
+
+    assert_eq!(vm.install_plugin("answers", answer_plugin), Ok(()));
+    assert!(vm.install_plugin("answers", answer_plugin).is_err());
+    assert!(matches!(
+      vm.load_plugin(Path::new("/missing/libanswers.so")),
+      Err(VmError::Load(_))
+    ));
+
+    let source = "import answers:{answer};\nassertEq(answer, 42);\n";
+    assert_eq!(vm.run(PathBuf::from("/app/main.lay"), source), Ok(0));
+  }
 }
@@ -0,0 +1,51 @@
+use support::{assert_file_exit_and_stdio, assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn cli() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/cli/commands.lay",
+      "std_lib/cli/errors.lay",
+      "std_lib/cli/parse.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn help() -> Result<(), std::io::Error> {
+  assert_file_exit_and_stdio(
+    "std_lib/cli/help.lay",
+    FILE_PATH,
+    None,
+    None,
+    Some(vec![
+      "Keep track of things to do",
+      "",
+      "Usage: todo [options] [command]",
+      "",
+      "Options:",
+      "  -q, --quiet          Print nothing",
+      "      --file <string>  Where items are kept (default: 'todo.txt')",
+      "  -h, --help           Print this message",
+      "",
+      "Commands:",
+      "  add                  Add an item",
+      "  list                 List the items",
+    ]),
+    None,
+    Outcome::Ok(0),
+  )
+}
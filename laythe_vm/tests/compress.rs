@@ -0,0 +1,26 @@
+use support::{assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn compress() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/compress/errors.lay",
+      "std_lib/compress/log_file.lay",
+      "std_lib/compress/round_trip.lay",
+      "std_lib/compress/stream.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
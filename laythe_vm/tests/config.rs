@@ -0,0 +1,25 @@
+use support::{assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn config() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/config/errors.lay",
+      "std_lib/config/toml.lay",
+      "std_lib/config/yaml.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
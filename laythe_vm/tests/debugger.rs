@@ -0,0 +1,226 @@
+use laythe_vm::{
+  dap,
+  debugger::{Breakpoint, Breakpoints, Debugger, PauseReason, Paused, Step},
+  json::Json,
+  protocol,
+  vm::Vm,
+};
+use std::{
+  cell::RefCell,
+  io::{self, Cursor, Write},
+  rc::Rc,
+  sync::{Arc, Mutex},
+};
+use support::{assert_files_exit_with_builder, fixture_path_inner, Outcome};
+
+mod support;
+
+const FILE_PATH: &str = file!();
+
+/// A debugger that replays a fixed set of steps recording each pause
+struct ScriptedDebugger {
+  steps: Vec<Step>,
+  pauses: Vec<(PauseReason, String, u32, usize)>,
+  locals: Vec<Vec<String>>,
+}
+
+impl ScriptedDebugger {
+  fn new(steps: &[Step]) -> Rc<RefCell<Self>> {
+    Rc::new(RefCell::new(Self {
+      steps: steps.iter().rev().copied().collect(),
+      pauses: vec![],
+      locals: vec![],
+    }))
+  }
+}
+
+impl Debugger for ScriptedDebugger {
+  fn start(&mut self, _breakpoints: &mut Breakpoints) -> Step {
+    self.steps.pop().unwrap_or(Step::Continue)
+  }
+
+  fn paused(&mut self, paused: &Paused, _breakpoints: &mut Breakpoints) -> Step {
+    let frame = paused.frame();
+    self.pauses.push((
+      paused.reason,
+      frame.name.clone(),
+      frame.line,
+      paused.frames.len(),
+    ));
+    self.locals.push(frame.locals.clone());
+    self.steps.pop().unwrap_or(Step::Continue)
+  }
+}
+
+fn run(debugger: Rc<RefCell<ScriptedDebugger>>, breakpoints: &[Breakpoint]) {
+  let mut builder = Vm::builder().debugger(debugger);
+  for breakpoint in breakpoints {
+    builder = builder.breakpoint(breakpoint.clone());
+  }
+
+  assert_files_exit_with_builder(
+    &["language/debugger/step.lay"],
+    FILE_PATH,
+    builder,
+    Outcome::Ok(0),
+  )
+  .expect("Debugged script failed");
+}
+
+#[test]
+fn step_over() {
+  let debugger = ScriptedDebugger::new(&[Step::Over, Step::Over, Step::Over, Step::Over]);
+  run(debugger.clone(), &[]);
+
+  let pauses = debugger.borrow().pauses.clone();
+  assert_eq!(
+    pauses,
+    vec![
+      (PauseReason::Step, "script".to_string(), 4, 1),
+      (PauseReason::Step, "script".to_string(), 6, 1),
+      (PauseReason::Step, "script".to_string(), 7, 1),
+      (PauseReason::Step, "script".to_string(), 8, 1),
+    ]
+  );
+}
+
+#[test]
+fn step_into() {
+  let debugger = ScriptedDebugger::new(&[Step::Over, Step::Over, Step::Into, Step::Into]);
+  run(debugger.clone(), &[]);
+
+  let pauses = debugger.borrow().pauses.clone();
+  assert_eq!(
+    pauses,
+    vec![
+      (PauseReason::Step, "script".to_string(), 4, 1),
+      (PauseReason::Step, "script".to_string(), 6, 1),
+      (PauseReason::Step, "add".to_string(), 2, 2),
+      (PauseReason::Step, "add".to_string(), 3, 2),
+    ]
+  );
+}
+
+#[test]
+fn breakpoints() {
+  let debugger = ScriptedDebugger::new(&[]);
+  run(debugger.clone(), &[Breakpoint::new("step.lay", 3)]);
+
+  let debugger = debugger.borrow();
+  assert_eq!(
+    debugger.pauses,
+    vec![
+      (PauseReason::Breakpoint, "add".to_string(), 3, 2),
+      (PauseReason::Breakpoint, "add".to_string(), 3, 2),
+    ]
+  );
+  assert_eq!(debugger.locals[0], vec!["<fn add>", "1", "2", "3"]);
+  assert_eq!(debugger.locals[1], vec!["<fn add>", "3", "3", "6"]);
+}
+
+/// Output shared with the dap server so it can be read once the session ends
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Output {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.lock().unwrap().write(buf)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+#[test]
+fn dap() {
+  let path = fixture_path_inner("language/debugger/step.lay", FILE_PATH).unwrap();
+  let path = path.to_str().unwrap();
+
+  let requests = [
+    Json::object(vec![("command", "initialize".into())]),
+    Json::object(vec![
+      ("command", "launch".into()),
+      ("arguments", Json::object(vec![("program", path.into())])),
+    ]),
+    Json::object(vec![
+      ("command", "setBreakpoints".into()),
+      (
+        "arguments",
+        Json::object(vec![
+          ("source", Json::object(vec![("path", path.into())])),
+          (
+            "breakpoints",
+            Json::array(vec![Json::object(vec![("line", 3u32.into())])]),
+          ),
+        ]),
+      ),
+    ]),
+    Json::object(vec![("command", "configurationDone".into())]),
+    Json::object(vec![
+      ("command", "variables".into()),
+      (
+        "arguments",
+        Json::object(vec![("variablesReference", 1u8.into())]),
+      ),
+    ]),
+    Json::object(vec![("command", "next".into())]),
+    Json::object(vec![("command", "continue".into())]),
+    Json::object(vec![("command", "continue".into())]),
+    Json::object(vec![("command", "disconnect".into())]),
+  ];
+
+  let input = requests
+    .iter()
+    .map(|request| {
+      let body = request.to_string();
+      format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    })
+    .collect::<String>();
+
+  let output = Output::default();
+  dap::serve(
+    Vm::builder(),
+    Box::new(Cursor::new(input.into_bytes())),
+    Box::new(output.clone()),
+  )
+  .expect("Dap session failed");
+
+  let bytes = output.0.lock().unwrap().clone();
+  let mut output = Cursor::new(bytes);
+  let mut messages = vec![];
+  while let Some(message) = protocol::read_message(&mut output).unwrap() {
+    messages.push(message);
+  }
+
+  let events = messages
+    .iter()
+    .filter_map(|message| {
+      let event = message.get("event")?.as_str()?;
+      let reason = message.get("body")?.get("reason").and_then(Json::as_str);
+      Some(format!("{} {}", event, reason.unwrap_or("")))
+    })
+    .collect::<Vec<String>>();
+
+  assert_eq!(
+    events,
+    vec![
+      "initialized ",
+      "stopped breakpoint",
+      "stopped step",
+      "stopped breakpoint",
+      "exited ",
+      "terminated "
+    ]
+  );
+
+  let variables = messages
+    .iter()
+    .find(|message| message.get("command").and_then(Json::as_str) == Some("variables"))
+    .and_then(|message| message.get("body")?.get("variables")?.as_array())
+    .unwrap()
+    .iter()
+    .filter_map(|variable| variable.get("value")?.as_str())
+    .collect::<Vec<&str>>();
+  assert_eq!(variables, vec!["<fn add>", "1", "2", "3"]);
+}
@@ -0,0 +1,693 @@
+use laythe_core::{managed::Trace, value::Value};
+use laythe_env::{
+  env::{Env, EnvImpl},
+  io::{Io, IoImpl},
+  stdio::{
+    support::{IoStdioTest, StdioTestContainer},
+    StdioBuffer,
+  },
+};
+use laythe_vm::{
+  embed::{FromValue, IntoValue, LyError, NativeClassBuilder, Rooted},
+  vm::{Vm, VmError},
+};
+use std::{
+  collections::HashMap,
+  io::{self, Write},
+  path::PathBuf,
+  str,
+  sync::Arc,
+};
+
+const SCRIPT: &str = "
+fn add(a, b) {
+  a + b
+}
+
+fn greet(name) {
+  'hello ' + name
+}
+
+fn double(list) {
+  list.iter().map(|x| x * 2).into(List.collect)
+}
+
+fn totals(scores) {
+  let result = {};
+  for entry in scores {
+    result[entry[0]] = entry[1].iter().reduce(0, |acc, x| acc + x);
+  }
+
+  return result;
+}
+
+fn swap(pair) {
+  let (a, b) = pair;
+  return (b, a);
+}
+
+fn fail() {
+  [][1];
+}
+
+class Point {
+  init(x, y) {
+    self.x = x;
+    self.y = y;
+  }
+}
+
+let answer = 42;
+";
+
+fn vm(stdio_container: &Arc<StdioTestContainer>) -> Vm {
+  let stdio = Arc::new(IoStdioTest::new(stdio_container));
+  let mut vm = Vm::builder()
+    .io(Io::default().with_stdio(stdio))
+    .build()
+    .expect("Unable to create vm");
+
+  let result = vm.run(PathBuf::from("/app/main.lay"), SCRIPT);
+  assert_eq!(result, Ok(0));
+  vm
+}
+
+fn global(vm: &Vm, name: &str) -> Rooted {
+  vm.get_global(name)
+    .unwrap_or_else(|| panic!("Global {} not found", name))
+}
+
+#[test]
+fn get_global() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let vm = vm(&stdio_container);
+
+  assert_eq!(global(&vm, "answer").get::<i64>(), Ok(42));
+  assert!(vm.get_global("missing").is_none());
+}
+
+#[test]
+fn call_with_numbers_and_strings() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let add = global(&vm, "add");
+  let args = [vm.to_value(1.5), vm.to_value(2_i64)];
+  let sum = vm.call(&add, &args).and_then(|sum| sum.get::<f64>());
+  assert_eq!(sum, Ok(3.5));
+
+  let greet = global(&vm, "greet");
+  let name = vm.to_value("laythe");
+  let greeting = vm
+    .call(&greet, &[name])
+    .and_then(|greeting| greeting.get::<String>());
+  assert_eq!(greeting, Ok("hello laythe".to_string()));
+}
+
+#[test]
+fn call_with_collections() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let double = global(&vm, "double");
+  let list = vm.to_value(vec![1_i64, 2, 3]);
+  let doubled = vm
+    .call(&double, &[list])
+    .and_then(|doubled| doubled.get::<Vec<i64>>());
+  assert_eq!(doubled, Ok(vec![2, 4, 6]));
+
+  let mut scores = HashMap::new();
+  scores.insert("a", vec![1_i64, 2]);
+  scores.insert("b", vec![3]);
+
+  let totals = global(&vm, "totals");
+  let scores = vm.to_value(scores);
+  let result = vm
+    .call(&totals, &[scores])
+    .and_then(|totals| totals.get::<HashMap<String, i64>>())
+    .expect("Unable to call totals");
+
+  assert_eq!(result.get("a"), Some(&3));
+  assert_eq!(result.get("b"), Some(&3));
+}
+
+#[test]
+fn call_with_tuples() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let swap = global(&vm, "swap");
+  let pair = vm.to_value((1_i64, "two"));
+  let swapped = vm
+    .call(&swap, &[pair])
+    .and_then(|swapped| swapped.get::<(String, i64)>());
+  assert_eq!(swapped, Ok(("two".to_string(), 1)));
+}
+
+#[test]
+fn call_class() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let point = global(&vm, "Point");
+  let args = [vm.to_value(1_i64), vm.to_value(2_i64)];
+  let instance = vm.call(&point, &args).expect("Unable to construct point");
+  assert!(instance.value().is_obj());
+}
+
+#[test]
+fn call_error() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let fail = global(&vm, "fail");
+  assert_eq!(
+    vm.call(&fail, &[]),
+    Err(LyError::Runtime {
+      class: "IndexError".to_string(),
+      message: "Index out of bounds. list was length 0 but attempted to index with 1.".to_string(),
+    })
+  );
+
+  let add = global(&vm, "add");
+  assert_eq!(
+    vm.call(&add, &[]),
+    Err(LyError::Runtime {
+      class: "RuntimeError".to_string(),
+      message: "'add' expected 2 argument(s) but got 0.".to_string(),
+    })
+  );
+
+  // the vm is still usable after an error
+  let args = [vm.to_value(1_i64), vm.to_value(1_i64)];
+  assert_eq!(vm.call(&add, &args).and_then(|sum| sum.get::<i64>()), Ok(2));
+}
+
+#[test]
+fn call_with_too_many_args() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let add = global(&vm, "add");
+  let args: Vec<Rooted> = (0..300_i64).map(|arg| vm.to_value(arg)).collect();
+  assert_eq!(
+    vm.call(&add, &args),
+    Err(LyError::Runtime {
+      class: "RuntimeError".to_string(),
+      message: "Cannot call with more than 255 arguments.".to_string(),
+    })
+  );
+}
+
+#[test]
+fn rooted_values_survive_collection() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+  let mut vm = Vm::builder()
+    .io(Io::default().with_stdio(stdio))
+    .gc_stress(true)
+    .build()
+    .expect("Unable to create vm");
+
+  assert_eq!(vm.run(PathBuf::from("/app/main.lay"), SCRIPT), Ok(0));
+  let double = global(&vm, "double");
+  let list = vm.to_value(vec![1_i64, 2, 3]);
+
+  // the first script's globals are only held by the handles now
+  let source = "
+let garbage = [];
+let i = 0;
+while i < 100 {
+  garbage.push([i]);
+  i = i + 1;
+}
+";
+  assert_eq!(vm.run(PathBuf::from("/app/other.lay"), source), Ok(0));
+  assert!(vm.get_global("double").is_none());
+
+  let doubled = vm
+    .call(&double, &[list])
+    .and_then(|doubled| doubled.get::<Vec<i64>>());
+  assert_eq!(doubled, Ok(vec![2, 4, 6]));
+}
+
+#[test]
+fn redirect_stdout() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+  let io = Io::default().with_stdio(stdio);
+  let mut vm = Vm::builder()
+    .io(io.clone())
+    .build()
+    .expect("Unable to create vm");
+
+  let buffer = StdioBuffer::default();
+  io.redirect_stdout(buffer.clone());
+  assert_eq!(
+    vm.run(PathBuf::from("/app/main.lay"), "print('captured');"),
+    Ok(0)
+  );
+
+  assert!(io.restore_stdout().is_some());
+  assert_eq!(
+    vm.run(PathBuf::from("/app/main.lay"), "print('restored');"),
+    Ok(0)
+  );
+
+  assert_eq!(str::from_utf8(&buffer.contents()), Ok("captured\n"));
+  assert_eq!(str::from_utf8(&stdio_container.stdout), Ok("restored\n"));
+}
+
+#[test]
+fn conversion_error() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let greet = global(&vm, "greet");
+  let name = vm.to_value("laythe");
+  assert_eq!(
+    vm.call(&greet, &[name])
+      .and_then(|greeting| greeting.get::<f64>()),
+    Err(LyError::Conversion {
+      expected: "number",
+      found: "string",
+    })
+  );
+}
+
+#[test]
+fn run_returns_errors() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let source = "
+fn fail() {
+  [][1];
+}
+
+fail();
+";
+  let result = vm.run(PathBuf::from("/app/failing.lay"), source);
+  let error = match result {
+    Err(VmError::Runtime(error)) => error,
+    result => panic!("Unexpected result {:?}", result),
+  };
+  assert_eq!(error.class, "IndexError");
+  assert!(error.diagnostic.is_some());
+  assert!(error.stack.contains("in fail()"));
+  assert!(stdio_container.stderr.is_empty());
+
+  vm.report(&VmError::Runtime(error));
+  let stderr = str::from_utf8(&stdio_container.stderr).expect("Stderr was not utf8");
+  assert!(stderr.contains("IndexError"));
+  assert!(stderr.contains("in fail()"));
+
+  let result = vm.run(PathBuf::from("/app/broken.lay"), "let = 1;");
+  assert!(matches!(result, Err(VmError::Compile(errors)) if !errors.is_empty()));
+}
+
+#[test]
+fn run_returns_error_causes() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let source = "
+fn lookup() {
+  return {}['missing'];
+}
+
+fn parse() {
+  try {
+    lookup();
+  } catch err {
+    raise ValueError('Could not parse.') from err;
+  }
+}
+
+parse();
+";
+  let result = vm.run(PathBuf::from("/app/failing.lay"), source);
+  let error = match result {
+    Err(VmError::Runtime(error)) => error,
+    result => panic!("Unexpected result {:?}", result),
+  };
+  assert_eq!(error.class, "ValueError");
+  assert!(error.stack.contains("in parse()"));
+
+  let cause = error.cause.as_ref().expect("Expected a cause");
+  assert_eq!(cause.class, "KeyError");
+  assert!(cause.diagnostic.is_none());
+  assert!(cause.stack.contains("[line 3] in lookup()"));
+  assert!(cause.cause.is_none());
+
+  vm.report(&VmError::Runtime(error));
+  let stderr = str::from_utf8(&stdio_container.stderr).expect("Stderr was not utf8");
+  assert!(stderr.contains("Caused by KeyError"));
+  assert!(stderr.contains("in lookup()"));
+}
+
+fn point_class() -> NativeClassBuilder {
+  NativeClassBuilder::new("Point")
+    .field("x")
+    .field("y")
+    .init(&["x", "y"], |this, (x, y): (f64, f64)| {
+      this.set("x", x)?;
+      this.set("y", y)
+    })
+    .method("length", &[], |this, ()| {
+      let (x, y): (f64, f64) = (this.get("x")?, this.get("y")?);
+      Ok((x * x + y * y).sqrt())
+    })
+    .method("scale", &["by"], |this, (by,): (i64,)| {
+      let (x, y): (f64, f64) = (this.get("x")?, this.get("y")?);
+      this.set("x", x * by as f64)?;
+      this.set("y", y * by as f64)?;
+      Ok(this.value())
+    })
+    .method(
+      "label",
+      &["prefix"],
+      |this, (prefix,): (Option<String>,)| {
+        let x: f64 = this.get("x")?;
+        Ok(format!("{}{}", prefix.unwrap_or_default(), x))
+      },
+    )
+    .method("parts", &[], |this, ()| {
+      let (x, y): (f64, f64) = (this.get("x")?, this.get("y")?);
+      Ok((x, y))
+    })
+    .static_method("origin", &[], |()| Ok(vec![0.0, 0.0]))
+    .static_method("check", &["value"], |(value,): (f64,)| {
+      if value < 0.0 {
+        Err(LyError::Runtime {
+          class: "RuntimeError".to_string(),
+          message: "value was negative".to_string(),
+        })
+      } else {
+        Ok(value)
+      }
+    })
+}
+
+fn run_with_point(source: &str) -> (Result<u16, VmError>, String) {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+  let mut vm = Vm::builder()
+    .io(Io::default().with_stdio(stdio))
+    .build()
+    .expect("Unable to create vm");
+
+  vm.register_class(point_class())
+    .expect("Unable to register class");
+  let result = vm.run(PathBuf::from("/app/main.lay"), source);
+  if let Err(error) = &result {
+    vm.report(error);
+  }
+
+  let stderr = str::from_utf8(&stdio_container.stderr).expect("Stderr was not utf8");
+  (result, stderr.to_string())
+}
+
+#[test]
+fn native_class() {
+  let (result, stderr) = run_with_point(
+    "
+let point = Point(3, 4);
+assertEq(point.x, 3);
+assertEq(point.length(), 5);
+assertEq(point.scale(2).y, 8);
+assertEq(point.label(nil), '6');
+assertEq(point.label('x='), 'x=6');
+let (x, y) = point.parts();
+assertEq(x + y, 14);
+assertEq(Point.origin().len(), 2);
+assertEq(Point.origin()[1], 0);
+assertEq(Point.check(1), 1);
+
+try {
+  Point.check(-1);
+  assert(false);
+} catch {
+  assert(true);
+}
+",
+  );
+
+  assert_eq!(result, Ok(0), "{}", stderr);
+}
+
+#[test]
+fn native_class_checks_arguments() {
+  let (result, stderr) = run_with_point("Point(1);");
+  assert!(matches!(result, Err(VmError::Runtime(_))));
+  assert!(stderr.contains("method \"init\" expected 2 argument(s) but received 1."));
+
+  let (result, stderr) = run_with_point("Point(1, 'two');");
+  assert!(matches!(result, Err(VmError::Runtime(_))));
+  assert!(
+    stderr.contains("method \"init\"'s parameter \"y\" required a number but received a string.")
+  );
+
+  let (result, stderr) = run_with_point("Point(1, 2).scale(1.5);");
+  assert!(matches!(result, Err(VmError::Runtime(_))));
+  assert!(stderr.contains("Expected integer but found number."));
+
+  let (result, stderr) = run_with_point("Point.check(-1);");
+  assert!(matches!(result, Err(VmError::Runtime(_))));
+  assert!(stderr.contains("value was negative"));
+}
+
+#[test]
+fn native_class_call_from_rust() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  let point = vm
+    .register_class(point_class())
+    .expect("Unable to register class");
+  let args = [vm.to_value(6_i64), vm.to_value(8_i64)];
+  let instance = vm.call(&point, &args).expect("Unable to construct point");
+  assert!(instance.value().is_obj());
+
+  assert!(vm.register_class(point_class()).is_err());
+}
+
+/// Host state holding values created by scripts
+#[derive(Default)]
+struct Tally {
+  total: f64,
+  kept: Vec<Value>,
+}
+
+impl Trace for Tally {
+  fn trace(&self) {
+    self.kept.iter().for_each(|value| value.trace());
+  }
+
+  fn visit_refs(&self, visitor: &mut dyn FnMut(usize)) {
+    self.kept.iter().for_each(|value| value.visit_refs(visitor));
+  }
+
+  fn trace_debug(&self, log: &mut dyn Write) {
+    self.kept.iter().for_each(|value| value.trace_debug(log));
+  }
+}
+
+struct Other;
+
+impl Trace for Other {}
+
+fn tally_class() -> NativeClassBuilder {
+  NativeClassBuilder::new("Tally")
+    .method("add", &["amount"], |this, (amount,): (f64,)| {
+      let tally = this.host_data::<Tally>().expect("Tally not provided");
+      tally.total += amount;
+      Ok(tally.total)
+    })
+    .method("keep", &["value"], |this, (value,): (Value,)| {
+      let tally = this.host_data::<Tally>().expect("Tally not provided");
+      tally.kept.push(value);
+      Ok(())
+    })
+    .method("kept", &[], |this, ()| {
+      let tally = this.host_data::<Tally>().expect("Tally not provided");
+      Ok(tally.kept.clone())
+    })
+}
+
+#[test]
+fn host_data() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+  let mut vm = Vm::builder()
+    .io(Io::default().with_stdio(stdio))
+    .gc_stress(true)
+    .build()
+    .expect("Unable to create vm");
+
+  vm.set_host_data(Tally::default());
+  vm.register_class(tally_class())
+    .expect("Unable to register class");
+
+  let source = "
+let tally = Tally();
+tally.add(2);
+assertEq(tally.add(3), 5);
+
+for i in [0, 1, 2] {
+  tally.keep([i]);
+}
+
+let garbage = [];
+let i = 0;
+while i < 100 {
+  garbage.push([i + 10]);
+  i = i + 1;
+}
+
+let kept = tally.kept();
+assertEq(kept.len(), 3);
+assertEq(kept[0][0], 0);
+assertEq(kept[2][0], 2);
+";
+  let result = vm.run(PathBuf::from("/app/main.lay"), source);
+  let stderr = str::from_utf8(&stdio_container.stderr).expect("Stderr was not utf8");
+  assert_eq!(result, Ok(0), "{}", stderr);
+
+  let tally = vm.host_data::<Tally>().expect("Tally not provided");
+  assert_eq!(tally.total, 5.0);
+  assert_eq!(tally.kept.len(), 3);
+
+  vm.host_data_mut::<Tally>()
+    .expect("Tally not provided")
+    .total = 0.0;
+  assert!(vm.host_data::<Other>().is_none());
+}
+
+#[test]
+fn native_fun() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+  let mut vm = Vm::builder()
+    .io(Io::default().with_stdio(stdio))
+    .gc_stress(true)
+    .build()
+    .expect("Unable to create vm");
+
+  vm.register_fun("joinAll", |hooks, args| {
+    let parts = args
+      .iter()
+      .map(|arg| String::from_value(*arg))
+      .collect::<Result<Vec<String>, LyError>>()?;
+    Ok(parts.join("-").into_value(&hooks.as_gc()))
+  })
+  .expect("Unable to register function");
+
+  let source = "
+assertEq(joinAll(), '');
+assertEq(joinAll('a', 'b', 'c'), 'a-b-c');
+
+try {
+  joinAll(1);
+  assert(false);
+} catch err {
+  assertEq(err.cls(), RuntimeError);
+}
+";
+  let result = vm.run(PathBuf::from("/app/main.lay"), source);
+  let stderr = str::from_utf8(&stdio_container.stderr).expect("Stderr was not utf8");
+  assert_eq!(result, Ok(0), "{}", stderr);
+
+  let join_all = vm.get_global("joinAll").expect("joinAll not found");
+  let result = vm
+    .call(&join_all, &[vm.to_value("x"), vm.to_value("y")])
+    .and_then(|result| result.get::<String>());
+  assert_eq!(result, Ok("x-y".to_string()));
+}
+
+#[derive(Debug)]
+struct IoEnvNoDir();
+
+impl IoImpl<Env> for IoEnvNoDir {
+  fn make(&self) -> Env {
+    Env::new(Box::new(EnvNoDir()))
+  }
+}
+
+struct EnvNoDir();
+
+impl EnvImpl for EnvNoDir {
+  fn current_dir(&self) -> io::Result<PathBuf> {
+    Err(io::Error::new(
+      io::ErrorKind::NotFound,
+      "no working directory",
+    ))
+  }
+
+  fn args(&self) -> Vec<String> {
+    vec![]
+  }
+
+  fn var(&self, _name: &str) -> Option<String> {
+    None
+  }
+
+  fn set_var(&self, _name: &str, _value: &str) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn platform(&self) -> &str {
+    "test"
+  }
+}
+
+#[test]
+fn build_without_working_directory() {
+  let io = Io::default().with_env(Arc::new(IoEnvNoDir()));
+
+  match Vm::builder().io(io).build() {
+    Err(VmError::Io { kind, .. }) => assert_eq!(kind, io::ErrorKind::NotFound),
+    Err(error) => panic!("Unexpected error {:?}", error),
+    Ok(_) => panic!("Expected the vm to fail to build"),
+  }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_values() {
+  use serde::de::DeserializeSeed;
+
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+  let mut vm = Vm::builder()
+    .io(Io::default().with_stdio(stdio))
+    .build()
+    .expect("Unable to create vm");
+
+  let source = "
+fn describe(config) {
+  return {
+    'name': config['name'],
+    'count': config['tags'].len(),
+    'debug': config['debug'],
+    'ratio': config['ratio'] * 2,
+  };
+}
+";
+  assert_eq!(vm.run(PathBuf::from("/app/main.lay"), source), Ok(0));
+
+  let mut json = serde_json::Deserializer::from_str(
+    r#"{ "name": "laythe", "tags": ["fast", "small"], "debug": null, "ratio": 0.25 }"#,
+  );
+  let config = vm
+    .deserialize_with(|seed| seed.deserialize(&mut json))
+    .expect("Unable to deserialize config");
+
+  let describe = global(&vm, "describe");
+  let result = vm.call(&describe, &[config]).expect("Unable to describe");
+
+  assert_eq!(
+    serde_json::to_value(result.value()).expect("Unable to serialize result"),
+    serde_json::json!({ "name": "laythe", "count": 2, "debug": null, "ratio": 0.5 })
+  );
+}
@@ -0,0 +1,29 @@
+use support::{assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn encoding() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/encoding/base64.lay",
+      "std_lib/encoding/base64_stream.lay",
+      "std_lib/encoding/errors.lay",
+      "std_lib/encoding/hex.lay",
+      "std_lib/encoding/url.lay",
+      "std_lib/encoding/utf8.lay",
+      "std_lib/encoding/utf8_decoder.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
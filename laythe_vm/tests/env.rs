@@ -1,9 +1,8 @@
-use laythe_vm::vm::ExecuteResult;
-use support::assert_files_exit;
+use support::{assert_files_exit, Outcome};
 
 mod support;
 
-fn test_files(paths: &[&str], result: ExecuteResult) -> Result<(), std::io::Error> {
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
   assert_files_exit(paths, FILE_PATH, result)
 }
 
@@ -13,6 +12,6 @@ const FILE_PATH: &str = file!();
 fn env() -> Result<(), std::io::Error> {
   test_files(
     &["std_lib/env/cwd.lay", "std_lib/env/args.lay"],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )
 }
@@ -0,0 +1,69 @@
+#![cfg(all(feature = "ffi", target_os = "linux"))]
+
+use laythe_env::{
+  io::Io,
+  stdio::support::{IoStdioTest, StdioTestContainer},
+};
+use laythe_vm::vm::{Vm, VmError};
+use std::{path::PathBuf, sync::Arc};
+
+fn run(source: &str) -> Result<u16, VmError> {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let io = Io::default().with_stdio(Arc::new(IoStdioTest::new(&stdio_container)));
+
+  let mut vm = Vm::builder()
+    .io(io)
+    .gc_stress(true)
+    .build()
+    .expect("Unable to create vm");
+  vm.run(PathBuf::from("/app/main.lay"), source)
+}
+
+#[test]
+fn calls_foreign_functions() {
+  let source = "
+import std.ffi:{Library};
+
+let libc = Library('libc.so.6');
+let strlen = libc.declare('strlen', ['string'], 'i64');
+let abs = libc.declare('abs', ['i32'], 'i32');
+let getenv = libc.declare('getenv', ['string'], 'string');
+
+let libm = Library('libm.so.6');
+let pow = libm.declare('pow', ['f64', 'f64'], 'f64');
+
+assertEq(strlen('laythe'), 6);
+assertEq(abs(-12), 12);
+assertEq(getenv('LAYTHE_FFI_UNSET_VARIABLE'), nil);
+assertEq(pow(2, 10), 1024);
+";
+
+  assert_eq!(run(source), Ok(0));
+}
+
+#[test]
+fn raises_ffi_errors() {
+  let source = "
+import std.ffi:{Library, FfiError};
+
+let libc = Library('libc.so.6');
+let abs = libc.declare('abs', ['i32'], 'i32');
+
+fn raises(f) {
+  try {
+    f();
+    return false;
+  } catch err {
+    return err.cls() == FfiError;
+  }
+}
+
+assert(raises(|| Library('libnot_a_library.so')));
+assert(raises(|| libc.declare('not_a_libc_function', [])));
+assert(raises(|| libc.declare('abs', ['int'], 'i32')));
+assert(raises(|| abs(1.5)));
+assert(raises(|| abs(1099511627776)));
+";
+
+  assert_eq!(run(source), Ok(0));
+}
@@ -1,9 +1,8 @@
-use laythe_vm::vm::ExecuteResult;
-use support::{assert_file_exit_and_stdio, assert_files_exit};
+use support::{assert_file_exit_and_stdio, assert_files_exit, Outcome};
 
 mod support;
 
-fn test_files(paths: &[&str], result: ExecuteResult) -> Result<(), std::io::Error> {
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
   assert_files_exit(paths, FILE_PATH, result)
 }
 
@@ -11,34 +10,220 @@ fn test_file_with_stdio(
   path: &str,
   stdout: Option<Vec<&str>>,
   stderr: Option<Vec<&str>>,
-  result: ExecuteResult,
+  result: Outcome,
 ) -> Result<(), std::io::Error> {
   assert_file_exit_and_stdio(path, FILE_PATH, None, None, stdout, stderr, result)
 }
 
 const FILE_PATH: &str = file!();
 
+#[test]
+fn assert() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/global/assert/almost_eq.lay",
+      "std_lib/global/assert/pass.lay",
+      "std_lib/global/assert/raises.lay",
+      "std_lib/global/assert/shadowed.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &["std_lib/global/assert/failure_raises.lay"],
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/assert/failure_binary.lay",
+    None,
+    Some(vec![
+      "error: AssertError: Assertion failed: 'a + b == 4",
+      "  a = 1",
+      "  b = 2",
+      "  a + b = 3'",
+      "  --> failure_binary.lay:4:1",
+      "  |",
+      "4 | assert(a + b == 4);",
+      "  | ^^^^^^^^^^^^^^^^^^",
+      "",
+      "  [line 4] in script",
+    ]),
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/assert/failure_call.lay",
+    None,
+    Some(vec![
+      "error: AssertError: Assertion failed: '!(double(list[0]) == list.len()) and list.len() > 5",
+      "  list[0] = 1",
+      "  double(list[0]) = 2",
+      "  list.len() = 2",
+      "  double(list[0]) == list.len() = true'",
+      "  --> failure_call.lay:7:1",
+      "  |",
+      "7 | assert(!(double(list[0]) == list.len()) and list.len() > 5);",
+      "  | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^",
+      "",
+      "  [line 7] in script",
+    ]),
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/assert/failure_literal.lay",
+    None,
+    Some(vec![
+      "error: AssertError: Assertion failed: 'false'",
+      "  --> failure_literal.lay:1:1",
+      "  |",
+      "1 | assert(false);",
+      "  | ^^^^^^^^^^^^^",
+      "",
+      "  [line 1] in script",
+    ]),
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/assert/failure_message.lay",
+    None,
+    Some(vec![
+      "error: AssertError: Assertion failed: 'one is not greater than two'",
+      "  --> failure_message.lay:1:1",
+      "  |",
+      "1 | assert(1 > 2, \"one is not greater than two\");",
+      "  | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^",
+      "",
+      "  [line 1] in script",
+    ]),
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/assert/failure_eq_message.lay",
+    None,
+    Some(vec![
+      "error: AssertError: Assertion failed: 'points should match'",
+      "  actual = Point(1, 2)",
+      "  expected = Point(1, 2)",
+      "   --> failure_eq_message.lay:10:1",
+      "   |",
+      "10 | assertEq(Point(1, 2), Point(1, 2), \"points should match\");",
+      "   | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^",
+      "",
+      "  [line 10] in script",
+    ]),
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/assert/failure_almost_eq.lay",
+    None,
+    Some(vec![
+      "error: AssertError: Assertion failed 1 and 1.5 are not within 0.1.",
+      "  --> failure_almost_eq.lay:1:1",
+      "  |",
+      "1 | assertAlmostEq(1, 1.5, 0.1);",
+      "  | ^^^^^^^^^^^^^^^^^^^^^^^^^^^",
+      "",
+      "  [line 1] in script",
+    ]),
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn attr() -> Result<(), std::io::Error> {
+  test_files(
+    &[
+      "std_lib/global/attr/get_attr.lay",
+      "std_lib/global/attr/set_attr.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &[
+      "std_lib/global/attr/get_attr_missing.lay",
+      "std_lib/global/attr/set_attr_frozen.lay",
+      "std_lib/global/attr/set_attr_not_instance.lay",
+    ],
+    Outcome::RuntimeError,
+  )
+}
+
 #[test]
 fn bool() -> Result<(), std::io::Error> {
-  test_files(&vec!["std_lib/global/bool/str.lay"], ExecuteResult::Ok(0))?;
+  test_files(&vec!["std_lib/global/bool/str.lay"], Outcome::Ok(0))?;
 
-  test_files(&vec![], ExecuteResult::CompileError)?;
+  test_files(&vec![], Outcome::CompileError)?;
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+  test_files(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn bytes() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/global/bytes/concat.lay",
+      "std_lib/global/bytes/iter.lay",
+      "std_lib/global/bytes/new.lay",
+      "std_lib/global/bytes/slice.lay",
+      "std_lib/global/bytes/str.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &vec![
+      "std_lib/global/bytes/index_out_of_bounds.lay",
+      "std_lib/global/bytes/new_invalid.lay",
+    ],
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn channel() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/global/channel/buffered.lay",
+      "std_lib/global/channel/buffered_blocks.lay",
+      "std_lib/global/channel/close.lay",
+      "std_lib/global/channel/close_wakes.lay",
+      "std_lib/global/channel/iter.lay",
+      "std_lib/global/channel/new.lay",
+      "std_lib/global/channel/rendezvous.lay",
+      "std_lib/global/channel/str.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &vec![
+      "std_lib/global/channel/deadlock.lay",
+      "std_lib/global/channel/new_negative.lay",
+    ],
+    Outcome::RuntimeError,
+  )
 }
 
 #[test]
 fn class() -> Result<(), std::io::Error> {
   test_files(
     &vec![
+      "std_lib/global/class/fields.lay",
+      "std_lib/global/class/methods.lay",
       "std_lib/global/class/name.lay",
       "std_lib/global/class/str.lay",
       "std_lib/global/class/superCls.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+  test_files(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -48,8 +233,10 @@ fn closure() -> Result<(), std::io::Error> {
       "std_lib/global/closure/name.lay",
       "std_lib/global/closure/call.lay",
       "std_lib/global/closure/len.lay",
+      "std_lib/global/closure/arity.lay",
+      "std_lib/global/closure/bind.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_files(
@@ -58,7 +245,61 @@ fn closure() -> Result<(), std::io::Error> {
       // "std_lib/global/closure/call_wrong_args.lay",
       "std_lib/global/closure/size_wrong_args.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn fiber() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/global/fiber/join.lay",
+      "std_lib/global/fiber/join_error.lay",
+      "std_lib/global/fiber/sleep.lay",
+      "std_lib/global/fiber/sleep_main.lay",
+      "std_lib/global/fiber/spawn.lay",
+      "std_lib/global/fiber/yield.lay",
+      "std_lib/global/fiber/yield_main.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &vec![
+      "std_lib/global/fiber/join_self.lay",
+      "std_lib/global/fiber/sleep_negative.lay",
+      "std_lib/global/fiber/spawn_params.lay",
+    ],
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn fmt() -> Result<(), std::io::Error> {
+  test_files(&["std_lib/global/fmt/basic.lay"], Outcome::Ok(0))?;
+
+  test_files(
+    &["std_lib/global/fmt/not_string.lay"],
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn inspect() -> Result<(), std::io::Error> {
+  test_files(
+    &[
+      "std_lib/global/inspect/basic.lay",
+      "std_lib/global/inspect/cycle.lay",
+      "std_lib/global/inspect/depth.lay",
+      "std_lib/global/inspect/nested_call.lay",
+      "std_lib/global/inspect/repr.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &["std_lib/global/inspect/repr_not_string.lay"],
+    Outcome::RuntimeError,
   )
 }
 
@@ -78,16 +319,17 @@ fn iter() -> Result<(), std::io::Error> {
       "std_lib/global/iter/len.lay",
       "std_lib/global/iter/map.lay",
       "std_lib/global/iter/next.lay",
+      "std_lib/global/iter/par_each.lay",
       "std_lib/global/iter/reduce.lay",
       "std_lib/global/iter/skip.lay",
       "std_lib/global/iter/str.lay",
       "std_lib/global/iter/take.lay",
       "std_lib/global/iter/zip.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+  test_files(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -101,6 +343,7 @@ fn list() -> Result<(), std::io::Error> {
       "std_lib/global/list/insert.lay",
       "std_lib/global/list/iter.lay",
       "std_lib/global/list/len.lay",
+      "std_lib/global/list/par_map.lay",
       "std_lib/global/list/pop.lay",
       "std_lib/global/list/push.lay",
       "std_lib/global/list/remove.lay",
@@ -108,7 +351,7 @@ fn list() -> Result<(), std::io::Error> {
       "std_lib/global/list/slice.lay",
       "std_lib/global/list/str.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_files(
@@ -116,7 +359,27 @@ fn list() -> Result<(), std::io::Error> {
       "std_lib/global/list/insert_out_of_bounds.lay",
       "std_lib/global/list/remove_out_of_bounds.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn tuple() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/global/tuple/has.lay",
+      "std_lib/global/tuple/index.lay",
+      "std_lib/global/tuple/iter.lay",
+      "std_lib/global/tuple/len.lay",
+      "std_lib/global/tuple/list.lay",
+      "std_lib/global/tuple/str.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &vec!["std_lib/global/tuple/index_out_of_bounds.lay"],
+    Outcome::RuntimeError,
   )
 }
 
@@ -133,12 +396,12 @@ fn map() -> Result<(), std::io::Error> {
       "std_lib/global/map/len.lay",
       "std_lib/global/map/str.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_files(
     &vec!["std_lib/global/map/remove_missing_key.lay"],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -148,28 +411,39 @@ fn method() -> Result<(), std::io::Error> {
     &vec![
       "std_lib/global/method/name.lay",
       "std_lib/global/method/call.lay",
+      "std_lib/global/method/arity.lay",
+      "std_lib/global/method/bind.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+  test_files(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
 fn module() -> Result<(), std::io::Error> {
-  test_files(
-    &vec!["std_lib/global/module/name.lay"],
-    ExecuteResult::Ok(0),
-  )?;
+  test_files(&vec!["std_lib/global/module/name.lay"], Outcome::Ok(0))?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+#[test]
+fn native() -> Result<(), std::io::Error> {
+  test_files(
+    &[
+      "std_lib/global/native/name.lay",
+      "std_lib/global/native/arity.lay",
+      "std_lib/global/native/bind.lay",
+    ],
+    Outcome::Ok(0),
+  )
 }
 
 #[test]
 fn nil() -> Result<(), std::io::Error> {
-  test_files(&vec!["std_lib/global/nil/str.lay"], ExecuteResult::Ok(0))?;
+  test_files(&vec!["std_lib/global/nil/str.lay"], Outcome::Ok(0))?;
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+  test_files(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -178,16 +452,28 @@ fn number() -> Result<(), std::io::Error> {
     &vec![
       "std_lib/global/number/ceil.lay",
       "std_lib/global/number/cmp.lay",
+      "std_lib/global/number/div_rem.lay",
       "std_lib/global/number/floor.lay",
       "std_lib/global/number/parse.lay",
+      "std_lib/global/number/parse_invalid.lay",
       "std_lib/global/number/round.lay",
       "std_lib/global/number/str.lay",
       "std_lib/global/number/times.lay",
+      "std_lib/global/number/to_fixed.lay",
+      "std_lib/global/number/to_precision.lay",
+      "std_lib/global/number/to_string.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+  test_files(
+    &vec![
+      "std_lib/global/number/div_rem_zero.lay",
+      "std_lib/global/number/to_fixed_invalid.lay",
+      "std_lib/global/number/to_string_invalid.lay",
+    ],
+    Outcome::RuntimeError,
+  )
 }
 
 #[test]
@@ -195,10 +481,74 @@ fn object() -> Result<(), std::io::Error> {
   test_files(
     &vec![
       "std_lib/global/object/cls.lay",
+      "std_lib/global/object/copy.lay",
       "std_lib/global/object/equals.lay",
+      "std_lib/global/object/freeze.lay",
+      "std_lib/global/object/isA.lay",
       "std_lib/global/object/str.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &vec![
+      "std_lib/global/object/freeze_instance.lay",
+      "std_lib/global/object/freeze_list.lay",
+      "std_lib/global/object/isA_not_class.lay",
+    ],
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn on_exit() -> Result<(), std::io::Error> {
+  test_file_with_stdio(
+    "std_lib/global/on_exit/order.lay",
+    Some(vec!["done", "third", "second", "first"]),
+    None,
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/on_exit/nested.lay",
+    Some(vec!["registering", "inner", "outer"]),
+    None,
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/on_exit/exit.lay",
+    Some(vec!["cleanup"]),
+    None,
+    Outcome::Ok(2),
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/on_exit/error.lay",
+    Some(vec!["third", "first"]),
+    Some(vec![
+      "error: IndexError: Index out of bounds. list was length 0 but attempted to index with 1.",
+      "  --> error.lay:3:3",
+      "  |",
+      "3 |   [][1];",
+      "  |   ^^^^^",
+      "",
+      "  [line 3] in lambda()",
+      "  [line 0] in native()",
+    ]),
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/on_exit/runtime_error.lay",
+    Some(vec![""]),
+    None,
+    Outcome::RuntimeError,
+  )?;
+
+  test_files(
+    &vec!["std_lib/global/on_exit/not_fun.lay"],
+    Outcome::RuntimeError,
   )
 }
 
@@ -208,21 +558,42 @@ fn print() -> Result<(), std::io::Error> {
     "std_lib/global/print/basic.lay",
     Some(vec!["10", "true", "['cat']", "{}"]),
     None,
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_with_stdio(
     "std_lib/global/print/multi.lay",
     Some(vec!["10 false true ['dog'] { 'cat': nil }"]),
     None,
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_with_stdio(
     "std_lib/global/print/with_newline_char.lay",
     Some(vec!["hi!", "bye!"]),
     None,
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/print/cycle.lay",
+    Some(vec!["[1, 'two', [...]]", "([(...)],)"]),
+    None,
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/print/print_err.lay",
+    Some(vec!["done"]),
+    Some(vec!["warning: 3 [true]", "", "end"]),
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdio(
+    "std_lib/global/print/repr.lay",
+    Some(vec!["Point(1, 2)", "[Point(3, 4)] done"]),
+    None,
+    Outcome::Ok(0),
   )
 }
 
@@ -230,16 +601,30 @@ fn print() -> Result<(), std::io::Error> {
 fn str() -> Result<(), std::io::Error> {
   test_files(
     &vec![
+      "std_lib/global/str/format.lay",
       "std_lib/global/str/has.lay",
       "std_lib/global/str/index.lay",
       "std_lib/global/str/iter.lay",
       "std_lib/global/str/len.lay",
+      "std_lib/global/str/parse_number.lay",
       "std_lib/global/str/slice.lay",
       "std_lib/global/str/split.lay",
       "std_lib/global/str/str.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+  test_files(
+    &[
+      "std_lib/global/str/format_missing_name.lay",
+      "std_lib/global/str/format_missing_value.lay",
+      "std_lib/global/str/format_unmatched.lay",
+    ],
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn type_() -> Result<(), std::io::Error> {
+  test_files(&["std_lib/global/type/basic.lay"], Outcome::Ok(0))
 }
@@ -0,0 +1,163 @@
+use laythe_env::io::Io;
+use laythe_native::{net::IoNetNative, time::IoTimeNative};
+use laythe_vm::vm::Vm;
+use std::{
+  io::{BufRead, BufReader, Read, Write},
+  net::{TcpListener, TcpStream},
+  path::PathBuf,
+  sync::Arc,
+  thread,
+  time::{Duration, Instant},
+};
+
+const SCRIPT: &str = "
+import std.net.http:{Server, Response};
+
+let server = nil;
+
+fn handle(request) {
+  if request.path == '/slow' {
+    Fiber.sleep(300);
+    return 'slow';
+  }
+
+  if request.path == '/echo' {
+    return Response(201, request.body, {'content-type': 'application/json'});
+  }
+
+  if request.path == '/shutdown' {
+    server.close();
+    return 'bye';
+  }
+
+  '${request.method} ${request.path} ${request.query[\"name\"]}'
+}
+
+server = Server(handle);
+server.listen(address);
+";
+
+fn free_address() -> String {
+  let listener = TcpListener::bind("127.0.0.1:0").expect("Unable to bind");
+  listener.local_addr().expect("No local address").to_string()
+}
+
+fn connect(address: &str) -> TcpStream {
+  let start = Instant::now();
+
+  loop {
+    match TcpStream::connect(address) {
+      Ok(stream) => return stream,
+      Err(err) if start.elapsed() > Duration::from_secs(10) => {
+        panic!("Unable to connect to {}: {}", address, err)
+      },
+      Err(_) => thread::sleep(Duration::from_millis(20)),
+    }
+  }
+}
+
+/// Send a request and read the status, headers and body of its response
+fn request(
+  stream: &mut BufReader<TcpStream>,
+  head: &str,
+  body: &str,
+) -> (String, Vec<String>, String) {
+  let request = format!("{}\r\ncontent-length: {}\r\n\r\n{}", head, body.len(), body);
+  stream
+    .get_mut()
+    .write_all(request.as_bytes())
+    .expect("Unable to write request");
+
+  let mut status = String::new();
+  stream.read_line(&mut status).expect("No status line");
+
+  let mut headers = vec![];
+  let mut length = 0;
+  loop {
+    let mut line = String::new();
+    stream.read_line(&mut line).expect("No header");
+
+    let line = line.trim_end().to_string();
+    if line.is_empty() {
+      break;
+    }
+
+    if let Some(value) = line.strip_prefix("content-length: ") {
+      length = value.parse().expect("Invalid content length");
+    }
+    headers.push(line);
+  }
+
+  let mut body = vec![0; length];
+  stream.read_exact(&mut body).expect("Unable to read body");
+
+  (
+    status.trim_end().to_string(),
+    headers,
+    String::from_utf8(body).expect("Body was not utf8"),
+  )
+}
+
+#[test]
+fn serve() {
+  let address = free_address();
+
+  let server = {
+    let address = address.clone();
+    thread::spawn(move || {
+      let io = Io::default()
+        .with_time(Arc::new(IoTimeNative::default()))
+        .with_net(Arc::new(IoNetNative()));
+
+      let mut vm = Vm::builder().io(io).build().expect("Unable to create vm");
+
+      let script = format!("let address = '{}';\n{}", address, SCRIPT);
+      vm.run(PathBuf::from("/app/main.lay"), &script)
+    })
+  };
+
+  // two requests share one kept alive connection
+  let mut first = BufReader::new(connect(&address));
+  let (status, headers, body) = request(
+    &mut first,
+    "GET /hello?name=two+words HTTP/1.1\r\nhost: test",
+    "",
+  );
+  assert_eq!(status, "HTTP/1.1 200 OK");
+  assert!(headers.contains(&"connection: keep-alive".to_string()));
+  assert_eq!(body, "GET /hello two words");
+
+  let (status, headers, body) = request(&mut first, "POST /echo HTTP/1.1", "{\"a\":1}");
+  assert_eq!(status, "HTTP/1.1 201 Created");
+  assert!(headers.contains(&"content-type: application/json".to_string()));
+  assert_eq!(body, "{\"a\":1}");
+
+  // a slow handler doesn't hold up other connections
+  let slow = {
+    let address = address.clone();
+    thread::spawn(move || {
+      let mut stream = BufReader::new(connect(&address));
+      request(&mut stream, "GET /slow HTTP/1.1", "").2
+    })
+  };
+
+  thread::sleep(Duration::from_millis(50));
+  let start = Instant::now();
+  let mut second = BufReader::new(connect(&address));
+  let (_, _, body) = request(&mut second, "GET /fast?name=x HTTP/1.1", "");
+  assert_eq!(body, "GET /fast x");
+  assert!(start.elapsed() < Duration::from_millis(250));
+  assert_eq!(slow.join().expect("Slow request failed"), "slow");
+
+  // malformed requests are rejected and closed
+  let mut third = BufReader::new(connect(&address));
+  let (status, headers, _) = request(&mut third, "GET /\r\nbroken", "");
+  assert_eq!(status, "HTTP/1.1 400 Bad Request");
+  assert!(headers.contains(&"connection: close".to_string()));
+
+  let (_, headers, body) = request(&mut first, "GET /shutdown HTTP/1.1", "");
+  assert_eq!(body, "bye");
+  assert!(headers.contains(&"connection: close".to_string()));
+
+  assert_eq!(server.join().expect("Server thread panicked"), Ok(0));
+}
@@ -0,0 +1,145 @@
+use laythe_env::{
+  io::{Io, IoImpl},
+  stdio::{
+    support::{StdioTest, StdioTestContainer},
+    Stdio, StdioImpl,
+  },
+};
+use laythe_vm::vm::{Vm, VmError};
+use std::{
+  collections::VecDeque,
+  io::{self, Read, Write},
+  path::PathBuf,
+  str,
+  sync::{Arc, Mutex},
+};
+use termcolor::WriteColor;
+
+/// Stdio whose lines arrive from the host after the script asks for them
+#[derive(Debug)]
+struct IoStdioAsync {
+  container: Arc<StdioTestContainer>,
+  lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl IoImpl<Stdio> for IoStdioAsync {
+  fn make(&self) -> Stdio {
+    Stdio::new(Box::new(StdioAsync {
+      inner: self.container.make_stdio(),
+      lines: Arc::clone(&self.lines),
+    }))
+  }
+}
+
+struct StdioAsync {
+  inner: StdioTest,
+  lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl StdioImpl for StdioAsync {
+  fn stdout(&mut self) -> &mut dyn Write {
+    self.inner.stdout()
+  }
+  fn stderr(&mut self) -> &mut dyn Write {
+    self.inner.stderr()
+  }
+  fn stderr_color(&mut self) -> &mut dyn WriteColor {
+    self.inner.stderr_color()
+  }
+  fn stdin(&mut self) -> &mut dyn Read {
+    self.inner.stdin()
+  }
+  fn read_line(&self, buffer: &mut String) -> io::Result<usize> {
+    match self.lines.lock().unwrap().pop_front() {
+      Some(line) => {
+        buffer.push_str(&line);
+        Ok(line.len())
+      },
+      None => Err(io::Error::new(io::ErrorKind::WouldBlock, "No input yet")),
+    }
+  }
+  fn is_tty(&self) -> bool {
+    false
+  }
+}
+
+const SCRIPT: &str = "
+import std.io.stdio:{stdin, stdout};
+
+Fiber.spawn(|| {
+  for i in 3.times() {
+    stdout.writeln('tick ' + i.str());
+    Fiber.yield();
+  }
+});
+
+let name = stdin.readLine();
+let greeting = stdin.readLine();
+stdout.writeln(greeting + ' ' + name);
+";
+
+fn stdout(container: &StdioTestContainer) -> String {
+  str::from_utf8(&container.stdout)
+    .expect("Stdout was not utf8")
+    .to_string()
+}
+
+#[test]
+fn resumes_with_host_input() {
+  let container = Arc::new(StdioTestContainer::default());
+  let lines = Arc::new(Mutex::new(VecDeque::new()));
+  let io = Io::default().with_stdio(Arc::new(IoStdioAsync {
+    container: Arc::clone(&container),
+    lines: Arc::clone(&lines),
+  }));
+
+  let mut vm = Vm::builder()
+    .io(io)
+    .gc_stress(true)
+    .build()
+    .expect("Unable to create vm");
+
+  let result = vm.run(PathBuf::from("/app/main.lay"), SCRIPT);
+  assert_eq!(result, Err(VmError::AwaitingInput));
+  assert_eq!(stdout(&container), "tick 0\ntick 1\ntick 2\n");
+
+  // resuming without input waits again
+  assert_eq!(vm.resume(), Err(VmError::AwaitingInput));
+
+  lines.lock().unwrap().push_back("laythe".to_string());
+  assert_eq!(vm.resume(), Err(VmError::AwaitingInput));
+
+  lines.lock().unwrap().push_back("hello".to_string());
+  assert_eq!(vm.resume(), Ok(0));
+  assert_eq!(stdout(&container), "tick 0\ntick 1\ntick 2\nhello laythe\n");
+
+  assert!(matches!(vm.resume(), Err(VmError::Internal(_))));
+}
+
+#[test]
+fn prompts_once_while_waiting() {
+  let container = Arc::new(StdioTestContainer::default());
+  let lines = Arc::new(Mutex::new(VecDeque::new()));
+  let io = Io::default().with_stdio(Arc::new(IoStdioAsync {
+    container: Arc::clone(&container),
+    lines: Arc::clone(&lines),
+  }));
+
+  let mut vm = Vm::builder().io(io).build().expect("Unable to create vm");
+
+  let source = "
+import std.io.stdio:{stdin, stdout};
+
+let age = stdin.readNumber('age? ');
+stdout.writeln((age + 1).str());
+";
+
+  let result = vm.run(PathBuf::from("/app/main.lay"), source);
+  assert_eq!(result, Err(VmError::AwaitingInput));
+  assert_eq!(vm.resume(), Err(VmError::AwaitingInput));
+  assert_eq!(stdout(&container), "age? ");
+
+  lines.lock().unwrap().push_back("41".to_string());
+  assert_eq!(vm.resume(), Ok(0));
+  assert_eq!(stdout(&container), "age? 42\n");
+}
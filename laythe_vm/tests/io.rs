@@ -1,16 +1,15 @@
-use laythe_vm::vm::ExecuteResult;
-use support::{assert_file_exit_and_stdio, assert_files_exit};
+use support::{assert_file_exit_and_stdio, assert_files_exit, Outcome};
 
 mod support;
 
-fn test_files(paths: &[&str], result: ExecuteResult) -> Result<(), std::io::Error> {
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
   assert_files_exit(paths, FILE_PATH, result)
 }
 
 fn test_file_with_stdout(
   path: &str,
   stdout: Vec<&str>,
-  result: ExecuteResult,
+  result: Outcome,
 ) -> Result<(), std::io::Error> {
   assert_file_exit_and_stdio(path, FILE_PATH, None, None, Some(stdout), None, result)
 }
@@ -18,7 +17,7 @@ fn test_file_with_stdout(
 fn test_file_with_stderr(
   path: &str,
   stderr: Vec<&str>,
-  result: ExecuteResult,
+  result: Outcome,
 ) -> Result<(), std::io::Error> {
   assert_file_exit_and_stdio(path, FILE_PATH, None, None, None, Some(stderr), result)
 }
@@ -26,7 +25,7 @@ fn test_file_with_stderr(
 fn test_file_with_stdin(
   path: &str,
   stdin: String,
-  result: ExecuteResult,
+  result: Outcome,
 ) -> Result<(), std::io::Error> {
   assert_file_exit_and_stdio(path, FILE_PATH, Some(stdin), None, None, None, result)
 }
@@ -34,7 +33,7 @@ fn test_file_with_stdin(
 fn test_file_with_stdin_lines(
   path: &str,
   lines: Vec<String>,
-  result: ExecuteResult,
+  result: Outcome,
 ) -> Result<(), std::io::Error> {
   assert_file_exit_and_stdio(path, FILE_PATH, None, Some(lines), None, None, result)
 }
@@ -46,41 +45,129 @@ fn stdio() -> Result<(), std::io::Error> {
   test_file_with_stdout(
     "std_lib/io/stdio/stdout/write.lay",
     vec!["expected 1 expected 2"],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_with_stdout(
     "std_lib/io/stdio/stdout/writeln.lay",
     vec!["expected 1", "expected 2"],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_with_stderr(
     "std_lib/io/stdio/stderr/write.lay",
     vec!["expected 1 expected 2"],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_with_stderr(
     "std_lib/io/stdio/stderr/writeln.lay",
     vec!["expected 1", "expected 2"],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdout(
+    "std_lib/io/stdio/stdout/write_lines.lay",
+    vec!["line 1", "line 2", "no newline"],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stderr(
+    "std_lib/io/stdio/stderr/write_lines.lay",
+    vec!["line 1", "line 2"],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdout(
+    "std_lib/io/stdio/stdout/capture.lay",
+    vec!["after"],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdout(
+    "std_lib/io/stdio/stderr/capture.lay",
+    vec!["visible"],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &["std_lib/io/stdio/stdout/write_lines_not_string.lay"],
+    Outcome::RuntimeError,
   )?;
 
   test_file_with_stdin(
     "std_lib/io/stdio/stdin/read.lay",
     "expected".to_string(),
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_with_stdin_lines(
     "std_lib/io/stdio/stdin/readline.lay",
     vec!["expected 1".to_string(), "expected 2".to_string()],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
+  )?;
+
+  assert_file_exit_and_stdio(
+    "std_lib/io/stdio/stdin/read_line_prompt.lay",
+    FILE_PATH,
+    None,
+    Some(vec!["ada".to_string()]),
+    Some(vec!["name? ", "hello ada"]),
+    None,
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdin_lines(
+    "std_lib/io/stdio/stdin/read_number.lay",
+    vec!["42".to_string(), " 1.5".to_string(), "many".to_string()],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdin(
+    "std_lib/io/stdio/stdin/read_char.lay",
+    "aé\nrest".to_string(),
+    Outcome::Ok(0),
   )
 }
 
 #[test]
 fn fs() -> Result<(), std::io::Error> {
-  test_files(&["std_lib/io/fs/file/readAllText.lay"], ExecuteResult::Ok(0))
+  let scratch = std::env::temp_dir().join(format!("laythe_file_{}.txt", std::process::id()));
+  std::env::set_var("LAYTHE_FILE_SCRATCH", &scratch);
+
+  test_files(
+    &[
+      "std_lib/io/fs/file/readAllText.lay",
+      "std_lib/io/fs/file/stream.lay",
+      "std_lib/io/fs/file/lines.lay",
+      "std_lib/io/fs/file/write.lay",
+      "std_lib/io/fs/glob/glob.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  std::fs::remove_file(scratch)?;
+
+  test_files(
+    &[
+      "std_lib/io/fs/file/closed.lay",
+      "std_lib/io/fs/file/bad_mode.lay",
+      "std_lib/io/fs/file/missing.lay",
+      "std_lib/io/fs/glob/bad_pattern.lay",
+    ],
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn path() -> Result<(), std::io::Error> {
+  test_files(
+    &["std_lib/io/path/parts.lay", "std_lib/io/path/normalize.lay"],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &["std_lib/io/path/relative_unreachable.lay"],
+    Outcome::RuntimeError,
+  )
 }
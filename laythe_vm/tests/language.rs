@@ -1,9 +1,13 @@
-use laythe_vm::vm::{default_native_vm, ExecuteResult};
-use support::{assert_file_exit_and_stdio, assert_files_exit};
+use laythe_vm::vm::{default_native_vm, LintLevel, Vm, VmConfig};
+use std::time::Duration;
+use support::{
+  assert_file_exit_and_stdio, assert_file_interrupted, assert_files_exit,
+  assert_files_exit_with_builder, assert_files_exit_with_config, Outcome,
+};
 
 mod support;
 
-fn test_file_exits(paths: &[&str], result: ExecuteResult) -> Result<(), std::io::Error> {
+fn test_file_exits(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
   assert_files_exit(paths, FILE_PATH, result)
 }
 
@@ -11,7 +15,7 @@ fn test_file_with_stdio(
   path: &str,
   stdout: Option<Vec<&str>>,
   stderr: Option<Vec<&str>>,
-  result: ExecuteResult,
+  result: Outcome,
 ) -> Result<(), std::io::Error> {
   assert_file_exit_and_stdio(path, FILE_PATH, None, None, stdout, stderr, result)
 }
@@ -20,7 +24,7 @@ const FILE_PATH: &str = file!();
 
 #[test]
 fn build() {
-  default_native_vm();
+  default_native_vm().expect("Unable to create vm");
   assert!(true);
 }
 
@@ -33,7 +37,7 @@ fn assignment() -> Result<(), std::io::Error> {
       "language/assignment/local.lay",
       "language/assignment/syntax.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -43,12 +47,94 @@ fn assignment() -> Result<(), std::io::Error> {
       "language/assignment/prefix_operator.lay",
       "language/assignment/to_this.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
     &vec!["language/assignment/undefined.lay"],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn async_await() -> Result<(), std::io::Error> {
+  test_file_exits(
+    &vec![
+      "language/async/basic.lay",
+      "language/async/catch.lay",
+      "language/async/closure.lay",
+      "language/async/lambda.lay",
+      "language/async/method.lay",
+      "language/async/nested.lay",
+      "language/async/non_fiber.lay",
+      "language/async/scheduling.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_exits(
+    &vec![
+      "language/async/async_init.lay",
+      "language/async/async_no_fn.lay",
+    ],
+    Outcome::CompileError,
+  )?;
+
+  test_file_exits(
+    &vec!["language/async/self_await.lay"],
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "language/async/completes_after_main.lay",
+    Some(vec!["main", "worker"]),
+    None,
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdio(
+    "language/async/unawaited_thrown.lay",
+    None,
+    Some(vec![
+      "error: IndexError: Index out of bounds. list was length 0 but attempted to index with 1.",
+      "  --> unawaited_thrown.lay:2:3",
+      "  |",
+      "2 |   [][1];",
+      "  |   ^^^^^",
+      "",
+      "  [line 2] in thrower()",
+    ]),
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdio(
+    "language/async/thrown.lay",
+    None,
+    Some(vec![
+      "error: IndexError: Index out of bounds. list was length 0 but attempted to index with 1.",
+      "  --> thrown.lay:5:7",
+      "  |",
+      "5 | await thrower();",
+      "  |       ^^^^^^^^^",
+      "",
+      "  [line 5] in script",
+    ]),
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "language/async/deadlock.lay",
+    None,
+    Some(vec![
+      "error: RuntimeError: Deadlock, all fibers are blocked.",
+      "   --> deadlock.lay:14:8",
+      "   |",
+      "14 | await a;",
+      "   |        ^",
+      "",
+      "  [line 14] in script",
+    ]),
+    Outcome::RuntimeError,
   )
 }
 
@@ -62,7 +148,7 @@ fn binary_assignment() -> Result<(), std::io::Error> {
       "language/binary_assignment/global.lay",
       "language/binary_assignment/operators.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -72,12 +158,12 @@ fn binary_assignment() -> Result<(), std::io::Error> {
       "language/assignment/prefix_operator.lay",
       "language/assignment/to_this.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
     &vec!["language/assignment/undefined.lay"],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -85,7 +171,7 @@ fn binary_assignment() -> Result<(), std::io::Error> {
 fn block() -> Result<(), std::io::Error> {
   test_file_exits(
     &vec!["language/block/empty.lay", "language/block/empty.lay"],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )
 }
 
@@ -93,7 +179,50 @@ fn block() -> Result<(), std::io::Error> {
 fn bool() -> Result<(), std::io::Error> {
   test_file_exits(
     &vec!["language/bool/equality.lay", "language/bool/not.lay"],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
+  )
+}
+
+#[test]
+fn budget() -> Result<(), std::io::Error> {
+  let instruction_budget = VmConfig {
+    instruction_budget: Some(100_000),
+    wall_clock_budget: None,
+    max_heap_bytes: None,
+  };
+
+  assert_files_exit_with_config(
+    &["language/budget/completes.lay"],
+    FILE_PATH,
+    instruction_budget,
+    Outcome::Ok(0),
+  )?;
+
+  assert_files_exit_with_config(
+    &[
+      "language/budget/infinite_fibers.lay",
+      "language/budget/infinite_loop.lay",
+      "language/budget/infinite_loop_native.lay",
+    ],
+    FILE_PATH,
+    instruction_budget,
+    Outcome::Timeout,
+  )?;
+
+  let wall_clock_budget = VmConfig {
+    instruction_budget: None,
+    wall_clock_budget: Some(Duration::from_millis(50)),
+    max_heap_bytes: None,
+  };
+
+  assert_files_exit_with_config(
+    &[
+      "language/budget/infinite_loop.lay",
+      "language/budget/sleep_forever.lay",
+    ],
+    FILE_PATH,
+    wall_clock_budget,
+    Outcome::Timeout,
   )
 }
 
@@ -107,12 +236,12 @@ fn break_() -> Result<(), std::io::Error> {
       "language/break/nested_while_loops.lay",
       "language/break/nested_for_loops.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
     &vec!["language/break/outside_loop.lay"],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )
 }
 
@@ -126,7 +255,7 @@ fn call() -> Result<(), std::io::Error> {
       "language/call/object.lay",
       "language/call/string.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -135,23 +264,26 @@ fn class() -> Result<(), std::io::Error> {
   test_file_exits(
     &vec![
       "language/class/empty.lay",
+      "language/class/expression.lay",
+      "language/class/expression_temporaries.lay",
       "language/class/inherited_method.lay",
       "language/class/local_inherit_other.lay",
       "language/class/local_reference_self.lay",
       "language/class/reference_self.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
     &vec![
+      "language/class/expression_missing_body.lay",
       "language/class/inherit_self.lay",
       "language/class/local_inherit_self.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -172,12 +304,12 @@ fn closure() -> Result<(), std::io::Error> {
       "language/closure/unused_closure.lay",
       "language/closure/unused_later_closure.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(&vec![], Outcome::CompileError)?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -190,12 +322,12 @@ fn continue_() -> Result<(), std::io::Error> {
       "language/continue/nested_while_loops.lay",
       "language/continue/nested_for_loops.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
     &vec!["language/continue/outside_loop.lay"],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )
 }
 
@@ -208,7 +340,32 @@ fn comments() -> Result<(), std::io::Error> {
       "language/comments/only_line_comment.lay",
       "language/comments/unicode.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
+  )
+}
+
+#[test]
+fn const_() -> Result<(), std::io::Error> {
+  test_file_exits(
+    &vec![
+      "language/const/export.lay",
+      "language/const/inline.lay",
+      "language/const/shadow.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_exits(
+    &vec![
+      "language/const/assign.lay",
+      "language/const/assign_binary.lay",
+      "language/const/assign_in_function.lay",
+      "language/const/local_scope.lay",
+      "language/const/missing_value.lay",
+      "language/const/not_literal.lay",
+      "language/const/redeclare.lay",
+    ],
+    Outcome::CompileError,
   )
 }
 
@@ -223,12 +380,12 @@ fn constructor() -> Result<(), std::io::Error> {
       "language/constructor/early_return.lay",
       "language/constructor/return_in_nested_function.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
     &vec!["language/constructor/return_value.lay"],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
@@ -237,7 +394,7 @@ fn constructor() -> Result<(), std::io::Error> {
       "language/constructor/extra_arguments.lay",
       "language/constructor/missing_arguments.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -249,8 +406,10 @@ fn exception() -> Result<(), std::io::Error> {
       "language/exception/one_deep_catch.lay",
       "language/exception/two_deep_catch.lay",
       "language/exception/top_level_catch_thrown.lay",
+      "language/exception/catch_binding.lay",
+      "language/exception/stacktrace.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -259,46 +418,76 @@ fn exception() -> Result<(), std::io::Error> {
       "language/exception/try_no_block.lay",
       "language/exception/try_no_catch.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_with_stdio(
     "language/exception/top_level_thrown.lay",
     None,
     Some(vec![
-      "IndexError: Index out of bounds. list was length 0 but attempted to index with 1.",
+      "error: IndexError: Index out of bounds. list was length 0 but attempted to index with 1.",
+      "  --> top_level_thrown.lay:1:1",
+      "  |",
+      "1 | [][1];",
+      "  | ^^^^^",
+      "",
       "  [line 1] in script",
     ]),
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )?;
 
   test_file_with_stdio(
     "language/exception/one_deep_thrown.lay",
     None,
     Some(vec![
-      "IndexError: Index out of bounds. list was length 0 but attempted to index with 1.",
+      "error: IndexError: Index out of bounds. list was length 0 but attempted to index with 1.",
+      "  --> one_deep_thrown.lay:2:3",
+      "  |",
+      "2 |   [][1];",
+      "  |   ^^^^^",
+      "",
       "  [line 2] in thrower()",
       "  [line 5] in script",
     ]),
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )?;
 
   test_file_with_stdio(
     "language/exception/two_deep_thrown.lay",
     None,
     Some(vec![
-      "IndexError: Index out of bounds. list was length 0 but attempted to index with 1.",
+      "error: IndexError: Index out of bounds. list was length 0 but attempted to index with 1.",
+      "  --> two_deep_thrown.lay:6:3",
+      "  |",
+      "6 |   [][1];",
+      "  |   ^^^^^",
+      "",
       "  [line 6] in thrower()",
       "  [line 2] in outer()",
       "  [line 9] in script",
     ]),
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "language/exception/operand_thrown.lay",
+    None,
+    Some(vec![
+      "error: RuntimeError: Operands must be two numbers or two strings.",
+      "  --> operand_thrown.lay:2:9",
+      "  |",
+      "2 | let b = a + nil;",
+      "  |         ^^^^^^^",
+      "",
+      "  [line 2] in script",
+    ]),
+    Outcome::RuntimeError,
   )
 }
 
 #[test]
 fn export() -> Result<(), std::io::Error> {
-  test_file_exits(&vec![], ExecuteResult::Ok(0))?;
+  test_file_exits(&vec!["language/export/reexport/main.lay"], Outcome::Ok(0))?;
 
   test_file_exits(
     &vec![
@@ -307,23 +496,48 @@ fn export() -> Result<(), std::io::Error> {
       "language/export/non_declaration_class.lay",
       "language/export/non_declaration_fun.lay",
       "language/export/non_declaration_let.lay",
+      "language/export/reexport_local.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(
+    &vec!["language/export/reexport_missing.lay"],
+    Outcome::RuntimeError,
+  )
 }
 
 #[test]
 fn expressions() -> Result<(), std::io::Error> {
+  test_file_exits(&vec!["language/expressions/evaluate.lay"], Outcome::Ok(0))?;
+
+  test_file_exits(&vec![], Outcome::CompileError)?;
+
+  test_file_exits(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn extension() -> Result<(), std::io::Error> {
   test_file_exits(
-    &vec!["language/expressions/evaluate.lay"],
-    ExecuteResult::Ok(0),
+    &[
+      "language/extension/builtin.lay",
+      "language/extension/class.lay",
+      "language/extension/local.lay",
+      "language/extension/super_class.lay",
+    ],
+    Outcome::Ok(0),
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(
+    &[
+      "language/extension/init.lay",
+      "language/extension/member.lay",
+      "language/extension/super.lay",
+    ],
+    Outcome::CompileError,
+  )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&["language/extension/not_class.lay"], Outcome::RuntimeError)
 }
 
 #[test]
@@ -331,15 +545,17 @@ fn field() -> Result<(), std::io::Error> {
   test_file_exits(
     &vec![
       "language/field/call_function_field.lay",
+      "language/field/dynamic.lay",
       "language/field/many.lay",
       "language/field/method_binds_self.lay",
       "language/field/method.lay",
       "language/field/on_instance.lay",
+      "language/field/set_undefined.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(&vec![], Outcome::CompileError)?;
 
   test_file_exits(
     &vec![
@@ -351,7 +567,6 @@ fn field() -> Result<(), std::io::Error> {
       "language/field/get_on_num.lay",
       "language/field/get_on_string.lay",
       "language/field/set_evaluation_order.lay",
-      "language/field/set_undefined.lay",
       "language/field/set_on_bool.lay",
       "language/field/set_on_class.lay",
       "language/field/set_on_function.lay",
@@ -360,7 +575,7 @@ fn field() -> Result<(), std::io::Error> {
       "language/field/set_on_string.lay",
       "language/field/get_undefined.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -372,7 +587,7 @@ fn for_loop() -> Result<(), std::io::Error> {
       "language/for/scope.lay",
       "language/for/closure_in_body.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -383,10 +598,10 @@ fn for_loop() -> Result<(), std::io::Error> {
       "language/for/let_in_body.lay",
       "language/for/statement_iterator.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -400,7 +615,7 @@ fn function() -> Result<(), std::io::Error> {
       "language/function/print.lay",
       "language/function/recursion.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -410,7 +625,7 @@ fn function() -> Result<(), std::io::Error> {
       "language/function/too_many_arguments.lay",
       "language/function/too_many_parameters.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
@@ -419,7 +634,24 @@ fn function() -> Result<(), std::io::Error> {
       "language/function/local_mutual_recursion.lay",
       "language/function/missing_arguments.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn gc_stress() -> Result<(), std::io::Error> {
+  assert_files_exit_with_builder(
+    &[
+      "language/class/inherited_method.lay",
+      "language/closure/nested_closure.lay",
+      "language/closure/reuse_closure_slot.lay",
+      "language/exception/two_deep_catch.lay",
+      "language/import/relative/main.lay",
+      "language/list/mixed.lay",
+    ],
+    FILE_PATH,
+    Vm::builder().gc_stress(true),
+    Outcome::Ok(0),
   )
 }
 
@@ -432,10 +664,10 @@ fn hooks() -> Result<(), std::io::Error> {
       "language/hooks/call_ly_closure.lay",
       "language/hooks/call_ly_instance.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(&vec![], Outcome::CompileError)?;
 
   test_file_exits(
     &vec![
@@ -444,7 +676,7 @@ fn hooks() -> Result<(), std::io::Error> {
       "language/hooks/pass_error_ly_closure.lay",
       "language/hooks/pass_error_ly_instance.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -457,7 +689,7 @@ fn if_stmt() -> Result<(), std::io::Error> {
       "language/if/if.lay",
       "language/if/truth.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -469,20 +701,22 @@ fn if_stmt() -> Result<(), std::io::Error> {
       "language/if/let_in_then.lay",
       "language/if/let_in_then.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
 fn implicit_return() -> Result<(), std::io::Error> {
   test_file_exits(
     &vec![
+      "language/implicit_return/expression_body.lay",
+      "language/implicit_return/in_assign_block.lay",
       "language/implicit_return/in_function.lay",
       "language/implicit_return/in_method.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -491,41 +725,79 @@ fn implicit_return() -> Result<(), std::io::Error> {
       "language/implicit_return/after_if.lay",
       "language/implicit_return/after_while.lay",
       "language/implicit_return/at_top_level.lay",
+      "language/implicit_return/expression_body_in_init.lay",
+      "language/implicit_return/expression_body_missing_semicolon.lay",
+      "language/implicit_return/in_assign_block_middle.lay",
       "language/implicit_return/in_function_middle.lay",
       "language/implicit_return/in_method_middle.lay",
       "language/implicit_return/in_init.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
 fn import() -> Result<(), std::io::Error> {
-  test_file_exits(&vec![], ExecuteResult::Ok(0))?;
+  test_file_exits(&vec!["language/import/relative/main.lay"], Outcome::Ok(0))?;
 
   test_file_exits(
     &vec![
       "language/import/missing_path.lay",
       "language/import/missing_semicolon.lay",
       "language/import/non_identifier_path.lay",
+      "language/import/relative_malformed.lay",
       "language/import/rename_missing.lay",
       "language/import/rename_not_identifer.lay",
       "language/import/symbols_rename_missing.lay",
       "language/import/symbols_rename_not_identifer.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
     &vec![
       "language/import/module_not_real.lay",
+      "language/import/relative_missing.lay",
+      "language/import/relative_symbol_missing.lay",
       // "language/import/rename_redefine.lay",
       "language/import/symbols_not_real.lay",
       // "language/import/symbols_redefine.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "language/import/cycle/main.lay",
+    None,
+    Some(vec![
+      "error: ImportError: Circular import b.lay -> c.lay -> b.lay.",
+      "  --> c.lay:1:8",
+      "  |",
+      "1 | import ./b;",
+      "  |        ^",
+      "",
+      "  [line 1] in script",
+      "  [line 1] in script",
+      "  [line 1] in script",
+    ]),
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_with_stdio(
+    "language/import/cycle/self_import.lay",
+    None,
+    Some(vec![
+      "error: ImportError: Circular import self_import.lay -> self_import.lay.",
+      "  --> self_import.lay:1:8",
+      "  |",
+      "1 | import ./self_import;",
+      "  |        ^",
+      "",
+      "  [line 1] in script",
+    ]),
+    Outcome::RuntimeError,
   )
 }
 
@@ -544,17 +816,17 @@ fn indexing() -> Result<(), std::io::Error> {
       "language/indexing/map_nested_set.lay",
       "language/indexing/map_pass_through_set.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(&vec![], Outcome::CompileError)?;
 
   test_file_exits(
     &vec![
       "language/indexing/list_out_of_range.lay",
       "language/indexing/map_key_not_found.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -564,14 +836,15 @@ fn inheritance() -> Result<(), std::io::Error> {
     &vec![
       "language/inheritance/constructor.lay",
       "language/inheritance/inherit_methods.lay",
+      "language/inheritance/redeclare_field.lay",
       "language/inheritance/set_fields_from_base_class.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
     &vec!["language/inheritance/parenthesized_superclass.lay"],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
@@ -580,7 +853,7 @@ fn inheritance() -> Result<(), std::io::Error> {
       "language/inheritance/inherit_from_nil.lay",
       "language/inheritance/inherit_from_number.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -588,17 +861,71 @@ fn inheritance() -> Result<(), std::io::Error> {
 fn inline_cache() -> Result<(), std::io::Error> {
   test_file_exits(
     &vec![
+      "language/inline_cache/extension_replace.lay",
       "language/inline_cache/invoke_thrash.lay",
       "language/inline_cache/property_get_thrash.lay",
       "language/inline_cache/property_set_thrash.lay",
       "language/inline_cache/super_invoke_thrash.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
+  )?;
+
+  test_file_exits(&vec![], Outcome::CompileError)?;
+
+  test_file_exits(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn interrupt() -> Result<(), std::io::Error> {
+  let delay = Duration::from_millis(20);
+
+  assert_file_interrupted(
+    "language/interrupt/loop.lay",
+    FILE_PATH,
+    delay,
+    vec![
+      "error: RuntimeError: Execution interrupted.",
+      "  --> loop.lay:1:11",
+      "  |",
+      "1 | while true {}",
+      "  |           ^",
+      "",
+      "  [line 1] in script",
+    ],
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  assert_file_interrupted(
+    "language/interrupt/native_loop.lay",
+    FILE_PATH,
+    delay,
+    vec![
+      "error: RuntimeError: Execution interrupted.",
+      "  --> native_loop.lay:2:13",
+      "  |",
+      "2 |   while true {}",
+      "  |             ^",
+      "",
+      "  [line 2] in spin()",
+      "  [line 5] in lambda()",
+      "  [line 0] in native()",
+      "  [line 5] in script",
+    ],
+  )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  assert_file_interrupted(
+    "language/interrupt/try_loop.lay",
+    FILE_PATH,
+    delay,
+    vec![
+      "error: RuntimeError: Execution interrupted.",
+      "  --> try_loop.lay:2:13",
+      "  |",
+      "2 |   while true {}",
+      "  |             ^",
+      "",
+      "  [line 2] in script",
+    ],
+  )
 }
 
 #[test]
@@ -608,20 +935,17 @@ fn iterator() -> Result<(), std::io::Error> {
       "language/iterator/equality.lay",
       "language/iterator/assign_iter_keep_state.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(&vec![], Outcome::CompileError)?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
 fn limit() -> Result<(), std::io::Error> {
-  test_file_exits(
-    &vec!["language/limit/reuse_constants.lay"],
-    ExecuteResult::Ok(0),
-  )?;
+  test_file_exits(&vec!["language/limit/reuse_constants.lay"], Outcome::Ok(0))?;
 
   test_file_exits(
     &vec![
@@ -630,12 +954,108 @@ fn limit() -> Result<(), std::io::Error> {
       "language/limit/too_many_locals.lay",
       "language/limit/too_many_upvalues.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
     &vec!["language/limit/stack_overflow.lay"],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
+  )?;
+
+  test_file_exits(
+    &vec![
+      "language/limit/stack_growth.lay",
+      "language/limit/stack_overflow_caught.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_exits(
+    &vec!["language/limit/deep_recursion.lay"],
+    Outcome::RuntimeError,
+  )?;
+
+  assert_files_exit_with_builder(
+    &["language/limit/deep_recursion.lay"],
+    FILE_PATH,
+    Vm::builder().frame_limit(1024).stack_size(16),
+    Outcome::Ok(0),
+  )?;
+
+  assert_files_exit_with_builder(
+    &["language/limit/stack_overflow_caught.lay"],
+    FILE_PATH,
+    Vm::builder().frame_limit(8),
+    Outcome::Ok(0),
+  )
+}
+
+#[test]
+fn lint() -> Result<(), std::io::Error> {
+  test_file_with_stdio(
+    "language/lint/clean.lay",
+    None,
+    Some(vec![""]),
+    Outcome::Ok(0),
+  )?;
+
+  test_file_with_stdio(
+    "language/lint/warnings.lay",
+    Some(vec!["1", "2"]),
+    Some(vec![
+      "warning: Unused import 'math'.",
+      "  --> warnings.lay:1:12",
+      "  |",
+      "1 | import std.math;",
+      "  |            ^^^^",
+      "",
+      "warning: Unused variable 'unused'.",
+      "  --> warnings.lay:4:7",
+      "  |",
+      "4 |   let unused = 1;",
+      "  |       ^^^^^^",
+      "  |",
+      "  = If this is intentional prefix it with an underscore: '_unused'",
+      "",
+      "warning: Variable 'a' shadows a variable in an outer scope.",
+      "  --> warnings.lay:5:7",
+      "  |",
+      "3 | fn example(a) {",
+      "  |            - a was originally declared here",
+      "4 |   let unused = 1;",
+      "5 |   for a in [1, 2] {",
+      "  |       ^",
+      "",
+      "warning: Unreachable code.",
+      "   --> warnings.lay:10:3",
+      "   |",
+      " 9 |   return a;",
+      "   |   -------- any code following this statement is unreachable",
+      "10 |   print(a);",
+      "   |   ^^^^^^^^",
+    ]),
+    Outcome::Ok(0),
+  )?;
+
+  assert_files_exit_with_builder(
+    &["language/lint/clean.lay"],
+    FILE_PATH,
+    Vm::builder().lint_level(LintLevel::Deny),
+    Outcome::Ok(0),
+  )?;
+
+  assert_files_exit_with_builder(
+    &["language/lint/warnings.lay"],
+    FILE_PATH,
+    Vm::builder().lint_level(LintLevel::Deny),
+    Outcome::CompileError,
+  )?;
+
+  assert_files_exit_with_builder(
+    &["language/lint/warnings.lay"],
+    FILE_PATH,
+    Vm::builder().lint_level(LintLevel::Allow),
+    Outcome::Ok(0),
   )
 }
 
@@ -650,7 +1070,7 @@ fn lambda() -> Result<(), std::io::Error> {
       "language/lambda/parameters.lay",
       "language/lambda/str.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -661,7 +1081,7 @@ fn lambda() -> Result<(), std::io::Error> {
       "language/lambda/too_many_parameters.lay",
       "language/lambda/too_many_arguments.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
@@ -670,7 +1090,7 @@ fn lambda() -> Result<(), std::io::Error> {
       "language/lambda/missing_arguments.lay",
       "language/lambda/local_mutual_recursion.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -682,7 +1102,7 @@ fn list() -> Result<(), std::io::Error> {
       "language/list/homogeneous.lay",
       "language/list/mixed.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -690,10 +1110,10 @@ fn list() -> Result<(), std::io::Error> {
       "language/list/missing_comma_in_initializer.lay",
       "language/list/missing_closing_bracket.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -705,12 +1125,12 @@ fn logical_operator() -> Result<(), std::io::Error> {
       "language/logical_operator/or_truth.lay",
       "language/logical_operator/or.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(&vec![], Outcome::CompileError)?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -721,7 +1141,7 @@ fn map() -> Result<(), std::io::Error> {
       "language/map/homogeneous.lay",
       "language/map/mixed.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -731,10 +1151,10 @@ fn map() -> Result<(), std::io::Error> {
       "language/map/statement_key.lay",
       "language/map/statement_value.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -743,9 +1163,11 @@ fn method() -> Result<(), std::io::Error> {
     &vec![
       "language/method/arity.lay",
       "language/method/empty_block.lay",
+      "language/method/on_missing_method.lay",
+      "language/method/on_missing_method_args.lay",
       "language/method/print_bound_method.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -753,7 +1175,7 @@ fn method() -> Result<(), std::io::Error> {
       "language/method/too_many_arguments.lay",
       "language/method/too_many_parameters.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
@@ -763,7 +1185,7 @@ fn method() -> Result<(), std::io::Error> {
       "language/method/not_found.lay",
       "language/method/refer_to_name.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -778,26 +1200,26 @@ fn native() -> Result<(), std::io::Error> {
       "language/native/signature_fixed_arity.lay",
       "language/native/signature_type.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(&vec![], Outcome::CompileError)?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
 fn nil() -> Result<(), std::io::Error> {
-  test_file_exits(&vec!["language/nil/literal.lay"], ExecuteResult::Ok(0))?;
+  test_file_exits(&vec!["language/nil/literal.lay"], Outcome::Ok(0))?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(&vec![], Outcome::CompileError)?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
 fn number() -> Result<(), std::io::Error> {
-  test_file_exits(&vec!["language/number/literals.lay"], ExecuteResult::Ok(0))?;
+  test_file_exits(&vec!["language/number/literals.lay"], Outcome::Ok(0))?;
 
   test_file_exits(
     &vec![
@@ -805,10 +1227,10 @@ fn number() -> Result<(), std::io::Error> {
       "language/number/leading_dot.lay",
       "language/number/trailing_dot.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
 
 #[test]
@@ -817,10 +1239,13 @@ fn operator() -> Result<(), std::io::Error> {
     &vec![
       "language/operator/add.lay",
       "language/operator/comparison.lay",
+      "language/operator/constant_fold.lay",
       "language/operator/divide.lay",
       "language/operator/equals_class.lay",
       "language/operator/equals_method.lay",
       "language/operator/equals.lay",
+      "language/operator/equals_string.lay",
+      "language/operator/fused.lay",
       "language/operator/multiply.lay",
       "language/operator/negate.lay",
       "language/operator/not_class.lay",
@@ -828,10 +1253,10 @@ fn operator() -> Result<(), std::io::Error> {
       "language/operator/not.lay",
       "language/operator/subtract.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::CompileError)?;
+  test_file_exits(&vec![], Outcome::CompileError)?;
 
   test_file_exits(
     &vec![
@@ -857,7 +1282,62 @@ fn operator() -> Result<(), std::io::Error> {
       "language/operator/subtract_nonnum_num.lay",
       "language/operator/subtract_num_nonnum.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn out_of_memory() -> Result<(), std::io::Error> {
+  let max_heap = VmConfig {
+    instruction_budget: None,
+    wall_clock_budget: None,
+    max_heap_bytes: Some(4 * 1024 * 1024),
+  };
+
+  assert_files_exit_with_config(
+    &[
+      "language/out_of_memory/caught.lay",
+      "language/out_of_memory/within_limit.lay",
+    ],
+    FILE_PATH,
+    max_heap,
+    Outcome::Ok(0),
+  )?;
+
+  assert_files_exit_with_config(
+    &["language/out_of_memory/uncaught.lay"],
+    FILE_PATH,
+    max_heap,
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn raise() -> Result<(), std::io::Error> {
+  test_file_exits(
+    &[
+      "language/raise/from.lay",
+      "language/raise/raise.lay",
+      "language/raise/rethrow.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_exits(
+    &[
+      "language/raise/missing_semicolon.lay",
+      "language/raise/missing_value.lay",
+    ],
+    Outcome::CompileError,
+  )?;
+
+  test_file_exits(
+    &[
+      "language/raise/cause_not_error.lay",
+      "language/raise/not_error.lay",
+      "language/raise/uncaught_from.lay",
+    ],
+    Outcome::RuntimeError,
   )
 }
 
@@ -869,7 +1349,7 @@ fn regression() -> Result<(), std::io::Error> {
       "language/regression/394.lay",
       "language/regression/continue.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )
 }
 
@@ -884,15 +1364,39 @@ fn return_test() -> Result<(), std::io::Error> {
       "language/return/return_nil_if_no_value.lay",
       "language/return/return_nil_if_no_value.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
     &vec!["language/return/at_top_level.lay"],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn sealed() -> Result<(), std::io::Error> {
+  test_file_exits(
+    &[
+      "language/sealed/class.lay",
+      "language/sealed/export.lay",
+      "language/sealed/rebound.lay",
+      "language/sealed/shadow.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_exits(&["language/sealed/not_class.lay"], Outcome::CompileError)?;
+
+  test_file_exits(
+    &[
+      "language/sealed/extend.lay",
+      "language/sealed/field.lay",
+      "language/sealed/inherit.lay",
+    ],
+    Outcome::RuntimeError,
+  )
 }
 
 #[test]
@@ -904,7 +1408,7 @@ fn static_method() -> Result<(), std::io::Error> {
       "language/static_method/empty_block.lay",
       "language/static_method/print_bound_method.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -914,7 +1418,7 @@ fn static_method() -> Result<(), std::io::Error> {
       "language/static_method/too_many_arguments.lay",
       "language/static_method/too_many_parameters.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
@@ -924,7 +1428,7 @@ fn static_method() -> Result<(), std::io::Error> {
       "language/static_method/not_found.lay",
       "language/static_method/refer_to_name.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -938,7 +1442,7 @@ fn string() -> Result<(), std::io::Error> {
       "language/string/escape.lay",
       "language/string/unicode_escape.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -953,12 +1457,12 @@ fn string() -> Result<(), std::io::Error> {
       "language/string/unterminated_double.lay",
       "language/string/unterminated_single.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
     &vec!["language/string/error_after_multiline.lay"],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -971,12 +1475,14 @@ fn super_() -> Result<(), std::io::Error> {
       "language/super/call_same_method.lay",
       "language/super/closure.lay",
       "language/super/constructor.lay",
+      "language/super/field.lay",
       "language/super/indirectly_inherited.lay",
+      "language/super/method_as_value.lay",
       "language/super/reassign_superclass.lay",
       "language/super/super_in_closure_in_inherited_method.lay",
       "language/super/super_in_inherited_method.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -987,19 +1493,135 @@ fn super_() -> Result<(), std::io::Error> {
       "language/super/super_without_dot.lay",
       "language/super/super_without_name.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
     &vec![
       "language/super/extra_arguments.lay",
       "language/super/missing_arguments.lay",
+      "language/super/no_superclass_field.lay",
       "language/super/no_superclass_method.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
+#[test]
+fn syntax_error() -> Result<(), std::io::Error> {
+  test_file_with_stdio(
+    "language/syntax_error/multiple.lay",
+    None,
+    Some(vec![
+      "error: Expected expression.",
+      "  --> multiple.lay:1:9",
+      "  |",
+      "1 | let a = ;",
+      "  |         ^",
+      "",
+      "error: Expected expression.",
+      "  --> multiple.lay:5:11",
+      "  |",
+      "5 |   let c = );",
+      "  |           ^",
+      "",
+      "error: Expected expression.",
+      "  --> multiple.lay:8:10",
+      "  |",
+      "8 | print(b +);",
+      "  |          ^",
+    ]),
+    Outcome::CompileError,
+  )
+}
+
+#[test]
+fn tuple() -> Result<(), std::io::Error> {
+  test_file_exits(
+    &vec![
+      "language/tuple/destructure.lay",
+      "language/tuple/equality.lay",
+      "language/tuple/literal.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_exits(
+    &vec![
+      "language/tuple/missing_closing_paren.lay",
+      "language/tuple/missing_equal.lay",
+    ],
+    Outcome::CompileError,
+  )?;
+
+  test_file_exits(
+    &vec![
+      "language/tuple/unpack_length.lay",
+      "language/tuple/unpack_not_tuple.lay",
+    ],
+    Outcome::RuntimeError,
+  )
+}
+
+#[test]
+fn type_check() -> Result<(), std::io::Error> {
+  assert_files_exit_with_builder(
+    &[
+      "language/type_check/annotated.lay",
+      "language/type_check/untyped.lay",
+    ],
+    FILE_PATH,
+    Vm::builder().type_check(true),
+    Outcome::Ok(0),
+  )?;
+
+  assert_files_exit_with_builder(
+    &[
+      "language/type_check/argument_mismatch.lay",
+      "language/type_check/field_mismatch.lay",
+      "language/type_check/let_mismatch.lay",
+      "language/type_check/operator_mismatch.lay",
+      "language/type_check/return_mismatch.lay",
+    ],
+    FILE_PATH,
+    Vm::builder().type_check(true),
+    Outcome::CompileError,
+  )?;
+
+  // annotations are erased when the check is not enabled
+  test_file_exits(
+    &[
+      "language/type_check/annotated.lay",
+      "language/type_check/field_mismatch.lay",
+      "language/type_check/let_mismatch.lay",
+      "language/type_check/return_mismatch.lay",
+    ],
+    Outcome::Ok(0),
+  )
+}
+
+#[test]
+fn with_() -> Result<(), std::io::Error> {
+  test_file_exits(
+    &vec![
+      "language/with/basic.lay",
+      "language/with/early_exit.lay",
+      "language/with/error.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_file_exits(
+    &vec![
+      "language/with/missing_brace.lay",
+      "language/with/missing_identifier.lay",
+    ],
+    Outcome::CompileError,
+  )?;
+
+  test_file_exits(&vec!["language/with/uncaught.lay"], Outcome::RuntimeError)
+}
+
 #[test]
 fn variable() -> Result<(), std::io::Error> {
   test_file_exits(
@@ -1018,7 +1640,7 @@ fn variable() -> Result<(), std::io::Error> {
       "language/variable/unreached_undefined.lay",
       "language/variable/use_global_in_initializer.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -1031,7 +1653,7 @@ fn variable() -> Result<(), std::io::Error> {
       "language/variable/use_nil_as_var.lay",
       "language/variable/use_this_as_var.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
   test_file_exits(
@@ -1040,7 +1662,7 @@ fn variable() -> Result<(), std::io::Error> {
       "language/variable/undefined_local.lay",
       "language/variable/undefined_local.lay",
     ],
-    ExecuteResult::RuntimeError,
+    Outcome::RuntimeError,
   )
 }
 
@@ -1053,7 +1675,7 @@ fn while_test() -> Result<(), std::io::Error> {
       "language/while/return_inside.lay",
       "language/while/syntax.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
   test_file_exits(
@@ -1062,8 +1684,8 @@ fn while_test() -> Result<(), std::io::Error> {
       "language/while/fun_in_body.lay",
       "language/while/var_in_body.lay",
     ],
-    ExecuteResult::CompileError,
+    Outcome::CompileError,
   )?;
 
-  test_file_exits(&vec![], ExecuteResult::RuntimeError)
+  test_file_exits(&vec![], Outcome::RuntimeError)
 }
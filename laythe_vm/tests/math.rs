@@ -1,9 +1,8 @@
-use laythe_vm::vm::ExecuteResult;
-use support::assert_files_exit;
+use support::{assert_files_exit, Outcome};
 
 mod support;
 
-fn test_files(paths: &[&str], result: ExecuteResult) -> Result<(), std::io::Error> {
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
   assert_files_exit(paths, FILE_PATH, result)
 }
 
@@ -20,10 +19,28 @@ fn utils() -> Result<(), std::io::Error> {
       "std_lib/math/utils/rem.lay",
       "std_lib/math/utils/sin.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_files(&vec![], ExecuteResult::CompileError)?;
+  test_files(&vec![], Outcome::CompileError)?;
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+  test_files(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn big_int() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/math/big_int/arithmetic.lay",
+      "std_lib/math/big_int/cmp.lay",
+      "std_lib/math/big_int/errors.lay",
+      "std_lib/math/big_int/parse.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(
+    &vec!["std_lib/math/big_int/div_zero.lay"],
+    Outcome::RuntimeError,
+  )
 }
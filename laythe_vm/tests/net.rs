@@ -0,0 +1,67 @@
+use support::{assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn address() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/net/address/errors.lay",
+      "std_lib/net/address/ip.lay",
+      "std_lib/net/address/resolve.lay",
+      "std_lib/net/address/socket.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn url() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/net/url/build.lay",
+      "std_lib/net/url/errors.lay",
+      "std_lib/net/url/helpers.lay",
+      "std_lib/net/url/parse.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn tcp() -> Result<(), std::io::Error> {
+  test_files(
+    &vec!["std_lib/net/tcp/client.lay", "std_lib/net/tcp/errors.lay"],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn http() -> Result<(), std::io::Error> {
+  test_files(
+    &vec!["std_lib/net/http/close.lay", "std_lib/net/http/errors.lay"],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
@@ -0,0 +1,25 @@
+use support::{assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn os() -> Result<(), std::io::Error> {
+  test_files(
+    &[
+      "std_lib/os/args.lay",
+      "std_lib/os/env.lay",
+      "std_lib/os/platform.lay",
+      "std_lib/os/set_env_error.lay",
+      "std_lib/os/signal.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&["std_lib/os/exit.lay"], Outcome::Ok(3))
+}
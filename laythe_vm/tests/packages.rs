@@ -0,0 +1,101 @@
+use laythe_env::stdio::support::{IoStdioTest, StdioTestContainer};
+use laythe_native::io::io_native;
+use laythe_vm::vm::{Vm, VmError};
+use std::{fs, path::Path, str, sync::Arc};
+use support::fixture_path_inner;
+
+mod support;
+
+const FILE_PATH: &str = file!();
+
+/// Run a script of a fixture package returning the result and stderr
+fn run_package(
+  package: &str,
+  script: &str,
+) -> Result<(Result<u16, VmError>, String), std::io::Error> {
+  let dir = fixture_path_inner(&format!("packages/{}", package), FILE_PATH)
+    .expect("No parent directory")
+    .canonicalize()?;
+  run_package_in(&dir, script)
+}
+
+/// Run a script of the package in the provided directory
+fn run_package_in(
+  dir: &Path,
+  script: &str,
+) -> Result<(Result<u16, VmError>, String), std::io::Error> {
+  let script = dir.join(script);
+  let source = fs::read_to_string(&script)?;
+
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+
+  let result = {
+    let mut vm = Vm::builder()
+      .io(io_native().with_stdio(stdio))
+      .manifest(dir.join("laythe.toml"))
+      .build()
+      .expect("Unable to create vm");
+    let result = vm.run(script, &source);
+    if let Err(error) = &result {
+      vm.report(error);
+    }
+    result
+  };
+
+  let stderr = str::from_utf8(&stdio_container.stderr).expect("Stderr was not utf8");
+  Ok((result, stderr.to_string()))
+}
+
+#[test]
+fn dependencies() -> Result<(), std::io::Error> {
+  let (result, stderr) = run_package("app", "main.lay")?;
+  assert_eq!(result, Ok(0), "{}", stderr);
+  Ok(())
+}
+
+#[test]
+fn undeclared_dependency() -> Result<(), std::io::Error> {
+  let (result, stderr) = run_package("app", "undeclared.lay")?;
+  assert!(matches!(result, Err(VmError::Runtime(_))));
+  assert!(stderr.contains("ImportError: Package app does not depend on math."));
+  Ok(())
+}
+
+#[test]
+fn missing_module() -> Result<(), std::io::Error> {
+  let (result, stderr) = run_package("app", "missing.lay")?;
+  assert!(matches!(result, Err(VmError::Runtime(_))));
+  assert!(stderr.contains("ImportError: Module shapes/square imported from missing.lay"));
+  Ok(())
+}
+
+#[test]
+fn invalid_manifest() -> Result<(), std::io::Error> {
+  let (result, stderr) = run_package("broken", "main.lay")?;
+  assert!(matches!(result, Err(VmError::Load(_))));
+  assert!(stderr.contains("line 1: Package version is missing."));
+  Ok(())
+}
+
+#[test]
+fn misnamed_dependency() -> Result<(), std::io::Error> {
+  let (result, stderr) = run_package("misnamed", "main.lay")?;
+  assert!(matches!(result, Err(VmError::Load(_))));
+  assert!(stderr.contains("Dependency shapes at"));
+  assert!(stderr.contains("is named math."));
+  Ok(())
+}
+
+#[test]
+fn dependency_cycle() -> Result<(), std::io::Error> {
+  // the root is named through a path that differs from how its dependent finds it
+  let dir = fixture_path_inner("packages/ring", FILE_PATH)
+    .expect("No parent directory")
+    .canonicalize()?
+    .join("../cycle");
+
+  let (result, stderr) = run_package_in(&dir, "main.lay")?;
+  assert_eq!(result, Ok(0), "{}", stderr);
+  Ok(())
+}
@@ -0,0 +1,55 @@
+use laythe_vm::vm::Vm;
+use std::{env, fs, process};
+use support::fixture_path_inner;
+
+mod support;
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn collapsed_stacks() -> Result<(), std::io::Error> {
+  let fixture =
+    fixture_path_inner("profiler/recursion.lay", FILE_PATH).expect("No parent directory");
+  let source = fs::read_to_string(&fixture)?;
+  let profile = env::temp_dir().join(format!("laythe_profile_{}.folded", process::id()));
+
+  let mut vm = Vm::builder()
+    .profile(profile.clone())
+    .build()
+    .expect("Unable to create vm");
+  assert_eq!(vm.run(fixture, &source), Ok(0));
+
+  let folded = fs::read_to_string(&profile)?;
+  fs::remove_file(&profile)?;
+
+  let stacks: Vec<(&str, u64)> = folded
+    .lines()
+    .map(|line| {
+      let (stack, micros) = line.rsplit_once(' ').expect("Expected a stack and a count");
+      (stack, micros.parse().expect("Expected a count"))
+    })
+    .collect();
+
+  let frames = |stack: &str| -> Vec<String> {
+    stack
+      .split(';')
+      .map(|frame| frame.split(' ').next().unwrap_or_default().to_string())
+      .collect()
+  };
+
+  assert!(stacks
+    .iter()
+    .any(|(stack, _)| frames(stack) == vec!["script"]));
+  assert!(stacks
+    .iter()
+    .any(|(stack, _)| frames(stack) == vec!["script", "run", "fib"]));
+
+  // fib(15) recurses down to fib(1) giving 15 fib frames below run
+  let deepest = stacks
+    .iter()
+    .map(|(stack, _)| frames(stack).len())
+    .max()
+    .unwrap_or_default();
+  assert_eq!(deepest, 17);
+  Ok(())
+}
@@ -1,9 +1,8 @@
-use laythe_vm::vm::ExecuteResult;
-use support::assert_files_exit;
+use support::{assert_files_exit, Outcome};
 
 mod support;
 
-fn test_files(paths: &[&str], result: ExecuteResult) -> Result<(), std::io::Error> {
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
   assert_files_exit(paths, FILE_PATH, result)
 }
 
@@ -14,13 +13,18 @@ fn utils() -> Result<(), std::io::Error> {
   test_files(
     &vec![
       "std_lib/regexp/class/captures.lay",
+      "std_lib/regexp/class/flags.lay",
       "std_lib/regexp/class/match.lay",
+      "std_lib/regexp/class/match_all.lay",
+      "std_lib/regexp/class/named_captures.lay",
+      "std_lib/regexp/class/replace.lay",
+      "std_lib/regexp/class/split.lay",
       "std_lib/regexp/class/test.lay",
     ],
-    ExecuteResult::Ok(0),
+    Outcome::Ok(0),
   )?;
 
-  test_files(&vec![], ExecuteResult::CompileError)?;
+  test_files(&vec![], Outcome::CompileError)?;
 
-  test_files(&vec![], ExecuteResult::RuntimeError)
+  test_files(&vec![], Outcome::RuntimeError)
 }
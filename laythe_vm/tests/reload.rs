@@ -0,0 +1,177 @@
+use laythe_env::{
+  io::Io,
+  resolver::{ImportResolver, ResolvedModule},
+  stdio::support::{IoStdioTest, StdioTestContainer},
+};
+use laythe_vm::vm::{Vm, VmError};
+use std::{
+  io,
+  path::{Path, PathBuf},
+  str,
+  sync::Arc,
+};
+
+/// Serves the first version of the counter module and the store
+/// module holding instances across runs
+#[derive(Debug)]
+struct ScriptResolver;
+
+impl ImportResolver for ScriptResolver {
+  fn resolve(&self, path: &Path) -> io::Result<ResolvedModule> {
+    match path.to_str() {
+      Some("/app/counter.lay") => Ok(ResolvedModule::Source(COUNTER.to_string())),
+      Some("/app/store.lay") => Ok(ResolvedModule::Source(STORE.to_string())),
+      _ => Err(io::Error::new(io::ErrorKind::NotFound, "Module not found")),
+    }
+  }
+}
+
+const STORE: &str = "
+export let saved = [];
+";
+
+const COUNTER: &str = "
+export class Counter {
+  init() {
+    self.count = 0;
+  }
+
+  step() {
+    self.count = self.count + 1;
+  }
+}
+";
+
+const SAVE: &str = "
+import ./counter:{Counter};
+import ./store:{saved};
+
+let counter = Counter();
+counter.step();
+assertEq(counter.count, 1);
+saved.push(counter);
+";
+
+fn vm(stdio_container: &Arc<StdioTestContainer>) -> Vm {
+  let stdio = Arc::new(IoStdioTest::new(stdio_container));
+  let io = Io::default()
+    .with_stdio(stdio)
+    .with_resolver(Arc::new(ScriptResolver));
+
+  Vm::builder().io(io).build().expect("Unable to create vm")
+}
+
+fn run(vm: &mut Vm, source: &str) -> Result<u16, VmError> {
+  vm.run(PathBuf::from("/app/main.lay"), source)
+}
+
+fn reload_counter(vm: &mut Vm, source: &str) -> Result<u16, VmError> {
+  vm.reload(PathBuf::from("/app/counter.lay"), source)
+}
+
+#[test]
+fn keeps_class_identity() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+  assert_eq!(run(&mut vm, SAVE), Ok(0));
+
+  let reloaded = "
+export class Counter {
+  init() {
+    self.count = 0;
+  }
+
+  step() {
+    self.count = self.count + 10;
+  }
+}
+";
+  assert_eq!(reload_counter(&mut vm, reloaded), Ok(0));
+
+  let check = "
+import ./counter:{Counter};
+import ./store:{saved};
+
+let counter = saved[0];
+assert(counter.cls() == Counter);
+counter.step();
+assertEq(counter.count, 11);
+";
+  assert_eq!(run(&mut vm, check), Ok(0));
+}
+
+#[test]
+fn replaces_class_with_new_fields() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+  assert_eq!(run(&mut vm, SAVE), Ok(0));
+
+  let reloaded = "
+export class Counter {
+  init() {
+    self.count = 0;
+    self.by = 10;
+  }
+
+  step() {
+    self.count = self.count + self.by;
+  }
+}
+";
+  assert_eq!(reload_counter(&mut vm, reloaded), Ok(0));
+
+  let check = "
+import ./counter:{Counter};
+import ./store:{saved};
+
+let counter = saved[0];
+assert(counter.cls() != Counter);
+counter.step();
+assertEq(counter.count, 2);
+
+let reloaded = Counter();
+reloaded.step();
+assertEq(reloaded.count, 10);
+";
+  assert_eq!(run(&mut vm, check), Ok(0));
+}
+
+#[test]
+fn failed_reload_keeps_module() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+  assert_eq!(run(&mut vm, SAVE), Ok(0));
+
+  let broken = "
+export class Counter {}
+missing();
+";
+  assert!(matches!(
+    reload_counter(&mut vm, broken),
+    Err(VmError::Runtime(_))
+  ));
+
+  let check = "
+import ./counter:{Counter};
+import ./store:{saved};
+
+assert(saved[0].cls() == Counter);
+let counter = Counter();
+counter.step();
+assertEq(counter.count, 1);
+";
+  assert_eq!(run(&mut vm, check), Ok(0));
+}
+
+#[test]
+fn module_not_loaded() {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let mut vm = vm(&stdio_container);
+
+  assert_eq!(
+    reload_counter(&mut vm, COUNTER),
+    Err(VmError::Load(
+      "Module /app/counter.lay has not been loaded.".to_string()
+    ))
+  );
+}
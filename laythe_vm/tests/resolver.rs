@@ -0,0 +1,138 @@
+use laythe_env::{
+  io::Io,
+  resolver::{ImportResolver, ResolvedModule},
+  stdio::support::{IoStdioTest, StdioTestContainer},
+};
+use laythe_vm::vm::{Vm, VmError};
+use std::{
+  collections::HashMap,
+  io,
+  path::{Path, PathBuf},
+  str,
+  sync::Arc,
+};
+
+/// A resolver serving modules from memory in place of a database
+#[derive(Debug, Default)]
+struct MemoryResolver {
+  modules: HashMap<PathBuf, ResolvedModule>,
+}
+
+impl MemoryResolver {
+  fn module(mut self, path: &str, module: ResolvedModule) -> Self {
+    self.modules.insert(PathBuf::from(path), module);
+    self
+  }
+}
+
+impl ImportResolver for MemoryResolver {
+  fn resolve(&self, path: &Path) -> io::Result<ResolvedModule> {
+    self
+      .modules
+      .get(path)
+      .cloned()
+      .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Module not in database"))
+  }
+}
+
+const MAIN: &str = "
+import ./shapes:{Square};
+import ./lib/geometry;
+
+assertEq(geometry.area(Square(3)), 9);
+";
+
+const SHAPES: &str = "
+export class Square {
+  init(side) {
+    self.side = side;
+  }
+}
+";
+
+const GEOMETRY: &str = "
+export fn area(square) {
+  square.side * square.side
+}
+";
+
+fn run(resolver: MemoryResolver, source: &str) -> (Result<u16, VmError>, String) {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+
+  let result = {
+    let io = Io::default()
+      .with_stdio(stdio)
+      .with_resolver(Arc::new(resolver));
+    let mut vm = Vm::builder().io(io).build().expect("Unable to create vm");
+    let result = vm.run(PathBuf::from("/db/main.lay"), source);
+    if let Err(error) = &result {
+      vm.report(error);
+    }
+    result
+  };
+
+  let stderr = str::from_utf8(&stdio_container.stderr).expect("Stderr was not utf8");
+  (result, stderr.to_string())
+}
+
+#[test]
+fn source_modules() {
+  let resolver = MemoryResolver::default()
+    .module("/db/shapes.lay", ResolvedModule::Source(SHAPES.to_string()))
+    .module(
+      "/db/lib/geometry.lay",
+      ResolvedModule::Source(GEOMETRY.to_string()),
+    );
+
+  assert_eq!(run(resolver, MAIN).0, Ok(0));
+}
+
+#[test]
+fn precompiled_modules() {
+  let mut compiler = Vm::builder()
+    .io(Io::default())
+    .build()
+    .expect("Unable to create vm");
+  let shapes = compiler
+    .precompile(PathBuf::from("/db/shapes.lay"), SHAPES)
+    .expect("Unable to precompile shapes");
+  let geometry = compiler
+    .precompile(PathBuf::from("/db/lib/geometry.lay"), GEOMETRY)
+    .expect("Unable to precompile geometry");
+
+  let resolver = MemoryResolver::default()
+    .module("/db/shapes.lay", ResolvedModule::Compiled(shapes))
+    .module("/db/lib/geometry.lay", ResolvedModule::Compiled(geometry));
+
+  assert_eq!(run(resolver, MAIN).0, Ok(0));
+}
+
+#[test]
+fn invalid_precompiled_module() {
+  let resolver = MemoryResolver::default()
+    .module("/db/shapes.lay", ResolvedModule::Compiled(vec![1, 2, 3]))
+    .module(
+      "/db/lib/geometry.lay",
+      ResolvedModule::Source(GEOMETRY.to_string()),
+    );
+
+  let (result, stderr) = run(resolver, MAIN);
+  assert!(matches!(result, Err(VmError::Runtime(_))));
+  assert!(stderr.contains(&format!(
+    "ImportError: Module ./shapes was not precompiled by Laythe {}.",
+    Vm::version()
+  )));
+}
+
+#[test]
+fn missing_module() {
+  let resolver =
+    MemoryResolver::default().module("/db/shapes.lay", ResolvedModule::Source(SHAPES.to_string()));
+
+  let (result, stderr) = run(resolver, MAIN);
+  assert!(matches!(result, Err(VmError::Runtime(_))));
+  assert!(
+    stderr.contains("ImportError: Module ./lib/geometry imported from main.lay was not found")
+  );
+}
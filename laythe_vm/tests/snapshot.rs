@@ -0,0 +1,145 @@
+use laythe_env::{
+  io::Io,
+  resolver::{ImportResolver, ResolvedModule},
+  stdio::support::{IoStdioTest, StdioTestContainer},
+};
+use laythe_vm::vm::{Vm, VmError};
+use std::{
+  io,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+/// Serves the counter and store modules only when they may be loaded
+#[derive(Debug)]
+struct ScriptResolver {
+  serve: bool,
+}
+
+impl ImportResolver for ScriptResolver {
+  fn resolve(&self, path: &Path) -> io::Result<ResolvedModule> {
+    match path.to_str() {
+      Some("/app/counter.lay") if self.serve => Ok(ResolvedModule::Source(COUNTER.to_string())),
+      Some("/app/store.lay") if self.serve => Ok(ResolvedModule::Source(STORE.to_string())),
+      _ => Err(io::Error::new(io::ErrorKind::NotFound, "Module not found")),
+    }
+  }
+}
+
+const STORE: &str = "
+export let saved = [];
+export let named = {};
+";
+
+const COUNTER: &str = "
+export class Counter {
+  init() {
+    self.count = 0;
+  }
+
+  step() {
+    self.count = self.count + 1;
+  }
+}
+";
+
+const MAIN: &str = "
+import ./counter:{Counter};
+import ./store:{saved, named};
+import std.math:{rem};
+
+let counter = Counter();
+counter.step();
+saved.push(counter);
+named['first'] = counter;
+
+fn makeTally() {
+  let total = 0;
+  |n| {
+    total = total + n;
+    return total;
+  }
+}
+
+let tally = makeTally();
+tally(2);
+
+fn describe() {
+  saved[0].step();
+  tally(1).str() + ' ' + named['first'].count.str() + ' ' + rem(7, 4).str()
+}
+";
+
+const CHECK: &str = "
+import ./counter:{Counter};
+import ./store:{saved};
+
+assertEq(saved[0].cls(), Counter);
+saved[0].step();
+assertEq(saved[0].count, 2);
+";
+
+fn vm(serve: bool) -> Vm {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let io = Io::default()
+    .with_stdio(Arc::new(IoStdioTest::new(&stdio_container)))
+    .with_resolver(Arc::new(ScriptResolver { serve }));
+
+  Vm::builder()
+    .io(io)
+    .gc_stress(true)
+    .build()
+    .expect("Unable to create vm")
+}
+
+fn snapshot() -> Vec<u8> {
+  let mut vm = vm(true);
+  assert_eq!(vm.run(PathBuf::from("/app/main.lay"), MAIN), Ok(0));
+  vm.snapshot().expect("Unable to snapshot vm")
+}
+
+fn describe(vm: &mut Vm) -> String {
+  let describe = vm.get_global("describe").expect("describe not found");
+  vm.call(&describe, &[])
+    .and_then(|result| result.get::<String>())
+    .expect("Unable to call describe")
+}
+
+#[test]
+fn restores_main_module() {
+  let snapshot = snapshot();
+
+  let mut vm = vm(false);
+  assert_eq!(vm.restore(&snapshot), Ok(()));
+  assert_eq!(describe(&mut vm), "3 2 3");
+  assert_eq!(describe(&mut vm), "4 3 3");
+}
+
+#[test]
+fn restores_imported_modules() {
+  let snapshot = snapshot();
+
+  let mut vm = vm(false);
+  assert_eq!(vm.restore(&snapshot), Ok(()));
+  assert_eq!(vm.run(PathBuf::from("/app/main.lay"), CHECK), Ok(0));
+}
+
+#[test]
+fn rejects_modules_already_loaded() {
+  let snapshot = snapshot();
+
+  let mut vm = vm(false);
+  assert_eq!(vm.restore(&snapshot), Ok(()));
+  assert!(matches!(vm.restore(&snapshot), Err(VmError::Load(_))));
+}
+
+#[test]
+fn rejects_corrupt_snapshots() {
+  let mut snapshot = snapshot();
+  let last = snapshot.len() - 1;
+  snapshot[last] ^= 0xff;
+
+  let mut vm = vm(false);
+  assert!(matches!(vm.restore(&snapshot), Err(VmError::Load(_))));
+  assert!(matches!(vm.restore(b"LYSS"), Err(VmError::Load(_))));
+}
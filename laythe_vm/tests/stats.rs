@@ -0,0 +1,55 @@
+use laythe_env::{
+  io::Io,
+  stdio::support::{IoStdioTest, StdioTestContainer},
+};
+use laythe_vm::vm::Vm;
+use std::{fs, str, sync::Arc};
+use support::fixture_path_inner;
+
+mod support;
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn op_codes_and_call_sites() -> Result<(), std::io::Error> {
+  let fixture = fixture_path_inner("stats/calls.lay", FILE_PATH).expect("No parent directory");
+  let source = fs::read_to_string(&fixture)?;
+
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+
+  {
+    let io = Io::default().with_stdio(stdio);
+    let mut vm = Vm::builder()
+      .io(io)
+      .stats(true)
+      .build()
+      .expect("Unable to create vm");
+    assert_eq!(vm.run(fixture, &source), Ok(0));
+  }
+
+  let stderr = str::from_utf8(&stdio_container.stderr).expect("Stderr was not utf8");
+  let lines: Vec<Vec<&str>> = stderr
+    .lines()
+    .map(|line| line.split_whitespace().collect())
+    .collect();
+
+  assert!(stderr.starts_with("Op codes executed: "));
+
+  // add is called on each of the 10 iterations and assertEq once
+  let calls = lines
+    .iter()
+    .find(|line| line.first() == Some(&"Call"))
+    .expect("Expected a call op code count");
+  assert_eq!(calls[1], "11");
+
+  let call_sites = lines
+    .iter()
+    .position(|line| line[..] == ["Call", "sites:", "4"])
+    .expect("Expected call site counts");
+  let hottest = &lines[call_sites + 1];
+  assert_eq!(hottest[0], "script");
+  assert!(hottest[1].ends_with("calls.lay):5"));
+  assert_eq!(hottest[2..], ["Call", "10"]);
+  Ok(())
+}
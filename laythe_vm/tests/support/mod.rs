@@ -2,13 +2,15 @@ use laythe_env::{
   io::Io,
   stdio::support::{IoStdioTest, StdioTestContainer, TestWriter},
 };
-use laythe_native::{env::IoEnvNative, fs::IoFsNative, time::IoTimeNative};
-use laythe_vm::vm::{ExecuteResult, Vm};
+use laythe_native::{env::IoEnvNative, fs::IoFsNative, net::IoNetNative, time::IoTimeNative};
+use laythe_vm::vm::{Vm, VmBuilder, VmConfig, VmError};
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::{self, Cursor};
 use std::path::{Path, PathBuf};
 use std::str;
+use std::thread;
+use std::time::Duration;
 use std::{fmt, sync::Arc};
 
 pub fn fixture_path_inner(fixture_path: &str, test_file_path: &str) -> Option<PathBuf> {
@@ -22,10 +24,26 @@ pub fn fixture_path_inner(fixture_path: &str, test_file_path: &str) -> Option<Pa
 }
 
 #[allow(dead_code)]
-pub fn assert_files_exit(
+pub fn assert_files_exit(paths: &[&str], test_file_path: &str, result: Outcome) -> io::Result<()> {
+  assert_files_exit_with_config(paths, test_file_path, VmConfig::default(), result)
+}
+
+#[allow(dead_code)]
+pub fn assert_files_exit_with_config(
+  paths: &[&str],
+  test_file_path: &str,
+  config: VmConfig,
+  result: Outcome,
+) -> io::Result<()> {
+  assert_files_exit_with_builder(paths, test_file_path, Vm::builder().config(config), result)
+}
+
+#[allow(dead_code)]
+pub fn assert_files_exit_with_builder(
   paths: &[&str],
   test_file_path: &str,
-  result: ExecuteResult,
+  builder: VmBuilder,
+  result: Outcome,
 ) -> io::Result<()> {
   for path in paths {
     let mut stdio_container = Arc::new(StdioTestContainer::default());
@@ -33,15 +51,18 @@ pub fn assert_files_exit(
     let time = Arc::new(IoTimeNative::default());
     let fs = Arc::new(IoFsNative());
     let env = Arc::new(IoEnvNative());
+    let net = Arc::new(IoNetNative());
 
     {
       let io = Io::default()
         .with_stdio(stdio)
         .with_time(time)
         .with_fs(fs)
-        .with_env(env);
+        .with_env(env)
+        .with_net(net);
 
-      if let Err(err) = assert_files_exit_inner(path, test_file_path, io, result.clone()) {
+      let builder = builder.clone().io(io);
+      if let Err(err) = assert_files_exit_inner(path, test_file_path, builder, result.clone()) {
         eprintln!(
           "{}",
           str::from_utf8(&*stdio_container.stdout).expect("Could not unwrap stdout")
@@ -67,7 +88,7 @@ pub fn assert_file_exit_and_stdio(
   lines: Option<Vec<String>>,
   stdout: Option<Vec<&str>>,
   stderr: Option<Vec<&str>>,
-  result: ExecuteResult,
+  result: Outcome,
 ) -> io::Result<()> {
   let stdio_container = Arc::new(StdioTestContainer {
     stdout: TestWriter::default(),
@@ -81,9 +102,11 @@ pub fn assert_file_exit_and_stdio(
   let stdio = Arc::new(IoStdioTest::new(&stdio_container));
 
   {
-    let io = Io::default().with_stdio(stdio);
+    let io = Io::default()
+      .with_stdio(stdio)
+      .with_fs(Arc::new(IoFsNative()));
 
-    if let Err(err) = assert_files_exit_inner(path, file_path, io, result) {
+    if let Err(err) = assert_files_exit_inner(path, file_path, Vm::builder().io(io), result) {
       stdio_container.log_stdio();
       return Err(err);
     }
@@ -138,13 +161,61 @@ pub fn assert_file_exit_and_stdio(
   Ok(())
 }
 
+#[allow(dead_code)]
+pub fn assert_file_interrupted(
+  path: &str,
+  test_file_path: &str,
+  delay: Duration,
+  stderr: Vec<&str>,
+) -> io::Result<()> {
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+  let io = Io::default()
+    .with_stdio(stdio)
+    .with_time(Arc::new(IoTimeNative::default()))
+    .with_fs(Arc::new(IoFsNative()))
+    .with_env(Arc::new(IoEnvNative()));
+
+  let mut vm = Vm::new(io).expect("Unable to create vm");
+  let interrupt = vm.interrupt_handle();
+
+  let test_path = fixture_path_inner(path, test_file_path).expect("No parent directory");
+  let source = std::fs::read_to_string(&test_path)?;
+
+  let interrupter = thread::spawn(move || {
+    thread::sleep(delay);
+    interrupt.interrupt();
+  });
+
+  let result = vm.run(test_path, &source);
+  interrupter.join().expect("Interrupt thread panicked");
+  if let Err(error) = &result {
+    vm.report(error);
+  }
+
+  if let Err(err) = ly_assert_eq(&Outcome::from(&result), &Outcome::Interrupted, None) {
+    stdio_container.log_stdio();
+    return Err(err);
+  }
+
+  let stderr_string = str::from_utf8(&stdio_container.stderr).expect("Could not unwrap stderr");
+  let stderr_lines: Vec<&str> = stderr_string.trim_end().split('\n').collect();
+
+  if let Err(err) = ly_assert_eq(&stderr_lines, &stderr, None) {
+    stdio_container.log_stdio();
+    return Err(err);
+  }
+
+  Ok(())
+}
+
 fn assert_files_exit_inner(
   path: &str,
   test_file_path: &str,
-  io: Io,
-  result: ExecuteResult,
+  builder: VmBuilder,
+  result: Outcome,
 ) -> io::Result<()> {
-  let mut vm = Vm::new(io.clone());
+  let mut vm = builder.build().expect("Unable to create vm");
 
   let test_path = fixture_path_inner(path, test_file_path).expect("No parent directory");
   let debug_path = test_path.to_str().map(|s| s.to_string());
@@ -159,8 +230,13 @@ fn assert_files_exit_inner(
   let mut source = String::new();
   file.read_to_string(&mut source)?;
 
+  let received = vm.run(test_path, &source);
+  if let Err(error) = &received {
+    vm.report(error);
+  }
+
   ly_assert_eq(
-    &vm.run(test_path, &source),
+    &Outcome::from(&received),
     &result,
     Some(format!("Failing file {:?}", debug_path)),
   )?;
@@ -168,6 +244,38 @@ fn assert_files_exit_inner(
   Ok(())
 }
 
+/// The outcome a script is expected to have. Errors are only compared
+/// by kind, the diagnostics themselves are checked through stderr
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+  Ok(u16),
+  CompileError,
+  RuntimeError,
+  LoadError,
+  IoError,
+  Timeout,
+  Interrupted,
+  InternalError,
+  AwaitingInput,
+}
+
+impl From<&Result<u16, VmError>> for Outcome {
+  fn from(result: &Result<u16, VmError>) -> Self {
+    match result {
+      Ok(code) => Outcome::Ok(*code),
+      Err(VmError::Compile(_)) => Outcome::CompileError,
+      Err(VmError::Runtime(_)) => Outcome::RuntimeError,
+      Err(VmError::Load(_)) => Outcome::LoadError,
+      Err(VmError::Io { .. }) => Outcome::IoError,
+      Err(VmError::Timeout) => Outcome::Timeout,
+      Err(VmError::Interrupted(_)) => Outcome::Interrupted,
+      Err(VmError::Internal(_)) => Outcome::InternalError,
+      Err(VmError::AwaitingInput) => Outcome::AwaitingInput,
+    }
+  }
+}
+
 /// Assert equal returning a result so debug information has a chance to be captured and displayed
 fn ly_assert_eq<T: PartialEq + fmt::Debug>(
   expected: &T,
@@ -0,0 +1,27 @@
+use support::{assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn sync() -> Result<(), std::io::Error> {
+  test_files(
+    &vec![
+      "std_lib/sync/atomic.lay",
+      "std_lib/sync/errors.lay",
+      "std_lib/sync/mutex.lay",
+      "std_lib/sync/rw_lock.lay",
+      "std_lib/sync/task_group.lay",
+    ],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec!["std_lib/sync/deadlock.lay"], Outcome::RuntimeError)
+}
@@ -0,0 +1,49 @@
+use support::{assert_file_exit_and_stdio, assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn term() -> Result<(), std::io::Error> {
+  test_files(
+    &vec!["std_lib/term/errors.lay", "std_lib/term/style.lay"],
+    Outcome::Ok(0),
+  )?;
+
+  test_files(&vec![], Outcome::CompileError)?;
+
+  test_files(&vec![], Outcome::RuntimeError)
+}
+
+#[test]
+fn screen() -> Result<(), std::io::Error> {
+  assert_file_exit_and_stdio(
+    "std_lib/term/screen.lay",
+    FILE_PATH,
+    None,
+    None,
+    Some(vec![
+      "\x1b[2J\x1b[H\x1b[?25l\x1b[3;5H\x1b[1A\x1b[3C\x1b[2K\r\x1b[?25h",
+    ]),
+    None,
+    Outcome::Ok(0),
+  )
+}
+
+#[test]
+fn keys() -> Result<(), std::io::Error> {
+  assert_file_exit_and_stdio(
+    "std_lib/term/keys.lay",
+    FILE_PATH,
+    Some("qé\x1b[A\x1bOD\x1b[3~\x1b[6~\x1b[H\x03\r\t\x7f\x1bx\x1b".to_string()),
+    None,
+    None,
+    None,
+    Outcome::Ok(0),
+  )
+}
@@ -0,0 +1,90 @@
+use laythe_env::{
+  io::Io,
+  stdio::support::{IoStdioTest, StdioTestContainer},
+};
+use laythe_vm::{
+  test_runner::{self, TestSummary},
+  vm::Vm,
+};
+use std::sync::Arc;
+use support::{assert_file_exit_and_stdio, fixture_path_inner, Outcome};
+
+mod support;
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn passing() -> Result<(), std::io::Error> {
+  assert_file_exit_and_stdio(
+    "std_lib/test/passing_test.lay",
+    FILE_PATH,
+    None,
+    None,
+    Some(vec![
+      "test list > compares by contents ... ok",
+      "test list > nested > compares maps by contents ... ok",
+      "test truthy ... ok",
+    ]),
+    None,
+    Outcome::Ok(0),
+  )
+}
+
+#[test]
+fn failing() -> Result<(), std::io::Error> {
+  assert_file_exit_and_stdio(
+    "std_lib/test/failing_test.lay",
+    FILE_PATH,
+    None,
+    None,
+    Some(vec![
+      "test strings > fails with a diff ... FAILED",
+      "    AssertError: Values are not equal",
+      "    - expected",
+      "    + actual",
+      "",
+      "      one",
+      "    - 2",
+      "    + two",
+      "      three",
+      "test strings > runs after a failure ... ok",
+    ]),
+    Some(vec![""]),
+    Outcome::Ok(0),
+  )
+}
+
+#[test]
+fn runner() -> Result<(), std::io::Error> {
+  let dir = fixture_path_inner("std_lib/test", FILE_PATH).expect("No parent directory");
+  let files = test_runner::discover(&[dir])?;
+
+  let names: Vec<String> = files
+    .iter()
+    .filter_map(|file| file.file_name())
+    .map(|name| name.to_string_lossy().to_string())
+    .collect();
+  assert_eq!(names, vec!["failing_test.lay", "passing_test.lay"]);
+
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+  let builder = Vm::builder().io(Io::default().with_stdio(stdio));
+
+  let mut output = vec![];
+  let summary = test_runner::run(&builder, &files, &mut output)?;
+
+  assert_eq!(
+    summary,
+    TestSummary {
+      files: 2,
+      passed: 4,
+      failed: 1,
+      errored: 0,
+    }
+  );
+  assert!(!summary.is_success());
+
+  let output = String::from_utf8(output).expect("Output was not utf8");
+  assert!(output.ends_with("test result: FAILED. 4 passed; 1 failed; 0 of 2 files errored\n"));
+  Ok(())
+}
@@ -0,0 +1,101 @@
+use laythe_env::io::Io;
+use laythe_vm::vm::Vm;
+use std::{path::PathBuf, thread};
+
+const SCRIPT: &str = "
+fn fib(n) {
+  if n < 2 {
+    return n;
+  }
+
+  fib(n - 1) + fib(n - 2)
+}
+
+fn describe(n) {
+  let parts = [];
+  let i = 0;
+  while i <= n {
+    parts.push('fib ' + i.str() + ' = ' + fib(i).str());
+    i = i + 1;
+  }
+
+  parts[n]
+}
+";
+
+fn vm() -> Vm {
+  let mut vm = Vm::builder()
+    .io(Io::default())
+    .gc_stress(true)
+    .build()
+    .expect("Unable to create vm");
+
+  assert_eq!(vm.run(PathBuf::from("/app/main.lay"), SCRIPT), Ok(0));
+  vm
+}
+
+fn describe(vm: &mut Vm, n: usize) -> String {
+  let describe = vm.get_global("describe").expect("describe not found");
+  let arg = vm.to_value(n as f64);
+
+  vm.call(&describe, &[arg])
+    .and_then(|result| result.get::<String>())
+    .expect("Unable to call describe")
+}
+
+#[test]
+fn vms_on_worker_threads() {
+  let workers: Vec<_> = (0..4)
+    .map(|n| {
+      let vm = vm()
+        .into_send()
+        .unwrap_or_else(|_| panic!("Vm not sendable"));
+
+      thread::spawn(move || {
+        let mut vm = vm.into_inner();
+        describe(&mut vm, n + 8)
+      })
+    })
+    .collect();
+
+  let results: Vec<String> = workers
+    .into_iter()
+    .map(|worker| worker.join().expect("Worker panicked"))
+    .collect();
+
+  assert_eq!(
+    results,
+    vec!["fib 8 = 21", "fib 9 = 34", "fib 10 = 55", "fib 11 = 89"]
+  );
+}
+
+#[test]
+fn vms_built_on_worker_threads() {
+  let workers: Vec<_> = (0..4)
+    .map(|n| thread::spawn(move || describe(&mut vm(), n)))
+    .collect();
+
+  let results: Vec<String> = workers
+    .into_iter()
+    .map(|worker| worker.join().expect("Worker panicked"))
+    .collect();
+
+  assert_eq!(
+    results,
+    vec!["fib 0 = 0", "fib 1 = 1", "fib 2 = 1", "fib 3 = 2"]
+  );
+}
+
+#[test]
+fn rooted_values_keep_vm_on_thread() {
+  let vm = vm();
+  let describe = vm.get_global("describe").expect("describe not found");
+
+  let vm = match vm.into_send() {
+    Ok(_) => panic!("Vm sent while a value was rooted"),
+    Err(vm) => vm,
+  };
+
+  drop(describe);
+  assert!(vm.into_send().is_ok());
+}
@@ -0,0 +1,23 @@
+use support::{assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn time() -> Result<(), std::io::Error> {
+  test_files(
+    &[
+      "std_lib/time/date_time.lay",
+      "std_lib/time/duration.lay",
+      "std_lib/time/errors.lay",
+      "std_lib/time/instant.lay",
+      "std_lib/time/timer.lay",
+    ],
+    Outcome::Ok(0),
+  )
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "tls")]
+
+use laythe_env::io::Io;
+use laythe_native::{net::IoNetNative, time::IoTimeNative};
+use laythe_vm::vm::Vm;
+use std::{io::Write, net::TcpListener, path::PathBuf, sync::Arc, thread};
+
+const SCRIPT: &str = "
+import std.net:{NetError};
+import std.net.tcp:{TcpStream, TlsStream};
+
+assertRaises(NetError, || TlsStream.wrap(TcpStream.connect(address), 'not a host!'));
+
+let tcp = TcpStream.connect(address);
+let tls = TlsStream.wrap(tcp, 'localhost');
+assertEq(tls.peer.str(), address);
+
+assertRaises(NetError, || tcp.read());
+assertRaises(NetError, || TlsStream.wrap(tcp, 'localhost'));
+assertRaises(NetError, || TlsStream.wrap(tls, 'localhost'));
+
+// the server doesn't speak tls so the handshake fails
+assertRaises(NetError, || tls.write('GET / HTTP/1.1\\r\\n\\r\\n'));
+";
+
+#[test]
+fn handshake() {
+  let listener = TcpListener::bind("127.0.0.1:0").expect("Unable to bind");
+  let address = listener.local_addr().expect("No local address").to_string();
+
+  let server = thread::spawn(move || {
+    for stream in listener.incoming().take(2) {
+      let mut stream = stream.expect("Unable to accept");
+      let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n");
+    }
+  });
+
+  let io = Io::default()
+    .with_time(Arc::new(IoTimeNative::default()))
+    .with_net(Arc::new(IoNetNative()));
+
+  let mut vm = Vm::builder().io(io).build().expect("Unable to create vm");
+
+  let script = format!("let address = '{}';\n{}", address, SCRIPT);
+  assert_eq!(vm.run(PathBuf::from("/app/main.lay"), &script), Ok(0));
+
+  server.join().expect("Server thread panicked");
+}
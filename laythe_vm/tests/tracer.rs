@@ -0,0 +1,100 @@
+use laythe_env::{
+  io::Io,
+  stdio::support::{IoStdioTest, StdioTestContainer},
+};
+use laythe_vm::{
+  tracer::{TraceConfig, TraceEvents},
+  vm::{Vm, VmBuilder},
+};
+use std::{fs, str, sync::Arc};
+use support::fixture_path_inner;
+
+mod support;
+
+const FILE_PATH: &str = file!();
+
+/// Run the fixture with the provided builder returning its stdout lines
+fn traced(builder: VmBuilder) -> Result<Vec<String>, std::io::Error> {
+  let fixture = fixture_path_inner("tracer/filtered.lay", FILE_PATH).expect("No parent directory");
+  let source = fs::read_to_string(&fixture)?;
+
+  let stdio_container = Arc::new(StdioTestContainer::default());
+  let stdio = Arc::new(IoStdioTest::new(&stdio_container));
+
+  {
+    let mut vm = builder
+      .io(Io::default().with_stdio(stdio))
+      .build()
+      .expect("Unable to create vm");
+    assert_eq!(vm.run(fixture, &source), Ok(0));
+  }
+
+  let stdout = str::from_utf8(&stdio_container.stdout).expect("Stdout was not utf8");
+  Ok(stdout.lines().map(|line| line.to_string()).collect())
+}
+
+#[test]
+fn calls_filtered_by_function() -> Result<(), std::io::Error> {
+  let events = TraceEvents {
+    calls: true,
+    ..TraceEvents::default()
+  };
+  let lines = traced(Vm::builder().tracer(TraceConfig::new(events).function("double")))?;
+
+  assert_eq!(lines.len(), 2);
+  assert!(lines[0].starts_with("[call] double ("));
+  assert!(lines[0].ends_with("filtered.lay) depth 3"));
+  assert!(lines[1].starts_with("[return] double ("));
+  assert!(lines[1].ends_with("filtered.lay) depth 3"));
+  Ok(())
+}
+
+#[test]
+fn channels_filtered_by_function() -> Result<(), std::io::Error> {
+  let events = TraceEvents {
+    channels: true,
+    ..TraceEvents::default()
+  };
+  let lines = traced(Vm::builder().tracer(TraceConfig::new(events).function("run")))?;
+
+  assert_eq!(lines.len(), 2);
+  assert!(lines[0].starts_with("[channel] send ok in run ("));
+  assert!(lines[1].starts_with("[channel] receive ok in run ("));
+  Ok(())
+}
+
+#[test]
+fn filtered_by_module() -> Result<(), std::io::Error> {
+  let events = TraceEvents::parse("calls,channels").expect("Expected valid events");
+  let lines = traced(Vm::builder().tracer(TraceConfig::new(events).module("other.lay")))?;
+
+  assert!(lines.is_empty());
+  Ok(())
+}
+
+#[test]
+fn gc() -> Result<(), std::io::Error> {
+  let events = TraceEvents {
+    gc: true,
+    ..TraceEvents::default()
+  };
+  let lines = traced(
+    Vm::builder()
+      .gc_stress(true)
+      .tracer(TraceConfig::new(events)),
+  )?;
+
+  assert!(!lines.is_empty());
+  assert!(lines
+    .iter()
+    .all(|line| line.starts_with("[gc] collection ")));
+  Ok(())
+}
+
+#[test]
+fn instructions() -> Result<(), std::io::Error> {
+  let lines = traced(Vm::builder().trace(true))?;
+
+  assert!(lines.iter().any(|line| line.starts_with("Local Stack:")));
+  Ok(())
+}
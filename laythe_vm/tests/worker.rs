@@ -0,0 +1,17 @@
+use support::{assert_files_exit, Outcome};
+
+mod support;
+
+fn test_files(paths: &[&str], result: Outcome) -> Result<(), std::io::Error> {
+  assert_files_exit(paths, FILE_PATH, result)
+}
+
+const FILE_PATH: &str = file!();
+
+#[test]
+fn worker() -> Result<(), std::io::Error> {
+  test_files(
+    &vec!["std_lib/worker/errors.lay", "std_lib/worker/worker.lay"],
+    Outcome::Ok(0),
+  )
+}
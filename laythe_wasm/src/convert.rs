@@ -0,0 +1,127 @@
+use js_sys::{Array, Object, Reflect};
+use laythe_core::{
+  hooks::GcHooks,
+  object::{List, Map, ObjectKind},
+  val,
+  value::{Value, VALUE_NIL},
+};
+use laythe_vm::embed::{IntoValue, LyError};
+use wasm_bindgen::{JsCast, JsValue};
+
+/// A JavaScript value passed into the vm. Arrays become lists and plain
+/// objects become maps keyed by their property names. Values with no
+/// Laythe equivalent such as functions and symbols become nil
+pub struct FromJs(pub JsValue);
+
+impl IntoValue for FromJs {
+  fn into_value(self, hooks: &GcHooks) -> Value {
+    js_to_value(hooks, &self.0)
+  }
+}
+
+fn js_to_value(hooks: &GcHooks, js: &JsValue) -> Value {
+  if let Some(boolean) = js.as_bool() {
+    return val!(boolean);
+  }
+
+  if let Some(num) = js.as_f64() {
+    return val!(num);
+  }
+
+  if let Some(string) = js.as_string() {
+    return val!(hooks.manage_str(string));
+  }
+
+  if Array::is_array(js) {
+    let array: &Array = js.unchecked_ref();
+    let mut list = hooks.manage_obj(List::with_capacity(array.length() as usize));
+    hooks.push_root(list);
+
+    for item in array.iter() {
+      let item = js_to_value(hooks, &item);
+      hooks.grow(&mut *list, |list| list.push(item));
+    }
+
+    hooks.pop_roots(1);
+    return val!(list);
+  }
+
+  if js.is_object() {
+    let mut map = hooks.manage_obj(Map::new());
+    hooks.push_root(map);
+
+    for entry in Object::entries(js.unchecked_ref()).iter() {
+      let entry: Array = entry.unchecked_into();
+      let key = js_to_value(hooks, &entry.get(0));
+      hooks.push_root(key);
+      let value = js_to_value(hooks, &entry.get(1));
+      hooks.grow(&mut *map, |map| map.insert(key, value));
+      hooks.pop_roots(1);
+    }
+
+    hooks.pop_roots(1);
+    return val!(map);
+  }
+
+  VALUE_NIL
+}
+
+/// Convert a value returned from the vm to JavaScript. Lists become
+/// arrays and maps become plain objects. Other objects such as instances
+/// and functions can't be converted
+pub fn to_js(value: Value) -> Result<JsValue, LyError> {
+  if value.is_nil() {
+    return Ok(JsValue::NULL);
+  }
+
+  if value.is_bool() {
+    return Ok(JsValue::from_bool(value.to_bool()));
+  }
+
+  if value.is_num() {
+    return Ok(JsValue::from_f64(value.to_num()));
+  }
+
+  if value.is_obj_kind(ObjectKind::String) {
+    return Ok(JsValue::from_str(&value.to_obj().to_str()));
+  }
+
+  if value.is_obj_kind(ObjectKind::List) {
+    let array = Array::new();
+    for item in value.to_obj().to_list().iter() {
+      array.push(&to_js(*item)?);
+    }
+
+    return Ok(array.into());
+  }
+
+  if value.is_obj_kind(ObjectKind::Map) {
+    let object = Object::new();
+    for (key, value) in value.to_obj().to_map().iter() {
+      Reflect::set(&object, &to_js(*key)?, &to_js(*value)?)
+        .map_err(|_| LyError::Internal("Unable to set object property.".to_string()))?;
+    }
+
+    return Ok(object.into());
+  }
+
+  Err(LyError::Conversion {
+    expected: "a value with a javascript equivalent",
+    found: value.value_type(),
+  })
+}
+
+/// Convert an error to a JavaScript error to throw
+pub fn js_error<E: ToString>(error: E) -> JsValue {
+  js_sys::Error::new(&error.to_string()).into()
+}
+
+/// The message of a value thrown from JavaScript
+pub fn js_message(error: &JsValue) -> String {
+  match error.dyn_ref::<js_sys::Error>() {
+    Some(error) => String::from(error.message()),
+    None => error
+      .as_string()
+      .unwrap_or_else(|| "JavaScript callback threw a value.".to_string()),
+  }
+}
@@ -0,0 +1,51 @@
+use laythe_env::{
+  env::{Env, EnvImpl, EnvVars},
+  io::IoImpl,
+};
+use std::{io, path::PathBuf, sync::Arc};
+
+/// The browser has no process environment so variables set by a script
+/// are only kept for the life of its vm
+#[derive(Debug, Default)]
+pub struct IoEnvWasm {
+  vars: EnvVars,
+}
+
+impl IoImpl<Env> for IoEnvWasm {
+  fn make(&self) -> Env {
+    Env::new(Box::new(EnvWasm {
+      vars: Arc::clone(&self.vars),
+    }))
+  }
+}
+
+struct EnvWasm {
+  vars: EnvVars,
+}
+
+impl EnvImpl for EnvWasm {
+  fn current_dir(&self) -> io::Result<PathBuf> {
+    Ok(PathBuf::new())
+  }
+
+  fn args(&self) -> Vec<String> {
+    vec![]
+  }
+
+  fn var(&self, name: &str) -> Option<String> {
+    self.vars.lock().unwrap().get(name).cloned()
+  }
+
+  fn set_var(&self, name: &str, value: &str) -> io::Result<()> {
+    self
+      .vars
+      .lock()
+      .unwrap()
+      .insert(name.to_string(), value.to_string());
+    Ok(())
+  }
+
+  fn platform(&self) -> &str {
+    "wasm"
+  }
+}
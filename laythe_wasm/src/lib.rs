@@ -1,13 +1,20 @@
 extern crate wasm_bindgen;
 
+mod convert;
+mod env_wasm;
 mod stdio_wasm;
 mod time_wasm;
 
-use js_sys::Function;
+use convert::{js_error, js_message, to_js, FromJs};
+use env_wasm::IoEnvWasm;
+use js_sys::{Array, Function};
 use laythe_env::io::Io;
-use laythe_vm::vm::{ExecuteResult, Vm};
+use laythe_vm::{
+  embed::{IntoValue, LyError},
+  vm::{Vm, VmError},
+};
 use std::{path::PathBuf, sync::Arc};
-use stdio_wasm::{IoStdioWasmConsole, IoStdioWasmJsFunction};
+use stdio_wasm::{InputLines, IoStdioWasmConsole, IoStdioWasmJsFunction};
 use time_wasm::IoTimeWasm;
 use wasm_bindgen::prelude::*;
 
@@ -18,43 +25,154 @@ pub fn set_panic_hook() {
 }
 
 #[wasm_bindgen]
-pub struct VmWasm(Vm);
+pub struct VmWasm {
+  vm: Vm,
+  input: InputLines,
+  awaiting_input: bool,
+}
 
 #[wasm_bindgen]
 impl VmWasm {
   pub fn new() -> Self {
     set_panic_hook();
+    let input = InputLines::default();
     let wasm_io = Io::default()
       .with_time(Arc::new(IoTimeWasm::default()))
-      .with_stdio(Arc::new(IoStdioWasmConsole()));
+      .with_env(Arc::new(IoEnvWasm::default()))
+      .with_stdio(Arc::new(IoStdioWasmConsole::new(&input)));
 
-    Self(Vm::new(wasm_io))
+    Self::with_io(wasm_io, input)
   }
 
   pub fn with_stdout(stdout: &Function) -> Self {
     set_panic_hook();
+    let input = InputLines::default();
     let wasm_io = Io::default()
       .with_time(Arc::new(IoTimeWasm::default()))
-      .with_stdio(Arc::new(IoStdioWasmJsFunction::new(Arc::new(
-        stdout.clone(),
-      ))));
+      .with_env(Arc::new(IoEnvWasm::default()))
+      .with_stdio(Arc::new(IoStdioWasmJsFunction::new(
+        Arc::new(stdout.clone()),
+        &input,
+      )));
 
-    Self(Vm::new(wasm_io))
+    Self::with_io(wasm_io, input)
   }
 
   pub fn version() -> String {
     Vm::version().to_string()
   }
 
+  /// Compile a script without running it, reporting any diagnostics
+  /// to stderr and throwing if it failed to compile
+  pub fn compile(&mut self, source: &str) -> Result<(), JsValue> {
+    let result = self.vm.check(PathBuf::from(SCRIPT_PATH), source);
+
+    if let Err(error) = &result {
+      self.vm.report(error);
+    }
+
+    result.map_err(js_error)
+  }
+
   pub fn run(&mut self, source: &str) -> f64 {
-    let result = self.0.run(PathBuf::from("script.ly"), source);
+    let result = self.vm.run(PathBuf::from(SCRIPT_PATH), source);
+    self.exit_code(result)
+  }
+
+  /// Is the last run waiting for a line of input. The host should call
+  /// `provide_input` then `resume` to continue it
+  pub fn awaiting_input(&self) -> bool {
+    self.awaiting_input
+  }
+
+  /// Provide a line of input for the script to read from stdin
+  pub fn provide_input(&mut self, line: &str) {
+    self.input.borrow_mut().push_back(line.to_string());
+  }
+
+  /// Resume a run waiting for input, returning its exit code like `run`
+  pub fn resume(&mut self) -> f64 {
+    let result = self.vm.resume();
+    self.exit_code(result)
+  }
+
+  /// Call a top level function of the last script run with JavaScript
+  /// arguments, returning its result. Errors the call raises are thrown
+  pub fn call(&mut self, name: &str, args: Array) -> Result<JsValue, JsValue> {
+    let callable = self
+      .vm
+      .get_global(name)
+      .ok_or_else(|| js_error(format!("Script has no top level symbol {}.", name)))?;
+    let args: Vec<_> = args
+      .iter()
+      .map(|arg| self.vm.to_value(FromJs(arg)))
+      .collect();
+
+    let result = self.vm.call(&callable, &args).map_err(js_error)?;
+    to_js(result.value()).map_err(js_error)
+  }
+
+  /// Register a JavaScript function as a global native function scripts
+  /// run after this point can call. Its arguments are converted to
+  /// JavaScript and anything it throws is raised as a `RuntimeError`
+  pub fn register(&mut self, name: &str, callback: Function) -> Result<(), JsValue> {
+    let callback = JsCallback(callback);
+
+    self
+      .vm
+      .register_fun(name, move |hooks, args| {
+        let js_args = Array::new();
+        for arg in args {
+          js_args.push(&to_js(*arg)?);
+        }
+
+        match callback.0.apply(&JsValue::NULL, &js_args) {
+          Ok(result) => Ok(FromJs(result).into_value(&hooks.as_gc())),
+          Err(error) => Err(LyError::Runtime {
+            class: "RuntimeError".to_string(),
+            message: js_message(&error),
+          }),
+        }
+      })
+      .map(|_| ())
+      .map_err(js_error)
+  }
+}
+
+impl VmWasm {
+  fn with_io(io: Io, input: InputLines) -> Self {
+    Self {
+      vm: Vm::new(io).expect("Unable to create vm"),
+      input,
+      awaiting_input: false,
+    }
+  }
+
+  fn exit_code(&mut self, result: Result<u16, VmError>) -> f64 {
+    self.awaiting_input = matches!(result, Err(VmError::AwaitingInput));
+
+    match &result {
+      Err(VmError::AwaitingInput) | Ok(_) => (),
+      Err(error) => self.vm.report(error),
+    }
 
     match result {
-      ExecuteResult::Ok(code) => code.into(),
-      ExecuteResult::FunResult(_) => 1.0,
-      ExecuteResult::InternalError => 2.0,
-      ExecuteResult::RuntimeError => 3.0,
-      ExecuteResult::CompileError => 4.0,
+      Ok(code) => code.into(),
+      Err(VmError::Internal(_)) | Err(VmError::Io { .. }) => 2.0,
+      Err(VmError::Runtime(_)) | Err(VmError::Load(_)) => 3.0,
+      Err(VmError::Compile(_)) => 4.0,
+      Err(VmError::Timeout) => 5.0,
+      Err(VmError::Interrupted(_)) => 6.0,
+      Err(VmError::AwaitingInput) => 7.0,
     }
   }
 }
+
+const SCRIPT_PATH: &str = "script.ly";
+
+/// A JavaScript function called from a native function
+struct JsCallback(Function);
+
+// wasm runs each vm on the browser's single thread so the callback is
+// never shared between threads
+unsafe impl Send for JsCallback {}
@@ -4,17 +4,73 @@ use laythe_env::{
   io::IoImpl,
   stdio::{MockRead, Stdio, StdioImpl},
 };
-use std::{cell::RefCell, io, sync::Arc};
+use std::{cell::RefCell, collections::VecDeque, io, sync::Arc};
 use termcolor::WriteColor;
 use wasm_bindgen::JsValue;
 use web_sys::console::{error_1, log_1};
 
+/// Lines of input the host has provided but a script hasn't read yet
+pub type InputLines = Arc<RefCell<VecDeque<String>>>;
+
+/// Read the next line of input, or block if the host hasn't provided one.
+/// The vm then returns to the host which resumes it once a line is provided
+fn read_input(input: &InputLines, buffer: &mut String) -> io::Result<usize> {
+  match input.borrow_mut().pop_front() {
+    Some(line) => {
+      buffer.push_str(&line);
+      buffer.push('\n');
+      Ok(line.len() + 1)
+    },
+    None => Err(io::Error::new(
+      io::ErrorKind::WouldBlock,
+      "No input has been provided.",
+    )),
+  }
+}
+
+/// Read the next character of input, taking the newline once a line is
+/// exhausted, or block if the host hasn't provided a line
+fn read_input_char(input: &InputLines) -> io::Result<Option<char>> {
+  let mut input = input.borrow_mut();
+
+  match input.front_mut() {
+    Some(line) if line.is_empty() => {
+      input.pop_front();
+      Ok(Some('\n'))
+    },
+    Some(line) => Ok(Some(line.remove(0))),
+    None => Err(io::Error::new(
+      io::ErrorKind::WouldBlock,
+      "No input has been provided.",
+    )),
+  }
+}
+
 #[derive(Debug)]
-pub struct IoStdioWasmConsole();
+pub struct IoStdioWasmConsole {
+  input: InputLines,
+}
+
+impl IoStdioWasmConsole {
+  pub fn new(input: &InputLines) -> Self {
+    Self {
+      input: Arc::clone(input),
+    }
+  }
+}
+
+// wasm runs each vm on the browser's single thread so the input lines
+// are never shared between threads
+unsafe impl Send for IoStdioWasmConsole {}
+unsafe impl Sync for IoStdioWasmConsole {}
 
 impl IoImpl<Stdio> for IoStdioWasmConsole {
   fn make(&self) -> Stdio {
-    Stdio::new(Box::new(StdioWasm::new(&log_1, &error_1)))
+    Stdio::new(Box::new(StdioWasm::new(
+      &log_1,
+      &error_1,
+      Arc::clone(&self.input),
+    )))
   }
 }
 
@@ -22,22 +78,30 @@ impl IoImpl<Stdio> for IoStdioWasmConsole {
 pub struct IoStdioWasmJsFunction {
   fun: Arc<Function>,
   line_buffer: Arc<RefCell<String>>,
+  input: InputLines,
 }
 
 impl IoStdioWasmJsFunction {
-  pub fn new(fun: Arc<Function>) -> Self {
+  pub fn new(fun: Arc<Function>, input: &InputLines) -> Self {
     Self {
       fun,
       line_buffer: Arc::new(RefCell::new("".to_string())),
+      input: Arc::clone(input),
     }
   }
 }
 
+// wasm runs each vm on the browser's single thread so the js callback
+// and its buffers are never shared between threads
+unsafe impl Send for IoStdioWasmJsFunction {}
+unsafe impl Sync for IoStdioWasmJsFunction {}
+
 impl IoImpl<Stdio> for IoStdioWasmJsFunction {
   fn make(&self) -> Stdio {
     Stdio::new(Box::new(StdioJsFunction::new(
       Arc::clone(&self.fun),
       Arc::clone(&self.line_buffer),
+      Arc::clone(&self.input),
     )))
   }
 }
@@ -50,6 +114,7 @@ where
   stdout: ConsoleWrapper<'a, O>,
   stderr: ConsoleWrapper<'a, E>,
   stdin: MockRead,
+  input: InputLines,
 }
 
 impl<'a, O, E> StdioWasm<'a, O, E>
@@ -57,15 +122,23 @@ where
   O: Fn(&JsValue),
   E: Fn(&JsValue),
 {
-  pub fn new(stdout_impl: &'a O, stderr_impl: &'a E) -> Self {
+  pub fn new(stdout_impl: &'a O, stderr_impl: &'a E, input: InputLines) -> Self {
     Self {
       stdout: ConsoleWrapper::new(stdout_impl),
       stderr: ConsoleWrapper::new(stderr_impl),
       stdin: MockRead(),
+      input,
     }
   }
 }
 
+unsafe impl<'a, O, E> Send for StdioWasm<'a, O, E>
+where
+  O: Fn(&JsValue) + Sync,
+  E: Fn(&JsValue) + Sync,
+{
+}
+
 impl<'a, O, E> StdioImpl for StdioWasm<'a, O, E>
 where
   O: Fn(&JsValue) + 'static + Sync,
@@ -83,8 +156,16 @@ where
   fn stdin(&mut self) -> &mut dyn io::Read {
     &mut self.stdin
   }
-  fn read_line(&self, _buffer: &mut String) -> io::Result<usize> {
-    Ok(0)
+  fn read_line(&self, buffer: &mut String) -> io::Result<usize> {
+    read_input(&self.input, buffer)
+  }
+
+  fn read_char(&mut self) -> io::Result<Option<char>> {
+    read_input_char(&self.input)
+  }
+
+  fn is_tty(&self) -> bool {
+    false
   }
 }
 
@@ -92,18 +173,22 @@ struct StdioJsFunction {
   stdout: FunWrapper,
   stderr: FunWrapper,
   stdin: MockRead,
+  input: InputLines,
 }
 
 impl StdioJsFunction {
-  pub fn new(stdout: Arc<Function>, line_buffer: Arc<RefCell<String>>) -> Self {
+  pub fn new(stdout: Arc<Function>, line_buffer: Arc<RefCell<String>>, input: InputLines) -> Self {
     Self {
       stdout: FunWrapper::new(Arc::clone(&stdout), &line_buffer),
       stderr: FunWrapper::new(stdout, &line_buffer),
       stdin: MockRead(),
+      input,
     }
   }
 }
 
+unsafe impl Send for StdioJsFunction {}
+
 impl StdioImpl for StdioJsFunction {
   fn stdout(&mut self) -> &mut dyn io::Write {
     &mut self.stdout
@@ -117,8 +202,16 @@ impl StdioImpl for StdioJsFunction {
   fn stdin(&mut self) -> &mut dyn io::Read {
     &mut self.stdin
   }
-  fn read_line(&self, _buffer: &mut String) -> io::Result<usize> {
-    Ok(0)
+  fn read_line(&self, buffer: &mut String) -> io::Result<usize> {
+    read_input(&self.input, buffer)
+  }
+
+  fn read_char(&mut self) -> io::Result<Option<char>> {
+    read_input_char(&self.input)
+  }
+
+  fn is_tty(&self) -> bool {
+    false
   }
 }
 
@@ -34,4 +34,11 @@ impl TimeImpl for TimeWasm {
     let nanos = ((delta % 1000) * 1000) as u32;
     Ok(Duration::new(secs, nanos))
   }
+
+  fn now(&self) -> Result<Duration, String> {
+    Ok(Duration::from_secs_f64(Date::now() / 1000.0))
+  }
+
+  // the browser's main thread cannot be blocked
+  fn sleep(&self, _duration: Duration) {}
 }
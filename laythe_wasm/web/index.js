@@ -10,6 +10,9 @@ const terminalElement = document.getElementById("terminal");
 let repl = false;
 let curr_line = "";
 
+// the vm of a script waiting on a line of input
+let inputVm = null;
+
 const editor = editorInit(editorElement);
 const term = terminalInit(terminalElement, VmWasm.version());
 
@@ -17,6 +20,8 @@ let timeoutHandle;
 
 const runScript = () => {
   repl = false;
+  inputVm = null;
+  curr_line = "";
   clearTimeout(timeoutHandle);
   const [model] = editor.getModels();
   const source = model.getValue();
@@ -24,12 +29,34 @@ const runScript = () => {
   term.clear();
   const vm = VmWasm.with_stdout((str) => term.writeln(str));
 
-  setImmediate(() => vm.run(source));
+  setImmediate(() => {
+    vm.run(source);
+    awaitInput(vm);
+  });
+};
+
+// if the script is blocked on stdin route the terminal's keys to it
+// until a line is entered then resume it
+const awaitInput = (vm) => {
+  inputVm = vm.awaiting_input() ? vm : null;
+};
+
+const provideInput = (line) => {
+  const vm = inputVm;
+  inputVm = null;
+
+  vm.provide_input(line);
+  setImmediate(() => {
+    vm.resume();
+    awaitInput(vm);
+  });
 };
 
 const startRepl = () => {
   if (!repl) {
     repl = true;
+    inputVm = null;
+    curr_line = "";
 
     clearTimeout(timeoutHandle);
     term.clear();
@@ -51,7 +78,24 @@ runElement.addEventListener("click", runScript);
 replElement.addEventListener("click", startRepl);
 
 term.onKey(({ key, domEvent: { keyCode } }) => {
-  if (repl) {
+  if (inputVm) {
+    switch (keyCode) {
+      case 13:
+        term.write('\r\n');
+        provideInput(curr_line);
+        curr_line = "";
+        break
+      case 8:
+        if (curr_line) {
+          curr_line = curr_line.slice(0, curr_line.length - 1);
+          term.write('\b \b');
+        }
+        break
+      default:
+        curr_line += key;
+        term.write(key);
+    }
+  } else if (repl) {
     console.log(key, keyCode);
 
     switch (keyCode) {